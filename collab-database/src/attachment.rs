@@ -0,0 +1,112 @@
+use crate::database::Database;
+use crate::entity::FieldType;
+use crate::fields::media_type_option::MediaCellData;
+use crate::rows::{Cell, RowChange, get_field_type_from_cell};
+use collab_entity::attachment::{AttachmentLocation, AttachmentReference, AttachmentWriteHook};
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+impl Database {
+  /// The attachments referenced by the media cells of the database, e.g. to fill a catalog of the
+  /// attachments of the workspace. Use [Database::set_attachment_hook] to keep the catalog up to
+  /// date.
+  pub async fn get_attachment_references(&self, auto_fetch: bool) -> Vec<AttachmentReference> {
+    let database_id = self.get_database_id();
+    let media_field_ids = self
+      .get_all_fields()
+      .into_iter()
+      .filter(|field| FieldType::from(field.field_type) == FieldType::Media)
+      .map(|field| field.id)
+      .collect::<Vec<_>>();
+    if media_field_ids.is_empty() {
+      return vec![];
+    }
+
+    let rows = self.collect_all_rows(auto_fetch).await;
+    let mut references = vec![];
+    for row in rows.into_iter().flatten() {
+      for field_id in &media_field_ids {
+        if let Some(cell) = row.cells.get(field_id) {
+          references.extend(media_cell_references(
+            &database_id,
+            row.id.as_str(),
+            field_id,
+            cell,
+          ));
+        }
+      }
+    }
+    references
+  }
+
+  /// Call the hook with the attachments of the media cells written to the rows of the database,
+  /// locally or by a remote peer, see [Database::get_attachment_references]. The hook is called
+  /// with no attachment for the cleared cells.
+  ///
+  /// Returns None if the database is opened without a [crate::database_state::DatabaseNotify].
+  /// The hook is called until the database is dropped or the returned task is aborted.
+  pub fn set_attachment_hook(&self, hook: Arc<dyn AttachmentWriteHook>) -> Option<JoinHandle<()>> {
+    let mut row_change_rx = self.subscribe_row_change()?;
+    let database_id = self.get_database_id();
+    let handle = tokio::spawn(async move {
+      loop {
+        let change = match row_change_rx.recv().await {
+          Ok(change) => change,
+          Err(RecvError::Lagged(count)) => {
+            warn!("The attachment hook missed {} row changes", count);
+            continue;
+          },
+          Err(RecvError::Closed) => break,
+        };
+        let RowChange::DidUpdateCell {
+          row_id,
+          field_id,
+          value,
+        } = change
+        else {
+          continue;
+        };
+        // A cleared cell doesn't have a field type
+        let references = match get_field_type_from_cell::<FieldType>(&value) {
+          Some(FieldType::Media) => {
+            media_cell_references(&database_id, row_id.as_str(), &field_id, &value)
+          },
+          Some(_) => continue,
+          None => vec![],
+        };
+        let location = AttachmentLocation::Cell {
+          row_id: row_id.to_string(),
+          field_id,
+        };
+        hook.did_write_attachments(&database_id, &location, references);
+      }
+    });
+    Some(handle)
+  }
+}
+
+/// The attachments referenced by a media cell.
+pub(crate) fn media_cell_references(
+  database_id: &str,
+  row_id: &str,
+  field_id: &str,
+  cell: &Cell,
+) -> Vec<AttachmentReference> {
+  let location = AttachmentLocation::Cell {
+    row_id: row_id.to_string(),
+    field_id: field_id.to_string(),
+  };
+  MediaCellData::from(cell)
+    .files
+    .into_iter()
+    .filter(|file| !file.url.trim().is_empty())
+    .map(|file| AttachmentReference {
+      object_id: database_id.to_string(),
+      location: location.clone(),
+      url: file.url,
+      size: None,
+    })
+    .collect()
+}
@@ -1,3 +1,5 @@
 pub use block::*;
+pub use row_chunk::*;
 
 mod block;
+mod row_chunk;
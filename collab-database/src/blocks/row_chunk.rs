@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::rows::RowId;
+use crate::views::RowOrder;
+
+/// The number of rows of a [RowChunk] when the caller has no preference.
+pub const DEFAULT_ROW_CHUNK_SIZE: usize = 500;
+
+/// A contiguous slice of the rows of a view. The row collabs of a chunk are loaded together, so a
+/// reader scrolling through a large view only loads the rows of the chunks it displays.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowChunk {
+  pub index: usize,
+  /// The index of the first row of the chunk in the view.
+  pub start: usize,
+  pub row_orders: Vec<RowOrder>,
+}
+
+impl RowChunk {
+  pub fn row_ids(&self) -> Vec<RowId> {
+    self
+      .row_orders
+      .iter()
+      .map(|order| order.id.clone())
+      .collect()
+  }
+
+  /// The range of the rows of the chunk in the view.
+  pub fn range(&self) -> Range<usize> {
+    self.start..self.start + self.row_orders.len()
+  }
+}
+
+/// The [RowOrder]s of a view split into [RowChunk]s of the same size, the last one may be
+/// smaller.
+///
+/// The chunks only page the reads of the rows: each row is still stored in its own collab and
+/// the row orders of the view are read from the database collab. The chunks are built from the
+/// row orders of the view, see [crate::database::Database::get_row_chunks_for_view], archived
+/// rows included since the archived flag is stored in the rows. They don't follow the changes of
+/// the view, build them again after the rows are moved, inserted or removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowChunks {
+  chunk_size: usize,
+  chunks: Vec<RowChunk>,
+  /// The position of each row in the view.
+  positions: HashMap<RowId, usize>,
+}
+
+impl RowChunks {
+  pub fn new(row_orders: Vec<RowOrder>, chunk_size: usize) -> Self {
+    let chunk_size = chunk_size.max(1);
+    let positions = row_orders
+      .iter()
+      .enumerate()
+      .map(|(position, order)| (order.id.clone(), position))
+      .collect();
+    let chunks = row_orders
+      .chunks(chunk_size)
+      .enumerate()
+      .map(|(index, chunk)| RowChunk {
+        index,
+        start: index * chunk_size,
+        row_orders: chunk.to_vec(),
+      })
+      .collect();
+    Self {
+      chunk_size,
+      chunks,
+      positions,
+    }
+  }
+
+  pub fn chunk_size(&self) -> usize {
+    self.chunk_size
+  }
+
+  pub fn num_of_chunks(&self) -> usize {
+    self.chunks.len()
+  }
+
+  pub fn num_of_rows(&self) -> usize {
+    self
+      .chunks
+      .last()
+      .map(|chunk| chunk.range().end)
+      .unwrap_or_default()
+  }
+
+  pub fn chunks(&self) -> &[RowChunk] {
+    &self.chunks
+  }
+
+  pub fn get_chunk(&self, index: usize) -> Option<&RowChunk> {
+    self.chunks.get(index)
+  }
+
+  /// The index of the chunk containing the row, None if the row is not in the view.
+  pub fn chunk_index_of_row(&self, row_id: &RowId) -> Option<usize> {
+    self
+      .positions
+      .get(row_id)
+      .map(|position| position / self.chunk_size)
+  }
+
+  /// The chunks containing the rows of the range, e.g. the rows visible in a grid. The part of
+  /// the range past the last row is ignored.
+  pub fn chunks_for_range(&self, range: Range<usize>) -> &[RowChunk] {
+    let end = range.end.min(self.num_of_rows());
+    if range.start >= end {
+      return &[];
+    }
+    let first = range.start / self.chunk_size;
+    let last = (end - 1) / self.chunk_size;
+    &self.chunks[first..=last]
+  }
+}
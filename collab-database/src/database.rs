@@ -1,26 +1,38 @@
 use std::borrow::{Borrow, BorrowMut};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
-use std::ops::{Deref, DerefMut};
+use std::ops::{Deref, DerefMut, Range};
 
-use crate::blocks::{Block, BlockEvent, InitRowChan};
+use crate::blocks::{Block, BlockEvent, InitRowChan, RowChunks};
+use crate::database_diff::{DatabaseChanges, DatabaseRevision, field_digest};
 use crate::database_state::DatabaseNotify;
 use crate::error::DatabaseError;
+use crate::fields::button_type_option::{AutomationEvent, ButtonAction, ButtonTypeOption};
+use crate::fields::date_type_option::DateCellData;
+use crate::fields::person_type_option::{PersonCellData, PersonTypeOption, WorkspaceMember};
+use crate::fields::relation_type_option::{DatabaseReference, RelationTypeOption};
+use crate::fields::select_type_option::{
+  SelectOption, SelectOptionColor, SelectOptionIds, SelectTypeOption,
+};
 use crate::fields::{
-  Field, FieldChangeReceiver, FieldMap, FieldUpdate, TypeOptionCellReader, TypeOptionCellWriter,
-  type_option_cell_reader, type_option_cell_writer,
+  Field, FieldChangeReceiver, FieldMap, FieldUpdate, TextTemplate, TypeOptionCellReader,
+  TypeOptionCellWriter, type_option_cell_reader, type_option_cell_writer,
 };
 use crate::meta::MetaMap;
+use crate::recurrence::{RowRecurrence, recurrence_from_out, recurrence_to_any};
 use crate::rows::{
-  CreateRowParams, CreateRowParamsValidator, DatabaseRow, Row, RowCell, RowChangeReceiver,
-  RowDetail, RowId, RowMeta, RowMetaKey, RowMetaUpdate, RowUpdate, meta_id_from_row_id,
+  Cell, Cells, CreateRowParams, CreateRowParamsValidator, DatabaseRow, DuplicateRowPolicy,
+  DuplicateRows, Row, RowCell, RowChangeReceiver, RowComment, RowCommentThread, RowDetail, RowId,
+  RowMeta, RowMetaKey, RowMetaUpdate, RowUpdate, cell_richness, group_duplicate_rows,
+  merge_relation_cells, meta_id_from_row_id, row_comment_threads,
 };
 use crate::util::encoded_collab;
 use crate::views::define::DATABASE_VIEW_ROW_ORDERS;
 use crate::views::{
-  CalculationMap, DatabaseLayout, DatabaseViewUpdate, DatabaseViews, FieldOrder,
+  CalculationMap, DatabaseLayout, DatabaseViewUpdate, DatabaseViews, FieldMapping, FieldOrder,
   FieldSettingsByFieldIdMap, FieldSettingsMap, FilterMap, GroupSettingMap, LayoutSetting,
-  OrderArray, OrderObjectPosition, RowOrder, RowOrderArray, SortMap, ViewChangeReceiver,
+  OrderArray, OrderObjectPosition, RowOrder, RowOrderArray, RowQuery, SortMap,
+  TimelineLayoutSetting, TimelineWindow, ViewChangeReceiver, ViewDefinition, timeline_bars,
 };
 use crate::workspace_database::DatabaseMeta;
 
@@ -29,6 +41,7 @@ use crate::entity::{
   DatabaseViewMeta, EncodedCollabInfo, EncodedDatabase, FieldType,
 };
 use crate::template::entity::DatabaseTemplate;
+use crate::template::relation_parse::RelationCellData;
 
 use collab::core::origin::CollabOrigin;
 use collab::lock::RwLock;
@@ -36,17 +49,21 @@ use collab::preclude::{
   Any, Array, Collab, FillRef, JsonValue, Map, MapExt, MapPrelim, MapRef, ReadTxn, ToJson,
   TransactionMut, YrsValue,
 };
-use collab::util::{AnyExt, ArrayExt};
+use collab::util::{AnyExt, ArrayExt, is_change_since_sv};
 use collab_entity::CollabType;
 use collab_entity::define::{DATABASE, DATABASE_ID, DATABASE_METAS};
 
 use futures::stream::StreamExt;
 use futures::{Stream, stream};
 use nanoid::nanoid;
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
 
-use crate::database_trait::{DatabaseCollabService, DatabaseDataVariant, DatabaseRowCollabService};
+use crate::database_trait::{
+  AutomationHandler, DatabaseCollabService, DatabaseDataVariant, DatabaseReferenceResolver,
+  DatabaseRowCollabService, MemberResolver,
+};
 use collab::core::collab::CollabOptions;
-use futures::future::join_all;
+use futures::future::{self, join_all};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 pub use tokio_stream::wrappers::WatchStream;
@@ -69,6 +86,8 @@ impl Drop for Database {
 
 const FIELDS: &str = "fields";
 const VIEWS: &str = "views";
+const DATABASE_ROW_RECURRENCES: &str = "row_recurrences";
+const DATABASE_PRIMARY_TEMPLATE: &str = "primary_template";
 
 #[derive(Clone)]
 pub struct DatabaseContext {
@@ -333,6 +352,22 @@ impl Database {
       .map(|notifier| notifier.field_change_tx.subscribe())
   }
 
+  /// Subscribe to the changes of a single field. Returns `None` if the database is opened without
+  /// a [DatabaseNotify].
+  pub fn subscribe_field_change_by_id(&self, field_id: &str) -> Option<FieldChangeReceiver> {
+    self.body.fields.subscribe_field_change(field_id)
+  }
+
+  /// Subscribe to the changes of a single row. The row is loaded if it's not in memory yet.
+  pub async fn subscribe_row_change_by_id(
+    &self,
+    row_id: &RowId,
+  ) -> Result<RowChangeReceiver, DatabaseError> {
+    let row = self.body.block.get_or_init_database_row(row_id).await?;
+    let receiver = row.read().await.subscribe_change();
+    Ok(receiver)
+  }
+
   pub fn subscribe_view_change(&self) -> Option<ViewChangeReceiver> {
     self
       .body
@@ -385,7 +420,8 @@ impl Database {
   /// created successfully. Otherwise, return None.
   pub async fn create_row(&mut self, params: CreateRowParams) -> Result<RowOrder, DatabaseError> {
     let client_id = self.collab_service.database_client_id().await;
-    let params = CreateRowParamsValidator::validate(params)?;
+    let mut params = CreateRowParamsValidator::validate(params)?;
+    self.generate_primary_cells(std::slice::from_mut(&mut params));
     let row_order = self.body.block.create_new_row(params, client_id).await?;
     let mut txn = self.collab.transact_mut();
     self
@@ -397,6 +433,32 @@ impl Database {
     Ok(row_order)
   }
 
+  /// Create the rows in one batch, e.g. when importing a large file. The rows are inserted to
+  /// the end of the rows of each view, in the given order, with a single update of the views.
+  /// Return the row orders of the created rows.
+  pub async fn create_rows(
+    &mut self,
+    params: Vec<CreateRowParams>,
+  ) -> Result<Vec<RowOrder>, DatabaseError> {
+    let client_id = self.collab_service.database_client_id().await;
+    let mut params = params
+      .into_iter()
+      .map(CreateRowParamsValidator::validate)
+      .collect::<Result<Vec<_>, _>>()?;
+    self.generate_primary_cells(&mut params);
+    let row_orders = self.body.block.create_rows(params, client_id).await;
+    let mut txn = self.collab.transact_mut();
+    self
+      .body
+      .views
+      .update_all_views(&mut txn, |_view_id, mut update| {
+        for row_order in &row_orders {
+          update = update.insert_row_order(row_order, &OrderObjectPosition::default());
+        }
+      });
+    Ok(row_orders)
+  }
+
   pub fn update_database_view<F>(&mut self, view_id: &str, f: F)
   where
     F: FnOnce(DatabaseViewUpdate),
@@ -405,6 +467,100 @@ impl Database {
     self.body.views.update_database_view(&mut txn, view_id, f);
   }
 
+  /// Add a recurrence of rows, or replace the one with the same id. Return
+  /// [DatabaseError::InvalidRecurrenceRule] if its rule is not supported.
+  pub fn upsert_row_recurrence(&mut self, recurrence: RowRecurrence) -> Result<(), DatabaseError> {
+    recurrence.rule()?;
+    let value = recurrence_to_any(&recurrence)?;
+    let mut txn = self.collab.transact_mut();
+    let recurrences = self
+      .body
+      .metas
+      .get_or_init_map(&mut txn, DATABASE_ROW_RECURRENCES);
+    recurrences.insert(&mut txn, recurrence.id.as_str(), value);
+    Ok(())
+  }
+
+  pub fn get_row_recurrence(&self, recurrence_id: &str) -> Option<RowRecurrence> {
+    let txn = self.collab.transact();
+    let recurrences: MapRef = self
+      .body
+      .metas
+      .get_with_txn(&txn, DATABASE_ROW_RECURRENCES)?;
+    recurrence_from_out(recurrences.get(&txn, recurrence_id)?)
+  }
+
+  /// The recurrences of rows of the database, ordered by start.
+  pub fn get_row_recurrences(&self) -> Vec<RowRecurrence> {
+    let txn = self.collab.transact();
+    let Some(recurrences) = self
+      .body
+      .metas
+      .get_with_txn::<_, MapRef>(&txn, DATABASE_ROW_RECURRENCES)
+    else {
+      return vec![];
+    };
+    let mut recurrences = recurrences
+      .iter(&txn)
+      .filter_map(|(_, value)| recurrence_from_out(value))
+      .collect::<Vec<_>>();
+    recurrences.sort_by(|a, b| a.start.cmp(&b.start).then_with(|| a.id.cmp(&b.id)));
+    recurrences
+  }
+
+  /// Remove a recurrence, the rows it created are kept.
+  pub fn remove_row_recurrence(&mut self, recurrence_id: &str) -> Option<RowRecurrence> {
+    let recurrence = self.get_row_recurrence(recurrence_id)?;
+    let mut txn = self.collab.transact_mut();
+    let recurrences: MapRef = self
+      .body
+      .metas
+      .get_with_txn(&txn, DATABASE_ROW_RECURRENCES)?;
+    recurrences.remove(&mut txn, recurrence_id);
+    Some(recurrence)
+  }
+
+  /// Create the rows of the occurrences of the recurrences that are due at `now`, in seconds since
+  /// the epoch. Return the rows created.
+  ///
+  /// Each occurrence creates its row once: calling it again, or on another client, doesn't create
+  /// it again since the row of an occurrence has a stable id, see
+  /// [RowRecurrence::occurrence_row_id]. A recurrence with an unsupported rule is skipped.
+  pub async fn materialize_due_rows(&mut self, now: i64) -> Result<Vec<RowOrder>, DatabaseError> {
+    let database_id = self.get_database_id();
+    let inline_view_id = self.body.get_inline_view_id(&self.collab.transact());
+    let mut row_orders = vec![];
+    for mut recurrence in self.get_row_recurrences() {
+      let occurrences = match recurrence.due_occurrences(now) {
+        Ok(occurrences) => occurrences,
+        Err(err) => {
+          error!("Skip the recurrence {}: {}", recurrence.id, err);
+          continue;
+        },
+      };
+      let Some(last_occurrence) = occurrences.last().copied() else {
+        continue;
+      };
+
+      for occurrence in occurrences {
+        let row_id = recurrence.occurrence_row_id(occurrence);
+        if self.contains_row(&inline_view_id, &row_id) {
+          continue;
+        }
+        let mut cells = recurrence.cells.clone();
+        if let Some(field_id) = &recurrence.date_field_id {
+          let date = DateCellData::from_timestamp_include_time(occurrence);
+          cells.insert(field_id.clone(), Cell::from(&date));
+        }
+        let params = CreateRowParams::new(row_id, database_id.clone()).with_cells(cells);
+        row_orders.push(self.create_row(params).await?);
+      }
+      recurrence.materialized_until = Some(last_occurrence);
+      self.upsert_row_recurrence(recurrence)?;
+    }
+    Ok(row_orders)
+  }
+
   pub fn contains_row(&self, view_id: &str, row_id: &RowId) -> bool {
     let txn = self.collab.transact();
     if let Some(YrsValue::YMap(view)) = self.body.views.get(&txn, view_id) {
@@ -427,6 +583,8 @@ impl Database {
   ) -> Result<(usize, RowOrder), DatabaseError> {
     let client_id = self.collab_service.database_client_id().await;
     let row_position = params.row_position.clone();
+    let mut params = params;
+    self.generate_primary_cells(std::slice::from_mut(&mut params));
     let row_order = self.body.create_row(params, client_id).await?;
 
     let mut txn = self.collab.transact_mut();
@@ -472,12 +630,92 @@ impl Database {
     };
   }
 
+  /// Archive the rows. Unlike [Self::remove_rows], the rows stay in the views: they are hidden
+  /// from [Self::get_rows_for_view], [Self::get_cells_for_field] and the [RowQuery]s, but can
+  /// still be read with [Self::get_archived_rows_for_view] or [RowQuery::with_archived].
+  pub async fn archive_rows(&mut self, row_ids: &[RowId]) {
+    for row_id in row_ids {
+      self
+        .body
+        .block
+        .update_row(row_id.clone(), |update| {
+          update.set_archived(true);
+        })
+        .await;
+    }
+  }
+
+  pub async fn unarchive_rows(&mut self, row_ids: &[RowId]) {
+    for row_id in row_ids {
+      self
+        .body
+        .block
+        .update_row(row_id.clone(), |update| {
+          update.set_archived(false);
+        })
+        .await;
+    }
+  }
+
+  /// Archive the rows of the view matching the query, e.g. the completed tasks. Return the ids of
+  /// the rows archived.
+  pub async fn archive_rows_by_query(
+    &mut self,
+    view_id: &str,
+    query: &RowQuery,
+    auto_fetch: bool,
+  ) -> Vec<RowId> {
+    let row_orders = self.get_row_orders_for_view(view_id);
+    let row_ids = self
+      .body
+      .block
+      .get_rows_from_row_orders(&row_orders, auto_fetch)
+      .await
+      .into_iter()
+      .filter(|row| query.matches(row))
+      .map(|row| row.id)
+      .collect::<Vec<_>>();
+    self.archive_rows(&row_ids).await;
+    row_ids
+  }
+
+  /// The ids of the archived rows among the row orders, the rows are loaded to read the flag.
+  async fn get_archived_row_ids(
+    &self,
+    row_orders: &[RowOrder],
+    auto_fetch: bool,
+  ) -> HashSet<RowId> {
+    self
+      .body
+      .block
+      .get_rows_from_row_orders(row_orders, auto_fetch)
+      .await
+      .into_iter()
+      .filter(|row| row.archived)
+      .map(|row| row.id)
+      .collect()
+  }
+
+  /// Return the archived rows of the view, ordered by the [RowOrder]s of the view.
+  pub async fn get_archived_rows_for_view(&self, view_id: &str, auto_fetch: bool) -> Vec<Row> {
+    let row_orders = self.get_row_orders_for_view(view_id);
+    self
+      .body
+      .block
+      .get_rows_from_row_orders(&row_orders, auto_fetch)
+      .await
+      .into_iter()
+      .filter(|row| row.archived)
+      .collect()
+  }
+
   /// Update the row
   pub async fn update_row<F>(&mut self, row_id: RowId, f: F)
   where
     F: FnOnce(RowUpdate),
   {
-    self.body.block.update_row(row_id, f).await;
+    self.body.block.update_row(row_id.clone(), f).await;
+    self.refresh_primary_cell(&row_id).await;
   }
 
   /// Update the meta of the row
@@ -513,6 +751,127 @@ impl Database {
     self.body.block.get_row_meta(row_id).await
   }
 
+  /// Return the comments of the row grouped by thread. The number of comments of a row is also
+  /// in its [RowMeta], and their changes are sent as
+  /// [crate::rows::RowChange::DidUpdateRowComment].
+  pub async fn get_row_comments(&self, row_id: &RowId) -> Vec<RowCommentThread> {
+    match self.body.block.get_database_row(row_id).await {
+      Some(database_row) => row_comment_threads(database_row.read().await.get_comments()),
+      None => vec![],
+    }
+  }
+
+  /// Add the comment to the row. A reply must reply to the first comment of a thread of the row.
+  pub async fn add_row_comment(
+    &mut self,
+    row_id: &RowId,
+    comment: RowComment,
+  ) -> Result<(), DatabaseError> {
+    let database_row = self.get_existing_database_row(row_id).await?;
+    let mut database_row = database_row.write().await;
+    if let Some(parent_id) = &comment.parent_id {
+      let comments = database_row.get_comments();
+      if !comments
+        .iter()
+        .any(|thread_comment| &thread_comment.id == parent_id && !thread_comment.is_reply())
+      {
+        return Err(DatabaseError::NoRequiredData(format!(
+          "thread {}",
+          parent_id
+        )));
+      }
+    }
+    database_row.add_comment(comment);
+    Ok(())
+  }
+
+  /// Replace the content of the comment of the row.
+  pub async fn edit_row_comment(
+    &mut self,
+    row_id: &RowId,
+    comment_id: &str,
+    content: &str,
+  ) -> Result<(), DatabaseError> {
+    let database_row = self.get_existing_database_row(row_id).await?;
+    let updated = database_row
+      .write()
+      .await
+      .update_comment(comment_id, |comment| {
+        comment.content = content.to_string();
+        comment.edited_at = timestamp();
+      });
+    if !updated {
+      return Err(DatabaseError::NoRequiredData(format!(
+        "comment {}",
+        comment_id
+      )));
+    }
+    Ok(())
+  }
+
+  /// Resolve or reopen the thread started by the comment.
+  pub async fn resolve_row_comment_thread(
+    &mut self,
+    row_id: &RowId,
+    comment_id: &str,
+    resolved: bool,
+  ) -> Result<(), DatabaseError> {
+    let database_row = self.get_existing_database_row(row_id).await?;
+    let mut database_row = database_row.write().await;
+    let is_thread = database_row
+      .get_comments()
+      .iter()
+      .any(|comment| comment.id == comment_id && !comment.is_reply());
+    if !is_thread {
+      return Err(DatabaseError::NoRequiredData(format!(
+        "thread {}",
+        comment_id
+      )));
+    }
+    database_row.update_comment(comment_id, |comment| comment.resolved = resolved);
+    Ok(())
+  }
+
+  /// Remove the comment of the row and, if it starts a thread, its replies. Return the removed
+  /// comments.
+  pub async fn remove_row_comment(
+    &mut self,
+    row_id: &RowId,
+    comment_id: &str,
+  ) -> Result<Vec<RowComment>, DatabaseError> {
+    let database_row = self.get_existing_database_row(row_id).await?;
+    let removed = database_row.write().await.remove_comment(comment_id);
+    if removed.is_empty() {
+      return Err(DatabaseError::NoRequiredData(format!(
+        "comment {}",
+        comment_id
+      )));
+    }
+    Ok(removed)
+  }
+
+  /// Unlike [Database::get_row], fail if the row doesn't exist instead of returning an
+  /// empty row.
+  async fn get_existing_database_row(
+    &self,
+    row_id: &RowId,
+  ) -> Result<Arc<RwLock<DatabaseRow>>, DatabaseError> {
+    let not_found = || DatabaseError::DatabaseRowNotFound {
+      row_id: row_id.clone(),
+      reason: "the row doesn't exist".to_string(),
+    };
+    let database_row = self
+      .body
+      .block
+      .get_database_row(row_id)
+      .await
+      .ok_or_else(not_found)?;
+    if database_row.read().await.get_row().is_none() {
+      return Err(not_found());
+    }
+    Ok(database_row)
+  }
+
   /// Return [TypeOptionCellReader] for the given field id.
   pub fn get_cell_reader(&self, field_id: &str) -> Option<Box<dyn TypeOptionCellReader>> {
     let txn = self.collab.transact();
@@ -606,7 +965,7 @@ impl Database {
   }
 
   /// Return a list of [Row] for the given view.
-  /// The rows here are ordered by [RowOrder]s of the view.
+  /// The rows here are ordered by [RowOrder]s of the view, the archived rows are skipped.
   pub async fn get_rows_for_view(
     &self,
     view_id: &str,
@@ -618,21 +977,64 @@ impl Database {
     self
       .get_rows_from_row_orders(row_orders, chunk_size, cancel_token, auto_fetch)
       .await
+      .filter(|result| future::ready(!matches!(result, Ok(row) if row.archived)))
   }
 
-  pub async fn get_row_order_at_index(&self, view_id: &str, index: u32) -> Option<RowOrder> {
-    let txn = self.collab.transact();
-    self.body.views.get_row_order_at_index(&txn, view_id, index)
+  /// Return the row order at the index of the view. Unless `include_archived` is true, the
+  /// archived rows are skipped: the index counts the other rows only, as in
+  /// [Self::get_row_index].
+  ///
+  /// Skipping the archived rows loads every row collab of the view to read its archived flag,
+  /// which is costly for a single lookup in a large view.
+  pub async fn get_row_order_at_index(
+    &self,
+    view_id: &str,
+    index: u32,
+    include_archived: bool,
+  ) -> Option<RowOrder> {
+    if include_archived {
+      let txn = self.collab.transact();
+      return self.body.views.get_row_order_at_index(&txn, view_id, index);
+    }
+    let row_orders = self.get_row_orders_for_view(view_id);
+    let archived_row_ids = self.get_archived_row_ids(&row_orders, false).await;
+    row_orders
+      .into_iter()
+      .filter(|order| !archived_row_ids.contains(&order.id))
+      .nth(index as usize)
   }
 
+  /// Return the orders of all the rows of the view, the archived rows included: the archived
+  /// flag is stored in the row collabs, which are not read here. Use [Self::get_rows_for_view],
+  /// or [Self::get_row_order_at_index] and [Self::get_rows_in_range] with `include_archived`
+  /// false, to skip them.
   pub fn get_row_orders_for_view(&self, view_id: &str) -> Vec<RowOrder> {
     let txn = self.collab.transact();
     self.body.views.get_row_orders(&txn, view_id)
   }
 
-  pub fn get_row_index(&self, view_id: &str, row_id: &RowId) -> Option<usize> {
-    let txn = self.collab.transact();
-    self.body.index_of_row(&txn, view_id, row_id)
+  /// Return the index of the row in the view, None if the row is not found. Unless
+  /// `include_archived` is true, the archived rows are skipped: the index counts the other rows
+  /// only, as in [Self::get_row_order_at_index], and None is returned for an archived row.
+  ///
+  /// Skipping the archived rows loads every row collab of the view to read its archived flag,
+  /// which is costly for a single lookup in a large view.
+  pub async fn get_row_index(
+    &self,
+    view_id: &str,
+    row_id: &RowId,
+    include_archived: bool,
+  ) -> Option<usize> {
+    if include_archived {
+      let txn = self.collab.transact();
+      return self.body.index_of_row(&txn, view_id, row_id);
+    }
+    let row_orders = self.get_row_orders_for_view(view_id);
+    let archived_row_ids = self.get_archived_row_ids(&row_orders, false).await;
+    row_orders
+      .into_iter()
+      .filter(|order| !archived_row_ids.contains(&order.id))
+      .position(|order| &order.id == row_id)
   }
 
   /// Return a list of [Row] for the given view.
@@ -661,7 +1063,62 @@ impl Database {
     })
   }
 
-  /// Return a list of [RowCell] for the given view and field.
+  /// Split the row orders of the view into chunks of `chunk_size` rows, see [RowChunks]. Only
+  /// the row orders of the view are read, none of the row collabs is loaded.
+  pub fn get_row_chunks_for_view(&self, view_id: &str, chunk_size: usize) -> RowChunks {
+    RowChunks::new(self.get_row_orders_for_view(view_id), chunk_size)
+  }
+
+  /// Return the rows of the range of the view, ordered by the [RowOrder]s of the view.
+  ///
+  /// The row collabs of the chunks containing the range are loaded, and no others, so reading
+  /// the visible rows of a large view doesn't load all its rows. The rows of the same chunks
+  /// around the range are cached for the next reads.
+  ///
+  /// The range is a range of positions in the [RowOrder]s of the view, the archived rows
+  /// included. Unless `include_archived` is true, the archived rows of the range are skipped, so
+  /// fewer rows than the length of the range may be returned.
+  pub async fn get_rows_in_range(
+    &self,
+    view_id: &str,
+    range: Range<usize>,
+    chunk_size: usize,
+    include_archived: bool,
+    auto_fetch: bool,
+  ) -> Result<Vec<Row>, DatabaseError> {
+    let chunks = self.get_row_chunks_for_view(view_id, chunk_size);
+    let database_id = self.get_database_id();
+    let mut rows = vec![];
+    for chunk in chunks.chunks_for_range(range.clone()) {
+      let mut database_rows = HashMap::new();
+      for database_row in self
+        .body
+        .block
+        .init_database_rows(chunk.row_ids(), auto_fetch)
+        .await?
+      {
+        let row_id = database_row.read().await.row_id.clone();
+        database_rows.insert(row_id, database_row);
+      }
+      for (position, row_order) in chunk.range().zip(chunk.row_orders.iter()) {
+        if !range.contains(&position) {
+          continue;
+        }
+        let row = match database_rows.get(&row_order.id) {
+          Some(database_row) => database_row.read().await.get_row(),
+          None => None,
+        };
+        let row = row.unwrap_or_else(|| Row::empty(row_order.id.clone(), &database_id));
+        if include_archived || !row.archived {
+          rows.push(row);
+        }
+      }
+    }
+    Ok(rows)
+  }
+
+  /// Return a list of [RowCell] for the given view and field. The cells of the archived rows are
+  /// skipped, so they are not part of the calculations of the view.
   pub async fn get_cells_for_field(
     &self,
     view_id: &str,
@@ -1156,6 +1613,38 @@ impl Database {
       });
   }
 
+  /// Return the bars of the timeline view that overlap the window, from `start` to `end`
+  /// timestamps in seconds, and the dependencies of these bars.
+  /// Return None if the view has no timeline layout setting or no start field.
+  pub async fn get_timeline_window(
+    &self,
+    view_id: &str,
+    start: i64,
+    end: i64,
+    auto_fetch: bool,
+  ) -> Option<TimelineWindow> {
+    let setting =
+      self.get_layout_setting::<TimelineLayoutSetting>(view_id, &DatabaseLayout::Timeline)?;
+    if setting.start_field_id.is_empty() {
+      return None;
+    }
+    let start_cells = self
+      .get_cells_for_field(view_id, &setting.start_field_id, auto_fetch)
+      .await;
+    let end_cells =
+      if setting.end_field_id.is_empty() || setting.end_field_id == setting.start_field_id {
+        None
+      } else {
+        Some(
+          self
+            .get_cells_for_field(view_id, &setting.end_field_id, auto_fetch)
+            .await,
+        )
+      };
+    let bars = timeline_bars(start_cells, end_cells);
+    Some(TimelineWindow::new(&setting, bars, start, end))
+  }
+
   /// Returns the field settings for the given field ids.
   /// If None, return field settings for all fields
   pub fn get_field_settings<T: From<FieldSettingsMap>>(
@@ -1284,6 +1773,53 @@ impl Database {
     Ok(())
   }
 
+  /// Export the configuration of the view, see [ViewDefinition].
+  pub fn export_view_definition(&self, view_id: &str) -> Option<ViewDefinition> {
+    let view = self.get_view(view_id)?;
+    let fields = self.get_fields(None);
+    Some(ViewDefinition::from_view(&view, &fields))
+  }
+
+  /// Create a linked view configured by the definition, exported from a view of this database or
+  /// of another one. The fields of the definition are renamed by the mapping, see
+  /// [ViewDefinition::suggest_field_mapping], and the settings of the fields that are not mapped
+  /// to a field of this database are skipped. Return the id of the new view.
+  pub fn import_view_definition(
+    &mut self,
+    definition: &ViewDefinition,
+    field_mapping: &FieldMapping,
+  ) -> Result<String, DatabaseError> {
+    let field_ids = self
+      .get_fields(None)
+      .into_iter()
+      .map(|field| field.id)
+      .collect::<HashSet<_>>();
+    let field_mapping = field_mapping
+      .iter()
+      .filter(|(_, field_id)| field_ids.contains(*field_id))
+      .map(|(from, to)| (from.clone(), to.clone()))
+      .collect::<FieldMapping>();
+    let definition = definition.with_field_mapping(&field_mapping);
+
+    let view_id = gen_database_view_id();
+    let timestamp = timestamp();
+    self.create_linked_view(CreateViewParams {
+      database_id: self.get_database_id(),
+      view_id: view_id.clone(),
+      name: definition.name,
+      layout: definition.layout,
+      layout_settings: definition.layout_settings,
+      filters: definition.filters,
+      group_settings: definition.group_settings,
+      sorts: definition.sorts,
+      field_settings: definition.field_settings,
+      created_at: timestamp,
+      modified_at: timestamp,
+      ..Default::default()
+    })?;
+    Ok(view_id)
+  }
+
   /// Create a linked view that duplicate the target view's setting including filter, sort,
   /// group, field setting, etc.
   pub fn duplicate_linked_view(&mut self, view_id: &str) -> Option<DatabaseView> {
@@ -1329,6 +1865,402 @@ impl Database {
     })
   }
 
+  /// Return the clusters of rows whose cells of the key fields match, e.g. the contacts with the
+  /// same email in an imported CRM table. The cells are compared by their text, trimmed and
+  /// lowercased. The rows with an empty key cell are skipped.
+  ///
+  /// Pass each cluster to [Self::merge_rows] to deduplicate the rows.
+  pub async fn find_duplicate_rows(
+    &self,
+    key_field_ids: &[&str],
+    auto_fetch: bool,
+  ) -> Vec<DuplicateRows> {
+    let rows = self
+      .collect_all_rows(auto_fetch)
+      .await
+      .into_iter()
+      .flatten()
+      .collect::<Vec<_>>();
+    let readers = key_field_ids
+      .iter()
+      .map(|field_id| self.get_cell_reader(field_id))
+      .collect::<Vec<_>>();
+    group_duplicate_rows(&rows, key_field_ids, &readers)
+  }
+
+  /// Deduplicate the rows whose cells of the key fields match, see [Self::find_duplicate_rows].
+  /// The first row of each cluster, in the order of the inline view, is kept and the relations
+  /// to its duplicates are re-pointed to it.
+  ///
+  /// Return the clusters of duplicated rows.
+  pub async fn dedupe_rows(
+    &mut self,
+    key_field_ids: &[&str],
+    policy: DuplicateRowPolicy,
+    auto_fetch: bool,
+  ) -> Vec<DuplicateRows> {
+    let duplicates = self.find_duplicate_rows(key_field_ids, auto_fetch).await;
+    for cluster in &duplicates {
+      let Some((keep_row_id, duplicate_row_ids)) = cluster.row_ids.split_first() else {
+        continue;
+      };
+      match policy {
+        DuplicateRowPolicy::Merge => {
+          self
+            .merge_rows(keep_row_id, duplicate_row_ids, auto_fetch)
+            .await;
+        },
+        DuplicateRowPolicy::Skip => {
+          let replaced = duplicate_row_ids
+            .iter()
+            .map(|row_id| (row_id.clone(), keep_row_id.clone()))
+            .collect::<HashMap<_, _>>();
+          self.repoint_relations(&replaced, auto_fetch).await;
+          self.remove_rows(duplicate_row_ids).await;
+        },
+      }
+    }
+    duplicates
+  }
+
+  /// Merge the duplicated rows into the kept row, then remove them.
+  ///
+  /// For each field, the kept row takes the richest cell, the one with the longest text, and
+  /// the relation cells are combined. The relation cells of the other rows pointing to a
+  /// duplicated row are re-pointed to the kept row. Call [Self::repoint_relations] on the
+  /// databases relating to this one to re-point their cells too.
+  ///
+  /// Return the merged row, or None if the kept row is not found.
+  pub async fn merge_rows(
+    &mut self,
+    keep_row_id: &RowId,
+    duplicate_row_ids: &[RowId],
+    auto_fetch: bool,
+  ) -> Option<Row> {
+    let mut rows = self
+      .collect_all_rows(auto_fetch)
+      .await
+      .into_iter()
+      .flatten()
+      .map(|row| (row.id.clone(), row))
+      .collect::<HashMap<_, _>>();
+    let kept_row = rows.remove(keep_row_id)?;
+    let duplicates = duplicate_row_ids
+      .iter()
+      .filter(|row_id| *row_id != keep_row_id)
+      .filter_map(|row_id| rows.remove(row_id))
+      .collect::<Vec<_>>();
+    let replaced = duplicates
+      .iter()
+      .map(|row| (row.id.clone(), keep_row_id.clone()))
+      .collect::<HashMap<_, _>>();
+
+    let mut merged_cells = Cells::new();
+    for field in self.get_all_fields() {
+      let kept_cell = kept_row.cells.get(&field.id);
+      if FieldType::from(field.field_type).is_relation() {
+        let cells = std::iter::once(kept_cell)
+          .chain(duplicates.iter().map(|row| row.cells.get(&field.id)))
+          .collect::<Vec<_>>();
+        let kept_row_ids = kept_cell
+          .map(|cell| RelationCellData::from(cell).row_ids)
+          .unwrap_or_default();
+        if let Some(cell) = merge_relation_cells(&cells, &replaced) {
+          if RelationCellData::from(&cell).row_ids != kept_row_ids {
+            merged_cells.insert(field.id.clone(), cell);
+          }
+        }
+        continue;
+      }
+
+      let reader = self.get_cell_reader(&field.id);
+      let mut richest = kept_cell;
+      let mut richness = cell_richness(reader.as_deref(), kept_cell);
+      for row in &duplicates {
+        let cell = row.cells.get(&field.id);
+        let cell_richness = cell_richness(reader.as_deref(), cell);
+        if cell_richness > richness {
+          richest = cell;
+          richness = cell_richness;
+        }
+      }
+      if let Some(cell) = richest {
+        if richest != kept_cell {
+          merged_cells.insert(field.id.clone(), cell.clone());
+        }
+      }
+    }
+
+    if !merged_cells.is_empty() {
+      self
+        .update_row(keep_row_id.clone(), |update| {
+          update.update_cells(|mut cells_update| {
+            for (field_id, cell) in merged_cells {
+              cells_update = cells_update.insert_cell(&field_id, cell);
+            }
+          });
+        })
+        .await;
+    }
+    self.repoint_relations(&replaced, auto_fetch).await;
+    let duplicate_row_ids = duplicates.into_iter().map(|row| row.id).collect::<Vec<_>>();
+    self.remove_rows(&duplicate_row_ids).await;
+    Some(self.get_row(keep_row_id).await)
+  }
+
+  /// Replace the row ids of the relation cells, the keys of `replaced` by their values. Used to
+  /// re-point the relations to the rows merged by [Self::merge_rows].
+  ///
+  /// Return the number of updated rows.
+  pub async fn repoint_relations(
+    &mut self,
+    replaced: &HashMap<RowId, RowId>,
+    auto_fetch: bool,
+  ) -> usize {
+    if replaced.is_empty() {
+      return 0;
+    }
+    let relation_field_ids = self
+      .get_all_fields()
+      .into_iter()
+      .filter(|field| FieldType::from(field.field_type).is_relation())
+      .map(|field| field.id)
+      .collect::<Vec<_>>();
+    if relation_field_ids.is_empty() {
+      return 0;
+    }
+
+    let rows = self
+      .collect_all_rows(auto_fetch)
+      .await
+      .into_iter()
+      .flatten()
+      .filter(|row| !replaced.contains_key(&row.id))
+      .collect::<Vec<_>>();
+    let mut num_of_updated_rows = 0;
+    for row in rows {
+      let mut updated_cells = Cells::new();
+      for field_id in &relation_field_ids {
+        let Some(cell) = row.cells.get(field_id) else {
+          continue;
+        };
+        let references_replaced = RelationCellData::from(cell)
+          .row_ids
+          .iter()
+          .any(|row_id| replaced.contains_key(row_id));
+        if references_replaced {
+          let cell = merge_relation_cells(&[Some(cell)], replaced).unwrap_or_default();
+          updated_cells.insert(field_id.clone(), cell);
+        }
+      }
+      if updated_cells.is_empty() {
+        continue;
+      }
+      num_of_updated_rows += 1;
+      self
+        .update_row(row.id, |update| {
+          update.update_cells(|mut cells_update| {
+            for (field_id, cell) in updated_cells {
+              cells_update = cells_update.insert_cell(&field_id, cell);
+            }
+          });
+        })
+        .await;
+    }
+    num_of_updated_rows
+  }
+
+  /// Merge the select options into the target option: the cells of the field selecting one of
+  /// the merged options select the target instead, then the merged options are removed from the
+  /// field. Return the number of updated rows.
+  ///
+  /// Every row is loaded before any cell is rewritten, the merge fails without changing anything
+  /// if a row can't be loaded. The options are removed once all the cells are rewritten, so no
+  /// cell is left selecting a missing option.
+  pub async fn merge_select_options(
+    &mut self,
+    field_id: &str,
+    merged_option_ids: &[String],
+    target_option_id: &str,
+    auto_fetch: bool,
+  ) -> Result<usize, DatabaseError> {
+    let (field_type, mut type_option) = self.get_select_type_option(field_id)?;
+    if !type_option
+      .options
+      .iter()
+      .any(|option| option.id == target_option_id)
+    {
+      return Err(DatabaseError::NoRequiredData(format!(
+        "select option {} of the field {}",
+        target_option_id, field_id
+      )));
+    }
+    let replaced = merged_option_ids
+      .iter()
+      .filter(|option_id| *option_id != target_option_id)
+      .map(|option_id| (option_id.clone(), target_option_id.to_string()))
+      .collect::<HashMap<_, _>>();
+    if replaced.is_empty() {
+      return Ok(0);
+    }
+
+    let rewrites = self
+      .load_all_rows_for_update(auto_fetch)
+      .await?
+      .into_iter()
+      .filter_map(|(row, database_row)| {
+        let mut option_ids = SelectOptionIds::from(row.cells.get(field_id)?);
+        option_ids
+          .replace_ids(&replaced)
+          .then(|| (row.id, database_row, option_ids.to_cell(field_type)))
+      })
+      .collect::<Vec<_>>();
+    let num_of_updated_rows = rewrites.len();
+    for (row_id, database_row, cell) in rewrites {
+      database_row.write().await.update(|update| {
+        update.update_cells(|cells_update| {
+          cells_update.insert_cell(field_id, cell);
+        });
+      });
+      self.refresh_primary_cell(&row_id).await;
+    }
+
+    type_option
+      .options
+      .retain(|option| !replaced.contains_key(&option.id));
+    self.set_select_type_option(field_id, field_type, type_option);
+    Ok(num_of_updated_rows)
+  }
+
+  /// Remove the select options of the field that no cell selects. Return the removed options.
+  ///
+  /// Fail without removing any option if a row can't be loaded, its cell may select one.
+  pub async fn remove_unused_select_options(
+    &mut self,
+    field_id: &str,
+    auto_fetch: bool,
+  ) -> Result<Vec<SelectOption>, DatabaseError> {
+    let (field_type, mut type_option) = self.get_select_type_option(field_id)?;
+    let used_option_ids = self
+      .load_all_rows_for_update(auto_fetch)
+      .await?
+      .into_iter()
+      .filter_map(|(row, _)| row.cells.get(field_id).map(SelectOptionIds::from))
+      .flat_map(SelectOptionIds::into_inner)
+      .collect::<HashSet<_>>();
+    let (used, unused): (Vec<_>, Vec<_>) = type_option
+      .options
+      .into_iter()
+      .partition(|option| used_option_ids.contains(&option.id));
+    if !unused.is_empty() {
+      type_option.options = used;
+      self.set_select_type_option(field_id, field_type, type_option);
+    }
+    Ok(unused)
+  }
+
+  /// Set the colors of the select options of the field, `colors` maps the option ids to their
+  /// new color. The options are updated in one transaction. Return the number of recolored
+  /// options.
+  pub fn recolor_select_options(
+    &mut self,
+    field_id: &str,
+    colors: &HashMap<String, SelectOptionColor>,
+  ) -> Result<usize, DatabaseError> {
+    let (field_type, mut type_option) = self.get_select_type_option(field_id)?;
+    let mut num_of_recolored = 0;
+    for option in type_option.options.iter_mut() {
+      if let Some(color) = colors.get(&option.id) {
+        if option.color != *color {
+          option.color = color.clone();
+          num_of_recolored += 1;
+        }
+      }
+    }
+    if num_of_recolored > 0 {
+      self.set_select_type_option(field_id, field_type, type_option);
+    }
+    Ok(num_of_recolored)
+  }
+
+  /// Load all the rows of the database, failing if one of them can't be loaded. Unlike
+  /// [Database::collect_all_rows], which skips them, an update of all the rows must not miss one.
+  async fn load_all_rows_for_update(
+    &self,
+    auto_fetch: bool,
+  ) -> Result<Vec<(Row, Arc<RwLock<DatabaseRow>>)>, DatabaseError> {
+    let row_ids = {
+      let txn = self.collab.transact();
+      let inline_view_id = self.body.get_inline_view_id(&txn);
+      self
+        .body
+        .views
+        .get_row_orders(&txn, &inline_view_id)
+        .into_iter()
+        .map(|order| order.id)
+        .collect::<Vec<_>>()
+    };
+    let mut seen = HashSet::new();
+    let row_ids = row_ids
+      .into_iter()
+      .filter(|row_id| seen.insert(row_id.clone()))
+      .collect::<Vec<_>>();
+    let mut loaded_rows = HashMap::new();
+    for database_row in self
+      .body
+      .block
+      .init_database_rows(row_ids.clone(), auto_fetch)
+      .await?
+    {
+      let row = database_row.read().await.get_row();
+      if let Some(row) = row {
+        loaded_rows.insert(row.id.clone(), (row, database_row));
+      }
+    }
+    row_ids
+      .into_iter()
+      .map(|row_id| {
+        loaded_rows
+          .remove(&row_id)
+          .ok_or_else(|| DatabaseError::DatabaseRowNotFound {
+            row_id,
+            reason: "the row can't be loaded".to_string(),
+          })
+      })
+      .collect()
+  }
+
+  fn get_select_type_option(
+    &self,
+    field_id: &str,
+  ) -> Result<(FieldType, SelectTypeOption), DatabaseError> {
+    let field = self
+      .get_field(field_id)
+      .ok_or_else(|| DatabaseError::NoRequiredData(format!("field {}", field_id)))?;
+    let field_type = FieldType::from(field.field_type);
+    if !field_type.is_select_option() {
+      return Err(DatabaseError::NoRequiredData(format!(
+        "{} is not a select field",
+        field_id
+      )));
+    }
+    let type_option = field
+      .get_type_option::<SelectTypeOption>(field_type.type_id())
+      .unwrap_or_default();
+    Ok((field_type, type_option))
+  }
+
+  fn set_select_type_option(
+    &mut self,
+    field_id: &str,
+    field_type: FieldType,
+    type_option: SelectTypeOption,
+  ) {
+    self.update_field(field_id, |update| {
+      update.set_type_option(field_type.into(), Some(type_option.into()));
+    });
+  }
+
   pub fn duplicate_field(
     &mut self,
     view_id: &str,
@@ -1415,6 +2347,96 @@ impl Database {
     rows_stream.collect::<Vec<_>>().await
   }
 
+  /// Return the rows and fields that changed since the given revision, and the current revision
+  /// to pass to the next call. Pass [DatabaseRevision::default] to get all rows and fields as
+  /// inserted.
+  ///
+  /// Unchanged rows are only compared by the state vector of their collab, so polling a large
+  /// database is cheap when little changed.
+  pub async fn changes_since(
+    &self,
+    revision: &DatabaseRevision,
+    auto_fetch: bool,
+  ) -> Result<DatabaseChanges, DatabaseError> {
+    let mut changes = DatabaseChanges {
+      database_id: self.get_database_id(),
+      ..Default::default()
+    };
+
+    for field in self.get_all_fields() {
+      let digest = field_digest(&field)?;
+      let field_id = field.id.clone();
+      match revision.fields.get(&field_id) {
+        None => changes.inserted_fields.push(field),
+        Some(old_digest) if *old_digest != digest => changes.updated_fields.push(field),
+        Some(_) => {},
+      }
+      changes.revision.fields.insert(field_id, digest);
+    }
+    changes.deleted_field_ids = revision
+      .fields
+      .keys()
+      .filter(|field_id| !changes.revision.fields.contains_key(*field_id))
+      .cloned()
+      .collect();
+
+    let row_ids = self
+      .get_all_row_orders()
+      .await
+      .into_iter()
+      .map(|row_order| row_order.id)
+      .collect::<Vec<_>>();
+    let mut unloaded_row_ids = row_ids
+      .iter()
+      .map(|row_id| row_id.to_string())
+      .collect::<HashSet<_>>();
+    let rows = self
+      .init_database_rows(row_ids.clone(), 20, None, auto_fetch)
+      .collect::<Vec<_>>()
+      .await;
+    for database_row in rows.into_iter().flatten() {
+      let database_row = database_row.read().await;
+      let row_id = database_row.row_id.to_string();
+      unloaded_row_ids.remove(&row_id);
+      let state_vector = database_row.collab.transact().state_vector();
+      let is_inserted = !revision.rows.contains_key(&row_id);
+      let is_updated = !is_inserted
+        && revision
+          .row_state_vector(&row_id)
+          .is_none_or(|old| is_change_since_sv(&database_row.collab, &old));
+      changes.revision.set_row_state_vector(row_id, &state_vector);
+      if !is_inserted && !is_updated {
+        continue;
+      }
+      if let Some(row) = database_row.get_row() {
+        if is_inserted {
+          changes.inserted_rows.push(row);
+        } else {
+          changes.updated_rows.push(row);
+        }
+      }
+    }
+
+    // Keep the old state vector of the rows that couldn't be loaded, so they are compared again
+    // on the next call instead of being reported as inserted.
+    for row_id in unloaded_row_ids {
+      if let Some(encoded) = revision.rows.get(&row_id) {
+        changes.revision.rows.insert(row_id, encoded.clone());
+      }
+    }
+    let current_row_ids = row_ids
+      .iter()
+      .map(|row_id| row_id.as_str())
+      .collect::<HashSet<_>>();
+    changes.deleted_row_ids = revision
+      .rows
+      .keys()
+      .filter(|row_id| !current_row_ids.contains(row_id.as_str()))
+      .cloned()
+      .collect();
+    Ok(changes)
+  }
+
   /// Return row orders of the inline database view
   pub async fn get_all_row_orders(&self) -> Vec<RowOrder> {
     let txn = self.collab.transact();
@@ -1452,6 +2474,419 @@ impl Database {
     self.body.fields.get_field(&txn, field_id)
   }
 
+  /// The database related by the relation field, the database of the field being in
+  /// `current_workspace_id`. Return None if the field is not a relation field.
+  pub fn get_relation_reference(
+    &self,
+    field_id: &str,
+    current_workspace_id: &str,
+  ) -> Option<DatabaseReference> {
+    let field = self.get_field(field_id)?;
+    if FieldType::from(field.field_type) != FieldType::Relation {
+      return None;
+    }
+    let type_option = field
+      .get_type_option::<RelationTypeOption>(FieldType::Relation.type_id())
+      .unwrap_or_default();
+    Some(type_option.database_reference(current_workspace_id))
+  }
+
+  /// Return the rows related by the cell of the relation field, in the order of the cell. The
+  /// related database is resolved by the resolver, so it may belong to another workspace.
+  ///
+  /// Fail with [DatabaseError::WorkspaceAccessDenied] when the user can't read the workspace of
+  /// the related database, see [DatabaseError::is_access_denied].
+  pub async fn get_related_rows(
+    &self,
+    field_id: &str,
+    row_id: &RowId,
+    current_workspace_id: &str,
+    resolver: &dyn DatabaseReferenceResolver,
+  ) -> Result<Vec<Row>, DatabaseError> {
+    let reference = self
+      .get_relation_reference(field_id, current_workspace_id)
+      .ok_or_else(|| {
+        DatabaseError::NoRequiredData(format!("{} is not a relation field", field_id))
+      })?;
+    let row_ids = match self.get_cell(field_id, row_id).await.cell {
+      Some(cell) => RelationCellData::from(&cell).row_ids,
+      None => vec![],
+    };
+    if row_ids.is_empty() {
+      return Ok(vec![]);
+    }
+
+    let mut rows = resolver
+      .get_referenced_rows(&reference, &row_ids)
+      .await?
+      .into_iter()
+      .map(|row| (row.id.clone(), row))
+      .collect::<HashMap<_, _>>();
+    Ok(
+      row_ids
+        .iter()
+        .filter_map(|row_id| rows.remove(row_id))
+        .collect(),
+    )
+  }
+
+  /// Return the members of the cell of the person field, in the order of the cell. The members
+  /// the resolver doesn't know, e.g. the ones who left the workspace, are skipped.
+  pub async fn get_cell_members(
+    &self,
+    field_id: &str,
+    row_id: &RowId,
+    resolver: &dyn MemberResolver,
+  ) -> Result<Vec<WorkspaceMember>, DatabaseError> {
+    self.check_person_field(field_id)?;
+    Ok(match self.get_cell(field_id, row_id).await.cell {
+      Some(cell) => PersonCellData::from(&cell).members(resolver),
+      None => vec![],
+    })
+  }
+
+  /// Turn the field into a person field. The text of its cells, the names or the emails of the
+  /// members separated by commas, is mapped to the members, e.g. for the people columns of the
+  /// imported databases.
+  ///
+  /// Return false and keep the field as is if one of the names can't be resolved, so that no
+  /// name is lost, or if all the cells are empty.
+  pub async fn convert_to_person_field(
+    &mut self,
+    field_id: &str,
+    resolver: &dyn MemberResolver,
+    auto_fetch: bool,
+  ) -> Result<bool, DatabaseError> {
+    let rows = self
+      .collect_all_rows(auto_fetch)
+      .await
+      .into_iter()
+      .flatten()
+      .collect::<Vec<_>>();
+    let cells = {
+      let reader = self
+        .get_cell_reader(field_id)
+        .ok_or_else(|| DatabaseError::NoRequiredData(format!("field {}", field_id)))?;
+      let mut cells = vec![];
+      for row in rows {
+        let text = row
+          .cells
+          .get(field_id)
+          .map(|cell| reader.stringify_cell(cell))
+          .unwrap_or_default();
+        let (cell_data, unresolved) = PersonCellData::from_names(&text, resolver);
+        if !unresolved.is_empty() {
+          return Ok(false);
+        }
+        cells.push((row.id, cell_data));
+      }
+      cells
+    };
+    if cells.iter().all(|(_, cell_data)| cell_data.uids.is_empty()) {
+      return Ok(false);
+    }
+
+    let field_type = i64::from(FieldType::Person);
+    let type_option = PersonTypeOption {
+      allow_multiple: cells.iter().any(|(_, cell_data)| cell_data.uids.len() > 1),
+    };
+    self.update_field(field_id, |update| {
+      update
+        .set_field_type(field_type)
+        .set_type_option(field_type, Some(type_option.into()));
+    });
+    for (row_id, cell_data) in cells {
+      self
+        .update_row(row_id, |update| {
+          update.update_cells(|cells_update| {
+            cells_update.insert_cell(field_id, Cell::from(&cell_data));
+          });
+        })
+        .await;
+    }
+    Ok(true)
+  }
+
+  fn check_person_field(&self, field_id: &str) -> Result<(), DatabaseError> {
+    let field = self
+      .get_field(field_id)
+      .ok_or_else(|| DatabaseError::NoRequiredData(format!("field {}", field_id)))?;
+    if !FieldType::from(field.field_type).is_person() {
+      return Err(DatabaseError::NoRequiredData(format!(
+        "{} is not a person field",
+        field_id
+      )));
+    }
+    Ok(())
+  }
+
+  /// Generate the primary cells from the other cells of their rows with the template, e.g.
+  /// `{Last name}, {First name}` for a contact list. The fields are referenced by name, see
+  /// [TextTemplate], and stay referenced when they are renamed.
+  ///
+  /// The primary cell of a row is generated again when the row is created or updated with
+  /// [Database::update_row], and when its select options are merged, so the edits of the primary
+  /// cells are replaced. The edits made through the [DatabaseRow] of a row, the remote changes and
+  /// the renames of the select options aren't followed, call [Database::refresh_primary_cells]
+  /// after them.
+  ///
+  /// Return the number of rows whose primary cell changed. Fail with
+  /// [DatabaseError::NoRequiredData] if the template references an unknown field or the primary
+  /// field itself.
+  pub async fn set_primary_template(
+    &mut self,
+    template: &str,
+    auto_fetch: bool,
+  ) -> Result<usize, DatabaseError> {
+    let primary_field = self
+      .get_primary_field()
+      .ok_or_else(|| DatabaseError::NoRequiredData("primary field".to_string()))?;
+    let fields = self.get_all_fields();
+    let template = TextTemplate::parse(template);
+    let mut field_ids = HashMap::new();
+    for name in template.field_names() {
+      let field = fields
+        .iter()
+        .find(|field| field.name == name)
+        .ok_or_else(|| DatabaseError::NoRequiredData(format!("field {}", name)))?;
+      if field.id == primary_field.id {
+        return Err(DatabaseError::NoRequiredData(format!(
+          "{} is the primary field",
+          name
+        )));
+      }
+      field_ids.insert(name.to_string(), field.id.clone());
+    }
+    // The fields are stored by id, so renaming them doesn't break the template.
+    let stored = template.render(|name| {
+      field_ids
+        .get(name)
+        .map(|field_id| format!("{{{}}}", field_id))
+    });
+    {
+      let mut txn = self.collab.transact_mut();
+      self.body.metas.insert(
+        &mut txn,
+        DATABASE_PRIMARY_TEMPLATE,
+        Any::String(stored.into()),
+      );
+    }
+    Ok(self.refresh_primary_cells(auto_fetch).await)
+  }
+
+  /// Return the template of the primary cells with the current names of the fields, see
+  /// [Database::set_primary_template]. The fields deleted since are left out.
+  pub fn get_primary_template(&self) -> Option<String> {
+    let template = TextTemplate::parse(&self.get_stored_primary_template()?);
+    let fields = self.get_all_fields();
+    Some(template.render(|field_id| {
+      fields
+        .iter()
+        .find(|field| field.id == field_id)
+        .map(|field| format!("{{{}}}", field.name))
+    }))
+  }
+
+  /// Stop generating the primary cells, their current content is kept.
+  pub fn remove_primary_template(&mut self) {
+    let mut txn = self.collab.transact_mut();
+    self.body.metas.remove(&mut txn, DATABASE_PRIMARY_TEMPLATE);
+  }
+
+  /// Generate the primary cells of all the rows again, e.g. after the options of a select field
+  /// of the template were renamed or the rows were edited through their [DatabaseRow]. Return the
+  /// number of rows whose primary cell changed.
+  pub async fn refresh_primary_cells(&mut self, auto_fetch: bool) -> usize {
+    if self.get_stored_primary_template().is_none() {
+      return 0;
+    }
+    let mut params = self
+      .collect_all_rows(auto_fetch)
+      .await
+      .into_iter()
+      .flatten()
+      .map(|row| CreateRowParams::new(row.id, row.database_id).with_cells(row.cells))
+      .collect::<Vec<_>>();
+    let cells = self.generate_primary_cells(&mut params);
+    let count = cells.len();
+    for (row_id, field_id, cell) in cells {
+      self
+        .body
+        .block
+        .update_row(row_id, |update| {
+          update.update_cells(|cells_update| {
+            cells_update.insert_cell(&field_id, cell);
+          });
+        })
+        .await;
+    }
+    count
+  }
+
+  fn get_stored_primary_template(&self) -> Option<String> {
+    let txn = self.collab.transact();
+    self
+      .body
+      .metas
+      .get_with_txn::<_, String>(&txn, DATABASE_PRIMARY_TEMPLATE)
+  }
+
+  /// Generate the primary cell of the row after it was updated.
+  async fn refresh_primary_cell(&self, row_id: &RowId) {
+    if self.get_stored_primary_template().is_none() {
+      return;
+    }
+    let Some(database_row) = self.body.block.get_database_row(row_id).await else {
+      return;
+    };
+    let Some(row) = database_row.read().await.get_row() else {
+      return;
+    };
+    let mut params = [CreateRowParams::new(row.id, row.database_id).with_cells(row.cells)];
+    for (_, field_id, cell) in self.generate_primary_cells(&mut params) {
+      database_row.write().await.update(|update| {
+        update.update_cells(|cells_update| {
+          cells_update.insert_cell(&field_id, cell);
+        });
+      });
+    }
+  }
+
+  /// Set the primary cells of the rows generated by the template of the database, see
+  /// [Database::set_primary_template]. Return the cells that changed, by row.
+  fn generate_primary_cells(&self, params: &mut [CreateRowParams]) -> Vec<(RowId, String, Cell)> {
+    let Some(template) = self.get_stored_primary_template() else {
+      return vec![];
+    };
+    let Some(primary_field) = self.get_primary_field() else {
+      return vec![];
+    };
+    let template = TextTemplate::parse(&template);
+    let readers = template
+      .field_names()
+      .into_iter()
+      .chain([primary_field.id.as_str()])
+      .filter_map(|field_id| Some((field_id.to_string(), self.get_cell_reader(field_id)?)))
+      .collect::<HashMap<_, _>>();
+    let Some(writer) = self.get_cell_writer(&primary_field.id) else {
+      return vec![];
+    };
+
+    let stringify =
+      |cells: &Cells, field_id: &str| match (cells.get(field_id), readers.get(field_id)) {
+        (Some(cell), Some(reader)) => reader.stringify_cell(cell),
+        _ => String::new(),
+      };
+    let mut changed = vec![];
+    for params in params.iter_mut() {
+      let text = template
+        .render(|field_id| Some(stringify(&params.cells, field_id)))
+        .trim()
+        .to_string();
+      if stringify(&params.cells, &primary_field.id) == text {
+        continue;
+      }
+      let cell = writer.convert_json_to_cell(serde_json::Value::String(text));
+      params.cells.insert(primary_field.id.clone(), cell.clone());
+      changed.push((params.id.clone(), primary_field.id.clone(), cell));
+    }
+    changed
+  }
+
+  /// Press the button of the row, running the actions of the button field in order. The urls
+  /// are opened and the events emitted by the handler, the cells are set in the row, so the
+  /// actions after a [ButtonAction::SetCells] see the new values.
+  pub async fn press_button(
+    &mut self,
+    field_id: &str,
+    row_id: &RowId,
+    handler: &dyn AutomationHandler,
+  ) -> Result<(), DatabaseError> {
+    let field = self
+      .get_field(field_id)
+      .ok_or_else(|| DatabaseError::NoRequiredData(format!("field {}", field_id)))?;
+    if !FieldType::from(field.field_type).is_button() {
+      return Err(DatabaseError::NoRequiredData(format!(
+        "{} is not a button field",
+        field_id
+      )));
+    }
+    let type_option = field
+      .get_type_option::<ButtonTypeOption>(FieldType::Button.type_id())
+      .unwrap_or_default();
+    let mut row = match self.get_database_row(row_id).await {
+      Some(database_row) => database_row.read().await.get_row(),
+      None => None,
+    }
+    .ok_or_else(|| DatabaseError::DatabaseRowNotFound {
+      row_id: row_id.clone(),
+      reason: format!("press the button {}", field_id),
+    })?;
+
+    for action in type_option.actions {
+      match action {
+        ButtonAction::OpenUrl { url } => {
+          let texts = self.cell_texts_by_field_name(&row);
+          let url = TextTemplate::parse(&url).render(|name| {
+            texts
+              .get(name)
+              .map(|text| utf8_percent_encode(text, NON_ALPHANUMERIC).to_string())
+          });
+          handler.open_url(&url);
+        },
+        ButtonAction::SetCells { cells } => {
+          let cells = cells
+            .into_iter()
+            .filter_map(|(field_id, value)| {
+              let cell = self.get_cell_writer(&field_id)?.convert_json_to_cell(value);
+              Some((field_id, cell))
+            })
+            .collect::<Vec<_>>();
+          row.cells.extend(cells.clone());
+          self
+            .update_row(row_id.clone(), |update| {
+              update.update_cells(|mut cells_update| {
+                for (field_id, cell) in cells {
+                  cells_update = cells_update.insert_cell(&field_id, cell);
+                }
+              });
+            })
+            .await;
+        },
+        ButtonAction::EmitEvent { event } => handler.emit_event(AutomationEvent {
+          event,
+          database_id: self.get_database_id(),
+          row_id: row_id.clone(),
+          field_id: field_id.to_string(),
+        }),
+      }
+    }
+    Ok(())
+  }
+
+  /// The text of the cells of the row by the names of their fields, to render the
+  /// [TextTemplate]s. When several fields have the same name, only one of them is used.
+  fn cell_texts_by_field_name(&self, row: &Row) -> HashMap<String, String> {
+    let mut texts = HashMap::new();
+    for field in self.get_all_fields() {
+      if texts.contains_key(&field.name) {
+        continue;
+      }
+      let field_type = FieldType::from(field.field_type);
+      let text = match (
+        row.cells.get(&field.id),
+        field.get_any_type_option(field_type.type_id()),
+      ) {
+        (Some(cell), Some(type_option)) => {
+          type_option_cell_reader(type_option, &field_type).stringify_cell(cell)
+        },
+        _ => String::new(),
+      };
+      texts.insert(field.name, text);
+    }
+    texts
+  }
+
   pub fn insert_field(&mut self, field: Field) {
     let mut txn = self.collab.transact_mut();
     self.body.fields.insert_field(&mut txn, field);
@@ -1928,6 +3363,7 @@ impl DatabaseBody {
       .await;
     rows
       .into_iter()
+      .filter(|row| !row.archived)
       .map(|row| RowCell::new(row.id, row.cells.get(field_id).cloned()))
       .collect()
   }
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use yrs::StateVector;
+use yrs::updates::decoder::Decode;
+use yrs::updates::encoder::Encode;
+
+use crate::error::DatabaseError;
+use crate::fields::Field;
+use crate::rows::Row;
+
+/// A marker of the state of the rows and fields of a database.
+///
+/// It's returned by [crate::database::Database::changes_since] and passed back on the next call,
+/// so only the rows and fields that changed in between are returned. Use [Self::encode] to store
+/// it as an opaque string.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DatabaseRevision {
+  /// The base64 encoded state vector of each row collab, keyed by row id.
+  #[serde(default)]
+  pub rows: HashMap<String, String>,
+  /// The digest of each field, keyed by field id.
+  #[serde(default)]
+  pub fields: HashMap<String, String>,
+}
+
+impl DatabaseRevision {
+  pub fn is_empty(&self) -> bool {
+    self.rows.is_empty() && self.fields.is_empty()
+  }
+
+  /// Encode the revision as a url safe string.
+  pub fn encode(&self) -> Result<String, DatabaseError> {
+    let json = serde_json::to_vec(self)?;
+    Ok(URL_SAFE_NO_PAD.encode(json))
+  }
+
+  pub fn decode(revision: &str) -> Result<Self, DatabaseError> {
+    let json = URL_SAFE_NO_PAD
+      .decode(revision)
+      .map_err(|err| DatabaseError::Internal(err.into()))?;
+    Ok(serde_json::from_slice(&json)?)
+  }
+
+  pub(crate) fn row_state_vector(&self, row_id: &str) -> Option<StateVector> {
+    let encoded = self.rows.get(row_id)?;
+    let bytes = URL_SAFE_NO_PAD.decode(encoded).ok()?;
+    StateVector::decode_v1(&bytes).ok()
+  }
+
+  pub(crate) fn set_row_state_vector(&mut self, row_id: String, state_vector: &StateVector) {
+    self
+      .rows
+      .insert(row_id, URL_SAFE_NO_PAD.encode(state_vector.encode_v1()));
+  }
+}
+
+/// The rows and fields that changed since a [DatabaseRevision].
+///
+/// A row is updated if its collab received any update, including updates that set a cell to the
+/// same value. Inserted rows are in the order of the inline view.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DatabaseChanges {
+  pub database_id: String,
+  /// The revision to pass to the next call.
+  pub revision: DatabaseRevision,
+  pub inserted_fields: Vec<Field>,
+  pub updated_fields: Vec<Field>,
+  pub deleted_field_ids: Vec<String>,
+  pub inserted_rows: Vec<Row>,
+  pub updated_rows: Vec<Row>,
+  pub deleted_row_ids: Vec<String>,
+}
+
+impl DatabaseChanges {
+  pub fn is_empty(&self) -> bool {
+    self.inserted_fields.is_empty()
+      && self.updated_fields.is_empty()
+      && self.deleted_field_ids.is_empty()
+      && self.inserted_rows.is_empty()
+      && self.updated_rows.is_empty()
+      && self.deleted_row_ids.is_empty()
+  }
+
+  pub fn to_json_value(&self) -> Result<JsonValue, DatabaseError> {
+    Ok(serde_json::to_value(self)?)
+  }
+}
+
+/// Return the digest of the field. The field is converted to a [JsonValue] first, whose maps
+/// are sorted, so the digest doesn't depend on the iteration order of the type options.
+pub(crate) fn field_digest(field: &Field) -> Result<String, DatabaseError> {
+  let json = serde_json::to_value(field)?.to_string();
+  Ok(URL_SAFE_NO_PAD.encode(Sha256::digest(json.as_bytes())))
+}
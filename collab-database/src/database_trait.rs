@@ -2,6 +2,9 @@ use crate::database::{Database, DatabaseBody, DatabaseContext, default_database_
 
 use crate::entity::CreateDatabaseParams;
 use crate::error::DatabaseError;
+use crate::fields::button_type_option::AutomationEvent;
+use crate::fields::person_type_option::WorkspaceMember;
+use crate::fields::relation_type_option::DatabaseReference;
 use crate::rows::{DatabaseRow, Row, RowChangeSender, RowId, default_database_row_from_row};
 use anyhow::anyhow;
 use async_trait::async_trait;
@@ -18,6 +21,7 @@ use futures::future::join_all;
 use rayon::prelude::*;
 use std::borrow::BorrowMut;
 use std::collections::HashMap;
+use std::fmt::Debug;
 use std::sync::Arc;
 use yrs::block::ClientID;
 
@@ -108,6 +112,54 @@ pub trait DatabaseRowCollabService: Send + Sync + 'static {
   ) -> Result<HashMap<RowId, Arc<RwLock<DatabaseRow>>>, DatabaseError>;
 }
 
+/// Resolve the databases referenced by the relation fields, which may belong to other workspaces
+/// than the database of the field, see [crate::fields::relation_type_option::RelationTypeOption].
+#[async_trait]
+pub trait DatabaseReferenceResolver: Send + Sync + 'static {
+  /// Return the rows of the referenced database, the rows that don't exist are skipped.
+  ///
+  /// Return [DatabaseError::WorkspaceAccessDenied] when the user can't read the workspace of the
+  /// database, and [DatabaseError::ReferenceUnavailable] when it can't be loaded, e.g. offline.
+  async fn get_referenced_rows(
+    &self,
+    reference: &DatabaseReference,
+    row_ids: &[RowId],
+  ) -> Result<Vec<Row>, DatabaseError>;
+}
+
+/// Resolve the workspace members referenced by the person fields, see
+/// [crate::fields::person_type_option::PersonTypeOption].
+pub trait MemberResolver: Send + Sync + Debug {
+  /// Return the member with the uid, None if the user isn't a member of the workspace.
+  fn get_member(&self, uid: i64) -> Option<WorkspaceMember>;
+
+  /// Return the member with the name or the email, e.g. to import the people columns of other
+  /// apps. See [WorkspaceMember::is_named].
+  fn find_member(&self, name_or_email: &str) -> Option<WorkspaceMember>;
+}
+
+impl MemberResolver for Vec<WorkspaceMember> {
+  fn get_member(&self, uid: i64) -> Option<WorkspaceMember> {
+    self.iter().find(|member| member.uid == uid).cloned()
+  }
+
+  fn find_member(&self, name_or_email: &str) -> Option<WorkspaceMember> {
+    self
+      .iter()
+      .find(|member| member.is_named(name_or_email))
+      .cloned()
+  }
+}
+
+/// The automation subsystem of the app, which runs the side effects of the button fields, see
+/// [crate::fields::button_type_option::ButtonTypeOption].
+pub trait AutomationHandler: Send + Sync {
+  /// Open the url of a pressed button, its templated cell values are already replaced.
+  fn open_url(&self, url: &str);
+
+  fn emit_event(&self, event: AutomationEvent);
+}
+
 #[async_trait]
 pub trait DatabaseCollabReader: Send + Sync + 'static {
   async fn reader_client_id(&self) -> ClientID;
@@ -1,11 +1,13 @@
 #![allow(clippy::upper_case_acronyms)]
 use crate::database::{DatabaseData, gen_database_id, gen_database_view_id, gen_row_id, timestamp};
 use crate::error::DatabaseError;
+use crate::fields::button_type_option::ButtonTypeOption;
 use crate::fields::checkbox_type_option::CheckboxTypeOption;
 use crate::fields::checklist_type_option::ChecklistTypeOption;
 use crate::fields::date_type_option::{DateTypeOption, TimeTypeOption};
 use crate::fields::media_type_option::MediaTypeOption;
 use crate::fields::number_type_option::NumberTypeOption;
+use crate::fields::person_type_option::PersonTypeOption;
 use crate::fields::relation_type_option::RelationTypeOption;
 use crate::fields::select_type_option::{MultiSelectTypeOption, SingleSelectTypeOption};
 use crate::fields::summary_type_option::SummarizationTypeOption;
@@ -286,6 +288,8 @@ pub enum FieldType {
   Translate = 12,
   Time = 13,
   Media = 14,
+  Person = 15,
+  Button = 16,
 }
 
 impl FieldType {
@@ -358,6 +362,8 @@ impl FieldType {
       FieldType::Translate => "Translate",
       FieldType::Time => "Time",
       FieldType::Media => "Media",
+      FieldType::Person => "Person",
+      FieldType::Button => "Button",
     };
     s.to_string()
   }
@@ -422,6 +428,14 @@ impl FieldType {
     matches!(self, FieldType::Media)
   }
 
+  pub fn is_person(&self) -> bool {
+    matches!(self, FieldType::Person)
+  }
+
+  pub fn is_button(&self) -> bool {
+    matches!(self, FieldType::Button)
+  }
+
   pub fn can_be_group(&self) -> bool {
     self.is_select_option() || self.is_checkbox() || self.is_url()
   }
@@ -449,6 +463,8 @@ impl From<i64> for FieldType {
       12 => FieldType::Translate,
       13 => FieldType::Time,
       14 => FieldType::Media,
+      15 => FieldType::Person,
+      16 => FieldType::Button,
       _ => {
         error!("Unknown field type: {}, fallback to text", index);
         FieldType::RichText
@@ -477,6 +493,8 @@ pub fn default_type_option_data_from_type(field_type: FieldType) -> TypeOptionDa
     FieldType::Relation => RelationTypeOption::default().into(),
     FieldType::Summary => SummarizationTypeOption::default().into(),
     FieldType::Translate => TranslateTypeOption::default().into(),
+    FieldType::Person => PersonTypeOption::default().into(),
+    FieldType::Button => ButtonTypeOption::default().into(),
   }
 }
 
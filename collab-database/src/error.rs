@@ -45,6 +45,22 @@ pub enum DatabaseError {
   #[error("Import data failed: {0}")]
   ImportData(String),
 
+  #[error("Invalid recurrence rule: {0}")]
+  InvalidRecurrenceRule(String),
+
+  #[error("No access to the database {database_id} of the workspace {workspace_id}")]
+  WorkspaceAccessDenied {
+    workspace_id: String,
+    database_id: String,
+  },
+
+  #[error("The database {database_id} of the workspace {workspace_id} is unavailable: {reason}")]
+  ReferenceUnavailable {
+    workspace_id: String,
+    database_id: String,
+    reason: String,
+  },
+
   #[error("Internal failure: {0}")]
   Internal(#[from] anyhow::Error),
 }
@@ -53,6 +69,12 @@ impl DatabaseError {
   pub fn is_no_required_data(&self) -> bool {
     matches!(self, DatabaseError::NoRequiredData(_))
   }
+
+  /// True if the user can't read the workspace of a referenced database, e.g. the database of a
+  /// relation field shared from another workspace. Show the related rows as unavailable then.
+  pub fn is_access_denied(&self) -> bool {
+    matches!(self, DatabaseError::WorkspaceAccessDenied { .. })
+  }
 }
 
 impl From<CollabValidateError> for DatabaseError {
@@ -2,8 +2,9 @@ use collab::preclude::{Map, MapExt, MapRef, ReadTxn, Subscription, TransactionMu
 
 use crate::database::timestamp;
 use crate::fields::{
-  Field, FieldBuilder, FieldChangeSender, FieldUpdate, field_from_map_ref, field_from_value,
-  field_id_from_value, primary_field_id_from_value, subscribe_field_change,
+  Field, FieldBuilder, FieldChangeReceiver, FieldChangeRouter, FieldChangeSender, FieldUpdate,
+  field_from_map_ref, field_from_value, field_id_from_value, primary_field_id_from_value,
+  subscribe_field_change,
 };
 use crate::views::FieldOrder;
 
@@ -12,17 +13,30 @@ pub struct FieldMap {
   container: MapRef,
   #[allow(dead_code)]
   subscription: Option<Subscription>,
+  router: FieldChangeRouter,
 }
 
 impl FieldMap {
   pub fn new(mut container: MapRef, field_change_tx: Option<FieldChangeSender>) -> Self {
-    let subscription = field_change_tx.map(|tx| subscribe_field_change(&mut container, tx));
+    let router = FieldChangeRouter::default();
+    let subscription =
+      field_change_tx.map(|tx| subscribe_field_change(&mut container, tx, router.clone()));
     Self {
       container,
       subscription,
+      router,
     }
   }
 
+  /// Subscribe to the changes of a single field. Returns `None` if the map is not observed, which
+  /// is the case when it's created without a [FieldChangeSender].
+  pub fn subscribe_field_change(&self, field_id: &str) -> Option<FieldChangeReceiver> {
+    self
+      .subscription
+      .as_ref()
+      .map(|_| self.router.subscribe(field_id))
+  }
+
   /// Insert a field into the map with a transaction
   pub fn insert_field(&self, txn: &mut TransactionMut, field: Field) {
     let map_ref: MapRef = self.container.get_or_init(txn, field.id.as_str());
@@ -1,5 +1,7 @@
 use crate::fields::{Field, field_from_map_ref, field_from_value};
 use collab::preclude::{DeepObservable, EntryChange, Event, MapRef, Subscription};
+use dashmap::DashMap;
+use std::sync::Arc;
 use tokio::sync::broadcast;
 use tracing::warn;
 
@@ -13,10 +15,59 @@ pub enum FieldChange {
   DidDeleteField { field_id: String },
 }
 
+impl FieldChange {
+  /// The id of the field that this change belongs to.
+  pub fn field_id(&self) -> &str {
+    match self {
+      FieldChange::DidCreateField { field } => &field.id,
+      FieldChange::DidUpdateField { field } => &field.id,
+      FieldChange::DidDeleteField { field_id } => field_id,
+    }
+  }
+}
+
+/// Routes [FieldChange]s to the subscribers of a single field.
+///
+/// Each subscribed field has its own channel, so a subscriber is only woken up by the changes of
+/// the field it's interested in. Channels without receivers are removed on the next change of
+/// their field.
+#[derive(Clone, Default)]
+pub struct FieldChangeRouter {
+  senders: Arc<DashMap<String, FieldChangeSender>>,
+}
+
+impl FieldChangeRouter {
+  pub fn subscribe(&self, field_id: &str) -> FieldChangeReceiver {
+    self
+      .senders
+      .entry(field_id.to_string())
+      .or_insert_with(|| broadcast::channel(100).0)
+      .subscribe()
+  }
+
+  fn send(&self, change: &FieldChange) {
+    let field_id = change.field_id();
+    let has_receivers = match self.senders.get(field_id) {
+      None => return,
+      Some(sender) => sender.send(change.clone()).is_ok(),
+    };
+    if !has_receivers {
+      self
+        .senders
+        .remove_if(field_id, |_, sender| sender.receiver_count() == 0);
+    }
+  }
+}
+
 pub(crate) fn subscribe_field_change(
   field_map: &mut MapRef,
   change_tx: FieldChangeSender,
+  router: FieldChangeRouter,
 ) -> Subscription {
+  let send = move |change: FieldChange| {
+    router.send(&change);
+    let _ = change_tx.send(change);
+  };
   field_map.observe_deep(move |txn, events| {
     for deep_event in events.iter() {
       match deep_event {
@@ -25,24 +76,23 @@ pub(crate) fn subscribe_field_change(
         Event::Map(event) => {
           let keys = event.keys(txn);
           for (key, value) in keys.iter() {
-            let _change_tx = change_tx.clone();
             match value {
               EntryChange::Inserted(value) => {
                 // tracing::trace!("field observer: Inserted: {}:{}", key, value);
                 if let Some(field) = field_from_value(value.clone(), txn) {
-                  let _ = change_tx.send(FieldChange::DidCreateField { field });
+                  send(FieldChange::DidCreateField { field });
                 }
               },
               EntryChange::Updated(_, _value) => {
                 // tracing::trace!("field observer: update: {}:{}", key, value);
                 if let Some(field) = field_from_map_ref(event.target(), txn) {
-                  let _ = change_tx.send(FieldChange::DidUpdateField { field });
+                  send(FieldChange::DidUpdateField { field });
                 }
               },
               EntryChange::Removed(_value) => {
                 let field_id = (**key).to_string();
                 if !field_id.is_empty() {
-                  let _ = change_tx.send(FieldChange::DidDeleteField { field_id });
+                  send(FieldChange::DidDeleteField { field_id });
                 } else {
                   warn!("field observer: delete: {}", key);
                 }
@@ -3,6 +3,7 @@ mod field_id;
 mod field_map;
 mod field_observer;
 mod field_settings;
+mod text_template;
 mod type_option;
 
 pub use field::*;
@@ -10,4 +11,5 @@ pub use field_id::*;
 pub use field_map::*;
 pub use field_observer::*;
 pub use field_settings::*;
+pub use text_template::*;
 pub use type_option::*;
@@ -0,0 +1,93 @@
+/// A text built from the cells of a row, e.g. `{Last name}, {First name}`. The fields are
+/// referenced by their names between braces. A brace without its pair is kept as is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextTemplate {
+  segments: Vec<TemplateSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplateSegment {
+  Text(String),
+  Field(String),
+}
+
+impl TextTemplate {
+  pub fn parse(template: &str) -> Self {
+    let mut segments = vec![];
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+      let Some(len) = rest[start + 1..].find('}') else {
+        break;
+      };
+      let name = &rest[start + 1..start + 1 + len];
+      // A brace inside the name, e.g. `{{Name}`, starts the placeholder again.
+      let (text, name) = match name.rfind('{') {
+        Some(index) => (&rest[..start + 1 + index], &name[index + 1..]),
+        None => (&rest[..start], name),
+      };
+      if !text.is_empty() {
+        segments.push(TemplateSegment::Text(text.to_string()));
+      }
+      segments.push(TemplateSegment::Field(name.trim().to_string()));
+      rest = &rest[start + 1 + len + 1..];
+    }
+    if !rest.is_empty() {
+      segments.push(TemplateSegment::Text(rest.to_string()));
+    }
+    Self { segments }
+  }
+
+  /// The names of the fields the template references, in the order they appear.
+  pub fn field_names(&self) -> Vec<&str> {
+    self
+      .segments
+      .iter()
+      .filter_map(|segment| match segment {
+        TemplateSegment::Field(name) => Some(name.as_str()),
+        TemplateSegment::Text(_) => None,
+      })
+      .collect()
+  }
+
+  pub fn references(&self, field_name: &str) -> bool {
+    self.field_names().contains(&field_name)
+  }
+
+  /// Replace the fields with the text returned by `value_of`. The unknown fields are replaced
+  /// with an empty text.
+  pub fn render<F>(&self, value_of: F) -> String
+  where
+    F: Fn(&str) -> Option<String>,
+  {
+    self
+      .segments
+      .iter()
+      .map(|segment| match segment {
+        TemplateSegment::Text(text) => text.clone(),
+        TemplateSegment::Field(name) => value_of(name).unwrap_or_default(),
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn text_template_test() {
+    let template = TextTemplate::parse("{Last name}, { First name } ({Age)");
+    assert_eq!(template.field_names(), vec!["Last name", "First name"]);
+    let text = template.render(|name| match name {
+      "Last name" => Some("Doe".to_string()),
+      "First name" => Some("John".to_string()),
+      _ => None,
+    });
+    assert_eq!(text, "Doe, John ({Age)");
+
+    let template = TextTemplate::parse("{{Name}} {Unknown}");
+    assert_eq!(template.field_names(), vec!["Name", "Unknown"]);
+    assert_eq!(template.render(|_| Some("x".to_string())), "{x} x");
+    assert_eq!(template.render(|_| None), "{} ");
+  }
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use collab::util::AnyMapExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::entity::FieldType;
+use crate::fields::{
+  TypeOptionCellReader, TypeOptionCellWriter, TypeOptionData, TypeOptionDataBuilder,
+};
+use crate::rows::{Cell, RowId, new_cell_builder};
+
+/// The type option of the button fields. Pressing the button of a row runs the actions in order,
+/// see [crate::database::Database::press_button]. The buttons don't store anything in the cells.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ButtonTypeOption {
+  /// The text displayed on the buttons.
+  #[serde(default)]
+  pub label: String,
+  #[serde(default)]
+  pub actions: Vec<ButtonAction>,
+}
+
+impl ButtonTypeOption {
+  pub fn new(label: &str) -> Self {
+    Self {
+      label: label.to_string(),
+      actions: vec![],
+    }
+  }
+
+  pub fn with_action(mut self, action: ButtonAction) -> Self {
+    self.actions.push(action);
+    self
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ButtonAction {
+  /// Open the url. It's a [crate::fields::TextTemplate] whose fields are replaced with the
+  /// percent-encoded text of the cells of the row, e.g. `https://google.com/search?q={Name}`.
+  OpenUrl { url: String },
+  /// Write the values into the cells of the row, by field id. The values are converted by the
+  /// [TypeOptionCellWriter] of the fields, the fields that don't exist anymore are skipped.
+  SetCells { cells: HashMap<String, Value> },
+  /// Emit the event to the automation subsystem, e.g. to start a workflow.
+  EmitEvent { event: String },
+}
+
+/// The event of a [ButtonAction::EmitEvent], emitted when a button is pressed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutomationEvent {
+  pub event: String,
+  pub database_id: String,
+  pub row_id: RowId,
+  /// The id of the button field.
+  pub field_id: String,
+}
+
+impl From<TypeOptionData> for ButtonTypeOption {
+  fn from(data: TypeOptionData) -> Self {
+    let label = data.get_as::<String>("label").unwrap_or_default();
+    let actions = data
+      .get_as::<String>("actions")
+      .map(|s| serde_json::from_str::<Vec<ButtonAction>>(&s).unwrap_or_default())
+      .unwrap_or_default();
+    Self { label, actions }
+  }
+}
+
+impl From<ButtonTypeOption> for TypeOptionData {
+  fn from(data: ButtonTypeOption) -> Self {
+    let actions = serde_json::to_string(&data.actions).unwrap_or_default();
+    TypeOptionDataBuilder::from([
+      ("label".into(), data.label.into()),
+      ("actions".into(), actions.into()),
+    ])
+  }
+}
+
+impl TypeOptionCellReader for ButtonTypeOption {
+  fn json_cell(&self, _cell: &Cell) -> Value {
+    Value::Null
+  }
+
+  fn stringify_cell(&self, _cell: &Cell) -> String {
+    String::new()
+  }
+
+  fn numeric_cell(&self, _cell: &Cell) -> Option<f64> {
+    None
+  }
+
+  fn convert_raw_cell_data(&self, _cell_data: &str) -> String {
+    String::new()
+  }
+}
+
+impl TypeOptionCellWriter for ButtonTypeOption {
+  fn convert_json_to_cell(&self, _json_value: Value) -> Cell {
+    new_cell_builder(FieldType::Button)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn button_type_option_serde_test() {
+    let type_option = ButtonTypeOption::new("Approve")
+      .with_action(ButtonAction::SetCells {
+        cells: HashMap::from([("status".to_string(), json!("Approved"))]),
+      })
+      .with_action(ButtonAction::EmitEvent {
+        event: "approved".to_string(),
+      });
+    let data = TypeOptionData::from(type_option.clone());
+    assert_eq!(
+      data.get_as::<String>("actions").unwrap(),
+      r#"[{"type":"set_cells","cells":{"status":"Approved"}},{"type":"emit_event","event":"approved"}]"#
+    );
+    assert_eq!(ButtonTypeOption::from(data), type_option);
+
+    // The unknown actions of the newer versions drop the actions, not the label.
+    let data = TypeOptionDataBuilder::from([
+      ("label".into(), "Send".to_string().into()),
+      (
+        "actions".into(),
+        r#"[{"type":"send_email"}]"#.to_string().into(),
+      ),
+    ]);
+    let type_option = ButtonTypeOption::from(data);
+    assert_eq!(type_option.label, "Send");
+    assert!(type_option.actions.is_empty());
+  }
+}
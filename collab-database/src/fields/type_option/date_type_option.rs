@@ -9,7 +9,7 @@ use crate::fields::{
 };
 use crate::rows::{Cell, new_cell_builder};
 use crate::template::entity::CELL_DATA;
-use chrono::{FixedOffset, MappedLocalTime, NaiveDateTime, NaiveTime, Offset};
+use chrono::{FixedOffset, MappedLocalTime, NaiveDate, NaiveDateTime, NaiveTime, Offset};
 use chrono_tz::Tz;
 use collab::util::AnyMapExt;
 use serde::de::Visitor;
@@ -297,6 +297,58 @@ impl DateTypeOption {
     }
   }
 
+  /// The date of the timestamp in the timezone of the field.
+  pub fn local_date(&self, timestamp: i64) -> Option<NaiveDate> {
+    let date_time = chrono::DateTime::from_timestamp(timestamp, 0)?.naive_utc();
+    let offset = self.get_timezone_offset(date_time);
+    Some(offset.from_utc_datetime(&date_time).date_naive())
+  }
+
+  /// The first and last dates of the cell in the timezone of the field. A cell that isn't a
+  /// range, or a range without an end, starts and ends the same day.
+  pub fn local_date_range(&self, cell_data: &DateCellData) -> Option<(NaiveDate, NaiveDate)> {
+    let start = self.local_date(cell_data.timestamp?)?;
+    let end = cell_data
+      .end_timestamp
+      .filter(|_| cell_data.is_range)
+      .and_then(|timestamp| self.local_date(timestamp))
+      .unwrap_or(start);
+    Some((start, end.max(start)))
+  }
+
+  /// Display the cell relative to `now`, in seconds, e.g. "tomorrow", "in 3 days" or
+  /// "2 hours ago". The days are counted in the timezone of the field, the hours and the
+  /// minutes only if the cell includes the time. A range is displayed from its start.
+  pub fn relative_display(&self, cell_data: &DateCellData, now: i64) -> String {
+    let Some(timestamp) = cell_data.timestamp else {
+      return "".to_string();
+    };
+    if cell_data.include_time {
+      let minutes = (timestamp - now) / 60;
+      if minutes == 0 {
+        return "now".to_string();
+      } else if minutes.abs() < 60 {
+        return format_relative(minutes, "minute");
+      } else if minutes.abs() < 24 * 60 {
+        return format_relative(minutes / 60, "hour");
+      }
+    }
+
+    let (Some(date), Some(today)) = (self.local_date(timestamp), self.local_date(now)) else {
+      return "".to_string();
+    };
+    let days = date.signed_duration_since(today).num_days();
+    match days {
+      0 => "today".to_string(),
+      1 => "tomorrow".to_string(),
+      -1 => "yesterday".to_string(),
+      _ if days.abs() < 7 => format_relative(days, "day"),
+      _ if days.abs() < 30 => format_relative(days / 7, "week"),
+      _ if days.abs() < 365 => format_relative(days / 30, "month"),
+      _ => format_relative(days / 365, "year"),
+    }
+  }
+
   /// returns offset of Tz timezone if provided or of the local timezone otherwise
   fn get_timezone_offset(&self, date_time: NaiveDateTime) -> FixedOffset {
     let current_timezone_offset = Local::now().offset().fix();
@@ -311,6 +363,16 @@ impl DateTypeOption {
   }
 }
 
+/// e.g. "in 3 days" if the count is positive, "3 days ago" otherwise.
+fn format_relative(count: i64, unit: &str) -> String {
+  let plural = if count.abs() == 1 { "" } else { "s" };
+  if count > 0 {
+    format!("in {} {}{}", count, unit, plural)
+  } else {
+    format!("{} {}{} ago", -count, unit, plural)
+  }
+}
+
 impl From<TypeOptionData> for DateTypeOption {
   fn from(data: TypeOptionData) -> Self {
     let date_format = data
@@ -820,4 +882,59 @@ mod tests {
     let str = date_type_option.stringify_cell(&Cell::from(&date_cell));
     assert_eq!(str, "Oct 12, 2019 07:20");
   }
+
+  #[test]
+  fn date_relative_display_test() {
+    let date_type_option = DateTypeOption::default_utc();
+    // 2023-01-01T12:00:00Z
+    let now = 1672574400;
+    let day = 24 * 60 * 60;
+    let display = |timestamp: i64| {
+      date_type_option.relative_display(&DateCellData::from_timestamp(timestamp), now)
+    };
+    assert_eq!(display(now - 12 * 60 * 60), "today");
+    assert_eq!(display(now + day), "tomorrow");
+    assert_eq!(display(now - day), "yesterday");
+    assert_eq!(display(now + 3 * day), "in 3 days");
+    assert_eq!(display(now - 14 * day), "2 weeks ago");
+    assert_eq!(display(now + 65 * day), "in 2 months");
+    assert_eq!(display(now - 400 * day), "1 year ago");
+
+    let display = |timestamp: i64| {
+      date_type_option.relative_display(&DateCellData::from_timestamp_include_time(timestamp), now)
+    };
+    assert_eq!(display(now + 30), "now");
+    assert_eq!(display(now + 5 * 60), "in 5 minutes");
+    assert_eq!(display(now - 3 * 60 * 60), "3 hours ago");
+    assert_eq!(display(now + 2 * day), "in 2 days");
+    assert_eq!(
+      date_type_option.relative_display(&DateCellData::default(), now),
+      ""
+    );
+  }
+
+  #[test]
+  fn date_local_date_range_test() {
+    let mut date_type_option = DateTypeOption::default_utc();
+    // 2023-01-01T20:00:00Z to 2023-01-03T20:00:00Z
+    let mut cell_data = DateCellData::from_timestamp(1672603200);
+    cell_data.end_timestamp = Some(1672776000);
+    let date = |day: u32| NaiveDate::from_ymd_opt(2023, 1, day).unwrap();
+    // The end is ignored unless the cell is a range.
+    assert_eq!(
+      date_type_option.local_date_range(&cell_data),
+      Some((date(1), date(1)))
+    );
+
+    cell_data.is_range = true;
+    assert_eq!(
+      date_type_option.local_date_range(&cell_data),
+      Some((date(1), date(3)))
+    );
+    date_type_option.timezone_id = "Asia/Singapore".to_string();
+    assert_eq!(
+      date_type_option.local_date_range(&cell_data),
+      Some((date(2), date(4)))
+    );
+  }
 }
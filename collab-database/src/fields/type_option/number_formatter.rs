@@ -0,0 +1,279 @@
+use rust_decimal::{Decimal, RoundingStrategy};
+use rusty_money::Locale;
+use serde::{Deserialize, Serialize};
+
+use crate::fields::number_type_option::NumberFormat;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberNotation {
+  /// 1234.5 is displayed as 1,234.5
+  #[default]
+  Standard,
+  /// 1234.5 is displayed as 1.2345E+3
+  Scientific,
+}
+
+impl NumberNotation {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      NumberNotation::Standard => "standard",
+      NumberNotation::Scientific => "scientific",
+    }
+  }
+}
+
+/// How [format_number] displays a number, on top of its [NumberFormat].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NumberDisplay {
+  /// The number of digits after the decimal separator, the number is rounded half away from
+  /// zero. None keeps the digits of the number.
+  pub precision: Option<u32>,
+  /// Group the digits of the integer part, e.g. 1,234,567.
+  pub thousands_separator: bool,
+  pub notation: NumberNotation,
+}
+
+impl NumberDisplay {
+  /// The default display of the format: the digits of the currencies and the percents are
+  /// grouped, the plain numbers are not.
+  pub fn new(format: NumberFormat) -> Self {
+    Self {
+      precision: None,
+      thousands_separator: format != NumberFormat::Num,
+      notation: NumberNotation::Standard,
+    }
+  }
+}
+
+/// The separators of a locale, e.g. `1,234.5` in the US and `1.234,5` in Europe.
+struct Separators {
+  thousands: &'static str,
+  decimal: &'static str,
+  /// The digits are grouped by 3 then by 2, e.g. 12,34,567 in India.
+  indian_grouping: bool,
+}
+
+impl Separators {
+  fn of(format: NumberFormat) -> Self {
+    let locale = match format {
+      // The plain numbers are always displayed the same way, whatever the currency set says.
+      NumberFormat::Num => &Locale::EnUs,
+      _ => &format.currency().locale,
+    };
+    match locale {
+      Locale::EnUs => Self::new(",", ".", false),
+      Locale::EnIn => Self::new(",", ".", true),
+      Locale::EnEu => Self::new(".", ",", false),
+      Locale::EnBy => Self::new(" ", ",", false),
+    }
+  }
+
+  fn new(thousands: &'static str, decimal: &'static str, indian_grouping: bool) -> Self {
+    Self {
+      thousands,
+      decimal,
+      indian_grouping,
+    }
+  }
+
+  fn group(&self, digits: &str) -> String {
+    let mut groups = vec![];
+    let mut end = digits.len();
+    let mut size = 3;
+    while end > size {
+      groups.push(&digits[end - size..end]);
+      end -= size;
+      if self.indian_grouping {
+        size = 2;
+      }
+    }
+    groups.push(&digits[..end]);
+    groups.reverse();
+    groups.join(self.thousands)
+  }
+}
+
+/// Display the number in the format, e.g. `-$1,234.50` or `12.5%`. The exports, the calculations
+/// and the text of the cells use it, so every platform displays the numbers the same way.
+///
+/// The separators are the ones of the locale of the currency. The symbol of a currency is put
+/// before or after the number as the currency does, a percent is the number followed by `%`.
+pub fn format_number(value: Decimal, format: NumberFormat, display: &NumberDisplay) -> String {
+  let separators = Separators::of(format);
+  let (number, is_negative) = match display.notation {
+    NumberNotation::Standard => format_standard(value, display, &separators),
+    NumberNotation::Scientific => format_scientific(value, display.precision, &separators),
+  };
+  let sign = if is_negative { "-" } else { "" };
+
+  match format {
+    NumberFormat::Num => format!("{}{}", sign, number),
+    NumberFormat::Percent => format!("{}{}%", sign, number),
+    _ => {
+      let currency = format.currency();
+      if currency.symbol_first {
+        format!("{}{}{}", sign, currency.symbol, number)
+      } else {
+        format!("{}{} {}", sign, number, currency.symbol)
+      }
+    },
+  }
+}
+
+/// Same as [format_number] for a number computed as a float, e.g. the average of a calculation.
+/// Return an empty string if the number can't be displayed, e.g. NaN.
+pub fn format_f64(value: f64, format: NumberFormat, display: &NumberDisplay) -> String {
+  match Decimal::try_from(value) {
+    Ok(decimal) => format_number(decimal.normalize(), format, display),
+    Err(_) => String::new(),
+  }
+}
+
+fn round(value: Decimal, precision: Option<u32>) -> Decimal {
+  match precision {
+    None => value,
+    Some(precision) => {
+      let mut value =
+        value.round_dp_with_strategy(precision, RoundingStrategy::MidpointAwayFromZero);
+      value.rescale(precision);
+      value
+    },
+  }
+}
+
+/// Return the number without its sign and whether it's negative.
+fn format_standard(
+  value: Decimal,
+  display: &NumberDisplay,
+  separators: &Separators,
+) -> (String, bool) {
+  let value = round(value, display.precision);
+  let text = value.abs().to_string();
+  let (integer, fraction) = match text.split_once('.') {
+    Some((integer, fraction)) => (integer, Some(fraction)),
+    None => (text.as_str(), None),
+  };
+  let mut number = if display.thousands_separator {
+    separators.group(integer)
+  } else {
+    integer.to_string()
+  };
+  if let Some(fraction) = fraction {
+    number.push_str(separators.decimal);
+    number.push_str(fraction);
+  }
+  (number, value.is_sign_negative() && !value.is_zero())
+}
+
+/// Return the number without its sign, e.g. `1.5E+3`, and whether it's negative.
+fn format_scientific(
+  value: Decimal,
+  precision: Option<u32>,
+  separators: &Separators,
+) -> (String, bool) {
+  let digits = value.mantissa().unsigned_abs().to_string();
+  let num_of_digits = digits.len() as i64;
+  let (mut mantissa, mut exponent) = if value.is_zero() {
+    (Decimal::ZERO, 0)
+  } else {
+    // The digits with the decimal point after the first one, e.g. 1234.5 -> 1.2345E+3.
+    let mantissa =
+      Decimal::from_i128_with_scale(value.mantissa().abs(), (num_of_digits - 1) as u32);
+    (mantissa, num_of_digits - 1 - value.scale() as i64)
+  };
+  mantissa = match precision {
+    Some(_) => round(mantissa, precision),
+    None => mantissa.normalize(),
+  };
+  // Rounding 9.99 to 1 digit gives 10.0, which is 1.0 of the next exponent.
+  if mantissa >= Decimal::TEN {
+    mantissa = round((mantissa / Decimal::TEN).normalize(), precision);
+    exponent += 1;
+  }
+  let mantissa = mantissa.to_string().replace('.', separators.decimal);
+  let exponent_sign = if exponent < 0 { "-" } else { "+" };
+  (
+    format!("{}E{}{}", mantissa, exponent_sign, exponent.abs()),
+    value.is_sign_negative() && !value.is_zero(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr;
+
+  use super::*;
+
+  fn format(value: &str, format: NumberFormat, display: &NumberDisplay) -> String {
+    format_number(Decimal::from_str(value).unwrap(), format, display)
+  }
+
+  #[test]
+  fn standard_notation_test() {
+    let display = NumberDisplay::new(NumberFormat::USD);
+    assert_eq!(
+      format("1234567.5", NumberFormat::USD, &display),
+      "$1,234,567.5"
+    );
+    assert_eq!(format("-0.2", NumberFormat::USD, &display), "-$0.2");
+    assert_eq!(format("1234.56", NumberFormat::EUR, &display), "€1.234,56");
+    assert_eq!(
+      format("1234.5", NumberFormat::Krona, &display),
+      "1 234,5 SEK"
+    );
+    assert_eq!(
+      format("1234567", NumberFormat::Rupee, &display),
+      "₹12,34,567"
+    );
+    assert_eq!(format("12.5", NumberFormat::Percent, &display), "12.5%");
+
+    let display = NumberDisplay::new(NumberFormat::Num);
+    assert_eq!(
+      format("1234567.5", NumberFormat::Num, &display),
+      "1234567.5"
+    );
+  }
+
+  #[test]
+  fn precision_test() {
+    let display = NumberDisplay {
+      precision: Some(2),
+      ..NumberDisplay::new(NumberFormat::USD)
+    };
+    assert_eq!(format("1234.5", NumberFormat::USD, &display), "$1,234.50");
+    assert_eq!(format("0.125", NumberFormat::USD, &display), "$0.13");
+    assert_eq!(format("-0.001", NumberFormat::USD, &display), "$0.00");
+
+    let display = NumberDisplay {
+      precision: Some(0),
+      ..NumberDisplay::new(NumberFormat::Num)
+    };
+    assert_eq!(format("2.5", NumberFormat::Num, &display), "3");
+  }
+
+  #[test]
+  fn scientific_notation_test() {
+    let display = NumberDisplay {
+      notation: NumberNotation::Scientific,
+      ..NumberDisplay::new(NumberFormat::Num)
+    };
+    assert_eq!(format("1234.5", NumberFormat::Num, &display), "1.2345E+3");
+    assert_eq!(format("-0.00012", NumberFormat::Num, &display), "-1.2E-4");
+    assert_eq!(format("0", NumberFormat::Num, &display), "0E+0");
+
+    let display = NumberDisplay {
+      precision: Some(1),
+      ..display
+    };
+    assert_eq!(format("9.96", NumberFormat::Num, &display), "1.0E+1");
+    assert_eq!(format("1234.5", NumberFormat::EUR, &display), "€1,2E+3");
+  }
+
+  #[test]
+  fn format_f64_test() {
+    let display = NumberDisplay::new(NumberFormat::USD);
+    assert_eq!(format_f64(1234.5, NumberFormat::USD, &display), "$1,234.5");
+    assert_eq!(format_f64(f64::NAN, NumberFormat::USD, &display), "");
+  }
+}
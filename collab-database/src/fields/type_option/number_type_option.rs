@@ -1,6 +1,7 @@
 #![allow(clippy::upper_case_acronyms)]
 
 use crate::error::DatabaseError;
+use crate::fields::number_formatter::{NumberDisplay, NumberNotation, format_f64, format_number};
 use crate::fields::number_type_option::number_currency::Currency;
 use crate::fields::{
   TypeOptionCellReader, TypeOptionCellWriter, TypeOptionData, TypeOptionDataBuilder,
@@ -34,6 +35,14 @@ pub struct NumberTypeOption {
   pub symbol: String,
   #[serde(default)]
   pub name: String,
+  /// See [NumberDisplay::precision].
+  #[serde(default)]
+  pub precision: Option<u32>,
+  /// See [NumberDisplay::thousands_separator], None uses the default of the format.
+  #[serde(default)]
+  pub thousands_separator: Option<bool>,
+  #[serde(default)]
+  pub notation: NumberNotation,
 }
 
 impl Default for NumberTypeOption {
@@ -45,6 +54,9 @@ impl Default for NumberTypeOption {
       scale: 0,
       symbol,
       name: "Number".to_string(),
+      precision: None,
+      thousands_separator: None,
+      notation: NumberNotation::default(),
     }
   }
 }
@@ -57,12 +69,20 @@ impl From<TypeOptionData> for NumberTypeOption {
 
 impl From<NumberTypeOption> for TypeOptionData {
   fn from(data: NumberTypeOption) -> Self {
-    TypeOptionDataBuilder::from([
+    let mut builder = TypeOptionDataBuilder::from([
       ("format".into(), Any::BigInt(data.format.value())),
       ("scale".into(), Any::BigInt(data.scale as i64)),
       ("name".into(), data.name.into()),
       ("symbol".into(), data.symbol.into()),
-    ])
+      ("notation".into(), data.notation.as_str().into()),
+    ]);
+    if let Some(precision) = data.precision {
+      builder.insert("precision".into(), Any::BigInt(precision as i64));
+    }
+    if let Some(thousands_separator) = data.thousands_separator {
+      builder.insert("thousands_separator".into(), thousands_separator.into());
+    }
+    builder
   }
 }
 
@@ -79,7 +99,10 @@ impl TypeOptionCellReader for NumberTypeOption {
 
   fn convert_raw_cell_data(&self, text: &str) -> String {
     match self.format_cell_data(text) {
-      Ok(cell_data) => cell_data.to_string(),
+      Ok(cell_data) => match cell_data.decimal() {
+        Some(decimal) => format_number(*decimal, self.format, &self.display()),
+        None => "".to_string(),
+      },
       Err(_) => "".to_string(),
     }
   }
@@ -157,6 +180,22 @@ impl NumberTypeOption {
     self.format = format;
     self.symbol = format.symbol();
   }
+
+  /// How the numbers of the field are displayed, see [format_number].
+  pub fn display(&self) -> NumberDisplay {
+    let mut display = NumberDisplay::new(self.format);
+    display.precision = self.precision;
+    if let Some(thousands_separator) = self.thousands_separator {
+      display.thousands_separator = thousands_separator;
+    }
+    display.notation = self.notation;
+    display
+  }
+
+  /// Display a number computed from the cells of the field, e.g. the sum of a calculation.
+  pub fn format_f64(&self, value: f64) -> String {
+    format_f64(value, self.format, &self.display())
+  }
 }
 
 fn number_format_from_i64<'de, D>(deserializer: D) -> Result<NumberFormat, D::Error>
@@ -833,6 +872,20 @@ mod tests {
     assert_number(&type_option, "1234.56", "€1.234,56");
   }
 
+  #[test]
+  fn number_display_type_option_test() {
+    let mut type_option = NumberTypeOption::new();
+    type_option.set_format(NumberFormat::USD);
+    type_option.precision = Some(2);
+    type_option.thousands_separator = Some(false);
+    let type_option = NumberTypeOption::from(TypeOptionData::from(type_option));
+    assert_eq!(type_option.precision, Some(2));
+
+    assert_number(&type_option, "1234.5", "$1234.50");
+    assert_number(&type_option, "-0.125", "-$0.13");
+    assert_eq!(type_option.format_f64(1234.5), "$1234.50");
+  }
+
   fn assert_number(type_option: &NumberTypeOption, input_str: &str, expected_str: &str) {
     let output = type_option.convert_raw_cell_data(input_str);
     assert_eq!(output, expected_str.to_owned());
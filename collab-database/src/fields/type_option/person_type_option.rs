@@ -0,0 +1,313 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use collab::util::AnyMapExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use yrs::Any;
+
+use crate::database_trait::MemberResolver;
+use crate::entity::FieldType;
+use crate::error::DatabaseError;
+use crate::fields::{
+  TypeOptionCellReader, TypeOptionCellWriter, TypeOptionData, TypeOptionDataBuilder,
+};
+use crate::rows::{Cell, new_cell_builder};
+use crate::template::entity::CELL_DATA;
+use crate::template::util::{ToCellString, TypeOptionCellData};
+
+/// The type option of the person fields. Their cells hold the uids of workspace members, whose
+/// names and avatars are resolved by a [MemberResolver].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonTypeOption {
+  /// Whether a cell can hold more than one member.
+  pub allow_multiple: bool,
+}
+
+impl Default for PersonTypeOption {
+  fn default() -> Self {
+    Self {
+      allow_multiple: true,
+    }
+  }
+}
+
+impl PersonTypeOption {
+  /// The names of the members of the cell, separated by commas. The members the resolver
+  /// doesn't know are skipped.
+  pub fn stringify_cell_with_members(&self, cell: &Cell, resolver: &dyn MemberResolver) -> String {
+    PersonCellData::from(cell)
+      .members(resolver)
+      .into_iter()
+      .map(|member| member.name)
+      .collect::<Vec<_>>()
+      .join(", ")
+  }
+}
+
+impl From<TypeOptionData> for PersonTypeOption {
+  fn from(data: TypeOptionData) -> Self {
+    let allow_multiple = data.get_as::<bool>("allow_multiple").unwrap_or(true);
+    Self { allow_multiple }
+  }
+}
+
+impl From<PersonTypeOption> for TypeOptionData {
+  fn from(data: PersonTypeOption) -> Self {
+    TypeOptionDataBuilder::from([("allow_multiple".into(), data.allow_multiple.into())])
+  }
+}
+
+impl TypeOptionCellReader for PersonTypeOption {
+  fn json_cell(&self, cell: &Cell) -> Value {
+    json!(PersonCellData::from(cell))
+  }
+
+  /// The uids of the members, see [PersonTypeOption::stringify_cell_with_members] for their
+  /// names.
+  fn stringify_cell(&self, cell: &Cell) -> String {
+    PersonCellData::from(cell).to_cell_string()
+  }
+
+  fn numeric_cell(&self, _cell: &Cell) -> Option<f64> {
+    None
+  }
+
+  fn convert_raw_cell_data(&self, cell_data: &str) -> String {
+    PersonCellData::from_str(cell_data)
+      .unwrap_or_default()
+      .to_cell_string()
+  }
+}
+
+impl TypeOptionCellWriter for PersonTypeOption {
+  /// Accept a uid, an array of uids or the uids separated by commas.
+  fn convert_json_to_cell(&self, json_value: Value) -> Cell {
+    let mut cell_data = match json_value {
+      Value::Number(uid) => PersonCellData::new(uid.as_i64().into_iter().collect()),
+      Value::String(s) => PersonCellData::from_str(&s).unwrap_or_default(),
+      Value::Array(uids) => PersonCellData::new(uids.iter().filter_map(Value::as_i64).collect()),
+      value => serde_json::from_value::<PersonCellData>(value).unwrap_or_default(),
+    };
+    if !self.allow_multiple {
+      cell_data.uids.truncate(1);
+    }
+    Cell::from(&cell_data)
+  }
+}
+
+/// A member of the workspace, as returned by a [MemberResolver].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceMember {
+  pub uid: i64,
+  pub name: String,
+  #[serde(default)]
+  pub email: Option<String>,
+  #[serde(default)]
+  pub avatar_url: Option<String>,
+}
+
+impl WorkspaceMember {
+  pub fn new(uid: i64, name: &str) -> Self {
+    Self {
+      uid,
+      name: name.to_string(),
+      email: None,
+      avatar_url: None,
+    }
+  }
+
+  pub fn with_email(mut self, email: &str) -> Self {
+    self.email = Some(email.to_string());
+    self
+  }
+
+  pub fn with_avatar_url(mut self, avatar_url: &str) -> Self {
+    self.avatar_url = Some(avatar_url.to_string());
+    self
+  }
+
+  /// Return true if the name or the email of the member is the text, ignoring the case and the
+  /// surrounding whitespaces.
+  pub fn is_named(&self, text: &str) -> bool {
+    let text = text.trim();
+    !text.is_empty()
+      && (self.name.trim().eq_ignore_ascii_case(text)
+        || self
+          .email
+          .as_deref()
+          .is_some_and(|email| email.trim().eq_ignore_ascii_case(text)))
+  }
+}
+
+/// The members of a person cell, by uid, in the order they were added.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersonCellData {
+  pub uids: Vec<i64>,
+}
+
+impl PersonCellData {
+  /// The duplicated uids are removed.
+  pub fn new(uids: Vec<i64>) -> Self {
+    let mut cell_data = Self::default();
+    for uid in uids {
+      cell_data.add(uid);
+    }
+    cell_data
+  }
+
+  /// Map the names or the emails of the members, separated by commas, to their uids, e.g. the
+  /// people columns of the imported databases. Return the cell and the names the resolver
+  /// doesn't know.
+  pub fn from_names(text: &str, resolver: &dyn MemberResolver) -> (Self, Vec<String>) {
+    let mut cell_data = Self::default();
+    let mut unresolved = vec![];
+    for name in text
+      .split(',')
+      .map(str::trim)
+      .filter(|name| !name.is_empty())
+    {
+      match resolver.find_member(name) {
+        Some(member) => cell_data.add(member.uid),
+        None => unresolved.push(name.to_string()),
+      }
+    }
+    (cell_data, unresolved)
+  }
+
+  pub fn contains(&self, uid: i64) -> bool {
+    self.uids.contains(&uid)
+  }
+
+  pub fn add(&mut self, uid: i64) {
+    if !self.contains(uid) {
+      self.uids.push(uid);
+    }
+  }
+
+  /// The members of the cell, in the order of the cell. The members the resolver doesn't know,
+  /// e.g. the ones who left the workspace, are skipped.
+  pub fn members(&self, resolver: &dyn MemberResolver) -> Vec<WorkspaceMember> {
+    self
+      .uids
+      .iter()
+      .filter_map(|uid| resolver.get_member(*uid))
+      .collect()
+  }
+}
+
+impl TypeOptionCellData for PersonCellData {
+  fn is_cell_empty(&self) -> bool {
+    self.uids.is_empty()
+  }
+}
+
+impl FromStr for PersonCellData {
+  type Err = DatabaseError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let uids = s
+      .split(',')
+      .filter_map(|uid| uid.trim().parse::<i64>().ok())
+      .collect();
+    Ok(Self::new(uids))
+  }
+}
+
+impl From<&Cell> for PersonCellData {
+  fn from(cell: &Cell) -> Self {
+    let uids = match cell.get(CELL_DATA) {
+      Some(Any::Array(array)) => array
+        .iter()
+        .filter_map(|item| match item {
+          Any::BigInt(uid) => Some(*uid),
+          Any::Number(uid) => Some(*uid as i64),
+          _ => None,
+        })
+        .collect(),
+      _ => vec![],
+    };
+    Self::new(uids)
+  }
+}
+
+impl From<&PersonCellData> for Cell {
+  fn from(cell_data: &PersonCellData) -> Self {
+    let uids = cell_data
+      .uids
+      .iter()
+      .map(|uid| Any::BigInt(*uid))
+      .collect::<Vec<_>>();
+    let mut cell = new_cell_builder(FieldType::Person);
+    cell.insert(CELL_DATA.into(), Any::Array(Arc::from(uids)));
+    cell
+  }
+}
+
+impl ToCellString for PersonCellData {
+  fn to_cell_string(&self) -> String {
+    self
+      .uids
+      .iter()
+      .map(|uid| uid.to_string())
+      .collect::<Vec<_>>()
+      .join(",")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn members() -> Vec<WorkspaceMember> {
+    vec![
+      WorkspaceMember::new(1, "Lucas").with_email("lucas@appflowy.io"),
+      WorkspaceMember::new(2, "Nathan"),
+    ]
+  }
+
+  #[test]
+  fn person_cell_data_test() {
+    let cell_data = PersonCellData::new(vec![2, 1, 2]);
+    assert_eq!(cell_data.uids, vec![2, 1]);
+    let cell = Cell::from(&cell_data);
+    assert_eq!(PersonCellData::from(&cell), cell_data);
+
+    let type_option = PersonTypeOption::default();
+    assert_eq!(type_option.stringify_cell(&cell), "2,1");
+    assert_eq!(
+      type_option.stringify_cell_with_members(&cell, &members()),
+      "Nathan, Lucas"
+    );
+
+    // A member who left the workspace is skipped.
+    let cell = Cell::from(&PersonCellData::new(vec![3, 1]));
+    assert_eq!(
+      type_option.stringify_cell_with_members(&cell, &members()),
+      "Lucas"
+    );
+  }
+
+  #[test]
+  fn person_cell_from_names_test() {
+    let (cell_data, unresolved) =
+      PersonCellData::from_names("nathan, LUCAS@appflowy.io , Annie,", &members());
+    assert_eq!(cell_data.uids, vec![2, 1]);
+    assert_eq!(unresolved, vec!["Annie"]);
+  }
+
+  #[test]
+  fn person_type_option_write_json_test() {
+    let type_option = PersonTypeOption::default();
+    let cell = type_option.convert_json_to_cell(json!([1, 2]));
+    assert_eq!(PersonCellData::from(&cell).uids, vec![1, 2]);
+    let cell = type_option.convert_json_to_cell(json!("3, 4"));
+    assert_eq!(PersonCellData::from(&cell).uids, vec![3, 4]);
+
+    let type_option = PersonTypeOption {
+      allow_multiple: false,
+    };
+    let cell = type_option.convert_json_to_cell(json!({ "uids": [1, 2] }));
+    assert_eq!(PersonCellData::from(&cell).uids, vec![1]);
+  }
+}
@@ -11,21 +11,75 @@ use std::str::FromStr;
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RelationTypeOption {
   pub database_id: String,
+  /// The workspace of the related database, None when it's in the workspace of the field.
+  #[serde(default)]
+  pub workspace_id: Option<String>,
+}
+
+impl RelationTypeOption {
+  pub fn new(database_id: &str) -> Self {
+    Self {
+      database_id: database_id.to_string(),
+      workspace_id: None,
+    }
+  }
+
+  /// Relate to a database of another workspace.
+  pub fn with_workspace_id(mut self, workspace_id: &str) -> Self {
+    self.workspace_id = Some(workspace_id.to_string());
+    self
+  }
+
+  /// The related database, the database of the field being in `current_workspace_id`.
+  pub fn database_reference(&self, current_workspace_id: &str) -> DatabaseReference {
+    DatabaseReference {
+      workspace_id: self
+        .workspace_id
+        .clone()
+        .unwrap_or_else(|| current_workspace_id.to_string()),
+      database_id: self.database_id.clone(),
+    }
+  }
+
+  pub fn is_cross_workspace(&self, current_workspace_id: &str) -> bool {
+    self
+      .workspace_id
+      .as_ref()
+      .is_some_and(|workspace_id| workspace_id != current_workspace_id)
+  }
 }
 
 impl From<TypeOptionData> for RelationTypeOption {
   fn from(data: TypeOptionData) -> Self {
     let database_id: String = data.get_as("database_id").unwrap_or_default();
-    Self { database_id }
+    let workspace_id = data
+      .get_as::<String>("workspace_id")
+      .filter(|workspace_id| !workspace_id.is_empty());
+    Self {
+      database_id,
+      workspace_id,
+    }
   }
 }
 
 impl From<RelationTypeOption> for TypeOptionData {
   fn from(data: RelationTypeOption) -> Self {
-    TypeOptionDataBuilder::from([("database_id".into(), data.database_id.into())])
+    let mut type_option =
+      TypeOptionDataBuilder::from([("database_id".into(), data.database_id.into())]);
+    if let Some(workspace_id) = data.workspace_id {
+      type_option.insert("workspace_id".into(), workspace_id.into());
+    }
+    type_option
   }
 }
 
+/// A database identified across the workspaces, e.g. the target of a relation field.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DatabaseReference {
+  pub workspace_id: String,
+  pub database_id: String,
+}
+
 impl TypeOptionCellReader for RelationTypeOption {
   fn json_cell(&self, cell: &Cell) -> Value {
     let cell_data = RelationCellData::from(cell);
@@ -12,6 +12,7 @@ use crate::template::util::{ToCellString, TypeOptionCellData};
 use collab::util::AnyMapExt;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
+use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
 
@@ -76,6 +77,28 @@ impl SelectTypeOption {
   pub fn to_json_string(&self) -> String {
     serde_json::to_string(self).unwrap()
   }
+
+  /// The groups of options whose names only differ by case or by the surrounding whitespace,
+  /// e.g. "Done" and "done ", in the order of the options. Imported databases often have them,
+  /// merge them with [crate::database::Database::merge_select_options].
+  pub fn similar_options(&self) -> Vec<Vec<SelectOption>> {
+    let mut groups: Vec<(String, Vec<SelectOption>)> = vec![];
+    for option in &self.options {
+      let name = option.name.trim().to_lowercase();
+      match groups
+        .iter_mut()
+        .find(|(group_name, _)| *group_name == name)
+      {
+        Some((_, group)) => group.push(option.clone()),
+        None => groups.push((name, vec![option.clone()])),
+      }
+    }
+    groups
+      .into_iter()
+      .map(|(_, group)| group)
+      .filter(|group| group.len() > 1)
+      .collect()
+  }
 }
 
 impl From<TypeOptionData> for SelectTypeOption {
@@ -318,6 +341,23 @@ impl SelectOptionIds {
     cell.insert(CELL_DATA.into(), self.to_cell_string().into());
     cell
   }
+
+  /// Replace the ids, the keys of `replaced` by their values, keeping each id once. Return true
+  /// if an id was replaced.
+  pub fn replace_ids(&mut self, replaced: &HashMap<String, String>) -> bool {
+    if !self.0.iter().any(|id| replaced.contains_key(id)) {
+      return false;
+    }
+    let mut ids = Vec::with_capacity(self.0.len());
+    for id in &self.0 {
+      let id = replaced.get(id).unwrap_or(id);
+      if !ids.contains(id) {
+        ids.push(id.clone());
+      }
+    }
+    self.0 = ids;
+    true
+  }
 }
 
 impl TypeOptionCellData for SelectOptionIds {
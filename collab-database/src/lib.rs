@@ -7,10 +7,13 @@ pub mod workspace_database;
 
 #[macro_use]
 mod macros;
+pub mod attachment;
 pub mod blocks;
+pub mod database_diff;
 pub mod database_state;
 pub mod database_trait;
 pub mod entity;
 pub mod error;
+pub mod recurrence;
 pub mod template;
 pub mod util;
@@ -0,0 +1,333 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, Utc, Weekday};
+use collab::preclude::encoding::serde::{from_any, to_any};
+use collab::preclude::{Any, YrsValue};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::DatabaseError;
+use crate::rows::{Cells, RowId};
+
+/// The number of occurrences of a rule that are looked at, so a rule without end doesn't loop
+/// forever.
+const MAX_OCCURRENCES: usize = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RecurrenceFrequency {
+  Daily,
+  Weekly,
+  Monthly,
+  Yearly,
+}
+
+impl RecurrenceFrequency {
+  fn as_str(&self) -> &'static str {
+    match self {
+      RecurrenceFrequency::Daily => "DAILY",
+      RecurrenceFrequency::Weekly => "WEEKLY",
+      RecurrenceFrequency::Monthly => "MONTHLY",
+      RecurrenceFrequency::Yearly => "YEARLY",
+    }
+  }
+}
+
+/// A recurrence rule, the subset of the iCalendar RRULE (RFC 5545) supported by the recurring
+/// rows: `FREQ`, `INTERVAL`, `COUNT`, `UNTIL` and, for the weekly rules, `BYDAY` without ordinal,
+/// e.g. `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;COUNT=10`.
+///
+/// The occurrences are computed in UTC. A monthly or yearly rule skips the months without the day
+/// of its start, e.g. the 31st, as RFC 5545 does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+  pub frequency: RecurrenceFrequency,
+  pub interval: u32,
+  /// The number of occurrences, counted from the start.
+  pub count: Option<u32>,
+  /// The last possible occurrence, in seconds since the epoch.
+  pub until: Option<i64>,
+  pub by_day: Vec<Weekday>,
+}
+
+impl RecurrenceRule {
+  pub fn new(frequency: RecurrenceFrequency) -> Self {
+    Self {
+      frequency,
+      interval: 1,
+      count: None,
+      until: None,
+      by_day: vec![],
+    }
+  }
+
+  /// The occurrences of the rule starting at `start`, up to `end` included. Both are in seconds
+  /// since the epoch.
+  pub fn occurrences(&self, start: i64, end: i64) -> Vec<i64> {
+    let Some(start_at) = DateTime::<Utc>::from_timestamp(start, 0).map(|date| date.naive_utc())
+    else {
+      return vec![];
+    };
+    let end = self.until.map_or(end, |until| until.min(end));
+    let max = self.count.map_or(MAX_OCCURRENCES, |count| {
+      (count as usize).min(MAX_OCCURRENCES)
+    });
+    let interval = self.interval.max(1) as i64;
+
+    let mut occurrences = vec![];
+    for period in 0..MAX_OCCURRENCES as i64 {
+      let candidates = self.period_candidates(start_at, period * interval);
+      // The candidates of a period are ordered: the first past the end ends the rule.
+      let Some(first) = candidates.first() else {
+        continue;
+      };
+      if first.and_utc().timestamp() > end {
+        break;
+      }
+      for candidate in candidates {
+        let timestamp = candidate.and_utc().timestamp();
+        if timestamp < start {
+          continue;
+        }
+        if timestamp > end || occurrences.len() >= max {
+          return occurrences;
+        }
+        occurrences.push(timestamp);
+      }
+    }
+    occurrences
+  }
+
+  /// The occurrences of the period `offset` periods after the start, ordered.
+  fn period_candidates(&self, start: NaiveDateTime, offset: i64) -> Vec<NaiveDateTime> {
+    match self.frequency {
+      RecurrenceFrequency::Daily => vec![start + Duration::days(offset)],
+      RecurrenceFrequency::Weekly if self.by_day.is_empty() => {
+        vec![start + Duration::weeks(offset)]
+      },
+      RecurrenceFrequency::Weekly => {
+        let week_start = start.date()
+          - Duration::days(start.weekday().num_days_from_monday() as i64)
+          + Duration::weeks(offset);
+        let mut days = self
+          .by_day
+          .iter()
+          .map(|day| day.num_days_from_monday())
+          .collect::<Vec<_>>();
+        days.sort_unstable();
+        days.dedup();
+        days
+          .into_iter()
+          .map(|day| (week_start + Duration::days(day as i64)).and_time(start.time()))
+          .collect()
+      },
+      RecurrenceFrequency::Monthly => add_months(start, offset).into_iter().collect(),
+      RecurrenceFrequency::Yearly => add_months(start, offset * 12).into_iter().collect(),
+    }
+  }
+}
+
+/// The date `months` months after the date, None if that month doesn't have its day.
+fn add_months(date: NaiveDateTime, months: i64) -> Option<NaiveDateTime> {
+  let first_of_month = date
+    .date()
+    .with_day(1)?
+    .checked_add_months(Months::new(u32::try_from(months).ok()?))?;
+  let day = NaiveDate::from_ymd_opt(first_of_month.year(), first_of_month.month(), date.day())?;
+  Some(day.and_time(date.time()))
+}
+
+impl FromStr for RecurrenceRule {
+  type Err = DatabaseError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let invalid = |reason: &str| DatabaseError::InvalidRecurrenceRule(format!("{}: {}", s, reason));
+    let rule = s.trim();
+    let rule = rule.strip_prefix("RRULE:").unwrap_or(rule);
+
+    let mut frequency = None;
+    let mut result = RecurrenceRule::new(RecurrenceFrequency::Daily);
+    for part in rule.split(';').filter(|part| !part.is_empty()) {
+      let (key, value) = part.split_once('=').ok_or_else(|| invalid(part))?;
+      match key.to_ascii_uppercase().as_str() {
+        "FREQ" => {
+          frequency = Some(match value.to_ascii_uppercase().as_str() {
+            "DAILY" => RecurrenceFrequency::Daily,
+            "WEEKLY" => RecurrenceFrequency::Weekly,
+            "MONTHLY" => RecurrenceFrequency::Monthly,
+            "YEARLY" => RecurrenceFrequency::Yearly,
+            _ => return Err(invalid("unsupported FREQ")),
+          })
+        },
+        "INTERVAL" => {
+          result.interval = value
+            .parse()
+            .ok()
+            .filter(|interval| *interval > 0)
+            .ok_or_else(|| invalid("INTERVAL must be a positive number"))?
+        },
+        "COUNT" => {
+          result.count = Some(
+            value
+              .parse()
+              .map_err(|_| invalid("COUNT must be a number"))?,
+          )
+        },
+        "UNTIL" => result.until = Some(parse_until(value).ok_or_else(|| invalid("bad UNTIL"))?),
+        "BYDAY" => {
+          result.by_day = value
+            .split(',')
+            .map(|day| parse_weekday(day).ok_or_else(|| invalid("unsupported BYDAY")))
+            .collect::<Result<_, _>>()?
+        },
+        // WKST only changes the weeks of the rules with an interval and BYDAY, the weeks start
+        // on Monday here.
+        "WKST" => {},
+        _ => return Err(invalid(&format!("unsupported {}", key))),
+      }
+    }
+    result.frequency = frequency.ok_or_else(|| invalid("missing FREQ"))?;
+    Ok(result)
+  }
+}
+
+impl Display for RecurrenceRule {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "FREQ={}", self.frequency.as_str())?;
+    if self.interval > 1 {
+      write!(f, ";INTERVAL={}", self.interval)?;
+    }
+    if let Some(count) = self.count {
+      write!(f, ";COUNT={}", count)?;
+    }
+    if let Some(until) = self
+      .until
+      .and_then(|until| DateTime::<Utc>::from_timestamp(until, 0))
+    {
+      write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%SZ"))?;
+    }
+    if !self.by_day.is_empty() {
+      let days = self.by_day.iter().map(weekday_code).collect::<Vec<_>>();
+      write!(f, ";BYDAY={}", days.join(","))?;
+    }
+    Ok(())
+  }
+}
+
+/// `UNTIL` is a date, `20240131`, or a UTC date time, `20240131T090000Z`.
+fn parse_until(value: &str) -> Option<i64> {
+  let value = value.trim_end_matches('Z');
+  if let Ok(date_time) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+    return Some(date_time.and_utc().timestamp());
+  }
+  let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+  // A date includes the occurrences of the whole day.
+  Some(date.and_hms_opt(23, 59, 59)?.and_utc().timestamp())
+}
+
+fn weekday_code(day: &Weekday) -> &'static str {
+  match day {
+    Weekday::Mon => "MO",
+    Weekday::Tue => "TU",
+    Weekday::Wed => "WE",
+    Weekday::Thu => "TH",
+    Weekday::Fri => "FR",
+    Weekday::Sat => "SA",
+    Weekday::Sun => "SU",
+  }
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+  match value.trim().to_ascii_uppercase().as_str() {
+    "MO" => Some(Weekday::Mon),
+    "TU" => Some(Weekday::Tue),
+    "WE" => Some(Weekday::Wed),
+    "TH" => Some(Weekday::Thu),
+    "FR" => Some(Weekday::Fri),
+    "SA" => Some(Weekday::Sat),
+    "SU" => Some(Weekday::Sun),
+    _ => None,
+  }
+}
+
+/// Rows created from a template on the occurrences of a rule, e.g. a weekly review task. See
+/// [crate::database::Database::materialize_due_rows].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RowRecurrence {
+  pub id: String,
+  /// The rule, see [RecurrenceRule].
+  pub rrule: String,
+  /// The first possible occurrence, in seconds since the epoch.
+  pub start: i64,
+  /// The cells of the rows created on the occurrences.
+  #[serde(default)]
+  pub cells: Cells,
+  /// The date field set to the occurrence in the created rows.
+  #[serde(default)]
+  pub date_field_id: Option<String>,
+  /// The last occurrence whose row was created.
+  #[serde(default)]
+  pub materialized_until: Option<i64>,
+}
+
+impl RowRecurrence {
+  pub fn new(rule: &RecurrenceRule, start: i64) -> Self {
+    Self {
+      id: Uuid::new_v4().to_string(),
+      rrule: rule.to_string(),
+      start,
+      cells: Cells::new(),
+      date_field_id: None,
+      materialized_until: None,
+    }
+  }
+
+  pub fn with_cells(mut self, cells: Cells) -> Self {
+    self.cells = cells;
+    self
+  }
+
+  pub fn with_date_field(mut self, field_id: &str) -> Self {
+    self.date_field_id = Some(field_id.to_string());
+    self
+  }
+
+  pub fn rule(&self) -> Result<RecurrenceRule, DatabaseError> {
+    RecurrenceRule::from_str(&self.rrule)
+  }
+
+  /// The occurrences up to `now` whose rows are not created yet.
+  pub fn due_occurrences(&self, now: i64) -> Result<Vec<i64>, DatabaseError> {
+    let occurrences = self.rule()?.occurrences(self.start, now);
+    Ok(
+      occurrences
+        .into_iter()
+        .filter(|occurrence| {
+          self
+            .materialized_until
+            .is_none_or(|materialized_until| *occurrence > materialized_until)
+        })
+        .collect(),
+    )
+  }
+
+  /// The id of the row of the occurrence. It's the same on every client, so two clients
+  /// materializing the same occurrence create the same row.
+  pub fn occurrence_row_id(&self, occurrence: i64) -> RowId {
+    let namespace = Uuid::parse_str(&self.id)
+      .unwrap_or_else(|_| Uuid::new_v5(&Uuid::NAMESPACE_OID, self.id.as_bytes()));
+    RowId::from(Uuid::new_v5(&namespace, occurrence.to_string().as_bytes()))
+  }
+}
+
+pub(crate) fn recurrence_to_any(recurrence: &RowRecurrence) -> Result<Any, DatabaseError> {
+  to_any(recurrence).map_err(|err| DatabaseError::Internal(anyhow!("encode recurrence: {}", err)))
+}
+
+pub(crate) fn recurrence_from_out(value: YrsValue) -> Option<RowRecurrence> {
+  match value {
+    YrsValue::Any(any) => from_any(&any).ok(),
+    _ => None,
+  }
+}
@@ -1,13 +1,54 @@
-use collab::preclude::Any;
+use collab::preclude::{Any, Array, ArrayRef, ReadTxn, TransactionMut, YrsValue};
 use collab::util::deserialize_i64_from_numeric;
 use serde::{Deserialize, Serialize};
+use tracing::error;
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::database::timestamp;
+
+/// A comment of a row, stored in the collab of the row so it's synced with the row.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RowComment {
-  uid: i64,
-  content: String,
+  /// Empty for the comments created before the comments had ids, they can't be edited.
+  #[serde(default)]
+  pub id: String,
+  pub uid: i64,
+  pub content: String,
   #[serde(deserialize_with = "deserialize_i64_from_numeric")]
-  created_at: i64,
+  pub created_at: i64,
+  /// The id of the first comment of the thread this comment replies to. None for the first
+  /// comment of a thread.
+  #[serde(default)]
+  pub parent_id: Option<String>,
+  /// 0 if the comment was never edited.
+  #[serde(default, deserialize_with = "deserialize_i64_from_numeric")]
+  pub edited_at: i64,
+  /// Whether the thread is resolved, only set on the first comment of a thread.
+  #[serde(default)]
+  pub resolved: bool,
+}
+
+impl RowComment {
+  pub fn new(uid: i64, content: &str) -> Self {
+    Self {
+      id: uuid::Uuid::new_v4().to_string(),
+      uid,
+      content: content.to_string(),
+      created_at: timestamp(),
+      parent_id: None,
+      edited_at: 0,
+      resolved: false,
+    }
+  }
+
+  /// Make the comment a reply in the thread of the comment.
+  pub fn reply_to(mut self, comment_id: &str) -> Self {
+    self.parent_id = Some(comment_id.to_string());
+    self
+  }
+
+  pub fn is_reply(&self) -> bool {
+    self.parent_id.is_some()
+  }
 }
 
 impl TryFrom<Any> for RowComment {
@@ -27,3 +68,196 @@ impl From<RowComment> for Any {
     Any::from_json(&json).unwrap()
   }
 }
+
+/// A comment of a row and its replies, in the order they were added.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowCommentThread {
+  pub comment: RowComment,
+  pub replies: Vec<RowComment>,
+}
+
+impl RowCommentThread {
+  /// The number of comments of the thread, the first one included.
+  pub fn comment_count(&self) -> usize {
+    self.replies.len() + 1
+  }
+}
+
+/// Group the comments of a row by thread, in the order the threads were started. The replies
+/// whose thread was removed, e.g. concurrently on another device, start their own thread.
+pub fn row_comment_threads(comments: Vec<RowComment>) -> Vec<RowCommentThread> {
+  let mut threads: Vec<RowCommentThread> = vec![];
+  for comment in comments {
+    let index = comment.parent_id.as_ref().and_then(|parent_id| {
+      threads
+        .iter()
+        .position(|thread| &thread.comment.id == parent_id)
+    });
+    match index {
+      Some(index) => threads[index].replies.push(comment),
+      None => threads.push(RowCommentThread {
+        comment,
+        replies: vec![],
+      }),
+    }
+  }
+  threads
+}
+
+pub(crate) fn comments_from_array_ref<T: ReadTxn>(
+  txn: &T,
+  array_ref: &ArrayRef,
+) -> Vec<RowComment> {
+  indexed_comments_from_array_ref(txn, array_ref)
+    .into_iter()
+    .map(|(_, comment)| comment)
+    .collect()
+}
+
+/// Return the comments with their index in the array. The entries that aren't comments are
+/// skipped, so the index of a comment may differ from its position in the returned list.
+fn indexed_comments_from_array_ref<T: ReadTxn>(
+  txn: &T,
+  array_ref: &ArrayRef,
+) -> Vec<(u32, RowComment)> {
+  array_ref
+    .iter(txn)
+    .enumerate()
+    .filter_map(|(index, value)| match value {
+      YrsValue::Any(any) => match RowComment::try_from(any) {
+        Ok(comment) => Some((index as u32, comment)),
+        Err(err) => {
+          error!("Failed to parse the row comment: {}", err);
+          None
+        },
+      },
+      _ => None,
+    })
+    .collect()
+}
+
+/// Update the comment with the id. Return false if there is no such comment.
+pub(crate) fn update_comment_in_array_ref<F>(
+  txn: &mut TransactionMut,
+  array_ref: &ArrayRef,
+  comment_id: &str,
+  f: F,
+) -> bool
+where
+  F: FnOnce(&mut RowComment),
+{
+  if comment_id.is_empty() {
+    return false;
+  }
+  let Some((index, comment)) = indexed_comments_from_array_ref(&*txn, array_ref)
+    .into_iter()
+    .find(|(_, comment)| comment.id == comment_id)
+  else {
+    return false;
+  };
+  let mut updated = comment.clone();
+  f(&mut updated);
+  if updated != comment {
+    array_ref.remove(txn, index);
+    array_ref.insert(txn, index, Any::from(updated));
+  }
+  true
+}
+
+/// Remove the comment with the id and, if it starts a thread, its replies. Return the removed
+/// comments.
+pub(crate) fn remove_comment_from_array_ref(
+  txn: &mut TransactionMut,
+  array_ref: &ArrayRef,
+  comment_id: &str,
+) -> Vec<RowComment> {
+  let mut removed = vec![];
+  if comment_id.is_empty() {
+    return removed;
+  }
+  let comments = indexed_comments_from_array_ref(&*txn, array_ref);
+  // Remove from the end, so the indexes of the comments to remove don't move.
+  for (index, comment) in comments.into_iter().rev() {
+    if comment.id == comment_id || comment.parent_id.as_deref() == Some(comment_id) {
+      array_ref.remove(txn, index);
+      removed.push(comment);
+    }
+  }
+  removed.reverse();
+  removed
+}
+
+#[cfg(test)]
+mod tests {
+  use collab::preclude::{Doc, Transact};
+
+  use super::*;
+
+  #[test]
+  fn row_comment_threads_test() {
+    let first = RowComment::new(1, "first");
+    let second = RowComment::new(2, "second");
+    let reply = RowComment::new(2, "reply").reply_to(&first.id);
+    let orphan = RowComment::new(3, "orphan").reply_to("removed");
+    let threads = row_comment_threads(vec![
+      first.clone(),
+      second.clone(),
+      reply.clone(),
+      orphan.clone(),
+    ]);
+    assert_eq!(threads.len(), 3);
+    assert_eq!(threads[0].comment, first);
+    assert_eq!(threads[0].replies, vec![reply]);
+    assert_eq!(threads[0].comment_count(), 2);
+    assert_eq!(threads[1].comment, second);
+    assert_eq!(threads[2].comment, orphan);
+  }
+
+  #[test]
+  fn row_comment_any_test() {
+    let comment = RowComment::new(1, "hello").reply_to("thread");
+    let any = Any::from(comment.clone());
+    assert_eq!(RowComment::try_from(any).unwrap(), comment);
+
+    // The comments created before the threads.
+    let any = Any::from_json(r#"{"uid":1,"content":"old","created_at":1700000000}"#).unwrap();
+    let comment = RowComment::try_from(any).unwrap();
+    assert_eq!(comment.id, "");
+    assert_eq!(comment.parent_id, None);
+    assert_eq!(comment.edited_at, 0);
+  }
+
+  #[test]
+  fn row_comment_array_with_unparsable_entry_test() {
+    let doc = Doc::new();
+    let array_ref = doc.get_or_insert_array("comments");
+    let first = RowComment::new(1, "first");
+    let second = RowComment::new(2, "second");
+    let reply = RowComment::new(1, "reply").reply_to(&second.id);
+    {
+      let mut txn = doc.transact_mut();
+      array_ref.push_back(&mut txn, Any::from(first.clone()));
+      array_ref.push_back(&mut txn, Any::from("not a comment"));
+      array_ref.push_back(&mut txn, Any::from(second.clone()));
+      array_ref.push_back(&mut txn, Any::from(reply.clone()));
+    }
+
+    let mut txn = doc.transact_mut();
+    assert!(update_comment_in_array_ref(
+      &mut txn,
+      &array_ref,
+      &second.id,
+      |comment| comment.content = "edited".to_string(),
+    ));
+    let comments = comments_from_array_ref(&txn, &array_ref);
+    assert_eq!(comments.len(), 3);
+    assert_eq!(comments[0], first);
+    assert_eq!(comments[1].content, "edited");
+    assert_eq!(comments[2], reply);
+
+    let removed = remove_comment_from_array_ref(&mut txn, &array_ref, &second.id);
+    assert_eq!(removed.len(), 2);
+    assert_eq!(comments_from_array_ref(&txn, &array_ref), vec![first]);
+    assert_eq!(array_ref.len(&txn), 2);
+  }
+}
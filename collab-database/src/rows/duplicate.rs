@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use collab::util::AnyMapExt;
+
+use crate::fields::TypeOptionCellReader;
+use crate::rows::{Cell, Row, RowId};
+use crate::template::entity::CELL_DATA;
+use crate::template::relation_parse::RelationCellData;
+
+/// Rows whose cells of the key fields match, returned by
+/// [crate::database::Database::find_duplicate_rows].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateRows {
+  /// The normalized text of the key cells, in the order of the key fields.
+  pub key: Vec<String>,
+  /// The ids of the matching rows, in the order of the inline view.
+  pub row_ids: Vec<RowId>,
+}
+
+/// What [crate::database::Database::dedupe_rows] does with the duplicates of a row.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateRowPolicy {
+  /// Remove the duplicates, the first row is kept as is.
+  Skip,
+  /// Merge the duplicates into the first row, see [crate::database::Database::merge_rows].
+  #[default]
+  Merge,
+}
+
+/// Group the rows by the normalized text of their key cells. Only the groups of two rows or more
+/// are returned. The rows with an empty key cell are skipped, two rows missing an email are not
+/// the same contact.
+pub(crate) fn group_duplicate_rows(
+  rows: &[Row],
+  key_field_ids: &[&str],
+  readers: &[Option<Box<dyn TypeOptionCellReader>>],
+) -> Vec<DuplicateRows> {
+  let mut groups: Vec<DuplicateRows> = vec![];
+  let mut index_by_key: HashMap<Vec<String>, usize> = HashMap::new();
+  for row in rows {
+    let key = key_field_ids
+      .iter()
+      .zip(readers)
+      .map(|(field_id, reader)| {
+        let text = cell_text(reader.as_deref(), row.cells.get(*field_id));
+        text.trim().to_lowercase()
+      })
+      .collect::<Vec<_>>();
+    if key.is_empty() || key.iter().any(|text| text.is_empty()) {
+      continue;
+    }
+    match index_by_key.get(&key) {
+      Some(index) => groups[*index].row_ids.push(row.id.clone()),
+      None => {
+        index_by_key.insert(key.clone(), groups.len());
+        groups.push(DuplicateRows {
+          key,
+          row_ids: vec![row.id.clone()],
+        });
+      },
+    }
+  }
+  groups.retain(|group| group.row_ids.len() > 1);
+  groups
+}
+
+/// The text of the cell, the raw [CELL_DATA] when the reader returns nothing.
+pub(crate) fn cell_text(reader: Option<&dyn TypeOptionCellReader>, cell: Option<&Cell>) -> String {
+  let Some(cell) = cell else {
+    return String::new();
+  };
+  let text = reader
+    .map(|reader| reader.stringify_cell(cell))
+    .unwrap_or_default();
+  if text.is_empty() {
+    cell.get_as::<String>(CELL_DATA).unwrap_or_default()
+  } else {
+    text
+  }
+}
+
+/// How much information the cell holds, the number of characters of its text.
+pub(crate) fn cell_richness(
+  reader: Option<&dyn TypeOptionCellReader>,
+  cell: Option<&Cell>,
+) -> usize {
+  cell_text(reader, cell).trim().chars().count()
+}
+
+/// Combine the row ids of the relation cells, replacing the ids found in `replaced`. Return None
+/// when the cells are empty.
+pub(crate) fn merge_relation_cells(
+  cells: &[Option<&Cell>],
+  replaced: &HashMap<RowId, RowId>,
+) -> Option<Cell> {
+  let mut row_ids: Vec<RowId> = vec![];
+  for cell in cells.iter().flatten() {
+    for row_id in RelationCellData::from(*cell).row_ids {
+      let row_id = replaced.get(&row_id).cloned().unwrap_or(row_id);
+      if !row_ids.contains(&row_id) {
+        row_ids.push(row_id);
+      }
+    }
+  }
+  if row_ids.is_empty() {
+    return None;
+  }
+  Some(Cell::from(RelationCellData { row_ids }))
+}
@@ -0,0 +1,121 @@
+use collab::preclude::{Any, Map, MapExt, MapRef, ReadTxn, ToJson, Transaction, YrsValue};
+use collab::util::AnyExt;
+
+use crate::rows::{
+  CELL_FIELD_TYPE, CREATED_AT, Cell, DEFAULT_ROW_HEIGHT, LAST_MODIFIED, ROW_ARCHIVED, ROW_CELLS,
+  ROW_DATABASE_ID, ROW_HEIGHT, ROW_ID, ROW_VISIBILITY, Row, RowId, row_from_map_ref,
+};
+
+/// A read-only view of a row that decodes only what is read, see [crate::rows::DatabaseRow::lazy_row].
+///
+/// [crate::rows::DatabaseRow::get_row] decodes all the cells of the row. A grid displaying a few
+/// columns of many rows reads them through a [LazyRow] instead: reading a cell decodes that cell,
+/// and reading a value of a cell decodes that value.
+///
+/// The view holds a read transaction of the row collab, drop it before updating the row.
+pub struct LazyRow<'a> {
+  txn: Transaction<'a>,
+  data: MapRef,
+}
+
+impl<'a> LazyRow<'a> {
+  pub fn new(txn: Transaction<'a>, data: MapRef) -> Self {
+    Self { txn, data }
+  }
+
+  pub fn id(&self) -> Option<RowId> {
+    let id: String = self.data.get_with_txn(&self.txn, ROW_ID)?;
+    Some(RowId::from(id))
+  }
+
+  pub fn database_id(&self) -> Option<String> {
+    self.data.get_with_txn(&self.txn, ROW_DATABASE_ID)
+  }
+
+  pub fn height(&self) -> i32 {
+    self
+      .get_i64(ROW_HEIGHT)
+      .map(|height| height as i32)
+      .unwrap_or(DEFAULT_ROW_HEIGHT)
+  }
+
+  pub fn visibility(&self) -> bool {
+    self
+      .data
+      .get_with_txn(&self.txn, ROW_VISIBILITY)
+      .unwrap_or(true)
+  }
+
+  pub fn archived(&self) -> bool {
+    self
+      .data
+      .get_with_txn(&self.txn, ROW_ARCHIVED)
+      .unwrap_or_default()
+  }
+
+  pub fn created_at(&self) -> i64 {
+    self.get_i64(CREATED_AT).unwrap_or_default()
+  }
+
+  pub fn modified_at(&self) -> i64 {
+    self.get_i64(LAST_MODIFIED).unwrap_or_default()
+  }
+
+  /// The ids of the fields with a cell in the row, no cell is decoded.
+  pub fn field_ids(&self) -> Vec<String> {
+    self
+      .cells_map()
+      .map(|cells| cells.keys(&self.txn).map(str::to_string).collect())
+      .unwrap_or_default()
+  }
+
+  pub fn has_cell(&self, field_id: &str) -> bool {
+    self.cell_map(field_id).is_some()
+  }
+
+  /// Decode the cell of the field, the other cells of the row are not read.
+  pub fn get_cell(&self, field_id: &str) -> Option<Cell> {
+    self.cell_map(field_id)?.to_json(&self.txn).into_map()
+  }
+
+  /// Decode one value of the cell of the field, e.g. its [crate::template::entity::CELL_DATA].
+  pub fn get_cell_value(&self, field_id: &str, key: &str) -> Option<Any> {
+    match self.cell_map(field_id)?.get(&self.txn, key)? {
+      YrsValue::Any(any) => Some(any),
+      value => Some(value.to_json(&self.txn)),
+    }
+  }
+
+  /// The field type written in the cell of the field, see [CELL_FIELD_TYPE].
+  pub fn get_cell_field_type(&self, field_id: &str) -> Option<i64> {
+    match self.get_cell_value(field_id, CELL_FIELD_TYPE)? {
+      Any::BigInt(value) => Some(value),
+      Any::Number(value) => Some(value as i64),
+      Any::String(value) => value.parse().ok(),
+      _ => None,
+    }
+  }
+
+  /// Decode the whole row, as [crate::rows::DatabaseRow::get_row] does.
+  pub fn to_row(&self) -> Option<Row> {
+    row_from_map_ref(&self.data, &self.txn)
+  }
+
+  fn cells_map(&self) -> Option<MapRef> {
+    self.data.get_with_txn(&self.txn, ROW_CELLS)
+  }
+
+  fn cell_map(&self, field_id: &str) -> Option<MapRef> {
+    self.cells_map()?.get_with_txn(&self.txn, field_id)
+  }
+
+  /// Read a timestamp or a number, written as a number or as a string by the older versions.
+  fn get_i64(&self, key: &str) -> Option<i64> {
+    match self.data.get(&self.txn, key)? {
+      YrsValue::Any(Any::BigInt(value)) => Some(value),
+      YrsValue::Any(Any::Number(value)) => Some(value as i64),
+      YrsValue::Any(Any::String(value)) => value.parse().ok(),
+      _ => None,
+    }
+  }
+}
@@ -1,11 +1,15 @@
 pub use cell::*;
 pub use comment::*;
+pub use duplicate::*;
+pub use lazy_row::*;
 pub use row::*;
 pub use row_id::*;
 pub use row_meta::*;
 pub use row_observer::*;
 mod cell;
 mod comment;
+mod duplicate;
+mod lazy_row;
 mod row;
 mod row_id;
 mod row_meta;
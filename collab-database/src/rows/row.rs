@@ -1,9 +1,11 @@
 use collab::preclude::{
-  Any, ArrayRef, Collab, FillRef, Map, MapExt, MapRef, ReadTxn, ToJson, TransactionMut, YrsValue,
+  Any, Array, ArrayRef, Collab, FillRef, Map, MapExt, MapRef, ReadTxn, ToJson, TransactionMut,
+  YrsValue,
 };
 use std::borrow::{Borrow, BorrowMut};
 use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, Ordering};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
@@ -16,8 +18,9 @@ use crate::database::timestamp;
 
 use crate::error::DatabaseError;
 use crate::rows::{
-  Cell, Cells, CellsUpdate, RowChangeSender, RowId, RowMeta, RowMetaUpdate,
-  subscribe_row_data_change,
+  Cell, Cells, CellsUpdate, LazyRow, RowChangeReceiver, RowChangeSender, RowComment, RowId,
+  RowMeta, RowMetaUpdate, comments_from_array_ref, remove_comment_from_array_ref,
+  subscribe_row_comment_change, subscribe_row_data_change, update_comment_in_array_ref,
 };
 
 use crate::util::encoded_collab;
@@ -27,6 +30,7 @@ use collab::core::collab::CollabOptions;
 use collab::core::origin::CollabOrigin;
 use collab::entity::EncodedCollab;
 use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
 use tracing::{error, trace};
 use uuid::Uuid;
 use yrs::block::ClientID;
@@ -42,6 +46,9 @@ pub struct DatabaseRow {
   pub row_id: RowId,
   pub collab: Collab,
   pub body: DatabaseRowBody,
+  /// Only receives the changes of this row, see [DatabaseRow::subscribe_change].
+  row_change_tx: RowChangeSender,
+  is_observed: AtomicBool,
 }
 
 pub fn default_database_row_from_row(row: Row, client_id: ClientID) -> EncodedCollab {
@@ -80,14 +87,18 @@ impl DatabaseRow {
     change_tx: Option<RowChangeSender>,
   ) -> Result<Self, DatabaseError> {
     let body = DatabaseRowBody::open(row_id.clone(), &mut collab)?;
-    if let Some(change_tx) = change_tx {
-      subscribe_row_data_change(row_id.clone(), &body.data, change_tx);
-    }
-    Ok(Self {
+    let (row_change_tx, _) = broadcast::channel(100);
+    let row = Self {
       row_id,
       collab,
       body,
-    })
+      row_change_tx,
+      is_observed: AtomicBool::new(false),
+    };
+    if change_tx.is_some() {
+      row.observe_changes(change_tx);
+    }
+    Ok(row)
   }
 
   pub fn create(
@@ -97,14 +108,45 @@ impl DatabaseRow {
     row: Row,
   ) -> Self {
     let body = DatabaseRowBody::create(row_id.clone(), &mut collab, row);
-    if let Some(change_tx) = change_tx {
-      subscribe_row_data_change(row_id.clone(), &body.data, change_tx);
-    }
-    Self {
+    let (row_change_tx, _) = broadcast::channel(100);
+    let row = Self {
       row_id,
       collab,
       body,
+      row_change_tx,
+      is_observed: AtomicBool::new(false),
+    };
+    if change_tx.is_some() {
+      row.observe_changes(change_tx);
+    }
+    row
+  }
+
+  /// Subscribe to the changes of this row only. Unlike the database wide [RowChangeSender], the
+  /// receiver is not woken up by the changes of other rows.
+  pub fn subscribe_change(&self) -> RowChangeReceiver {
+    self.observe_changes(None);
+    self.row_change_tx.subscribe()
+  }
+
+  /// Observe the data and the comments of the row. The rows are only observed when the database
+  /// listens to the row changes, or once [DatabaseRow::subscribe_change] was called.
+  fn observe_changes(&self, change_tx: Option<RowChangeSender>) {
+    if self.is_observed.swap(true, Ordering::AcqRel) {
+      return;
     }
+    subscribe_row_data_change(
+      self.row_id.clone(),
+      &self.body.data,
+      change_tx.clone(),
+      self.row_change_tx.clone(),
+    );
+    subscribe_row_comment_change(
+      self.row_id.clone(),
+      &self.body.comments,
+      change_tx,
+      self.row_change_tx.clone(),
+    );
   }
 
   pub fn encoded_collab(&self) -> Result<EncodedCollab, DatabaseError> {
@@ -125,14 +167,17 @@ impl DatabaseRow {
   pub fn get_row_meta(&self) -> Option<RowMeta> {
     let txn = self.collab.transact();
     let row_id = Uuid::parse_str(&self.body.row_id).ok()?;
-    Some(RowMeta::from_map_ref(&txn, &row_id, &self.body.meta))
+    let comment_count = self.body.comments.len(&txn) as i64;
+    Some(RowMeta::from_map_ref(&txn, &row_id, &self.body.meta).with_comment_count(comment_count))
   }
 
   pub fn get_row_detail(&self) -> Option<RowDetail> {
     let txn = self.collab.transact();
     let row = row_from_map_ref(&self.body.data, &txn)?;
     let row_id = Uuid::parse_str(&self.body.row_id).ok()?;
-    let meta = RowMeta::from_map_ref(&txn, &row_id, &self.body.meta);
+    let comment_count = self.body.comments.len(&txn) as i64;
+    let meta =
+      RowMeta::from_map_ref(&txn, &row_id, &self.body.meta).with_comment_count(comment_count);
     RowDetail::new(row, meta)
   }
 
@@ -146,6 +191,39 @@ impl DatabaseRow {
     cell_from_map_ref(&self.body.data, &txn, field_id)
   }
 
+  /// The comments of the row in the order they were added, see [crate::rows::row_comment_threads]
+  /// to group them by thread.
+  pub fn get_comments(&self) -> Vec<RowComment> {
+    let txn = self.collab.transact();
+    self.body.comments(&txn)
+  }
+
+  pub fn add_comment(&mut self, comment: RowComment) {
+    let mut txn = self.collab.transact_mut();
+    self.body.add_comment(&mut txn, comment);
+  }
+
+  /// Update the comment with the id. Return false if the row has no such comment.
+  pub fn update_comment<F>(&mut self, comment_id: &str, f: F) -> bool
+  where
+    F: FnOnce(&mut RowComment),
+  {
+    let mut txn = self.collab.transact_mut();
+    self.body.update_comment(&mut txn, comment_id, f)
+  }
+
+  /// Remove the comment with the id and, if it starts a thread, its replies. Return the removed
+  /// comments.
+  pub fn remove_comment(&mut self, comment_id: &str) -> Vec<RowComment> {
+    let mut txn = self.collab.transact_mut();
+    self.body.remove_comment(&mut txn, comment_id)
+  }
+
+  /// Read the row without decoding all its cells, see [LazyRow].
+  pub fn lazy_row(&self) -> LazyRow<'_> {
+    LazyRow::new(self.collab.transact(), self.body.data.clone())
+  }
+
   pub fn update<F>(&mut self, f: F)
   where
     F: FnOnce(RowUpdate),
@@ -211,7 +289,6 @@ pub struct DatabaseRowBody {
   data: MapRef,
   #[allow(dead_code)]
   meta: MapRef,
-  #[allow(dead_code)]
   comments: ArrayRef,
 }
 
@@ -238,6 +315,7 @@ impl DatabaseRowBody {
             .set_database_id(row.database_id)
             .set_height(row.height)
             .set_visibility(row.visibility)
+            .set_archived(row.archived)
             .set_created_at(row.created_at)
             .set_last_modified(row.modified_at)
             .set_cells(row.cells);
@@ -310,6 +388,25 @@ impl DatabaseRowBody {
     Some(cells)
   }
 
+  pub fn comments<T: ReadTxn>(&self, txn: &T) -> Vec<RowComment> {
+    comments_from_array_ref(txn, &self.comments)
+  }
+
+  pub fn add_comment(&self, txn: &mut TransactionMut, comment: RowComment) {
+    self.comments.push_back(txn, Any::from(comment));
+  }
+
+  pub fn update_comment<F>(&self, txn: &mut TransactionMut, comment_id: &str, f: F) -> bool
+  where
+    F: FnOnce(&mut RowComment),
+  {
+    update_comment_in_array_ref(txn, &self.comments, comment_id, f)
+  }
+
+  pub fn remove_comment(&self, txn: &mut TransactionMut, comment_id: &str) -> Vec<RowComment> {
+    remove_comment_from_array_ref(txn, &self.comments, comment_id)
+  }
+
   pub fn get_data(&self) -> &MapRef {
     &self.data
   }
@@ -341,9 +438,14 @@ impl RowDetail {
     let data: MapRef = collab.get_with_txn(&txn, DATABASE_ROW_DATA)?.cast().ok()?;
     let meta: MapRef = collab.get_with_txn(&txn, META)?.cast().ok()?;
     let row = row_from_map_ref(&data, &txn)?;
+    let comment_count = collab
+      .get_with_txn(&txn, COMMENT)
+      .and_then(|comments| comments.cast::<ArrayRef>().ok())
+      .map(|comments| comments.len(&txn) as i64)
+      .unwrap_or(0);
 
     let row_id = Uuid::parse_str(&row.id).ok()?;
-    let meta = RowMeta::from_map_ref(&txn, &row_id, &meta);
+    let meta = RowMeta::from_map_ref(&txn, &row_id, &meta).with_comment_count(comment_count);
     let row_document_id = meta_id_from_row_id(&row_id, RowMetaKey::DocumentId);
     Some(Self {
       row,
@@ -366,6 +468,10 @@ pub struct Row {
   pub height: i32,
   #[serde(default = "default_visibility")]
   pub visibility: bool,
+  /// An archived row is kept in the views but hidden from their rows, see
+  /// [crate::database::Database::archive_rows].
+  #[serde(default)]
+  pub archived: bool,
   #[serde(deserialize_with = "deserialize_i64")]
   pub created_at: i64,
   #[serde(alias = "last_modified", deserialize_with = "deserialize_i64")]
@@ -419,7 +525,7 @@ impl RowMetaKey {
   }
 }
 
-const DEFAULT_ROW_HEIGHT: i32 = 60;
+pub(crate) const DEFAULT_ROW_HEIGHT: i32 = 60;
 impl Row {
   /// Creates a new instance of [Row]
   /// The default height of a [Row] is 60
@@ -433,6 +539,7 @@ impl Row {
       cells: HashMap::new(),
       height: DEFAULT_ROW_HEIGHT,
       visibility: true,
+      archived: false,
       created_at: timestamp,
       modified_at: timestamp,
     }
@@ -445,6 +552,7 @@ impl Row {
       cells: HashMap::new(),
       height: DEFAULT_ROW_HEIGHT,
       visibility: true,
+      archived: false,
       created_at: 0,
       modified_at: 0,
     }
@@ -530,6 +638,7 @@ impl<'a, 'b> RowUpdate<'a, 'b> {
   }
 
   impl_bool_update!(set_visibility, set_visibility_if_not_none, ROW_VISIBILITY);
+  impl_bool_update!(set_archived, set_archived_if_not_none, ROW_ARCHIVED);
   impl_i32_update!(set_height, set_height_at_if_not_none, ROW_HEIGHT);
   impl_i64_update!(set_created_at, set_created_at_if_not_none, CREATED_AT);
   impl_i64_update!(
@@ -609,6 +718,7 @@ impl<'a, 'b> RowUpdate<'a, 'b> {
 pub(crate) const ROW_ID: &str = "id";
 pub const ROW_DATABASE_ID: &str = "database_id";
 pub(crate) const ROW_VISIBILITY: &str = "visibility";
+pub(crate) const ROW_ARCHIVED: &str = "archived";
 
 pub const ROW_HEIGHT: &str = "height";
 pub const ROW_CELLS: &str = "cells";
@@ -737,6 +847,7 @@ impl From<CreateRowParams> for Row {
       cells: params.cells,
       height: params.height,
       visibility: params.visibility,
+      archived: false,
       created_at: params.created_at,
       modified_at: params.modified_at,
     }
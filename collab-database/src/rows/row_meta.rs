@@ -114,6 +114,9 @@ pub struct RowMeta {
   pub cover: Option<RowCover>,
   pub is_document_empty: bool,
   pub attachment_count: i64,
+  /// The number of comments of the row, replies included, e.g. for the badges of the cards.
+  #[serde(default)]
+  pub comment_count: i64,
 }
 
 impl RowMeta {
@@ -124,6 +127,7 @@ impl RowMeta {
       cover: None,
       is_document_empty: true,
       attachment_count: 0,
+      comment_count: 0,
     }
   }
 
@@ -147,9 +151,16 @@ impl RowMeta {
           &meta_id_from_row_id(row_id, RowMetaKey::AttachmentCount),
         )
         .unwrap_or(0),
+      comment_count: 0,
     }
   }
 
+  /// The comments are stored out of the meta map, see [crate::rows::DatabaseRow::get_comments].
+  pub(crate) fn with_comment_count(mut self, comment_count: i64) -> Self {
+    self.comment_count = comment_count;
+    self
+  }
+
   #[allow(dead_code)]
   pub(crate) fn fill_map_ref(self, txn: &mut TransactionMut, row_id: &Uuid, map_ref: &MapRef) {
     if let Some(icon) = self.icon_url {
@@ -1,6 +1,8 @@
-use crate::rows::{Cell, ROW_CELLS, ROW_HEIGHT, ROW_VISIBILITY, Row, RowId};
+use crate::rows::{Cell, ROW_ARCHIVED, ROW_CELLS, ROW_HEIGHT, ROW_VISIBILITY, RowComment, RowId};
 
-use collab::preclude::{DeepObservable, EntryChange, Event, MapRef, TransactionMut};
+use collab::preclude::{
+  Array, ArrayRef, Change, DeepObservable, EntryChange, Event, MapRef, TransactionMut, YrsValue,
+};
 use collab::preclude::{PathSegment, ToJson};
 use std::ops::Deref;
 
@@ -22,22 +24,74 @@ pub enum RowChange {
     row_id: RowId,
     value: i32,
   },
+  DidUpdateArchived {
+    row_id: RowId,
+    value: bool,
+  },
   DidUpdateCell {
     row_id: RowId,
     field_id: String,
     value: Cell,
   },
+  /// The comments of the row were added, edited or removed, locally or by another device.
   DidUpdateRowComment {
-    row: Row,
+    row_id: RowId,
+    /// The comments added by the change, e.g. to notify the users they mention. The edited
+    /// comments are inserted again, their `edited_at` is set.
+    inserted: Vec<RowComment>,
+    comment_count: i64,
   },
 }
 
+impl RowChange {
+  /// The id of the row that this change belongs to.
+  pub fn row_id(&self) -> &RowId {
+    match self {
+      RowChange::DidUpdateVisibility { row_id, .. } => row_id,
+      RowChange::DidUpdateHeight { row_id, .. } => row_id,
+      RowChange::DidUpdateArchived { row_id, .. } => row_id,
+      RowChange::DidUpdateCell { row_id, .. } => row_id,
+      RowChange::DidUpdateRowComment { row_id, .. } => row_id,
+    }
+  }
+}
+
+/// Sends the changes of a row to the database wide sender and to the sender of the row itself.
+struct RowChangeNotifier {
+  change_tx: Option<RowChangeSender>,
+  row_change_tx: RowChangeSender,
+}
+
+impl RowChangeNotifier {
+  fn has_receivers(&self) -> bool {
+    self.change_tx.is_some() || self.row_change_tx.receiver_count() > 0
+  }
+
+  fn send(&self, change: RowChange) {
+    if self.row_change_tx.receiver_count() > 0 {
+      let _ = self.row_change_tx.send(change.clone());
+    }
+    if let Some(change_tx) = &self.change_tx {
+      let _ = change_tx.send(change);
+    }
+  }
+}
+
 pub(crate) fn subscribe_row_data_change(
   row_id: RowId,
   row_data_map: &MapRef,
-  change_tx: RowChangeSender,
+  change_tx: Option<RowChangeSender>,
+  row_change_tx: RowChangeSender,
 ) {
+  let change_tx = RowChangeNotifier {
+    change_tx,
+    row_change_tx,
+  };
   row_data_map.observe_deep_with("change", move |txn, events| {
+    // Nobody listens to the changes of this row, skip parsing the events.
+    if !change_tx.has_receivers() {
+      return;
+    }
     for event in events.iter() {
       match event {
         Event::Text(_) => {},
@@ -54,9 +108,47 @@ pub(crate) fn subscribe_row_data_change(
   });
 }
 
+pub(crate) fn subscribe_row_comment_change(
+  row_id: RowId,
+  comments: &ArrayRef,
+  change_tx: Option<RowChangeSender>,
+  row_change_tx: RowChangeSender,
+) {
+  let change_tx = RowChangeNotifier {
+    change_tx,
+    row_change_tx,
+  };
+  comments.observe_deep_with("change", move |txn, events| {
+    if !change_tx.has_receivers() {
+      return;
+    }
+    for event in events.iter() {
+      if let Event::Array(array_event) = event {
+        let inserted = array_event
+          .delta(txn)
+          .iter()
+          .flat_map(|change| match change {
+            Change::Added(values) => values.clone(),
+            _ => vec![],
+          })
+          .filter_map(|value| match value {
+            YrsValue::Any(any) => RowComment::try_from(any).ok(),
+            _ => None,
+          })
+          .collect();
+        change_tx.send(RowChange::DidUpdateRowComment {
+          row_id: row_id.clone(),
+          inserted,
+          comment_count: array_event.target().len(txn) as i64,
+        });
+      }
+    }
+  });
+}
+
 fn handle_map_event(
   row_id: &RowId,
-  change_tx: &RowChangeSender,
+  change_tx: &RowChangeNotifier,
   txn: &TransactionMut,
   event: &Event,
   map_event: &MapEvent,
@@ -68,29 +160,42 @@ fn handle_map_event(
         // When the event path is identified as [RowChangePath::Unknown], it indicates that the path itself remains unchanged.
         // In this scenario, the modification is confined to the key/value pairs within the map at the existing path.
         // Essentially, even though the overall path stays the same, the contents (specific key/value pairs) at this path are the ones being updated.
-        if let EntryChange::Updated(_, value) = enctry_change {
-          let change_value = RowChangeValue::from(key.deref());
-          match change_value {
-            RowChangeValue::Unknown(_s) => {
-              trace!("row observe value update: {}:{:?}", key, value.to_json(txn))
-            },
-            RowChangeValue::Height => {
-              if let Ok(value) = value.clone().cast::<i64>() {
-                let _ = change_tx.send(RowChange::DidUpdateHeight {
-                  row_id: row_id.clone(),
-                  value: value as i32,
-                });
-              }
-            },
-            RowChangeValue::Visibility => {
-              if let Ok(value) = value.clone().cast::<bool>() {
-                let _ = change_tx.send(RowChange::DidUpdateVisibility {
-                  row_id: row_id.clone(),
-                  value,
-                });
-              }
-            },
-          }
+        let change_value = RowChangeValue::from(key.deref());
+        let value = match enctry_change {
+          EntryChange::Updated(_, value) => value,
+          // The rows created before the archived flag existed don't have it, archiving them
+          // inserts it.
+          EntryChange::Inserted(value) if matches!(change_value, RowChangeValue::Archived) => value,
+          _ => continue,
+        };
+        match change_value {
+          RowChangeValue::Unknown(_s) => {
+            trace!("row observe value update: {}:{:?}", key, value.to_json(txn))
+          },
+          RowChangeValue::Height => {
+            if let Ok(value) = value.clone().cast::<i64>() {
+              change_tx.send(RowChange::DidUpdateHeight {
+                row_id: row_id.clone(),
+                value: value as i32,
+              });
+            }
+          },
+          RowChangeValue::Visibility => {
+            if let Ok(value) = value.clone().cast::<bool>() {
+              change_tx.send(RowChange::DidUpdateVisibility {
+                row_id: row_id.clone(),
+                value,
+              });
+            }
+          },
+          RowChangeValue::Archived => {
+            if let Ok(value) = value.clone().cast::<bool>() {
+              change_tx.send(RowChange::DidUpdateArchived {
+                row_id: row_id.clone(),
+                value,
+              });
+            }
+          },
         }
       },
       RowChangePath::Cells => {
@@ -104,7 +209,7 @@ fn handle_map_event(
             if let Some(cell) = value.to_json(txn).into_map() {
               // when insert a cell into the row, the key is the field_id
               let field_id = key.to_string();
-              let _ = change_tx.send(RowChange::DidUpdateCell {
+              change_tx.send(RowChange::DidUpdateCell {
                 row_id: row_id.clone(),
                 field_id,
                 value: cell,
@@ -122,7 +227,7 @@ fn handle_map_event(
             if let Some(PathSegment::Key(key)) = event.path().pop_back() {
               if let Some(cell) = event.target().to_json(txn).into_map() {
                 let field_id = key.deref().to_string();
-                let _ = change_tx.send(RowChange::DidUpdateCell {
+                change_tx.send(RowChange::DidUpdateCell {
                   row_id: row_id.clone(),
                   field_id,
                   value: cell,
@@ -134,7 +239,7 @@ fn handle_map_event(
             trace!("row observe delete: {}", key);
             if let Some(PathSegment::Key(key)) = event.path().pop_back() {
               let field_id = key.deref().to_string();
-              let _ = change_tx.send(RowChange::DidUpdateCell {
+              change_tx.send(RowChange::DidUpdateCell {
                 row_id: row_id.clone(),
                 field_id,
                 value: Cell::default(),
@@ -176,6 +281,7 @@ enum RowChangeValue {
   Unknown(String),
   Height,
   Visibility,
+  Archived,
 }
 
 impl From<&str> for RowChangeValue {
@@ -183,6 +289,7 @@ impl From<&str> for RowChangeValue {
     match s {
       ROW_HEIGHT => Self::Height,
       ROW_VISIBILITY => Self::Visibility,
+      ROW_ARCHIVED => Self::Archived,
       s => Self::Unknown(s.to_string()),
     }
   }
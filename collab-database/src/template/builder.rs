@@ -7,7 +7,7 @@ use crate::entity::FieldType;
 use crate::fields::checkbox_type_option::CheckboxTypeOption;
 use crate::fields::date_type_option::{DateFormat, DateTypeOption};
 use crate::fields::media_type_option::MediaTypeOption;
-use crate::fields::number_type_option::NumberTypeOption;
+use crate::fields::number_type_option::{NumberFormat, NumberTypeOption};
 use crate::fields::select_type_option::SelectTypeOption;
 use crate::fields::text_type_option::RichTextTypeOption;
 use crate::fields::timestamp_type_option::TimestampTypeOption;
@@ -130,6 +130,7 @@ pub struct FieldTemplateBuilder {
   pub name: String,
   pub field_type: FieldType,
   pub is_primary: bool,
+  number_format: NumberFormat,
   cells: Vec<String>,
 }
 
@@ -141,10 +142,17 @@ impl FieldTemplateBuilder {
       name,
       field_type,
       is_primary,
+      number_format: NumberFormat::Num,
       cells: vec![],
     }
   }
 
+  /// Set the format of a number field, e.g. its currency. Ignored by the other field types.
+  pub fn with_number_format(mut self, number_format: NumberFormat) -> Self {
+    self.number_format = number_format;
+    self
+  }
+
   pub fn create_cell<T: ToString>(mut self, cell: T) -> Self {
     self.cells.push(cell.to_string());
     self
@@ -267,9 +275,14 @@ impl FieldTemplateBuilder {
       },
       FieldType::Number => {
         let cell_template = string_cell_template(&field_type, self.cells);
+        let type_option = NumberTypeOption {
+          format: self.number_format,
+          symbol: self.number_format.symbol(),
+          ..Default::default()
+        };
         field_template
           .type_options
-          .insert(field_type, NumberTypeOption::default().into());
+          .insert(field_type, type_option.into());
 
         cell_template
       },
@@ -1,9 +1,14 @@
 use crate::database::{gen_database_id, gen_database_view_id};
 use crate::entity::FieldType;
 use crate::error::DatabaseError;
+use crate::fields::number_type_option::NumberFormat;
 use crate::template::builder::{DatabaseTemplateBuilder, FileUrlBuilder};
-use crate::template::date_parse::parse_date_cell;
+use crate::template::currency_parse::{
+  DecimalSeparator, detect_decimal_separator, detect_number_format, parse_formatted_number,
+};
+use crate::template::date_parse::DateAmbiguity;
 use crate::template::entity::DatabaseTemplate;
+use crate::template::locale::ImportLocale;
 use percent_encoding::percent_decode_str;
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
@@ -16,13 +21,30 @@ pub struct CSVTemplate {
   pub resource: Option<CSVResource>,
   pub database_id: String,
   pub view_id: String,
+  /// The date cells whose timestamp may not be the one that was meant, e.g. `04/03/2024`.
+  pub ambiguous_dates: Vec<AmbiguousDateCell>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmbiguousDateCell {
+  pub field_name: String,
+  /// The index of the row, the header excepted.
+  pub row_index: usize,
+  pub cell: String,
+  pub ambiguities: Vec<DateAmbiguity>,
+}
+
+#[derive(Clone)]
 pub struct CSVField {
   name: String,
   field_type: FieldType,
+  /// The currency of a number field.
+  number_format: NumberFormat,
+  /// The decimal separator of the cells of a number field.
+  decimal_separator: DecimalSeparator,
 }
 
+#[derive(Clone)]
 pub struct CSVResource {
   pub server_url: String,
   pub workspace_id: String,
@@ -33,47 +55,86 @@ impl CSVTemplate {
   pub fn try_from_reader(
     reader: impl io::Read,
     auto_field_type: bool,
-    mut csv_resource: Option<CSVResource>,
+    csv_resource: Option<CSVResource>,
   ) -> Result<Self, DatabaseError> {
-    let mut fields: Vec<CSVField> = vec![];
+    Self::try_from_reader_with_locale(
+      reader,
+      auto_field_type,
+      csv_resource,
+      &ImportLocale::default(),
+    )
+  }
 
+  /// Same as [CSVTemplate::try_from_reader], but localized checkbox and date values are detected
+  /// with the given [ImportLocale] and rewritten to values the database understands. The dates
+  /// are converted to UTC from the timezone of the locale's date options, and the numbers are
+  /// read with the locale's decimal separator, e.g. `1.234,56 €`. The currency of a number
+  /// column is set as the format of its field.
+  pub fn try_from_reader_with_locale(
+    reader: impl io::Read,
+    auto_field_type: bool,
+    csv_resource: Option<CSVResource>,
+    locale: &ImportLocale,
+  ) -> Result<Self, DatabaseError> {
     let mut reader = csv::Reader::from_reader(reader);
-    if let Ok(headers) = reader.headers() {
-      for header in headers {
-        fields.push(CSVField {
-          name: header.to_string(),
-          field_type: FieldType::RichText,
-        });
-      }
-    } else {
-      return Err(DatabaseError::InvalidCSV("No header".to_string()));
-    }
+    let headers = read_csv_headers(&mut reader)?;
+    let rows = read_csv_rows(&mut reader, usize::MAX);
+    let mut template = Self::from_rows(headers, rows, auto_field_type, csv_resource, locale);
+
+    // filter out resources that are not used
+    filter_out_resources(&template.fields, &template.rows, &mut template.resource);
+    Ok(template)
+  }
 
-    let rows: Vec<Vec<String>> = reader
-      .records()
-      .flat_map(|r| r.ok())
-      .map(|record| {
-        record
-          .into_iter()
-          .filter_map(|s| Some(percent_decode_str(s).decode_utf8().ok()?.to_string()))
-          .collect::<Vec<String>>()
+  /// Create the template of the rows of a csv file. The types of the fields are detected from
+  /// the rows if `auto_field_type` is true.
+  pub(crate) fn from_rows(
+    headers: Vec<String>,
+    mut rows: Vec<Vec<String>>,
+    auto_field_type: bool,
+    csv_resource: Option<CSVResource>,
+    locale: &ImportLocale,
+  ) -> Self {
+    let mut fields = headers
+      .into_iter()
+      .map(|name| CSVField {
+        name,
+        field_type: FieldType::RichText,
+        number_format: NumberFormat::Num,
+        decimal_separator: DecimalSeparator::Auto,
       })
-      .collect();
+      .collect::<Vec<_>>();
 
+    let mut ambiguous_dates = vec![];
     if auto_field_type {
-      auto_detect_field_type(&mut fields, &rows, &csv_resource);
+      auto_detect_field_type(&mut fields, &rows, &csv_resource, locale);
+      ambiguous_dates = find_ambiguous_dates(&fields, &rows, locale);
+      normalize_localized_cells(&fields, &mut rows, locale);
     }
 
-    // filter out resources that are not used
-    filter_out_resources(&fields, &rows, &mut csv_resource);
-
-    Ok(CSVTemplate {
+    CSVTemplate {
       fields,
       rows,
       resource: csv_resource,
       database_id: gen_database_id(),
       view_id: gen_database_view_id(),
-    })
+      ambiguous_dates,
+    }
+  }
+
+  /// Create a template with the fields of this template and other rows of the same csv file, e.g.
+  /// the next rows of a file imported by chunks.
+  pub(crate) fn with_rows(&self, mut rows: Vec<Vec<String>>, locale: &ImportLocale) -> Self {
+    let ambiguous_dates = find_ambiguous_dates(&self.fields, &rows, locale);
+    normalize_localized_cells(&self.fields, &mut rows, locale);
+    CSVTemplate {
+      fields: self.fields.clone(),
+      rows,
+      resource: self.resource.clone(),
+      database_id: self.database_id.clone(),
+      view_id: self.view_id.clone(),
+      ambiguous_dates,
+    }
   }
 
   pub fn reset_view_id(&mut self, view_id: String) {
@@ -90,6 +151,7 @@ impl CSVTemplate {
       resource,
       database_id,
       view_id,
+      ..
     } = self;
 
     let mut builder =
@@ -108,6 +170,7 @@ impl CSVTemplate {
           field_type,
           field_index == 0,
           |mut field_builder| {
+            field_builder = field_builder.with_number_format(field.number_format);
             for row in rows.iter() {
               if let Some(cell) = row.get(field_index) {
                 field_builder = field_builder.create_cell(cell)
@@ -123,6 +186,33 @@ impl CSVTemplate {
   }
 }
 
+pub(crate) fn read_csv_headers<R: io::Read>(
+  reader: &mut csv::Reader<R>,
+) -> Result<Vec<String>, DatabaseError> {
+  let headers = reader
+    .headers()
+    .map_err(|_| DatabaseError::InvalidCSV("No header".to_string()))?;
+  Ok(headers.iter().map(|header| header.to_string()).collect())
+}
+
+/// Read at most `limit` rows. The records that can't be read are skipped.
+pub(crate) fn read_csv_rows<R: io::Read>(
+  reader: &mut csv::Reader<R>,
+  limit: usize,
+) -> Vec<Vec<String>> {
+  reader
+    .records()
+    .take(limit)
+    .flat_map(|r| r.ok())
+    .map(|record| {
+      record
+        .into_iter()
+        .filter_map(|s| Some(percent_decode_str(s).decode_utf8().ok()?.to_string()))
+        .collect::<Vec<String>>()
+    })
+    .collect()
+}
+
 fn filter_out_resources(
   fields: &[CSVField],
   rows: &[Vec<String>],
@@ -165,6 +255,7 @@ fn auto_detect_field_type(
   fields: &mut Vec<CSVField>,
   rows: &[Vec<String>],
   resources: &Option<CSVResource>,
+  locale: &ImportLocale,
 ) {
   let num_fields = fields.len();
   fields
@@ -182,18 +273,81 @@ fn auto_detect_field_type(
         })
         .collect();
 
-      field.field_type = detect_field_type_from_cells_with_resource(&cells, resources);
+      let field_type = detect_field_type_from_cells_with_resource(&cells, resources, locale);
+      // Only the first cells are sampled, a status column whose first values are all different
+      // is detected by looking at the whole column.
+      field.field_type = if field_type == FieldType::RichText && is_low_cardinality_field(&cells) {
+        FieldType::SingleSelect
+      } else {
+        field_type
+      };
+      if field.field_type == FieldType::Number {
+        if let Some(separator) = detect_decimal_separator(&cells, locale.decimal_separator) {
+          field.decimal_separator = separator;
+          field.number_format = detect_number_format(&cells, separator);
+        }
+      }
     });
 }
 
+/// Rewrite the localized checkbox, date and number cells, so they can be parsed when building the
+/// database template.
+fn normalize_localized_cells(fields: &[CSVField], rows: &mut [Vec<String>], locale: &ImportLocale) {
+  for (field_index, field) in fields.iter().enumerate() {
+    let normalize: Box<dyn Fn(&str) -> Option<String>> = match field.field_type {
+      FieldType::Checkbox => Box::new(|cell| locale.normalize_checkbox_cell(cell)),
+      FieldType::DateTime => Box::new(|cell| locale.normalize_date_cell(cell)),
+      FieldType::Number => Box::new(|cell| normalize_number_cell(cell, field.decimal_separator)),
+      _ => continue,
+    };
+    for row in rows.iter_mut() {
+      if let Some(cell) = row.get_mut(field_index) {
+        if let Some(normalized) = normalize(cell) {
+          *cell = normalized;
+        }
+      }
+    }
+  }
+}
+
+fn find_ambiguous_dates(
+  fields: &[CSVField],
+  rows: &[Vec<String>],
+  locale: &ImportLocale,
+) -> Vec<AmbiguousDateCell> {
+  let mut ambiguous_dates = vec![];
+  for (field_index, field) in fields.iter().enumerate() {
+    if field.field_type != FieldType::DateTime {
+      continue;
+    }
+    for (row_index, row) in rows.iter().enumerate() {
+      let Some(cell) = row.get(field_index) else {
+        continue;
+      };
+      if let Some(outcome) = locale.parse_date_outcome(cell) {
+        if outcome.is_ambiguous() {
+          ambiguous_dates.push(AmbiguousDateCell {
+            field_name: field.name.clone(),
+            row_index,
+            cell: cell.clone(),
+            ambiguities: outcome.ambiguities,
+          });
+        }
+      }
+    }
+  }
+  ambiguous_dates
+}
+
 #[allow(dead_code)]
 fn detect_field_type_from_cells(cells: &[&str]) -> FieldType {
-  detect_field_type_from_cells_with_resource(cells, &None)
+  detect_field_type_from_cells_with_resource(cells, &None, &ImportLocale::default())
 }
 
 fn detect_field_type_from_cells_with_resource(
   cells: &[&str],
   resources: &Option<CSVResource>,
+  locale: &ImportLocale,
 ) -> FieldType {
   let cells = cells
     .iter()
@@ -202,7 +356,7 @@ fn detect_field_type_from_cells_with_resource(
     .cloned()
     .collect::<Vec<&str>>();
 
-  if is_number_cell(&cells) {
+  if is_number_cell(&cells, locale) {
     return FieldType::Number;
   }
 
@@ -215,11 +369,11 @@ fn detect_field_type_from_cells_with_resource(
     return FieldType::URL;
   }
 
-  if is_checkbox_cell(&cells) {
+  if is_checkbox_cell(&cells, locale) {
     return FieldType::Checkbox;
   }
 
-  if is_date_cell(&cells) {
+  if is_date_cell(&cells, locale) {
     // TODO(nathan): handle this case: April 23, 2024 → May 22, 2024
     return FieldType::DateTime;
   }
@@ -270,11 +424,11 @@ fn is_media_cell(cells: &[&str], resource: &Option<CSVResource>) -> bool {
   valid_count >= half_count
 }
 
-fn is_date_cell(cells: &[&str]) -> bool {
+fn is_date_cell(cells: &[&str], locale: &ImportLocale) -> bool {
   let half_count = cells.len() / 2;
   let valid_count = cells
     .iter()
-    .filter(|&&cell| parse_date_cell(cell).is_some())
+    .filter(|&&cell| locale.parse_date(cell).is_some())
     .count();
 
   if valid_count == 0 {
@@ -287,23 +441,15 @@ fn is_date_cell(cells: &[&str]) -> bool {
 /// Detect if a column is a checkbox field.
 /// Optimized by checking if valid checkbox values (e.g., "Yes", "No", "1", "0", "True", "False")
 /// appear in multiple cells and returning early if a non-checkbox value is found.
-fn is_checkbox_cell(cells: &[&str]) -> bool {
-  // Define the set of valid checkbox values (in lowercase for case-insensitive comparison)
-  let valid_checkbox_values: HashSet<&str> = ["yes", "no", "1", "0", "true", "false"]
-    .iter()
-    .cloned()
-    .collect();
-
+/// The valid values come from the checked and unchecked values of the [ImportLocale].
+fn is_checkbox_cell(cells: &[&str], locale: &ImportLocale) -> bool {
   // Track how many valid checkbox values we encounter
   let mut valid_checkbox_count = 0;
 
   // Early exit strategy: Iterate through the cells and check their values
   for &cell in cells {
-    // Convert the cell value to lowercase and trim any whitespace
-    let trimmed_cell = cell.trim().to_lowercase();
-
     // Check if the cell contains a valid checkbox value
-    if valid_checkbox_values.contains(trimmed_cell.as_str()) {
+    if locale.parse_checkbox(cell).is_some() {
       valid_checkbox_count += 1;
     } else {
       // If a cell has an invalid value, return false early
@@ -364,23 +510,50 @@ fn is_link_field(cells: &[&str]) -> bool {
     .all(|cell| cell.starts_with("http://") || cell.starts_with("https://"))
 }
 
-fn is_number_cell(cells: &[&str]) -> bool {
-  let all_count = cells.len();
-  let valid_count = cells
-    .iter()
-    .filter(|&&cell| cell.parse::<f64>().is_ok())
-    .count();
+fn is_number_cell(cells: &[&str], locale: &ImportLocale) -> bool {
+  detect_decimal_separator(cells, locale.decimal_separator).is_some()
+}
 
-  if valid_count == 0 {
+/// The maximum number of distinct values of a column detected by [is_low_cardinality_field].
+const MAX_LOW_CARDINALITY_OPTIONS: usize = 20;
+/// The maximum length of a value of a column detected by [is_low_cardinality_field].
+const MAX_LOW_CARDINALITY_VALUE_LEN: usize = 50;
+
+/// Detect if a column holds a few short values repeated across its cells, e.g. a status or an
+/// owner, which is imported as a single-select field.
+fn is_low_cardinality_field(cells: &[&str]) -> bool {
+  let values = cells
+    .iter()
+    .map(|cell| cell.trim())
+    .filter(|cell| !cell.is_empty())
+    .collect::<Vec<_>>();
+  if values.len() < 4 {
+    return false;
+  }
+  if values
+    .iter()
+    .any(|value| value.contains(',') || value.chars().count() > MAX_LOW_CARDINALITY_VALUE_LEN)
+  {
     return false;
   }
+  let distinct = values.iter().collect::<HashSet<_>>().len();
+  distinct <= MAX_LOW_CARDINALITY_OPTIONS && distinct * 2 <= values.len()
+}
 
-  valid_count >= all_count
+/// Rewrite a formatted number as a plain number, see [parse_formatted_number].
+fn normalize_number_cell(cell: &str, separator: DecimalSeparator) -> Option<String> {
+  let number = parse_formatted_number(cell, separator)?;
+  let normalized = number.value.to_string();
+  if normalized == cell.trim() {
+    return None;
+  }
+  Some(normalized)
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::template::date_parse::DateParseOptions;
 
   #[test]
   fn test_detect_field_type_url() {
@@ -420,7 +593,7 @@ mod tests {
   #[test]
   fn test_is_checkbox_cell_invalid_value() {
     let cells = vec!["Yes", "No", "Maybe"];
-    assert!(!is_checkbox_cell(&cells));
+    assert!(!is_checkbox_cell(&cells, &ImportLocale::default()));
   }
 
   #[test]
@@ -470,11 +643,12 @@ mod tests {
 
   #[test]
   fn test_is_checkbox_cell() {
+    let locale = ImportLocale::default();
     let cells = vec!["Yes", "No", "1", "0", "true", "false", "yes", "no"];
-    assert!(is_checkbox_cell(&cells));
+    assert!(is_checkbox_cell(&cells, &locale));
 
     let cells = vec!["Yes", "No", "Maybe"];
-    assert!(!is_checkbox_cell(&cells));
+    assert!(!is_checkbox_cell(&cells, &locale));
   }
 
   #[test]
@@ -486,9 +660,147 @@ mod tests {
       "August 13, 2023",
       "12/09/2023",
     ];
-    assert!(is_date_cell(&cells));
+    assert!(is_date_cell(&cells, &ImportLocale::default()));
 
     let cells = vec!["2023-05-21", "Invalid Date", "12/09/2023"];
-    assert!(is_date_cell(&cells));
+    assert!(is_date_cell(&cells, &ImportLocale::default()));
+  }
+
+  #[test]
+  fn test_localized_checkbox_cell() {
+    let cells = vec!["Oui", "Non", "oui", "Ja", "Nein"];
+    assert!(!is_checkbox_cell(&cells, &ImportLocale::default()));
+    assert!(is_checkbox_cell(&cells, &ImportLocale::all()));
+  }
+
+  #[test]
+  fn test_localized_date_cell() {
+    let cells = vec!["22 août 2024", "3 mars 2023", "22. August 2024"];
+    assert!(!is_date_cell(&cells, &ImportLocale::default()));
+    assert!(is_date_cell(&cells, &ImportLocale::all()));
+  }
+
+  #[test]
+  fn test_csv_template_with_locale() {
+    let csv = "Nom,Terminé,Date\nA,Oui,22 août 2024\nB,Non,3 mars 2023 → 5 mars 2023\nC,Oui,1 janvier 2024\n";
+    let template =
+      CSVTemplate::try_from_reader_with_locale(csv.as_bytes(), true, None, &ImportLocale::french())
+        .unwrap();
+    assert_eq!(template.fields[1].field_type, FieldType::Checkbox);
+    assert_eq!(template.fields[2].field_type, FieldType::DateTime);
+    assert_eq!(template.rows[0][1], "Yes");
+    assert_eq!(template.rows[1][1], "No");
+    assert_eq!(template.rows[0][2], "2024-08-22");
+    assert_eq!(template.rows[1][2], "2023-03-03 → 2023-03-05");
+  }
+
+  #[test]
+  fn test_parse_formatted_number() {
+    let parse_formatted_number =
+      |cell| parse_formatted_number(cell, DecimalSeparator::Dot).map(|number| number.value);
+    assert_eq!(parse_formatted_number("12.5"), Some(12.5));
+    assert_eq!(parse_formatted_number("1,234.5"), Some(1234.5));
+    assert_eq!(parse_formatted_number("$1,200.00"), Some(1200.0));
+    assert_eq!(parse_formatted_number("-€3"), Some(-3.0));
+    assert_eq!(parse_formatted_number("45%"), Some(45.0));
+    assert_eq!(parse_formatted_number("1,2"), None);
+    assert_eq!(parse_formatted_number("1, 2"), None);
+    assert_eq!(parse_formatted_number("$"), None);
+    assert_eq!(parse_formatted_number("Done"), None);
+  }
+
+  #[test]
+  fn test_is_low_cardinality_field() {
+    let mut cells = vec!["A", "B", "C", "D", "E", "F", "G", "H", "I", "J"];
+    assert!(!is_low_cardinality_field(&cells));
+    cells.extend(["A", "B", "C", "D", "E", "F", "G", "H", "I", "J"]);
+    assert!(is_low_cardinality_field(&cells));
+    assert!(!is_low_cardinality_field(&["A", "A", "A"]));
+    assert!(!is_low_cardinality_field(&["A, B", "A, B", "A", "A"]));
+  }
+
+  #[test]
+  fn test_csv_template_with_formatted_numbers_and_low_cardinality() {
+    let mut csv = "Name,Price,Status\n".to_string();
+    for index in 0..30 {
+      // the ten first statuses are all different, then they are repeated
+      csv.push_str(&format!(
+        "Row {index},\"$1,{index:03}.50\",S{}\n",
+        index % 10
+      ));
+    }
+    let template = CSVTemplate::try_from_reader(csv.as_bytes(), true, None).unwrap();
+    assert_eq!(template.fields[1].field_type, FieldType::Number);
+    assert_eq!(template.rows[1][1], "1001.5");
+    assert_eq!(template.fields[2].field_type, FieldType::SingleSelect);
+  }
+
+  #[test]
+  fn test_dates_in_source_timezone() {
+    let csv = "Name,Date\nLaunch,\"March 4, 2024 2:00 PM\"\nReview,04/03/2024 10:00\n";
+    let locale = ImportLocale::english()
+      .with_date_options(DateParseOptions::new(chrono_tz::Tz::America__New_York));
+    let template =
+      CSVTemplate::try_from_reader_with_locale(csv.as_bytes(), true, None, &locale).unwrap();
+    assert_eq!(template.fields[1].field_type, FieldType::DateTime);
+    assert_eq!(template.rows[0][1], "2024-03-04 19:00");
+    assert_eq!(template.rows[1][1], "2024-04-03 14:00");
+    assert_eq!(
+      template.ambiguous_dates,
+      vec![AmbiguousDateCell {
+        field_name: "Date".to_string(),
+        row_index: 1,
+        cell: "04/03/2024 10:00".to_string(),
+        ambiguities: vec![DateAmbiguity::DayMonthOrder],
+      }]
+    );
+  }
+
+  #[test]
+  fn test_parse_localized_number() {
+    let number = parse_formatted_number("1.234,56 €", DecimalSeparator::Auto).unwrap();
+    assert_eq!(number.value, 1234.56);
+    assert_eq!(number.format, NumberFormat::EUR);
+    let number = parse_formatted_number("CHF -1'234.5", DecimalSeparator::Auto).unwrap();
+    assert_eq!(number.value, -1234.5);
+    assert_eq!(number.format, NumberFormat::Franc);
+    let number = parse_formatted_number("CA$12", DecimalSeparator::Auto).unwrap();
+    assert_eq!(number.format, NumberFormat::CanadianDollar);
+    assert_eq!(
+      parse_formatted_number("1.234", DecimalSeparator::Comma).map(|number| number.value),
+      Some(1234.0)
+    );
+    assert_eq!(parse_formatted_number("1,5", DecimalSeparator::Dot), None);
+  }
+
+  #[test]
+  fn test_csv_template_with_currencies() {
+    let csv = "Name,Price,Cost,Amount\n\
+               A,\"1.234,56 €\",\"$1,234.56\",\"1,5\"\n\
+               B,\"7,00 €\",$3,\"2,25\"\n\
+               C,\"12 000,00 €\",£4,3\n";
+    let template = CSVTemplate::try_from_reader(csv.as_bytes(), true, None).unwrap();
+    assert_eq!(template.fields[1].field_type, FieldType::Number);
+    assert_eq!(template.fields[1].number_format, NumberFormat::EUR);
+    assert_eq!(template.rows[0][1], "1234.56");
+    assert_eq!(template.rows[1][1], "7");
+    assert_eq!(template.rows[2][1], "12000");
+
+    // The currencies are different.
+    assert_eq!(template.fields[2].field_type, FieldType::Number);
+    assert_eq!(template.fields[2].number_format, NumberFormat::Num);
+    assert_eq!(template.rows[0][2], "1234.56");
+
+    // The decimal separator is detected for the whole column.
+    assert_eq!(template.fields[3].field_type, FieldType::Number);
+    assert_eq!(template.rows[0][3], "1.5");
+    assert_eq!(template.rows[2][3], "3");
+
+    // A column that can't be read with the decimal separator of the locale is not a number.
+    let locale = ImportLocale::english().with_decimal_separator(DecimalSeparator::Dot);
+    let template =
+      CSVTemplate::try_from_reader_with_locale(csv.as_bytes(), true, None, &locale).unwrap();
+    assert_ne!(template.fields[3].field_type, FieldType::Number);
+    assert_eq!(template.fields[2].field_type, FieldType::Number);
   }
 }
@@ -0,0 +1,254 @@
+use crate::database::{Database, timestamp};
+use crate::database_trait::{DatabaseCollabService, DatabaseRowCollabService};
+use crate::entity::FieldType;
+use crate::error::DatabaseError;
+use crate::fields::select_type_option::SelectTypeOption;
+use crate::rows::CreateRowParams;
+use crate::template::builder::FileUrlBuilder;
+use crate::template::csv::{CSVResource, CSVTemplate, read_csv_headers, read_csv_rows};
+use crate::template::entity::{CELL_DATA, DatabaseTemplate};
+use crate::template::locale::ImportLocale;
+use crate::template::option_parse::SELECT_OPTION_SEPARATOR;
+use crate::template::util::create_row_params_from_template;
+use collab::preclude::Any;
+use collab::util::AnyMapExt;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+/// The number of rows read and inserted at once by [CSVStreamImporter].
+pub const DEFAULT_CSV_CHUNK_SIZE: usize = 1000;
+
+/// The progress of a [CSVStreamImporter], reported after each chunk of rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CSVImportProgress {
+  /// The number of rows inserted into the database.
+  pub imported_rows: usize,
+  /// The number of bytes of the csv file read so far.
+  pub read_bytes: u64,
+}
+
+pub type CSVImportProgressCallback = Arc<dyn Fn(CSVImportProgress) + Send + Sync>;
+
+/// Import a csv file into a new database without reading the whole file in memory.
+///
+/// The types of the fields are detected from the first chunk of rows, which is used to create the
+/// database. The next rows are read and inserted chunk by chunk with [Database::create_rows], so
+/// only one chunk of rows is in memory at a time. The select options found in the next chunks
+/// are added to their fields.
+pub struct CSVStreamImporter {
+  chunk_size: usize,
+  auto_field_type: bool,
+  locale: ImportLocale,
+  resource: Option<CSVResource>,
+  file_url_builder: Option<Arc<dyn FileUrlBuilder>>,
+  progress: Option<CSVImportProgressCallback>,
+}
+
+impl Default for CSVStreamImporter {
+  fn default() -> Self {
+    Self {
+      chunk_size: DEFAULT_CSV_CHUNK_SIZE,
+      auto_field_type: true,
+      locale: ImportLocale::default(),
+      resource: None,
+      file_url_builder: None,
+      progress: None,
+    }
+  }
+}
+
+impl CSVStreamImporter {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+    self.chunk_size = chunk_size.max(1);
+    self
+  }
+
+  /// Import all the fields as text fields when false, see [CSVTemplate::try_from_reader].
+  pub fn with_auto_field_type(mut self, auto_field_type: bool) -> Self {
+    self.auto_field_type = auto_field_type;
+    self
+  }
+
+  pub fn with_locale(mut self, locale: ImportLocale) -> Self {
+    self.locale = locale;
+    self
+  }
+
+  /// Set the files referenced by the media cells and how their urls are built.
+  pub fn with_resource(
+    mut self,
+    resource: CSVResource,
+    file_url_builder: Arc<dyn FileUrlBuilder>,
+  ) -> Self {
+    self.resource = Some(resource);
+    self.file_url_builder = Some(file_url_builder);
+    self
+  }
+
+  pub fn with_progress(mut self, progress: CSVImportProgressCallback) -> Self {
+    self.progress = Some(progress);
+    self
+  }
+
+  pub async fn import(
+    self,
+    reader: impl io::Read + Send,
+    database_collab_service: Arc<dyn DatabaseCollabService>,
+    database_row_collab_service: Arc<dyn DatabaseRowCollabService>,
+  ) -> Result<Database, DatabaseError> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = read_csv_headers(&mut reader)?;
+    let rows = read_csv_rows(&mut reader, self.chunk_size);
+    let mut imported_rows = rows.len();
+    let first_chunk = CSVTemplate::from_rows(
+      headers,
+      rows,
+      self.auto_field_type,
+      self.resource.clone(),
+      &self.locale,
+    );
+    let template = first_chunk.with_rows(vec![], &self.locale);
+
+    let database_template = first_chunk
+      .try_into_database_template(self.file_url_builder())
+      .await?;
+    let field_ids = database_template
+      .fields
+      .iter()
+      .map(|field| field.field_id.clone())
+      .collect::<Vec<_>>();
+    let mut database = Database::create_with_template(
+      database_template,
+      database_collab_service,
+      database_row_collab_service,
+    )
+    .await?;
+    self.report_progress(imported_rows, reader.position().byte());
+
+    while !reader.is_done() {
+      let rows = read_csv_rows(&mut reader, self.chunk_size);
+      if rows.is_empty() {
+        break;
+      }
+      imported_rows += rows.len();
+      let chunk = template
+        .with_rows(rows, &self.locale)
+        .try_into_database_template(self.file_url_builder())
+        .await?;
+      let params = chunk_row_params(&mut database, &field_ids, chunk);
+      database.create_rows(params).await?;
+      self.report_progress(imported_rows, reader.position().byte());
+    }
+    Ok(database)
+  }
+
+  fn file_url_builder(&self) -> Option<Box<dyn FileUrlBuilder>> {
+    self
+      .file_url_builder
+      .clone()
+      .map(|builder| Box::new(SharedFileUrlBuilder(builder)) as Box<dyn FileUrlBuilder>)
+  }
+
+  fn report_progress(&self, imported_rows: usize, read_bytes: u64) {
+    if let Some(progress) = &self.progress {
+      progress(CSVImportProgress {
+        imported_rows,
+        read_bytes,
+      });
+    }
+  }
+}
+
+struct SharedFileUrlBuilder(Arc<dyn FileUrlBuilder>);
+
+#[async_trait::async_trait]
+impl FileUrlBuilder for SharedFileUrlBuilder {
+  async fn build(&self, database_id: &str, path: &Path) -> Option<String> {
+    self.0.build(database_id, path).await
+  }
+}
+
+/// Turn the rows of the template of a chunk into rows of the database. The fields of the
+/// template are the fields of the database, in the same order, but with other ids, and the
+/// select options of the template are matched by name with the options of the database. The
+/// new options are added to the fields of the database.
+fn chunk_row_params(
+  database: &mut Database,
+  field_ids: &[String],
+  chunk: DatabaseTemplate,
+) -> Vec<CreateRowParams> {
+  let database_id = database.get_database_id();
+  let mut chunk_field_ids = HashMap::new();
+  let mut option_ids = HashMap::new();
+  for (field, field_id) in chunk.fields.iter().zip(field_ids) {
+    let is_select = matches!(
+      field.field_type,
+      FieldType::SingleSelect | FieldType::MultiSelect
+    );
+    chunk_field_ids.insert(field.field_id.clone(), (field_id.clone(), is_select));
+    if !is_select {
+      continue;
+    }
+    let Some(chunk_type_option) = field.type_options.get(&field.field_type) else {
+      continue;
+    };
+    let Some(database_field) = database.get_field(field_id) else {
+      continue;
+    };
+    let mut type_option = database_field
+      .get_type_option::<SelectTypeOption>(field.field_type.type_id())
+      .unwrap_or_default();
+    let options_count = type_option.options.len();
+    for option in SelectTypeOption::from(chunk_type_option.clone()).options {
+      match type_option
+        .options
+        .iter()
+        .find(|existing| existing.name == option.name)
+      {
+        Some(existing) => {
+          option_ids.insert(option.id, existing.id.clone());
+        },
+        None => {
+          option_ids.insert(option.id.clone(), option.id.clone());
+          type_option.options.push(option);
+        },
+      }
+    }
+    if type_option.options.len() != options_count {
+      database.update_field(field_id, |update| {
+        update.set_type_option(field.field_type.into(), Some(type_option.into()));
+      });
+    }
+  }
+
+  let timestamp = timestamp();
+  chunk
+    .rows
+    .into_iter()
+    .map(|mut row| {
+      row.cells = row
+        .cells
+        .into_iter()
+        .filter_map(|(chunk_field_id, mut cell)| {
+          let (field_id, is_select) = chunk_field_ids.get(&chunk_field_id)?;
+          if let Some(ids) = cell.get_as::<String>(CELL_DATA).filter(|_| *is_select) {
+            let ids = ids
+              .split(SELECT_OPTION_SEPARATOR)
+              .map(|id| option_ids.get(id).map_or(id, |id| id.as_str()))
+              .collect::<Vec<_>>()
+              .join(SELECT_OPTION_SEPARATOR);
+            cell.insert(CELL_DATA.to_string(), Any::from(ids));
+          }
+          Some((field_id.clone(), cell))
+        })
+        .collect();
+      create_row_params_from_template(&database_id, row, timestamp)
+    })
+    .collect()
+}
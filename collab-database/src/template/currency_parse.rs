@@ -0,0 +1,222 @@
+use crate::fields::number_type_option::NumberFormat;
+
+/// The characters grouping the digits of a number by three, besides the `,` or the `.` that is not
+/// the decimal separator, e.g. `1 234,56` or `1'234.56`.
+const THOUSANDS_SEPARATORS: [char; 4] = [' ', '\u{a0}', '\u{202f}', '\''];
+
+/// The currency symbols and codes written before or after the numbers, and the format of the
+/// number field they are imported into. The longest symbols come first, so `CA$` is not read
+/// as `$`.
+const CURRENCIES: [(&str, NumberFormat); 57] = [
+  ("CA$", NumberFormat::CanadianDollar),
+  ("HK$", NumberFormat::HongKongDollar),
+  ("NZ$", NumberFormat::NewZealandDollar),
+  ("NT$", NumberFormat::NewTaiwanDollar),
+  ("MX$", NumberFormat::MexicanPeso),
+  ("US$", NumberFormat::USD),
+  ("USD", NumberFormat::USD),
+  ("CAD", NumberFormat::CanadianDollar),
+  ("EUR", NumberFormat::EUR),
+  ("GBP", NumberFormat::Pound),
+  ("JPY", NumberFormat::Yen),
+  ("RUB", NumberFormat::Ruble),
+  ("INR", NumberFormat::Rupee),
+  ("KRW", NumberFormat::Won),
+  ("CNY", NumberFormat::Yuan),
+  ("RMB", NumberFormat::Yuan),
+  ("BRL", NumberFormat::Real),
+  ("TRY", NumberFormat::Lira),
+  ("IDR", NumberFormat::Rupiah),
+  ("CHF", NumberFormat::Franc),
+  ("HKD", NumberFormat::HongKongDollar),
+  ("NZD", NumberFormat::NewZealandDollar),
+  ("SEK", NumberFormat::Krona),
+  ("NOK", NumberFormat::NorwegianKrone),
+  ("MXN", NumberFormat::MexicanPeso),
+  ("ZAR", NumberFormat::Rand),
+  ("TWD", NumberFormat::NewTaiwanDollar),
+  ("DKK", NumberFormat::DanishKrone),
+  ("THB", NumberFormat::Baht),
+  ("HUF", NumberFormat::Forint),
+  ("CZK", NumberFormat::Koruna),
+  ("ILS", NumberFormat::Shekel),
+  ("CLP", NumberFormat::ChileanPeso),
+  ("PHP", NumberFormat::PhilippinePeso),
+  ("AED", NumberFormat::Dirham),
+  ("COP", NumberFormat::ColombianPeso),
+  ("SAR", NumberFormat::Riyal),
+  ("MYR", NumberFormat::Ringgit),
+  ("RON", NumberFormat::Leu),
+  ("ARS", NumberFormat::ArgentinePeso),
+  ("UYU", NumberFormat::UruguayanPeso),
+  ("R$", NumberFormat::Real),
+  ("Rp", NumberFormat::Rupiah),
+  ("RM", NumberFormat::Ringgit),
+  ("Kč", NumberFormat::Koruna),
+  ("Ft", NumberFormat::Forint),
+  ("kr", NumberFormat::Krona),
+  ("$", NumberFormat::USD),
+  ("€", NumberFormat::EUR),
+  ("£", NumberFormat::Pound),
+  ("¥", NumberFormat::Yen),
+  ("₹", NumberFormat::Rupee),
+  ("₩", NumberFormat::Won),
+  ("₽", NumberFormat::Ruble),
+  ("₺", NumberFormat::Lira),
+  ("₪", NumberFormat::Shekel),
+  ("฿", NumberFormat::Baht),
+];
+
+/// The character separating the integer part of a number from its fraction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DecimalSeparator {
+  /// Use the separator that reads all the numbers of a column, `.` first.
+  #[default]
+  Auto,
+  /// `1,234.56`, as in English.
+  Dot,
+  /// `1.234,56` or `1 234,56`, as in most European languages.
+  Comma,
+}
+
+/// A number parsed by [parse_formatted_number].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FormattedNumber {
+  pub value: f64,
+  /// The currency of the number, [NumberFormat::Percent] for `45%`, or [NumberFormat::Num].
+  pub format: NumberFormat,
+}
+
+/// Parse a formatted number, e.g. `$1,234.56`, `1.234,56 €`, `-CHF 3` or `45%`. With
+/// [DecimalSeparator::Auto], `.` is tried as the decimal separator first.
+pub fn parse_formatted_number(cell: &str, separator: DecimalSeparator) -> Option<FormattedNumber> {
+  let cell = cell.trim();
+  if cell.is_empty() {
+    return None;
+  }
+  // With a decimal comma, `1.234` is 1234.
+  if separator != DecimalSeparator::Comma {
+    if let Ok(value) = cell.parse::<f64>() {
+      return Some(FormattedNumber {
+        value,
+        format: NumberFormat::Num,
+      });
+    }
+  }
+
+  let (negative, unsigned) = strip_sign(cell);
+  let (format, unsigned) = strip_currency(unsigned);
+  // The sign can be written after the currency symbol, e.g. `$-3`.
+  let (negative_amount, unsigned) = strip_sign(unsigned);
+  if negative && negative_amount {
+    return None;
+  }
+  let value = match separator {
+    DecimalSeparator::Dot => parse_number(unsigned, '.', ','),
+    DecimalSeparator::Comma => parse_number(unsigned, ',', '.'),
+    DecimalSeparator::Auto => {
+      parse_number(unsigned, '.', ',').or_else(|| parse_number(unsigned, ',', '.'))
+    },
+  }?;
+  Some(FormattedNumber {
+    value: if negative || negative_amount {
+      -value
+    } else {
+      value
+    },
+    format,
+  })
+}
+
+/// Return the decimal separator that reads all the non-empty cells, or None if the cells are not
+/// all numbers.
+pub fn detect_decimal_separator(
+  cells: &[&str],
+  separator: DecimalSeparator,
+) -> Option<DecimalSeparator> {
+  let cells = cells
+    .iter()
+    .filter(|cell| !cell.trim().is_empty())
+    .collect::<Vec<_>>();
+  if cells.is_empty() {
+    return None;
+  }
+  let candidates = match separator {
+    DecimalSeparator::Auto => vec![DecimalSeparator::Dot, DecimalSeparator::Comma],
+    separator => vec![separator],
+  };
+  candidates.into_iter().find(|separator| {
+    cells
+      .iter()
+      .all(|cell| parse_formatted_number(cell, *separator).is_some())
+  })
+}
+
+/// Return the currency of the cells, or [NumberFormat::Num] if the cells have no currency or
+/// different currencies.
+pub fn detect_number_format(cells: &[&str], separator: DecimalSeparator) -> NumberFormat {
+  let mut formats = cells
+    .iter()
+    .filter_map(|cell| parse_formatted_number(cell, separator))
+    .map(|number| number.format)
+    .filter(|format| *format != NumberFormat::Num);
+  match formats.next() {
+    Some(format) if formats.all(|other| other == format) => format,
+    _ => NumberFormat::Num,
+  }
+}
+
+fn strip_sign(value: &str) -> (bool, &str) {
+  match value.strip_prefix('-') {
+    Some(rest) => (true, rest.trim_start()),
+    None => (false, value),
+  }
+}
+
+fn strip_currency(value: &str) -> (NumberFormat, &str) {
+  if let Some(rest) = value.strip_suffix('%') {
+    return (NumberFormat::Percent, rest.trim_end());
+  }
+  for (symbol, format) in CURRENCIES {
+    if let Some(rest) = value.strip_prefix(symbol) {
+      return (format, rest.trim_start());
+    }
+    if let Some(rest) = value.strip_suffix(symbol) {
+      return (format, rest.trim_end());
+    }
+  }
+  (NumberFormat::Num, value)
+}
+
+/// Parse a number whose digits may be grouped by three, e.g. `1,234.5` or `1 234,5`.
+fn parse_number(value: &str, decimal: char, thousands: char) -> Option<f64> {
+  if !value.starts_with(|c: char| c.is_ascii_digit()) {
+    return None;
+  }
+  let (integer, fraction) = match value.split_once(decimal) {
+    Some((integer, fraction)) => (integer, Some(fraction)),
+    None => (value, None),
+  };
+  let is_separator = |c: char| c == thousands || THOUSANDS_SEPARATORS.contains(&c);
+  let mut groups = integer.split(is_separator);
+  let first = groups.next()?;
+  let is_digits = |group: &str| !group.is_empty() && group.bytes().all(|b| b.is_ascii_digit());
+  if !is_digits(first) {
+    return None;
+  }
+  if integer.contains(is_separator)
+    && (first.len() > 3 || !groups.all(|group| group.len() == 3 && is_digits(group)))
+  {
+    return None;
+  }
+
+  let mut number = integer.replace(is_separator, "");
+  if let Some(fraction) = fraction {
+    if !is_digits(fraction) {
+      return None;
+    }
+    number.push('.');
+    number.push_str(fraction);
+  }
+  number.parse::<f64>().ok()
+}
@@ -1,5 +1,135 @@
 #![allow(deprecated)]
-use chrono::{NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono::{
+  DateTime, Duration, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+};
+use chrono_tz::Tz;
+
+/// The formats of the dates, the slash separated dates excepted, see [DateOrder].
+const DATE_FORMATS: [&str; 8] = [
+  "%Y-%m-%d",
+  "%Y/%m/%d",
+  "%B %d, %Y",
+  "%b %d, %Y",
+  "%B %d %Y",
+  "%d %B %Y",
+  "%d %b %Y",
+  "%Y.%m.%d",
+];
+const TIME_FORMATS: [&str; 5] = ["%H:%M:%S", "%H:%M", "%I:%M:%S %p", "%I:%M %p", "%I:%M%p"];
+
+/// The offsets of the timezone abbreviations, in minutes. The abbreviations used by several
+/// timezones are in [AMBIGUOUS_TIMEZONE_ABBREVIATIONS].
+const TIMEZONE_ABBREVIATIONS: [(&str, i32); 26] = [
+  ("UTC", 0),
+  ("GMT", 0),
+  ("Z", 0),
+  ("EST", -5 * 60),
+  ("EDT", -4 * 60),
+  ("CDT", -5 * 60),
+  ("MST", -7 * 60),
+  ("MDT", -6 * 60),
+  ("PST", -8 * 60),
+  ("PDT", -7 * 60),
+  ("AKST", -9 * 60),
+  ("AKDT", -8 * 60),
+  ("HST", -10 * 60),
+  ("WET", 0),
+  ("WEST", 60),
+  ("CET", 60),
+  ("CEST", 2 * 60),
+  ("EET", 2 * 60),
+  ("EEST", 3 * 60),
+  ("MSK", 3 * 60),
+  ("JST", 9 * 60),
+  ("KST", 9 * 60),
+  ("AEST", 10 * 60),
+  ("AEDT", 11 * 60),
+  ("NZST", 12 * 60),
+  ("NZDT", 13 * 60),
+];
+
+/// The abbreviations used by several timezones, with the offset that is used, in minutes.
+const AMBIGUOUS_TIMEZONE_ABBREVIATIONS: [(&str, i32); 4] = [
+  // Central Standard Time and China Standard Time.
+  ("CST", -6 * 60),
+  // British Summer Time and Bangladesh Standard Time.
+  ("BST", 60),
+  // India, Irish and Israel Standard Time.
+  ("IST", 5 * 60 + 30),
+  // Arabia Standard Time and Atlantic Standard Time.
+  ("AST", -4 * 60),
+];
+
+/// How the dates like `04/03/2024` are read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DateOrder {
+  /// `04/03/2024` is April 3rd, as in the exports of the US.
+  #[default]
+  MonthFirst,
+  /// `04/03/2024` is March 4th.
+  DayFirst,
+}
+
+/// How the date cells of an imported csv file are parsed, see [parse_date_cell_with_options].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateParseOptions {
+  /// The timezone of the dates with a time but without a timezone, e.g. the timezone of the
+  /// user who exported the data. The dates without a time are not converted.
+  pub timezone: Tz,
+  pub date_order: DateOrder,
+}
+
+impl Default for DateParseOptions {
+  fn default() -> Self {
+    Self {
+      timezone: Tz::UTC,
+      date_order: DateOrder::default(),
+    }
+  }
+}
+
+impl DateParseOptions {
+  pub fn new(timezone: Tz) -> Self {
+    Self {
+      timezone,
+      ..Default::default()
+    }
+  }
+
+  pub fn with_date_order(mut self, date_order: DateOrder) -> Self {
+    self.date_order = date_order;
+    self
+  }
+}
+
+/// Why the timestamp of a parsed date may not be the one that was meant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateAmbiguity {
+  /// Both the day and the month are 12 or less, e.g. `04/03/2024`. The date was read with the
+  /// [DateOrder] of the options.
+  DayMonthOrder,
+  /// The timezone abbreviation is used by several timezones, e.g. `IST`.
+  TimezoneAbbreviation(String),
+  /// The time happens twice in the timezone, when the clocks go back. The earliest time is
+  /// used.
+  RepeatedLocalTime,
+  /// The time doesn't exist in the timezone, when the clocks go forward. The time is moved
+  /// forward by an hour.
+  SkippedLocalTime,
+}
+
+/// A parsed date cell, with the reasons its timestamp may be wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedDateOutcome {
+  pub parsed: ParsedDateCell,
+  pub ambiguities: Vec<DateAmbiguity>,
+}
+
+impl ParsedDateOutcome {
+  pub fn is_ambiguous(&self) -> bool {
+    !self.ambiguities.is_empty()
+  }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParsedDateCell {
@@ -9,33 +139,55 @@ pub struct ParsedDateCell {
   pub is_range: bool,
 }
 
+/// Parse a date cell in UTC, see [parse_date_cell_with_options].
 pub fn parse_date_cell(cell: &str) -> Option<ParsedDateCell> {
+  parse_date_cell_with_options(cell, &DateParseOptions::default()).map(|outcome| outcome.parsed)
+}
+
+/// Parse a date cell, or a range of dates separated by `→`, to UTC timestamps. The times are
+/// converted from their timezone, e.g. `March 4, 2024 2:00 PM (EST)` or
+/// `2024-03-04 14:00 GMT+1`, or from the timezone of the options when they have none.
+pub fn parse_date_cell_with_options(
+  cell: &str,
+  options: &DateParseOptions,
+) -> Option<ParsedDateOutcome> {
   let cell = cell.trim();
   if cell.is_empty() {
     return None;
   }
 
+  let mut ambiguities = vec![];
   if let Some((start, end)) = cell.split_once('→') {
-    let (start_ts, start_include_time) = parse_single_datetime(start.trim())?;
-    let (end_ts, end_include_time) = parse_single_datetime(end.trim())?;
-    return Some(ParsedDateCell {
-      timestamp: start_ts,
-      end_timestamp: Some(end_ts),
-      include_time: start_include_time || end_include_time,
-      is_range: true,
+    let (start_ts, start_include_time) = parse_single_datetime(start, options, &mut ambiguities)?;
+    let (end_ts, end_include_time) = parse_single_datetime(end, options, &mut ambiguities)?;
+    return Some(ParsedDateOutcome {
+      parsed: ParsedDateCell {
+        timestamp: start_ts,
+        end_timestamp: Some(end_ts),
+        include_time: start_include_time || end_include_time,
+        is_range: true,
+      },
+      ambiguities,
     });
   }
 
-  let (timestamp, include_time) = parse_single_datetime(cell)?;
-  Some(ParsedDateCell {
-    timestamp,
-    end_timestamp: None,
-    include_time,
-    is_range: false,
+  let (timestamp, include_time) = parse_single_datetime(cell, options, &mut ambiguities)?;
+  Some(ParsedDateOutcome {
+    parsed: ParsedDateCell {
+      timestamp,
+      end_timestamp: None,
+      include_time,
+      is_range: false,
+    },
+    ambiguities,
   })
 }
 
-fn parse_single_datetime(cell: &str) -> Option<(i64, bool)> {
+fn parse_single_datetime(
+  cell: &str,
+  options: &DateParseOptions,
+  ambiguities: &mut Vec<DateAmbiguity>,
+) -> Option<(i64, bool)> {
   let cell = cell.trim();
   if cell.is_empty() {
     return None;
@@ -55,50 +207,219 @@ fn parse_single_datetime(cell: &str) -> Option<(i64, bool)> {
     }
   }
 
-  if let Ok(date) = chrono::DateTime::parse_from_rfc3339(cell) {
+  if let Ok(date) = DateTime::parse_from_rfc3339(cell) {
     return Some((date.timestamp(), true));
   }
 
-  if let Ok(naive_datetime) = NaiveDateTime::parse_from_str(cell, "%Y-%m-%d %H:%M:%S") {
-    return Some((Utc.from_utc_datetime(&naive_datetime).timestamp(), true));
+  let (value, offset) = split_timezone(cell, ambiguities);
+  let value = value.trim();
+  if let Some(date) = parse_date(value, options.date_order, ambiguities) {
+    // A date without a time is the same day in every timezone.
+    let datetime = date.and_hms(0, 0, 0);
+    return Some((Utc.from_utc_datetime(&datetime).timestamp(), false));
   }
 
-  if let Ok(naive_datetime) = NaiveDateTime::parse_from_str(cell, "%Y-%m-%d %H:%M") {
-    return Some((Utc.from_utc_datetime(&naive_datetime).timestamp(), true));
-  }
+  let datetime = parse_naive_datetime(value, options.date_order, ambiguities)?;
+  let timestamp = match offset {
+    Some(offset) => offset.from_local_datetime(&datetime).earliest()?.timestamp(),
+    None => match options.timezone.from_local_datetime(&datetime) {
+      LocalResult::Single(datetime) => datetime.timestamp(),
+      LocalResult::Ambiguous(earliest, _) => {
+        ambiguities.push(DateAmbiguity::RepeatedLocalTime);
+        earliest.timestamp()
+      },
+      LocalResult::None => {
+        ambiguities.push(DateAmbiguity::SkippedLocalTime);
+        let datetime = datetime + Duration::hours(1);
+        options
+          .timezone
+          .from_local_datetime(&datetime)
+          .earliest()?
+          .timestamp()
+      },
+    },
+  };
+  Some((timestamp, true))
+}
 
-  if let Ok(naive_datetime) = NaiveDateTime::parse_from_str(cell, "%Y-%m-%d %I:%M %p") {
-    return Some((Utc.from_utc_datetime(&naive_datetime).timestamp(), true));
+/// Remove the timezone at the end of the cell, e.g. ` (EST)`, ` GMT+8`, ` UTC-05:00` or
+/// ` (America/New_York)`, and return its offset. The timezones of the IANA database are
+/// converted at the date of the cell, so the offset is the one of the time in the cell.
+fn split_timezone<'a>(
+  cell: &'a str,
+  ambiguities: &mut Vec<DateAmbiguity>,
+) -> (&'a str, Option<TimezoneSuffix>) {
+  let (value, suffix) = if let Some(stripped) = cell.strip_suffix(')') {
+    match stripped.rfind('(') {
+      Some(index) => (&cell[..index], &stripped[index + 1..]),
+      None => return (cell, None),
+    }
+  } else {
+    match cell.rfind(' ') {
+      Some(index) => (&cell[..index], &cell[index + 1..]),
+      None => return (cell, None),
+    }
+  };
+
+  match parse_timezone(suffix.trim(), ambiguities) {
+    Some(timezone) => (value, Some(timezone)),
+    None => (cell, None),
   }
+}
 
-  if let Ok(naive_date) = NaiveDate::parse_from_str(cell, "%Y-%m-%d") {
-    let datetime = naive_date.and_hms(0, 0, 0);
-    return Some((Utc.from_utc_datetime(&datetime).timestamp(), false));
+/// The timezone written after a date.
+#[derive(Debug, Clone, Copy)]
+enum TimezoneSuffix {
+  Offset(FixedOffset),
+  Named(Tz),
+}
+
+impl TimezoneSuffix {
+  fn from_local_datetime(&self, datetime: &NaiveDateTime) -> LocalResult<DateTime<Utc>> {
+    match self {
+      TimezoneSuffix::Offset(offset) => offset
+        .from_local_datetime(datetime)
+        .map(|value| value.with_timezone(&Utc)),
+      TimezoneSuffix::Named(timezone) => timezone
+        .from_local_datetime(datetime)
+        .map(|value| value.with_timezone(&Utc)),
+    }
   }
+}
 
-  if let Ok(naive_date) = NaiveDate::parse_from_str(cell, "%Y/%m/%d") {
-    let datetime = naive_date.and_hms(0, 0, 0);
-    return Some((Utc.from_utc_datetime(&datetime).timestamp(), false));
+fn parse_timezone(value: &str, ambiguities: &mut Vec<DateAmbiguity>) -> Option<TimezoneSuffix> {
+  let upper = value.to_ascii_uppercase();
+  if let Some((_, minutes)) = TIMEZONE_ABBREVIATIONS
+    .iter()
+    .find(|(abbreviation, _)| *abbreviation == upper)
+  {
+    return FixedOffset::east_opt(minutes * 60).map(TimezoneSuffix::Offset);
+  }
+  if let Some((abbreviation, minutes)) = AMBIGUOUS_TIMEZONE_ABBREVIATIONS
+    .iter()
+    .find(|(abbreviation, _)| *abbreviation == upper)
+  {
+    ambiguities.push(DateAmbiguity::TimezoneAbbreviation(
+      abbreviation.to_string(),
+    ));
+    return FixedOffset::east_opt(minutes * 60).map(TimezoneSuffix::Offset);
   }
 
-  if let Ok(naive_date) = NaiveDate::parse_from_str(cell, "%m/%d/%Y") {
-    let datetime = naive_date.and_hms(0, 0, 0);
-    return Some((Utc.from_utc_datetime(&datetime).timestamp(), false));
+  // GMT+8, UTC-05:00, +0530
+  let offset = upper
+    .strip_prefix("GMT")
+    .or_else(|| upper.strip_prefix("UTC"))
+    .unwrap_or(&upper);
+  if let Some(offset) = parse_offset(offset) {
+    return Some(TimezoneSuffix::Offset(offset));
   }
 
-  if let Ok(naive_date) = NaiveDate::parse_from_str(cell, "%B %d, %Y") {
-    let datetime = naive_date.and_hms(0, 0, 0);
-    return Some((Utc.from_utc_datetime(&datetime).timestamp(), false));
+  value.parse::<Tz>().ok().map(TimezoneSuffix::Named)
+}
+
+fn parse_offset(value: &str) -> Option<FixedOffset> {
+  let sign = match value.chars().next()? {
+    '+' => 1,
+    '-' => -1,
+    _ => return None,
+  };
+  let value = &value[1..];
+  let (hours, minutes) = match value.split_once(':') {
+    Some((hours, minutes)) => (hours, minutes),
+    None if value.len() == 4 => value.split_at(2),
+    None => (value, "0"),
+  };
+  let hours = hours.parse::<i32>().ok().filter(|hours| *hours <= 14)?;
+  let minutes = minutes
+    .parse::<i32>()
+    .ok()
+    .filter(|minutes| *minutes < 60)?;
+  FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+fn parse_date(
+  value: &str,
+  date_order: DateOrder,
+  ambiguities: &mut Vec<DateAmbiguity>,
+) -> Option<NaiveDate> {
+  for format in DATE_FORMATS {
+    if let Ok(date) = NaiveDate::parse_from_str(value, format) {
+      return Some(date);
+    }
   }
+  parse_slash_date(value, date_order, ambiguities)
+}
 
-  if let Ok(naive_date) = NaiveDate::parse_from_str(cell, "%d/%m/%Y") {
-    let datetime = naive_date.and_hms(0, 0, 0);
-    return Some((Utc.from_utc_datetime(&datetime).timestamp(), false));
+/// Parse `04/03/2024` with the order of the options, unless only one order is a valid date.
+fn parse_slash_date(
+  value: &str,
+  date_order: DateOrder,
+  ambiguities: &mut Vec<DateAmbiguity>,
+) -> Option<NaiveDate> {
+  let parts = value.split('/').collect::<Vec<_>>();
+  if parts.len() != 3 || parts[2].len() != 4 {
+    return None;
   }
+  let first = parts[0].parse::<u32>().ok()?;
+  let second = parts[1].parse::<u32>().ok()?;
+  let year = parts[2].parse::<i32>().ok()?;
+  let month_first = NaiveDate::from_ymd_opt(year, first, second);
+  let day_first = NaiveDate::from_ymd_opt(year, second, first);
+  match (month_first, day_first) {
+    (Some(month_first), Some(day_first)) => {
+      if month_first != day_first {
+        ambiguities.push(DateAmbiguity::DayMonthOrder);
+      }
+      Some(match date_order {
+        DateOrder::MonthFirst => month_first,
+        DateOrder::DayFirst => day_first,
+      })
+    },
+    (month_first, day_first) => month_first.or(day_first),
+  }
+}
 
+/// Parse a date followed by a time, e.g. `March 4, 2024 2:00 PM` or `2024-03-04T14:00`.
+fn parse_naive_datetime(
+  value: &str,
+  date_order: DateOrder,
+  ambiguities: &mut Vec<DateAmbiguity>,
+) -> Option<NaiveDateTime> {
+  // 2024-03-04T14:00
+  let value = match value.find('T') {
+    Some(index) if index > 0 && value.as_bytes()[index - 1].is_ascii_digit() => {
+      format!("{} {}", &value[..index], &value[index + 1..])
+    },
+    _ => value.to_string(),
+  };
+  let value = value.trim();
+  for (index, _) in value.match_indices(' ') {
+    let (date, time) = (value[..index].trim(), value[index..].trim());
+    let Some(time) = parse_time(time) else {
+      continue;
+    };
+    let mut date_ambiguities = vec![];
+    if let Some(date) = parse_date(date, date_order, &mut date_ambiguities) {
+      ambiguities.extend(date_ambiguities);
+      return Some(date.and_time(time));
+    }
+  }
   None
 }
 
+fn parse_time(value: &str) -> Option<NaiveTime> {
+  let value = value.to_ascii_uppercase();
+  TIME_FORMATS
+    .iter()
+    .find_map(|format| NaiveTime::parse_from_str(&value, format).ok())
+    .or_else(|| {
+      // `2 PM` isn't parsed by chrono without the minutes.
+      let (hour, meridiem) = value.split_once(' ')?;
+      let time = format!("{}:00 {}", hour, meridiem);
+      NaiveTime::parse_from_str(&time, "%I:%M %p").ok()
+    })
+}
+
 pub fn cast_string_to_timestamp(cell: &str) -> Option<i64> {
   // Try to parse as a UNIX timestamp directly
   if let Ok(unix_timestamp) = cell.parse::<i64>() {
@@ -326,4 +647,81 @@ mod tests {
         .to_string()
     );
   }
+
+  #[test]
+  fn test_timezone_abbreviation() {
+    let parsed = parse_date_cell("March 4, 2024 2:00 PM (EST)").unwrap();
+    assert_eq!(
+      parsed.timestamp,
+      Utc.ymd(2024, 3, 4).and_hms(19, 0, 0).timestamp()
+    );
+    assert!(parsed.include_time);
+
+    let parsed = parse_date_cell("2024-03-04 14:00 GMT+8").unwrap();
+    assert_eq!(
+      parsed.timestamp,
+      Utc.ymd(2024, 3, 4).and_hms(6, 0, 0).timestamp()
+    );
+
+    let outcome =
+      parse_date_cell_with_options("2024-03-04 14:00 IST", &DateParseOptions::default()).unwrap();
+    assert_eq!(
+      outcome.ambiguities,
+      vec![DateAmbiguity::TimezoneAbbreviation("IST".to_string())]
+    );
+  }
+
+  #[test]
+  fn test_source_timezone() {
+    let options = DateParseOptions::new(Tz::America__New_York);
+    // Daylight saving time started on March 10, 2024.
+    let outcome = parse_date_cell_with_options("March 4, 2024 2:00 PM", &options).unwrap();
+    assert_eq!(
+      outcome.parsed.timestamp,
+      Utc.ymd(2024, 3, 4).and_hms(19, 0, 0).timestamp()
+    );
+    let outcome = parse_date_cell_with_options("2024-07-04 14:00", &options).unwrap();
+    assert_eq!(
+      outcome.parsed.timestamp,
+      Utc.ymd(2024, 7, 4).and_hms(18, 0, 0).timestamp()
+    );
+    assert!(!outcome.is_ambiguous());
+
+    // The dates without a time are not converted.
+    let outcome = parse_date_cell_with_options("July 4, 2024", &options).unwrap();
+    assert_eq!(
+      outcome.parsed.timestamp,
+      Utc.ymd(2024, 7, 4).and_hms(0, 0, 0).timestamp()
+    );
+    assert!(!outcome.parsed.include_time);
+
+    let outcome = parse_date_cell_with_options("2024-11-03 01:30", &options).unwrap();
+    assert_eq!(outcome.ambiguities, vec![DateAmbiguity::RepeatedLocalTime]);
+    assert_eq!(
+      outcome.parsed.timestamp,
+      Utc.ymd(2024, 11, 3).and_hms(5, 30, 0).timestamp()
+    );
+  }
+
+  #[test]
+  fn test_day_month_order() {
+    let outcome = parse_date_cell_with_options("04/03/2024", &DateParseOptions::default()).unwrap();
+    assert_eq!(outcome.ambiguities, vec![DateAmbiguity::DayMonthOrder]);
+    assert_eq!(
+      outcome.parsed.timestamp,
+      Utc.ymd(2024, 4, 3).and_hms(0, 0, 0).timestamp()
+    );
+
+    let options = DateParseOptions::default().with_date_order(DateOrder::DayFirst);
+    let outcome = parse_date_cell_with_options("04/03/2024 10:00", &options).unwrap();
+    assert_eq!(outcome.ambiguities, vec![DateAmbiguity::DayMonthOrder]);
+    assert_eq!(
+      outcome.parsed.timestamp,
+      Utc.ymd(2024, 3, 4).and_hms(10, 0, 0).timestamp()
+    );
+
+    // Only one order is a valid date.
+    let outcome = parse_date_cell_with_options("22/08/2024", &options).unwrap();
+    assert!(!outcome.is_ambiguous());
+  }
 }
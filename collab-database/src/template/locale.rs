@@ -0,0 +1,396 @@
+use chrono::{NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+use crate::template::currency_parse::DecimalSeparator;
+use crate::template::date_parse::{
+  DateParseOptions, ParsedDateCell, ParsedDateOutcome, parse_date_cell,
+  parse_date_cell_with_options,
+};
+
+/// The values that are used by the default (English) checkbox detection. Cells with these values
+/// are kept as is, other localized values are rewritten to [CHECKED] or [UNCHECKED].
+const DEFAULT_CHECKED_VALUES: [&str; 3] = ["yes", "true", "1"];
+const DEFAULT_UNCHECKED_VALUES: [&str; 3] = ["no", "false", "0"];
+const CHECKED: &str = "Yes";
+const UNCHECKED: &str = "No";
+
+/// Formats that are tried after the localized month names were replaced by month numbers.
+const LOCALIZED_DATE_FORMATS: [&str; 2] = ["%d %m %Y", "%Y年%m月%d日"];
+const LOCALIZED_DATETIME_FORMATS: [&str; 2] = ["%d %m %Y %H:%M", "%Y年%m月%d日 %H:%M"];
+
+/// A table of localized strings used when importing exported data, for example a Notion export
+/// that was created with a non-English UI.
+///
+/// The table is used to detect untitled pages, to parse localized dates and to coerce localized
+/// checkbox values. Use [ImportLocale::all] when the language of the export is unknown, and
+/// [ImportLocale::with_date_options] to set the timezone the dates were exported in.
+#[derive(Debug, Clone)]
+pub struct ImportLocale {
+  /// Names that are given to pages without a title, e.g. "Untitled" or "Sans titre".
+  pub untitled_names: Vec<String>,
+  /// Values of a checked checkbox cell, compared case-insensitively.
+  pub checked_values: Vec<String>,
+  /// Values of an unchecked checkbox cell, compared case-insensitively.
+  pub unchecked_values: Vec<String>,
+  /// Localized month names in lowercase and the corresponding month number (1-12).
+  pub month_names: Vec<(String, u32)>,
+  /// The timezone and the day/month order of the dates.
+  pub date_options: DateParseOptions,
+  /// The decimal separator of the numbers, detected for each column by default.
+  pub decimal_separator: DecimalSeparator,
+}
+
+impl Default for ImportLocale {
+  fn default() -> Self {
+    Self::english()
+  }
+}
+
+impl ImportLocale {
+  pub fn english() -> Self {
+    Self::new(
+      &["Untitled"],
+      &DEFAULT_CHECKED_VALUES,
+      &DEFAULT_UNCHECKED_VALUES,
+      &[
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+      ],
+    )
+  }
+
+  pub fn french() -> Self {
+    Self::new(
+      &["Sans titre"],
+      &["oui", "vrai"],
+      &["non", "faux"],
+      &[
+        "janvier",
+        "février",
+        "mars",
+        "avril",
+        "mai",
+        "juin",
+        "juillet",
+        "août",
+        "septembre",
+        "octobre",
+        "novembre",
+        "décembre",
+      ],
+    )
+  }
+
+  pub fn german() -> Self {
+    Self::new(
+      &["Unbenannt", "Ohne Titel"],
+      &["ja", "wahr"],
+      &["nein", "falsch"],
+      &[
+        "januar",
+        "februar",
+        "märz",
+        "april",
+        "mai",
+        "juni",
+        "juli",
+        "august",
+        "september",
+        "oktober",
+        "november",
+        "dezember",
+      ],
+    )
+  }
+
+  pub fn spanish() -> Self {
+    Self::new(
+      &["Sin título"],
+      &["sí", "si", "verdadero"],
+      &["no", "falso"],
+      &[
+        "enero",
+        "febrero",
+        "marzo",
+        "abril",
+        "mayo",
+        "junio",
+        "julio",
+        "agosto",
+        "septiembre",
+        "octubre",
+        "noviembre",
+        "diciembre",
+      ],
+    )
+  }
+
+  pub fn portuguese() -> Self {
+    Self::new(
+      &["Sem título"],
+      &["sim", "verdadeiro"],
+      &["não", "nao", "falso"],
+      &[
+        "janeiro",
+        "fevereiro",
+        "março",
+        "abril",
+        "maio",
+        "junho",
+        "julho",
+        "agosto",
+        "setembro",
+        "outubro",
+        "novembro",
+        "dezembro",
+      ],
+    )
+  }
+
+  pub fn italian() -> Self {
+    Self::new(
+      &["Senza titolo"],
+      &["sì", "vero"],
+      &["no", "falso"],
+      &[
+        "gennaio",
+        "febbraio",
+        "marzo",
+        "aprile",
+        "maggio",
+        "giugno",
+        "luglio",
+        "agosto",
+        "settembre",
+        "ottobre",
+        "novembre",
+        "dicembre",
+      ],
+    )
+  }
+
+  pub fn chinese() -> Self {
+    Self::new(&["无标题", "未命名"], &["是"], &["否"], &[])
+  }
+
+  pub fn japanese() -> Self {
+    Self::new(&["無題", "名称未設定"], &["はい"], &["いいえ"], &[])
+  }
+
+  pub fn korean() -> Self {
+    Self::new(&["제목 없음"], &["예"], &["아니요"], &[])
+  }
+
+  /// A table that contains the strings of all the built-in locales.
+  pub fn all() -> Self {
+    [
+      Self::french(),
+      Self::german(),
+      Self::spanish(),
+      Self::portuguese(),
+      Self::italian(),
+      Self::chinese(),
+      Self::japanese(),
+      Self::korean(),
+    ]
+    .into_iter()
+    .fold(Self::english(), |mut acc, locale| {
+      acc.extend(locale);
+      acc
+    })
+  }
+
+  fn new(untitled: &[&str], checked: &[&str], unchecked: &[&str], months: &[&str]) -> Self {
+    Self {
+      untitled_names: untitled.iter().map(|s| s.to_string()).collect(),
+      checked_values: checked.iter().map(|s| s.to_string()).collect(),
+      unchecked_values: unchecked.iter().map(|s| s.to_string()).collect(),
+      month_names: months
+        .iter()
+        .enumerate()
+        .map(|(index, name)| (name.to_string(), index as u32 + 1))
+        .collect(),
+      date_options: DateParseOptions::default(),
+      decimal_separator: DecimalSeparator::default(),
+    }
+  }
+
+  pub fn with_date_options(mut self, date_options: DateParseOptions) -> Self {
+    self.date_options = date_options;
+    self
+  }
+
+  pub fn with_decimal_separator(mut self, decimal_separator: DecimalSeparator) -> Self {
+    self.decimal_separator = decimal_separator;
+    self
+  }
+
+  /// Add the strings of another locale to this one. The date options and the decimal separator
+  /// of this locale are kept.
+  pub fn extend(&mut self, other: ImportLocale) {
+    fn merge(target: &mut Vec<String>, values: Vec<String>) {
+      for value in values {
+        if !target.contains(&value) {
+          target.push(value);
+        }
+      }
+    }
+    merge(&mut self.untitled_names, other.untitled_names);
+    merge(&mut self.checked_values, other.checked_values);
+    merge(&mut self.unchecked_values, other.unchecked_values);
+    for month in other.month_names {
+      if !self.month_names.contains(&month) {
+        self.month_names.push(month);
+      }
+    }
+  }
+
+  /// Return true if the name is the name given to untitled pages in one of the locales.
+  pub fn is_untitled(&self, name: &str) -> bool {
+    let name = name.trim().to_lowercase();
+    self
+      .untitled_names
+      .iter()
+      .any(|untitled| untitled.to_lowercase() == name)
+  }
+
+  /// Parse a localized checkbox value. Return None if the value is not a checkbox value.
+  pub fn parse_checkbox(&self, cell: &str) -> Option<bool> {
+    let cell = cell.trim().to_lowercase();
+    if self.checked_values.iter().any(|value| *value == cell) {
+      Some(true)
+    } else if self.unchecked_values.iter().any(|value| *value == cell) {
+      Some(false)
+    } else {
+      None
+    }
+  }
+
+  /// Rewrite a localized checkbox value to a value understood by the checkbox type option.
+  /// Return None if the cell doesn't need to be rewritten.
+  pub fn normalize_checkbox_cell(&self, cell: &str) -> Option<String> {
+    let lower = cell.trim().to_lowercase();
+    if DEFAULT_CHECKED_VALUES.contains(&lower.as_str())
+      || DEFAULT_UNCHECKED_VALUES.contains(&lower.as_str())
+    {
+      return None;
+    }
+    self
+      .parse_checkbox(cell)
+      .map(|checked| if checked { CHECKED } else { UNCHECKED }.to_string())
+  }
+
+  /// Parse a date cell. The formats supported by [parse_date_cell_with_options] are tried first,
+  /// then the localized formats of this table.
+  pub fn parse_date(&self, cell: &str) -> Option<ParsedDateCell> {
+    self.parse_date_outcome(cell).map(|outcome| outcome.parsed)
+  }
+
+  /// Like [ImportLocale::parse_date], with the reasons the parsed date may be wrong.
+  pub fn parse_date_outcome(&self, cell: &str) -> Option<ParsedDateOutcome> {
+    if let Some(outcome) = parse_date_cell_with_options(cell, &self.date_options) {
+      return Some(outcome);
+    }
+    self
+      .parse_localized_date(cell)
+      .map(|parsed| ParsedDateOutcome {
+        parsed,
+        ambiguities: vec![],
+      })
+  }
+
+  fn parse_localized_date(&self, cell: &str) -> Option<ParsedDateCell> {
+    let cell = cell.trim();
+    if let Some((start, end)) = cell.split_once('→') {
+      let (start_ts, start_include_time) = self.parse_localized_datetime(start)?;
+      let (end_ts, end_include_time) = self.parse_localized_datetime(end)?;
+      return Some(ParsedDateCell {
+        timestamp: start_ts,
+        end_timestamp: Some(end_ts),
+        include_time: start_include_time || end_include_time,
+        is_range: true,
+      });
+    }
+
+    let (timestamp, include_time) = self.parse_localized_datetime(cell)?;
+    Some(ParsedDateCell {
+      timestamp,
+      end_timestamp: None,
+      include_time,
+      is_range: false,
+    })
+  }
+
+  /// Rewrite a localized date, or a date that isn't in UTC, to a UTC date understood by
+  /// [parse_date_cell]. Return None if the cell doesn't need to be rewritten or can't be parsed.
+  pub fn normalize_date_cell(&self, cell: &str) -> Option<String> {
+    let parsed = self.parse_date(cell)?;
+    if parse_date_cell(cell).as_ref() == Some(&parsed) {
+      return None;
+    }
+    let format = |timestamp: i64| {
+      let datetime = Utc.timestamp_opt(timestamp, 0).single()?;
+      Some(if parsed.include_time {
+        datetime.format("%Y-%m-%d %H:%M").to_string()
+      } else {
+        datetime.format("%Y-%m-%d").to_string()
+      })
+    };
+    let start = format(parsed.timestamp)?;
+    match parsed.end_timestamp {
+      Some(end) => Some(format!("{} → {}", start, format(end)?)),
+      None => Some(start),
+    }
+  }
+
+  fn parse_localized_datetime(&self, cell: &str) -> Option<(i64, bool)> {
+    let value = self.replace_month_names(cell);
+    for format in LOCALIZED_DATETIME_FORMATS {
+      if let Ok(datetime) = NaiveDateTime::parse_from_str(&value, format) {
+        let datetime = self
+          .date_options
+          .timezone
+          .from_local_datetime(&datetime)
+          .earliest()?;
+        return Some((datetime.timestamp(), true));
+      }
+    }
+    for format in LOCALIZED_DATE_FORMATS {
+      if let Ok(date) = NaiveDate::parse_from_str(&value, format) {
+        let datetime = date.and_hms_opt(0, 0, 0)?;
+        return Some((Utc.from_utc_datetime(&datetime).timestamp(), false));
+      }
+    }
+    None
+  }
+
+  /// Turn "22. August 2024" or "22 de agosto de 2024" into "22 8 2024".
+  fn replace_month_names(&self, cell: &str) -> String {
+    let mut value = cell.trim().to_lowercase();
+    let mut month_names = self.month_names.iter().collect::<Vec<_>>();
+    // Replace the longest names first, so "juillet" is not matched by "juil".
+    month_names.sort_by_key(|(name, _)| std::cmp::Reverse(name.chars().count()));
+    for (name, month) in month_names {
+      if value.contains(name.as_str()) {
+        value = value.replacen(name.as_str(), &format!(" {} ", month), 1);
+        break;
+      }
+    }
+
+    value
+      .replace(['.', ','], " ")
+      .split_whitespace()
+      .filter(|part| *part != "de")
+      .collect::<Vec<_>>()
+      .join(" ")
+  }
+}
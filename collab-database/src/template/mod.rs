@@ -2,12 +2,16 @@ pub mod builder;
 pub mod check_list_parse;
 pub mod checkbox_parse;
 pub mod csv;
+pub mod csv_stream;
+pub mod currency_parse;
 pub mod date_parse;
 pub mod entity;
+pub mod locale;
 pub mod media_parse;
 pub mod number_parse;
 pub mod option_parse;
 pub mod relation_parse;
+pub mod sample_data;
 pub mod summary_parse;
 pub mod time_parse;
 pub mod timestamp_parse;
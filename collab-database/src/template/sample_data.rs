@@ -0,0 +1,437 @@
+use crate::database::{gen_database_id, gen_database_view_id};
+use crate::entity::FieldType;
+use crate::fields::media_type_option::{MediaCellData, MediaFile, MediaFileType, MediaUploadType};
+use crate::fields::relation_type_option::RelationTypeOption;
+use crate::rows::Cell;
+use crate::template::builder::DatabaseTemplateBuilder;
+use crate::template::check_list_parse::ChecklistCellData;
+use crate::template::entity::DatabaseTemplate;
+use crate::template::relation_parse::RelationCellData;
+
+use chrono::{Duration, NaiveDate};
+
+/// The kind of sample database created by the [SampleDatabaseGenerator].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleDatabaseKind {
+  /// A project board: tasks with a status, an assignee, a due date and sub tasks.
+  Tasks,
+  /// A sales pipeline: companies with a deal stage, a deal size and contracts.
+  Crm,
+  /// An editorial calendar: posts with a channel, a publish date and review steps.
+  ContentCalendar,
+}
+
+/// Generates realistic databases without any network or file access.
+///
+/// Every generated database contains a field of each [FieldType] except [FieldType::Person],
+/// since the sample databases have no workspace members, and [FieldType::Button], whose cells
+/// store nothing. Every cell is populated. The content is derived from the seed, so the same
+/// generator always produces the same cell values, which keeps benchmarks and tests comparable
+/// between runs. Ids are generated on each call.
+#[derive(Debug, Clone)]
+pub struct SampleDatabaseGenerator {
+  kind: SampleDatabaseKind,
+  num_rows: usize,
+  seed: u64,
+}
+
+impl SampleDatabaseGenerator {
+  pub fn new(kind: SampleDatabaseKind) -> Self {
+    Self {
+      kind,
+      num_rows: 20,
+      seed: 0,
+    }
+  }
+
+  pub fn with_num_rows(mut self, num_rows: usize) -> Self {
+    self.num_rows = num_rows;
+    self
+  }
+
+  pub fn with_seed(mut self, seed: u64) -> Self {
+    self.seed = seed;
+    self
+  }
+
+  pub fn kind(&self) -> SampleDatabaseKind {
+    self.kind
+  }
+
+  pub fn num_rows(&self) -> usize {
+    self.num_rows
+  }
+
+  pub async fn generate(&self) -> DatabaseTemplate {
+    self
+      .generate_with_ids(gen_database_id(), gen_database_view_id())
+      .await
+  }
+
+  pub async fn generate_with_ids(&self, database_id: String, view_id: String) -> DatabaseTemplate {
+    let schema = SampleSchema::from(self.kind);
+    let mut rng = SampleRng::new(self.seed);
+    let mut builder = DatabaseTemplateBuilder::new(database_id.clone(), view_id, None);
+
+    let mut media_field_id = None;
+    let mut relation_field_id = None;
+    for (index, (name, field_type)) in schema.fields().into_iter().enumerate() {
+      let cells = (0..self.num_rows)
+        .map(|row_index| schema.cell(field_type, row_index, &mut rng))
+        .collect::<Vec<_>>();
+      builder = builder
+        .create_field(
+          &None,
+          &database_id,
+          name,
+          field_type,
+          index == 0,
+          |mut field| {
+            for cell in cells {
+              field = field.create_cell(cell);
+            }
+            field
+          },
+        )
+        .await;
+    }
+
+    let mut template = builder.build();
+    for field in template.fields.iter_mut() {
+      match field.field_type {
+        FieldType::Media => media_field_id = Some(field.field_id.clone()),
+        FieldType::Relation => {
+          // The sample rows relate to other rows of the same database.
+          let type_option = RelationTypeOption::new(&database_id);
+          field
+            .type_options
+            .insert(FieldType::Relation, type_option.into());
+          relation_field_id = Some(field.field_id.clone());
+        },
+        _ => {},
+      }
+    }
+
+    // Media and relation cells can't be expressed as plain strings, they are written after the
+    // rows were created.
+    let row_ids = template
+      .rows
+      .iter()
+      .map(|row| row.row_id.clone())
+      .collect::<Vec<_>>();
+    for (row_index, row) in template.rows.iter_mut().enumerate() {
+      if let Some(field_id) = &media_field_id {
+        let cell: Cell = schema.media_cell(row_index, &mut rng).into();
+        row.cells.insert(field_id.clone(), cell);
+      }
+      if let Some(field_id) = &relation_field_id {
+        // The rows relate to a row before them, the first row to the last one.
+        let related_row = if row_index > 0 {
+          row_ids[rng.below(row_index)].clone()
+        } else {
+          row_ids[row_ids.len() - 1].clone()
+        };
+        let cell: Cell = RelationCellData {
+          row_ids: vec![related_row.into()],
+        }
+        .into();
+        row.cells.insert(field_id.clone(), cell);
+      }
+    }
+    template
+  }
+}
+
+struct SampleSchema {
+  kind: SampleDatabaseKind,
+}
+
+impl From<SampleDatabaseKind> for SampleSchema {
+  fn from(kind: SampleDatabaseKind) -> Self {
+    Self { kind }
+  }
+}
+
+impl SampleSchema {
+  /// The fields of the database. The first field is the primary field.
+  fn fields(&self) -> Vec<(&'static str, FieldType)> {
+    let names = match self.kind {
+      SampleDatabaseKind::Tasks => [
+        "Task",
+        "Estimate (h)",
+        "Due date",
+        "Status",
+        "Tags",
+        "Done",
+        "Spec",
+        "Subtasks",
+        "Last edited",
+        "Created",
+        "Blocked by",
+        "Summary",
+        "Translation",
+        "Time spent",
+        "Attachments",
+      ],
+      SampleDatabaseKind::Crm => [
+        "Company",
+        "Deal size",
+        "Next follow-up",
+        "Stage",
+        "Industry",
+        "Active",
+        "Website",
+        "Onboarding",
+        "Last edited",
+        "Created",
+        "Parent company",
+        "Notes summary",
+        "Translated notes",
+        "Call duration",
+        "Contracts",
+      ],
+      SampleDatabaseKind::ContentCalendar => [
+        "Title",
+        "Word count",
+        "Publish date",
+        "Status",
+        "Channels",
+        "Published",
+        "Link",
+        "Review",
+        "Last edited",
+        "Created",
+        "Related posts",
+        "Summary",
+        "Translation",
+        "Reading time",
+        "Assets",
+      ],
+    };
+    names.into_iter().zip(ALL_FIELD_TYPES).collect()
+  }
+
+  fn cell(&self, field_type: FieldType, row_index: usize, rng: &mut SampleRng) -> String {
+    match field_type {
+      FieldType::RichText => self.title(row_index, rng),
+      FieldType::Number => match self.kind {
+        SampleDatabaseKind::Tasks => (1 + rng.below(40)).to_string(),
+        SampleDatabaseKind::Crm => (1_000 * (5 + rng.below(500))).to_string(),
+        SampleDatabaseKind::ContentCalendar => (300 + rng.below(2_700)).to_string(),
+      },
+      FieldType::DateTime => sample_date(rng.below(365) as i64)
+        .format("%Y-%m-%d")
+        .to_string(),
+      FieldType::SingleSelect => rng.pick(self.statuses()).to_string(),
+      FieldType::MultiSelect => {
+        let tags = self.tags();
+        let first = rng.below(tags.len());
+        let second = rng.below(tags.len());
+        if first == second {
+          tags[first].to_string()
+        } else {
+          format!("{}, {}", tags[first], tags[second])
+        }
+      },
+      FieldType::Checkbox => if rng.below(2) == 0 { "Yes" } else { "No" }.to_string(),
+      FieldType::URL => format!("https://example.com/{}/{}", self.slug(), row_index + 1),
+      FieldType::Checklist => {
+        let options = self
+          .checklist()
+          .iter()
+          .map(|option| option.to_string())
+          .collect::<Vec<_>>();
+        let num_selected = rng.below(options.len() + 1);
+        let selected = options[..num_selected].to_vec();
+        let cell = ChecklistCellData::from((options, selected));
+        serde_json::to_string(&cell).unwrap_or_default()
+      },
+      FieldType::LastEditedTime | FieldType::CreatedTime => {
+        let days = rng.below(90) as i64;
+        let seconds = rng.below(86_400) as i64;
+        let timestamp = sample_date(-days)
+          .and_hms_opt(0, 0, 0)
+          .map(|datetime| datetime.and_utc().timestamp() + seconds)
+          .unwrap_or_default();
+        timestamp.to_string()
+      },
+      // Relation cells are filled in after the rows were created.
+      FieldType::Relation => String::new(),
+      FieldType::Summary => format!(
+        "{} Generated summary for row {}.",
+        rng.pick(SUMMARY_SENTENCES),
+        row_index + 1
+      ),
+      FieldType::Translate => rng.pick(TRANSLATIONS).to_string(),
+      FieldType::Time => (60 * (5 + rng.below(240))).to_string(),
+      // Media cells are filled in after the rows were created.
+      FieldType::Media => String::new(),
+      // The sample databases have no members.
+      FieldType::Person => String::new(),
+      // The buttons don't store anything in the cells.
+      FieldType::Button => String::new(),
+    }
+  }
+
+  fn media_cell(&self, row_index: usize, rng: &mut SampleRng) -> MediaCellData {
+    let (extension, file_type) = rng.pick(&SAMPLE_FILES);
+    let name = format!("{}-{}.{}", self.slug(), row_index + 1, extension);
+    let url = format!("https://example.com/files/{}", name);
+    MediaCellData {
+      files: vec![MediaFile::new(
+        name,
+        url,
+        MediaUploadType::Network,
+        file_type.clone(),
+      )],
+    }
+  }
+
+  fn title(&self, row_index: usize, rng: &mut SampleRng) -> String {
+    match self.kind {
+      SampleDatabaseKind::Tasks => format!(
+        "{} {}",
+        rng.pick(&["Fix", "Design", "Review", "Write", "Ship", "Refactor"]),
+        rng.pick(&[
+          "login flow",
+          "sync engine",
+          "release notes",
+          "onboarding",
+          "search",
+          "billing page"
+        ])
+      ),
+      SampleDatabaseKind::Crm => format!(
+        "{} {} #{}",
+        rng.pick(&["Acme", "Globex", "Initech", "Umbrella", "Hooli", "Stark"]),
+        rng.pick(&["Inc.", "LLC", "GmbH", "Ltd."]),
+        row_index + 1
+      ),
+      SampleDatabaseKind::ContentCalendar => format!(
+        "{} {}",
+        rng.pick(&[
+          "How to",
+          "Why we",
+          "10 tips for",
+          "A guide to",
+          "Lessons from"
+        ]),
+        rng.pick(&[
+          "self-hosting",
+          "remote work",
+          "note taking",
+          "open source",
+          "writing specs"
+        ])
+      ),
+    }
+  }
+
+  fn statuses(&self) -> &'static [&'static str] {
+    match self.kind {
+      SampleDatabaseKind::Tasks => &["To Do", "In Progress", "Blocked", "Done"],
+      SampleDatabaseKind::Crm => &["Lead", "Qualified", "Proposal", "Won", "Lost"],
+      SampleDatabaseKind::ContentCalendar => &["Idea", "Drafting", "In Review", "Scheduled"],
+    }
+  }
+
+  fn tags(&self) -> &'static [&'static str] {
+    match self.kind {
+      SampleDatabaseKind::Tasks => &["Frontend", "Backend", "Design", "Docs", "Bug"],
+      SampleDatabaseKind::Crm => &["SaaS", "Finance", "Healthcare", "Retail", "Education"],
+      SampleDatabaseKind::ContentCalendar => &["Blog", "Newsletter", "Twitter", "LinkedIn"],
+    }
+  }
+
+  fn checklist(&self) -> &'static [&'static str] {
+    match self.kind {
+      SampleDatabaseKind::Tasks => &["Write tests", "Update docs", "Code review"],
+      SampleDatabaseKind::Crm => &["Intro call", "Demo", "Send contract", "Kickoff"],
+      SampleDatabaseKind::ContentCalendar => &["Outline", "Draft", "Edit", "Images"],
+    }
+  }
+
+  fn slug(&self) -> &'static str {
+    match self.kind {
+      SampleDatabaseKind::Tasks => "tasks",
+      SampleDatabaseKind::Crm => "crm",
+      SampleDatabaseKind::ContentCalendar => "posts",
+    }
+  }
+}
+
+/// Field types in the order they are created. The first one is used for the primary field.
+/// [FieldType::Person] and [FieldType::Button] are left out, see [SampleDatabaseGenerator].
+const ALL_FIELD_TYPES: [FieldType; 15] = [
+  FieldType::RichText,
+  FieldType::Number,
+  FieldType::DateTime,
+  FieldType::SingleSelect,
+  FieldType::MultiSelect,
+  FieldType::Checkbox,
+  FieldType::URL,
+  FieldType::Checklist,
+  FieldType::LastEditedTime,
+  FieldType::CreatedTime,
+  FieldType::Relation,
+  FieldType::Summary,
+  FieldType::Translate,
+  FieldType::Time,
+  FieldType::Media,
+];
+
+const SAMPLE_FILES: [(&str, MediaFileType); 4] = [
+  ("png", MediaFileType::Image),
+  ("pdf", MediaFileType::Document),
+  ("zip", MediaFileType::Archive),
+  ("mp4", MediaFileType::Video),
+];
+
+const SUMMARY_SENTENCES: &[&str] = &[
+  "Progress is on track.",
+  "Waiting for feedback from the team.",
+  "Needs a follow-up next week.",
+  "Scope was reduced after the last review.",
+];
+
+const TRANSLATIONS: &[&str] = &[
+  "Bonjour le monde",
+  "Hallo Welt",
+  "Hola mundo",
+  "Olá mundo",
+  "你好，世界",
+];
+
+fn sample_date(offset_days: i64) -> NaiveDate {
+  let base = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+  base + Duration::days(offset_days)
+}
+
+/// A small splitmix64 generator. It's not meant to be cryptographically secure, it only needs
+/// to be deterministic and to avoid pulling a random number crate into the library.
+struct SampleRng {
+  state: u64,
+}
+
+impl SampleRng {
+  fn new(seed: u64) -> Self {
+    Self { state: seed }
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = self.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+  }
+
+  /// Return a number in `0..upper`. `upper` must be greater than 0.
+  fn below(&mut self, upper: usize) -> usize {
+    (self.next_u64() % upper as u64) as usize
+  }
+
+  fn pick<'a, T>(&mut self, values: &'a [T]) -> &'a T {
+    &values[self.below(values.len())]
+  }
+}
@@ -3,7 +3,7 @@ use crate::entity::{CreateDatabaseParams, CreateViewParams};
 use crate::error::DatabaseError;
 use crate::fields::Field;
 use crate::rows::{CreateRowParams, RowId};
-use crate::template::entity::DatabaseTemplate;
+use crate::template::entity::{DatabaseTemplate, RowTemplate};
 
 /// This trait that provides methods to extend the [TypeOption::CellData] functionalities.
 pub trait TypeOptionCellData {
@@ -50,19 +50,11 @@ pub(crate) fn create_database_params_from_template(
     fields.push(field);
   }
 
-  let mut rows = vec![];
-  for row_template in template.rows {
-    rows.push(CreateRowParams {
-      id: RowId::from(row_template.row_id),
-      database_id: database_id.clone(),
-      cells: row_template.cells,
-      height: row_template.height,
-      visibility: row_template.visibility,
-      row_position: Default::default(),
-      created_at: timestamp,
-      modified_at: timestamp,
-    });
-  }
+  let rows = template
+    .rows
+    .into_iter()
+    .map(|row_template| create_row_params_from_template(&database_id, row_template, timestamp))
+    .collect();
 
   let mut views = vec![];
   for view_template in template.views {
@@ -90,3 +82,20 @@ pub(crate) fn create_database_params_from_template(
     views,
   }
 }
+
+pub(crate) fn create_row_params_from_template(
+  database_id: &str,
+  row_template: RowTemplate,
+  timestamp: i64,
+) -> CreateRowParams {
+  CreateRowParams {
+    id: RowId::from(row_template.row_id),
+    database_id: database_id.to_string(),
+    cells: row_template.cells,
+    height: row_template.height,
+    visibility: row_template.visibility,
+    row_position: Default::default(),
+    created_at: timestamp,
+    modified_at: timestamp,
+  }
+}
@@ -1,6 +1,189 @@
+use chrono::{Days, Months, NaiveDate};
 use collab::preclude::Any;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::fields::date_type_option::{DateCellData, DateTypeOption};
+use crate::fields::person_type_option::PersonCellData;
+use crate::rows::Row;
+
 pub type FilterArray = Vec<Any>;
 pub type FilterMap = HashMap<String, Any>;
 pub type FilterMapBuilder = HashMap<String, Any>;
+
+/// A condition on the cells of a date field. The timestamps are in seconds, the conditions
+/// compare the days of the cells in the timezone of the field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DateFilterCondition {
+  IsEmpty,
+  IsNotEmpty,
+  IsToday,
+  IsTomorrow,
+  IsYesterday,
+  /// From 7 days ago to today.
+  IsWithinPastWeek,
+  /// From today to 7 days from today.
+  IsWithinNextWeek,
+  /// From the same day of the previous month to today.
+  IsWithinPastMonth,
+  /// From today to the same day of the next month.
+  IsWithinNextMonth,
+  /// The day of the timestamp.
+  IsOn(i64),
+  /// The days before the day of the timestamp.
+  IsBefore(i64),
+  /// The days after the day of the timestamp.
+  IsAfter(i64),
+  /// From the day of the start to the day of the end, both included.
+  IsBetween {
+    start: i64,
+    end: i64,
+  },
+}
+
+impl DateFilterCondition {
+  /// The first and last days matching the condition, None if the condition doesn't depend on
+  /// the days or one of its timestamps is invalid.
+  fn days(&self, type_option: &DateTypeOption, now: i64) -> Option<(NaiveDate, NaiveDate)> {
+    let today = type_option.local_date(now)?;
+    match *self {
+      DateFilterCondition::IsEmpty | DateFilterCondition::IsNotEmpty => None,
+      DateFilterCondition::IsToday => Some((today, today)),
+      DateFilterCondition::IsTomorrow => today.succ_opt().map(|day| (day, day)),
+      DateFilterCondition::IsYesterday => today.pred_opt().map(|day| (day, day)),
+      DateFilterCondition::IsWithinPastWeek => Some((today.checked_sub_days(Days::new(7))?, today)),
+      DateFilterCondition::IsWithinNextWeek => Some((today, today.checked_add_days(Days::new(7))?)),
+      DateFilterCondition::IsWithinPastMonth => {
+        Some((today.checked_sub_months(Months::new(1))?, today))
+      },
+      DateFilterCondition::IsWithinNextMonth => {
+        Some((today, today.checked_add_months(Months::new(1))?))
+      },
+      DateFilterCondition::IsOn(timestamp) => {
+        let day = type_option.local_date(timestamp)?;
+        Some((day, day))
+      },
+      DateFilterCondition::IsBefore(timestamp) => Some((
+        NaiveDate::MIN,
+        type_option.local_date(timestamp)?.pred_opt()?,
+      )),
+      DateFilterCondition::IsAfter(timestamp) => Some((
+        type_option.local_date(timestamp)?.succ_opt()?,
+        NaiveDate::MAX,
+      )),
+      DateFilterCondition::IsBetween { start, end } => {
+        let start = type_option.local_date(start)?;
+        let end = type_option.local_date(end)?;
+        Some((start.min(end), start.max(end)))
+      },
+    }
+  }
+}
+
+/// Filter the rows by the cells of a date field, see [crate::views::RowQuery::with_filter]:
+///
+/// ```ignore
+/// let filter = DateFilter::new(field_id, DateFilterCondition::IsWithinNextWeek, type_option);
+/// let query = RowQuery::new().with_filter(filter.into_row_filter(now));
+/// ```
+#[derive(Clone, Debug)]
+pub struct DateFilter {
+  pub field_id: String,
+  pub condition: DateFilterCondition,
+  /// The type option of the field, its timezone decides when the days start.
+  pub type_option: DateTypeOption,
+}
+
+impl DateFilter {
+  pub fn new(
+    field_id: impl Into<String>,
+    condition: DateFilterCondition,
+    type_option: DateTypeOption,
+  ) -> Self {
+    Self {
+      field_id: field_id.into(),
+      condition,
+      type_option,
+    }
+  }
+
+  /// Return true if the cell of the row matches the condition at `now`, in seconds. A range
+  /// matches if one of its days does.
+  pub fn matches(&self, row: &Row, now: i64) -> bool {
+    let cell_data = row
+      .cells
+      .get(&self.field_id)
+      .map(DateCellData::from)
+      .unwrap_or_default();
+    let days = self.type_option.local_date_range(&cell_data);
+    match self.condition {
+      DateFilterCondition::IsEmpty => days.is_none(),
+      DateFilterCondition::IsNotEmpty => days.is_some(),
+      condition => match (days, condition.days(&self.type_option, now)) {
+        (Some((start, end)), Some((first, last))) => start <= last && end >= first,
+        _ => false,
+      },
+    }
+  }
+
+  /// The relative conditions are evaluated at `now` for as long as the filter is used, so a
+  /// long-lived query should be recreated when the day changes.
+  pub fn into_row_filter(self, now: i64) -> impl Fn(&Row) -> bool + Send + Sync + 'static {
+    move |row| self.matches(row, now)
+  }
+}
+
+/// A condition on the cells of a person field.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PersonFilterCondition {
+  IsEmpty,
+  IsNotEmpty,
+  /// The cell includes the current user, e.g. the tasks assigned to me.
+  IncludesMe,
+  /// The cell includes one of the members.
+  Includes(Vec<i64>),
+  /// The cell includes none of the members.
+  DoesNotInclude(Vec<i64>),
+}
+
+/// Filter the rows by the cells of a person field, see [crate::views::RowQuery::with_filter].
+#[derive(Clone, Debug)]
+pub struct PersonFilter {
+  pub field_id: String,
+  pub condition: PersonFilterCondition,
+}
+
+impl PersonFilter {
+  pub fn new(field_id: impl Into<String>, condition: PersonFilterCondition) -> Self {
+    Self {
+      field_id: field_id.into(),
+      condition,
+    }
+  }
+
+  /// Return true if the cell of the row matches the condition, `uid` being the current user.
+  pub fn matches(&self, row: &Row, uid: i64) -> bool {
+    let cell_data = row
+      .cells
+      .get(&self.field_id)
+      .map(PersonCellData::from)
+      .unwrap_or_default();
+    match &self.condition {
+      PersonFilterCondition::IsEmpty => cell_data.uids.is_empty(),
+      PersonFilterCondition::IsNotEmpty => !cell_data.uids.is_empty(),
+      PersonFilterCondition::IncludesMe => cell_data.contains(uid),
+      PersonFilterCondition::Includes(members) => {
+        members.iter().any(|member| cell_data.contains(*member))
+      },
+      PersonFilterCondition::DoesNotInclude(members) => {
+        !members.iter().any(|member| cell_data.contains(*member))
+      },
+    }
+  }
+
+  pub fn into_row_filter(self, uid: i64) -> impl Fn(&Row) -> bool + Send + Sync + 'static {
+    move |row| self.matches(row, uid)
+  }
+}
@@ -1,6 +1,11 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use yrs::{Any, encoding::serde::from_any};
+use tracing::error;
+use yrs::Any;
+use yrs::encoding::serde::{from_any, to_any};
 
 use super::{LayoutSetting, LayoutSettingBuilder};
 
@@ -118,3 +123,154 @@ impl From<BoardLayoutSetting> for LayoutSetting {
     ])
   }
 }
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TimelineLayoutSetting {
+  /// The date field where the bars start.
+  #[serde(default)]
+  pub start_field_id: String,
+  /// The date field where the bars end. If empty, or if it's the start field, the bars end at
+  /// the end of the date range of the start field.
+  #[serde(default)]
+  pub end_field_id: String,
+  #[serde(default)]
+  pub scale: TimelineScale,
+  #[serde(default)]
+  pub dependencies: Vec<TimelineDependency>,
+}
+
+impl TimelineLayoutSetting {
+  pub fn new(start_field_id: String, end_field_id: String) -> Self {
+    Self {
+      start_field_id,
+      end_field_id,
+      scale: TimelineScale::default(),
+      dependencies: vec![],
+    }
+  }
+
+  /// Add a finish-to-start dependency: the row `to_row_id` can't start before the row
+  /// `from_row_id` finishes. Return false if the dependency already exists, links a row to
+  /// itself or would create a cycle.
+  pub fn add_dependency(&mut self, from_row_id: &str, to_row_id: &str) -> bool {
+    if from_row_id == to_row_id
+      || self.has_dependency(from_row_id, to_row_id)
+      || self.depends_on(from_row_id, to_row_id)
+    {
+      return false;
+    }
+    self.dependencies.push(TimelineDependency {
+      from_row_id: from_row_id.to_string(),
+      to_row_id: to_row_id.to_string(),
+    });
+    true
+  }
+
+  pub fn remove_dependency(&mut self, from_row_id: &str, to_row_id: &str) {
+    self
+      .dependencies
+      .retain(|dependency| !dependency.links(from_row_id, to_row_id));
+  }
+
+  /// Remove the dependencies of the row and the dependencies on it, e.g. when the row is deleted.
+  pub fn remove_row(&mut self, row_id: &str) {
+    self
+      .dependencies
+      .retain(|dependency| dependency.from_row_id != row_id && dependency.to_row_id != row_id);
+  }
+
+  pub fn has_dependency(&self, from_row_id: &str, to_row_id: &str) -> bool {
+    self
+      .dependencies
+      .iter()
+      .any(|dependency| dependency.links(from_row_id, to_row_id))
+  }
+
+  /// Return true if the row `row_id` depends on the row `other_row_id`, directly or through
+  /// other rows.
+  pub fn depends_on(&self, row_id: &str, other_row_id: &str) -> bool {
+    let mut visited = HashSet::new();
+    let mut stack = vec![row_id];
+    while let Some(current) = stack.pop() {
+      if !visited.insert(current) {
+        continue;
+      }
+      for dependency in self.dependencies.iter() {
+        if dependency.to_row_id == current {
+          if dependency.from_row_id == other_row_id {
+            return true;
+          }
+          stack.push(&dependency.from_row_id);
+        }
+      }
+    }
+    false
+  }
+}
+
+impl From<LayoutSetting> for TimelineLayoutSetting {
+  /// The setting written by a peer may have an unknown scale or badly shaped dependencies, the
+  /// default setting is used instead.
+  fn from(setting: LayoutSetting) -> Self {
+    from_any(&Any::from(setting)).unwrap_or_else(|err| {
+      error!("Failed to parse the timeline layout setting: {}", err);
+      Self::default()
+    })
+  }
+}
+
+impl From<TimelineLayoutSetting> for LayoutSetting {
+  fn from(setting: TimelineLayoutSetting) -> Self {
+    let dependencies = to_any(&setting.dependencies).unwrap_or_else(|_| Any::Array(Arc::from([])));
+    LayoutSettingBuilder::from([
+      ("start_field_id".into(), setting.start_field_id.into()),
+      ("end_field_id".into(), setting.end_field_id.into()),
+      ("scale".into(), Any::BigInt(setting.scale.value())),
+      ("dependencies".into(), dependencies),
+    ])
+  }
+}
+
+/// A finish-to-start dependency between two rows of a timeline.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct TimelineDependency {
+  /// The row that must finish first.
+  pub from_row_id: String,
+  /// The row that can't start before the other row finishes.
+  pub to_row_id: String,
+}
+
+impl TimelineDependency {
+  fn links(&self, from_row_id: &str, to_row_id: &str) -> bool {
+    self.from_row_id == from_row_id && self.to_row_id == to_row_id
+  }
+}
+
+/// The time unit of a column of the timeline.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum TimelineScale {
+  Day = 0,
+  #[default]
+  Week = 1,
+  Month = 2,
+  Quarter = 3,
+}
+
+impl From<i64> for TimelineScale {
+  fn from(value: i64) -> Self {
+    match value {
+      0 => TimelineScale::Day,
+      1 => TimelineScale::Week,
+      2 => TimelineScale::Month,
+      3 => TimelineScale::Quarter,
+      _ => TimelineScale::Week,
+    }
+  }
+}
+
+impl TimelineScale {
+  pub fn value(&self) -> i64 {
+    *self as i64
+  }
+}
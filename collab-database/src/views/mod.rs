@@ -6,9 +6,12 @@ mod filter;
 mod group;
 mod layout;
 mod layout_settings;
+mod query;
 mod row_order;
 mod sort;
+mod timeline;
 mod view;
+mod view_definition;
 mod view_map;
 mod view_observer;
 
@@ -19,8 +22,11 @@ pub use filter::*;
 pub use group::*;
 pub use layout::*;
 pub use layout_settings::*;
+pub use query::*;
 pub use row_order::*;
 pub use sort::*;
+pub use timeline::*;
 pub use view::*;
+pub use view_definition::*;
 pub use view_map::*;
 pub use view_observer::*;
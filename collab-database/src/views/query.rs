@@ -0,0 +1,483 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use futures::future::{Either, select};
+use futures::{Stream, stream};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::error;
+
+use crate::blocks::Block;
+use crate::database::Database;
+use crate::entity::DatabaseView;
+use crate::rows::{Row, RowChange, RowChangeReceiver, RowId};
+use crate::views::{DatabaseViewChange, ViewChangeReceiver};
+
+pub type RowFilter = Arc<dyn Fn(&Row) -> bool + Send + Sync>;
+pub type RowComparator = Arc<dyn Fn(&Row, &Row) -> Ordering + Send + Sync>;
+
+/// The rows of a view matching a filter, ordered by a comparator, see [DatabaseView::subscribe].
+///
+/// The rows that compare equal, or all the rows without a comparator, keep the order of the view.
+/// The archived rows don't match unless the query includes them, see [RowQuery::with_archived].
+#[derive(Clone, Default)]
+pub struct RowQuery {
+  filter: Option<RowFilter>,
+  sort: Option<RowComparator>,
+  include_archived: bool,
+}
+
+impl RowQuery {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_filter<F>(mut self, filter: F) -> Self
+  where
+    F: Fn(&Row) -> bool + Send + Sync + 'static,
+  {
+    self.filter = Some(Arc::new(filter));
+    self
+  }
+
+  pub fn with_sort<F>(mut self, sort: F) -> Self
+  where
+    F: Fn(&Row, &Row) -> Ordering + Send + Sync + 'static,
+  {
+    self.sort = Some(Arc::new(sort));
+    self
+  }
+
+  pub fn with_archived(mut self, include_archived: bool) -> Self {
+    self.include_archived = include_archived;
+    self
+  }
+
+  pub fn matches(&self, row: &Row) -> bool {
+    (self.include_archived || !row.archived)
+      && self.filter.as_ref().is_none_or(|filter| filter(row))
+  }
+}
+
+/// A change of the result of a [RowQuery]. The changes of a batch are applied in order, each
+/// index is the position in the result after the previous changes of the batch.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryChange {
+  /// The row entered the result.
+  Inserted { row: Row, index: usize },
+  /// The row left the result, it was removed from the view or doesn't match the filter anymore.
+  Removed { row_id: RowId, index: usize },
+  /// The row moved in the result, its content is sent by the [QueryChange::Updated] following it
+  /// when it changed.
+  Moved {
+    row_id: RowId,
+    from: usize,
+    to: usize,
+  },
+  /// The row of the result changed, it stays at the same index.
+  Updated { row: Row, index: usize },
+}
+
+/// The result of a [RowQuery], kept up to date with the changes of the rows of the view.
+///
+/// A change of a row only evaluates the query for that row and moves it in the result, the other
+/// rows are not compared again.
+pub struct QueryResultSet {
+  query: RowQuery,
+  /// The ids of the rows of the view, in the order of the view.
+  view_row_ids: Vec<RowId>,
+  /// The index of the rows in the view, the order of the rows that compare equal.
+  positions: HashMap<RowId, usize>,
+  rows: HashMap<RowId, Row>,
+  /// The ids of the rows matching the query, ordered.
+  result: Vec<RowId>,
+}
+
+impl QueryResultSet {
+  /// Evaluate the query on the rows of the view, given in the order of the view.
+  pub fn new(query: RowQuery, rows: Vec<Row>) -> Self {
+    let mut result_set = Self {
+      query,
+      view_row_ids: rows.iter().map(|row| row.id.clone()).collect(),
+      positions: HashMap::new(),
+      rows: rows.into_iter().map(|row| (row.id.clone(), row)).collect(),
+      result: vec![],
+    };
+    result_set.update_positions();
+    result_set.result = result_set.evaluate();
+    result_set
+  }
+
+  pub fn row_ids(&self) -> &[RowId] {
+    &self.result
+  }
+
+  pub fn rows(&self) -> Vec<Row> {
+    self
+      .result
+      .iter()
+      .filter_map(|row_id| self.rows.get(row_id).cloned())
+      .collect()
+  }
+
+  pub fn len(&self) -> usize {
+    self.result.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.result.is_empty()
+  }
+
+  pub fn index_of(&self, row_id: &RowId) -> Option<usize> {
+    self.result.iter().position(|id| id == row_id)
+  }
+
+  /// Apply a change of a row of the view. The changes of the rows that are not in the view are
+  /// ignored.
+  pub fn apply_row_change(&mut self, change: &RowChange) -> Vec<QueryChange> {
+    let Some(mut row) = self.rows.get(change.row_id()).cloned() else {
+      return vec![];
+    };
+    match change {
+      RowChange::DidUpdateVisibility { value, .. } => row.visibility = *value,
+      RowChange::DidUpdateHeight { value, .. } => row.height = *value,
+      RowChange::DidUpdateArchived { value, .. } => row.archived = *value,
+      RowChange::DidUpdateCell {
+        field_id, value, ..
+      } => {
+        row.cells.insert(field_id.clone(), value.clone());
+      },
+      // The comments are not part of the row.
+      RowChange::DidUpdateRowComment { .. } => return vec![],
+    }
+    self.update_row(row)
+  }
+
+  /// Replace a row of the view, e.g. after it was reloaded. The rows that are not in the view are
+  /// ignored.
+  pub fn update_row(&mut self, row: Row) -> Vec<QueryChange> {
+    if !self.positions.contains_key(&row.id) {
+      return vec![];
+    }
+    let row_id = row.id.clone();
+    let old_index = self.index_of(&row_id);
+    let matches = self.query.matches(&row);
+    self.rows.insert(row_id.clone(), row.clone());
+
+    match (old_index, matches) {
+      (None, false) => vec![],
+      (Some(index), false) => {
+        self.result.remove(index);
+        vec![QueryChange::Removed { row_id, index }]
+      },
+      (None, true) => {
+        let index = self.insert_index(&row_id);
+        self.result.insert(index, row_id);
+        vec![QueryChange::Inserted { row, index }]
+      },
+      (Some(from), true) => {
+        self.result.remove(from);
+        let to = self.insert_index(&row_id);
+        self.result.insert(to, row_id.clone());
+        let mut changes = vec![];
+        if from != to {
+          changes.push(QueryChange::Moved { row_id, from, to });
+        }
+        changes.push(QueryChange::Updated { row, index: to });
+        changes
+      },
+    }
+  }
+
+  /// Replace the rows of the view after rows were inserted, removed or moved in the view.
+  /// `row_ids` are the ids of the rows of the view in their new order and `new_rows` the rows
+  /// that were not in the view.
+  pub fn update_view_rows(&mut self, row_ids: Vec<RowId>, new_rows: Vec<Row>) -> Vec<QueryChange> {
+    let view_row_ids = row_ids.iter().collect::<HashSet<_>>();
+    self.rows.retain(|row_id, _| view_row_ids.contains(row_id));
+    for row in new_rows {
+      if view_row_ids.contains(&row.id) {
+        self.rows.insert(row.id.clone(), row);
+      }
+    }
+    self.view_row_ids = row_ids
+      .into_iter()
+      .filter(|row_id| self.rows.contains_key(row_id))
+      .collect();
+    self.update_positions();
+
+    let result = self.evaluate();
+    diff_results(&mut self.result, &result, &self.rows)
+  }
+
+  fn update_positions(&mut self) {
+    self.positions = self
+      .view_row_ids
+      .iter()
+      .enumerate()
+      .map(|(index, row_id)| (row_id.clone(), index))
+      .collect();
+  }
+
+  fn evaluate(&self) -> Vec<RowId> {
+    let mut result = self
+      .view_row_ids
+      .iter()
+      .filter(|row_id| {
+        self
+          .rows
+          .get(*row_id)
+          .is_some_and(|row| self.query.matches(row))
+      })
+      .cloned()
+      .collect::<Vec<_>>();
+    result.sort_by(|a, b| self.compare(a, b));
+    result
+  }
+
+  fn insert_index(&self, row_id: &RowId) -> usize {
+    self
+      .result
+      .partition_point(|id| self.compare(id, row_id) == Ordering::Less)
+  }
+
+  fn compare(&self, a: &RowId, b: &RowId) -> Ordering {
+    let ordering = match (&self.query.sort, self.rows.get(a), self.rows.get(b)) {
+      (Some(sort), Some(a), Some(b)) => sort(a, b),
+      _ => Ordering::Equal,
+    };
+    ordering.then_with(|| self.positions.get(a).cmp(&self.positions.get(b)))
+  }
+}
+
+/// Turn the old result into the new one, returning the changes applied.
+fn diff_results(
+  old: &mut Vec<RowId>,
+  new: &[RowId],
+  rows: &HashMap<RowId, Row>,
+) -> Vec<QueryChange> {
+  let mut changes = vec![];
+  let kept = new.iter().collect::<HashSet<_>>();
+  for index in (0..old.len()).rev() {
+    if !kept.contains(&old[index]) {
+      let row_id = old.remove(index);
+      changes.push(QueryChange::Removed { row_id, index });
+    }
+  }
+
+  for (index, row_id) in new.iter().enumerate() {
+    if old.get(index) == Some(row_id) {
+      continue;
+    }
+    match old.iter().position(|id| id == row_id) {
+      Some(from) => {
+        old.remove(from);
+        old.insert(index, row_id.clone());
+        changes.push(QueryChange::Moved {
+          row_id: row_id.clone(),
+          from,
+          to: index,
+        });
+      },
+      None => {
+        old.insert(index, row_id.clone());
+        if let Some(row) = rows.get(row_id) {
+          changes.push(QueryChange::Inserted {
+            row: row.clone(),
+            index,
+          });
+        }
+      },
+    }
+  }
+  changes
+}
+
+impl DatabaseView {
+  /// Subscribe to the rows of this view matching the query, see [QuerySubscription]. The
+  /// initial result is [QuerySubscription::result_set] and its changes are computed as the rows
+  /// and the row orders of the view change. Returns `None` if the database is opened without a
+  /// [crate::database_state::DatabaseNotify].
+  pub async fn subscribe(&self, database: &Database, query: RowQuery) -> Option<QuerySubscription> {
+    // Subscribe before reading the rows, so no change is missed.
+    let row_change_rx = database.subscribe_row_change()?;
+    let view_change_rx = database.subscribe_view_change()?;
+    let view_row_ids = database
+      .get_row_orders_for_view(&self.id)
+      .into_iter()
+      .map(|order| order.id)
+      .collect::<Vec<_>>();
+    let block = database.body.block.clone();
+    let rows = match block.init_database_rows(view_row_ids.clone(), false).await {
+      Ok(database_rows) => {
+        let mut rows = HashMap::new();
+        for database_row in database_rows {
+          if let Some(row) = database_row.read().await.get_row() {
+            rows.insert(row.id.clone(), row);
+          }
+        }
+        view_row_ids
+          .iter()
+          .filter_map(|row_id| rows.remove(row_id))
+          .collect()
+      },
+      Err(err) => {
+        error!("Failed to load the rows of the view {}: {:?}", self.id, err);
+        vec![]
+      },
+    };
+    Some(QuerySubscription::new(
+      self.id.clone(),
+      block,
+      QueryResultSet::new(query, rows),
+      view_row_ids,
+      row_change_rx,
+      view_change_rx,
+    ))
+  }
+}
+
+/// A [RowQuery] on a view, updated as the rows of the view change, see [DatabaseView::subscribe].
+pub struct QuerySubscription {
+  view_id: String,
+  block: Block,
+  result_set: QueryResultSet,
+  /// The ids of the rows of the view, including the ones whose collab couldn't be loaded, to
+  /// follow the indexes of [DatabaseViewChange::DidUpdateRowOrders].
+  view_row_ids: Vec<RowId>,
+  row_change_rx: RowChangeReceiver,
+  view_change_rx: ViewChangeReceiver,
+}
+
+impl QuerySubscription {
+  pub(crate) fn new(
+    view_id: String,
+    block: Block,
+    result_set: QueryResultSet,
+    view_row_ids: Vec<RowId>,
+    row_change_rx: RowChangeReceiver,
+    view_change_rx: ViewChangeReceiver,
+  ) -> Self {
+    Self {
+      view_id,
+      block,
+      result_set,
+      view_row_ids,
+      row_change_rx,
+      view_change_rx,
+    }
+  }
+
+  /// The current result of the query.
+  pub fn result_set(&self) -> &QueryResultSet {
+    &self.result_set
+  }
+
+  /// Wait for the next changes of the result. The changes of the rows that don't change the
+  /// result are skipped.
+  ///
+  /// Return None when the database is closed.
+  pub async fn next_changes(&mut self) -> Option<Vec<QueryChange>> {
+    loop {
+      // Drop the pending receive of the other channel before handling the event.
+      let event = match select(
+        Box::pin(self.row_change_rx.recv()),
+        Box::pin(self.view_change_rx.recv()),
+      )
+      .await
+      {
+        Either::Left((result, _)) => Either::Left(result),
+        Either::Right((result, _)) => Either::Right(result),
+      };
+      let changes = match event {
+        Either::Left(Ok(change)) => self.result_set.apply_row_change(&change),
+        Either::Left(Err(RecvError::Lagged(_))) => self.reload_rows().await,
+        Either::Right(Ok(change)) => self.apply_view_change(change).await,
+        // The order of the view can't be recovered, the next changes of the rows are still
+        // applied.
+        Either::Right(Err(RecvError::Lagged(_))) => vec![],
+        Either::Left(Err(RecvError::Closed)) | Either::Right(Err(RecvError::Closed)) => {
+          return None;
+        },
+      };
+      if !changes.is_empty() {
+        return Some(changes);
+      }
+    }
+  }
+
+  /// Turn the subscription into a stream of the changes of the result.
+  pub fn into_stream(self) -> impl Stream<Item = Vec<QueryChange>> {
+    stream::unfold(self, |mut subscription| async move {
+      let changes = subscription.next_changes().await?;
+      Some((changes, subscription))
+    })
+  }
+
+  async fn apply_view_change(&mut self, change: DatabaseViewChange) -> Vec<QueryChange> {
+    let DatabaseViewChange::DidUpdateRowOrders {
+      database_view_id,
+      insert_row_orders,
+      delete_row_indexes,
+      ..
+    } = change
+    else {
+      return vec![];
+    };
+    if database_view_id != self.view_id {
+      return vec![];
+    }
+
+    // The indexes of the deleted rows count the inserted rows before them, and the indexes of the
+    // inserted rows count the deleted rows before them: insert first, then delete.
+    let mut insert_row_orders = insert_row_orders;
+    insert_row_orders.sort_by_key(|(_, index)| *index);
+    for (row_order, index) in &insert_row_orders {
+      let index = (*index as usize).min(self.view_row_ids.len());
+      self.view_row_ids.insert(index, row_order.id.clone());
+    }
+    let mut delete_row_indexes = delete_row_indexes;
+    delete_row_indexes.sort_unstable_by(|a, b| b.cmp(a));
+    for index in delete_row_indexes {
+      if (index as usize) < self.view_row_ids.len() {
+        self.view_row_ids.remove(index as usize);
+      }
+    }
+
+    let new_row_ids = insert_row_orders
+      .into_iter()
+      .map(|(row_order, _)| row_order.id)
+      .filter(|row_id| self.result_set.positions.get(row_id).is_none())
+      .collect::<Vec<_>>();
+    let new_rows = self.load_rows(new_row_ids).await;
+    self
+      .result_set
+      .update_view_rows(self.view_row_ids.clone(), new_rows)
+  }
+
+  /// Reload all the rows of the view after some of their changes were missed.
+  async fn reload_rows(&mut self) -> Vec<QueryChange> {
+    let rows = self.load_rows(self.view_row_ids.clone()).await;
+    let mut changes = vec![];
+    for row in rows {
+      changes.extend(self.result_set.update_row(row));
+    }
+    changes
+  }
+
+  async fn load_rows(&self, row_ids: Vec<RowId>) -> Vec<Row> {
+    if row_ids.is_empty() {
+      return vec![];
+    }
+    let Ok(database_rows) = self.block.init_database_rows(row_ids, false).await else {
+      return vec![];
+    };
+    let mut rows = vec![];
+    for database_row in database_rows {
+      if let Some(row) = database_row.read().await.get_row() {
+        rows.push(row);
+      }
+    }
+    rows
+  }
+}
@@ -0,0 +1,115 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::fields::date_type_option::DateCellData;
+use crate::rows::{RowCell, RowId};
+use crate::views::{TimelineDependency, TimelineLayoutSetting};
+
+/// A row drawn as a bar on the timeline. The timestamps are in seconds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TimelineBar {
+  pub row_id: RowId,
+  pub start: i64,
+  pub end: i64,
+  pub include_time: bool,
+}
+
+impl TimelineBar {
+  /// Return true if the bar overlaps the window, both ends included.
+  pub fn overlaps(&self, window_start: i64, window_end: i64) -> bool {
+    self.start <= window_end && self.end >= window_start
+  }
+}
+
+/// The bars of a timeline within a time window, and the dependencies of these bars.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TimelineWindow {
+  /// The bars overlapping the window, ordered by the row orders of the view.
+  pub bars: Vec<TimelineBar>,
+  /// The dependencies with at least one of their rows in the window, so the frontend can draw
+  /// the links leaving the window.
+  pub dependencies: Vec<TimelineDependency>,
+}
+
+impl TimelineWindow {
+  pub fn new(
+    setting: &TimelineLayoutSetting,
+    bars: Vec<TimelineBar>,
+    window_start: i64,
+    window_end: i64,
+  ) -> Self {
+    let bars = bars
+      .into_iter()
+      .filter(|bar| bar.overlaps(window_start, window_end))
+      .collect::<Vec<_>>();
+    let row_ids = bars
+      .iter()
+      .map(|bar| bar.row_id.as_str())
+      .collect::<HashSet<_>>();
+    let dependencies = setting
+      .dependencies
+      .iter()
+      .filter(|dependency| {
+        row_ids.contains(dependency.from_row_id.as_str())
+          || row_ids.contains(dependency.to_row_id.as_str())
+      })
+      .cloned()
+      .collect();
+    Self { bars, dependencies }
+  }
+
+  pub fn bar(&self, row_id: &str) -> Option<&TimelineBar> {
+    self.bars.iter().find(|bar| bar.row_id.as_str() == row_id)
+  }
+
+  /// Return the dependencies whose row starts before the row it depends on finishes. Only the
+  /// dependencies with both rows in the window are checked.
+  pub fn violated_dependencies(&self) -> Vec<&TimelineDependency> {
+    self
+      .dependencies
+      .iter()
+      .filter(|dependency| {
+        match (
+          self.bar(&dependency.from_row_id),
+          self.bar(&dependency.to_row_id),
+        ) {
+          (Some(from), Some(to)) => to.start < from.end,
+          _ => false,
+        }
+      })
+      .collect()
+  }
+}
+
+/// Build the bars of the rows from the cells of the start field and of the end field, see
+/// [TimelineLayoutSetting::end_field_id]. The rows without a start date have no bar, and a bar
+/// never ends before it starts.
+pub fn timeline_bars(
+  start_cells: Vec<RowCell>,
+  end_cells: Option<Vec<RowCell>>,
+) -> Vec<TimelineBar> {
+  let end_cells = end_cells
+    .unwrap_or_default()
+    .into_iter()
+    .filter_map(|row_cell| Some((row_cell.row_id, DateCellData::from(&row_cell.cell?))))
+    .collect::<HashMap<_, _>>();
+  start_cells
+    .into_iter()
+    .filter_map(|row_cell| {
+      let start_data = DateCellData::from(row_cell.cell.as_ref()?);
+      let start = start_data.timestamp?;
+      let end = match end_cells.get(&row_cell.row_id) {
+        Some(end_data) => end_data.timestamp,
+        None if start_data.is_range => start_data.end_timestamp,
+        None => None,
+      };
+      Some(TimelineBar {
+        start,
+        end: end.unwrap_or(start).max(start),
+        include_time: start_data.include_time,
+        row_id: row_cell.row_id,
+      })
+    })
+    .collect()
+}
@@ -0,0 +1,233 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use collab::preclude::Any;
+use serde::{Deserialize, Serialize};
+
+use crate::entity::DatabaseView;
+use crate::error::DatabaseError;
+use crate::fields::Field;
+use crate::views::{
+  DatabaseLayout, FieldSettingsByFieldIdMap, FilterMap, GroupSettingMap, LayoutSettings, SortMap,
+};
+
+/// The version of the [ViewDefinition] format written by this version.
+pub const VIEW_DEFINITION_VERSION: u32 = 1;
+
+/// Maps the ids of the fields of the exported view to the ids of the fields of the database the
+/// view is imported into.
+pub type FieldMapping = HashMap<String, String>;
+
+/// A field referenced by a [ViewDefinition], used to map it to a field of another database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ViewDefinitionField {
+  pub field_id: String,
+  pub name: String,
+  pub field_type: i64,
+}
+
+/// The configuration of a view without its rows and fields: layout, filters, sorts, group settings
+/// and field settings. Export it with [crate::database::Database::export_view_definition] to
+/// share a view setup, e.g. a sprint board, with other databases.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViewDefinition {
+  pub version: u32,
+  pub name: String,
+  pub layout: DatabaseLayout,
+  #[serde(default)]
+  pub layout_settings: LayoutSettings,
+  #[serde(default)]
+  pub filters: Vec<FilterMap>,
+  #[serde(default)]
+  pub sorts: Vec<SortMap>,
+  #[serde(default)]
+  pub group_settings: Vec<GroupSettingMap>,
+  #[serde(default)]
+  pub field_settings: FieldSettingsByFieldIdMap,
+  /// The fields referenced by the settings of the view.
+  #[serde(default)]
+  pub fields: Vec<ViewDefinitionField>,
+}
+
+impl ViewDefinition {
+  /// The definition of the view, `fields` are the fields of its database.
+  pub fn from_view(view: &DatabaseView, fields: &[Field]) -> Self {
+    let mut definition = Self {
+      version: VIEW_DEFINITION_VERSION,
+      name: view.name.clone(),
+      layout: view.layout,
+      layout_settings: view.layout_settings.clone(),
+      filters: view.filters.clone(),
+      sorts: view.sorts.clone(),
+      group_settings: view.group_settings.clone(),
+      field_settings: view.field_settings.clone(),
+      fields: vec![],
+    };
+    let field_ids = definition.referenced_field_ids();
+    definition.fields = fields
+      .iter()
+      .filter(|field| field_ids.contains(&field.id))
+      .map(|field| ViewDefinitionField {
+        field_id: field.id.clone(),
+        name: field.name.clone(),
+        field_type: field.field_type,
+      })
+      .collect();
+    definition
+  }
+
+  pub fn to_json(&self) -> Result<String, DatabaseError> {
+    Ok(serde_json::to_string(self)?)
+  }
+
+  /// Parse a definition, return [DatabaseError::ImportData] if it was written by a newer version.
+  pub fn from_json(json: &str) -> Result<Self, DatabaseError> {
+    let definition: Self = serde_json::from_str(json)?;
+    if definition.version > VIEW_DEFINITION_VERSION {
+      return Err(DatabaseError::ImportData(format!(
+        "unsupported view definition version: {}",
+        definition.version
+      )));
+    }
+    Ok(definition)
+  }
+
+  /// The ids of the fields referenced by the settings of the view, sorted.
+  pub fn referenced_field_ids(&self) -> BTreeSet<String> {
+    let mut field_ids = self.field_settings.keys().cloned().collect::<BTreeSet<_>>();
+    let maps = self
+      .layout_settings
+      .values()
+      .chain(self.filters.iter())
+      .chain(self.sorts.iter())
+      .chain(self.group_settings.iter());
+    for map in maps {
+      for (key, value) in map {
+        collect_field_ids(key, value, &mut field_ids);
+      }
+    }
+    field_ids.retain(|field_id| !field_id.is_empty());
+    field_ids
+  }
+
+  /// Map the referenced fields to the fields of another database with the same name, ignoring the
+  /// case, and preferably the same type. The fields without a match are not mapped.
+  pub fn suggest_field_mapping(&self, target_fields: &[Field]) -> FieldMapping {
+    self
+      .fields
+      .iter()
+      .filter_map(|field| {
+        let same_name = |target: &&Field| target.name.eq_ignore_ascii_case(&field.name);
+        let target = target_fields
+          .iter()
+          .filter(same_name)
+          .find(|target| target.field_type == field.field_type)
+          .or_else(|| target_fields.iter().find(same_name))?;
+        Some((field.field_id.clone(), target.id.clone()))
+      })
+      .collect()
+  }
+
+  /// The definition with the fields renamed by the mapping. The filters, sorts and group settings
+  /// of the fields that are not mapped are removed, as are the unmapped fields of the layout and
+  /// field settings.
+  pub fn with_field_mapping(&self, mapping: &FieldMapping) -> Self {
+    let remap_maps = |maps: &[HashMap<String, Any>]| {
+      maps
+        .iter()
+        .filter_map(|map| remap_map(map, mapping))
+        .collect::<Vec<_>>()
+    };
+    let mut layout_settings = LayoutSettings::new();
+    for (layout, setting) in self.layout_settings.iter() {
+      let setting = setting
+        .iter()
+        .filter_map(|(key, value)| Some((key.clone(), remap_value(key, value, mapping)?)))
+        .collect();
+      layout_settings.insert(*layout, setting);
+    }
+    let field_settings = self
+      .field_settings
+      .iter()
+      .filter_map(|(field_id, settings)| Some((mapping.get(field_id)?.clone(), settings.clone())))
+      .collect::<HashMap<_, _>>();
+    let fields = self
+      .fields
+      .iter()
+      .filter_map(|field| {
+        Some(ViewDefinitionField {
+          field_id: mapping.get(&field.field_id)?.clone(),
+          ..field.clone()
+        })
+      })
+      .collect();
+
+    Self {
+      version: self.version,
+      name: self.name.clone(),
+      layout: self.layout,
+      layout_settings,
+      filters: remap_maps(&self.filters),
+      sorts: remap_maps(&self.sorts),
+      group_settings: remap_maps(&self.group_settings),
+      field_settings: FieldSettingsByFieldIdMap::from(field_settings),
+      fields,
+    }
+  }
+}
+
+/// A key holding the id of a field, e.g. `field_id` or `start_field_id`.
+fn is_field_id_key(key: &str) -> bool {
+  key.ends_with("field_id")
+}
+
+fn collect_field_ids(key: &str, value: &Any, field_ids: &mut BTreeSet<String>) {
+  match value {
+    Any::String(field_id) if is_field_id_key(key) => {
+      field_ids.insert(field_id.to_string());
+    },
+    Any::Map(map) => {
+      for (key, value) in map.iter() {
+        collect_field_ids(key, value, field_ids);
+      }
+    },
+    Any::Array(values) => {
+      for value in values.iter() {
+        collect_field_ids(key, value, field_ids);
+      }
+    },
+    _ => {},
+  }
+}
+
+/// Rename the fields referenced by the map, None if one of them is not mapped.
+fn remap_map(map: &HashMap<String, Any>, mapping: &FieldMapping) -> Option<HashMap<String, Any>> {
+  map
+    .iter()
+    .map(|(key, value)| Some((key.clone(), remap_value(key, value, mapping)?)))
+    .collect()
+}
+
+fn remap_value(key: &str, value: &Any, mapping: &FieldMapping) -> Option<Any> {
+  match value {
+    Any::String(field_id) if is_field_id_key(key) && !field_id.is_empty() => {
+      let field_id = mapping.get(field_id.as_ref())?;
+      Some(Any::String(Arc::from(field_id.as_str())))
+    },
+    Any::Map(map) => {
+      let map = map
+        .iter()
+        .map(|(key, value)| Some((key.clone(), remap_value(key, value, mapping)?)))
+        .collect::<Option<HashMap<_, _>>>()?;
+      Some(Any::Map(Arc::new(map)))
+    },
+    Any::Array(values) => {
+      let values = values
+        .iter()
+        .map(|value| remap_value(key, value, mapping))
+        .collect::<Option<Vec<_>>>()?;
+      Some(Any::Array(Arc::from(values)))
+    },
+    value => Some(value.clone()),
+  }
+}
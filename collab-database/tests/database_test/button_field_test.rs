@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use collab_database::database_trait::AutomationHandler;
+use collab_database::entity::FieldType;
+use collab_database::error::DatabaseError;
+use collab_database::fields::button_type_option::{
+  AutomationEvent, ButtonAction, ButtonTypeOption,
+};
+use collab_database::fields::text_type_option::RichTextTypeOption;
+use collab_database::fields::{Field, TypeOptionData};
+use collab_database::rows::{Cells, CreateRowParams, RowId};
+use collab_database::views::OrderObjectPosition;
+use serde_json::json;
+
+use crate::database_test::helper::{
+  DatabaseTest, create_database, default_field_settings_by_layout,
+};
+use crate::helper::TestTextCell;
+
+#[derive(Default)]
+struct TestAutomationHandler {
+  urls: Mutex<Vec<String>>,
+  events: Mutex<Vec<AutomationEvent>>,
+}
+
+impl AutomationHandler for TestAutomationHandler {
+  fn open_url(&self, url: &str) {
+    self.urls.lock().unwrap().push(url.to_string());
+  }
+
+  fn emit_event(&self, event: AutomationEvent) {
+    self.events.lock().unwrap().push(event);
+  }
+}
+
+fn create_field(
+  test: &mut DatabaseTest,
+  field_id: &str,
+  name: &str,
+  field_type: FieldType,
+  type_option: TypeOptionData,
+) {
+  let field = Field::new(
+    field_id.to_string(),
+    name.to_string(),
+    field_type.into(),
+    false,
+  )
+  .with_type_option_data(field_type.type_id(), type_option);
+  test.create_field(
+    None,
+    field,
+    &OrderObjectPosition::default(),
+    default_field_settings_by_layout(),
+  );
+}
+
+#[tokio::test]
+async fn press_button_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut test = create_database(1, &database_id);
+  create_field(
+    &mut test,
+    "name",
+    "Name",
+    FieldType::RichText,
+    RichTextTypeOption.into(),
+  );
+  create_field(
+    &mut test,
+    "status",
+    "Status",
+    FieldType::RichText,
+    RichTextTypeOption.into(),
+  );
+  let type_option = ButtonTypeOption::new("Approve")
+    .with_action(ButtonAction::OpenUrl {
+      url: "https://appflowy.io/search?q={Name}&s={Status}".to_string(),
+    })
+    .with_action(ButtonAction::SetCells {
+      cells: HashMap::from([
+        ("status".to_string(), json!("Approved")),
+        ("deleted".to_string(), json!("")),
+      ]),
+    })
+    .with_action(ButtonAction::OpenUrl {
+      url: "https://appflowy.io/{Status}".to_string(),
+    })
+    .with_action(ButtonAction::EmitEvent {
+      event: "approved".to_string(),
+    });
+  create_field(
+    &mut test,
+    "approve",
+    "Approve",
+    FieldType::Button,
+    type_option.into(),
+  );
+
+  let cells = Cells::from([
+    ("name".to_string(), TestTextCell::from("Lucas & Co").into()),
+    ("status".to_string(), TestTextCell::from("Draft").into()),
+  ]);
+  let params = CreateRowParams::new(uuid::Uuid::new_v4(), database_id.clone()).with_cells(cells);
+  let row_id = test.create_row(params).await.unwrap().id;
+
+  let handler = TestAutomationHandler::default();
+  test
+    .press_button("approve", &row_id, &handler)
+    .await
+    .unwrap();
+  // The second url sees the cell set by the action before it.
+  assert_eq!(
+    *handler.urls.lock().unwrap(),
+    vec![
+      "https://appflowy.io/search?q=Lucas%20%26%20Co&s=Draft",
+      "https://appflowy.io/Approved",
+    ]
+  );
+  assert_eq!(
+    *handler.events.lock().unwrap(),
+    vec![AutomationEvent {
+      event: "approved".to_string(),
+      database_id: database_id.clone(),
+      row_id: row_id.clone(),
+      field_id: "approve".to_string(),
+    }]
+  );
+  let row = test.get_row(&row_id).await;
+  let status = TestTextCell::from(row.cells.get("status").unwrap().clone());
+  assert_eq!(status.0, "Approved");
+  assert!(!row.cells.contains_key("deleted"));
+}
+
+#[tokio::test]
+async fn press_invalid_button_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut test = create_database(1, &database_id);
+  create_field(
+    &mut test,
+    "name",
+    "Name",
+    FieldType::RichText,
+    RichTextTypeOption.into(),
+  );
+  create_field(
+    &mut test,
+    "approve",
+    "Approve",
+    FieldType::Button,
+    ButtonTypeOption::new("Approve").into(),
+  );
+  let params = CreateRowParams::new(uuid::Uuid::new_v4(), database_id.clone());
+  let row_id = test.create_row(params).await.unwrap().id;
+
+  let handler = TestAutomationHandler::default();
+  assert!(
+    test
+      .press_button("name", &row_id, &handler)
+      .await
+      .unwrap_err()
+      .is_no_required_data()
+  );
+  assert!(matches!(
+    test
+      .press_button("approve", &RowId::from(uuid::Uuid::new_v4()), &handler)
+      .await,
+    Err(DatabaseError::DatabaseRowNotFound { .. })
+  ));
+  // A button without actions does nothing.
+  test
+    .press_button("approve", &row_id, &handler)
+    .await
+    .unwrap();
+  assert!(handler.urls.lock().unwrap().is_empty());
+  assert!(handler.events.lock().unwrap().is_empty());
+}
@@ -1,3 +1,4 @@
+use collab::preclude::Any;
 use collab_database::rows::Cells;
 
 use crate::database_test::helper::create_database_with_default_data;
@@ -89,3 +90,35 @@ fn cells_serde_test() {
   let number_cell = TestNumberCell::from(cell);
   assert_eq!(number_cell.0, 1);
 }
+
+#[tokio::test]
+async fn lazy_row_cell_test() {
+  let database_id = uuid::Uuid::new_v4();
+  let database_test = create_database_with_default_data(1, &database_id.to_string()).await;
+  let row_id = database_test.pre_define_row_ids[0].clone();
+  let row = database_test.get_row(&row_id).await;
+
+  let database_row = database_test.get_database_row(&row_id).await.unwrap();
+  let read_guard = database_row.read().await;
+  let lazy_row = read_guard.lazy_row();
+  assert_eq!(lazy_row.id(), Some(row_id));
+  assert_eq!(lazy_row.height(), row.height);
+  assert_eq!(lazy_row.visibility(), row.visibility);
+  assert_eq!(lazy_row.created_at(), row.created_at);
+
+  let mut field_ids = lazy_row.field_ids();
+  field_ids.sort();
+  let mut expected_field_ids = row.cells.keys().cloned().collect::<Vec<_>>();
+  expected_field_ids.sort();
+  assert_eq!(field_ids, expected_field_ids);
+
+  assert!(lazy_row.has_cell("f1"));
+  assert!(!lazy_row.has_cell("unknown"));
+  assert_eq!(lazy_row.get_cell("f1"), row.cells.get("f1").cloned());
+  assert_eq!(
+    lazy_row.get_cell_value("f1", "data"),
+    Some(Any::from("1f1cell"))
+  );
+  assert_eq!(lazy_row.get_cell_value("f1", "unknown"), None);
+  assert_eq!(lazy_row.to_row(), Some(row));
+}
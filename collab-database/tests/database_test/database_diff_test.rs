@@ -0,0 +1,84 @@
+use crate::database_test::helper::create_database_with_default_data;
+use crate::helper::TestTextCell;
+use collab_database::database::gen_row_id;
+use collab_database::database_diff::DatabaseRevision;
+use collab_database::rows::CreateRowParams;
+use collab_database::template::entity::CELL_DATA;
+
+#[tokio::test]
+async fn changes_since_empty_revision_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let database_test = create_database_with_default_data(1, &database_id).await;
+
+  let changes = database_test
+    .changes_since(&DatabaseRevision::default(), false)
+    .await
+    .unwrap();
+  assert_eq!(changes.database_id, database_id);
+  assert_eq!(changes.inserted_rows.len(), 3);
+  assert_eq!(changes.inserted_fields.len(), 3);
+  assert!(changes.updated_rows.is_empty());
+  assert_eq!(changes.revision.rows.len(), 3);
+
+  // Nothing changed since the returned revision.
+  let changes = database_test
+    .changes_since(&changes.revision, false)
+    .await
+    .unwrap();
+  assert!(changes.is_empty(), "{:?}", changes);
+}
+
+#[tokio::test]
+async fn changes_since_revision_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut database_test = create_database_with_default_data(1, &database_id).await;
+  let revision = database_test
+    .changes_since(&DatabaseRevision::default(), false)
+    .await
+    .unwrap()
+    .revision;
+
+  // The revision survives a round trip through its string form.
+  let revision = DatabaseRevision::decode(&revision.encode().unwrap()).unwrap();
+
+  let row_ids = database_test.pre_define_row_ids.clone();
+  database_test
+    .update_row(row_ids[0].clone(), |row_update| {
+      row_update.update_cells(|cells_update| {
+        cells_update.insert("f1", TestTextCell("updated".to_string()));
+      });
+    })
+    .await;
+  database_test.remove_row(&row_ids[1]).await;
+  let new_row_id = gen_row_id();
+  database_test
+    .create_row(CreateRowParams::new(
+      new_row_id.clone(),
+      database_id.clone(),
+    ))
+    .await
+    .unwrap();
+  database_test.update_field("f2", |update| {
+    update.set_name("renamed");
+  });
+  database_test.delete_field("f3");
+
+  let changes = database_test.changes_since(&revision, false).await.unwrap();
+  assert_eq!(changes.updated_rows.len(), 1);
+  assert_eq!(changes.updated_rows[0].id, row_ids[0]);
+  assert_eq!(
+    changes.updated_rows[0].cells["f1"].get(CELL_DATA).unwrap(),
+    &"updated".into()
+  );
+  assert_eq!(changes.deleted_row_ids, vec![row_ids[1].to_string()]);
+  assert_eq!(changes.inserted_rows.len(), 1);
+  assert_eq!(changes.inserted_rows[0].id, new_row_id);
+
+  assert!(changes.inserted_fields.is_empty());
+  assert_eq!(changes.updated_fields.len(), 1);
+  assert_eq!(changes.updated_fields[0].name, "renamed");
+  assert_eq!(changes.deleted_field_ids, vec!["f3".to_string()]);
+
+  let json = changes.to_json_value().unwrap();
+  assert_eq!(json["deleted_field_ids"][0], "f3");
+}
@@ -0,0 +1,84 @@
+use collab_database::fields::date_type_option::{DateCellData, DateTypeOption};
+use collab_database::rows::{Cells, CreateRowParams, RowId};
+use collab_database::views::{DateFilter, DateFilterCondition, RowQuery};
+
+use crate::database_test::helper::{DatabaseTest, create_database};
+
+const DAY: i64 = 24 * 60 * 60;
+// 2024-01-01T12:00:00Z
+const NOW: i64 = 1704110400;
+
+async fn create_date_row(test: &mut DatabaseTest, database_id: &str, cell: DateCellData) -> RowId {
+  let params = CreateRowParams::new(uuid::Uuid::new_v4(), database_id.to_string())
+    .with_cells(Cells::from([("f2".to_string(), (&cell).into())]));
+  test.create_row(params).await.unwrap().id
+}
+
+async fn filter_row_ids(test: &DatabaseTest, condition: DateFilterCondition) -> Vec<RowId> {
+  let filter = DateFilter::new("f2", condition, DateTypeOption::default_utc());
+  let query = RowQuery::new().with_filter(filter.into_row_filter(NOW));
+  let subscription = test
+    .get_view("v1")
+    .unwrap()
+    .subscribe(test, query)
+    .await
+    .unwrap();
+  subscription.result_set().row_ids().to_vec()
+}
+
+#[tokio::test]
+async fn date_filter_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut test = create_database(1, &database_id);
+  let today = create_date_row(&mut test, &database_id, DateCellData::from_timestamp(NOW)).await;
+  let next_week = create_date_row(
+    &mut test,
+    &database_id,
+    DateCellData::from_timestamp_include_time(NOW + 5 * DAY),
+  )
+  .await;
+  let mut range = DateCellData::from_timestamp(NOW - 10 * DAY);
+  range.end_timestamp = Some(NOW - 2 * DAY);
+  range.is_range = true;
+  let last_week = create_date_row(&mut test, &database_id, range).await;
+  let empty = create_date_row(&mut test, &database_id, DateCellData::default()).await;
+
+  assert_eq!(
+    filter_row_ids(&test, DateFilterCondition::IsToday).await,
+    vec![today.clone()]
+  );
+  assert_eq!(
+    filter_row_ids(&test, DateFilterCondition::IsWithinNextWeek).await,
+    vec![today.clone(), next_week.clone()]
+  );
+  // The range ends within the past week.
+  assert_eq!(
+    filter_row_ids(&test, DateFilterCondition::IsWithinPastWeek).await,
+    vec![today.clone(), last_week.clone()]
+  );
+  assert_eq!(
+    filter_row_ids(&test, DateFilterCondition::IsBefore(NOW)).await,
+    vec![last_week.clone()]
+  );
+  assert_eq!(
+    filter_row_ids(
+      &test,
+      DateFilterCondition::IsBetween {
+        start: NOW + DAY,
+        end: NOW - 3 * DAY,
+      }
+    )
+    .await,
+    vec![today.clone(), last_week]
+  );
+  assert_eq!(
+    filter_row_ids(&test, DateFilterCondition::IsEmpty).await,
+    vec![empty]
+  );
+  assert_eq!(
+    filter_row_ids(&test, DateFilterCondition::IsNotEmpty)
+      .await
+      .len(),
+    3
+  );
+}
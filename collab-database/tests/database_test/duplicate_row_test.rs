@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use crate::database_test::helper::{DatabaseTest, create_database};
+use collab::preclude::Any;
+use collab_database::entity::FieldType;
+use collab_database::fields::Field;
+use collab_database::rows::{
+  Cell, Cells, CreateRowParams, DuplicateRowPolicy, RowId, new_cell_builder,
+};
+use collab_database::template::entity::CELL_DATA;
+use collab_database::template::relation_parse::RelationCellData;
+use collab_database::views::OrderObjectPosition;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn find_duplicate_rows_test() {
+  let database_test = create_contact_database().await;
+  let duplicates = database_test.find_duplicate_rows(&["email"], false).await;
+  assert_eq!(duplicates.len(), 1);
+  assert_eq!(duplicates[0].key, vec!["ann@example.com".to_string()]);
+  assert_eq!(
+    duplicates[0].row_ids,
+    vec![RowId::from("r1"), RowId::from("r2")]
+  );
+
+  // r1 and r2 have different names
+  let duplicates = database_test
+    .find_duplicate_rows(&["email", "name"], false)
+    .await;
+  assert!(duplicates.is_empty());
+  assert!(
+    database_test
+      .find_duplicate_rows(&[], false)
+      .await
+      .is_empty()
+  );
+}
+
+#[tokio::test]
+async fn merge_duplicate_rows_test() {
+  let mut database_test = create_contact_database().await;
+  let row = database_test
+    .merge_rows(&RowId::from("r1"), &[RowId::from("r2")], false)
+    .await
+    .unwrap();
+
+  // the longest texts are kept and the relations are combined
+  assert_eq!(text(&row.cells["name"]), "Ann Smith");
+  assert_eq!(text(&row.cells["email"]), "ann@example.com");
+  assert_eq!(text(&row.cells["phone"]), "555-0100");
+  assert_eq!(
+    RelationCellData::from(&row.cells["related"]).row_ids,
+    vec![RowId::from("r3")]
+  );
+
+  // r4 pointed to the removed r2
+  let row = database_test.get_row(&RowId::from("r4")).await;
+  assert_eq!(
+    RelationCellData::from(&row.cells["related"]).row_ids,
+    vec![RowId::from("r1"), RowId::from("r3")]
+  );
+  let row_ids = database_test
+    .get_all_row_orders()
+    .await
+    .into_iter()
+    .map(|row_order| row_order.id.to_string())
+    .collect::<Vec<_>>();
+  assert_eq!(row_ids, vec!["r1", "r3", "r4"]);
+  assert!(
+    database_test
+      .find_duplicate_rows(&["email"], false)
+      .await
+      .is_empty()
+  );
+}
+
+#[tokio::test]
+async fn skip_duplicate_rows_test() {
+  let mut database_test = create_contact_database().await;
+  let duplicates = database_test
+    .dedupe_rows(&["email"], DuplicateRowPolicy::Skip, false)
+    .await;
+  assert_eq!(duplicates.len(), 1);
+
+  // r1 is kept as is
+  let row = database_test.get_row(&RowId::from("r1")).await;
+  assert_eq!(text(&row.cells["name"]), "Ann");
+  assert_eq!(text(&row.cells["phone"]), "");
+  let row = database_test.get_row(&RowId::from("r4")).await;
+  assert_eq!(
+    RelationCellData::from(&row.cells["related"]).row_ids,
+    vec![RowId::from("r1"), RowId::from("r3")]
+  );
+  let row_ids = database_test
+    .get_all_row_orders()
+    .await
+    .into_iter()
+    .map(|row_order| row_order.id.to_string())
+    .collect::<Vec<_>>();
+  assert_eq!(row_ids, vec!["r1", "r3", "r4"]);
+}
+
+#[tokio::test]
+async fn merge_duplicate_rows_by_key_test() {
+  let mut database_test = create_contact_database().await;
+  let duplicates = database_test
+    .dedupe_rows(&["email"], DuplicateRowPolicy::Merge, false)
+    .await;
+  assert_eq!(duplicates.len(), 1);
+  let row = database_test.get_row(&RowId::from("r1")).await;
+  assert_eq!(text(&row.cells["name"]), "Ann Smith");
+  assert_eq!(text(&row.cells["phone"]), "555-0100");
+  assert_eq!(database_test.get_all_row_orders().await.len(), 3);
+
+  // no duplicated names
+  assert!(
+    database_test
+      .dedupe_rows(&["name"], DuplicateRowPolicy::Merge, false)
+      .await
+      .is_empty()
+  );
+}
+
+#[tokio::test]
+async fn repoint_relations_test() {
+  let mut database_test = create_contact_database().await;
+  let replaced = HashMap::from([(RowId::from("r3"), RowId::from("r1"))]);
+  assert_eq!(database_test.repoint_relations(&replaced, false).await, 2);
+  let row = database_test.get_row(&RowId::from("r4")).await;
+  assert_eq!(
+    RelationCellData::from(&row.cells["related"]).row_ids,
+    vec![RowId::from("r2"), RowId::from("r1")]
+  );
+}
+
+async fn create_contact_database() -> DatabaseTest {
+  let database_id = Uuid::new_v4().to_string();
+  let mut database_test = create_database(1, &database_id);
+  for (field_id, field_type) in [
+    ("name", FieldType::RichText),
+    ("email", FieldType::RichText),
+    ("phone", FieldType::RichText),
+    ("related", FieldType::Relation),
+  ] {
+    let mut field = Field::from_field_type(field_id, field_type, field_id == "name");
+    field.id = field_id.to_string();
+    database_test.create_field(None, field, &OrderObjectPosition::End, HashMap::new());
+  }
+
+  let rows = [
+    ("r1", "Ann", "ann@example.com", "", vec![]),
+    (
+      "r2",
+      "Ann Smith",
+      " ANN@example.com ",
+      "555-0100",
+      vec!["r3"],
+    ),
+    ("r3", "Bob", "bob@example.com", "", vec!["r2"]),
+    // an empty email is not a duplicate of another empty email
+    ("r4", "", "", "", vec!["r2", "r3"]),
+  ];
+  for (row_id, name, email, phone, related) in rows {
+    let mut cells = Cells::from([
+      ("name".to_string(), text_cell(name)),
+      ("email".to_string(), text_cell(email)),
+      ("phone".to_string(), text_cell(phone)),
+    ]);
+    if !related.is_empty() {
+      let row_ids = related.into_iter().map(RowId::from).collect();
+      cells.insert("related".to_string(), RelationCellData { row_ids }.into());
+    }
+    let params = CreateRowParams::new(RowId::from(row_id), database_id.clone()).with_cells(cells);
+    database_test.create_row(params).await.unwrap();
+  }
+  database_test
+}
+
+fn text_cell(text: &str) -> Cell {
+  let mut cell = new_cell_builder(FieldType::RichText);
+  cell.insert(CELL_DATA.to_string(), Any::from(text));
+  cell
+}
+
+fn text(cell: &Cell) -> String {
+  match cell.get(CELL_DATA) {
+    Some(Any::String(text)) => text.to_string(),
+    _ => String::new(),
+  }
+}
@@ -53,3 +53,39 @@ async fn observe_field_update_and_delete_test() {
   .await
   .unwrap();
 }
+
+#[tokio::test]
+async fn observe_single_field_change_test() {
+  setup_log();
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut database_test = create_database_with_default_data(1, &database_id).await;
+  let fields = database_test.get_fields(None);
+  let (first, second) = (fields[0].clone(), fields[1].clone());
+
+  let mut first_rx = database_test
+    .subscribe_field_change_by_id(&first.id)
+    .unwrap();
+  let mut second_rx = database_test
+    .subscribe_field_change_by_id(&second.id)
+    .unwrap();
+
+  database_test.update_field(&first.id, |update| {
+    update.set_name("renamed");
+  });
+
+  match first_rx.try_recv().unwrap() {
+    FieldChange::DidUpdateField { field } => {
+      assert_eq!(field.id, first.id);
+      assert_eq!(field.name, "renamed");
+    },
+    change => panic!("unexpected change: {:?}", change),
+  }
+  assert!(second_rx.try_recv().is_err());
+
+  database_test.delete_field(&second.id);
+  assert!(first_rx.try_recv().is_err());
+  match second_rx.try_recv().unwrap() {
+    FieldChange::DidDeleteField { field_id } => assert_eq!(field_id, second.id),
+    change => panic!("unexpected change: {:?}", change),
+  }
+}
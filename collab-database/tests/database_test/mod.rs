@@ -1,6 +1,10 @@
 mod block_test;
+mod button_field_test;
 mod cell_test;
 mod cell_type_option_test;
+mod database_diff_test;
+mod date_filter_test;
+mod duplicate_row_test;
 mod encode_collab_test;
 mod field_observe_test;
 mod field_setting_test;
@@ -9,10 +13,21 @@ mod filter_test;
 mod group_test;
 pub mod helper;
 mod layout_test;
+mod person_field_test;
+mod primary_template_test;
 // mod restore_test;
+mod query_test;
+mod relation_reference_test;
+mod row_archive_test;
+mod row_chunk_test;
+mod row_comment_test;
 mod row_observe_test;
+mod row_recurrence_test;
 mod row_test;
+mod select_option_test;
 mod sort_test;
+mod timeline_test;
 mod type_option_test;
+mod view_definition_test;
 mod view_observe_test;
 mod view_test;
@@ -0,0 +1,182 @@
+use collab_database::entity::FieldType;
+use collab_database::fields::Field;
+use collab_database::fields::person_type_option::{
+  PersonCellData, PersonTypeOption, WorkspaceMember,
+};
+use collab_database::fields::text_type_option::RichTextTypeOption;
+use collab_database::rows::{Cells, CreateRowParams, RowId};
+use collab_database::views::{OrderObjectPosition, PersonFilter, PersonFilterCondition, RowQuery};
+
+use crate::database_test::helper::{
+  DatabaseTest, create_database, default_field_settings_by_layout,
+};
+use crate::helper::TestTextCell;
+
+fn members() -> Vec<WorkspaceMember> {
+  vec![
+    WorkspaceMember::new(1, "Lucas").with_avatar_url("https://appflowy.io/lucas.png"),
+    WorkspaceMember::new(2, "Nathan").with_email("nathan@appflowy.io"),
+  ]
+}
+
+fn create_field(test: &mut DatabaseTest, field_id: &str, field_type: FieldType) {
+  let type_option = match field_type {
+    FieldType::Person => PersonTypeOption::default().into(),
+    _ => RichTextTypeOption.into(),
+  };
+  let field = Field::new(
+    field_id.to_string(),
+    field_id.to_string(),
+    field_type.into(),
+    false,
+  )
+  .with_type_option_data(field_type.type_id(), type_option);
+  test.create_field(
+    None,
+    field,
+    &OrderObjectPosition::default(),
+    default_field_settings_by_layout(),
+  );
+}
+
+async fn create_row(test: &mut DatabaseTest, database_id: &str, cells: Cells) -> RowId {
+  let params =
+    CreateRowParams::new(uuid::Uuid::new_v4(), database_id.to_string()).with_cells(cells);
+  test.create_row(params).await.unwrap().id
+}
+
+#[tokio::test]
+async fn person_cell_members_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut test = create_database(1, &database_id);
+  create_field(&mut test, "assignees", FieldType::Person);
+  let cell =
+    |uids: Vec<i64>| Cells::from([("assignees".to_string(), (&PersonCellData::new(uids)).into())]);
+  let row_1 = create_row(&mut test, &database_id, cell(vec![2, 1])).await;
+  let row_2 = create_row(&mut test, &database_id, cell(vec![2, 3])).await;
+  let row_3 = create_row(&mut test, &database_id, Cells::new()).await;
+
+  let members = test
+    .get_cell_members("assignees", &row_1, &members())
+    .await
+    .unwrap();
+  assert_eq!(
+    members
+      .iter()
+      .map(|member| member.name.as_str())
+      .collect::<Vec<_>>(),
+    vec!["Nathan", "Lucas"]
+  );
+  assert_eq!(
+    members[1].avatar_url.as_deref(),
+    Some("https://appflowy.io/lucas.png")
+  );
+  // The member 3 left the workspace.
+  assert_eq!(
+    test
+      .get_cell_members("assignees", &row_2, &members())
+      .await
+      .unwrap()
+      .len(),
+    1
+  );
+  assert!(
+    test
+      .get_cell_members("unknown", &row_1, &members())
+      .await
+      .unwrap_err()
+      .is_no_required_data()
+  );
+
+  let filter_row_ids = |condition: PersonFilterCondition| {
+    let filter = PersonFilter::new("assignees", condition);
+    let query = RowQuery::new().with_filter(filter.into_row_filter(1));
+    let test = &test;
+    async move {
+      let subscription = test
+        .get_view("v1")
+        .unwrap()
+        .subscribe(test, query)
+        .await
+        .unwrap();
+      subscription.result_set().row_ids().to_vec()
+    }
+  };
+  assert_eq!(
+    filter_row_ids(PersonFilterCondition::IncludesMe).await,
+    vec![row_1.clone()]
+  );
+  assert_eq!(
+    filter_row_ids(PersonFilterCondition::Includes(vec![3])).await,
+    vec![row_2.clone()]
+  );
+  assert_eq!(
+    filter_row_ids(PersonFilterCondition::DoesNotInclude(vec![1])).await,
+    vec![row_2, row_3.clone()]
+  );
+  assert_eq!(
+    filter_row_ids(PersonFilterCondition::IsEmpty).await,
+    vec![row_3]
+  );
+}
+
+#[tokio::test]
+async fn convert_to_person_field_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut test = create_database(1, &database_id);
+  create_field(&mut test, "owner", FieldType::RichText);
+  create_field(&mut test, "reviewer", FieldType::RichText);
+  let text_cells = |owner: &str, reviewer: &str| {
+    Cells::from([
+      ("owner".to_string(), TestTextCell::from(owner).into()),
+      ("reviewer".to_string(), TestTextCell::from(reviewer).into()),
+    ])
+  };
+  let row_1 = create_row(&mut test, &database_id, text_cells("Lucas", "Annie")).await;
+  let row_2 = create_row(
+    &mut test,
+    &database_id,
+    text_cells("nathan@appflowy.io, lucas", "Nathan"),
+  )
+  .await;
+  let row_3 = create_row(&mut test, &database_id, Cells::new()).await;
+
+  assert!(
+    test
+      .convert_to_person_field("owner", &members(), false)
+      .await
+      .unwrap()
+  );
+  let field = test.get_field("owner").unwrap();
+  assert_eq!(FieldType::from(field.field_type), FieldType::Person);
+  let type_option = field
+    .get_type_option::<PersonTypeOption>(FieldType::Person.type_id())
+    .unwrap();
+  assert!(type_option.allow_multiple);
+  let uids = |row_id: RowId| {
+    let test = &test;
+    async move {
+      test
+        .get_cell("owner", &row_id)
+        .await
+        .cell
+        .map(|cell| PersonCellData::from(&cell).uids)
+        .unwrap_or_default()
+    }
+  };
+  assert_eq!(uids(row_1.clone()).await, vec![1]);
+  assert_eq!(uids(row_2).await, vec![2, 1]);
+  assert!(uids(row_3).await.is_empty());
+
+  // Annie isn't a member, the names are kept.
+  assert!(
+    !test
+      .convert_to_person_field("reviewer", &members(), false)
+      .await
+      .unwrap()
+  );
+  let field = test.get_field("reviewer").unwrap();
+  assert_eq!(FieldType::from(field.field_type), FieldType::RichText);
+  let cell = test.get_cell("reviewer", &row_1).await.cell.unwrap();
+  assert_eq!(TestTextCell::from(cell).0, "Annie");
+}
@@ -0,0 +1,203 @@
+use collab_database::entity::FieldType;
+use collab_database::fields::Field;
+use collab_database::fields::select_type_option::{
+  SelectOption, SelectOptionColor, SelectOptionIds, SelectTypeOption,
+};
+use collab_database::fields::text_type_option::RichTextTypeOption;
+use collab_database::rows::{Cell, Cells, CreateRowParams, RowId};
+use collab_database::views::OrderObjectPosition;
+
+use crate::database_test::helper::{
+  DatabaseTest, create_database, default_field_settings_by_layout,
+};
+use crate::helper::TestTextCell;
+
+fn create_text_field(test: &mut DatabaseTest, field_id: &str, name: &str, is_primary: bool) {
+  let field = Field::new(
+    field_id.to_string(),
+    name.to_string(),
+    FieldType::RichText.into(),
+    is_primary,
+  )
+  .with_type_option_data(FieldType::RichText.type_id(), RichTextTypeOption.into());
+  test.create_field(
+    None,
+    field,
+    &OrderObjectPosition::default(),
+    default_field_settings_by_layout(),
+  );
+}
+
+async fn create_contact(test: &mut DatabaseTest, last_name: &str, first_name: &str) -> RowId {
+  let cells = Cells::from([
+    ("last".to_string(), TestTextCell::from(last_name).into()),
+    ("first".to_string(), TestTextCell::from(first_name).into()),
+  ]);
+  let params = CreateRowParams::new(uuid::Uuid::new_v4(), test.get_database_id()).with_cells(cells);
+  test.create_row(params).await.unwrap().id
+}
+
+async fn primary_text(test: &DatabaseTest, row_id: &RowId) -> String {
+  let row = test.get_row(row_id).await;
+  TestTextCell::from(row.cells.get("name").unwrap().clone()).0
+}
+
+#[tokio::test]
+async fn primary_template_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut test = create_database(1, &database_id);
+  create_text_field(&mut test, "name", "Name", true);
+  create_text_field(&mut test, "last", "Last name", false);
+  create_text_field(&mut test, "first", "First name", false);
+  let lovelace = create_contact(&mut test, "Lovelace", "Ada").await;
+  let hopper = create_contact(&mut test, "Hopper", "").await;
+
+  let count = test
+    .set_primary_template("{Last name}, {First name}", true)
+    .await
+    .unwrap();
+  assert_eq!(count, 2);
+  assert_eq!(primary_text(&test, &lovelace).await, "Lovelace, Ada");
+  assert_eq!(primary_text(&test, &hopper).await, "Hopper,");
+  assert_eq!(test.refresh_primary_cells(true).await, 0);
+
+  // The primary cell follows the cells it's generated from.
+  test
+    .update_row(hopper.clone(), |update| {
+      update.update_cells(|cells_update| {
+        cells_update.insert_cell("first", TestTextCell::from("Grace").into());
+      });
+    })
+    .await;
+  assert_eq!(primary_text(&test, &hopper).await, "Hopper, Grace");
+  let turing = create_contact(&mut test, "Turing", "Alan").await;
+  assert_eq!(primary_text(&test, &turing).await, "Turing, Alan");
+
+  // The template references the fields, not their names.
+  test.update_field("first", |update| {
+    update.set_name("Given name");
+  });
+  assert_eq!(
+    test.get_primary_template().unwrap(),
+    "{Last name}, {Given name}"
+  );
+  assert_eq!(test.refresh_primary_cells(true).await, 0);
+
+  // The cells generated so far are kept.
+  test.remove_primary_template();
+  assert!(test.get_primary_template().is_none());
+  test
+    .update_row(turing.clone(), |update| {
+      update.update_cells(|cells_update| {
+        cells_update.insert_cell("first", TestTextCell::from("A.").into());
+      });
+    })
+    .await;
+  assert_eq!(primary_text(&test, &turing).await, "Turing, Alan");
+}
+
+#[tokio::test]
+async fn invalid_primary_template_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut test = create_database(1, &database_id);
+  create_text_field(&mut test, "name", "Name", true);
+  create_text_field(&mut test, "last", "Last name", false);
+  for template in ["{Last name} {Nickname}", "{Name} {Last name}"] {
+    assert!(
+      test
+        .set_primary_template(template, true)
+        .await
+        .unwrap_err()
+        .is_no_required_data()
+    );
+  }
+  assert!(test.get_primary_template().is_none());
+}
+
+fn status_cell(option_ids: &[&str]) -> Cell {
+  SelectOptionIds::from(
+    option_ids
+      .iter()
+      .map(|id| id.to_string())
+      .collect::<Vec<_>>(),
+  )
+  .to_cell(FieldType::MultiSelect)
+}
+
+fn status_type_option(options: &[(&str, &str)]) -> SelectTypeOption {
+  SelectTypeOption {
+    options: options
+      .iter()
+      .map(|(id, name)| SelectOption {
+        id: id.to_string(),
+        name: name.to_string(),
+        color: SelectOptionColor::default(),
+      })
+      .collect(),
+    disable_color: false,
+  }
+}
+
+#[tokio::test]
+async fn primary_template_refresh_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut test = create_database(1, &database_id);
+  create_text_field(&mut test, "name", "Name", true);
+  create_text_field(&mut test, "last", "Last name", false);
+  let field = Field::new(
+    "status".to_string(),
+    "Status".to_string(),
+    FieldType::MultiSelect.into(),
+    false,
+  )
+  .with_type_option_data(
+    FieldType::MultiSelect.type_id(),
+    status_type_option(&[("o1", "Done"), ("o2", "done ")]).into(),
+  );
+  test.create_field(
+    None,
+    field,
+    &OrderObjectPosition::default(),
+    default_field_settings_by_layout(),
+  );
+  let cells = Cells::from([
+    ("last".to_string(), TestTextCell::from("Lovelace").into()),
+    ("status".to_string(), status_cell(&["o2"])),
+  ]);
+  let params = CreateRowParams::new(uuid::Uuid::new_v4(), test.get_database_id()).with_cells(cells);
+  let lovelace = test.create_row(params).await.unwrap().id;
+  test
+    .set_primary_template("{Last name} {Status}", true)
+    .await
+    .unwrap();
+  assert_eq!(primary_text(&test, &lovelace).await, "Lovelace done");
+
+  // Merging the select options generates the primary cells of the rewritten rows.
+  test
+    .merge_select_options("status", &["o1".to_string(), "o2".to_string()], "o1", true)
+    .await
+    .unwrap();
+  assert_eq!(primary_text(&test, &lovelace).await, "Lovelace Done");
+
+  // Renaming a select option isn't followed until the primary cells are refreshed.
+  test.update_field("status", |update| {
+    update.set_type_option(
+      FieldType::MultiSelect.into(),
+      Some(status_type_option(&[("o1", "Finished")]).into()),
+    );
+  });
+  assert_eq!(primary_text(&test, &lovelace).await, "Lovelace Done");
+  assert_eq!(test.refresh_primary_cells(true).await, 1);
+  assert_eq!(primary_text(&test, &lovelace).await, "Lovelace Finished");
+
+  // Neither are the edits made through the row itself.
+  let database_row = test.get_database_row(&lovelace).await.unwrap();
+  database_row.write().await.update(|update| {
+    update.update_cells(|cells_update| {
+      cells_update.insert_cell("last", TestTextCell::from("King").into());
+    });
+  });
+  assert_eq!(primary_text(&test, &lovelace).await, "Lovelace Finished");
+  assert_eq!(test.refresh_primary_cells(true).await, 1);
+  assert_eq!(primary_text(&test, &lovelace).await, "King Finished");
+}
@@ -0,0 +1,179 @@
+use std::time::Duration;
+
+use collab::util::AnyMapExt;
+use collab_database::rows::{Cells, Row};
+use collab_database::views::{QueryChange, QueryResultSet, QuerySubscription, RowQuery};
+use tokio::time::timeout;
+
+use crate::database_test::helper::create_database_with_default_data;
+use crate::helper::TestTextCell;
+
+fn text_row(text: &str) -> Row {
+  let mut row = Row::new(uuid::Uuid::new_v4(), "d1");
+  row.cells = Cells::from([("f1".to_string(), TestTextCell::from(text).into())]);
+  row
+}
+
+fn text_of(row: &Row) -> String {
+  row
+    .cells
+    .get("f1")
+    .and_then(|cell| cell.get_as::<String>("data"))
+    .unwrap_or_default()
+}
+
+fn text_query() -> RowQuery {
+  RowQuery::new()
+    .with_filter(|row| !text_of(row).starts_with('x'))
+    .with_sort(|a, b| text_of(a).cmp(&text_of(b)))
+}
+
+fn result_texts(result_set: &QueryResultSet) -> Vec<String> {
+  result_set.rows().iter().map(text_of).collect()
+}
+
+#[test]
+fn query_result_set_filter_and_sort_test() {
+  let rows = vec![text_row("c"), text_row("x1"), text_row("a"), text_row("b")];
+  let result_set = QueryResultSet::new(text_query(), rows);
+  assert_eq!(result_texts(&result_set), vec!["a", "b", "c"]);
+}
+
+#[test]
+fn query_result_set_update_row_test() {
+  let rows = vec![text_row("c"), text_row("x1"), text_row("a"), text_row("b")];
+  let ids = rows.iter().map(|row| row.id.clone()).collect::<Vec<_>>();
+  let mut result_set = QueryResultSet::new(text_query(), rows.clone());
+
+  // The row starts to match the filter
+  let mut row = rows[1].clone();
+  row.cells = text_row("bb").cells;
+  let changes = result_set.update_row(row.clone());
+  assert_eq!(changes, vec![QueryChange::Inserted { row, index: 2 }]);
+  assert_eq!(result_texts(&result_set), vec!["a", "b", "bb", "c"]);
+
+  // The row moves
+  let mut row = rows[0].clone();
+  row.cells = text_row("0").cells;
+  let changes = result_set.update_row(row.clone());
+  assert_eq!(
+    changes,
+    vec![
+      QueryChange::Moved {
+        row_id: ids[0].clone(),
+        from: 3,
+        to: 0
+      },
+      QueryChange::Updated { row, index: 0 },
+    ]
+  );
+
+  // The row stops matching the filter
+  let mut row = rows[2].clone();
+  row.cells = text_row("xa").cells;
+  let changes = result_set.update_row(row);
+  assert_eq!(
+    changes,
+    vec![QueryChange::Removed {
+      row_id: ids[2].clone(),
+      index: 1
+    }]
+  );
+  assert_eq!(result_texts(&result_set), vec!["0", "b", "bb"]);
+
+  // The rows that are not in the view are ignored
+  assert!(result_set.update_row(text_row("a")).is_empty());
+}
+
+#[test]
+fn query_result_set_update_view_rows_test() {
+  let rows = vec![text_row("a"), text_row("b"), text_row("c")];
+  let ids = rows.iter().map(|row| row.id.clone()).collect::<Vec<_>>();
+  let mut result_set = QueryResultSet::new(RowQuery::new(), rows);
+
+  // Remove the first row, move the last one to the front and insert a new row at the end
+  let new_row = text_row("d");
+  let row_ids = vec![ids[2].clone(), ids[1].clone(), new_row.id.clone()];
+  let changes = result_set.update_view_rows(row_ids.clone(), vec![new_row.clone()]);
+  assert_eq!(
+    changes,
+    vec![
+      QueryChange::Removed {
+        row_id: ids[0].clone(),
+        index: 0
+      },
+      QueryChange::Moved {
+        row_id: ids[2].clone(),
+        from: 1,
+        to: 0
+      },
+      QueryChange::Inserted {
+        row: new_row,
+        index: 2
+      },
+    ]
+  );
+  assert_eq!(result_set.row_ids(), row_ids.as_slice());
+}
+
+#[tokio::test]
+async fn subscribe_query_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut database_test = create_database_with_default_data(1, &database_id).await;
+  let row_ids = database_test.pre_define_row_ids.clone();
+  let query = RowQuery::new().with_filter(|row| row.cells.contains_key("f2"));
+  let mut subscription = database_test
+    .get_view("v1")
+    .unwrap()
+    .subscribe(&database_test, query)
+    .await
+    .unwrap();
+  assert_eq!(
+    subscription.result_set().row_ids(),
+    &[row_ids[0].clone(), row_ids[1].clone()]
+  );
+
+  // The third row enters the result when its f2 cell is written
+  database_test
+    .update_row(row_ids[2].clone(), |row| {
+      row.update_cells(|cells| {
+        cells.insert("f2", TestTextCell::from("3f2cell"));
+      });
+    })
+    .await;
+  let changes = next_changes(&mut subscription).await;
+  assert!(matches!(
+    changes.as_slice(),
+    [QueryChange::Inserted { row, index: 2 }] if row.id == row_ids[2]
+  ));
+
+  // The first row leaves the result when it's removed from the view
+  database_test.remove_row(&row_ids[0]).await;
+  let removed = loop {
+    let changes = next_changes(&mut subscription).await;
+    if let Some(change) = changes
+      .into_iter()
+      .find(|change| matches!(change, QueryChange::Removed { .. }))
+    {
+      break change;
+    }
+  };
+  assert_eq!(
+    removed,
+    QueryChange::Removed {
+      row_id: row_ids[0].clone(),
+      index: 0
+    }
+  );
+  assert_eq!(
+    subscription.result_set().row_ids(),
+    &[row_ids[1].clone(), row_ids[2].clone()]
+  );
+}
+
+async fn next_changes(subscription: &mut QuerySubscription) -> Vec<QueryChange> {
+  timeout(Duration::from_secs(2), subscription.next_changes())
+    .await
+    .unwrap()
+    .unwrap()
+}
@@ -0,0 +1,182 @@
+use std::collections::{HashMap, HashSet};
+
+use async_trait::async_trait;
+use collab_database::database_trait::DatabaseReferenceResolver;
+use collab_database::entity::FieldType;
+use collab_database::error::DatabaseError;
+use collab_database::fields::relation_type_option::{DatabaseReference, RelationTypeOption};
+use collab_database::fields::{Field, TypeOptionData};
+use collab_database::rows::{Cells, CreateRowParams, Row, RowId};
+use collab_database::template::relation_parse::RelationCellData;
+use collab_database::views::OrderObjectPosition;
+use uuid::Uuid;
+
+use crate::database_test::helper::{
+  DatabaseTest, create_database, default_field_settings_by_layout,
+};
+
+const WORKSPACE_ID: &str = "w1";
+
+#[derive(Default)]
+struct TestReferenceResolver {
+  rows: HashMap<DatabaseReference, Vec<Row>>,
+  denied_workspaces: HashSet<String>,
+}
+
+#[async_trait]
+impl DatabaseReferenceResolver for TestReferenceResolver {
+  async fn get_referenced_rows(
+    &self,
+    reference: &DatabaseReference,
+    row_ids: &[RowId],
+  ) -> Result<Vec<Row>, DatabaseError> {
+    if self.denied_workspaces.contains(&reference.workspace_id) {
+      return Err(DatabaseError::WorkspaceAccessDenied {
+        workspace_id: reference.workspace_id.clone(),
+        database_id: reference.database_id.clone(),
+      });
+    }
+    let rows = self
+      .rows
+      .get(reference)
+      .ok_or_else(|| DatabaseError::ReferenceUnavailable {
+        workspace_id: reference.workspace_id.clone(),
+        database_id: reference.database_id.clone(),
+        reason: "not loaded".to_string(),
+      })?;
+    Ok(
+      rows
+        .iter()
+        .filter(|row| row_ids.contains(&row.id))
+        .cloned()
+        .collect(),
+    )
+  }
+}
+
+fn create_relation_field(test: &mut DatabaseTest, field_id: &str, type_option: RelationTypeOption) {
+  let mut field = Field::new(
+    field_id.to_string(),
+    "relation".to_string(),
+    FieldType::Relation.into(),
+    false,
+  );
+  field
+    .type_options
+    .insert(FieldType::Relation.type_id(), type_option.into());
+  test.create_field(
+    None,
+    field,
+    &OrderObjectPosition::default(),
+    default_field_settings_by_layout(),
+  );
+}
+
+async fn create_relation_row(
+  test: &mut DatabaseTest,
+  field_id: &str,
+  row_ids: Vec<RowId>,
+) -> RowId {
+  let database_id = test.get_database_id();
+  let params = CreateRowParams::new(Uuid::new_v4(), database_id).with_cells(Cells::from([(
+    field_id.to_string(),
+    RelationCellData { row_ids }.into(),
+  )]));
+  test.create_row(params).await.unwrap().id
+}
+
+#[test]
+fn relation_type_option_workspace_test() {
+  let type_option = RelationTypeOption::new("d2");
+  assert!(!type_option.is_cross_workspace(WORKSPACE_ID));
+  assert_eq!(
+    type_option.database_reference(WORKSPACE_ID),
+    DatabaseReference {
+      workspace_id: WORKSPACE_ID.to_string(),
+      database_id: "d2".to_string(),
+    }
+  );
+
+  let type_option = RelationTypeOption::new("d2").with_workspace_id("w2");
+  assert!(type_option.is_cross_workspace(WORKSPACE_ID));
+  assert!(!type_option.is_cross_workspace("w2"));
+
+  // The workspace id survives the round trip through the type option data
+  let type_option = RelationTypeOption::from(TypeOptionData::from(type_option));
+  assert_eq!(type_option.workspace_id.as_deref(), Some("w2"));
+  assert_eq!(
+    RelationTypeOption::from(TypeOptionData::from(RelationTypeOption::new("d2"))).workspace_id,
+    None
+  );
+}
+
+#[tokio::test]
+async fn get_cross_workspace_related_rows_test() {
+  let database_id = Uuid::new_v4().to_string();
+  let mut test = create_database(1, &database_id);
+  create_relation_field(
+    &mut test,
+    "rel",
+    RelationTypeOption::new("d2").with_workspace_id("w2"),
+  );
+
+  let related_rows = (0..3)
+    .map(|_| Row::new(Uuid::new_v4(), "d2"))
+    .collect::<Vec<_>>();
+  let row_id = create_relation_row(
+    &mut test,
+    "rel",
+    vec![related_rows[2].id.clone(), related_rows[0].id.clone()],
+  )
+  .await;
+
+  let reference = test.get_relation_reference("rel", WORKSPACE_ID).unwrap();
+  assert_eq!(reference.workspace_id, "w2");
+  assert_eq!(reference.database_id, "d2");
+
+  let resolver = TestReferenceResolver {
+    rows: HashMap::from([(reference.clone(), related_rows.clone())]),
+    ..Default::default()
+  };
+  let rows = test
+    .get_related_rows("rel", &row_id, WORKSPACE_ID, &resolver)
+    .await
+    .unwrap();
+  assert_eq!(
+    rows.iter().map(|row| row.id.clone()).collect::<Vec<_>>(),
+    vec![related_rows[2].id.clone(), related_rows[0].id.clone()]
+  );
+}
+
+#[tokio::test]
+async fn related_rows_access_denied_test() {
+  let database_id = Uuid::new_v4().to_string();
+  let mut test = create_database(1, &database_id);
+  create_relation_field(
+    &mut test,
+    "rel",
+    RelationTypeOption::new("d2").with_workspace_id("w2"),
+  );
+  let row_id = create_relation_row(&mut test, "rel", vec![RowId::from(Uuid::new_v4())]).await;
+
+  let resolver = TestReferenceResolver {
+    denied_workspaces: HashSet::from(["w2".to_string()]),
+    ..Default::default()
+  };
+  let err = test
+    .get_related_rows("rel", &row_id, WORKSPACE_ID, &resolver)
+    .await
+    .unwrap_err();
+  assert!(err.is_access_denied());
+
+  // An empty cell doesn't need the related database
+  let row_id = create_relation_row(&mut test, "rel", vec![]).await;
+  let rows = test
+    .get_related_rows("rel", &row_id, WORKSPACE_ID, &resolver)
+    .await
+    .unwrap();
+  assert!(rows.is_empty());
+
+  // Only the relation fields reference a database
+  assert!(test.get_relation_reference("f1", WORKSPACE_ID).is_none());
+}
@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use collab_database::rows::RowId;
+use collab_database::views::{QueryChange, RowQuery};
+use tokio::time::timeout;
+
+use crate::database_test::helper::{DatabaseTest, create_database_with_default_data};
+
+async fn view_row_ids(test: &DatabaseTest) -> Vec<RowId> {
+  test
+    .get_rows_for_view("v1")
+    .await
+    .into_iter()
+    .map(|row| row.id)
+    .collect()
+}
+
+#[tokio::test]
+async fn archive_rows_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut test = create_database_with_default_data(1, &database_id).await;
+  let row_ids = test.pre_define_row_ids.clone();
+
+  test.archive_rows(&[row_ids[1].clone()]).await;
+  assert!(test.get_row(&row_ids[1]).await.archived);
+  assert_eq!(
+    view_row_ids(&test).await,
+    vec![row_ids[0].clone(), row_ids[2].clone()]
+  );
+  // The archived rows are kept in the view, they are not deleted.
+  assert_eq!(test.get_row_orders_for_view("v1").len(), 3);
+  // The index skips the archived rows unless they are included.
+  let row_order = test.get_row_order_at_index("v1", 1, false).await.unwrap();
+  assert_eq!(row_order.id, row_ids[2]);
+  let row_order = test.get_row_order_at_index("v1", 1, true).await.unwrap();
+  assert_eq!(row_order.id, row_ids[1]);
+  assert!(test.get_row_order_at_index("v1", 2, false).await.is_none());
+  // The row indexes count the same rows.
+  assert_eq!(test.get_row_index("v1", &row_ids[2], false).await, Some(1));
+  assert_eq!(test.get_row_index("v1", &row_ids[2], true).await, Some(2));
+  assert_eq!(test.get_row_index("v1", &row_ids[1], false).await, None);
+  assert_eq!(test.get_row_index("v1", &row_ids[1], true).await, Some(1));
+
+  let cells = test.get_cells_for_field("v1", "f1", false).await;
+  let cell_row_ids = cells
+    .into_iter()
+    .map(|cell| cell.row_id)
+    .collect::<Vec<_>>();
+  assert_eq!(cell_row_ids, vec![row_ids[0].clone(), row_ids[2].clone()]);
+
+  let archived = test.get_archived_rows_for_view("v1", false).await;
+  assert_eq!(archived.len(), 1);
+  assert_eq!(archived[0].id, row_ids[1]);
+
+  test.unarchive_rows(&[row_ids[1].clone()]).await;
+  assert!(!test.get_row(&row_ids[1]).await.archived);
+  assert_eq!(view_row_ids(&test).await, row_ids);
+  assert!(
+    test
+      .get_archived_rows_for_view("v1", false)
+      .await
+      .is_empty()
+  );
+}
+
+#[tokio::test]
+async fn archive_rows_by_query_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut test = create_database_with_default_data(1, &database_id).await;
+  let row_ids = test.pre_define_row_ids.clone();
+
+  let query = RowQuery::new().with_filter(|row| row.cells.contains_key("f3"));
+  let archived = test.archive_rows_by_query("v1", &query, false).await;
+  assert_eq!(archived, vec![row_ids[0].clone(), row_ids[2].clone()]);
+  assert_eq!(view_row_ids(&test).await, vec![row_ids[1].clone()]);
+
+  // The archived rows don't match the queries, unless they include them.
+  assert!(
+    test
+      .archive_rows_by_query("v1", &query, false)
+      .await
+      .is_empty()
+  );
+  let query = RowQuery::new()
+    .with_archived(true)
+    .with_filter(|row| row.archived);
+  let subscription = test
+    .get_view("v1")
+    .unwrap()
+    .subscribe(&test, query)
+    .await
+    .unwrap();
+  assert_eq!(
+    subscription.result_set().row_ids(),
+    &[row_ids[0].clone(), row_ids[2].clone()]
+  );
+}
+
+#[tokio::test]
+async fn archived_row_leaves_query_result_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut test = create_database_with_default_data(1, &database_id).await;
+  let row_ids = test.pre_define_row_ids.clone();
+  let mut subscription = test
+    .get_view("v1")
+    .unwrap()
+    .subscribe(&test, RowQuery::new())
+    .await
+    .unwrap();
+  assert_eq!(subscription.result_set().row_ids(), row_ids.as_slice());
+
+  test.archive_rows(&[row_ids[0].clone()]).await;
+  let changes = timeout(Duration::from_secs(2), subscription.next_changes())
+    .await
+    .unwrap()
+    .unwrap();
+  assert_eq!(
+    changes,
+    vec![QueryChange::Removed {
+      row_id: row_ids[0].clone(),
+      index: 0
+    }]
+  );
+}
@@ -0,0 +1,101 @@
+use collab_database::blocks::RowChunks;
+use collab_database::rows::{CreateRowParams, RowId};
+use collab_database::views::RowOrder;
+use uuid::Uuid;
+
+use crate::database_test::helper::create_database;
+
+fn row_orders(count: usize) -> Vec<RowOrder> {
+  (0..count)
+    .map(|_| RowOrder::new(Uuid::new_v4().into(), 0))
+    .collect()
+}
+
+#[test]
+fn row_chunks_split_test() {
+  let orders = row_orders(25);
+  let chunks = RowChunks::new(orders.clone(), 10);
+  assert_eq!(chunks.num_of_chunks(), 3);
+  assert_eq!(chunks.num_of_rows(), 25);
+  assert_eq!(chunks.get_chunk(2).unwrap().range(), 20..25);
+  assert_eq!(chunks.chunk_index_of_row(&orders[14].id), Some(1));
+  assert_eq!(chunks.chunk_index_of_row(&Uuid::new_v4().into()), None);
+}
+
+#[test]
+fn row_chunks_for_range_test() {
+  let chunks = RowChunks::new(row_orders(25), 10);
+  let indexes = |range| {
+    chunks
+      .chunks_for_range(range)
+      .iter()
+      .map(|chunk| chunk.index)
+      .collect::<Vec<_>>()
+  };
+  assert_eq!(indexes(0..10), vec![0]);
+  assert_eq!(indexes(9..11), vec![0, 1]);
+  assert_eq!(indexes(15..100), vec![1, 2]);
+  assert!(indexes(30..40).is_empty());
+  assert!(indexes(5..5).is_empty());
+}
+
+#[test]
+fn empty_row_chunks_test() {
+  let chunks = RowChunks::new(vec![], 0);
+  assert_eq!(chunks.chunk_size(), 1);
+  assert_eq!(chunks.num_of_chunks(), 0);
+  assert!(chunks.chunks_for_range(0..10).is_empty());
+}
+
+#[tokio::test]
+async fn get_rows_in_range_test() {
+  let database_id = Uuid::new_v4().to_string();
+  let mut database_test = create_database(1, &database_id);
+  let mut row_ids = vec![];
+  for _ in 0..25 {
+    let row_id = Uuid::new_v4();
+    database_test
+      .create_row_in_view("v1", CreateRowParams::new(row_id, database_id.clone()))
+      .await
+      .unwrap();
+    row_ids.push(row_id.to_string());
+  }
+
+  let chunks = database_test.get_row_chunks_for_view("v1", 10);
+  assert_eq!(chunks.num_of_chunks(), 3);
+
+  let rows = database_test
+    .get_rows_in_range("v1", 8..13, 10, true, false)
+    .await
+    .unwrap();
+  let ids = rows
+    .iter()
+    .map(|row| row.id.to_string())
+    .collect::<Vec<_>>();
+  assert_eq!(ids, row_ids[8..13].to_vec());
+
+  let rows = database_test
+    .get_rows_in_range("v1", 20..50, 10, true, false)
+    .await
+    .unwrap();
+  assert_eq!(rows.len(), 5);
+
+  // The archived rows of the range are skipped unless they are included.
+  database_test
+    .archive_rows(&[RowId::from(row_ids[9].clone())])
+    .await;
+  let rows = database_test
+    .get_rows_in_range("v1", 8..13, 10, false, false)
+    .await
+    .unwrap();
+  let ids = rows
+    .iter()
+    .map(|row| row.id.to_string())
+    .collect::<Vec<_>>();
+  assert_eq!(ids, [&row_ids[8..9], &row_ids[10..13]].concat());
+  let rows = database_test
+    .get_rows_in_range("v1", 8..13, 10, true, false)
+    .await
+    .unwrap();
+  assert_eq!(rows.len(), 5);
+}
@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use collab_database::error::DatabaseError;
+use collab_database::rows::{RowChange, RowComment, RowId};
+use tokio::time::timeout;
+
+use crate::database_test::helper::create_database_with_default_data;
+
+#[tokio::test]
+async fn row_comment_threads_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut test = create_database_with_default_data(1, &database_id).await;
+  let row_id = test.pre_define_row_ids[0].clone();
+
+  let first = RowComment::new(1, "Can we ship it on Friday?");
+  let second = RowComment::new(2, "The icon is missing");
+  let reply = RowComment::new(2, "Yes").reply_to(&first.id);
+  for comment in [first.clone(), second.clone(), reply.clone()] {
+    test.add_row_comment(&row_id, comment).await.unwrap();
+  }
+  // A reply to a reply, or to a comment of another row, is rejected.
+  assert!(
+    test
+      .add_row_comment(&row_id, RowComment::new(1, "Great").reply_to(&reply.id))
+      .await
+      .unwrap_err()
+      .is_no_required_data()
+  );
+
+  let threads = test.get_row_comments(&row_id).await;
+  assert_eq!(threads.len(), 2);
+  assert_eq!(threads[0].comment, first);
+  assert_eq!(threads[0].replies, vec![reply.clone()]);
+  assert_eq!(threads[1].comment, second);
+  assert_eq!(test.get_row_meta(&row_id).await.unwrap().comment_count, 3);
+  let other_row_id = test.pre_define_row_ids[1].clone();
+  assert_eq!(
+    test
+      .get_row_meta(&other_row_id)
+      .await
+      .unwrap()
+      .comment_count,
+    0
+  );
+
+  test
+    .edit_row_comment(&row_id, &reply.id, "Yes, after the review")
+    .await
+    .unwrap();
+  test
+    .resolve_row_comment_thread(&row_id, &first.id, true)
+    .await
+    .unwrap();
+  assert!(
+    test
+      .resolve_row_comment_thread(&row_id, &reply.id, true)
+      .await
+      .unwrap_err()
+      .is_no_required_data()
+  );
+  let threads = test.get_row_comments(&row_id).await;
+  assert!(threads[0].comment.resolved);
+  assert_eq!(threads[0].replies[0].content, "Yes, after the review");
+  assert!(threads[0].replies[0].edited_at > 0);
+
+  // Removing the first comment of a thread removes its replies.
+  let removed = test.remove_row_comment(&row_id, &first.id).await.unwrap();
+  assert_eq!(removed.len(), 2);
+  let threads = test.get_row_comments(&row_id).await;
+  assert_eq!(threads.len(), 1);
+  assert_eq!(threads[0].comment, second);
+  assert_eq!(test.get_row_meta(&row_id).await.unwrap().comment_count, 1);
+  assert!(
+    test
+      .remove_row_comment(&row_id, &first.id)
+      .await
+      .unwrap_err()
+      .is_no_required_data()
+  );
+
+  assert!(matches!(
+    test
+      .add_row_comment(&RowId::from(uuid::Uuid::new_v4()), RowComment::new(1, "Hi"))
+      .await,
+    Err(DatabaseError::DatabaseRowNotFound { .. })
+  ));
+}
+
+#[tokio::test]
+async fn row_comment_change_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut test = create_database_with_default_data(1, &database_id).await;
+  let row_id = test.pre_define_row_ids[0].clone();
+  let mut row_change_rx = test.subscribe_row_change().unwrap();
+
+  let comment = RowComment::new(1, "Hello @Lucas");
+  test
+    .add_row_comment(&row_id, comment.clone())
+    .await
+    .unwrap();
+  let change = timeout(Duration::from_secs(2), row_change_rx.recv())
+    .await
+    .unwrap()
+    .unwrap();
+  match change {
+    RowChange::DidUpdateRowComment {
+      row_id: changed_row_id,
+      inserted,
+      comment_count,
+    } => {
+      assert_eq!(changed_row_id, row_id);
+      assert_eq!(inserted, vec![comment.clone()]);
+      assert_eq!(comment_count, 1);
+    },
+    change => panic!("unexpected change: {:?}", change),
+  }
+
+  test.remove_row_comment(&row_id, &comment.id).await.unwrap();
+  let change = timeout(Duration::from_secs(2), row_change_rx.recv())
+    .await
+    .unwrap()
+    .unwrap();
+  assert!(matches!(
+    change,
+    RowChange::DidUpdateRowComment {
+      comment_count: 0,
+      ..
+    }
+  ));
+}
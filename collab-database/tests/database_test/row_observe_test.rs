@@ -150,3 +150,45 @@ async fn observer_update_row_test() {
   .await
   .unwrap();
 }
+
+#[tokio::test]
+async fn observer_single_row_change_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut database_test = create_database(1, &database_id);
+  let first_row_id = gen_row_id();
+  let second_row_id = gen_row_id();
+  for row_id in [&first_row_id, &second_row_id] {
+    database_test
+      .create_row(CreateRowParams::new(row_id.clone(), database_id.clone()))
+      .await
+      .unwrap();
+  }
+
+  let mut first_rx = database_test
+    .subscribe_row_change_by_id(&first_row_id)
+    .await
+    .unwrap();
+  let mut second_rx = database_test
+    .subscribe_row_change_by_id(&second_row_id)
+    .await
+    .unwrap();
+
+  database_test
+    .update_row(first_row_id.clone(), |row| {
+      row.set_height(100);
+    })
+    .await;
+
+  let change = tokio::time::timeout(Duration::from_secs(2), first_rx.recv())
+    .await
+    .unwrap()
+    .unwrap();
+  match change {
+    RowChange::DidUpdateHeight { row_id, value } => {
+      assert_eq!(row_id, first_row_id);
+      assert_eq!(value, 100);
+    },
+    change => panic!("unexpected change: {:?}", change),
+  }
+  assert!(second_rx.try_recv().is_err());
+}
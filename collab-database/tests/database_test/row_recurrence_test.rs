@@ -0,0 +1,181 @@
+use std::str::FromStr;
+
+use chrono::{NaiveDate, Weekday};
+use collab_database::error::DatabaseError;
+use collab_database::fields::date_type_option::DateCellData;
+use collab_database::recurrence::{RecurrenceFrequency, RecurrenceRule, RowRecurrence};
+use collab_database::rows::Cells;
+
+use crate::database_test::helper::create_database;
+use crate::helper::TestTextCell;
+
+fn timestamp(year: i32, month: u32, day: u32, hour: u32) -> i64 {
+  NaiveDate::from_ymd_opt(year, month, day)
+    .unwrap()
+    .and_hms_opt(hour, 0, 0)
+    .unwrap()
+    .and_utc()
+    .timestamp()
+}
+
+#[test]
+fn parse_recurrence_rule_test() {
+  let rule = RecurrenceRule::from_str("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;COUNT=10").unwrap();
+  assert_eq!(rule.frequency, RecurrenceFrequency::Weekly);
+  assert_eq!(rule.interval, 2);
+  assert_eq!(rule.count, Some(10));
+  assert_eq!(rule.by_day, vec![Weekday::Mon, Weekday::Thu]);
+  assert_eq!(
+    rule.to_string(),
+    "FREQ=WEEKLY;INTERVAL=2;COUNT=10;BYDAY=MO,TH"
+  );
+
+  let rule = RecurrenceRule::from_str("FREQ=DAILY;UNTIL=20240105").unwrap();
+  assert_eq!(rule.until, Some(timestamp(2024, 1, 5, 23) + 59 * 60 + 59));
+
+  for invalid in [
+    "INTERVAL=2",
+    "FREQ=HOURLY",
+    "FREQ=DAILY;BYMONTH=1",
+    "FREQ=DAILY;INTERVAL=0",
+  ] {
+    assert!(matches!(
+      RecurrenceRule::from_str(invalid),
+      Err(DatabaseError::InvalidRecurrenceRule(_))
+    ));
+  }
+}
+
+#[test]
+fn recurrence_rule_occurrences_test() {
+  let start = timestamp(2024, 1, 1, 9);
+  let end = timestamp(2024, 12, 31, 0);
+
+  let rule = RecurrenceRule::from_str("FREQ=DAILY;COUNT=3").unwrap();
+  assert_eq!(
+    rule.occurrences(start, end),
+    vec![start, timestamp(2024, 1, 2, 9), timestamp(2024, 1, 3, 9)]
+  );
+
+  // 2024-01-01 is a Monday.
+  let rule = RecurrenceRule::from_str("FREQ=WEEKLY;INTERVAL=2;BYDAY=WE,MO").unwrap();
+  assert_eq!(
+    rule.occurrences(start, timestamp(2024, 1, 20, 0)),
+    vec![
+      start,
+      timestamp(2024, 1, 3, 9),
+      timestamp(2024, 1, 15, 9),
+      timestamp(2024, 1, 17, 9),
+    ]
+  );
+
+  // The months without a 31st are skipped.
+  let rule = RecurrenceRule::from_str("FREQ=MONTHLY;COUNT=3").unwrap();
+  assert_eq!(
+    rule.occurrences(timestamp(2024, 1, 31, 9), end),
+    vec![
+      timestamp(2024, 1, 31, 9),
+      timestamp(2024, 3, 31, 9),
+      timestamp(2024, 5, 31, 9),
+    ]
+  );
+
+  let rule = RecurrenceRule::from_str("FREQ=YEARLY").unwrap();
+  assert_eq!(
+    rule.occurrences(timestamp(2024, 2, 29, 9), timestamp(2032, 1, 1, 0)),
+    vec![timestamp(2024, 2, 29, 9), timestamp(2028, 2, 29, 9)]
+  );
+}
+
+#[tokio::test]
+async fn materialize_due_rows_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut test = create_database(1, &database_id);
+  let start = timestamp(2024, 1, 1, 9);
+
+  let mut cells = Cells::new();
+  cells.insert("f1".to_string(), TestTextCell::from("weekly review").into());
+  let rule = RecurrenceRule::from_str("FREQ=DAILY;INTERVAL=7").unwrap();
+  let recurrence = RowRecurrence::new(&rule, start)
+    .with_cells(cells)
+    .with_date_field("f2");
+  test.upsert_row_recurrence(recurrence.clone()).unwrap();
+  let recurrences = test.get_row_recurrences();
+  assert_eq!(recurrences.len(), 1);
+  assert_eq!(recurrences[0].id, recurrence.id);
+  assert_eq!(recurrences[0].rrule, "FREQ=DAILY;INTERVAL=7");
+
+  // Nothing is due before the start.
+  let rows = test.materialize_due_rows(start - 1).await.unwrap();
+  assert!(rows.is_empty());
+
+  let rows = test
+    .materialize_due_rows(timestamp(2024, 1, 10, 0))
+    .await
+    .unwrap();
+  assert_eq!(rows.len(), 2);
+  assert_eq!(rows[0].id, recurrence.occurrence_row_id(start));
+  assert_eq!(test.get_row_orders_for_view("v1").len(), 2);
+
+  let row = test.get_row(&rows[1].id).await;
+  let text = TestTextCell::from(row.cells.get("f1").unwrap().clone());
+  assert_eq!(text.0, "weekly review");
+  let date = DateCellData::from(row.cells.get("f2").unwrap());
+  assert_eq!(date.timestamp, Some(timestamp(2024, 1, 8, 9)));
+
+  // Materializing again creates the new occurrences only.
+  let rows = test
+    .materialize_due_rows(timestamp(2024, 1, 10, 0))
+    .await
+    .unwrap();
+  assert!(rows.is_empty());
+  let rows = test
+    .materialize_due_rows(timestamp(2024, 1, 16, 0))
+    .await
+    .unwrap();
+  assert_eq!(rows.len(), 1);
+  assert_eq!(test.get_row_orders_for_view("v1").len(), 3);
+  assert_eq!(
+    test
+      .get_row_recurrence(&recurrence.id)
+      .unwrap()
+      .materialized_until,
+    Some(timestamp(2024, 1, 15, 9))
+  );
+
+  // A recurrence materialized on another client whose state wasn't synced doesn't create the
+  // same rows twice.
+  test.upsert_row_recurrence(recurrence.clone()).unwrap();
+  let rows = test
+    .materialize_due_rows(timestamp(2024, 1, 16, 0))
+    .await
+    .unwrap();
+  assert!(rows.is_empty());
+  assert_eq!(test.get_row_orders_for_view("v1").len(), 3);
+
+  // The created rows are kept when the recurrence is removed.
+  assert!(test.remove_row_recurrence(&recurrence.id).is_some());
+  assert!(test.get_row_recurrences().is_empty());
+  let rows = test
+    .materialize_due_rows(timestamp(2024, 2, 1, 0))
+    .await
+    .unwrap();
+  assert!(rows.is_empty());
+  assert_eq!(test.get_row_orders_for_view("v1").len(), 3);
+}
+
+#[tokio::test]
+async fn invalid_row_recurrence_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut test = create_database(1, &database_id);
+  let mut recurrence = RowRecurrence::new(
+    &RecurrenceRule::new(RecurrenceFrequency::Daily),
+    timestamp(2024, 1, 1, 9),
+  );
+  recurrence.rrule = "FREQ=SECONDLY".to_string();
+  assert!(matches!(
+    test.upsert_row_recurrence(recurrence),
+    Err(DatabaseError::InvalidRecurrenceRule(_))
+  ));
+  assert!(test.get_row_recurrences().is_empty());
+}
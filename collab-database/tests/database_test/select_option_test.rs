@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use collab_database::entity::FieldType;
+use collab_database::fields::Field;
+use collab_database::fields::select_type_option::{
+  SelectOption, SelectOptionColor, SelectOptionIds, SelectTypeOption,
+};
+use collab_database::rows::{Cells, CreateRowParams, RowId};
+use collab_database::views::{OrderObjectPosition, RowOrder};
+
+use crate::database_test::helper::{
+  DatabaseTest, create_database, default_field_settings_by_layout,
+};
+
+const FIELD_ID: &str = "status";
+
+fn option(id: &str, name: &str) -> SelectOption {
+  SelectOption {
+    id: id.to_string(),
+    name: name.to_string(),
+    color: SelectOptionColor::default(),
+  }
+}
+
+/// A database with a multi select field whose options "Done" and "done " are the same, and a
+/// row for each of the given cells.
+async fn create_select_database(cells: &[&[&str]]) -> (DatabaseTest, Vec<RowId>) {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut test = create_database(1, &database_id);
+  let type_option = SelectTypeOption {
+    options: vec![
+      option("o1", "Done"),
+      option("o2", "done "),
+      option("o3", "Todo"),
+      option("o4", "Doing"),
+    ],
+    disable_color: false,
+  };
+  let field = Field::new(
+    FIELD_ID.to_string(),
+    "Status".to_string(),
+    FieldType::MultiSelect.into(),
+    false,
+  )
+  .with_type_option_data(FieldType::MultiSelect.type_id(), type_option.into());
+  test.create_field(
+    None,
+    field,
+    &OrderObjectPosition::default(),
+    default_field_settings_by_layout(),
+  );
+
+  let mut row_ids = vec![];
+  for option_ids in cells {
+    let option_ids = SelectOptionIds::from(
+      option_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>(),
+    );
+    let params =
+      CreateRowParams::new(uuid::Uuid::new_v4(), database_id.clone()).with_cells(Cells::from([(
+        FIELD_ID.to_string(),
+        option_ids.to_cell(FieldType::MultiSelect),
+      )]));
+    row_ids.push(test.create_row(params).await.unwrap().id);
+  }
+  (test, row_ids)
+}
+
+fn option_ids(test: &DatabaseTest) -> Vec<String> {
+  select_type_option(test)
+    .options
+    .into_iter()
+    .map(|option| option.id)
+    .collect()
+}
+
+fn select_type_option(test: &DatabaseTest) -> SelectTypeOption {
+  test
+    .get_field(FIELD_ID)
+    .unwrap()
+    .get_type_option::<SelectTypeOption>(FieldType::MultiSelect.type_id())
+    .unwrap()
+}
+
+async fn cell_option_ids(test: &DatabaseTest, row_id: &RowId) -> Vec<String> {
+  let cell = test.get_cell(FIELD_ID, row_id).await.cell.unwrap();
+  SelectOptionIds::from(&cell).into_inner()
+}
+
+#[tokio::test]
+async fn merge_select_options_test() {
+  let (mut test, row_ids) = create_select_database(&[&["o1"], &["o2", "o3"], &["o1", "o2"]]).await;
+
+  let similar = select_type_option(&test).similar_options();
+  assert_eq!(similar.len(), 1);
+  let similar_ids = similar[0]
+    .iter()
+    .map(|option| option.id.clone())
+    .collect::<Vec<_>>();
+  assert_eq!(similar_ids, vec!["o1", "o2"]);
+
+  let updated = test
+    .merge_select_options(FIELD_ID, &similar_ids, "o1", false)
+    .await
+    .unwrap();
+  assert_eq!(updated, 2);
+  assert_eq!(option_ids(&test), vec!["o1", "o3", "o4"]);
+  assert_eq!(cell_option_ids(&test, &row_ids[0]).await, vec!["o1"]);
+  assert_eq!(cell_option_ids(&test, &row_ids[1]).await, vec!["o1", "o3"]);
+  // The merged option is selected once.
+  assert_eq!(cell_option_ids(&test, &row_ids[2]).await, vec!["o1"]);
+
+  assert!(
+    test
+      .merge_select_options(FIELD_ID, &["o3".to_string()], "o2", false)
+      .await
+      .unwrap_err()
+      .is_no_required_data()
+  );
+}
+
+#[tokio::test]
+async fn remove_unused_select_options_test() {
+  let (mut test, _) = create_select_database(&[&["o1"], &["o1", "o3"]]).await;
+  let removed = test
+    .remove_unused_select_options(FIELD_ID, false)
+    .await
+    .unwrap()
+    .into_iter()
+    .map(|option| option.id)
+    .collect::<Vec<_>>();
+  assert_eq!(removed, vec!["o2", "o4"]);
+  assert_eq!(option_ids(&test), vec!["o1", "o3"]);
+
+  assert!(
+    test
+      .remove_unused_select_options(FIELD_ID, false)
+      .await
+      .unwrap()
+      .is_empty()
+  );
+}
+
+#[tokio::test]
+async fn update_select_options_with_missing_row_test() {
+  let (mut test, row_ids) = create_select_database(&[&["o2"]]).await;
+  // The view references a row whose data can't be loaded.
+  {
+    let database = &mut test.database;
+    let mut txn = database.collab.transact_mut();
+    let row_order = RowOrder::new(RowId::from(uuid::Uuid::new_v4()), 0);
+    database
+      .body
+      .views
+      .update_all_views(&mut txn, |_view_id, update| {
+        update.insert_row_order(&row_order, &OrderObjectPosition::default());
+      });
+  }
+
+  let merged = ["o1".to_string(), "o2".to_string()];
+  assert!(
+    test
+      .merge_select_options(FIELD_ID, &merged, "o1", false)
+      .await
+      .is_err()
+  );
+  assert_eq!(option_ids(&test), vec!["o1", "o2", "o3", "o4"]);
+  assert_eq!(cell_option_ids(&test, &row_ids[0]).await, vec!["o2"]);
+
+  assert!(
+    test
+      .remove_unused_select_options(FIELD_ID, false)
+      .await
+      .is_err()
+  );
+  assert_eq!(option_ids(&test), vec!["o1", "o2", "o3", "o4"]);
+}
+
+#[tokio::test]
+async fn recolor_select_options_test() {
+  let (mut test, _) = create_select_database(&[]).await;
+  let colors = HashMap::from([
+    ("o1".to_string(), SelectOptionColor::Green),
+    ("o3".to_string(), SelectOptionColor::Purple),
+    ("unknown".to_string(), SelectOptionColor::Blue),
+  ]);
+  // o3 already has the color.
+  assert_eq!(test.recolor_select_options(FIELD_ID, &colors).unwrap(), 1);
+  let options = select_type_option(&test).options;
+  assert_eq!(options[0].color, SelectOptionColor::Green);
+  assert_eq!(options[2].color, SelectOptionColor::Purple);
+
+  assert!(
+    test
+      .recolor_select_options("unknown", &colors)
+      .unwrap_err()
+      .is_no_required_data()
+  );
+}
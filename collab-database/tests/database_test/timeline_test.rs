@@ -0,0 +1,151 @@
+use crate::database_test::helper::{DatabaseTest, create_database};
+use collab::preclude::Any;
+use collab_database::fields::date_type_option::DateCellData;
+use collab_database::rows::{Cells, CreateRowParams, RowId};
+use collab_database::views::{
+  DatabaseLayout, LayoutSettingBuilder, TimelineLayoutSetting, TimelineScale,
+};
+use uuid::Uuid;
+
+const DAY: i64 = 24 * 60 * 60;
+
+#[tokio::test]
+async fn timeline_layout_setting_round_trip_test() {
+  let mut database_test = create_database(1, &Uuid::new_v4().to_string());
+  let mut setting = TimelineLayoutSetting::new("start".to_string(), "end".to_string());
+  setting.scale = TimelineScale::Month;
+  assert!(setting.add_dependency("r1", "r2"));
+  database_test.insert_layout_setting("v1", &DatabaseLayout::Timeline, setting.clone());
+
+  let saved = database_test
+    .get_layout_setting::<TimelineLayoutSetting>("v1", &DatabaseLayout::Timeline)
+    .unwrap();
+  assert_eq!(saved, setting);
+}
+
+#[test]
+fn invalid_timeline_layout_setting_test() {
+  // A peer wrote an unknown scale and badly shaped dependencies.
+  let setting = LayoutSettingBuilder::from([
+    ("start_field_id".to_string(), Any::from("start")),
+    ("scale".to_string(), Any::BigInt(99)),
+    ("dependencies".to_string(), Any::from("r1 -> r2")),
+  ]);
+  assert_eq!(
+    TimelineLayoutSetting::from(setting),
+    TimelineLayoutSetting::default()
+  );
+}
+
+#[test]
+fn timeline_dependency_test() {
+  let mut setting = TimelineLayoutSetting::new("start".to_string(), String::new());
+  assert!(setting.add_dependency("r1", "r2"));
+  assert!(setting.add_dependency("r2", "r3"));
+  assert!(!setting.add_dependency("r1", "r2"));
+  assert!(!setting.add_dependency("r1", "r1"));
+  // r3 depends on r1 through r2
+  assert!(setting.depends_on("r3", "r1"));
+  assert!(!setting.add_dependency("r3", "r1"));
+
+  setting.remove_row("r2");
+  assert!(setting.dependencies.is_empty());
+  assert!(setting.add_dependency("r3", "r1"));
+  setting.remove_dependency("r3", "r1");
+  assert!(!setting.has_dependency("r3", "r1"));
+}
+
+#[tokio::test]
+async fn timeline_window_with_end_field_test() {
+  let mut database_test = create_timeline_database().await;
+  let mut setting = TimelineLayoutSetting::new("start".to_string(), "end".to_string());
+  setting.add_dependency("r1", "r2");
+  setting.add_dependency("r2", "r3");
+  database_test.insert_layout_setting("v1", &DatabaseLayout::Timeline, setting);
+
+  let window = database_test
+    .get_timeline_window("v1", 0, 4 * DAY, false)
+    .await
+    .unwrap();
+  let row_ids = window
+    .bars
+    .iter()
+    .map(|bar| bar.row_id.to_string())
+    .collect::<Vec<_>>();
+  assert_eq!(row_ids, vec!["r1", "r2"]);
+  assert_eq!(window.bar("r1").unwrap().end, 3 * DAY);
+  // An end before the start is clamped to the start.
+  assert_eq!(window.bar("r2").unwrap().start, 2 * DAY);
+  assert_eq!(window.bar("r2").unwrap().end, 2 * DAY);
+  assert_eq!(window.dependencies.len(), 2);
+
+  // r2 starts before r1 finishes
+  let violated = window.violated_dependencies();
+  assert_eq!(violated.len(), 1);
+  assert_eq!(violated[0].to_row_id, "r2");
+
+  let window = database_test
+    .get_timeline_window("v1", 20 * DAY, 30 * DAY, false)
+    .await
+    .unwrap();
+  assert_eq!(window.bars.len(), 1);
+  assert_eq!(window.bars[0].row_id.to_string(), "r3");
+  assert_eq!(window.dependencies.len(), 1);
+  assert!(window.violated_dependencies().is_empty());
+}
+
+#[tokio::test]
+async fn timeline_window_with_date_range_test() {
+  let mut database_test = create_timeline_database().await;
+  database_test.insert_layout_setting(
+    "v1",
+    &DatabaseLayout::Timeline,
+    TimelineLayoutSetting::new("start".to_string(), String::new()),
+  );
+
+  let window = database_test
+    .get_timeline_window("v1", 0, 30 * DAY, false)
+    .await
+    .unwrap();
+  assert_eq!(window.bars.len(), 3);
+  // Only r3 has a date range in the start field.
+  assert_eq!(window.bar("r1").unwrap().end, DAY);
+  assert_eq!(window.bar("r3").unwrap().end, 25 * DAY);
+  assert!(window.dependencies.is_empty());
+}
+
+#[tokio::test]
+async fn timeline_window_without_setting_test() {
+  let database_test = create_timeline_database().await;
+  assert!(
+    database_test
+      .get_timeline_window("v1", 0, 30 * DAY, false)
+      .await
+      .is_none()
+  );
+}
+
+async fn create_timeline_database() -> DatabaseTest {
+  let database_id = Uuid::new_v4().to_string();
+  let mut database_test = create_database(1, &database_id);
+  let mut range = DateCellData::from_timestamp(21 * DAY);
+  range.end_timestamp = Some(25 * DAY);
+  range.is_range = true;
+  let rows = [
+    ("r1", DateCellData::from_timestamp(DAY), Some(3 * DAY)),
+    ("r2", DateCellData::from_timestamp(2 * DAY), Some(DAY)),
+    ("r3", range, None),
+  ];
+  for (row_id, start, end) in rows {
+    let mut cells = Cells::from([("start".into(), (&start).into())]);
+    if let Some(end) = end {
+      cells.insert("end".into(), (&DateCellData::from_timestamp(end)).into());
+    }
+    let params = CreateRowParams::new(RowId::from(row_id), database_id.clone()).with_cells(cells);
+    database_test.create_row(params).await.unwrap();
+  }
+  // A row without a start date has no bar.
+  let params = CreateRowParams::new(RowId::from("r4"), database_id.clone());
+  database_test.create_row(params).await.unwrap();
+  database_test
+}
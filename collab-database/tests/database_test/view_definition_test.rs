@@ -0,0 +1,116 @@
+use collab_database::entity::DatabaseView;
+use collab_database::fields::Field;
+use collab_database::views::{DatabaseLayout, FieldMapping, OrderObjectPosition, ViewDefinition};
+
+use crate::database_test::helper::{
+  DatabaseTest, create_database, create_database_with_default_data,
+  default_field_settings_by_layout,
+};
+use crate::helper::{TestFieldType, TestFilter};
+
+fn filter(id: &str, field_id: &str) -> TestFilter {
+  TestFilter {
+    id: id.to_string(),
+    field_id: field_id.to_string(),
+    field_type: TestFieldType::RichText,
+    condition: 0,
+    content: format!("{} content", id),
+  }
+}
+
+fn create_field(test: &mut DatabaseTest, field_id: &str, name: &str, field_type: i64) {
+  test.create_field(
+    None,
+    Field::new(field_id.to_string(), name.to_string(), field_type, false),
+    &OrderObjectPosition::default(),
+    default_field_settings_by_layout(),
+  );
+}
+
+#[tokio::test]
+async fn export_view_definition_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut test = create_database_with_default_data(1, &database_id).await;
+  test.insert_filter("v1", filter("filter_1", "f1"));
+  test.insert_filter("v1", filter("filter_2", "f2"));
+
+  let definition = test.export_view_definition("v1").unwrap();
+  assert_eq!(definition.filters.len(), 2);
+  let field_ids = definition
+    .fields
+    .iter()
+    .map(|field| field.field_id.as_str())
+    .collect::<Vec<_>>();
+  assert!(field_ids.contains(&"f1"));
+  assert!(field_ids.contains(&"f2"));
+
+  let json = definition.to_json().unwrap();
+  let parsed = ViewDefinition::from_json(&json).unwrap();
+  assert_eq!(parsed.name, definition.name);
+  assert_eq!(parsed.layout, definition.layout);
+  assert_eq!(parsed.fields, definition.fields);
+  assert_eq!(parsed.filters.len(), 2);
+
+  assert!(test.export_view_definition("unknown").is_none());
+}
+
+#[test]
+fn unsupported_view_definition_version_test() {
+  let mut definition = ViewDefinition::from_view(
+    &DatabaseView::new(
+      "d1".to_string(),
+      "v1".to_string(),
+      "Board".to_string(),
+      DatabaseLayout::Board,
+    ),
+    &[],
+  );
+  definition.version += 1;
+  let json = definition.to_json().unwrap();
+  assert!(ViewDefinition::from_json(&json).is_err());
+}
+
+#[tokio::test]
+async fn import_view_definition_with_field_mapping_test() {
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut source = create_database_with_default_data(1, &database_id).await;
+  source.insert_filter("v1", filter("filter_1", "f1"));
+  source.insert_filter("v1", filter("filter_2", "f2"));
+  let definition = source.export_view_definition("v1").unwrap();
+
+  // The target database names its fields differently, only the text field matches
+  let database_id = uuid::Uuid::new_v4().to_string();
+  let mut target = create_database(1, &database_id);
+  let text_field = source.get_field("f1").unwrap();
+  create_field(
+    &mut target,
+    "g1",
+    &text_field.name.to_uppercase(),
+    text_field.field_type,
+  );
+  create_field(&mut target, "g2", "status", 2);
+
+  let mapping = definition.suggest_field_mapping(&target.get_fields(None));
+  assert_eq!(
+    mapping,
+    FieldMapping::from([("f1".to_string(), "g1".to_string())])
+  );
+
+  let view_id = target
+    .import_view_definition(&definition, &mapping)
+    .unwrap();
+  let view = target.get_view(&view_id).unwrap();
+  assert_eq!(view.name, definition.name);
+  assert_eq!(view.layout, definition.layout);
+  let filters = target.get_all_filters::<TestFilter>(&view_id);
+  assert_eq!(filters.len(), 1);
+  assert_eq!(filters[0].id, "filter_1");
+  assert_eq!(filters[0].field_id, "g1");
+
+  // A mapping to a field that doesn't exist in the target is ignored
+  let mapping = FieldMapping::from([("f2".to_string(), "missing".to_string())]);
+  let view_id = target
+    .import_view_definition(&definition, &mapping)
+    .unwrap();
+  assert!(target.get_all_filters::<TestFilter>(&view_id).is_empty());
+}
@@ -2,11 +2,14 @@ use crate::helper::make_rocks_db;
 use crate::user_test::helper::TestUserDatabaseServiceImpl;
 use collab::core::collab::default_client_id;
 use collab_database::database::Database;
+use collab_database::entity::FieldType;
+use collab_database::fields::select_type_option::SelectTypeOption;
 use collab_database::rows::Row;
 use collab_database::template::csv::CSVTemplate;
+use collab_database::template::csv_stream::{CSVImportProgress, CSVStreamImporter};
 use collab_database::template::entity::CELL_DATA;
 use futures::StreamExt;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
 #[tokio::test]
@@ -82,3 +85,84 @@ async fn import_csv_test() {
     }
   }
 }
+
+#[tokio::test]
+async fn import_csv_by_chunks_test() {
+  let mut csv_data = "Name,Status,Price\n".to_string();
+  for index in 0..250 {
+    // A status that is not in the first chunk appears in the last rows.
+    let status = if index >= 240 {
+      "Late"
+    } else {
+      ["Todo", "Doing", "Done"][index % 3]
+    };
+    csv_data.push_str(&format!("Row {index},{status},\"${index}.50\"\n"));
+  }
+
+  let workspace_id = Uuid::new_v4().to_string();
+  let service = Arc::new(TestUserDatabaseServiceImpl::new(
+    1,
+    workspace_id,
+    make_rocks_db(),
+    default_client_id(),
+  ));
+  let progress = Arc::new(Mutex::new(vec![]));
+  let cloned_progress = progress.clone();
+  let database = CSVStreamImporter::new()
+    .with_chunk_size(100)
+    .with_progress(Arc::new(move |value: CSVImportProgress| {
+      cloned_progress.lock().unwrap().push(value);
+    }))
+    .import(csv_data.as_bytes(), service.clone(), service)
+    .await
+    .unwrap();
+
+  let progress = progress.lock().unwrap().clone();
+  assert_eq!(
+    progress
+      .iter()
+      .map(|value| value.imported_rows)
+      .collect::<Vec<_>>(),
+    vec![100, 200, 250]
+  );
+  assert_eq!(progress[2].read_bytes, csv_data.len() as u64);
+
+  let view_id = database.get_first_database_view_id().unwrap();
+  let fields = database.get_fields_in_view(&view_id, None);
+  let status = &fields[1];
+  assert_eq!(FieldType::from(status.field_type), FieldType::SingleSelect);
+  let options = status
+    .get_type_option::<SelectTypeOption>(FieldType::SingleSelect.type_id())
+    .unwrap()
+    .options;
+  assert_eq!(options.len(), 4);
+
+  let rows: Vec<Row> = database
+    .get_all_rows(50, None, false)
+    .await
+    .filter_map(|result| async move { result.ok() })
+    .collect()
+    .await;
+  assert_eq!(rows.len(), 250);
+  for (index, row) in rows.iter().enumerate() {
+    let option_id = row
+      .cells
+      .get(&status.id)
+      .unwrap()
+      .get(CELL_DATA)
+      .cloned()
+      .unwrap()
+      .cast::<String>()
+      .unwrap();
+    let option = options
+      .iter()
+      .find(|option| option.id == option_id)
+      .unwrap();
+    let expected = if index >= 240 {
+      "Late"
+    } else {
+      ["Todo", "Doing", "Done"][index % 3]
+    };
+    assert_eq!(option.name, expected, "Row: {}", index);
+  }
+}
@@ -1,2 +1,3 @@
 mod create_template_test;
 mod import_csv_test;
+mod sample_data_test;
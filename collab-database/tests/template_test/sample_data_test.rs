@@ -0,0 +1,97 @@
+use crate::helper::make_rocks_db;
+use crate::user_test::helper::TestUserDatabaseServiceImpl;
+use collab::core::collab::default_client_id;
+use collab_database::database::Database;
+use collab_database::rows::Row;
+use collab_database::template::entity::CELL_DATA;
+use collab_database::template::sample_data::{SampleDatabaseGenerator, SampleDatabaseKind};
+use futures::StreamExt;
+use std::collections::HashSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn sample_database_contains_all_field_types_test() {
+  for kind in [
+    SampleDatabaseKind::Tasks,
+    SampleDatabaseKind::Crm,
+    SampleDatabaseKind::ContentCalendar,
+  ] {
+    let template = SampleDatabaseGenerator::new(kind)
+      .with_num_rows(12)
+      .generate()
+      .await;
+    assert_eq!(template.rows.len(), 12);
+    assert_eq!(template.fields.len(), 15);
+    assert!(template.fields[0].is_primary);
+
+    let field_types = template
+      .fields
+      .iter()
+      .map(|field| field.field_type)
+      .collect::<HashSet<_>>();
+    assert_eq!(field_types.len(), 15);
+
+    for (row_index, row) in template.rows.iter().enumerate() {
+      for field in template.fields.iter() {
+        let cell = row.cells.get(&field.field_id);
+        assert!(
+          cell.and_then(|cell| cell.get(CELL_DATA)).is_some(),
+          "{:?} row {} field {} is empty",
+          kind,
+          row_index,
+          field.name
+        );
+      }
+    }
+  }
+}
+
+#[tokio::test]
+async fn sample_database_is_deterministic_test() {
+  let generator = SampleDatabaseGenerator::new(SampleDatabaseKind::Tasks)
+    .with_num_rows(5)
+    .with_seed(42);
+  let first = generator.generate().await;
+  let second = generator.generate().await;
+
+  let primary_cells = |template: &collab_database::template::entity::DatabaseTemplate| {
+    let field_id = template.fields[0].field_id.clone();
+    template
+      .rows
+      .iter()
+      .map(|row| row.cells[&field_id][CELL_DATA].to_string())
+      .collect::<Vec<_>>()
+  };
+  assert_eq!(primary_cells(&first), primary_cells(&second));
+}
+
+#[tokio::test]
+async fn create_database_from_sample_data_test() {
+  let template = SampleDatabaseGenerator::new(SampleDatabaseKind::Crm)
+    .with_num_rows(30)
+    .generate()
+    .await;
+  let db = make_rocks_db();
+  let service = Arc::new(TestUserDatabaseServiceImpl::new(
+    1,
+    Uuid::new_v4().to_string(),
+    db,
+    default_client_id(),
+  ));
+  let database = Database::create_with_template(template, service.clone(), service)
+    .await
+    .unwrap();
+
+  let view_id = database.get_first_database_view_id().unwrap();
+  let fields = database.get_fields_in_view(&view_id, None);
+  assert_eq!(fields.len(), 15);
+
+  let rows: Vec<Row> = database
+    .get_all_rows(10, None, false)
+    .await
+    .filter_map(|result| async move { result.ok() })
+    .collect()
+    .await;
+  assert_eq!(rows.len(), 30);
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::{Arc, RwLock};
+
+use crate::blocks::TextDelta;
+use crate::document_data::generate_id;
+
+/// What an [Annotation] reports.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AnnotationKind {
+  Misspelling,
+  Grammar,
+  Style,
+}
+
+/// A range of the text of a block reported by an external checker, e.g. a spell checker.
+///
+/// The annotations are transient: they are kept in memory by [DocumentAnnotations] and never
+/// written to the collab, so they are not synced to the other peers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+  pub id: String,
+  /// The checker that reported the annotation, see [DocumentAnnotations::set_block_annotations].
+  pub source: String,
+  pub kind: AnnotationKind,
+  /// The range of the text of the block, in UTF-16 code units as the offsets of the deltas.
+  pub range: Range<u32>,
+  pub message: Option<String>,
+  /// The replacements of the text of the range, the best first.
+  pub suggestions: Vec<String>,
+  /// The language the text was checked in, e.g. `en-US`.
+  pub language: Option<String>,
+}
+
+impl Annotation {
+  pub fn new(kind: AnnotationKind, range: Range<u32>) -> Self {
+    Self {
+      id: generate_id(),
+      source: String::new(),
+      kind,
+      range,
+      message: None,
+      suggestions: vec![],
+      language: None,
+    }
+  }
+
+  pub fn with_message(mut self, message: &str) -> Self {
+    self.message = Some(message.to_string());
+    self
+  }
+
+  pub fn with_suggestions(mut self, suggestions: Vec<String>) -> Self {
+    self.suggestions = suggestions;
+    self
+  }
+
+  pub fn with_language(mut self, language: &str) -> Self {
+    self.language = Some(language.to_string());
+    self
+  }
+
+  /// Move the range through the change of the text. Return false when the change touched the
+  /// text of the range, the annotation is stale then: an insertion inside the range, or a
+  /// deletion overlapping it. The insertions at the bounds of the range are outside of it.
+  fn remap(&mut self, delta: &[TextDelta]) -> bool {
+    let Range { start, end } = self.range;
+    let mut position = 0;
+    let mut shift = 0i64;
+    for op in delta {
+      if position >= end {
+        break;
+      }
+      match op {
+        TextDelta::Retain(len, _) => position += len,
+        TextDelta::Inserted(text, _) => {
+          if position > start {
+            return false;
+          }
+          shift += text.encode_utf16().count() as i64;
+        },
+        TextDelta::Deleted(len) => {
+          if position + len > start {
+            return false;
+          }
+          shift -= *len as i64;
+          position += len;
+        },
+      }
+    }
+    self.range = (start as i64 + shift) as u32..(end as i64 + shift) as u32;
+    true
+  }
+}
+
+/// The annotations of the blocks of a document, see [crate::document::Document::annotations].
+///
+/// Cloning it shares the annotations, so the checkers running in the background can report to
+/// the same document.
+#[derive(Debug, Clone, Default)]
+pub struct DocumentAnnotations {
+  /// The annotations by block id.
+  annotations: Arc<RwLock<HashMap<String, Vec<Annotation>>>>,
+}
+
+impl DocumentAnnotations {
+  /// Replace the annotations reported by the source on the block, e.g. after the block was
+  /// checked again. The empty ranges are skipped.
+  pub fn set_block_annotations(&self, block_id: &str, source: &str, annotations: Vec<Annotation>) {
+    let Ok(mut blocks) = self.annotations.write() else {
+      return;
+    };
+    let block_annotations = blocks.entry(block_id.to_string()).or_default();
+    block_annotations.retain(|annotation| annotation.source != source);
+    block_annotations.extend(
+      annotations
+        .into_iter()
+        .filter(|annotation| !annotation.range.is_empty())
+        .map(|mut annotation| {
+          annotation.source = source.to_string();
+          annotation
+        }),
+    );
+    block_annotations.sort_by_key(|annotation| (annotation.range.start, annotation.range.end));
+    if block_annotations.is_empty() {
+      blocks.remove(block_id);
+    }
+  }
+
+  /// The annotations of the block, ordered by range.
+  pub fn get_block_annotations(&self, block_id: &str) -> Vec<Annotation> {
+    self
+      .annotations
+      .read()
+      .ok()
+      .and_then(|blocks| blocks.get(block_id).cloned())
+      .unwrap_or_default()
+  }
+
+  /// The annotations of the block whose range contains the offset, e.g. under the cursor.
+  pub fn get_annotations_at(&self, block_id: &str, offset: u32) -> Vec<Annotation> {
+    self
+      .get_block_annotations(block_id)
+      .into_iter()
+      .filter(|annotation| annotation.range.contains(&offset))
+      .collect()
+  }
+
+  /// The ids of the blocks with annotations.
+  pub fn get_annotated_block_ids(&self) -> Vec<String> {
+    self
+      .annotations
+      .read()
+      .map(|blocks| blocks.keys().cloned().collect())
+      .unwrap_or_default()
+  }
+
+  /// Remove an annotation, e.g. when the user ignores it.
+  pub fn remove_annotation(&self, block_id: &str, annotation_id: &str) -> Option<Annotation> {
+    let mut blocks = self.annotations.write().ok()?;
+    let block_annotations = blocks.get_mut(block_id)?;
+    let index = block_annotations
+      .iter()
+      .position(|annotation| annotation.id == annotation_id)?;
+    let annotation = block_annotations.remove(index);
+    if block_annotations.is_empty() {
+      blocks.remove(block_id);
+    }
+    Some(annotation)
+  }
+
+  pub fn clear_block(&self, block_id: &str) {
+    if let Ok(mut blocks) = self.annotations.write() {
+      blocks.remove(block_id);
+    }
+  }
+
+  /// Remove the annotations reported by the source, e.g. when its checker is disabled.
+  pub fn clear_source(&self, source: &str) {
+    if let Ok(mut blocks) = self.annotations.write() {
+      blocks.retain(|_, block_annotations| {
+        block_annotations.retain(|annotation| annotation.source != source);
+        !block_annotations.is_empty()
+      });
+    }
+  }
+
+  pub fn clear(&self) {
+    if let Ok(mut blocks) = self.annotations.write() {
+      blocks.clear();
+    }
+  }
+
+  /// Remap the annotations of the block through a change of its text. The annotations whose
+  /// text was changed are removed.
+  ///
+  /// The document calls it for every change of the text, see
+  /// [crate::document::Document::annotations].
+  pub fn apply_text_delta(&self, block_id: &str, delta: &[TextDelta]) {
+    let Ok(mut blocks) = self.annotations.write() else {
+      return;
+    };
+    if let Some(block_annotations) = blocks.get_mut(block_id) {
+      block_annotations.retain_mut(|annotation| annotation.remap(delta));
+      if block_annotations.is_empty() {
+        blocks.remove(block_id);
+      }
+    }
+  }
+}
@@ -0,0 +1,69 @@
+use crate::blocks::{Block, BlockType};
+use crate::document::Document;
+use collab_entity::attachment::{AttachmentLocation, AttachmentReference};
+use serde_json::Value;
+
+// do not change the key values, they come from the flutter code.
+const URL_KEY: &str = "url";
+const SIZE_KEY: &str = "size";
+const IMAGES_KEY: &str = "images";
+
+impl Document {
+  /// The attachments referenced by the image, file, video and audio blocks of the document, e.g.
+  /// to fill a catalog of the attachments of the workspace. Use [Document::set_attachment_hook]
+  /// to keep the catalog up to date.
+  pub fn get_attachment_references(&self) -> Vec<AttachmentReference> {
+    let object_id = self.object_id().to_string();
+    self
+      .get_all_block_ids()
+      .into_iter()
+      .filter_map(|block_id| self.get_block(&block_id))
+      .flat_map(|block| block_attachment_references(&object_id, &block))
+      .collect()
+  }
+}
+
+/// Whether the block type can reference attachments.
+pub(crate) fn is_attachment_block(block: &Block) -> bool {
+  matches!(
+    BlockType::from_block_ty(&block.ty),
+    BlockType::Image
+      | BlockType::MultiImage
+      | BlockType::File
+      | BlockType::Video
+      | BlockType::Audio
+  )
+}
+
+/// The attachments referenced by the block. The links to external websites are included, the
+/// catalog can't tell them apart from the uploaded files.
+pub(crate) fn block_attachment_references(
+  object_id: &str,
+  block: &Block,
+) -> Vec<AttachmentReference> {
+  if !is_attachment_block(block) {
+    return vec![];
+  }
+  let location = AttachmentLocation::Block {
+    block_id: block.id.clone(),
+  };
+  let reference = |data: &Value| {
+    let url = data.get(URL_KEY)?.as_str()?.trim();
+    if url.is_empty() {
+      return None;
+    }
+    Some(AttachmentReference {
+      object_id: object_id.to_string(),
+      location: location.clone(),
+      url: url.to_string(),
+      size: data.get(SIZE_KEY).and_then(Value::as_i64),
+    })
+  };
+
+  let data = Value::Object(block.data.clone().into_iter().collect());
+  let mut references = reference(&data).into_iter().collect::<Vec<_>>();
+  if let Some(Value::Array(images)) = data.get(IMAGES_KEY) {
+    references.extend(images.iter().filter_map(reference));
+  }
+  references
+}
@@ -1,11 +1,13 @@
 use crate::block_parser::{
-  BlockParserRegistry, BulletedListParser, CalloutParser, CodeBlockParser, DividerParser,
-  DocumentParserDelegate, FileBlockParser, HeadingParser, ImageParser, LinkPreviewParser,
-  MathEquationParser, NumberedListParser, OutputFormat, PageParser, ParagraphParser, ParseContext,
-  QuoteListParser, SimpleColumnParser, SimpleColumnsParser, SimpleTableCellParser,
-  SimpleTableParser, SimpleTableRowParser, SubpageParser, TodoListParser, ToggleListParser,
+  AiPromptParser, AiResponseParser, BibliographyParser, BlockParser, BlockParserRegistry,
+  BulletedListParser, CalloutParser, CodeBlockParser, CustomBlockParser, DiagramParser,
+  DividerParser, DocumentParserDelegate, EmbedParser, FileBlockParser, HeadingParser, ImageParser,
+  LinkPreviewParser, MathEquationParser, NumberedListParser, OutputFormat, PageParser,
+  ParagraphParser, ParseContext, QuoteListParser, SimpleColumnParser, SimpleColumnsParser,
+  SimpleTableCellParser, SimpleTableParser, SimpleTableRowParser, SubpageParser, TodoListParser,
+  ToggleListParser,
 };
-use crate::blocks::{Block, DocumentData};
+use crate::blocks::{Block, BlockSchemaRegistry, DocumentData};
 use crate::error::DocumentError;
 use std::sync::Arc;
 
@@ -54,7 +56,9 @@ impl DocumentParser {
       .register(Arc::new(ImageParser))
       .register(Arc::new(CalloutParser))
       .register(Arc::new(CodeBlockParser))
+      .register(Arc::new(DiagramParser))
       .register(Arc::new(DividerParser))
+      .register(Arc::new(EmbedParser))
       .register(Arc::new(FileBlockParser))
       .register(Arc::new(LinkPreviewParser))
       .register(Arc::new(MathEquationParser))
@@ -63,11 +67,34 @@ impl DocumentParser {
       .register(Arc::new(SimpleTableParser))
       .register(Arc::new(SimpleTableRowParser))
       .register(Arc::new(SimpleTableCellParser))
-      .register(Arc::new(SubpageParser));
+      .register(Arc::new(SubpageParser))
+      .register(Arc::new(BibliographyParser::default()))
+      .register(Arc::new(AiPromptParser))
+      .register(Arc::new(AiResponseParser));
 
     parser
   }
 
+  /// Register the parser, it replaces the parser of the same block type, e.g. a
+  /// [BibliographyParser] with the rendered references.
+  pub fn with_parser(mut self, parser: Arc<dyn BlockParser + Send + Sync>) -> Self {
+    self.registry.register(parser);
+    self
+  }
+
+  /// Export the custom blocks of the registry, see [CustomBlockParser].
+  pub fn with_block_schemas(mut self, schemas: &BlockSchemaRegistry) -> Self {
+    for schema in schemas.schemas() {
+      let parser = CustomBlockParser {
+        schema: schema.clone(),
+      };
+      self
+        .registry
+        .register_with_type(&schema.ty, Arc::new(parser));
+    }
+    self
+  }
+
   pub fn parse_document(
     &self,
     document_data: &DocumentData,
@@ -0,0 +1,201 @@
+use crate::block_parser::{DocumentParser, OutputFormat};
+use crate::blocks::{AttrKey, BlockType, DocumentData};
+use crate::citation::Citation;
+use crate::error::DocumentError;
+use crate::importer::define::{FORMULA_ATTR, IMAGE_EXTENSIONS, UNDERLINE_ATTR, URL_FIELD};
+use collab::preclude::{Any, Attrs};
+
+/// The directory of the [LatexExport::assets], relative to the exported `.tex` file.
+pub const LATEX_ASSET_DIR: &str = "images";
+
+/// The packages used by the LaTeX output of the block parsers.
+const LATEX_PACKAGES: &str = "\\usepackage{amsmath}
+\\usepackage{amssymb}
+\\usepackage{graphicx}
+\\usepackage{hyperref}
+\\usepackage{listings}
+\\usepackage[normalem]{ulem}";
+
+/// A document exported to LaTeX, see [DocumentParser::export_latex].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatexExport {
+  /// The body of the document, without the preamble.
+  pub content: String,
+  /// The images the content includes. The caller downloads each image to its path, the
+  /// exporter doesn't fetch anything.
+  pub assets: Vec<LatexAsset>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatexAsset {
+  pub block_id: String,
+  pub url: String,
+  /// The path the content includes the image from, relative to the `.tex` file, e.g.
+  /// `images/{block_id}.png`.
+  pub path: String,
+}
+
+impl LatexExport {
+  /// A complete `article` document with the given title, ready to be compiled.
+  pub fn standalone(&self, title: &str) -> String {
+    let (title, make_title) = if title.is_empty() {
+      ("".to_string(), "")
+    } else {
+      (
+        format!("\\title{{{}}}\n\\date{{}}\n", escape_latex(title)),
+        "\\maketitle\n\n",
+      )
+    };
+    format!(
+      "\\documentclass{{article}}\n{}\n{}\n\\begin{{document}}\n{}{}\n\\end{{document}}\n",
+      LATEX_PACKAGES, title, make_title, self.content
+    )
+  }
+}
+
+impl DocumentParser {
+  /// Export the document to LaTeX: the headings become sections, the math equations are kept
+  /// verbatim, the code blocks become listings, the tables become tabulars and the images are
+  /// included from the [LatexExport::assets].
+  pub fn export_latex(&self, document_data: &DocumentData) -> Result<LatexExport, DocumentError> {
+    let content = self.parse_document(document_data, OutputFormat::Latex)?;
+    let mut assets = vec![];
+    let mut stack = vec![document_data.page_id.as_str()];
+    while let Some(block_id) = stack.pop() {
+      let Some(block) = document_data.blocks.get(block_id) else {
+        continue;
+      };
+      if block.ty == BlockType::Image.as_str() {
+        if let Some(url) = block.data.get(URL_FIELD).and_then(|url| url.as_str()) {
+          if !url.is_empty() {
+            assets.push(LatexAsset {
+              block_id: block.id.clone(),
+              url: url.to_string(),
+              path: latex_asset_path(&block.id, url),
+            });
+          }
+        }
+      }
+      if let Some(children) = document_data.meta.children_map.get(&block.children) {
+        stack.extend(children.iter().rev().map(String::as_str));
+      }
+    }
+    Ok(LatexExport {
+      content: merge_adjacent_lists(&content),
+      assets,
+    })
+  }
+}
+
+/// The path an image is included from, the extension of the url is kept when it is an image
+/// extension.
+pub fn latex_asset_path(block_id: &str, url: &str) -> String {
+  let path = url.split(['?', '#']).next().unwrap_or_default();
+  let extension = path
+    .rsplit('/')
+    .next()
+    .and_then(|file_name| file_name.rsplit_once('.'))
+    .map(|(_, extension)| extension.to_lowercase())
+    .filter(|extension| IMAGE_EXTENSIONS.contains(&extension.as_str()))
+    .unwrap_or_else(|| "png".to_string());
+  format!("{}/{}.{}", LATEX_ASSET_DIR, block_id, extension)
+}
+
+/// Escape the characters LaTeX treats as commands.
+pub fn escape_latex(text: &str) -> String {
+  let mut result = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '\\' => result.push_str("\\textbackslash{}"),
+      '{' | '}' | '$' | '&' | '#' | '%' | '_' => {
+        result.push('\\');
+        result.push(c);
+      },
+      '~' => result.push_str("\\textasciitilde{}"),
+      '^' => result.push_str("\\textasciicircum{}"),
+      c => result.push(c),
+    }
+  }
+  result
+}
+
+pub fn format_text_with_latex_attributes(text: &str, attributes: &Attrs) -> String {
+  if let Some(Any::String(formula)) = attributes.get(FORMULA_ATTR) {
+    return format!("${}$", formula);
+  }
+  if let Some(citation) = Citation::from_attrs(attributes) {
+    return match citation.locator {
+      Some(locator) => format!("\\cite[{}]{{{}}}", escape_latex(&locator), citation.key),
+      None => format!("\\cite{{{}}}", citation.key),
+    };
+  }
+
+  let mut result = escape_latex(text);
+  if let Some(Any::Bool(true)) = attributes.get(AttrKey::Code.as_str()) {
+    result = format!("\\texttt{{{}}}", result);
+  }
+
+  if let Some(Any::Bool(true)) = attributes.get(AttrKey::Bold.as_str()) {
+    result = format!("\\textbf{{{}}}", result);
+  }
+
+  if let Some(Any::Bool(true)) = attributes.get(AttrKey::Italic.as_str()) {
+    result = format!("\\textit{{{}}}", result);
+  }
+
+  if let Some(Any::Bool(true)) = attributes.get(UNDERLINE_ATTR) {
+    result = format!("\\uline{{{}}}", result);
+  }
+
+  if let Some(Any::Bool(true)) = attributes.get(AttrKey::Strikethrough.as_str()) {
+    result = format!("\\sout{{{}}}", result);
+  }
+
+  if let Some(Any::String(href)) = attributes.get(AttrKey::Href.as_str()) {
+    result = format!("\\href{{{}}}{{{}}}", escape_latex_url(href), result);
+  }
+
+  result
+}
+
+/// The language of a code block as named by the `listings` package, None if the package doesn't
+/// know the language.
+pub fn latex_listing_language(language: &str) -> Option<&'static str> {
+  let language = match language.to_lowercase().as_str() {
+    "bash" | "shell" | "sh" => "bash",
+    "c" => "C",
+    "c++" | "cpp" => "C++",
+    "html" => "HTML",
+    "java" => "Java",
+    "latex" | "tex" => "TeX",
+    "matlab" => "Matlab",
+    "php" => "PHP",
+    "python" => "Python",
+    "r" => "R",
+    "ruby" => "Ruby",
+    "sql" => "SQL",
+    "xml" => "XML",
+    _ => return None,
+  };
+  Some(language)
+}
+
+/// `hyperref` reads the urls verbatim, only the characters that end the argument or start a
+/// comment are escaped.
+pub(crate) fn escape_latex_url(url: &str) -> String {
+  url
+    .replace('\\', "/")
+    .replace('%', "\\%")
+    .replace('#', "\\#")
+}
+
+/// Each list item is exported in its own list environment, the environments of the consecutive
+/// items are merged into one list.
+fn merge_adjacent_lists(content: &str) -> String {
+  let mut content = content.to_string();
+  for environment in ["itemize", "enumerate"] {
+    let boundary = format!("\\end{{{0}}}\n\\begin{{{0}}}\n", environment);
+    content = content.replace(&boundary, "");
+  }
+  content
+}
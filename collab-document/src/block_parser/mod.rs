@@ -1,10 +1,12 @@
 pub mod document_parser;
+pub mod latex;
 pub mod parsers;
 pub mod registry;
 pub mod text_utils;
 pub mod traits;
 
 pub use document_parser::*;
+pub use latex::*;
 pub use parsers::*;
 pub use registry::*;
 pub use text_utils::*;
@@ -0,0 +1,58 @@
+use crate::block_parser::{
+  BlockParser, DefaultDocumentTextExtractor, DocumentTextExtractor, OutputFormat, ParseContext,
+  ParseResult,
+};
+use crate::blocks::{Block, BlockType};
+use crate::error::DocumentError;
+
+/// Parse the AI prompt block.
+///
+/// The prompt is exported as a labelled quote so it reads apart from the response.
+pub struct AiPromptParser;
+
+impl BlockParser for AiPromptParser {
+  fn parse(&self, block: &Block, context: &ParseContext) -> Result<ParseResult, DocumentError> {
+    let text_extractor = DefaultDocumentTextExtractor;
+    let content = text_extractor.extract_text_from_block(block, context)?;
+    let indent = context.get_indent();
+
+    let formatted_content = match context.format {
+      OutputFormat::Markdown => format!("{}> **Prompt:** {}", indent, content),
+      OutputFormat::PlainText => format!("{}Prompt: {}", indent, content),
+      OutputFormat::Latex => format!(
+        "\\begin{{quote}}\n\\textbf{{Prompt:}} {}\n\\end{{quote}}",
+        content
+      ),
+    };
+
+    Ok(ParseResult::new(formatted_content))
+  }
+
+  fn block_type(&self) -> &'static str {
+    BlockType::AiPrompt.as_str()
+  }
+}
+
+/// Parse the AI response block.
+///
+/// The response is exported as its text, the previous responses kept in the block data are not.
+pub struct AiResponseParser;
+
+impl BlockParser for AiResponseParser {
+  fn parse(&self, block: &Block, context: &ParseContext) -> Result<ParseResult, DocumentError> {
+    let text_extractor = DefaultDocumentTextExtractor;
+    let content = text_extractor.extract_text_from_block(block, context)?;
+    let indent = context.get_indent();
+
+    let formatted_content = match context.format {
+      OutputFormat::Markdown | OutputFormat::PlainText => format!("{}{}", indent, content),
+      OutputFormat::Latex => content,
+    };
+
+    Ok(ParseResult::new(formatted_content))
+  }
+
+  fn block_type(&self) -> &'static str {
+    BlockType::AiResponse.as_str()
+  }
+}
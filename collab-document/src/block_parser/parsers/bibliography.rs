@@ -0,0 +1,83 @@
+use serde_json::Value;
+
+use crate::block_parser::{BlockParser, OutputFormat, ParseContext, ParseResult, escape_latex};
+use crate::blocks::{Block, BlockType};
+use crate::error::DocumentError;
+use crate::importer::define::BIBLIOGRAPHY_TITLE_FIELD;
+
+/// The title of a bibliography block without one.
+const DEFAULT_BIBLIOGRAPHY_TITLE: &str = "References";
+
+/// An entry of the bibliography, rendered in a citation style, see
+/// [crate::citation::CitationRenderer::bibliography].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BibliographyEntry {
+  pub key: String,
+  pub text: String,
+}
+
+/// Parse the bibliography block, it lists the references cited by the document.
+///
+/// The references live in the reference store of the workspace, so the default parser has no
+/// entries and only exports the title. Use [crate::citation::export_with_citations] to export
+/// the entries.
+///
+/// Bibliography block data:
+///   title: string, optional
+#[derive(Debug, Clone, Default)]
+pub struct BibliographyParser {
+  pub entries: Vec<BibliographyEntry>,
+}
+
+impl BibliographyParser {
+  pub fn new(entries: Vec<BibliographyEntry>) -> Self {
+    Self { entries }
+  }
+}
+
+impl BlockParser for BibliographyParser {
+  fn parse(&self, block: &Block, context: &ParseContext) -> Result<ParseResult, DocumentError> {
+    let title = block
+      .data
+      .get(BIBLIOGRAPHY_TITLE_FIELD)
+      .and_then(Value::as_str)
+      .filter(|title| !title.is_empty())
+      .unwrap_or(DEFAULT_BIBLIOGRAPHY_TITLE);
+
+    let formatted_content = match context.format {
+      OutputFormat::Markdown => {
+        let mut lines = vec![format!("## {}", title)];
+        lines.extend(self.entries.iter().map(|entry| entry.text.clone()));
+        lines.join("\n\n")
+      },
+      OutputFormat::PlainText => {
+        let mut lines = vec![title.to_string()];
+        lines.extend(self.entries.iter().map(|entry| entry.text.clone()));
+        lines.join("\n")
+      },
+      OutputFormat::Latex => {
+        if self.entries.is_empty() {
+          format!("\\section*{{{}}}", escape_latex(title))
+        } else {
+          let items = self
+            .entries
+            .iter()
+            .map(|entry| format!("\\bibitem{{{}}} {}", entry.key, escape_latex(&entry.text)))
+            .collect::<Vec<_>>();
+          format!(
+            "\\renewcommand{{\\refname}}{{{}}}\n\\begin{{thebibliography}}{{{}}}\n{}\n\\end{{thebibliography}}",
+            escape_latex(title),
+            self.entries.len(),
+            items.join("\n")
+          )
+        }
+      },
+    };
+
+    Ok(ParseResult::new(formatted_content))
+  }
+
+  fn block_type(&self) -> &'static str {
+    BlockType::Bibliography.as_str()
+  }
+}
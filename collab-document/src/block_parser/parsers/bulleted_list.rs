@@ -25,6 +25,7 @@ impl BlockParser for BulletedListParser {
         let indent = context.get_indent();
         format!("{}{}", indent, content)
       },
+      OutputFormat::Latex => format!("\\begin{{itemize}}\n\\item {}", content),
     };
 
     let children_content = self.parse_children(block, context);
@@ -34,6 +35,9 @@ impl BlockParser for BulletedListParser {
       result.push('\n');
       result.push_str(&children_content);
     }
+    if context.format == OutputFormat::Latex {
+      result.push_str("\n\\end{itemize}");
+    }
 
     Ok(ParseResult::new(result))
   }
@@ -40,6 +40,8 @@ impl BlockParser for CalloutParser {
         let indent = context.get_indent();
         format!("{}{} {}", indent, icon, content)
       },
+      // the icon is left out, the emojis are not supported by the default LaTeX fonts
+      OutputFormat::Latex => format!("\\begin{{quote}}\n{}", content),
     };
 
     let children_content = self.parse_children(block, context);
@@ -49,6 +51,9 @@ impl BlockParser for CalloutParser {
       result.push('\n');
       result.push_str(&children_content);
     }
+    if context.format == OutputFormat::Latex {
+      result.push_str("\n\\end{quote}");
+    }
 
     Ok(ParseResult::new(result))
   }
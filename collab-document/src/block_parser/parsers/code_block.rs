@@ -2,7 +2,7 @@ use serde_json::Value;
 
 use crate::block_parser::{
   BlockParser, DefaultDocumentTextExtractor, DocumentTextExtractor, OutputFormat, ParseContext,
-  ParseResult,
+  ParseResult, latex_listing_language,
 };
 use crate::blocks::{Block, BlockType};
 use crate::error::DocumentError;
@@ -20,7 +20,17 @@ const LANGUAGE_KEY: &str = "language";
 impl BlockParser for CodeBlockParser {
   fn parse(&self, block: &Block, context: &ParseContext) -> Result<ParseResult, DocumentError> {
     let text_extractor = DefaultDocumentTextExtractor;
-    let content = text_extractor.extract_text_from_block(block, context)?;
+    let content = match context.format {
+      // the code is kept verbatim in a listing
+      OutputFormat::Latex => {
+        let plain_text_context = ParseContext {
+          format: OutputFormat::PlainText,
+          ..context.clone()
+        };
+        text_extractor.extract_text_from_block(block, &plain_text_context)?
+      },
+      _ => text_extractor.extract_text_from_block(block, context)?,
+    };
 
     let language = block
       .data
@@ -40,6 +50,13 @@ impl BlockParser for CodeBlockParser {
         let indent = context.get_indent();
         format!("{}{}", indent, content)
       },
+      OutputFormat::Latex => match latex_listing_language(&language) {
+        Some(language) => format!(
+          "\\begin{{lstlisting}}[language={}]\n{}\n\\end{{lstlisting}}",
+          language, content
+        ),
+        None => format!("\\begin{{lstlisting}}\n{}\n\\end{{lstlisting}}", content),
+      },
     };
 
     Ok(ParseResult::new(formatted_content))
@@ -0,0 +1,60 @@
+use serde_json::Value;
+
+use crate::block_parser::{BlockParser, OutputFormat, ParseContext, ParseResult, escape_latex};
+use crate::blocks::{Block, BlockSchema};
+use crate::error::DocumentError;
+
+/// The type returned by [CustomBlockParser::block_type], the parser is registered under the type
+/// of its schema instead.
+const CUSTOM_BLOCK_TYPE: &str = "custom";
+
+/// Parse a custom block registered in a [crate::blocks::BlockSchemaRegistry].
+///
+/// The content of the block is the value of the [BlockSchema::text_field] of its data, followed
+/// by its children if the schema allows them.
+pub struct CustomBlockParser {
+  pub schema: BlockSchema,
+}
+
+impl BlockParser for CustomBlockParser {
+  fn parse(&self, block: &Block, context: &ParseContext) -> Result<ParseResult, DocumentError> {
+    let content = self
+      .schema
+      .text_field
+      .as_ref()
+      .and_then(|field| block.data.get(field))
+      .map(|value| match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "".to_string(),
+        value => value.to_string(),
+      })
+      .unwrap_or_default();
+
+    let content = match context.format {
+      OutputFormat::Latex => escape_latex(&content),
+      _ => content,
+    };
+    let mut result = if content.is_empty() {
+      content
+    } else {
+      format!("{}{}", context.get_indent(), content)
+    };
+    let children_content = self.parse_children(block, context);
+    if !children_content.is_empty() {
+      if !result.is_empty() {
+        result.push('\n');
+      }
+      result.push_str(&children_content);
+    }
+
+    Ok(ParseResult::new(result))
+  }
+
+  fn block_type(&self) -> &'static str {
+    CUSTOM_BLOCK_TYPE
+  }
+
+  fn can_parse(&self, block_type: &str) -> bool {
+    self.schema.ty == block_type
+  }
+}
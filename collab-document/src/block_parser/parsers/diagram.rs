@@ -0,0 +1,46 @@
+use crate::block_parser::{BlockParser, OutputFormat, ParseContext, ParseResult};
+use crate::blocks::{Block, BlockType, DiagramData};
+use crate::error::DocumentError;
+
+/// Parse the diagram block, it is exported as the fenced code block it was imported from.
+///
+/// Diagram block data:
+///   kind: string, `mermaid` or `plantuml`
+///   source: string
+pub struct DiagramParser;
+
+impl BlockParser for DiagramParser {
+  fn parse(&self, block: &Block, context: &ParseContext) -> Result<ParseResult, DocumentError> {
+    let Some(diagram) = DiagramData::from_block_data(&block.data) else {
+      return Ok(ParseResult::empty());
+    };
+
+    let formatted_content = match context.format {
+      OutputFormat::Markdown => {
+        let indent = context.get_indent();
+        format!(
+          "{}```{}\n{}\n{}```",
+          indent,
+          diagram.kind.as_str(),
+          diagram.source,
+          indent
+        )
+      },
+      OutputFormat::PlainText => {
+        let indent = context.get_indent();
+        format!("{}{}", indent, diagram.source)
+      },
+      // LaTeX can't render the diagrams, the source is kept verbatim
+      OutputFormat::Latex => format!(
+        "\\begin{{lstlisting}}\n{}\n\\end{{lstlisting}}",
+        diagram.source
+      ),
+    };
+
+    Ok(ParseResult::new(formatted_content))
+  }
+
+  fn block_type(&self) -> &'static str {
+    BlockType::Diagram.as_str()
+  }
+}
@@ -18,6 +18,7 @@ impl BlockParser for DividerParser {
         let indent = context.get_indent();
         format!("{}---", indent)
       },
+      OutputFormat::Latex => "\\noindent\\rule{\\linewidth}{0.4pt}".to_string(),
     };
 
     Ok(ParseResult::new(formatted_content))
@@ -0,0 +1,64 @@
+use serde_json::Value;
+
+use crate::block_parser::{
+  BlockParser, OutputFormat, ParseContext, ParseResult, escape_latex, escape_latex_url,
+};
+use crate::blocks::{Block, BlockType};
+use crate::error::DocumentError;
+use crate::importer::define::{EMBED_TITLE_FIELD, URL_FIELD};
+
+/// Parse the embed block, it is exported as a link to the embedded page.
+///
+/// Embed block data:
+///   url: string
+///   provider: string
+///   title: string, optional
+///   description: string, optional
+///   thumbnail_url: string, optional
+pub struct EmbedParser;
+
+impl BlockParser for EmbedParser {
+  fn parse(&self, block: &Block, context: &ParseContext) -> Result<ParseResult, DocumentError> {
+    let url = block
+      .data
+      .get(URL_FIELD)
+      .and_then(Value::as_str)
+      .unwrap_or_default();
+    if url.is_empty() {
+      return Ok(ParseResult::empty());
+    }
+    let title = block
+      .data
+      .get(EMBED_TITLE_FIELD)
+      .and_then(Value::as_str)
+      .filter(|title| !title.is_empty());
+
+    let formatted_content = match context.format {
+      OutputFormat::Markdown => {
+        let indent = context.get_indent();
+        format!("{}[{}]({})", indent, title.unwrap_or(url), url)
+      },
+      OutputFormat::PlainText => {
+        let indent = context.get_indent();
+        match title {
+          Some(title) => format!("{}{} {}", indent, title, url),
+          None => format!("{}{}", indent, url),
+        }
+      },
+      OutputFormat::Latex => match title {
+        Some(title) => format!(
+          "\\href{{{}}}{{{}}}",
+          escape_latex_url(url),
+          escape_latex(title)
+        ),
+        None => format!("\\url{{{}}}", escape_latex_url(url)),
+      },
+    };
+
+    Ok(ParseResult::new(formatted_content))
+  }
+
+  fn block_type(&self) -> &'static str {
+    BlockType::Embed.as_str()
+  }
+}
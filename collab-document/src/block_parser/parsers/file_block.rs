@@ -1,6 +1,8 @@
 use serde_json::Value;
 
-use crate::block_parser::{BlockParser, OutputFormat, ParseContext, ParseResult};
+use crate::block_parser::{
+  BlockParser, OutputFormat, ParseContext, ParseResult, escape_latex, escape_latex_url,
+};
 use crate::blocks::{Block, BlockType};
 use crate::error::DocumentError;
 
@@ -52,6 +54,17 @@ impl BlockParser for FileBlockParser {
           format!("{}{}({})", indent, name, url)
         }
       },
+      OutputFormat::Latex => {
+        if url.is_empty() {
+          escape_latex(&name)
+        } else {
+          format!(
+            "\\href{{{}}}{{{}}}",
+            escape_latex_url(&url),
+            escape_latex(&name)
+          )
+        }
+      },
     };
 
     Ok(ParseResult::new(formatted_content))
@@ -41,6 +41,16 @@ impl BlockParser for HeadingParser {
         format!("{} {}", "#".repeat(level), content)
       },
       OutputFormat::PlainText => content,
+      OutputFormat::Latex => {
+        let command = match level {
+          1 => "section",
+          2 => "subsection",
+          3 => "subsubsection",
+          4 => "paragraph",
+          _ => "subparagraph",
+        };
+        format!("\\{}{{{}}}", command, content)
+      },
     };
 
     let children_content = self.parse_children(block, context);
@@ -1,4 +1,4 @@
-use crate::block_parser::{BlockParser, ParseContext, ParseResult};
+use crate::block_parser::{BlockParser, ParseContext, ParseResult, latex_asset_path};
 use crate::blocks::{Block, BlockType};
 use crate::error::DocumentError;
 
@@ -29,6 +29,16 @@ impl BlockParser for ImageParser {
         }
       },
       crate::block_parser::OutputFormat::PlainText => url.to_string(),
+      crate::block_parser::OutputFormat::Latex => {
+        if url.is_empty() {
+          "".to_string()
+        } else {
+          format!(
+            "\\begin{{center}}\n\\includegraphics[width=\\linewidth]{{{}}}\n\\end{{center}}",
+            latex_asset_path(&block.id, url)
+          )
+        }
+      },
     };
 
     let children_content = self.parse_children(block, context);
@@ -1,6 +1,6 @@
 use serde_json::Value;
 
-use crate::block_parser::{BlockParser, OutputFormat, ParseContext, ParseResult};
+use crate::block_parser::{BlockParser, OutputFormat, ParseContext, ParseResult, escape_latex_url};
 use crate::blocks::{Block, BlockType};
 use crate::error::DocumentError;
 
@@ -41,6 +41,13 @@ impl BlockParser for LinkPreviewParser {
           format!("{}{}", indent, url)
         }
       },
+      OutputFormat::Latex => {
+        if url.is_empty() {
+          "".to_string()
+        } else {
+          format!("\\url{{{}}}", escape_latex_url(&url))
+        }
+      },
     };
 
     Ok(ParseResult::new(formatted_content))
@@ -37,6 +37,13 @@ impl BlockParser for MathEquationParser {
         let indent = context.get_indent();
         format!("{}{}", indent, formula)
       },
+      OutputFormat::Latex => {
+        if formula.is_empty() {
+          "".to_string()
+        } else {
+          format!("\\[\n{}\n\\]", formula)
+        }
+      },
     };
 
     Ok(ParseResult::new(formatted_content))
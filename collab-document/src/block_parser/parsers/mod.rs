@@ -1,7 +1,12 @@
+pub mod ai_transcript;
+pub mod bibliography;
 pub mod bulleted_list;
 pub mod callout;
 pub mod code_block;
+pub mod custom_block;
+pub mod diagram;
 pub mod divider;
+pub mod embed;
 pub mod file_block;
 pub mod heading;
 pub mod image;
@@ -20,10 +25,15 @@ pub mod subpage;
 pub mod todo_list;
 pub mod toggle_list;
 
+pub use ai_transcript::*;
+pub use bibliography::*;
 pub use bulleted_list::*;
 pub use callout::*;
 pub use code_block::*;
+pub use custom_block::*;
+pub use diagram::*;
 pub use divider::*;
+pub use embed::*;
 pub use file_block::*;
 pub use heading::*;
 pub use image::*;
@@ -41,6 +41,7 @@ impl BlockParser for NumberedListParser {
         let indent = context.get_indent();
         format!("{}{}. {}", indent, number, content)
       },
+      OutputFormat::Latex => format!("\\begin{{enumerate}}\n\\item {}", content),
     };
 
     let list_context = context.with_list_context(Some(number + 1));
@@ -51,6 +52,9 @@ impl BlockParser for NumberedListParser {
       result.push('\n');
       result.push_str(&children_content);
     }
+    if context.format == OutputFormat::Latex {
+      result.push_str("\n\\end{enumerate}");
+    }
 
     Ok(ParseResult::new(result))
   }
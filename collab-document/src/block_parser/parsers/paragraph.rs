@@ -1,5 +1,6 @@
 use crate::block_parser::{
-  BlockParser, DefaultDocumentTextExtractor, DocumentTextExtractor, ParseContext, ParseResult,
+  BlockParser, DefaultDocumentTextExtractor, DocumentTextExtractor, OutputFormat, ParseContext,
+  ParseResult,
 };
 use crate::blocks::{Block, BlockType};
 use crate::error::DocumentError;
@@ -18,6 +19,10 @@ impl BlockParser for ParagraphParser {
     let children_content = self.parse_children(block, context);
 
     let mut result = content;
+    // LaTeX ends a paragraph with an empty line
+    if context.format == OutputFormat::Latex && !result.is_empty() {
+      result.push('\n');
+    }
     if !children_content.is_empty() {
       if !result.is_empty() {
         result.push('\n');
@@ -24,6 +24,7 @@ impl BlockParser for QuoteListParser {
         let indent = context.get_indent();
         format!("{}{}", indent, content)
       },
+      OutputFormat::Latex => format!("\\begin{{quote}}\n{}", content),
     };
 
     let children_content = self.parse_children(block, context);
@@ -33,6 +34,9 @@ impl BlockParser for QuoteListParser {
       result.push('\n');
       result.push_str(&children_content);
     }
+    if context.format == OutputFormat::Latex {
+      result.push_str("\n\\end{quote}");
+    }
 
     Ok(ParseResult::new(result))
   }
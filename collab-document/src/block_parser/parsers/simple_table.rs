@@ -49,6 +49,40 @@ impl BlockParser for SimpleTableParser {
 
         Ok(ParseResult::new("".to_string()))
       },
+      OutputFormat::Latex => {
+        let Some(child_ids) = context.document_data.meta.children_map.get(&block.children) else {
+          return Ok(ParseResult::new("".to_string()));
+        };
+        let child_context = context.with_depth(context.depth + 1);
+        let rows = child_ids
+          .iter()
+          .filter_map(|child_id| context.document_data.blocks.get(child_id))
+          .collect::<Vec<_>>();
+        let num_columns = rows
+          .iter()
+          .filter_map(|row| context.document_data.meta.children_map.get(&row.children))
+          .map(|cell_ids| cell_ids.len())
+          .max()
+          .unwrap_or(0);
+        if num_columns == 0 {
+          return Ok(ParseResult::new("".to_string()));
+        }
+
+        let mut lines = vec![
+          format!("\\begin{{tabular}}{{|{}}}", "l|".repeat(num_columns)),
+          "\\hline".to_string(),
+        ];
+        for row in rows {
+          let row_content = context
+            .parser
+            .parse_block(row, &child_context)
+            .unwrap_or_default();
+          lines.push(row_content);
+          lines.push("\\hline".to_string());
+        }
+        lines.push("\\end{tabular}".to_string());
+        Ok(ParseResult::new(lines.join("\n")))
+      },
     }
   }
 
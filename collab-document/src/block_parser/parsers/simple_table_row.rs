@@ -33,6 +33,19 @@ impl BlockParser for SimpleTableRowParser {
         OutputFormat::Markdown => {
           format!("| {} |", cell_contents.join(" | "))
         },
+        // a line break would end the row of the tabular
+        OutputFormat::Latex => {
+          let cell_contents = cell_contents
+            .iter()
+            .map(|cell_content| {
+              cell_content
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+            })
+            .collect::<Vec<_>>();
+          format!("{} \\\\", cell_contents.join(" & "))
+        },
       };
 
       return Ok(ParseResult::new(result));
@@ -41,6 +41,14 @@ impl BlockParser for SubpageParser {
           format!("{}{}", indent, view_id)
         }
       },
+      // the subpages are exported on their own, only a comment keeps the reference
+      OutputFormat::Latex => {
+        if view_id.is_empty() {
+          "".to_string()
+        } else {
+          format!("% subpage: {}", view_id)
+        }
+      },
     };
 
     Ok(ParseResult::new(formatted_content))
@@ -35,6 +35,14 @@ impl BlockParser for TodoListParser {
         let indent = context.get_indent();
         format!("{}{}", indent, content)
       },
+      OutputFormat::Latex => {
+        let checkbox = if is_checked {
+          "$\\boxtimes$"
+        } else {
+          "$\\square$"
+        };
+        format!("\\begin{{itemize}}\n\\item[{}] {}", checkbox, content)
+      },
     };
 
     let children_content = self.parse_children(block, context);
@@ -44,6 +52,9 @@ impl BlockParser for TodoListParser {
       result.push('\n');
       result.push_str(&children_content);
     }
+    if context.format == OutputFormat::Latex {
+      result.push_str("\n\\end{itemize}");
+    }
 
     Ok(ParseResult::new(result))
   }
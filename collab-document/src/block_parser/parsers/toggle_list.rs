@@ -33,7 +33,7 @@ impl BlockParser for ToggleListParser {
           )
         }
       },
-      OutputFormat::PlainText => {
+      OutputFormat::PlainText | OutputFormat::Latex => {
         let indent = context.get_indent();
         let mut result = format!("{}{}", indent, content);
         if !children_content.is_empty() {
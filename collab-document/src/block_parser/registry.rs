@@ -31,6 +31,17 @@ impl BlockParserRegistry {
     self
   }
 
+  /// Register the parser under the given block type instead of [BlockParser::block_type], e.g.
+  /// the parsers of the custom blocks whose types are only known at runtime.
+  pub fn register_with_type(
+    &mut self,
+    block_type: &str,
+    parser: Arc<dyn BlockParser + Send + Sync>,
+  ) -> &mut Self {
+    self.parsers.insert(block_type.to_string(), parser);
+    self
+  }
+
   pub fn unregister(&mut self, block_type: &str) -> Option<Arc<dyn BlockParser + Send + Sync>> {
     self.parsers.remove(block_type)
   }
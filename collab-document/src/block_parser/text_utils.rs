@@ -1,11 +1,13 @@
 use crate::block_parser::OutputFormat;
+use crate::block_parser::latex::{escape_latex, format_text_with_latex_attributes};
 use crate::block_parser::traits::ParseContext;
 use crate::blocks::{AttrKey, Block, TextDelta};
+use crate::citation::Citation;
 use crate::error::DocumentError;
 use collab::preclude::{Any, Attrs};
 
 pub trait DocumentTextExtractor {
-  /// Get the plain text or markdown text from the block
+  /// Get the plain text, markdown text or LaTeX text from the block
   fn extract_text_from_block(
     &self,
     block: &Block,
@@ -25,6 +27,13 @@ pub trait DocumentTextExtractor {
     delta_json: &str,
     context: Option<&ParseContext>,
   ) -> Result<String, DocumentError>;
+
+  /// Get the LaTeX text from the delta json string with delegate support
+  fn extract_latex_text_from_delta_with_context(
+    &self,
+    delta_json: &str,
+    context: Option<&ParseContext>,
+  ) -> Result<String, DocumentError>;
 }
 
 pub struct DefaultDocumentTextExtractor;
@@ -52,6 +61,9 @@ impl DocumentTextExtractor for DefaultDocumentTextExtractor {
           OutputFormat::Markdown => {
             self.extract_markdown_text_from_delta_with_context(json, Some(context))
           },
+          OutputFormat::Latex => {
+            self.extract_latex_text_from_delta_with_context(json, Some(context))
+          },
         },
         None => Ok("".to_string()),
       };
@@ -120,9 +132,49 @@ impl DocumentTextExtractor for DefaultDocumentTextExtractor {
 
     Ok(result)
   }
+
+  fn extract_latex_text_from_delta_with_context(
+    &self,
+    delta_json: &str,
+    context: Option<&ParseContext>,
+  ) -> Result<String, DocumentError> {
+    let deltas: Vec<TextDelta> = serde_json::from_str(delta_json)
+      .map_err(|_| DocumentError::ParseDeltaJsonToTextDeltaError)?;
+
+    let mut result = "".to_string();
+
+    for delta in deltas {
+      if let TextDelta::Inserted(text, attributes) = delta {
+        if let Some(context) = context {
+          if let Some(delegate) = context.parser.get_delegate() {
+            if let Some(text) = delegate.handle_text_delta(&text, attributes.as_ref(), context) {
+              result.push_str(&escape_latex(&text));
+              continue;
+            }
+          }
+        }
+
+        let formatted_text = match attributes {
+          Some(attrs) => format_text_with_latex_attributes(&text, &attrs),
+          None => escape_latex(&text),
+        };
+        result.push_str(&formatted_text);
+      }
+    }
+
+    Ok(result)
+  }
 }
 
 pub fn format_text_with_attributes(text: &str, attributes: &Attrs) -> String {
+  // the pandoc citation syntax
+  if let Some(citation) = Citation::from_attrs(attributes) {
+    return match citation.locator {
+      Some(locator) => format!("[@{}, {}]", citation.key, locator),
+      None => format!("[@{}]", citation.key),
+    };
+  }
+
   let mut result = text.to_string();
 
   if let Some(Any::Bool(true)) = attributes.get(AttrKey::Bold.as_str()) {
@@ -11,6 +11,7 @@ use collab::preclude::Attrs;
 pub enum OutputFormat {
   PlainText,
   Markdown,
+  Latex,
 }
 
 #[derive(Debug, Clone)]
@@ -86,6 +87,8 @@ impl<'a> ParseContext<'a> {
     match self.format {
       OutputFormat::PlainText => "  ".repeat(self.depth),
       OutputFormat::Markdown => "  ".repeat(self.depth),
+      // LaTeX nests the environments instead
+      OutputFormat::Latex => "".to_string(),
     }
   }
 }
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+pub const AI_MODEL_FIELD: &str = "model";
+pub const AI_CREATED_AT_FIELD: &str = "created_at";
+pub const AI_PROMPT_ID_FIELD: &str = "prompt_id";
+pub const AI_RESPONSE_STATUS_FIELD: &str = "status";
+pub const AI_RESPONSE_HISTORY_FIELD: &str = "history";
+
+/// Typed view of the data of an AI prompt block. The prompt itself is the text of the block.
+///
+/// Use [AiPromptData::from_block_data] to read it and [AiPromptData::write_to_block_data] to
+/// write it back, other keys in the block data are left untouched.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AiPromptData {
+  /// The model the prompt was sent to, e.g. `gpt-4o`.
+  pub model: Option<String>,
+  /// When the prompt was sent, in seconds since the epoch.
+  pub created_at: i64,
+}
+
+impl AiPromptData {
+  pub fn from_block_data(data: &HashMap<String, Value>) -> Self {
+    Self {
+      model: string_field(data, AI_MODEL_FIELD),
+      created_at: data
+        .get(AI_CREATED_AT_FIELD)
+        .and_then(Value::as_i64)
+        .unwrap_or_default(),
+    }
+  }
+
+  pub fn write_to_block_data(&self, data: &mut HashMap<String, Value>) {
+    write_optional_string(data, AI_MODEL_FIELD, &self.model);
+    data.insert(AI_CREATED_AT_FIELD.to_string(), json!(self.created_at));
+  }
+
+  pub fn into_block_data(self) -> HashMap<String, Value> {
+    let mut data = HashMap::new();
+    self.write_to_block_data(&mut data);
+    data
+  }
+}
+
+/// The state of an AI response block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AiResponseStatus {
+  /// The response is being appended, see [crate::document::Document::append_ai_response_text].
+  #[default]
+  Streaming,
+  Completed,
+  /// The user stopped the response, its text is kept.
+  Stopped,
+  Failed,
+}
+
+impl AiResponseStatus {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      AiResponseStatus::Streaming => "streaming",
+      AiResponseStatus::Completed => "completed",
+      AiResponseStatus::Stopped => "stopped",
+      AiResponseStatus::Failed => "failed",
+    }
+  }
+
+  pub fn from_name(name: &str) -> Option<Self> {
+    match name {
+      "streaming" => Some(AiResponseStatus::Streaming),
+      "completed" => Some(AiResponseStatus::Completed),
+      "stopped" => Some(AiResponseStatus::Stopped),
+      "failed" => Some(AiResponseStatus::Failed),
+      _ => None,
+    }
+  }
+}
+
+/// A previous response to the prompt, kept when the response is regenerated.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AiResponseVersion {
+  pub text: String,
+  #[serde(default)]
+  pub model: Option<String>,
+  #[serde(default)]
+  pub created_at: i64,
+  #[serde(default)]
+  pub status: AiResponseStatus,
+}
+
+/// Typed view of the data of an AI response block. The response itself is the text of the block,
+/// appended while it's streamed.
+///
+/// Use [AiResponseData::from_block_data] to read it and [AiResponseData::write_to_block_data] to
+/// write it back, other keys in the block data are left untouched.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AiResponseData {
+  /// The id of the AI prompt block the block responds to.
+  pub prompt_id: Option<String>,
+  pub model: Option<String>,
+  /// When the response was requested, in seconds since the epoch.
+  pub created_at: i64,
+  pub status: AiResponseStatus,
+  /// The previous responses, the oldest first.
+  pub history: Vec<AiResponseVersion>,
+}
+
+impl AiResponseData {
+  /// Read the data of the block. A missing or unknown status is read as completed, a malformed
+  /// version of the history is skipped.
+  pub fn from_block_data(data: &HashMap<String, Value>) -> Self {
+    let history = data
+      .get(AI_RESPONSE_HISTORY_FIELD)
+      .and_then(Value::as_array)
+      .map(|versions| {
+        versions
+          .iter()
+          .filter_map(|version| serde_json::from_value(version.clone()).ok())
+          .collect()
+      })
+      .unwrap_or_default();
+    Self {
+      prompt_id: string_field(data, AI_PROMPT_ID_FIELD),
+      model: string_field(data, AI_MODEL_FIELD),
+      created_at: data
+        .get(AI_CREATED_AT_FIELD)
+        .and_then(Value::as_i64)
+        .unwrap_or_default(),
+      status: data
+        .get(AI_RESPONSE_STATUS_FIELD)
+        .and_then(Value::as_str)
+        .and_then(AiResponseStatus::from_name)
+        .unwrap_or(AiResponseStatus::Completed),
+      history,
+    }
+  }
+
+  pub fn write_to_block_data(&self, data: &mut HashMap<String, Value>) {
+    write_optional_string(data, AI_PROMPT_ID_FIELD, &self.prompt_id);
+    write_optional_string(data, AI_MODEL_FIELD, &self.model);
+    data.insert(AI_CREATED_AT_FIELD.to_string(), json!(self.created_at));
+    data.insert(
+      AI_RESPONSE_STATUS_FIELD.to_string(),
+      json!(self.status.as_str()),
+    );
+    if self.history.is_empty() {
+      data.remove(AI_RESPONSE_HISTORY_FIELD);
+    } else {
+      data.insert(AI_RESPONSE_HISTORY_FIELD.to_string(), json!(self.history));
+    }
+  }
+
+  pub fn into_block_data(self) -> HashMap<String, Value> {
+    let mut data = HashMap::new();
+    self.write_to_block_data(&mut data);
+    data
+  }
+}
+
+fn string_field(data: &HashMap<String, Value>, key: &str) -> Option<String> {
+  data
+    .get(key)
+    .and_then(Value::as_str)
+    .filter(|value| !value.is_empty())
+    .map(str::to_string)
+}
+
+fn write_optional_string(data: &mut HashMap<String, Value>, key: &str, value: &Option<String>) {
+  match value {
+    Some(value) => {
+      data.insert(key.to_string(), json!(value));
+    },
+    None => {
+      data.remove(key);
+    },
+  }
+}
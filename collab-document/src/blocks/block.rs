@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
-use crate::blocks::{Block, ChildrenOperation, hashmap_to_json_str, json_str_to_hashmap};
+use crate::blocks::{
+  Block, ChildrenOperation, adapt_legacy_block_data, hashmap_to_json_str, json_str_to_hashmap,
+};
 use crate::error::DocumentError;
 use collab::preclude::{Map, MapExt, MapRef, ReadTxn, TransactionMut};
 use serde_json::Value;
@@ -11,13 +13,14 @@ pub const EXTERNAL_TYPE_MAP: &str = "map";
 
 const ID: &str = "id";
 const TYPE: &str = "ty";
-const PARENT: &str = "parent";
+pub(crate) const PARENT: &str = "parent";
 const CHILDREN: &str = "children";
 const DATA: &str = "data";
 const EXTERNAL_ID: &str = "external_id";
 const EXTERNAL_TYPE: &str = "external_type";
 
 /// for block operate, there has a root map, and a children map.
+#[derive(Clone)]
 pub struct BlockOperation {
   root: MapRef,
   children_operation: ChildrenOperation,
@@ -44,6 +47,11 @@ impl BlockOperation {
       .collect()
   }
 
+  /// The number of blocks, the page block included.
+  pub fn num_of_blocks<T: ReadTxn>(&self, txn: &T) -> usize {
+    self.root.len(txn) as usize
+  }
+
   /// create a block
   pub fn create_block_with_txn(
     &self,
@@ -107,6 +115,31 @@ impl BlockOperation {
       .map(|map| block_from_map(txn, map))
   }
 
+  /// Returns the parent id of the block with the given id, without decoding the rest of the block.
+  pub fn get_block_parent_with_txn<T: ReadTxn>(&self, txn: &T, id: &str) -> Option<String> {
+    self
+      .root
+      .get_with_txn::<T, MapRef>(txn, id)
+      .and_then(|map| map.get_with_txn(txn, PARENT))
+  }
+
+  /// Returns a map from the children id and the external id of each block to the block id.
+  pub fn get_block_id_index<T: ReadTxn>(&self, txn: &T) -> HashMap<String, String> {
+    let mut index = HashMap::new();
+    for (id, value) in self.root.iter(txn) {
+      let Ok(map) = value.cast::<MapRef>() else {
+        continue;
+      };
+      if let Some(children) = map.get_with_txn::<T, String>(txn, CHILDREN) {
+        index.insert(children, id.to_string());
+      }
+      if let Some(external_id) = map.get_with_txn::<T, String>(txn, EXTERNAL_ID) {
+        index.insert(external_id, id.to_string());
+      }
+    }
+    index
+  }
+
   /// Update the block with the given id.
   /// Except \`data\` and \`parent\` and \'external_id\' and \'external_type\' field, other fields can be updated.
   /// If you want to turn into other block, you should delete the block and create a new block.
@@ -152,7 +185,8 @@ fn block_from_map<T: ReadTxn>(txn: &T, map: MapRef) -> Block {
   let parent: String = map.get_with_txn(txn, PARENT).unwrap_or_default();
   let children: String = map.get_with_txn(txn, CHILDREN).unwrap_or_default();
   let json_str: String = map.get_with_txn(txn, DATA).unwrap_or_default();
-  let data = json_str_to_hashmap(&json_str).unwrap_or_default();
+  let mut data = json_str_to_hashmap(&json_str).unwrap_or_default();
+  adapt_legacy_block_data(&ty, &mut data);
   let external_id: Option<String> = map.get_with_txn(txn, EXTERNAL_ID);
   let external_type: Option<String> = map.get_with_txn(txn, EXTERNAL_TYPE);
   Block {
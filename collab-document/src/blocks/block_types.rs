@@ -23,10 +23,13 @@ pub enum BlockType {
   MathEquation,
   Code,
   AiWriter,
+  AiPrompt,
+  AiResponse,
   ToggleList,
   Outline,
   LinkPreview,
   Video,
+  Audio,
   File,
   SubPage,
   Error,
@@ -35,6 +38,9 @@ pub enum BlockType {
   SimpleTableCell,
   SimpleColumns,
   SimpleColumn,
+  Diagram,
+  Embed,
+  Bibliography,
   Custom(String),
 
   // Legacy types
@@ -62,10 +68,13 @@ impl BlockType {
       BlockType::MathEquation => "math_equation",
       BlockType::Code => "code",
       BlockType::AiWriter => "ai_writer",
+      BlockType::AiPrompt => "ai_prompt",
+      BlockType::AiResponse => "ai_response",
       BlockType::ToggleList => "toggle_list",
       BlockType::Outline => "outline",
       BlockType::LinkPreview => "link_preview",
       BlockType::Video => "video",
+      BlockType::Audio => "audio",
       BlockType::File => "file",
       BlockType::SubPage => "sub_page",
       BlockType::Error => "errorBlockComponentBuilderKey",
@@ -74,6 +83,9 @@ impl BlockType {
       BlockType::SimpleTableCell => "simple_table_cell",
       BlockType::SimpleColumns => "simple_columns",
       BlockType::SimpleColumn => "simple_column",
+      BlockType::Diagram => "diagram",
+      BlockType::Embed => "embed",
+      BlockType::Bibliography => "bibliography",
       BlockType::Table => "table",
       BlockType::TableCell => "table/cell",
       BlockType::Custom(s) => s,
@@ -99,10 +111,13 @@ impl BlockType {
       "math_equation" => BlockType::MathEquation,
       "code" => BlockType::Code,
       "ai_writer" => BlockType::AiWriter,
+      "ai_prompt" => BlockType::AiPrompt,
+      "ai_response" => BlockType::AiResponse,
       "toggle_list" => BlockType::ToggleList,
       "outline" => BlockType::Outline,
       "link_preview" => BlockType::LinkPreview,
       "video" => BlockType::Video,
+      "audio" => BlockType::Audio,
       "file" => BlockType::File,
       "sub_page" => BlockType::SubPage,
       "errorBlockComponentBuilderKey" => BlockType::Error,
@@ -111,6 +126,9 @@ impl BlockType {
       "simple_table_cell" => BlockType::SimpleTableCell,
       "simple_columns" => BlockType::SimpleColumns,
       "simple_column" => BlockType::SimpleColumn,
+      "diagram" => BlockType::Diagram,
+      "embed" => BlockType::Embed,
+      "bibliography" => BlockType::Bibliography,
       "table" => BlockType::Table,
       "table/cell" => BlockType::TableCell,
       _ => BlockType::Custom(s.to_string()),
@@ -0,0 +1,295 @@
+use crate::blocks::{
+  AiPromptData, AiResponseData, AiResponseStatus, Block, BlockType, DiagramData, DiagramKind,
+  DocumentData, DocumentMeta, SimpleTableData,
+};
+use crate::document_data::generate_id;
+use crate::error::DocumentError;
+use crate::importer::define::{
+  CHECKED_FIELD, COL_POSITION_FIELD, EMBED_PROVIDER_FIELD, EXTERNAL_IMAGE_TYPE, IMAGE_TYPE_FIELD,
+  LEVEL_FIELD, ROW_POSITION_FIELD, URL_FIELD,
+};
+use crate::importer::embed::EmbedProvider;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+/// A block to add to a document, with its text and its children, see [DocumentDataBuilder] and
+/// [DocumentData::push_block]. The ids of the blocks are generated when they are added, unless
+/// the block was given an id.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewBlock {
+  pub id: Option<String>,
+  pub ty: String,
+  pub data: HashMap<String, Value>,
+  /// The delta of the text of the block, None for the blocks without text, e.g. an image.
+  pub delta: Option<Value>,
+  pub children: Vec<NewBlock>,
+}
+
+impl NewBlock {
+  pub fn new(ty: BlockType) -> Self {
+    Self {
+      id: None,
+      ty: ty.to_string(),
+      data: HashMap::new(),
+      delta: None,
+      children: vec![],
+    }
+  }
+
+  /// Use the id instead of a generated one, e.g. to keep the ids that other blocks refer to.
+  pub fn with_id(mut self, id: &str) -> Self {
+    self.id = Some(id.to_string());
+    self
+  }
+
+  /// Set the plain text of the block.
+  pub fn with_text(mut self, text: &str) -> Self {
+    let delta = if text.is_empty() {
+      json!([])
+    } else {
+      json!([{ "insert": text }])
+    };
+    self.delta = Some(delta);
+    self
+  }
+
+  /// Set the delta of the block, e.g. `[{ "insert": "bold", "attributes": { "bold": true } }]`.
+  pub fn with_delta(mut self, delta: Value) -> Self {
+    self.delta = Some(delta);
+    self
+  }
+
+  pub fn with_data(mut self, key: &str, value: Value) -> Self {
+    self.data.insert(key.to_string(), value);
+    self
+  }
+
+  pub fn with_child(mut self, child: NewBlock) -> Self {
+    self.children.push(child);
+    self
+  }
+
+  pub fn with_children(mut self, children: Vec<NewBlock>) -> Self {
+    self.children.extend(children);
+    self
+  }
+}
+
+impl Block {
+  pub fn paragraph(text: &str) -> NewBlock {
+    NewBlock::new(BlockType::Paragraph).with_text(text)
+  }
+
+  pub fn heading(level: u8, text: &str) -> NewBlock {
+    NewBlock::new(BlockType::Heading)
+      .with_data(LEVEL_FIELD, json!(level))
+      .with_text(text)
+  }
+
+  pub fn todo(text: &str, checked: bool) -> NewBlock {
+    NewBlock::new(BlockType::TodoList)
+      .with_data(CHECKED_FIELD, json!(checked))
+      .with_text(text)
+  }
+
+  /// An image hosted at the url.
+  pub fn image(url: &str) -> NewBlock {
+    NewBlock::new(BlockType::Image)
+      .with_data(URL_FIELD, json!(url))
+      .with_data(IMAGE_TYPE_FIELD, json!(EXTERNAL_IMAGE_TYPE))
+  }
+
+  /// A diagram rendered by the clients from its source.
+  pub fn diagram(kind: DiagramKind, source: &str) -> NewBlock {
+    let mut block = NewBlock::new(BlockType::Diagram);
+    block.data = DiagramData {
+      kind,
+      source: source.to_string(),
+    }
+    .into_block_data();
+    block
+  }
+
+  /// An embed of the page at the url, e.g. a YouTube video or a Figma file, see
+  /// [crate::importer::embed::EmbedProvider::from_url].
+  pub fn embed(url: &str) -> NewBlock {
+    let block = NewBlock::new(BlockType::Embed).with_data(URL_FIELD, json!(url));
+    match EmbedProvider::from_url(url) {
+      Some(provider) => block.with_data(EMBED_PROVIDER_FIELD, json!(provider.as_str())),
+      None => block,
+    }
+  }
+
+  /// An AI prompt, the prompt is the text of the block.
+  pub fn ai_prompt(prompt: &str, model: Option<&str>) -> NewBlock {
+    let mut block = NewBlock::new(BlockType::AiPrompt).with_text(prompt);
+    block.data = AiPromptData {
+      model: model.map(str::to_string),
+      created_at: 0,
+    }
+    .into_block_data();
+    block
+  }
+
+  /// A completed AI response, the response is the text of the block.
+  pub fn ai_response(response: &str, prompt_id: Option<&str>) -> NewBlock {
+    let mut block = NewBlock::new(BlockType::AiResponse).with_text(response);
+    block.data = AiResponseData {
+      prompt_id: prompt_id.map(str::to_string),
+      status: AiResponseStatus::Completed,
+      ..Default::default()
+    }
+    .into_block_data();
+    block
+  }
+
+  pub fn divider() -> NewBlock {
+    NewBlock::new(BlockType::Divider)
+  }
+
+  /// An empty simple table, each of its cells holds an empty paragraph.
+  pub fn table(rows: usize, cols: usize) -> NewBlock {
+    let rows = (0..rows)
+      .map(|row| {
+        let cells = (0..cols)
+          .map(|col| {
+            NewBlock::new(BlockType::SimpleTableCell)
+              .with_data(ROW_POSITION_FIELD, json!(row))
+              .with_data(COL_POSITION_FIELD, json!(col))
+              .with_child(Block::paragraph(""))
+          })
+          .collect();
+        NewBlock::new(BlockType::SimpleTableRow).with_children(cells)
+      })
+      .collect();
+    NewBlock {
+      data: SimpleTableData::default().into_block_data(),
+      ..NewBlock::new(BlockType::SimpleTable)
+    }
+    .with_children(rows)
+  }
+}
+
+impl DocumentData {
+  /// Add the block and its descendants as the last child of the parent. Return the id of the
+  /// block.
+  pub fn push_block(&mut self, parent_id: &str, block: NewBlock) -> Result<String, DocumentError> {
+    let children_id = self
+      .blocks
+      .get(parent_id)
+      .map(|parent| parent.children.clone())
+      .ok_or(DocumentError::ParentIsNotFound)?;
+    let block_id = self.insert_new_block(parent_id, block);
+    self
+      .meta
+      .children_map
+      .entry(children_id)
+      .or_default()
+      .push(block_id.clone());
+    Ok(block_id)
+  }
+
+  fn insert_new_block(&mut self, parent_id: &str, block: NewBlock) -> String {
+    let block_id = block.id.unwrap_or_else(generate_id);
+    let external_id = block.delta.map(|delta| {
+      self
+        .meta
+        .text_map
+        .get_or_insert_with(Default::default)
+        .insert(block_id.clone(), delta.to_string());
+      block_id.clone()
+    });
+    let children_ids = block
+      .children
+      .into_iter()
+      .map(|child| self.insert_new_block(&block_id, child))
+      .collect();
+    self
+      .meta
+      .children_map
+      .insert(block_id.clone(), children_ids);
+    self.blocks.insert(
+      block_id.clone(),
+      Block {
+        id: block_id.clone(),
+        ty: block.ty,
+        parent: parent_id.to_string(),
+        children: block_id.clone(),
+        external_type: external_id.as_ref().map(|_| "text".to_string()),
+        external_id,
+        data: block.data,
+      },
+    );
+    block_id
+  }
+}
+
+/// Build a [DocumentData] block by block, e.g. `DocumentDataBuilder::new().with_block(
+/// Block::heading(1, "Title")).build()`. The children map and the text map are filled in as the
+/// blocks are added.
+#[derive(Debug, Clone)]
+pub struct DocumentDataBuilder {
+  data: DocumentData,
+}
+
+impl Default for DocumentDataBuilder {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl DocumentDataBuilder {
+  /// A document with an empty page.
+  pub fn new() -> Self {
+    Self::with_page_id(&generate_id())
+  }
+
+  pub fn with_page_id(page_id: &str) -> Self {
+    let page = Block {
+      id: page_id.to_string(),
+      ty: BlockType::Page.to_string(),
+      parent: "".to_string(),
+      children: page_id.to_string(),
+      external_id: None,
+      external_type: None,
+      data: HashMap::new(),
+    };
+    Self {
+      data: DocumentData {
+        page_id: page_id.to_string(),
+        blocks: HashMap::from([(page_id.to_string(), page)]),
+        meta: DocumentMeta {
+          children_map: HashMap::from([(page_id.to_string(), vec![])]),
+          text_map: Some(HashMap::new()),
+          lazy_text: false,
+        },
+      },
+    }
+  }
+
+  pub fn page_id(&self) -> &str {
+    &self.data.page_id
+  }
+
+  /// Add the block at the end of the page.
+  pub fn with_block(mut self, block: NewBlock) -> Self {
+    self.push(block);
+    self
+  }
+
+  /// Add the block at the end of the page and return its id.
+  pub fn push(&mut self, block: NewBlock) -> String {
+    let page_id = self.data.page_id.clone();
+    // The page block always exists.
+    self.data.push_block(&page_id, block).unwrap_or_default()
+  }
+
+  /// Add the block as the last child of the parent and return its id.
+  pub fn push_child(&mut self, parent_id: &str, block: NewBlock) -> Result<String, DocumentError> {
+    self.data.push_block(parent_id, block)
+  }
+
+  pub fn build(self) -> DocumentData {
+    self.data
+  }
+}
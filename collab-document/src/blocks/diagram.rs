@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use serde_json::{Value, json};
+
+pub const DIAGRAM_KIND: &str = "kind";
+pub const DIAGRAM_SOURCE: &str = "source";
+
+/// The languages a diagram block is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagramKind {
+  Mermaid,
+  PlantUml,
+}
+
+impl DiagramKind {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      DiagramKind::Mermaid => "mermaid",
+      DiagramKind::PlantUml => "plantuml",
+    }
+  }
+
+  /// The kind of diagram a fenced code block is written in, e.g. ```` ```mermaid ````. None for
+  /// the code blocks of other languages.
+  pub fn from_code_language(language: &str) -> Option<Self> {
+    match language.trim().to_lowercase().as_str() {
+      "mermaid" => Some(DiagramKind::Mermaid),
+      "plantuml" | "puml" => Some(DiagramKind::PlantUml),
+      _ => None,
+    }
+  }
+}
+
+/// Typed view of the data of a diagram block: the diagram is stored as its source, the clients
+/// render it.
+///
+/// Use [DiagramData::from_block_data] to read it and [DiagramData::write_to_block_data] to write
+/// it back, other keys in the block data are left untouched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagramData {
+  pub kind: DiagramKind,
+  pub source: String,
+}
+
+impl DiagramData {
+  /// The diagram of a fenced code block, None when the language is not a diagram language.
+  pub fn from_code_block(language: &str, code: &str) -> Option<Self> {
+    DiagramKind::from_code_language(language).map(|kind| Self {
+      kind,
+      source: code.to_string(),
+    })
+  }
+
+  /// Read the diagram from the block data, None if the kind is missing or unknown.
+  pub fn from_block_data(data: &HashMap<String, Value>) -> Option<Self> {
+    let kind = data
+      .get(DIAGRAM_KIND)
+      .and_then(|kind| kind.as_str())
+      .and_then(DiagramKind::from_code_language)?;
+    let source = data
+      .get(DIAGRAM_SOURCE)
+      .and_then(|source| source.as_str())
+      .unwrap_or_default()
+      .to_string();
+    Some(Self { kind, source })
+  }
+
+  /// Write the diagram into the block data.
+  pub fn write_to_block_data(&self, data: &mut HashMap<String, Value>) {
+    data.insert(DIAGRAM_KIND.to_string(), json!(self.kind.as_str()));
+    data.insert(DIAGRAM_SOURCE.to_string(), json!(self.source));
+  }
+
+  pub fn into_block_data(self) -> HashMap<String, Value> {
+    let mut data = HashMap::new();
+    self.write_to_block_data(&mut data);
+    data
+  }
+}
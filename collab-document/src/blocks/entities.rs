@@ -33,6 +33,11 @@ pub struct DocumentMeta {
   /// - @key: [Block]'s `external_id`
   /// - @value: text delta json string - "\[ { "insert": "Hello World!", "attributes": { "bold": true } } \]"
   pub text_map: Option<HashMap<String, String>>,
+  /// True if the text deltas are kept in the document instead of the `text_map`.
+  /// The `text_map` only holds the texts that have been hydrated, the others are read on demand
+  /// with `Document::get_text` or `Document::load_block_text`.
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  pub lazy_text: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
@@ -0,0 +1,178 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A block data shape written by older versions of the apps.
+///
+/// Blocks are adapted to the current shape when they are read, the stored data is left as is, so
+/// long-lived documents keep working without an eager migration. Each hit is counted, see
+/// [legacy_read_metrics], to know when a legacy path can be removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LegacyDataShape {
+  /// `heading.level` stored as a string, e.g. `"2"`.
+  StringHeadingLevel,
+  /// `numbered_list.number` stored as a string.
+  StringListNumber,
+  /// `todo_list.checked` stored as `"true"`/`"false"` or `0`/`1`.
+  NonBoolChecked,
+  /// `sub_page.view_id`, renamed to `viewId`.
+  SnakeCaseViewId,
+  /// `simple_table.rows_len` and `simple_table.cols_len`, renamed to `rowsLen` and `colsLen`.
+  SnakeCaseTableLen,
+  /// `simple_table_cell.position` stored as `"<row>,<col>"` or `"<row>:<col>"`, split into
+  /// `rowPosition` and `colPosition`.
+  DelimitedCellPosition,
+}
+
+impl LegacyDataShape {
+  pub const ALL: [LegacyDataShape; 6] = [
+    LegacyDataShape::StringHeadingLevel,
+    LegacyDataShape::StringListNumber,
+    LegacyDataShape::NonBoolChecked,
+    LegacyDataShape::SnakeCaseViewId,
+    LegacyDataShape::SnakeCaseTableLen,
+    LegacyDataShape::DelimitedCellPosition,
+  ];
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      LegacyDataShape::StringHeadingLevel => "string_heading_level",
+      LegacyDataShape::StringListNumber => "string_list_number",
+      LegacyDataShape::NonBoolChecked => "non_bool_checked",
+      LegacyDataShape::SnakeCaseViewId => "snake_case_view_id",
+      LegacyDataShape::SnakeCaseTableLen => "snake_case_table_len",
+      LegacyDataShape::DelimitedCellPosition => "delimited_cell_position",
+    }
+  }
+
+  fn index(&self) -> usize {
+    *self as usize
+  }
+}
+
+static LEGACY_READ_HITS: [AtomicU64; 6] = [
+  AtomicU64::new(0),
+  AtomicU64::new(0),
+  AtomicU64::new(0),
+  AtomicU64::new(0),
+  AtomicU64::new(0),
+  AtomicU64::new(0),
+];
+
+/// The number of blocks adapted from each legacy shape since the process started, or since the
+/// last [reset_legacy_read_metrics].
+pub fn legacy_read_metrics() -> HashMap<LegacyDataShape, u64> {
+  LegacyDataShape::ALL
+    .iter()
+    .map(|shape| {
+      (
+        *shape,
+        LEGACY_READ_HITS[shape.index()].load(Ordering::Relaxed),
+      )
+    })
+    .collect()
+}
+
+pub fn reset_legacy_read_metrics() {
+  for hits in LEGACY_READ_HITS.iter() {
+    hits.store(0, Ordering::Relaxed);
+  }
+}
+
+/// Map the legacy keys and value shapes of the block data to the current schema. Return the
+/// legacy shapes that were found.
+pub fn adapt_legacy_block_data(
+  block_type: &str,
+  data: &mut HashMap<String, Value>,
+) -> Vec<LegacyDataShape> {
+  let mut shapes = vec![];
+  match block_type {
+    "heading" => {
+      if string_to_number(data, "level") {
+        shapes.push(LegacyDataShape::StringHeadingLevel);
+      }
+    },
+    "numbered_list" => {
+      if string_to_number(data, "number") {
+        shapes.push(LegacyDataShape::StringListNumber);
+      }
+    },
+    "todo_list" => {
+      if to_bool(data, "checked") {
+        shapes.push(LegacyDataShape::NonBoolChecked);
+      }
+    },
+    "sub_page" => {
+      if rename_key(data, "view_id", "viewId") {
+        shapes.push(LegacyDataShape::SnakeCaseViewId);
+      }
+    },
+    "simple_table" => {
+      let rows = rename_key(data, "rows_len", "rowsLen");
+      let cols = rename_key(data, "cols_len", "colsLen");
+      if rows || cols {
+        shapes.push(LegacyDataShape::SnakeCaseTableLen);
+      }
+    },
+    "simple_table_cell" => {
+      if split_position(data) {
+        shapes.push(LegacyDataShape::DelimitedCellPosition);
+      }
+    },
+    _ => {},
+  }
+  for shape in shapes.iter() {
+    LEGACY_READ_HITS[shape.index()].fetch_add(1, Ordering::Relaxed);
+  }
+  shapes
+}
+
+fn string_to_number(data: &mut HashMap<String, Value>, key: &str) -> bool {
+  let Some(number) = data
+    .get(key)
+    .and_then(Value::as_str)
+    .and_then(|value| value.trim().parse::<i64>().ok())
+  else {
+    return false;
+  };
+  data.insert(key.to_string(), Value::from(number));
+  true
+}
+
+fn to_bool(data: &mut HashMap<String, Value>, key: &str) -> bool {
+  let checked = match data.get(key) {
+    Some(Value::String(value)) => value.eq_ignore_ascii_case("true") || value == "1",
+    Some(Value::Number(value)) => value.as_i64() == Some(1),
+    _ => return false,
+  };
+  data.insert(key.to_string(), Value::Bool(checked));
+  true
+}
+
+/// Move the value of `from` to `to`. The current key wins when both are present.
+fn rename_key(data: &mut HashMap<String, Value>, from: &str, to: &str) -> bool {
+  let Some(value) = data.remove(from) else {
+    return false;
+  };
+  data.entry(to.to_string()).or_insert(value);
+  true
+}
+
+fn split_position(data: &mut HashMap<String, Value>) -> bool {
+  if data.contains_key("rowPosition") || data.contains_key("colPosition") {
+    return false;
+  }
+  let Some(position) = data.get("position").and_then(Value::as_str) else {
+    return false;
+  };
+  let Some((row, col)) = position.split_once([',', ':']) else {
+    return false;
+  };
+  let (Ok(row), Ok(col)) = (row.trim().parse::<i64>(), col.trim().parse::<i64>()) else {
+    return false;
+  };
+  data.remove("position");
+  data.insert("rowPosition".to_string(), Value::from(row));
+  data.insert("colPosition".to_string(), Value::from(col));
+  true
+}
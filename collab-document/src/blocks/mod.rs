@@ -1,17 +1,33 @@
+mod ai_transcript;
 mod attr_keys;
 mod block;
 mod block_types;
+mod builder;
 mod children;
+mod diagram;
 mod entities;
+mod legacy;
+mod schema;
+mod simple_column;
+mod simple_table;
+mod subtree;
 mod text;
 mod text_entities;
 mod utils;
 
+pub use ai_transcript::*;
 pub use attr_keys::*;
 pub use block::*;
 pub use block_types::*;
+pub use builder::*;
 pub use children::*;
+pub use diagram::*;
 pub use entities::*;
+pub use legacy::*;
+pub use schema::*;
+pub use simple_column::*;
+pub use simple_table::*;
+pub use subtree::*;
 pub use text::*;
 pub use text_entities::*;
 pub use utils::*;
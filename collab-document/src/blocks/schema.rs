@@ -0,0 +1,263 @@
+use crate::blocks::{Block, BlockType, DocumentData};
+use crate::error::DocumentError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The kind of value of a field of the block data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockFieldKind {
+  String,
+  Number,
+  Bool,
+  Array,
+  Object,
+  /// Any json value.
+  Any,
+}
+
+impl BlockFieldKind {
+  pub fn matches(&self, value: &Value) -> bool {
+    match self {
+      BlockFieldKind::String => value.is_string(),
+      BlockFieldKind::Number => value.is_number(),
+      BlockFieldKind::Bool => value.is_boolean(),
+      BlockFieldKind::Array => value.is_array(),
+      BlockFieldKind::Object => value.is_object(),
+      BlockFieldKind::Any => true,
+    }
+  }
+}
+
+/// A field of the data of a custom block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockFieldSchema {
+  pub name: String,
+  pub kind: BlockFieldKind,
+  /// Whether a block without the field is invalid.
+  #[serde(default)]
+  pub required: bool,
+  /// The value of the field of a new block, see [BlockSchema::default_data].
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub default: Option<Value>,
+}
+
+/// Which blocks a custom block can have as children.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "types", rename_all = "snake_case")]
+pub enum ChildPolicy {
+  /// The block has no children, e.g. a poll or a drawing.
+  #[default]
+  None,
+  /// Any block can be a child.
+  Any,
+  /// Only the blocks of the given types can be children.
+  Only(Vec<String>),
+}
+
+impl ChildPolicy {
+  pub fn allows(&self, child_type: &str) -> bool {
+    match self {
+      ChildPolicy::None => false,
+      ChildPolicy::Any => true,
+      ChildPolicy::Only(types) => types.iter().any(|ty| ty == child_type),
+    }
+  }
+}
+
+/// The schema of a custom block type, e.g. the drawio or the poll block of a plugin, see
+/// [BlockSchemaRegistry].
+///
+/// The schemas are serializable, so a plugin can ship them as json.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockSchema {
+  /// The type of the block, stored in [Block::ty].
+  pub ty: String,
+  #[serde(default)]
+  pub fields: Vec<BlockFieldSchema>,
+  #[serde(default)]
+  pub children: ChildPolicy,
+  /// The field exported as the content of the block by the plain text and markdown exports, see
+  /// [crate::block_parser::DocumentParser::with_block_schemas]. The block is exported empty if
+  /// it's None.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub text_field: Option<String>,
+}
+
+impl BlockSchema {
+  pub fn new(ty: &str) -> Self {
+    Self {
+      ty: ty.to_string(),
+      fields: vec![],
+      children: ChildPolicy::default(),
+      text_field: None,
+    }
+  }
+
+  /// Add a required field.
+  pub fn with_field(mut self, name: &str, kind: BlockFieldKind) -> Self {
+    self.fields.push(BlockFieldSchema {
+      name: name.to_string(),
+      kind,
+      required: true,
+      default: None,
+    });
+    self
+  }
+
+  /// Add an optional field, with the value of a new block.
+  pub fn with_optional_field(
+    mut self,
+    name: &str,
+    kind: BlockFieldKind,
+    default: Option<Value>,
+  ) -> Self {
+    self.fields.push(BlockFieldSchema {
+      name: name.to_string(),
+      kind,
+      required: false,
+      default,
+    });
+    self
+  }
+
+  pub fn with_children(mut self, children: ChildPolicy) -> Self {
+    self.children = children;
+    self
+  }
+
+  /// See [BlockSchema::text_field].
+  pub fn with_text_field(mut self, name: &str) -> Self {
+    self.text_field = Some(name.to_string());
+    self
+  }
+
+  pub fn field(&self, name: &str) -> Option<&BlockFieldSchema> {
+    self.fields.iter().find(|field| field.name == name)
+  }
+
+  /// The data of a new block: the default values of the fields that have one.
+  pub fn default_data(&self) -> HashMap<String, Value> {
+    self
+      .fields
+      .iter()
+      .filter_map(|field| Some((field.name.clone(), field.default.clone()?)))
+      .collect()
+  }
+
+  /// Check that the required fields are present and that the fields have the expected kind. The
+  /// fields that are not in the schema are kept, e.g. the ones added by a newer version of the
+  /// plugin.
+  pub fn validate_data(&self, data: &HashMap<String, Value>) -> Result<(), DocumentError> {
+    for field in self.fields.iter() {
+      match data.get(&field.name) {
+        None | Some(Value::Null) if field.required => {
+          return Err(self.violation(format!("the field `{}` is missing", field.name)));
+        },
+        Some(value) if !value.is_null() && !field.kind.matches(value) => {
+          return Err(self.violation(format!(
+            "the field `{}` is not a {:?} value",
+            field.name, field.kind
+          )));
+        },
+        _ => {},
+      }
+    }
+    Ok(())
+  }
+
+  fn violation(&self, reason: String) -> DocumentError {
+    DocumentError::BlockSchemaViolation {
+      block_type: self.ty.clone(),
+      reason,
+    }
+  }
+}
+
+/// The custom block types registered by the embedders of the document, so the plugins can add
+/// their blocks without extending [BlockType]. The blocks of the registered types are validated
+/// when they are written, see [crate::document::Document::set_block_schemas], and exported with
+/// [crate::block_parser::DocumentParser::with_block_schemas].
+///
+/// The blocks of the types that are not registered are not validated.
+#[derive(Debug, Clone, Default)]
+pub struct BlockSchemaRegistry {
+  schemas: HashMap<String, BlockSchema>,
+}
+
+impl BlockSchemaRegistry {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Register the schema of a custom block type, replacing the previous schema of the type.
+  /// The built-in block types can't be registered.
+  pub fn register(&mut self, schema: BlockSchema) -> Result<&mut Self, DocumentError> {
+    if schema.ty.is_empty() || !matches!(BlockType::from_block_ty(&schema.ty), BlockType::Custom(_))
+    {
+      return Err(DocumentError::BlockSchemaViolation {
+        block_type: schema.ty,
+        reason: "only the custom block types can be registered".to_string(),
+      });
+    }
+    self.schemas.insert(schema.ty.clone(), schema);
+    Ok(self)
+  }
+
+  pub fn unregister(&mut self, block_type: &str) -> Option<BlockSchema> {
+    self.schemas.remove(block_type)
+  }
+
+  pub fn get(&self, block_type: &str) -> Option<&BlockSchema> {
+    self.schemas.get(block_type)
+  }
+
+  pub fn schemas(&self) -> impl Iterator<Item = &BlockSchema> {
+    self.schemas.values()
+  }
+
+  /// Validate the data of the block if its type is registered.
+  pub fn validate_block(&self, block: &Block) -> Result<(), DocumentError> {
+    match self.get(&block.ty) {
+      Some(schema) => schema.validate_data(&block.data),
+      None => Ok(()),
+    }
+  }
+
+  /// Check that a block of the child type can be a child of a block of the parent type, if the
+  /// parent type is registered.
+  pub fn validate_child(&self, parent_type: &str, child_type: &str) -> Result<(), DocumentError> {
+    match self.get(parent_type) {
+      Some(schema) if !schema.children.allows(child_type) => {
+        Err(schema.violation(format!("a `{}` block can't be a child", child_type)))
+      },
+      _ => Ok(()),
+    }
+  }
+
+  /// Validate the blocks of the document and their children.
+  pub fn validate_document(&self, data: &DocumentData) -> Result<(), DocumentError> {
+    for block in data.blocks.values() {
+      self.validate_block(block)?;
+      let Some(children) = data.meta.children_map.get(&block.children) else {
+        continue;
+      };
+      for child in children.iter().filter_map(|id| data.blocks.get(id)) {
+        self.validate_child(&block.ty, &child.ty)?;
+      }
+    }
+    Ok(())
+  }
+}
+
+impl From<Vec<BlockSchema>> for BlockSchemaRegistry {
+  /// The schemas of the built-in block types are skipped.
+  fn from(schemas: Vec<BlockSchema>) -> Self {
+    let mut registry = Self::new();
+    for schema in schemas {
+      let _ = registry.register(schema);
+    }
+    registry
+  }
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use serde_json::{Value, json};
+
+use super::simple_table::value_as_f64;
+
+pub const SIMPLE_COLUMN_WIDTH_RATIO: &str = "ratio";
+
+/// Typed view of the layout settings stored in the data of a simple column block.
+///
+/// Use [SimpleColumnData::from_block_data] to read them and
+/// [SimpleColumnData::write_to_block_data] to write them back, other keys in the block data are
+/// left untouched.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimpleColumnData {
+  /// The share of the width of the parent simple columns block taken by the column, between 0
+  /// and 1. None if the columns share the width equally.
+  pub width_ratio: Option<f64>,
+}
+
+impl SimpleColumnData {
+  /// Read the settings from the block data. A missing, malformed or out of range ratio is read
+  /// as None.
+  pub fn from_block_data(data: &HashMap<String, Value>) -> Self {
+    let width_ratio = data
+      .get(SIMPLE_COLUMN_WIDTH_RATIO)
+      .and_then(value_as_f64)
+      .filter(|ratio| *ratio > 0.0 && *ratio <= 1.0);
+    Self { width_ratio }
+  }
+
+  /// Write the settings into the block data.
+  pub fn write_to_block_data(&self, data: &mut HashMap<String, Value>) {
+    match self.width_ratio {
+      Some(ratio) => {
+        data.insert(SIMPLE_COLUMN_WIDTH_RATIO.to_string(), json!(ratio));
+      },
+      None => {
+        data.remove(SIMPLE_COLUMN_WIDTH_RATIO);
+      },
+    }
+  }
+
+  pub fn into_block_data(self) -> HashMap<String, Value> {
+    let mut data = HashMap::new();
+    self.write_to_block_data(&mut data);
+    data
+  }
+}
+
+/// Scale the width ratios of the columns of a simple columns block so they sum to 1. Return None
+/// if a column has no ratio or a ratio that isn't positive, the columns then share the width
+/// equally.
+pub fn normalize_column_width_ratios(ratios: &[Option<f64>]) -> Option<Vec<f64>> {
+  let ratios = ratios
+    .iter()
+    .map(|ratio| ratio.filter(|ratio| ratio.is_finite() && *ratio > 0.0))
+    .collect::<Option<Vec<_>>>()?;
+  let total = ratios.iter().sum::<f64>();
+  if ratios.is_empty() || total <= 0.0 {
+    return None;
+  }
+  Some(ratios.into_iter().map(|ratio| ratio / total).collect())
+}
@@ -0,0 +1,149 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::{Map, Value, json};
+
+pub const SIMPLE_TABLE_COLUMN_WIDTHS: &str = "column_widths";
+pub const SIMPLE_TABLE_ENABLE_HEADER_ROW: &str = "enable_header_row";
+pub const SIMPLE_TABLE_ENABLE_HEADER_COLUMN: &str = "enable_header_column";
+pub const SIMPLE_TABLE_ENABLE_STRIPED_ROWS: &str = "enable_striped_rows";
+
+/// The width of a column that doesn't have an explicit width.
+pub const SIMPLE_TABLE_DEFAULT_COLUMN_WIDTH: f64 = 160.0;
+
+/// Typed view of the presentation settings stored in the data of a simple table block.
+///
+/// The settings are stored as plain keys in the block data so that clients that don't know about
+/// this type keep working. Use [SimpleTableData::from_block_data] to read them and
+/// [SimpleTableData::write_to_block_data] to write them back, other keys in the block data are
+/// left untouched.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimpleTableData {
+  /// The width of each column, keyed by the zero-based column index. Columns without an entry
+  /// use [SIMPLE_TABLE_DEFAULT_COLUMN_WIDTH].
+  pub column_widths: BTreeMap<usize, f64>,
+  /// Whether the first row is rendered as a header.
+  pub enable_header_row: bool,
+  /// Whether the first column is rendered as a header.
+  pub enable_header_column: bool,
+  /// Whether the rows are rendered with alternating background colors.
+  pub enable_striped_rows: bool,
+}
+
+impl SimpleTableData {
+  /// Read the settings from the block data. Missing or malformed values fall back to the
+  /// default value instead of failing, the block data may have been written by any client.
+  pub fn from_block_data(data: &HashMap<String, Value>) -> Self {
+    let column_widths = data
+      .get(SIMPLE_TABLE_COLUMN_WIDTHS)
+      .and_then(|value| value.as_object())
+      .map(|widths| {
+        widths
+          .iter()
+          .filter_map(|(index, width)| {
+            let index = index.parse::<usize>().ok()?;
+            let width = value_as_f64(width)?;
+            Some((index, width))
+          })
+          .collect()
+      })
+      .unwrap_or_default();
+
+    Self {
+      column_widths,
+      enable_header_row: value_as_bool(data.get(SIMPLE_TABLE_ENABLE_HEADER_ROW)),
+      enable_header_column: value_as_bool(data.get(SIMPLE_TABLE_ENABLE_HEADER_COLUMN)),
+      enable_striped_rows: value_as_bool(data.get(SIMPLE_TABLE_ENABLE_STRIPED_ROWS)),
+    }
+  }
+
+  /// Write the settings into the block data.
+  pub fn write_to_block_data(&self, data: &mut HashMap<String, Value>) {
+    if self.column_widths.is_empty() {
+      data.remove(SIMPLE_TABLE_COLUMN_WIDTHS);
+    } else {
+      let widths = self
+        .column_widths
+        .iter()
+        .map(|(index, width)| (index.to_string(), json!(width)))
+        .collect::<Map<String, Value>>();
+      data.insert(
+        SIMPLE_TABLE_COLUMN_WIDTHS.to_string(),
+        Value::Object(widths),
+      );
+    }
+    data.insert(
+      SIMPLE_TABLE_ENABLE_HEADER_ROW.to_string(),
+      Value::Bool(self.enable_header_row),
+    );
+    data.insert(
+      SIMPLE_TABLE_ENABLE_HEADER_COLUMN.to_string(),
+      Value::Bool(self.enable_header_column),
+    );
+    data.insert(
+      SIMPLE_TABLE_ENABLE_STRIPED_ROWS.to_string(),
+      Value::Bool(self.enable_striped_rows),
+    );
+  }
+
+  pub fn into_block_data(self) -> HashMap<String, Value> {
+    let mut data = HashMap::new();
+    self.write_to_block_data(&mut data);
+    data
+  }
+
+  /// Return the width of the column, or [SIMPLE_TABLE_DEFAULT_COLUMN_WIDTH] if it's not set.
+  pub fn column_width(&self, column: usize) -> f64 {
+    self
+      .column_widths
+      .get(&column)
+      .copied()
+      .unwrap_or(SIMPLE_TABLE_DEFAULT_COLUMN_WIDTH)
+  }
+
+  pub fn set_column_width(&mut self, column: usize, width: f64) {
+    self.column_widths.insert(column, width);
+  }
+
+  /// Remove the explicit width of the column, so it falls back to the default width.
+  pub fn reset_column_width(&mut self, column: usize) {
+    self.column_widths.remove(&column);
+  }
+
+  /// Return true if the cell at the given position is rendered as a header cell.
+  pub fn is_header_cell(&self, row: usize, column: usize) -> bool {
+    (self.enable_header_row && row == 0) || (self.enable_header_column && column == 0)
+  }
+
+  /// Return true if the row is rendered with the alternate background color.
+  pub fn is_striped_row(&self, row: usize) -> bool {
+    if !self.enable_striped_rows {
+      return false;
+    }
+    // The header row is not part of the striping.
+    let row = if self.enable_header_row {
+      match row.checked_sub(1) {
+        Some(row) => row,
+        None => return false,
+      }
+    } else {
+      row
+    };
+    row % 2 == 1
+  }
+}
+
+pub(crate) fn value_as_f64(value: &Value) -> Option<f64> {
+  match value {
+    Value::Number(number) => number.as_f64(),
+    Value::String(s) => s.parse::<f64>().ok(),
+    _ => None,
+  }
+}
+
+fn value_as_bool(value: Option<&Value>) -> bool {
+  match value {
+    Some(Value::Bool(b)) => *b,
+    Some(Value::String(s)) => s.eq_ignore_ascii_case("true"),
+    _ => false,
+  }
+}
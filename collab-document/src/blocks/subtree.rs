@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use collab::preclude::ReadTxn;
+use serde_json::Value;
+
+use crate::blocks::block::PARENT;
+use crate::blocks::{BlockEvent, BlockEventPayload, BlockOperation, DeltaType};
+use crate::document::{BLOCKS, META};
+
+pub type SubtreeChangedCallback = Arc<dyn Fn(&Vec<BlockEvent>, bool) + Send + Sync + 'static>;
+
+struct SubtreeSubscriber {
+  block_id: String,
+  callback: SubtreeChangedCallback,
+}
+
+/// The subscribers of block subtrees of a document, keyed by the subscription key.
+///
+/// All subscribers share a single observer on the document. For each transaction, the block
+/// affected by each event payload and the ancestors of that block are resolved once, then every
+/// subscriber receives only the payloads that touch its subtree. Subscribers whose subtree is not
+/// touched are not called at all.
+#[derive(Clone, Default)]
+pub(crate) struct SubtreeSubscribers {
+  subscribers: Arc<RwLock<HashMap<String, SubtreeSubscriber>>>,
+}
+
+impl SubtreeSubscribers {
+  pub fn insert(&self, key: String, block_id: String, callback: SubtreeChangedCallback) {
+    if let Ok(mut subscribers) = self.subscribers.write() {
+      subscribers.insert(key, SubtreeSubscriber { block_id, callback });
+    }
+  }
+
+  /// Remove the subscriber, returns true if there are no subscribers left.
+  pub fn remove(&self, key: &str) -> bool {
+    match self.subscribers.write() {
+      Ok(mut subscribers) => {
+        subscribers.remove(key);
+        subscribers.is_empty()
+      },
+      Err(_) => true,
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self
+      .subscribers
+      .read()
+      .map(|subscribers| subscribers.is_empty())
+      .unwrap_or(true)
+  }
+
+  pub fn dispatch<T: ReadTxn>(
+    &self,
+    txn: &T,
+    block_operation: &BlockOperation,
+    events: &[BlockEvent],
+    is_remote: bool,
+  ) {
+    // Clone the callbacks so a callback can subscribe or unsubscribe without deadlocking.
+    let subscribers = match self.subscribers.read() {
+      Ok(subscribers) if !subscribers.is_empty() => subscribers
+        .values()
+        .map(|subscriber| (subscriber.block_id.clone(), subscriber.callback.clone()))
+        .collect::<Vec<_>>(),
+      _ => return,
+    };
+
+    let mut resolver = BlockPathResolver::new(txn, block_operation);
+    let event_paths = events
+      .iter()
+      .map(|event| {
+        event
+          .iter()
+          .map(|payload| resolver.block_path(payload))
+          .collect::<Vec<_>>()
+      })
+      .collect::<Vec<_>>();
+
+    for (block_id, callback) in subscribers {
+      let filtered = events
+        .iter()
+        .zip(event_paths.iter())
+        .filter_map(|(event, paths)| {
+          let payloads = event
+            .iter()
+            .zip(paths.iter())
+            .filter(|(_, path)| path.contains(&block_id))
+            .map(|(payload, _)| payload.clone())
+            .collect::<Vec<_>>();
+          if payloads.is_empty() {
+            None
+          } else {
+            Some(BlockEvent::new(payloads))
+          }
+        })
+        .collect::<Vec<_>>();
+      if !filtered.is_empty() {
+        callback(&filtered, is_remote);
+      }
+    }
+  }
+}
+
+/// Resolves the block that an event payload belongs to, together with its ancestors.
+struct BlockPathResolver<'a, T: ReadTxn> {
+  txn: &'a T,
+  block_operation: &'a BlockOperation,
+  /// Maps the children id and the external id of each block to the block id. It's only built
+  /// when an id doesn't match a block id.
+  index: Option<HashMap<String, String>>,
+  /// The ancestors of the blocks that have been resolved, including the block itself.
+  paths: HashMap<String, HashSet<String>>,
+}
+
+impl<'a, T: ReadTxn> BlockPathResolver<'a, T> {
+  fn new(txn: &'a T, block_operation: &'a BlockOperation) -> Self {
+    Self {
+      txn,
+      block_operation,
+      index: None,
+      paths: HashMap::new(),
+    }
+  }
+
+  /// Returns the ids of the block that the payload belongs to and of all its ancestors.
+  fn block_path(&mut self, payload: &BlockEventPayload) -> HashSet<String> {
+    let path = payload.path.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+    match path.as_slice() {
+      [BLOCKS] => {
+        if payload.command == DeltaType::Removed {
+          // The block is gone, use the parent stored in the removed value.
+          let mut path = parent_from_removed_value(&payload.value)
+            .map(|parent| self.ancestors(&parent))
+            .unwrap_or_default();
+          path.insert(payload.id.clone());
+          path
+        } else {
+          self.ancestors(&payload.id)
+        }
+      },
+      [BLOCKS, block_id, ..] => self.ancestors(block_id),
+      // The children map and the text map are keyed by the children id and the external id.
+      [META, _, id, ..] => self.ancestors_of_owner(id),
+      [META, _] => self.ancestors_of_owner(&payload.id),
+      _ => HashSet::new(),
+    }
+  }
+
+  fn ancestors_of_owner(&mut self, id: &str) -> HashSet<String> {
+    // In most documents, the children id and the external id are the block id.
+    if self
+      .block_operation
+      .get_block_parent_with_txn(self.txn, id)
+      .is_some()
+    {
+      return self.ancestors(id);
+    }
+    let (txn, block_operation) = (self.txn, self.block_operation);
+    let block_id = self
+      .index
+      .get_or_insert_with(|| block_operation.get_block_id_index(txn))
+      .get(id)
+      .cloned();
+    match block_id {
+      Some(block_id) => self.ancestors(&block_id),
+      None => HashSet::new(),
+    }
+  }
+
+  fn ancestors(&mut self, block_id: &str) -> HashSet<String> {
+    if let Some(path) = self.paths.get(block_id) {
+      return path.clone();
+    }
+    let mut path = HashSet::new();
+    let mut current = Some(block_id.to_string());
+    while let Some(id) = current {
+      if id.is_empty() || !path.insert(id.clone()) {
+        // Reached the page block, or a cycle in a malformed document.
+        break;
+      }
+      if let Some(cached) = self.paths.get(&id) {
+        path.extend(cached.iter().cloned());
+        break;
+      }
+      current = self
+        .block_operation
+        .get_block_parent_with_txn(self.txn, &id);
+    }
+    self.paths.insert(block_id.to_string(), path.clone());
+    path
+  }
+}
+
+fn parent_from_removed_value(value: &str) -> Option<String> {
+  let value = serde_json::from_str::<HashMap<String, Value>>(value).ok()?;
+  let parent = value.get(PARENT)?.as_str()?;
+  // The values of a removed block map are serialized as json strings.
+  let parent = serde_json::from_str::<String>(parent).unwrap_or_else(|_| parent.to_string());
+  Some(parent)
+}
@@ -0,0 +1,487 @@
+use crate::blocks::{BlockType, DiagramData, DocumentData, TextDelta};
+use crate::error::DocumentError;
+use crate::importer::define::{
+  BOLD_ATTR, CHECKED_FIELD, CODE_ATTR, FILE_NAME_FIELD, FORMULA_ATTR, FORMULA_FIELD, HREF_ATTR,
+  ITALIC_ATTR, LANGUAGE_FIELD, LEVEL_FIELD, MENTION_ATTR, MENTION_PAGE_ID_FIELD,
+  STRIKETHROUGH_ATTR, UNDERLINE_ATTR, URL_FIELD,
+};
+use collab::preclude::{Any, Attrs};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The icon of a callout without one, the same as the one of the document parser.
+const DEFAULT_CALLOUT_ICON: &str = "💡";
+const CALLOUT_ICON_FIELD: &str = "icon";
+const DIVIDER: &str = "———";
+
+/// The flavor of markdown a chat application renders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatFormat {
+  /// Slack `mrkdwn`: `*bold*`, `_italic_`, `~strike~` and `<url|text>` links, without headings.
+  Slack,
+  /// Discord markdown: `**bold**`, `*italic*`, `~~strike~~`, `[text](url)` links and headings
+  /// up to level 3.
+  Discord,
+}
+
+impl ChatFormat {
+  /// The maximum number of characters of a message.
+  pub fn max_message_len(&self) -> usize {
+    match self {
+      ChatFormat::Slack => 40_000,
+      ChatFormat::Discord => 2_000,
+    }
+  }
+}
+
+/// Convert a document, or a range of its blocks, into a message for a chat application.
+///
+/// The blocks the chat applications can't render are degraded: a callout becomes a quote
+/// starting with its icon, the children of a toggle are flattened under it, a table becomes a
+/// code block and the math equations are printed as code.
+#[derive(Debug, Clone)]
+pub struct ChatExporter {
+  format: ChatFormat,
+  /// The names of the pages, by view id, used to print the page mentions.
+  page_names: HashMap<String, String>,
+}
+
+impl ChatExporter {
+  pub fn new(format: ChatFormat) -> Self {
+    Self {
+      format,
+      page_names: HashMap::new(),
+    }
+  }
+
+  /// Print the mentions of these pages with their name. The other page mentions are left out.
+  pub fn with_page_names(mut self, page_names: HashMap<String, String>) -> Self {
+    self.page_names = page_names;
+    self
+  }
+
+  /// Export the whole document. The texts must be hydrated.
+  pub fn export(&self, data: &DocumentData) -> String {
+    let mut lines = vec![];
+    for child_id in children(data, &data.page_id) {
+      self.export_block(data, child_id, 0, &mut lines);
+    }
+    lines.join("\n")
+  }
+
+  /// Export the blocks from `start_block_id` to `end_block_id`, both included, with their
+  /// children. The two blocks must have the same parent, the blocks up to the last child of the
+  /// parent are exported when `end_block_id` is not a sibling that follows `start_block_id`.
+  pub fn export_range(
+    &self,
+    data: &DocumentData,
+    start_block_id: &str,
+    end_block_id: &str,
+  ) -> Result<String, DocumentError> {
+    let start = data
+      .blocks
+      .get(start_block_id)
+      .ok_or(DocumentError::BlockIsNotFound)?;
+    let siblings = children(data, &start.parent);
+    let start_index = siblings
+      .iter()
+      .position(|id| *id == start_block_id)
+      .ok_or(DocumentError::ParentIsNotFound)?;
+    let mut lines = vec![];
+    for block_id in siblings[start_index..].iter() {
+      self.export_block(data, block_id, 0, &mut lines);
+      if *block_id == end_block_id {
+        break;
+      }
+    }
+    Ok(lines.join("\n"))
+  }
+
+  /// Split the message into messages under [ChatFormat::max_message_len], between lines when
+  /// possible. A code block cut in two is closed and opened again.
+  pub fn split_message(&self, message: &str) -> Vec<String> {
+    let max_len = self.format.max_message_len();
+    let mut messages = vec![];
+    let mut current = String::new();
+    let mut in_code_block = false;
+    for line in message.lines() {
+      let mut line = line.to_string();
+      // leave room for the fence closing a code block cut in two
+      while line.chars().count() > max_len - 4 {
+        let head = line.chars().take(max_len - 4).collect::<String>();
+        line = line.chars().skip(max_len - 4).collect();
+        push_line(&mut messages, &mut current, &head, max_len, in_code_block);
+      }
+      push_line(&mut messages, &mut current, &line, max_len, in_code_block);
+      if line.starts_with("```") {
+        in_code_block = !in_code_block;
+      }
+    }
+    if !current.is_empty() {
+      messages.push(current);
+    }
+    messages
+  }
+
+  fn export_block(
+    &self,
+    data: &DocumentData,
+    block_id: &str,
+    depth: usize,
+    lines: &mut Vec<String>,
+  ) {
+    let Some(block) = data.blocks.get(block_id) else {
+      return;
+    };
+    let indent = "  ".repeat(depth);
+    let text = self.text(data, block_id);
+    let mut child_depth = depth + 1;
+    match BlockType::from_block_ty(&block.ty) {
+      BlockType::Heading => {
+        let level = block
+          .data
+          .get(LEVEL_FIELD)
+          .and_then(Value::as_u64)
+          .unwrap_or(1);
+        let line = match self.format {
+          ChatFormat::Slack => format!("*{}*", text),
+          ChatFormat::Discord if level <= 3 => {
+            format!("{} {}", "#".repeat(level as usize), text)
+          },
+          ChatFormat::Discord => format!("**{}**", text),
+        };
+        lines.push(line);
+        child_depth = depth;
+      },
+      BlockType::BulletedList => {
+        let bullet = match self.format {
+          ChatFormat::Slack => "•",
+          ChatFormat::Discord => "-",
+        };
+        lines.push(format!("{}{} {}", indent, bullet, text));
+      },
+      BlockType::NumberedList => {
+        let number = list_number(data, block_id);
+        lines.push(format!("{}{}. {}", indent, number, text));
+      },
+      BlockType::TodoList => {
+        let checked = block
+          .data
+          .get(CHECKED_FIELD)
+          .and_then(Value::as_bool)
+          .unwrap_or(false);
+        let checkbox = if checked { "☑" } else { "☐" };
+        lines.push(format!("{}{} {}", indent, checkbox, text));
+      },
+      BlockType::Quote => {
+        lines.push(format!("> {}", text));
+        child_depth = depth;
+      },
+      BlockType::Callout => {
+        let icon = block
+          .data
+          .get(CALLOUT_ICON_FIELD)
+          .and_then(Value::as_str)
+          .filter(|icon| !icon.is_empty())
+          .unwrap_or(DEFAULT_CALLOUT_ICON);
+        lines.push(format!("> {} {}", icon, text));
+        child_depth = depth;
+      },
+      BlockType::ToggleList => {
+        lines.push(format!("{}{}", indent, text));
+        child_depth = depth;
+      },
+      BlockType::Code => {
+        let language = match self.format {
+          ChatFormat::Slack => "",
+          ChatFormat::Discord => block
+            .data
+            .get(LANGUAGE_FIELD)
+            .and_then(Value::as_str)
+            .unwrap_or_default(),
+        };
+        let code = self.raw_text(data, block_id);
+        lines.push(format!("```{}\n{}\n```", language, code));
+      },
+      BlockType::Diagram => {
+        // the chat applications don't render the diagrams, the source is sent as code
+        if let Some(diagram) = DiagramData::from_block_data(&block.data) {
+          let language = match self.format {
+            ChatFormat::Slack => "",
+            ChatFormat::Discord => diagram.kind.as_str(),
+          };
+          lines.push(format!("```{}\n{}\n```", language, diagram.source));
+        }
+      },
+      BlockType::MathEquation => {
+        let formula = block
+          .data
+          .get(FORMULA_FIELD)
+          .and_then(Value::as_str)
+          .unwrap_or_default();
+        if !formula.is_empty() {
+          lines.push(format!("`{}`", formula));
+        }
+      },
+      BlockType::Divider => lines.push(DIVIDER.to_string()),
+      BlockType::Image
+      | BlockType::LinkPreview
+      | BlockType::Embed
+      | BlockType::Video
+      | BlockType::Audio => {
+        // the chat applications unfurl the urls
+        if let Some(url) = block.data.get(URL_FIELD).and_then(Value::as_str) {
+          if !url.is_empty() {
+            lines.push(format!("{}{}", indent, url));
+          }
+        }
+      },
+      BlockType::File => {
+        let name = block
+          .data
+          .get(FILE_NAME_FIELD)
+          .and_then(Value::as_str)
+          .unwrap_or_default();
+        let url = block
+          .data
+          .get(URL_FIELD)
+          .and_then(Value::as_str)
+          .unwrap_or_default();
+        if !url.is_empty() {
+          lines.push(format!("{}{}", indent, self.link(name, url)));
+        } else if !name.is_empty() {
+          lines.push(format!("{}{}", indent, self.escape(name)));
+        }
+      },
+      BlockType::SimpleTable => {
+        let rows = children(data, block_id)
+          .into_iter()
+          .map(|row_id| {
+            children(data, row_id)
+              .into_iter()
+              .map(|cell_id| self.cell_text(data, cell_id))
+              .collect::<Vec<_>>()
+              .join(" | ")
+          })
+          .collect::<Vec<_>>();
+        if !rows.is_empty() {
+          lines.push(format!("```\n{}\n```", rows.join("\n")));
+        }
+        return;
+      },
+      BlockType::SubPage => {},
+      BlockType::Page | BlockType::SimpleColumns | BlockType::SimpleColumn => {
+        child_depth = depth;
+      },
+      _ => {
+        if block.external_id.is_some() {
+          lines.push(format!("{}{}", indent, text));
+        } else {
+          child_depth = depth;
+        }
+      },
+    }
+
+    let quoted = matches!(
+      BlockType::from_block_ty(&block.ty),
+      BlockType::Quote | BlockType::Callout
+    );
+    for child_id in children(data, block_id) {
+      if quoted {
+        // the children of a quote stay in the quote
+        let mut child_lines = vec![];
+        self.export_block(data, child_id, child_depth, &mut child_lines);
+        for child_line in child_lines {
+          lines.extend(child_line.lines().map(|line| format!("> {}", line)));
+        }
+      } else {
+        self.export_block(data, child_id, child_depth, lines);
+      }
+    }
+  }
+
+  /// The formatted text of the block.
+  fn text(&self, data: &DocumentData, block_id: &str) -> String {
+    let Some(deltas) = block_deltas(data, block_id) else {
+      return String::new();
+    };
+    deltas
+      .into_iter()
+      .filter_map(|delta| match delta {
+        TextDelta::Inserted(text, attributes) => Some(self.format_text(&text, attributes.as_ref())),
+        _ => None,
+      })
+      .collect()
+  }
+
+  /// The text of the block without formatting nor escaping, e.g. the code of a code block.
+  fn raw_text(&self, data: &DocumentData, block_id: &str) -> String {
+    block_deltas(data, block_id)
+      .unwrap_or_default()
+      .into_iter()
+      .filter_map(|delta| match delta {
+        TextDelta::Inserted(text, _) => Some(text),
+        _ => None,
+      })
+      .collect()
+  }
+
+  fn cell_text(&self, data: &DocumentData, cell_id: &str) -> String {
+    let mut texts = vec![];
+    let mut stack = children(data, cell_id);
+    stack.reverse();
+    while let Some(block_id) = stack.pop() {
+      let text = self.raw_text(data, block_id);
+      if !text.is_empty() {
+        texts.push(text);
+      }
+      stack.extend(children(data, block_id).into_iter().rev());
+    }
+    texts.join(" ")
+  }
+
+  fn format_text(&self, text: &str, attributes: Option<&Attrs>) -> String {
+    let Some(attributes) = attributes else {
+      return self.escape(text);
+    };
+    if let Some(Any::Map(mention)) = attributes.get(MENTION_ATTR) {
+      return match mention.get(MENTION_PAGE_ID_FIELD) {
+        Some(Any::String(page_id)) => self
+          .page_names
+          .get(page_id.as_ref())
+          .map(|name| format!("@{}", self.escape(name)))
+          .unwrap_or_default(),
+        _ => String::new(),
+      };
+    }
+    if let Some(Any::String(formula)) = attributes.get(FORMULA_ATTR) {
+      return format!("`{}`", formula);
+    }
+    if is_set(attributes, CODE_ATTR) {
+      return format!("`{}`", text);
+    }
+
+    let mut result = self.escape(text);
+    let (bold, italic, strike) = match self.format {
+      ChatFormat::Slack => ("*", "_", "~"),
+      ChatFormat::Discord => ("**", "*", "~~"),
+    };
+    if is_set(attributes, BOLD_ATTR) {
+      result = format!("{}{}{}", bold, result, bold);
+    }
+    if is_set(attributes, ITALIC_ATTR) {
+      result = format!("{}{}{}", italic, result, italic);
+    }
+    if is_set(attributes, STRIKETHROUGH_ATTR) {
+      result = format!("{}{}{}", strike, result, strike);
+    }
+    // Slack has no underline
+    if self.format == ChatFormat::Discord && is_set(attributes, UNDERLINE_ATTR) {
+      result = format!("__{}__", result);
+    }
+    if let Some(Any::String(href)) = attributes.get(HREF_ATTR) {
+      result = self.link_with_text(&result, href);
+    }
+    result
+  }
+
+  fn link(&self, text: &str, url: &str) -> String {
+    self.link_with_text(&self.escape(text), url)
+  }
+
+  /// A link whose text is already escaped.
+  fn link_with_text(&self, text: &str, url: &str) -> String {
+    if text.is_empty() {
+      return url.to_string();
+    }
+    match self.format {
+      ChatFormat::Slack => format!("<{}|{}>", url, text),
+      ChatFormat::Discord => format!("[{}]({})", text, url),
+    }
+  }
+
+  /// Slack reads `&`, `<` and `>` as control characters, Discord the markdown characters.
+  fn escape(&self, text: &str) -> String {
+    match self.format {
+      ChatFormat::Slack => text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;"),
+      ChatFormat::Discord => {
+        let mut result = String::with_capacity(text.len());
+        for c in text.chars() {
+          if matches!(c, '\\' | '*' | '_' | '~' | '`' | '|') {
+            result.push('\\');
+          }
+          result.push(c);
+        }
+        result
+      },
+    }
+  }
+}
+
+fn children<'a>(data: &'a DocumentData, block_id: &str) -> Vec<&'a str> {
+  data
+    .blocks
+    .get(block_id)
+    .and_then(|block| data.meta.children_map.get(&block.children))
+    .map(|children| children.iter().map(String::as_str).collect())
+    .unwrap_or_default()
+}
+
+fn block_deltas(data: &DocumentData, block_id: &str) -> Option<Vec<TextDelta>> {
+  let text_id = data.blocks.get(block_id)?.external_id.as_ref()?;
+  let delta = data.meta.text_map.as_ref()?.get(text_id)?;
+  serde_json::from_str(delta).ok()
+}
+
+/// The number of a numbered list item: its position among the numbered list items before it.
+fn list_number(data: &DocumentData, block_id: &str) -> usize {
+  let Some(block) = data.blocks.get(block_id) else {
+    return 1;
+  };
+  let siblings = children(data, &block.parent);
+  let Some(index) = siblings.iter().position(|id| *id == block_id) else {
+    return 1;
+  };
+  siblings[..index]
+    .iter()
+    .rev()
+    .take_while(|id| {
+      data
+        .blocks
+        .get(**id)
+        .is_some_and(|block| block.ty == BlockType::NumberedList.as_str())
+    })
+    .count()
+    + 1
+}
+
+fn is_set(attributes: &Attrs, key: &str) -> bool {
+  matches!(attributes.get(key), Some(Any::Bool(true)))
+}
+
+fn push_line(
+  messages: &mut Vec<String>,
+  current: &mut String,
+  line: &str,
+  max_len: usize,
+  in_code_block: bool,
+) {
+  // leave room for the fence closing a code block cut in two
+  let len = current.chars().count() + line.chars().count() + 1;
+  if !current.is_empty() && len > max_len - 4 {
+    if in_code_block {
+      current.push_str("\n```");
+    }
+    messages.push(std::mem::take(current));
+    if in_code_block {
+      current.push_str("```");
+    }
+  }
+  if !current.is_empty() {
+    current.push('\n');
+  }
+  current.push_str(line);
+}
@@ -0,0 +1,481 @@
+use crate::block_parser::{
+  BibliographyEntry, BibliographyParser, DocumentParser, DocumentParserDelegate, OutputFormat,
+  ParseContext,
+};
+use crate::blocks::{DocumentData, TextDelta};
+use crate::error::DocumentError;
+use crate::importer::define::{CITATION_ATTR, CITATION_KEY_FIELD, CITATION_LOCATOR_FIELD};
+use collab::preclude::{Any, Attrs};
+use collab_entity::reference::{Reference, ReferenceAuthor};
+use serde_json::{Map, Value, json};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// A citation of a reference in the text, stored in the `citation` attribute of the delta, e.g.
+/// `{"insert": "@smith2020", "attributes": {"citation": {"key": "smith2020", "locator": "p. 12"}}}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Citation {
+  /// The key of the cited reference in the reference store of the workspace.
+  pub key: String,
+  /// Where in the reference, e.g. `p. 12` or `chap. 3`.
+  pub locator: Option<String>,
+}
+
+impl Citation {
+  pub fn new(key: &str) -> Self {
+    Self {
+      key: key.to_string(),
+      locator: None,
+    }
+  }
+
+  pub fn with_locator(mut self, locator: &str) -> Self {
+    self.locator = Some(locator.to_string()).filter(|locator| !locator.is_empty());
+    self
+  }
+
+  /// Read the citation of the attributes of a text delta.
+  pub fn from_attrs(attributes: &Attrs) -> Option<Self> {
+    let Some(Any::Map(citation)) = attributes.get(CITATION_ATTR) else {
+      return None;
+    };
+    let key = match citation.get(CITATION_KEY_FIELD) {
+      Some(Any::String(key)) if !key.is_empty() => key.to_string(),
+      _ => return None,
+    };
+    let locator = match citation.get(CITATION_LOCATOR_FIELD) {
+      Some(Any::String(locator)) if !locator.is_empty() => Some(locator.to_string()),
+      _ => None,
+    };
+    Some(Self { key, locator })
+  }
+
+  /// The value of the `citation` attribute.
+  pub fn to_attribute_value(&self) -> Value {
+    let mut value = Map::new();
+    value.insert(CITATION_KEY_FIELD.to_string(), json!(self.key));
+    if let Some(locator) = &self.locator {
+      value.insert(CITATION_LOCATOR_FIELD.to_string(), json!(locator));
+    }
+    Value::Object(value)
+  }
+}
+
+/// The citations of the document, in the order of the blocks.
+pub fn collect_citations(data: &DocumentData) -> Vec<Citation> {
+  let mut citations = vec![];
+  let mut stack = vec![data.page_id.as_str()];
+  while let Some(block_id) = stack.pop() {
+    let Some(block) = data.blocks.get(block_id) else {
+      continue;
+    };
+    let delta = block
+      .external_id
+      .as_ref()
+      .and_then(|text_id| data.meta.text_map.as_ref()?.get(text_id));
+    if let Some(delta) = delta {
+      let deltas = serde_json::from_str::<Vec<TextDelta>>(delta).unwrap_or_default();
+      for delta in deltas {
+        if let TextDelta::Inserted(_, Some(attributes)) = delta {
+          citations.extend(Citation::from_attrs(&attributes));
+        }
+      }
+    }
+    if let Some(children) = data.meta.children_map.get(&block.children) {
+      stack.extend(children.iter().rev().map(String::as_str));
+    }
+  }
+  citations
+}
+
+/// The citation styles, named after their id in the CSL style repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CitationStyle {
+  /// `(Smith & Doe, 2020, p. 12)`
+  Apa,
+  /// `(Smith and Doe 12)`
+  Mla,
+  /// `(Smith and Doe 2020, 12)`
+  ChicagoAuthorDate,
+  /// `[1, p. 12]`, the references are numbered in the order they are first cited.
+  Ieee,
+}
+
+impl CitationStyle {
+  pub fn csl_id(&self) -> &'static str {
+    match self {
+      CitationStyle::Apa => "apa",
+      CitationStyle::Mla => "modern-language-association",
+      CitationStyle::ChicagoAuthorDate => "chicago-author-date",
+      CitationStyle::Ieee => "ieee",
+    }
+  }
+
+  pub fn from_csl_id(id: &str) -> Option<Self> {
+    match id {
+      "apa" => Some(CitationStyle::Apa),
+      "modern-language-association" | "mla" => Some(CitationStyle::Mla),
+      "chicago-author-date" => Some(CitationStyle::ChicagoAuthorDate),
+      "ieee" => Some(CitationStyle::Ieee),
+      _ => None,
+    }
+  }
+}
+
+/// Render the citations and the bibliography of a document in a citation style.
+///
+/// Pass it as the delegate of a [DocumentParser] to render the citations of the text, see
+/// [export_with_citations].
+#[derive(Debug, Clone)]
+pub struct CitationRenderer {
+  style: CitationStyle,
+  references: HashMap<String, Reference>,
+  /// The cited keys in the order they are first cited.
+  cited_keys: Vec<String>,
+}
+
+impl CitationRenderer {
+  pub fn new(style: CitationStyle, references: HashMap<String, Reference>) -> Self {
+    Self {
+      style,
+      references,
+      cited_keys: vec![],
+    }
+  }
+
+  /// A renderer for the citations of the document, see [collect_citations].
+  pub fn for_document(
+    style: CitationStyle,
+    references: HashMap<String, Reference>,
+    data: &DocumentData,
+  ) -> Self {
+    let keys = collect_citations(data)
+      .into_iter()
+      .map(|citation| citation.key)
+      .collect::<Vec<_>>();
+    Self::new(style, references).with_cited_keys(keys)
+  }
+
+  /// Set the cited keys, in the order they are first cited. The duplicates are skipped.
+  pub fn with_cited_keys(mut self, keys: Vec<String>) -> Self {
+    let mut seen = HashSet::new();
+    self.cited_keys = keys
+      .into_iter()
+      .filter(|key| seen.insert(key.clone()))
+      .collect();
+    self
+  }
+
+  /// The citation in the text. A citation of an unknown reference is rendered as its key.
+  pub fn render_citation(&self, citation: &Citation) -> String {
+    let Some(reference) = self.references.get(&citation.key) else {
+      return format!("[{}]", citation.key);
+    };
+    let locator = citation.locator.as_deref();
+    match self.style {
+      CitationStyle::Apa => {
+        let mut parts = vec![
+          short_authors(reference, " & ", false),
+          year_or_no_date(reference),
+        ];
+        parts.extend(locator.map(str::to_string));
+        format!("({})", parts.join(", "))
+      },
+      CitationStyle::Mla => match locator {
+        Some(locator) => format!("({} {})", short_authors(reference, " and ", false), locator),
+        None => format!("({})", short_authors(reference, " and ", false)),
+      },
+      CitationStyle::ChicagoAuthorDate => {
+        let author_date = format!(
+          "{} {}",
+          short_authors(reference, " and ", true),
+          year_or_no_date(reference)
+        );
+        match locator {
+          Some(locator) => format!("({}, {})", author_date, locator),
+          None => format!("({})", author_date),
+        }
+      },
+      CitationStyle::Ieee => {
+        let number = self.number(&citation.key);
+        match locator {
+          Some(locator) => format!("[{}, {}]", number, locator),
+          None => format!("[{}]", number),
+        }
+      },
+    }
+  }
+
+  /// The entries of the cited references. The IEEE entries are numbered in the order of the
+  /// citations, the others are sorted by author and year. The unknown references are skipped.
+  pub fn bibliography(&self) -> Vec<BibliographyEntry> {
+    let mut references = self
+      .cited_keys
+      .iter()
+      .filter_map(|key| self.references.get(key))
+      .collect::<Vec<_>>();
+    if self.style != CitationStyle::Ieee {
+      references.sort_by_key(|reference| {
+        (
+          reference
+            .authors
+            .first()
+            .map(|author| author.family.to_lowercase())
+            .unwrap_or_else(|| reference.title.to_lowercase()),
+          reference.year.clone().unwrap_or_default(),
+          reference.title.to_lowercase(),
+        )
+      });
+    }
+    references
+      .into_iter()
+      .map(|reference| BibliographyEntry {
+        key: reference.key.clone(),
+        text: self.render_entry(reference),
+      })
+      .collect()
+  }
+
+  fn render_entry(&self, reference: &Reference) -> String {
+    let title = reference.title.trim_end_matches('.');
+    let container = reference.container_title.as_deref();
+    let volume = reference.volume.as_deref();
+    let issue = reference.issue.as_deref();
+    let pages = reference
+      .pages
+      .as_deref()
+      .map(|pages| pages.replace("--", "–"));
+    let link = doi_or_url(reference);
+    let mut entry = String::new();
+    match self.style {
+      CitationStyle::Apa => {
+        entry.push_str(&format!(
+          "{} ({}). {}.",
+          apa_authors(&reference.authors),
+          year_or_no_date(reference),
+          title
+        ));
+        if let Some(container) = container {
+          entry.push_str(&format!(" {}", container));
+          if let Some(volume) = volume {
+            entry.push_str(&format!(", {}", volume));
+            if let Some(issue) = issue {
+              entry.push_str(&format!("({})", issue));
+            }
+          }
+          if let Some(pages) = &pages {
+            entry.push_str(&format!(", {}", pages));
+          }
+          entry.push('.');
+        } else if let Some(publisher) = &reference.publisher {
+          entry.push_str(&format!(" {}.", publisher));
+        }
+        if let Some(link) = link {
+          entry.push_str(&format!(" {}", link));
+        }
+      },
+      CitationStyle::Mla => {
+        entry.push_str(&format!(
+          "{}. \"{}.\"",
+          mla_authors(&reference.authors),
+          title
+        ));
+        let mut parts = vec![];
+        parts.extend(container.map(str::to_string));
+        parts.extend(volume.map(|volume| format!("vol. {}", volume)));
+        parts.extend(issue.map(|issue| format!("no. {}", issue)));
+        parts.extend(reference.publisher.clone());
+        parts.extend(reference.year.clone());
+        parts.extend(pages.map(|pages| format!("pp. {}", pages)));
+        if !parts.is_empty() {
+          entry.push_str(&format!(" {}.", parts.join(", ")));
+        }
+      },
+      CitationStyle::ChicagoAuthorDate => {
+        entry.push_str(&format!(
+          "{}. {}. \"{}.\"",
+          chicago_authors(&reference.authors),
+          year_or_no_date(reference),
+          title
+        ));
+        if let Some(container) = container {
+          entry.push_str(&format!(" {}", container));
+          if let Some(volume) = volume {
+            entry.push_str(&format!(" {}", volume));
+          }
+          if let Some(issue) = issue {
+            entry.push_str(&format!(" ({})", issue));
+          }
+          if let Some(pages) = &pages {
+            entry.push_str(&format!(": {}", pages));
+          }
+          entry.push('.');
+        } else if let Some(publisher) = &reference.publisher {
+          entry.push_str(&format!(" {}.", publisher));
+        }
+        if let Some(link) = link {
+          entry.push_str(&format!(" {}.", link));
+        }
+      },
+      CitationStyle::Ieee => {
+        let mut parts = vec![];
+        parts.extend(container.map(str::to_string));
+        parts.extend(volume.map(|volume| format!("vol. {}", volume)));
+        parts.extend(issue.map(|issue| format!("no. {}", issue)));
+        parts.extend(pages.map(|pages| format!("pp. {}", pages)));
+        parts.extend(reference.year.clone());
+        let title = if parts.is_empty() {
+          format!("\"{}.\"", title)
+        } else {
+          format!("\"{},\" {}.", title, parts.join(", "))
+        };
+        entry.push_str(&format!(
+          "[{}] {}, {}",
+          self.number(&reference.key),
+          ieee_authors(&reference.authors),
+          title
+        ));
+      },
+    }
+    entry
+  }
+
+  /// The number of the reference in the IEEE style, 0 when it's not cited.
+  fn number(&self, key: &str) -> usize {
+    self
+      .cited_keys
+      .iter()
+      .position(|cited_key| cited_key == key)
+      .map(|index| index + 1)
+      .unwrap_or_default()
+  }
+}
+
+impl DocumentParserDelegate for CitationRenderer {
+  fn handle_text_delta(
+    &self,
+    _text: &str,
+    attributes: Option<&Attrs>,
+    _context: &ParseContext,
+  ) -> Option<String> {
+    let citation = Citation::from_attrs(attributes?)?;
+    Some(self.render_citation(&citation))
+  }
+}
+
+/// Export the document with its citations and its bibliography blocks rendered in the style.
+/// `references` are the references of the workspace, at least the cited ones.
+pub fn export_with_citations(
+  data: &DocumentData,
+  style: CitationStyle,
+  references: HashMap<String, Reference>,
+  format: OutputFormat,
+) -> Result<String, DocumentError> {
+  let renderer = CitationRenderer::for_document(style, references, data);
+  let bibliography = BibliographyParser::new(renderer.bibliography());
+  DocumentParser::with_default_parsers()
+    .with_parser(Arc::new(bibliography))
+    .with_delegate(Arc::new(renderer))
+    .parse_document(data, format)
+}
+
+fn year_or_no_date(reference: &Reference) -> String {
+  reference.year.clone().unwrap_or_else(|| "n.d.".to_string())
+}
+
+fn doi_or_url(reference: &Reference) -> Option<String> {
+  match (&reference.doi, &reference.url) {
+    (Some(doi), _) if doi.starts_with("http") => Some(doi.clone()),
+    (Some(doi), _) => Some(format!("https://doi.org/{}", doi)),
+    (None, Some(url)) => Some(url.clone()),
+    (None, None) => None,
+  }
+}
+
+/// The authors in the text: the family names of one or two authors, the first one followed by
+/// `et al.` for more. Chicago lists up to three authors.
+fn short_authors(reference: &Reference, and: &str, up_to_three: bool) -> String {
+  let families = reference
+    .authors
+    .iter()
+    .map(|author| author.family.as_str())
+    .collect::<Vec<_>>();
+  match families.as_slice() {
+    [] => format!("\"{}\"", reference.title),
+    [first] => first.to_string(),
+    [first, second] => format!("{}{}{}", first, and, second),
+    [first, second, third] if up_to_three => format!("{}, {},{}{}", first, second, and, third),
+    [first, ..] => format!("{} et al.", first),
+  }
+}
+
+/// `Smith, J., Doe, A., & Lee, B.`
+fn apa_authors(authors: &[ReferenceAuthor]) -> String {
+  let names = authors
+    .iter()
+    .map(|author| match author.initials() {
+      Some(initials) => format!("{}, {}", author.family, initials),
+      None => author.family.clone(),
+    })
+    .collect::<Vec<_>>();
+  match names.as_slice() {
+    [] => "Anonymous".to_string(),
+    [name] => name.clone(),
+    [rest @ .., last] => format!("{}, & {}", rest.join(", "), last),
+  }
+}
+
+/// `Smith, John, and Alice Doe` or `Smith, John, et al.`
+fn mla_authors(authors: &[ReferenceAuthor]) -> String {
+  match authors {
+    [] => "Anonymous".to_string(),
+    [author] => inverted_name(author),
+    [first, second] => format!("{}, and {}", inverted_name(first), full_name(second)),
+    [first, ..] => format!("{}, et al.", inverted_name(first)),
+  }
+}
+
+/// `Smith, John, Alice Doe, and Bob Lee`
+fn chicago_authors(authors: &[ReferenceAuthor]) -> String {
+  match authors {
+    [] => "Anonymous".to_string(),
+    [author] => inverted_name(author),
+    [first, rest @ ..] => {
+      let mut names = vec![inverted_name(first)];
+      names.extend(rest.iter().map(full_name));
+      let last = names.pop().unwrap_or_default();
+      format!("{}, and {}", names.join(", "), last)
+    },
+  }
+}
+
+/// `J. Smith, A. Doe, and B. Lee`
+fn ieee_authors(authors: &[ReferenceAuthor]) -> String {
+  let names = authors
+    .iter()
+    .map(|author| match author.initials() {
+      Some(initials) => format!("{} {}", initials, author.family),
+      None => author.family.clone(),
+    })
+    .collect::<Vec<_>>();
+  match names.as_slice() {
+    [] => "Anonymous".to_string(),
+    [name] => name.clone(),
+    [first, second] => format!("{} and {}", first, second),
+    [rest @ .., last] => format!("{}, and {}", rest.join(", "), last),
+  }
+}
+
+fn inverted_name(author: &ReferenceAuthor) -> String {
+  match &author.given {
+    Some(given) => format!("{}, {}", author.family, given),
+    None => author.family.clone(),
+  }
+}
+
+fn full_name(author: &ReferenceAuthor) -> String {
+  match &author.given {
+    Some(given) => format!("{} {}", given, author.family),
+    None => author.family.clone(),
+  }
+}
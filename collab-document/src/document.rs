@@ -3,35 +3,50 @@ use collab::core::collab::DataSource;
 use collab::core::origin::CollabOrigin;
 use collab::entity::EncodedCollab;
 use collab::preclude::block::ClientID;
+use collab::preclude::updates::decoder::Decode;
 use collab::preclude::*;
 use collab_entity::CollabType;
+use collab_entity::attachment::{AttachmentLocation, AttachmentWriteHook};
 use collab_entity::define::DOCUMENT_ROOT;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::borrow::{Borrow, BorrowMut};
 use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
 use std::vec;
 
+use crate::annotation::DocumentAnnotations;
+use crate::attachment::{block_attachment_references, is_attachment_block};
 use crate::block_parser::DocumentParser;
 use crate::block_parser::OutputFormat;
+use crate::blocks::BlockSchemaRegistry;
 use crate::blocks::BlockType;
+use crate::blocks::{AiPromptData, AiResponseData, AiResponseStatus, AiResponseVersion};
 use crate::blocks::{
   Block, BlockAction, BlockActionPayload, BlockActionType, BlockEvent, BlockOperation,
-  ChildrenOperation, DocumentData, DocumentMeta, EXTERNAL_TYPE_TEXT, TextDelta, TextOperation,
+  ChildrenOperation, DeltaType, DocumentData, DocumentMeta, EXTERNAL_TYPE_TEXT, SimpleColumnData,
+  SimpleTableData, SubtreeChangedCallback, SubtreeSubscribers, TextDelta, TextOperation,
   deserialize_text_delta, parse_event,
 };
 use crate::document_awareness::DocumentAwarenessState;
+use crate::document_data::{
+  derived_document_collab_data, generate_id, sanitized_client_id_from_document_id,
+};
 use crate::error::DocumentError;
+use crate::importer::fragment::DocumentFragment;
+use crate::limits::DocumentLimits;
 
 /// The page_id is a reference that points to the block's id.
 /// The block that is referenced by this page_id is the first block of the document.
 /// Crossing this block, we can build the whole document tree.
 const PAGE_ID: &str = "page_id";
 /// Document's all [Block] Map.
-const BLOCKS: &str = "blocks";
+pub(crate) const BLOCKS: &str = "blocks";
 /// Document's meta data.
-const META: &str = "meta";
+pub(crate) const META: &str = "meta";
+/// The key of the observer set by [Document::set_attachment_hook].
+const ATTACHMENT_HOOK_KEY: &str = "attachment_hook";
 /// [Block]'s relation map. And it's also in [META].
 /// The key is the parent block's children_id, and the value is the children block's id.
 const CHILDREN_MAP: &str = "children_map";
@@ -42,6 +57,17 @@ const TEXT_MAP: &str = "text_map";
 pub struct Document {
   collab: Collab,
   body: DocumentBody,
+  subtree_subscribers: SubtreeSubscribers,
+  /// The observer shared by all the subtree subscribers, see [Document::subscribe_subtree_changed].
+  subtree_subscription: Option<Subscription>,
+  /// See [Document::set_limits].
+  limits: Option<DocumentLimits>,
+  /// See [Document::set_block_schemas].
+  block_schemas: Option<Arc<BlockSchemaRegistry>>,
+  /// See [Document::annotations].
+  annotations: DocumentAnnotations,
+  /// The observer remapping the annotations, set by the first call of [Document::annotations].
+  annotation_subscription: Option<Subscription>,
 }
 
 impl Document {
@@ -50,7 +76,33 @@ impl Document {
   pub fn open(mut collab: Collab) -> Result<Self, DocumentError> {
     CollabType::Document.validate_require_data(&collab)?;
     let body = DocumentBody::new(&mut collab, None)?;
-    Ok(Self { collab, body })
+    Ok(Self {
+      collab,
+      body,
+      subtree_subscribers: SubtreeSubscribers::default(),
+      subtree_subscription: None,
+      limits: None,
+      block_schemas: None,
+      annotations: DocumentAnnotations::default(),
+      annotation_subscription: None,
+    })
+  }
+
+  /// Open the document of the collab, or write its initial content if the collab is empty, e.g. a
+  /// database row document that is created the first time the row is opened.
+  ///
+  /// The initial content is [derived_document_collab_data]: its block ids and the client id that
+  /// writes it are derived from the document id. Clients that create the same document at the
+  /// same time generate identical updates, so the document converges to a single page once the
+  /// updates are synced, instead of one page per client.
+  pub fn get_or_create(mut collab: Collab) -> Result<Self, DocumentError> {
+    if CollabType::Document.validate_require_data(&collab).is_err() {
+      let encoded_collab = derived_document_collab_data(collab.object_id())?;
+      let update = Update::decode_v1(&encoded_collab.doc_state)
+        .map_err(|err| DocumentError::Internal(err.into()))?;
+      collab.apply_update(update)?;
+    }
+    Self::open(collab)
   }
 
   /// Opening a document with given [DataSource]
@@ -69,7 +121,16 @@ impl Document {
 
   pub fn create_with_data(mut collab: Collab, data: DocumentData) -> Result<Self, DocumentError> {
     let body = DocumentBody::new(&mut collab, Some(data))?;
-    Ok(Self { collab, body })
+    Ok(Self {
+      collab,
+      body,
+      subtree_subscribers: SubtreeSubscribers::default(),
+      subtree_subscription: None,
+      limits: None,
+      block_schemas: None,
+      annotations: DocumentAnnotations::default(),
+      annotation_subscription: None,
+    })
   }
 
   pub fn create(
@@ -102,6 +163,20 @@ impl Document {
     })
   }
 
+  /// Encode a copy of the document for publishing, without its edit history nor the metadata
+  /// of its editors, see [DocumentData::sanitize].
+  ///
+  /// The copy is written in a single transaction by a client id derived from the document id, so
+  /// it doesn't carry the deleted content, the client ids of the editors or the order of their
+  /// edits.
+  pub fn export_sanitized(&self) -> Result<EncodedCollab, DocumentError> {
+    let mut data = self.get_document_data()?;
+    data.sanitize();
+    let document_id = self.collab.object_id().to_string();
+    let client_id = sanitized_client_id_from_document_id(&document_id);
+    Document::create(&document_id, data, client_id)?.encode_collab()
+  }
+
   /// open a document and subscribe to the document changes.
   pub fn subscribe_block_changed<K, F>(&mut self, key: K, callback: F)
   where
@@ -121,12 +196,242 @@ impl Document {
     });
   }
 
+  /// Subscribe to the changes of the subtree rooted at the given block.
+  ///
+  /// The callback only receives the event payloads that touch the block or one of its
+  /// descendants: the block fields, the children and the text of each block in the subtree. It's
+  /// not called for transactions that don't touch the subtree. Subscribing again with the same
+  /// key replaces the previous subscription.
+  ///
+  /// All subtree subscriptions of a document share a single observer, the changed blocks of a
+  /// transaction are resolved once no matter how many subtrees are observed.
+  pub fn subscribe_subtree_changed<K, F>(&mut self, key: K, block_id: &str, callback: F)
+  where
+    K: ToString,
+    F: Fn(&Vec<BlockEvent>, bool) + Send + Sync + 'static,
+  {
+    let callback: SubtreeChangedCallback = Arc::new(callback);
+    self
+      .subtree_subscribers
+      .insert(key.to_string(), block_id.to_string(), callback);
+    if self.subtree_subscription.is_some() {
+      return;
+    }
+
+    let object_id = self.object_id().to_string();
+    let self_origin = self.origin().clone();
+    let subscribers = self.subtree_subscribers.clone();
+    let block_operation = self.body.block_operation.clone();
+    let subscription = self.body.root.observe_deep(move |txn, events| {
+      if subscribers.is_empty() {
+        return;
+      }
+      let origin = CollabOrigin::from(txn);
+      let block_events = events
+        .iter()
+        .map(|deep_event| parse_event(&object_id, txn, deep_event))
+        .collect::<Vec<BlockEvent>>();
+      let is_remote = self_origin != origin;
+      subscribers.dispatch(txn, &block_operation, &block_events, is_remote);
+    });
+    self.subtree_subscription = Some(subscription);
+  }
+
+  /// Remove the subtree subscription with the given key. The shared observer is removed with the
+  /// last subscription.
+  pub fn unsubscribe_subtree_changed(&mut self, key: &str) {
+    if self.subtree_subscribers.remove(key) {
+      self.subtree_subscription = None;
+    }
+  }
+
+  /// Call the hook with the attachments of the image, file, video and audio blocks written to
+  /// the document, locally or by a remote peer, see [Document::get_attachment_references]. The
+  /// hook is called with no attachment for the removed blocks. Setting a hook again replaces the
+  /// previous one.
+  pub fn set_attachment_hook(&mut self, hook: Arc<dyn AttachmentWriteHook>) {
+    let object_id = self.object_id().to_string();
+    let block_operation = self.body.block_operation.clone();
+    self
+      .body
+      .root
+      .observe_deep_with(ATTACHMENT_HOOK_KEY, move |txn, events| {
+        let mut block_ids = vec![];
+        for event in events.iter() {
+          for payload in parse_event(&object_id, txn, event).iter() {
+            let block_id = match payload.path.as_slice() {
+              [blocks] if blocks == BLOCKS => &payload.id,
+              [blocks, block_id, ..] if blocks == BLOCKS => block_id,
+              _ => continue,
+            };
+            if !block_ids.contains(block_id) {
+              block_ids.push(block_id.clone());
+            }
+          }
+        }
+
+        for block_id in block_ids {
+          let block = block_operation.get_block_with_txn(txn, &block_id);
+          let references = match &block {
+            Some(block) if is_attachment_block(block) => {
+              block_attachment_references(&object_id, block)
+            },
+            Some(_) => continue,
+            None => vec![],
+          };
+          let location = AttachmentLocation::Block { block_id };
+          hook.did_write_attachments(&object_id, &location, references);
+        }
+      });
+  }
+
+  /// The transient annotations of the document, e.g. the misspellings reported by a spell
+  /// checker. They are not written to the collab.
+  ///
+  /// The annotations follow the changes of the text, local or remote: the ones after a change
+  /// are moved, the ones whose text was changed are removed, see
+  /// [DocumentAnnotations::apply_text_delta]. The annotations of the removed blocks are removed.
+  pub fn annotations(&mut self) -> DocumentAnnotations {
+    if self.annotation_subscription.is_none() {
+      let object_id = self.object_id().to_string();
+      let annotations = self.annotations.clone();
+      let block_operation = self.body.block_operation.clone();
+      let subscription = self.body.root.observe_deep(move |txn, events| {
+        let block_ids = annotations.get_annotated_block_ids();
+        if block_ids.is_empty() {
+          return;
+        }
+        for event in events.iter() {
+          for payload in parse_event(&object_id, txn, event).iter() {
+            match payload.path.as_slice() {
+              [meta, text_map, ..] if meta == META && text_map == TEXT_MAP => {
+                let Ok(delta) = deserialize_text_delta(&payload.value) else {
+                  continue;
+                };
+                // The annotations are keyed by block id, the texts by the external id of their
+                // block.
+                let block_id = block_ids.iter().find(|block_id| {
+                  block_operation
+                    .get_block_with_txn(txn, block_id)
+                    .and_then(|block| block.external_id)
+                    .is_some_and(|text_id| text_id == payload.id)
+                });
+                if let Some(block_id) = block_id {
+                  annotations.apply_text_delta(block_id, &delta);
+                }
+              },
+              [blocks] if blocks == BLOCKS && payload.command == DeltaType::Removed => {
+                annotations.clear_block(&payload.id);
+              },
+              _ => {},
+            }
+          }
+        }
+      });
+      self.annotation_subscription = Some(subscription);
+    }
+    self.annotations.clone()
+  }
+
   /// Get document data.
   pub fn get_document_data(&self) -> Result<DocumentData, DocumentError> {
     let txn = self.collab.transact();
     self.body.get_document_data(&txn)
   }
 
+  /// Get the subtree rooted at the given block.
+  ///
+  /// Only the blocks reachable from `block_id` within `depth` levels are materialized. A depth
+  /// of `Some(0)` returns the block itself, `None` returns the whole subtree. The page_id of the
+  /// returned [DocumentData] is the given block id.
+  ///
+  /// The text deltas are not decoded, the returned data is marked as `lazy_text` and its
+  /// `text_map` is empty. Use [Document::load_block_text] to load the text of a block when it's
+  /// first read.
+  pub fn get_subtree(
+    &self,
+    block_id: &str,
+    depth: Option<usize>,
+  ) -> Result<DocumentData, DocumentError> {
+    let txn = self.collab.transact();
+    self.body.get_subtree(&txn, block_id, depth)
+  }
+
+  /// Get the document data without decoding the text deltas.
+  ///
+  /// The returned data is marked as `lazy_text` and its `text_map` is empty. It's meant for
+  /// operations that only need the structure of the document, like building an outline or
+  /// counting blocks. Use [Document::get_text] or [Document::load_block_text] to read the text of
+  /// a block, or [Document::hydrate_text] to load all of them.
+  pub fn get_document_structure(&self) -> Result<DocumentData, DocumentError> {
+    let txn = self.collab.transact();
+    self.body.get_document_structure(&txn)
+  }
+
+  /// Get the text delta json string of the given block, read directly from the document.
+  /// Return None if the block doesn't exist or has no text.
+  pub fn get_text(&self, block_id: &str) -> Option<String> {
+    let txn = self.collab.transact();
+    let external_id = self
+      .body
+      .block_operation
+      .get_block_with_txn(&txn, block_id)?
+      .external_id?;
+    let delta = self
+      .body
+      .text_operation
+      .get_delta_with_txn(&txn, &external_id)?;
+    serde_json::to_string(&delta).ok()
+  }
+
+  /// Load the text of every block of the data that is not hydrated yet, and clear the
+  /// `lazy_text` flag.
+  pub fn hydrate_text(&self, data: &mut DocumentData) {
+    if !data.meta.lazy_text {
+      return;
+    }
+    let txn = self.collab.transact();
+    let text_map = data.meta.text_map.get_or_insert_with(HashMap::new);
+    for block in data.blocks.values() {
+      let Some(external_id) = &block.external_id else {
+        continue;
+      };
+      if text_map.contains_key(external_id) {
+        continue;
+      }
+      if let Some(delta) = self
+        .body
+        .text_operation
+        .get_delta_with_txn(&txn, external_id)
+        .and_then(|delta| serde_json::to_string(&delta).ok())
+      {
+        text_map.insert(external_id.clone(), delta);
+      }
+    }
+    data.meta.lazy_text = false;
+  }
+
+  /// Load the text delta json string of the given block into the `text_map` of the data.
+  ///
+  /// The text is only read from the document the first time, subsequent calls return the
+  /// cached value. Return None if the block doesn't exist in the data or has no text.
+  pub fn load_block_text(&self, data: &mut DocumentData, block_id: &str) -> Option<String> {
+    let external_id = data.blocks.get(block_id)?.external_id.clone()?;
+    let text_map = data.meta.text_map.get_or_insert_with(HashMap::new);
+    if let Some(delta) = text_map.get(&external_id) {
+      return Some(delta.clone());
+    }
+
+    let txn = self.collab.transact();
+    let delta = self
+      .body
+      .text_operation
+      .get_delta_with_txn(&txn, &external_id)?;
+    let delta = serde_json::to_string(&delta).ok()?;
+    text_map.insert(external_id, delta.clone());
+    Some(delta)
+  }
+
   /// Get page id
   pub fn get_page_id(&self) -> Option<String> {
     let txn = self.collab.transact();
@@ -154,6 +459,26 @@ impl Document {
       .apply_delta(&mut txn, text_id, delta);
   }
 
+  /// Check the blocks and the texts written with [Document::insert_block],
+  /// [Document::insert_fragment], [Document::set_block_delta] and the insert actions of
+  /// [Document::apply_action] against the limits.
+  ///
+  /// The blocks over the number of blocks or the depth are rejected whatever the policy, a
+  /// written document can't be truncated. The texts are truncated or rejected depending on the
+  /// policy. The text deltas applied with [Document::apply_text_delta] and the updates of the
+  /// remote peers are not checked.
+  pub fn set_limits(&mut self, limits: DocumentLimits) {
+    self.limits = Some(limits);
+  }
+
+  /// Validate the custom blocks written with [Document::insert_block],
+  /// [Document::insert_fragment], [Document::update_block] and the insert and update actions of
+  /// [Document::apply_action] against their schema: their data, and the type of the blocks
+  /// inserted as their children. The updates of the remote peers are not validated.
+  pub fn set_block_schemas(&mut self, schemas: Arc<BlockSchemaRegistry>) {
+    self.block_schemas = Some(schemas);
+  }
+
   /// Apply actions to the document.
   pub fn apply_action(&mut self, actions: Vec<BlockAction>) -> Result<(), DocumentError> {
     let mut txn = self.collab.transact_mut();
@@ -162,8 +487,33 @@ impl Document {
       tracing::trace!("apply_action: {:?}", action);
 
       let result = match action.action {
-        BlockActionType::Insert => self.body.handle_insert_action(&mut txn, action.payload),
-        BlockActionType::Update => self.body.handle_update_action(&mut txn, action.payload),
+        BlockActionType::Insert => {
+          if let Some(block) = &action.payload.block {
+            let parent_id = match &action.payload.parent_id {
+              Some(parent_id) if block.parent.is_empty() => parent_id,
+              _ => &block.parent,
+            };
+            if let Some(limits) = &self.limits {
+              self
+                .body
+                .check_insert_limits(&txn, limits, parent_id, 1, 1)?;
+            }
+            if let Some(schemas) = &self.block_schemas {
+              self
+                .body
+                .validate_block_schema(&txn, schemas, block, parent_id)?;
+            }
+          }
+          self.body.handle_insert_action(&mut txn, action.payload)
+        },
+        BlockActionType::Update => {
+          if let (Some(schemas), Some(block)) = (&self.block_schemas, &action.payload.block) {
+            self
+              .body
+              .validate_block_data_schema(&txn, schemas, &block.id, &block.data)?;
+          }
+          self.body.handle_update_action(&mut txn, action.payload)
+        },
         BlockActionType::Delete => self.body.handle_delete_action(&mut txn, action.payload),
         BlockActionType::Move => self.body.handle_move_action(&mut txn, action.payload),
         BlockActionType::InsertText | BlockActionType::ApplyTextDelta => self
@@ -210,9 +560,94 @@ impl Document {
     prev_id: Option<String>,
   ) -> Result<Block, DocumentError> {
     let mut txn = self.collab.transact_mut();
+    if let Some(limits) = &self.limits {
+      self
+        .body
+        .check_insert_limits(&txn, limits, &block.parent, 1, 1)?;
+    }
+    if let Some(schemas) = &self.block_schemas {
+      self
+        .body
+        .validate_block_schema(&txn, schemas, &block, &block.parent)?;
+    }
     self.body.insert_block(&mut txn, block, prev_id)
   }
 
+  /// Insert the blocks of an imported fragment into the parent of the fragment, in one transaction.
+  /// The top-level blocks are inserted after `prev_id`, or at the first position if it's None.
+  ///
+  /// Return the ids of the inserted top-level blocks, in order.
+  pub fn insert_fragment(
+    &mut self,
+    fragment: DocumentFragment,
+    prev_id: Option<String>,
+  ) -> Result<Vec<String>, DocumentError> {
+    let mut txn = self.collab.transact_mut();
+    if let Some(limits) = &self.limits {
+      // The blocks of the fragment come after their parent.
+      let mut depths: HashMap<&str, usize> = HashMap::new();
+      let mut max_depth = 0;
+      for block in fragment.blocks.iter() {
+        let depth = depths
+          .get(block.parent.as_str())
+          .map_or(1, |depth| depth + 1);
+        depths.insert(&block.id, depth);
+        max_depth = max_depth.max(depth);
+      }
+      self.body.check_insert_limits(
+        &txn,
+        limits,
+        &fragment.parent_id,
+        fragment.blocks.len(),
+        max_depth,
+      )?;
+    }
+    if let Some(schemas) = &self.block_schemas {
+      let block_types = fragment
+        .blocks
+        .iter()
+        .map(|block| (block.id.as_str(), block.ty.as_str()))
+        .collect::<HashMap<_, _>>();
+      for block in fragment.blocks.iter() {
+        schemas.validate_block(block)?;
+        match block_types.get(block.parent.as_str()) {
+          Some(parent_type) => schemas.validate_child(parent_type, &block.ty)?,
+          None => self
+            .body
+            .validate_block_schema(&txn, schemas, block, &block.parent)?,
+        }
+      }
+    }
+    // The texts are checked before any of them is written.
+    let mut texts = Vec::with_capacity(fragment.text_map.len());
+    for (text_id, delta) in fragment.text_map {
+      let delta = deserialize_text_delta(&delta).ok().unwrap_or_default();
+      let delta = match &self.limits {
+        Some(limits) => limits.limit_text_delta(delta)?,
+        None => delta,
+      };
+      texts.push((text_id, delta));
+    }
+    for (text_id, delta) in texts {
+      self
+        .body
+        .text_operation
+        .apply_delta(&mut txn, &text_id, delta);
+    }
+
+    // The last inserted child of each parent, the next sibling is inserted after it.
+    let mut last_child_ids: HashMap<String, String> = HashMap::new();
+    if let Some(prev_id) = prev_id {
+      last_child_ids.insert(fragment.parent_id.clone(), prev_id);
+    }
+    for block in fragment.blocks {
+      let prev_id = last_child_ids.get(&block.parent).cloned();
+      let block = self.body.insert_block(&mut txn, block, prev_id)?;
+      last_child_ids.insert(block.parent, block.id);
+    }
+    Ok(fragment.top_level_ids)
+  }
+
   pub fn delete_block(&mut self, block_id: &str) -> Result<(), DocumentError> {
     let mut txn = self.collab.transact_mut();
     self.body.delete_block(&mut txn, block_id)
@@ -313,6 +748,10 @@ impl Document {
     if delta.is_empty() {
       return Ok(());
     }
+    let delta = match &self.limits {
+      Some(limits) => limits.limit_text_delta(delta)?,
+      None => delta,
+    };
 
     let block_id = block_id.as_ref();
     let mut txn = self.collab.transact_mut();
@@ -345,6 +784,11 @@ impl Document {
     data: HashMap<String, Value>,
   ) -> Result<(), DocumentError> {
     let mut txn = self.collab.transact_mut();
+    if let Some(schemas) = &self.block_schemas {
+      self
+        .body
+        .validate_block_data_schema(&txn, schemas, block_id, &data)?;
+    }
     self
       .body
       .update_block_data(&mut txn, block_id, data, None, None)
@@ -360,6 +804,237 @@ impl Document {
     self.body.move_block(&mut txn, block_id, parent_id, prev_id)
   }
 
+  /// Get the presentation settings of the simple table block with the given id.
+  pub fn get_simple_table_data(&self, block_id: &str) -> Result<SimpleTableData, DocumentError> {
+    let block = self.get_simple_table_block(block_id)?;
+    Ok(SimpleTableData::from_block_data(&block.data))
+  }
+
+  /// Set the presentation settings of the simple table block with the given id. The other keys
+  /// of the block data are kept.
+  pub fn set_simple_table_data(
+    &mut self,
+    block_id: &str,
+    table_data: SimpleTableData,
+  ) -> Result<(), DocumentError> {
+    let mut data = self.get_simple_table_block(block_id)?.data;
+    table_data.write_to_block_data(&mut data);
+    self.update_block(block_id, data)
+  }
+
+  /// Update the presentation settings of the simple table block with the given id.
+  pub fn update_simple_table_data<F>(&mut self, block_id: &str, f: F) -> Result<(), DocumentError>
+  where
+    F: FnOnce(&mut SimpleTableData),
+  {
+    let mut table_data = self.get_simple_table_data(block_id)?;
+    f(&mut table_data);
+    self.set_simple_table_data(block_id, table_data)
+  }
+
+  /// Get the layout settings of the simple column block with the given id.
+  pub fn get_simple_column_data(&self, block_id: &str) -> Result<SimpleColumnData, DocumentError> {
+    let block = self.get_block_of_type(block_id, BlockType::SimpleColumn)?;
+    Ok(SimpleColumnData::from_block_data(&block.data))
+  }
+
+  /// Set the layout settings of the simple column block with the given id. The other keys of the
+  /// block data are kept.
+  pub fn set_simple_column_data(
+    &mut self,
+    block_id: &str,
+    column_data: SimpleColumnData,
+  ) -> Result<(), DocumentError> {
+    let mut data = self
+      .get_block_of_type(block_id, BlockType::SimpleColumn)?
+      .data;
+    column_data.write_to_block_data(&mut data);
+    self.update_block(block_id, data)
+  }
+
+  /// Insert an AI prompt block under the parent, after `prev_id` or at the first position if it's
+  /// None. The prompt is the text of the block.
+  pub fn insert_ai_prompt(
+    &mut self,
+    parent_id: &str,
+    prev_id: Option<String>,
+    prompt: &str,
+    model: Option<String>,
+  ) -> Result<Block, DocumentError> {
+    let data = AiPromptData {
+      model,
+      created_at: chrono::Utc::now().timestamp(),
+    };
+    let block = new_text_block(BlockType::AiPrompt, parent_id, data.into_block_data());
+    let block = self.insert_block(block, prev_id)?;
+    self.set_block_delta(
+      &block.id,
+      vec![TextDelta::Inserted(prompt.to_string(), None)],
+    )?;
+    Ok(block)
+  }
+
+  /// Insert an empty AI response block after the AI prompt block, ready to stream the response
+  /// with [Document::append_ai_response_text].
+  pub fn insert_ai_response(
+    &mut self,
+    prompt_id: &str,
+    model: Option<String>,
+  ) -> Result<Block, DocumentError> {
+    let prompt = self.get_block_of_type(prompt_id, BlockType::AiPrompt)?;
+    let data = AiResponseData {
+      prompt_id: Some(prompt.id.clone()),
+      model,
+      created_at: chrono::Utc::now().timestamp(),
+      status: AiResponseStatus::Streaming,
+      history: vec![],
+    };
+    let block = new_text_block(
+      BlockType::AiResponse,
+      &prompt.parent,
+      data.into_block_data(),
+    );
+    self.insert_block(block, Some(prompt.id))
+  }
+
+  /// Get the data of the AI response block with the given id.
+  pub fn get_ai_response_data(&self, block_id: &str) -> Result<AiResponseData, DocumentError> {
+    let block = self.get_block_of_type(block_id, BlockType::AiResponse)?;
+    Ok(AiResponseData::from_block_data(&block.data))
+  }
+
+  /// Append a chunk of the streamed response to the text of the AI response block. The peers
+  /// receive the chunks as small text inserts instead of the whole text.
+  ///
+  /// Return [DocumentError::AiResponseNotStreaming] once the response is finished, see
+  /// [Document::finish_ai_response].
+  pub fn append_ai_response_text(
+    &mut self,
+    block_id: &str,
+    text: &str,
+  ) -> Result<(), DocumentError> {
+    let block = self.get_block_of_type(block_id, BlockType::AiResponse)?;
+    if AiResponseData::from_block_data(&block.data).status != AiResponseStatus::Streaming {
+      return Err(DocumentError::AiResponseNotStreaming);
+    }
+    let text_id = block
+      .external_id
+      .ok_or(DocumentError::ExternalIdIsNotFound)?;
+    if text.is_empty() {
+      return Ok(());
+    }
+    let mut txn = self.collab.transact_mut();
+    let text_ref = self
+      .body
+      .text_operation
+      .get_text_with_txn(&mut txn, &text_id);
+    text_ref.push(&mut txn, text);
+    Ok(())
+  }
+
+  /// Set the final status of the streamed AI response, e.g. completed or stopped by the user.
+  pub fn finish_ai_response(
+    &mut self,
+    block_id: &str,
+    status: AiResponseStatus,
+  ) -> Result<(), DocumentError> {
+    let mut response = self.get_ai_response_data(block_id)?;
+    response.status = status;
+    self.write_ai_response(block_id, response, None)
+  }
+
+  /// Keep the current response of the AI response block in its history and clear its text to
+  /// stream a new response. The model of the previous response is kept when `model` is None.
+  pub fn regenerate_ai_response(
+    &mut self,
+    block_id: &str,
+    model: Option<String>,
+  ) -> Result<(), DocumentError> {
+    let mut response = self.get_ai_response_data(block_id)?;
+    let text = self.get_plain_text_from_block(block_id).unwrap_or_default();
+    response.history.push(AiResponseVersion {
+      text,
+      model: response.model.clone(),
+      created_at: response.created_at,
+      status: response.status,
+    });
+    response.model = model.or(response.model);
+    response.created_at = chrono::Utc::now().timestamp();
+    response.status = AiResponseStatus::Streaming;
+    self.write_ai_response(block_id, response, Some(vec![]))
+  }
+
+  /// Make a previous response of the history, see [AiResponseData::history], the current response
+  /// of the AI response block. The current response is kept at the end of the history.
+  pub fn select_ai_response_version(
+    &mut self,
+    block_id: &str,
+    index: usize,
+  ) -> Result<(), DocumentError> {
+    let mut response = self.get_ai_response_data(block_id)?;
+    if index >= response.history.len() {
+      return Err(DocumentError::NoRequiredData);
+    }
+    let text = self.get_plain_text_from_block(block_id).unwrap_or_default();
+    let selected = response.history.remove(index);
+    response.history.push(AiResponseVersion {
+      text,
+      model: response.model.clone(),
+      created_at: response.created_at,
+      status: response.status,
+    });
+    response.model = selected.model;
+    response.created_at = selected.created_at;
+    response.status = selected.status;
+    let delta = if selected.text.is_empty() {
+      vec![]
+    } else {
+      vec![TextDelta::Inserted(selected.text, None)]
+    };
+    self.write_ai_response(block_id, response, Some(delta))
+  }
+
+  /// Write the data of the AI response block, and replace its text if `delta` is set, in one
+  /// transaction.
+  fn write_ai_response(
+    &mut self,
+    block_id: &str,
+    response: AiResponseData,
+    delta: Option<Vec<TextDelta>>,
+  ) -> Result<(), DocumentError> {
+    let block = self.get_block_of_type(block_id, BlockType::AiResponse)?;
+    let mut data = block.data;
+    response.write_to_block_data(&mut data);
+    let mut txn = self.collab.transact_mut();
+    self
+      .body
+      .update_block_data(&mut txn, block_id, data, None, None)?;
+    if let (Some(delta), Some(text_id)) = (delta, block.external_id) {
+      self
+        .body
+        .text_operation
+        .set_delta(&mut txn, &text_id, delta);
+    }
+    Ok(())
+  }
+
+  fn get_simple_table_block(&self, block_id: &str) -> Result<Block, DocumentError> {
+    self.get_block_of_type(block_id, BlockType::SimpleTable)
+  }
+
+  fn get_block_of_type(&self, block_id: &str, ty: BlockType) -> Result<Block, DocumentError> {
+    let block = self
+      .get_block(block_id)
+      .ok_or(DocumentError::BlockIsNotFound)?;
+    if block.ty != ty.as_str() {
+      return Err(DocumentError::BlockTypeMismatch {
+        expected: ty.as_str().to_string(),
+        actual: block.ty,
+      });
+    }
+    Ok(block)
+  }
+
   pub fn redo(&mut self) -> bool {
     self.collab.redo().unwrap_or(false)
   }
@@ -540,19 +1215,31 @@ impl DocumentBody {
     text_operation: &TextOperation,
     block_operation: &BlockOperation,
   ) -> Result<(), DocumentError> {
+    // The text of lazy data lives in another document, writing it would drop the text.
+    if data.meta.lazy_text {
+      return Err(DocumentError::TextNotHydrated);
+    }
     root.insert(txn, PAGE_ID, data.page_id);
 
-    for (_, block) in data.blocks {
+    // Write in the order of the ids, so the same data always produces the same update, see
+    // [Document::get_or_create].
+    let mut blocks = data.blocks.into_iter().collect::<Vec<_>>();
+    blocks.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (_, block) in blocks {
       block_operation.create_block_with_txn(txn, block)?;
     }
 
-    for (id, child_ids) in data.meta.children_map {
+    let mut children_map = data.meta.children_map.into_iter().collect::<Vec<_>>();
+    children_map.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (id, child_ids) in children_map {
       let map = children_operation.get_or_init_children(txn, &id);
       child_ids.iter().for_each(|child_id| {
         map.push_back(txn, child_id.to_string());
       });
     }
     if let Some(text_map) = data.meta.text_map {
+      let mut text_map = text_map.into_iter().collect::<Vec<_>>();
+      text_map.sort_by(|(a, _), (b, _)| a.cmp(b));
       for (id, delta) in text_map {
         let delta = serde_json::from_str(&delta).unwrap_or_else(|_| vec![]);
         text_operation.apply_delta(txn, &id, delta)
@@ -594,6 +1281,9 @@ impl DocumentBody {
     txn: &mut TransactionMut,
     doc_data: Option<DocumentData>,
   ) -> Result<(), DocumentError> {
+    if doc_data.as_ref().is_some_and(|data| data.meta.lazy_text) {
+      return Err(DocumentError::TextNotHydrated);
+    }
     self
       .block_operation
       .get_all_blocks(txn)
@@ -662,6 +1352,73 @@ impl DocumentBody {
     let block = self.block_operation.create_block_with_txn(txn, block)?;
     self.insert_block_to_parent(txn, &block, prev_id)
   }
+  /// Check the number of blocks and the depth of the document once the blocks are inserted
+  /// under the parent. `depth` is the depth of the deepest inserted block below the parent.
+  fn check_insert_limits<T: ReadTxn>(
+    &self,
+    txn: &T,
+    limits: &DocumentLimits,
+    parent_id: &str,
+    num_of_blocks: usize,
+    depth: usize,
+  ) -> Result<(), DocumentError> {
+    // The page block isn't counted.
+    let num_of_blocks = self.block_operation.num_of_blocks(txn).saturating_sub(1) + num_of_blocks;
+    limits.check_insert(num_of_blocks, self.block_depth(txn, parent_id) + depth)
+  }
+
+  /// Validate the block inserted into the parent against the schema of its type and the schema
+  /// of the type of the parent.
+  fn validate_block_schema<T: ReadTxn>(
+    &self,
+    txn: &T,
+    schemas: &BlockSchemaRegistry,
+    block: &Block,
+    parent_id: &str,
+  ) -> Result<(), DocumentError> {
+    schemas.validate_block(block)?;
+    match self.block_operation.get_block_with_txn(txn, parent_id) {
+      Some(parent) => schemas.validate_child(&parent.ty, &block.ty),
+      None => Ok(()),
+    }
+  }
+
+  /// Validate the data written to the block against the schema of its type.
+  fn validate_block_data_schema<T: ReadTxn>(
+    &self,
+    txn: &T,
+    schemas: &BlockSchemaRegistry,
+    block_id: &str,
+    data: &HashMap<String, Value>,
+  ) -> Result<(), DocumentError> {
+    let schema = self
+      .block_operation
+      .get_block_with_txn(txn, block_id)
+      .and_then(|block| schemas.get(&block.ty).cloned());
+    match schema {
+      Some(schema) => schema.validate_data(data),
+      None => Ok(()),
+    }
+  }
+
+  /// The depth of the block, 0 for the page block.
+  fn block_depth<T: ReadTxn>(&self, txn: &T, block_id: &str) -> usize {
+    let max_depth = self.block_operation.num_of_blocks(txn);
+    let mut depth = 0;
+    let mut block_id = block_id.to_string();
+    // The depth is bounded by the number of blocks, in case the parents form a cycle.
+    while depth < max_depth {
+      match self.block_operation.get_block_with_txn(txn, &block_id) {
+        Some(block) if !block.parent.is_empty() => {
+          depth += 1;
+          block_id = block.parent;
+        },
+        _ => break,
+      }
+    }
+    depth
+  }
+
   /// Insert block with the given parent id and prev id.
   fn insert_block_to_parent(
     &self,
@@ -782,6 +1539,19 @@ impl DocumentBody {
   }
 
   pub fn get_document_data<T: ReadTxn>(&self, txn: &T) -> Result<DocumentData, DocumentError> {
+    self.read_document_data(txn, true)
+  }
+
+  /// Get the document data without decoding the text deltas, see [Document::get_document_structure].
+  pub fn get_document_structure<T: ReadTxn>(&self, txn: &T) -> Result<DocumentData, DocumentError> {
+    self.read_document_data(txn, false)
+  }
+
+  fn read_document_data<T: ReadTxn>(
+    &self,
+    txn: &T,
+    with_text: bool,
+  ) -> Result<DocumentData, DocumentError> {
     let page_id = self
       .root
       .get(txn, PAGE_ID)
@@ -790,18 +1560,70 @@ impl DocumentBody {
 
     let blocks = self.block_operation.get_all_blocks(txn);
     let children_map = self.children_operation.get_all_children(txn);
-    let text_map = self.text_operation.serialize_all_text_delta(txn);
+    let text_map = if with_text {
+      self.text_operation.serialize_all_text_delta(txn)
+    } else {
+      HashMap::new()
+    };
     let document_data = DocumentData {
       page_id,
       blocks,
       meta: DocumentMeta {
         children_map,
         text_map: Some(text_map),
+        lazy_text: !with_text,
       },
     };
     Ok(document_data)
   }
 
+  pub fn get_subtree<T: ReadTxn>(
+    &self,
+    txn: &T,
+    block_id: &str,
+    depth: Option<usize>,
+  ) -> Result<DocumentData, DocumentError> {
+    let root = self
+      .block_operation
+      .get_block_with_txn(txn, block_id)
+      .ok_or(DocumentError::BlockIsNotFound)?;
+
+    let mut blocks = HashMap::new();
+    let mut children_map = HashMap::new();
+    let mut queue = vec![(root, 0)];
+    while let Some((block, level)) = queue.pop() {
+      let child_ids = self
+        .children_operation
+        .get_children(txn, &block.children)
+        .into_iter()
+        .map(|child| child.to_string(txn))
+        .collect::<Vec<_>>();
+
+      if depth.is_none_or(|depth| level < depth) {
+        for child_id in child_ids.iter() {
+          if let Some(child) = self.block_operation.get_block_with_txn(txn, child_id) {
+            queue.push((child, level + 1));
+          }
+        }
+        children_map.insert(block.children.clone(), child_ids);
+      } else {
+        // The children of the blocks at the last level are not materialized.
+        children_map.insert(block.children.clone(), vec![]);
+      }
+      blocks.insert(block.id.clone(), block);
+    }
+
+    Ok(DocumentData {
+      page_id: block_id.to_string(),
+      blocks,
+      meta: DocumentMeta {
+        children_map,
+        text_map: Some(HashMap::new()),
+        lazy_text: true,
+      },
+    })
+  }
+
   /// move the block to the new parent.
   pub fn move_block(
     &self,
@@ -991,6 +1813,19 @@ impl From<&Document> for DocumentIndexContent {
   }
 }
 
+/// A block with an empty text, to insert into the document.
+fn new_text_block(ty: BlockType, parent_id: &str, data: HashMap<String, Value>) -> Block {
+  Block {
+    id: generate_id(),
+    ty: ty.to_string(),
+    parent: parent_id.to_string(),
+    children: generate_id(),
+    external_id: Some(generate_id()),
+    external_type: Some(EXTERNAL_TYPE_TEXT.to_string()),
+    data,
+  }
+}
+
 pub fn gen_document_id() -> String {
   uuid::Uuid::new_v4().to_string()
 }
@@ -29,6 +29,24 @@ pub const PARAGRAPH_BLOCK_TYPE: &str = "paragraph";
 /// A `DocumentData` instance populated with a single page block and a single child text block.
 ///
 pub fn default_document_data(document_id: &str) -> DocumentData {
+  let page_id = page_id_from_document_id(document_id).unwrap_or_else(generate_id);
+  document_data_with_ids(page_id, |_| generate_id())
+}
+
+/// Same as [default_document_data], except that all the block ids are derived from the document
+/// id, so the data is the same every time it's generated for a document.
+pub fn derived_document_data(document_id: &str) -> DocumentData {
+  let page_id = page_id_from_document_id(document_id)
+    .unwrap_or_else(|| derived_id_from_document_id(document_id, PAGE));
+  document_data_with_ids(page_id, |name| {
+    derived_id_from_document_id(document_id, name)
+  })
+}
+
+fn document_data_with_ids<F: FnMut(&str) -> String>(
+  page_id: String,
+  mut gen_id: F,
+) -> DocumentData {
   let page_type = PAGE.to_string();
   let text_type = PARAGRAPH_BLOCK_TYPE.to_string();
 
@@ -37,7 +55,6 @@ pub fn default_document_data(document_id: &str) -> DocumentData {
   let mut text_map: HashMap<String, String> = HashMap::new();
 
   // page block
-  let page_id = page_id_from_document_id(document_id).unwrap_or_else(generate_id);
   let children_id = page_id.clone();
   let root = Block {
     id: page_id.clone(),
@@ -51,9 +68,9 @@ pub fn default_document_data(document_id: &str) -> DocumentData {
   blocks.insert(page_id.clone(), root);
 
   // text block
-  let text_block_id = generate_id();
-  let text_block_children_id = generate_id();
-  let text_external_id = generate_id();
+  let text_block_id = gen_id("text_block");
+  let text_block_children_id = gen_id("text_block_children");
+  let text_external_id = gen_id("text_block_text");
   let text_block = Block {
     id: text_block_id.clone(),
     ty: text_type,
@@ -78,6 +95,7 @@ pub fn default_document_data(document_id: &str) -> DocumentData {
     meta: DocumentMeta {
       children_map,
       text_map: Some(text_map),
+      lazy_text: false,
     },
   }
 }
@@ -93,10 +111,186 @@ pub fn default_document_collab_data(
   document.encode_collab()
 }
 
+/// Generates the collab data of [derived_document_data], written by a client id derived from the
+/// document id. Every client generates the same update for a document, so a document created by
+/// several clients at the same time converges to a single page once their updates are merged.
+pub fn derived_document_collab_data(document_id: &str) -> Result<EncodedCollab, DocumentError> {
+  let document_data = derived_document_data(document_id);
+  let client_id = derived_client_id_from_document_id(document_id);
+  let document = Document::create(document_id, document_data, client_id)?;
+  document.encode_collab()
+}
+
+/// How [DocumentData::merge] places the top-level blocks of the merged document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeStrategy {
+  /// Append the blocks after the existing children of the parent. If the parent_id is None, the
+  /// blocks are appended to the page.
+  Append { parent_id: Option<String> },
+  /// Alternate the existing children of the parent with the merged blocks, starting with the
+  /// existing ones. The remaining blocks of the longer list are appended at the end.
+  Interleave { parent_id: Option<String> },
+}
+
+impl MergeStrategy {
+  fn parent_id(&self) -> Option<&String> {
+    match self {
+      MergeStrategy::Append { parent_id } | MergeStrategy::Interleave { parent_id } => {
+        parent_id.as_ref()
+      },
+    }
+  }
+}
+
+impl DocumentData {
+  /// Merge the blocks of another document into this one.
+  ///
+  /// The page block of `other` is dropped and its children become children of the chosen parent.
+  /// Every merged block, children id and text id is remapped to a newly generated id, so the
+  /// same document can be merged multiple times without conflicts.
+  ///
+  /// Return the ids of the merged top-level blocks, in order.
+  pub fn merge(
+    &mut self,
+    mut other: DocumentData,
+    strategy: MergeStrategy,
+  ) -> Result<Vec<String>, DocumentError> {
+    // The text ids are remapped, the text of lazy data couldn't be hydrated after the merge.
+    if other.meta.lazy_text {
+      return Err(DocumentError::TextNotHydrated);
+    }
+    let parent_id = strategy
+      .parent_id()
+      .cloned()
+      .unwrap_or_else(|| self.page_id.clone());
+    let parent_children_id = self
+      .blocks
+      .get(&parent_id)
+      .map(|block| block.children.clone())
+      .ok_or(DocumentError::ParentIsNotFound)?;
+    let other_page = other
+      .blocks
+      .remove(&other.page_id)
+      .ok_or(DocumentError::PageBlockNotFound)?;
+
+    let id_map = other
+      .blocks
+      .keys()
+      .map(|id| (id.clone(), generate_id()))
+      .collect::<HashMap<_, _>>();
+    let mut other_text_map = other.meta.text_map.take().unwrap_or_default();
+    let remap_children = |children_id: &str| -> Vec<String> {
+      other
+        .meta
+        .children_map
+        .get(children_id)
+        .map(|children| {
+          children
+            .iter()
+            .filter_map(|child| id_map.get(child).cloned())
+            .collect()
+        })
+        .unwrap_or_default()
+    };
+    let top_level_ids = remap_children(&other_page.children);
+
+    for (old_id, block) in other.blocks.iter() {
+      let new_id = id_map[old_id].clone();
+      // Most blocks use their own id as the children id, keep that convention.
+      let new_children_id = if block.children == *old_id {
+        new_id.clone()
+      } else {
+        generate_id()
+      };
+      let new_parent_id = id_map
+        .get(&block.parent)
+        .cloned()
+        .unwrap_or_else(|| parent_id.clone());
+      let new_external_id = block.external_id.as_ref().map(|external_id| {
+        let new_external_id = generate_id();
+        if let Some(delta) = other_text_map.remove(external_id) {
+          self
+            .meta
+            .text_map
+            .get_or_insert_with(HashMap::new)
+            .insert(new_external_id.clone(), delta);
+        }
+        new_external_id
+      });
+
+      self
+        .meta
+        .children_map
+        .insert(new_children_id.clone(), remap_children(&block.children));
+      self.blocks.insert(
+        new_id.clone(),
+        Block {
+          id: new_id,
+          ty: block.ty.clone(),
+          parent: new_parent_id,
+          children: new_children_id,
+          external_id: new_external_id,
+          external_type: block.external_type.clone(),
+          data: block.data.clone(),
+        },
+      );
+    }
+
+    let existing = self
+      .meta
+      .children_map
+      .remove(&parent_children_id)
+      .unwrap_or_default();
+    let children = match strategy {
+      MergeStrategy::Append { .. } => existing
+        .into_iter()
+        .chain(top_level_ids.iter().cloned())
+        .collect(),
+      MergeStrategy::Interleave { .. } => {
+        let mut children = Vec::with_capacity(existing.len() + top_level_ids.len());
+        let mut existing = existing.into_iter();
+        let mut merged = top_level_ids.iter().cloned();
+        loop {
+          match (existing.next(), merged.next()) {
+            (None, None) => break,
+            (a, b) => children.extend(a.into_iter().chain(b)),
+          }
+        }
+        children
+      },
+    };
+    self.meta.children_map.insert(parent_children_id, children);
+    Ok(top_level_ids)
+  }
+}
+
 pub fn generate_id() -> String {
   nanoid!(10)
 }
 
+fn derived_id_from_document_id(document_id: &str, name: &str) -> String {
+  let namespace = Uuid::parse_str(document_id)
+    .unwrap_or_else(|_| Uuid::new_v5(&Uuid::NAMESPACE_OID, document_id.as_bytes()));
+  Uuid::new_v5(&namespace, name.as_bytes()).to_string()
+}
+
+/// The client id that writes the initial content of [derived_document_collab_data]. It's kept
+/// below 2^32 like the ids of [collab::core::collab::default_client_id].
+fn derived_client_id_from_document_id(document_id: &str) -> ClientID {
+  derived_client_id(document_id, "client_id")
+}
+
+/// The client id that writes the copies of [Document::export_sanitized].
+pub(crate) fn sanitized_client_id_from_document_id(document_id: &str) -> ClientID {
+  derived_client_id(document_id, "sanitized_client_id")
+}
+
+fn derived_client_id(document_id: &str, name: &str) -> ClientID {
+  let uuid = derived_id_from_document_id(document_id, name);
+  let bytes = Uuid::parse_str(&uuid).unwrap_or_default().into_bytes();
+  u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as ClientID
+}
+
 pub fn page_id_from_document_id(document_id: &str) -> Option<String> {
   let document_uuid = Uuid::parse_str(document_id).ok()?;
   Some(Uuid::new_v5(&document_uuid, PAGE.as_bytes()).to_string())
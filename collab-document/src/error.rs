@@ -1,4 +1,45 @@
+use crate::limits::DocumentLimit;
 use collab_entity::CollabValidateError;
+use std::fmt::{Display, Formatter};
+
+/// The kind of failure an error represents, so callers like API servers can map it to a user
+/// message or a retry policy without matching every variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+  /// The imported content is malformed or in an unsupported format.
+  SourceFormat,
+  /// A file or another resource the content refers to is missing or unreadable.
+  Resource,
+  /// The content doesn't fit the document structure, e.g. a block whose parent doesn't exist.
+  Structural,
+  /// Reading or writing the collab or the file system failed.
+  Storage,
+  /// An unexpected failure, usually a bug.
+  Internal,
+}
+
+impl ErrorCategory {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      ErrorCategory::SourceFormat => "source_format",
+      ErrorCategory::Resource => "resource",
+      ErrorCategory::Structural => "structural",
+      ErrorCategory::Storage => "storage",
+      ErrorCategory::Internal => "internal",
+    }
+  }
+
+  /// Only storage failures may succeed when retried, the other failures depend on the content.
+  pub fn is_retryable(&self) -> bool {
+    matches!(self, ErrorCategory::Storage)
+  }
+}
+
+impl Display for ErrorCategory {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum DocumentError {
@@ -58,6 +99,105 @@ pub enum DocumentError {
 
   #[error("Unable to find the page block")]
   PageBlockNotFound,
+
+  #[error("Expected a {expected} block, found {actual}")]
+  BlockTypeMismatch { expected: String, actual: String },
+
+  #[error("The text of the document data is not hydrated")]
+  TextNotHydrated,
+
+  #[error("The input is {size} bytes, over the limit of {limit} bytes")]
+  InputTooLarge { size: usize, limit: usize },
+
+  #[error("Line {line} is {len} bytes, over the limit of {limit} bytes")]
+  LineTooLong {
+    line: usize,
+    len: usize,
+    limit: usize,
+  },
+
+  #[error("The input looks like a binary file")]
+  BinaryContent,
+
+  #[error("The document is over the {limit} limit: {size}, the limit is {max}")]
+  DocumentLimitExceeded {
+    limit: DocumentLimit,
+    size: usize,
+    max: usize,
+  },
+
+  #[error("Invalid {block_type} block: {reason}")]
+  BlockSchemaViolation { block_type: String, reason: String },
+
+  #[error("The AI response is not streaming")]
+  AiResponseNotStreaming,
+}
+
+impl DocumentError {
+  /// A stable code identifying the error, which doesn't change when the message is reworded.
+  pub fn code(&self) -> &'static str {
+    match self {
+      DocumentError::Internal(_) => "document.internal",
+      DocumentError::CollabError(_) => "document.collab",
+      DocumentError::BlockCreateError => "document.block_create_failed",
+      DocumentError::BlockAlreadyExists => "document.block_already_exists",
+      DocumentError::BlockIsNotFound => "document.block_not_found",
+      DocumentError::PageIdIsEmpty => "document.page_id_empty",
+      DocumentError::ConvertDataError => "document.invalid_block_data",
+      DocumentError::ParentIsNotFound => "document.parent_not_found",
+      DocumentError::CreateRootBlockError => "document.root_block_create_failed",
+      DocumentError::DeleteBlockError => "document.block_delete_failed",
+      DocumentError::TextActionParamsError => "document.invalid_text_action",
+      DocumentError::NoRequiredData => "document.missing_required_data",
+      DocumentError::ExternalIdIsNotFound => "document.external_id_not_found",
+      DocumentError::ParseDocumentError => "document.parse_failed",
+      DocumentError::ParseMarkdownError => "document.parse_markdown_failed",
+      DocumentError::ParseDeltaJsonToTextDeltaError => "document.invalid_delta_json",
+      DocumentError::NoBlockChildrenFound => "document.block_children_not_found",
+      DocumentError::UnknownBlockType(_) => "document.unknown_block_type",
+      DocumentError::PageBlockNotFound => "document.page_block_not_found",
+      DocumentError::BlockTypeMismatch { .. } => "document.block_type_mismatch",
+      DocumentError::TextNotHydrated => "document.text_not_hydrated",
+      DocumentError::InputTooLarge { .. } => "document.input_too_large",
+      DocumentError::LineTooLong { .. } => "document.line_too_long",
+      DocumentError::BinaryContent => "document.binary_content",
+      DocumentError::DocumentLimitExceeded { .. } => "document.limit_exceeded",
+      DocumentError::BlockSchemaViolation { .. } => "document.block_schema_violation",
+      DocumentError::AiResponseNotStreaming => "document.ai_response_not_streaming",
+    }
+  }
+
+  pub fn category(&self) -> ErrorCategory {
+    match self {
+      DocumentError::Internal(_) => ErrorCategory::Internal,
+      // The collab may be missing data that isn't synced yet.
+      DocumentError::CollabError(_) | DocumentError::NoRequiredData => ErrorCategory::Storage,
+      DocumentError::ConvertDataError
+      | DocumentError::ParseDocumentError
+      | DocumentError::ParseMarkdownError
+      | DocumentError::ParseDeltaJsonToTextDeltaError
+      | DocumentError::UnknownBlockType(_)
+      | DocumentError::InputTooLarge { .. }
+      | DocumentError::LineTooLong { .. }
+      | DocumentError::BinaryContent => ErrorCategory::SourceFormat,
+      DocumentError::BlockCreateError
+      | DocumentError::BlockAlreadyExists
+      | DocumentError::BlockIsNotFound
+      | DocumentError::PageIdIsEmpty
+      | DocumentError::ParentIsNotFound
+      | DocumentError::CreateRootBlockError
+      | DocumentError::DeleteBlockError
+      | DocumentError::TextActionParamsError
+      | DocumentError::ExternalIdIsNotFound
+      | DocumentError::NoBlockChildrenFound
+      | DocumentError::PageBlockNotFound
+      | DocumentError::BlockTypeMismatch { .. }
+      | DocumentError::TextNotHydrated
+      | DocumentError::DocumentLimitExceeded { .. }
+      | DocumentError::BlockSchemaViolation { .. }
+      | DocumentError::AiResponseNotStreaming => ErrorCategory::Structural,
+    }
+  }
 }
 
 impl From<CollabValidateError> for DocumentError {
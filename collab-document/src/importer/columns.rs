@@ -0,0 +1,67 @@
+/// When a table with an empty header row is imported as columns, see
+/// [crate::importer::md_importer::MDImporter::with_columns_detection].
+///
+/// Notion exports its columns as a table with an empty header row, which can't be told apart
+/// from a small table whose header was left empty. A table whose header carries the
+/// [crate::importer::define::COLUMN_WIDTH_RATIO_COMMENT] hints, e.g. one converted from the
+/// column list of a Notion html export, is imported as columns unless the detection is off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColumnsDetection {
+  /// The tables are always imported as tables.
+  Off,
+  /// Only a table with a single row of at most three cells is imported as columns.
+  Conservative,
+  /// The rules matching the Notion markdown exports, see [ColumnsThresholds::AGGRESSIVE].
+  #[default]
+  Aggressive,
+  Custom(ColumnsThresholds),
+}
+
+/// The size of the tables with an empty header row that are imported as columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnsThresholds {
+  /// The maximum number of columns of a table with content.
+  pub max_columns: usize,
+  /// The maximum number of rows of a table with content, the header row excluded.
+  pub max_rows: usize,
+  /// The minimum number of columns of a table without content, the layout of empty columns.
+  /// None if such tables are imported as tables.
+  pub min_empty_columns: Option<usize>,
+}
+
+impl ColumnsThresholds {
+  pub const CONSERVATIVE: Self = Self {
+    max_columns: 3,
+    max_rows: 1,
+    min_empty_columns: None,
+  };
+
+  pub const AGGRESSIVE: Self = Self {
+    max_columns: 5,
+    max_rows: 3,
+    min_empty_columns: Some(6),
+  };
+
+  /// Whether a table with an empty header row of the given size is imported as columns.
+  pub fn matches(&self, num_of_columns: usize, num_of_rows: usize, is_empty: bool) -> bool {
+    if is_empty {
+      self
+        .min_empty_columns
+        .is_some_and(|min_empty_columns| num_of_columns >= min_empty_columns)
+    } else {
+      num_of_columns <= self.max_columns && num_of_rows <= self.max_rows
+    }
+  }
+}
+
+impl ColumnsDetection {
+  /// The thresholds of the detection, None if it's off.
+  pub fn thresholds(&self) -> Option<ColumnsThresholds> {
+    match self {
+      ColumnsDetection::Off => None,
+      ColumnsDetection::Conservative => Some(ColumnsThresholds::CONSERVATIVE),
+      ColumnsDetection::Aggressive => Some(ColumnsThresholds::AGGRESSIVE),
+      ColumnsDetection::Custom(thresholds) => Some(*thresholds),
+    }
+  }
+}
@@ -0,0 +1,276 @@
+use crate::blocks::DocumentData;
+use crate::importer::define::*;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde_json::{Map, Value, json};
+
+const MONTHS: [&str; 12] = [
+  "january",
+  "february",
+  "march",
+  "april",
+  "may",
+  "june",
+  "july",
+  "august",
+  "september",
+  "october",
+  "november",
+  "december",
+];
+
+/// The separator Notion uses between the start and the end of a date range.
+const RANGE_SEPARATOR: &str = "→";
+
+/// The text of formatted ops that must not be converted, e.g. inline code or a link text.
+const VERBATIM_ATTRS: [&str; 4] = [CODE_ATTR, HREF_ATTR, FORMULA_ATTR, MENTION_ATTR];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ParsedDate {
+  date_time: NaiveDateTime,
+  include_time: bool,
+  /// The length of the parsed text, in bytes.
+  len: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum InlinePiece {
+  Text(String),
+  Date(ParsedDate),
+}
+
+/// Convert the dates Notion exports as plain text to date mentions in the text of all the blocks.
+///
+/// A date is converted if it's mentioned, e.g. `@March 4, 2024` or `@2024-03-04 10:30 AM`, or
+/// if it's part of a range, e.g. `2024-03-04 → 2024-03-06`. A range becomes two mentions.
+pub(crate) fn convert_date_mentions(document_data: &mut DocumentData) {
+  let Some(text_map) = document_data.meta.text_map.as_mut() else {
+    return;
+  };
+  for delta in text_map.values_mut() {
+    if !delta.contains('@') && !delta.contains(RANGE_SEPARATOR) {
+      continue;
+    }
+    if let Some(converted) = convert_delta(delta) {
+      *delta = converted;
+    }
+  }
+}
+
+/// Return None if the delta has no date to convert.
+fn convert_delta(delta: &str) -> Option<String> {
+  let ops: Vec<Value> = serde_json::from_str(delta).ok()?;
+  let mut converted = false;
+  let mut new_ops = Vec::with_capacity(ops.len());
+  for op in ops {
+    let attributes = op.get("attributes").and_then(Value::as_object);
+    let is_verbatim =
+      attributes.is_some_and(|attrs| VERBATIM_ATTRS.iter().any(|key| attrs.contains_key(*key)));
+    let pieces = match op.get("insert").and_then(Value::as_str) {
+      Some(text) if !is_verbatim => split_date_mentions(text),
+      _ => None,
+    };
+    let Some(pieces) = pieces else {
+      new_ops.push(op);
+      continue;
+    };
+
+    converted = true;
+    let attributes = attributes.cloned().unwrap_or_default();
+    for piece in pieces {
+      new_ops.push(piece_to_op(piece, &attributes));
+    }
+  }
+  converted.then(|| Value::Array(new_ops).to_string())
+}
+
+fn piece_to_op(piece: InlinePiece, attributes: &Map<String, Value>) -> Value {
+  let (insert, attributes) = match piece {
+    InlinePiece::Text(text) => (text, attributes.clone()),
+    InlinePiece::Date(date) => {
+      let mut attributes = attributes.clone();
+      attributes.insert(
+        MENTION_ATTR.to_string(),
+        json!({
+          MENTION_TYPE_FIELD: MENTION_TYPE_DATE,
+          MENTION_DATE_FIELD: date.date_time.format("%Y-%m-%dT%H:%M:%S%.3f").to_string(),
+          MENTION_INCLUDE_TIME_FIELD: date.include_time,
+        }),
+      );
+      (MENTION_SYMBOL.to_string(), attributes)
+    },
+  };
+  if attributes.is_empty() {
+    json!({ "insert": insert })
+  } else {
+    json!({ "insert": insert, "attributes": attributes })
+  }
+}
+
+/// Return None if the text has no date to convert.
+fn split_date_mentions(text: &str) -> Option<Vec<InlinePiece>> {
+  let mut pieces = vec![];
+  let mut last = 0;
+  let mut index = 0;
+  while index < text.len() {
+    let rest = &text[index..];
+    let at_word_start = text[..index]
+      .chars()
+      .next_back()
+      .is_none_or(|c| !c.is_alphanumeric());
+    if at_word_start {
+      if let Some((dates, len)) = match_date_mention(rest) {
+        if last < index {
+          pieces.push(InlinePiece::Text(text[last..index].to_string()));
+        }
+        pieces.extend(dates);
+        index += len;
+        last = index;
+        continue;
+      }
+    }
+    index += rest.chars().next().map_or(1, char::len_utf8);
+  }
+  if pieces.is_empty() {
+    return None;
+  }
+  if last < text.len() {
+    pieces.push(InlinePiece::Text(text[last..].to_string()));
+  }
+  Some(pieces)
+}
+
+/// Match a mentioned date or a date range at the start of the text, and return the pieces it
+/// converts to and the length of the matched text.
+fn match_date_mention(text: &str) -> Option<(Vec<InlinePiece>, usize)> {
+  let (mentioned, body) = match text.strip_prefix('@') {
+    Some(body) => (true, body),
+    None => (false, text),
+  };
+  let start = parse_date_prefix(body)?;
+  let after_start = &body[start.len..];
+
+  let mut matched = None;
+  if let Some(after_separator) = after_start
+    .trim_start_matches(' ')
+    .strip_prefix(RANGE_SEPARATOR)
+  {
+    let end_text = after_separator.trim_start_matches(' ');
+    let end_body = end_text.strip_prefix('@').unwrap_or(end_text);
+    if let Some(end) = parse_date_prefix(end_body) {
+      let len = text.len() - end_body.len() + end.len;
+      let pieces = vec![
+        InlinePiece::Date(start),
+        InlinePiece::Text(format!(" {} ", RANGE_SEPARATOR)),
+        InlinePiece::Date(end),
+      ];
+      matched = Some((pieces, len));
+    }
+  }
+  if matched.is_none() && mentioned {
+    matched = Some((
+      vec![InlinePiece::Date(start)],
+      text.len() - after_start.len(),
+    ));
+  }
+
+  // The date must not be followed by a word, e.g. `@2024-03-04th`.
+  let (pieces, len) = matched?;
+  let at_word_end = text[len..]
+    .chars()
+    .next()
+    .is_none_or(|c| !c.is_alphanumeric());
+  at_word_end.then_some((pieces, len))
+}
+
+/// Parse a date at the start of the text, either `2024-03-04` or `March 4, 2024`, optionally
+/// followed by a time, either `10:30` or `10:30 AM`.
+fn parse_date_prefix(text: &str) -> Option<ParsedDate> {
+  let (date, mut len) = parse_iso_date(text).or_else(|| parse_month_name_date(text))?;
+  let mut date_time = date.and_time(NaiveTime::MIN);
+  let mut include_time = false;
+  if let Some((time, time_len)) = text[len..].strip_prefix(' ').and_then(parse_time) {
+    date_time = date.and_time(time);
+    include_time = true;
+    len += 1 + time_len;
+  }
+  Some(ParsedDate {
+    date_time,
+    include_time,
+    len,
+  })
+}
+
+fn parse_iso_date(text: &str) -> Option<(NaiveDate, usize)> {
+  let (year, _) = take_digits(text, 4, 4)?;
+  let (month, _) = take_digits(text[4..].strip_prefix('-')?, 2, 2)?;
+  let (day, _) = take_digits(text[7..].strip_prefix('-')?, 2, 2)?;
+  let date = NaiveDate::from_ymd_opt(year as i32, month, day)?;
+  Some((date, 10))
+}
+
+/// Parse `March 4, 2024` or `Mar 4, 2024`.
+fn parse_month_name_date(text: &str) -> Option<(NaiveDate, usize)> {
+  let name_len = text
+    .bytes()
+    .take_while(|byte| byte.is_ascii_alphabetic())
+    .count();
+  let name = text[..name_len].to_ascii_lowercase();
+  let month = MONTHS
+    .iter()
+    .position(|month| *month == name || (name.len() == 3 && month.starts_with(&name)))?;
+
+  let rest = text[name_len..].strip_prefix(' ')?;
+  let (day, day_len) = take_digits(rest, 1, 2)?;
+  let rest = rest[day_len..].strip_prefix(", ")?;
+  let (year, _) = take_digits(rest, 4, 4)?;
+  let date = NaiveDate::from_ymd_opt(year as i32, month as u32 + 1, day)?;
+  Some((date, name_len + 1 + day_len + 2 + 4))
+}
+
+fn parse_time(text: &str) -> Option<(NaiveTime, usize)> {
+  let (mut hour, hour_len) = take_digits(text, 1, 2)?;
+  let (minute, _) = take_digits(text[hour_len..].strip_prefix(':')?, 2, 2)?;
+  let mut len = hour_len + 3;
+
+  let period = text[len..]
+    .strip_prefix(' ')
+    .and_then(|rest| rest.get(..2))
+    .map(|period| period.to_ascii_uppercase());
+  let is_period_end = text
+    .get(len + 3..)
+    .is_some_and(|rest| rest.chars().next().is_none_or(|c| !c.is_alphanumeric()));
+  if let Some(period) = period.filter(|period| is_period_end && (period == "AM" || period == "PM"))
+  {
+    if !(1..=12).contains(&hour) {
+      return None;
+    }
+    hour = match (period.as_str(), hour) {
+      ("AM", 12) => 0,
+      ("PM", 12) => 12,
+      ("PM", hour) => hour + 12,
+      (_, hour) => hour,
+    };
+    len += 3;
+  }
+  let time = NaiveTime::from_hms_opt(hour, minute, 0)?;
+  Some((time, len))
+}
+
+/// Parse between `min` and `max` ascii digits at the start of the text, which must not be
+/// followed by another digit.
+fn take_digits(text: &str, min: usize, max: usize) -> Option<(u32, usize)> {
+  let len = text
+    .bytes()
+    .take(max)
+    .take_while(|byte| byte.is_ascii_digit())
+    .count();
+  if len < min
+    || text
+      .as_bytes()
+      .get(len)
+      .is_some_and(|byte| byte.is_ascii_digit())
+  {
+    return None;
+  }
+  Some((text[..len].parse().ok()?, len))
+}
@@ -22,6 +22,21 @@ pub const URL_FIELD: &str = "url";
 pub const IMAGE_TYPE_FIELD: &str = "image_type";
 pub const EXTERNAL_IMAGE_TYPE: i32 = 2;
 
+// Video and Audio Keys
+pub const MEDIA_PROVIDER_FIELD: &str = "provider";
+pub const MEDIA_PROVIDER_ID_FIELD: &str = "provider_id";
+pub const MEDIA_NAME_FIELD: &str = "name";
+
+// Embed Keys
+pub const EMBED_PROVIDER_FIELD: &str = "provider";
+pub const EMBED_TITLE_FIELD: &str = "title";
+pub const EMBED_DESCRIPTION_FIELD: &str = "description";
+pub const EMBED_THUMBNAIL_FIELD: &str = "thumbnail_url";
+
+// File Keys
+pub const FILE_NAME_FIELD: &str = "name";
+pub const FILE_SIZE_FIELD: &str = "size";
+
 // Math Equation Keys
 pub const FORMULA_FIELD: &str = "formula";
 
@@ -32,8 +47,30 @@ pub const HREF_ATTR: &str = "href";
 pub const CODE_ATTR: &str = "code";
 pub const FORMULA_ATTR: &str = "formula";
 pub const STRIKETHROUGH_ATTR: &str = "strikethrough";
+pub const UNDERLINE_ATTR: &str = "underline";
+pub const SUPERSCRIPT_ATTR: &str = "superscript";
+pub const SUBSCRIPT_ATTR: &str = "subscript";
+pub const FONT_COLOR_ATTR: &str = "font_color";
+pub const BG_COLOR_ATTR: &str = "bg_color";
 pub const INLINE_MATH_SYMBOL: &str = "$";
 
+// Mention Keys
+pub const MENTION_ATTR: &str = "mention";
+pub const MENTION_SYMBOL: &str = "$";
+pub const MENTION_TYPE_FIELD: &str = "type";
+pub const MENTION_TYPE_PAGE: &str = "page";
+pub const MENTION_PAGE_ID_FIELD: &str = "page_id";
+pub const MENTION_BLOCK_ID_FIELD: &str = "block_id";
+pub const MENTION_TYPE_DATE: &str = "date";
+pub const MENTION_DATE_FIELD: &str = "date";
+pub const MENTION_INCLUDE_TIME_FIELD: &str = "include_time";
+
+// Citation Keys
+pub const CITATION_ATTR: &str = "citation";
+pub const CITATION_KEY_FIELD: &str = "key";
+pub const CITATION_LOCATOR_FIELD: &str = "locator";
+pub const BIBLIOGRAPHY_TITLE_FIELD: &str = "title";
+
 // Table Keys
 pub const ROWS_LEN_FIELD: &str = "rowsLen";
 pub const COLS_LEN_FIELD: &str = "colsLen";
@@ -42,6 +79,11 @@ pub const ROW_DEFAULT_HEIGHT_FIELD: &str = "rowDefaultHeight";
 pub const ROW_POSITION_FIELD: &str = "rowPosition";
 pub const COL_POSITION_FIELD: &str = "colPosition";
 
+// Simple Column Keys
+/// The name of the html comment giving the width ratio of a column in the header row of a
+/// columns table, e.g. `<!-- column-width-ratio: 0.25 -->`.
+pub const COLUMN_WIDTH_RATIO_COMMENT: &str = "column-width-ratio";
+
 // List Keys
 pub const CHECKED_FIELD: &str = "checked";
 pub const START_NUMBER_FIELD: &str = "number";
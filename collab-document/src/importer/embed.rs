@@ -0,0 +1,315 @@
+use crate::blocks::{BlockType, DocumentData};
+use crate::error::DocumentError;
+use crate::importer::define::{
+  EMBED_DESCRIPTION_FIELD, EMBED_PROVIDER_FIELD, EMBED_THUMBNAIL_FIELD, EMBED_TITLE_FIELD,
+  URL_FIELD,
+};
+use crate::importer::html::decode_entities;
+use crate::importer::media::{MediaLink, MediaProvider, single_link_from_delta, split_http_url};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::warn;
+
+/// The services whose links are imported as embed blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EmbedProvider {
+  YouTube,
+  Figma,
+  Twitter,
+  GoogleMaps,
+}
+
+impl EmbedProvider {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      EmbedProvider::YouTube => "youtube",
+      EmbedProvider::Figma => "figma",
+      EmbedProvider::Twitter => "twitter",
+      EmbedProvider::GoogleMaps => "google_maps",
+    }
+  }
+
+  pub fn from_name(name: &str) -> Option<Self> {
+    match name {
+      "youtube" => Some(EmbedProvider::YouTube),
+      "figma" => Some(EmbedProvider::Figma),
+      "twitter" => Some(EmbedProvider::Twitter),
+      "google_maps" => Some(EmbedProvider::GoogleMaps),
+      _ => None,
+    }
+  }
+
+  /// Recognize the links to a YouTube video, a Figma file, a tweet or a Google Maps place.
+  pub fn from_url(url: &str) -> Option<Self> {
+    let url = url.trim();
+    if let Some(MediaLink {
+      provider: MediaProvider::YouTube { .. },
+      ..
+    }) = MediaLink::from_url(url)
+    {
+      return Some(EmbedProvider::YouTube);
+    }
+
+    let (host, path, _) = split_http_url(url)?;
+    let first_segment = path.split('/').next().unwrap_or_default();
+    match host.as_str() {
+      "figma.com" if matches!(first_segment, "file" | "design" | "proto" | "board") => {
+        Some(EmbedProvider::Figma)
+      },
+      "twitter.com" | "mobile.twitter.com" | "x.com" if path.contains("/status/") => {
+        Some(EmbedProvider::Twitter)
+      },
+      "maps.google.com" | "maps.app.goo.gl" => Some(EmbedProvider::GoogleMaps),
+      "goo.gl" if first_segment == "maps" => Some(EmbedProvider::GoogleMaps),
+      _ if host.starts_with("google.") && first_segment == "maps" => {
+        Some(EmbedProvider::GoogleMaps)
+      },
+      _ => None,
+    }
+  }
+
+  /// The oEmbed endpoint describing the url. None for Google Maps, which only describes its
+  /// pages with OpenGraph tags, see [EmbedMetadata::from_open_graph].
+  pub fn oembed_url(&self, url: &str) -> Option<String> {
+    let endpoint = match self {
+      EmbedProvider::YouTube => "https://www.youtube.com/oembed",
+      EmbedProvider::Figma => "https://www.figma.com/api/oembed",
+      EmbedProvider::Twitter => "https://publish.twitter.com/oembed",
+      EmbedProvider::GoogleMaps => return None,
+    };
+    Some(format!(
+      "{}?format=json&url={}",
+      endpoint,
+      encode_query_value(url)
+    ))
+  }
+}
+
+/// The description of an embedded url, stored in the data of its embed block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EmbedMetadata {
+  pub title: Option<String>,
+  pub description: Option<String>,
+  pub thumbnail_url: Option<String>,
+}
+
+impl EmbedMetadata {
+  /// Read an oEmbed response. oEmbed has no description, the author is used instead.
+  pub fn from_oembed(json: &str) -> Result<Self, DocumentError> {
+    let value: Value = serde_json::from_str(json).map_err(|_| DocumentError::ConvertDataError)?;
+    let field = |key: &str| {
+      value
+        .get(key)
+        .and_then(Value::as_str)
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+    };
+    Ok(Self {
+      title: field("title"),
+      description: field("author_name"),
+      thumbnail_url: field("thumbnail_url"),
+    })
+  }
+
+  /// Read the `og:title`, `og:description` and `og:image` meta tags of an html page. The title
+  /// of the page is used when it has no `og:title`.
+  pub fn from_open_graph(html: &str) -> Self {
+    let mut metadata = Self::default();
+    let lowercase = html.to_ascii_lowercase();
+    let mut offset = 0;
+    while let Some(start) = lowercase[offset..].find("<meta") {
+      let start = offset + start;
+      let end = lowercase[start..]
+        .find('>')
+        .map(|end| start + end)
+        .unwrap_or(html.len());
+      let tag = &html[start..end];
+      offset = end;
+
+      let property = tag_attribute(tag, "property").or_else(|| tag_attribute(tag, "name"));
+      let Some(content) = tag_attribute(tag, "content").filter(|content| !content.is_empty())
+      else {
+        continue;
+      };
+      let field = match property.as_deref() {
+        Some("og:title") => &mut metadata.title,
+        Some("og:description") => &mut metadata.description,
+        Some("og:image") => &mut metadata.thumbnail_url,
+        _ => continue,
+      };
+      field.get_or_insert(content);
+    }
+
+    if metadata.title.is_none() {
+      if let Some(start) = lowercase.find("<title>") {
+        let start = start + "<title>".len();
+        let end = lowercase[start..]
+          .find("</title>")
+          .map(|end| start + end)
+          .unwrap_or(html.len());
+        let title = decode_entities(html[start..end].trim());
+        metadata.title = Some(title).filter(|title| !title.is_empty());
+      }
+    }
+    metadata
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.title.is_none() && self.description.is_none() && self.thumbnail_url.is_none()
+  }
+
+  /// Write the metadata into the block data, the missing fields are left untouched.
+  pub fn write_to_block_data(&self, data: &mut HashMap<String, Value>) {
+    for (key, value) in [
+      (EMBED_TITLE_FIELD, &self.title),
+      (EMBED_DESCRIPTION_FIELD, &self.description),
+      (EMBED_THUMBNAIL_FIELD, &self.thumbnail_url),
+    ] {
+      if let Some(value) = value {
+        data.insert(key.to_string(), Value::String(value.clone()));
+      }
+    }
+  }
+}
+
+/// Resolve the metadata of an embedded url, e.g. by fetching its oEmbed endpoint, see
+/// [EmbedProvider::oembed_url], or the OpenGraph tags of its page, see
+/// [EmbedMetadata::from_open_graph].
+#[async_trait]
+pub trait EmbedResolver: Send + Sync {
+  /// Return None when the url has no metadata.
+  async fn resolve(
+    &self,
+    url: &str,
+    provider: EmbedProvider,
+  ) -> Result<Option<EmbedMetadata>, DocumentError>;
+}
+
+/// Fill the title, description and thumbnail of the embed blocks of the document with the
+/// metadata returned by the resolver. The blocks the resolver fails on keep their url.
+///
+/// Return the number of embed blocks whose metadata was resolved.
+pub async fn resolve_embeds(
+  document_data: &mut DocumentData,
+  resolver: &dyn EmbedResolver,
+) -> usize {
+  let mut embeds = document_data
+    .blocks
+    .values()
+    .filter(|block| block.ty == BlockType::Embed.as_str())
+    .filter_map(|block| {
+      let url = block.data.get(URL_FIELD)?.as_str()?.to_string();
+      let provider = block
+        .data
+        .get(EMBED_PROVIDER_FIELD)
+        .and_then(Value::as_str)
+        .and_then(EmbedProvider::from_name)
+        .or_else(|| EmbedProvider::from_url(&url))?;
+      Some((block.id.clone(), url, provider))
+    })
+    .collect::<Vec<_>>();
+  embeds.sort_by(|(left, ..), (right, ..)| left.cmp(right));
+
+  let mut resolved = 0;
+  for (block_id, url, provider) in embeds {
+    let metadata = match resolver.resolve(&url, provider).await {
+      Ok(Some(metadata)) if !metadata.is_empty() => metadata,
+      Ok(_) => continue,
+      Err(err) => {
+        warn!("failed to resolve the embedded url {}: {}", url, err);
+        continue;
+      },
+    };
+    if let Some(block) = document_data.blocks.get_mut(&block_id) {
+      metadata.write_to_block_data(&mut block.data);
+      resolved += 1;
+    }
+  }
+  resolved
+}
+
+/// Convert the paragraphs that only contain a link to an embeddable page, see
+/// [EmbedProvider::from_url], to embed blocks. The text of the link is kept as the title of the
+/// block when it's not the url.
+pub(crate) fn convert_embed_links(document_data: &mut DocumentData) {
+  let Some(text_map) = document_data.meta.text_map.as_mut() else {
+    return;
+  };
+  for block in document_data.blocks.values_mut() {
+    if block.ty != BlockType::Paragraph.as_str() {
+      continue;
+    }
+    let Some((name, url)) = block
+      .external_id
+      .as_ref()
+      .and_then(|text_id| text_map.get(text_id))
+      .and_then(|delta| single_link_from_delta(delta))
+    else {
+      continue;
+    };
+    let Some(provider) = EmbedProvider::from_url(&url) else {
+      continue;
+    };
+
+    if let Some(text_id) = block.external_id.take() {
+      text_map.remove(&text_id);
+    }
+    block.external_type = None;
+    block.ty = BlockType::Embed.to_string();
+    block
+      .data
+      .insert(URL_FIELD.to_string(), Value::String(url.clone()));
+    block.data.insert(
+      EMBED_PROVIDER_FIELD.to_string(),
+      Value::String(provider.as_str().to_string()),
+    );
+    if name != url {
+      block
+        .data
+        .insert(EMBED_TITLE_FIELD.to_string(), Value::String(name));
+    }
+  }
+}
+
+/// The value of the attribute of an html tag, unescaped.
+fn tag_attribute(tag: &str, name: &str) -> Option<String> {
+  let lowercase = tag.to_ascii_lowercase();
+  let mut offset = 0;
+  while let Some(position) = lowercase[offset..].find(name) {
+    let start = offset + position;
+    offset = start + name.len();
+    let preceded_by_space = lowercase[..start]
+      .chars()
+      .next_back()
+      .is_some_and(char::is_whitespace);
+    let rest = lowercase[offset..].trim_start();
+    if !preceded_by_space || !rest.starts_with('=') {
+      continue;
+    }
+    let value_start = tag.len() - rest[1..].trim_start().len();
+    let value = &tag[value_start..];
+    let value = match value.chars().next() {
+      Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or_default(),
+      _ => value
+        .split(|c: char| c.is_whitespace() || c == '/')
+        .next()
+        .unwrap_or_default(),
+    };
+    return Some(decode_entities(value.trim()));
+  }
+  None
+}
+
+/// Percent-encode a url passed as a query value.
+fn encode_query_value(value: &str) -> String {
+  let mut encoded = String::with_capacity(value.len());
+  for byte in value.bytes() {
+    if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+      encoded.push(byte as char);
+    } else {
+      encoded.push_str(&format!("%{:02X}", byte));
+    }
+  }
+  encoded
+}
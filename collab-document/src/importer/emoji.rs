@@ -0,0 +1,182 @@
+use markdown::mdast;
+use std::collections::HashMap;
+
+/// A table of emoji shortcodes, e.g. `rocket` for 🚀, used by
+/// [crate::importer::md_importer::MDImporter::with_emoji_shortcodes].
+pub trait EmojiShortcodeTable: Send + Sync {
+  /// Return the emoji of the shortcode, the shortcode is given without the surrounding colons.
+  fn emoji(&self, shortcode: &str) -> Option<&str>;
+}
+
+impl EmojiShortcodeTable for HashMap<String, String> {
+  fn emoji(&self, shortcode: &str) -> Option<&str> {
+    self.get(shortcode).map(String::as_str)
+  }
+}
+
+/// The most common shortcodes used by GitHub and Notion.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultEmojiShortcodes;
+
+impl EmojiShortcodeTable for DefaultEmojiShortcodes {
+  fn emoji(&self, shortcode: &str) -> Option<&str> {
+    DEFAULT_SHORTCODES
+      .iter()
+      .find(|(code, _)| *code == shortcode)
+      .map(|(_, emoji)| *emoji)
+  }
+}
+
+/// Replace the shortcodes in the text nodes with their emoji. Code is left untouched since it's
+/// not parsed as text nodes.
+pub(crate) fn replace_emoji_shortcodes(node: &mut mdast::Node, table: &dyn EmojiShortcodeTable) {
+  if let mdast::Node::Text(text) = node {
+    if let Some(replaced) = replace_shortcodes(&text.value, table) {
+      text.value = replaced;
+    }
+    return;
+  }
+  if let Some(children) = node.children_mut() {
+    for child in children.iter_mut() {
+      replace_emoji_shortcodes(child, table);
+    }
+  }
+}
+
+/// Return None if the text has no known shortcode.
+fn replace_shortcodes(text: &str, table: &dyn EmojiShortcodeTable) -> Option<String> {
+  if !text.contains(':') {
+    return None;
+  }
+  let mut output = String::with_capacity(text.len());
+  let mut replaced = false;
+  let mut rest = text;
+  while let Some(start) = rest.find(':') {
+    output.push_str(&rest[..start]);
+    let after_colon = &rest[start + 1..];
+    let code_len = after_colon
+      .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-')))
+      .unwrap_or(after_colon.len());
+    let emoji = (code_len > 0 && after_colon[code_len..].starts_with(':'))
+      .then(|| table.emoji(&after_colon[..code_len]))
+      .flatten();
+    match emoji {
+      Some(emoji) => {
+        output.push_str(emoji);
+        rest = &after_colon[code_len + 1..];
+        replaced = true;
+      },
+      None => {
+        // The closing colon may open the next shortcode, e.g. `at 10:30:rocket:`.
+        output.push(':');
+        rest = after_colon;
+      },
+    }
+  }
+  output.push_str(rest);
+  replaced.then_some(output)
+}
+
+const DEFAULT_SHORTCODES: &[(&str, &str)] = &[
+  ("+1", "👍"),
+  ("-1", "👎"),
+  ("100", "💯"),
+  ("alarm_clock", "⏰"),
+  ("angry", "😠"),
+  ("arrow_down", "⬇️"),
+  ("arrow_left", "⬅️"),
+  ("arrow_right", "➡️"),
+  ("arrow_up", "⬆️"),
+  ("art", "🎨"),
+  ("beer", "🍺"),
+  ("bell", "🔔"),
+  ("blue_heart", "💙"),
+  ("book", "📖"),
+  ("books", "📚"),
+  ("bookmark", "🔖"),
+  ("boom", "💥"),
+  ("bug", "🐛"),
+  ("bulb", "💡"),
+  ("calendar", "📆"),
+  ("camera", "📷"),
+  ("chart_with_upwards_trend", "📈"),
+  ("check", "✔️"),
+  ("clap", "👏"),
+  ("clipboard", "📋"),
+  ("coffee", "☕"),
+  ("computer", "💻"),
+  ("confused", "😕"),
+  ("construction", "🚧"),
+  ("cry", "😢"),
+  ("dart", "🎯"),
+  ("date", "📅"),
+  ("dog", "🐶"),
+  ("email", "📧"),
+  ("exclamation", "❗"),
+  ("eyes", "👀"),
+  ("fire", "🔥"),
+  ("flag", "🚩"),
+  ("gear", "⚙️"),
+  ("gift", "🎁"),
+  ("globe_with_meridians", "🌐"),
+  ("green_heart", "💚"),
+  ("grin", "😁"),
+  ("grinning", "😀"),
+  ("hammer", "🔨"),
+  ("heart", "❤️"),
+  ("heart_eyes", "😍"),
+  ("heavy_check_mark", "✔️"),
+  ("hourglass", "⌛"),
+  ("house", "🏠"),
+  ("hugs", "🤗"),
+  ("information_source", "ℹ️"),
+  ("joy", "😂"),
+  ("key", "🔑"),
+  ("laughing", "😆"),
+  ("link", "🔗"),
+  ("lock", "🔒"),
+  ("mag", "🔍"),
+  ("memo", "📝"),
+  ("moneybag", "💰"),
+  ("muscle", "💪"),
+  ("no_entry", "⛔"),
+  ("ok_hand", "👌"),
+  ("package", "📦"),
+  ("paperclip", "📎"),
+  ("partying_face", "🥳"),
+  ("pencil", "📝"),
+  ("pencil2", "✏️"),
+  ("point_right", "👉"),
+  ("pray", "🙏"),
+  ("pushpin", "📌"),
+  ("question", "❓"),
+  ("raised_hands", "🙌"),
+  ("recycle", "♻️"),
+  ("red_circle", "🔴"),
+  ("relaxed", "☺️"),
+  ("rocket", "🚀"),
+  ("rotating_light", "🚨"),
+  ("scream", "😱"),
+  ("see_no_evil", "🙈"),
+  ("shield", "🛡️"),
+  ("slightly_smiling_face", "🙂"),
+  ("smile", "😄"),
+  ("smiley", "😃"),
+  ("sob", "😭"),
+  ("sparkles", "✨"),
+  ("star", "⭐"),
+  ("sunglasses", "😎"),
+  ("sunny", "☀️"),
+  ("tada", "🎉"),
+  ("thinking", "🤔"),
+  ("thumbsdown", "👎"),
+  ("thumbsup", "👍"),
+  ("trophy", "🏆"),
+  ("warning", "⚠️"),
+  ("wave", "👋"),
+  ("white_check_mark", "✅"),
+  ("wink", "😉"),
+  ("wrench", "🔧"),
+  ("x", "❌"),
+  ("zap", "⚡"),
+];
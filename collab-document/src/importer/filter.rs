@@ -0,0 +1,254 @@
+use crate::blocks::{Block, DocumentData};
+use crate::importer::define::{HREF_ATTR, URL_FIELD};
+use crate::importer::fragment::DocumentFragment;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// The url schemes that run code when the link is opened.
+const UNSAFE_URL_SCHEMES: [&str; 3] = ["javascript:", "vbscript:", "data:text/html"];
+
+/// A filter of the imported content, e.g. to strip the unsafe links, redact the emails or limit
+/// the length of the texts of untrusted content, see
+/// [crate::importer::md_importer::MDImporter::with_content_filter].
+///
+/// The filters run once the blocks are converted, on each block then on each text of its delta.
+/// The pasted fragments are filtered too.
+pub trait ContentFilter: Send + Sync {
+  /// Filter a block, its data can be rewritten. Return false to drop the block with its children.
+  /// The page block of a document is not filtered.
+  fn filter_block(&self, block: &mut Block) -> bool {
+    let _ = block;
+    true
+  }
+
+  /// Filter a text of the delta of the block, the text and its attributes, e.g. the `href` of a
+  /// link, can be rewritten. A text left empty is removed from the delta.
+  fn filter_text(&self, block: &Block, text: &mut String, attributes: &mut Map<String, Value>) {
+    let _ = (block, text, attributes);
+  }
+}
+
+/// Remove the links running code when they are opened, e.g. `javascript:` urls, and the html
+/// script elements left in the texts.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnsafeContentFilter;
+
+impl ContentFilter for UnsafeContentFilter {
+  fn filter_block(&self, block: &mut Block) -> bool {
+    let is_unsafe = block
+      .data
+      .get(URL_FIELD)
+      .and_then(Value::as_str)
+      .is_some_and(is_unsafe_url);
+    if is_unsafe {
+      block.data.remove(URL_FIELD);
+    }
+    true
+  }
+
+  fn filter_text(&self, _block: &Block, text: &mut String, attributes: &mut Map<String, Value>) {
+    let is_unsafe = attributes
+      .get(HREF_ATTR)
+      .and_then(Value::as_str)
+      .is_some_and(is_unsafe_url);
+    if is_unsafe {
+      attributes.remove(HREF_ATTR);
+    }
+    if let Some(stripped) = strip_script_elements(text) {
+      *text = stripped;
+    }
+  }
+}
+
+/// Truncate the texts longer than the given number of characters.
+#[derive(Debug, Clone, Copy)]
+pub struct TextLengthLimit {
+  pub max_chars: usize,
+}
+
+impl ContentFilter for TextLengthLimit {
+  fn filter_text(&self, _block: &Block, text: &mut String, _attributes: &mut Map<String, Value>) {
+    if let Some((index, _)) = text.char_indices().nth(self.max_chars) {
+      text.truncate(index);
+    }
+  }
+}
+
+fn is_unsafe_url(url: &str) -> bool {
+  let url = url
+    .trim()
+    .chars()
+    .filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control())
+    .collect::<String>()
+    .to_lowercase();
+  UNSAFE_URL_SCHEMES
+    .iter()
+    .any(|scheme| url.starts_with(scheme))
+}
+
+/// Remove the `<script>` elements of the text, None if it has none.
+fn strip_script_elements(text: &str) -> Option<String> {
+  let lowercase = text.to_ascii_lowercase();
+  if !lowercase.contains("<script") {
+    return None;
+  }
+  let mut output = String::with_capacity(text.len());
+  let mut rest = 0;
+  while let Some(offset) = lowercase[rest..].find("<script") {
+    let start = rest + offset;
+    output.push_str(&text[rest..start]);
+    rest = match lowercase[start..].find("</script>") {
+      Some(end) => start + end + "</script>".len(),
+      None => text.len(),
+    };
+  }
+  output.push_str(&text[rest..]);
+  Some(output)
+}
+
+/// Run the filters on the blocks of the document, but its page block.
+pub(crate) fn filter_document_data(data: &mut DocumentData, filters: &[Arc<dyn ContentFilter>]) {
+  if filters.is_empty() {
+    return;
+  }
+  let mut text_map = data.meta.text_map.take().unwrap_or_default();
+  let mut dropped = vec![];
+  let mut stack = data
+    .blocks
+    .get(&data.page_id)
+    .and_then(|page| data.meta.children_map.get(&page.children))
+    .cloned()
+    .unwrap_or_default();
+  while let Some(id) = stack.pop() {
+    let Some(block) = data.blocks.get_mut(&id) else {
+      continue;
+    };
+    let text = block
+      .external_id
+      .as_ref()
+      .and_then(|external_id| text_map.get_mut(external_id));
+    if !filter_block(filters, block, text) {
+      dropped.push(id);
+      continue;
+    }
+    if let Some(children) = data.meta.children_map.get(&block.children) {
+      stack.extend(children.iter().cloned());
+    }
+  }
+
+  for id in dropped {
+    let Some(block) = data.blocks.get(&id) else {
+      continue;
+    };
+    if let Some(siblings) = data
+      .blocks
+      .get(&block.parent)
+      .and_then(|parent| data.meta.children_map.get_mut(&parent.children))
+    {
+      siblings.retain(|sibling| sibling != &id);
+    }
+    remove_block_tree(data, &mut text_map, &id);
+  }
+  data.meta.text_map = Some(text_map);
+}
+
+fn remove_block_tree(data: &mut DocumentData, text_map: &mut HashMap<String, String>, id: &str) {
+  let Some(block) = data.blocks.remove(id) else {
+    return;
+  };
+  if let Some(external_id) = &block.external_id {
+    text_map.remove(external_id);
+  }
+  for child in data
+    .meta
+    .children_map
+    .remove(&block.children)
+    .unwrap_or_default()
+  {
+    remove_block_tree(data, text_map, &child);
+  }
+}
+
+impl DocumentFragment {
+  /// Run the filters on the blocks of the fragment, see [ContentFilter].
+  pub fn apply_content_filters(&mut self, filters: &[Arc<dyn ContentFilter>]) {
+    if filters.is_empty() {
+      return;
+    }
+    // The blocks come after their parent, a dropped parent drops its children.
+    let mut dropped = HashSet::new();
+    for block in self.blocks.iter_mut() {
+      let text = block
+        .external_id
+        .as_ref()
+        .and_then(|external_id| self.text_map.get_mut(external_id));
+      if dropped.contains(&block.parent) || !filter_block(filters, block, text) {
+        dropped.insert(block.id.clone());
+      }
+    }
+    if dropped.is_empty() {
+      return;
+    }
+    for block in self
+      .blocks
+      .iter()
+      .filter(|block| dropped.contains(&block.id))
+    {
+      if let Some(external_id) = &block.external_id {
+        self.text_map.remove(external_id);
+      }
+    }
+    self.blocks.retain(|block| !dropped.contains(&block.id));
+    self.top_level_ids.retain(|id| !dropped.contains(id));
+  }
+}
+
+/// Run the filters on the block then on its delta. Return false if the block is dropped.
+fn filter_block(
+  filters: &[Arc<dyn ContentFilter>],
+  block: &mut Block,
+  text: Option<&mut String>,
+) -> bool {
+  if !filters.iter().all(|filter| filter.filter_block(block)) {
+    return false;
+  }
+  let Some(text) = text else {
+    return true;
+  };
+  let Ok(ops) = serde_json::from_str::<Vec<Value>>(text) else {
+    return true;
+  };
+  let block: &Block = block;
+  let ops = ops
+    .into_iter()
+    .filter_map(|mut op| {
+      let Some(Value::String(insert)) = op.get_mut("insert") else {
+        return Some(op);
+      };
+      let mut insert = std::mem::take(insert);
+      let mut attributes = match op.get("attributes") {
+        Some(Value::Object(attributes)) => attributes.clone(),
+        _ => Map::new(),
+      };
+      for filter in filters {
+        filter.filter_text(block, &mut insert, &mut attributes);
+      }
+      if insert.is_empty() {
+        return None;
+      }
+      op["insert"] = Value::String(insert);
+      let op_map = op.as_object_mut()?;
+      if attributes.is_empty() {
+        op_map.remove("attributes");
+      } else {
+        op_map.insert("attributes".to_string(), Value::Object(attributes));
+      }
+      Some(op)
+    })
+    .collect::<Vec<_>>();
+  if let Ok(filtered) = serde_json::to_string(&ops) {
+    *text = filtered;
+  }
+  true
+}
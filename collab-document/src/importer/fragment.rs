@@ -0,0 +1,310 @@
+use crate::blocks::{Block, BlockType, DocumentData, SimpleTableData};
+use crate::document_data::generate_id;
+use crate::importer::define::*;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+/// The format of pasted content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentType {
+  Markdown,
+  Html,
+  PlainText,
+}
+
+/// Blocks to insert at a cursor position, see [crate::importer::md_importer::MDImporter::import_fragment].
+///
+/// Unlike [DocumentData], a fragment has no page block. Its top-level blocks are children of
+/// `parent_id`, the block the fragment is pasted into.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocumentFragment {
+  /// The block the top-level blocks are inserted into.
+  pub parent_id: String,
+  /// The blocks in insertion order: a block comes after its parent and after its previous
+  /// sibling.
+  pub blocks: Vec<Block>,
+  /// The ids of the top-level blocks, in order.
+  pub top_level_ids: Vec<String>,
+  /// The text delta json strings keyed by the external id of the blocks.
+  pub text_map: HashMap<String, String>,
+}
+
+impl DocumentFragment {
+  pub fn is_empty(&self) -> bool {
+    self.blocks.is_empty()
+  }
+
+  /// Detach the content of the page of the document data, the children of the page become the
+  /// top-level blocks of the fragment.
+  pub fn from_document_data(mut data: DocumentData, parent_id: &str) -> Self {
+    let mut fragment = DocumentFragment {
+      parent_id: parent_id.to_string(),
+      ..Default::default()
+    };
+    let Some(page) = data.blocks.remove(&data.page_id) else {
+      return fragment;
+    };
+    let mut text_map = data.meta.text_map.take().unwrap_or_default();
+    let top_level_ids = data
+      .meta
+      .children_map
+      .get(&page.children)
+      .cloned()
+      .unwrap_or_default();
+
+    // Depth first, so each block comes after its parent and its previous siblings.
+    let mut stack = top_level_ids.iter().rev().cloned().collect::<Vec<_>>();
+    while let Some(id) = stack.pop() {
+      let Some(mut block) = data.blocks.remove(&id) else {
+        continue;
+      };
+      if block.parent == page.id {
+        block.parent = parent_id.to_string();
+      }
+      if let Some(children) = data.meta.children_map.get(&block.children) {
+        stack.extend(children.iter().rev().cloned());
+      }
+      if let Some(delta) = block
+        .external_id
+        .as_ref()
+        .and_then(|external_id| text_map.remove(external_id))
+      {
+        fragment
+          .text_map
+          .insert(block.external_id.clone().unwrap_or_default(), delta);
+      }
+      fragment.blocks.push(block);
+    }
+    fragment.top_level_ids = top_level_ids;
+    fragment
+  }
+
+  /// Each line of the text becomes a paragraph, the text is not parsed.
+  pub(crate) fn from_plain_text(text: &str, parent_id: &str) -> Self {
+    let mut fragment = DocumentFragment {
+      parent_id: parent_id.to_string(),
+      ..Default::default()
+    };
+    let text = text.replace("\r\n", "\n");
+    for line in text.trim_matches('\n').split('\n') {
+      let id = fragment.push_text_block(parent_id, BlockType::Paragraph, HashMap::new(), line);
+      fragment.top_level_ids.push(id);
+    }
+    fragment
+  }
+
+  /// Convert the table to a simple table. The first row is rendered as a header if it's detected
+  /// as one, and the cells of numeric columns are aligned to the right.
+  pub fn from_tsv_table(table: &TsvTable, parent_id: &str) -> Self {
+    let mut fragment = DocumentFragment {
+      parent_id: parent_id.to_string(),
+      ..Default::default()
+    };
+    let table_data = SimpleTableData {
+      enable_header_row: table.has_header_row,
+      ..Default::default()
+    };
+    let table_id = generate_id();
+    fragment.push_block(
+      &table_id,
+      BlockType::SimpleTable,
+      table_data.into_block_data(),
+      parent_id,
+    );
+    fragment.top_level_ids.push(table_id.clone());
+
+    for (row_index, row) in table.rows.iter().enumerate() {
+      let row_id = generate_id();
+      fragment.push_block(
+        &row_id,
+        BlockType::SimpleTableRow,
+        HashMap::new(),
+        &table_id,
+      );
+      for col_index in 0..table.column_count() {
+        let mut cell_data = HashMap::new();
+        cell_data.insert(ROW_POSITION_FIELD.to_string(), json!(row_index));
+        cell_data.insert(COL_POSITION_FIELD.to_string(), json!(col_index));
+        if table.is_numeric_column(col_index) {
+          cell_data.insert(ALIGN_FIELD.to_string(), json!(ALIGN_RIGHT));
+        }
+        let cell_id = generate_id();
+        fragment.push_block(&cell_id, BlockType::SimpleTableCell, cell_data, &row_id);
+
+        // Every cell holds one paragraph.
+        let text = row.get(col_index).map(String::as_str).unwrap_or_default();
+        fragment.push_text_block(&cell_id, BlockType::Paragraph, HashMap::new(), text);
+      }
+    }
+    fragment
+  }
+
+  fn push_block(
+    &mut self,
+    id: &str,
+    block_type: BlockType,
+    data: HashMap<String, Value>,
+    parent_id: &str,
+  ) {
+    self.blocks.push(Block {
+      id: id.to_string(),
+      ty: block_type.to_string(),
+      data,
+      parent: parent_id.to_string(),
+      children: id.to_string(),
+      external_id: None,
+      external_type: None,
+    });
+  }
+
+  fn push_text_block(
+    &mut self,
+    parent_id: &str,
+    block_type: BlockType,
+    data: HashMap<String, Value>,
+    text: &str,
+  ) -> String {
+    let id = generate_id();
+    self.push_block(&id, block_type, data, parent_id);
+    if let Some(block) = self.blocks.last_mut() {
+      block.external_id = Some(id.clone());
+      block.external_type = Some("text".to_string());
+    }
+    let delta = if text.is_empty() {
+      json!([])
+    } else {
+      json!([{ "insert": text }])
+    };
+    self.text_map.insert(id.clone(), delta.to_string());
+    id
+  }
+}
+
+/// Tab-separated text, as copied from a spreadsheet like Excel, Numbers or Google Sheets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TsvTable {
+  /// The cells of each row. The rows may have different lengths.
+  pub rows: Vec<Vec<String>>,
+  /// Whether the first row looks like a header: none of its cells is empty or numeric, and at
+  /// least one column is numeric below it.
+  pub has_header_row: bool,
+  /// Whether each column only contains numbers, the header row excluded. Empty cells are ignored.
+  pub numeric_columns: Vec<bool>,
+}
+
+impl TsvTable {
+  /// Detect tab-separated text. Return None unless the text has at least two rows and every row
+  /// contains a tab, so a single line or prose with a stray tab is kept as paragraphs.
+  ///
+  /// Cells wrapped in double quotes may contain tabs and line breaks, and `""` inside them is a
+  /// literal quote, which is how spreadsheets copy multi-line cells.
+  pub fn detect(text: &str) -> Option<Self> {
+    let text = text.replace("\r\n", "\n");
+    let text = text.trim_end_matches('\n');
+    if !text.contains('\t') {
+      return None;
+    }
+
+    let rows = parse_tsv_rows(text);
+    let is_table = rows.len() >= 2 && rows.iter().all(|row| row.len() >= 2);
+    if !is_table {
+      return None;
+    }
+
+    let column_count = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let body_numeric_columns = numeric_columns(&rows[1..], column_count);
+    let has_header_row = body_numeric_columns.iter().any(|numeric| *numeric)
+      && rows[0]
+        .iter()
+        .all(|cell| !cell.trim().is_empty() && !is_numeric(cell));
+    let numeric_columns = if has_header_row {
+      body_numeric_columns
+    } else {
+      numeric_columns(&rows, column_count)
+    };
+    Some(Self {
+      rows,
+      has_header_row,
+      numeric_columns,
+    })
+  }
+
+  pub fn column_count(&self) -> usize {
+    self.numeric_columns.len()
+  }
+
+  pub fn is_numeric_column(&self, column: usize) -> bool {
+    self.numeric_columns.get(column).copied().unwrap_or(false)
+  }
+}
+
+fn parse_tsv_rows(text: &str) -> Vec<Vec<String>> {
+  let mut rows = vec![];
+  let mut row = vec![];
+  let mut cell = String::new();
+  let mut chars = text.chars().peekable();
+  let mut at_cell_start = true;
+  let mut in_quotes = false;
+  while let Some(c) = chars.next() {
+    if in_quotes {
+      if c == '"' {
+        if chars.peek() == Some(&'"') {
+          chars.next();
+          cell.push('"');
+        } else {
+          in_quotes = false;
+        }
+      } else {
+        cell.push(c);
+      }
+      continue;
+    }
+    match c {
+      '"' if at_cell_start => {
+        in_quotes = true;
+        at_cell_start = false;
+      },
+      '\t' => {
+        row.push(std::mem::take(&mut cell));
+        at_cell_start = true;
+      },
+      '\n' => {
+        row.push(std::mem::take(&mut cell));
+        rows.push(std::mem::take(&mut row));
+        at_cell_start = true;
+      },
+      _ => {
+        cell.push(c);
+        at_cell_start = false;
+      },
+    }
+  }
+  row.push(cell);
+  rows.push(row);
+  rows
+}
+
+/// A column is numeric if it has at least one number and all its other cells are empty.
+fn numeric_columns(rows: &[Vec<String>], column_count: usize) -> Vec<bool> {
+  (0..column_count)
+    .map(|column| {
+      let mut cells = rows
+        .iter()
+        .filter_map(|row| row.get(column))
+        .filter(|cell| !cell.trim().is_empty())
+        .peekable();
+      cells.peek().is_some() && cells.all(|cell| is_numeric(cell))
+    })
+    .collect()
+}
+
+/// Return true for numbers as formatted by spreadsheets, e.g. `-1,234.5`, `$12`, `45%`.
+fn is_numeric(cell: &str) -> bool {
+  let cell = cell.trim();
+  let cell = cell
+    .strip_prefix(['$', '€', '£', '¥'])
+    .unwrap_or(cell)
+    .trim_end_matches('%')
+    .replace(',', "");
+  !cell.is_empty() && cell.parse::<f64>().is_ok_and(|number| number.is_finite())
+}
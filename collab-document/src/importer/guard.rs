@@ -0,0 +1,95 @@
+use crate::error::DocumentError;
+
+/// The number of characters inspected by [is_binary].
+const BINARY_SAMPLE_LEN: usize = 8192;
+
+/// The limits an input must stay under to be parsed, see
+/// [crate::importer::md_importer::MDImporter::with_input_limits].
+///
+/// The uploaded files are frequently mislabeled, e.g. an image or a database dump named `.md`.
+/// Checking them up front returns a specific error instead of keeping the parser busy for
+/// minutes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputLimits {
+  /// The maximum size of the input, in bytes.
+  pub max_input_bytes: usize,
+  /// The maximum size of a line, in bytes.
+  pub max_line_bytes: usize,
+  /// Whether the inputs that look like binary files are rejected.
+  pub reject_binary: bool,
+}
+
+impl Default for InputLimits {
+  fn default() -> Self {
+    Self {
+      max_input_bytes: 16 * 1024 * 1024,
+      max_line_bytes: 1024 * 1024,
+      reject_binary: true,
+    }
+  }
+}
+
+impl InputLimits {
+  /// No limit, every input is parsed.
+  pub fn unlimited() -> Self {
+    Self {
+      max_input_bytes: usize::MAX,
+      max_line_bytes: usize::MAX,
+      reject_binary: false,
+    }
+  }
+
+  /// Check the size of the input and whether it's binary, the length of its lines excluded.
+  /// Used for the inputs whose lines are not parsed as is, e.g. minified html.
+  pub fn check_size(&self, input: &str) -> Result<(), DocumentError> {
+    if input.len() > self.max_input_bytes {
+      return Err(DocumentError::InputTooLarge {
+        size: input.len(),
+        limit: self.max_input_bytes,
+      });
+    }
+    if self.reject_binary && is_binary(input) {
+      return Err(DocumentError::BinaryContent);
+    }
+    Ok(())
+  }
+
+  /// Check the size of the input, whether it's binary and the length of its lines.
+  pub fn check(&self, input: &str) -> Result<(), DocumentError> {
+    self.check_size(input)?;
+    if input.len() <= self.max_line_bytes {
+      return Ok(());
+    }
+    match input
+      .split('\n')
+      .enumerate()
+      .find(|(_, line)| line.len() > self.max_line_bytes)
+    {
+      Some((index, line)) => Err(DocumentError::LineTooLong {
+        line: index + 1,
+        len: line.len(),
+        limit: self.max_line_bytes,
+      }),
+      None => Ok(()),
+    }
+  }
+}
+
+/// Whether the text looks like the content of a binary file: the beginning of the text contains
+/// a NUL character, or more than a tenth of its characters are control characters or the
+/// replacement characters left by an invalid utf-8 decoding.
+pub fn is_binary(text: &str) -> bool {
+  let mut total = 0;
+  let mut suspicious = 0;
+  for c in text.chars().take(BINARY_SAMPLE_LEN) {
+    if c == '\0' {
+      return true;
+    }
+    total += 1;
+    let is_control = c.is_control() && !matches!(c, '\n' | '\r' | '\t' | '\x0c');
+    if is_control || c == char::REPLACEMENT_CHARACTER {
+      suspicious += 1;
+    }
+  }
+  suspicious * 10 > total
+}
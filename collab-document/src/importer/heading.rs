@@ -0,0 +1,56 @@
+/// The deepest heading level of a document.
+pub const MAX_HEADING_LEVEL: u8 = 6;
+
+/// How a heading deeper than [HeadingPolicy::max_level] is imported, e.g. a `####` heading when
+/// the headings stop at the third level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeadingOverflow {
+  /// Import it as a heading of the max level.
+  #[default]
+  Clamp,
+  /// Import it as a paragraph whose text is bold.
+  DemoteToBoldParagraph,
+  /// Import it as a heading keeping its level in the block data.
+  KeepLevel,
+}
+
+/// The heading levels supported by the importer and what happens to the deeper ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeadingPolicy {
+  /// The deepest heading level, from 1 to [MAX_HEADING_LEVEL].
+  pub max_level: u8,
+  pub overflow: HeadingOverflow,
+}
+
+impl Default for HeadingPolicy {
+  fn default() -> Self {
+    Self {
+      max_level: MAX_HEADING_LEVEL,
+      overflow: HeadingOverflow::default(),
+    }
+  }
+}
+
+impl HeadingPolicy {
+  pub fn new(max_level: u8, overflow: HeadingOverflow) -> Self {
+    Self {
+      max_level: max_level.clamp(1, MAX_HEADING_LEVEL),
+      overflow,
+    }
+  }
+
+  /// The level of the heading block of a markdown heading of the given depth, None if the
+  /// heading is imported as a paragraph.
+  pub fn heading_level(&self, depth: u8) -> Option<u8> {
+    let max_level = self.max_level.clamp(1, MAX_HEADING_LEVEL);
+    let level = depth.clamp(1, MAX_HEADING_LEVEL);
+    if level <= max_level {
+      return Some(level);
+    }
+    match self.overflow {
+      HeadingOverflow::Clamp => Some(max_level),
+      HeadingOverflow::DemoteToBoldParagraph => None,
+      HeadingOverflow::KeepLevel => Some(level),
+    }
+  }
+}
@@ -0,0 +1,797 @@
+//! A small HTML to markdown converter for pasted content.
+//!
+//! It only understands the subset of HTML that clipboards produce for rich text: headings,
+//! paragraphs, lists, quotes, asides, code, tables, links, images and inline formatting. Unknown
+//! elements are unwrapped, scripts and styles are dropped. The markdown is then imported with the
+//! markdown importer, so both content types produce the same blocks.
+
+use crate::importer::define::COLUMN_WIDTH_RATIO_COMMENT;
+use crate::importer::media::MediaLink;
+
+const VOID_ELEMENTS: [&str; 12] = [
+  "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "wbr",
+];
+const SKIPPED_ELEMENTS: [&str; 7] = [
+  "head", "script", "style", "title", "noscript", "template", "svg",
+];
+const BLOCK_ELEMENTS: [&str; 28] = [
+  "address",
+  "article",
+  "aside",
+  "blockquote",
+  "body",
+  "dd",
+  "details",
+  "div",
+  "dl",
+  "dt",
+  "figcaption",
+  "figure",
+  "footer",
+  "h1",
+  "h2",
+  "h3",
+  "h4",
+  "h5",
+  "h6",
+  "header",
+  "hr",
+  "html",
+  "main",
+  "nav",
+  "ol",
+  "p",
+  "pre",
+  "section",
+];
+
+#[derive(Debug)]
+enum HtmlNode {
+  Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<HtmlNode>,
+  },
+  Text(String),
+}
+
+impl HtmlNode {
+  fn attr(&self, key: &str) -> Option<&str> {
+    match self {
+      HtmlNode::Element { attributes, .. } => attributes
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.as_str()),
+      HtmlNode::Text(_) => None,
+    }
+  }
+
+  fn has_class(&self, class: &str) -> bool {
+    self
+      .attr("class")
+      .is_some_and(|classes| classes.split_whitespace().any(|c| c == class))
+  }
+
+  fn text_content(&self) -> String {
+    match self {
+      HtmlNode::Text(text) => text.clone(),
+      HtmlNode::Element { children, .. } => children.iter().map(|c| c.text_content()).collect(),
+    }
+  }
+}
+
+fn is_block_element(name: &str) -> bool {
+  BLOCK_ELEMENTS.contains(&name) || matches!(name, "ul" | "li" | "table" | "thead" | "tbody" | "tr")
+}
+
+/// Convert the html to markdown.
+pub(crate) fn html_to_markdown(html: &str) -> String {
+  let nodes = parse_html(html);
+  let blocks = render_blocks(&nodes);
+  blocks.join("\n\n")
+}
+
+fn parse_html(html: &str) -> Vec<HtmlNode> {
+  // The stack of open elements, the first entry is the synthetic root.
+  let mut stack: Vec<(String, Vec<(String, String)>, Vec<HtmlNode>)> =
+    vec![(String::new(), vec![], vec![])];
+  let mut rest = html;
+
+  while !rest.is_empty() {
+    if let Some(comment) = rest.strip_prefix("<!--") {
+      rest = comment.find("-->").map(|i| &comment[i + 3..]).unwrap_or("");
+      continue;
+    }
+    if rest.starts_with("<!") || rest.starts_with("<?") {
+      rest = rest.find('>').map(|i| &rest[i + 1..]).unwrap_or("");
+      continue;
+    }
+    if let Some(tag) = rest.strip_prefix("</") {
+      let end = tag.find('>').unwrap_or(tag.len());
+      let name = tag[..end].trim().to_lowercase();
+      rest = tag.get(end + 1..).unwrap_or("");
+      if stack.iter().skip(1).any(|(open, _, _)| *open == name) {
+        while let Some((open, _, _)) = stack.last() {
+          let is_match = *open == name;
+          close_element(&mut stack);
+          if is_match {
+            break;
+          }
+        }
+      }
+      continue;
+    }
+    if rest.starts_with('<') && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+      let (name, attributes, self_closing, remaining) = parse_start_tag(&rest[1..]);
+      rest = remaining;
+
+      if SKIPPED_ELEMENTS.contains(&name.as_str()) && !self_closing {
+        let closing = format!("</{}", name);
+        rest = find_ignore_case(rest, &closing)
+          .map(|i| {
+            let after = &rest[i..];
+            after.find('>').map(|j| &after[j + 1..]).unwrap_or("")
+          })
+          .unwrap_or("");
+        continue;
+      }
+
+      close_implied_elements(&mut stack, &name);
+      if self_closing || VOID_ELEMENTS.contains(&name.as_str()) {
+        if let Some((_, _, children)) = stack.last_mut() {
+          children.push(HtmlNode::Element {
+            name,
+            attributes,
+            children: vec![],
+          });
+        }
+      } else {
+        stack.push((name, attributes, vec![]));
+      }
+      continue;
+    }
+
+    // A `<` that doesn't start a tag is text.
+    let skip = usize::from(rest.starts_with('<'));
+    let end = rest[skip..]
+      .find('<')
+      .map(|i| i + skip)
+      .unwrap_or(rest.len());
+    let text = decode_entities(&rest[..end]);
+    if let Some((_, _, children)) = stack.last_mut() {
+      children.push(HtmlNode::Text(text));
+    }
+    rest = &rest[end..];
+  }
+
+  while stack.len() > 1 {
+    close_element(&mut stack);
+  }
+  stack
+    .pop()
+    .map(|(_, _, children)| children)
+    .unwrap_or_default()
+}
+
+fn close_element(stack: &mut Vec<(String, Vec<(String, String)>, Vec<HtmlNode>)>) {
+  if stack.len() <= 1 {
+    return;
+  }
+  if let Some((name, attributes, children)) = stack.pop() {
+    if let Some((_, _, parent_children)) = stack.last_mut() {
+      parent_children.push(HtmlNode::Element {
+        name,
+        attributes,
+        children,
+      });
+    }
+  }
+}
+
+/// Close the elements whose end tag is optional, e.g. a `<li>` is closed by the next `<li>`.
+fn close_implied_elements(
+  stack: &mut Vec<(String, Vec<(String, String)>, Vec<HtmlNode>)>,
+  name: &str,
+) {
+  let closed_by: &[&str] = match name {
+    "li" => &["li"],
+    "tr" => &["tr"],
+    "td" | "th" => &["td", "th"],
+    "dt" | "dd" => &["dt", "dd"],
+    _ if is_block_element(name) => &["p"],
+    _ => &[],
+  };
+  let boundary: &[&str] = match name {
+    "li" => &["ul", "ol"],
+    "tr" => &["table", "thead", "tbody", "tfoot"],
+    "td" | "th" => &["tr", "table"],
+    _ => &[],
+  };
+  let Some(index) = stack.iter().rposition(|(open, _, _)| {
+    closed_by.contains(&open.as_str()) || boundary.contains(&open.as_str())
+  }) else {
+    return;
+  };
+  if index == 0 || boundary.contains(&stack[index].0.as_str()) {
+    return;
+  }
+  while stack.len() > index {
+    close_element(stack);
+  }
+}
+
+/// Parse the tag after the `<`. Return the lowercase name, the attributes, whether the tag is
+/// self closing, and the input after the tag.
+fn parse_start_tag(input: &str) -> (String, Vec<(String, String)>, bool, &str) {
+  let name_end = input
+    .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+    .unwrap_or(input.len());
+  let name = input[..name_end].to_lowercase();
+  let mut rest = &input[name_end..];
+  let mut attributes = vec![];
+  let mut self_closing = false;
+
+  loop {
+    rest = rest.trim_start();
+    if rest.is_empty() {
+      break;
+    }
+    if let Some(after) = rest.strip_prefix("/>") {
+      self_closing = true;
+      rest = after;
+      break;
+    }
+    if let Some(after) = rest.strip_prefix('>') {
+      rest = after;
+      break;
+    }
+    if let Some(after) = rest.strip_prefix('/') {
+      rest = after;
+      continue;
+    }
+
+    let is_delimiter = |c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/';
+    let key_end = match rest.find(is_delimiter) {
+      // Skip a stray character so the loop always makes progress.
+      Some(0) => rest.chars().next().map_or(1, char::len_utf8),
+      Some(end) => end,
+      None => rest.len(),
+    };
+    let key = rest[..key_end].to_lowercase();
+    rest = rest[key_end..].trim_start();
+    let mut value = String::new();
+    if let Some(after) = rest.strip_prefix('=') {
+      let after = after.trim_start();
+      if let Some(quote) = after.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let quoted = &after[1..];
+        let end = quoted.find(quote).unwrap_or(quoted.len());
+        value = decode_entities(&quoted[..end]);
+        rest = quoted.get(end + 1..).unwrap_or("");
+      } else {
+        let end = after
+          .find(|c: char| c.is_whitespace() || c == '>')
+          .unwrap_or(after.len());
+        value = decode_entities(&after[..end]);
+        rest = &after[end..];
+      }
+    }
+    attributes.push((key, value));
+  }
+  (name, attributes, self_closing, rest)
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+  // The ascii lowercase keeps the byte offsets of the haystack.
+  haystack
+    .to_ascii_lowercase()
+    .find(&needle.to_ascii_lowercase())
+}
+
+/// Decode the html entities of the text: the common named entities, e.g. `&amp;`, and the
+/// numeric ones, e.g. `&#39;` or `&#x2708;`. The unknown entities are kept as is.
+pub fn decode_entities(text: &str) -> String {
+  if !text.contains('&') {
+    return text.to_string();
+  }
+  let mut output = String::with_capacity(text.len());
+  let mut rest = text;
+  while let Some(start) = rest.find('&') {
+    output.push_str(&rest[..start]);
+    rest = &rest[start..];
+    let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+      let entity = &rest[1..end];
+      let c = match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => entity
+          .strip_prefix("#x")
+          .or_else(|| entity.strip_prefix("#X"))
+          .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+          .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+          .and_then(char::from_u32),
+      }?;
+      Some((c, end))
+    });
+    match decoded {
+      Some((c, end)) => {
+        output.push(c);
+        rest = &rest[end + 1..];
+      },
+      None => {
+        output.push('&');
+        rest = &rest[1..];
+      },
+    }
+  }
+  output.push_str(rest);
+  output
+}
+
+/// Render the nodes as markdown blocks. Consecutive inline nodes are rendered as a paragraph.
+fn render_blocks(nodes: &[HtmlNode]) -> Vec<String> {
+  let mut blocks = vec![];
+  let mut inline = String::new();
+  let flush = |inline: &mut String, blocks: &mut Vec<String>| {
+    let paragraph = inline.trim();
+    if !paragraph.is_empty() {
+      blocks.push(escape_block_start(paragraph));
+    }
+    inline.clear();
+  };
+
+  for node in nodes {
+    let HtmlNode::Element { name, children, .. } = node else {
+      inline.push_str(&render_inline(std::slice::from_ref(node), 0));
+      continue;
+    };
+    let name = name.as_str();
+    if matches!(name, "video" | "audio" | "iframe") {
+      if let Some(link) = render_media(node) {
+        flush(&mut inline, &mut blocks);
+        blocks.push(link);
+        continue;
+      }
+    }
+    if name == "br" {
+      flush(&mut inline, &mut blocks);
+      continue;
+    }
+    if !is_block_element(name) && !matches!(name, "img") {
+      inline.push_str(&render_inline(std::slice::from_ref(node), 0));
+      continue;
+    }
+    flush(&mut inline, &mut blocks);
+
+    match name {
+      "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+        let level = name[1..].parse::<usize>().unwrap_or(1);
+        let text = render_inline(children, 0);
+        let text = text.trim();
+        if !text.is_empty() {
+          blocks.push(format!("{} {}", "#".repeat(level), text));
+        }
+      },
+      "p" | "dt" | "dd" | "figcaption" => {
+        let mut nested = render_blocks(children);
+        blocks.append(&mut nested);
+      },
+      "ul" | "ol" => {
+        let list = render_list(node, name == "ol");
+        if !list.is_empty() {
+          blocks.push(list);
+        }
+      },
+      "blockquote" => {
+        let quote = render_blocks(children).join("\n\n");
+        if !quote.is_empty() {
+          blocks.push(prefix_lines(&quote, "> ", "> "));
+        }
+      },
+      "pre" => blocks.push(render_code_block(node)),
+      "aside" => blocks.push(render_callout(node)),
+      "hr" => blocks.push("---".to_string()),
+      "table" => {
+        let table = render_table(node);
+        if !table.is_empty() {
+          blocks.push(table);
+        }
+      },
+      "img" => {
+        if let Some(image) = render_image(node) {
+          blocks.push(image);
+        }
+      },
+      "div" if node.has_class("column-list") => match render_column_list(node) {
+        Some(columns) => blocks.push(columns),
+        None => blocks.append(&mut render_blocks(children)),
+      },
+      _ => {
+        let mut nested = render_blocks(children);
+        blocks.append(&mut nested);
+      },
+    }
+  }
+  flush(&mut inline, &mut blocks);
+  blocks
+}
+
+fn render_list(list: &HtmlNode, ordered: bool) -> String {
+  let HtmlNode::Element { children, .. } = list else {
+    return String::new();
+  };
+  let start = list
+    .attr("start")
+    .and_then(|start| start.parse::<usize>().ok())
+    .unwrap_or(1);
+  let mut items = vec![];
+  for item in children {
+    let HtmlNode::Element {
+      name,
+      children: item_children,
+      ..
+    } = item
+    else {
+      continue;
+    };
+    if name != "li" {
+      continue;
+    }
+
+    let mut marker = if ordered {
+      format!("{}. ", start + items.len())
+    } else {
+      "- ".to_string()
+    };
+    if let Some(checkbox) = item_children
+      .iter()
+      .find(|child| matches!(child, HtmlNode::Element { name, .. } if name == "input"))
+    {
+      if checkbox.attr("type") == Some("checkbox") {
+        let checked = checkbox.attr("checked").is_some();
+        marker.push_str(if checked { "[x] " } else { "[ ] " });
+      }
+    }
+
+    let content = render_blocks(item_children).join("\n");
+    let indent = " ".repeat(marker.chars().count().min(4));
+    items.push(prefix_lines(&content, &marker, &indent));
+  }
+  items.join("\n")
+}
+
+/// Render the aside as a callout in the format of the Notion exports: the icon and the first
+/// paragraph on the line after `<aside>`, the other blocks are the children of the callout. The
+/// icon is read from the `data-icon` attribute.
+fn render_callout(aside: &HtmlNode) -> String {
+  let HtmlNode::Element { children, .. } = aside else {
+    return String::new();
+  };
+  let mut blocks = render_blocks(children);
+  let has_text = blocks
+    .first()
+    .is_some_and(|block| is_plain_paragraph(block));
+  let first = if has_text {
+    blocks.remove(0)
+  } else {
+    String::new()
+  };
+  let icon = aside.attr("data-icon").unwrap_or_default();
+  let mut lines = vec![format!("<aside>\n{}", format!("{} {}", icon, first).trim())];
+  lines.extend(blocks);
+  lines.push("</aside>".to_string());
+  lines.join("\n\n")
+}
+
+/// Return false if the rendered block is a heading, a list, a quote, a code block, a table, an
+/// image or a divider.
+fn is_plain_paragraph(block: &str) -> bool {
+  let digits = block.chars().take_while(|c| c.is_ascii_digit()).count();
+  let is_ordered_item = digits > 0 && block[digits..].starts_with(". ");
+  !is_ordered_item && !block.starts_with(['#', '>', '-', '`', '~', '|', '!'])
+}
+
+fn render_code_block(pre: &HtmlNode) -> String {
+  let language = match pre {
+    HtmlNode::Element { children, .. } => children
+      .iter()
+      .filter_map(|child| child.attr("class"))
+      .chain(pre.attr("class"))
+      .flat_map(|class| class.split_whitespace())
+      .find_map(|class| {
+        class
+          .strip_prefix("language-")
+          .or_else(|| class.strip_prefix("lang-"))
+      })
+      .unwrap_or_default()
+      .to_string(),
+    HtmlNode::Text(_) => String::new(),
+  };
+  let code = pre.text_content();
+  let code = code.trim_matches('\n');
+  let fence = if code.contains("```") { "~~~" } else { "```" };
+  format!("{fence}{language}\n{code}\n{fence}")
+}
+
+fn render_table(table: &HtmlNode) -> String {
+  let mut rows: Vec<Vec<String>> = vec![];
+  collect_table_rows(table, &mut rows);
+  let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+  if columns == 0 {
+    return String::new();
+  }
+
+  let format_row = |row: &Vec<String>| {
+    let mut cells = row.clone();
+    cells.resize(columns, String::new());
+    format!("| {} |", cells.join(" | "))
+  };
+  let mut lines = vec![format_row(&rows[0])];
+  lines.push(format!("|{}", " --- |".repeat(columns)));
+  lines.extend(rows.iter().skip(1).map(format_row));
+  lines.join("\n")
+}
+
+/// Render the columns of a Notion html export as a columns table, see
+/// [crate::importer::define::COLUMN_WIDTH_RATIO_COMMENT]. Each block of a column is rendered in
+/// its own row. Return None if there are fewer than two columns.
+fn render_column_list(column_list: &HtmlNode) -> Option<String> {
+  let HtmlNode::Element { children, .. } = column_list else {
+    return None;
+  };
+  let columns = children
+    .iter()
+    .filter(|child| child.has_class("column"))
+    .collect::<Vec<_>>();
+  if columns.len() < 2 {
+    return None;
+  }
+
+  let mut ratios = columns
+    .iter()
+    .map(|column| column.attr("style").and_then(parse_width_ratio))
+    .collect::<Vec<_>>();
+  if ratios.iter().all(Option::is_none) {
+    // The hints mark the table as columns even when the widths are unknown
+    ratios = vec![Some(1.0); columns.len()];
+  }
+  let header = ratios
+    .iter()
+    .map(|ratio| match ratio {
+      Some(ratio) => format!("<!-- {}: {} -->", COLUMN_WIDTH_RATIO_COMMENT, ratio),
+      None => String::new(),
+    })
+    .collect::<Vec<_>>();
+
+  let column_blocks = columns
+    .iter()
+    .map(|column| match column {
+      HtmlNode::Element { children, .. } => render_blocks(children)
+        .into_iter()
+        .map(|block| block.replace('\n', " ").replace('|', "\\|"))
+        .collect::<Vec<_>>(),
+      HtmlNode::Text(_) => vec![],
+    })
+    .collect::<Vec<_>>();
+  let num_of_rows = column_blocks.iter().map(Vec::len).max().unwrap_or(0).max(1);
+
+  let mut lines = vec![format!("| {} |", header.join(" | "))];
+  lines.push(format!("|{}", " --- |".repeat(columns.len())));
+  for row in 0..num_of_rows {
+    let cells = column_blocks
+      .iter()
+      .map(|blocks| blocks.get(row).map(String::as_str).unwrap_or_default())
+      .collect::<Vec<_>>();
+    lines.push(format!("| {} |", cells.join(" | ")));
+  }
+  Some(lines.join("\n"))
+}
+
+/// Return the width ratio of a column from its style, e.g. `width:50%` or the
+/// `width:calc((100% - (min(32px, 4vw) * 1)) * 0.5)` of the Notion exports.
+fn parse_width_ratio(style: &str) -> Option<f64> {
+  let width = style.split(';').find_map(|declaration| {
+    let (property, value) = declaration.split_once(':')?;
+    property
+      .trim()
+      .eq_ignore_ascii_case("width")
+      .then(|| value.trim())
+  })?;
+  let ratio = if let Some(percent) = width.strip_suffix('%') {
+    percent.trim().parse::<f64>().ok()? / 100.0
+  } else if width.starts_with("calc(") {
+    let factor = width.trim_end_matches(')').rsplit('*').next()?;
+    factor.trim().parse::<f64>().ok()?
+  } else {
+    return None;
+  };
+  (ratio > 0.0 && ratio <= 1.0).then_some(ratio)
+}
+
+fn collect_table_rows(node: &HtmlNode, rows: &mut Vec<Vec<String>>) {
+  let HtmlNode::Element { name, children, .. } = node else {
+    return;
+  };
+  if name == "tr" {
+    let cells = children
+      .iter()
+      .filter(|cell| matches!(cell, HtmlNode::Element { name, .. } if name == "td" || name == "th"))
+      .map(|cell| match cell {
+        HtmlNode::Element { children, .. } => render_inline(children, 0)
+          .replace('\n', " ")
+          .replace('|', "\\|")
+          .trim()
+          .to_string(),
+        HtmlNode::Text(_) => String::new(),
+      })
+      .collect::<Vec<_>>();
+    rows.push(cells);
+    return;
+  }
+  for child in children {
+    collect_table_rows(child, rows);
+  }
+}
+
+/// Render a video, an audio or an embedded player as a link paragraph, which the importer turns
+/// into a video or audio block, see [MediaLink::from_url]. Return None for an iframe that isn't a
+/// known video player.
+fn render_media(media: &HtmlNode) -> Option<String> {
+  let HtmlNode::Element { name, children, .. } = media else {
+    return None;
+  };
+  let src = media
+    .attr("src")
+    .or_else(|| {
+      children.iter().find_map(|child| match child {
+        HtmlNode::Element { name, .. } if name == "source" => child.attr("src"),
+        _ => None,
+      })
+    })
+    .map(str::trim)
+    .filter(|src| !src.is_empty())?;
+  if name == "iframe" && MediaLink::from_url(src).is_none() {
+    return None;
+  }
+  Some(format!("[{}]({})", escape_text(src), src))
+}
+
+fn render_image(image: &HtmlNode) -> Option<String> {
+  let src = image.attr("src").filter(|src| !src.is_empty())?;
+  let alt = image.attr("alt").unwrap_or_default();
+  Some(format!("![{}]({})", escape_text(alt), src))
+}
+
+/// Render the nodes as inline markdown. `depth` is the number of enclosing formatting marks,
+/// line breaks are only kept outside of them because a mark can't span paragraphs.
+fn render_inline(nodes: &[HtmlNode], depth: usize) -> String {
+  let mut output = String::new();
+  for node in nodes {
+    match node {
+      HtmlNode::Text(text) => output.push_str(&escape_text(&collapse_whitespace(text))),
+      HtmlNode::Element { name, children, .. } => match name.as_str() {
+        "strong" | "b" => output.push_str(&wrap(&render_inline(children, depth + 1), "**")),
+        "em" | "i" => output.push_str(&wrap(&render_inline(children, depth + 1), "*")),
+        "s" | "del" | "strike" => output.push_str(&wrap(&render_inline(children, depth + 1), "~~")),
+        "code" | "kbd" | "samp" => {
+          let code = collapse_whitespace(&node.text_content());
+          if !code.trim().is_empty() {
+            let fence = if code.contains('`') { "``" } else { "`" };
+            output.push_str(&format!("{fence}{code}{fence}"));
+          }
+        },
+        "a" => {
+          let text = render_inline(children, depth + 1);
+          match node.attr("href").filter(|href| !href.is_empty()) {
+            Some(href) if !text.trim().is_empty() => {
+              output.push_str(&format!("[{}]({})", text.trim(), href.replace(' ', "%20")))
+            },
+            _ => output.push_str(&text),
+          }
+        },
+        "img" => {
+          if let Some(image) = render_image(node) {
+            output.push_str(&image);
+          }
+        },
+        "br" => output.push_str(if depth == 0 { "\n\n" } else { " " }),
+        "input" => {},
+        _ => output.push_str(&render_inline(children, depth)),
+      },
+    }
+  }
+  output
+}
+
+/// Wrap the text with the formatting mark, keeping the surrounding spaces outside of it.
+fn wrap(text: &str, mark: &str) -> String {
+  let trimmed = text.trim();
+  if trimmed.is_empty() {
+    return text.to_string();
+  }
+  let leading = if text.starts_with(char::is_whitespace) {
+    " "
+  } else {
+    ""
+  };
+  let trailing = if text.ends_with(char::is_whitespace) {
+    " "
+  } else {
+    ""
+  };
+  format!("{leading}{mark}{trimmed}{mark}{trailing}")
+}
+
+fn collapse_whitespace(text: &str) -> String {
+  let mut output = String::with_capacity(text.len());
+  let mut last_was_space = false;
+  for c in text.chars() {
+    if c.is_whitespace() {
+      if !last_was_space {
+        output.push(' ');
+      }
+      last_was_space = true;
+    } else {
+      output.push(c);
+      last_was_space = false;
+    }
+  }
+  output
+}
+
+fn escape_text(text: &str) -> String {
+  let mut output = String::with_capacity(text.len());
+  for c in text.chars() {
+    if matches!(
+      c,
+      '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '~' | '$' | '|'
+    ) {
+      output.push('\\');
+    }
+    output.push(c);
+  }
+  output
+}
+
+/// Escape the characters that would turn the start of a paragraph into another block.
+fn escape_block_start(paragraph: &str) -> String {
+  paragraph
+    .split("\n\n")
+    .map(|line| {
+      let line = line.trim();
+      let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+      let is_list_marker =
+        line.starts_with(['-', '+']) || (digits > 0 && line[digits..].starts_with(['.', ')']));
+      if line.starts_with(['#', '>']) || is_list_marker {
+        if digits > 0 {
+          format!("{}\\{}", &line[..digits], &line[digits..])
+        } else {
+          format!("\\{line}")
+        }
+      } else {
+        line.to_string()
+      }
+    })
+    .filter(|line| !line.is_empty())
+    .collect::<Vec<_>>()
+    .join("\n\n")
+}
+
+fn prefix_lines(text: &str, first: &str, rest: &str) -> String {
+  text
+    .lines()
+    .enumerate()
+    .map(|(i, line)| {
+      let prefix = if i == 0 { first } else { rest };
+      if line.is_empty() {
+        prefix.trim_end().to_string()
+      } else {
+        format!("{prefix}{line}")
+      }
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
@@ -0,0 +1,289 @@
+use crate::importer::define::{
+  BG_COLOR_ATTR, FONT_COLOR_ATTR, SUBSCRIPT_ATTR, SUPERSCRIPT_ATTR, UNDERLINE_ATTR,
+};
+use markdown::mdast;
+use serde_json::Value;
+
+/// The inline html tags styling the text.
+const STYLE_TAGS: [&str; 6] = ["span", "mark", "u", "ins", "sup", "sub"];
+
+/// The background of a `<mark>` without a color, the yellow highlight of Notion.
+const DEFAULT_HIGHLIGHT_COLOR: (u8, u8, u8) = (251, 243, 219);
+
+/// The colors of the `highlight-<color>` classes of a Notion export, the text color then the
+/// background color.
+const NOTION_COLORS: [(&str, (u8, u8, u8), (u8, u8, u8)); 9] = [
+  ("gray", (120, 119, 116), (241, 241, 239)),
+  ("brown", (159, 107, 83), (244, 238, 238)),
+  ("orange", (217, 115, 13), (251, 236, 221)),
+  ("yellow", (203, 145, 47), (251, 243, 219)),
+  ("green", (68, 131, 97), (237, 243, 236)),
+  ("blue", (51, 126, 169), (231, 243, 248)),
+  ("purple", (144, 101, 176), (244, 240, 247)),
+  ("pink", (193, 76, 138), (249, 238, 243)),
+  ("red", (212, 76, 71), (253, 235, 236)),
+];
+
+const CSS_COLORS: [(&str, (u8, u8, u8)); 13] = [
+  ("black", (0, 0, 0)),
+  ("white", (255, 255, 255)),
+  ("gray", (128, 128, 128)),
+  ("grey", (128, 128, 128)),
+  ("red", (255, 0, 0)),
+  ("orange", (255, 165, 0)),
+  ("yellow", (255, 255, 0)),
+  ("green", (0, 128, 0)),
+  ("teal", (0, 128, 128)),
+  ("blue", (0, 0, 255)),
+  ("purple", (128, 0, 128)),
+  ("pink", (255, 192, 203)),
+  ("brown", (165, 42, 42)),
+];
+
+/// An inline html tag styling the text, e.g. `<span style="color:#e03e3e">`, `<mark>` or `<u>`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum InlineStyleTag {
+  /// The opening tag with the delta attributes of its style, empty if it has none.
+  Open(Vec<(String, Value)>),
+  Close,
+}
+
+/// Parse an inline `<span>`, `<mark>`, `<u>`, `<ins>`, `<sup>` or `<sub>` tag. The `color` and
+/// `background-color` of the style attribute, and the `highlight-<color>` classes of Notion, are
+/// mapped to the font and background colors of the delta.
+pub(crate) fn parse_inline_style_tag(html: &str) -> Option<InlineStyleTag> {
+  let html = html.trim();
+  let inner = html.strip_prefix('<')?.strip_suffix('>')?;
+  if inner.contains(['<', '>']) {
+    return None;
+  }
+  if let Some(name) = inner.strip_prefix('/') {
+    let name = name.trim().to_lowercase();
+    return STYLE_TAGS
+      .contains(&name.as_str())
+      .then_some(InlineStyleTag::Close);
+  }
+
+  // A self-closing tag styles nothing
+  if inner.ends_with('/') {
+    return None;
+  }
+  let (name, attrs) = inner.split_once(char::is_whitespace).unwrap_or((inner, ""));
+  let name = name.to_lowercase();
+  if !STYLE_TAGS.contains(&name.as_str()) {
+    return None;
+  }
+
+  let mut font_color = None;
+  let mut bg_color = (name == "mark").then_some(DEFAULT_HIGHLIGHT_COLOR);
+  if let Some(class) = attr_value(attrs, "class") {
+    for class in class.split_whitespace() {
+      let Some(color) = class.strip_prefix("highlight-") else {
+        continue;
+      };
+      match color.strip_suffix("_background") {
+        Some(color) => bg_color = notion_color(color).map(|(_, bg)| bg).or(bg_color),
+        None => font_color = notion_color(color).map(|(fg, _)| fg).or(font_color),
+      }
+    }
+  }
+  if let Some(style) = attr_value(attrs, "style") {
+    for declaration in style.split(';') {
+      let Some((property, value)) = declaration.split_once(':') else {
+        continue;
+      };
+      match property.trim().to_lowercase().as_str() {
+        "color" => font_color = css_color(value).or(font_color),
+        "background-color" | "background" => bg_color = css_color(value).or(bg_color),
+        _ => {},
+      }
+    }
+  }
+
+  let mut attributes = vec![];
+  match name.as_str() {
+    "u" | "ins" => attributes.push((UNDERLINE_ATTR.to_string(), Value::Bool(true))),
+    "sup" => attributes.push((SUPERSCRIPT_ATTR.to_string(), Value::Bool(true))),
+    "sub" => attributes.push((SUBSCRIPT_ATTR.to_string(), Value::Bool(true))),
+    _ => {},
+  }
+  if let Some(color) = font_color {
+    attributes.push((FONT_COLOR_ATTR.to_string(), Value::String(hex_color(color))));
+  }
+  if let Some(color) = bg_color {
+    attributes.push((BG_COLOR_ATTR.to_string(), Value::String(hex_color(color))));
+  }
+  Some(InlineStyleTag::Open(attributes))
+}
+
+/// Return the value of the attribute in the attributes of an html tag.
+fn attr_value<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+  let mut rest = attrs;
+  loop {
+    rest = rest.trim_start();
+    if rest.is_empty() {
+      return None;
+    }
+    let key_end = rest.find(['=', ' ', '\t', '\n']).unwrap_or(rest.len());
+    let key = &rest[..key_end];
+    rest = rest[key_end..].trim_start();
+    let Some(after_eq) = rest.strip_prefix('=') else {
+      continue;
+    };
+    let after_eq = after_eq.trim_start();
+    let (value, after_value) = match after_eq.chars().next() {
+      Some(quote @ ('"' | '\'')) => {
+        let value = &after_eq[1..];
+        let end = value.find(quote).unwrap_or(value.len());
+        (&value[..end], value.get(end + 1..).unwrap_or_default())
+      },
+      _ => {
+        let end = after_eq.find(char::is_whitespace).unwrap_or(after_eq.len());
+        (&after_eq[..end], &after_eq[end..])
+      },
+    };
+    if key.eq_ignore_ascii_case(name) {
+      return Some(value);
+    }
+    rest = after_value;
+  }
+}
+
+fn notion_color(name: &str) -> Option<((u8, u8, u8), (u8, u8, u8))> {
+  NOTION_COLORS
+    .iter()
+    .find(|(color, _, _)| *color == name)
+    .map(|(_, fg, bg)| (*fg, *bg))
+}
+
+/// Parse a css color, `#rgb`, `#rrggbb`, `rgb(r, g, b)`, `rgba(r, g, b, a)` or a basic color
+/// name. The alpha channel is dropped, the document colors are opaque.
+fn css_color(value: &str) -> Option<(u8, u8, u8)> {
+  let value = value
+    .trim()
+    .trim_end_matches("!important")
+    .trim()
+    .to_lowercase();
+  if let Some(hex) = value.strip_prefix('#') {
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    return match hex.len() {
+      3 | 4 => {
+        let digits = hex
+          .chars()
+          .map(|c| channel(&c.to_string()).map(|v| v * 17))
+          .collect::<Option<Vec<_>>>()?;
+        Some((digits[0], digits[1], digits[2]))
+      },
+      6 | 8 => Some((
+        channel(&hex[0..2])?,
+        channel(&hex[2..4])?,
+        channel(&hex[4..6])?,
+      )),
+      _ => None,
+    };
+  }
+  if let Some(args) = value
+    .strip_prefix("rgba(")
+    .or_else(|| value.strip_prefix("rgb("))
+  {
+    let channels = args
+      .strip_suffix(')')?
+      .split([',', ' ', '/'])
+      .filter(|s| !s.is_empty())
+      .take(3)
+      .map(|s| {
+        s.trim()
+          .parse::<f64>()
+          .ok()
+          .map(|v| v.clamp(0.0, 255.0).round() as u8)
+      })
+      .collect::<Option<Vec<_>>>()?;
+    let [r, g, b] = channels.as_slice() else {
+      return None;
+    };
+    return Some((*r, *g, *b));
+  }
+  CSS_COLORS
+    .iter()
+    .find(|(name, _)| *name == value)
+    .map(|(_, rgb)| *rgb)
+}
+
+/// Format the color the way the document stores it, `0xAARRGGBB`.
+fn hex_color((r, g, b): (u8, u8, u8)) -> String {
+  format!("0xff{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Convert the pandoc superscripts and subscripts of the texts, e.g. `2^10^` or `H~2~O`, to
+/// `<sup>` and `<sub>` tags. Like pandoc, the script can't contain whitespace.
+///
+/// The single tildes must not be parsed as strikethrough, see
+/// [crate::importer::md_importer::MDImporter::with_pandoc_scripts].
+pub(crate) fn convert_pandoc_scripts(node: &mut mdast::Node) {
+  let Some(children) = node.children_mut() else {
+    return;
+  };
+  let mut converted = Vec::with_capacity(children.len());
+  for mut child in children.drain(..) {
+    match &child {
+      mdast::Node::Text(text) => converted.extend(split_pandoc_scripts(text)),
+      _ => {
+        convert_pandoc_scripts(&mut child);
+        converted.push(child);
+      },
+    }
+  }
+  *children = converted;
+}
+
+fn split_pandoc_scripts(text: &mdast::Text) -> Vec<mdast::Node> {
+  let value = &text.value;
+  let mut nodes = vec![];
+  let mut plain_start = 0;
+  let mut search_start = 0;
+  while let Some(offset) = value[search_start..].find(['^', '~']) {
+    let start = search_start + offset;
+    let delimiter = &value[start..start + 1];
+    let script_start = start + 1;
+    let script = value[script_start..]
+      .find(delimiter)
+      .map(|len| &value[script_start..script_start + len])
+      .filter(|script| !script.is_empty() && !script.contains(char::is_whitespace));
+    let Some(script) = script else {
+      search_start = script_start;
+      continue;
+    };
+
+    let tag = if delimiter == "^" { "sup" } else { "sub" };
+    if plain_start < start {
+      nodes.push(text_node(&value[plain_start..start]));
+    }
+    nodes.push(html_node(format!("<{}>", tag)));
+    nodes.push(text_node(script));
+    nodes.push(html_node(format!("</{}>", tag)));
+    plain_start = script_start + script.len() + 1;
+    search_start = plain_start;
+  }
+
+  if nodes.is_empty() {
+    return vec![mdast::Node::Text(text.clone())];
+  }
+  if plain_start < value.len() {
+    nodes.push(text_node(&value[plain_start..]));
+  }
+  nodes
+}
+
+fn text_node(value: &str) -> mdast::Node {
+  mdast::Node::Text(mdast::Text {
+    value: value.to_string(),
+    position: None,
+  })
+}
+
+fn html_node(value: String) -> mdast::Node {
+  mdast::Node::Html(mdast::Html {
+    value,
+    position: None,
+  })
+}
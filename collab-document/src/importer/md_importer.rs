@@ -1,14 +1,38 @@
-use crate::blocks::{Block, BlockType, DocumentData, DocumentMeta};
+use crate::blocks::{
+  Block, BlockType, DocumentData, DocumentMeta, SimpleColumnData, normalize_column_width_ratios,
+};
+use crate::document::Document;
 use crate::document_data::generate_id;
 use crate::error::DocumentError;
+use crate::importer::columns::ColumnsDetection;
+use crate::importer::date_mention::convert_date_mentions;
 use crate::importer::define::*;
 use crate::importer::delta::Delta;
+use crate::importer::embed::convert_embed_links;
+use crate::importer::emoji::{EmojiShortcodeTable, replace_emoji_shortcodes};
+use crate::importer::filter::{ContentFilter, filter_document_data};
+use crate::importer::fragment::{ContentType, DocumentFragment, TsvTable};
+use crate::importer::guard::InputLimits;
+use crate::importer::heading::HeadingPolicy;
+use crate::importer::inline_style::convert_pandoc_scripts;
+use crate::importer::html::html_to_markdown;
+use crate::importer::media::{MediaLink, convert_file_links, convert_media_links};
+use crate::importer::report::{
+  ConvertedTable, FormattingLoss, FormattingLossKind, FormattingLossReport,
+  collect_formatting_losses,
+};
+use crate::importer::sync::{MarkdownSyncReport, sync_document_data};
+use crate::importer::typography::{TypographyOptions, apply_typography};
 use crate::importer::util::*;
+use crate::importer::whitespace::{WhitespaceOptions, normalize_whitespace};
+use crate::invariants::{check_document, fill_missing_texts};
+use crate::limits::DocumentLimits;
 use markdown::mdast::AlignKind;
 use markdown::{Constructs, ParseOptions, mdast, to_mdast};
 use serde_json::Value;
 use std::collections::HashMap;
-use tracing::trace;
+use std::sync::Arc;
+use tracing::{debug, trace, warn};
 
 #[derive(Default)]
 pub struct MDImporter {
@@ -20,6 +44,67 @@ pub struct MDImporter {
   /// - math text, math flow, autolink features.
   /// - default Markdown features.
   pub parse_options: ParseOptions,
+
+  /// If set, `:rocket:`-style shortcodes in the text are converted to their emoji.
+  pub emoji_shortcodes: Option<Arc<dyn EmojiShortcodeTable>>,
+
+  /// The filters run on the imported blocks and texts, in order, e.g. to strip the unsafe links
+  /// of untrusted content. See [ContentFilter].
+  pub content_filters: Vec<Arc<dyn ContentFilter>>,
+
+  /// If true, a paragraph that only contains a bare url is imported as a link preview block
+  /// instead of a paragraph with a link. Notion exports its web bookmarks this way.
+  pub bare_url_as_link_preview: bool,
+
+  /// If true, a paragraph that only contains a link to a video or an audio file, or to a YouTube
+  /// or Vimeo video, is imported as a video or audio block, see [MediaLink::from_url]. Notion
+  /// exports its video and audio blocks this way.
+  pub media_embeds: bool,
+
+  /// If true, a paragraph that only contains a link to a YouTube video, a Figma file, a tweet or
+  /// a Google Maps place is imported as an embed block, see
+  /// [crate::importer::embed::EmbedProvider::from_url]. The YouTube links are imported as embeds
+  /// rather than videos when both are enabled. Use [crate::importer::embed::resolve_embeds] to
+  /// fill their title, description and thumbnail.
+  pub embeds: bool,
+
+  /// If true, a paragraph that only contains a link to a local file, e.g. a pdf or a zip in the
+  /// asset folder of a Notion export, is imported as a file block named after the link text.
+  pub file_blocks: bool,
+
+  /// If true, the dates Notion exports as plain text, e.g. `@March 4, 2024` or
+  /// `2024-03-04 → 2024-03-06`, are imported as date mentions.
+  pub date_mentions: bool,
+
+  /// If true, the slug of each heading is stored in its data, see
+  /// [DocumentData::assign_heading_slugs].
+  pub heading_slugs: bool,
+
+  /// The limits the inputs are checked against before they are parsed.
+  pub input_limits: InputLimits,
+
+  /// The limits the imported documents are truncated to, or rejected over, see
+  /// [DocumentData::enforce_limits].
+  pub document_limits: DocumentLimits,
+
+  /// How the whitespace of the text is normalized. The defaults convert the non-breaking spaces,
+  /// expand the tabs, trim the trailing whitespace and apply NFC.
+  pub whitespace: WhitespaceOptions,
+
+  /// The smart typography applied to the text, e.g. curly quotes and dashes. None keeps the
+  /// straight quotes and the hyphens.
+  pub typography: Option<TypographyOptions>,
+
+  /// When a table with an empty header row is imported as columns. The tables imported as
+  /// columns are listed in [FormattingLossReport::converted_tables].
+  pub columns_detection: ColumnsDetection,
+
+  /// How the headings deeper than the supported levels are imported.
+  pub heading_policy: HeadingPolicy,
+
+  /// If true, the pandoc superscripts and subscripts, e.g. `2^10^` or `H~2~O`, are imported as
+  /// superscript and subscript text. The `<sup>` and `<sub>` html tags are always imported.
+  pub pandoc_scripts: bool,
 }
 
 impl MDImporter {
@@ -35,42 +120,403 @@ impl MDImporter {
       ..ParseOptions::gfm()
     });
 
-    Self { parse_options }
+    Self {
+      parse_options,
+      emoji_shortcodes: None,
+      content_filters: vec![],
+      bare_url_as_link_preview: false,
+      media_embeds: false,
+      embeds: false,
+      file_blocks: false,
+      date_mentions: false,
+      heading_slugs: false,
+      input_limits: InputLimits::default(),
+      document_limits: DocumentLimits::default(),
+      whitespace: WhitespaceOptions::default(),
+      typography: None,
+      columns_detection: ColumnsDetection::default(),
+      heading_policy: HeadingPolicy::default(),
+      pandoc_scripts: false,
+    }
+  }
+
+  /// See [MDImporter::date_mentions].
+  pub fn with_date_mentions(mut self, enabled: bool) -> Self {
+    self.date_mentions = enabled;
+    self
+  }
+
+  /// See [MDImporter::heading_slugs].
+  pub fn with_heading_slugs(mut self, enabled: bool) -> Self {
+    self.heading_slugs = enabled;
+    self
+  }
+
+  /// See [MDImporter::input_limits].
+  pub fn with_input_limits(mut self, limits: InputLimits) -> Self {
+    self.input_limits = limits;
+    self
+  }
+
+  /// See [MDImporter::document_limits].
+  pub fn with_document_limits(mut self, limits: DocumentLimits) -> Self {
+    self.document_limits = limits;
+    self
+  }
+
+  /// See [MDImporter::whitespace]. Use [WhitespaceOptions::preserve] to import the text as is.
+  pub fn with_whitespace(mut self, options: WhitespaceOptions) -> Self {
+    self.whitespace = options;
+    self
+  }
+
+  /// See [MDImporter::typography].
+  pub fn with_typography(mut self, options: TypographyOptions) -> Self {
+    self.typography = Some(options);
+    self
+  }
+
+  /// See [MDImporter::columns_detection].
+  pub fn with_columns_detection(mut self, detection: ColumnsDetection) -> Self {
+    self.columns_detection = detection;
+    self
+  }
+
+  /// See [MDImporter::heading_policy].
+  pub fn with_heading_policy(mut self, policy: HeadingPolicy) -> Self {
+    self.heading_policy = policy;
+    self
+  }
+
+  /// See [MDImporter::pandoc_scripts]. A single tilde then marks a subscript instead of a
+  /// strikethrough, `~~text~~` is still a strikethrough.
+  pub fn with_pandoc_scripts(mut self, enabled: bool) -> Self {
+    self.pandoc_scripts = enabled;
+    if enabled {
+      self.parse_options.gfm_strikethrough_single_tilde = false;
+    }
+    self
+  }
+
+  /// See [MDImporter::media_embeds].
+  pub fn with_media_embeds(mut self, enabled: bool) -> Self {
+    self.media_embeds = enabled;
+    self
   }
 
+  /// See [MDImporter::embeds].
+  pub fn with_embeds(mut self, enabled: bool) -> Self {
+    self.embeds = enabled;
+    self
+  }
+
+  /// See [MDImporter::file_blocks].
+  pub fn with_file_blocks(mut self, enabled: bool) -> Self {
+    self.file_blocks = enabled;
+    self
+  }
+
+  /// See [MDImporter::bare_url_as_link_preview].
+  pub fn with_bare_url_as_link_preview(mut self, enabled: bool) -> Self {
+    self.bare_url_as_link_preview = enabled;
+    self
+  }
+
+  /// Convert the shortcodes found in the table to their emoji. Use
+  /// [crate::importer::emoji::DefaultEmojiShortcodes] for the shortcodes commonly used by GitHub
+  /// and Notion.
+  pub fn with_emoji_shortcodes<T: EmojiShortcodeTable + 'static>(mut self, table: T) -> Self {
+    self.emoji_shortcodes = Some(Arc::new(table));
+    self
+  }
+
+  /// Add a filter run on the imported content, see [MDImporter::content_filters].
+  pub fn with_content_filter<T: ContentFilter + 'static>(mut self, filter: T) -> Self {
+    self.content_filters.push(Arc::new(filter));
+    self
+  }
+
+  /// Import the markdown. If the parser fails, the content is imported as plain text paragraphs,
+  /// see [MDImporter::parse].
   pub fn import(&self, document_id: &str, md: String) -> Result<DocumentData, DocumentError> {
-    let md_node =
-      to_mdast(&md, &self.parse_options).map_err(|_| DocumentError::ParseMarkdownError)?;
+    self.input_limits.check(&md)?;
+    let (mut md_node, _) = self.parse(document_id, &md);
+    self.post_process(&mut md_node);
+    let mut data = self.import_mdast(document_id, &md_node);
+    data.enforce_limits(&self.document_limits)?;
+    Ok(data)
+  }
+
+  /// Import html content, e.g. a page saved by another note-taking app. The html is converted
+  /// to markdown first, so it produces the same blocks as the equivalent markdown.
+  ///
+  /// The html is checked against the [MDImporter::input_limits] except for the length of its
+  /// lines, the html of a saved page is often minified in one line.
+  pub fn import_html(&self, document_id: &str, html: &str) -> Result<DocumentData, DocumentError> {
+    self.input_limits.check_size(html)?;
+    self.import(document_id, html_to_markdown(html))
+  }
+
+  /// Import the markdown and report the formatting that can't be represented by the document,
+  /// e.g. underlines, footnotes or nested tables. Each loss is also logged at debug level.
+  ///
+  /// A parser failure is reported as [FormattingLossKind::ParseFailure], the content is then
+  /// imported as plain text paragraphs.
+  pub fn import_with_report(
+    &self,
+    document_id: &str,
+    md: String,
+  ) -> Result<(DocumentData, FormattingLossReport), DocumentError> {
+    self.input_limits.check(&md)?;
+    let (mut md_node, parse_error) = self.parse(document_id, &md);
+    let mut report = FormattingLossReport::default();
+    if let Some(error) = parse_error {
+      report.losses.push(FormattingLoss {
+        kind: FormattingLossKind::ParseFailure,
+        line: None,
+        column: None,
+        detail: format!("markdown parsing failed, imported as plain text: {}", error),
+      });
+    }
+    collect_formatting_losses(&md_node, &mut report);
+    for loss in report.losses.iter() {
+      debug!("[{}] {}: {}", document_id, loss.kind, loss);
+    }
+    if !report.is_lossless() {
+      debug!(
+        "[{}] import markdown with formatting losses: {:?}",
+        document_id,
+        report.counts()
+      );
+    }
+
+    self.post_process(&mut md_node);
+    collect_converted_tables(&md_node, self.columns_detection, &mut report);
+    let mut data = self.import_mdast(document_id, &md_node);
+    data.enforce_limits(&self.document_limits)?;
+    Ok((data, report))
+  }
+
+  /// Sync the document with the new content of its markdown source, e.g. a changed .md file kept
+  /// as the source of truth. Unlike importing the markdown again, the collaboration history of
+  /// the document is kept: the markdown is imported and diffed against the blocks of the
+  /// document, the unchanged blocks are kept, the changed blocks are updated in place when their
+  /// type is unchanged and the other blocks are deleted or inserted.
+  ///
+  /// The data fields that markdown can't represent, e.g. the colors of the blocks, are kept.
+  pub fn sync_into(
+    &self,
+    document: &mut Document,
+    md: String,
+  ) -> Result<MarkdownSyncReport, DocumentError> {
+    let page_id = document.get_page_id().ok_or(DocumentError::PageIdIsEmpty)?;
+    let data = self.import(&page_id, md)?;
+    let report = sync_document_data(document, &data)?;
+    debug!(
+      "[{}] sync markdown: {} inserted, {} updated, {} deleted",
+      page_id,
+      report.inserted.len(),
+      report.updated.len(),
+      report.deleted.len()
+    );
+    Ok(report)
+  }
+
+  /// Import pasted content as blocks to insert into `parent_block_id` at the cursor position,
+  /// see [crate::document::Document::insert_fragment]. HTML is converted to markdown first.
+  ///
+  /// Tab-separated markdown or plain text, as copied from a spreadsheet, is imported as a simple
+  /// table. Use [TsvTable::detect] to offer another conversion, e.g. to a database.
+  pub fn import_fragment(
+    &self,
+    parent_block_id: &str,
+    content: &str,
+    content_type: ContentType,
+  ) -> Result<DocumentFragment, DocumentError> {
+    self.input_limits.check_size(content)?;
+    if content_type != ContentType::Html {
+      if let Some(table) = TsvTable::detect(content) {
+        let mut fragment = DocumentFragment::from_tsv_table(&table, parent_block_id);
+        fragment.apply_content_filters(&self.content_filters);
+        return Ok(fragment);
+      }
+    }
+    let md = match content_type {
+      ContentType::Markdown => content.to_string(),
+      ContentType::Html => html_to_markdown(content),
+      ContentType::PlainText => {
+        let mut fragment = DocumentFragment::from_plain_text(content, parent_block_id);
+        fragment.apply_content_filters(&self.content_filters);
+        return Ok(fragment);
+      },
+    };
+    let data = self.import(&generate_id(), md)?;
+    Ok(DocumentFragment::from_document_data(data, parent_block_id))
+  }
+
+  /// Parse the markdown into its ast. The parser only fails on some constructs, e.g. invalid MDX
+  /// expressions, but one pathological file shouldn't block the import of a whole workspace: the
+  /// content is then split into paragraphs on blank lines and the parser error is returned.
+  fn parse(&self, document_id: &str, md: &str) -> (mdast::Node, Option<String>) {
+    match to_mdast(md, &self.parse_options) {
+      Ok(md_node) => (md_node, None),
+      Err(err) => {
+        warn!(
+          "[{}] failed to parse markdown, import it as plain text: {}",
+          document_id, err
+        );
+        (plain_text_mdast(md), Some(err.to_string()))
+      },
+    }
+  }
+
+  fn post_process(&self, md_node: &mut mdast::Node) {
+    merge_adjacent_lists(md_node);
+    normalize_whitespace(md_node, &self.whitespace);
+    if let Some(typography) = &self.typography {
+      apply_typography(md_node, typography);
+    }
+    if let Some(table) = &self.emoji_shortcodes {
+      replace_emoji_shortcodes(md_node, table.as_ref());
+    }
+    if self.pandoc_scripts {
+      convert_pandoc_scripts(md_node);
+    }
+  }
 
+  fn import_mdast(&self, document_id: &str, md_node: &mdast::Node) -> DocumentData {
     let mut document_data = DocumentData {
       page_id: document_id.to_string(),
       blocks: HashMap::new(),
       meta: DocumentMeta {
         children_map: HashMap::new(),
         text_map: Some(HashMap::new()),
+        lazy_text: false,
       },
     };
 
     process_mdast_node(
       &mut document_data,
-      &md_node,
+      md_node,
       None,
       Some(document_id.to_string()),
       None,
       None,
-      &self.parse_options,
+      ConvertOptions {
+        parse_options: &self.parse_options,
+        columns_detection: self.columns_detection,
+        heading_policy: self.heading_policy,
+      },
     );
+    if self.date_mentions {
+      convert_date_mentions(&mut document_data);
+    }
+    if self.embeds {
+      convert_embed_links(&mut document_data);
+    }
+    if self.media_embeds {
+      convert_media_links(&mut document_data);
+    }
+    if self.file_blocks {
+      convert_file_links(&mut document_data);
+    }
+    if self.bare_url_as_link_preview {
+      convert_bare_urls_to_link_previews(&mut document_data);
+    }
+    filter_document_data(&mut document_data, &self.content_filters);
+    if self.heading_slugs {
+      document_data.assign_heading_slugs();
+    }
+
+    fill_missing_texts(&mut document_data);
+    for violation in check_document(&document_data) {
+      warn!("imported markdown produced an invalid document: {}", violation);
+    }
+    document_data
+  }
+}
+
+/// The markdown as a root of plain text paragraphs, one per block of lines separated by blank
+/// lines.
+fn plain_text_mdast(md: &str) -> mdast::Node {
+  let mut paragraphs = vec![];
+  let mut lines: Vec<&str> = vec![];
+  for line in md.lines().chain(std::iter::once("")) {
+    if !line.trim().is_empty() {
+      lines.push(line.trim_end());
+      continue;
+    }
+    if lines.is_empty() {
+      continue;
+    }
+    paragraphs.push(mdast::Node::Paragraph(mdast::Paragraph {
+      children: vec![mdast::Node::Text(mdast::Text {
+        value: lines.join("\n"),
+        position: None,
+      })],
+      position: None,
+    }));
+    lines.clear();
+  }
+  mdast::Node::Root(mdast::Root {
+    children: paragraphs,
+    position: None,
+  })
+}
+
+/// Convert the paragraphs whose whole text is a link to its own url, e.g. `<https://appflowy.io>`
+/// or `[https://appflowy.io](https://appflowy.io)`, to link preview blocks.
+fn convert_bare_urls_to_link_previews(document_data: &mut DocumentData) {
+  let Some(text_map) = document_data.meta.text_map.as_mut() else {
+    return;
+  };
+  for block in document_data.blocks.values_mut() {
+    if block.ty != BlockType::Paragraph.as_str() {
+      continue;
+    }
+    let Some(url) = block
+      .external_id
+      .as_ref()
+      .and_then(|text_id| text_map.get(text_id))
+      .and_then(|delta| bare_url_from_delta(delta))
+    else {
+      continue;
+    };
 
-    Ok(document_data)
+    if let Some(text_id) = block.external_id.take() {
+      text_map.remove(&text_id);
+    }
+    block.external_type = None;
+    block.ty = BlockType::LinkPreview.to_string();
+    block.data.insert(URL_FIELD.to_string(), Value::String(url));
   }
 }
 
+fn bare_url_from_delta(delta: &str) -> Option<String> {
+  let ops: Vec<Value> = serde_json::from_str(delta).ok()?;
+  let [op] = ops.as_slice() else {
+    return None;
+  };
+  let text = op.get("insert")?.as_str()?.trim();
+  let attributes = op.get("attributes")?.as_object()?;
+  let href = attributes.get(HREF_ATTR)?.as_str()?;
+  // Only a plain link, a bold or otherwise formatted link is kept as text.
+  let is_bare_url = attributes.len() == 1 && text == href;
+  is_bare_url.then(|| href.to_string())
+}
+
 struct NotionColumnsTableInfo<'a> {
   col_count: usize,
   body_rows: &'a [mdast::Node],
+  /// The width ratio of each column, None if the columns share the width equally.
+  width_ratios: Option<Vec<f64>>,
 }
 
-fn parse_notion_columns_table(table: &mdast::Table) -> Option<NotionColumnsTableInfo<'_>> {
+fn parse_notion_columns_table(
+  table: &mdast::Table,
+  columns_detection: ColumnsDetection,
+) -> Option<NotionColumnsTableInfo<'_>> {
+  let thresholds = columns_detection.thresholds()?;
   let mut rows = table.children.iter();
   let header = rows.next()?;
   let mdast::Node::TableRow(header) = header else {
@@ -97,6 +543,23 @@ fn parse_notion_columns_table(table: &mdast::Table) -> Option<NotionColumnsTable
     return None;
   }
 
+  // The width hints mark the table as columns, e.g. one converted from a Notion html export
+  let width_ratios = header
+    .children
+    .iter()
+    .map(|cell| match cell {
+      mdast::Node::TableCell(cell) => column_width_ratio_hint(cell),
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+  if width_ratios.iter().any(Option::is_some) {
+    return Some(NotionColumnsTableInfo {
+      col_count,
+      body_rows,
+      width_ratios: normalize_column_width_ratios(&width_ratios),
+    });
+  }
+
   let all_body_empty = body_rows.iter().all(|row| {
     let mdast::Node::TableRow(row) = row else {
       return false;
@@ -112,15 +575,55 @@ fn parse_notion_columns_table(table: &mdast::Table) -> Option<NotionColumnsTable
     })
   });
 
-  if all_body_empty {
-    if col_count < 6 {
-      return None;
-    }
-  } else if col_count > 5 || body_rows.len() > 3 {
+  if !thresholds.matches(col_count, body_rows.len(), all_body_empty) {
     return None;
   }
 
-  Some(NotionColumnsTableInfo { col_count, body_rows })
+  Some(NotionColumnsTableInfo {
+    col_count,
+    body_rows,
+    width_ratios: None,
+  })
+}
+
+/// Record the tables imported as columns in the report.
+fn collect_converted_tables(
+  node: &mdast::Node,
+  columns_detection: ColumnsDetection,
+  report: &mut FormattingLossReport,
+) {
+  if let mdast::Node::Table(table) = node {
+    if let Some(info) = parse_notion_columns_table(table, columns_detection) {
+      let table = ConvertedTable {
+        line: node.position().map(|position| position.start.line),
+        num_of_columns: info.col_count,
+        num_of_rows: info.body_rows.len(),
+      };
+      debug!("table imported as columns: {}", table);
+      report.converted_tables.push(table);
+      return;
+    }
+  }
+  if let Some(children) = node.children() {
+    for child in children {
+      collect_converted_tables(child, columns_detection, report);
+    }
+  }
+}
+
+/// Return the width ratio given by the [COLUMN_WIDTH_RATIO_COMMENT] of a header cell.
+fn column_width_ratio_hint(cell: &mdast::TableCell) -> Option<f64> {
+  cell.children.iter().find_map(|node| {
+    let mdast::Node::Html(html) = node else {
+      return None;
+    };
+    let comment = html.value.trim().strip_prefix("<!--")?.strip_suffix("-->")?;
+    let (name, ratio) = comment.split_once(':')?;
+    if name.trim() != COLUMN_WIDTH_RATIO_COMMENT {
+      return None;
+    }
+    ratio.trim().parse::<f64>().ok()
+  })
 }
 
 fn is_table_cell_empty(cell: &mdast::TableCell) -> bool {
@@ -147,6 +650,14 @@ fn collect_cell_text(nodes: &[mdast::Node], out: &mut String) {
   }
 }
 
+/// The options of the importer used while converting the mdast nodes to blocks.
+#[derive(Clone, Copy)]
+struct ConvertOptions<'a> {
+  parse_options: &'a ParseOptions,
+  columns_detection: ColumnsDetection,
+  heading_policy: HeadingPolicy,
+}
+
 /// This function will recursively process the mdast node and convert it to document blocks
 /// The document blocks will be stored in the document data
 fn process_mdast_node(
@@ -156,7 +667,7 @@ fn process_mdast_node(
   block_id: Option<String>,
   list_type: Option<&str>,
   start_number: Option<u32>,
-  parse_options: &ParseOptions,
+  options: ConvertOptions,
 ) {
   // If the node is an inline node, process it as an inline node
   if is_inline_node(node) {
@@ -181,7 +692,7 @@ fn process_mdast_node(
       children,
       Some(&list_type),
       start_number,
-      parse_options,
+      options,
     );
     return;
   }
@@ -205,7 +716,7 @@ fn process_mdast_node(
   }
 
   if let mdast::Node::Table(table) = node {
-    if let Some(info) = parse_notion_columns_table(table) {
+    if let Some(info) = parse_notion_columns_table(table, options.columns_detection) {
       let id = block_id.unwrap_or_else(generate_id);
       let block = Block {
         id: id.clone(),
@@ -222,10 +733,16 @@ fn process_mdast_node(
 
       for col_index in 0..info.col_count {
         let column_id = generate_id();
+        let column_data = SimpleColumnData {
+          width_ratio: info
+            .width_ratios
+            .as_ref()
+            .and_then(|ratios| ratios.get(col_index).copied()),
+        };
         let column_block = Block {
           id: column_id.clone(),
           ty: BlockType::SimpleColumn.to_string(),
-          data: BlockData::new(),
+          data: column_data.into_block_data(),
           parent: id.clone(),
           children: column_id.clone(),
           external_id: Some(column_id.clone()),
@@ -253,7 +770,7 @@ fn process_mdast_node(
             &cell.children,
             None,
             None,
-            parse_options,
+            options,
           );
         }
       }
@@ -264,7 +781,14 @@ fn process_mdast_node(
   // Process other nodes as normal nodes
   let id = block_id.unwrap_or_else(generate_id);
 
-  let block = create_block(&id, node, parent_id.clone(), list_type, start_number);
+  let block = create_block(
+    &id,
+    node,
+    parent_id.clone(),
+    list_type,
+    start_number,
+    options.heading_policy,
+  );
 
   document_data.blocks.insert(id.clone(), block);
   ensure_children_map_entry(document_data, &id);
@@ -279,7 +803,7 @@ fn process_mdast_node(
         &root.children,
         None,
         start_number,
-        parse_options,
+        options,
       );
     },
     mdast::Node::Paragraph(para) => {
@@ -290,17 +814,28 @@ fn process_mdast_node(
         &para.children,
         None,
         start_number,
-        parse_options,
+        options,
       );
     },
     mdast::Node::Heading(heading) => {
+      // A heading demoted to a paragraph keeps its emphasis as bold text
+      let demoted;
+      let children = if options.heading_policy.heading_level(heading.depth).is_some() {
+        &heading.children[..]
+      } else {
+        demoted = [mdast::Node::Strong(mdast::Strong {
+          children: heading.children.clone(),
+          position: None,
+        })];
+        &demoted[..]
+      };
       process_mdast_node_children(
         document_data,
         Some(id.clone()),
-        &heading.children,
+        children,
         None,
         start_number,
-        parse_options,
+        options,
       );
     },
     // handle the blockquote and list item node
@@ -319,7 +854,7 @@ fn process_mdast_node(
               &para.children,
               None,
               start_number,
-              parse_options,
+              options,
             );
           }
 
@@ -330,11 +865,13 @@ fn process_mdast_node(
             rest,
             list_type,
             start_number,
-            parse_options,
+            options,
           );
         }
       }
     },
+    // the diagrams keep their source in the block data
+    mdast::Node::Code(code) if code_diagram_kind(code).is_some() => {},
     mdast::Node::Code(code) => {
       let mut delta = Delta::new();
       delta.insert(code.value.clone(), Vec::new());
@@ -350,7 +887,7 @@ fn process_mdast_node(
             row_index,
             &id,
             &table.align,
-            parse_options,
+            options,
           );
         }
       }
@@ -377,11 +914,12 @@ fn create_block(
   parent_id: Option<String>,
   list_type: Option<&str>,
   start_number: Option<u32>,
+  heading_policy: HeadingPolicy,
 ) -> Block {
   Block {
     id: id.to_string(),
-    ty: mdast_node_type_to_block_type(node, list_type),
-    data: mdast_node_to_block_data(node, start_number),
+    ty: mdast_node_type_to_block_type(node, list_type, heading_policy),
+    data: mdast_node_to_block_data(node, start_number, heading_policy),
     parent: parent_id.unwrap_or_default(),
     children: id.to_string(),
     external_id: Some(id.to_string()),
@@ -428,7 +966,7 @@ fn process_table_row(
   row_index: usize,
   table_id: &str,
   align: &[AlignKind],
-  parse_options: &ParseOptions,
+  options: ConvertOptions,
 ) {
   let row_id = generate_id();
   let row_block = create_simple_table_row_block(&row_id, table_id);
@@ -453,7 +991,7 @@ fn process_table_row(
         &cell_node.children,
         None,
         None,
-        parse_options,
+        options,
       );
     }
   }
@@ -472,6 +1010,7 @@ fn create_paragraph_block(document_data: &mut DocumentData, parent_id: &str) ->
     Some(parent_id.to_string()),
     None,
     None,
+    HeadingPolicy::default(),
   );
 
   document_data
@@ -488,13 +1027,10 @@ fn create_paragraph_block(document_data: &mut DocumentData, parent_id: &str) ->
 }
 
 pub fn create_image_block(block_id: &str, url: String, parent_id: &str) -> Block {
-  let mut data = BlockData::new();
-  data.insert(URL_FIELD.to_string(), url.into());
-  data.insert(IMAGE_TYPE_FIELD.to_string(), EXTERNAL_IMAGE_TYPE.into());
   Block {
     id: block_id.to_string(),
     ty: BlockType::Image.to_string(),
-    data,
+    data: Block::image(&url).data,
     parent: parent_id.to_string(),
     children: "".to_string(),
     external_id: None,
@@ -555,10 +1091,24 @@ fn process_mdast_node_children(
   children: &[mdast::Node],
   list_type: Option<&str>,
   start_number: Option<u32>,
-  parse_options: &ParseOptions,
+  options: ConvertOptions,
 ) {
   let mut idx = 0;
   while idx < children.len() {
+    // The text styled by html spans and marks is imported with the inline nodes around it
+    if is_inline_style_html(&children[idx]) {
+      let end = children[idx..]
+        .iter()
+        .position(|child| !is_inline_node(child) && !is_inline_style_html(child))
+        .map_or(children.len(), |pos| idx + pos);
+      if let Some(parent_id) = &parent_id {
+        let delta = process_children_inline(&children[idx..end], Vec::new());
+        insert_delta_to_text_map(document_data, parent_id, delta);
+      }
+      idx = end;
+      continue;
+    }
+
     if let mdast::Node::Html(html) = &children[idx] {
       let value = html.value.trim();
       if value == "</aside>" || value == "</details>" {
@@ -585,7 +1135,12 @@ fn process_mdast_node_children(
         document_data.blocks.insert(callout_id.clone(), block);
         update_children_map(document_data, parent_id.clone(), &callout_id);
 
-        insert_markdown_as_inline_delta(document_data, &callout_id, &callout.content, parse_options);
+        insert_markdown_as_inline_delta(
+          document_data,
+          &callout_id,
+          &callout.content,
+          options.parse_options,
+        );
 
         idx += 1;
         while idx < children.len() {
@@ -603,7 +1158,7 @@ fn process_mdast_node_children(
             None,
             list_type,
             start_number,
-            parse_options,
+            options,
           );
           idx += 1;
         }
@@ -626,11 +1181,16 @@ fn process_mdast_node_children(
 
         let mut summary_written = false;
         if let Some(details) = parse_details_html(value) {
-          insert_markdown_as_inline_delta(document_data, &toggle_id, &details.summary, parse_options);
+          insert_markdown_as_inline_delta(
+            document_data,
+            &toggle_id,
+            &details.summary,
+            options.parse_options,
+          );
           summary_written = true;
 
           if !details.body.trim().is_empty() {
-            if let Ok(inner_node) = to_mdast(&details.body, parse_options) {
+            if let Ok(inner_node) = to_mdast(&details.body, options.parse_options) {
               if let mdast::Node::Root(root) = inner_node {
                 process_mdast_node_children(
                   document_data,
@@ -638,7 +1198,7 @@ fn process_mdast_node_children(
                   &root.children,
                   None,
                   None,
-                  parse_options,
+                  options,
                 );
               }
             }
@@ -656,12 +1216,17 @@ fn process_mdast_node_children(
 
             if !summary_written && v.starts_with("<summary>") {
               if let Some((summary, rest)) = extract_tag_content(v, "summary") {
-                insert_markdown_as_inline_delta(document_data, &toggle_id, &summary, parse_options);
+                insert_markdown_as_inline_delta(
+                  document_data,
+                  &toggle_id,
+                  &summary,
+                  options.parse_options,
+                );
                 summary_written = true;
 
                 let body = rest.trim();
                 if !body.is_empty() {
-                  if let Ok(inner_node) = to_mdast(body, parse_options) {
+                  if let Ok(inner_node) = to_mdast(body, options.parse_options) {
                     if let mdast::Node::Root(root) = inner_node {
                       process_mdast_node_children(
                         document_data,
@@ -669,7 +1234,7 @@ fn process_mdast_node_children(
                         &root.children,
                         None,
                         None,
-                        parse_options,
+                        options,
                       );
                     }
                   }
@@ -687,7 +1252,7 @@ fn process_mdast_node_children(
             None,
             list_type,
             start_number,
-            parse_options,
+            options,
           );
           idx += 1;
         }
@@ -702,7 +1267,7 @@ fn process_mdast_node_children(
       None,
       list_type,
       start_number,
-      parse_options,
+      options,
     );
     idx += 1;
   }
@@ -0,0 +1,273 @@
+use crate::blocks::{BlockType, DocumentData};
+use crate::importer::define::{
+  FILE_NAME_FIELD, HREF_ATTR, MEDIA_NAME_FIELD, MEDIA_PROVIDER_FIELD, MEDIA_PROVIDER_ID_FIELD,
+  URL_FIELD,
+};
+use serde_json::Value;
+
+const VIDEO_EXTENSIONS: [&str; 6] = ["mp4", "webm", "mov", "m4v", "ogv", "mkv"];
+const AUDIO_EXTENSIONS: [&str; 8] = ["mp3", "wav", "ogg", "oga", "m4a", "flac", "aac", "opus"];
+const IMAGE_EXTENSIONS: [&str; 9] = [
+  "png", "jpg", "jpeg", "gif", "webp", "svg", "bmp", "heic", "avif",
+];
+/// The extensions of the exported pages, a link to them is a link to another page.
+const PAGE_EXTENSIONS: [&str; 4] = ["md", "csv", "html", "htm"];
+
+/// The kind of media a link points at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKind {
+  Video,
+  Audio,
+}
+
+impl MediaKind {
+  pub fn block_type(&self) -> BlockType {
+    match self {
+      MediaKind::Video => BlockType::Video,
+      MediaKind::Audio => BlockType::Audio,
+    }
+  }
+}
+
+/// Where the media is hosted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MediaProvider {
+  /// A video or audio file, e.g. one exported with a Notion page.
+  File,
+  YouTube {
+    video_id: String,
+  },
+  Vimeo {
+    video_id: String,
+  },
+}
+
+impl MediaProvider {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      MediaProvider::File => "file",
+      MediaProvider::YouTube { .. } => "youtube",
+      MediaProvider::Vimeo { .. } => "vimeo",
+    }
+  }
+
+  /// The id of the media on the provider, None for a file.
+  pub fn media_id(&self) -> Option<&str> {
+    match self {
+      MediaProvider::File => None,
+      MediaProvider::YouTube { video_id } | MediaProvider::Vimeo { video_id } => Some(video_id),
+    }
+  }
+}
+
+/// A link to a video or an audio file, imported as a video or audio block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaLink {
+  pub kind: MediaKind,
+  pub provider: MediaProvider,
+}
+
+impl MediaLink {
+  /// Recognize the links to the video and audio files by their extension, and the links to the
+  /// YouTube and Vimeo videos.
+  pub fn from_url(url: &str) -> Option<Self> {
+    let url = url.trim();
+    let http_url = split_http_url(url);
+    let path = match &http_url {
+      Some((_, path, _)) => path,
+      None => url.split(['?', '#']).next().unwrap_or_default(),
+    };
+    let file_name = path.rsplit('/').next().unwrap_or_default();
+    if let Some((_, extension)) = file_name.rsplit_once('.') {
+      let extension = extension.to_lowercase();
+      if VIDEO_EXTENSIONS.contains(&extension.as_str()) {
+        return Some(Self::file(MediaKind::Video));
+      }
+      if AUDIO_EXTENSIONS.contains(&extension.as_str()) {
+        return Some(Self::file(MediaKind::Audio));
+      }
+    }
+
+    let (host, path, query) = http_url?;
+    let provider = match host.as_str() {
+      "youtube.com" | "m.youtube.com" | "music.youtube.com" | "youtube-nocookie.com" => {
+        let video_id = match path.split_once('/') {
+          Some(("embed" | "shorts" | "live" | "v", rest)) => first_segment(rest),
+          _ if path == "watch" => query_param(query, "v"),
+          _ => None,
+        }?;
+        MediaProvider::YouTube { video_id }
+      },
+      "youtu.be" => MediaProvider::YouTube {
+        video_id: first_segment(path)?,
+      },
+      "vimeo.com" | "player.vimeo.com" => {
+        let video_id = path
+          .split('/')
+          .find(|segment| !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()))?;
+        MediaProvider::Vimeo {
+          video_id: video_id.to_string(),
+        }
+      },
+      _ => return None,
+    };
+    Some(Self {
+      kind: MediaKind::Video,
+      provider,
+    })
+  }
+
+  fn file(kind: MediaKind) -> Self {
+    Self {
+      kind,
+      provider: MediaProvider::File,
+    }
+  }
+}
+
+/// Convert the paragraphs that only contain a link to a video or an audio file to video and
+/// audio blocks. The text of the link is kept as the name of the block when it's not the url.
+pub(crate) fn convert_media_links(document_data: &mut DocumentData) {
+  let Some(text_map) = document_data.meta.text_map.as_mut() else {
+    return;
+  };
+  for block in document_data.blocks.values_mut() {
+    if block.ty != BlockType::Paragraph.as_str() {
+      continue;
+    }
+    let Some((name, url)) = block
+      .external_id
+      .as_ref()
+      .and_then(|text_id| text_map.get(text_id))
+      .and_then(|delta| single_link_from_delta(delta))
+    else {
+      continue;
+    };
+    let Some(media) = MediaLink::from_url(&url) else {
+      continue;
+    };
+
+    if let Some(text_id) = block.external_id.take() {
+      text_map.remove(&text_id);
+    }
+    block.external_type = None;
+    block.ty = media.kind.block_type().to_string();
+    block
+      .data
+      .insert(URL_FIELD.to_string(), Value::String(url.clone()));
+    block.data.insert(
+      MEDIA_PROVIDER_FIELD.to_string(),
+      Value::String(media.provider.as_str().to_string()),
+    );
+    if let Some(media_id) = media.provider.media_id() {
+      block.data.insert(
+        MEDIA_PROVIDER_ID_FIELD.to_string(),
+        Value::String(media_id.to_string()),
+      );
+    }
+    if name != url {
+      block
+        .data
+        .insert(MEDIA_NAME_FIELD.to_string(), Value::String(name));
+    }
+  }
+}
+
+/// Convert the paragraphs that only contain a link to a local file, e.g. a pdf exported in the
+/// asset folder of a Notion page, to file blocks. The text of the link, the original name of the
+/// file in a Notion export, is kept as the name of the block.
+///
+/// The links to images, video and audio files, and to the other exported pages are kept.
+pub(crate) fn convert_file_links(document_data: &mut DocumentData) {
+  let Some(text_map) = document_data.meta.text_map.as_mut() else {
+    return;
+  };
+  for block in document_data.blocks.values_mut() {
+    if block.ty != BlockType::Paragraph.as_str() {
+      continue;
+    }
+    let Some((name, url)) = block
+      .external_id
+      .as_ref()
+      .and_then(|text_id| text_map.get(text_id))
+      .and_then(|delta| single_link_from_delta(delta))
+    else {
+      continue;
+    };
+    if !is_local_file_link(&url) {
+      continue;
+    }
+
+    if let Some(text_id) = block.external_id.take() {
+      text_map.remove(&text_id);
+    }
+    block.external_type = None;
+    block.ty = BlockType::File.to_string();
+    block.data.insert(URL_FIELD.to_string(), Value::String(url));
+    block
+      .data
+      .insert(FILE_NAME_FIELD.to_string(), Value::String(name));
+  }
+}
+
+/// Whether the url is a relative path to a file that isn't an image, a video, an audio file or
+/// an exported page.
+fn is_local_file_link(url: &str) -> bool {
+  if url.contains("://") || url.starts_with("mailto:") || url.starts_with('#') {
+    return false;
+  }
+  let path = url.split(['?', '#']).next().unwrap_or_default();
+  let file_name = path.rsplit('/').next().unwrap_or_default();
+  let Some((stem, extension)) = file_name.rsplit_once('.') else {
+    return false;
+  };
+  let extension = extension.to_lowercase();
+  !stem.is_empty()
+    && !extension.is_empty()
+    && !IMAGE_EXTENSIONS.contains(&extension.as_str())
+    && !PAGE_EXTENSIONS.contains(&extension.as_str())
+    && MediaLink::from_url(url).is_none()
+}
+
+/// Return the text and the href of a delta made of a single link.
+pub(crate) fn single_link_from_delta(delta: &str) -> Option<(String, String)> {
+  let ops: Vec<Value> = serde_json::from_str(delta).ok()?;
+  let [op] = ops.as_slice() else {
+    return None;
+  };
+  let text = op.get("insert")?.as_str()?.trim();
+  let href = op.get("attributes")?.get(HREF_ATTR)?.as_str()?.trim();
+  if text.is_empty() || href.is_empty() {
+    return None;
+  }
+  Some((text.to_string(), href.to_string()))
+}
+
+/// Return the lowercase host without `www.`, the path without its leading slash and the query of
+/// an http(s) url.
+pub(crate) fn split_http_url(url: &str) -> Option<(String, &str, &str)> {
+  let rest = url
+    .strip_prefix("https://")
+    .or_else(|| url.strip_prefix("http://"))?;
+  let rest = rest.split('#').next().unwrap_or_default();
+  let (rest, query) = rest.split_once('?').unwrap_or((rest, ""));
+  let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+  let host = host.to_lowercase();
+  let host = host
+    .strip_prefix("www.")
+    .map(str::to_string)
+    .unwrap_or(host);
+  Some((host, path, query))
+}
+
+pub(crate) fn first_segment(path: &str) -> Option<String> {
+  let segment = path.split('/').next()?;
+  (!segment.is_empty()).then(|| segment.to_string())
+}
+
+pub(crate) fn query_param(query: &str, name: &str) -> Option<String> {
+  query.split('&').find_map(|pair| {
+    let (key, value) = pair.split_once('=')?;
+    (key == name && !value.is_empty()).then(|| value.to_string())
+  })
+}
@@ -1,4 +1,19 @@
+pub mod columns;
+mod date_mention;
 pub mod define;
 mod delta;
+pub mod embed;
+pub mod emoji;
+pub mod filter;
+pub mod fragment;
+pub mod guard;
+pub mod heading;
+pub mod html;
+mod inline_style;
+pub mod media;
 pub mod md_importer;
+pub mod report;
+pub mod sync;
+pub mod typography;
 mod util;
+pub mod whitespace;
@@ -0,0 +1,369 @@
+use crate::importer::inline_style::parse_inline_style_tag;
+use crate::importer::util::is_inline_node;
+use markdown::mdast;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// The kind of formatting that can't be represented by the document blocks and is dropped or
+/// degraded when importing markdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FormattingLossKind {
+  /// `<u>` or `<ins>` tags. The text is kept, the underline is dropped.
+  Underline,
+  /// `<sup>` tags. The text is kept, the superscript is dropped.
+  Superscript,
+  /// `<sub>` tags. The text is kept, the subscript is dropped.
+  Subscript,
+  /// `<mark>` tags. The text is kept, the highlight is dropped.
+  Highlight,
+  /// Hard line breaks (`<br>`, trailing backslash or two trailing spaces).
+  LineBreak,
+  /// Raw html inside a paragraph that is imported as plain text or dropped.
+  InlineHtml,
+  /// Raw html block that is imported as plain text.
+  HtmlBlock,
+  /// A table inside a table. The inner table is flattened into plain text.
+  NestedTableFlattened,
+  /// Footnote references and definitions. The footnote is imported as plain text.
+  Footnote,
+  /// The alt text of an image.
+  ImageAltText,
+  /// The title of a link or an image.
+  Title,
+  /// The info string after the language of a fenced code block.
+  CodeMetadata,
+  /// Reference style links and images. The definition is not resolved.
+  ReferenceLink,
+  /// Content that is dropped entirely, e.g. a list item that starts with a code block.
+  DroppedContent,
+  /// The markdown couldn't be parsed. The content is imported as plain text paragraphs.
+  ParseFailure,
+}
+
+impl FormattingLossKind {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      FormattingLossKind::Underline => "underline",
+      FormattingLossKind::Superscript => "superscript",
+      FormattingLossKind::Subscript => "subscript",
+      FormattingLossKind::Highlight => "highlight",
+      FormattingLossKind::LineBreak => "line_break",
+      FormattingLossKind::InlineHtml => "inline_html",
+      FormattingLossKind::HtmlBlock => "html_block",
+      FormattingLossKind::NestedTableFlattened => "nested_table_flattened",
+      FormattingLossKind::Footnote => "footnote",
+      FormattingLossKind::ImageAltText => "image_alt_text",
+      FormattingLossKind::Title => "title",
+      FormattingLossKind::CodeMetadata => "code_metadata",
+      FormattingLossKind::ReferenceLink => "reference_link",
+      FormattingLossKind::DroppedContent => "dropped_content",
+      FormattingLossKind::ParseFailure => "parse_failure",
+    }
+  }
+}
+
+impl Display for FormattingLossKind {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+/// A single piece of formatting that was lost while importing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormattingLoss {
+  pub kind: FormattingLossKind,
+  /// The 1-based line in the source markdown, if known.
+  pub line: Option<usize>,
+  /// The 1-based column in the source markdown, if known.
+  pub column: Option<usize>,
+  /// A human readable description, e.g. "underline dropped".
+  pub detail: String,
+}
+
+impl Display for FormattingLoss {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self.line {
+      Some(line) => write!(f, "{} at line {}", self.detail, line),
+      None => f.write_str(&self.detail),
+    }
+  }
+}
+
+/// A table imported as columns, see [crate::importer::columns::ColumnsDetection].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConvertedTable {
+  /// The 1-based line in the source markdown, if known.
+  pub line: Option<usize>,
+  pub num_of_columns: usize,
+  /// The number of rows, the header row excluded.
+  pub num_of_rows: usize,
+}
+
+impl Display for ConvertedTable {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "table of {} columns and {} rows",
+      self.num_of_columns, self.num_of_rows
+    )?;
+    if let Some(line) = self.line {
+      write!(f, " at line {}", line)?;
+    }
+    Ok(())
+  }
+}
+
+/// The formatting losses collected while importing a markdown document, in source order.
+#[derive(Debug, Clone, Default)]
+pub struct FormattingLossReport {
+  pub losses: Vec<FormattingLoss>,
+  /// The tables imported as columns, in source order. They are not losses, but a table whose
+  /// header was left empty may be imported as columns by mistake.
+  pub converted_tables: Vec<ConvertedTable>,
+}
+
+impl FormattingLossReport {
+  /// Return true if nothing was dropped.
+  pub fn is_lossless(&self) -> bool {
+    self.losses.is_empty()
+  }
+
+  pub fn len(&self) -> usize {
+    self.losses.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.losses.is_empty()
+  }
+
+  /// The number of losses of the given kind.
+  pub fn count(&self, kind: FormattingLossKind) -> usize {
+    self.losses.iter().filter(|loss| loss.kind == kind).count()
+  }
+
+  /// The number of losses per kind. Kinds without any loss are not included.
+  pub fn counts(&self) -> BTreeMap<FormattingLossKind, usize> {
+    let mut counts = BTreeMap::new();
+    for loss in self.losses.iter() {
+      *counts.entry(loss.kind).or_insert(0) += 1;
+    }
+    counts
+  }
+
+  pub fn iter_kind(&self, kind: FormattingLossKind) -> impl Iterator<Item = &FormattingLoss> {
+    self.losses.iter().filter(move |loss| loss.kind == kind)
+  }
+
+  fn push(&mut self, kind: FormattingLossKind, node: &mdast::Node, detail: impl Into<String>) {
+    let start = node.position().map(|position| &position.start);
+    self.losses.push(FormattingLoss {
+      kind,
+      line: start.map(|point| point.line),
+      column: start.map(|point| point.column),
+      detail: detail.into(),
+    });
+  }
+}
+
+#[derive(Clone, Copy, Default)]
+struct LossContext {
+  in_table: bool,
+  in_paragraph: bool,
+}
+
+/// Walk the markdown ast and record the formatting that the
+/// [MDImporter](crate::importer::md_importer::MDImporter) can't represent. The rules mirror how
+/// the importer converts each node.
+pub(crate) fn collect_formatting_losses(node: &mdast::Node, report: &mut FormattingLossReport) {
+  visit_node(node, LossContext::default(), report);
+}
+
+fn visit_node(node: &mdast::Node, ctx: LossContext, report: &mut FormattingLossReport) {
+  match node {
+    mdast::Node::Html(html) => record_html(node, &html.value, ctx, report),
+    mdast::Node::Break(_) => {
+      report.push(FormattingLossKind::LineBreak, node, "line break dropped");
+    },
+    mdast::Node::FootnoteReference(reference) => {
+      report.push(
+        FormattingLossKind::Footnote,
+        node,
+        format!(
+          "footnote reference [^{}] imported as plain text",
+          reference.identifier
+        ),
+      );
+    },
+    mdast::Node::FootnoteDefinition(definition) => {
+      // The definition is imported as a plain paragraph, its children are not processed.
+      report.push(
+        FormattingLossKind::Footnote,
+        node,
+        format!(
+          "footnote definition [^{}] imported as plain text",
+          definition.identifier
+        ),
+      );
+    },
+    mdast::Node::Image(image) => {
+      if !image.alt.is_empty() {
+        report.push(
+          FormattingLossKind::ImageAltText,
+          node,
+          format!("image alt text \"{}\" dropped", image.alt),
+        );
+      }
+      if image.title.is_some() {
+        report.push(FormattingLossKind::Title, node, "image title dropped");
+      }
+    },
+    mdast::Node::Link(link) => {
+      if link.title.is_some() {
+        report.push(FormattingLossKind::Title, node, "link title dropped");
+      }
+      visit_children(&link.children, ctx, report);
+    },
+    mdast::Node::Code(code) => {
+      if let Some(meta) = &code.meta {
+        report.push(
+          FormattingLossKind::CodeMetadata,
+          node,
+          format!("code block metadata \"{}\" dropped", meta),
+        );
+      }
+    },
+    mdast::Node::LinkReference(reference) => {
+      report.push(
+        FormattingLossKind::ReferenceLink,
+        node,
+        format!("reference link [{}] not resolved", reference.identifier),
+      );
+    },
+    mdast::Node::ImageReference(reference) => {
+      report.push(
+        FormattingLossKind::ReferenceLink,
+        node,
+        format!("reference image [{}] not resolved", reference.identifier),
+      );
+    },
+    mdast::Node::Table(table) => {
+      let ctx = LossContext {
+        in_table: true,
+        ..ctx
+      };
+      visit_children(&table.children, ctx, report);
+    },
+    mdast::Node::Paragraph(para) => {
+      let ctx = LossContext {
+        in_paragraph: true,
+        ..ctx
+      };
+      visit_children(&para.children, ctx, report);
+    },
+    mdast::Node::Heading(heading) => {
+      let ctx = LossContext {
+        in_paragraph: true,
+        ..ctx
+      };
+      visit_children(&heading.children, ctx, report);
+    },
+    mdast::Node::Blockquote(_) | mdast::Node::ListItem(_) => {
+      let children = node.children().map(|c| c.as_slice()).unwrap_or_default();
+      // The importer uses the first child as the content of the block only if it's a paragraph,
+      // any other first child is skipped.
+      if let Some((first, rest)) = children.split_first() {
+        if matches!(first, mdast::Node::Paragraph(_)) {
+          visit_node(first, ctx, report);
+        } else {
+          report.push(
+            FormattingLossKind::DroppedContent,
+            first,
+            format!("{} dropped", node_name(first)),
+          );
+        }
+        visit_children(rest, ctx, report);
+      }
+    },
+    _ => {
+      if let Some(children) = node.children() {
+        visit_children(children, ctx, report);
+      }
+    },
+  }
+}
+
+fn visit_children(children: &[mdast::Node], ctx: LossContext, report: &mut FormattingLossReport) {
+  for child in children {
+    visit_node(child, ctx, report);
+  }
+}
+
+fn record_html(
+  node: &mdast::Node,
+  value: &str,
+  ctx: LossContext,
+  report: &mut FormattingLossReport,
+) {
+  let value = value.trim();
+  let Some(tag) = html_tag_name(value) else {
+    return;
+  };
+
+  // Closing tags are not counted, the opening tag already recorded the loss.
+  if value.starts_with("</") {
+    return;
+  }
+
+  let kind = match tag.as_str() {
+    // Callouts and toggles are converted by the importer.
+    "aside" | "details" | "summary" => return,
+    // The inline styles, e.g. colors or underlines, are converted to text attributes.
+    _ if ctx.in_paragraph && parse_inline_style_tag(value).is_some() => return,
+    "u" | "ins" => FormattingLossKind::Underline,
+    "sup" => FormattingLossKind::Superscript,
+    "sub" => FormattingLossKind::Subscript,
+    "mark" => FormattingLossKind::Highlight,
+    "br" => FormattingLossKind::LineBreak,
+    "table" if ctx.in_table => FormattingLossKind::NestedTableFlattened,
+    // The rest of a nested table is part of the loss recorded for its <table> tag.
+    "thead" | "tbody" | "tr" | "td" | "th" if ctx.in_table => return,
+    _ if ctx.in_paragraph => FormattingLossKind::InlineHtml,
+    _ => FormattingLossKind::HtmlBlock,
+  };
+
+  let detail = match kind {
+    FormattingLossKind::NestedTableFlattened => "nested table flattened".to_string(),
+    FormattingLossKind::InlineHtml | FormattingLossKind::HtmlBlock => {
+      format!("html <{}> imported as plain text", tag)
+    },
+    _ => format!("{} dropped", kind.as_str().replace('_', " ")),
+  };
+  report.push(kind, node, detail);
+}
+
+/// Return the lowercase tag name of the first html tag in the value.
+fn html_tag_name(value: &str) -> Option<String> {
+  let rest = value.strip_prefix('<')?;
+  let rest = rest.strip_prefix('/').unwrap_or(rest);
+  let name = rest
+    .chars()
+    .take_while(|c| c.is_ascii_alphanumeric())
+    .collect::<String>()
+    .to_lowercase();
+  if name.is_empty() { None } else { Some(name) }
+}
+
+fn node_name(node: &mdast::Node) -> &'static str {
+  match node {
+    mdast::Node::Code(_) => "code block",
+    mdast::Node::Table(_) => "table",
+    mdast::Node::Heading(_) => "heading",
+    mdast::Node::List(_) => "list",
+    mdast::Node::Blockquote(_) => "quote",
+    mdast::Node::Math(_) => "math block",
+    mdast::Node::Html(_) => "html block",
+    mdast::Node::ThematicBreak(_) => "divider",
+    _ if is_inline_node(node) => "inline content",
+    _ => "block",
+  }
+}
@@ -0,0 +1,423 @@
+use crate::blocks::{Block, DocumentData, deserialize_text_delta};
+use crate::document::Document;
+use crate::error::DocumentError;
+use crate::importer::fragment::DocumentFragment;
+use serde_json::{Map, Value};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use tracing::trace;
+
+/// The blocks changed by [crate::importer::md_importer::MDImporter::sync_into].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MarkdownSyncReport {
+  /// The ids of the inserted blocks. Their children are inserted with them and not listed.
+  pub inserted: Vec<String>,
+  /// The ids of the blocks whose data or text is updated in place.
+  pub updated: Vec<String>,
+  /// The ids of the deleted blocks. Their children are deleted with them and not listed.
+  pub deleted: Vec<String>,
+}
+
+impl MarkdownSyncReport {
+  pub fn is_unchanged(&self) -> bool {
+    self.inserted.is_empty() && self.updated.is_empty() && self.deleted.is_empty()
+  }
+}
+
+/// A block-level change turning the blocks of the document into the imported blocks.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SyncOperation {
+  Delete {
+    block_id: String,
+  },
+  Update {
+    block_id: String,
+    data: Option<HashMap<String, Value>>,
+    /// The new delta json of the text of the block.
+    delta: Option<String>,
+  },
+  /// Insert a block and its descendants after `prev_id`, or at the first position if it's None.
+  Insert {
+    prev_id: Option<String>,
+    fragment: DocumentFragment,
+  },
+}
+
+/// Apply the minimal block-level changes turning the blocks of the document into the blocks of
+/// the data. The page block of the document is kept.
+pub(crate) fn sync_document_data(
+  document: &mut Document,
+  data: &DocumentData,
+) -> Result<MarkdownSyncReport, DocumentError> {
+  let current = document.get_document_data()?;
+  let operations = diff_document_data(&current, data);
+  let mut report = MarkdownSyncReport::default();
+  for operation in operations {
+    trace!("sync markdown: {:?}", operation);
+    match operation {
+      SyncOperation::Delete { block_id } => {
+        document.delete_block(&block_id)?;
+        report.deleted.push(block_id);
+      },
+      SyncOperation::Update {
+        block_id,
+        data,
+        delta,
+      } => {
+        if let Some(data) = data {
+          document.update_block(&block_id, data)?;
+        }
+        if let Some(delta) = delta {
+          let delta = deserialize_text_delta(&delta)
+            .map_err(|_| DocumentError::ParseDeltaJsonToTextDeltaError)?;
+          document.set_block_delta(&block_id, delta)?;
+        }
+        report.updated.push(block_id);
+      },
+      SyncOperation::Insert { prev_id, fragment } => {
+        let ids = document.insert_fragment(fragment, prev_id)?;
+        report.inserted.extend(ids);
+      },
+    }
+  }
+  Ok(report)
+}
+
+/// Diff the children of the pages of the documents, see [BlockDiff::diff_children].
+pub(crate) fn diff_document_data(old: &DocumentData, new: &DocumentData) -> Vec<SyncOperation> {
+  let mut diff = BlockDiff {
+    old: DocumentSide::new(old),
+    new: DocumentSide::new(new),
+    operations: vec![],
+  };
+  diff.diff_children(&old.page_id, &new.page_id);
+  diff.operations
+}
+
+struct BlockDiff<'a> {
+  old: DocumentSide<'a>,
+  new: DocumentSide<'a>,
+  operations: Vec<SyncOperation>,
+}
+
+impl BlockDiff<'_> {
+  /// The identical children are matched in order, they are kept as is. Between two of them, a
+  /// new child is paired with the next old child of the same type, which is updated in place and
+  /// whose children are diffed the same way. The other old children are deleted and the other
+  /// new children inserted.
+  fn diff_children(&mut self, old_parent_id: &str, new_parent_id: &str) {
+    let old_ids = self.old.children_ids(old_parent_id);
+    let new_ids = self.new.children_ids(new_parent_id);
+    let old_fingerprints = old_ids
+      .iter()
+      .map(|id| self.old.fingerprint(id))
+      .collect::<Vec<_>>();
+    let new_fingerprints = new_ids
+      .iter()
+      .map(|id| self.new.fingerprint(id))
+      .collect::<Vec<_>>();
+    let anchors = longest_common_subsequence(&old_fingerprints, &new_fingerprints);
+
+    // The new children and the old child each one is kept as or updated from, if any.
+    let mut matches: Vec<(usize, Option<usize>)> = Vec::with_capacity(new_ids.len());
+    let mut old_start = 0;
+    let mut new_start = 0;
+    let sentinel = (old_ids.len(), new_ids.len());
+    for (old_end, new_end) in anchors.into_iter().chain(std::iter::once(sentinel)) {
+      let mut next_old = old_start;
+      for new_index in new_start..new_end {
+        let paired = (next_old..old_end).find(|old_index| {
+          self
+            .old
+            .can_update_from(&old_ids[*old_index], &self.new, &new_ids[new_index])
+        });
+        if let Some(old_index) = paired {
+          next_old = old_index + 1;
+        }
+        matches.push((new_index, paired));
+      }
+      if new_end < new_ids.len() {
+        matches.push((new_end, Some(old_end)));
+      }
+      old_start = old_end + 1;
+      new_start = new_end + 1;
+    }
+
+    let kept = matches
+      .iter()
+      .filter_map(|(_, old_index)| *old_index)
+      .collect::<HashSet<_>>();
+    for (old_index, old_id) in old_ids.iter().enumerate() {
+      if !kept.contains(&old_index) {
+        self.operations.push(SyncOperation::Delete {
+          block_id: old_id.clone(),
+        });
+      }
+    }
+
+    let mut prev_id: Option<String> = None;
+    for (new_index, old_index) in matches {
+      let new_id = &new_ids[new_index];
+      match old_index {
+        Some(old_index) => {
+          let old_id = &old_ids[old_index];
+          if self.old.fingerprint(old_id) != self.new.fingerprint(new_id) {
+            self.update_block(old_id, new_id);
+            self.diff_children(old_id, new_id);
+          }
+          prev_id = Some(old_id.clone());
+        },
+        None => {
+          self.operations.push(SyncOperation::Insert {
+            prev_id: prev_id.clone(),
+            fragment: self.new.subtree_fragment(new_id, old_parent_id),
+          });
+          prev_id = Some(new_id.clone());
+        },
+      }
+    }
+  }
+
+  fn update_block(&mut self, old_id: &str, new_id: &str) {
+    let (Some(old_block), Some(new_block)) = (
+      self.old.data.blocks.get(old_id),
+      self.new.data.blocks.get(new_id),
+    ) else {
+      return;
+    };
+    // The fields that markdown can't represent, e.g. the colors, are kept.
+    let mut data = old_block.data.clone();
+    data.extend(new_block.data.clone());
+    let data = (data != old_block.data).then_some(data);
+    let new_delta = self.new.delta(new_id);
+    let delta = (new_delta != self.old.delta(old_id)).then(|| Value::Array(new_delta).to_string());
+    if data.is_some() || delta.is_some() {
+      self.operations.push(SyncOperation::Update {
+        block_id: old_id.to_string(),
+        data,
+        delta,
+      });
+    }
+  }
+}
+
+struct DocumentSide<'a> {
+  data: &'a DocumentData,
+  fingerprints: HashMap<String, u64>,
+}
+
+impl<'a> DocumentSide<'a> {
+  fn new(data: &'a DocumentData) -> Self {
+    Self {
+      data,
+      fingerprints: HashMap::new(),
+    }
+  }
+
+  /// The ids of the children of the block. The children missing from the blocks, see
+  /// [crate::invariants::InvariantViolation::MissingChild], are skipped.
+  fn children_ids(&self, block_id: &str) -> Vec<String> {
+    self
+      .data
+      .blocks
+      .get(block_id)
+      .and_then(|block| self.data.meta.children_map.get(&block.children))
+      .map(|children_ids| {
+        children_ids
+          .iter()
+          .filter(|child_id| self.data.blocks.contains_key(*child_id))
+          .cloned()
+          .collect()
+      })
+      .unwrap_or_default()
+  }
+
+  /// The normalized delta of the text of the block, empty if the block has no text.
+  fn delta(&self, block_id: &str) -> Vec<Value> {
+    let delta = self
+      .data
+      .blocks
+      .get(block_id)
+      .and_then(|block| block.external_id.as_ref())
+      .and_then(|external_id| self.data.meta.text_map.as_ref()?.get(external_id))
+      .and_then(|delta| serde_json::from_str::<Vec<Value>>(delta).ok())
+      .unwrap_or_default();
+    normalize_delta(delta)
+  }
+
+  /// A hash of the type, the data, the text and the children of the block.
+  fn fingerprint(&mut self, block_id: &str) -> u64 {
+    if let Some(fingerprint) = self.fingerprints.get(block_id) {
+      return *fingerprint;
+    }
+    let Some(block) = self.data.blocks.get(block_id) else {
+      return 0;
+    };
+    let mut hasher = DefaultHasher::new();
+    block.ty.hash(&mut hasher);
+    let data = block
+      .data
+      .iter()
+      .map(|(key, value)| (key.clone(), value.clone()))
+      .collect::<Map<_, _>>();
+    Value::Object(data).to_string().hash(&mut hasher);
+    Value::Array(self.delta(block_id))
+      .to_string()
+      .hash(&mut hasher);
+    for child_id in self.children_ids(block_id) {
+      self.fingerprint(&child_id).hash(&mut hasher);
+    }
+    let fingerprint = hasher.finish();
+    self.fingerprints.insert(block_id.to_string(), fingerprint);
+    fingerprint
+  }
+
+  /// Whether the block can be updated in place into the new block. A text can't be cleared with
+  /// [Document::set_block_delta], so a block whose text is cleared is replaced.
+  fn can_update_from(&self, block_id: &str, new: &DocumentSide, new_block_id: &str) -> bool {
+    let (Some(block), Some(new_block)) = (
+      self.data.blocks.get(block_id),
+      new.data.blocks.get(new_block_id),
+    ) else {
+      return false;
+    };
+    if block.ty != new_block.ty {
+      return false;
+    }
+    let new_delta = new.delta(new_block_id);
+    if new_delta.is_empty() {
+      return self.delta(block_id).is_empty();
+    }
+    block.external_id.is_some() || new_block.external_id.is_none()
+  }
+
+  /// The block and its descendants, as a fragment inserted into the parent.
+  fn subtree_fragment(&self, block_id: &str, parent_id: &str) -> DocumentFragment {
+    let mut fragment = DocumentFragment {
+      parent_id: parent_id.to_string(),
+      top_level_ids: vec![block_id.to_string()],
+      ..Default::default()
+    };
+    let mut stack = vec![(block_id.to_string(), parent_id.to_string())];
+    while let Some((block_id, parent_id)) = stack.pop() {
+      let Some(block) = self.data.blocks.get(&block_id) else {
+        continue;
+      };
+      if let Some(external_id) = &block.external_id {
+        if let Some(delta) = self
+          .data
+          .meta
+          .text_map
+          .as_ref()
+          .and_then(|text_map| text_map.get(external_id))
+        {
+          fragment.text_map.insert(external_id.clone(), delta.clone());
+        }
+      }
+      let children_ids = self.children_ids(&block_id);
+      fragment.blocks.push(Block {
+        parent: parent_id,
+        ..block.clone()
+      });
+      // The children come after their parent and after their previous sibling.
+      stack.extend(
+        children_ids
+          .into_iter()
+          .rev()
+          .map(|child_id| (child_id, block_id.clone())),
+      );
+    }
+    fragment
+  }
+}
+
+/// Merge the adjacent inserts with the same attributes, the texts written by the importer and
+/// the texts read from the document don't split the inserts the same way.
+fn normalize_delta(delta: Vec<Value>) -> Vec<Value> {
+  let mut normalized: Vec<Value> = Vec::with_capacity(delta.len());
+  for mut op in delta {
+    let Some(insert) = op.get("insert").and_then(Value::as_str).map(str::to_string) else {
+      normalized.push(op);
+      continue;
+    };
+    if let Some(object) = op.as_object_mut() {
+      let is_empty = object.get("attributes").is_none_or(|attributes| {
+        attributes.is_null() || attributes.as_object().is_some_and(Map::is_empty)
+      });
+      if is_empty {
+        object.remove("attributes");
+      }
+    }
+    if let Some(last) = normalized.last_mut() {
+      if last.get("attributes") == op.get("attributes") {
+        if let Some(Value::String(text)) = last.get_mut("insert") {
+          text.push_str(&insert);
+          continue;
+        }
+      }
+    }
+    normalized.push(op);
+  }
+  normalized
+}
+
+/// The indexes of the pairs of equal items of the longest common subsequence, in order.
+///
+/// The common prefix and suffix are matched first, so the quadratic table only covers the
+/// changed middle of the lists, which is small for the usual edits of a long document.
+fn longest_common_subsequence(old: &[u64], new: &[u64]) -> Vec<(usize, usize)> {
+  let prefix = old
+    .iter()
+    .zip(new)
+    .take_while(|(old, new)| old == new)
+    .count();
+  let suffix = old[prefix..]
+    .iter()
+    .rev()
+    .zip(new[prefix..].iter().rev())
+    .take_while(|(old, new)| old == new)
+    .count();
+  let old_middle = &old[prefix..old.len() - suffix];
+  let new_middle = &new[prefix..new.len() - suffix];
+
+  let mut pairs = (0..prefix).map(|index| (index, index)).collect::<Vec<_>>();
+  pairs.extend(
+    middle_longest_common_subsequence(old_middle, new_middle)
+      .into_iter()
+      .map(|(i, j)| (prefix + i, prefix + j)),
+  );
+  let (old_suffix_start, new_suffix_start) = (old.len() - suffix, new.len() - suffix);
+  pairs.extend((0..suffix).map(|index| (old_suffix_start + index, new_suffix_start + index)));
+  pairs
+}
+
+fn middle_longest_common_subsequence(old: &[u64], new: &[u64]) -> Vec<(usize, usize)> {
+  if old.is_empty() || new.is_empty() {
+    return vec![];
+  }
+  let mut lengths = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+  for i in (0..old.len()).rev() {
+    for j in (0..new.len()).rev() {
+      lengths[i][j] = if old[i] == new[j] {
+        lengths[i + 1][j + 1] + 1
+      } else {
+        lengths[i + 1][j].max(lengths[i][j + 1])
+      };
+    }
+  }
+  let mut pairs = vec![];
+  let (mut i, mut j) = (0, 0);
+  while i < old.len() && j < new.len() {
+    if old[i] == new[j] {
+      pairs.push((i, j));
+      i += 1;
+      j += 1;
+    } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+      i += 1;
+    } else {
+      j += 1;
+    }
+  }
+  pairs
+}
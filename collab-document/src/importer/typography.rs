@@ -0,0 +1,186 @@
+use markdown::mdast;
+
+/// The quotation marks of a language, used by [TypographyOptions].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuoteLocale {
+  /// “double” and ‘single’.
+  #[default]
+  English,
+  /// „double“ and ‚single‘.
+  German,
+  /// «double» and ‹single›.
+  French,
+  /// ”double” and ’single’.
+  Swedish,
+  /// 「double」 and 『single』.
+  Japanese,
+}
+
+impl QuoteLocale {
+  /// The quotation marks of a language tag, e.g. `de` or `fr-CA`. English for an unknown
+  /// language.
+  pub fn from_language_tag(tag: &str) -> Self {
+    let language = tag
+      .split(['-', '_'])
+      .next()
+      .unwrap_or_default()
+      .to_lowercase();
+    match language.as_str() {
+      "de" | "cs" | "sk" | "sl" | "hr" | "bg" | "lt" | "et" => QuoteLocale::German,
+      "fr" | "ru" | "uk" | "be" | "hy" | "el" | "fa" => QuoteLocale::French,
+      "sv" | "fi" => QuoteLocale::Swedish,
+      "ja" | "zh" => QuoteLocale::Japanese,
+      _ => QuoteLocale::English,
+    }
+  }
+
+  /// The opening and closing double quotes, then the opening and closing single quotes.
+  fn marks(&self) -> (char, char, char, char) {
+    match self {
+      QuoteLocale::English => ('“', '”', '‘', '’'),
+      QuoteLocale::German => ('„', '“', '‚', '‘'),
+      QuoteLocale::French => ('«', '»', '‹', '›'),
+      QuoteLocale::Swedish => ('”', '”', '’', '’'),
+      QuoteLocale::Japanese => ('「', '」', '『', '』'),
+    }
+  }
+}
+
+/// The smart typography applied to the imported text, the way the editor converts it while
+/// typing, see [crate::importer::md_importer::MDImporter::with_typography]. Code is left
+/// untouched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypographyOptions {
+  /// Whether the straight quotes are converted to the quotation marks of the locale. An
+  /// apostrophe inside a word is converted to `’`.
+  pub quotes: bool,
+  pub locale: QuoteLocale,
+  /// Whether `--` and `---` are converted to the en and em dashes.
+  pub dashes: bool,
+  /// Whether `...` is converted to an ellipsis.
+  pub ellipsis: bool,
+}
+
+impl Default for TypographyOptions {
+  fn default() -> Self {
+    Self {
+      quotes: true,
+      locale: QuoteLocale::default(),
+      dashes: true,
+      ellipsis: true,
+    }
+  }
+}
+
+impl TypographyOptions {
+  pub fn with_locale(locale: QuoteLocale) -> Self {
+    Self {
+      locale,
+      ..Default::default()
+    }
+  }
+
+  /// Apply the typography to a text with nothing before it.
+  pub fn apply(&self, text: &str) -> String {
+    let mut state = TypographyState::default();
+    self.apply_with_state(text, &mut state)
+  }
+
+  fn apply_with_state(&self, text: &str, state: &mut TypographyState) -> String {
+    let mut text = text.to_string();
+    if self.dashes {
+      text = text.replace("---", "\u{2014}").replace("--", "\u{2013}");
+    }
+    if self.ellipsis {
+      text = text.replace("...", "\u{2026}");
+    }
+    if !self.quotes || !text.contains(['"', '\'']) {
+      state.prev = text.chars().last().or(state.prev);
+      return text;
+    }
+
+    let (double_open, double_close, single_open, single_close) = self.locale.marks();
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut output = String::with_capacity(text.len());
+    for (index, &c) in chars.iter().enumerate() {
+      let next = chars.get(index + 1).copied();
+      let converted = match c {
+        '"' if opens_quote(state.prev) => double_open,
+        '"' => double_close,
+        '\'' if state.prev.is_some_and(char::is_alphanumeric) => {
+          // the end of a quote, otherwise an apostrophe inside or at the end of a word
+          if state.single_quote_open && !next.is_some_and(char::is_alphanumeric) {
+            state.single_quote_open = false;
+            single_close
+          } else {
+            '\u{2019}'
+          }
+        },
+        '\'' if opens_quote(state.prev) => {
+          state.single_quote_open = true;
+          single_open
+        },
+        '\'' => {
+          state.single_quote_open = false;
+          single_close
+        },
+        _ => c,
+      };
+      output.push(converted);
+      state.prev = Some(c);
+    }
+    output
+  }
+}
+
+/// The text before the current one in a block.
+#[derive(Default)]
+struct TypographyState {
+  prev: Option<char>,
+  single_quote_open: bool,
+}
+
+/// Whether a quote after the character opens a quotation.
+fn opens_quote(prev: Option<char>) -> bool {
+  match prev {
+    None => true,
+    Some(c) => c.is_whitespace() || matches!(c, '(' | '[' | '{' | '\u{2013}' | '\u{2014}' | '/'),
+  }
+}
+
+/// Apply the typography to the text nodes of the markdown. The quotes are matched within a block,
+/// e.g. a quote opened before a bold text is closed after it.
+pub(crate) fn apply_typography(node: &mut mdast::Node, options: &TypographyOptions) {
+  let is_block = matches!(
+    node,
+    mdast::Node::Paragraph(_) | mdast::Node::Heading(_) | mdast::Node::TableCell(_)
+  );
+  if is_block {
+    let mut state = TypographyState::default();
+    apply_typography_inline(node, options, &mut state);
+    return;
+  }
+  if let Some(children) = node.children_mut() {
+    for child in children.iter_mut() {
+      apply_typography(child, options);
+    }
+  }
+}
+
+fn apply_typography_inline(
+  node: &mut mdast::Node,
+  options: &TypographyOptions,
+  state: &mut TypographyState,
+) {
+  match node {
+    mdast::Node::Text(text) => text.value = options.apply_with_state(&text.value, state),
+    mdast::Node::InlineCode(code) => state.prev = code.value.chars().last().or(state.prev),
+    _ => {
+      if let Some(children) = node.children_mut() {
+        for child in children.iter_mut() {
+          apply_typography_inline(child, options, state);
+        }
+      }
+    },
+  }
+}
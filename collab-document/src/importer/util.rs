@@ -1,7 +1,9 @@
 use super::delta::{Delta, Operation};
 use crate::{
-  blocks::{BlockType, DocumentData},
+  blocks::{BlockType, DiagramData, DiagramKind, DocumentData, SimpleTableData},
   importer::define::*,
+  importer::heading::HeadingPolicy,
+  importer::inline_style::{InlineStyleTag, parse_inline_style_tag},
 };
 use markdown::mdast;
 use serde_json::Value;
@@ -11,12 +13,20 @@ use tracing::trace;
 pub type BlockData = HashMap<String, Value>;
 
 /// Convert the node type to string
-pub(crate) fn mdast_node_type_to_block_type(node: &mdast::Node, list_type: Option<&str>) -> String {
+pub(crate) fn mdast_node_type_to_block_type(
+  node: &mdast::Node,
+  list_type: Option<&str>,
+  heading_policy: HeadingPolicy,
+) -> String {
   match node {
     mdast::Node::Root(_) => BlockType::Page,
     mdast::Node::Paragraph(_) => BlockType::Paragraph,
-    mdast::Node::Heading(_) => BlockType::Heading,
+    mdast::Node::Heading(heading) => match heading_policy.heading_level(heading.depth) {
+      Some(_) => BlockType::Heading,
+      None => BlockType::Paragraph,
+    },
     mdast::Node::Blockquote(_) => BlockType::Quote,
+    mdast::Node::Code(code) if code_diagram_kind(code).is_some() => BlockType::Diagram,
     mdast::Node::Code(_) => BlockType::Code,
     mdast::Node::Image(_) => BlockType::Image,
     mdast::Node::ImageReference(_) => BlockType::Image,
@@ -60,18 +70,37 @@ pub(crate) fn mdast_node_type_to_block_type(node: &mdast::Node, list_type: Optio
   .to_string()
 }
 
+/// The kind of diagram of a fenced code block, None when its language is not a diagram
+/// language.
+pub(crate) fn code_diagram_kind(code: &mdast::Code) -> Option<DiagramKind> {
+  code
+    .lang
+    .as_deref()
+    .and_then(DiagramKind::from_code_language)
+}
+
 /// Convert the mdast node to block data
-pub(crate) fn mdast_node_to_block_data(node: &mdast::Node, start_number: Option<u32>) -> BlockData {
+pub(crate) fn mdast_node_to_block_data(
+  node: &mdast::Node,
+  start_number: Option<u32>,
+  heading_policy: HeadingPolicy,
+) -> BlockData {
   let mut data = BlockData::new();
 
   match node {
     mdast::Node::Heading(heading) => {
-      let level = heading.depth.clamp(1, 6);
-      data.insert(LEVEL_FIELD.to_string(), level.into());
+      if let Some(level) = heading_policy.heading_level(heading.depth) {
+        data.insert(LEVEL_FIELD.to_string(), level.into());
+      }
     },
     mdast::Node::Code(code) => {
       let language = code.lang.as_ref().cloned().unwrap_or_default();
-      data.insert(LANGUAGE_FIELD.to_string(), language.into());
+      match DiagramData::from_code_block(&language, &code.value) {
+        Some(diagram) => diagram.write_to_block_data(&mut data),
+        None => {
+          data.insert(LANGUAGE_FIELD.to_string(), language.into());
+        },
+      }
     },
     mdast::Node::Image(image) => {
       data.insert(URL_FIELD.to_string(), image.url.clone().into());
@@ -88,7 +117,12 @@ pub(crate) fn mdast_node_to_block_data(node: &mdast::Node, start_number: Option<
       data.insert(FORMULA_FIELD.to_string(), math.value.clone().into());
     },
     mdast::Node::Table(_table) => {
-      // SimpleTable doesn't need special data fields
+      // The first row of a markdown table is always the header row
+      let table_data = SimpleTableData {
+        enable_header_row: true,
+        ..Default::default()
+      };
+      table_data.write_to_block_data(&mut data);
     },
     mdast::Node::ListItem(list) => {
       if let Some(checked) = list.checked {
@@ -135,6 +169,55 @@ pub(crate) fn is_inline_node(node: &mdast::Node) -> bool {
   )
 }
 
+/// Merge adjacent lists of the same type into one list, recursively.
+///
+/// The markdown parser starts a new list when the list marker changes, e.g. from `1.` to `1)` or
+/// from `-` to `*`, which Notion exports often do between items separated by blank lines. Each
+/// list restarts the numbering, so numbered lists are only merged if the start number of the next
+/// list continues the previous one.
+pub(crate) fn merge_adjacent_lists(node: &mut mdast::Node) {
+  let Some(children) = node.children_mut() else {
+    return;
+  };
+  let mut merged: Vec<mdast::Node> = Vec::with_capacity(children.len());
+  for mut child in std::mem::take(children) {
+    merge_adjacent_lists(&mut child);
+    if let mdast::Node::List(next) = &mut child {
+      if let Some(mdast::Node::List(prev)) = merged.last_mut() {
+        if is_list_continuation(prev, next) {
+          prev.spread |= next.spread;
+          if let (Some(prev_position), Some(next_position)) = (&mut prev.position, &next.position) {
+            prev_position.end = next_position.end.clone();
+          }
+          prev.children.append(&mut next.children);
+          continue;
+        }
+      }
+    }
+    merged.push(child);
+  }
+  *children = merged;
+}
+
+fn is_list_continuation(prev: &mdast::List, next: &mdast::List) -> bool {
+  if prev.ordered != next.ordered {
+    return false;
+  }
+  // A todo list is an unordered list whose items have a checkbox.
+  if is_task_item(prev.children.last()) != is_task_item(next.children.first()) {
+    return false;
+  }
+  if !prev.ordered {
+    return true;
+  }
+  let prev_end = prev.start.unwrap_or(1) + prev.children.len() as u32;
+  next.start == Some(prev_end)
+}
+
+fn is_task_item(item: Option<&mdast::Node>) -> bool {
+  matches!(item, Some(mdast::Node::ListItem(item)) if item.checked.is_some())
+}
+
 /// Get the list type and children of the md ast node
 pub(crate) fn get_mdast_node_info(
   node: &mdast::Node,
@@ -218,12 +301,38 @@ pub(crate) fn process_children_inline(
   attributes: Vec<(String, Value)>,
 ) -> Delta {
   let mut delta = Delta::new();
+  // The attributes of the html spans and marks the children are in, e.g. a text color
+  let mut styles: Vec<Vec<(String, Value)>> = vec![];
   for child in children {
-    delta.extend(inline_mdast_node_to_delta(child, attributes.clone()));
+    if let mdast::Node::Html(html) = child {
+      match parse_inline_style_tag(&html.value) {
+        Some(InlineStyleTag::Open(style)) => {
+          styles.push(style);
+          continue;
+        },
+        Some(InlineStyleTag::Close) => {
+          styles.pop();
+          continue;
+        },
+        None => {},
+      }
+    }
+
+    let mut child_attributes = attributes.clone();
+    for (key, value) in styles.iter().flatten() {
+      child_attributes.retain(|(k, _)| k != key);
+      child_attributes.push((key.clone(), value.clone()));
+    }
+    delta.extend(inline_mdast_node_to_delta(child, child_attributes));
   }
   delta
 }
 
+/// Whether the node is an inline `<span>` or `<mark>` tag, see [process_children_inline].
+pub(crate) fn is_inline_style_html(node: &mdast::Node) -> bool {
+  matches!(node, mdast::Node::Html(html) if parse_inline_style_tag(&html.value).is_some())
+}
+
 pub(crate) fn insert_delta_to_text_map(
   document_data: &mut DocumentData,
   parent_id: &str,
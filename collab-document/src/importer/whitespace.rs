@@ -0,0 +1,137 @@
+use markdown::mdast;
+use unicode_normalization::UnicodeNormalization;
+
+/// How the whitespace of the imported text is normalized, see
+/// [crate::importer::md_importer::MDImporter::with_whitespace].
+///
+/// Exported pages often contain non-breaking spaces, tabs or decomposed accents that look the
+/// same as their plain counterpart but don't match a search and move the caret unexpectedly.
+/// Code is left untouched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WhitespaceOptions {
+  /// Whether the non-breaking spaces are converted to regular spaces.
+  pub convert_nbsp: bool,
+  /// The number of columns between two tab stops the tabs are expanded to. None keeps the tabs.
+  pub tab_width: Option<usize>,
+  /// Whether the whitespace at the end of the lines and of the paragraphs is removed.
+  pub trim_trailing: bool,
+  /// Whether the text is converted to the Unicode Normalization Form C.
+  pub unicode_nfc: bool,
+}
+
+impl Default for WhitespaceOptions {
+  fn default() -> Self {
+    Self {
+      convert_nbsp: true,
+      tab_width: Some(4),
+      trim_trailing: true,
+      unicode_nfc: true,
+    }
+  }
+}
+
+impl WhitespaceOptions {
+  /// The text is imported as is.
+  pub fn preserve() -> Self {
+    Self {
+      convert_nbsp: false,
+      tab_width: None,
+      trim_trailing: false,
+      unicode_nfc: false,
+    }
+  }
+
+  /// Normalize the text according to the options.
+  pub fn normalize(&self, text: &str) -> String {
+    let mut text = if self.unicode_nfc {
+      text.nfc().collect::<String>()
+    } else {
+      text.to_string()
+    };
+    if self.convert_nbsp {
+      text = text.replace(['\u{00a0}', '\u{202f}'], " ");
+    }
+    if let Some(tab_width) = self.tab_width {
+      text = expand_tabs(&text, tab_width);
+    }
+    if self.trim_trailing {
+      text = trim_trailing_whitespace(&text);
+    }
+    text
+  }
+}
+
+/// Normalize the text nodes of the markdown. The whitespace at the end of a block is removed
+/// with the last text node, the one at the end of a text node followed by formatted text is
+/// kept.
+pub(crate) fn normalize_whitespace(node: &mut mdast::Node, options: &WhitespaceOptions) {
+  if let mdast::Node::Text(text) = node {
+    text.value = options.normalize(&text.value);
+    return;
+  }
+  let is_block = matches!(
+    node,
+    mdast::Node::Paragraph(_) | mdast::Node::Heading(_) | mdast::Node::TableCell(_)
+  );
+  if let Some(children) = node.children_mut() {
+    for child in children.iter_mut() {
+      normalize_whitespace(child, options);
+    }
+    if is_block && options.trim_trailing {
+      if let Some(mdast::Node::Text(text)) = children.last_mut() {
+        let trimmed_len = text.value.trim_end().len();
+        text.value.truncate(trimmed_len);
+      }
+    }
+  }
+}
+
+/// Replace each tab with the spaces up to the next tab stop.
+fn expand_tabs(text: &str, tab_width: usize) -> String {
+  if !text.contains('\t') {
+    return text.to_string();
+  }
+  let tab_width = tab_width.max(1);
+  let mut output = String::with_capacity(text.len());
+  let mut column = 0;
+  for c in text.chars() {
+    match c {
+      '\t' => {
+        let spaces = tab_width - column % tab_width;
+        output.extend(std::iter::repeat_n(' ', spaces));
+        column += spaces;
+      },
+      '\n' => {
+        output.push(c);
+        column = 0;
+      },
+      _ => {
+        output.push(c);
+        column += 1;
+      },
+    }
+  }
+  output
+}
+
+/// Remove the spaces and tabs before each line break.
+fn trim_trailing_whitespace(text: &str) -> String {
+  if !text.contains('\n') {
+    return text.to_string();
+  }
+  let lines = text.split('\n').collect::<Vec<_>>();
+  let last_index = lines.len() - 1;
+  lines
+    .into_iter()
+    .enumerate()
+    .map(|(index, line)| {
+      // the end of the text is only trimmed at the end of its block
+      if index == last_index {
+        line
+      } else {
+        line.trim_end_matches([' ', '\t'])
+      }
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
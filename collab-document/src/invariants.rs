@@ -0,0 +1,260 @@
+use crate::blocks::{BlockType, DocumentData};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+/// The block types whose text is read by the editor. A block of these types whose text is
+/// missing is rendered as a blank block.
+const TEXT_BLOCK_TYPES: [BlockType; 9] = [
+  BlockType::Paragraph,
+  BlockType::Heading,
+  BlockType::TodoList,
+  BlockType::BulletedList,
+  BlockType::NumberedList,
+  BlockType::Quote,
+  BlockType::Callout,
+  BlockType::ToggleList,
+  BlockType::Code,
+];
+
+/// A broken rule of the structure of a [DocumentData], see [check_document].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvariantViolation {
+  MissingPageBlock,
+  /// The block is stored under another key than its id.
+  BlockIdMismatch {
+    key: String,
+    block_id: String,
+  },
+  /// A children list refers to a block that doesn't exist.
+  MissingChild {
+    parent_id: String,
+    child_id: String,
+  },
+  /// The block is listed in the children of a block that is not its parent.
+  ParentMismatch {
+    block_id: String,
+    parent_id: String,
+    listed_in: String,
+  },
+  /// The parent of the block doesn't exist.
+  MissingParent {
+    block_id: String,
+    parent_id: String,
+  },
+  /// The block is not listed in the children of its parent.
+  NotInParentChildren {
+    block_id: String,
+    parent_id: String,
+  },
+  /// The block is listed more than once in the children lists.
+  DuplicateChild {
+    block_id: String,
+  },
+  /// The block can't be reached from the page, e.g. its ancestors form a cycle.
+  Unreachable {
+    block_id: String,
+  },
+  /// The block holds a text, but its text is not in the text map.
+  MissingText {
+    block_id: String,
+    external_id: String,
+  },
+}
+
+impl Display for InvariantViolation {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      InvariantViolation::MissingPageBlock => write!(f, "the page block is missing"),
+      InvariantViolation::BlockIdMismatch { key, block_id } => {
+        write!(f, "the block {} is stored under the key {}", block_id, key)
+      },
+      InvariantViolation::MissingChild {
+        parent_id,
+        child_id,
+      } => write!(f, "the child {} of {} doesn't exist", child_id, parent_id),
+      InvariantViolation::ParentMismatch {
+        block_id,
+        parent_id,
+        listed_in,
+      } => write!(
+        f,
+        "the block {} is a child of {} but its parent is {}",
+        block_id, listed_in, parent_id
+      ),
+      InvariantViolation::MissingParent {
+        block_id,
+        parent_id,
+      } => write!(f, "the parent {} of {} doesn't exist", parent_id, block_id),
+      InvariantViolation::NotInParentChildren {
+        block_id,
+        parent_id,
+      } => write!(
+        f,
+        "the block {} is not in the children of its parent {}",
+        block_id, parent_id
+      ),
+      InvariantViolation::DuplicateChild { block_id } => {
+        write!(f, "the block {} is listed more than once", block_id)
+      },
+      InvariantViolation::Unreachable { block_id } => {
+        write!(f, "the block {} can't be reached from the page", block_id)
+      },
+      InvariantViolation::MissingText {
+        block_id,
+        external_id,
+      } => write!(f, "the text {} of {} is missing", external_id, block_id),
+    }
+  }
+}
+
+/// Check the structure of the document: the children lists and the parents of the blocks agree,
+/// every block is reachable from the page and the text blocks have their text. The texts are
+/// not checked if they are not hydrated.
+///
+/// Return the broken rules, the document is valid if there is none.
+pub fn check_document(data: &DocumentData) -> Vec<InvariantViolation> {
+  let mut violations = vec![];
+  let Some(page) = data.blocks.get(&data.page_id) else {
+    return vec![InvariantViolation::MissingPageBlock];
+  };
+
+  // The block owning each children list.
+  let owners = data
+    .blocks
+    .values()
+    .map(|block| (block.children.as_str(), block.id.as_str()))
+    .collect::<HashMap<_, _>>();
+  let mut listed = HashSet::new();
+  for (key, block) in data.blocks.iter() {
+    if key != &block.id {
+      violations.push(InvariantViolation::BlockIdMismatch {
+        key: key.clone(),
+        block_id: block.id.clone(),
+      });
+    }
+    let Some(children) = data.meta.children_map.get(&block.children) else {
+      continue;
+    };
+    for child_id in children {
+      if !listed.insert(child_id.as_str()) {
+        violations.push(InvariantViolation::DuplicateChild {
+          block_id: child_id.clone(),
+        });
+      }
+      match data.blocks.get(child_id) {
+        None => violations.push(InvariantViolation::MissingChild {
+          parent_id: block.id.clone(),
+          child_id: child_id.clone(),
+        }),
+        Some(child) if child.parent != block.id => {
+          violations.push(InvariantViolation::ParentMismatch {
+            block_id: child_id.clone(),
+            parent_id: child.parent.clone(),
+            listed_in: block.id.clone(),
+          })
+        },
+        Some(_) => {},
+      }
+    }
+  }
+
+  for block in data.blocks.values() {
+    if block.id == page.id {
+      continue;
+    }
+    match data.blocks.get(&block.parent) {
+      None => violations.push(InvariantViolation::MissingParent {
+        block_id: block.id.clone(),
+        parent_id: block.parent.clone(),
+      }),
+      Some(parent) => {
+        let is_listed = data
+          .meta
+          .children_map
+          .get(&parent.children)
+          .is_some_and(|children| children.contains(&block.id));
+        if !is_listed && owners.get(parent.children.as_str()) == Some(&parent.id.as_str()) {
+          violations.push(InvariantViolation::NotInParentChildren {
+            block_id: block.id.clone(),
+            parent_id: parent.id.clone(),
+          });
+        }
+      },
+    }
+  }
+
+  let reachable = reachable_block_ids(data);
+  for block in data.blocks.values() {
+    if !reachable.contains(block.id.as_str()) {
+      violations.push(InvariantViolation::Unreachable {
+        block_id: block.id.clone(),
+      });
+    }
+  }
+
+  if let Some(text_map) = data.meta.text_map.as_ref().filter(|_| !data.meta.lazy_text) {
+    for block in data.blocks.values() {
+      if !is_text_block_type(&block.ty) {
+        continue;
+      }
+      if let Some(external_id) = &block.external_id {
+        if !text_map.contains_key(external_id) {
+          violations.push(InvariantViolation::MissingText {
+            block_id: block.id.clone(),
+            external_id: external_id.clone(),
+          });
+        }
+      }
+    }
+  }
+  violations
+}
+
+/// Panic with the broken rules if the document is not valid, see [check_document].
+pub fn assert_document_valid(data: &DocumentData) {
+  let violations = check_document(data);
+  if !violations.is_empty() {
+    let violations = violations
+      .iter()
+      .map(|violation| violation.to_string())
+      .collect::<Vec<_>>();
+    panic!("invalid document data:\n{}", violations.join("\n"));
+  }
+}
+
+/// Add an empty text to the text blocks whose text is missing, e.g. an empty table cell.
+pub(crate) fn fill_missing_texts(data: &mut DocumentData) {
+  let Some(text_map) = data.meta.text_map.as_mut() else {
+    return;
+  };
+  for block in data.blocks.values() {
+    if let Some(external_id) = &block.external_id {
+      if is_text_block_type(&block.ty) && !text_map.contains_key(external_id) {
+        text_map.insert(external_id.clone(), "[]".to_string());
+      }
+    }
+  }
+}
+
+fn is_text_block_type(ty: &str) -> bool {
+  TEXT_BLOCK_TYPES
+    .iter()
+    .any(|block_type| block_type.as_str() == ty)
+}
+
+fn reachable_block_ids(data: &DocumentData) -> HashSet<&str> {
+  let mut reachable = HashSet::new();
+  let mut stack = vec![data.page_id.as_str()];
+  while let Some(block_id) = stack.pop() {
+    let Some(block) = data.blocks.get(block_id) else {
+      continue;
+    };
+    if !reachable.insert(block.id.as_str()) {
+      continue;
+    }
+    if let Some(children) = data.meta.children_map.get(&block.children) {
+      stack.extend(children.iter().map(String::as_str));
+    }
+  }
+  reachable
+}
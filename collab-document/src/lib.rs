@@ -1,7 +1,18 @@
+pub mod annotation;
+pub mod attachment;
 pub mod block_parser;
 pub mod blocks;
+pub mod chat;
+pub mod citation;
 pub mod document;
 pub mod document_awareness;
 pub mod document_data;
 pub mod error;
 pub mod importer;
+pub mod invariants;
+pub mod limits;
+pub mod print;
+pub mod provenance;
+pub mod redaction;
+pub mod sanitize;
+pub mod slug;
@@ -0,0 +1,319 @@
+use crate::blocks::{Block, DocumentData, TextDelta, deserialize_text_delta};
+use crate::error::DocumentError;
+use std::fmt::{Display, Formatter};
+
+/// The text appended to a truncated text.
+pub const TRUNCATED_TEXT_MARKER: &str = "…";
+
+/// What happens to a document over one of its [DocumentLimits].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LimitPolicy {
+  /// The content over the limit is dropped and a marker tells what was dropped: the texts end
+  /// with [TRUNCATED_TEXT_MARKER], the dropped blocks are replaced by a paragraph counting them
+  /// and the blocks nested too deep are moved up to the deepest level.
+  #[default]
+  Truncate,
+  /// The document is rejected with [DocumentError::DocumentLimitExceeded].
+  Error,
+}
+
+/// The limit a document exceeds, see [DocumentError::DocumentLimitExceeded].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DocumentLimit {
+  Blocks,
+  TextLength,
+  Depth,
+}
+
+impl DocumentLimit {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      DocumentLimit::Blocks => "blocks",
+      DocumentLimit::TextLength => "text_length",
+      DocumentLimit::Depth => "depth",
+    }
+  }
+}
+
+impl Display for DocumentLimit {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+/// The size a document must stay under, so a huge or deeply nested import doesn't make the
+/// editor unusable. Applied to the imported documents, see
+/// [crate::importer::md_importer::MDImporter::with_document_limits], and to the blocks written
+/// with the [crate::document::Document] APIs, see [crate::document::Document::set_limits].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DocumentLimits {
+  /// The maximum number of blocks, the page block excluded.
+  pub max_blocks: usize,
+  /// The maximum number of characters of the text of a block.
+  pub max_text_len: usize,
+  /// The maximum depth of a block, the children of the page block are at depth 1.
+  pub max_depth: usize,
+  pub policy: LimitPolicy,
+}
+
+impl Default for DocumentLimits {
+  fn default() -> Self {
+    Self {
+      max_blocks: 50_000,
+      max_text_len: 100_000,
+      max_depth: 32,
+      policy: LimitPolicy::default(),
+    }
+  }
+}
+
+impl DocumentLimits {
+  /// No limit, every document is kept as is.
+  pub fn unlimited() -> Self {
+    Self {
+      max_blocks: usize::MAX,
+      max_text_len: usize::MAX,
+      max_depth: usize::MAX,
+      policy: LimitPolicy::default(),
+    }
+  }
+
+  pub fn with_policy(mut self, policy: LimitPolicy) -> Self {
+    self.policy = policy;
+    self
+  }
+
+  /// Check the number of blocks and the depth of the blocks inserted into a document. The
+  /// structure of a document can't be truncated once it's written, so both policies return an
+  /// error.
+  pub(crate) fn check_insert(
+    &self,
+    num_of_blocks: usize,
+    max_depth: usize,
+  ) -> Result<(), DocumentError> {
+    if num_of_blocks > self.max_blocks {
+      return Err(DocumentError::DocumentLimitExceeded {
+        limit: DocumentLimit::Blocks,
+        size: num_of_blocks,
+        max: self.max_blocks,
+      });
+    }
+    if max_depth > self.max_depth {
+      return Err(DocumentError::DocumentLimitExceeded {
+        limit: DocumentLimit::Depth,
+        size: max_depth,
+        max: self.max_depth,
+      });
+    }
+    Ok(())
+  }
+
+  /// Truncate the delta over the maximum text length, or return an error, depending on the
+  /// policy.
+  pub(crate) fn limit_text_delta(
+    &self,
+    delta: Vec<TextDelta>,
+  ) -> Result<Vec<TextDelta>, DocumentError> {
+    let len = text_len(&delta);
+    if len <= self.max_text_len {
+      return Ok(delta);
+    }
+    match self.policy {
+      LimitPolicy::Error => Err(DocumentError::DocumentLimitExceeded {
+        limit: DocumentLimit::TextLength,
+        size: len,
+        max: self.max_text_len,
+      }),
+      LimitPolicy::Truncate => Ok(truncate_text_delta(delta, self.max_text_len)),
+    }
+  }
+
+  /// Truncate the json delta over the maximum text length, see [DocumentLimits::limit_text_delta].
+  /// Return None if the delta is kept as is.
+  pub(crate) fn limit_text_json(&self, delta: &str) -> Result<Option<String>, DocumentError> {
+    let Ok(ops) = deserialize_text_delta(delta) else {
+      return Ok(None);
+    };
+    if text_len(&ops) <= self.max_text_len {
+      return Ok(None);
+    }
+    let ops = self.limit_text_delta(ops)?;
+    Ok(serde_json::to_string(&ops).ok())
+  }
+}
+
+impl DocumentData {
+  /// Apply the limits to the document, in this order: the depth of the blocks, the number of
+  /// blocks in the reading order, then the length of each text.
+  ///
+  /// The blocks that are not reachable from the page are not counted. The texts must be hydrated,
+  /// the texts of a `lazy_text` data are not read.
+  pub fn enforce_limits(&mut self, limits: &DocumentLimits) -> Result<(), DocumentError> {
+    let page_id = self.page_id.clone();
+    let max_depth = limits.max_depth.max(1);
+    let depth = self.max_block_depth(&page_id, 0);
+    if depth > max_depth {
+      if limits.policy == LimitPolicy::Error {
+        return Err(DocumentError::DocumentLimitExceeded {
+          limit: DocumentLimit::Depth,
+          size: depth,
+          max: max_depth,
+        });
+      }
+      self.flatten_blocks(&page_id, 0, max_depth);
+    }
+
+    let mut block_ids = vec![];
+    self.collect_descendant_ids(&page_id, &mut block_ids);
+    if block_ids.len() > limits.max_blocks {
+      if limits.policy == LimitPolicy::Error {
+        return Err(DocumentError::DocumentLimitExceeded {
+          limit: DocumentLimit::Blocks,
+          size: block_ids.len(),
+          max: limits.max_blocks,
+        });
+      }
+      let num_of_kept = limits.max_blocks.saturating_sub(1);
+      let dropped = block_ids.split_off(num_of_kept);
+      let num_of_dropped = dropped.len();
+      for block_id in dropped {
+        self.remove_limited_block(&block_id);
+      }
+      if limits.max_blocks > 0 {
+        self.push_truncated_blocks_marker(num_of_dropped)?;
+      }
+    }
+
+    if let Some(text_map) = self.meta.text_map.as_mut() {
+      for delta in text_map.values_mut() {
+        if let Some(truncated) = limits.limit_text_json(delta)? {
+          *delta = truncated;
+        }
+      }
+    }
+    Ok(())
+  }
+
+  fn children_ids(&self, block_id: &str) -> Vec<String> {
+    self
+      .blocks
+      .get(block_id)
+      .and_then(|block| self.meta.children_map.get(&block.children))
+      .cloned()
+      .unwrap_or_default()
+  }
+
+  /// The depth of the deepest descendant of the block at the given depth.
+  fn max_block_depth(&self, block_id: &str, depth: usize) -> usize {
+    self
+      .children_ids(block_id)
+      .iter()
+      .map(|child_id| self.max_block_depth(child_id, depth + 1))
+      .max()
+      .unwrap_or(depth)
+  }
+
+  /// Move the descendants of the blocks at the maximum depth right after them, in the reading
+  /// order, so no block is deeper than the maximum depth.
+  fn flatten_blocks(&mut self, block_id: &str, depth: usize, max_depth: usize) {
+    let children = self.children_ids(block_id);
+    if depth + 1 < max_depth {
+      for child_id in children.iter() {
+        self.flatten_blocks(child_id, depth + 1, max_depth);
+      }
+      return;
+    }
+
+    let mut flattened = Vec::with_capacity(children.len());
+    for child_id in children {
+      let mut descendant_ids = vec![];
+      self.collect_descendant_ids(&child_id, &mut descendant_ids);
+      flattened.push(child_id);
+      for descendant_id in descendant_ids {
+        if let Some(descendant) = self.blocks.get_mut(&descendant_id) {
+          descendant.parent = block_id.to_string();
+          self
+            .meta
+            .children_map
+            .insert(descendant.children.clone(), vec![]);
+        }
+        flattened.push(descendant_id);
+      }
+    }
+    if let Some(block) = self.blocks.get(block_id) {
+      self
+        .meta
+        .children_map
+        .insert(block.children.clone(), flattened);
+    }
+  }
+
+  /// Collect the ids of the descendants of the block, in the reading order.
+  fn collect_descendant_ids(&self, block_id: &str, block_ids: &mut Vec<String>) {
+    for child_id in self.children_ids(block_id) {
+      block_ids.push(child_id.clone());
+      self.collect_descendant_ids(&child_id, block_ids);
+    }
+  }
+
+  fn remove_limited_block(&mut self, block_id: &str) {
+    let Some(block) = self.blocks.remove(block_id) else {
+      return;
+    };
+    self.meta.children_map.remove(&block.children);
+    if let Some(siblings) = self
+      .blocks
+      .get(&block.parent)
+      .and_then(|parent| self.meta.children_map.get_mut(&parent.children))
+    {
+      siblings.retain(|sibling| sibling != block_id);
+    }
+    if let (Some(external_id), Some(text_map)) = (&block.external_id, self.meta.text_map.as_mut()) {
+      text_map.remove(external_id);
+    }
+  }
+
+  /// Append a paragraph telling how many blocks were dropped to the page.
+  fn push_truncated_blocks_marker(&mut self, num_of_dropped: usize) -> Result<(), DocumentError> {
+    let page_id = self.page_id.clone();
+    let marker = Block::paragraph(&format!("[{} blocks truncated]", num_of_dropped));
+    self.push_block(&page_id, marker)?;
+    Ok(())
+  }
+}
+
+/// The number of characters inserted by the delta.
+fn text_len(delta: &[TextDelta]) -> usize {
+  delta
+    .iter()
+    .map(|op| match op {
+      TextDelta::Inserted(text, _) => text.chars().count(),
+      _ => 0,
+    })
+    .sum()
+}
+
+/// Keep the first characters of the delta and append [TRUNCATED_TEXT_MARKER], so the text is
+/// at most `max_len` characters long.
+fn truncate_text_delta(delta: Vec<TextDelta>, max_len: usize) -> Vec<TextDelta> {
+  let marker_len = TRUNCATED_TEXT_MARKER.chars().count();
+  let mut remaining = max_len.saturating_sub(marker_len);
+  let mut truncated = vec![];
+  for op in delta {
+    let TextDelta::Inserted(mut text, attrs) = op else {
+      continue;
+    };
+    if remaining == 0 {
+      break;
+    }
+    if let Some((index, _)) = text.char_indices().nth(remaining) {
+      text.truncate(index);
+    }
+    remaining -= text.chars().count();
+    truncated.push(TextDelta::Inserted(text, attrs));
+  }
+  if max_len >= marker_len {
+    truncated.push(TextDelta::Inserted(TRUNCATED_TEXT_MARKER.to_string(), None));
+  }
+  truncated
+}
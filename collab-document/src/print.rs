@@ -0,0 +1,451 @@
+use crate::blocks::{
+  BlockType, DiagramData, DocumentData, SimpleColumnData, SimpleTableData,
+  normalize_column_width_ratios, value_as_f64,
+};
+use crate::importer::define::{ALIGN_FIELD, ALIGN_LEFT, LEVEL_FIELD, URL_FIELD};
+use crate::slug::delta_plain_text;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The type of the block that starts a new page when the document is printed.
+pub const PAGE_BREAK_BLOCK_TYPE: &str = "page_break";
+
+/// The fields of the image data giving the size the image is displayed with, in pixels.
+pub const IMAGE_WIDTH_FIELD: &str = "width";
+pub const IMAGE_HEIGHT_FIELD: &str = "height";
+
+/// The size of an A4 page, in points.
+pub const A4_PAGE_WIDTH: f64 = 595.0;
+pub const A4_PAGE_HEIGHT: f64 = 842.0;
+pub const DEFAULT_PAGE_MARGIN: f64 = 56.0;
+
+/// How a document is laid out on the printed pages, see [PrintModel::from_document_data].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrintOptions {
+  pub page_width: f64,
+  pub page_height: f64,
+  pub margin: f64,
+  /// The headings up to this level start a new page, e.g. `Some(1)` prints each top level
+  /// section on its own pages. None if only the page break blocks start a new page.
+  pub break_before_heading: Option<u8>,
+  /// The headings up to this level are repeated in the header of the pages that follow them.
+  /// None if the pages have no header.
+  pub header_level: Option<u8>,
+}
+
+impl Default for PrintOptions {
+  fn default() -> Self {
+    Self {
+      page_width: A4_PAGE_WIDTH,
+      page_height: A4_PAGE_HEIGHT,
+      margin: DEFAULT_PAGE_MARGIN,
+      break_before_heading: None,
+      header_level: Some(1),
+    }
+  }
+}
+
+impl PrintOptions {
+  pub fn with_page_size(mut self, width: f64, height: f64) -> Self {
+    self.page_width = width;
+    self.page_height = height;
+    self
+  }
+
+  pub fn with_margin(mut self, margin: f64) -> Self {
+    self.margin = margin;
+    self
+  }
+
+  pub fn with_break_before_heading(mut self, level: Option<u8>) -> Self {
+    self.break_before_heading = level;
+    self
+  }
+
+  pub fn with_header_level(mut self, level: Option<u8>) -> Self {
+    self.header_level = level;
+    self
+  }
+
+  /// The width available to the content of a page, never negative.
+  pub fn content_width(&self) -> f64 {
+    (self.page_width - 2.0 * self.margin).max(0.0)
+  }
+}
+
+/// A document resolved into the pages, the sizes and the texts a PDF renderer needs, so the
+/// renderer doesn't have to know the semantics of the blocks.
+///
+/// The pages are the logical pages between two page breaks, a renderer still splits a page that
+/// doesn't fit on one sheet. The sizes are in the unit of the [PrintOptions], the points of a PDF
+/// by default.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PrintModel {
+  pub page_width: f64,
+  pub page_height: f64,
+  pub margin: f64,
+  pub pages: Vec<PrintPage>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PrintPage {
+  /// The text of the last heading up to [PrintOptions::header_level] before the page or at its
+  /// top, None if there is none.
+  pub header: Option<String>,
+  pub elements: Vec<PrintElement>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PrintElement {
+  Heading {
+    block_id: String,
+    level: u8,
+    text: String,
+  },
+  /// A block holding a text, e.g. a paragraph, a list item or a quote. The children of a block
+  /// are printed after it with a depth increased by one.
+  Text {
+    block_id: String,
+    block_type: String,
+    text: String,
+    depth: usize,
+  },
+  /// An image scaled down to fit the width of the content, the aspect ratio is kept. The height
+  /// is None if the size of the image is not stored in the document.
+  Image {
+    block_id: String,
+    url: String,
+    width: f64,
+    height: Option<f64>,
+    align: String,
+  },
+  /// A table whose columns are scaled down to fit the width of the content.
+  Table {
+    block_id: String,
+    column_widths: Vec<f64>,
+    header_row: bool,
+    header_column: bool,
+    rows: Vec<Vec<String>>,
+  },
+  Columns {
+    block_id: String,
+    columns: Vec<PrintColumn>,
+  },
+  Divider {
+    block_id: String,
+  },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PrintColumn {
+  pub width: f64,
+  pub elements: Vec<PrintElement>,
+}
+
+impl PrintModel {
+  /// Lay out the document. The texts must be hydrated, the blocks of a `lazy_text` data whose
+  /// text is not in the `text_map` are printed with an empty text.
+  pub fn from_document_data(data: &DocumentData, options: &PrintOptions) -> Self {
+    let mut layout = PrintLayout {
+      data,
+      options,
+      pages: vec![PrintPage::default()],
+      header: None,
+    };
+    for child_id in layout.children(&data.page_id) {
+      layout.push_block(child_id);
+    }
+    let mut pages = layout.pages;
+    // a page break at the end of the document doesn't add a blank page
+    if pages.len() > 1 && pages.last().is_some_and(|page| page.elements.is_empty()) {
+      pages.pop();
+    }
+    Self {
+      page_width: options.page_width,
+      page_height: options.page_height,
+      margin: options.margin,
+      pages,
+    }
+  }
+}
+
+impl DocumentData {
+  /// See [PrintModel::from_document_data].
+  pub fn to_print_model(&self, options: &PrintOptions) -> PrintModel {
+    PrintModel::from_document_data(self, options)
+  }
+}
+
+struct PrintLayout<'a> {
+  data: &'a DocumentData,
+  options: &'a PrintOptions,
+  pages: Vec<PrintPage>,
+  header: Option<String>,
+}
+
+impl<'a> PrintLayout<'a> {
+  fn push_block(&mut self, block_id: &str) {
+    let Some(block) = self.data.blocks.get(block_id) else {
+      return;
+    };
+    if block.ty == PAGE_BREAK_BLOCK_TYPE {
+      self.break_page();
+      return;
+    }
+    if block.ty == BlockType::Heading.as_str() {
+      let level = heading_level(&block.data);
+      let text = self.text(block_id);
+      if self
+        .options
+        .break_before_heading
+        .is_some_and(|max_level| level <= max_level)
+      {
+        self.break_page();
+      }
+      if self
+        .options
+        .header_level
+        .is_some_and(|max_level| level <= max_level)
+      {
+        self.header = Some(text.clone());
+        let page = self.current_page();
+        if page.elements.is_empty() {
+          page.header = Some(text.clone());
+        }
+      }
+      self.current_page().elements.push(PrintElement::Heading {
+        block_id: block_id.to_string(),
+        level,
+        text,
+      });
+      for child_id in self.children(block_id) {
+        self.push_block(child_id);
+      }
+      return;
+    }
+
+    let width = self.options.content_width();
+    let mut elements = vec![];
+    self.collect_elements(block_id, 0, width, &mut elements);
+    self.current_page().elements.extend(elements);
+  }
+
+  /// Lay out a block that is not a page break or a top level heading into the elements.
+  fn collect_elements(
+    &self,
+    block_id: &str,
+    depth: usize,
+    width: f64,
+    elements: &mut Vec<PrintElement>,
+  ) {
+    let Some(block) = self.data.blocks.get(block_id) else {
+      return;
+    };
+    let block_id = block_id.to_string();
+    match BlockType::from_block_ty(&block.ty) {
+      BlockType::Heading => elements.push(PrintElement::Heading {
+        level: heading_level(&block.data),
+        text: self.text(&block_id),
+        block_id,
+      }),
+      BlockType::Divider => elements.push(PrintElement::Divider { block_id }),
+      BlockType::Image => {
+        let url = block
+          .data
+          .get(URL_FIELD)
+          .and_then(Value::as_str)
+          .unwrap_or_default()
+          .to_string();
+        let (width, height) = image_size(&block.data, width);
+        let align = block
+          .data
+          .get(ALIGN_FIELD)
+          .and_then(Value::as_str)
+          .unwrap_or(ALIGN_LEFT)
+          .to_string();
+        elements.push(PrintElement::Image {
+          block_id,
+          url,
+          width,
+          height,
+          align,
+        });
+      },
+      BlockType::Diagram => {
+        // the source of the diagram, rendered by the client as for the editor
+        if let Some(diagram) = DiagramData::from_block_data(&block.data) {
+          elements.push(PrintElement::Text {
+            block_type: block.ty.clone(),
+            text: diagram.source,
+            depth,
+            block_id,
+          });
+        }
+      },
+      BlockType::SimpleTable => elements.push(self.table(block_id, &block.data, width)),
+      BlockType::SimpleColumns => {
+        let column_ids = self.children(&block_id);
+        let ratios = column_ids
+          .iter()
+          .map(|column_id| {
+            self
+              .data
+              .blocks
+              .get(*column_id)
+              .and_then(|column| SimpleColumnData::from_block_data(&column.data).width_ratio)
+          })
+          .collect::<Vec<_>>();
+        let ratios = normalize_column_width_ratios(&ratios)
+          .unwrap_or_else(|| vec![1.0 / column_ids.len().max(1) as f64; column_ids.len()]);
+        let columns = column_ids
+          .into_iter()
+          .zip(ratios)
+          .map(|(column_id, ratio)| {
+            let width = width * ratio;
+            let mut elements = vec![];
+            for child_id in self.children(column_id) {
+              self.collect_elements(child_id, 0, width, &mut elements);
+            }
+            PrintColumn { width, elements }
+          })
+          .collect();
+        elements.push(PrintElement::Columns { block_id, columns });
+      },
+      _ => {
+        let has_text = block.external_id.is_some();
+        if has_text {
+          elements.push(PrintElement::Text {
+            block_type: block.ty.clone(),
+            text: self.text(&block_id),
+            depth,
+            block_id: block_id.clone(),
+          });
+        }
+        let depth = if has_text { depth + 1 } else { depth };
+        for child_id in self.children(&block_id) {
+          self.collect_elements(child_id, depth, width, elements);
+        }
+      },
+    }
+  }
+
+  fn table(&self, block_id: String, data: &HashMap<String, Value>, width: f64) -> PrintElement {
+    let table_data = SimpleTableData::from_block_data(data);
+    let rows = self
+      .children(&block_id)
+      .into_iter()
+      .map(|row_id| {
+        self
+          .children(row_id)
+          .into_iter()
+          .map(|cell_id| self.cell_text(cell_id))
+          .collect::<Vec<_>>()
+      })
+      .collect::<Vec<_>>();
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let mut column_widths = (0..columns)
+      .map(|column| table_data.column_width(column))
+      .collect::<Vec<_>>();
+    let total = column_widths.iter().sum::<f64>();
+    if total > width && total > 0.0 {
+      let scale = width / total;
+      column_widths
+        .iter_mut()
+        .for_each(|column_width| *column_width *= scale);
+    }
+    PrintElement::Table {
+      block_id,
+      column_widths,
+      header_row: table_data.enable_header_row,
+      header_column: table_data.enable_header_column,
+      rows,
+    }
+  }
+
+  /// The texts of the blocks of a table cell, one per line.
+  fn cell_text(&self, cell_id: &str) -> String {
+    let mut lines = vec![];
+    let mut stack = self.children(cell_id);
+    stack.reverse();
+    while let Some(block_id) = stack.pop() {
+      if self
+        .data
+        .blocks
+        .get(block_id)
+        .is_some_and(|block| block.external_id.is_some())
+      {
+        lines.push(self.text(block_id));
+      }
+      stack.extend(self.children(block_id).into_iter().rev());
+    }
+    lines.join("\n")
+  }
+
+  fn break_page(&mut self) {
+    if self.current_page().elements.is_empty() {
+      return;
+    }
+    self.pages.push(PrintPage {
+      header: self.header.clone(),
+      elements: vec![],
+    });
+  }
+
+  fn current_page(&mut self) -> &mut PrintPage {
+    // the layout always starts with a page
+    self.pages.last_mut().unwrap()
+  }
+
+  fn children(&self, block_id: &str) -> Vec<&'a str> {
+    self
+      .data
+      .blocks
+      .get(block_id)
+      .and_then(|block| self.data.meta.children_map.get(&block.children))
+      .map(|children| children.iter().map(String::as_str).collect())
+      .unwrap_or_default()
+  }
+
+  fn text(&self, block_id: &str) -> String {
+    self
+      .data
+      .blocks
+      .get(block_id)
+      .and_then(|block| block.external_id.as_ref())
+      .and_then(|text_id| self.data.meta.text_map.as_ref()?.get(text_id))
+      .map(|delta| delta_plain_text(delta))
+      .unwrap_or_default()
+  }
+}
+
+fn heading_level(data: &HashMap<String, Value>) -> u8 {
+  data
+    .get(LEVEL_FIELD)
+    .and_then(value_as_f64)
+    .map(|level| level.clamp(1.0, 6.0) as u8)
+    .unwrap_or(1)
+}
+
+/// The width and the height of an image printed in the width, the stored size is scaled down
+/// when the image is wider than the content. An image without a stored width takes the whole
+/// width.
+fn image_size(data: &HashMap<String, Value>, max_width: f64) -> (f64, Option<f64>) {
+  let width = data
+    .get(IMAGE_WIDTH_FIELD)
+    .and_then(value_as_f64)
+    .filter(|width| width.is_finite() && *width > 0.0);
+  let height = data
+    .get(IMAGE_HEIGHT_FIELD)
+    .and_then(value_as_f64)
+    .filter(|height| height.is_finite() && *height > 0.0);
+  match width {
+    Some(width) if width > max_width => {
+      let scale = max_width / width;
+      (max_width, height.map(|height| height * scale))
+    },
+    Some(width) => (width, height),
+    None => (max_width, None),
+  }
+}
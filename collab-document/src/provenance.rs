@@ -0,0 +1,111 @@
+use crate::blocks::{Block, DocumentData};
+use crate::document::Document;
+use crate::error::DocumentError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The field of the block data holding the [BlockProvenance] of the imported blocks. The blocks
+/// written by the users don't have it.
+pub const PROVENANCE_FIELD: &str = "import_source";
+
+/// Where an imported block comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockProvenance {
+  /// The importer that created the block, e.g. `notion` or `docx`.
+  pub importer: String,
+  /// The file, or the entry of the export, the block was imported from.
+  pub source_path: String,
+  /// When the block was imported, in seconds since the epoch.
+  pub imported_at: i64,
+}
+
+impl BlockProvenance {
+  /// The provenance of the blocks imported now.
+  pub fn new<S: ToString, P: ToString>(importer: S, source_path: P) -> Self {
+    Self {
+      importer: importer.to_string(),
+      source_path: source_path.to_string(),
+      imported_at: chrono::Utc::now().timestamp(),
+    }
+  }
+
+  /// The provenance of the block, `None` if it wasn't imported.
+  pub fn from_block(block: &Block) -> Option<Self> {
+    serde_json::from_value(block.data.get(PROVENANCE_FIELD)?.clone()).ok()
+  }
+
+  fn to_value(&self) -> Value {
+    serde_json::to_value(self).unwrap_or_default()
+  }
+}
+
+impl DocumentData {
+  /// Tag the blocks with the provenance, the page block excluded. The blocks already tagged keep
+  /// their provenance.
+  pub fn tag_provenance(&mut self, provenance: &BlockProvenance) {
+    let value = provenance.to_value();
+    for block in self.blocks.values_mut() {
+      if block.id != self.page_id && !block.data.contains_key(PROVENANCE_FIELD) {
+        block
+          .data
+          .insert(PROVENANCE_FIELD.to_string(), value.clone());
+      }
+    }
+  }
+
+  /// The ids of the imported blocks whose provenance matches the predicate, in document order.
+  pub fn imported_block_ids<F>(&self, predicate: F) -> Vec<String>
+  where
+    F: Fn(&BlockProvenance) -> bool,
+  {
+    let mut block_ids = vec![];
+    let mut stack = vec![self.page_id.clone()];
+    while let Some(block_id) = stack.pop() {
+      let Some(block) = self.blocks.get(&block_id) else {
+        continue;
+      };
+      if BlockProvenance::from_block(block).is_some_and(|provenance| predicate(&provenance)) {
+        block_ids.push(block_id);
+      }
+      if let Some(children) = self.meta.children_map.get(&block.children) {
+        stack.extend(children.iter().rev().cloned());
+      }
+    }
+    block_ids
+  }
+}
+
+impl Document {
+  /// The provenance of the block, `None` if the block wasn't imported or doesn't exist.
+  pub fn get_block_provenance(&self, block_id: &str) -> Option<BlockProvenance> {
+    BlockProvenance::from_block(&self.get_block(block_id)?)
+  }
+
+  /// The ids of the imported blocks whose provenance matches the predicate, in document order,
+  /// e.g. to remove the blocks imported from a file before importing it again.
+  pub fn find_imported_blocks<F>(&self, predicate: F) -> Result<Vec<String>, DocumentError>
+  where
+    F: Fn(&BlockProvenance) -> bool,
+  {
+    Ok(self.get_document_structure()?.imported_block_ids(predicate))
+  }
+
+  /// Tag the blocks of the document with the provenance, see [DocumentData::tag_provenance].
+  pub fn tag_provenance(&mut self, provenance: &BlockProvenance) -> Result<(), DocumentError> {
+    let page_id = self.get_page_id();
+    let value = provenance.to_value();
+    for block_id in self.get_all_block_ids() {
+      if Some(&block_id) == page_id.as_ref() {
+        continue;
+      }
+      let Some((_, mut data)) = self.get_block_data(&block_id) else {
+        continue;
+      };
+      if !data.contains_key(PROVENANCE_FIELD) {
+        data.insert(PROVENANCE_FIELD.to_string(), value.clone());
+        self.update_block(&block_id, data)?;
+      }
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,230 @@
+use crate::blocks::{Block, BlockType, DocumentData};
+use crate::document_data::generate_id;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashSet;
+use std::fmt::Debug;
+
+/// The field of the block data holding the sensitivity tag of a block, e.g. `"sensitive"`.
+pub const SENSITIVITY_FIELD: &str = "sensitivity";
+/// The tag excluded by [SensitivityTagPolicy::default].
+pub const SENSITIVE_TAG: &str = "sensitive";
+/// The text of the redacted blocks.
+pub const REDACTED_TEXT: &str = "[Redacted]";
+
+/// What happens to a block when the document is exported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionAction {
+  Keep,
+  /// Remove the block and its children.
+  Exclude,
+  /// Replace the block and its children with a paragraph saying the content was redacted, so the
+  /// readers know something was there.
+  Redact,
+}
+
+/// Decides which blocks are kept in an export, see [DocumentData::redact].
+///
+/// The policy is asked for each block in document order, the children of an excluded or
+/// redacted block are not visited.
+pub trait RedactionPolicy: Send + Sync + Debug {
+  fn block_action(&self, block: &Block) -> RedactionAction;
+}
+
+/// The default [RedactionPolicy], it applies the same action to every block whose
+/// [SENSITIVITY_FIELD] is one of the given tags.
+#[derive(Debug, Clone)]
+pub struct SensitivityTagPolicy {
+  tags: HashSet<String>,
+  action: RedactionAction,
+}
+
+impl Default for SensitivityTagPolicy {
+  fn default() -> Self {
+    Self::new([SENSITIVE_TAG], RedactionAction::Exclude)
+  }
+}
+
+impl SensitivityTagPolicy {
+  pub fn new<I, S>(tags: I, action: RedactionAction) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: ToString,
+  {
+    Self {
+      tags: tags.into_iter().map(|tag| tag.to_string()).collect(),
+      action,
+    }
+  }
+}
+
+impl RedactionPolicy for SensitivityTagPolicy {
+  fn block_action(&self, block: &Block) -> RedactionAction {
+    let is_tagged = block
+      .data
+      .get(SENSITIVITY_FIELD)
+      .and_then(|tag| tag.as_str())
+      .is_some_and(|tag| self.tags.contains(tag));
+    if is_tagged {
+      self.action
+    } else {
+      RedactionAction::Keep
+    }
+  }
+}
+
+/// A block removed or redacted by [DocumentData::redact].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedactedBlock {
+  pub block_id: String,
+  /// The type of the block before it was redacted.
+  pub block_type: String,
+  pub action: RedactionAction,
+  /// The number of children removed with the block, at any depth.
+  pub num_of_descendants: usize,
+}
+
+/// The blocks removed or redacted from a document, in document order. It's meant to be shipped
+/// with the export, so the admins can review what was left out.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedactionManifest {
+  pub blocks: Vec<RedactedBlock>,
+}
+
+impl RedactionManifest {
+  pub fn is_empty(&self) -> bool {
+    self.blocks.is_empty()
+  }
+
+  pub fn num_of_excluded(&self) -> usize {
+    self.count(RedactionAction::Exclude)
+  }
+
+  pub fn num_of_redacted(&self) -> usize {
+    self.count(RedactionAction::Redact)
+  }
+
+  fn count(&self, action: RedactionAction) -> usize {
+    self
+      .blocks
+      .iter()
+      .filter(|block| block.action == action)
+      .count()
+  }
+}
+
+impl DocumentData {
+  /// Remove or redact the blocks selected by the policy, before the document is exported.
+  ///
+  /// The page block is always kept. A redacted block becomes a paragraph with [REDACTED_TEXT],
+  /// its data and children are dropped so nothing of the original content is left.
+  pub fn redact(&mut self, policy: &dyn RedactionPolicy) -> RedactionManifest {
+    let mut manifest = RedactionManifest::default();
+    let page_id = self.page_id.clone();
+    self.redact_children(&page_id, policy, &mut manifest);
+    manifest
+  }
+
+  fn redact_children(
+    &mut self,
+    parent_id: &str,
+    policy: &dyn RedactionPolicy,
+    manifest: &mut RedactionManifest,
+  ) {
+    let Some(children_id) = self
+      .blocks
+      .get(parent_id)
+      .map(|block| block.children.clone())
+    else {
+      return;
+    };
+    let Some(children) = self.meta.children_map.get(&children_id).cloned() else {
+      return;
+    };
+    let mut kept = Vec::with_capacity(children.len());
+    for child_id in children {
+      let Some(block) = self.blocks.get(&child_id) else {
+        continue;
+      };
+      let action = policy.block_action(block);
+      let block_type = block.ty.clone();
+      match action {
+        RedactionAction::Keep => {
+          kept.push(child_id.clone());
+          self.redact_children(&child_id, policy, manifest);
+        },
+        RedactionAction::Exclude => {
+          let num_of_descendants = self.remove_descendants(&child_id);
+          self.remove_block(&child_id);
+          manifest.blocks.push(RedactedBlock {
+            block_id: child_id,
+            block_type,
+            action,
+            num_of_descendants,
+          });
+        },
+        RedactionAction::Redact => {
+          let num_of_descendants = self.remove_descendants(&child_id);
+          self.replace_with_placeholder(&child_id);
+          kept.push(child_id.clone());
+          manifest.blocks.push(RedactedBlock {
+            block_id: child_id,
+            block_type,
+            action,
+            num_of_descendants,
+          });
+        },
+      }
+    }
+    self.meta.children_map.insert(children_id, kept);
+  }
+
+  /// Remove the descendants of the block and return how many were removed.
+  fn remove_descendants(&mut self, block_id: &str) -> usize {
+    let Some(children_id) = self
+      .blocks
+      .get(block_id)
+      .map(|block| block.children.clone())
+    else {
+      return 0;
+    };
+    let children = self
+      .meta
+      .children_map
+      .insert(children_id, vec![])
+      .unwrap_or_default();
+    let mut count = 0;
+    for child_id in children {
+      count += 1 + self.remove_descendants(&child_id);
+      self.remove_block(&child_id);
+    }
+    count
+  }
+
+  fn remove_block(&mut self, block_id: &str) {
+    let Some(block) = self.blocks.remove(block_id) else {
+      return;
+    };
+    self.meta.children_map.remove(&block.children);
+    if let (Some(external_id), Some(text_map)) = (&block.external_id, self.meta.text_map.as_mut()) {
+      text_map.remove(external_id);
+    }
+  }
+
+  fn replace_with_placeholder(&mut self, block_id: &str) {
+    let Some(block) = self.blocks.get_mut(block_id) else {
+      return;
+    };
+    block.ty = BlockType::Paragraph.to_string();
+    block.data.clear();
+    let external_id = block.external_id.get_or_insert_with(generate_id).clone();
+    block.external_type = Some("text".to_string());
+    let delta = json!([{ "insert": REDACTED_TEXT }]).to_string();
+    self
+      .meta
+      .text_map
+      .get_or_insert_with(Default::default)
+      .insert(external_id, delta);
+  }
+}
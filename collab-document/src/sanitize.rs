@@ -0,0 +1,107 @@
+use crate::blocks::DocumentData;
+use crate::provenance::PROVENANCE_FIELD;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// The block data fields that tell who changed a block and when. They are removed by
+/// [DocumentData::sanitize].
+pub const AUTHORSHIP_FIELDS: [&str; 7] = [
+  "created_by",
+  "last_modified_by",
+  "uploaded_by",
+  "author",
+  "created_at",
+  "last_modified",
+  "uploaded_at",
+];
+
+/// The text attributes marking the comments and the suggestions. They are removed by
+/// [DocumentData::sanitize], the marked text is kept.
+pub const REVIEW_ATTRIBUTES: [&str; 4] = ["comment", "comments", "suggestion", "suggestions"];
+
+impl DocumentData {
+  /// Strip the data that is only meaningful to the editors of the document, before it's
+  /// published:
+  /// - the blocks and texts left by deleted blocks, which are not reachable from the page
+  /// - the [AUTHORSHIP_FIELDS] of the blocks
+  /// - the [PROVENANCE_FIELD] of the imported blocks, it tells the paths of the imported files
+  /// - the [REVIEW_ATTRIBUTES] of the texts
+  ///
+  /// The texts must be hydrated, the texts of a `lazy_text` data are not read.
+  pub fn sanitize(&mut self) {
+    let reachable = self.reachable_block_ids();
+    self.blocks.retain(|id, _| reachable.contains(id));
+    let children_ids = self
+      .blocks
+      .values()
+      .map(|block| block.children.clone())
+      .collect::<HashSet<_>>();
+    self
+      .meta
+      .children_map
+      .retain(|id, _| children_ids.contains(id));
+
+    let external_ids = self
+      .blocks
+      .values()
+      .filter_map(|block| block.external_id.clone())
+      .collect::<HashSet<_>>();
+    if let Some(text_map) = self.meta.text_map.as_mut() {
+      text_map.retain(|id, _| external_ids.contains(id));
+      for delta in text_map.values_mut() {
+        if let Some(sanitized) = strip_review_attributes(delta) {
+          *delta = sanitized;
+        }
+      }
+    }
+
+    for block in self.blocks.values_mut() {
+      for field in AUTHORSHIP_FIELDS {
+        block.data.remove(field);
+      }
+      block.data.remove(PROVENANCE_FIELD);
+    }
+  }
+
+  fn reachable_block_ids(&self) -> HashSet<String> {
+    let mut reachable = HashSet::new();
+    let mut stack = vec![self.page_id.clone()];
+    while let Some(block_id) = stack.pop() {
+      let Some(block) = self.blocks.get(&block_id) else {
+        continue;
+      };
+      if !reachable.insert(block_id) {
+        continue;
+      }
+      if let Some(children) = self.meta.children_map.get(&block.children) {
+        stack.extend(children.iter().cloned());
+      }
+    }
+    reachable
+  }
+}
+
+/// Return the delta without the [REVIEW_ATTRIBUTES], or None if it has none or can't be parsed.
+fn strip_review_attributes(delta: &str) -> Option<String> {
+  let mut ops = serde_json::from_str::<Vec<Value>>(delta).ok()?;
+  let mut changed = false;
+  for op in ops.iter_mut() {
+    let Some(op) = op.as_object_mut() else {
+      continue;
+    };
+    let Some(attributes) = op.get_mut("attributes").and_then(Value::as_object_mut) else {
+      continue;
+    };
+    for attribute in REVIEW_ATTRIBUTES {
+      changed |= attributes.remove(attribute).is_some();
+    }
+    if attributes.is_empty() {
+      op.remove("attributes");
+    }
+  }
+  if changed {
+    serde_json::to_string(&ops).ok()
+  } else {
+    None
+  }
+}
@@ -0,0 +1,138 @@
+use crate::blocks::{BlockType, DocumentData};
+use crate::document::Document;
+use crate::error::DocumentError;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// The field of the heading data holding the slug of the heading, the anchor the links to the
+/// heading use, e.g. `#getting-started`.
+pub const SLUG_FIELD: &str = "slug";
+
+/// The anchor GitHub and Notion generate for a heading: the text is lowercased, the punctuation
+/// and the symbols are removed and the spaces become hyphens.
+///
+/// `Getting Started!` becomes `getting-started` and `What's new in v1.0?` becomes
+/// `whats-new-in-v10`.
+pub fn heading_slug(text: &str) -> String {
+  text
+    .trim()
+    .to_lowercase()
+    .chars()
+    .filter_map(|c| match c {
+      ' ' => Some('-'),
+      '-' | '_' => Some(c),
+      c if c.is_alphanumeric() => Some(c),
+      _ => None,
+    })
+    .collect()
+}
+
+/// Generates the slugs of the headings of a document, in document order. A slug that is already
+/// used gets a `-1`, `-2`, … suffix, like GitHub does.
+#[derive(Debug, Clone, Default)]
+pub struct HeadingSlugger {
+  used: HashSet<String>,
+  occurrences: HashMap<String, usize>,
+}
+
+impl HeadingSlugger {
+  pub fn slug(&mut self, text: &str) -> String {
+    let base = heading_slug(text);
+    let mut slug = base.clone();
+    while self.used.contains(&slug) {
+      let occurrence = self.occurrences.entry(base.clone()).or_default();
+      *occurrence += 1;
+      slug = format!("{}-{}", base, occurrence);
+    }
+    self.used.insert(slug.clone());
+    slug
+  }
+}
+
+impl DocumentData {
+  /// The ids and the slugs of the headings, in document order.
+  ///
+  /// The texts must be hydrated, the headings of a `lazy_text` data have empty slugs.
+  pub fn heading_slugs(&self) -> Vec<(String, String)> {
+    let mut slugger = HeadingSlugger::default();
+    let mut slugs = vec![];
+    let mut stack = vec![self.page_id.clone()];
+    while let Some(block_id) = stack.pop() {
+      let Some(block) = self.blocks.get(&block_id) else {
+        continue;
+      };
+      if block.ty == BlockType::Heading.as_str() {
+        let text = block
+          .external_id
+          .as_ref()
+          .and_then(|text_id| self.meta.text_map.as_ref()?.get(text_id))
+          .map(|delta| delta_plain_text(delta))
+          .unwrap_or_default();
+        slugs.push((block_id.clone(), slugger.slug(&text)));
+      }
+      if let Some(children) = self.meta.children_map.get(&block.children) {
+        stack.extend(children.iter().rev().cloned());
+      }
+    }
+    slugs
+  }
+
+  /// Store the slug of each heading in its [SLUG_FIELD], replacing the slugs of a previous call.
+  pub fn assign_heading_slugs(&mut self) {
+    for (block_id, slug) in self.heading_slugs() {
+      if let Some(block) = self.blocks.get_mut(&block_id) {
+        block
+          .data
+          .insert(SLUG_FIELD.to_string(), Value::String(slug));
+      }
+    }
+  }
+
+  /// The id of the heading an intra-document link points to, e.g. `#getting-started`. The
+  /// stored slugs are used when the headings have one, they are generated otherwise.
+  pub fn heading_id_by_anchor(&self, anchor: &str) -> Option<String> {
+    let anchor = anchor.strip_prefix('#').unwrap_or(anchor);
+    let stored = self.blocks.values().find(|block| {
+      block.ty == BlockType::Heading.as_str()
+        && block.data.get(SLUG_FIELD).and_then(Value::as_str) == Some(anchor)
+    });
+    match stored {
+      Some(block) => Some(block.id.clone()),
+      None => self
+        .heading_slugs()
+        .into_iter()
+        .find(|(_, slug)| slug == anchor)
+        .map(|(block_id, _)| block_id),
+    }
+  }
+}
+
+impl Document {
+  /// Store the slug of each heading of the document, see [DocumentData::assign_heading_slugs].
+  /// Only the headings whose slug changed are updated.
+  pub fn assign_heading_slugs(&mut self) -> Result<(), DocumentError> {
+    let data = self.get_document_data()?;
+    for (block_id, slug) in data.heading_slugs() {
+      let Some(mut block_data) = data.blocks.get(&block_id).map(|block| block.data.clone()) else {
+        continue;
+      };
+      if block_data.get(SLUG_FIELD).and_then(Value::as_str) == Some(slug.as_str()) {
+        continue;
+      }
+      block_data.insert(SLUG_FIELD.to_string(), Value::String(slug));
+      self.update_block(&block_id, block_data)?;
+    }
+    Ok(())
+  }
+}
+
+/// The text of a delta json string, the embeds excluded.
+pub(crate) fn delta_plain_text(delta: &str) -> String {
+  let Ok(Value::Array(ops)) = serde_json::from_str::<Value>(delta) else {
+    return String::new();
+  };
+  ops
+    .iter()
+    .filter_map(|op| op.get("insert")?.as_str())
+    .collect()
+}
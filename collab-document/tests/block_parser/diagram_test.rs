@@ -0,0 +1,53 @@
+use collab_document::block_parser::{DocumentParser, OutputFormat};
+use collab_document::blocks::{
+  Block, BlockType, DiagramData, DiagramKind, DocumentDataBuilder, NewBlock,
+};
+use serde_json::json;
+
+#[test]
+fn diagram_kind_from_code_language_test() {
+  assert_eq!(
+    DiagramKind::from_code_language("Mermaid"),
+    Some(DiagramKind::Mermaid)
+  );
+  assert_eq!(
+    DiagramKind::from_code_language("puml"),
+    Some(DiagramKind::PlantUml)
+  );
+  assert_eq!(DiagramKind::from_code_language("rust"), None);
+  assert_eq!(DiagramData::from_code_block("rust", "fn main() {}"), None);
+}
+
+#[test]
+fn diagram_parser_test() {
+  let data = DocumentDataBuilder::new()
+    .with_block(Block::diagram(DiagramKind::Mermaid, "graph TD\n  A --> B"))
+    .with_block(Block::diagram(DiagramKind::PlantUml, "Alice -> Bob"))
+    .build();
+  let parser = DocumentParser::with_default_parsers();
+
+  let markdown = parser
+    .parse_document(&data, OutputFormat::Markdown)
+    .unwrap();
+  assert_eq!(
+    markdown,
+    "```mermaid\ngraph TD\n  A --> B\n```\n```plantuml\nAlice -> Bob\n```"
+  );
+
+  let plain_text = parser
+    .parse_document(&data, OutputFormat::PlainText)
+    .unwrap();
+  assert_eq!(plain_text, "graph TD\n  A --> B\nAlice -> Bob");
+}
+
+#[test]
+fn diagram_parser_unknown_kind_test() {
+  let data = DocumentDataBuilder::new()
+    .with_block(NewBlock::new(BlockType::Diagram).with_data("kind", json!("graphviz")))
+    .build();
+  let parser = DocumentParser::with_default_parsers();
+  let markdown = parser
+    .parse_document(&data, OutputFormat::Markdown)
+    .unwrap();
+  assert_eq!(markdown, "");
+}
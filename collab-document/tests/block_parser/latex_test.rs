@@ -0,0 +1,104 @@
+use collab_document::block_parser::{DocumentParser, escape_latex, latex_asset_path};
+use collab_document::blocks::{Block, BlockType, DocumentDataBuilder, NewBlock};
+use serde_json::json;
+
+#[test]
+fn export_document_to_latex_test() {
+  let mut builder = DocumentDataBuilder::new();
+  builder.push(Block::heading(1, "Results & Discussion"));
+  builder.push(NewBlock::new(BlockType::Paragraph).with_delta(json!([
+    { "insert": "Mean " },
+    { "insert": "x_1", "attributes": { "bold": true } },
+  ])));
+  builder.push(Block::paragraph("a").with_child(Block::paragraph("nested")));
+  builder.push(NewBlock::new(BlockType::BulletedList).with_text("first"));
+  builder.push(NewBlock::new(BlockType::BulletedList).with_text("second"));
+  builder.push(Block::todo("done", true));
+  builder.push(NewBlock::new(BlockType::MathEquation).with_data("formula", json!("E = mc^2")));
+  builder.push(
+    NewBlock::new(BlockType::Code)
+      .with_data("language", json!("python"))
+      .with_text("print(1 % 2)"),
+  );
+  let mut table = Block::table(2, 2);
+  for (row, texts) in [["A", "B"], ["1", "2"]].into_iter().enumerate() {
+    for (col, text) in texts.into_iter().enumerate() {
+      table.children[row].children[col].children[0] = Block::paragraph(text);
+    }
+  }
+  builder.push(table);
+  let image_id = builder.push(Block::image("https://appflowy.io/plot.JPG?size=2"));
+  let data = builder.build();
+
+  let export = DocumentParser::with_default_parsers()
+    .export_latex(&data)
+    .unwrap();
+  let image = format!(
+    "\\begin{{center}}\n\\includegraphics[width=\\linewidth]{{images/{}.jpg}}\n\\end{{center}}",
+    image_id
+  );
+  let expected = [
+    "\\section{Results \\& Discussion}",
+    "Mean \\textbf{x\\_1}\n",
+    // the children of a block are followed by a line break
+    "a\n\nnested\n\n",
+    // the consecutive list items are merged into one list
+    "\\begin{itemize}\n\\item first\n\\item second\n\\item[$\\boxtimes$] done\n\\end{itemize}",
+    "\\[\nE = mc^2\n\\]",
+    "\\begin{lstlisting}[language=Python]\nprint(1 % 2)\n\\end{lstlisting}",
+    "\\begin{tabular}{|l|l|}\n\\hline\nA & B \\\\\n\\hline\n1 & 2 \\\\\n\\hline\n\\end{tabular}",
+    image.as_str(),
+  ]
+  .join("\n");
+  assert_eq!(export.content, expected);
+  assert_eq!(export.assets.len(), 1);
+  assert_eq!(export.assets[0].block_id, image_id);
+  assert_eq!(export.assets[0].url, "https://appflowy.io/plot.JPG?size=2");
+  assert_eq!(export.assets[0].path, format!("images/{}.jpg", image_id));
+
+  let standalone = export.standalone("Paper");
+  assert!(standalone.starts_with("\\documentclass{article}\n"));
+  assert!(standalone.contains("\\usepackage{listings}"));
+  assert!(standalone.contains("\\title{Paper}"));
+  assert!(standalone.ends_with(&format!("{}\n\\end{{document}}\n", export.content)));
+}
+
+#[test]
+fn export_nested_list_to_latex_test() {
+  let data = DocumentDataBuilder::new()
+    .with_block(
+      NewBlock::new(BlockType::NumberedList)
+        .with_text("one")
+        .with_child(NewBlock::new(BlockType::BulletedList).with_text("child")),
+    )
+    .with_block(NewBlock::new(BlockType::NumberedList).with_text("two"))
+    .build();
+  let export = DocumentParser::with_default_parsers()
+    .export_latex(&data)
+    .unwrap();
+  assert_eq!(
+    export.content,
+    "\\begin{enumerate}\n\\item one\n\\begin{itemize}\n\\item child\n\\end{itemize}\n\n\\item two\n\\end{enumerate}"
+  );
+  assert!(export.assets.is_empty());
+}
+
+#[test]
+fn escape_latex_test() {
+  assert_eq!(
+    escape_latex("50% of $x_1 & {y} #2 ~ ^ \\"),
+    "50\\% of \\$x\\_1 \\& \\{y\\} \\#2 \\textasciitilde{} \\textasciicircum{} \\textbackslash{}"
+  );
+  assert_eq!(
+    latex_asset_path("b1", "https://a.io/x/photo.png"),
+    "images/b1.png"
+  );
+  assert_eq!(
+    latex_asset_path("b1", "https://a.io/x/file?id=1"),
+    "images/b1.png"
+  );
+  assert_eq!(
+    latex_asset_path("b1", "/local/pic.webp#top"),
+    "images/b1.webp"
+  );
+}
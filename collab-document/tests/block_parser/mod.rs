@@ -1,11 +1,13 @@
 mod bulleted_list_test;
 mod callout_test;
 mod code_block_test;
+mod diagram_test;
 mod divider_test;
 mod document_parser_test;
 mod file_block_test;
 mod heading_test;
 mod image_test;
+mod latex_test;
 mod link_preview_test;
 mod math_equation_test;
 mod numbered_list_test;
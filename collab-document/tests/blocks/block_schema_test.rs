@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use collab_document::block_parser::{DocumentParser, OutputFormat};
+use collab_document::blocks::{
+  Block, BlockFieldKind, BlockSchema, BlockSchemaRegistry, BlockType, ChildPolicy,
+};
+use collab_document::error::DocumentError;
+use serde_json::json;
+
+use crate::blocks::block_test_core::{BlockTestCore, generate_id};
+
+fn poll_schema() -> BlockSchema {
+  BlockSchema::new("poll")
+    .with_field("question", BlockFieldKind::String)
+    .with_optional_field("options", BlockFieldKind::Array, Some(json!([])))
+    .with_optional_field("multiple", BlockFieldKind::Bool, Some(json!(false)))
+    .with_children(ChildPolicy::Only(vec![BlockType::Paragraph.to_string()]))
+    .with_text_field("question")
+}
+
+fn custom_block(ty: &str, parent_id: &str, data: HashMap<String, serde_json::Value>) -> Block {
+  Block {
+    id: generate_id(),
+    ty: ty.to_string(),
+    parent: parent_id.to_string(),
+    children: generate_id(),
+    external_id: None,
+    external_type: None,
+    data,
+  }
+}
+
+#[test]
+fn block_schema_validation_test() {
+  let schema = poll_schema();
+  assert_eq!(
+    schema.default_data(),
+    HashMap::from([
+      ("options".to_string(), json!([])),
+      ("multiple".to_string(), json!(false)),
+    ])
+  );
+
+  let mut data = schema.default_data();
+  let err = schema.validate_data(&data).unwrap_err();
+  assert!(matches!(err, DocumentError::BlockSchemaViolation { .. }));
+  assert_eq!(err.code(), "document.block_schema_violation");
+
+  data.insert("question".to_string(), json!("Lunch?"));
+  schema.validate_data(&data).unwrap();
+  data.insert("multiple".to_string(), json!("yes"));
+  assert!(schema.validate_data(&data).is_err());
+
+  // The schemas round-trip through json, so a plugin can ship them as a file.
+  let json = serde_json::to_value(&schema).unwrap();
+  assert_eq!(
+    json["children"],
+    json!({ "kind": "only", "types": ["paragraph"] })
+  );
+  let decoded: BlockSchema = serde_json::from_value(json).unwrap();
+  assert_eq!(decoded, schema);
+}
+
+#[test]
+fn register_block_schema_test() {
+  let mut registry = BlockSchemaRegistry::new();
+  registry.register(poll_schema()).unwrap();
+  assert!(registry.get("poll").is_some());
+
+  // The built-in block types can't be redefined.
+  assert!(registry.register(BlockSchema::new("paragraph")).is_err());
+  assert!(registry.get("paragraph").is_none());
+
+  let registry =
+    BlockSchemaRegistry::from(vec![BlockSchema::new("drawio"), BlockSchema::new("code")]);
+  assert!(registry.get("drawio").is_some());
+  assert!(registry.get("code").is_none());
+}
+
+#[test]
+fn document_validates_custom_blocks_test() {
+  let mut test = BlockTestCore::new();
+  let mut registry = BlockSchemaRegistry::new();
+  registry.register(poll_schema()).unwrap();
+  test.document.set_block_schemas(Arc::new(registry));
+  let page_id = test.get_page().id;
+
+  let err = test
+    .document
+    .insert_block(custom_block("poll", &page_id, HashMap::new()), None)
+    .unwrap_err();
+  assert!(matches!(err, DocumentError::BlockSchemaViolation { .. }));
+
+  let data = HashMap::from([("question".to_string(), json!("Lunch?"))]);
+  let poll = test
+    .document
+    .insert_block(custom_block("poll", &page_id, data), None)
+    .unwrap();
+
+  // Only the children allowed by the schema can be inserted.
+  test.insert_text_block("Pizza".to_string(), &poll.id, None);
+  let err = test
+    .document
+    .insert_block(
+      custom_block(BlockType::Divider.as_str(), &poll.id, HashMap::new()),
+      None,
+    )
+    .unwrap_err();
+  assert!(matches!(err, DocumentError::BlockSchemaViolation { .. }));
+
+  let err = test
+    .document
+    .update_block(
+      &poll.id,
+      HashMap::from([("question".to_string(), json!(1))]),
+    )
+    .unwrap_err();
+  assert!(matches!(err, DocumentError::BlockSchemaViolation { .. }));
+
+  // The blocks of the types that are not registered are not validated.
+  test
+    .document
+    .insert_block(custom_block("drawio", &page_id, HashMap::new()), None)
+    .unwrap();
+}
+
+#[test]
+fn export_custom_blocks_test() {
+  let mut test = BlockTestCore::new();
+  let page_id = test.get_page().id;
+  let data = HashMap::from([("question".to_string(), json!("Lunch?"))]);
+  let poll = test
+    .document
+    .insert_block(custom_block("poll", &page_id, data), None)
+    .unwrap();
+  test.insert_text_block("Pizza".to_string(), &poll.id, None);
+
+  let mut registry = BlockSchemaRegistry::new();
+  registry.register(poll_schema()).unwrap();
+  let parser = DocumentParser::with_default_parsers().with_block_schemas(&registry);
+  let document_data = test.get_document_data();
+  let text = parser
+    .parse_document(&document_data, OutputFormat::PlainText)
+    .unwrap();
+  let lines = text
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty())
+    .collect::<Vec<_>>();
+  assert!(
+    lines.windows(2).any(|lines| lines == ["Lunch?", "Pizza"]),
+    "{}",
+    text
+  );
+}
@@ -112,6 +112,7 @@ impl BlockTestCore {
     let meta = DocumentMeta {
       children_map,
       text_map: Some(text_map),
+      lazy_text: false,
     };
     DocumentData {
       page_id,
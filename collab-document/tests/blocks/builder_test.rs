@@ -0,0 +1,109 @@
+use crate::util::{block_delta, children_ids};
+use collab::core::collab::default_client_id;
+use collab_document::blocks::{Block, BlockType, DocumentDataBuilder};
+use collab_document::document::Document;
+use collab_document::error::DocumentError;
+use serde_json::json;
+
+#[test]
+fn typed_block_builders_test() {
+  let mut builder = DocumentDataBuilder::new();
+  let heading_id = builder.push(Block::heading(2, "Groceries"));
+  let todo_id = builder.push(Block::todo("Milk", true));
+  let image_id = builder.push(Block::image("https://appflowy.io/logo.png"));
+  builder
+    .push_child(&todo_id, Block::paragraph("2 liters"))
+    .unwrap();
+  let err = builder.push_child("unknown", Block::divider()).unwrap_err();
+  assert!(matches!(err, DocumentError::ParentIsNotFound));
+  let data = builder.build();
+
+  assert_eq!(
+    children_ids(&data, &data.page_id),
+    vec![heading_id.clone(), todo_id.clone(), image_id.clone()]
+  );
+  let heading = &data.blocks[&heading_id];
+  assert_eq!(heading.ty, BlockType::Heading.as_str());
+  assert_eq!(heading.data["level"], json!(2));
+  assert_eq!(
+    block_delta(&data, &heading_id),
+    json!([{ "insert": "Groceries" }])
+  );
+  assert_eq!(data.blocks[&todo_id].data["checked"], json!(true));
+  let todo_children = children_ids(&data, &todo_id);
+  assert_eq!(todo_children.len(), 1);
+  assert_eq!(data.blocks[&todo_children[0]].parent, todo_id);
+
+  // The blocks without text have no entry in the text map.
+  let image = &data.blocks[&image_id];
+  assert_eq!(image.data["url"], json!("https://appflowy.io/logo.png"));
+  assert!(image.external_id.is_none());
+  assert!(children_ids(&data, &image_id).is_empty());
+}
+
+#[test]
+fn table_builder_test() {
+  let data = DocumentDataBuilder::new()
+    .with_block(Block::table(2, 3))
+    .build();
+  let table_id = children_ids(&data, &data.page_id)[0].clone();
+  assert_eq!(data.blocks[&table_id].ty, BlockType::SimpleTable.as_str());
+
+  let rows = children_ids(&data, &table_id);
+  assert_eq!(rows.len(), 2);
+  for (row_index, row_id) in rows.iter().enumerate() {
+    let cells = children_ids(&data, row_id);
+    assert_eq!(cells.len(), 3);
+    for (col_index, cell_id) in cells.iter().enumerate() {
+      let cell = &data.blocks[cell_id];
+      assert_eq!(cell.data["rowPosition"], json!(row_index));
+      assert_eq!(cell.data["colPosition"], json!(col_index));
+      let paragraphs = children_ids(&data, cell_id);
+      assert_eq!(paragraphs.len(), 1);
+      assert_eq!(block_delta(&data, &paragraphs[0]), json!([]));
+    }
+  }
+  // The page, the table, 2 rows, 6 cells and their paragraphs.
+  assert_eq!(data.blocks.len(), 1 + 1 + 2 + 6 + 6);
+}
+
+#[test]
+fn block_with_id_builder_test() {
+  let mut builder = DocumentDataBuilder::new();
+  let block_id = builder.push(
+    Block::paragraph("Referenced")
+      .with_id("block_1")
+      .with_child(Block::paragraph("Child")),
+  );
+  let data = builder.build();
+  assert_eq!(block_id, "block_1");
+  assert_eq!(children_ids(&data, &data.page_id), vec!["block_1"]);
+  assert_eq!(
+    block_delta(&data, "block_1"),
+    json!([{ "insert": "Referenced" }])
+  );
+  let child_id = children_ids(&data, "block_1")[0].clone();
+  assert_ne!(child_id, "block_1");
+  assert_eq!(data.blocks[&child_id].parent, "block_1");
+}
+
+#[test]
+fn create_document_from_builder_test() {
+  let data = DocumentDataBuilder::new()
+    .with_block(Block::heading(1, "Title"))
+    .with_block(Block::paragraph("Hello"))
+    .with_block(Block::divider())
+    .build();
+  let page_id = data.page_id.clone();
+  let document = Document::create("builder_test", data, default_client_id()).unwrap();
+  let ids = document.get_block_children_ids(&page_id);
+  assert_eq!(ids.len(), 3);
+  assert_eq!(
+    document.get_plain_text_from_block(&ids[0]).unwrap(),
+    "Title"
+  );
+  assert_eq!(
+    document.get_plain_text_from_block(&ids[1]).unwrap(),
+    "Hello"
+  );
+}
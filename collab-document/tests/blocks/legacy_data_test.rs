@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use collab::core::collab::default_client_id;
+use collab_document::blocks::{
+  Block, LegacyDataShape, adapt_legacy_block_data, legacy_read_metrics,
+};
+use collab_document::document::Document;
+use collab_document::document_data::default_document_data;
+use serde_json::{Value, json};
+
+fn data(value: Value) -> HashMap<String, Value> {
+  serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn adapt_legacy_value_shapes_test() {
+  let mut heading = data(json!({ "level": "2" }));
+  let shapes = adapt_legacy_block_data("heading", &mut heading);
+  assert_eq!(shapes, vec![LegacyDataShape::StringHeadingLevel]);
+  assert_eq!(heading["level"], json!(2));
+
+  let mut todo = data(json!({ "checked": "true" }));
+  adapt_legacy_block_data("todo_list", &mut todo);
+  assert_eq!(todo["checked"], json!(true));
+  let mut todo = data(json!({ "checked": 0 }));
+  adapt_legacy_block_data("todo_list", &mut todo);
+  assert_eq!(todo["checked"], json!(false));
+
+  let mut cell = data(json!({ "position": "1:3" }));
+  let shapes = adapt_legacy_block_data("simple_table_cell", &mut cell);
+  assert_eq!(shapes, vec![LegacyDataShape::DelimitedCellPosition]);
+  assert_eq!(cell, data(json!({ "rowPosition": 1, "colPosition": 3 })));
+}
+
+#[test]
+fn adapt_legacy_keys_test() {
+  let mut table = data(json!({ "rows_len": 2, "colsLen": 3, "cols_len": 5 }));
+  let shapes = adapt_legacy_block_data("simple_table", &mut table);
+  assert_eq!(shapes, vec![LegacyDataShape::SnakeCaseTableLen]);
+  // the current key wins over the legacy one
+  assert_eq!(table, data(json!({ "rowsLen": 2, "colsLen": 3 })));
+
+  // the current shapes and the other block types are left untouched
+  let mut sub_page = data(json!({ "viewId": "view" }));
+  assert!(adapt_legacy_block_data("sub_page", &mut sub_page).is_empty());
+  let mut paragraph = data(json!({ "level": "2" }));
+  assert!(adapt_legacy_block_data("paragraph", &mut paragraph).is_empty());
+  assert_eq!(paragraph["level"], json!("2"));
+}
+
+#[test]
+fn read_legacy_block_data_test() {
+  let mut document_data = default_document_data("1");
+  let page = document_data.blocks[&document_data.page_id].clone();
+  let block = Block {
+    id: "legacy_sub_page".to_string(),
+    ty: "sub_page".to_string(),
+    parent: page.id.clone(),
+    children: "legacy_sub_page".to_string(),
+    external_id: None,
+    external_type: None,
+    data: data(json!({ "view_id": "view" })),
+  };
+  document_data
+    .meta
+    .children_map
+    .get_mut(&page.children)
+    .unwrap()
+    .push(block.id.clone());
+  document_data
+    .meta
+    .children_map
+    .insert(block.children.clone(), vec![]);
+  document_data.blocks.insert(block.id.clone(), block);
+
+  let hits_before = legacy_read_metrics()[&LegacyDataShape::SnakeCaseViewId];
+  let document = Document::create("1", document_data, default_client_id()).unwrap();
+  let block = document.get_block("legacy_sub_page").unwrap();
+  assert_eq!(block.data, data(json!({ "viewId": "view" })));
+  let hits_after = legacy_read_metrics()[&LegacyDataShape::SnakeCaseViewId];
+  assert!(hits_after > hits_before);
+}
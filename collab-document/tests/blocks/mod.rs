@@ -1,3 +1,8 @@
+mod block_schema_test;
 mod block_test;
 pub mod block_test_core;
+mod builder_test;
+mod legacy_data_test;
+mod simple_table_data_test;
+mod subtree_subscription_test;
 mod text_test;
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+use collab_document::blocks::{
+  Block, BlockType, SIMPLE_TABLE_DEFAULT_COLUMN_WIDTH, SimpleTableData,
+};
+use collab_document::error::DocumentError;
+use serde_json::json;
+
+use crate::blocks::block_test_core::{BlockTestCore, generate_id};
+
+fn insert_simple_table(
+  test: &mut BlockTestCore,
+  data: HashMap<String, serde_json::Value>,
+) -> Block {
+  let block = Block {
+    id: generate_id(),
+    ty: BlockType::SimpleTable.as_str().to_string(),
+    parent: test.get_page().id,
+    children: generate_id(),
+    external_id: None,
+    external_type: None,
+    data,
+  };
+  test.document.insert_block(block, None).unwrap()
+}
+
+#[test]
+fn simple_table_data_default_test() {
+  let mut test = BlockTestCore::new();
+  let table = insert_simple_table(&mut test, HashMap::new());
+
+  let table_data = test.document.get_simple_table_data(&table.id).unwrap();
+  assert_eq!(table_data, SimpleTableData::default());
+  assert_eq!(
+    table_data.column_width(3),
+    SIMPLE_TABLE_DEFAULT_COLUMN_WIDTH
+  );
+  assert!(!table_data.is_header_cell(0, 0));
+}
+
+#[test]
+fn simple_table_data_read_untyped_json_test() {
+  let mut test = BlockTestCore::new();
+  let mut data = HashMap::new();
+  data.insert(
+    "column_widths".to_string(),
+    json!({ "0": 120, "1": "200.5", "x": 10 }),
+  );
+  data.insert("enable_header_row".to_string(), json!(true));
+  data.insert("enable_header_column".to_string(), json!("true"));
+  let table = insert_simple_table(&mut test, data);
+
+  let table_data = test.document.get_simple_table_data(&table.id).unwrap();
+  assert_eq!(table_data.column_width(0), 120.0);
+  assert_eq!(table_data.column_width(1), 200.5);
+  assert_eq!(table_data.column_widths.len(), 2);
+  assert!(table_data.enable_header_row);
+  assert!(table_data.enable_header_column);
+  assert!(!table_data.enable_striped_rows);
+  assert!(table_data.is_header_cell(0, 2));
+  assert!(table_data.is_header_cell(2, 0));
+  assert!(!table_data.is_header_cell(1, 1));
+}
+
+#[test]
+fn simple_table_data_update_keeps_other_keys_test() {
+  let mut test = BlockTestCore::new();
+  let mut data = HashMap::new();
+  data.insert("column_colors".to_string(), json!({ "0": "0xFFFF0000" }));
+  let table = insert_simple_table(&mut test, data);
+
+  test
+    .document
+    .update_simple_table_data(&table.id, |table_data| {
+      table_data.set_column_width(1, 240.0);
+      table_data.enable_header_row = true;
+      table_data.enable_striped_rows = true;
+    })
+    .unwrap();
+
+  let block = test.get_block(&table.id);
+  assert_eq!(block.data["column_colors"], json!({ "0": "0xFFFF0000" }));
+  assert_eq!(block.data["column_widths"], json!({ "1": 240.0 }));
+  assert_eq!(block.data["enable_header_row"], json!(true));
+
+  let table_data = test.document.get_simple_table_data(&table.id).unwrap();
+  assert_eq!(table_data.column_width(1), 240.0);
+  // The header row is not striped, the rows after it alternate.
+  assert!(!table_data.is_striped_row(0));
+  assert!(!table_data.is_striped_row(1));
+  assert!(table_data.is_striped_row(2));
+  assert!(!table_data.is_striped_row(3));
+
+  test
+    .document
+    .update_simple_table_data(&table.id, |table_data| table_data.reset_column_width(1))
+    .unwrap();
+  let block = test.get_block(&table.id);
+  assert!(!block.data.contains_key("column_widths"));
+}
+
+#[test]
+fn simple_table_data_on_other_block_test() {
+  let test = BlockTestCore::new();
+  let page = test.get_page();
+  let result = test.document.get_simple_table_data(&page.id);
+  assert!(matches!(
+    result,
+    Err(DocumentError::BlockTypeMismatch { .. })
+  ));
+
+  let result = test.document.get_simple_table_data("unknown");
+  assert!(matches!(result, Err(DocumentError::BlockIsNotFound)));
+}
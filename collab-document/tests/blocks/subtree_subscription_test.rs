@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde_json::json;
+
+use crate::blocks::block_test_core::BlockTestCore;
+
+fn recorder() -> (Arc<Mutex<Vec<String>>>, impl Fn(&str) + Clone) {
+  let ids = Arc::new(Mutex::new(vec![]));
+  let cloned_ids = ids.clone();
+  let record = move |id: &str| cloned_ids.lock().unwrap().push(id.to_string());
+  (ids, record)
+}
+
+#[test]
+fn subtree_subscriber_only_receives_changes_of_its_subtree_test() {
+  let mut test = BlockTestCore::new();
+  let page = test.get_page();
+  let first = test.insert_text_block("first".to_string(), &page.id, None);
+  let second = test.insert_text_block("second".to_string(), &page.id, Some(first.id.clone()));
+
+  let (first_events, record_first) = recorder();
+  test
+    .document
+    .subscribe_subtree_changed("first", &first.id, move |events, _| {
+      for event in events {
+        for payload in event.iter() {
+          record_first(&payload.id);
+        }
+      }
+    });
+  let (second_events, record_second) = recorder();
+  test
+    .document
+    .subscribe_subtree_changed("second", &second.id, move |events, _| {
+      for event in events {
+        for payload in event.iter() {
+          record_second(&payload.id);
+        }
+      }
+    });
+
+  // Nest a block in the first block, the second subscriber is not notified.
+  let nested = test.insert_text_block("nested".to_string(), &first.id, None);
+  assert!(!first_events.lock().unwrap().is_empty());
+  assert!(second_events.lock().unwrap().is_empty());
+
+  // Edit the text of the nested block.
+  first_events.lock().unwrap().clear();
+  let text_id = nested.external_id.clone().unwrap();
+  test.document.apply_text_delta(
+    &text_id,
+    json!([{ "retain": 6 }, { "insert": "!" }]).to_string(),
+  );
+  assert_eq!(first_events.lock().unwrap().as_slice(), &[text_id]);
+  assert!(second_events.lock().unwrap().is_empty());
+
+  // Update the second block, the first subscriber is not notified.
+  first_events.lock().unwrap().clear();
+  let mut data = HashMap::new();
+  data.insert("checked".to_string(), json!(true));
+  test.update_block_data(&second.id, data);
+  assert!(first_events.lock().unwrap().is_empty());
+  assert!(second_events.lock().unwrap().contains(&second.id));
+}
+
+#[test]
+fn subtree_subscriber_receives_removed_descendant_test() {
+  let mut test = BlockTestCore::new();
+  let page = test.get_page();
+  let parent = test.insert_text_block("parent".to_string(), &page.id, None);
+  let child = test.insert_text_block("child".to_string(), &parent.id, None);
+
+  let (events, record) = recorder();
+  test
+    .document
+    .subscribe_subtree_changed("parent", &parent.id, move |events, _| {
+      for event in events {
+        for payload in event.iter() {
+          record(&payload.id);
+        }
+      }
+    });
+
+  test.delete_block(&child.id);
+  assert!(!events.lock().unwrap().is_empty());
+}
+
+#[test]
+fn unsubscribe_subtree_test() {
+  let mut test = BlockTestCore::new();
+  let page = test.get_page();
+  let block = test.insert_text_block("block".to_string(), &page.id, None);
+
+  let (events, record) = recorder();
+  test
+    .document
+    .subscribe_subtree_changed("block", &block.id, move |_, _| record("called"));
+  test.insert_text_block("child".to_string(), &block.id, None);
+  assert_eq!(events.lock().unwrap().len(), 1);
+
+  test.document.unsubscribe_subtree_changed("block");
+  test.insert_text_block("child 2".to_string(), &block.id, None);
+  assert_eq!(events.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn page_subtree_subscriber_receives_all_block_changes_test() {
+  let mut test = BlockTestCore::new();
+  let page = test.get_page();
+
+  let (events, record) = recorder();
+  test
+    .document
+    .subscribe_subtree_changed("page", &page.id, move |_, _| record("called"));
+  let block = test.insert_text_block("block".to_string(), &page.id, None);
+  test.insert_text_block("nested".to_string(), &block.id, None);
+  assert_eq!(events.lock().unwrap().len(), 2);
+}
@@ -0,0 +1,117 @@
+use collab_document::blocks::{Block, BlockType, DocumentDataBuilder, NewBlock};
+use collab_document::chat::{ChatExporter, ChatFormat};
+use serde_json::json;
+use std::collections::HashMap;
+
+fn rich_paragraph() -> NewBlock {
+  NewBlock::new(BlockType::Paragraph).with_delta(json!([
+    { "insert": "Ship " },
+    { "insert": "v2", "attributes": { "bold": true } },
+    { "insert": " by " },
+    { "insert": "Friday", "attributes": { "italic": true, "href": "https://appflowy.io" } },
+    { "insert": " with " },
+    {
+      "insert": "$",
+      "attributes": { "mention": { "type": "page", "page_id": "roadmap" } }
+    },
+    { "insert": " & <team>" },
+  ]))
+}
+
+#[test]
+fn export_document_to_slack_and_discord_test() {
+  let mut builder = DocumentDataBuilder::new();
+  builder.push(Block::heading(1, "Plan"));
+  builder.push(rich_paragraph());
+  builder.push(
+    NewBlock::new(BlockType::Callout)
+      .with_data("icon", json!("⚠️"))
+      .with_text("Careful")
+      .with_child(Block::paragraph("really")),
+  );
+  builder.push(
+    NewBlock::new(BlockType::ToggleList)
+      .with_text("Details")
+      .with_child(NewBlock::new(BlockType::BulletedList).with_text("hidden")),
+  );
+  builder.push(NewBlock::new(BlockType::NumberedList).with_text("one"));
+  builder.push(NewBlock::new(BlockType::NumberedList).with_text("two"));
+  builder.push(Block::todo("done", true));
+  builder.push(
+    NewBlock::new(BlockType::Code)
+      .with_data("language", json!("rust"))
+      .with_text("let a = 1;"),
+  );
+  let mut table = Block::table(1, 2);
+  table.children[0].children[0].children[0] = Block::paragraph("a");
+  table.children[0].children[1].children[0] = Block::paragraph("b");
+  builder.push(table);
+  let data = builder.build();
+  let page_names = HashMap::from([("roadmap".to_string(), "Roadmap".to_string())]);
+
+  let slack = ChatExporter::new(ChatFormat::Slack)
+    .with_page_names(page_names.clone())
+    .export(&data);
+  assert_eq!(
+    slack,
+    [
+      "*Plan*",
+      "Ship *v2* by <https://appflowy.io|_Friday_> with @Roadmap &amp; &lt;team&gt;",
+      "> ⚠️ Careful",
+      "> really",
+      "Details",
+      "• hidden",
+      "1. one",
+      "2. two",
+      "☑ done",
+      "```\nlet a = 1;\n```",
+      "```\na | b\n```",
+    ]
+    .join("\n")
+  );
+
+  let discord = ChatExporter::new(ChatFormat::Discord)
+    .with_page_names(page_names)
+    .export(&data);
+  assert!(discord.starts_with(
+    "# Plan\nShip **v2** by [*Friday*](https://appflowy.io) with @Roadmap & <team>\n"
+  ));
+  assert!(discord.contains("Details\n- hidden\n"));
+  assert!(discord.contains("```rust\nlet a = 1;\n```"));
+}
+
+#[test]
+fn export_block_range_to_chat_test() {
+  let mut builder = DocumentDataBuilder::new();
+  builder.push(Block::paragraph("before"));
+  let start_id = builder.push(Block::paragraph("first_part"));
+  builder.push(Block::paragraph("second"));
+  let end_id = builder.push(Block::paragraph("third"));
+  builder.push(Block::paragraph("after"));
+  let data = builder.build();
+
+  let exporter = ChatExporter::new(ChatFormat::Discord);
+  assert_eq!(
+    exporter.export_range(&data, &start_id, &end_id).unwrap(),
+    "first\\_part\nsecond\nthird"
+  );
+  assert!(exporter.export_range(&data, "unknown", &end_id).is_err());
+}
+
+#[test]
+fn split_chat_message_test() {
+  let exporter = ChatExporter::new(ChatFormat::Discord);
+  let code = (0..300)
+    .map(|i| format!("line {}", i))
+    .collect::<Vec<_>>()
+    .join("\n");
+  let message = format!("intro\n```\n{}\n```", code);
+  let messages = exporter.split_message(&message);
+  assert!(messages.len() > 1);
+  for message in messages.iter() {
+    assert!(message.chars().count() <= ChatFormat::Discord.max_message_len());
+    // each part of the code block is a complete code block
+    assert_eq!(message.matches("```").count() % 2, 0);
+  }
+  assert_eq!(exporter.split_message("short"), vec!["short"]);
+}
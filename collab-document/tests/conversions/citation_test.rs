@@ -0,0 +1,141 @@
+use collab_document::block_parser::{DocumentParser, OutputFormat};
+use collab_document::blocks::{BlockType, DocumentDataBuilder, NewBlock};
+use collab_document::citation::{
+  Citation, CitationRenderer, CitationStyle, collect_citations, export_with_citations,
+};
+use collab_entity::reference::{Reference, ReferenceAuthor};
+use serde_json::json;
+use std::collections::HashMap;
+
+fn author(family: &str, given: &str) -> ReferenceAuthor {
+  ReferenceAuthor {
+    family: family.to_string(),
+    given: Some(given.to_string()),
+  }
+}
+
+fn references() -> HashMap<String, Reference> {
+  let article = Reference {
+    key: "smith2020".to_string(),
+    kind: "article".to_string(),
+    title: "Deep Learning".to_string(),
+    authors: vec![author("Smith", "John"), author("Lee", "Bob")],
+    year: Some("2020".to_string()),
+    container_title: Some("Nature".to_string()),
+    volume: Some("5".to_string()),
+    issue: Some("2".to_string()),
+    pages: Some("10--20".to_string()),
+    doi: Some("10.1/abc".to_string()),
+    ..Default::default()
+  };
+  let book = Reference {
+    key: "doe2019".to_string(),
+    kind: "book".to_string(),
+    title: "A Book".to_string(),
+    authors: vec![author("Doe", "Alice")],
+    year: Some("2019".to_string()),
+    publisher: Some("Press".to_string()),
+    ..Default::default()
+  };
+  HashMap::from([(article.key.clone(), article), (book.key.clone(), book)])
+}
+
+fn cited_document() -> collab_document::blocks::DocumentData {
+  let smith = Citation::new("smith2020").with_locator("p. 12");
+  let doe = Citation::new("doe2019");
+  let mut builder = DocumentDataBuilder::new();
+  builder.push(NewBlock::new(BlockType::Paragraph).with_delta(json!([
+    { "insert": "As shown " },
+    { "insert": "@smith2020", "attributes": { "citation": smith.to_attribute_value() } },
+    { "insert": " and " },
+    { "insert": "@doe2019", "attributes": { "citation": doe.to_attribute_value() } },
+    { "insert": "." },
+  ])));
+  builder.push(NewBlock::new(BlockType::Bibliography));
+  builder.build()
+}
+
+#[test]
+fn collect_citations_test() {
+  let citations = collect_citations(&cited_document());
+  assert_eq!(
+    citations,
+    vec![
+      Citation::new("smith2020").with_locator("p. 12"),
+      Citation::new("doe2019"),
+    ]
+  );
+}
+
+#[test]
+fn export_citations_without_references_test() {
+  let data = cited_document();
+  let parser = DocumentParser::with_default_parsers();
+  let markdown = parser
+    .parse_document(&data, OutputFormat::Markdown)
+    .unwrap();
+  assert_eq!(
+    markdown,
+    "As shown [@smith2020, p. 12] and [@doe2019].\n## References"
+  );
+
+  let latex = parser.parse_document(&data, OutputFormat::Latex).unwrap();
+  assert!(latex.contains("As shown \\cite[p. 12]{smith2020} and \\cite{doe2019}."));
+}
+
+#[test]
+fn export_citations_in_apa_style_test() {
+  let markdown = export_with_citations(
+    &cited_document(),
+    CitationStyle::Apa,
+    references(),
+    OutputFormat::Markdown,
+  )
+  .unwrap();
+  assert_eq!(
+    markdown,
+    "As shown (Smith & Lee, 2020, p. 12) and (Doe, 2019).\n## References\n\n\
+     Doe, A. (2019). A Book. Press.\n\n\
+     Smith, J., & Lee, B. (2020). Deep Learning. Nature, 5(2), 10–20. https://doi.org/10.1/abc"
+  );
+}
+
+#[test]
+fn export_citations_in_ieee_style_test() {
+  let data = cited_document();
+  let renderer = CitationRenderer::for_document(CitationStyle::Ieee, references(), &data);
+  let entries = renderer
+    .bibliography()
+    .into_iter()
+    .map(|entry| entry.text)
+    .collect::<Vec<_>>();
+  assert_eq!(
+    entries,
+    vec![
+      "[1] J. Smith and B. Lee, \"Deep Learning,\" Nature, vol. 5, no. 2, pp. 10–20, 2020.",
+      "[2] A. Doe, \"A Book,\" 2019.",
+    ]
+  );
+
+  let text = export_with_citations(
+    &data,
+    CitationStyle::Ieee,
+    references(),
+    OutputFormat::PlainText,
+  )
+  .unwrap();
+  assert!(text.starts_with("As shown [1, p. 12] and [2]."));
+}
+
+#[test]
+fn render_unknown_citation_test() {
+  let renderer = CitationRenderer::new(CitationStyle::Mla, HashMap::new());
+  assert_eq!(
+    renderer.render_citation(&Citation::new("missing")),
+    "[missing]"
+  );
+  assert_eq!(
+    CitationStyle::from_csl_id(CitationStyle::ChicagoAuthorDate.csl_id()),
+    Some(CitationStyle::ChicagoAuthorDate)
+  );
+}
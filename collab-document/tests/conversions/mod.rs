@@ -1 +1,4 @@
+mod chat_test;
+mod citation_test;
 mod plain_text_test;
+mod print_model_test;
@@ -0,0 +1,144 @@
+use collab_document::blocks::{
+  Block, BlockType, DocumentDataBuilder, NewBlock, SIMPLE_COLUMN_WIDTH_RATIO,
+  SIMPLE_TABLE_COLUMN_WIDTHS, SIMPLE_TABLE_ENABLE_HEADER_ROW,
+};
+use collab_document::print::{
+  IMAGE_HEIGHT_FIELD, IMAGE_WIDTH_FIELD, PAGE_BREAK_BLOCK_TYPE, PrintElement, PrintModel,
+  PrintOptions,
+};
+use serde_json::json;
+
+fn page_break() -> NewBlock {
+  NewBlock::new(BlockType::Custom(PAGE_BREAK_BLOCK_TYPE.to_string()))
+}
+
+#[test]
+fn print_page_breaks_and_headers_test() {
+  let data = DocumentDataBuilder::new()
+    .with_block(Block::heading(1, "Intro"))
+    .with_block(Block::paragraph("Hello").with_child(Block::paragraph("nested")))
+    .with_block(page_break())
+    .with_block(Block::paragraph("Still intro"))
+    .with_block(Block::heading(1, "Usage"))
+    .with_block(Block::heading(2, "Install"))
+    .with_block(page_break())
+    .build();
+
+  let model = data.to_print_model(&PrintOptions::default());
+  assert_eq!(model.pages.len(), 2);
+  assert_eq!(model.pages[0].header.as_deref(), Some("Intro"));
+  assert!(matches!(
+    &model.pages[0].elements[1],
+    PrintElement::Text { text, depth: 0, .. } if text == "Hello"
+  ));
+  assert!(matches!(
+    &model.pages[0].elements[2],
+    PrintElement::Text { text, depth: 1, .. } if text == "nested"
+  ));
+  // the header of a page is the last top level heading before it
+  assert_eq!(model.pages[1].header.as_deref(), Some("Intro"));
+  assert_eq!(model.pages[1].elements.len(), 3);
+
+  let options = PrintOptions::default()
+    .with_break_before_heading(Some(1))
+    .with_header_level(None);
+  let model = PrintModel::from_document_data(&data, &options);
+  assert_eq!(model.pages.len(), 3);
+  assert!(model.pages.iter().all(|page| page.header.is_none()));
+  assert!(matches!(
+    &model.pages[2].elements[..],
+    [
+      PrintElement::Heading { level: 1, .. },
+      PrintElement::Heading { level: 2, .. }
+    ]
+  ));
+}
+
+#[test]
+fn print_image_size_test() {
+  let data = DocumentDataBuilder::new()
+    .with_block(
+      Block::image("https://appflowy.io/wide.png")
+        .with_data(IMAGE_WIDTH_FIELD, json!(1000))
+        .with_data(IMAGE_HEIGHT_FIELD, json!(500))
+        .with_data("align", json!("center")),
+    )
+    .with_block(
+      Block::image("https://appflowy.io/small.png").with_data(IMAGE_WIDTH_FIELD, json!("100")),
+    )
+    .with_block(Block::image("https://appflowy.io/unknown.png"))
+    .build();
+
+  let options = PrintOptions::default()
+    .with_page_size(600.0, 800.0)
+    .with_margin(50.0);
+  let model = data.to_print_model(&options);
+  let sizes = model.pages[0]
+    .elements
+    .iter()
+    .map(|element| match element {
+      PrintElement::Image {
+        width,
+        height,
+        align,
+        ..
+      } => (*width, *height, align.as_str()),
+      _ => panic!("unexpected element: {:?}", element),
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(
+    sizes,
+    vec![
+      (500.0, Some(250.0), "center"),
+      (100.0, None, "left"),
+      (500.0, None, "left")
+    ]
+  );
+}
+
+#[test]
+fn print_table_and_columns_width_test() {
+  let mut builder = DocumentDataBuilder::new();
+  let mut table = Block::table(2, 3)
+    .with_data(SIMPLE_TABLE_COLUMN_WIDTHS, json!({ "0": 400, "2": 240 }))
+    .with_data(SIMPLE_TABLE_ENABLE_HEADER_ROW, json!(true));
+  table.children[0].children[1].children[0] = Block::paragraph("Name");
+  builder.push(table);
+  builder.push(NewBlock::new(BlockType::SimpleColumns).with_children(vec![
+      NewBlock::new(BlockType::SimpleColumn)
+        .with_data(SIMPLE_COLUMN_WIDTH_RATIO, json!(0.75))
+        .with_child(Block::paragraph("left")),
+      NewBlock::new(BlockType::SimpleColumn)
+        .with_data(SIMPLE_COLUMN_WIDTH_RATIO, json!(0.25))
+        .with_child(Block::image("https://appflowy.io/logo.png")),
+    ]));
+  let data = builder.build();
+
+  let options = PrintOptions::default()
+    .with_page_size(600.0, 800.0)
+    .with_margin(100.0);
+  let model = data.to_print_model(&options);
+  let PrintElement::Table {
+    column_widths,
+    header_row,
+    rows,
+    ..
+  } = &model.pages[0].elements[0]
+  else {
+    panic!("expected a table");
+  };
+  // 400 + 160 + 240 scaled down to the 400 wide content
+  assert_eq!(column_widths, &vec![200.0, 80.0, 120.0]);
+  assert!(header_row);
+  assert_eq!(rows[0], vec!["", "Name", ""]);
+
+  let PrintElement::Columns { columns, .. } = &model.pages[0].elements[1] else {
+    panic!("expected columns");
+  };
+  assert_eq!(columns[0].width, 300.0);
+  assert_eq!(columns[1].width, 100.0);
+  assert!(matches!(
+    &columns[1].elements[..],
+    [PrintElement::Image { width, .. }] if *width == 100.0
+  ));
+}
@@ -0,0 +1,162 @@
+use collab_document::block_parser::{DocumentParser, OutputFormat};
+use collab_document::blocks::{AiResponseStatus, Block, DocumentDataBuilder};
+use collab_document::error::DocumentError;
+
+use crate::blocks::block_test_core::BlockTestCore;
+
+#[test]
+fn stream_ai_response_test() {
+  let mut test = BlockTestCore::new();
+  let page = test.get_page();
+  let prompt = test
+    .document
+    .insert_ai_prompt(
+      &page.id,
+      None,
+      "Summarize the notes",
+      Some("gpt-4o".to_string()),
+    )
+    .unwrap();
+  let response = test
+    .document
+    .insert_ai_response(&prompt.id, Some("gpt-4o".to_string()))
+    .unwrap();
+  // The response comes right after its prompt.
+  let children = test.document.get_block_children_ids(&page.id);
+  assert_eq!(children[..2], [prompt.id.clone(), response.id.clone()]);
+  assert_eq!(
+    test.document.get_plain_text_from_block(&prompt.id).unwrap(),
+    "Summarize the notes"
+  );
+
+  for chunk in ["The notes ", "cover ", "the roadmap."] {
+    test
+      .document
+      .append_ai_response_text(&response.id, chunk)
+      .unwrap();
+  }
+  assert_eq!(
+    test
+      .document
+      .get_plain_text_from_block(&response.id)
+      .unwrap(),
+    "The notes cover the roadmap."
+  );
+
+  test
+    .document
+    .finish_ai_response(&response.id, AiResponseStatus::Completed)
+    .unwrap();
+  let data = test.document.get_ai_response_data(&response.id).unwrap();
+  assert_eq!(data.status, AiResponseStatus::Completed);
+  assert_eq!(data.prompt_id.as_deref(), Some(prompt.id.as_str()));
+
+  let err = test
+    .document
+    .append_ai_response_text(&response.id, "more")
+    .unwrap_err();
+  assert!(matches!(err, DocumentError::AiResponseNotStreaming));
+
+  // Only an AI prompt can be responded to.
+  let err = test
+    .document
+    .insert_ai_response(&response.id, None)
+    .unwrap_err();
+  assert!(matches!(err, DocumentError::BlockTypeMismatch { .. }));
+}
+
+#[test]
+fn regenerate_ai_response_test() {
+  let mut test = BlockTestCore::new();
+  let page = test.get_page();
+  let prompt = test
+    .document
+    .insert_ai_prompt(&page.id, None, "Name the project", None)
+    .unwrap();
+  let response = test
+    .document
+    .insert_ai_response(&prompt.id, Some("model-a".to_string()))
+    .unwrap();
+  test
+    .document
+    .append_ai_response_text(&response.id, "Atlas")
+    .unwrap();
+  test
+    .document
+    .finish_ai_response(&response.id, AiResponseStatus::Completed)
+    .unwrap();
+
+  test
+    .document
+    .regenerate_ai_response(&response.id, Some("model-b".to_string()))
+    .unwrap();
+  assert_eq!(
+    test
+      .document
+      .get_plain_text_from_block(&response.id)
+      .unwrap(),
+    ""
+  );
+  test
+    .document
+    .append_ai_response_text(&response.id, "Beacon")
+    .unwrap();
+  test
+    .document
+    .finish_ai_response(&response.id, AiResponseStatus::Stopped)
+    .unwrap();
+
+  let data = test.document.get_ai_response_data(&response.id).unwrap();
+  assert_eq!(data.model.as_deref(), Some("model-b"));
+  assert_eq!(data.history.len(), 1);
+  assert_eq!(data.history[0].text, "Atlas");
+  assert_eq!(data.history[0].model.as_deref(), Some("model-a"));
+  assert_eq!(data.history[0].status, AiResponseStatus::Completed);
+
+  // Going back to the first response keeps the second one in the history.
+  test
+    .document
+    .select_ai_response_version(&response.id, 0)
+    .unwrap();
+  assert_eq!(
+    test
+      .document
+      .get_plain_text_from_block(&response.id)
+      .unwrap(),
+    "Atlas"
+  );
+  let data = test.document.get_ai_response_data(&response.id).unwrap();
+  assert_eq!(data.model.as_deref(), Some("model-a"));
+  assert_eq!(data.status, AiResponseStatus::Completed);
+  assert_eq!(data.history.len(), 1);
+  assert_eq!(data.history[0].text, "Beacon");
+  assert_eq!(data.history[0].status, AiResponseStatus::Stopped);
+
+  assert!(
+    test
+      .document
+      .select_ai_response_version(&response.id, 1)
+      .is_err()
+  );
+}
+
+#[test]
+fn export_ai_transcript_test() {
+  let data = DocumentDataBuilder::new()
+    .with_block(Block::ai_prompt("Write a haiku", Some("gpt-4o")))
+    .with_block(Block::ai_response("Quiet morning code", None))
+    .build();
+  let parser = DocumentParser::with_default_parsers();
+  assert_eq!(
+    parser
+      .parse_document(&data, OutputFormat::Markdown)
+      .unwrap(),
+    "> **Prompt:** Write a haiku\nQuiet morning code"
+  );
+  assert_eq!(
+    parser
+      .parse_document(&data, OutputFormat::PlainText)
+      .unwrap(),
+    "Prompt: Write a haiku\nQuiet morning code"
+  );
+}
@@ -0,0 +1,126 @@
+use collab_document::annotation::{Annotation, AnnotationKind, DocumentAnnotations};
+use collab_document::blocks::TextDelta;
+use serde_json::json;
+
+use crate::blocks::block_test_core::BlockTestCore;
+
+fn ranges(annotations: &DocumentAnnotations, block_id: &str) -> Vec<std::ops::Range<u32>> {
+  annotations
+    .get_block_annotations(block_id)
+    .into_iter()
+    .map(|annotation| annotation.range)
+    .collect()
+}
+
+#[test]
+fn annotations_follow_text_changes_test() {
+  let mut test = BlockTestCore::new();
+  let page = test.get_page();
+  let block = test.insert_text_block("teh quick brwn fox".to_string(), &page.id, None);
+  let text_id = block.external_id.clone().unwrap();
+
+  let annotations = test.document.annotations();
+  annotations.set_block_annotations(
+    &block.id,
+    "spell",
+    vec![
+      Annotation::new(AnnotationKind::Misspelling, 10..14)
+        .with_suggestions(vec!["brown".to_string()])
+        .with_language("en-US"),
+      Annotation::new(AnnotationKind::Misspelling, 0..3).with_suggestions(vec!["the".to_string()]),
+    ],
+  );
+  assert_eq!(ranges(&annotations, &block.id), vec![0..3, 10..14]);
+
+  // An insertion before the second misspelling moves it.
+  test.document.apply_text_delta(
+    &text_id,
+    json!([{ "retain": 4 }, { "insert": "very " }]).to_string(),
+  );
+  assert_eq!(ranges(&annotations, &block.id), vec![0..3, 15..19]);
+
+  // A deletion before it moves it back.
+  test.document.apply_text_delta(
+    &text_id,
+    json!([{ "retain": 4 }, { "delete": 5 }]).to_string(),
+  );
+  assert_eq!(ranges(&annotations, &block.id), vec![0..3, 10..14]);
+
+  // Fixing the misspelling removes it.
+  test.document.apply_text_delta(
+    &text_id,
+    json!([{ "retain": 12 }, { "insert": "o" }]).to_string(),
+  );
+  let remaining = annotations.get_block_annotations(&block.id);
+  assert_eq!(remaining.len(), 1);
+  assert_eq!(remaining[0].range, 0..3);
+  assert_eq!(remaining[0].source, "spell");
+
+  // Removing the block removes its annotations.
+  test.delete_block(&block.id);
+  assert!(annotations.get_block_annotations(&block.id).is_empty());
+  assert!(annotations.get_annotated_block_ids().is_empty());
+}
+
+#[test]
+fn annotations_are_replaced_by_source_test() {
+  let annotations = DocumentAnnotations::default();
+  annotations.set_block_annotations(
+    "b1",
+    "spell",
+    vec![Annotation::new(AnnotationKind::Misspelling, 0..3)],
+  );
+  annotations.set_block_annotations(
+    "b1",
+    "grammar",
+    vec![
+      Annotation::new(AnnotationKind::Grammar, 4..12).with_message("Use the active voice"),
+      Annotation::new(AnnotationKind::Style, 5..5),
+    ],
+  );
+  assert_eq!(ranges(&annotations, "b1"), vec![0..3, 4..12]);
+
+  // Checking again replaces the annotations of the source only.
+  annotations.set_block_annotations(
+    "b1",
+    "spell",
+    vec![Annotation::new(AnnotationKind::Misspelling, 13..16)],
+  );
+  assert_eq!(ranges(&annotations, "b1"), vec![4..12, 13..16]);
+
+  let at_cursor = annotations.get_annotations_at("b1", 6);
+  assert_eq!(at_cursor.len(), 1);
+  assert_eq!(at_cursor[0].kind, AnnotationKind::Grammar);
+
+  let ignored = annotations
+    .remove_annotation("b1", &at_cursor[0].id)
+    .unwrap();
+  assert_eq!(ignored.message.as_deref(), Some("Use the active voice"));
+
+  annotations.clear_source("spell");
+  assert!(annotations.get_block_annotations("b1").is_empty());
+}
+
+#[test]
+fn annotation_offsets_are_utf16_test() {
+  let annotations = DocumentAnnotations::default();
+  annotations.set_block_annotations(
+    "b1",
+    "spell",
+    vec![
+      Annotation::new(AnnotationKind::Misspelling, 4..8),
+      Annotation::new(AnnotationKind::Misspelling, 10..14),
+    ],
+  );
+  // The emoji is 2 UTF-16 code units.
+  annotations.apply_text_delta(
+    "b1",
+    &[
+      TextDelta::Retain(4, None),
+      TextDelta::Inserted("😀".to_string(), None),
+      TextDelta::Retain(6, None),
+      TextDelta::Deleted(1),
+    ],
+  );
+  assert_eq!(ranges(&annotations, "b1"), vec![6..10]);
+}
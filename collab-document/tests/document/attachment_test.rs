@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use collab_document::blocks::{Block, BlockType};
+use collab_entity::attachment::{AttachmentLocation, AttachmentReference, AttachmentWriteHook};
+use serde_json::json;
+
+use crate::util::{DocumentTest, get_document_data};
+
+#[derive(Default)]
+struct RecordingHook {
+  writes: Mutex<Vec<(AttachmentLocation, Vec<String>)>>,
+}
+
+impl AttachmentWriteHook for RecordingHook {
+  fn did_write_attachments(
+    &self,
+    _object_id: &str,
+    location: &AttachmentLocation,
+    references: Vec<AttachmentReference>,
+  ) {
+    let urls = references.into_iter().map(|r| r.url).collect();
+    self.writes.lock().unwrap().push((location.clone(), urls));
+  }
+}
+
+#[test]
+fn document_attachment_hook_test() {
+  let test = DocumentTest::new(1, "1");
+  let mut document = test.document;
+  let hook = Arc::new(RecordingHook::default());
+  document.set_attachment_hook(hook.clone());
+
+  let (page_id, _, _) = get_document_data(&document);
+  let image = Block {
+    id: "image".to_string(),
+    ty: BlockType::Image.as_str().to_string(),
+    parent: page_id,
+    children: "".to_string(),
+    external_id: None,
+    external_type: None,
+    data: HashMap::from([
+      ("url".to_string(), json!("https://example.com/a.png")),
+      ("size".to_string(), json!(42)),
+    ]),
+  };
+  document.insert_block(image, None).unwrap();
+  let references = document.get_attachment_references();
+  assert_eq!(references.len(), 1);
+  assert_eq!(references[0].size, Some(42));
+
+  document
+    .update_block(
+      "image",
+      HashMap::from([("url".to_string(), json!("https://example.com/b.png"))]),
+    )
+    .unwrap();
+  document.delete_block("image").unwrap();
+
+  let location = AttachmentLocation::Block {
+    block_id: "image".to_string(),
+  };
+  let writes = hook.writes.lock().unwrap().clone();
+  assert_eq!(
+    writes,
+    vec![
+      (
+        location.clone(),
+        vec!["https://example.com/a.png".to_string()]
+      ),
+      (
+        location.clone(),
+        vec!["https://example.com/b.png".to_string()]
+      ),
+      (location, vec![]),
+    ]
+  );
+  assert!(document.get_attachment_references().is_empty());
+}
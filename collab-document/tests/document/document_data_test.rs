@@ -2,7 +2,8 @@ use collab::core::collab::{CollabOptions, default_client_id};
 use collab::core::origin::CollabOrigin;
 use collab::preclude::Collab;
 use collab_document::document::Document;
-use collab_document::document_data::default_document_data;
+use collab_document::document_data::{MergeStrategy, default_document_data};
+use collab_document::importer::md_importer::MDImporter;
 
 #[test]
 fn get_default_data_test() {
@@ -37,3 +38,88 @@ fn validate_document_data() {
   let result = Document::open(new_collab);
   assert!(result.is_err())
 }
+
+#[test]
+fn merge_document_data_append_test() {
+  let importer = MDImporter::new(None);
+  let mut data = importer
+    .import("1", "# Part 1\n\nfirst".to_string())
+    .unwrap();
+  let other = importer
+    .import("2", "# Part 2\n\nsecond".to_string())
+    .unwrap();
+  let num_of_blocks = data.blocks.len() + other.blocks.len() - 1;
+
+  let merged_ids = data
+    .merge(other.clone(), MergeStrategy::Append { parent_id: None })
+    .unwrap();
+  assert_eq!(merged_ids.len(), 2);
+  assert_eq!(data.blocks.len(), num_of_blocks);
+
+  let page = data.blocks.get(&data.page_id).unwrap();
+  let children = data.meta.children_map.get(&page.children).unwrap();
+  assert_eq!(children.len(), 4);
+  assert_eq!(&children[2..], merged_ids.as_slice());
+
+  // The merged blocks get new ids and keep their text
+  for id in merged_ids.iter() {
+    assert!(!other.blocks.contains_key(id));
+    let block = data.blocks.get(id).unwrap();
+    assert_eq!(block.parent, data.page_id);
+    let external_id = block.external_id.as_ref().unwrap();
+    assert!(
+      data
+        .meta
+        .text_map
+        .as_ref()
+        .unwrap()
+        .contains_key(external_id)
+    );
+  }
+
+  // Merging the same document twice doesn't conflict
+  data
+    .merge(other, MergeStrategy::Append { parent_id: None })
+    .unwrap();
+  let page = data.blocks.get(&data.page_id).unwrap();
+  assert_eq!(data.meta.children_map[&page.children].len(), 6);
+  let document = Document::create("1", data, default_client_id()).unwrap();
+  assert!(document.validate().is_ok());
+}
+
+#[test]
+fn merge_document_data_interleave_test() {
+  let importer = MDImporter::new(None);
+  let mut data = importer.import("1", "a\n\nb\n\nc".to_string()).unwrap();
+  let other = importer.import("2", "1\n\n2".to_string()).unwrap();
+  let page = data.blocks.get(&data.page_id).unwrap().clone();
+  let existing = data.meta.children_map[&page.children].clone();
+
+  let merged_ids = data
+    .merge(other, MergeStrategy::Interleave { parent_id: None })
+    .unwrap();
+  let children = data.meta.children_map[&page.children].clone();
+  assert_eq!(
+    children,
+    vec![
+      existing[0].clone(),
+      merged_ids[0].clone(),
+      existing[1].clone(),
+      merged_ids[1].clone(),
+      existing[2].clone(),
+    ]
+  );
+}
+
+#[test]
+fn merge_document_data_with_unknown_parent_test() {
+  let mut data = default_document_data("1");
+  let other = default_document_data("2");
+  let result = data.merge(
+    other,
+    MergeStrategy::Append {
+      parent_id: Some("unknown".to_string()),
+    },
+  );
+  assert!(result.is_err());
+}
@@ -0,0 +1,69 @@
+use collab::core::collab::{CollabOptions, default_client_id};
+use collab::core::origin::CollabOrigin;
+use collab::preclude::updates::decoder::Decode;
+use collab::preclude::{Collab, ReadTxn, StateVector, Update};
+use collab_document::document::Document;
+use collab_document::document_data::{default_document_data, derived_document_data};
+
+fn empty_collab(document_id: &str) -> Collab {
+  let options = CollabOptions::new(document_id.to_string(), default_client_id());
+  Collab::new_with_options(CollabOrigin::Empty, options).unwrap()
+}
+
+fn sync(from: &Document, to: &mut Document) {
+  let update = from
+    .transact()
+    .encode_state_as_update_v1(&StateVector::default());
+  to.apply_update(Update::decode_v1(&update).unwrap())
+    .unwrap();
+}
+
+#[test]
+fn derived_document_data_test() {
+  let document_id = uuid::Uuid::new_v4().to_string();
+  assert_eq!(
+    derived_document_data(&document_id),
+    derived_document_data(&document_id)
+  );
+  assert_eq!(
+    derived_document_data(&document_id).page_id,
+    default_document_data(&document_id).page_id
+  );
+  assert_ne!(
+    derived_document_data(&document_id),
+    derived_document_data(&uuid::Uuid::new_v4().to_string())
+  );
+}
+
+#[test]
+fn concurrent_get_or_create_converges_test() {
+  let document_id = uuid::Uuid::new_v4().to_string();
+  let mut document_a = Document::get_or_create(empty_collab(&document_id)).unwrap();
+  let mut document_b = Document::get_or_create(empty_collab(&document_id)).unwrap();
+  assert_ne!(document_a.client_id(), document_b.client_id());
+
+  sync(&document_a, &mut document_b);
+  sync(&document_b, &mut document_a);
+
+  let data_a = document_a.get_document_data().unwrap();
+  let data_b = document_b.get_document_data().unwrap();
+  assert_eq!(data_a, data_b);
+  let derived = derived_document_data(&document_id);
+  assert_eq!(data_a.blocks, derived.blocks);
+  assert_eq!(data_a.meta.children_map, derived.meta.children_map);
+  let page_id = document_a.get_page_id().unwrap();
+  assert_eq!(document_a.get_block_children_ids(&page_id).len(), 1);
+}
+
+#[test]
+fn get_or_create_keeps_existing_document_test() {
+  let document_id = uuid::Uuid::new_v4().to_string();
+  let data = default_document_data(&document_id);
+  let document = Document::create(&document_id, data.clone(), default_client_id()).unwrap();
+  let (collab, _) = document.split();
+
+  let document = Document::get_or_create(collab).unwrap();
+  let existing = document.get_document_data().unwrap();
+  assert_eq!(existing.page_id, data.page_id);
+  assert_eq!(existing.blocks, data.blocks);
+}
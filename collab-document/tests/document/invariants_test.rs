@@ -0,0 +1,206 @@
+use collab::core::collab::default_client_id;
+use collab_document::blocks::{Block, DocumentData, DocumentDataBuilder, NewBlock};
+use collab_document::document::Document;
+use collab_document::importer::md_importer::MDImporter;
+use collab_document::invariants::{InvariantViolation, assert_document_valid, check_document};
+use proptest::prelude::*;
+use serde_json::Value;
+use std::collections::HashMap;
+
+fn sample_document_data() -> DocumentData {
+  let mut builder = DocumentDataBuilder::with_page_id("page");
+  let heading_id = builder.push(Block::heading(1, "Title"));
+  builder
+    .push_child(&heading_id, Block::paragraph("Nested"))
+    .unwrap();
+  builder.push(Block::divider());
+  builder.build()
+}
+
+fn first_child_id(data: &DocumentData, block_id: &str) -> String {
+  data.meta.children_map[&data.blocks[block_id].children][0].clone()
+}
+
+#[test]
+fn valid_document_data_test() {
+  let data = sample_document_data();
+  assert!(check_document(&data).is_empty());
+  assert_document_valid(&data);
+}
+
+#[test]
+fn detect_children_mismatch_test() {
+  let mut data = sample_document_data();
+  let heading_id = first_child_id(&data, "page");
+  let nested_id = first_child_id(&data, &heading_id);
+
+  // The nested paragraph claims the page as its parent but is only listed by the heading.
+  data.blocks.get_mut(&nested_id).unwrap().parent = "page".to_string();
+  let violations = check_document(&data);
+  assert!(violations.contains(&InvariantViolation::ParentMismatch {
+    block_id: nested_id.clone(),
+    parent_id: "page".to_string(),
+    listed_in: heading_id.clone(),
+  }));
+  assert!(
+    violations.contains(&InvariantViolation::NotInParentChildren {
+      block_id: nested_id.clone(),
+      parent_id: "page".to_string(),
+    })
+  );
+
+  let mut data = sample_document_data();
+  data
+    .meta
+    .children_map
+    .get_mut("page")
+    .unwrap()
+    .push("ghost".to_string());
+  assert_eq!(
+    check_document(&data),
+    vec![InvariantViolation::MissingChild {
+      parent_id: "page".to_string(),
+      child_id: "ghost".to_string(),
+    }]
+  );
+}
+
+#[test]
+fn detect_missing_text_test() {
+  let mut data = sample_document_data();
+  let heading_id = first_child_id(&data, "page");
+  data.meta.text_map.as_mut().unwrap().remove(&heading_id);
+  assert_eq!(
+    check_document(&data),
+    vec![InvariantViolation::MissingText {
+      block_id: heading_id.clone(),
+      external_id: heading_id,
+    }]
+  );
+
+  // The texts of a lazily loaded document are not checked.
+  data.meta.lazy_text = true;
+  assert!(check_document(&data).is_empty());
+}
+
+#[test]
+fn detect_unreachable_block_test() {
+  let mut data = sample_document_data();
+  let heading_id = first_child_id(&data, "page");
+  data.meta.children_map.get_mut("page").unwrap().clear();
+  let violations = check_document(&data);
+  assert!(violations.contains(&InvariantViolation::Unreachable {
+    block_id: heading_id.clone(),
+  }));
+  assert!(
+    violations.contains(&InvariantViolation::NotInParentChildren {
+      block_id: heading_id,
+      parent_id: "page".to_string(),
+    })
+  );
+}
+
+#[test]
+#[should_panic(expected = "invalid document data")]
+fn assert_invalid_document_data_test() {
+  let mut data = sample_document_data();
+  data.blocks.remove("page");
+  assert_document_valid(&data);
+}
+
+#[test]
+fn imported_markdown_is_valid_test() {
+  let md = r#"# Title
+
+A paragraph with **bold** text.
+
+- item
+  - nested item
+    1. numbered
+
+| a | b |
+|---|---|
+|   | 2 |
+
+---
+
+```rust
+fn main() {}
+```
+
+![image](https://example.com/image.png)
+
+> quote
+"#;
+  let data = MDImporter::new(None)
+    .import("invariants_test", md.to_string())
+    .unwrap();
+  assert_document_valid(&data);
+}
+
+/// A block tree in the shape the editor and the importers produce.
+#[derive(Debug, Clone, PartialEq)]
+struct BlockTree {
+  ty: String,
+  data: HashMap<String, Value>,
+  delta: Option<Value>,
+  children: Vec<BlockTree>,
+}
+
+fn block_tree(data: &DocumentData, block_id: &str) -> BlockTree {
+  let block = &data.blocks[block_id];
+  let delta = block.external_id.as_ref().and_then(|external_id| {
+    let text_map = data.meta.text_map.as_ref()?;
+    serde_json::from_str(text_map.get(external_id)?).ok()
+  });
+  let children = data
+    .meta
+    .children_map
+    .get(&block.children)
+    .map(|ids| ids.iter().map(|id| block_tree(data, id)).collect())
+    .unwrap_or_default();
+  BlockTree {
+    ty: block.ty.clone(),
+    data: block.data.clone(),
+    delta,
+    children,
+  }
+}
+
+fn new_block_strategy() -> impl Strategy<Value = NewBlock> {
+  let text = "[a-zA-Z0-9 ]{1,12}";
+  let leaf = prop_oneof![
+    text.prop_map(|text| Block::paragraph(&text)),
+    (1u8..=6, text).prop_map(|(level, text)| Block::heading(level, &text)),
+    (text, any::<bool>()).prop_map(|(text, checked)| Block::todo(&text, checked)),
+    Just(Block::image("https://example.com/image.png")),
+    Just(Block::divider()),
+  ];
+  leaf.prop_recursive(3, 24, 4, |inner| {
+    ("[a-zA-Z0-9 ]{1,12}", prop::collection::vec(inner, 1..4))
+      .prop_map(|(text, children)| Block::paragraph(&text).with_children(children))
+  })
+}
+
+proptest! {
+  #![proptest_config(ProptestConfig::with_cases(32))]
+
+  #[test]
+  fn document_data_round_trip_test(blocks in prop::collection::vec(new_block_strategy(), 0..6)) {
+    let mut builder = DocumentDataBuilder::new();
+    for block in blocks {
+      builder.push(block);
+    }
+    let data = builder.build();
+    assert_document_valid(&data);
+
+    let document = Document::create("round_trip_test", data.clone(), default_client_id()).unwrap();
+    let round_trip = document.get_document_data().unwrap();
+    assert_document_valid(&round_trip);
+    prop_assert_eq!(round_trip.page_id.clone(), data.page_id.clone());
+    prop_assert_eq!(
+      block_tree(&round_trip, &round_trip.page_id),
+      block_tree(&data, &data.page_id)
+    );
+  }
+}
@@ -0,0 +1,193 @@
+use crate::util::{block_delta, children_ids};
+use collab::core::collab::default_client_id;
+use collab_document::blocks::{Block, DocumentData, TextDelta};
+use collab_document::document::Document;
+use collab_document::document_data::default_document_data;
+use collab_document::error::DocumentError;
+use collab_document::importer::md_importer::MDImporter;
+use collab_document::limits::{DocumentLimit, DocumentLimits, LimitPolicy};
+use serde_json::json;
+
+fn import_with_limits(md: &str, limits: DocumentLimits) -> Result<DocumentData, DocumentError> {
+  MDImporter::new(None)
+    .with_document_limits(limits)
+    .import("limits_test", md.to_string())
+}
+
+fn paragraph(id: &str, parent: &str) -> Block {
+  Block {
+    id: id.to_string(),
+    ty: "paragraph".to_string(),
+    parent: parent.to_string(),
+    children: "".to_string(),
+    external_id: None,
+    external_type: None,
+    data: Default::default(),
+  }
+}
+
+#[test]
+fn truncate_blocks_with_marker_test() {
+  let limits = DocumentLimits {
+    max_blocks: 3,
+    ..DocumentLimits::default()
+  };
+  let data = import_with_limits("a\n\nb\n\nc\n\nd\n\ne", limits).unwrap();
+  let ids = children_ids(&data, &data.page_id);
+  assert_eq!(ids.len(), 3);
+  assert_eq!(data.blocks.len(), 4);
+  assert_eq!(block_delta(&data, &ids[0]), json!([{ "insert": "a" }]));
+  assert_eq!(block_delta(&data, &ids[1]), json!([{ "insert": "b" }]));
+  assert_eq!(
+    block_delta(&data, &ids[2]),
+    json!([{ "insert": "[3 blocks truncated]" }])
+  );
+}
+
+#[test]
+fn reject_too_many_blocks_test() {
+  let limits = DocumentLimits {
+    max_blocks: 3,
+    ..DocumentLimits::default()
+  }
+  .with_policy(LimitPolicy::Error);
+  let err = import_with_limits("a\n\nb\n\nc\n\nd\n\ne", limits).unwrap_err();
+  assert!(matches!(
+    err,
+    DocumentError::DocumentLimitExceeded {
+      limit: DocumentLimit::Blocks,
+      size: 5,
+      max: 3,
+    }
+  ));
+  assert_eq!(err.code(), "document.limit_exceeded");
+}
+
+#[test]
+fn flatten_deep_blocks_test() {
+  let md = "- a\n  - b\n    - c\n      - d\n- e";
+  let limits = DocumentLimits {
+    max_depth: 2,
+    ..DocumentLimits::default()
+  };
+  let data = import_with_limits(md, limits).unwrap();
+  let ids = children_ids(&data, &data.page_id);
+  assert_eq!(ids.len(), 2);
+  assert_eq!(block_delta(&data, &ids[1]), json!([{ "insert": "e" }]));
+
+  // b, c and d are the children of a, in the reading order.
+  let nested_ids = children_ids(&data, &ids[0]);
+  let texts = nested_ids
+    .iter()
+    .map(|id| block_delta(&data, id)[0]["insert"].clone())
+    .collect::<Vec<_>>();
+  assert_eq!(texts, vec![json!("b"), json!("c"), json!("d")]);
+  for id in nested_ids.iter() {
+    assert_eq!(data.blocks[id].parent, ids[0]);
+    assert!(children_ids(&data, id).is_empty());
+  }
+
+  let err = import_with_limits(md, limits.with_policy(LimitPolicy::Error)).unwrap_err();
+  assert!(matches!(
+    err,
+    DocumentError::DocumentLimitExceeded {
+      limit: DocumentLimit::Depth,
+      size: 4,
+      max: 2,
+    }
+  ));
+}
+
+#[test]
+fn truncate_long_text_test() {
+  let limits = DocumentLimits {
+    max_text_len: 8,
+    ..DocumentLimits::default()
+  };
+  let data = import_with_limits("**Hello** world", limits).unwrap();
+  let ids = children_ids(&data, &data.page_id);
+  assert_eq!(
+    block_delta(&data, &ids[0]),
+    json!([
+      { "insert": "Hello", "attributes": { "bold": true } },
+      { "insert": " w" },
+      { "insert": "…" }
+    ])
+  );
+
+  let err =
+    import_with_limits("**Hello** world", limits.with_policy(LimitPolicy::Error)).unwrap_err();
+  assert!(matches!(
+    err,
+    DocumentError::DocumentLimitExceeded {
+      limit: DocumentLimit::TextLength,
+      size: 11,
+      max: 8,
+    }
+  ));
+}
+
+#[test]
+fn document_mutation_limits_test() {
+  let data = default_document_data("limits_test");
+  let page_id = data.page_id.clone();
+  let mut document = Document::create("limits_test", data, default_client_id()).unwrap();
+  let first_id = document.get_block_children_ids(&page_id)[0].clone();
+  document.set_limits(DocumentLimits {
+    max_blocks: 2,
+    max_text_len: 5,
+    max_depth: 1,
+    policy: LimitPolicy::Error,
+  });
+
+  // The blocks over the limits are rejected.
+  let err = document
+    .insert_block(paragraph("nested", &first_id), None)
+    .unwrap_err();
+  assert!(matches!(
+    err,
+    DocumentError::DocumentLimitExceeded {
+      limit: DocumentLimit::Depth,
+      ..
+    }
+  ));
+  document
+    .insert_block(paragraph("second", &page_id), Some(first_id.clone()))
+    .unwrap();
+  let err = document
+    .insert_block(paragraph("third", &page_id), None)
+    .unwrap_err();
+  assert!(matches!(
+    err,
+    DocumentError::DocumentLimitExceeded {
+      limit: DocumentLimit::Blocks,
+      size: 3,
+      max: 2,
+    }
+  ));
+  assert!(document.get_block("third").is_none());
+
+  // The texts are rejected or truncated depending on the policy.
+  let delta = vec![TextDelta::Inserted("Hello world".to_string(), None)];
+  let err = document
+    .set_block_delta(&first_id, delta.clone())
+    .unwrap_err();
+  assert!(matches!(
+    err,
+    DocumentError::DocumentLimitExceeded {
+      limit: DocumentLimit::TextLength,
+      ..
+    }
+  ));
+  document.set_limits(DocumentLimits {
+    max_blocks: 2,
+    max_text_len: 5,
+    max_depth: 1,
+    policy: LimitPolicy::Truncate,
+  });
+  document.set_block_delta(&first_id, delta).unwrap();
+  assert_eq!(
+    document.get_plain_text_from_block(&first_id).unwrap(),
+    "Hell…"
+  );
+}
@@ -1,5 +1,16 @@
+mod ai_transcript_test;
+mod annotation_test;
+mod attachment_test;
 mod awareness_test;
 mod document_data_test;
 mod document_test;
+mod get_or_create_test;
+mod invariants_test;
+mod limits_test;
+mod provenance_test;
+mod redaction_test;
 mod redo_undo_test;
 mod restore_test;
+mod sanitize_test;
+mod slug_test;
+mod subtree_test;
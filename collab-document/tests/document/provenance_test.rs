@@ -0,0 +1,97 @@
+use crate::util::{insert_block_for_page, top_level_ids};
+use collab::core::collab::default_client_id;
+use collab_document::document::Document;
+use collab_document::importer::md_importer::MDImporter;
+use collab_document::provenance::{BlockProvenance, PROVENANCE_FIELD};
+
+#[test]
+fn tag_imported_blocks_test() {
+  let mut data = MDImporter::new(None)
+    .import("1", "# Title\n\n- first\n  - nested\n".to_string())
+    .unwrap();
+  let provenance = BlockProvenance::new("notion", "Page 1a2b.md");
+  data.tag_provenance(&provenance);
+  // the page is the document, it's not imported
+  assert!(
+    !data.blocks[&data.page_id]
+      .data
+      .contains_key(PROVENANCE_FIELD)
+  );
+  assert!(
+    data
+      .blocks
+      .values()
+      .filter(|block| block.id != data.page_id)
+      .all(|block| BlockProvenance::from_block(block) == Some(provenance.clone()))
+  );
+
+  // the blocks already tagged keep their provenance
+  let ids = top_level_ids(&data);
+  data.tag_provenance(&BlockProvenance::new("docx", "other.docx"));
+  assert_eq!(
+    BlockProvenance::from_block(&data.blocks[&ids[0]]),
+    Some(provenance.clone())
+  );
+
+  // in document order, the nested block after its parent
+  let imported = data.imported_block_ids(|provenance| provenance.importer == "notion");
+  assert_eq!(imported.len(), 3);
+  assert_eq!(imported[..2], ids[..]);
+  assert!(
+    data
+      .imported_block_ids(|provenance| provenance.importer == "docx")
+      .is_empty()
+  );
+}
+
+#[test]
+fn find_imported_blocks_in_document_test() {
+  let mut data = MDImporter::new(None)
+    .import("1", "first\n\nsecond".to_string())
+    .unwrap();
+  data.tag_provenance(&BlockProvenance::new("html_folder", "notes/trip.html"));
+  let imported_ids = top_level_ids(&data);
+  let mut document = Document::create("1", data, default_client_id()).unwrap();
+
+  // the blocks written by the user are not imported
+  let user_block = insert_block_for_page(&mut document, "user_block".to_string());
+  assert!(document.get_block_provenance(&user_block.id).is_none());
+  let provenance = document.get_block_provenance(&imported_ids[0]).unwrap();
+  assert_eq!(provenance.importer, "html_folder");
+  assert_eq!(provenance.source_path, "notes/trip.html");
+  assert!(provenance.imported_at > 0);
+
+  let found = document
+    .find_imported_blocks(|provenance| provenance.source_path == "notes/trip.html")
+    .unwrap();
+  assert_eq!(found, imported_ids);
+
+  // tagging the document tags the blocks without a provenance
+  document
+    .tag_provenance(&BlockProvenance::new("enex", "notebook/note"))
+    .unwrap();
+  assert_eq!(
+    document
+      .get_block_provenance(&user_block.id)
+      .unwrap()
+      .importer,
+    "enex"
+  );
+  assert_eq!(
+    document
+      .get_block_provenance(&imported_ids[1])
+      .unwrap()
+      .importer,
+    "html_folder"
+  );
+
+  // the provenance is not published
+  let mut data = document.get_document_data().unwrap();
+  data.sanitize();
+  assert!(
+    data
+      .blocks
+      .values()
+      .all(|block| !block.data.contains_key(PROVENANCE_FIELD))
+  );
+}
@@ -0,0 +1,87 @@
+use crate::util::top_level_ids;
+use collab::core::collab::default_client_id;
+use collab_document::blocks::{BlockType, DocumentData};
+use collab_document::document::Document;
+use collab_document::importer::md_importer::MDImporter;
+use collab_document::redaction::{
+  REDACTED_TEXT, RedactionAction, SENSITIVITY_FIELD, SensitivityTagPolicy,
+};
+use serde_json::json;
+
+/// A heading, a paragraph and a list with a nested item, the heading and the list being tagged.
+fn tagged_document(tag: &str) -> DocumentData {
+  let mut data = MDImporter::new(None)
+    .import(
+      "1",
+      "# Salaries\n\npublic\n\n- item\n  - nested".to_string(),
+    )
+    .unwrap();
+  let ids = top_level_ids(&data);
+  for id in [&ids[0], &ids[2]] {
+    data
+      .blocks
+      .get_mut(id)
+      .unwrap()
+      .data
+      .insert(SENSITIVITY_FIELD.to_string(), json!(tag));
+  }
+  data
+}
+
+#[test]
+fn exclude_sensitive_blocks_test() {
+  let mut data = tagged_document("sensitive");
+  let ids = top_level_ids(&data);
+  let num_of_blocks = data.blocks.len();
+  let num_of_texts = data.meta.text_map.as_ref().unwrap().len();
+
+  let manifest = data.redact(&SensitivityTagPolicy::default());
+  assert_eq!(manifest.num_of_excluded(), 2);
+  assert_eq!(manifest.num_of_redacted(), 0);
+  assert_eq!(manifest.blocks[0].block_id, ids[0]);
+  assert_eq!(
+    manifest.blocks[0].block_type,
+    BlockType::Heading.to_string()
+  );
+  assert_eq!(manifest.blocks[1].block_id, ids[2]);
+  assert_eq!(manifest.blocks[1].num_of_descendants, 1);
+
+  // the blocks, their children and their texts are gone
+  assert_eq!(top_level_ids(&data), vec![ids[1].clone()]);
+  assert_eq!(data.blocks.len(), num_of_blocks - 3);
+  assert_eq!(data.meta.text_map.as_ref().unwrap().len(), num_of_texts - 3);
+  assert!(Document::create("1", data, default_client_id()).is_ok());
+}
+
+#[test]
+fn redact_tagged_blocks_test() {
+  let mut data = tagged_document("internal");
+  let ids = top_level_ids(&data);
+  let policy = SensitivityTagPolicy::new(["internal", "confidential"], RedactionAction::Redact);
+
+  let manifest = data.redact(&policy);
+  assert_eq!(manifest.num_of_redacted(), 2);
+  assert_eq!(
+    manifest.blocks[1].block_type,
+    BlockType::BulletedList.to_string()
+  );
+
+  // the blocks are kept in place as placeholders without their data and children
+  assert_eq!(top_level_ids(&data), ids);
+  let list = &data.blocks[&ids[2]];
+  assert_eq!(list.ty, BlockType::Paragraph.to_string());
+  assert!(list.data.is_empty());
+  assert!(data.meta.children_map[&list.children].is_empty());
+  let text = &data.meta.text_map.as_ref().unwrap()[list.external_id.as_ref().unwrap()];
+  assert_eq!(text, &json!([{ "insert": REDACTED_TEXT }]).to_string());
+  assert!(Document::create("1", data, default_client_id()).is_ok());
+}
+
+#[test]
+fn redact_untagged_document_test() {
+  let mut data = tagged_document("public");
+  let expected = data.clone();
+  let manifest = data.redact(&SensitivityTagPolicy::default());
+  assert!(manifest.is_empty());
+  assert_eq!(data, expected);
+}
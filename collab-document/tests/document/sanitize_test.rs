@@ -0,0 +1,120 @@
+use crate::util::{block_delta, top_level_ids};
+use collab::core::collab::DataSource;
+use collab::core::origin::CollabOrigin;
+use collab::preclude::updates::decoder::Decode;
+use collab::preclude::{ReadTxn, StateVector, Update};
+use collab_document::blocks::{BlockType, DocumentData};
+use collab_document::document::Document;
+use collab_document::importer::md_importer::MDImporter;
+use serde_json::json;
+
+/// Two paragraphs, the first one with the authorship fields and a commented text.
+fn reviewed_document(document_id: &str) -> DocumentData {
+  let mut data = MDImporter::new(None)
+    .import(document_id, "Hello world\n\nsecond".to_string())
+    .unwrap();
+  let first_id = top_level_ids(&data)[0].clone();
+  let first = data.blocks.get_mut(&first_id).unwrap();
+  first
+    .data
+    .insert("created_by".to_string(), json!("alice@appflowy.io"));
+  first
+    .data
+    .insert("last_modified".to_string(), json!(1714557600));
+  let external_id = first.external_id.clone().unwrap();
+  data.meta.text_map.as_mut().unwrap().insert(
+    external_id,
+    json!([
+      { "insert": "Hello ", "attributes": { "comment": "thread_1" } },
+      { "insert": "world", "attributes": { "bold": true, "suggestion": "s_1" } }
+    ])
+    .to_string(),
+  );
+  data
+}
+
+#[test]
+fn sanitize_document_data_test() {
+  let mut data = reviewed_document("1");
+  let ids = top_level_ids(&data);
+  // a block left by a deleted parent
+  let mut orphan = data.blocks[&ids[1]].clone();
+  orphan.id = "orphan".to_string();
+  orphan.parent = "deleted".to_string();
+  orphan.external_id = Some("orphan_text".to_string());
+  data.blocks.insert(orphan.id.clone(), orphan);
+  data
+    .meta
+    .text_map
+    .as_mut()
+    .unwrap()
+    .insert("orphan_text".to_string(), "[]".to_string());
+
+  data.sanitize();
+  assert!(!data.blocks.contains_key("orphan"));
+  assert!(
+    !data
+      .meta
+      .text_map
+      .as_ref()
+      .unwrap()
+      .contains_key("orphan_text")
+  );
+  let first = &data.blocks[&ids[0]];
+  assert_eq!(first.ty, BlockType::Paragraph.to_string());
+  assert!(!first.data.contains_key("created_by"));
+  assert!(!first.data.contains_key("last_modified"));
+
+  // the review marks are removed, the text and the other attributes are kept
+  assert_eq!(
+    block_delta(&data, &ids[0]),
+    json!([
+      { "insert": "Hello " },
+      { "insert": "world", "attributes": { "bold": true } }
+    ])
+  );
+}
+
+#[test]
+fn export_sanitized_document_test() {
+  let document_id = uuid::Uuid::new_v4().to_string();
+  let mut document = Document::create(&document_id, reviewed_document(&document_id), 1).unwrap();
+  let second_id = top_level_ids(&document.get_document_data().unwrap())[1].clone();
+
+  // another editor deletes the second paragraph
+  let mut editor = Document::open_with_options(
+    CollabOrigin::Empty,
+    DataSource::DocStateV1(document.encode_collab().unwrap().doc_state.to_vec()),
+    &document_id,
+    2,
+  )
+  .unwrap();
+  editor.delete_block(&second_id).unwrap();
+  let update = editor
+    .transact()
+    .encode_state_as_update_v1(&StateVector::default());
+  document
+    .apply_update(Update::decode_v1(&update).unwrap())
+    .unwrap();
+  assert_eq!(document.transact().state_vector().len(), 2);
+
+  let encoded = document.export_sanitized().unwrap();
+  let update = Update::decode_v1(&encoded.doc_state).unwrap();
+  assert_eq!(update.state_vector().len(), 1);
+
+  let exported = Document::open_with_options(
+    CollabOrigin::Empty,
+    DataSource::DocStateV1(encoded.doc_state.to_vec()),
+    &document_id,
+    3,
+  )
+  .unwrap();
+  let data = exported.get_document_data().unwrap();
+  let ids = top_level_ids(&data);
+  assert_eq!(ids.len(), 1);
+  assert!(!data.blocks[&ids[0]].data.contains_key("created_by"));
+  assert_eq!(
+    block_delta(&data, &ids[0])[0],
+    json!({ "insert": "Hello " })
+  );
+}
@@ -0,0 +1,101 @@
+use collab::core::collab::default_client_id;
+use collab_document::blocks::DocumentData;
+use collab_document::document::Document;
+use collab_document::importer::md_importer::MDImporter;
+use collab_document::slug::{HeadingSlugger, SLUG_FIELD, heading_slug};
+use serde_json::json;
+
+const MARKDOWN: &str = "# Getting Started!\n\ntext\n\n## What's new in v1.0?\n\n# Getting Started\n\n## Getting Started-1\n";
+
+fn heading_ids(data: &DocumentData) -> Vec<String> {
+  data
+    .heading_slugs()
+    .into_iter()
+    .map(|(block_id, _)| block_id)
+    .collect()
+}
+
+#[test]
+fn heading_slug_test() {
+  assert_eq!(heading_slug("Getting Started!"), "getting-started");
+  assert_eq!(heading_slug("  What's new in v1.0? "), "whats-new-in-v10");
+  assert_eq!(
+    heading_slug("snake_case & kebab-case"),
+    "snake_case--kebab-case"
+  );
+  assert_eq!(heading_slug("Café 🎉 Ünïcode"), "café--ünïcode");
+  assert_eq!(heading_slug("???"), "");
+
+  // the duplicates get a suffix, even when the suffixed slug is itself a heading
+  let mut slugger = HeadingSlugger::default();
+  assert_eq!(slugger.slug("Intro"), "intro");
+  assert_eq!(slugger.slug("Intro-1"), "intro-1");
+  assert_eq!(slugger.slug("Intro"), "intro-2");
+  assert_eq!(slugger.slug("intro"), "intro-3");
+}
+
+#[test]
+fn import_markdown_with_heading_slugs_test() {
+  let data = MDImporter::new(None)
+    .with_heading_slugs(true)
+    .import("1", MARKDOWN.to_string())
+    .unwrap();
+  let slugs = heading_ids(&data)
+    .iter()
+    .map(|id| data.blocks[id].data[SLUG_FIELD].clone())
+    .collect::<Vec<_>>();
+  assert_eq!(
+    slugs,
+    vec![
+      json!("getting-started"),
+      json!("whats-new-in-v10"),
+      json!("getting-started-1"),
+      json!("getting-started-1-1"),
+    ]
+  );
+
+  let ids = heading_ids(&data);
+  assert_eq!(
+    data.heading_id_by_anchor("#whats-new-in-v10"),
+    Some(ids[1].clone())
+  );
+  assert_eq!(
+    data.heading_id_by_anchor("getting-started-1"),
+    Some(ids[2].clone())
+  );
+  assert_eq!(data.heading_id_by_anchor("#missing"), None);
+
+  // the slugs are not stored by default
+  let data = MDImporter::new(None)
+    .import("1", MARKDOWN.to_string())
+    .unwrap();
+  assert!(
+    data
+      .blocks
+      .values()
+      .all(|block| !block.data.contains_key(SLUG_FIELD))
+  );
+  assert_eq!(
+    data.heading_id_by_anchor("#getting-started"),
+    Some(heading_ids(&data)[0].clone())
+  );
+}
+
+#[test]
+fn assign_heading_slugs_to_document_test() {
+  let data = MDImporter::new(None)
+    .import("1", MARKDOWN.to_string())
+    .unwrap();
+  let ids = heading_ids(&data);
+  let text_id = data.blocks[&ids[1]].external_id.clone().unwrap();
+  let mut document = Document::create("1", data, default_client_id()).unwrap();
+  document.assign_heading_slugs().unwrap();
+  let (_, block_data) = document.get_block_data(&ids[3]).unwrap();
+  assert_eq!(block_data[SLUG_FIELD], json!("getting-started-1-1"));
+
+  // the slugs follow the text of the headings
+  document.apply_text_delta(&text_id, json!([{ "insert": "Intro " }]).to_string());
+  document.assign_heading_slugs().unwrap();
+  let (_, block_data) = document.get_block_data(&ids[1]).unwrap();
+  assert_eq!(block_data[SLUG_FIELD], json!("intro-whats-new-in-v10"));
+}
@@ -0,0 +1,118 @@
+use collab::core::collab::default_client_id;
+use collab_document::document::Document;
+use collab_document::error::DocumentError;
+use collab_document::importer::md_importer::MDImporter;
+
+fn nested_list_document() -> Document {
+  let markdown = r#"# Title
+- a
+  - b
+    - c
+- d
+"#;
+  let data = MDImporter::new(None)
+    .import("subtree_test", markdown.to_string())
+    .unwrap();
+  Document::create("subtree_test", data, default_client_id()).unwrap()
+}
+
+#[test]
+fn get_subtree_with_depth_test() {
+  let document = nested_list_document();
+  let page_id = document.get_page_id().unwrap();
+  let page_children = document.get_block_children_ids(&page_id);
+  assert_eq!(page_children.len(), 3);
+  let list_a = page_children[1].clone();
+
+  let subtree = document.get_subtree(&list_a, None).unwrap();
+  assert_eq!(subtree.page_id, list_a);
+  assert_eq!(subtree.blocks.len(), 3);
+
+  let subtree = document.get_subtree(&list_a, Some(1)).unwrap();
+  assert_eq!(subtree.blocks.len(), 2);
+  let list_b = document.get_block_children_ids(&list_a)[0].clone();
+  let list_b_children = &subtree.blocks.get(&list_b).unwrap().children;
+  assert!(subtree.meta.children_map[list_b_children].is_empty());
+
+  let subtree = document.get_subtree(&list_a, Some(0)).unwrap();
+  assert_eq!(subtree.blocks.len(), 1);
+}
+
+#[test]
+fn get_subtree_loads_text_lazily_test() {
+  let document = nested_list_document();
+  let page_id = document.get_page_id().unwrap();
+  let list_a = document.get_block_children_ids(&page_id)[1].clone();
+
+  let mut subtree = document.get_subtree(&list_a, None).unwrap();
+  assert!(subtree.meta.text_map.as_ref().unwrap().is_empty());
+
+  let delta = document.load_block_text(&mut subtree, &list_a).unwrap();
+  assert!(delta.contains("\"a\""));
+  assert_eq!(subtree.meta.text_map.as_ref().unwrap().len(), 1);
+
+  // The second read is served from the text map
+  let cached = document.load_block_text(&mut subtree, &list_a).unwrap();
+  assert_eq!(delta, cached);
+  assert_eq!(subtree.meta.text_map.as_ref().unwrap().len(), 1);
+
+  // Blocks outside of the subtree are not loaded
+  let list_d = document.get_block_children_ids(&page_id)[2].clone();
+  assert!(document.load_block_text(&mut subtree, &list_d).is_none());
+}
+
+#[test]
+fn get_subtree_of_unknown_block_test() {
+  let document = nested_list_document();
+  let result = document.get_subtree("unknown", None);
+  assert!(matches!(result, Err(DocumentError::BlockIsNotFound)));
+}
+
+#[test]
+fn get_document_structure_keeps_text_lazy_test() {
+  let document = nested_list_document();
+  let mut structure = document.get_document_structure().unwrap();
+  assert!(structure.meta.lazy_text);
+  assert!(structure.meta.text_map.as_ref().unwrap().is_empty());
+
+  let full = document.get_document_data().unwrap();
+  assert!(!full.meta.lazy_text);
+  assert_eq!(structure.blocks, full.blocks);
+  assert_eq!(structure.meta.children_map, full.meta.children_map);
+
+  // Read the text of a single block on demand.
+  let page_id = document.get_page_id().unwrap();
+  let title_id = document.get_block_children_ids(&page_id)[0].clone();
+  let title = document.get_text(&title_id).unwrap();
+  assert!(title.contains("Title"));
+  assert!(structure.meta.text_map.as_ref().unwrap().is_empty());
+
+  document.hydrate_text(&mut structure);
+  assert!(!structure.meta.lazy_text);
+  assert_eq!(structure.meta.text_map, full.meta.text_map);
+}
+
+#[test]
+fn lazy_document_data_can_not_be_written_test() {
+  let document = nested_list_document();
+  let structure = document.get_document_structure().unwrap();
+  let result = Document::create("lazy_test", structure, default_client_id());
+  assert!(matches!(result, Err(DocumentError::TextNotHydrated)));
+
+  let subtree = document
+    .get_subtree(&document.get_page_id().unwrap(), None)
+    .unwrap();
+  assert!(subtree.meta.lazy_text);
+}
+
+#[test]
+fn lazy_flag_is_not_serialized_when_unset_test() {
+  let document = nested_list_document();
+  let json = serde_json::to_value(document.get_document_data().unwrap()).unwrap();
+  assert!(json["meta"].get("lazy_text").is_none());
+
+  let json = serde_json::to_value(document.get_document_structure().unwrap()).unwrap();
+  assert_eq!(json["meta"]["lazy_text"], serde_json::json!(true));
+  let data: collab_document::blocks::DocumentData = serde_json::from_value(json).unwrap();
+  assert!(data.meta.lazy_text);
+}
@@ -0,0 +1,95 @@
+use crate::importer::util::{get_children_blocks, get_delta_json, get_page_block};
+use collab_document::blocks::Block;
+use collab_document::importer::filter::{ContentFilter, TextLengthLimit, UnsafeContentFilter};
+use collab_document::importer::fragment::ContentType;
+use collab_document::importer::md_importer::MDImporter;
+use serde_json::{Map, Value, json};
+
+/// Redact the words that look like an email and drop the code blocks.
+struct RedactFilter;
+
+impl ContentFilter for RedactFilter {
+  fn filter_block(&self, block: &mut Block) -> bool {
+    block.ty != "code"
+  }
+
+  fn filter_text(&self, _block: &Block, text: &mut String, _attributes: &mut Map<String, Value>) {
+    *text = text
+      .split(' ')
+      .map(|word| if word.contains('@') { "[redacted]" } else { word })
+      .collect::<Vec<_>>()
+      .join(" ");
+  }
+}
+
+#[test]
+fn unsafe_links_are_removed_test() {
+  let markdown = r#"[click](javascript:alert(1)) and [AppFlowy](https://appflowy.io)
+
+<script>alert(1)</script>"#;
+  let result = MDImporter::new(None)
+    .with_content_filter(UnsafeContentFilter)
+    .import("test_document", markdown.to_string())
+    .unwrap();
+  let page = get_page_block(&result);
+  let children = get_children_blocks(&result, &page.id);
+  assert_eq!(
+    get_delta_json(&result, &children[0].id),
+    json!([
+      {"insert": "click"},
+      {"insert": " and "},
+      {"insert": "AppFlowy", "attributes": {"href": "https://appflowy.io"}},
+    ])
+  );
+  let text_map = result.meta.text_map.as_ref().unwrap();
+  assert!(text_map.values().all(|delta| !delta.contains("script")));
+}
+
+#[test]
+fn custom_filter_redacts_and_drops_blocks_test() {
+  let markdown = r#"Contact me at jane@example.com
+
+```
+secret
+```
+
+- item by bob@example.com"#;
+  let result = MDImporter::new(None)
+    .with_content_filter(RedactFilter)
+    .with_content_filter(TextLengthLimit { max_chars: 20 })
+    .import("test_document", markdown.to_string())
+    .unwrap();
+  let page = get_page_block(&result);
+  let children = get_children_blocks(&result, &page.id);
+  let types = children
+    .iter()
+    .map(|block| block.ty.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(types, vec!["paragraph", "bulleted_list"]);
+  assert!(result.blocks.values().all(|block| block.ty != "code"));
+  assert_eq!(
+    get_delta_json(&result, &children[0].id),
+    json!([{"insert": "Contact me at [redac"}])
+  );
+  assert_eq!(
+    get_delta_json(&result, &children[1].id),
+    json!([{"insert": "item by [redacted]"}])
+  );
+}
+
+#[test]
+fn fragments_are_filtered_test() {
+  let importer = MDImporter::new(None).with_content_filter(RedactFilter);
+  for content_type in [ContentType::Markdown, ContentType::PlainText] {
+    let fragment = importer
+      .import_fragment("parent_id", "mail jane@example.com", content_type)
+      .unwrap();
+    assert_eq!(fragment.top_level_ids.len(), 1);
+    let block = &fragment.blocks[0];
+    let delta = &fragment.text_map[block.external_id.as_ref().unwrap()];
+    assert_eq!(
+      serde_json::from_str::<Value>(delta).unwrap(),
+      json!([{"insert": "mail [redacted]"}])
+    );
+  }
+}
@@ -0,0 +1,175 @@
+use crate::importer::util::{get_children_blocks, get_page_block};
+use assert_json_diff::assert_json_eq;
+use async_trait::async_trait;
+use collab_document::block_parser::{DocumentParser, OutputFormat};
+use collab_document::blocks::{Block, DocumentDataBuilder};
+use collab_document::error::DocumentError;
+use collab_document::importer::embed::{
+  EmbedMetadata, EmbedProvider, EmbedResolver, resolve_embeds,
+};
+use collab_document::importer::md_importer::MDImporter;
+use serde_json::json;
+
+#[test]
+fn embed_provider_from_url_test() {
+  let cases = [
+    ("https://youtu.be/dQw4w9WgXcQ", Some(EmbedProvider::YouTube)),
+    (
+      "https://www.figma.com/design/abc123/Landing-page",
+      Some(EmbedProvider::Figma),
+    ),
+    (
+      "https://x.com/appflowy/status/1234567890",
+      Some(EmbedProvider::Twitter),
+    ),
+    (
+      "https://www.google.com/maps/place/Eiffel+Tower",
+      Some(EmbedProvider::GoogleMaps),
+    ),
+    (
+      "https://maps.app.goo.gl/abc",
+      Some(EmbedProvider::GoogleMaps),
+    ),
+    ("https://twitter.com/appflowy", None),
+    ("https://www.figma.com/pricing", None),
+    ("https://appflowy.io", None),
+  ];
+  for (url, provider) in cases {
+    assert_eq!(EmbedProvider::from_url(url), provider, "{}", url);
+  }
+
+  assert_eq!(
+    EmbedProvider::YouTube
+      .oembed_url("https://youtu.be/dQw4w9WgXcQ?t=1")
+      .unwrap(),
+    "https://www.youtube.com/oembed?format=json&url=https%3A%2F%2Fyoutu.be%2FdQw4w9WgXcQ%3Ft%3D1"
+  );
+  assert_eq!(
+    EmbedProvider::GoogleMaps.oembed_url("https://maps.google.com/?q=Paris"),
+    None
+  );
+}
+
+#[test]
+fn import_embed_links_test() {
+  let markdown = r#"[https://youtu.be/dQw4w9WgXcQ](https://youtu.be/dQw4w9WgXcQ)
+
+[Landing page](https://www.figma.com/file/abc123/Landing)
+
+<https://x.com/appflowy/status/1234567890>
+
+[Office](https://www.google.com/maps/place/Office)
+
+[AppFlowy](https://appflowy.io)"#;
+
+  let importer = MDImporter::new(None)
+    .with_embeds(true)
+    .with_media_embeds(true);
+  let result = importer
+    .import("test_document", markdown.to_string())
+    .unwrap();
+  let page = get_page_block(&result);
+  let children = get_children_blocks(&result, &page.id);
+  let types = children
+    .iter()
+    .map(|block| block.ty.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(types, vec!["embed", "embed", "embed", "embed", "paragraph"]);
+  assert_json_eq!(
+    json!(children[0].data),
+    json!({"url": "https://youtu.be/dQw4w9WgXcQ", "provider": "youtube"})
+  );
+  assert_json_eq!(
+    json!(children[1].data),
+    json!({
+      "url": "https://www.figma.com/file/abc123/Landing",
+      "provider": "figma",
+      "title": "Landing page",
+    })
+  );
+  assert_eq!(children[2].data["provider"], json!("twitter"));
+  assert_eq!(children[3].data["provider"], json!("google_maps"));
+  assert!(children[0].external_id.is_none());
+
+  let markdown = DocumentParser::with_default_parsers()
+    .parse_document(&result, OutputFormat::Markdown)
+    .unwrap();
+  assert!(markdown.starts_with(
+    "[https://youtu.be/dQw4w9WgXcQ](https://youtu.be/dQw4w9WgXcQ)\n\
+     [Landing page](https://www.figma.com/file/abc123/Landing)"
+  ));
+}
+
+struct TestResolver;
+
+#[async_trait]
+impl EmbedResolver for TestResolver {
+  async fn resolve(
+    &self,
+    url: &str,
+    provider: EmbedProvider,
+  ) -> Result<Option<EmbedMetadata>, DocumentError> {
+    match provider {
+      EmbedProvider::YouTube => EmbedMetadata::from_oembed(
+        r#"{"title": "Never Gonna Give You Up", "author_name": "Rick Astley",
+            "thumbnail_url": "https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg"}"#,
+      )
+      .map(Some),
+      EmbedProvider::GoogleMaps => Ok(Some(EmbedMetadata::from_open_graph(
+        r#"<html><head><title>Ignored</title>
+        <meta property="og:title" content="Eiffel Tower &amp; Park">
+        <meta content="A landmark" property="og:description" />
+        <meta property="og:image" content='https://maps.example.com/eiffel.png'>
+        </head></html>"#,
+      ))),
+      _ => Err(DocumentError::Internal(anyhow::anyhow!(
+        "{} is unreachable",
+        url
+      ))),
+    }
+  }
+}
+
+#[tokio::test]
+async fn resolve_embeds_test() {
+  let mut data = DocumentDataBuilder::new()
+    .with_block(Block::embed("https://youtu.be/dQw4w9WgXcQ"))
+    .with_block(Block::embed(
+      "https://www.google.com/maps/place/Eiffel+Tower",
+    ))
+    .with_block(Block::embed("https://x.com/appflowy/status/1234567890"))
+    .build();
+
+  let resolved = resolve_embeds(&mut data, &TestResolver).await;
+  assert_eq!(resolved, 2);
+
+  let page = get_page_block(&data);
+  let children = get_children_blocks(&data, &page.id);
+  assert_json_eq!(
+    json!(children[0].data),
+    json!({
+      "url": "https://youtu.be/dQw4w9WgXcQ",
+      "provider": "youtube",
+      "title": "Never Gonna Give You Up",
+      "description": "Rick Astley",
+      "thumbnail_url": "https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg",
+    })
+  );
+  assert_json_eq!(
+    json!(children[1].data),
+    json!({
+      "url": "https://www.google.com/maps/place/Eiffel+Tower",
+      "provider": "google_maps",
+      "title": "Eiffel Tower & Park",
+      "description": "A landmark",
+      "thumbnail_url": "https://maps.example.com/eiffel.png",
+    })
+  );
+  assert_json_eq!(
+    json!(children[2].data),
+    json!({
+      "url": "https://x.com/appflowy/status/1234567890",
+      "provider": "twitter",
+    })
+  );
+}
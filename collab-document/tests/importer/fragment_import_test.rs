@@ -0,0 +1,229 @@
+use collab::core::collab::default_client_id;
+use collab_document::blocks::SimpleTableData;
+use collab_document::blocks::{BlockType, TextDelta};
+use collab_document::document::Document;
+use collab_document::document_data::default_document_data;
+use collab_document::importer::fragment::{ContentType, DocumentFragment, TsvTable};
+use collab_document::importer::md_importer::MDImporter;
+use serde_json::json;
+
+fn import_fragment(content: &str, content_type: ContentType) -> DocumentFragment {
+  MDImporter::new(None)
+    .import_fragment("parent_id", content, content_type)
+    .unwrap()
+}
+
+fn block_types(fragment: &DocumentFragment, ids: &[String]) -> Vec<String> {
+  ids
+    .iter()
+    .map(|id| {
+      fragment
+        .blocks
+        .iter()
+        .find(|block| &block.id == id)
+        .unwrap()
+        .ty
+        .clone()
+    })
+    .collect()
+}
+
+fn delta_json(fragment: &DocumentFragment, block_id: &str) -> serde_json::Value {
+  let block = fragment
+    .blocks
+    .iter()
+    .find(|block| block.id == block_id)
+    .unwrap();
+  let text_id = block.external_id.as_ref().unwrap();
+  serde_json::from_str(&fragment.text_map[text_id]).unwrap()
+}
+
+#[test]
+fn markdown_fragment_test() {
+  let fragment = import_fragment("# Title\n\n- a\n  - b\n- c\n\nend", ContentType::Markdown);
+  assert_eq!(fragment.parent_id, "parent_id");
+  assert_eq!(
+    block_types(&fragment, &fragment.top_level_ids),
+    vec![
+      BlockType::Heading.to_string(),
+      BlockType::BulletedList.to_string(),
+      BlockType::BulletedList.to_string(),
+      BlockType::Paragraph.to_string(),
+    ]
+  );
+  assert!(!fragment.blocks.iter().any(|block| block.ty == "page"));
+
+  // Each block comes after its parent and after its previous sibling.
+  let ids = fragment
+    .blocks
+    .iter()
+    .map(|block| block.id.clone())
+    .collect::<Vec<_>>();
+  for (index, block) in fragment.blocks.iter().enumerate() {
+    if block.parent == "parent_id" {
+      continue;
+    }
+    let parent_index = ids.iter().position(|id| id == &block.parent).unwrap();
+    assert!(parent_index < index);
+  }
+  let nested = &fragment.blocks[2];
+  assert_eq!(nested.parent, fragment.top_level_ids[1]);
+  assert_eq!(delta_json(&fragment, &nested.id), json!([{"insert": "b"}]));
+}
+
+#[test]
+fn html_fragment_test() {
+  let html = r#"<meta charset="utf-8"><h2>Title</h2>
+<p>Some <b>bold</b> and <a href="https://appflowy.io">link</a> &amp; more</p>
+<ul><li>one<li>two</ul>
+<table><tr><th>a</th><th>b</th></tr><tr><td>1</td><td>2</td></tr></table>"#;
+  let fragment = import_fragment(html, ContentType::Html);
+  assert_eq!(
+    block_types(&fragment, &fragment.top_level_ids),
+    vec![
+      BlockType::Heading.to_string(),
+      BlockType::Paragraph.to_string(),
+      BlockType::BulletedList.to_string(),
+      BlockType::BulletedList.to_string(),
+      BlockType::SimpleTable.to_string(),
+    ]
+  );
+  assert_eq!(
+    delta_json(&fragment, &fragment.top_level_ids[1]),
+    json!([
+      {"insert": "Some "},
+      {"insert": "bold", "attributes": {"bold": true}},
+      {"insert": " and "},
+      {"insert": "link", "attributes": {"href": "https://appflowy.io"}},
+      {"insert": " & more"},
+    ])
+  );
+  let cells = fragment
+    .blocks
+    .iter()
+    .filter(|block| block.ty == BlockType::SimpleTableCell.to_string())
+    .count();
+  assert_eq!(cells, 4);
+}
+
+#[test]
+fn plain_text_fragment_test() {
+  let fragment = import_fragment(
+    "\n# not a heading\r\n\r\n**line**\n",
+    ContentType::PlainText,
+  );
+  assert_eq!(fragment.top_level_ids.len(), 3);
+  assert!(
+    fragment
+      .blocks
+      .iter()
+      .all(|block| block.ty == BlockType::Paragraph.to_string() && block.parent == "parent_id")
+  );
+  assert_eq!(
+    delta_json(&fragment, &fragment.top_level_ids[0]),
+    json!([{"insert": "# not a heading"}])
+  );
+  assert_eq!(delta_json(&fragment, &fragment.top_level_ids[1]), json!([]));
+  assert_eq!(
+    delta_json(&fragment, &fragment.top_level_ids[2]),
+    json!([{"insert": "**line**"}])
+  );
+}
+
+#[test]
+fn insert_fragment_at_cursor_test() {
+  let data = default_document_data("fragment_test");
+  let page_id = data.page_id.clone();
+  let mut document = Document::create("fragment_test", data, default_client_id()).unwrap();
+  let first_id = document.get_block_children_ids(&page_id)[0].clone();
+
+  let fragment = MDImporter::new(None)
+    .import_fragment(&page_id, "one\n\n- two\n  - three", ContentType::Markdown)
+    .unwrap();
+  let inserted = document
+    .insert_fragment(fragment.clone(), Some(first_id.clone()))
+    .unwrap();
+  assert_eq!(inserted, fragment.top_level_ids);
+
+  let children = document.get_block_children_ids(&page_id);
+  assert_eq!(
+    children,
+    vec![first_id, inserted[0].clone(), inserted[1].clone()]
+  );
+  assert_eq!(
+    document.get_plain_text_from_block(&inserted[0]).unwrap(),
+    "one"
+  );
+  let nested = document.get_block_children_ids(&inserted[1]);
+  assert_eq!(nested.len(), 1);
+  let (_, delta) = document.get_block_delta(&nested[0]).unwrap();
+  assert_eq!(delta, vec![TextDelta::Inserted("three".to_string(), None)]);
+}
+
+#[test]
+fn tsv_paste_as_simple_table_test() {
+  let tsv = "Name\tPrice\tNote\r\nApple\t1,200.50\tfresh\r\nPear\t$3\t\r\n";
+  let fragment = import_fragment(tsv, ContentType::PlainText);
+  assert_eq!(fragment.top_level_ids.len(), 1);
+  let table = &fragment.blocks[0];
+  assert_eq!(table.ty, BlockType::SimpleTable.to_string());
+  assert!(SimpleTableData::from_block_data(&table.data).enable_header_row);
+
+  let cells = fragment
+    .blocks
+    .iter()
+    .filter(|block| block.ty == BlockType::SimpleTableCell.to_string())
+    .collect::<Vec<_>>();
+  assert_eq!(cells.len(), 9);
+  for cell in cells.iter() {
+    let align = cell.data.get("align").and_then(|align| align.as_str());
+    let is_price_column = cell.data["colPosition"] == json!(1);
+    assert_eq!(align == Some("right"), is_price_column);
+  }
+
+  // Each cell holds one paragraph with the text of the cell.
+  let price_cell = cells
+    .iter()
+    .find(|cell| cell.data["rowPosition"] == json!(1) && cell.data["colPosition"] == json!(1))
+    .unwrap();
+  let paragraph = fragment
+    .blocks
+    .iter()
+    .find(|block| block.parent == price_cell.id)
+    .unwrap();
+  assert_eq!(
+    delta_json(&fragment, &paragraph.id),
+    json!([{"insert": "1,200.50"}])
+  );
+}
+
+#[test]
+fn tsv_detection_test() {
+  // A single line or prose with a stray tab is not a table.
+  assert!(TsvTable::detect("a\tb").is_none());
+  assert!(TsvTable::detect("first line\nsecond\tline").is_none());
+
+  // Without a numeric column the first row can't be told apart from the data.
+  let table = TsvTable::detect("a\tb\nc\td").unwrap();
+  assert!(!table.has_header_row);
+  assert_eq!(table.numeric_columns, vec![false, false]);
+
+  // Quoted cells may contain line breaks, tabs and escaped quotes.
+  let table = TsvTable::detect("\"multi\nline\"\t\"say \"\"hi\"\"\"\n1\t2").unwrap();
+  assert_eq!(
+    table.rows,
+    vec![
+      vec!["multi\nline".to_string(), "say \"hi\"".to_string()],
+      vec!["1".to_string(), "2".to_string()],
+    ]
+  );
+  assert!(table.has_header_row);
+  assert_eq!(table.numeric_columns, vec![true, true]);
+
+  // Tab-separated markdown is pasted as a table too.
+  let fragment = import_fragment("x\ty\n1\t2", ContentType::Markdown);
+  assert_eq!(
+    block_types(&fragment, &fragment.top_level_ids),
+    vec![BlockType::SimpleTable.to_string()]
+  );
+}
@@ -0,0 +1,71 @@
+use collab_document::error::DocumentError;
+use collab_document::importer::guard::{InputLimits, is_binary};
+use collab_document::importer::md_importer::MDImporter;
+
+#[test]
+fn binary_detection_test() {
+  assert!(!is_binary("# Title\n\nSome text\twith a tab.\r\n"));
+  assert!(!is_binary("中文 and émojis 🎉"));
+  assert!(is_binary("PK\u{3}\u{4}\0\0zip content"));
+  let png = String::from_utf8_lossy(&[0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0xff, 0xfe]);
+  assert!(is_binary(&png));
+}
+
+#[test]
+fn reject_binary_input_test() {
+  let importer = MDImporter::new(None);
+  let err = importer
+    .import("test_document", "\u{1}\u{2}\u{3}binary".to_string())
+    .unwrap_err();
+  assert!(matches!(err, DocumentError::BinaryContent));
+  assert_eq!(err.code(), "document.binary_content");
+
+  let err = importer
+    .import_html("test_document", "<p>\0</p>")
+    .unwrap_err();
+  assert!(matches!(err, DocumentError::BinaryContent));
+}
+
+#[test]
+fn reject_large_input_test() {
+  let limits = InputLimits {
+    max_input_bytes: 64,
+    max_line_bytes: 16,
+    ..InputLimits::default()
+  };
+  let importer = MDImporter::new(None).with_input_limits(limits);
+  let err = importer
+    .import("test_document", "a".repeat(65))
+    .unwrap_err();
+  assert!(matches!(
+    err,
+    DocumentError::InputTooLarge {
+      size: 65,
+      limit: 64
+    }
+  ));
+
+  let err = importer
+    .import("test_document", format!("short\nshort\n{}", "b".repeat(17)))
+    .unwrap_err();
+  assert!(matches!(
+    err,
+    DocumentError::LineTooLong {
+      line: 3,
+      len: 17,
+      limit: 16
+    }
+  ));
+  assert_eq!(
+    err.to_string(),
+    "Line 3 is 17 bytes, over the limit of 16 bytes"
+  );
+
+  // the lines of the html are not checked, the html of a saved page is often minified
+  let html = "<p>c</p>".repeat(6);
+  assert!(importer.import_html("test_document", &html).is_ok());
+
+  // without limits, everything is parsed
+  let importer = MDImporter::new(None).with_input_limits(InputLimits::unlimited());
+  assert!(importer.import("test_document", "d".repeat(100)).is_ok());
+}
@@ -0,0 +1,223 @@
+use collab_document::blocks::BlockType;
+use collab_document::importer::columns::{ColumnsDetection, ColumnsThresholds};
+use collab_document::importer::md_importer::MDImporter;
+use collab_document::importer::report::{ConvertedTable, FormattingLossKind};
+use markdown::ParseOptions;
+
+#[test]
+fn plain_markdown_is_lossless_test() {
+  let markdown = r#"# Title
+
+Some **bold** and *italic* text with a [link](https://appflowy.io).
+
+- item 1
+- item 2
+"#;
+  let importer = MDImporter::new(None);
+  let (_, report) = importer
+    .import_with_report("test_document", markdown.to_string())
+    .unwrap();
+  assert!(report.is_lossless(), "{:?}", report.losses);
+}
+
+#[test]
+fn line_break_loss_test() {
+  let markdown = r#"first line
+
+This is <u>underlined</u> and E = mc<sup>2</sup>.
+
+Another<br>line and<br>more.
+"#;
+  let importer = MDImporter::new(None);
+  let (data, report) = importer
+    .import_with_report("test_document", markdown.to_string())
+    .unwrap();
+  assert!(!data.blocks.is_empty());
+
+  // The underlines and superscripts are imported as text attributes.
+  assert_eq!(report.count(FormattingLossKind::Underline), 0);
+  assert_eq!(report.count(FormattingLossKind::Superscript), 0);
+  assert_eq!(report.count(FormattingLossKind::LineBreak), 2);
+
+  let counts = report.counts();
+  assert_eq!(counts.get(&FormattingLossKind::LineBreak), Some(&2));
+  assert_eq!(counts.get(&FormattingLossKind::Subscript), None);
+
+  let line_break = report
+    .iter_kind(FormattingLossKind::LineBreak)
+    .next()
+    .unwrap();
+  assert_eq!(line_break.line, Some(5));
+  assert_eq!(line_break.to_string(), "line break dropped at line 5");
+}
+
+#[test]
+fn footnote_code_meta_and_title_loss_test() {
+  let markdown = r#"Text with a footnote[^1].
+
+```rust title="main.rs"
+fn main() {}
+```
+
+[link](https://appflowy.io "AppFlowy")
+
+![logo](https://appflowy.io/logo.png)
+
+[^1]: The footnote.
+"#;
+  let importer = MDImporter::new(None);
+  let (_, report) = importer
+    .import_with_report("test_document", markdown.to_string())
+    .unwrap();
+
+  assert_eq!(report.count(FormattingLossKind::Footnote), 2);
+  assert_eq!(report.count(FormattingLossKind::CodeMetadata), 1);
+  assert_eq!(report.count(FormattingLossKind::Title), 1);
+  assert_eq!(report.count(FormattingLossKind::ImageAltText), 1);
+}
+
+#[test]
+fn nested_table_and_dropped_content_loss_test() {
+  let markdown = r#"| a | b |
+| --- | --- |
+| <table><tr><td>inner</td></tr></table> | text |
+
+- ```
+  code as the first child of a list item
+  ```
+"#;
+  let importer = MDImporter::new(None);
+  let (_, report) = importer
+    .import_with_report("test_document", markdown.to_string())
+    .unwrap();
+
+  assert_eq!(report.count(FormattingLossKind::NestedTableFlattened), 1);
+  let nested = report
+    .iter_kind(FormattingLossKind::NestedTableFlattened)
+    .next()
+    .unwrap();
+  assert_eq!(nested.to_string(), "nested table flattened at line 3");
+
+  assert_eq!(report.count(FormattingLossKind::DroppedContent), 1);
+}
+
+#[test]
+fn parse_failure_falls_back_to_plain_text_test() {
+  // the unclosed MDX expression can't be parsed
+  let markdown = "# Title\n\nfirst line\nsecond line\n\n\n{ unclosed\n";
+  let importer = MDImporter::new(Some(ParseOptions::mdx()));
+  let (data, report) = importer
+    .import_with_report("test_document", markdown.to_string())
+    .unwrap();
+  assert_eq!(report.count(FormattingLossKind::ParseFailure), 1);
+
+  let page = &data.blocks[&data.page_id];
+  let blocks = data.meta.children_map[&page.children]
+    .iter()
+    .map(|id| &data.blocks[id])
+    .collect::<Vec<_>>();
+  assert_eq!(blocks.len(), 3);
+  assert!(
+    blocks
+      .iter()
+      .all(|block| block.ty == BlockType::Paragraph.as_str())
+  );
+  let text_map = data.meta.text_map.as_ref().unwrap();
+  let text = &text_map[blocks[1].external_id.as_ref().unwrap()];
+  assert!(text.contains("first line\\nsecond line"));
+
+  // the import without a report doesn't fail either
+  let data = importer
+    .import("test_document", markdown.to_string())
+    .unwrap();
+  assert_eq!(data.blocks.len(), 4);
+}
+
+/// The types of the top level blocks of the imported markdown.
+fn top_level_block_types(importer: &MDImporter, markdown: &str) -> Vec<String> {
+  let data = importer
+    .import("test_document", markdown.to_string())
+    .unwrap();
+  let page = &data.blocks[&data.page_id];
+  data.meta.children_map[&page.children]
+    .iter()
+    .map(|id| data.blocks[id].ty.clone())
+    .collect()
+}
+
+#[test]
+fn columns_detection_test() {
+  let two_rows = "|  |  |\n| --- | --- |\n| a | b |\n| c | d |\n";
+  let columns = BlockType::SimpleColumns.to_string();
+  let table = BlockType::SimpleTable.to_string();
+
+  let importer = MDImporter::new(None);
+  assert_eq!(
+    top_level_block_types(&importer, two_rows),
+    vec![columns.clone()]
+  );
+
+  let importer = MDImporter::new(None).with_columns_detection(ColumnsDetection::Off);
+  assert_eq!(
+    top_level_block_types(&importer, two_rows),
+    vec![table.clone()]
+  );
+
+  let importer = MDImporter::new(None).with_columns_detection(ColumnsDetection::Conservative);
+  assert_eq!(
+    top_level_block_types(&importer, two_rows),
+    vec![table.clone()]
+  );
+  let one_row = "|  |  |\n| --- | --- |\n| a | b |\n";
+  assert_eq!(top_level_block_types(&importer, one_row), vec![columns]);
+
+  let importer =
+    MDImporter::new(None).with_columns_detection(ColumnsDetection::Custom(ColumnsThresholds {
+      max_columns: 5,
+      max_rows: 1,
+      min_empty_columns: None,
+    }));
+  assert_eq!(top_level_block_types(&importer, two_rows), vec![table]);
+}
+
+#[test]
+fn converted_tables_report_test() {
+  let markdown = r#"# Title
+
+|  |  |
+| --- | --- |
+| Left | Right |
+
+| Name | Age |
+| --- | --- |
+| Lucas | 30 |
+"#;
+  let importer = MDImporter::new(None);
+  let (_, report) = importer
+    .import_with_report("test_document", markdown.to_string())
+    .unwrap();
+  assert_eq!(
+    report.converted_tables,
+    vec![ConvertedTable {
+      line: Some(3),
+      num_of_columns: 2,
+      num_of_rows: 1,
+    }]
+  );
+
+  let importer = MDImporter::new(None).with_columns_detection(ColumnsDetection::Off);
+  let (_, report) = importer
+    .import_with_report("test_document", markdown.to_string())
+    .unwrap();
+  assert!(report.converted_tables.is_empty());
+}
+
+#[test]
+fn inline_html_colors_are_lossless_test() {
+  let markdown = r#"Some <span style="color:red">red</span> and <mark>highlighted</mark> text."#;
+  let importer = MDImporter::new(None);
+  let (_, report) = importer
+    .import_with_report("test_document", markdown.to_string())
+    .unwrap();
+  assert!(report.is_lossless(), "{:?}", report.losses);
+}
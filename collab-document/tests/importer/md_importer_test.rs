@@ -4,8 +4,15 @@ use crate::importer::util::{
 };
 use assert_json_diff::assert_json_eq;
 use collab::core::collab::default_client_id;
+use collab_document::blocks::{SimpleColumnData, SimpleTableData};
 use collab_document::document::{Document, gen_document_id};
+use collab_document::importer::emoji::DefaultEmojiShortcodes;
+use collab_document::importer::heading::{HeadingOverflow, HeadingPolicy};
+use collab_document::importer::md_importer::MDImporter;
+use collab_document::importer::typography::{QuoteLocale, TypographyOptions};
+use collab_document::importer::whitespace::WhitespaceOptions;
 use serde_json::json;
+use std::collections::HashMap;
 
 #[test]
 fn test_override_document() {
@@ -90,6 +97,62 @@ fn test_notion_columns_from_large_empty_header_table() {
   }
 }
 
+#[test]
+fn test_notion_columns_with_width_ratio_hints() {
+  // the hints make a table with many rows a columns table
+  let markdown = r#"| <!-- column-width-ratio: 0.25 --> | <!-- column-width-ratio: 0.75 --> |
+| --- | --- |
+| a | b |
+| c | d |
+| e | f |
+| g | h |
+"#;
+
+  let result = markdown_to_document_data(markdown);
+  let page = get_page_block(&result);
+  let columns = get_children_blocks(&result, &page.id).remove(0);
+  assert_eq!(columns.ty, "simple_columns");
+  let cols = get_children_blocks(&result, &columns.id);
+  assert_eq!(cols.len(), 2);
+  assert_eq!(SimpleColumnData::from_block_data(&cols[0].data).width_ratio, Some(0.25));
+  assert_eq!(SimpleColumnData::from_block_data(&cols[1].data).width_ratio, Some(0.75));
+  assert_eq!(get_children_blocks(&result, &cols[0].id).len(), 4);
+}
+
+#[test]
+fn test_notion_html_column_list_width_ratios() {
+  let html = r#"<div class="column-list">
+<div style="width:calc((100% - (min(32px, 4vw) * 2)) * 0.5)" class="column"><p>Left</p><p>More</p></div>
+<div style="width:calc((100% - (min(32px, 4vw) * 2)) * 0.25)" class="column"><p>Middle</p></div>
+<div style="width:calc((100% - (min(32px, 4vw) * 2)) * 0.25)" class="column"><p>Right</p></div>
+</div>"#;
+
+  let result = MDImporter::new(None).import_html("test_document", html).unwrap();
+  let page = get_page_block(&result);
+  let columns = get_children_blocks(&result, &page.id).remove(0);
+  assert_eq!(columns.ty, "simple_columns");
+  let cols = get_children_blocks(&result, &columns.id);
+  assert_eq!(cols.len(), 3);
+  let left_children = get_children_blocks(&result, &cols[0].id);
+  assert_eq!(left_children.len(), 2);
+  assert_eq!(get_delta_json(&result, &left_children[1].id), json!([{ "insert": "More" }]));
+  assert_eq!(get_children_blocks(&result, &cols[2].id).len(), 1);
+
+  let col_ids = cols.iter().map(|col| col.id.clone()).collect::<Vec<_>>();
+  let mut document = Document::create("test_document", result, default_client_id()).unwrap();
+  let ratios = col_ids
+    .iter()
+    .map(|id| document.get_simple_column_data(id).unwrap().width_ratio)
+    .collect::<Vec<_>>();
+  assert_eq!(ratios, vec![Some(0.5), Some(0.25), Some(0.25)]);
+
+  document
+    .set_simple_column_data(&col_ids[0], SimpleColumnData { width_ratio: None })
+    .unwrap();
+  assert_eq!(document.get_simple_column_data(&col_ids[0]).unwrap().width_ratio, None);
+  assert!(document.get_simple_column_data(&columns.id).is_err());
+}
+
 #[test]
 fn test_inline_elements() {
   let markdown = "This is **bold**, *italic*, ~~delete~~, and [a link](https://example.com).";
@@ -227,6 +290,41 @@ fn test_headings() {
   }
 }
 
+#[test]
+fn test_heading_overflow_policy() {
+  let markdown = "## Heading 2\n\n#### Heading 4";
+  let import = |overflow| {
+    let result = MDImporter::new(None)
+      .with_heading_policy(HeadingPolicy::new(3, overflow))
+      .import("test_document", markdown.to_string())
+      .unwrap();
+    let page = get_page_block(&result);
+    let children = get_children_blocks(&result, &page.id);
+    assert_eq!(children[0].ty, "heading");
+    assert_eq!(children[0].data["level"], 2);
+    let delta = get_delta_json(&result, &children[1].id);
+    (children[1].clone(), delta)
+  };
+
+  let (block, delta) = import(HeadingOverflow::Clamp);
+  assert_eq!(block.ty, "heading");
+  assert_eq!(block.data["level"], 3);
+  assert_eq!(delta, json!([{"insert": "Heading 4"}]));
+
+  let (block, delta) = import(HeadingOverflow::KeepLevel);
+  assert_eq!(block.ty, "heading");
+  assert_eq!(block.data["level"], 4);
+  assert_eq!(delta, json!([{"insert": "Heading 4"}]));
+
+  let (block, delta) = import(HeadingOverflow::DemoteToBoldParagraph);
+  assert_eq!(block.ty, "paragraph");
+  assert!(!block.data.contains_key("level"));
+  assert_eq!(
+    delta,
+    json!([{"insert": "Heading 4", "attributes": {"bold": true}}])
+  );
+}
+
 #[test]
 fn test_numbered_list() {
   let markdown = "1. First item\n2. Second item\n3. Third item";
@@ -407,6 +505,34 @@ fn main() {
   );
 }
 
+#[test]
+fn test_diagram_code_block() {
+  let markdown = r#"
+```mermaid
+graph TD
+  A --> B
+```
+
+```puml
+Alice -> Bob: hello
+```
+"#;
+
+  let result = markdown_to_document_data(markdown);
+  assert!(result.blocks.values().all(|block| block.ty != "code"));
+  let diagrams = get_children_blocks(&result, &result.page_id);
+  assert_eq!(diagrams.len(), 2);
+  assert_eq!(
+    json!(diagrams[0].data),
+    json!({ "kind": "mermaid", "source": "graph TD\n  A --> B" })
+  );
+  assert_eq!(
+    json!(diagrams[1].data),
+    json!({ "kind": "plantuml", "source": "Alice -> Bob: hello" })
+  );
+  assert!(diagrams.iter().all(|block| block.ty == "diagram"));
+}
+
 #[test]
 fn test_divider() {
   let markdown = "---";
@@ -525,6 +651,9 @@ fn test_table() {
   let table = get_block_by_type(&result, "simple_table");
 
   assert_eq!(table.ty, "simple_table");
+  let table_data = SimpleTableData::from_block_data(&table.data);
+  assert!(table_data.enable_header_row);
+  assert!(!table_data.enable_header_column);
 
   let table_cells = result
     .blocks
@@ -619,3 +748,514 @@ This is inside.
   assert_eq!(children[1].ty, "bulleted_list");
   assert_eq!(get_delta_json(&result, &children[1].id), json!([{ "insert": "Item 1" }]));
 }
+
+#[test]
+fn test_numbered_list_continued_with_other_marker() {
+  // Changing the marker starts a new list, the numbering of the second list continues the first.
+  let markdown = "1. First item\n2. Second item\n\n3) Third item\n\n4) Fourth item";
+
+  let result = markdown_to_document_data(markdown);
+  let page = get_page_block(&result);
+  let list = get_children_blocks(&result, &page.id);
+
+  assert_eq!(list.len(), 4);
+  for item in list.iter() {
+    assert_eq!(item.ty, "numbered_list");
+    assert_eq!(item.data.get("number"), Some(&json!(1)));
+  }
+}
+
+#[test]
+fn test_numbered_list_restart_is_kept() {
+  let markdown = "1. First item\n2. Second item\n\n1) New first item";
+
+  let result = markdown_to_document_data(markdown);
+  let page = get_page_block(&result);
+  let list = get_children_blocks(&result, &page.id);
+
+  assert_eq!(list.len(), 3);
+  assert_eq!(list[1].data.get("number"), Some(&json!(1)));
+  assert_eq!(list[2].data.get("number"), Some(&json!(1)));
+
+  // The numbering of a list that doesn't continue the previous one is kept.
+  let markdown = "1. First item\n\n5) Fifth item";
+  let result = markdown_to_document_data(markdown);
+  let page = get_page_block(&result);
+  let list = get_children_blocks(&result, &page.id);
+  assert_eq!(list[1].data.get("number"), Some(&json!(5)));
+}
+
+#[test]
+fn test_bulleted_list_with_other_marker_is_merged() {
+  let markdown = "- First item\n\n* Second item\n\n- [ ] Todo item";
+
+  let result = markdown_to_document_data(markdown);
+  let page = get_page_block(&result);
+  let list = get_children_blocks(&result, &page.id);
+
+  let types = list.iter().map(|item| item.ty.as_str()).collect::<Vec<_>>();
+  assert_eq!(types, vec!["bulleted_list", "bulleted_list", "todo_list"]);
+}
+
+#[test]
+fn test_emoji_shortcodes() {
+  let markdown = "Launch :rocket: at 10:30:tada: **:fire:** `:rocket:` :unknown:";
+
+  // Shortcodes are kept as text by default.
+  let result = markdown_to_document_data(markdown);
+  let page = get_page_block(&result);
+  let paragraph = get_children_blocks(&result, &page.id).pop().unwrap();
+  let delta = get_delta_json(&result, &paragraph.id);
+  assert_eq!(delta[0]["insert"], "Launch :rocket: at 10:30:tada: ");
+
+  let importer = MDImporter::new(None).with_emoji_shortcodes(DefaultEmojiShortcodes);
+  let result = importer
+    .import("test_document", markdown.to_string())
+    .unwrap();
+  let page = get_page_block(&result);
+  let paragraph = get_children_blocks(&result, &page.id).pop().unwrap();
+  let delta = get_delta_json(&result, &paragraph.id);
+  assert_eq!(
+    delta,
+    json!([
+      {"insert": "Launch 🚀 at 10:30🎉 "},
+      {"insert": "🔥", "attributes": {"bold": true}},
+      {"insert": " "},
+      {"insert": ":rocket:", "attributes": {"code": true}},
+      {"insert": " :unknown:"},
+    ])
+  );
+}
+
+#[test]
+fn test_custom_emoji_shortcodes() {
+  let table = HashMap::from([("appflowy".to_string(), "🦋".to_string())]);
+  let importer = MDImporter::new(None).with_emoji_shortcodes(table);
+  let result = importer
+    .import("test_document", "- :appflowy: :rocket:".to_string())
+    .unwrap();
+  let page = get_page_block(&result);
+  let item = get_children_blocks(&result, &page.id).pop().unwrap();
+  assert_eq!(
+    get_delta_json(&result, &item.id),
+    json!([{"insert": "🦋 :rocket:"}])
+  );
+}
+
+#[test]
+fn test_bare_url_as_link_preview() {
+  let markdown = r#"https://appflowy.io
+
+[https://github.com/AppFlowy-IO](https://github.com/AppFlowy-IO)
+
+[AppFlowy](https://appflowy.io)
+
+Visit https://appflowy.io today"#;
+
+  // Bare urls are imported as links by default.
+  let result = markdown_to_document_data(markdown);
+  let page = get_page_block(&result);
+  let types = get_children_blocks(&result, &page.id)
+    .into_iter()
+    .map(|block| block.ty)
+    .collect::<Vec<_>>();
+  assert_eq!(types, vec!["paragraph"; 4]);
+
+  let importer = MDImporter::new(None).with_bare_url_as_link_preview(true);
+  let result = importer
+    .import("test_document", markdown.to_string())
+    .unwrap();
+  let page = get_page_block(&result);
+  let children = get_children_blocks(&result, &page.id);
+  let types = children
+    .iter()
+    .map(|block| block.ty.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(
+    types,
+    vec!["link_preview", "link_preview", "paragraph", "paragraph"]
+  );
+  assert_eq!(children[0].data["url"], json!("https://appflowy.io"));
+  assert_eq!(
+    children[1].data["url"],
+    json!("https://github.com/AppFlowy-IO")
+  );
+  assert!(children[0].external_id.is_none());
+}
+
+#[test]
+fn test_date_mentions() {
+  let markdown = "Due @March 4, 2024 10:30 AM, see `@2024-03-04`\n\nSprint: 2024-03-04 → 2024-03-06";
+
+  // Dates are kept as text by default.
+  let result = markdown_to_document_data(markdown);
+  let page = get_page_block(&result);
+  let paragraph = get_children_blocks(&result, &page.id).remove(0);
+  assert_eq!(
+    get_delta_json(&result, &paragraph.id),
+    json!([
+      {"insert": "Due @March 4, 2024 10:30 AM, see "},
+      {"insert": "@2024-03-04", "attributes": {"code": true}},
+    ])
+  );
+
+  let importer = MDImporter::new(None).with_date_mentions(true);
+  let result = importer
+    .import("test_document", markdown.to_string())
+    .unwrap();
+  let page = get_page_block(&result);
+  let children = get_children_blocks(&result, &page.id);
+  assert_eq!(
+    get_delta_json(&result, &children[0].id),
+    json!([
+      {"insert": "Due "},
+      {"insert": "$", "attributes": {"mention": {
+        "type": "date",
+        "date": "2024-03-04T10:30:00.000",
+        "include_time": true,
+      }}},
+      {"insert": ", see "},
+      {"insert": "@2024-03-04", "attributes": {"code": true}},
+    ])
+  );
+  assert_eq!(
+    get_delta_json(&result, &children[1].id),
+    json!([
+      {"insert": "Sprint: "},
+      {"insert": "$", "attributes": {"mention": {
+        "type": "date",
+        "date": "2024-03-04T00:00:00.000",
+        "include_time": false,
+      }}},
+      {"insert": " → "},
+      {"insert": "$", "attributes": {"mention": {
+        "type": "date",
+        "date": "2024-03-06T00:00:00.000",
+        "include_time": false,
+      }}},
+    ])
+  );
+}
+
+#[test]
+fn test_invalid_date_mentions_are_kept_as_text() {
+  let importer = MDImporter::new(None).with_date_mentions(true);
+  for text in [
+    "2024-03-04",
+    "March 4, 2024",
+    "@February 30, 2024",
+    "@2024-13-01",
+    "@2024-03-04th",
+    "mail@2024-03-04",
+  ] {
+    let result = importer.import("test_document", text.to_string()).unwrap();
+    let page = get_page_block(&result);
+    let paragraph = get_children_blocks(&result, &page.id).remove(0);
+    assert_eq!(
+      get_delta_json(&result, &paragraph.id),
+      json!([{"insert": text}]),
+      "{}",
+      text
+    );
+  }
+}
+
+#[test]
+fn test_whitespace_normalization() {
+  // a decomposed é, non-breaking spaces, a tab and trailing whitespace
+  let markdown = "Cafe\u{0301}\u{00a0}menu\ta\u{00a0}\nnext line\u{00a0}\n\n```\nlet\ta = 1;\u{00a0}\n```";
+  let result = MDImporter::new(None)
+    .import("test_document", markdown.to_string())
+    .unwrap();
+  let page = get_page_block(&result);
+  let blocks = get_children_blocks(&result, &page.id);
+  assert_eq!(
+    get_delta_json(&result, &blocks[0].id),
+    json!([{"insert": "Caf\u{00e9} menu   a\nnext line"}])
+  );
+  // code is left untouched
+  assert_eq!(
+    get_delta_json(&result, &blocks[1].id),
+    json!([{"insert": "let\ta = 1;\u{00a0}"}])
+  );
+
+  let result = MDImporter::new(None)
+    .with_whitespace(WhitespaceOptions::preserve())
+    .import("test_document", markdown.to_string())
+    .unwrap();
+  let page = get_page_block(&result);
+  let blocks = get_children_blocks(&result, &page.id);
+  assert_eq!(
+    get_delta_json(&result, &blocks[0].id),
+    json!([{"insert": "Cafe\u{0301}\u{00a0}menu\ta\u{00a0}\nnext line\u{00a0}"}])
+  );
+}
+
+#[test]
+fn test_whitespace_before_formatted_text_is_kept() {
+  let result = MDImporter::new(None)
+    .import("test_document", "hello\u{00a0}**world**".to_string())
+    .unwrap();
+  let page = get_page_block(&result);
+  let paragraph = get_children_blocks(&result, &page.id).pop().unwrap();
+  assert_eq!(
+    get_delta_json(&result, &paragraph.id),
+    json!([
+      {"insert": "hello "},
+      {"insert": "world", "attributes": {"bold": true}},
+    ])
+  );
+}
+
+#[test]
+fn test_media_links_as_media_blocks() {
+  let markdown = r#"[demo.mp4](assets/demo.mp4)
+
+[https://www.youtube.com/watch?v=dQw4w9WgXcQ](https://www.youtube.com/watch?v=dQw4w9WgXcQ)
+
+[Launch](https://vimeo.com/76979871)
+
+[podcast.mp3](https://example.com/podcast.mp3?download=1)
+
+[AppFlowy](https://appflowy.io)"#;
+
+  // Media links are imported as links by default.
+  let result = markdown_to_document_data(markdown);
+  let page = get_page_block(&result);
+  let types = get_children_blocks(&result, &page.id)
+    .into_iter()
+    .map(|block| block.ty)
+    .collect::<Vec<_>>();
+  assert_eq!(types, vec!["paragraph"; 5]);
+
+  let importer = MDImporter::new(None).with_media_embeds(true);
+  let result = importer
+    .import("test_document", markdown.to_string())
+    .unwrap();
+  let page = get_page_block(&result);
+  let children = get_children_blocks(&result, &page.id);
+  let types = children
+    .iter()
+    .map(|block| block.ty.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(types, vec!["video", "video", "video", "audio", "paragraph"]);
+  assert_json_eq!(
+    json!(children[0].data),
+    json!({"url": "assets/demo.mp4", "provider": "file", "name": "demo.mp4"})
+  );
+  assert!(children[0].external_id.is_none());
+  assert_json_eq!(
+    json!(children[1].data),
+    json!({
+      "url": "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+      "provider": "youtube",
+      "provider_id": "dQw4w9WgXcQ",
+    })
+  );
+  assert_json_eq!(
+    json!(children[2].data),
+    json!({
+      "url": "https://vimeo.com/76979871",
+      "provider": "vimeo",
+      "provider_id": "76979871",
+      "name": "Launch",
+    })
+  );
+  assert_eq!(children[3].data["provider"], json!("file"));
+}
+
+#[test]
+fn test_html_media_elements() {
+  let html = r#"<video controls><source src="assets/demo.webm" type="video/webm"></video>
+<iframe src="https://www.youtube.com/embed/dQw4w9WgXcQ"></iframe>
+<audio src="assets/voice.m4a"></audio>"#;
+  let result = MDImporter::new(None)
+    .with_media_embeds(true)
+    .import_html("test_document", html)
+    .unwrap();
+  let page = get_page_block(&result);
+  let children = get_children_blocks(&result, &page.id);
+  let media = children
+    .iter()
+    .map(|block| (block.ty.as_str(), block.data["url"].clone()))
+    .collect::<Vec<_>>();
+  assert_eq!(
+    media,
+    vec![
+      ("video", json!("assets/demo.webm")),
+      ("video", json!("https://www.youtube.com/embed/dQw4w9WgXcQ")),
+      ("audio", json!("assets/voice.m4a")),
+    ]
+  );
+}
+
+#[test]
+fn test_local_file_links_as_file_blocks() {
+  let markdown = r#"[Quarterly report.pdf](Project%20Plan/Quarterly%20report.pdf)
+
+[archive.zip](assets/archive.zip)
+
+[diagram.png](assets/diagram.png)
+
+[Tasks](Tasks%20abc123.md)
+
+[spec.pdf](https://example.com/spec.pdf)
+
+See [notes.docx](assets/notes.docx) for details"#;
+
+  let importer = MDImporter::new(None).with_file_blocks(true);
+  let result = importer
+    .import("test_document", markdown.to_string())
+    .unwrap();
+  let page = get_page_block(&result);
+  let children = get_children_blocks(&result, &page.id);
+  let types = children
+    .iter()
+    .map(|block| block.ty.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(
+    types,
+    vec!["file", "file", "paragraph", "paragraph", "paragraph", "paragraph"]
+  );
+  assert_json_eq!(
+    json!(children[0].data),
+    json!({
+      "url": "Project%20Plan/Quarterly%20report.pdf",
+      "name": "Quarterly report.pdf",
+    })
+  );
+  assert!(children[0].external_id.is_none());
+  assert_eq!(children[1].data["name"], json!("archive.zip"));
+}
+
+#[test]
+fn test_inline_html_colors() {
+  let markdown = r#"Plain <span style="color: #e03e3e">red **bold**</span> and <mark>highlighted</mark> text
+
+<span class="highlight-blue_background">Notion</span> <span style="color:rgb(51, 126, 169); background-color: yellow">both</span>"#;
+  let result = markdown_to_document_data(markdown);
+  let page = get_page_block(&result);
+  let children = get_children_blocks(&result, &page.id);
+  assert_eq!(children.len(), 2);
+
+  assert_json_eq!(
+    get_delta_json(&result, &children[0].id),
+    json!([
+      {"insert": "Plain "},
+      {"insert": "red ", "attributes": {"font_color": "0xffe03e3e"}},
+      {"insert": "bold", "attributes": {"font_color": "0xffe03e3e", "bold": true}},
+      {"insert": " and "},
+      {"insert": "highlighted", "attributes": {"bg_color": "0xfffbf3db"}},
+      {"insert": " text"},
+    ])
+  );
+  assert_json_eq!(
+    get_delta_json(&result, &children[1].id),
+    json!([
+      {"insert": "Notion", "attributes": {"bg_color": "0xffe7f3f8"}},
+      {"insert": " "},
+      {"insert": "both", "attributes": {"font_color": "0xff337ea9", "bg_color": "0xffffff00"}},
+    ])
+  );
+}
+
+#[test]
+fn test_underline_and_scripts() {
+  let markdown = "<u>Note</u>: H<sub>2</sub>O and 2<sup>10</sup>";
+  let result = markdown_to_document_data(markdown);
+  let page = get_page_block(&result);
+  let paragraph = get_children_blocks(&result, &page.id).remove(0);
+  assert_json_eq!(
+    get_delta_json(&result, &paragraph.id),
+    json!([
+      {"insert": "Note", "attributes": {"underline": true}},
+      {"insert": ": H"},
+      {"insert": "2", "attributes": {"subscript": true}},
+      {"insert": "O and 2"},
+      {"insert": "10", "attributes": {"superscript": true}},
+    ])
+  );
+
+  // The pandoc scripts are only imported when enabled, a single tilde is a strikethrough by
+  // default.
+  let markdown = "H~2~O and 2^10^, ~~removed~~";
+  let result = markdown_to_document_data(markdown);
+  let page = get_page_block(&result);
+  let paragraph = get_children_blocks(&result, &page.id).remove(0);
+  assert_json_eq!(
+    get_delta_json(&result, &paragraph.id),
+    json!([
+      {"insert": "H"},
+      {"insert": "2", "attributes": {"strikethrough": true}},
+      {"insert": "O and 2^10^, "},
+      {"insert": "removed", "attributes": {"strikethrough": true}},
+    ])
+  );
+
+  let result = MDImporter::new(None)
+    .with_pandoc_scripts(true)
+    .import("test_document", markdown.to_string())
+    .unwrap();
+  let page = get_page_block(&result);
+  let paragraph = get_children_blocks(&result, &page.id).remove(0);
+  assert_json_eq!(
+    get_delta_json(&result, &paragraph.id),
+    json!([
+      {"insert": "H"},
+      {"insert": "2", "attributes": {"subscript": true}},
+      {"insert": "O and 2"},
+      {"insert": "10", "attributes": {"superscript": true}},
+      {"insert": ", "},
+      {"insert": "removed", "attributes": {"strikethrough": true}},
+    ])
+  );
+}
+
+#[test]
+fn test_smart_typography() {
+  let markdown = r#"She said "it's **done**" -- twice... `"code" -- kept`
+
+Pages 1---3, 'quoted'"#;
+
+  // The text is kept as is by default.
+  let result = markdown_to_document_data(markdown);
+  let page = get_page_block(&result);
+  let paragraph = get_children_blocks(&result, &page.id).remove(0);
+  assert_eq!(
+    get_delta_json(&result, &paragraph.id)[0],
+    json!({"insert": "She said \"it's "})
+  );
+
+  let result = MDImporter::new(None)
+    .with_typography(TypographyOptions::default())
+    .import("test_document", markdown.to_string())
+    .unwrap();
+  let page = get_page_block(&result);
+  let children = get_children_blocks(&result, &page.id);
+  assert_json_eq!(
+    get_delta_json(&result, &children[0].id),
+    json!([
+      {"insert": "She said “it’s "},
+      {"insert": "done", "attributes": {"bold": true}},
+      {"insert": "” – twice… "},
+      {"insert": "\"code\" -- kept", "attributes": {"code": true}},
+    ])
+  );
+  assert_json_eq!(
+    get_delta_json(&result, &children[1].id),
+    json!([{"insert": "Pages 1—3, ‘quoted’"}])
+  );
+}
+
+#[test]
+fn test_typography_locales() {
+  let text = r#""Hallo", sagte 'er'"#;
+  let german = TypographyOptions::with_locale(QuoteLocale::from_language_tag("de-DE"));
+  assert_eq!(german.apply(text), "„Hallo“, sagte ‚er‘");
+
+  let french = TypographyOptions::with_locale(QuoteLocale::from_language_tag("fr"));
+  assert_eq!(french.apply(text), "«Hallo», sagte ‹er›");
+
+  let english = TypographyOptions::with_locale(QuoteLocale::from_language_tag("xx"));
+  assert_eq!(english.apply("don't 'stop'"), "don’t ‘stop’");
+}
@@ -0,0 +1,165 @@
+use collab::core::collab::default_client_id;
+use collab_document::document::Document;
+use collab_document::importer::md_importer::MDImporter;
+use collab_document::invariants::assert_document_valid;
+use serde_json::json;
+
+fn create_document(md: &str) -> Document {
+  let data = MDImporter::new(None)
+    .import("md_sync_test", md.to_string())
+    .unwrap();
+  Document::create("md_sync_test", data, default_client_id()).unwrap()
+}
+
+fn page_children(document: &Document) -> Vec<String> {
+  let page_id = document.get_page_id().unwrap();
+  document.get_block_children_ids(&page_id)
+}
+
+fn plain_texts(document: &Document, block_ids: &[String]) -> Vec<String> {
+  block_ids
+    .iter()
+    .map(|id| document.get_plain_text_from_block(id).unwrap_or_default())
+    .collect()
+}
+
+#[test]
+fn sync_unchanged_markdown_test() {
+  let md = "# Title\n\n- a\n  - b\n\nend";
+  let mut document = create_document(md);
+  let ids = page_children(&document);
+  let report = MDImporter::new(None)
+    .sync_into(&mut document, md.to_string())
+    .unwrap();
+  assert!(report.is_unchanged());
+  assert_eq!(page_children(&document), ids);
+}
+
+#[test]
+fn sync_changed_markdown_keeps_blocks_test() {
+  let mut document = create_document("# Title\n\nfirst\n\nsecond\n\nthird");
+  let ids = page_children(&document);
+
+  let report = MDImporter::new(None)
+    .sync_into(
+      &mut document,
+      "# Title\n\nfirst\n\nsecond **edited**\n\nnew\n\nthird".to_string(),
+    )
+    .unwrap();
+  assert!(report.deleted.is_empty());
+  assert_eq!(report.updated, vec![ids[2].clone()]);
+  assert_eq!(report.inserted.len(), 1);
+
+  // The unchanged and the updated blocks keep their ids.
+  let new_ids = page_children(&document);
+  assert_eq!(
+    new_ids,
+    vec![
+      ids[0].clone(),
+      ids[1].clone(),
+      ids[2].clone(),
+      report.inserted[0].clone(),
+      ids[3].clone(),
+    ]
+  );
+  assert_eq!(
+    document.get_block_delta_json(&ids[2]).unwrap(),
+    json!([
+      { "insert": "second " },
+      { "insert": "edited", "attributes": { "bold": true } }
+    ])
+  );
+  assert_eq!(
+    plain_texts(&document, &new_ids),
+    vec!["Title", "first", "second edited", "new", "third"]
+  );
+}
+
+#[test]
+fn sync_deleted_and_nested_blocks_test() {
+  let mut document = create_document("- a\n  - b\n  - c\n- d\n\nremoved");
+  let ids = page_children(&document);
+  let nested_ids = document.get_block_children_ids(&ids[0]);
+
+  let report = MDImporter::new(None)
+    .sync_into(&mut document, "- a\n  - c\n- d".to_string())
+    .unwrap();
+  assert_eq!(report.deleted, vec![ids[2].clone(), nested_ids[0].clone()]);
+  assert!(report.inserted.is_empty());
+
+  assert_eq!(
+    page_children(&document),
+    vec![ids[0].clone(), ids[1].clone()]
+  );
+  assert_eq!(
+    document.get_block_children_ids(&ids[0]),
+    vec![nested_ids[1].clone()]
+  );
+  assert!(document.get_block(&ids[2]).is_none());
+  assert_document_valid(&document.get_document_data().unwrap());
+}
+
+#[test]
+fn sync_keeps_data_missing_from_markdown_test() {
+  let mut document = create_document("# Title\n\ntext");
+  let ids = page_children(&document);
+  let mut data = document.get_block(&ids[0]).unwrap().data;
+  data.insert("bgColor".to_string(), json!("red"));
+  document.update_block(&ids[0], data).unwrap();
+
+  let report = MDImporter::new(None)
+    .sync_into(&mut document, "## Title\n\ntext".to_string())
+    .unwrap();
+  assert_eq!(report.updated, vec![ids[0].clone()]);
+  let data = document.get_block(&ids[0]).unwrap().data;
+  assert_eq!(data["level"], json!(2));
+  assert_eq!(data["bgColor"], json!("red"));
+}
+
+#[test]
+fn sync_changed_block_type_test() {
+  let mut document = create_document("first\n\nsecond");
+  let ids = page_children(&document);
+  let report = MDImporter::new(None)
+    .sync_into(&mut document, "first\n\n> second".to_string())
+    .unwrap();
+  assert_eq!(report.deleted, vec![ids[1].clone()]);
+  assert_eq!(report.inserted.len(), 1);
+
+  let new_ids = page_children(&document);
+  assert_eq!(new_ids[0], ids[0]);
+  let quote = document.get_block(&new_ids[1]).unwrap();
+  assert_eq!(quote.ty, "quote");
+  assert_eq!(plain_texts(&document, &new_ids), vec!["first", "second"]);
+}
+
+#[test]
+fn sync_skips_missing_child_test() {
+  let mut data = MDImporter::new(None)
+    .import("md_sync_test", "first\n\nsecond".to_string())
+    .unwrap();
+  let page_children_id = data.blocks[&data.page_id].children.clone();
+  data
+    .meta
+    .children_map
+    .get_mut(&page_children_id)
+    .unwrap()
+    .insert(1, "ghost".to_string());
+  let mut document = Document::create("md_sync_test", data, default_client_id()).unwrap();
+  let ids = page_children(&document);
+  assert!(ids.contains(&"ghost".to_string()));
+
+  let report = MDImporter::new(None)
+    .sync_into(&mut document, "first\n\nsecond\n\nthird".to_string())
+    .unwrap();
+  assert!(report.deleted.is_empty());
+  assert_eq!(report.inserted.len(), 1);
+  let new_ids = page_children(&document)
+    .into_iter()
+    .filter(|id| id != "ghost")
+    .collect::<Vec<_>>();
+  assert_eq!(
+    plain_texts(&document, &new_ids),
+    vec!["first", "second", "third"]
+  );
+}
@@ -1,3 +1,9 @@
+mod content_filter_test;
+mod embed_test;
+mod fragment_import_test;
+mod input_guard_test;
+mod md_import_report_test;
 mod md_importer_customer_test;
 mod md_importer_test;
+mod md_sync_test;
 pub mod util;
@@ -17,7 +17,7 @@ use collab_plugins::CollabKVDB;
 use collab_plugins::local_storage::rocksdb::rocksdb_plugin::RocksdbDiskPlugin;
 use collab_plugins::local_storage::rocksdb::util::KVDBCollabPersistenceImpl;
 use nanoid::nanoid;
-use serde_json::json;
+use serde_json::{Value, json};
 use tempfile::TempDir;
 use tracing_subscriber::{EnvFilter, fmt::Subscriber, util::SubscriberInitExt};
 use uuid::Uuid;
@@ -99,6 +99,7 @@ impl DocumentTest {
     let meta = DocumentMeta {
       children_map,
       text_map: Some(text_map),
+      lazy_text: false,
     };
     let document_data = DocumentData {
       page_id,
@@ -274,3 +275,19 @@ pub fn try_decode_from_encode_collab(document: &Document) {
     CollabOptions::new("1".to_string(), default_client_id()).with_data_source(data.into());
   let _ = Collab::new_with_options(CollabOrigin::Empty, options).unwrap();
 }
+
+/// The ids of the children of the block.
+pub fn children_ids(data: &DocumentData, block_id: &str) -> Vec<String> {
+  data.meta.children_map[&data.blocks[block_id].children].clone()
+}
+
+/// The ids of the children of the page.
+pub fn top_level_ids(data: &DocumentData) -> Vec<String> {
+  children_ids(data, &data.page_id)
+}
+
+/// The delta of the text of the block.
+pub fn block_delta(data: &DocumentData, block_id: &str) -> Value {
+  let external_id = data.blocks[block_id].external_id.as_ref().unwrap();
+  serde_json::from_str(&data.meta.text_map.as_ref().unwrap()[external_id]).unwrap()
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// Where an attachment is referenced in its collab.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "ty", rename_all = "snake_case")]
+pub enum AttachmentLocation {
+  /// An image, file, video or audio block of a document.
+  Block { block_id: String },
+  /// A media cell of a database row.
+  Cell { row_id: String, field_id: String },
+}
+
+impl AttachmentLocation {
+  /// A key identifying the location in its collab.
+  pub fn key(&self) -> String {
+    match self {
+      AttachmentLocation::Block { block_id } => format!("block:{}", block_id),
+      AttachmentLocation::Cell { row_id, field_id } => format!("cell:{}:{}", row_id, field_id),
+    }
+  }
+}
+
+/// An attachment referenced by a collab, e.g. the url of an uploaded image.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttachmentReference {
+  /// The id of the collab referencing the attachment, the document id or the database id.
+  pub object_id: String,
+  pub location: AttachmentLocation,
+  pub url: String,
+  /// The size of the attachment in bytes, when the collab knows it.
+  pub size: Option<i64>,
+}
+
+/// Called when the attachments of a collab are written, used to maintain a catalog of the
+/// attachments of a workspace.
+pub trait AttachmentWriteHook: Send + Sync {
+  /// The attachments at the location were written, they replace the ones previously referenced
+  /// there. `references` is empty when the attachments were removed.
+  fn did_write_attachments(
+    &self,
+    object_id: &str,
+    location: &AttachmentLocation,
+    references: Vec<AttachmentReference>,
+  );
+}
@@ -1,8 +1,10 @@
 pub use collab_object::*;
 
 mod collab_object;
+pub mod attachment;
 pub mod define;
 pub mod proto;
+pub mod reference;
 pub mod reminder;
 
 pub use collab::entity::*;
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+/// A bibliographic reference of a workspace, cited in the documents by its key.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reference {
+  /// The cite key, e.g. `smith2020`, unique in the workspace.
+  pub key: String,
+  /// The kind of work, named as the BibTeX entry types, e.g. `article` or `book`.
+  pub kind: String,
+  pub title: String,
+  #[serde(default)]
+  pub authors: Vec<ReferenceAuthor>,
+  #[serde(default)]
+  pub year: Option<String>,
+  /// The journal of an article, the book of a chapter or the proceedings of a paper.
+  #[serde(default)]
+  pub container_title: Option<String>,
+  #[serde(default)]
+  pub publisher: Option<String>,
+  #[serde(default)]
+  pub volume: Option<String>,
+  #[serde(default)]
+  pub issue: Option<String>,
+  #[serde(default)]
+  pub pages: Option<String>,
+  #[serde(default)]
+  pub doi: Option<String>,
+  #[serde(default)]
+  pub url: Option<String>,
+}
+
+/// An author or an editor of a [Reference]. An organization only has a family name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferenceAuthor {
+  pub family: String,
+  #[serde(default)]
+  pub given: Option<String>,
+}
+
+impl ReferenceAuthor {
+  /// The initials of the given names, e.g. `J. R.` for `John Ronald`.
+  pub fn initials(&self) -> Option<String> {
+    let given = self.given.as_deref()?;
+    let initials = given
+      .split([' ', '-'])
+      .filter_map(|name| name.chars().next())
+      .map(|initial| format!("{}.", initial))
+      .collect::<Vec<_>>();
+    (!initials.is_empty()).then(|| initials.join(" "))
+  }
+}
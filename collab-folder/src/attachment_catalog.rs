@@ -0,0 +1,413 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use collab::core::collab::{CollabOptions, DataSource};
+use collab::core::origin::CollabOrigin;
+use collab::preclude::block::ClientID;
+use collab::preclude::encoding::serde::{from_any, to_any};
+use collab::preclude::{Collab, Map, MapExt, MapRef, Out, ReadTxn, TransactionMut};
+use collab_entity::attachment::{AttachmentLocation, AttachmentReference, AttachmentWriteHook};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::error::FolderError;
+use crate::view::timestamp;
+
+const ATTACHMENT_CATALOG: &str = "attachment_catalog";
+const REFERENCES: &str = "references";
+const FILES: &str = "files";
+
+/// The attachments referenced by the documents and databases of a workspace, stored in its own
+/// collab so every device of the workspace shares it.
+///
+/// The references are written by the [AttachmentWriteHook] of the collabs, see
+/// [AttachmentCatalogHook], and by [AttachmentCatalog::sync_object] when a collab is opened.
+/// Each file remembers when its last reference was removed, so that [AttachmentCatalog::scan_orphans]
+/// only returns the files that stayed unreferenced for a grace period, e.g. long enough for an
+/// undo or for an offline device to sync.
+pub struct AttachmentCatalog {
+  pub collab: Collab,
+  body: AttachmentCatalogBody,
+}
+
+struct AttachmentCatalogBody {
+  /// The references by `{object_id}/{location key}`.
+  references: MapRef,
+  /// The [AttachmentFile] by url.
+  files: MapRef,
+}
+
+/// A file of the catalog.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttachmentFile {
+  pub url: String,
+  /// The size of the file in bytes, when it's known.
+  #[serde(default)]
+  pub size: Option<i64>,
+  /// When the last reference to the file was removed, in seconds. None while it's referenced.
+  #[serde(default)]
+  pub unreferenced_since: Option<i64>,
+}
+
+/// A file no longer referenced anywhere, returned by [AttachmentCatalog::scan_orphans].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrphanAttachment {
+  pub url: String,
+  pub size: Option<i64>,
+  /// When the last reference to the file was removed, in seconds.
+  pub unreferenced_since: i64,
+}
+
+impl AttachmentCatalog {
+  pub fn open(mut collab: Collab) -> Self {
+    let (references, files) = {
+      let mut txn = collab.context.transact_mut();
+      let catalog: MapRef = collab.data.get_or_init_map(&mut txn, ATTACHMENT_CATALOG);
+      let references: MapRef = catalog.get_or_init(&mut txn, REFERENCES);
+      let files: MapRef = catalog.get_or_init(&mut txn, FILES);
+      (references, files)
+    };
+    Self {
+      collab,
+      body: AttachmentCatalogBody { references, files },
+    }
+  }
+
+  pub fn from_collab_doc_state(
+    origin: CollabOrigin,
+    collab_doc_state: DataSource,
+    object_id: &str,
+    client_id: ClientID,
+  ) -> Result<Self, FolderError> {
+    let options =
+      CollabOptions::new(object_id.to_string(), client_id).with_data_source(collab_doc_state);
+    let collab = Collab::new_with_options(origin, options)?;
+    Ok(Self::open(collab))
+  }
+
+  pub fn close(&self) {
+    self.collab.remove_all_plugins();
+  }
+
+  /// Replace the attachments referenced at the location of the collab. An empty `references`
+  /// removes the location. `now` is in seconds.
+  pub fn write_attachments(
+    &mut self,
+    object_id: &str,
+    location: &AttachmentLocation,
+    references: Vec<AttachmentReference>,
+    now: i64,
+  ) {
+    let mut txn = self.collab.transact_mut();
+    let key = reference_key(object_id, location);
+    let previous = self.body.get_references_with_txn(&txn, &key);
+    self
+      .body
+      .write_references_with_txn(&mut txn, &key, &references);
+    self
+      .body
+      .update_files_with_txn(&mut txn, previous, &references, now);
+  }
+
+  /// Replace all the attachments of the collab, e.g. with the references of a document or of a
+  /// database when it's opened, in case some writes were missed. `now` is in seconds.
+  pub fn sync_object(&mut self, object_id: &str, references: Vec<AttachmentReference>, now: i64) {
+    let mut by_key = HashMap::<String, Vec<AttachmentReference>>::new();
+    for reference in references {
+      let key = reference_key(object_id, &reference.location);
+      by_key.entry(key).or_default().push(reference);
+    }
+
+    let mut txn = self.collab.transact_mut();
+    let mut previous = vec![];
+    for key in self.body.object_keys_with_txn(&txn, object_id) {
+      if !by_key.contains_key(&key) {
+        previous.extend(self.body.get_references_with_txn(&txn, &key));
+        self.body.references.remove(&mut txn, &key);
+      }
+    }
+    let mut current = vec![];
+    for (key, references) in by_key {
+      previous.extend(self.body.get_references_with_txn(&txn, &key));
+      self
+        .body
+        .write_references_with_txn(&mut txn, &key, &references);
+      current.extend(references);
+    }
+    self
+      .body
+      .update_files_with_txn(&mut txn, previous, &current, now);
+  }
+
+  /// Remove all the attachments of the collab, e.g. when its view is purged from the trash.
+  /// `now` is in seconds.
+  pub fn remove_object(&mut self, object_id: &str, now: i64) {
+    self.sync_object(object_id, vec![], now);
+  }
+
+  /// The attachments referenced by the collab.
+  pub fn get_object_references(&self, object_id: &str) -> Vec<AttachmentReference> {
+    let txn = self.collab.transact();
+    self
+      .body
+      .object_keys_with_txn(&txn, object_id)
+      .into_iter()
+      .flat_map(|key| self.body.get_references_with_txn(&txn, &key))
+      .collect()
+  }
+
+  /// The places the file is referenced.
+  pub fn get_url_references(&self, url: &str) -> Vec<AttachmentReference> {
+    let txn = self.collab.transact();
+    self
+      .body
+      .all_references_with_txn(&txn)
+      .into_iter()
+      .filter(|reference| reference.url == url)
+      .collect()
+  }
+
+  pub fn get_file(&self, url: &str) -> Option<AttachmentFile> {
+    let txn = self.collab.transact();
+    self.body.get_file_with_txn(&txn, url)
+  }
+
+  pub fn get_all_files(&self) -> Vec<AttachmentFile> {
+    let txn = self.collab.transact();
+    self.body.all_files_with_txn(&txn)
+  }
+
+  /// Set the size of the file, e.g. when the host learns it from the blob storage.
+  pub fn set_file_size(&mut self, url: &str, size: i64) {
+    let mut txn = self.collab.transact_mut();
+    if let Some(mut file) = self.body.get_file_with_txn(&txn, url) {
+      file.size = Some(size);
+      self.body.insert_file_with_txn(&mut txn, file);
+    }
+  }
+
+  /// List the files that are unreferenced for at least `grace_secs` at `now`, in seconds, so the
+  /// host can delete them from the blob storage and then call [AttachmentCatalog::remove_files].
+  ///
+  /// The scan checks the references of every file, so a file whose references were written
+  /// concurrently on another device is marked unreferenced from `now`, or referenced again.
+  pub fn scan_orphans(&mut self, now: i64, grace_secs: i64) -> Vec<OrphanAttachment> {
+    let mut txn = self.collab.transact_mut();
+    let referenced = self.body.referenced_urls_with_txn(&txn);
+    let files = self.body.all_files_with_txn(&txn);
+
+    let mut orphans = vec![];
+    for mut file in files {
+      match (referenced.contains(&file.url), file.unreferenced_since) {
+        (true, Some(_)) => {
+          file.unreferenced_since = None;
+          self.body.insert_file_with_txn(&mut txn, file);
+        },
+        (true, None) => {},
+        (false, None) => {
+          file.unreferenced_since = Some(now);
+          self.body.insert_file_with_txn(&mut txn, file);
+        },
+        (false, Some(unreferenced_since)) => {
+          if unreferenced_since.saturating_add(grace_secs) <= now {
+            orphans.push(OrphanAttachment {
+              url: file.url,
+              size: file.size,
+              unreferenced_since,
+            });
+          }
+        },
+      }
+    }
+    orphans.sort_by(|a, b| a.url.cmp(&b.url));
+    orphans
+  }
+
+  /// Remove the files deleted from the blob storage. A file referenced again since the scan is
+  /// kept.
+  ///
+  /// Return the urls of the removed files.
+  pub fn remove_files(&mut self, urls: &[String]) -> Vec<String> {
+    let mut txn = self.collab.transact_mut();
+    let referenced = self.body.referenced_urls_with_txn(&txn);
+    let mut removed = vec![];
+    for url in urls {
+      if referenced.contains(url) || self.body.get_file_with_txn(&txn, url).is_none() {
+        continue;
+      }
+      self.body.files.remove(&mut txn, url);
+      removed.push(url.clone());
+    }
+    removed
+  }
+}
+
+impl AttachmentCatalogBody {
+  fn object_keys_with_txn<T: ReadTxn>(&self, txn: &T, object_id: &str) -> Vec<String> {
+    let prefix = format!("{}/", object_id);
+    self
+      .references
+      .keys(txn)
+      .filter(|key| key.starts_with(&prefix))
+      .map(|key| key.to_string())
+      .collect()
+  }
+
+  fn get_references_with_txn<T: ReadTxn>(&self, txn: &T, key: &str) -> Vec<AttachmentReference> {
+    match self.references.get(txn, key) {
+      Some(Out::Any(any)) => from_any(&any).unwrap_or_default(),
+      _ => vec![],
+    }
+  }
+
+  fn all_references_with_txn<T: ReadTxn>(&self, txn: &T) -> Vec<AttachmentReference> {
+    self
+      .references
+      .iter(txn)
+      .flat_map(|(_, value)| match value {
+        Out::Any(any) => from_any::<Vec<AttachmentReference>>(&any).unwrap_or_default(),
+        _ => vec![],
+      })
+      .collect()
+  }
+
+  fn referenced_urls_with_txn<T: ReadTxn>(&self, txn: &T) -> HashSet<String> {
+    self
+      .all_references_with_txn(txn)
+      .into_iter()
+      .map(|reference| reference.url)
+      .collect()
+  }
+
+  fn write_references_with_txn(
+    &self,
+    txn: &mut TransactionMut,
+    key: &str,
+    references: &[AttachmentReference],
+  ) {
+    if references.is_empty() {
+      self.references.remove(txn, key);
+      return;
+    }
+    match to_any(&references) {
+      Ok(any) => {
+        self.references.insert(txn, key, any);
+      },
+      Err(err) => error!("Failed to encode attachment references: {}", err),
+    }
+  }
+
+  /// Add the files of the current references and mark the files of the previous references
+  /// that are no longer referenced anywhere.
+  fn update_files_with_txn(
+    &self,
+    txn: &mut TransactionMut,
+    previous: Vec<AttachmentReference>,
+    current: &[AttachmentReference],
+    now: i64,
+  ) {
+    for reference in current {
+      let file = match self.get_file_with_txn(txn, &reference.url) {
+        Some(mut file) => {
+          if reference.size.is_some() {
+            file.size = reference.size;
+          }
+          file.unreferenced_since = None;
+          file
+        },
+        None => AttachmentFile {
+          url: reference.url.clone(),
+          size: reference.size,
+          unreferenced_since: None,
+        },
+      };
+      self.insert_file_with_txn(txn, file);
+    }
+
+    let current_urls = current
+      .iter()
+      .map(|reference| reference.url.as_str())
+      .collect::<HashSet<_>>();
+    let removed_urls = previous
+      .into_iter()
+      .map(|reference| reference.url)
+      .filter(|url| !current_urls.contains(url.as_str()))
+      .collect::<HashSet<_>>();
+    if removed_urls.is_empty() {
+      return;
+    }
+    let referenced = self.referenced_urls_with_txn(txn);
+    for url in removed_urls {
+      if referenced.contains(&url) {
+        continue;
+      }
+      if let Some(mut file) = self.get_file_with_txn(txn, &url) {
+        if file.unreferenced_since.is_none() {
+          file.unreferenced_since = Some(now);
+          self.insert_file_with_txn(txn, file);
+        }
+      }
+    }
+  }
+
+  fn get_file_with_txn<T: ReadTxn>(&self, txn: &T, url: &str) -> Option<AttachmentFile> {
+    match self.files.get(txn, url)? {
+      Out::Any(any) => from_any(&any).ok(),
+      _ => None,
+    }
+  }
+
+  fn all_files_with_txn<T: ReadTxn>(&self, txn: &T) -> Vec<AttachmentFile> {
+    self
+      .files
+      .iter(txn)
+      .filter_map(|(_, value)| match value {
+        Out::Any(any) => from_any::<AttachmentFile>(&any).ok(),
+        _ => None,
+      })
+      .collect()
+  }
+
+  fn insert_file_with_txn(&self, txn: &mut TransactionMut, file: AttachmentFile) {
+    match to_any(&file) {
+      Ok(any) => {
+        self.files.insert(txn, file.url.as_str(), any);
+      },
+      Err(err) => error!("Failed to encode attachment file: {}", err),
+    }
+  }
+}
+
+fn reference_key(object_id: &str, location: &AttachmentLocation) -> String {
+  format!("{}/{}", object_id, location.key())
+}
+
+/// Writes the attachments reported by the documents and databases to the catalog, pass it to
+/// `Document::set_attachment_hook` and `Database::set_attachment_hook`.
+///
+/// The hook of a document is called while the document is written, so the catalog must not be
+/// locked while writing a document.
+#[derive(Clone)]
+pub struct AttachmentCatalogHook {
+  catalog: Arc<Mutex<AttachmentCatalog>>,
+}
+
+impl AttachmentCatalogHook {
+  pub fn new(catalog: Arc<Mutex<AttachmentCatalog>>) -> Self {
+    Self { catalog }
+  }
+}
+
+impl AttachmentWriteHook for AttachmentCatalogHook {
+  fn did_write_attachments(
+    &self,
+    object_id: &str,
+    location: &AttachmentLocation,
+    references: Vec<AttachmentReference>,
+  ) {
+    match self.catalog.lock() {
+      Ok(mut catalog) => catalog.write_attachments(object_id, location, references, timestamp()),
+      Err(err) => error!("Failed to lock the attachment catalog: {}", err),
+    }
+  }
+}
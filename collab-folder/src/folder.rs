@@ -492,8 +492,7 @@ pub struct FolderBody {
   pub meta: MapRef,
   #[allow(dead_code)]
   subscription: Subscription,
-  #[allow(dead_code)]
-  notifier: Option<FolderNotify>,
+  pub(crate) notifier: Option<FolderNotify>,
 }
 
 impl FolderBody {
@@ -16,6 +16,10 @@ pub enum ViewChange {
   DidCreateView { view: View },
   DidDeleteView { views: Vec<Arc<View>> },
   DidUpdate { view: View },
+  /// The views purged from the trash with their descendants, see
+  /// [crate::Folder::purge_expired_trash]. Their documents, databases and attachments can be
+  /// deleted from the storage.
+  DidPurgeViews { views: Vec<Arc<View>> },
 }
 
 pub type ViewChangeSender = broadcast::Sender<ViewChange>;
@@ -1,19 +1,25 @@
+pub use attachment_catalog::*;
 pub use entities::*;
 pub use folder::*;
 pub use folder_migration::*;
 pub use folder_observe::*;
+pub use reference_store::*;
 pub use relation::*;
 pub use section::*;
 // pub use trash::*;
 pub use space_info::*;
+pub use trash_purge::*;
 pub use view::*;
 pub use workspace::*;
 
+mod attachment_catalog;
 mod entities;
 mod folder;
+mod reference_store;
 mod relation;
 mod section;
 // mod trash;
+mod trash_purge;
 mod view;
 mod workspace;
 
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use collab::core::collab::{CollabOptions, DataSource};
+use collab::core::origin::CollabOrigin;
+use collab::preclude::block::ClientID;
+use collab::preclude::encoding::serde::{from_any, to_any};
+use collab::preclude::{Collab, Map, MapExt, MapRef, Out, ReadTxn};
+use collab_entity::reference::Reference;
+use tracing::error;
+
+use crate::error::FolderError;
+
+const REFERENCE_STORE: &str = "reference_store";
+const REFERENCES: &str = "references";
+
+/// The bibliographic references of a workspace, stored in their own collab so every document of
+/// the workspace cites the same references by their key.
+pub struct ReferenceStore {
+  pub collab: Collab,
+  /// The [Reference] by key.
+  references: MapRef,
+}
+
+impl ReferenceStore {
+  pub fn open(mut collab: Collab) -> Self {
+    let references = {
+      let mut txn = collab.context.transact_mut();
+      let store: MapRef = collab.data.get_or_init_map(&mut txn, REFERENCE_STORE);
+      store.get_or_init(&mut txn, REFERENCES)
+    };
+    Self { collab, references }
+  }
+
+  pub fn from_collab_doc_state(
+    origin: CollabOrigin,
+    collab_doc_state: DataSource,
+    object_id: &str,
+    client_id: ClientID,
+  ) -> Result<Self, FolderError> {
+    let options =
+      CollabOptions::new(object_id.to_string(), client_id).with_data_source(collab_doc_state);
+    let collab = Collab::new_with_options(origin, options)?;
+    Ok(Self::open(collab))
+  }
+
+  pub fn close(&self) {
+    self.collab.remove_all_plugins();
+  }
+
+  /// Insert the reference, or replace the reference with the same key.
+  ///
+  /// Return the replaced reference.
+  pub fn insert_reference(&mut self, reference: Reference) -> Option<Reference> {
+    let mut txn = self.collab.transact_mut();
+    let previous = get_reference_with_txn(&self.references, &txn, &reference.key);
+    match to_any(&reference) {
+      Ok(any) => {
+        self
+          .references
+          .insert(&mut txn, reference.key.as_str(), any);
+      },
+      Err(err) => error!("Failed to encode reference {}: {}", reference.key, err),
+    }
+    previous
+  }
+
+  pub fn remove_reference(&mut self, key: &str) -> Option<Reference> {
+    let mut txn = self.collab.transact_mut();
+    let previous = get_reference_with_txn(&self.references, &txn, key)?;
+    self.references.remove(&mut txn, key);
+    Some(previous)
+  }
+
+  pub fn get_reference(&self, key: &str) -> Option<Reference> {
+    let txn = self.collab.transact();
+    get_reference_with_txn(&self.references, &txn, key)
+  }
+
+  /// The references with the keys, e.g. the keys cited by a document. The unknown keys are
+  /// skipped.
+  pub fn get_references(&self, keys: &[String]) -> HashMap<String, Reference> {
+    let txn = self.collab.transact();
+    keys
+      .iter()
+      .filter_map(|key| get_reference_with_txn(&self.references, &txn, key))
+      .map(|reference| (reference.key.clone(), reference))
+      .collect()
+  }
+
+  /// All the references, ordered by key.
+  pub fn get_all_references(&self) -> Vec<Reference> {
+    let txn = self.collab.transact();
+    let mut references = self
+      .references
+      .iter(&txn)
+      .filter_map(|(_, value)| match value {
+        Out::Any(any) => from_any::<Reference>(&any).ok(),
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+    references.sort_by(|a, b| a.key.cmp(&b.key));
+    references
+  }
+}
+
+fn get_reference_with_txn<T: ReadTxn>(
+  references: &MapRef,
+  txn: &T,
+  key: &str,
+) -> Option<Reference> {
+  match references.get(txn, key)? {
+    Out::Any(any) => from_any(&any).ok(),
+    _ => None,
+  }
+}
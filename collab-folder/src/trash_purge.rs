@@ -0,0 +1,160 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use collab::preclude::Map;
+use collab::util::MapExt;
+
+use crate::folder::Folder;
+use crate::folder_observe::ViewChange;
+use crate::section::{Section, SectionItem};
+use crate::view::View;
+
+const TRASH_RETENTION: &str = "trash_retention";
+const LAST_TRASH_PURGE: &str = "last_trash_purge";
+
+/// How long a view stays in the trash when no retention is set, 30 days.
+pub const DEFAULT_TRASH_RETENTION_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// When the views in the trash are purged, stored in the folder meta so every device applies
+/// the same retention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrashPurgeSchedule {
+  /// How long a view stays in the trash before being purged, in seconds.
+  pub retention_secs: i64,
+  /// When the trash was last purged, in seconds. None if it was never purged.
+  pub last_purged_at: Option<i64>,
+}
+
+impl TrashPurgeSchedule {
+  /// Whether a view moved to the trash at `trashed_at` is expired at `now`.
+  pub fn is_expired(&self, trashed_at: i64, now: i64) -> bool {
+    trashed_at.saturating_add(self.retention_secs) <= now
+  }
+}
+
+/// A view of the trash past the retention, returned by [Folder::get_expired_trash] and
+/// [Folder::purge_expired_trash].
+#[derive(Debug, Clone)]
+pub struct ExpiredTrashItem {
+  pub view_id: String,
+  pub name: String,
+  /// When the view was moved to the trash, in seconds.
+  pub trashed_at: i64,
+  /// The view and its descendants, which are purged with it. The storage backends delete the
+  /// documents, databases and attachments of these views.
+  pub views: Vec<View>,
+}
+
+impl Folder {
+  pub fn get_trash_purge_schedule(&self) -> TrashPurgeSchedule {
+    let txn = self.collab.transact();
+    TrashPurgeSchedule {
+      retention_secs: self
+        .body
+        .meta
+        .get_with_txn(&txn, TRASH_RETENTION)
+        .unwrap_or(DEFAULT_TRASH_RETENTION_SECS),
+      last_purged_at: self.body.meta.get_with_txn(&txn, LAST_TRASH_PURGE),
+    }
+  }
+
+  /// Set how long the views stay in the trash, in seconds. None restores the
+  /// [DEFAULT_TRASH_RETENTION_SECS].
+  pub fn set_trash_retention(&mut self, retention_secs: Option<i64>) {
+    let mut txn = self.collab.transact_mut();
+    match retention_secs {
+      Some(retention_secs) => {
+        self
+          .body
+          .meta
+          .insert(&mut txn, TRASH_RETENTION, retention_secs.max(0));
+      },
+      None => {
+        self.body.meta.remove(&mut txn, TRASH_RETENTION);
+      },
+    }
+  }
+
+  /// When the next view of the user's trash expires, in seconds. None if the trash is empty.
+  /// Used to schedule the next [Folder::purge_expired_trash].
+  pub fn next_trash_purge_at(&self, uid: i64) -> Option<i64> {
+    let schedule = self.get_trash_purge_schedule();
+    self
+      .get_my_trash_sections(uid)
+      .iter()
+      .map(|item| item.timestamp.saturating_add(schedule.retention_secs))
+      .min()
+  }
+
+  /// List the views of the user's trash past the retention at `now`, in seconds, without
+  /// purging them.
+  pub fn get_expired_trash(&self, uid: i64, now: i64) -> Vec<ExpiredTrashItem> {
+    let schedule = self.get_trash_purge_schedule();
+    self
+      .get_my_trash_sections(uid)
+      .into_iter()
+      .filter(|item| schedule.is_expired(item.timestamp, now))
+      .map(|item| self.expired_trash_item(item, uid))
+      .collect()
+  }
+
+  /// Purge the views of the user's trash past the retention at `now`, in seconds, with their
+  /// descendants. The views are removed from the folder and from the sections of the user, and
+  /// a [ViewChange::DidPurgeViews] is sent so the storage backends delete their content.
+  ///
+  /// Return the purged views.
+  pub fn purge_expired_trash(&mut self, uid: i64, now: i64) -> Vec<ExpiredTrashItem> {
+    let expired = self.get_expired_trash(uid, now);
+    let mut view_ids = vec![];
+    let mut purged_views = vec![];
+    let mut seen = HashSet::new();
+    for view in expired.iter().flat_map(|item| item.views.iter()) {
+      if seen.insert(view.id.clone()) {
+        view_ids.push(view.id.clone());
+        purged_views.push(Arc::new(view.clone()));
+      }
+    }
+
+    {
+      let mut txn = self.collab.transact_mut();
+      if !view_ids.is_empty() {
+        for section in [
+          Section::Trash,
+          Section::Favorite,
+          Section::Recent,
+          Section::Private,
+        ] {
+          if let Some(op) = self.body.section.section_op(&txn, section, uid) {
+            op.delete_section_items_with_txn(&mut txn, view_ids.clone());
+          }
+        }
+        self.body.views.delete_views(&mut txn, view_ids);
+      }
+      self.body.meta.insert(&mut txn, LAST_TRASH_PURGE, now);
+    }
+
+    if !purged_views.is_empty() {
+      if let Some(notifier) = self.body.notifier.as_ref() {
+        let _ = notifier.view_change_tx.send(ViewChange::DidPurgeViews {
+          views: purged_views,
+        });
+      }
+    }
+    expired
+  }
+
+  fn expired_trash_item(&self, item: SectionItem, uid: i64) -> ExpiredTrashItem {
+    let views = self.get_view_recursively(&item.id, uid);
+    let name = views
+      .iter()
+      .find(|view| view.id == item.id)
+      .map(|view| view.name.clone())
+      .unwrap_or_default();
+    ExpiredTrashItem {
+      view_id: item.id,
+      name,
+      trashed_at: item.timestamp,
+      views,
+    }
+  }
+}
@@ -0,0 +1,118 @@
+use std::sync::{Arc, Mutex};
+
+use collab::core::collab::{CollabOptions, default_client_id};
+use collab::core::origin::CollabOrigin;
+use collab::preclude::Collab;
+use collab_entity::attachment::{AttachmentLocation, AttachmentReference, AttachmentWriteHook};
+use collab_folder::{AttachmentCatalog, AttachmentCatalogHook, OrphanAttachment};
+
+fn create_catalog() -> AttachmentCatalog {
+  let options = CollabOptions::new("catalog".to_string(), default_client_id());
+  let collab = Collab::new_with_options(CollabOrigin::Empty, options).unwrap();
+  AttachmentCatalog::open(collab)
+}
+
+fn block(block_id: &str) -> AttachmentLocation {
+  AttachmentLocation::Block {
+    block_id: block_id.to_string(),
+  }
+}
+
+fn reference(object_id: &str, location: &AttachmentLocation, url: &str) -> AttachmentReference {
+  AttachmentReference {
+    object_id: object_id.to_string(),
+    location: location.clone(),
+    url: url.to_string(),
+    size: Some(10),
+  }
+}
+
+#[test]
+fn attachment_orphan_after_grace_period_test() {
+  let mut catalog = create_catalog();
+  let b1 = block("b1");
+  let b2 = block("b2");
+  catalog.write_attachments("d1", &b1, vec![reference("d1", &b1, "a.png")], 100);
+  catalog.write_attachments("d1", &b2, vec![reference("d1", &b2, "a.png")], 100);
+  assert_eq!(catalog.get_url_references("a.png").len(), 2);
+
+  // still referenced by the second block
+  catalog.write_attachments("d1", &b1, vec![], 200);
+  assert_eq!(catalog.get_file("a.png").unwrap().unreferenced_since, None);
+  assert!(catalog.scan_orphans(1000, 10).is_empty());
+
+  catalog.write_attachments("d1", &b2, vec![], 300);
+  assert_eq!(
+    catalog.get_file("a.png").unwrap().unreferenced_since,
+    Some(300)
+  );
+  assert!(catalog.scan_orphans(305, 10).is_empty());
+  assert_eq!(
+    catalog.scan_orphans(310, 10),
+    vec![OrphanAttachment {
+      url: "a.png".to_string(),
+      size: Some(10),
+      unreferenced_since: 300,
+    }]
+  );
+
+  let removed = catalog.remove_files(&["a.png".to_string()]);
+  assert_eq!(removed, vec!["a.png".to_string()]);
+  assert!(catalog.get_all_files().is_empty());
+}
+
+#[test]
+fn attachment_referenced_again_is_not_orphan_test() {
+  let mut catalog = create_catalog();
+  let b1 = block("b1");
+  catalog.write_attachments("d1", &b1, vec![reference("d1", &b1, "a.png")], 100);
+  catalog.write_attachments("d1", &b1, vec![], 200);
+
+  // the block is restored by an undo
+  catalog.write_attachments("d1", &b1, vec![reference("d1", &b1, "a.png")], 250);
+  assert!(catalog.scan_orphans(1000, 10).is_empty());
+  assert!(catalog.remove_files(&["a.png".to_string()]).is_empty());
+  assert!(catalog.get_file("a.png").is_some());
+}
+
+#[test]
+fn attachment_sync_and_remove_object_test() {
+  let mut catalog = create_catalog();
+  let b1 = block("b1");
+  let b2 = block("b2");
+  let cell = AttachmentLocation::Cell {
+    row_id: "r1".to_string(),
+    field_id: "f1".to_string(),
+  };
+  catalog.write_attachments("d1", &b1, vec![reference("d1", &b1, "a.png")], 100);
+  catalog.write_attachments("db1", &cell, vec![reference("db1", &cell, "c.pdf")], 100);
+
+  // the first block was removed while the hook wasn't set
+  catalog.sync_object("d1", vec![reference("d1", &b2, "b.png")], 200);
+  let references = catalog.get_object_references("d1");
+  assert_eq!(references, vec![reference("d1", &b2, "b.png")]);
+  assert_eq!(
+    catalog.get_file("a.png").unwrap().unreferenced_since,
+    Some(200)
+  );
+
+  catalog.remove_object("db1", 300);
+  assert!(catalog.get_object_references("db1").is_empty());
+  let orphans = catalog
+    .scan_orphans(400, 50)
+    .into_iter()
+    .map(|orphan| orphan.url)
+    .collect::<Vec<_>>();
+  assert_eq!(orphans, vec!["a.png".to_string(), "c.pdf".to_string()]);
+}
+
+#[test]
+fn attachment_catalog_hook_test() {
+  let catalog = Arc::new(Mutex::new(create_catalog()));
+  let hook = AttachmentCatalogHook::new(catalog.clone());
+  let b1 = block("b1");
+  hook.did_write_attachments("d1", &b1, vec![reference("d1", &b1, "a.png")]);
+  let catalog = catalog.lock().unwrap();
+  assert_eq!(catalog.get_object_references("d1").len(), 1);
+  assert_eq!(catalog.get_file("a.png").unwrap().size, Some(10));
+}
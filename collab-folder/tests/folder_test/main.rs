@@ -1,10 +1,13 @@
+mod attachment_catalog_test;
 mod child_views_test;
 mod custom_section;
 mod favorite_test;
 mod load_disk;
 mod recent_views_test;
+mod reference_store_test;
 mod serde_test;
 mod space_info_test;
+mod trash_purge_test;
 mod trash_test;
 mod util;
 mod view_test;
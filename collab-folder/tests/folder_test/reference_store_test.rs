@@ -0,0 +1,76 @@
+use collab::core::collab::{CollabOptions, default_client_id};
+use collab::core::origin::CollabOrigin;
+use collab::preclude::Collab;
+use collab_entity::reference::{Reference, ReferenceAuthor};
+use collab_folder::ReferenceStore;
+
+fn create_store() -> ReferenceStore {
+  let options = CollabOptions::new("references".to_string(), default_client_id());
+  let collab = Collab::new_with_options(CollabOrigin::Empty, options).unwrap();
+  ReferenceStore::open(collab)
+}
+
+fn reference(key: &str, title: &str) -> Reference {
+  Reference {
+    key: key.to_string(),
+    kind: "article".to_string(),
+    title: title.to_string(),
+    authors: vec![ReferenceAuthor {
+      family: "Smith".to_string(),
+      given: Some("John".to_string()),
+    }],
+    year: Some("2020".to_string()),
+    ..Default::default()
+  }
+}
+
+#[test]
+fn insert_and_replace_reference_test() {
+  let mut store = create_store();
+  assert!(
+    store
+      .insert_reference(reference("smith2020", "First"))
+      .is_none()
+  );
+  assert!(
+    store
+      .insert_reference(reference("doe2019", "Second"))
+      .is_none()
+  );
+
+  let replaced = store
+    .insert_reference(reference("smith2020", "Updated"))
+    .unwrap();
+  assert_eq!(replaced.title, "First");
+  assert_eq!(
+    store.get_reference("smith2020"),
+    Some(reference("smith2020", "Updated"))
+  );
+
+  let keys = store
+    .get_all_references()
+    .into_iter()
+    .map(|reference| reference.key)
+    .collect::<Vec<_>>();
+  assert_eq!(keys, vec!["doe2019", "smith2020"]);
+}
+
+#[test]
+fn get_and_remove_references_test() {
+  let mut store = create_store();
+  store.insert_reference(reference("smith2020", "First"));
+  store.insert_reference(reference("doe2019", "Second"));
+
+  let references = store.get_references(&["doe2019".to_string(), "unknown".to_string()]);
+  assert_eq!(references.len(), 1);
+  assert_eq!(references["doe2019"].title, "Second");
+
+  assert_eq!(
+    store
+      .remove_reference("doe2019")
+      .map(|reference| reference.key),
+    Some("doe2019".to_string())
+  );
+  assert!(store.remove_reference("doe2019").is_none());
+  assert_eq!(store.get_all_references().len(), 1);
+}
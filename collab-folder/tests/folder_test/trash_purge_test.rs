@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use collab_folder::{
+  DEFAULT_TRASH_RETENTION_SECS, FolderData, SectionItem, UserId, ViewChange, Workspace, timestamp,
+};
+
+use crate::util::{create_folder_with_data, create_folder_with_workspace, make_test_view};
+
+#[test]
+fn trash_purge_schedule_test() {
+  let uid = UserId::from(1);
+  let mut folder = create_folder_with_workspace(uid.clone(), "w1").folder;
+  let schedule = folder.get_trash_purge_schedule();
+  assert_eq!(schedule.retention_secs, DEFAULT_TRASH_RETENTION_SECS);
+  assert_eq!(schedule.last_purged_at, None);
+  assert_eq!(folder.next_trash_purge_at(uid.as_i64()), None);
+
+  folder.set_trash_retention(Some(60));
+  assert_eq!(folder.get_trash_purge_schedule().retention_secs, 60);
+  folder.insert_view(make_test_view("v1", "w1", vec![]), None, uid.as_i64());
+  folder.add_trash_view_ids(vec!["v1".to_string()], uid.as_i64());
+  let trashed_at = folder.get_my_trash_sections(uid.as_i64())[0].timestamp;
+  assert_eq!(
+    folder.next_trash_purge_at(uid.as_i64()),
+    Some(trashed_at + 60)
+  );
+
+  folder.set_trash_retention(None);
+  assert_eq!(
+    folder.get_trash_purge_schedule().retention_secs,
+    DEFAULT_TRASH_RETENTION_SECS
+  );
+}
+
+#[test]
+fn expired_trash_dry_run_test() {
+  let uid = UserId::from(1);
+  let mut folder = create_folder_with_workspace(uid.clone(), "w1").folder;
+  folder.set_trash_retention(Some(10));
+  let mut view_1 = make_test_view("v1", "w1", vec![]);
+  view_1.name = "Old notes".to_string();
+  folder.insert_view(view_1, None, uid.as_i64());
+  folder.insert_view(make_test_view("v1_1", "v1", vec![]), None, uid.as_i64());
+  folder.add_trash_view_ids(vec!["v1".to_string()], uid.as_i64());
+
+  let now = timestamp();
+  assert!(folder.get_expired_trash(uid.as_i64(), now).is_empty());
+  let expired = folder.get_expired_trash(uid.as_i64(), now + 20);
+  assert_eq!(expired.len(), 1);
+  assert_eq!(expired[0].view_id, "v1");
+  assert_eq!(expired[0].name, "Old notes");
+  let view_ids = expired[0]
+    .views
+    .iter()
+    .map(|view| view.id.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(view_ids, vec!["v1", "v1_1"]);
+
+  // the dry run doesn't remove anything
+  assert!(folder.get_view("v1", uid.as_i64()).is_some());
+  assert_eq!(folder.get_my_trash_sections(uid.as_i64()).len(), 1);
+}
+
+#[test]
+fn purge_expired_trash_test() {
+  let uid = UserId::from(1);
+  let workspace = Workspace::new("w1".to_string(), "".to_string(), uid.as_i64());
+  let mut folder_data = FolderData::new(uid.as_i64(), workspace);
+  folder_data.views = vec![
+    make_test_view("v1", "w1", vec![]),
+    make_test_view("v1_1", "v1", vec![]),
+    make_test_view("v2", "w1", vec![]),
+  ];
+  let item = |id: &str, timestamp: i64| SectionItem {
+    id: id.to_string(),
+    timestamp,
+  };
+  folder_data.trash = HashMap::from([(uid.clone(), vec![item("v1", 100), item("v2", 1000)])]);
+  folder_data.favorites = HashMap::from([(uid.clone(), vec![item("v1", 100)])]);
+  let mut folder_test = create_folder_with_data(uid.clone(), "w1", folder_data);
+  let folder = &mut folder_test.folder;
+  folder.set_trash_retention(Some(10));
+
+  // v2 is not expired yet
+  let purged = folder.purge_expired_trash(uid.as_i64(), 500);
+  assert_eq!(purged.len(), 1);
+  assert_eq!(purged[0].view_id, "v1");
+  assert!(folder.get_view("v1", uid.as_i64()).is_none());
+  assert!(folder.get_view("v1_1", uid.as_i64()).is_none());
+  assert!(folder.get_view("v2", uid.as_i64()).is_some());
+  let trash = folder.get_my_trash_sections(uid.as_i64());
+  assert_eq!(trash.len(), 1);
+  assert_eq!(trash[0].id, "v2");
+  assert!(folder.get_my_favorite_sections(uid.as_i64()).is_empty());
+  assert_eq!(folder.get_trash_purge_schedule().last_purged_at, Some(500));
+  assert_eq!(folder.next_trash_purge_at(uid.as_i64()), Some(1010));
+
+  let mut purged_view_ids = vec![];
+  while let Ok(change) = folder_test.view_rx.try_recv() {
+    if let ViewChange::DidPurgeViews { views } = change {
+      purged_view_ids.extend(views.iter().map(|view| view.id.clone()));
+    }
+  }
+  assert_eq!(purged_view_ids, vec!["v1", "v1_1"]);
+}
@@ -24,7 +24,7 @@ pub struct FolderTest {
   cleaner: Cleaner,
 
   #[allow(dead_code)]
-  view_rx: ViewChangeReceiver,
+  pub(crate) view_rx: ViewChangeReceiver,
 
   #[allow(dead_code)]
   pub(crate) section_rx: Option<SectionChangeReceiver>,
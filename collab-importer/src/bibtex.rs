@@ -0,0 +1,300 @@
+use crate::error::ImporterError;
+use collab_entity::reference::{Reference, ReferenceAuthor};
+use collab_folder::ReferenceStore;
+use std::collections::HashMap;
+
+/// The entry types of a BibTeX file that are not references.
+const NON_REFERENCE_ENTRIES: [&str; 3] = ["comment", "preamble", "string"];
+
+/// The number of references added to and updated in the reference store by [import_bibtex].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BibtexImportSummary {
+  pub added: usize,
+  pub updated: usize,
+}
+
+/// Import the entries of a BibTeX file into the reference store of the workspace. An entry
+/// replaces the reference with the same cite key.
+pub fn import_bibtex(
+  store: &mut ReferenceStore,
+  content: &str,
+) -> Result<BibtexImportSummary, ImporterError> {
+  let mut summary = BibtexImportSummary::default();
+  for reference in parse_bibtex(content)? {
+    match store.insert_reference(reference) {
+      Some(_) => summary.updated += 1,
+      None => summary.added += 1,
+    }
+  }
+  Ok(summary)
+}
+
+/// Parse the entries of a BibTeX file, e.g. `@article{smith2020, title = {...}, year = 2020}`.
+///
+/// The `@comment`, `@preamble` and `@string` entries are skipped, and the text outside of the
+/// entries is ignored as BibTeX does.
+pub fn parse_bibtex(content: &str) -> Result<Vec<Reference>, ImporterError> {
+  let mut parser = BibtexParser {
+    chars: content.chars().collect(),
+    position: 0,
+  };
+  let mut references = vec![];
+  while parser.skip_to_entry() {
+    let kind = parser.read_identifier().to_ascii_lowercase();
+    if kind.is_empty() {
+      return Err(parser.error("missing entry type after @"));
+    }
+    parser.skip_whitespace();
+    let close = match parser.next() {
+      Some('{') => '}',
+      Some('(') => ')',
+      _ => return Err(parser.error(&format!("missing body of the @{} entry", kind))),
+    };
+    if NON_REFERENCE_ENTRIES.contains(&kind.as_str()) {
+      parser.skip_balanced(close)?;
+      continue;
+    }
+
+    parser.skip_whitespace();
+    let key = parser.read_until(&[',', close]).trim().to_string();
+    if key.is_empty() {
+      return Err(parser.error(&format!("missing cite key of the @{} entry", kind)));
+    }
+    let fields = parser.read_fields(close)?;
+    references.push(reference_from_fields(key, kind, fields));
+  }
+  Ok(references)
+}
+
+struct BibtexParser {
+  chars: Vec<char>,
+  position: usize,
+}
+
+impl BibtexParser {
+  fn peek(&self) -> Option<char> {
+    self.chars.get(self.position).copied()
+  }
+
+  fn next(&mut self) -> Option<char> {
+    let c = self.peek()?;
+    self.position += 1;
+    Some(c)
+  }
+
+  fn error(&self, message: &str) -> ImporterError {
+    let line = self.chars[..self.position.min(self.chars.len())]
+      .iter()
+      .filter(|c| **c == '\n')
+      .count()
+      + 1;
+    ImporterError::ParseBibtexError(format!("{} at line {}", message, line))
+  }
+
+  /// Move past the next `@`, return false at the end of the file.
+  fn skip_to_entry(&mut self) -> bool {
+    while let Some(c) = self.next() {
+      if c == '@' {
+        return true;
+      }
+    }
+    false
+  }
+
+  fn skip_whitespace(&mut self) {
+    while self.peek().is_some_and(char::is_whitespace) {
+      self.position += 1;
+    }
+  }
+
+  fn read_identifier(&mut self) -> String {
+    let mut identifier = String::new();
+    while let Some(c) = self.peek() {
+      if c.is_alphanumeric() || matches!(c, '_' | '-' | ':' | '.') {
+        identifier.push(c);
+        self.position += 1;
+      } else {
+        break;
+      }
+    }
+    identifier
+  }
+
+  fn read_until(&mut self, stops: &[char]) -> String {
+    let mut text = String::new();
+    while let Some(c) = self.peek() {
+      if stops.contains(&c) {
+        break;
+      }
+      text.push(c);
+      self.position += 1;
+    }
+    text
+  }
+
+  /// Skip the body of an entry, up to its closing delimiter.
+  fn skip_balanced(&mut self, close: char) -> Result<(), ImporterError> {
+    let mut depth = 0;
+    while let Some(c) = self.next() {
+      match c {
+        '{' => depth += 1,
+        '}' if depth > 0 => depth -= 1,
+        c if c == close && depth == 0 => return Ok(()),
+        _ => {},
+      }
+    }
+    Err(self.error("unterminated entry"))
+  }
+
+  /// Read the `name = value` fields of an entry, up to its closing delimiter.
+  fn read_fields(&mut self, close: char) -> Result<HashMap<String, String>, ImporterError> {
+    let mut fields = HashMap::new();
+    loop {
+      self.skip_whitespace();
+      match self.next() {
+        Some(',') => continue,
+        Some(c) if c == close => return Ok(fields),
+        Some(_) => self.position -= 1,
+        None => return Err(self.error("unterminated entry")),
+      }
+
+      let name = self.read_identifier().to_ascii_lowercase();
+      if name.is_empty() {
+        return Err(self.error("invalid field name"));
+      }
+      self.skip_whitespace();
+      if self.next() != Some('=') {
+        return Err(self.error(&format!("missing = after the {} field", name)));
+      }
+      let value = self.read_value()?;
+      fields.insert(name, value);
+    }
+  }
+
+  /// Read a field value: braced or quoted text, a number, or their concatenation with `#`.
+  /// The `@string` macros are not expanded, their names are kept.
+  fn read_value(&mut self) -> Result<String, ImporterError> {
+    let mut value = String::new();
+    loop {
+      self.skip_whitespace();
+      match self.peek() {
+        Some('{') => {
+          self.position += 1;
+          value.push_str(&self.read_braced('}')?);
+        },
+        Some('"') => {
+          self.position += 1;
+          value.push_str(&self.read_braced('"')?);
+        },
+        Some(c) if c.is_alphanumeric() => value.push_str(&self.read_identifier()),
+        _ => return Err(self.error("missing field value")),
+      }
+      self.skip_whitespace();
+      if self.peek() == Some('#') {
+        self.position += 1;
+      } else {
+        return Ok(value);
+      }
+    }
+  }
+
+  /// Read the text up to the closing delimiter, the nested braces are kept.
+  fn read_braced(&mut self, close: char) -> Result<String, ImporterError> {
+    let mut text = String::new();
+    let mut depth = 0;
+    while let Some(c) = self.next() {
+      match c {
+        '{' => depth += 1,
+        '}' if depth > 0 => depth -= 1,
+        c if c == close && depth == 0 => return Ok(text),
+        _ => {},
+      }
+      text.push(c);
+    }
+    Err(self.error("unterminated field value"))
+  }
+}
+
+fn reference_from_fields(
+  key: String,
+  kind: String,
+  mut fields: HashMap<String, String>,
+) -> Reference {
+  let authors = fields
+    .remove("author")
+    .or_else(|| fields.remove("editor"))
+    .map(|authors| parse_authors(&authors))
+    .unwrap_or_default();
+  let mut field = |name: &str| {
+    fields
+      .remove(name)
+      .map(|value| clean_text(&value))
+      .filter(|value| !value.is_empty())
+  };
+  Reference {
+    key,
+    kind,
+    title: field("title").unwrap_or_default(),
+    authors,
+    year: field("year"),
+    container_title: field("journal")
+      .or_else(|| field("journaltitle"))
+      .or_else(|| field("booktitle")),
+    publisher: field("publisher")
+      .or_else(|| field("institution"))
+      .or_else(|| field("school")),
+    volume: field("volume"),
+    issue: field("number").or_else(|| field("issue")),
+    pages: field("pages").map(|pages| pages.replace("--", "–")),
+    doi: field("doi"),
+    url: field("url"),
+  }
+}
+
+/// Split the names joined with `and`, written `Family, Given` or `Given Family`. A name in braces,
+/// e.g. `{World Health Organization}`, is an organization.
+fn parse_authors(authors: &str) -> Vec<ReferenceAuthor> {
+  authors
+    .split(" and ")
+    .map(str::trim)
+    .filter(|name| !name.is_empty())
+    .map(|name| {
+      if name.starts_with('{') && name.ends_with('}') {
+        return ReferenceAuthor {
+          family: clean_text(name),
+          given: None,
+        };
+      }
+      let name = clean_text(name);
+      parse_name(&name)
+    })
+    .collect()
+}
+
+fn parse_name(name: &str) -> ReferenceAuthor {
+  match name.split_once(',') {
+    Some((family, given)) => ReferenceAuthor {
+      family: family.trim().to_string(),
+      given: Some(given.trim().to_string()).filter(|given| !given.is_empty()),
+    },
+    None => match name.rsplit_once(' ') {
+      Some((given, family)) => ReferenceAuthor {
+        family: family.to_string(),
+        given: Some(given.trim().to_string()),
+      },
+      None => ReferenceAuthor {
+        family: name.to_string(),
+        given: None,
+      },
+    },
+  }
+}
+
+/// Remove the braces protecting the case of the words and collapse the whitespace.
+fn clean_text(value: &str) -> String {
+  value
+    .replace(['{', '}'], "")
+    .split_whitespace()
+    .collect::<Vec<_>>()
+    .join(" ")
+}
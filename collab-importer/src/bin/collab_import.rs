@@ -0,0 +1,40 @@
+use collab_importer::cli::{CliArgs, run};
+use collab_importer::tools::{EXIT_OK, EXIT_SOFTWARE, EXIT_USAGE, exit_code};
+
+#[tokio::main]
+async fn main() {
+  let args = match CliArgs::parse(std::env::args().skip(1)) {
+    Ok(args) => args,
+    Err(usage) => {
+      eprintln!("{}", usage);
+      std::process::exit(EXIT_USAGE);
+    },
+  };
+
+  let unzip_dir = std::env::temp_dir().join(format!("collab_import_{}", uuid::Uuid::new_v4()));
+  let result = run(&args, &unzip_dir).await;
+  // The exit below skips the destructors, the unzipped export is removed before.
+  let _ = std::fs::remove_dir_all(&unzip_dir);
+  let manifest = match result {
+    Ok(manifest) => manifest,
+    Err(err) => {
+      eprintln!("[{}] {}", err.code(), err);
+      std::process::exit(exit_code(&err));
+    },
+  };
+  match serde_json::to_string_pretty(&manifest) {
+    Ok(manifest) => println!("{}", manifest),
+    Err(err) => {
+      eprintln!("{}", err);
+      std::process::exit(EXIT_SOFTWARE);
+    },
+  }
+  if let (Some(out), false) = (&args.out, args.dry_run) {
+    eprintln!(
+      "Wrote {} collabs to {}",
+      manifest.collabs.len(),
+      out.display()
+    );
+  }
+  std::process::exit(EXIT_OK);
+}
@@ -0,0 +1,340 @@
+//! The `collab-import` command line tool, built with the `cli` feature.
+//!
+//! `collab-import notion export.zip --out workspace.archive [--dry-run] [--include <glob>]...`
+//! imports a Notion export and writes the imported views to a workspace archive: a zip file with
+//! a `manifest.json` describing the views and their collabs, the collabs encoded with
+//! [EncodedCollab::encode_to_bytes] in `collabs/`, and the files of the databases in `files/`.
+//! The archive is read back with [read_import_archive] to restore the views.
+
+use crate::error::ImporterError;
+use crate::imported_collab::ImportedCollab;
+use crate::notion::NotionImporter;
+use crate::notion::importer::ImportedInfo;
+use crate::zip_tool::multi_part::unzip_multi_part;
+use crate::zip_tool::safe_unzip::UnzipLimits;
+use collab::entity::EncodedCollab;
+use collab_entity::CollabType;
+use collab_folder::View;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::FileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+/// The name of the manifest in the archive.
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+/// The version of the archive layout, written to [ImportManifest::version].
+pub const ARCHIVE_VERSION: u32 = 1;
+/// The host of the urls of the imported files when none is given.
+pub const DEFAULT_HOST: &str = "http://localhost";
+
+/// The source of the import.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportSource {
+  /// A Notion export, zipped or unzipped.
+  Notion(PathBuf),
+}
+
+/// The arguments of the tool: `notion <path-to-export> [--out <archive>] [--dry-run]
+/// [--include <glob>]... [--workspace-id <id>] [--host <url>]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliArgs {
+  pub source: ImportSource,
+  /// The archive to write. Required unless `dry_run` is set.
+  pub out: Option<PathBuf>,
+  /// Import and print the manifest without writing the archive.
+  pub dry_run: bool,
+  /// The glob patterns of the paths of the views to import, e.g. `Team Space/**`. All the views
+  /// are imported when empty, see [matches_glob].
+  pub includes: Vec<String>,
+  /// The workspace the views are imported into, a new id when None.
+  pub workspace_id: Option<String>,
+  /// The host of the urls of the imported files.
+  pub host: String,
+}
+
+impl CliArgs {
+  pub const USAGE: &'static str = "usage: collab-import notion <path-to-export> \
+[--out <archive>] [--dry-run] [--include <glob>]... [--workspace-id <id>] [--host <url>]";
+
+  /// Parse the arguments, without the program name. Return [CliArgs::USAGE] with the reason when
+  /// they are invalid, the tool should exit with [crate::tools::EXIT_USAGE].
+  pub fn parse<I, S>(args: I) -> Result<Self, String>
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    let mut args = args.into_iter().map(Into::into);
+    match args.next().as_deref() {
+      Some("notion") => {},
+      Some(command) => return Err(format!("unknown command {}\n{}", command, Self::USAGE)),
+      None => return Err(Self::USAGE.to_string()),
+    }
+
+    let mut path = None;
+    let mut out = None;
+    let mut dry_run = false;
+    let mut includes = vec![];
+    let mut workspace_id = None;
+    let mut host = DEFAULT_HOST.to_string();
+    while let Some(arg) = args.next() {
+      match arg.as_str() {
+        "--dry-run" => dry_run = true,
+        "--out" | "--include" | "--workspace-id" | "--host" => {
+          let value = args
+            .next()
+            .ok_or_else(|| format!("{} expects a value\n{}", arg, Self::USAGE))?;
+          match arg.as_str() {
+            "--out" => out = Some(PathBuf::from(value)),
+            "--include" => includes.push(value),
+            "--workspace-id" => workspace_id = Some(value),
+            _ => host = value,
+          }
+        },
+        _ if arg.starts_with("--") => {
+          return Err(format!("unknown option {}\n{}", arg, Self::USAGE));
+        },
+        _ if path.is_none() => path = Some(PathBuf::from(arg)),
+        _ => return Err(format!("unexpected argument {}\n{}", arg, Self::USAGE)),
+      }
+    }
+    let path = path.ok_or_else(|| Self::USAGE.to_string())?;
+    if out.is_none() && !dry_run {
+      return Err(format!(
+        "--out is required without --dry-run\n{}",
+        Self::USAGE
+      ));
+    }
+    Ok(Self {
+      source: ImportSource::Notion(path),
+      out,
+      dry_run,
+      includes,
+      workspace_id,
+      host,
+    })
+  }
+}
+
+/// Describe the collabs of a workspace archive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportManifest {
+  pub version: u32,
+  pub name: String,
+  pub workspace_id: String,
+  /// The imported views, each after its parent, to insert into the folder.
+  pub views: Vec<View>,
+  pub collabs: Vec<ManifestCollab>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestCollab {
+  pub object_id: String,
+  pub collab_type: CollabType,
+  /// The name of the imported page the collab belongs to.
+  pub name: String,
+  /// The path of the encoded collab in the archive.
+  pub path: String,
+  pub size: u64,
+  /// The paths of the files of the collab in the archive.
+  pub files: Vec<String>,
+}
+
+/// Return whether the path, the names of a view and of its parents joined with `/`, matches the
+/// glob pattern. `*` matches any characters of a name, `?` one character, and `**` any number of
+/// names, e.g. `Team Space/**` matches `Team Space` and all the views under it.
+pub fn matches_glob(pattern: &str, path: &str) -> bool {
+  let pattern = pattern.trim_matches('/').split('/').collect::<Vec<_>>();
+  let path = path.split('/').collect::<Vec<_>>();
+  matches_segments(&pattern, &path)
+}
+
+fn matches_segments(pattern: &[&str], path: &[&str]) -> bool {
+  match pattern.split_first() {
+    None => path.is_empty(),
+    Some((&"**", rest)) => (0..=path.len()).any(|skip| matches_segments(rest, &path[skip..])),
+    Some((segment, rest)) => match path.split_first() {
+      Some((name, path)) => matches_name(segment, name) && matches_segments(rest, path),
+      None => false,
+    },
+  }
+}
+
+fn matches_name(pattern: &str, name: &str) -> bool {
+  let pattern = pattern.chars().collect::<Vec<_>>();
+  let name = name.chars().collect::<Vec<_>>();
+  // The names matched by the prefix of the pattern, by their length
+  let mut matched = vec![false; name.len() + 1];
+  matched[0] = true;
+  for c in pattern {
+    let mut next = vec![false; name.len() + 1];
+    for len in 0..=name.len() {
+      next[len] = match c {
+        '*' => matched[len] || (len > 0 && next[len - 1]),
+        '?' => len > 0 && matched[len - 1],
+        c => len > 0 && matched[len - 1] && name[len - 1] == c,
+      };
+    }
+    matched = next;
+  }
+  matched[name.len()]
+}
+
+/// Run the tool: import the source, keeping the views matching the `includes` of the arguments,
+/// and write the archive unless it's a dry run. The zipped exports are unzipped into `unzip_dir`.
+pub async fn run(args: &CliArgs, unzip_dir: &Path) -> Result<ImportManifest, ImporterError> {
+  let workspace_id = args
+    .workspace_id
+    .clone()
+    .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+  let imported = match &args.source {
+    ImportSource::Notion(path) => {
+      import_notion(path, unzip_dir, &workspace_id, &args.host, &args.includes).await?
+    },
+  };
+  let out = args.out.as_deref().filter(|_| !args.dry_run);
+  write_import_archive(imported, out).await
+}
+
+/// Import the Notion export, a zip file or a directory, keeping the views matching one of the
+/// `includes` patterns when there are any. A zip file is unzipped into `unzip_dir` within the
+/// [UnzipLimits::default].
+pub async fn import_notion(
+  path: &Path,
+  unzip_dir: &Path,
+  workspace_id: &str,
+  host: &str,
+  includes: &[String],
+) -> Result<ImportedInfo, ImporterError> {
+  let export_dir = if path.is_dir() {
+    path.to_path_buf()
+  } else {
+    // The export is untrusted, its paths are sanitized and its size is limited.
+    unzip_multi_part(&[path.to_path_buf()], unzip_dir, &UnzipLimits::default())
+      .await?
+      .unzip_dir
+  };
+  let mut imported = NotionImporter::new(1, &export_dir, workspace_id, host.to_string())?
+    .import()
+    .await?;
+  if !includes.is_empty() {
+    imported.retain_views(|path| includes.iter().any(|pattern| matches_glob(pattern, path)));
+  }
+  Ok(imported)
+}
+
+/// Build the collabs of the imported views and write them to the archive at `out`, or only
+/// return their manifest when `out` is None.
+pub async fn write_import_archive(
+  imported: ImportedInfo,
+  out: Option<&Path>,
+) -> Result<ImportManifest, ImporterError> {
+  let mut manifest = ImportManifest {
+    version: ARCHIVE_VERSION,
+    name: imported.name.clone(),
+    workspace_id: imported.workspace_id.clone(),
+    views: imported.build_nested_views().await.flatten_views(),
+    collabs: vec![],
+  };
+  let mut writer = out
+    .map(|out| File::create(out).map(ZipWriter::new))
+    .transpose()?;
+
+  let mut stream = imported.into_collab_stream().await;
+  while let Some(info) = stream.next().await {
+    for imported_collab in info.imported_collabs {
+      let bytes = imported_collab
+        .encoded_collab
+        .encode_to_bytes()
+        .map_err(|err| ImporterError::Internal(err.into()))?;
+      let path = format!("collabs/{}", imported_collab.object_id);
+      let files = info
+        .resources
+        .iter()
+        .filter(|resource| resource.object_id == imported_collab.object_id)
+        .flat_map(|resource| resource.files.iter())
+        .collect::<Vec<_>>();
+      let mut file_paths = vec![];
+      for file in files {
+        let file_name = Path::new(file)
+          .file_name()
+          .map(|name| name.to_string_lossy().to_string())
+          .unwrap_or_else(|| file.clone());
+        let file_path = format!("files/{}/{}", imported_collab.object_id, file_name);
+        if let Some(writer) = writer.as_mut() {
+          let content = std::fs::read(file)?;
+          write_zip_entry(writer, &file_path, &content)?;
+        }
+        file_paths.push(file_path);
+      }
+      if let Some(writer) = writer.as_mut() {
+        write_zip_entry(writer, &path, &bytes)?;
+      }
+      manifest.collabs.push(ManifestCollab {
+        object_id: imported_collab.object_id,
+        collab_type: imported_collab.collab_type,
+        name: info.name.clone(),
+        path,
+        size: bytes.len() as u64,
+        files: file_paths,
+      });
+    }
+  }
+
+  if let Some(mut writer) = writer {
+    let content =
+      serde_json::to_vec_pretty(&manifest).map_err(|err| ImporterError::Internal(err.into()))?;
+    write_zip_entry(&mut writer, MANIFEST_FILE_NAME, &content)?;
+    writer
+      .finish()
+      .map_err(|err| ImporterError::Internal(err.into()))?;
+  }
+  Ok(manifest)
+}
+
+/// Read the manifest and the collabs of an archive written by [write_import_archive].
+pub fn read_import_archive(
+  path: &Path,
+) -> Result<(ImportManifest, Vec<ImportedCollab>), ImporterError> {
+  let mut archive =
+    ZipArchive::new(File::open(path)?).map_err(|err| ImporterError::InvalidArchive(err.into()))?;
+  let manifest = read_zip_entry(&mut archive, MANIFEST_FILE_NAME)?;
+  let manifest: ImportManifest =
+    serde_json::from_slice(&manifest).map_err(|err| ImporterError::InvalidArchive(err.into()))?;
+
+  let mut collabs = vec![];
+  for collab in &manifest.collabs {
+    let bytes = read_zip_entry(&mut archive, &collab.path)?;
+    let encoded_collab = EncodedCollab::decode_from_bytes(&bytes)
+      .map_err(|err| ImporterError::InvalidArchive(err.into()))?;
+    collabs.push(ImportedCollab {
+      object_id: collab.object_id.clone(),
+      collab_type: collab.collab_type,
+      encoded_collab,
+    });
+  }
+  Ok((manifest, collabs))
+}
+
+fn write_zip_entry(
+  writer: &mut ZipWriter<File>,
+  path: &str,
+  content: &[u8],
+) -> Result<(), ImporterError> {
+  writer
+    .start_file(path, FileOptions::default())
+    .map_err(|err| ImporterError::Internal(err.into()))?;
+  writer.write_all(content)?;
+  Ok(())
+}
+
+fn read_zip_entry(archive: &mut ZipArchive<File>, path: &str) -> Result<Vec<u8>, ImporterError> {
+  let mut file = archive
+    .by_name(path)
+    .map_err(|err| ImporterError::InvalidArchive(err.into()))?;
+  let mut content = vec![];
+  file.read_to_end(&mut content)?;
+  Ok(content)
+}
@@ -0,0 +1,130 @@
+use crate::error::ImporterError;
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use std::collections::HashMap;
+
+/// An object of the `entities.xml` file of a Confluence export, e.g. a page, the body of a page
+/// or an attachment.
+///
+/// ```xml
+/// <object class="Page" package="com.atlassian.confluence.pages">
+///   <id name="id">98305</id>
+///   <property name="title"><![CDATA[Home]]></property>
+///   <property name="parent" class="Page"><id name="id">98304</id></property>
+///   <collection name="bodyContents"><element class="BodyContent"><id name="id">1</id></element></collection>
+/// </object>
+/// ```
+#[derive(Debug, Default)]
+pub(crate) struct EntityObject {
+  pub class: String,
+  pub id: String,
+  /// The values of the properties, or the ids of the objects they reference.
+  pub properties: HashMap<String, String>,
+  /// The ids of the objects of the collections.
+  pub collections: HashMap<String, Vec<String>>,
+}
+
+impl EntityObject {
+  pub fn property(&self, name: &str) -> Option<&str> {
+    self
+      .properties
+      .get(name)
+      .map(|value| value.trim())
+      .filter(|value| !value.is_empty())
+  }
+
+  /// Return false for the past versions, the drafts and the deleted content.
+  pub fn is_current(&self) -> bool {
+    self.property("originalVersion").is_none()
+      && self
+        .property("contentStatus")
+        .is_none_or(|status| status == "current")
+  }
+}
+
+pub(crate) fn parse_entities(xml: &str) -> Result<Vec<EntityObject>, ImporterError> {
+  let mut reader = Reader::from_str(xml);
+  let mut objects = vec![];
+  let mut object: Option<EntityObject> = None;
+  // The open elements with their name attribute, the innermost last.
+  let mut path: Vec<(String, String)> = vec![];
+  let mut text = String::new();
+  loop {
+    let event = reader
+      .read_event()
+      .map_err(|err| ImporterError::ParseConfluenceError(err.to_string()))?;
+    match event {
+      Event::Start(start) => {
+        let element = String::from_utf8_lossy(start.local_name().as_ref()).to_string();
+        let mut name = String::new();
+        let mut class = String::new();
+        for attr in start.attributes().flatten() {
+          match attr.key.local_name().as_ref() {
+            b"name" => name = String::from_utf8_lossy(&attr.value).to_string(),
+            b"class" => class = String::from_utf8_lossy(&attr.value).to_string(),
+            _ => {},
+          }
+        }
+        if element == "object" {
+          object = Some(EntityObject {
+            class,
+            ..Default::default()
+          });
+        }
+        path.push((element, name));
+        text.clear();
+      },
+      Event::Text(value) => {
+        let value = value
+          .unescape()
+          .map_err(|err| ImporterError::ParseConfluenceError(err.to_string()))?;
+        text.push_str(&value);
+      },
+      Event::CData(data) => text.push_str(&String::from_utf8_lossy(&data.into_inner())),
+      Event::End(_) => {
+        let (element, name) = path.pop().ok_or_else(|| {
+          ImporterError::ParseConfluenceError("unexpected closing tag".to_string())
+        })?;
+        let value = std::mem::take(&mut text);
+        if element == "object" {
+          objects.extend(object.take());
+          continue;
+        }
+        let Some(object) = object.as_mut() else {
+          continue;
+        };
+        let parent = path.last().map(|(element, name)| (element.as_str(), name));
+        match (element.as_str(), parent) {
+          ("id", Some(("object", _))) => object.id = value.trim().to_string(),
+          ("id", Some(("property", property))) => {
+            object
+              .properties
+              .insert(property.clone(), value.trim().to_string());
+          },
+          ("id", Some(("element", _))) => {
+            if let Some((_, collection)) = path.iter().rev().nth(1) {
+              object
+                .collections
+                .entry(collection.clone())
+                .or_default()
+                .push(value.trim().to_string());
+            }
+          },
+          // A property referencing an object already got the id of the object.
+          ("property", _) => {
+            object.properties.entry(name).or_insert(value);
+          },
+          _ => {},
+        }
+      },
+      Event::Eof => break,
+      _ => {},
+    }
+  }
+  if !path.is_empty() {
+    return Err(ImporterError::ParseConfluenceError(
+      "unclosed xml element".to_string(),
+    ));
+  }
+  Ok(objects)
+}
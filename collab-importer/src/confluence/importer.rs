@@ -0,0 +1,460 @@
+use crate::confluence::entities::{EntityObject, parse_entities};
+use crate::confluence::storage::{AttachmentMedia, storage_to_html};
+use crate::error::{ImporterError, ImporterResultExt};
+use crate::imported_collab::{ImportType, ImportedCollab, ImportedCollabInfo};
+use crate::notion::page::CollabResource;
+use crate::util::{FileId, upload_file_url};
+use collab::core::collab::default_client_id;
+use collab_document::document::Document;
+use collab_document::document_data::default_document_collab_data;
+use collab_document::importer::md_importer::MDImporter;
+use collab_document::provenance::BlockProvenance;
+use collab_entity::CollabType;
+use collab_folder::ViewLayout;
+use collab_folder::hierarchy_builder::{NestedChildViewBuilder, NestedViews, ParentChildViews};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// The file describing the spaces, pages and attachments of the export.
+const ENTITIES_FILE: &str = "entities.xml";
+/// The directory of the attachments, `attachments/<page id>/<attachment id>/<version>`.
+const ATTACHMENTS_DIR: &str = "attachments";
+const UNTITLED_PAGE: &str = "Untitled";
+const IMAGE_EXTENSIONS: [&str; 7] = ["png", "jpg", "jpeg", "gif", "webp", "svg", "bmp"];
+
+/// Imports the XML space exports of Confluence, an unzipped directory with an `entities.xml`
+/// file and the attachments.
+///
+/// Each space is imported as a folder page whose children are the root pages of the space, and
+/// each page keeps its child pages. The storage format bodies of the pages are converted to
+/// blocks, and the attachments are written to the output directory and returned as resources of
+/// the imported collabs. Only the current version of the pages is imported, the history, the
+/// drafts and the trashed pages are skipped.
+pub struct ConfluenceImporter {
+  uid: i64,
+  host: String,
+  workspace_id: String,
+}
+
+impl ConfluenceImporter {
+  pub fn new<S: ToString>(uid: i64, workspace_id: S, host: String) -> Self {
+    Self {
+      uid,
+      host,
+      workspace_id: workspace_id.to_string(),
+    }
+  }
+
+  pub fn import_dir<P: AsRef<Path>>(
+    &self,
+    export_dir: P,
+    output_dir: P,
+  ) -> Result<ConfluenceImportedInfo, ImporterError> {
+    let export_dir = export_dir.as_ref();
+    let entities_path = export_dir.join(ENTITIES_FILE);
+    if !entities_path.exists() {
+      return Err(ImporterError::FileNotFound);
+    }
+    let xml = std::fs::read_to_string(&entities_path)?;
+    let objects =
+      parse_entities(&xml).with_context(|| format!("import {}", entities_path.display()))?;
+    let entities = Entities::new(objects);
+
+    let default_space_name = export_dir
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_else(|| UNTITLED_PAGE.to_string());
+    let context = ImportContext {
+      export_dir,
+      output_dir: output_dir.as_ref(),
+      entities: &entities,
+    };
+    let spaces = entities
+      .root_pages_by_space()
+      .into_iter()
+      .map(|(space_name, root_pages)| {
+        let name = space_name.unwrap_or_else(|| default_space_name.clone());
+        self.import_space(&name, &root_pages, &context)
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ConfluenceImportedInfo {
+      uid: self.uid,
+      workspace_id: self.workspace_id.clone(),
+      spaces,
+    })
+  }
+
+  fn import_space(
+    &self,
+    name: &str,
+    root_pages: &[&EntityObject],
+    context: &ImportContext,
+  ) -> Result<ConfluenceSpace, ImporterError> {
+    let view_id = uuid::Uuid::new_v4().to_string();
+    let pages = root_pages
+      .iter()
+      .map(|page| self.import_page(page, context))
+      .collect::<Result<Vec<_>, _>>()?;
+
+    let encoded_collab = default_document_collab_data(&view_id, default_client_id())?;
+    let collab_info = ImportedCollabInfo {
+      name: name.to_string(),
+      imported_collabs: vec![ImportedCollab {
+        object_id: view_id.clone(),
+        collab_type: CollabType::Document,
+        encoded_collab,
+      }],
+      resources: vec![],
+      import_type: ImportType::Document,
+    };
+    Ok(ConfluenceSpace {
+      view_id,
+      name: name.to_string(),
+      pages,
+      collab_info,
+    })
+  }
+
+  fn import_page(
+    &self,
+    page: &EntityObject,
+    context: &ImportContext,
+  ) -> Result<ConfluencePage, ImporterError> {
+    let view_id = uuid::Uuid::new_v4().to_string();
+    let title = page.property("title").unwrap_or(UNTITLED_PAGE).to_string();
+
+    let (attachments, files) = self.write_attachments(&view_id, page, context)?;
+    let body = context.entities.body(&page.id).unwrap_or_default();
+    let html = storage_to_html(body, &attachments).unwrap_or_else(|err| {
+      // The html importer is lenient, the macros are lost but the text is kept.
+      warn!("invalid storage format in page {}: {}", title, err);
+      body.to_string()
+    });
+    let mut document_data = MDImporter::new(None)
+      .import_html(&view_id, &html)
+      .with_context(|| format!("import page {}", title))?;
+    document_data.tag_provenance(&BlockProvenance::new(
+      "confluence",
+      format!("{}#{}", ENTITIES_FILE, page.id),
+    ));
+    let document = Document::create(&view_id, document_data, default_client_id())?;
+    let encoded_collab = document.encode_collab()?;
+    let collab_info = ImportedCollabInfo {
+      name: title.clone(),
+      imported_collabs: vec![ImportedCollab {
+        object_id: view_id.clone(),
+        collab_type: CollabType::Document,
+        encoded_collab,
+      }],
+      resources: vec![CollabResource {
+        object_id: view_id.clone(),
+        files,
+      }],
+      import_type: ImportType::Document,
+    };
+
+    let children = context
+      .entities
+      .child_pages(&page.id)
+      .into_iter()
+      .map(|child| self.import_page(child, context))
+      .collect::<Result<Vec<_>, _>>()?;
+    Ok(ConfluencePage {
+      view_id,
+      title,
+      content_id: page.id.clone(),
+      children,
+      collab_info,
+    })
+  }
+
+  /// Copy the attachments of the page to `output_dir` and return the uploaded attachments keyed
+  /// by file name with the written files.
+  fn write_attachments(
+    &self,
+    view_id: &str,
+    page: &EntityObject,
+    context: &ImportContext,
+  ) -> Result<(HashMap<String, AttachmentMedia>, Vec<String>), ImporterError> {
+    let mut attachments = HashMap::new();
+    let mut files = vec![];
+    for attachment in context.entities.attachments(&page.id) {
+      let Some(file_name) = attachment.property("title") else {
+        continue;
+      };
+      let Some(source_path) = attachment_path(context.export_dir, &page.id, attachment) else {
+        warn!("attachment {} of page {} is not found", file_name, page.id);
+        continue;
+      };
+      let bytes = std::fs::read(&source_path)?;
+      let media_dir = context.output_dir.join(view_id);
+      std::fs::create_dir_all(&media_dir)?;
+      let file_path = media_dir.join(sanitize_filename::sanitize(file_name));
+      std::fs::write(&file_path, &bytes)?;
+
+      let ext = Path::new(file_name)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+      let is_image = IMAGE_EXTENSIONS.contains(&ext.as_str());
+      let file_id = FileId::from_bytes(&bytes, ext);
+      let url = upload_file_url(&self.host, &self.workspace_id, view_id, &file_id);
+      attachments.insert(
+        file_name.to_string(),
+        AttachmentMedia {
+          url,
+          file_name: file_name.to_string(),
+          is_image,
+        },
+      );
+      files.push(file_path.to_string_lossy().to_string());
+    }
+    Ok((attachments, files))
+  }
+}
+
+struct ImportContext<'a> {
+  export_dir: &'a Path,
+  output_dir: &'a Path,
+  entities: &'a Entities,
+}
+
+/// The current pages, bodies and attachments of the export.
+struct Entities {
+  pages: Vec<EntityObject>,
+  /// The names of the spaces, keyed by space id.
+  spaces: HashMap<String, String>,
+  /// The storage format bodies, keyed by page id.
+  bodies: HashMap<String, String>,
+  /// The attachments, keyed by page id.
+  attachments: HashMap<String, Vec<EntityObject>>,
+}
+
+impl Entities {
+  fn new(objects: Vec<EntityObject>) -> Self {
+    let mut pages = vec![];
+    let mut spaces = HashMap::new();
+    let mut bodies = HashMap::new();
+    let mut attachments: HashMap<String, Vec<EntityObject>> = HashMap::new();
+    for object in objects {
+      match object.class.as_str() {
+        "Page" if object.is_current() => pages.push(object),
+        "Space" => {
+          if let Some(name) = object.property("name") {
+            spaces.insert(object.id.clone(), name.to_string());
+          }
+        },
+        "BodyContent" => {
+          if let Some(content) = object.property("content") {
+            let body = object.properties.get("body").cloned().unwrap_or_default();
+            bodies.insert(content.to_string(), body);
+          }
+        },
+        "Attachment" if object.is_current() => {
+          let page_id = object
+            .property("containerContent")
+            .or_else(|| object.property("content"))
+            .map(|id| id.to_string());
+          if let Some(page_id) = page_id {
+            attachments.entry(page_id).or_default().push(object);
+          }
+        },
+        _ => {},
+      }
+    }
+    pages.sort_by(|a, b| page_order(a).cmp(&page_order(b)));
+    Self {
+      pages,
+      spaces,
+      bodies,
+      attachments,
+    }
+  }
+
+  fn body(&self, page_id: &str) -> Option<&str> {
+    self.bodies.get(page_id).map(|body| body.as_str())
+  }
+
+  fn attachments(&self, page_id: &str) -> &[EntityObject] {
+    self
+      .attachments
+      .get(page_id)
+      .map(|attachments| attachments.as_slice())
+      .unwrap_or_default()
+  }
+
+  fn child_pages(&self, page_id: &str) -> Vec<&EntityObject> {
+    self
+      .pages
+      .iter()
+      .filter(|page| page.property("parent") == Some(page_id))
+      .collect()
+  }
+
+  /// The pages without a parent in the export, grouped by the name of their space. The spaces
+  /// are ordered by name, the pages of an unknown space come last.
+  fn root_pages_by_space(&self) -> Vec<(Option<String>, Vec<&EntityObject>)> {
+    let mut groups: Vec<(Option<String>, Vec<&EntityObject>)> = vec![];
+    for page in self.pages.iter() {
+      let is_root = page
+        .property("parent")
+        .is_none_or(|parent| !self.pages.iter().any(|page| page.id == parent));
+      if !is_root {
+        continue;
+      }
+      let space = page
+        .property("space")
+        .and_then(|space_id| self.spaces.get(space_id))
+        .cloned();
+      match groups.iter_mut().find(|(name, _)| *name == space) {
+        Some((_, pages)) => pages.push(page),
+        None => groups.push((space, vec![page])),
+      }
+    }
+    groups.sort_by(|(a, _), (b, _)| match (a, b) {
+      (Some(a), Some(b)) => a.cmp(b),
+      (a, b) => b.is_some().cmp(&a.is_some()),
+    });
+    groups
+  }
+}
+
+/// Pages are ordered by their position among their siblings, then by title.
+fn page_order(page: &EntityObject) -> (i64, String) {
+  let position = page
+    .property("position")
+    .and_then(|position| position.parse().ok())
+    .unwrap_or(i64::MAX);
+  (
+    position,
+    page.property("title").unwrap_or_default().to_string(),
+  )
+}
+
+/// The file of the attachment, stored under its version number. Older exports may use another
+/// name, in which case the first file of the attachment directory is used.
+fn attachment_path(export_dir: &Path, page_id: &str, attachment: &EntityObject) -> Option<PathBuf> {
+  let dir = export_dir
+    .join(ATTACHMENTS_DIR)
+    .join(page_id)
+    .join(&attachment.id);
+  let version = attachment.property("version").unwrap_or("1");
+  let path = dir.join(version);
+  if path.is_file() {
+    return Some(path);
+  }
+  let mut files = std::fs::read_dir(&dir)
+    .ok()?
+    .flatten()
+    .map(|entry| entry.path())
+    .filter(|path| path.is_file())
+    .collect::<Vec<_>>();
+  files.sort();
+  files.into_iter().next()
+}
+
+/// The spaces and pages imported by [ConfluenceImporter].
+#[derive(Debug, Clone)]
+pub struct ConfluenceImportedInfo {
+  pub uid: i64,
+  pub workspace_id: String,
+  pub spaces: Vec<ConfluenceSpace>,
+}
+
+impl ConfluenceImportedInfo {
+  pub fn num_of_pages(&self) -> usize {
+    self
+      .spaces
+      .iter()
+      .flat_map(|space| space.pages.iter())
+      .map(ConfluencePage::num_of_pages)
+      .sum()
+  }
+
+  /// The views of the spaces, each with the views of its pages, under the workspace.
+  pub fn build_nested_views(&self) -> NestedViews {
+    let views = self
+      .spaces
+      .iter()
+      .map(|space| {
+        let pages = space
+          .pages
+          .iter()
+          .map(|page| page.build_view(self.uid, &space.view_id))
+          .collect::<Vec<_>>();
+        NestedChildViewBuilder::new(self.uid, self.workspace_id.clone())
+          .with_view_id(&space.view_id)
+          .with_name(&space.name)
+          .with_layout(ViewLayout::Document)
+          .with_children(pages)
+          .build()
+      })
+      .collect();
+    NestedViews { views }
+  }
+
+  /// The collabs of the spaces and of their pages, each page before its children.
+  pub fn into_collab_infos(self) -> Vec<ImportedCollabInfo> {
+    let mut collab_infos = vec![];
+    for space in self.spaces {
+      collab_infos.push(space.collab_info);
+      for page in space.pages {
+        page.collect_collab_infos(&mut collab_infos);
+      }
+    }
+    collab_infos
+  }
+}
+
+/// An imported space, a folder page whose children are the root pages of the space.
+#[derive(Debug, Clone)]
+pub struct ConfluenceSpace {
+  pub view_id: String,
+  pub name: String,
+  pub pages: Vec<ConfluencePage>,
+  pub collab_info: ImportedCollabInfo,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfluencePage {
+  pub view_id: String,
+  pub title: String,
+  /// The id of the page in the export.
+  pub content_id: String,
+  pub children: Vec<ConfluencePage>,
+  pub collab_info: ImportedCollabInfo,
+}
+
+impl ConfluencePage {
+  /// The number of pages, this page included.
+  pub fn num_of_pages(&self) -> usize {
+    1 + self
+      .children
+      .iter()
+      .map(ConfluencePage::num_of_pages)
+      .sum::<usize>()
+  }
+
+  fn build_view(&self, uid: i64, parent_view_id: &str) -> ParentChildViews {
+    let children = self
+      .children
+      .iter()
+      .map(|child| child.build_view(uid, &self.view_id))
+      .collect::<Vec<_>>();
+    NestedChildViewBuilder::new(uid, parent_view_id.to_string())
+      .with_view_id(&self.view_id)
+      .with_name(&self.title)
+      .with_layout(ViewLayout::Document)
+      .with_children(children)
+      .build()
+  }
+
+  fn collect_collab_infos(self, collab_infos: &mut Vec<ImportedCollabInfo>) {
+    collab_infos.push(self.collab_info);
+    for child in self.children {
+      child.collect_collab_infos(collab_infos);
+    }
+  }
+}
@@ -0,0 +1,5 @@
+mod entities;
+mod importer;
+mod storage;
+
+pub use importer::*;
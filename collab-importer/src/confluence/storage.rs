@@ -0,0 +1,207 @@
+use crate::error::ImporterError;
+use crate::xhtml::{XhtmlNode, escape_html, parse_xhtml, write_checkbox, write_element};
+use std::collections::HashMap;
+use tracing::warn;
+
+/// An uploaded attachment of a page, referenced by its file name in the storage format.
+#[derive(Debug, Clone)]
+pub(crate) struct AttachmentMedia {
+  pub url: String,
+  pub file_name: String,
+  pub is_image: bool,
+}
+
+/// Convert the storage format body of a Confluence page to html that the html importer
+/// understands:
+/// - the info, tip, note, warning and panel macros become callouts.
+/// - the code and noformat macros become code blocks.
+/// - `<ac:image>` and the links to attachments point to the uploaded attachments.
+/// - the task lists become checkbox list items.
+/// - the other macros are replaced with their body, or dropped when they have none.
+pub(crate) fn storage_to_html(
+  body: &str,
+  attachments: &HashMap<String, AttachmentMedia>,
+) -> Result<String, ImporterError> {
+  let nodes = parse_xhtml(body).map_err(ImporterError::ParseConfluenceError)?;
+  let mut html = String::with_capacity(body.len());
+  write_nodes(&nodes, attachments, &mut html);
+  Ok(html)
+}
+
+fn write_nodes(
+  nodes: &[XhtmlNode],
+  attachments: &HashMap<String, AttachmentMedia>,
+  html: &mut String,
+) {
+  for node in nodes {
+    write_node(node, attachments, html);
+  }
+}
+
+fn write_node(node: &XhtmlNode, attachments: &HashMap<String, AttachmentMedia>, html: &mut String) {
+  let XhtmlNode::Element { name, children, .. } = node else {
+    if let XhtmlNode::Text(text) = node {
+      html.push_str(text);
+    }
+    return;
+  };
+
+  match name.as_str() {
+    "ac:structured-macro" | "ac:macro" => write_macro(node, attachments, html),
+    "ac:image" => {
+      if let Some(attachment) = find_attachment(node, attachments) {
+        html.push_str(&format!(
+          "<img src=\"{}\" alt=\"{}\"/>",
+          escape_html(&attachment.url),
+          escape_html(&attachment.file_name)
+        ));
+      } else if let Some(url) = node.child("ri:url").and_then(|url| url.attr("ri:value")) {
+        html.push_str(&format!("<img src=\"{}\"/>", url));
+      } else {
+        let file_name = node
+          .child("ri:attachment")
+          .and_then(|attachment| attachment.attr("ri:filename"));
+        warn!("image {:?} is not found in the attachments", file_name);
+      }
+    },
+    "ac:link" => {
+      let body = node
+        .child("ac:link-body")
+        .or_else(|| node.child("ac:plain-text-link-body"))
+        .map(|body| body.text_content())
+        .filter(|text| !text.trim().is_empty());
+      if let Some(attachment) = find_attachment(node, attachments) {
+        let text = body.unwrap_or_else(|| escape_html(&attachment.file_name));
+        html.push_str(&format!(
+          "<a href=\"{}\">{}</a>",
+          escape_html(&attachment.url),
+          text
+        ));
+      } else if let Some(text) = body.or_else(|| {
+        node
+          .child("ri:page")
+          .and_then(|page| page.attr("ri:content-title"))
+          .map(|title| title.to_string())
+      }) {
+        // The links to the other pages are kept as text, the pages are imported with new ids.
+        html.push_str(&text);
+      }
+    },
+    "ac:task-list" => {
+      html.push_str("<ul>");
+      for task in children
+        .iter()
+        .filter(|child| child.name() == Some("ac:task"))
+      {
+        let complete = task
+          .child("ac:task-status")
+          .is_some_and(|status| status.text_content().trim() == "complete");
+        html.push_str("<li>");
+        write_checkbox(complete, html);
+        if let Some(body) = task.child("ac:task-body") {
+          write_nodes(body.children(), attachments, html);
+        }
+        html.push_str("</li>");
+      }
+      html.push_str("</ul>");
+    },
+    "ac:emoticon" => {
+      if let Some(emoji) = node.attr("ac:emoji-fallback") {
+        html.push_str(emoji);
+      }
+    },
+    "ac:placeholder" | "ac:parameter" => {},
+    "ac:layout" | "ac:layout-section" | "ac:layout-cell" => {
+      html.push_str("<div>");
+      write_nodes(children, attachments, html);
+      html.push_str("</div>");
+    },
+    "time" => {
+      if let Some(datetime) = node.attr("datetime") {
+        html.push_str(datetime);
+      }
+    },
+    _ if name.starts_with("ac:") || name.starts_with("ri:") => {
+      write_nodes(children, attachments, html)
+    },
+    _ => write_element(node, html, |html| write_nodes(children, attachments, html)),
+  }
+}
+
+fn write_macro(
+  node: &XhtmlNode,
+  attachments: &HashMap<String, AttachmentMedia>,
+  html: &mut String,
+) {
+  let macro_name = node.attr("ac:name").unwrap_or_default();
+  let title = macro_parameter(node, "title");
+  match macro_name {
+    "info" | "tip" | "note" | "warning" | "panel" => {
+      html.push_str(&format!(
+        "<aside data-icon=\"{}\">",
+        callout_icon(macro_name)
+      ));
+      if let Some(title) = title {
+        html.push_str(&format!("<p><strong>{}</strong></p>", title));
+      }
+      if let Some(body) = node.child("ac:rich-text-body") {
+        write_nodes(body.children(), attachments, html);
+      }
+      html.push_str("</aside>");
+    },
+    "code" | "noformat" => {
+      let code = node
+        .child("ac:plain-text-body")
+        .map(|body| body.text_content())
+        .unwrap_or_default();
+      match macro_parameter(node, "language") {
+        Some(language) => html.push_str(&format!(
+          "<pre><code class=\"language-{}\">{}</code></pre>",
+          language.to_lowercase(),
+          code
+        )),
+        None => html.push_str(&format!("<pre><code>{}</code></pre>", code)),
+      }
+    },
+    _ => {
+      if let Some(body) = node.child("ac:rich-text-body") {
+        // e.g. the expand macro, its title is kept above its content.
+        if let Some(title) = title {
+          html.push_str(&format!("<p><strong>{}</strong></p>", title));
+        }
+        write_nodes(body.children(), attachments, html);
+      } else {
+        warn!("macro {} is not imported", macro_name);
+      }
+    },
+  }
+}
+
+fn macro_parameter(node: &XhtmlNode, name: &str) -> Option<String> {
+  node
+    .children()
+    .iter()
+    .find(|child| child.name() == Some("ac:parameter") && child.attr("ac:name") == Some(name))
+    .map(|parameter| parameter.text_content())
+    .filter(|value| !value.trim().is_empty())
+}
+
+fn callout_icon(macro_name: &str) -> &'static str {
+  match macro_name {
+    "tip" => "✅",
+    "note" => "📝",
+    "warning" => "🚨",
+    "panel" => "📌",
+    _ => "💡",
+  }
+}
+
+/// The attachment referenced by the `<ri:attachment>` child of the node.
+fn find_attachment<'a>(
+  node: &XhtmlNode,
+  attachments: &'a HashMap<String, AttachmentMedia>,
+) -> Option<&'a AttachmentMedia> {
+  let file_name = node.child("ri:attachment")?.attr("ri:filename")?;
+  let file_name = quick_xml::escape::unescape(file_name).ok()?;
+  attachments.get(file_name.as_ref())
+}
@@ -0,0 +1,275 @@
+use crate::docx::parser::{DocxBlock, DocxContent, DocxParagraphKind, DocxRun, parse_docx};
+use crate::error::ImporterError;
+use crate::imported_collab::{ImportType, ImportedCollab, ImportedCollabInfo};
+use crate::notion::page::CollabResource;
+use crate::util::{FileId, upload_file_url};
+use collab::core::collab::default_client_id;
+use collab_document::blocks::{
+  Block, BlockType, DocumentData, DocumentDataBuilder, NewBlock, SimpleTableData,
+};
+use collab_document::document::Document;
+use collab_document::document_data::generate_id;
+use collab_document::error::DocumentError;
+use collab_document::importer::define::*;
+use collab_document::provenance::BlockProvenance;
+use collab_entity::CollabType;
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+const UNDERLINE_ATTR: &str = "underline";
+
+/// Imports a Word (.docx) file as a document.
+///
+/// Paragraph styles are mapped to headings and quotes, numbering to bulleted and numbered lists,
+/// tables to simple tables and run properties to inline formatting. Embedded images are extracted
+/// to the output directory and returned as resources of the imported collab.
+pub struct DocxImporter {
+  host: String,
+  workspace_id: String,
+}
+
+impl DocxImporter {
+  pub fn new<S: ToString>(workspace_id: S, host: String) -> Self {
+    Self {
+      host,
+      workspace_id: workspace_id.to_string(),
+    }
+  }
+
+  /// Import the docx file. The images of the document are written to `output_dir`.
+  pub fn import_file<P: AsRef<Path>>(
+    &self,
+    view_id: &str,
+    file_path: P,
+    output_dir: P,
+  ) -> Result<ImportedCollabInfo, ImporterError> {
+    let file_path = file_path.as_ref();
+    if !file_path.exists() {
+      return Err(ImporterError::FileNotFound);
+    }
+    let name = file_path
+      .file_stem()
+      .map(|stem| stem.to_string_lossy().to_string())
+      .unwrap_or_default();
+    let reader = BufReader::new(File::open(file_path)?);
+    let (mut document_data, resource) = self.import_reader(view_id, reader, output_dir.as_ref())?;
+    document_data.tag_provenance(&BlockProvenance::new("docx", file_path.display()));
+
+    let document = Document::create(view_id, document_data, default_client_id())?;
+    let encoded_collab = document.encode_collab()?;
+    Ok(ImportedCollabInfo {
+      name,
+      imported_collabs: vec![ImportedCollab {
+        object_id: view_id.to_string(),
+        collab_type: CollabType::Document,
+        encoded_collab,
+      }],
+      resources: vec![resource],
+      import_type: ImportType::Document,
+    })
+  }
+
+  /// Convert the docx content to document data. The images of the document are written to
+  /// `output_dir` and listed in the returned resource.
+  pub fn import_reader<R: Read + Seek>(
+    &self,
+    view_id: &str,
+    reader: R,
+    output_dir: &Path,
+  ) -> Result<(DocumentData, CollabResource), ImporterError> {
+    let DocxContent { blocks, media } = parse_docx(reader)?;
+
+    let mut image_urls = HashMap::new();
+    let mut files = vec![];
+    if !media.is_empty() {
+      let media_dir = output_dir.join(view_id);
+      std::fs::create_dir_all(&media_dir)?;
+      for (media_path, bytes) in media {
+        let file_name = Path::new(&media_path)
+          .file_name()
+          .map(|name| name.to_string_lossy().to_string())
+          .unwrap_or_else(generate_id);
+        let ext = Path::new(&file_name)
+          .extension()
+          .map(|ext| ext.to_string_lossy().to_string())
+          .unwrap_or_default();
+        let file_path: PathBuf = media_dir.join(&file_name);
+        std::fs::write(&file_path, &bytes)?;
+
+        let file_id = FileId::from_bytes(&bytes, ext);
+        let url = upload_file_url(&self.host, &self.workspace_id, view_id, &file_id);
+        image_urls.insert(media_path, url);
+        files.push(file_path.to_string_lossy().to_string());
+      }
+    }
+
+    let mut builder = DocxDocumentBuilder::new(view_id, image_urls);
+    builder.push_blocks(view_id, &blocks)?;
+    let resource = CollabResource {
+      object_id: view_id.to_string(),
+      files,
+    };
+    Ok((builder.build(), resource))
+  }
+}
+
+struct DocxDocumentBuilder {
+  builder: DocumentDataBuilder,
+  image_urls: HashMap<String, String>,
+}
+
+impl DocxDocumentBuilder {
+  fn new(page_id: &str, image_urls: HashMap<String, String>) -> Self {
+    Self {
+      builder: DocumentDataBuilder::with_page_id(page_id),
+      image_urls,
+    }
+  }
+
+  fn build(self) -> DocumentData {
+    self.builder.build()
+  }
+
+  fn push_blocks(&mut self, parent_id: &str, blocks: &[DocxBlock]) -> Result<(), DocumentError> {
+    // The last list item of each nesting level, used to nest list items by their level.
+    let mut list_stack: Vec<(usize, String)> = vec![];
+    for block in blocks {
+      match block {
+        DocxBlock::Paragraph { kind, level, runs } => {
+          let block_type = match kind {
+            DocxParagraphKind::Paragraph => BlockType::Paragraph,
+            DocxParagraphKind::Heading(_) => BlockType::Heading,
+            DocxParagraphKind::Quote => BlockType::Quote,
+            DocxParagraphKind::BulletedList => BlockType::BulletedList,
+            DocxParagraphKind::NumberedList => BlockType::NumberedList,
+          };
+          let mut new_block = NewBlock::new(block_type).with_delta(runs_to_delta(runs));
+          if let DocxParagraphKind::Heading(level) = kind {
+            new_block = new_block.with_data(LEVEL_FIELD, json!(level));
+          }
+
+          let is_list = matches!(
+            kind,
+            DocxParagraphKind::BulletedList | DocxParagraphKind::NumberedList
+          );
+          let parent = if is_list {
+            while list_stack.last().is_some_and(|(l, _)| l >= level) {
+              list_stack.pop();
+            }
+            list_stack
+              .last()
+              .map(|(_, id)| id.clone())
+              .unwrap_or_else(|| parent_id.to_string())
+          } else {
+            list_stack.clear();
+            parent_id.to_string()
+          };
+
+          let id = self.builder.push_child(&parent, new_block)?;
+          if is_list {
+            list_stack.push((*level, id));
+          }
+        },
+        DocxBlock::Image { media_path } => {
+          list_stack.clear();
+          match self.image_urls.get(media_path) {
+            Some(url) => {
+              self.builder.push_child(parent_id, Block::image(url))?;
+            },
+            None => warn!("image {} is not found in the docx file", media_path),
+          }
+        },
+        DocxBlock::Table {
+          has_header_row,
+          column_widths,
+          rows,
+        } => {
+          list_stack.clear();
+          self.push_table(parent_id, *has_header_row, column_widths, rows)?;
+        },
+      }
+    }
+    Ok(())
+  }
+
+  fn push_table(
+    &mut self,
+    parent_id: &str,
+    has_header_row: bool,
+    column_widths: &[f64],
+    rows: &[Vec<Vec<DocxBlock>>],
+  ) -> Result<(), DocumentError> {
+    let mut table_data = SimpleTableData {
+      enable_header_row: has_header_row,
+      ..Default::default()
+    };
+    for (column, width) in column_widths.iter().enumerate() {
+      if *width > 0.0 {
+        table_data.set_column_width(column, *width);
+      }
+    }
+
+    let mut table = NewBlock::new(BlockType::SimpleTable);
+    table.data = table_data.into_block_data();
+    let table_id = self.builder.push_child(parent_id, table)?;
+
+    for (row_index, row) in rows.iter().enumerate() {
+      let row_id = self
+        .builder
+        .push_child(&table_id, NewBlock::new(BlockType::SimpleTableRow))?;
+
+      for (col_index, cell) in row.iter().enumerate() {
+        let new_cell = NewBlock::new(BlockType::SimpleTableCell)
+          .with_data(ROW_POSITION_FIELD, json!(row_index))
+          .with_data(COL_POSITION_FIELD, json!(col_index));
+        if cell.is_empty() {
+          // Every cell holds at least one paragraph.
+          let new_cell = new_cell.with_child(Block::paragraph(""));
+          self.builder.push_child(&row_id, new_cell)?;
+        } else {
+          let cell_id = self.builder.push_child(&row_id, new_cell)?;
+          self.push_blocks(&cell_id, cell)?;
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+fn runs_to_delta(runs: &[DocxRun]) -> Value {
+  let ops = runs
+    .iter()
+    .map(|run| {
+      let mut attributes = Map::new();
+      if run.bold {
+        attributes.insert(BOLD_ATTR.to_string(), json!(true));
+      }
+      if run.italic {
+        attributes.insert(ITALIC_ATTR.to_string(), json!(true));
+      }
+      if run.underline {
+        attributes.insert(UNDERLINE_ATTR.to_string(), json!(true));
+      }
+      if run.strikethrough {
+        attributes.insert(STRIKETHROUGH_ATTR.to_string(), json!(true));
+      }
+      if run.code {
+        attributes.insert(CODE_ATTR.to_string(), json!(true));
+      }
+      if let Some(href) = &run.href {
+        attributes.insert(HREF_ATTR.to_string(), json!(href));
+      }
+
+      if attributes.is_empty() {
+        json!({ "insert": run.text })
+      } else {
+        json!({ "insert": run.text, "attributes": attributes })
+      }
+    })
+    .collect::<Vec<_>>();
+  Value::Array(ops)
+}
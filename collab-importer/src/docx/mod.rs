@@ -0,0 +1,5 @@
+mod importer;
+pub mod parser;
+mod xml;
+
+pub use importer::*;
@@ -0,0 +1,501 @@
+use crate::docx::xml::{XmlElement, parse_xml};
+use crate::error::ImporterError;
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+use zip::ZipArchive;
+
+const DOCUMENT_PART: &str = "word/document.xml";
+const DOCUMENT_RELS_PART: &str = "word/_rels/document.xml.rels";
+const NUMBERING_PART: &str = "word/numbering.xml";
+const STYLES_PART: &str = "word/styles.xml";
+
+/// Fonts that are used for inline code in Word documents.
+const MONOSPACE_FONTS: [&str; 6] = [
+  "consolas",
+  "courier",
+  "courier new",
+  "menlo",
+  "monaco",
+  "source code pro",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocxParagraphKind {
+  Paragraph,
+  Heading(u8),
+  Quote,
+  BulletedList,
+  NumberedList,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocxRun {
+  pub text: String,
+  pub bold: bool,
+  pub italic: bool,
+  pub underline: bool,
+  pub strikethrough: bool,
+  pub code: bool,
+  pub href: Option<String>,
+}
+
+impl DocxRun {
+  fn same_format(&self, other: &DocxRun) -> bool {
+    self.bold == other.bold
+      && self.italic == other.italic
+      && self.underline == other.underline
+      && self.strikethrough == other.strikethrough
+      && self.code == other.code
+      && self.href == other.href
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DocxBlock {
+  Paragraph {
+    kind: DocxParagraphKind,
+    /// The list nesting level, 0 for top level list items and other paragraphs.
+    level: usize,
+    runs: Vec<DocxRun>,
+  },
+  Image {
+    /// The path of the image inside the docx archive, e.g. `word/media/image1.png`.
+    media_path: String,
+  },
+  Table {
+    has_header_row: bool,
+    /// The width of each column in pixels.
+    column_widths: Vec<f64>,
+    rows: Vec<Vec<Vec<DocxBlock>>>,
+  },
+}
+
+/// The content of a docx file that is relevant for the import.
+pub struct DocxContent {
+  pub blocks: Vec<DocxBlock>,
+  /// The files in `word/media`, keyed by their path inside the archive.
+  pub media: HashMap<String, Vec<u8>>,
+}
+
+/// Parse the docx archive.
+pub fn parse_docx<R: Read + Seek>(reader: R) -> Result<DocxContent, ImporterError> {
+  let mut archive = ZipArchive::new(reader).map_err(|e| ImporterError::InvalidArchive(e.into()))?;
+
+  let document = read_part(&mut archive, DOCUMENT_PART)?.ok_or_else(|| {
+    ImporterError::InvalidFileType(format!("{} is missing, not a docx file", DOCUMENT_PART))
+  })?;
+  let relationships = read_part(&mut archive, DOCUMENT_RELS_PART)?
+    .map(|xml| parse_relationships(&xml))
+    .transpose()?
+    .unwrap_or_default();
+  let numbering = read_part(&mut archive, NUMBERING_PART)?
+    .map(|xml| Numbering::parse(&xml))
+    .transpose()?
+    .unwrap_or_default();
+  let styles = read_part(&mut archive, STYLES_PART)?
+    .map(|xml| Styles::parse(&xml))
+    .transpose()?
+    .unwrap_or_default();
+
+  let document = parse_xml(&document)?;
+  let body = document.child("body").ok_or_else(|| {
+    ImporterError::ParseDocxError("word/document.xml doesn't have a body".to_string())
+  })?;
+  let context = ParseContext {
+    relationships,
+    numbering,
+    styles,
+  };
+  let mut blocks = vec![];
+  context.parse_block_elements(&body.children, &mut blocks);
+
+  let mut media = HashMap::new();
+  for media_path in collect_media_paths(&blocks) {
+    if media.contains_key(&media_path) {
+      continue;
+    }
+    if let Ok(mut file) = archive.by_name(&media_path) {
+      let mut bytes = vec![];
+      file.read_to_end(&mut bytes)?;
+      media.insert(media_path, bytes);
+    }
+  }
+
+  Ok(DocxContent { blocks, media })
+}
+
+fn read_part<R: Read + Seek>(
+  archive: &mut ZipArchive<R>,
+  name: &str,
+) -> Result<Option<String>, ImporterError> {
+  match archive.by_name(name) {
+    Ok(mut file) => {
+      let mut content = String::new();
+      file.read_to_string(&mut content)?;
+      Ok(Some(content))
+    },
+    Err(zip::result::ZipError::FileNotFound) => Ok(None),
+    Err(e) => {
+      Err(ImporterError::InvalidArchive(e.into()).context(format!("Failed to read {}", name)))
+    },
+  }
+}
+
+fn collect_media_paths(blocks: &[DocxBlock]) -> Vec<String> {
+  let mut paths = vec![];
+  for block in blocks {
+    match block {
+      DocxBlock::Image { media_path } => paths.push(media_path.clone()),
+      DocxBlock::Table { rows, .. } => {
+        for cell in rows.iter().flatten() {
+          paths.extend(collect_media_paths(cell));
+        }
+      },
+      DocxBlock::Paragraph { .. } => {},
+    }
+  }
+  paths
+}
+
+/// Maps relationship ids to their target, e.g. `rId5` to `media/image1.png`.
+fn parse_relationships(xml: &str) -> Result<HashMap<String, String>, ImporterError> {
+  let root = parse_xml(xml)?;
+  Ok(
+    root
+      .children_named("Relationship")
+      .filter_map(|rel| {
+        let id = rel.attr("Id")?;
+        let target = rel.attr("Target")?;
+        Some((id.to_string(), target.to_string()))
+      })
+      .collect(),
+  )
+}
+
+#[derive(Default)]
+struct Numbering {
+  /// numId -> abstractNumId
+  nums: HashMap<String, String>,
+  /// (abstractNumId, ilvl) -> numFmt
+  formats: HashMap<(String, String), String>,
+}
+
+impl Numbering {
+  fn parse(xml: &str) -> Result<Self, ImporterError> {
+    let root = parse_xml(xml)?;
+    let mut numbering = Numbering::default();
+    for abstract_num in root.children_named("abstractNum") {
+      let Some(abstract_id) = abstract_num.attr("abstractNumId") else {
+        continue;
+      };
+      for lvl in abstract_num.children_named("lvl") {
+        if let (Some(ilvl), Some(format)) = (lvl.attr("ilvl"), lvl.child_val("numFmt")) {
+          numbering.formats.insert(
+            (abstract_id.to_string(), ilvl.to_string()),
+            format.to_string(),
+          );
+        }
+      }
+    }
+    for num in root.children_named("num") {
+      if let (Some(num_id), Some(abstract_id)) = (num.attr("numId"), num.child_val("abstractNumId"))
+      {
+        numbering
+          .nums
+          .insert(num_id.to_string(), abstract_id.to_string());
+      }
+    }
+    Ok(numbering)
+  }
+
+  fn is_bullet(&self, num_id: &str, ilvl: &str) -> bool {
+    self
+      .nums
+      .get(num_id)
+      .and_then(|abstract_id| self.formats.get(&(abstract_id.clone(), ilvl.to_string())))
+      .map(|format| format == "bullet")
+      // Without a numbering definition, fall back to a bulleted list.
+      .unwrap_or(true)
+  }
+}
+
+#[derive(Default)]
+struct Styles {
+  /// styleId -> lowercase style name, e.g. `Heading1` -> `heading 1`
+  names: HashMap<String, String>,
+  /// styleId -> the style it's based on
+  based_on: HashMap<String, String>,
+}
+
+impl Styles {
+  fn parse(xml: &str) -> Result<Self, ImporterError> {
+    let root = parse_xml(xml)?;
+    let mut styles = Styles::default();
+    for style in root.children_named("style") {
+      let Some(style_id) = style.attr("styleId") else {
+        continue;
+      };
+      if let Some(name) = style.child_val("name") {
+        styles
+          .names
+          .insert(style_id.to_string(), name.to_lowercase());
+      }
+      if let Some(based_on) = style.child_val("basedOn") {
+        styles
+          .based_on
+          .insert(style_id.to_string(), based_on.to_string());
+      }
+    }
+    Ok(styles)
+  }
+
+  /// Resolve the paragraph kind of the style, following the `basedOn` chain.
+  fn paragraph_kind(&self, style_id: &str) -> Option<DocxParagraphKind> {
+    let mut current = style_id.to_string();
+    // Guard against cycles in malformed documents.
+    for _ in 0..8 {
+      let name = self
+        .names
+        .get(&current)
+        .cloned()
+        .unwrap_or_else(|| current.to_lowercase());
+      if let Some(kind) = paragraph_kind_from_style_name(&name) {
+        return Some(kind);
+      }
+      current = self.based_on.get(&current)?.clone();
+    }
+    None
+  }
+}
+
+fn paragraph_kind_from_style_name(name: &str) -> Option<DocxParagraphKind> {
+  let name = name.replace(' ', "");
+  if name == "title" {
+    return Some(DocxParagraphKind::Heading(1));
+  }
+  if name == "subtitle" {
+    return Some(DocxParagraphKind::Heading(2));
+  }
+  if let Some(level) = name.strip_prefix("heading") {
+    let level = level.parse::<u8>().ok()?;
+    return Some(DocxParagraphKind::Heading(level.clamp(1, 6)));
+  }
+  if name == "quote" || name == "intensequote" {
+    return Some(DocxParagraphKind::Quote);
+  }
+  if name.starts_with("listbullet") {
+    return Some(DocxParagraphKind::BulletedList);
+  }
+  if name.starts_with("listnumber") {
+    return Some(DocxParagraphKind::NumberedList);
+  }
+  None
+}
+
+struct ParseContext {
+  relationships: HashMap<String, String>,
+  numbering: Numbering,
+  styles: Styles,
+}
+
+impl ParseContext {
+  fn parse_block_elements(&self, elements: &[XmlElement], blocks: &mut Vec<DocxBlock>) {
+    for element in elements {
+      match element.name.as_str() {
+        "p" => self.parse_paragraph(element, blocks),
+        "tbl" => blocks.push(self.parse_table(element)),
+        // Content controls and tracked insertions wrap regular block content.
+        "sdt" | "sdtContent" | "ins" | "customXml" => {
+          self.parse_block_elements(&element.children, blocks)
+        },
+        _ => {},
+      }
+    }
+  }
+
+  fn parse_paragraph(&self, paragraph: &XmlElement, blocks: &mut Vec<DocxBlock>) {
+    let properties = paragraph.child("pPr");
+    let mut kind = properties
+      .and_then(|p| p.child_val("pStyle"))
+      .and_then(|style_id| self.styles.paragraph_kind(style_id))
+      .unwrap_or(DocxParagraphKind::Paragraph);
+
+    if kind == DocxParagraphKind::Paragraph {
+      if let Some(level) = properties
+        .and_then(|p| p.child_val("outlineLvl"))
+        .and_then(|level| level.parse::<u8>().ok())
+        .filter(|level| *level < 6)
+      {
+        kind = DocxParagraphKind::Heading(level + 1);
+      }
+    }
+
+    let mut level = 0;
+    if let Some(num_pr) = properties.and_then(|p| p.child("numPr")) {
+      let num_id = num_pr.child_val("numId").unwrap_or("0");
+      let ilvl = num_pr.child_val("ilvl").unwrap_or("0");
+      // numId 0 removes the numbering inherited from the style.
+      if num_id != "0" {
+        level = ilvl.parse::<usize>().unwrap_or(0);
+        kind = if self.numbering.is_bullet(num_id, ilvl) {
+          DocxParagraphKind::BulletedList
+        } else {
+          DocxParagraphKind::NumberedList
+        };
+      }
+    }
+
+    let mut runs = vec![];
+    let mut images = vec![];
+    self.parse_inline_elements(&paragraph.children, None, &mut runs, &mut images);
+
+    // A paragraph that only holds images is replaced by the images.
+    if !(runs.is_empty() && !images.is_empty()) {
+      blocks.push(DocxBlock::Paragraph {
+        kind,
+        level,
+        runs: merge_runs(runs),
+      });
+    }
+    blocks.extend(
+      images
+        .into_iter()
+        .map(|media_path| DocxBlock::Image { media_path }),
+    );
+  }
+
+  fn parse_inline_elements(
+    &self,
+    elements: &[XmlElement],
+    href: Option<&str>,
+    runs: &mut Vec<DocxRun>,
+    images: &mut Vec<String>,
+  ) {
+    for element in elements {
+      match element.name.as_str() {
+        "r" => self.parse_run(element, href, runs, images),
+        "hyperlink" => {
+          let target = element
+            .attr("id")
+            .and_then(|id| self.relationships.get(id))
+            .map(|target| target.as_str());
+          self.parse_inline_elements(&element.children, target.or(href), runs, images);
+        },
+        "ins" | "smartTag" | "sdt" | "sdtContent" | "fldSimple" | "customXml" => {
+          self.parse_inline_elements(&element.children, href, runs, images)
+        },
+        _ => {},
+      }
+    }
+  }
+
+  fn parse_run(
+    &self,
+    run: &XmlElement,
+    href: Option<&str>,
+    runs: &mut Vec<DocxRun>,
+    images: &mut Vec<String>,
+  ) {
+    let properties = run.child("rPr");
+    let font = properties
+      .and_then(|p| p.child("rFonts"))
+      .and_then(|fonts| fonts.attr("ascii"))
+      .map(|font| font.to_lowercase());
+    let template = DocxRun {
+      text: String::new(),
+      bold: properties.is_some_and(|p| p.is_on("b")),
+      italic: properties.is_some_and(|p| p.is_on("i")),
+      underline: properties.is_some_and(|p| p.is_on("u")),
+      strikethrough: properties.is_some_and(|p| p.is_on("strike") || p.is_on("dstrike")),
+      code: font.is_some_and(|font| MONOSPACE_FONTS.contains(&font.as_str())),
+      href: href.map(|s| s.to_string()),
+    };
+
+    let mut text = String::new();
+    for child in run.children.iter() {
+      match child.name.as_str() {
+        "t" => text.push_str(&child.text),
+        "tab" => text.push('\t'),
+        "br" | "cr" => text.push('\n'),
+        "noBreakHyphen" => text.push('-'),
+        "drawing" | "pict" | "object" => {
+          let embed = child
+            .find("blip")
+            .and_then(|blip| blip.attr("embed"))
+            .or_else(|| child.find("imagedata").and_then(|data| data.attr("id")));
+          if let Some(target) = embed.and_then(|id| self.relationships.get(id)) {
+            images.push(resolve_part_path(target));
+          }
+        },
+        _ => {},
+      }
+    }
+
+    if !text.is_empty() {
+      runs.push(DocxRun { text, ..template });
+    }
+  }
+
+  fn parse_table(&self, table: &XmlElement) -> DocxBlock {
+    let column_widths = table
+      .child("tblGrid")
+      .map(|grid| {
+        grid
+          .children_named("gridCol")
+          .map(|col| {
+            col
+              .attr("w")
+              .and_then(|w| w.parse::<f64>().ok())
+              // Twentieths of a point to pixels at 96 dpi.
+              .map(|twips| (twips / 15.0).round())
+              .unwrap_or_default()
+          })
+          .collect()
+      })
+      .unwrap_or_default();
+
+    let rows = table
+      .children_named("tr")
+      .map(|row| {
+        row
+          .children_named("tc")
+          .map(|cell| {
+            let mut blocks = vec![];
+            self.parse_block_elements(&cell.children, &mut blocks);
+            blocks
+          })
+          .collect::<Vec<_>>()
+      })
+      .collect::<Vec<_>>();
+
+    let has_header_row = table
+      .child("tr")
+      .and_then(|row| row.child("trPr"))
+      .is_some_and(|properties| properties.is_on("tblHeader"));
+
+    DocxBlock::Table {
+      has_header_row,
+      column_widths,
+      rows,
+    }
+  }
+}
+
+/// Relationship targets are relative to the `word` folder.
+fn resolve_part_path(target: &str) -> String {
+  match target.strip_prefix('/') {
+    Some(absolute) => absolute.to_string(),
+    None => format!("word/{}", target.trim_start_matches("./")),
+  }
+}
+
+fn merge_runs(runs: Vec<DocxRun>) -> Vec<DocxRun> {
+  let mut merged: Vec<DocxRun> = Vec::with_capacity(runs.len());
+  for run in runs {
+    match merged.last_mut() {
+      Some(last) if last.same_format(&run) => last.text.push_str(&run.text),
+      _ => merged.push(run),
+    }
+  }
+  merged
+}
@@ -0,0 +1,135 @@
+use crate::error::ImporterError;
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+use std::collections::HashMap;
+
+/// A minimal in-memory xml element. Namespace prefixes are stripped from element and attribute
+/// names, `w:p` becomes `p` and `r:id` becomes `id`. The parts of a docx file that we read don't
+/// have conflicting local names, so the prefix is not needed.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct XmlElement {
+  pub name: String,
+  pub attributes: HashMap<String, String>,
+  pub children: Vec<XmlElement>,
+  /// The text directly inside this element.
+  pub text: String,
+}
+
+impl XmlElement {
+  pub fn attr(&self, name: &str) -> Option<&str> {
+    self.attributes.get(name).map(|s| s.as_str())
+  }
+
+  pub fn child(&self, name: &str) -> Option<&XmlElement> {
+    self.children.iter().find(|child| child.name == name)
+  }
+
+  pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+    self.children.iter().filter(move |child| child.name == name)
+  }
+
+  /// Find the first descendant with the given name, depth first.
+  pub fn find(&self, name: &str) -> Option<&XmlElement> {
+    for child in self.children.iter() {
+      if child.name == name {
+        return Some(child);
+      }
+      if let Some(found) = child.find(name) {
+        return Some(found);
+      }
+    }
+    None
+  }
+
+  /// The `val` attribute of the child with the given name, e.g. `<w:pStyle w:val="Heading1"/>`.
+  pub fn child_val(&self, name: &str) -> Option<&str> {
+    self.child(name).and_then(|child| child.attr("val"))
+  }
+
+  /// Return true if the child toggle property is on. `<w:b/>` and `<w:b w:val="true"/>` are on,
+  /// `<w:b w:val="0"/>` is off.
+  pub fn is_on(&self, name: &str) -> bool {
+    match self.child(name) {
+      None => false,
+      Some(child) => !matches!(child.attr("val"), Some("0") | Some("false") | Some("none")),
+    }
+  }
+}
+
+pub(crate) fn parse_xml(xml: &str) -> Result<XmlElement, ImporterError> {
+  let mut reader = Reader::from_str(xml);
+  let mut stack: Vec<XmlElement> = vec![XmlElement::default()];
+  loop {
+    let event = reader
+      .read_event()
+      .map_err(|err| ImporterError::ParseDocxError(err.to_string()))?;
+    match event {
+      Event::Start(start) => stack.push(element_from_start(&start)?),
+      Event::Empty(start) => {
+        let element = element_from_start(&start)?;
+        if let Some(parent) = stack.last_mut() {
+          parent.children.push(element);
+        }
+      },
+      Event::End(_) => {
+        let element = stack
+          .pop()
+          .ok_or_else(|| ImporterError::ParseDocxError("unexpected closing tag".to_string()))?;
+        match stack.last_mut() {
+          Some(parent) => parent.children.push(element),
+          None => {
+            return Err(ImporterError::ParseDocxError(
+              "unexpected closing tag".to_string(),
+            ));
+          },
+        }
+      },
+      Event::Text(text) => {
+        let text = text
+          .unescape()
+          .map_err(|err| ImporterError::ParseDocxError(err.to_string()))?;
+        if let Some(element) = stack.last_mut() {
+          element.text.push_str(&text);
+        }
+      },
+      Event::CData(data) => {
+        if let Some(element) = stack.last_mut() {
+          element
+            .text
+            .push_str(&String::from_utf8_lossy(data.into_inner().as_ref()));
+        }
+      },
+      Event::Eof => break,
+      _ => {},
+    }
+  }
+
+  // The document element is the only child of the synthetic root.
+  let mut root = stack
+    .pop()
+    .filter(|_| stack.is_empty())
+    .ok_or_else(|| ImporterError::ParseDocxError("unclosed xml element".to_string()))?;
+  root
+    .children
+    .pop()
+    .ok_or_else(|| ImporterError::ParseDocxError("empty xml document".to_string()))
+}
+
+fn element_from_start(start: &BytesStart) -> Result<XmlElement, ImporterError> {
+  let name = String::from_utf8_lossy(start.local_name().as_ref()).to_string();
+  let mut attributes = HashMap::new();
+  for attr in start.attributes().flatten() {
+    let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).to_string();
+    let value = attr
+      .unescape_value()
+      .map_err(|err| ImporterError::ParseDocxError(err.to_string()))?
+      .to_string();
+    attributes.insert(key, value);
+  }
+  Ok(XmlElement {
+    name,
+    attributes,
+    children: vec![],
+    text: String::new(),
+  })
+}
@@ -0,0 +1,216 @@
+use crate::imported_collab::{ImportType, ImportedCollabInfo};
+use collab::core::collab::{DataSource, default_client_id};
+use collab::core::origin::CollabOrigin;
+use collab_document::document::Document;
+use collab_entity::CollabType;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// The number of consecutive words hashed together.
+const SHINGLE_SIZE: usize = 5;
+
+/// The default similarity above which two pages are reported as near-duplicates.
+pub const DEFAULT_SIMILARITY_THRESHOLD: f64 = 0.8;
+
+/// The shingled hashes of the text of a page: the hashes of every run of [SHINGLE_SIZE]
+/// consecutive words. Pages that share most of their shingles have mostly the same content,
+/// even if some paragraphs were edited, added or removed.
+#[derive(Debug, Clone)]
+pub struct PageFingerprint {
+  pub view_id: String,
+  pub name: String,
+  pub word_count: usize,
+  shingles: HashSet<u64>,
+}
+
+impl PageFingerprint {
+  /// The text is compared case insensitively and ignoring punctuation.
+  pub fn from_text(view_id: &str, name: &str, text: &str) -> Self {
+    let words = text
+      .split(|c: char| !c.is_alphanumeric())
+      .filter(|word| !word.is_empty())
+      .map(|word| word.to_lowercase())
+      .collect::<Vec<_>>();
+    // A page shorter than a shingle is fingerprinted as a whole.
+    let shingles = words
+      .windows(SHINGLE_SIZE.min(words.len()).max(1))
+      .map(|shingle| fxhash::hash64(&shingle.join(" ")))
+      .collect();
+    Self {
+      view_id: view_id.to_string(),
+      name: name.to_string(),
+      word_count: words.len(),
+      shingles,
+    }
+  }
+
+  pub fn from_document(view_id: &str, name: &str, document: &Document) -> Self {
+    Self::from_text(view_id, name, &document.to_plain_text().join("\n"))
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.shingles.is_empty()
+  }
+
+  /// The Jaccard similarity of the shingles of the two pages, from 0.0 to 1.0.
+  pub fn similarity(&self, other: &PageFingerprint) -> f64 {
+    if self.is_empty() || other.is_empty() {
+      return 0.0;
+    }
+    let shared = self.shingles.intersection(&other.shingles).count();
+    jaccard(shared, self.shingles.len(), other.shingles.len())
+  }
+}
+
+/// Two pages whose contents are near-duplicates.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicatePagePair {
+  pub view_id: String,
+  pub name: String,
+  pub duplicate_view_id: String,
+  pub duplicate_name: String,
+  pub similarity: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DuplicatePageReport {
+  /// The near-duplicate pairs, the most similar first.
+  pub pairs: Vec<DuplicatePagePair>,
+}
+
+impl DuplicatePageReport {
+  pub fn is_empty(&self) -> bool {
+    self.pairs.is_empty()
+  }
+
+  /// Group the pages that are near-duplicates of each other, directly or through another page.
+  /// Each group is sorted by view id.
+  pub fn groups(&self) -> Vec<Vec<String>> {
+    let mut parents: HashMap<&str, &str> = HashMap::new();
+    for pair in self.pairs.iter() {
+      let root = find_root(&mut parents, &pair.view_id);
+      let duplicate_root = find_root(&mut parents, &pair.duplicate_view_id);
+      if root != duplicate_root {
+        parents.insert(duplicate_root, root);
+      }
+    }
+
+    let ids = parents.keys().copied().collect::<Vec<_>>();
+    let mut groups: HashMap<&str, Vec<String>> = HashMap::new();
+    for id in ids {
+      let root = find_root(&mut parents, id);
+      groups.entry(root).or_default().push(id.to_string());
+    }
+    let mut groups = groups
+      .into_values()
+      .map(|mut group| {
+        group.sort();
+        group
+      })
+      .collect::<Vec<_>>();
+    groups.sort();
+    groups
+  }
+}
+
+/// Report the pages whose similarity is at least `threshold`, see [PageFingerprint::similarity].
+/// Empty pages are never reported.
+pub fn find_near_duplicate_pages(
+  fingerprints: &[PageFingerprint],
+  threshold: f64,
+) -> DuplicatePageReport {
+  // Only compare the pages that share at least one shingle.
+  let mut pages_by_shingle: HashMap<u64, Vec<usize>> = HashMap::new();
+  for (index, fingerprint) in fingerprints.iter().enumerate() {
+    for shingle in fingerprint.shingles.iter() {
+      pages_by_shingle.entry(*shingle).or_default().push(index);
+    }
+  }
+  let mut shared_counts: HashMap<(usize, usize), usize> = HashMap::new();
+  for pages in pages_by_shingle.values() {
+    for (i, a) in pages.iter().enumerate() {
+      for b in pages[i + 1..].iter() {
+        *shared_counts.entry((*a, *b)).or_default() += 1;
+      }
+    }
+  }
+
+  let mut pairs = shared_counts
+    .into_iter()
+    .filter_map(|((a, b), shared)| {
+      let page = &fingerprints[a];
+      let duplicate = &fingerprints[b];
+      let similarity = jaccard(shared, page.shingles.len(), duplicate.shingles.len());
+      (similarity >= threshold).then(|| DuplicatePagePair {
+        view_id: page.view_id.clone(),
+        name: page.name.clone(),
+        duplicate_view_id: duplicate.view_id.clone(),
+        duplicate_name: duplicate.name.clone(),
+        similarity,
+      })
+    })
+    .collect::<Vec<_>>();
+  pairs.sort_by(|a, b| {
+    b.similarity
+      .total_cmp(&a.similarity)
+      .then_with(|| a.view_id.cmp(&b.view_id))
+      .then_with(|| a.duplicate_view_id.cmp(&b.duplicate_view_id))
+  });
+  DuplicatePageReport { pairs }
+}
+
+/// Fingerprint the documents of the imported collabs: the document pages and the row documents
+/// of the databases. Row documents are named after their database.
+pub fn fingerprint_imported_collabs(infos: &[ImportedCollabInfo]) -> Vec<PageFingerprint> {
+  let mut fingerprints = vec![];
+  for info in infos {
+    let document_ids = match &info.import_type {
+      ImportType::Document => vec![],
+      ImportType::Database {
+        row_document_ids, ..
+      } => row_document_ids.clone(),
+    };
+    for imported_collab in info.imported_collabs.iter() {
+      let is_page = match info.import_type {
+        ImportType::Document => imported_collab.collab_type == CollabType::Document,
+        ImportType::Database { .. } => document_ids.contains(&imported_collab.object_id),
+      };
+      if !is_page {
+        continue;
+      }
+      let document = Document::open_with_options(
+        CollabOrigin::Empty,
+        DataSource::DocStateV1(imported_collab.encoded_collab.doc_state.to_vec()),
+        &imported_collab.object_id,
+        default_client_id(),
+      );
+      if let Ok(document) = document {
+        fingerprints.push(PageFingerprint::from_document(
+          &imported_collab.object_id,
+          &info.name,
+          &document,
+        ));
+      }
+    }
+  }
+  fingerprints
+}
+
+/// Find the root of the group of the id, compressing the path to it.
+fn find_root<'a>(parents: &mut HashMap<&'a str, &'a str>, id: &'a str) -> &'a str {
+  let parent = *parents.entry(id).or_insert(id);
+  if parent == id {
+    return id;
+  }
+  let root = find_root(parents, parent);
+  parents.insert(id, root);
+  root
+}
+
+fn jaccard(shared: usize, len: usize, other_len: usize) -> f64 {
+  let union = len + other_len - shared;
+  if union == 0 {
+    return 0.0;
+  }
+  shared as f64 / union as f64
+}
@@ -0,0 +1,103 @@
+use crate::error::ImporterError;
+use crate::xhtml::{XhtmlNode, escape_html, parse_xhtml, write_checkbox, write_element};
+use std::collections::HashMap;
+use tracing::warn;
+
+/// An uploaded resource of a note, referenced by its hash in the ENML content.
+#[derive(Debug, Clone)]
+pub(crate) struct EnmlMedia {
+  pub url: String,
+  pub file_name: String,
+  pub is_image: bool,
+}
+
+/// Convert the ENML content of a note to html that the html importer understands:
+/// - `<en-media>` becomes an image, or a link for the other files, pointing to the uploaded
+///   resource with the same hash.
+/// - `<en-todo>` and the checklists of the recent Evernote versions become checkbox list items.
+/// - `<en-crypt>` is dropped since the encrypted text can't be read.
+pub(crate) fn enml_to_html(
+  enml: &str,
+  media: &HashMap<String, EnmlMedia>,
+) -> Result<String, ImporterError> {
+  let nodes = parse_xhtml(enml).map_err(ImporterError::ParseEnexError)?;
+  let mut html = String::with_capacity(enml.len());
+  write_nodes(&nodes, media, &mut html);
+  Ok(html)
+}
+
+fn write_nodes(nodes: &[XhtmlNode], media: &HashMap<String, EnmlMedia>, html: &mut String) {
+  for node in nodes {
+    write_node(node, media, html);
+  }
+}
+
+fn write_node(node: &XhtmlNode, media: &HashMap<String, EnmlMedia>, html: &mut String) {
+  let XhtmlNode::Element { name, children, .. } = node else {
+    if let XhtmlNode::Text(text) = node {
+      html.push_str(text);
+    }
+    return;
+  };
+
+  match name.as_str() {
+    "en-note" => {
+      html.push_str("<div>");
+      write_nodes(children, media, html);
+      html.push_str("</div>");
+    },
+    "en-media" => {
+      let Some(media) = node.attr("hash").and_then(|hash| media.get(hash)) else {
+        warn!("resource {:?} is not found in the note", node.attr("hash"));
+        return;
+      };
+      if media.is_image {
+        html.push_str(&format!(
+          "<img src=\"{}\" alt=\"{}\"/>",
+          escape_html(&media.url),
+          escape_html(&media.file_name)
+        ));
+      } else {
+        html.push_str(&format!(
+          "<a href=\"{}\">{}</a>",
+          escape_html(&media.url),
+          escape_html(&media.file_name)
+        ));
+      }
+    },
+    "en-todo" => write_checkbox(node.attr("checked") == Some("true"), html),
+    "en-crypt" => warn!("encrypted content is not imported"),
+    // A paragraph starting with a todo is a checklist item.
+    "div" | "p" if starts_with_todo(children) => {
+      html.push_str("<ul><li>");
+      write_nodes(children, media, html);
+      html.push_str("</li></ul>");
+    },
+    // The checklists of the recent versions are styled lists.
+    "li" if has_style(node, "--en-checked:") => {
+      html.push_str("<li>");
+      write_checkbox(has_style(node, "--en-checked:true"), html);
+      write_nodes(children, media, html);
+      html.push_str("</li>");
+    },
+    _ => write_element(node, html, |html| write_nodes(children, media, html)),
+  }
+}
+
+fn starts_with_todo(children: &[XhtmlNode]) -> bool {
+  children
+    .iter()
+    .find(|child| !child.is_blank())
+    .and_then(XhtmlNode::name)
+    == Some("en-todo")
+}
+
+fn has_style(node: &XhtmlNode, declaration: &str) -> bool {
+  node.attr("style").is_some_and(|style| {
+    style
+      .chars()
+      .filter(|c| !c.is_whitespace())
+      .collect::<String>()
+      .contains(declaration)
+  })
+}
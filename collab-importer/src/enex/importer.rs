@@ -0,0 +1,347 @@
+use crate::enex::enml::{EnmlMedia, enml_to_html};
+use crate::enex::parser::{EnexNote, parse_enex};
+use crate::error::{ImporterError, ImporterResultExt};
+use crate::imported_collab::{ImportType, ImportedCollab, ImportedCollabInfo};
+use crate::notion::page::CollabResource;
+use crate::util::{FileId, upload_file_url};
+use collab::core::collab::default_client_id;
+use collab_document::blocks::DocumentData;
+use collab_document::document::Document;
+use collab_document::document_data::default_document_collab_data;
+use collab_document::importer::md_importer::MDImporter;
+use collab_document::provenance::BlockProvenance;
+use collab_entity::CollabType;
+use collab_folder::ViewLayout;
+use collab_folder::hierarchy_builder::{NestedChildViewBuilder, NestedViews, ParentChildViews};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::warn;
+
+/// The name of the notes without a title.
+const UNTITLED_NOTE: &str = "Untitled";
+
+/// The fields of the page block data where the metadata of a note is stored.
+pub const TAGS_FIELD: &str = "tags";
+pub const CREATED_AT_FIELD: &str = "created_at";
+pub const UPDATED_AT_FIELD: &str = "updated_at";
+pub const AUTHOR_FIELD: &str = "author";
+pub const SOURCE_URL_FIELD: &str = "source_url";
+
+/// Imports Evernote exports (.enex files).
+///
+/// Each export is a notebook, imported as a folder page with one document per note. The ENML
+/// content of the notes is converted to blocks, the tags, dates, author and source url are
+/// stored in the data of the page block, and the attached files are written to the output
+/// directory and returned as resources of the imported collabs.
+pub struct EnexImporter {
+  uid: i64,
+  host: String,
+  workspace_id: String,
+}
+
+impl EnexImporter {
+  pub fn new<S: ToString>(uid: i64, workspace_id: S, host: String) -> Self {
+    Self {
+      uid,
+      host,
+      workspace_id: workspace_id.to_string(),
+    }
+  }
+
+  /// Import the .enex file as a notebook named after the file.
+  pub fn import_file<P: AsRef<Path>>(
+    &self,
+    file_path: P,
+    output_dir: P,
+  ) -> Result<EnexImportedInfo, ImporterError> {
+    let notebook = self.import_notebook_file(file_path.as_ref(), output_dir.as_ref())?;
+    Ok(self.imported_info(vec![notebook]))
+  }
+
+  /// Import each .enex file of the directory as a notebook, ordered by file name.
+  pub fn import_dir<P: AsRef<Path>>(
+    &self,
+    dir: P,
+    output_dir: P,
+  ) -> Result<EnexImportedInfo, ImporterError> {
+    let mut file_paths = std::fs::read_dir(dir.as_ref())?
+      .flatten()
+      .map(|entry| entry.path())
+      .filter(|path| {
+        path
+          .extension()
+          .is_some_and(|ext| ext.eq_ignore_ascii_case("enex"))
+      })
+      .collect::<Vec<_>>();
+    if file_paths.is_empty() {
+      return Err(ImporterError::CannotImport);
+    }
+    file_paths.sort();
+
+    let notebooks = file_paths
+      .iter()
+      .map(|file_path| self.import_notebook_file(file_path, output_dir.as_ref()))
+      .collect::<Result<Vec<_>, _>>()?;
+    Ok(self.imported_info(notebooks))
+  }
+
+  /// Import the content of an .enex file as a notebook with the given name.
+  pub fn import_notebook(
+    &self,
+    name: &str,
+    enex: &str,
+    output_dir: &Path,
+  ) -> Result<EnexNotebook, ImporterError> {
+    let view_id = uuid::Uuid::new_v4().to_string();
+    let notes = parse_enex(enex)?
+      .into_iter()
+      .map(|note| self.import_note(name, note, output_dir))
+      .collect::<Result<Vec<_>, _>>()?;
+
+    let encoded_collab = default_document_collab_data(&view_id, default_client_id())?;
+    let collab_info = ImportedCollabInfo {
+      name: name.to_string(),
+      imported_collabs: vec![ImportedCollab {
+        object_id: view_id.clone(),
+        collab_type: CollabType::Document,
+        encoded_collab,
+      }],
+      resources: vec![],
+      import_type: ImportType::Document,
+    };
+    Ok(EnexNotebook {
+      view_id,
+      name: name.to_string(),
+      notes,
+      collab_info,
+    })
+  }
+
+  fn import_notebook_file(
+    &self,
+    file_path: &Path,
+    output_dir: &Path,
+  ) -> Result<EnexNotebook, ImporterError> {
+    if !file_path.exists() {
+      return Err(ImporterError::FileNotFound);
+    }
+    let name = file_path
+      .file_stem()
+      .map(|stem| stem.to_string_lossy().to_string())
+      .unwrap_or_default();
+    let enex = std::fs::read_to_string(file_path)?;
+    self
+      .import_notebook(&name, &enex, output_dir)
+      .with_context(|| format!("import {}", file_path.display()))
+  }
+
+  fn import_note(
+    &self,
+    notebook: &str,
+    note: EnexNote,
+    output_dir: &Path,
+  ) -> Result<EnexImportedNote, ImporterError> {
+    let view_id = uuid::Uuid::new_v4().to_string();
+    let title = if note.title.is_empty() {
+      UNTITLED_NOTE.to_string()
+    } else {
+      note.title.clone()
+    };
+
+    let (media, files) = self.write_resources(&view_id, &note, output_dir)?;
+    let html = enml_to_html(&note.content, &media).unwrap_or_else(|err| {
+      // The html importer is lenient, the attached files are lost but the text is kept.
+      warn!("invalid ENML in note {}: {}", title, err);
+      note.content.clone()
+    });
+    let mut document_data = MDImporter::new(None)
+      .import_html(&view_id, &html)
+      .with_context(|| format!("import note {}", title))?;
+    write_note_metadata(&mut document_data, &note);
+    document_data.tag_provenance(&BlockProvenance::new(
+      "enex",
+      format!("{}/{}", notebook, title),
+    ));
+
+    let document = Document::create(&view_id, document_data, default_client_id())?;
+    let encoded_collab = document.encode_collab()?;
+    let collab_info = ImportedCollabInfo {
+      name: title.clone(),
+      imported_collabs: vec![ImportedCollab {
+        object_id: view_id.clone(),
+        collab_type: CollabType::Document,
+        encoded_collab,
+      }],
+      resources: vec![CollabResource {
+        object_id: view_id.clone(),
+        files,
+      }],
+      import_type: ImportType::Document,
+    };
+    Ok(EnexImportedNote {
+      view_id,
+      title,
+      tags: note.tags,
+      created_at: note.created_at,
+      updated_at: note.updated_at,
+      collab_info,
+    })
+  }
+
+  /// Write the resources of the note to `output_dir`, named after their hash, and return the
+  /// uploaded media keyed by hash with the written files.
+  fn write_resources(
+    &self,
+    view_id: &str,
+    note: &EnexNote,
+    output_dir: &Path,
+  ) -> Result<(HashMap<String, EnmlMedia>, Vec<String>), ImporterError> {
+    let mut media = HashMap::new();
+    let mut files = vec![];
+    if note.resources.is_empty() {
+      return Ok((media, files));
+    }
+    let media_dir = output_dir.join(view_id);
+    std::fs::create_dir_all(&media_dir)?;
+    for resource in note.resources.iter() {
+      if media.contains_key(&resource.hash) {
+        continue;
+      }
+      let ext = resource.extension();
+      let file_path = media_dir.join(format!("{}.{}", resource.hash, ext));
+      std::fs::write(&file_path, &resource.data)?;
+
+      let file_id = FileId::from_bytes(&resource.data, ext);
+      let url = upload_file_url(&self.host, &self.workspace_id, view_id, &file_id);
+      let file_name = resource
+        .file_name
+        .clone()
+        .unwrap_or_else(|| file_id.clone());
+      media.insert(
+        resource.hash.clone(),
+        EnmlMedia {
+          url,
+          file_name,
+          is_image: resource.is_image(),
+        },
+      );
+      files.push(file_path.to_string_lossy().to_string());
+    }
+    Ok((media, files))
+  }
+
+  fn imported_info(&self, notebooks: Vec<EnexNotebook>) -> EnexImportedInfo {
+    EnexImportedInfo {
+      uid: self.uid,
+      workspace_id: self.workspace_id.clone(),
+      notebooks,
+    }
+  }
+}
+
+/// The notebooks and notes imported by [EnexImporter].
+#[derive(Debug, Clone)]
+pub struct EnexImportedInfo {
+  pub uid: i64,
+  pub workspace_id: String,
+  pub notebooks: Vec<EnexNotebook>,
+}
+
+impl EnexImportedInfo {
+  pub fn num_of_notes(&self) -> usize {
+    self
+      .notebooks
+      .iter()
+      .map(|notebook| notebook.notes.len())
+      .sum()
+  }
+
+  /// The views of the notebooks, each with the views of its notes, under the workspace.
+  pub fn build_nested_views(&self) -> NestedViews {
+    let views = self
+      .notebooks
+      .iter()
+      .map(|notebook| {
+        let notes = notebook
+          .notes
+          .iter()
+          .map(|note| {
+            NestedChildViewBuilder::new(self.uid, notebook.view_id.clone())
+              .with_view_id(&note.view_id)
+              .with_name(&note.title)
+              .with_layout(ViewLayout::Document)
+              .build()
+          })
+          .collect::<Vec<ParentChildViews>>();
+        NestedChildViewBuilder::new(self.uid, self.workspace_id.clone())
+          .with_view_id(&notebook.view_id)
+          .with_name(&notebook.name)
+          .with_layout(ViewLayout::Document)
+          .with_children(notes)
+          .build()
+      })
+      .collect();
+    NestedViews { views }
+  }
+
+  /// The collabs of the notebooks and of their notes, each notebook before its notes.
+  pub fn into_collab_infos(self) -> Vec<ImportedCollabInfo> {
+    self
+      .notebooks
+      .into_iter()
+      .flat_map(|notebook| {
+        std::iter::once(notebook.collab_info)
+          .chain(notebook.notes.into_iter().map(|note| note.collab_info))
+      })
+      .collect()
+  }
+}
+
+/// An imported notebook, a folder page whose children are the notes.
+#[derive(Debug, Clone)]
+pub struct EnexNotebook {
+  pub view_id: String,
+  pub name: String,
+  pub notes: Vec<EnexImportedNote>,
+  pub collab_info: ImportedCollabInfo,
+}
+
+#[derive(Debug, Clone)]
+pub struct EnexImportedNote {
+  pub view_id: String,
+  pub title: String,
+  pub tags: Vec<String>,
+  /// The creation time, in seconds.
+  pub created_at: Option<i64>,
+  /// The last modification time, in seconds.
+  pub updated_at: Option<i64>,
+  pub collab_info: ImportedCollabInfo,
+}
+
+fn write_note_metadata(document_data: &mut DocumentData, note: &EnexNote) {
+  let Some(page) = document_data.blocks.get_mut(&document_data.page_id) else {
+    return;
+  };
+  if !note.tags.is_empty() {
+    page.data.insert(TAGS_FIELD.to_string(), json!(note.tags));
+  }
+  if let Some(created_at) = note.created_at {
+    page
+      .data
+      .insert(CREATED_AT_FIELD.to_string(), json!(created_at));
+  }
+  if let Some(updated_at) = note.updated_at {
+    page
+      .data
+      .insert(UPDATED_AT_FIELD.to_string(), json!(updated_at));
+  }
+  if let Some(author) = &note.author {
+    page.data.insert(AUTHOR_FIELD.to_string(), json!(author));
+  }
+  if let Some(source_url) = &note.source_url {
+    page
+      .data
+      .insert(SOURCE_URL_FIELD.to_string(), json!(source_url));
+  }
+}
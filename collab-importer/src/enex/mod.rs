@@ -0,0 +1,5 @@
+mod enml;
+mod importer;
+pub mod parser;
+
+pub use importer::*;
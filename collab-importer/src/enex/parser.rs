@@ -0,0 +1,185 @@
+use crate::error::ImporterError;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chrono::NaiveDateTime;
+use md5::{Digest, Md5};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+/// A note of an Evernote export.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnexNote {
+  pub title: String,
+  /// The ENML content of the note, an XHTML document whose root element is `<en-note>`.
+  pub content: String,
+  /// The creation time, in seconds.
+  pub created_at: Option<i64>,
+  /// The last modification time, in seconds.
+  pub updated_at: Option<i64>,
+  pub tags: Vec<String>,
+  pub author: Option<String>,
+  pub source_url: Option<String>,
+  pub resources: Vec<EnexResource>,
+}
+
+/// A file attached to a note, e.g. an image. The content of the note references it by hash with
+/// an `<en-media>` element.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnexResource {
+  pub mime: String,
+  pub file_name: Option<String>,
+  pub data: Vec<u8>,
+  /// The md5 hash of the data in lowercase hex.
+  pub hash: String,
+}
+
+impl EnexResource {
+  pub fn is_image(&self) -> bool {
+    self.mime.starts_with("image/")
+  }
+
+  /// The extension of the file name, or the subtype of the mime type, e.g. `png`.
+  pub fn extension(&self) -> String {
+    self
+      .file_name
+      .as_deref()
+      .and_then(|name| name.rsplit_once('.'))
+      .map(|(_, ext)| ext.to_string())
+      .or_else(|| {
+        self
+          .mime
+          .split_once('/')
+          .map(|(_, subtype)| subtype.to_string())
+      })
+      .unwrap_or_default()
+  }
+}
+
+/// Parse the notes of an .enex file.
+pub fn parse_enex(xml: &str) -> Result<Vec<EnexNote>, ImporterError> {
+  let mut reader = Reader::from_str(xml);
+  let mut notes = vec![];
+  let mut note: Option<EnexNote> = None;
+  let mut resource: Option<EnexResource> = None;
+  // The names of the open elements, the innermost last.
+  let mut path: Vec<String> = vec![];
+  let mut text = String::new();
+  loop {
+    let event = reader
+      .read_event()
+      .map_err(|err| ImporterError::ParseEnexError(err.to_string()))?;
+    match event {
+      Event::Start(start) => {
+        let name = String::from_utf8_lossy(start.local_name().as_ref()).to_string();
+        match name.as_str() {
+          "note" => note = Some(EnexNote::default()),
+          "resource" => resource = Some(EnexResource::default()),
+          _ => {},
+        }
+        path.push(name);
+        text.clear();
+      },
+      Event::Text(value) => {
+        let value = value
+          .unescape()
+          .map_err(|err| ImporterError::ParseEnexError(err.to_string()))?;
+        text.push_str(&value);
+      },
+      Event::CData(data) => text.push_str(&String::from_utf8_lossy(&data.into_inner())),
+      Event::End(_) => {
+        let name = path
+          .pop()
+          .ok_or_else(|| ImporterError::ParseEnexError("unexpected closing tag".to_string()))?;
+        let parent = path.last().map(String::as_str).unwrap_or_default();
+        let value = std::mem::take(&mut text);
+        match (parent, name.as_str()) {
+          ("en-export", "note") => notes.extend(note.take()),
+          ("note", "resource") => {
+            if let (Some(note), Some(mut resource)) = (note.as_mut(), resource.take()) {
+              resource.hash = hex::encode(Md5::digest(&resource.data));
+              note.resources.push(resource);
+            }
+          },
+          (_, "data") => {
+            if let Some(resource) = resource.as_mut() {
+              let base64 = value
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect::<String>();
+              resource.data = STANDARD
+                .decode(base64)
+                .map_err(|err| ImporterError::ParseEnexError(err.to_string()))?;
+            }
+          },
+          (_, "mime") => {
+            if let Some(resource) = resource.as_mut() {
+              resource.mime = value.trim().to_string();
+            }
+          },
+          ("resource-attributes", "file-name") => {
+            if let Some(resource) = resource.as_mut() {
+              resource.file_name = non_empty(value);
+            }
+          },
+          (_, _) if resource.is_some() => {},
+          ("note", "title") => {
+            if let Some(note) = note.as_mut() {
+              note.title = value.trim().to_string();
+            }
+          },
+          ("note", "content") => {
+            if let Some(note) = note.as_mut() {
+              note.content = value;
+            }
+          },
+          ("note", "created") => {
+            if let Some(note) = note.as_mut() {
+              note.created_at = parse_enex_date(&value);
+            }
+          },
+          ("note", "updated") => {
+            if let Some(note) = note.as_mut() {
+              note.updated_at = parse_enex_date(&value);
+            }
+          },
+          ("note", "tag") => {
+            if let Some(note) = note.as_mut() {
+              note.tags.extend(non_empty(value));
+            }
+          },
+          ("note-attributes", "author") => {
+            if let Some(note) = note.as_mut() {
+              note.author = non_empty(value);
+            }
+          },
+          ("note-attributes", "source-url") => {
+            if let Some(note) = note.as_mut() {
+              note.source_url = non_empty(value);
+            }
+          },
+          _ => {},
+        }
+      },
+      Event::Eof => break,
+      _ => {},
+    }
+  }
+  if !path.is_empty() {
+    return Err(ImporterError::ParseEnexError(
+      "unclosed xml element".to_string(),
+    ));
+  }
+  Ok(notes)
+}
+
+/// Parse a date of an export, e.g. `20240304T101500Z`, and return it in seconds.
+fn parse_enex_date(value: &str) -> Option<i64> {
+  NaiveDateTime::parse_from_str(value.trim(), "%Y%m%dT%H%M%SZ")
+    .ok()
+    .map(|date_time| date_time.and_utc().timestamp())
+}
+
+fn non_empty(value: String) -> Option<String> {
+  let value = value.trim();
+  (!value.is_empty()).then(|| value.to_string())
+}
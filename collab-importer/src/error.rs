@@ -1,3 +1,4 @@
+use collab_document::error::ErrorCategory;
 use std::str::Utf8Error;
 
 #[derive(Debug, thiserror::Error)]
@@ -11,6 +12,12 @@ pub enum ImporterError {
   #[error("{0}")]
   InvalidFileType(String),
 
+  #[error("Invalid zip archive: {0}")]
+  InvalidArchive(#[source] anyhow::Error),
+
+  #[error("Unsafe zip archive: {0}")]
+  UnsafeArchive(#[from] UnsafeArchiveError),
+
   #[error(transparent)]
   ImportMarkdownError(#[from] collab_document::error::DocumentError),
 
@@ -20,6 +27,34 @@ pub enum ImporterError {
   #[error("Parse markdown error: {0}")]
   ParseMarkdownError(markdown::message::Message),
 
+  #[error("Parse docx error: {0}")]
+  ParseDocxError(String),
+
+  #[error("Parse enex error: {0}")]
+  ParseEnexError(String),
+
+  #[error("Parse confluence export error: {0}")]
+  ParseConfluenceError(String),
+
+  #[error("Parse trello export error: {0}")]
+  ParseTrelloError(String),
+
+  #[error("Parse roam export error: {0}")]
+  ParseRoamError(String),
+
+  #[error("Parse opml error: {0}")]
+  ParseOpmlError(String),
+
+  #[error("Parse bibtex error: {0}")]
+  ParseBibtexError(String),
+
+  /// A request to the Notion API failed. The status is missing when the request couldn't be sent.
+  #[error("Notion API error: {message}")]
+  NotionApiError {
+    status: Option<u16>,
+    message: String,
+  },
+
   #[error(transparent)]
   Utf8Error(#[from] Utf8Error),
 
@@ -32,6 +67,148 @@ pub enum ImporterError {
   #[error("Can not import file")]
   CannotImport,
 
+  #[error("Import cancelled")]
+  Cancelled,
+
   #[error(transparent)]
   Internal(#[from] anyhow::Error),
+
+  /// An error with a description of what was being done when it happened, see
+  /// [ImporterError::context].
+  #[error("{context}: {source}")]
+  Context {
+    context: String,
+    #[source]
+    source: Box<ImporterError>,
+  },
+}
+
+/// Why an archive was rejected by [crate::zip_tool::safe_unzip::safe_unzip], see
+/// [crate::zip_tool::safe_unzip::UnzipLimits].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum UnsafeArchiveError {
+  #[error("more than {limit} files")]
+  TooManyFiles { limit: usize },
+
+  #[error("more than {limit} bytes once extracted")]
+  TooLarge { limit: u64 },
+
+  #[error("{file} is compressed more than {limit} times")]
+  CompressionRatioTooHigh { file: String, limit: u64 },
+}
+
+impl ImporterError {
+  /// Wrap the error with a description of what was being done, e.g. the file being imported.
+  /// The code and the category of the error are the ones of the wrapped error.
+  pub fn context<C: Into<String>>(self, context: C) -> Self {
+    ImporterError::Context {
+      context: context.into(),
+      source: Box::new(self),
+    }
+  }
+
+  /// Return the error without its contexts.
+  pub fn root(&self) -> &ImporterError {
+    match self {
+      ImporterError::Context { source, .. } => source.root(),
+      _ => self,
+    }
+  }
+
+  /// A stable code identifying the error, which doesn't change when the message is reworded.
+  pub fn code(&self) -> &'static str {
+    match self {
+      ImporterError::InvalidPath(_) => "importer.invalid_path",
+      ImporterError::InvalidPathFormat => "importer.invalid_path_format",
+      ImporterError::InvalidFileType(_) => "importer.invalid_file_type",
+      ImporterError::InvalidArchive(_) => "importer.invalid_archive",
+      ImporterError::UnsafeArchive(_) => "importer.unsafe_archive",
+      ImporterError::ImportMarkdownError(err) => err.code(),
+      ImporterError::ImportCsvError(_) => "importer.invalid_csv",
+      ImporterError::ParseMarkdownError(_) => "importer.parse_markdown_failed",
+      ImporterError::ParseDocxError(_) => "importer.parse_docx_failed",
+      ImporterError::ParseEnexError(_) => "importer.parse_enex_failed",
+      ImporterError::ParseConfluenceError(_) => "importer.parse_confluence_failed",
+      ImporterError::ParseTrelloError(_) => "importer.parse_trello_failed",
+      ImporterError::ParseRoamError(_) => "importer.parse_roam_failed",
+      ImporterError::ParseOpmlError(_) => "importer.parse_opml_failed",
+      ImporterError::NotionApiError { .. } => "importer.notion_api_failed",
+      ImporterError::Utf8Error(_) => "importer.invalid_utf8",
+      ImporterError::IOError(_) => "importer.io",
+      ImporterError::FileNotFound => "importer.file_not_found",
+      ImporterError::CannotImport => "importer.nothing_to_import",
+      ImporterError::Cancelled => "importer.cancelled",
+      ImporterError::Internal(_) => "importer.internal",
+      ImporterError::Context { source, .. } => source.code(),
+    }
+  }
+
+  pub fn category(&self) -> ErrorCategory {
+    match self {
+      ImporterError::InvalidFileType(_)
+      | ImporterError::InvalidArchive(_)
+      | ImporterError::UnsafeArchive(_)
+      | ImporterError::ImportCsvError(_)
+      | ImporterError::ParseMarkdownError(_)
+      | ImporterError::ParseDocxError(_)
+      | ImporterError::ParseEnexError(_)
+      | ImporterError::ParseConfluenceError(_)
+      | ImporterError::ParseTrelloError(_)
+      | ImporterError::ParseRoamError(_)
+      | ImporterError::ParseOpmlError(_)
+      | ImporterError::Utf8Error(_)
+      | ImporterError::CannotImport => ErrorCategory::SourceFormat,
+      ImporterError::InvalidPath(_)
+      | ImporterError::InvalidPathFormat
+      | ImporterError::FileNotFound => ErrorCategory::Resource,
+      ImporterError::IOError(err) if err.kind() == std::io::ErrorKind::NotFound => {
+        ErrorCategory::Resource
+      },
+      // The import can be started again.
+      ImporterError::IOError(_) | ImporterError::Cancelled => ErrorCategory::Storage,
+      ImporterError::NotionApiError { status, .. } => match status {
+        // The request can be sent again, e.g. once the rate limit is reset.
+        None | Some(429) | Some(500..) => ErrorCategory::Storage,
+        Some(401) | Some(403) | Some(404) => ErrorCategory::Resource,
+        Some(_) => ErrorCategory::SourceFormat,
+      },
+      ImporterError::ImportMarkdownError(err) => err.category(),
+      ImporterError::Internal(_) => ErrorCategory::Internal,
+      ImporterError::Context { source, .. } => source.category(),
+    }
+  }
+
+  pub fn is_retryable(&self) -> bool {
+    self.category().is_retryable()
+  }
+
+  /// Return the messages of the contexts, from the outermost, followed by the message of the
+  /// root error.
+  pub fn context_chain(&self) -> Vec<String> {
+    let mut chain = vec![];
+    let mut error = self;
+    while let ImporterError::Context { context, source } = error {
+      chain.push(context.clone());
+      error = source.as_ref();
+    }
+    chain.push(error.to_string());
+    chain
+  }
+}
+
+/// Add a context to the error of a result, see [ImporterError::context].
+pub trait ImporterResultExt<T> {
+  fn context<C: Into<String>>(self, context: C) -> Result<T, ImporterError>;
+
+  fn with_context<C: Into<String>, F: FnOnce() -> C>(self, f: F) -> Result<T, ImporterError>;
+}
+
+impl<T, E: Into<ImporterError>> ImporterResultExt<T> for Result<T, E> {
+  fn context<C: Into<String>>(self, context: C) -> Result<T, ImporterError> {
+    self.map_err(|err| err.into().context(context))
+  }
+
+  fn with_context<C: Into<String>, F: FnOnce() -> C>(self, f: F) -> Result<T, ImporterError> {
+    self.map_err(|err| err.into().context(f()))
+  }
 }
@@ -0,0 +1,158 @@
+use crate::error::ImporterError;
+use crate::xhtml::escape_html;
+use collab::util::AnyMapExt;
+use collab_database::database::Database;
+use collab_database::error::DatabaseError;
+use collab_database::fields::TypeOptionCellReader;
+use collab_database::rows::Cell;
+use collab_database::template::entity::CELL_DATA;
+use collab_document::block_parser::{
+  DefaultDocumentTextExtractor, DocumentParser, DocumentTextExtractor, OutputFormat, ParseContext,
+};
+use collab_document::blocks::{BlockType, DocumentData};
+
+/// A card of a study deck, exported by [write_anki_csv].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flashcard {
+  pub front: String,
+  pub back: String,
+  pub tags: Vec<String>,
+}
+
+/// The flashcards of a document: the summary of each toggle list is the front of a card and its
+/// children are the back. The toggles nested in a card are part of its back. Each card is tagged
+/// with the heading it is under, if any.
+///
+/// The toggles without a summary or without children are skipped.
+pub fn flashcards_from_document(data: &DocumentData) -> Result<Vec<Flashcard>, ImporterError> {
+  let parser = DocumentParser::with_default_parsers();
+  let context = ParseContext::new(data, &parser, OutputFormat::PlainText);
+  let mut cards = vec![];
+  let mut heading = None;
+  let mut stack = vec![data.page_id.as_str()];
+  while let Some(block_id) = stack.pop() {
+    let Some(block) = data.blocks.get(block_id) else {
+      continue;
+    };
+    let children = data
+      .meta
+      .children_map
+      .get(&block.children)
+      .map(|children| children.as_slice())
+      .unwrap_or_default();
+    if block.ty == BlockType::Heading.as_str() {
+      let text = DefaultDocumentTextExtractor.extract_text_from_block(block, &context)?;
+      heading = Some(text.trim().to_string()).filter(|text| !text.is_empty());
+    } else if block.ty == BlockType::ToggleList.as_str() {
+      let front = DefaultDocumentTextExtractor.extract_text_from_block(block, &context)?;
+      let mut back = vec![];
+      for child in children.iter().filter_map(|id| data.blocks.get(id)) {
+        let text = parser.parse_block(child, &context)?;
+        if !text.trim().is_empty() {
+          back.push(text.trim_end().to_string());
+        }
+      }
+      if !front.trim().is_empty() && !back.is_empty() {
+        cards.push(Flashcard {
+          front: front.trim().to_string(),
+          back: back.join("\n"),
+          tags: heading.iter().map(|heading| tag_name(heading)).collect(),
+        });
+      }
+      continue;
+    }
+    stack.extend(children.iter().rev().map(String::as_str));
+  }
+  Ok(cards)
+}
+
+/// The flashcards of a database, one card per row in the order of the rows. The front is the
+/// cell of `front_field_id`, the primary field by default, and the back is the cell of
+/// `back_field_id`, the first other field by default.
+///
+/// The rows whose front or back cell is empty are skipped.
+pub async fn flashcards_from_database(
+  database: &Database,
+  front_field_id: Option<&str>,
+  back_field_id: Option<&str>,
+) -> Result<Vec<Flashcard>, ImporterError> {
+  let fields = database.get_all_fields();
+  let front_field = match front_field_id {
+    Some(field_id) => fields.iter().find(|field| field.id == field_id),
+    None => fields.iter().find(|field| field.is_primary),
+  }
+  .ok_or_else(|| DatabaseError::NoRequiredData("the front field of the flashcards".to_string()))?;
+  let back_field = match back_field_id {
+    Some(field_id) => fields.iter().find(|field| field.id == field_id),
+    None => fields.iter().find(|field| field.id != front_field.id),
+  }
+  .ok_or_else(|| DatabaseError::NoRequiredData("the back field of the flashcards".to_string()))?;
+
+  let front_reader = database.get_cell_reader(&front_field.id);
+  let back_reader = database.get_cell_reader(&back_field.id);
+  let mut cards = vec![];
+  for row in database.collect_all_rows(false).await.into_iter().flatten() {
+    let front = cell_text(front_reader.as_deref(), row.cells.get(&front_field.id));
+    let back = cell_text(back_reader.as_deref(), row.cells.get(&back_field.id));
+    if !front.trim().is_empty() && !back.trim().is_empty() {
+      cards.push(Flashcard {
+        front: front.trim().to_string(),
+        back: back.trim().to_string(),
+        tags: vec![],
+      });
+    }
+  }
+  Ok(cards)
+}
+
+/// The cards as the text file Anki imports: the front, the back and the tags columns of each
+/// card, preceded by the header lines telling Anki how to read the file. The fields are HTML, so
+/// the line breaks of the cards are kept.
+///
+/// The cards are put in `deck` when given, in the deck chosen in the import dialog otherwise.
+pub fn write_anki_csv(cards: &[Flashcard], deck: Option<&str>) -> Result<String, ImporterError> {
+  let mut content = String::from("#separator:Comma\n#html:true\n#tags column:3\n");
+  if let Some(deck) = deck {
+    content.push_str(&format!("#deck:{}\n", deck.replace(['\r', '\n'], " ")));
+  }
+
+  let mut writer = csv::Writer::from_writer(vec![]);
+  for card in cards {
+    writer
+      .write_record([
+        anki_field(&card.front),
+        anki_field(&card.back),
+        card.tags.join(" "),
+      ])
+      .map_err(|err| ImporterError::Internal(err.into()))?;
+  }
+  let records = writer
+    .into_inner()
+    .map_err(|err| ImporterError::Internal(anyhow::anyhow!(err.to_string())))?;
+  content.push_str(&String::from_utf8_lossy(&records));
+  Ok(content)
+}
+
+fn anki_field(text: &str) -> String {
+  escape_html(text).replace('\n', "<br>")
+}
+
+/// Anki separates the tags by spaces.
+fn tag_name(text: &str) -> String {
+  text.split_whitespace().collect::<Vec<_>>().join("_")
+}
+
+/// The text of the cell, the raw [CELL_DATA] when the reader returns nothing.
+fn cell_text(reader: Option<&dyn TypeOptionCellReader>, cell: Option<&Cell>) -> String {
+  let Some(cell) = cell else {
+    return String::new();
+  };
+  let text = reader
+    .map(|reader| reader.stringify_cell(cell))
+    .unwrap_or_default();
+  if text.is_empty() {
+    cell.get_as::<String>(CELL_DATA).unwrap_or_default()
+  } else {
+    text
+  }
+}
@@ -0,0 +1,362 @@
+use crate::error::{ImporterError, ImporterResultExt};
+use crate::html_folder::links::{html_title, rewrite_links};
+use crate::imported_collab::{ImportType, ImportedCollab, ImportedCollabInfo};
+use crate::name_collision::{
+  ExistingView, NameCollisionAction, NameCollisionPolicy, NameCollisionReport,
+  NameCollisionResolver,
+};
+use crate::notion::page::CollabResource;
+use crate::util::{FileId, upload_file_url};
+use collab::core::collab::default_client_id;
+use collab_document::document::Document;
+use collab_document::document_data::default_document_collab_data;
+use collab_document::importer::html::decode_entities;
+use collab_document::importer::md_importer::MDImporter;
+use collab_document::provenance::BlockProvenance;
+use collab_entity::CollabType;
+use collab_folder::ViewLayout;
+use collab_folder::hierarchy_builder::{NestedChildViewBuilder, NestedViews, ParentChildViews};
+use percent_encoding::percent_decode_str;
+use std::path::{Path, PathBuf};
+
+const HTML_EXTENSIONS: [&str; 2] = ["html", "htm"];
+const UNTITLED_PAGE: &str = "Untitled";
+
+/// Imports a directory of html files with their assets, e.g. the notes exported from Apple Notes
+/// or the pages saved by SingleFile.
+///
+/// Each html file is imported as a page, and each directory containing html files as a folder
+/// page with the pages of the directory as children. A directory named after an html file, e.g.
+/// `Trip/` next to `Trip.html`, holds the child pages of that file. The directories without html
+/// files are assets: the images and the files linked by the pages are returned as resources of
+/// the imported collabs and their links are replaced by the url they are uploaded to.
+pub struct HtmlFolderImporter {
+  uid: i64,
+  host: String,
+  workspace_id: String,
+}
+
+impl HtmlFolderImporter {
+  pub fn new<S: ToString>(uid: i64, workspace_id: S, host: String) -> Self {
+    Self {
+      uid,
+      host,
+      workspace_id: workspace_id.to_string(),
+    }
+  }
+
+  pub fn import_dir<P: AsRef<Path>>(
+    &self,
+    export_dir: P,
+  ) -> Result<HtmlFolderImportedInfo, ImporterError> {
+    let export_dir = export_dir.as_ref();
+    if !export_dir.is_dir() {
+      return Err(ImporterError::FileNotFound);
+    }
+    let export_dir = export_dir.canonicalize()?;
+    let name = export_dir
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_else(|| UNTITLED_PAGE.to_string());
+    let pages = self.import_entries(&export_dir, &export_dir)?;
+
+    let view_id = uuid::Uuid::new_v4().to_string();
+    let collab_info = folder_collab_info(&view_id, &name)?;
+    Ok(HtmlFolderImportedInfo {
+      uid: self.uid,
+      workspace_id: self.workspace_id.clone(),
+      view_id,
+      name,
+      pages,
+      collab_info,
+      collision: NameCollisionAction::Imported,
+    })
+  }
+
+  /// Import the html files and the directories with html files of `dir`, ordered by name.
+  fn import_entries(
+    &self,
+    dir: &Path,
+    export_dir: &Path,
+  ) -> Result<Vec<HtmlFolderPage>, ImporterError> {
+    let mut html_files = vec![];
+    let mut dirs = vec![];
+    for entry in std::fs::read_dir(dir)? {
+      let entry = entry?;
+      let path = entry.path();
+      if entry.file_name().to_string_lossy().starts_with('.') {
+        continue;
+      }
+      // The symlinks are skipped, they could point outside of the export.
+      let file_type = entry.file_type()?;
+      if file_type.is_file() && is_html_file(&path) {
+        html_files.push(path);
+      } else if file_type.is_dir() && contains_html_files(&path) {
+        dirs.push(path);
+      }
+    }
+    html_files.sort();
+    dirs.sort();
+
+    let mut pages = vec![];
+    for path in html_files {
+      let mut page = self.import_html_file(&path, export_dir)?;
+      if let Some(index) = dirs
+        .iter()
+        .position(|dir| dir.file_name() == path.file_stem())
+      {
+        let dir = dirs.remove(index);
+        page.children = self.import_entries(&dir, export_dir)?;
+      }
+      pages.push(page);
+    }
+    for dir in dirs {
+      let title = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| UNTITLED_PAGE.to_string());
+      let view_id = uuid::Uuid::new_v4().to_string();
+      let collab_info = folder_collab_info(&view_id, &title)?;
+      let children = self.import_entries(&dir, export_dir)?;
+      pages.push(HtmlFolderPage {
+        view_id,
+        title,
+        file_path: None,
+        children,
+        collab_info,
+      });
+    }
+    pages.sort_by(|a, b| a.title.to_lowercase().cmp(&b.title.to_lowercase()));
+    Ok(pages)
+  }
+
+  fn import_html_file(
+    &self,
+    path: &Path,
+    export_dir: &Path,
+  ) -> Result<HtmlFolderPage, ImporterError> {
+    let view_id = uuid::Uuid::new_v4().to_string();
+    // The exporters don't always write utf-8, the invalid characters are replaced.
+    let html = String::from_utf8_lossy(&std::fs::read(path)?).to_string();
+    let title = html_title(&html)
+      .or_else(|| {
+        path
+          .file_stem()
+          .map(|stem| stem.to_string_lossy().to_string())
+      })
+      .unwrap_or_else(|| UNTITLED_PAGE.to_string());
+
+    let page_dir = path.parent().unwrap_or(export_dir);
+    let mut files = vec![];
+    let html = rewrite_links(&html, |link| {
+      let asset_path = resolve_asset(page_dir, export_dir, link)?;
+      let bytes = std::fs::read(&asset_path).ok()?;
+      let ext = asset_path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+      let file_id = FileId::from_bytes(&bytes, ext);
+      let file = asset_path.to_string_lossy().to_string();
+      if !files.contains(&file) {
+        files.push(file);
+      }
+      Some(upload_file_url(
+        &self.host,
+        &self.workspace_id,
+        &view_id,
+        &file_id,
+      ))
+    });
+
+    let mut document_data = MDImporter::new(None)
+      .import_html(&view_id, &html)
+      .with_context(|| format!("import {}", path.display()))?;
+    let source_path = path.strip_prefix(export_dir).unwrap_or(path);
+    document_data.tag_provenance(&BlockProvenance::new("html_folder", source_path.display()));
+    let document = Document::create(&view_id, document_data, default_client_id())?;
+    let encoded_collab = document.encode_collab()?;
+    let collab_info = ImportedCollabInfo {
+      name: title.clone(),
+      imported_collabs: vec![ImportedCollab {
+        object_id: view_id.clone(),
+        collab_type: CollabType::Document,
+        encoded_collab,
+      }],
+      resources: vec![CollabResource {
+        object_id: view_id.clone(),
+        files,
+      }],
+      import_type: ImportType::Document,
+    };
+    Ok(HtmlFolderPage {
+      view_id,
+      title,
+      file_path: Some(path.to_path_buf()),
+      children: vec![],
+      collab_info,
+    })
+  }
+}
+
+/// The asset file a relative link of a page points to. The links to the web, to other pages and
+/// to files outside of the export are not assets.
+fn resolve_asset(page_dir: &Path, export_dir: &Path, link: &str) -> Option<PathBuf> {
+  let link = decode_entities(link.trim());
+  let is_relative = !link.is_empty()
+    && !link.contains(':')
+    && !link.starts_with('/')
+    && !link.starts_with('#')
+    && !link.starts_with('?');
+  if !is_relative {
+    return None;
+  }
+  let link = link.split(['?', '#']).next().unwrap_or_default();
+  let link = percent_decode_str(link).decode_utf8().ok()?;
+  let path = page_dir.join(link.as_ref()).canonicalize().ok()?;
+  (path.starts_with(export_dir) && path.is_file() && !is_html_file(&path)).then_some(path)
+}
+
+fn is_html_file(path: &Path) -> bool {
+  path
+    .extension()
+    .map(|ext| ext.to_string_lossy().to_lowercase())
+    .is_some_and(|ext| HTML_EXTENSIONS.contains(&ext.as_str()))
+}
+
+fn contains_html_files(dir: &Path) -> bool {
+  walkdir::WalkDir::new(dir)
+    .into_iter()
+    .flatten()
+    .any(|entry| entry.file_type().is_file() && is_html_file(entry.path()))
+}
+
+fn folder_collab_info(view_id: &str, name: &str) -> Result<ImportedCollabInfo, ImporterError> {
+  let encoded_collab = default_document_collab_data(view_id, default_client_id())?;
+  Ok(ImportedCollabInfo {
+    name: name.to_string(),
+    imported_collabs: vec![ImportedCollab {
+      object_id: view_id.to_string(),
+      collab_type: CollabType::Document,
+      encoded_collab,
+    }],
+    resources: vec![],
+    import_type: ImportType::Document,
+  })
+}
+
+/// The pages imported by [HtmlFolderImporter], under a folder page named after the directory.
+#[derive(Debug, Clone)]
+pub struct HtmlFolderImportedInfo {
+  pub uid: i64,
+  pub workspace_id: String,
+  pub view_id: String,
+  pub name: String,
+  pub pages: Vec<HtmlFolderPage>,
+  pub collab_info: ImportedCollabInfo,
+  collision: NameCollisionAction,
+}
+
+impl HtmlFolderImportedInfo {
+  /// Apply the policy to the folder of the directory when its name is used by a view of the
+  /// workspace. The pages of a merged folder are imported under the existing view.
+  pub fn resolve_name_collisions(
+    &mut self,
+    existing_views: &[ExistingView],
+    policy: NameCollisionPolicy,
+  ) -> NameCollisionReport {
+    let mut resolver = NameCollisionResolver::new(policy, existing_views);
+    let action = resolver.resolve(&self.view_id, &self.name, true);
+    if let NameCollisionAction::Renamed { name } = &action {
+      self.name = name.clone();
+      self.collab_info.name = name.clone();
+    }
+    self.collision = action;
+    resolver.into_report()
+  }
+
+  /// The number of pages, the folder of the directory excluded.
+  pub fn num_of_pages(&self) -> usize {
+    self.pages.iter().map(HtmlFolderPage::num_of_pages).sum()
+  }
+
+  /// The view of the directory, with the views of its pages, under the workspace.
+  pub fn build_nested_views(&self) -> NestedViews {
+    match &self.collision {
+      NameCollisionAction::Skipped { .. } => NestedViews { views: vec![] },
+      NameCollisionAction::Merged { existing_view_id } => NestedViews {
+        views: self.build_page_views(existing_view_id),
+      },
+      _ => {
+        let view = NestedChildViewBuilder::new(self.uid, self.workspace_id.clone())
+          .with_view_id(&self.view_id)
+          .with_name(&self.name)
+          .with_layout(ViewLayout::Document)
+          .with_children(self.build_page_views(&self.view_id))
+          .build();
+        NestedViews { views: vec![view] }
+      },
+    }
+  }
+
+  fn build_page_views(&self, parent_view_id: &str) -> Vec<ParentChildViews> {
+    self
+      .pages
+      .iter()
+      .map(|page| page.build_view(self.uid, parent_view_id))
+      .collect()
+  }
+
+  /// The collabs of the directory and of the pages, each page before its children.
+  pub fn into_collab_infos(self) -> Vec<ImportedCollabInfo> {
+    if matches!(self.collision, NameCollisionAction::Skipped { .. }) {
+      return vec![];
+    }
+    let mut collab_infos = vec![self.collab_info];
+    for page in self.pages {
+      page.collect_collab_infos(&mut collab_infos);
+    }
+    collab_infos
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct HtmlFolderPage {
+  pub view_id: String,
+  pub title: String,
+  /// The html file of the page, `None` for the pages of the directories.
+  pub file_path: Option<PathBuf>,
+  pub children: Vec<HtmlFolderPage>,
+  pub collab_info: ImportedCollabInfo,
+}
+
+impl HtmlFolderPage {
+  /// The number of pages, this page included.
+  pub fn num_of_pages(&self) -> usize {
+    1 + self
+      .children
+      .iter()
+      .map(HtmlFolderPage::num_of_pages)
+      .sum::<usize>()
+  }
+
+  fn build_view(&self, uid: i64, parent_view_id: &str) -> ParentChildViews {
+    let children = self
+      .children
+      .iter()
+      .map(|child| child.build_view(uid, &self.view_id))
+      .collect::<Vec<_>>();
+    NestedChildViewBuilder::new(uid, parent_view_id.to_string())
+      .with_view_id(&self.view_id)
+      .with_name(&self.title)
+      .with_layout(ViewLayout::Document)
+      .with_children(children)
+      .build()
+  }
+
+  fn collect_collab_infos(self, collab_infos: &mut Vec<ImportedCollabInfo>) {
+    collab_infos.push(self.collab_info);
+    for child in self.children {
+      child.collect_collab_infos(collab_infos);
+    }
+  }
+}
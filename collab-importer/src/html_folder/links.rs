@@ -0,0 +1,69 @@
+use collab_document::importer::html::decode_entities;
+
+/// The attributes whose values are links to other files.
+const LINK_ATTRIBUTES: [&[u8]; 2] = [b"src", b"href"];
+
+/// Replace the values of the quoted `src` and `href` attributes of the html with the value
+/// returned by `rewrite`, the attributes for which it returns `None` are kept as is.
+pub(crate) fn rewrite_links<F>(html: &str, mut rewrite: F) -> String
+where
+  F: FnMut(&str) -> Option<String>,
+{
+  let bytes = html.as_bytes();
+  let mut output = String::with_capacity(html.len());
+  let mut copied = 0;
+  let mut index = 1;
+  while index < bytes.len() {
+    let Some((start, end)) = attribute_value_at(bytes, index) else {
+      index += 1;
+      continue;
+    };
+    if let Some(link) = rewrite(&html[start..end]) {
+      output.push_str(&html[copied..start]);
+      output.push_str(&link);
+      copied = end;
+    }
+    index = end + 1;
+  }
+  output.push_str(&html[copied..]);
+  output
+}
+
+/// The range of the value of the link attribute starting at `index`, e.g. `src="logo.png"`.
+/// The attribute has to follow a whitespace, and its value has to be quoted.
+fn attribute_value_at(bytes: &[u8], index: usize) -> Option<(usize, usize)> {
+  if !bytes[index - 1].is_ascii_whitespace() {
+    return None;
+  }
+  let name = LINK_ATTRIBUTES.iter().find(|name| {
+    bytes
+      .get(index..index + name.len())
+      .is_some_and(|candidate| candidate.eq_ignore_ascii_case(name))
+  })?;
+  let mut pos = skip_whitespaces(bytes, index + name.len());
+  if bytes.get(pos) != Some(&b'=') {
+    return None;
+  }
+  pos = skip_whitespaces(bytes, pos + 1);
+  let quote = *bytes.get(pos).filter(|c| matches!(c, b'"' | b'\''))?;
+  let start = pos + 1;
+  let len = bytes[start..].iter().position(|c| *c == quote)?;
+  Some((start, start + len))
+}
+
+fn skip_whitespaces(bytes: &[u8], mut pos: usize) -> usize {
+  while bytes.get(pos).is_some_and(|c| c.is_ascii_whitespace()) {
+    pos += 1;
+  }
+  pos
+}
+
+/// The text of the `<title>` element of the html.
+pub(crate) fn html_title(html: &str) -> Option<String> {
+  let lower = html.to_ascii_lowercase();
+  let open = lower.find("<title")?;
+  let start = open + lower[open..].find('>')? + 1;
+  let end = start + lower[start..].find("</title")?;
+  let title = decode_entities(html[start..end].trim());
+  (!title.is_empty()).then_some(title)
+}
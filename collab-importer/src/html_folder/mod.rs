@@ -0,0 +1,4 @@
+mod importer;
+pub(crate) mod links;
+
+pub use importer::*;
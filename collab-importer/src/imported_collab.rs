@@ -1,6 +1,6 @@
 use crate::error::ImporterError;
-use crate::notion::NotionImporter;
 use crate::notion::page::CollabResource;
+use crate::notion::{NotionBatchImporter, NotionImporter};
 use crate::util::{Either, unzip_from_path_or_memory};
 use collab::entity::EncodedCollab;
 use collab_entity::CollabType;
@@ -35,6 +35,28 @@ pub async fn import_notion_zip_file(
   Ok(RepeatedImportedCollabInfo { infos })
 }
 
+/// Import several Notion export archives into one workspace, each archive under its own space.
+pub async fn import_notion_zip_files(
+  uid: i64,
+  host: &str,
+  workspace_id: &str,
+  zip_files: Vec<PathBuf>,
+  output_dir: PathBuf,
+) -> Result<RepeatedImportedCollabInfo, ImporterError> {
+  let importer = zip_files.into_iter().fold(
+    NotionBatchImporter::new(uid, workspace_id, host.to_string(), output_dir),
+    |importer, zip_file| importer.with_archive(zip_file),
+  );
+  let infos = importer
+    .import()
+    .await?
+    .into_collab_stream()
+    .await
+    .collect::<Vec<ImportedCollabInfo>>()
+    .await;
+  Ok(RepeatedImportedCollabInfo { infos })
+}
+
 #[derive(Debug, Clone)]
 pub struct RepeatedImportedCollabInfo {
   pub infos: Vec<ImportedCollabInfo>,
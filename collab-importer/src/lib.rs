@@ -1,6 +1,23 @@
+pub mod bibtex;
+pub mod cli;
+pub mod confluence;
+pub mod docx;
+pub mod duplicate_page;
+pub mod enex;
 pub mod error;
+pub mod flashcard;
+pub mod html_folder;
 pub mod imported_collab;
+pub mod name_collision;
 pub mod notion;
+pub mod opml;
+pub mod preview;
+pub mod progress;
+pub mod publish;
+pub mod roam;
 mod space_view;
+pub mod tools;
+pub mod trello;
 pub mod util;
+mod xhtml;
 pub mod zip_tool;
@@ -0,0 +1,201 @@
+use crate::error::ImporterError;
+use collab::core::collab::{DataSource, default_client_id};
+use collab::core::origin::CollabOrigin;
+use collab::entity::EncodedCollab;
+use collab_document::document::Document;
+use collab_document::importer::fragment::DocumentFragment;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// The suffix added to the name of a renamed view, see [NameCollisionPolicy::Rename].
+pub const IMPORTED_NAME_SUFFIX: &str = " (imported)";
+
+/// What to do with an imported view whose name is already used by a view of the folder it's
+/// imported into. The names are compared ignoring the case and the surrounding spaces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NameCollisionPolicy {
+  /// Import the view with the [IMPORTED_NAME_SUFFIX] suffix, e.g. `Notes (imported)`, or
+  /// `Notes (imported 2)` if that name is used too.
+  #[default]
+  Rename,
+  /// Append the content of the imported page to the existing page, and import the sub pages
+  /// under the existing page. The databases can't be merged, they are renamed.
+  Merge,
+  /// Don't import the view, nor its sub pages.
+  Skip,
+}
+
+/// A view of the folder the views are imported into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExistingView {
+  pub view_id: String,
+  pub name: String,
+}
+
+impl ExistingView {
+  pub fn new<S: ToString, N: ToString>(view_id: S, name: N) -> Self {
+    Self {
+      view_id: view_id.to_string(),
+      name: name.to_string(),
+    }
+  }
+}
+
+/// What was done with an imported view.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum NameCollisionAction {
+  /// The name wasn't used, the view is imported as is.
+  Imported,
+  Renamed {
+    name: String,
+  },
+  /// The collab of the imported view is still returned by the importer, its content is appended
+  /// to the existing view with [merge_imported_document].
+  Merged {
+    existing_view_id: String,
+  },
+  Skipped {
+    existing_view_id: String,
+  },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NameCollisionOutcome {
+  pub view_id: String,
+  /// The name of the view in the import.
+  pub name: String,
+  pub action: NameCollisionAction,
+}
+
+/// The outcome of each view imported into the folder, in the order of the views.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct NameCollisionReport {
+  pub outcomes: Vec<NameCollisionOutcome>,
+}
+
+impl NameCollisionReport {
+  /// Whether any imported view had the name of an existing view.
+  pub fn has_collisions(&self) -> bool {
+    self
+      .outcomes
+      .iter()
+      .any(|outcome| outcome.action != NameCollisionAction::Imported)
+  }
+
+  /// The imported views merged into existing views, with the id of the existing view.
+  pub fn merged_views(&self) -> Vec<(&str, &str)> {
+    self
+      .outcomes
+      .iter()
+      .filter_map(|outcome| match &outcome.action {
+        NameCollisionAction::Merged { existing_view_id } => {
+          Some((outcome.view_id.as_str(), existing_view_id.as_str()))
+        },
+        _ => None,
+      })
+      .collect()
+  }
+}
+
+/// Apply a [NameCollisionPolicy] to the views imported into a folder, one view after the other.
+/// The names given to the renamed views are taken too, so two imported views named after the
+/// same existing view are renamed differently.
+#[derive(Debug, Clone)]
+pub struct NameCollisionResolver {
+  policy: NameCollisionPolicy,
+  existing_view_ids: HashMap<String, String>,
+  names: HashSet<String>,
+  report: NameCollisionReport,
+}
+
+impl NameCollisionResolver {
+  pub fn new(policy: NameCollisionPolicy, existing_views: &[ExistingView]) -> Self {
+    let mut existing_view_ids = HashMap::new();
+    for view in existing_views {
+      existing_view_ids
+        .entry(normalize_name(&view.name))
+        .or_insert_with(|| view.view_id.clone());
+    }
+    let names = existing_view_ids.keys().cloned().collect();
+    Self {
+      policy,
+      existing_view_ids,
+      names,
+      report: NameCollisionReport::default(),
+    }
+  }
+
+  /// Decide what to do with the imported view. A view that can't be merged, e.g. a database, is
+  /// renamed instead.
+  pub fn resolve(&mut self, view_id: &str, name: &str, can_merge: bool) -> NameCollisionAction {
+    let action = match self.existing_view_ids.get(&normalize_name(name)) {
+      None => {
+        self.names.insert(normalize_name(name));
+        NameCollisionAction::Imported
+      },
+      Some(existing_view_id) => match self.policy {
+        NameCollisionPolicy::Merge if can_merge => NameCollisionAction::Merged {
+          existing_view_id: existing_view_id.clone(),
+        },
+        NameCollisionPolicy::Skip => NameCollisionAction::Skipped {
+          existing_view_id: existing_view_id.clone(),
+        },
+        NameCollisionPolicy::Rename | NameCollisionPolicy::Merge => NameCollisionAction::Renamed {
+          name: self.unused_name(name),
+        },
+      },
+    };
+    self.report.outcomes.push(NameCollisionOutcome {
+      view_id: view_id.to_string(),
+      name: name.to_string(),
+      action: action.clone(),
+    });
+    action
+  }
+
+  pub fn into_report(self) -> NameCollisionReport {
+    self.report
+  }
+
+  fn unused_name(&mut self, name: &str) -> String {
+    let mut renamed = format!("{}{}", name.trim(), IMPORTED_NAME_SUFFIX);
+    let mut index = 2;
+    while self.names.contains(&normalize_name(&renamed)) {
+      renamed = format!("{} (imported {})", name.trim(), index);
+      index += 1;
+    }
+    self.names.insert(normalize_name(&renamed));
+    renamed
+  }
+}
+
+fn normalize_name(name: &str) -> String {
+  name.trim().to_lowercase()
+}
+
+/// Append the content of the imported document of a merged view, see
+/// [NameCollisionAction::Merged], to the existing document. Return the ids of the appended
+/// top-level blocks.
+pub fn merge_imported_document(
+  document: &mut Document,
+  imported: &EncodedCollab,
+  imported_view_id: &str,
+) -> Result<Vec<String>, ImporterError> {
+  let imported = Document::open_with_options(
+    CollabOrigin::Empty,
+    DataSource::DocStateV1(imported.doc_state.to_vec()),
+    imported_view_id,
+    default_client_id(),
+  )?;
+  let page_id = document
+    .get_page_id()
+    .ok_or_else(|| ImporterError::Internal(anyhow::anyhow!("the document has no page")))?;
+  let fragment = DocumentFragment::from_document_data(imported.get_document_data()?, &page_id);
+  if fragment.is_empty() {
+    return Ok(vec![]);
+  }
+  let prev_id = document.get_block_children_ids(&page_id).pop();
+  Ok(document.insert_fragment(fragment, prev_id)?)
+}
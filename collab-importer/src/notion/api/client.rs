@@ -0,0 +1,265 @@
+use crate::error::ImporterError;
+use async_trait::async_trait;
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The version of the Notion API the requests are written for.
+pub const NOTION_API_VERSION: &str = "2022-06-28";
+pub const NOTION_API_BASE_URL: &str = "https://api.notion.com/v1";
+/// The maximum page size of the paginated endpoints.
+const PAGE_SIZE: u32 = 100;
+/// The number of times a rate limited request, or a request failed by the server, is retried.
+const MAX_RETRIES: u32 = 3;
+/// The delay before the first retry when the response has no `Retry-After` header. The delay is
+/// doubled after each retry.
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+/// The longest delay before a retry, the `Retry-After` header included.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotionApiMethod {
+  Get,
+  Post,
+}
+
+/// A request to the Notion API, or the download of a file hosted by Notion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotionApiRequest {
+  pub method: NotionApiMethod,
+  /// The full url, with its query.
+  pub url: String,
+  /// The headers to send, e.g. the authorization and the version of the API.
+  pub headers: Vec<(String, String)>,
+  pub body: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotionApiResponse {
+  pub status: u16,
+  /// The headers of the response, only `Retry-After` is read.
+  pub headers: Vec<(String, String)>,
+  pub body: Vec<u8>,
+}
+
+impl NotionApiResponse {
+  fn header(&self, name: &str) -> Option<&str> {
+    self
+      .headers
+      .iter()
+      .find(|(key, _)| key.eq_ignore_ascii_case(name))
+      .map(|(_, value)| value.as_str())
+  }
+}
+
+/// Send the requests of the [NotionApiClient], so the embedders use their own http client.
+///
+/// A transport only fails when the request can't be sent, the responses with an error status are
+/// returned as is.
+#[async_trait]
+pub trait NotionApiTransport: Send + Sync {
+  async fn send(&self, request: NotionApiRequest) -> Result<NotionApiResponse, ImporterError>;
+}
+
+/// A client of the Notion public API, authenticated with the token of an integration. Only the
+/// pages and the databases shared with the integration can be read.
+#[derive(Clone)]
+pub struct NotionApiClient {
+  token: String,
+  base_url: String,
+  transport: Arc<dyn NotionApiTransport>,
+  max_retries: u32,
+  retry_base_delay: Duration,
+}
+
+impl NotionApiClient {
+  pub fn new<T: NotionApiTransport + 'static>(token: &str, transport: T) -> Self {
+    Self {
+      token: token.to_string(),
+      base_url: NOTION_API_BASE_URL.to_string(),
+      transport: Arc::new(transport),
+      max_retries: MAX_RETRIES,
+      retry_base_delay: RETRY_BASE_DELAY,
+    }
+  }
+
+  /// Send the requests to another server, e.g. a proxy.
+  pub fn with_base_url(mut self, base_url: &str) -> Self {
+    self.base_url = base_url.trim_end_matches('/').to_string();
+    self
+  }
+
+  /// Retry the rate limited requests and the requests failed by the server at most
+  /// `max_retries` times. The `Retry-After` header of the response is honored, otherwise the
+  /// delay starts at `base_delay` and is doubled after each retry. A delay is at most one minute.
+  pub fn with_retries(mut self, max_retries: u32, base_delay: Duration) -> Self {
+    self.max_retries = max_retries;
+    self.retry_base_delay = base_delay;
+    self
+  }
+
+  /// All the pages shared with the integration, including the rows of the databases.
+  pub async fn search_pages(&self) -> Result<Vec<Value>, ImporterError> {
+    self.search("page").await
+  }
+
+  /// All the databases shared with the integration.
+  pub async fn search_databases(&self) -> Result<Vec<Value>, ImporterError> {
+    self.search("database").await
+  }
+
+  /// The children of the block or of the page, in order. The children of the children are not
+  /// included.
+  pub async fn block_children(&self, block_id: &str) -> Result<Vec<Value>, ImporterError> {
+    let url = format!("{}/blocks/{}/children", self.base_url, block_id);
+    let mut results = vec![];
+    let mut cursor: Option<String> = None;
+    loop {
+      let mut page_url = format!("{}?page_size={}", url, PAGE_SIZE);
+      if let Some(cursor) = &cursor {
+        page_url.push_str("&start_cursor=");
+        page_url.extend(utf8_percent_encode(cursor, NON_ALPHANUMERIC));
+      }
+      let response = self.request(NotionApiMethod::Get, page_url, None).await?;
+      cursor = next_cursor(&response);
+      results.extend(take_results(response));
+      if cursor.is_none() {
+        return Ok(results);
+      }
+    }
+  }
+
+  pub async fn retrieve_database(&self, database_id: &str) -> Result<Value, ImporterError> {
+    let url = format!("{}/databases/{}", self.base_url, database_id);
+    self.request(NotionApiMethod::Get, url, None).await
+  }
+
+  /// The rows of the database, in the order of the database.
+  pub async fn query_database(&self, database_id: &str) -> Result<Vec<Value>, ImporterError> {
+    let url = format!("{}/databases/{}/query", self.base_url, database_id);
+    self.paginate_post(&url, json!({})).await
+  }
+
+  /// Download a file, e.g. an image uploaded to Notion. The urls of the uploaded files expire an
+  /// hour after the block is read.
+  pub async fn download(&self, url: &str) -> Result<Vec<u8>, ImporterError> {
+    let request = NotionApiRequest {
+      method: NotionApiMethod::Get,
+      url: url.to_string(),
+      headers: vec![],
+      body: None,
+    };
+    let response = self.send(request).await?;
+    if !(200..300).contains(&response.status) {
+      return Err(ImporterError::NotionApiError {
+        status: Some(response.status),
+        message: format!("failed to download {}", url),
+      });
+    }
+    Ok(response.body)
+  }
+
+  async fn search(&self, object: &str) -> Result<Vec<Value>, ImporterError> {
+    let url = format!("{}/search", self.base_url);
+    let body = json!({ "filter": { "property": "object", "value": object } });
+    self.paginate_post(&url, body).await
+  }
+
+  async fn paginate_post(&self, url: &str, body: Value) -> Result<Vec<Value>, ImporterError> {
+    let mut results = vec![];
+    let mut cursor: Option<String> = None;
+    loop {
+      let mut body = body.clone();
+      body["page_size"] = json!(PAGE_SIZE);
+      if let Some(cursor) = &cursor {
+        body["start_cursor"] = json!(cursor);
+      }
+      let response = self
+        .request(NotionApiMethod::Post, url.to_string(), Some(body))
+        .await?;
+      cursor = next_cursor(&response);
+      results.extend(take_results(response));
+      if cursor.is_none() {
+        return Ok(results);
+      }
+    }
+  }
+
+  async fn request(
+    &self,
+    method: NotionApiMethod,
+    url: String,
+    body: Option<Value>,
+  ) -> Result<Value, ImporterError> {
+    let request = NotionApiRequest {
+      method,
+      url,
+      headers: vec![
+        (
+          "Authorization".to_string(),
+          format!("Bearer {}", self.token),
+        ),
+        ("Notion-Version".to_string(), NOTION_API_VERSION.to_string()),
+        ("Content-Type".to_string(), "application/json".to_string()),
+      ],
+      body,
+    };
+    let response = self.send(request).await?;
+    let body = serde_json::from_slice::<Value>(&response.body).unwrap_or(Value::Null);
+    if !(200..300).contains(&response.status) {
+      // The errors are described by a json object, e.g. {"code": "rate_limited", "message": ..}.
+      let message = body
+        .get("message")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| String::from_utf8_lossy(&response.body).to_string());
+      return Err(ImporterError::NotionApiError {
+        status: Some(response.status),
+        message,
+      });
+    }
+    if body.is_null() {
+      return Err(ImporterError::NotionApiError {
+        status: Some(response.status),
+        message: "the response is not json".to_string(),
+      });
+    }
+    Ok(body)
+  }
+
+  /// Send the request, retrying it while it is rate limited or failed by the server. The last
+  /// response is returned once the retries are exhausted.
+  async fn send(&self, request: NotionApiRequest) -> Result<NotionApiResponse, ImporterError> {
+    let mut delay = self.retry_base_delay;
+    let mut retries = 0;
+    loop {
+      let response = self.transport.send(request.clone()).await?;
+      let is_retryable = response.status == 429 || (500..600).contains(&response.status);
+      if !is_retryable || retries >= self.max_retries {
+        return Ok(response);
+      }
+      let retry_after = response
+        .header("Retry-After")
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs);
+      tokio::time::sleep(retry_after.unwrap_or(delay).min(MAX_RETRY_DELAY)).await;
+      delay = delay.saturating_mul(2).min(MAX_RETRY_DELAY);
+      retries += 1;
+    }
+  }
+}
+
+fn next_cursor(response: &Value) -> Option<String> {
+  if !response["has_more"].as_bool().unwrap_or(false) {
+    return None;
+  }
+  response["next_cursor"].as_str().map(str::to_string)
+}
+
+fn take_results(mut response: Value) -> Vec<Value> {
+  match response["results"].take() {
+    Value::Array(results) => results,
+    _ => vec![],
+  }
+}
@@ -0,0 +1,364 @@
+use crate::error::ImporterError;
+use crate::notion::api::client::NotionApiClient;
+use crate::notion::api::markdown::{
+  ApiLink, ApiLinks, block_type, blocks_to_markdown, encode_link, normalize_id, plain_text,
+  property_to_string, url_file_name,
+};
+use serde_json::{Value, json};
+use std::collections::HashSet;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use tracing::warn;
+
+/// The blocks whose file can be hosted by Notion.
+const FILE_BLOCK_TYPES: [&str; 5] = ["image", "file", "pdf", "video", "audio"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ApiObjectKind {
+  Page,
+  Database,
+}
+
+/// A page or a database returned by the search endpoint.
+#[derive(Debug, Clone)]
+struct ApiObject {
+  id: String,
+  kind: ApiObjectKind,
+  title: String,
+  /// The page or the database containing the object, `None` when the object is at the root of
+  /// the workspace or when its parent isn't shared with the integration.
+  parent_id: Option<String>,
+  value: Value,
+}
+
+/// Write the pages and the databases shared with an integration in the layout of a Notion zip
+/// export, so they are imported by [crate::notion::NotionImporter]:
+/// - a page is written to `{title} {id}.md`, its sub pages and its files to the `{title} {id}`
+///   directory next to it.
+/// - a database is written to `{title} {id}_all.csv`, its rows to the `{title} {id}` directory.
+///
+/// Unlike a zip export, the links between the pages are never broken and the size of the
+/// workspace isn't limited.
+pub struct NotionApiExporter {
+  client: NotionApiClient,
+}
+
+impl NotionApiExporter {
+  pub fn new(client: NotionApiClient) -> Self {
+    Self { client }
+  }
+
+  /// Export the workspace to `out_dir/workspace_name` and return the path of the directory.
+  pub async fn export(
+    &self,
+    out_dir: &Path,
+    workspace_name: &str,
+  ) -> Result<PathBuf, ImporterError> {
+    let mut objects = vec![];
+    for page in self.client.search_pages().await? {
+      objects.push(api_object(page, ApiObjectKind::Page));
+    }
+    for database in self.client.search_databases().await? {
+      objects.push(api_object(database, ApiObjectKind::Database));
+    }
+
+    let ids = objects
+      .iter()
+      .map(|object| object.id.clone())
+      .collect::<HashSet<_>>();
+    for object in objects.iter_mut() {
+      if let Some(parent_id) = &object.parent_id {
+        if !ids.contains(parent_id) {
+          object.parent_id = None;
+        }
+      }
+    }
+
+    let links = objects
+      .iter()
+      .map(|object| {
+        let extension = match object.kind {
+          ApiObjectKind::Page => "md",
+          ApiObjectKind::Database => "csv",
+        };
+        let link = ApiLink {
+          title: object.title.clone(),
+          file_name: format!("{}.{}", entry_name(object), extension),
+        };
+        (object.id.clone(), link)
+      })
+      .collect::<ApiLinks>();
+    let workspace = Workspace { objects, links };
+
+    let root = out_dir.join(sanitize_file_name(workspace_name));
+    tokio::fs::create_dir_all(&root).await?;
+    for object in workspace.children_of(None) {
+      self.export_object(&workspace, object, &root).await?;
+    }
+    Ok(root)
+  }
+
+  fn export_object<'a>(
+    &'a self,
+    workspace: &'a Workspace,
+    object: &'a ApiObject,
+    dir: &'a Path,
+  ) -> Pin<Box<dyn Future<Output = Result<(), ImporterError>> + Send + 'a>> {
+    Box::pin(async move {
+      match object.kind {
+        ApiObjectKind::Page => self.export_page(workspace, object, dir, None).await,
+        ApiObjectKind::Database => self.export_database(workspace, object, dir).await,
+      }
+    })
+  }
+
+  /// Write the page and its sub pages. The properties of a database row are written between the
+  /// title and the content, as in a Notion export.
+  async fn export_page(
+    &self,
+    workspace: &Workspace,
+    page: &ApiObject,
+    dir: &Path,
+    properties: Option<Vec<(String, String)>>,
+  ) -> Result<(), ImporterError> {
+    let name = entry_name(page);
+    let page_dir = dir.join(&name);
+    let mut blocks = self.block_tree(&page.id).await?;
+    self.download_files(&mut blocks, &page_dir, &name).await?;
+
+    let mut markdown = format!("# {}\n\n", page.title);
+    if let Some(properties) = properties {
+      let properties = properties
+        .into_iter()
+        .map(|(name, value)| format!("{}: {}", name, value))
+        .collect::<Vec<_>>();
+      if !properties.is_empty() {
+        markdown.push_str(&properties.join("\n"));
+        markdown.push_str("\n\n");
+      }
+    }
+    markdown.push_str(&blocks_to_markdown(&blocks, &workspace.links));
+    markdown.push('\n');
+    tokio::fs::write(dir.join(format!("{}.md", name)), markdown).await?;
+
+    let sub_objects = workspace.children_of(Some(&page.id));
+    if !sub_objects.is_empty() {
+      tokio::fs::create_dir_all(&page_dir).await?;
+    }
+    for object in sub_objects {
+      self.export_object(workspace, object, &page_dir).await?;
+    }
+    Ok(())
+  }
+
+  /// Write the rows of the database to a csv file, the title column first, and the content of
+  /// the rows to the directory of the database.
+  async fn export_database(
+    &self,
+    workspace: &Workspace,
+    database: &ApiObject,
+    dir: &Path,
+  ) -> Result<(), ImporterError> {
+    let name = entry_name(database);
+    let database_dir = dir.join(&name);
+    tokio::fs::create_dir_all(&database_dir).await?;
+
+    let mut columns = vec![];
+    let mut title_column = None;
+    if let Some(properties) = database.value["properties"].as_object() {
+      for (column, property) in properties {
+        if property["type"].as_str() == Some("title") {
+          title_column = Some(column.clone());
+        } else {
+          columns.push(column.clone());
+        }
+      }
+    }
+    columns.sort();
+    let title_column = title_column.unwrap_or_else(|| "Name".to_string());
+    columns.insert(0, title_column.clone());
+
+    let rows = self.client.query_database(&database.id).await?;
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer
+      .write_record(&columns)
+      .map_err(|err| ImporterError::Internal(err.into()))?;
+    for row in rows {
+      let row = api_object(row, ApiObjectKind::Page);
+      let cells = columns
+        .iter()
+        .map(|column| property_to_string(&row.value["properties"][column], &workspace.links))
+        .collect::<Vec<_>>();
+      writer
+        .write_record(&cells)
+        .map_err(|err| ImporterError::Internal(err.into()))?;
+
+      let properties = columns
+        .iter()
+        .zip(cells)
+        .skip(1)
+        .map(|(column, cell)| (column.clone(), cell))
+        .collect();
+      self
+        .export_page(workspace, &row, &database_dir, Some(properties))
+        .await?;
+    }
+    let csv = writer
+      .into_inner()
+      .map_err(|err| ImporterError::Internal(anyhow::anyhow!(err.to_string())))?;
+    tokio::fs::write(dir.join(format!("{}_all.csv", name)), csv).await?;
+    Ok(())
+  }
+
+  /// The blocks of the page, with the children of each block in its `children` key. The sub pages
+  /// and the databases are exported on their own.
+  async fn block_tree(&self, block_id: &str) -> Result<Vec<Value>, ImporterError> {
+    let mut blocks = self.client.block_children(block_id).await?;
+    let mut stack = blocks.iter_mut().collect::<Vec<_>>();
+    while let Some(block) = stack.pop() {
+      let has_children = block["has_children"].as_bool().unwrap_or(false);
+      if !has_children || matches!(block_type(block), "child_page" | "child_database") {
+        continue;
+      }
+      let id = block["id"].as_str().unwrap_or_default().to_string();
+      block["children"] = Value::Array(self.client.block_children(&id).await?);
+      if let Some(children) = block["children"].as_array_mut() {
+        stack.extend(children.iter_mut());
+      }
+    }
+    Ok(blocks)
+  }
+
+  /// Download the files hosted by Notion to the directory of the page, and replace their urls,
+  /// which expire, with the relative paths of the downloaded files. A file that can't be
+  /// downloaded keeps its url.
+  async fn download_files(
+    &self,
+    blocks: &mut [Value],
+    page_dir: &Path,
+    page_dir_name: &str,
+  ) -> Result<(), ImporterError> {
+    let mut file_names = HashSet::new();
+    let mut stack = blocks.iter_mut().collect::<Vec<_>>();
+    while let Some(block) = stack.pop() {
+      let ty = block_type(block).to_string();
+      if FILE_BLOCK_TYPES.contains(&ty.as_str()) {
+        if let Some(url) = block[&ty]["file"]["url"].as_str().map(str::to_string) {
+          match self.client.download(&url).await {
+            Ok(bytes) => {
+              let file_name = unique_file_name(&url_file_name(&url), &mut file_names);
+              tokio::fs::create_dir_all(page_dir).await?;
+              tokio::fs::write(page_dir.join(&file_name), bytes).await?;
+              let path = encode_link(&format!("{}/{}", page_dir_name, file_name));
+              block[&ty]["type"] = json!("external");
+              block[&ty]["external"] = json!({ "url": path });
+              block[&ty]["name"] = json!(file_name);
+              if let Some(content) = block[&ty].as_object_mut() {
+                content.remove("file");
+              }
+            },
+            Err(err) => warn!("Failed to download {}: {}", url, err),
+          }
+        }
+      }
+      if let Some(children) = block["children"].as_array_mut() {
+        stack.extend(children.iter_mut());
+      }
+    }
+    Ok(())
+  }
+}
+
+struct Workspace {
+  objects: Vec<ApiObject>,
+  links: ApiLinks,
+}
+
+impl Workspace {
+  fn children_of(&self, parent_id: Option<&str>) -> Vec<&ApiObject> {
+    self
+      .objects
+      .iter()
+      // The rows are exported with their database.
+      .filter(|object| !is_database_row(object))
+      .filter(|object| object.parent_id.as_deref() == parent_id)
+      .collect()
+  }
+}
+
+fn is_database_row(object: &ApiObject) -> bool {
+  object.value["parent"]["type"].as_str() == Some("database_id") && object.parent_id.is_some()
+}
+
+fn api_object(value: Value, kind: ApiObjectKind) -> ApiObject {
+  let id = normalize_id(value["id"].as_str().unwrap_or_default());
+  let title = match kind {
+    ApiObjectKind::Page => value["properties"]
+      .as_object()
+      .and_then(|properties| {
+        properties
+          .values()
+          .find(|property| property["type"].as_str() == Some("title"))
+      })
+      .map(|property| plain_text(&property["title"]))
+      .unwrap_or_default(),
+    ApiObjectKind::Database => plain_text(&value["title"]),
+  };
+  let title = if title.trim().is_empty() {
+    "Untitled".to_string()
+  } else {
+    title.trim().to_string()
+  };
+
+  let parent = &value["parent"];
+  let parent_id = match parent["type"].as_str() {
+    Some("page_id") => parent["page_id"].as_str().map(normalize_id),
+    Some("database_id") => parent["database_id"].as_str().map(normalize_id),
+    _ => None,
+  };
+  ApiObject {
+    id,
+    kind,
+    title,
+    parent_id,
+    value,
+  }
+}
+
+/// The name of the exported file and directory of the object, without the extension.
+fn entry_name(object: &ApiObject) -> String {
+  format!("{} {}", sanitize_file_name(&object.title), object.id)
+}
+
+fn sanitize_file_name(name: &str) -> String {
+  let name = name
+    .chars()
+    .map(|c| match c {
+      '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => ' ',
+      c if c.is_control() => ' ',
+      c => c,
+    })
+    .collect::<String>();
+  let name = name.trim();
+  if name.is_empty() {
+    "Untitled".to_string()
+  } else {
+    name.to_string()
+  }
+}
+
+fn unique_file_name(name: &str, file_names: &mut HashSet<String>) -> String {
+  let name = sanitize_file_name(name);
+  let (stem, extension) = match name.rsplit_once('.') {
+    Some((stem, extension)) if !stem.is_empty() => (stem.to_string(), format!(".{}", extension)),
+    _ => (name.clone(), String::new()),
+  };
+  let mut file_name = name;
+  let mut index = 1;
+  while !file_names.insert(file_name.clone()) {
+    file_name = format!("{} {}{}", stem, index, extension);
+    index += 1;
+  }
+  file_name
+}
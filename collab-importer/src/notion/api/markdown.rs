@@ -0,0 +1,434 @@
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The characters escaped in a segment of a relative link, as in the links of a Notion export.
+const LINK_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+  .remove(b'-')
+  .remove(b'_')
+  .remove(b'.')
+  .remove(b'~');
+
+/// A page or a database shared with the integration, as it is linked from the exported pages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ApiLink {
+  pub title: String,
+  /// The name of the exported file, e.g. `Tasks 2b5c..04.md` or `Projects 1a2b..f9.csv`.
+  pub file_name: String,
+}
+
+/// The pages and the databases by id, without dashes.
+pub(crate) type ApiLinks = HashMap<String, ApiLink>;
+
+/// Escape the segments of a relative path, e.g. `Project 1a2b/diagram.png`.
+pub(crate) fn encode_link(path: &str) -> String {
+  path
+    .split('/')
+    .map(|segment| utf8_percent_encode(segment, LINK_SEGMENT).to_string())
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+/// Remove the dashes of a Notion id, the ids of the exported file names don't have them.
+pub(crate) fn normalize_id(id: &str) -> String {
+  id.replace('-', "").to_ascii_lowercase()
+}
+
+/// Render the blocks of a page as markdown. The children of a block are read from its `children`
+/// key, see [crate::notion::api::NotionApiExporter].
+pub(crate) fn blocks_to_markdown(blocks: &[Value], links: &ApiLinks) -> String {
+  let mut parts: Vec<String> = vec![];
+  let mut index = 0;
+  while index < blocks.len() {
+    let block = &blocks[index];
+    if block_type(block) == "numbered_list_item" {
+      // The numbers of the items restart after any other block.
+      let mut number = 1;
+      while index < blocks.len() && block_type(&blocks[index]) == "numbered_list_item" {
+        parts.push(render_list_item(
+          &blocks[index],
+          &format!("{}. ", number),
+          links,
+        ));
+        number += 1;
+        index += 1;
+      }
+      continue;
+    }
+
+    let markdown = render_block(block, links);
+    if !markdown.is_empty() {
+      parts.push(markdown);
+    }
+    index += 1;
+  }
+  parts.join("\n\n")
+}
+
+fn render_block(block: &Value, links: &ApiLinks) -> String {
+  let ty = block_type(block);
+  let content = &block[ty];
+  match ty {
+    "paragraph" => {
+      let text = rich_text_to_markdown(&content["rich_text"], links);
+      with_children(text, block, "    ", links)
+    },
+    "heading_1" | "heading_2" | "heading_3" => {
+      let level = &ty[ty.len() - 1..];
+      let hashes = "#".repeat(level.parse().unwrap_or(1));
+      let heading = format!(
+        "{} {}",
+        hashes,
+        rich_text_to_markdown(&content["rich_text"], links)
+      );
+      // The children of a toggle heading follow the heading.
+      with_children(heading, block, "", links)
+    },
+    "bulleted_list_item" | "toggle" => render_list_item(block, "- ", links),
+    "numbered_list_item" => render_list_item(block, "1. ", links),
+    "to_do" => {
+      let prefix = if content["checked"].as_bool().unwrap_or(false) {
+        "- [x] "
+      } else {
+        "- [ ] "
+      };
+      render_list_item(block, prefix, links)
+    },
+    "quote" | "callout" => {
+      let mut text = rich_text_to_markdown(&content["rich_text"], links);
+      if let Some(emoji) = content["icon"]["emoji"].as_str() {
+        text = format!("{} {}", emoji, text);
+      }
+      let text = with_children(text, block, "", links);
+      prefix_lines(&text, "> ")
+    },
+    "code" => {
+      let language = content["language"].as_str().unwrap_or_default();
+      let language = if language == "plain text" {
+        ""
+      } else {
+        language
+      };
+      format!(
+        "```{}\n{}\n```",
+        language,
+        plain_text(&content["rich_text"])
+      )
+    },
+    "equation" => format!(
+      "$$\n{}\n$$",
+      content["expression"].as_str().unwrap_or_default()
+    ),
+    "divider" => "---".to_string(),
+    "image" => {
+      let url = file_url(content);
+      let caption = plain_text(&content["caption"]);
+      format!("![{}]({})", caption, url)
+    },
+    "file" | "pdf" | "video" | "audio" => {
+      let url = file_url(content);
+      let mut name = plain_text(&content["caption"]);
+      if name.is_empty() {
+        name = content["name"]
+          .as_str()
+          .map(str::to_string)
+          .unwrap_or_else(|| url_file_name(&url));
+      }
+      format!("[{}]({})", name, url)
+    },
+    "bookmark" | "embed" | "link_preview" => {
+      let url = content["url"].as_str().unwrap_or_default();
+      let caption = plain_text(&content["caption"]);
+      let text = if caption.is_empty() { url } else { &caption };
+      format!("[{}]({})", text, url)
+    },
+    "child_page" | "child_database" | "link_to_page" => {
+      let id = match ty {
+        "link_to_page" => content["page_id"]
+          .as_str()
+          .or_else(|| content["database_id"].as_str())
+          .unwrap_or_default(),
+        _ => block["id"].as_str().unwrap_or_default(),
+      };
+      match links.get(&normalize_id(id)) {
+        Some(link) => format!("[{}]({})", link.title, encode_link(&link.file_name)),
+        None => content["title"].as_str().unwrap_or_default().to_string(),
+      }
+    },
+    "table" => render_table(block, links),
+    // The content of the columns and of the synced blocks is exported one after the other.
+    "column_list" | "column" | "synced_block" => blocks_to_markdown(children(block), links),
+    _ => String::new(),
+  }
+}
+
+/// A list item, with its children indented under it.
+fn render_list_item(block: &Value, prefix: &str, links: &ApiLinks) -> String {
+  let text = rich_text_to_markdown(&block[block_type(block)]["rich_text"], links);
+  let indent = " ".repeat(prefix.len());
+  with_children(format!("{}{}", prefix, text), block, &indent, links)
+}
+
+fn with_children(text: String, block: &Value, indent: &str, links: &ApiLinks) -> String {
+  let children = blocks_to_markdown(children(block), links);
+  if children.is_empty() {
+    return text;
+  }
+  format!("{}\n\n{}", text, prefix_lines(&children, indent))
+}
+
+fn render_table(block: &Value, links: &ApiLinks) -> String {
+  let rows = children(block)
+    .iter()
+    .map(|row| {
+      row["table_row"]["cells"]
+        .as_array()
+        .map(|cells| {
+          cells
+            .iter()
+            .map(|cell| rich_text_to_markdown(cell, links).replace('|', "\\|"))
+            .collect::<Vec<_>>()
+        })
+        .unwrap_or_default()
+    })
+    .collect::<Vec<_>>();
+  let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+  if width == 0 {
+    return String::new();
+  }
+
+  // A markdown table always has a header, an empty one is used when the first row isn't one.
+  let has_header = block["table"]["has_column_header"]
+    .as_bool()
+    .unwrap_or(false);
+  let mut rows = rows.into_iter();
+  let header = if has_header {
+    rows.next().unwrap_or_default()
+  } else {
+    vec![]
+  };
+  let mut lines = vec![
+    table_row(&header, width),
+    table_row(&vec!["---".to_string(); width], width),
+  ];
+  lines.extend(rows.map(|row| table_row(&row, width)));
+  lines.join("\n")
+}
+
+fn table_row(cells: &[String], width: usize) -> String {
+  let cells = (0..width)
+    .map(|index| cells.get(index).map(String::as_str).unwrap_or(" "))
+    .collect::<Vec<_>>();
+  format!("| {} |", cells.join(" | "))
+}
+
+/// Render the rich text with its styles. The mentions of the pages and the databases shared with
+/// the integration are rendered as links to their exported files.
+pub(crate) fn rich_text_to_markdown(rich_text: &Value, links: &ApiLinks) -> String {
+  let Some(items) = rich_text.as_array() else {
+    return String::new();
+  };
+
+  let mut markdown = String::new();
+  for item in items {
+    let text = item["plain_text"].as_str().unwrap_or_default();
+    match item["type"].as_str() {
+      Some("equation") => {
+        let expression = item["equation"]["expression"].as_str().unwrap_or(text);
+        markdown.push_str(&format!("${}$", expression));
+        continue;
+      },
+      Some("mention") => {
+        let mention = &item["mention"];
+        let id = match mention["type"].as_str() {
+          Some("page") => mention["page"]["id"].as_str(),
+          Some("database") => mention["database"]["id"].as_str(),
+          _ => None,
+        };
+        if let Some(link) = id.and_then(|id| links.get(&normalize_id(id))) {
+          markdown.push_str(&format!(
+            "[{}]({})",
+            link.title,
+            encode_link(&link.file_name)
+          ));
+          continue;
+        }
+      },
+      _ => {},
+    }
+
+    let mut styled = styled_text(text, &item["annotations"]);
+    if let Some(url) = item["href"].as_str() {
+      if !styled.trim().is_empty() {
+        styled = format!("[{}]({})", styled, url);
+      }
+    }
+    markdown.push_str(&styled);
+  }
+  markdown
+}
+
+/// Wrap the text with the markers of its styles. The spaces around the text are kept outside of
+/// the markers, otherwise the markers are not parsed.
+fn styled_text(text: &str, annotations: &Value) -> String {
+  let trimmed = text.trim();
+  if trimmed.is_empty() {
+    return text.to_string();
+  }
+
+  let mut styled = trimmed.to_string();
+  if annotations["code"].as_bool().unwrap_or(false) {
+    styled = format!("`{}`", styled);
+  }
+  for (annotation, marker) in [("bold", "**"), ("italic", "*"), ("strikethrough", "~~")] {
+    if annotations[annotation].as_bool().unwrap_or(false) {
+      styled = format!("{}{}{}", marker, styled, marker);
+    }
+  }
+
+  let start = text.len() - text.trim_start().len();
+  let end = text.trim_end().len();
+  format!("{}{}{}", &text[..start], styled, &text[end..])
+}
+
+/// The text of the rich text, without its styles.
+pub(crate) fn plain_text(rich_text: &Value) -> String {
+  rich_text
+    .as_array()
+    .map(|items| {
+      items
+        .iter()
+        .filter_map(|item| item["plain_text"].as_str())
+        .collect::<String>()
+    })
+    .unwrap_or_default()
+}
+
+/// The value of a property of a database row, as it is written in the csv file of a Notion
+/// export.
+pub(crate) fn property_to_string(property: &Value, links: &ApiLinks) -> String {
+  let ty = property["type"].as_str().unwrap_or_default();
+  let value = &property[ty];
+  match ty {
+    "title" | "rich_text" => plain_text(value),
+    "number" => value.as_f64().map(|n| n.to_string()).unwrap_or_default(),
+    "select" | "status" => value["name"].as_str().unwrap_or_default().to_string(),
+    "multi_select" => join_values(value, |option| option["name"].as_str().map(str::to_string)),
+    "date" => date_to_string(value),
+    "checkbox" => {
+      if value.as_bool().unwrap_or(false) {
+        "Yes".to_string()
+      } else {
+        "No".to_string()
+      }
+    },
+    "url" | "email" | "phone_number" | "created_time" | "last_edited_time" => {
+      value.as_str().unwrap_or_default().to_string()
+    },
+    "created_by" | "last_edited_by" => value["name"].as_str().unwrap_or_default().to_string(),
+    "people" => join_values(value, |person| person["name"].as_str().map(str::to_string)),
+    "relation" => join_values(value, |relation| {
+      let link = links.get(&normalize_id(relation["id"].as_str()?))?;
+      Some(format!("{} ({})", link.title, encode_link(&link.file_name)))
+    }),
+    "files" => join_values(value, |file| {
+      file["name"]
+        .as_str()
+        .or_else(|| file["external"]["url"].as_str())
+        .map(str::to_string)
+    }),
+    "formula" => {
+      let formula_type = value["type"].as_str().unwrap_or_default();
+      match formula_type {
+        "date" => date_to_string(&value["date"]),
+        _ => scalar_to_string(&value[formula_type]),
+      }
+    },
+    "rollup" => {
+      let rollup_type = value["type"].as_str().unwrap_or_default();
+      match rollup_type {
+        "array" => join_values(&value["array"], |item| {
+          Some(property_to_string(item, links)).filter(|s| !s.is_empty())
+        }),
+        "date" => date_to_string(&value["date"]),
+        _ => scalar_to_string(&value[rollup_type]),
+      }
+    },
+    "unique_id" => match value["number"].as_i64() {
+      Some(number) => match value["prefix"].as_str() {
+        Some(prefix) => format!("{}-{}", prefix, number),
+        None => number.to_string(),
+      },
+      None => String::new(),
+    },
+    _ => String::new(),
+  }
+}
+
+fn date_to_string(date: &Value) -> String {
+  let start = date["start"].as_str().unwrap_or_default();
+  match date["end"].as_str() {
+    Some(end) => format!("{} → {}", start, end),
+    None => start.to_string(),
+  }
+}
+
+fn scalar_to_string(value: &Value) -> String {
+  match value {
+    Value::String(s) => s.clone(),
+    Value::Number(n) => n.to_string(),
+    Value::Bool(true) => "Yes".to_string(),
+    Value::Bool(false) => "No".to_string(),
+    _ => String::new(),
+  }
+}
+
+fn join_values<F: Fn(&Value) -> Option<String>>(values: &Value, f: F) -> String {
+  values
+    .as_array()
+    .map(|values| values.iter().filter_map(f).collect::<Vec<_>>().join(", "))
+    .unwrap_or_default()
+}
+
+pub(crate) fn block_type(block: &Value) -> &str {
+  block["type"].as_str().unwrap_or_default()
+}
+
+pub(crate) fn children(block: &Value) -> &[Value] {
+  block["children"]
+    .as_array()
+    .map(Vec::as_slice)
+    .unwrap_or_default()
+}
+
+/// The url of a file block: the file hosted by Notion, or the external one.
+pub(crate) fn file_url(content: &Value) -> String {
+  content["file"]["url"]
+    .as_str()
+    .or_else(|| content["external"]["url"].as_str())
+    .unwrap_or_default()
+    .to_string()
+}
+
+/// The name of the file at the url, without its query.
+pub(crate) fn url_file_name(url: &str) -> String {
+  let path = url.split(['?', '#']).next().unwrap_or(url);
+  let name = path.rsplit('/').next().unwrap_or(path);
+  percent_encoding::percent_decode_str(name)
+    .decode_utf8_lossy()
+    .to_string()
+}
+
+fn prefix_lines(text: &str, prefix: &str) -> String {
+  text
+    .lines()
+    .map(|line| {
+      if line.is_empty() && prefix.trim().is_empty() {
+        String::new()
+      } else {
+        format!("{}{}", prefix, line)
+      }
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
@@ -0,0 +1,6 @@
+mod client;
+mod export;
+mod markdown;
+
+pub use client::*;
+pub use export::*;
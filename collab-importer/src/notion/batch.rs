@@ -0,0 +1,187 @@
+use crate::error::{ImporterError, ImporterResultExt};
+use crate::notion::file::NotionFile;
+use crate::notion::importer::{link_workspace_pages, refresh_csv_relation};
+use crate::notion::page::NotionPage;
+use crate::notion::{CSVRelation, ImportedInfo, NotionImporter};
+use crate::preview::ImportPreviewHook;
+use crate::util::{Either, unzip_from_path_or_memory};
+use collab_database::template::locale::ImportLocale;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::info;
+
+/// Imports several Notion export archives, e.g. a workspace exported teamspace by teamspace,
+/// into one [ImportedInfo].
+///
+/// Each archive is unzipped in its own directory of `output_dir` and imported as a top-level
+/// space named after the archive. The archives share the id namespace of the import: a page
+/// already imported from a previous archive, recognized by its Notion id, is skipped, so the
+/// pages exported in several archives are imported once and the links between the archives can
+/// be resolved with [ImportedInfo::view_id_by_notion_id].
+pub struct NotionBatchImporter {
+  uid: i64,
+  host: String,
+  workspace_id: String,
+  output_dir: PathBuf,
+  archives: Vec<PathBuf>,
+  locale: ImportLocale,
+  preview_hook: Option<Arc<dyn ImportPreviewHook>>,
+}
+
+impl NotionBatchImporter {
+  pub fn new<P: Into<PathBuf>, S: ToString>(
+    uid: i64,
+    workspace_id: S,
+    host: String,
+    output_dir: P,
+  ) -> Self {
+    Self {
+      uid,
+      host,
+      workspace_id: workspace_id.to_string(),
+      output_dir: output_dir.into(),
+      archives: vec![],
+      locale: ImportLocale::default(),
+      preview_hook: None,
+    }
+  }
+
+  /// Add an archive to import. The archives are imported in the order they are added.
+  pub fn with_archive<P: Into<PathBuf>>(mut self, zip_file: P) -> Self {
+    self.archives.push(zip_file.into());
+    self
+  }
+
+  /// Set the locale of the archives, see [NotionImporter::with_locale].
+  pub fn with_locale(mut self, locale: ImportLocale) -> Self {
+    self.locale = locale;
+    self
+  }
+
+  /// Set the hook invoked with the preview of each imported page, see
+  /// [NotionImporter::with_preview_hook].
+  pub fn with_preview_hook(mut self, hook: Arc<dyn ImportPreviewHook>) -> Self {
+    self.preview_hook = Some(hook);
+    self
+  }
+
+  pub async fn import(self) -> Result<ImportedInfo, ImporterError> {
+    if self.archives.is_empty() {
+      return Err(ImporterError::CannotImport);
+    }
+
+    let mut notion_ids = HashSet::new();
+    let mut spaces = vec![];
+    for (index, zip_file) in self.archives.iter().enumerate() {
+      let space = self
+        .import_archive(index, zip_file, &mut notion_ids)
+        .await
+        .with_context(|| format!("import {}", zip_file.display()))?;
+      spaces.push(space);
+    }
+
+    // The links between the pages of different archives are resolved too
+    link_workspace_pages(&mut spaces);
+    refresh_csv_relation(&mut spaces);
+    let name = spaces
+      .iter()
+      .map(|space| space.notion_name.as_str())
+      .collect::<Vec<_>>()
+      .join(", ");
+    let info = ImportedInfo::new(
+      self.uid,
+      self.workspace_id.clone(),
+      self.host.clone(),
+      name,
+      spaces,
+    )?;
+    Ok(match self.preview_hook {
+      Some(hook) => info.with_preview_hook(hook),
+      None => info,
+    })
+  }
+
+  /// Import the pages of the archive under a space named after the archive.
+  async fn import_archive(
+    &self,
+    index: usize,
+    zip_file: &Path,
+    notion_ids: &mut HashSet<String>,
+  ) -> Result<NotionPage, ImporterError> {
+    if !zip_file.exists() {
+      return Err(ImporterError::FileNotFound);
+    }
+    // The archives are unzipped apart, they usually have the same root directory.
+    let output_dir = self.output_dir.join(index.to_string());
+    tokio::fs::create_dir_all(&output_dir).await?;
+    let unzip_dir =
+      unzip_from_path_or_memory(Either::Left(zip_file.to_path_buf()), output_dir).await?;
+
+    let mut pages =
+      NotionImporter::new(self.uid, &unzip_dir, &self.workspace_id, self.host.clone())?
+        .with_locale(self.locale.clone())
+        .collect_pages()
+        .await?;
+    if pages.is_empty() {
+      return Err(ImporterError::CannotImport);
+    }
+    let num_of_duplicates = retain_new_pages(&mut pages, notion_ids);
+    collect_notion_ids(&pages, notion_ids);
+    if num_of_duplicates > 0 {
+      info!(
+        "skip {} pages of {} imported from a previous archive",
+        num_of_duplicates,
+        zip_file.display()
+      );
+    }
+
+    let name = zip_file
+      .file_stem()
+      .map(|stem| stem.to_string_lossy().to_string())
+      .unwrap_or_else(|| format!("Archive {}", index + 1));
+    Ok(NotionPage {
+      notion_name: name,
+      notion_id: None,
+      notion_file: NotionFile::Empty,
+      view_id: uuid::Uuid::new_v4().to_string(),
+      workspace_id: self.workspace_id.clone(),
+      children: pages,
+      external_links: vec![],
+      host: self.host.clone(),
+      is_dir: true,
+      csv_relation: CSVRelation::default(),
+      locale: Arc::new(self.locale.clone()),
+      relations: None,
+      workspace_view_ids: None,
+      row_dedupe: None,
+      member_resolver: None,
+    })
+  }
+}
+
+/// Remove the pages whose Notion id is in `notion_ids`, with their children. Return the number
+/// of removed pages.
+fn retain_new_pages(pages: &mut Vec<NotionPage>, notion_ids: &HashSet<String>) -> usize {
+  let num_of_pages = pages.len();
+  pages.retain(|page| {
+    page
+      .notion_id
+      .as_ref()
+      .is_none_or(|id| !notion_ids.contains(id))
+  });
+  let mut num_of_duplicates = num_of_pages - pages.len();
+  for page in pages.iter_mut() {
+    num_of_duplicates += retain_new_pages(&mut page.children, notion_ids);
+  }
+  num_of_duplicates
+}
+
+fn collect_notion_ids(pages: &[NotionPage], notion_ids: &mut HashSet<String>) {
+  for page in pages {
+    if let Some(id) = &page.notion_id {
+      notion_ids.insert(id.clone());
+    }
+    collect_notion_ids(&page.children, notion_ids);
+  }
+}
@@ -0,0 +1,43 @@
+use collab_database::fields::Field;
+use collab_database::rows::DuplicateRowPolicy;
+
+/// Deduplicate the rows of the imported databases, e.g. when a refreshed export of a Notion
+/// database is imported again. See [crate::notion::NotionImporter::with_row_dedupe].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RowDedupeOptions {
+  /// The name of the column identifying a row. The primary column is used when None, or when
+  /// the database has no such column.
+  pub key_column: Option<String>,
+  pub policy: DuplicateRowPolicy,
+}
+
+impl RowDedupeOptions {
+  pub fn new(policy: DuplicateRowPolicy) -> Self {
+    Self {
+      key_column: None,
+      policy,
+    }
+  }
+
+  pub fn with_key_column<T: ToString>(mut self, key_column: T) -> Self {
+    self.key_column = Some(key_column.to_string());
+    self
+  }
+
+  /// Return the field of the key column, matched by name ignoring the case, or the primary field.
+  pub(crate) fn key_field<'a>(&self, fields: &'a [Field]) -> Option<&'a Field> {
+    self
+      .key_column
+      .as_deref()
+      .and_then(|key_column| {
+        fields.iter().find(|field| {
+          field
+            .name
+            .trim_start_matches('\u{feff}')
+            .trim()
+            .eq_ignore_ascii_case(key_column.trim())
+        })
+      })
+      .or_else(|| fields.iter().find(|field| field.is_primary))
+  }
+}
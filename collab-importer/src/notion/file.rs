@@ -32,6 +32,13 @@ pub enum NotionFile {
     size: u64,
     resources: Vec<Resource>,
   },
+  /// A page of an html export. Notion's html exports keep the colors, the toggles and the
+  /// columns that its markdown exports drop.
+  Html {
+    file_path: PathBuf,
+    size: u64,
+    resources: Vec<Resource>,
+  },
 }
 
 impl NotionFile {
@@ -39,6 +46,15 @@ impl NotionFile {
     matches!(self, NotionFile::Markdown { .. })
   }
 
+  pub fn is_html(&self) -> bool {
+    matches!(self, NotionFile::Html { .. })
+  }
+
+  /// Whether the page is imported as a document, from a markdown or an html file.
+  pub fn is_document(&self) -> bool {
+    self.is_markdown() || self.is_html()
+  }
+
   pub fn is_csv(&self) -> bool {
     matches!(self, NotionFile::CSV { .. })
   }
@@ -46,12 +62,24 @@ impl NotionFile {
     match self {
       NotionFile::CSV { file_path, .. } => Some(file_path),
       NotionFile::Markdown { file_path, .. } => Some(file_path),
+      NotionFile::Html { file_path, .. } => Some(file_path),
       _ => None,
     }
   }
+  /// The size of the file, 0 for the pages without a file.
+  pub fn size(&self) -> u64 {
+    match self {
+      NotionFile::Empty => 0,
+      NotionFile::CSV { size, .. }
+      | NotionFile::CSVPart { size, .. }
+      | NotionFile::Markdown { size, .. }
+      | NotionFile::Html { size, .. } => *size,
+    }
+  }
+
   pub fn upload_files(&self) -> Vec<PathBuf> {
     match self {
-      NotionFile::Markdown { resources, .. } => resources
+      NotionFile::Markdown { resources, .. } | NotionFile::Html { resources, .. } => resources
         .iter()
         .flat_map(|r| r.file_paths())
         .cloned()
@@ -1,8 +1,21 @@
 use crate::error::ImporterError;
 use crate::imported_collab::{ImportType, ImportedCollab, ImportedCollabInfo};
+use crate::name_collision::{
+  ExistingView, NameCollisionAction, NameCollisionPolicy, NameCollisionReport,
+  NameCollisionResolver,
+};
+use crate::notion::api::{NotionApiClient, NotionApiExporter};
+use crate::notion::dedupe::RowDedupeOptions;
 use crate::notion::file::NotionFile;
-use crate::notion::page::{CollabResource, NotionPage, build_imported_collab_recursively};
+use crate::notion::mapping::ImportMapping;
+use crate::notion::page::{
+  CollabBuildHooks, CollabResource, NotionPage, build_imported_collab_recursively_with_hooks,
+};
+use crate::notion::relation::NotionRelationIndex;
 use crate::notion::walk_dir::{file_name_from_path, process_entry, walk_sub_dir};
+use crate::preview::ImportPreviewHook;
+use crate::progress::ImportProgressTracker;
+use collab_database::database_trait::MemberResolver;
 use collab_folder::hierarchy_builder::{
   NestedChildViewBuilder, NestedViews, ParentChildViews, ViewExtraBuilder,
 };
@@ -13,15 +26,20 @@ use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
 
 use crate::space_view::create_space_view;
+use crate::zip_tool::multi_part::unzip_multi_part;
+use crate::zip_tool::safe_unzip::UnzipLimits;
 use anyhow::Error;
 use collab::preclude::Collab;
+use collab_database::template::date_parse::DateParseOptions;
+use collab_database::template::locale::ImportLocale;
 use collab_entity::CollabType;
 use csv::Reader;
 use fancy_regex::Regex;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::sync::{Arc, Mutex};
-use walkdir::WalkDir;
+use tokio_util::sync::CancellationToken;
+use walkdir::{DirEntry, WalkDir};
 
 #[derive(Debug)]
 pub struct NotionImporter {
@@ -30,6 +48,14 @@ pub struct NotionImporter {
   workspace_id: String,
   path: PathBuf,
   workspace_name: String,
+  locale: Arc<ImportLocale>,
+  preview_hook: Option<Arc<dyn ImportPreviewHook>>,
+  progress: Option<ImportProgressTracker>,
+  cancel_token: Option<CancellationToken>,
+  resolve_relations: bool,
+  row_dedupe: Option<RowDedupeOptions>,
+  import_mapping: Option<ImportMapping>,
+  member_resolver: Option<Arc<dyn MemberResolver>>,
   pub views: Option<NotionPage>,
 }
 
@@ -59,50 +85,263 @@ impl NotionImporter {
       workspace_id: workspace_id.to_string(),
       path,
       workspace_name,
+      locale: Arc::new(ImportLocale::default()),
+      preview_hook: None,
+      progress: None,
+      cancel_token: None,
+      resolve_relations: false,
+      row_dedupe: None,
+      import_mapping: None,
+      member_resolver: None,
       views: None,
     })
   }
 
+  /// Create an importer for a Notion export split in several zip files, e.g.
+  /// `Export-abc-Part-1.zip` and `Export-abc-Part-2.zip`. Each path is a part or a directory of
+  /// parts. The parts are extracted into `out_dir` and merged, see [unzip_multi_part].
+  pub async fn from_zip_parts<S: ToString>(
+    uid: i64,
+    parts: &[PathBuf],
+    out_dir: &Path,
+    workspace_id: S,
+    host: String,
+  ) -> Result<Self, ImporterError> {
+    let unzip = unzip_multi_part(parts, out_dir, &UnzipLimits::default()).await?;
+    Self::new(uid, unzip.unzip_dir, workspace_id, host)
+  }
+
+  /// Create an importer for the pages and the databases shared with a Notion integration. They
+  /// are read with the Notion API and written to `out_dir/workspace_name` in the layout of a zip
+  /// export, see [NotionApiExporter].
+  pub async fn from_api<S: ToString>(
+    uid: i64,
+    client: NotionApiClient,
+    out_dir: &Path,
+    workspace_name: &str,
+    workspace_id: S,
+    host: String,
+  ) -> Result<Self, ImporterError> {
+    let export_dir = NotionApiExporter::new(client)
+      .export(out_dir, workspace_name)
+      .await?;
+    Self::new(uid, export_dir, workspace_id, host)
+  }
+
+  /// Set the locale used to detect untitled pages and to parse localized CSV values.
+  /// By default, only the English strings are recognized, use [ImportLocale::all] when the
+  /// language of the export is unknown.
+  pub fn with_locale(mut self, locale: ImportLocale) -> Self {
+    self.locale = Arc::new(locale);
+    self
+  }
+
+  /// Set the timezone the dates of the databases were exported in, and how dates like
+  /// `04/03/2024` are read. The dates are imported as UTC timestamps.
+  pub fn with_date_options(mut self, date_options: DateParseOptions) -> Self {
+    Arc::make_mut(&mut self.locale).date_options = date_options;
+    self
+  }
+
+  /// Set the hook invoked with the preview of each imported page, when the collabs are built by
+  /// [ImportedInfo::into_collab_stream].
+  pub fn with_preview_hook(mut self, hook: Arc<dyn ImportPreviewHook>) -> Self {
+    self.preview_hook = Some(hook);
+    self
+  }
+
+  /// Report the files discovered in the export and the pages parsed by
+  /// [ImportedInfo::into_collab_stream] to the tracker.
+  pub fn with_progress(mut self, progress: ImportProgressTracker) -> Self {
+    self.progress = Some(progress);
+    self
+  }
+
+  /// Stop the import when the token is cancelled. The pages discovered before the cancellation
+  /// are returned, and [ImportedInfo::into_collab_stream] ends after the pages built before the
+  /// cancellation, see [ImportedInfo::is_cancelled].
+  pub fn with_cancel_token(mut self, cancel_token: CancellationToken) -> Self {
+    self.cancel_token = Some(cancel_token);
+    self
+  }
+
+  /// Turn the relation columns of the databases into relation fields. The cells of a relation
+  /// column reference pages of another database of the export, or of the same database, with
+  /// their title and link. Without it, the relation columns are imported as text.
+  pub fn with_relations(mut self, enabled: bool) -> Self {
+    self.resolve_relations = enabled;
+    self
+  }
+
+  /// Remove the duplicated rows of the databases, the rows whose cells of the key column match,
+  /// e.g. when a refreshed export of a database is imported again. The first row is kept, and
+  /// its duplicates are merged into it or skipped depending on the policy.
+  pub fn with_row_dedupe(mut self, row_dedupe: RowDedupeOptions) -> Self {
+    self.row_dedupe = Some(row_dedupe);
+    self
+  }
+
+  /// Give the pages, the databases and the rows the ids they were created with by a previous
+  /// import of the export, so that importing the export again updates them instead of
+  /// duplicating them. The mapping completed with the ids of the new pages and rows is returned
+  /// by [ImportedInfo::import_mapping], to be saved for the next import.
+  pub fn with_import_mapping(mut self, mapping: ImportMapping) -> Self {
+    self.import_mapping = Some(mapping);
+    self
+  }
+
+  /// Turn the people columns of the databases, the columns whose cells are all names or emails
+  /// of workspace members, into person fields. Without it, they are imported as text or select
+  /// fields.
+  pub fn with_member_resolver(mut self, resolver: Arc<dyn MemberResolver>) -> Self {
+    self.member_resolver = Some(resolver);
+    self
+  }
+
   /// Return a ImportedInfo struct that contains all the views and their children recursively.
   pub async fn import(mut self) -> Result<ImportedInfo, ImporterError> {
     let views = self.collect_pages().await?;
+    self.imported_info(views).await
+  }
+
+  /// Import a single page of the export, with its sub pages and its files, under an existing
+  /// view instead of a new space. The page is the file or the directory of the page, either
+  /// relative to the export or absolute. See [ImportedInfo::with_parent_view].
+  pub async fn import_page_into<P: AsRef<Path>>(
+    mut self,
+    parent_view_id: &str,
+    page_path: P,
+  ) -> Result<ImportedInfo, ImporterError> {
+    let page_path = self.path.join(page_path.as_ref());
+    if !page_path.exists() {
+      return Err(ImporterError::InvalidPath(format!(
+        "Path: does not exist: {:?}",
+        page_path
+      )));
+    }
+
+    if let Some(progress) = &self.progress {
+      let path = page_path.clone();
+      let num_of_files = tokio::task::spawn_blocking(move || count_files(&path))
+        .await
+        .unwrap_or_default();
+      progress.files_discovered(num_of_files);
+    }
+
+    let notion_export = self.export_context().await;
+    let host = self.host.clone();
+    let workspace_id = self.workspace_id.clone();
+    let page = tokio::task::spawn_blocking(move || {
+      let entry = page_entry(&page_path)?;
+      process_entry(&host, &workspace_id, &entry, false, &notion_export)
+    })
+    .await
+    .map_err(|err| ImporterError::Internal(err.into()))?;
+
+    let views = page.into_iter().collect();
+    let info = self.imported_info(views).await?;
+    Ok(info.with_parent_view(parent_view_id))
+  }
+
+  async fn imported_info(
+    &mut self,
+    mut views: Vec<NotionPage>,
+  ) -> Result<ImportedInfo, ImporterError> {
     if views.is_empty() {
-      return Err(ImporterError::CannotImport);
+      return Err(if self.is_cancelled() {
+        ImporterError::Cancelled
+      } else {
+        ImporterError::CannotImport
+      });
+    }
+    if let Some(mapping) = &mut self.import_mapping {
+      mapping.assign_view_ids(&mut views, &self.path);
     }
+    link_workspace_pages(&mut views);
+    if let Some(row_dedupe) = &self.row_dedupe {
+      for_each_page_mut(&mut views, &mut |page| {
+        page.row_dedupe = Some(row_dedupe.clone())
+      });
+    }
+    if let Some(resolver) = &self.member_resolver {
+      for_each_page_mut(&mut views, &mut |page| {
+        page.member_resolver = Some(resolver.clone())
+      });
+    }
+    // The ids of the databases and of their rows are mapped by the relation index
+    if self.resolve_relations || self.import_mapping.is_some() {
+      let relations =
+        NotionRelationIndex::build(&views, self.import_mapping.as_ref(), self.resolve_relations)
+          .await;
+      if let Some(mapping) = &mut self.import_mapping {
+        relations.record_ids(mapping);
+      }
+      let relations = Arc::new(relations);
+      for_each_page_mut(&mut views, &mut |page| {
+        page.relations = Some(relations.clone())
+      });
+    }
+    refresh_csv_relation(&mut views);
 
-    ImportedInfo::new(
+    let info = ImportedInfo::new(
       self.uid,
       self.workspace_id.clone(),
       self.host.clone(),
       self.workspace_name.clone(),
       views,
-    )
+    )?;
+    let info = match self.preview_hook.take() {
+      Some(hook) => info.with_preview_hook(hook),
+      None => info,
+    };
+    let info = match self.progress.take() {
+      Some(progress) => info.with_progress(progress),
+      None => info,
+    };
+    let info = match self.import_mapping.take() {
+      Some(mapping) => info.with_import_mapping(mapping),
+      None => info,
+    };
+    Ok(match self.cancel_token.take() {
+      Some(cancel_token) => info.with_cancel_token(cancel_token),
+      None => info,
+    })
   }
 
-  async fn collect_pages(&mut self) -> Result<Vec<NotionPage>, ImporterError> {
+  fn is_cancelled(&self) -> bool {
+    self
+      .cancel_token
+      .as_ref()
+      .is_some_and(CancellationToken::is_cancelled)
+  }
+
+  pub(crate) async fn collect_pages(&mut self) -> Result<Vec<NotionPage>, ImporterError> {
     let mut has_spaces = false;
     let mut has_pages = false;
 
-    let path = self.path.clone();
-    let csv_relation = tokio::task::spawn_blocking(move || {
-      find_parent_child_csv_relationships(&path).unwrap_or_default()
-    })
-    .await
-    .unwrap_or_default();
-
-    let no_subpages = !has_subdirectories(&self.path, 1);
-    let notion_export = NotionExportContext {
-      csv_relation,
-      no_subpages,
-    };
+    if let Some(progress) = &self.progress {
+      let path = self.path.clone();
+      let num_of_files = tokio::task::spawn_blocking(move || count_files(&path))
+        .await
+        .unwrap_or_default();
+      progress.files_discovered(num_of_files);
+    }
 
+    let notion_export = self.export_context().await;
     let path = self.path.clone();
     let host = self.host.clone();
     let workspace_id = self.workspace_id.clone();
+    let cancel_token = self.cancel_token.clone();
     let pages = tokio::task::spawn_blocking(move || {
       // Process entries and track whether we have spaces (directories) and pages (non-directories)
       let mut notion_pages: Vec<NotionPage> = vec![];
       for entry in walk_sub_dir(&path) {
+        if cancel_token
+          .as_ref()
+          .is_some_and(CancellationToken::is_cancelled)
+        {
+          break;
+        }
         if let Some(view) = process_entry(&host, &workspace_id, &entry, false, &notion_export) {
           has_spaces |= view.is_dir;
           has_pages |= !view.is_dir;
@@ -134,6 +373,37 @@ impl NotionImporter {
 
     Ok(pages)
   }
+
+  async fn export_context(&self) -> NotionExportContext {
+    let path = self.path.clone();
+    let csv_relation = tokio::task::spawn_blocking(move || {
+      find_parent_child_csv_relationships(&path).unwrap_or_default()
+    })
+    .await
+    .unwrap_or_default();
+
+    let no_subpages = !has_subdirectories(&self.path, 1);
+    NotionExportContext {
+      csv_relation,
+      no_subpages,
+      locale: self.locale.clone(),
+    }
+  }
+}
+
+/// The entry of the page at the path. The sub pages of a page are in the directory next to its
+/// file, so the directory is used when the path is the file of a page with sub pages.
+fn page_entry(page_path: &Path) -> Option<DirEntry> {
+  let mut path = page_path.to_path_buf();
+  if page_path.is_file() {
+    let stem = page_path.file_stem()?.to_string_lossy();
+    let stem = stem.strip_suffix("_all").unwrap_or(&stem);
+    let dir = page_path.with_file_name(stem);
+    if dir.is_dir() {
+      path = dir;
+    }
+  }
+  WalkDir::new(path).max_depth(0).into_iter().next()?.ok()
 }
 
 #[derive(Debug)]
@@ -145,6 +415,14 @@ pub struct ImportedInfo {
   views: Vec<NotionPage>,
   space_view: ParentChildViews,
   space_collab: Collab,
+  preview_hook: Option<Arc<dyn ImportPreviewHook>>,
+  progress: Option<ImportProgressTracker>,
+  cancel_token: Option<CancellationToken>,
+  parent_view_id: Option<String>,
+  /// The imported views merged into existing views, by their id, see
+  /// [Self::resolve_name_collisions].
+  merged_view_ids: HashMap<String, String>,
+  import_mapping: Option<ImportMapping>,
 }
 
 pub type ImportedCollabInfoStream<'a> = Pin<Box<dyn Stream<Item = ImportedCollabInfo> + 'a>>;
@@ -173,15 +451,118 @@ impl ImportedInfo {
       views,
       space_view,
       space_collab,
+      preview_hook: None,
+      progress: None,
+      cancel_token: None,
+      parent_view_id: None,
+      merged_view_ids: HashMap::new(),
+      import_mapping: None,
     })
   }
 
+  /// Import the views under an existing view, e.g. a folder of the workspace, instead of a new
+  /// space. The collab of the space isn't built by [Self::into_collab_stream] then.
+  pub fn with_parent_view(mut self, parent_view_id: &str) -> Self {
+    self.parent_view_id = Some(parent_view_id.to_string());
+    self
+  }
+
+  /// Keep the views whose path, the names of the view and of its parents joined with `/`, is
+  /// included, with all their sub views. The parents of the included views are kept too.
+  pub fn retain_views<F>(&mut self, is_included: F)
+  where
+    F: Fn(&str) -> bool,
+  {
+    retain_pages(&mut self.views, "", &is_included);
+  }
+
+  /// Apply the policy to the views whose names are used by the views of the folder they are
+  /// imported into, see [Self::with_parent_view]. The sub pages of a merged page are imported
+  /// under the existing view, and the collab of the merged page is returned by
+  /// [Self::into_collab_stream] to be appended with
+  /// [crate::name_collision::merge_imported_document].
+  pub fn resolve_name_collisions(
+    &mut self,
+    existing_views: &[ExistingView],
+    policy: NameCollisionPolicy,
+  ) -> NameCollisionReport {
+    let mut resolver = NameCollisionResolver::new(policy, existing_views);
+    for mut view in std::mem::take(&mut self.views) {
+      let can_merge = !view.notion_file.is_csv();
+      match resolver.resolve(&view.view_id, &view.notion_name, can_merge) {
+        NameCollisionAction::Imported => {},
+        NameCollisionAction::Renamed { name } => view.notion_name = name,
+        NameCollisionAction::Merged { existing_view_id } => {
+          self
+            .merged_view_ids
+            .insert(view.view_id.clone(), existing_view_id);
+        },
+        NameCollisionAction::Skipped { .. } => continue,
+      }
+      self.views.push(view);
+    }
+    resolver.into_report()
+  }
+
+  /// Set the hook invoked with the preview of each imported page by [Self::into_collab_stream].
+  pub fn with_preview_hook(mut self, hook: Arc<dyn ImportPreviewHook>) -> Self {
+    self.preview_hook = Some(hook);
+    self
+  }
+
+  /// Report the pages parsed by [Self::into_collab_stream] to the tracker.
+  pub fn with_progress(mut self, progress: ImportProgressTracker) -> Self {
+    self.progress = Some(progress);
+    self
+  }
+
+  /// End [Self::into_collab_stream] when the token is cancelled, the pages not built yet are
+  /// skipped.
+  pub fn with_cancel_token(mut self, cancel_token: CancellationToken) -> Self {
+    self.cancel_token = Some(cancel_token);
+    self
+  }
+
+  pub(crate) fn with_import_mapping(mut self, mapping: ImportMapping) -> Self {
+    self.import_mapping = Some(mapping);
+    self
+  }
+
+  /// The mapping of the ids of the import, see [NotionImporter::with_import_mapping]. Save it
+  /// with [ImportMapping::save] to import the export again.
+  pub fn import_mapping(&self) -> Option<&ImportMapping> {
+    self.import_mapping.as_ref()
+  }
+
+  /// Whether the import was cancelled. The views and the collabs are then the partial result of
+  /// the import.
+  pub fn is_cancelled(&self) -> bool {
+    self
+      .cancel_token
+      .as_ref()
+      .is_some_and(CancellationToken::is_cancelled)
+  }
+
   pub fn views(&self) -> &Vec<NotionPage> {
     &self.views
   }
 
+  /// The id of the view of the page with the given Notion id, searched in all the imported
+  /// pages.
+  pub fn view_id_by_notion_id(&self, notion_id: &str) -> Option<String> {
+    fn search_view(views: &[NotionPage], notion_id: &str) -> Option<String> {
+      views.iter().find_map(|view| {
+        if view.notion_id.as_deref() == Some(notion_id) {
+          return Some(view.view_id.clone());
+        }
+        search_view(&view.children, notion_id)
+      })
+    }
+    search_view(&self.views, notion_id)
+  }
+
   fn has_space_view(&self) -> bool {
-    !self.views.iter().any(|view| !view.is_dir)
+    self.parent_view_id.is_some() || !self.views.iter().any(|view| !view.is_dir)
   }
 
   fn space_ids(&self) -> Vec<String> {
@@ -197,10 +578,15 @@ impl ImportedInfo {
   pub async fn into_collab_stream(self) -> ImportedCollabInfoStream<'static> {
     // Create a stream for each view by resolving the futures into streams
     let has_space = self.has_space_view();
-    let view_streams = self
-      .views
-      .into_iter()
-      .map(move |view| async move { build_imported_collab_recursively(view).await });
+    let hooks = CollabBuildHooks {
+      preview_hook: self.preview_hook.clone(),
+      progress: self.progress.clone(),
+      cancel_token: self.cancel_token.clone(),
+    };
+    let view_streams = self.views.into_iter().map(move |view| {
+      let hooks = hooks.clone();
+      async move { build_imported_collab_recursively_with_hooks(view, hooks).await }
+    });
 
     if has_space {
       let combined_stream = stream::iter(view_streams)
@@ -237,6 +623,11 @@ impl ImportedInfo {
   }
 
   pub async fn build_nested_views(&self) -> NestedViews {
+    if let Some(parent_view_id) = &self.parent_view_id {
+      let views = self.convert_views(parent_view_id).await;
+      return NestedViews { views };
+    }
+
     let space_ids = self.space_ids();
     let parent_id = if space_ids.is_empty() {
       self.space_view.view.id.clone()
@@ -244,10 +635,7 @@ impl ImportedInfo {
       self.workspace_id.clone()
     };
 
-    let mut views: Vec<ParentChildViews> = stream::iter(&self.views)
-      .then(|notion_page| convert_notion_page_to_parent_child(&parent_id, notion_page, self.uid))
-      .collect()
-      .await;
+    let mut views = self.convert_views(&parent_id).await;
 
     let views = if space_ids.is_empty() {
       let mut space_view = self.space_view.clone();
@@ -271,6 +659,26 @@ impl ImportedInfo {
     NestedViews { views }
   }
 
+  /// The views of the imported pages under the parent. The sub pages of a merged page are put
+  /// under the existing view instead of the page.
+  async fn convert_views(&self, parent_id: &str) -> Vec<ParentChildViews> {
+    let mut views = vec![];
+    for notion_page in &self.views {
+      match self.merged_view_ids.get(&notion_page.view_id) {
+        Some(existing_view_id) => {
+          for child in &notion_page.children {
+            views
+              .push(convert_notion_page_to_parent_child(existing_view_id, child, self.uid).await);
+          }
+        },
+        None => {
+          views.push(convert_notion_page_to_parent_child(parent_id, notion_page, self.uid).await)
+        },
+      }
+    }
+    views
+  }
+
   pub fn num_of_csv(&self) -> usize {
     self
       .views
@@ -286,6 +694,85 @@ impl ImportedInfo {
       .map(|view| view.num_of_markdown())
       .sum::<usize>()
   }
+
+  pub fn num_of_html(&self) -> usize {
+    self
+      .views
+      .iter()
+      .map(|view| view.num_of_html())
+      .sum::<usize>()
+  }
+}
+
+/// Give the pages the view ids of all the pages, so that the links between the pages are
+/// imported as mentions wherever the linked page is.
+pub(crate) fn link_workspace_pages(pages: &mut [NotionPage]) {
+  fn collect_view_ids(pages: &[NotionPage], view_ids: &mut HashMap<String, String>) {
+    for page in pages {
+      if let Some(notion_id) = &page.notion_id {
+        view_ids
+          .entry(notion_id.clone())
+          .or_insert_with(|| page.view_id.clone());
+      }
+      collect_view_ids(&page.children, view_ids);
+    }
+  }
+
+  fn set_view_ids(pages: &mut [NotionPage], view_ids: &Arc<HashMap<String, String>>) {
+    for page in pages.iter_mut() {
+      page.workspace_view_ids = Some(view_ids.clone());
+      set_view_ids(&mut page.children, view_ids);
+      if let NotionFile::CSV { row_documents, .. } = &mut page.notion_file {
+        for row_document in row_documents.iter_mut() {
+          set_view_ids(std::slice::from_mut(&mut row_document.page), view_ids);
+        }
+      }
+    }
+  }
+
+  let mut view_ids = HashMap::new();
+  collect_view_ids(pages, &mut view_ids);
+  set_view_ids(pages, &Arc::new(view_ids));
+}
+
+/// Return whether a page was kept.
+fn retain_pages<F>(pages: &mut Vec<NotionPage>, path: &str, is_included: &F) -> bool
+where
+  F: Fn(&str) -> bool,
+{
+  pages.retain_mut(|page| {
+    let page_path = if path.is_empty() {
+      page.notion_name.clone()
+    } else {
+      format!("{}/{}", path, page.notion_name)
+    };
+    is_included(&page_path) || retain_pages(&mut page.children, &page_path, is_included)
+  });
+  !pages.is_empty()
+}
+
+/// Call `f` with each page and its sub pages, a page before its sub pages.
+pub(crate) fn for_each_page_mut<F>(pages: &mut [NotionPage], f: &mut F)
+where
+  F: FnMut(&mut NotionPage),
+{
+  for page in pages.iter_mut() {
+    f(page);
+    for_each_page_mut(&mut page.children, f);
+  }
+}
+
+/// Store the databases in their [CSVRelation] again, the databases linked from the documents are
+/// looked up in it. The relation holds copies of the pages, so it's refreshed once all the
+/// options of the pages are set.
+pub(crate) fn refresh_csv_relation(pages: &mut [NotionPage]) {
+  for_each_page_mut(pages, &mut |page| {
+    if let NotionFile::CSV { file_path, .. } = &page.notion_file {
+      page
+        .csv_relation
+        .set_page_by_path_buf(file_path.clone(), page.clone());
+    }
+  });
 }
 
 #[async_recursion::async_recursion]
@@ -299,6 +786,7 @@ async fn convert_notion_page_to_parent_child(
     NotionFile::CSV { .. } => ViewLayout::Grid,
     NotionFile::CSVPart { .. } => ViewLayout::Grid,
     NotionFile::Markdown { .. } => ViewLayout::Document,
+    NotionFile::Html { .. } => ViewLayout::Document,
   };
   let mut view_builder = NestedChildViewBuilder::new(uid, parent_id.to_string())
     .with_name(&notion_page.notion_name)
@@ -319,6 +807,7 @@ async fn convert_notion_page_to_parent_child(
 pub struct NotionExportContext {
   pub csv_relation: CSVRelation,
   pub no_subpages: bool,
+  pub locale: Arc<ImportLocale>,
 }
 
 /// [CSVRelation] manages parent-child relationships between CSV files exported in zip format from Notion.
@@ -506,6 +995,18 @@ fn has_subdirectories(path: &PathBuf, max_depth: usize) -> bool {
     .any(|entry| entry.file_type().is_dir() && entry.path() != path)
 }
 
+/// The number of files of the export, the hidden files excluded.
+fn count_files(path: &PathBuf) -> usize {
+  WalkDir::new(path)
+    .into_iter()
+    .filter_entry(|entry| {
+      entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.')
+    })
+    .filter_map(Result::ok)
+    .filter(|entry| entry.file_type().is_file())
+    .count()
+}
+
 #[cfg(test)]
 mod test_csv_relation {
   use super::*;
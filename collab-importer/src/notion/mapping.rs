@@ -0,0 +1,111 @@
+use crate::error::ImporterError;
+use crate::notion::importer::for_each_page_mut;
+use crate::notion::page::NotionPage;
+use collab_database::database::gen_row_id;
+use collab_database::rows::RowId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tokio::fs;
+
+/// The ids created by the import of a Notion export, by the identifiers of the export, see
+/// [crate::notion::NotionImporter::with_import_mapping].
+///
+/// When the same export, or a refreshed export of the same workspace, is imported again with the
+/// mapping of the previous import, the pages, the databases and the rows get the ids they were
+/// created with. Their collabs then update the existing views and rows instead of duplicating
+/// them. Import them under the same parent view, see
+/// [crate::notion::ImportedInfo::with_parent_view], so that no new space is created.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportMapping {
+  /// The view ids by the Notion id of the pages, or by the path of the pages without Notion id,
+  /// relative to the export.
+  #[serde(default)]
+  pub views: HashMap<String, String>,
+  /// The database ids by the Notion id of the database pages.
+  #[serde(default)]
+  pub databases: HashMap<String, String>,
+  /// The row ids by the Notion id of the database pages, then by the title of the rows. The rows
+  /// with the same title are matched in the order of the csv.
+  #[serde(default)]
+  pub rows: HashMap<String, HashMap<String, Vec<String>>>,
+}
+
+impl ImportMapping {
+  /// Read the mapping saved by [Self::save]. An empty mapping is returned if the file doesn't
+  /// exist, e.g. for the first import.
+  pub async fn load(path: &Path) -> Result<Self, ImporterError> {
+    if !fs::try_exists(path).await? {
+      return Ok(Self::default());
+    }
+    let content = fs::read_to_string(path).await?;
+    serde_json::from_str(&content).map_err(|err| ImporterError::Internal(err.into()))
+  }
+
+  pub async fn save(&self, path: &Path) -> Result<(), ImporterError> {
+    let content =
+      serde_json::to_string_pretty(self).map_err(|err| ImporterError::Internal(err.into()))?;
+    fs::write(path, content).await?;
+    Ok(())
+  }
+
+  pub fn database_id(&self, notion_id: &str) -> Option<&str> {
+    self.databases.get(notion_id).map(String::as_str)
+  }
+
+  /// Give the pages the view ids of the previous import, and record the view ids of the new
+  /// pages. The entries of the pages missing from the export are kept.
+  pub(crate) fn assign_view_ids(&mut self, pages: &mut [NotionPage], export_path: &Path) {
+    for_each_page_mut(pages, &mut |page| {
+      if let Some(key) = view_key(page, export_path) {
+        match self.views.get(&key) {
+          Some(view_id) => page.view_id = view_id.clone(),
+          None => {
+            self.views.insert(key, page.view_id.clone());
+          },
+        }
+      }
+    });
+  }
+
+  /// The ids of the rows of the database with the titles, the ids of the previous import or new
+  /// ones.
+  pub(crate) fn row_ids(&self, notion_id: &str, titles: &[&str]) -> Vec<RowId> {
+    let row_ids_by_title = self.rows.get(notion_id);
+    let mut occurrences = HashMap::new();
+    titles
+      .iter()
+      .map(|title| {
+        let occurrence = occurrences.entry(*title).or_insert(0);
+        let row_id = row_ids_by_title
+          .and_then(|row_ids| row_ids.get(*title))
+          .and_then(|row_ids| row_ids.get(*occurrence))
+          .map(|row_id| RowId::from(row_id.clone()))
+          .unwrap_or_else(gen_row_id);
+        *occurrence += 1;
+        row_id
+      })
+      .collect()
+  }
+
+  /// Record the ids of the rows of the database, in the order of their titles.
+  pub(crate) fn insert_rows(&mut self, notion_id: &str, titles: &[String], row_ids: &[RowId]) {
+    let mut row_ids_by_title: HashMap<String, Vec<String>> = HashMap::new();
+    for (title, row_id) in titles.iter().zip(row_ids) {
+      row_ids_by_title
+        .entry(title.clone())
+        .or_default()
+        .push(row_id.to_string());
+    }
+    self.rows.insert(notion_id.to_string(), row_ids_by_title);
+  }
+}
+
+fn view_key(page: &NotionPage, export_path: &Path) -> Option<String> {
+  if let Some(notion_id) = &page.notion_id {
+    return Some(notion_id.clone());
+  }
+  let path = page.notion_file.file_path()?;
+  let path = path.strip_prefix(export_path).unwrap_or(path.as_path());
+  Some(path.to_string_lossy().to_string())
+}
@@ -1,6 +1,12 @@
+pub mod api;
+mod batch;
+pub mod dedupe;
 pub mod file;
 pub mod importer;
+pub mod mapping;
 pub mod page;
+pub mod relation;
 mod walk_dir;
 
+pub use batch::*;
 pub use importer::*;
@@ -1,23 +1,33 @@
 use crate::error::ImporterError;
 use crate::imported_collab::{ImportType, ImportedCollab, ImportedCollabInfo};
 
+use collab::preclude::Any;
 use collab_database::database::{Database, get_row_document_id};
+use collab_database::entity::FieldType;
+use collab_database::fields::{FieldVisibility, VISIBILITY};
 use collab_database::template::csv::{CSVResource, CSVTemplate};
+use collab_database::template::locale::ImportLocale;
 use collab_document::blocks::{BlockType, TextDelta, mention_block_data, mention_block_delta};
 use collab_document::document::Document;
-use collab_document::importer::define::URL_FIELD;
+use collab_document::importer::define::{FILE_SIZE_FIELD, URL_FIELD};
 use collab_document::importer::md_importer::{MDImporter, create_image_block};
+use collab_document::provenance::BlockProvenance;
 use collab_entity::CollabType;
 use futures::stream::{self, StreamExt};
 
+use crate::notion::dedupe::RowDedupeOptions;
 use crate::notion::file::NotionFile;
 use crate::notion::walk_dir::{extract_delta_link, extract_external_links};
+use crate::notion::relation::NotionRelationIndex;
 use crate::notion::{CSVRelation, ImportedCollabInfoStream};
+use crate::preview::{ImportPreviewHook, ImportedPagePreview};
+use crate::progress::ImportProgressTracker;
 use crate::util::{FileId, upload_file_url};
 use collab::core::collab::default_client_id;
-use collab_database::database_trait::NoPersistenceDatabaseCollabService;
+use collab_database::database_trait::{MemberResolver, NoPersistenceDatabaseCollabService};
 use collab_database::rows::RowId;
 use collab_database::template::builder::FileUrlBuilder;
+use collab_database::views::FieldSettingsMapBuilder;
 use collab_document::document_data::default_document_data;
 use csv::Reader;
 use percent_encoding::percent_decode_str;
@@ -29,7 +39,8 @@ use std::future::Future;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::fs;
-use tracing::error;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
 
 fn normalize_csv_header(header: &str) -> String {
   header.trim().to_lowercase()
@@ -88,7 +99,7 @@ fn title_column_candidates(headers: &[String]) -> Vec<usize> {
   out
 }
 
-fn parse_csv_from_str(content: &str) -> Option<(Vec<String>, Vec<Vec<String>>)> {
+pub(crate) fn parse_csv_from_str(content: &str) -> Option<(Vec<String>, Vec<Vec<String>>)> {
   let mut reader = Reader::from_reader(content.as_bytes());
   let headers = reader
     .headers()
@@ -111,7 +122,7 @@ fn parse_csv_from_str(content: &str) -> Option<(Vec<String>, Vec<Vec<String>>)>
   Some((headers, rows))
 }
 
-fn select_title_column_index(
+pub(crate) fn select_title_column_index(
   headers: &[String],
   rows: &[Vec<String>],
   row_titles: &HashSet<String>,
@@ -149,6 +160,44 @@ fn select_title_column_index(
   }
 }
 
+/// Read the headers of the csv of the view matching the `_all.csv` file. Return None when the
+/// export doesn't contain it.
+async fn read_view_csv_headers(all_csv_path: &Path) -> Option<HashSet<String>> {
+  let file_name = all_csv_path.file_name()?.to_str()?;
+  let stem = file_name.strip_suffix("_all.csv")?;
+  let view_csv_path = all_csv_path.with_file_name(format!("{}.csv", stem));
+  let content = fs::read_to_string(view_csv_path).await.ok()?;
+  let (headers, _) = parse_csv_from_str(&content)?;
+  let headers = headers
+    .iter()
+    .map(|header| header.trim_start_matches('\u{feff}').trim().to_string())
+    .collect::<HashSet<_>>();
+  if headers.is_empty() {
+    return None;
+  }
+  Some(headers)
+}
+
+/// Turn the text and select columns whose cells are all names or emails of workspace members
+/// into person fields.
+async fn convert_person_columns(database: &mut Database, resolver: &dyn MemberResolver) {
+  for field in database.get_all_fields() {
+    let field_type = FieldType::from(field.field_type);
+    if field.is_primary || !(field_type.is_text() || field_type.is_select_option()) {
+      continue;
+    }
+    if let Err(err) = database
+      .convert_to_person_field(&field.id, resolver, false)
+      .await
+    {
+      warn!(
+        "Failed to convert {} to a person field: {}",
+        field.name, err
+      );
+    }
+  }
+}
+
 fn reorder_csv_template_primary_column(csv_template: &mut CSVTemplate, title_idx: usize) {
   if title_idx == 0 {
     return;
@@ -180,6 +229,20 @@ pub struct NotionPage {
   pub host: String,
   pub is_dir: bool,
   pub csv_relation: CSVRelation,
+  pub locale: Arc<ImportLocale>,
+  /// Set when the relation columns of the databases are resolved or when the ids of the import
+  /// are mapped, see [crate::notion::NotionImporter::with_relations] and
+  /// [crate::notion::NotionImporter::with_import_mapping].
+  pub relations: Option<Arc<NotionRelationIndex>>,
+  /// The view ids of all the pages of the export by their Notion id, used to turn the links to
+  /// the pages outside of this page into mentions.
+  pub workspace_view_ids: Option<Arc<HashMap<String, String>>>,
+  /// Set when the duplicated rows of the databases are removed, see
+  /// [crate::notion::NotionImporter::with_row_dedupe].
+  pub row_dedupe: Option<RowDedupeOptions>,
+  /// Set when the people columns of the databases are turned into person fields, see
+  /// [crate::notion::NotionImporter::with_member_resolver].
+  pub member_resolver: Option<Arc<dyn MemberResolver>>,
 }
 
 impl NotionPage {
@@ -217,6 +280,20 @@ impl NotionPage {
       }
   }
 
+  /// Returns the number of html files in the view and its children.
+  pub fn num_of_html(&self) -> usize {
+    self
+      .children
+      .iter()
+      .map(|view| view.num_of_html())
+      .sum::<usize>()
+      + if matches!(self.notion_file, NotionFile::Html { .. }) {
+        1
+      } else {
+        0
+      }
+  }
+
   pub fn get_external_link_notion_view(&self) -> HashMap<String, NotionPage> {
     let mut linked_views = HashMap::new();
     for links in self.external_links.iter() {
@@ -276,11 +353,20 @@ impl NotionPage {
   pub async fn as_document(&self) -> Result<(Document, CollabResource), ImporterError> {
     let external_link_views = self.get_external_link_notion_view();
     match &self.notion_file {
-      NotionFile::Markdown { file_path, .. } => {
+      NotionFile::Markdown { file_path, .. } | NotionFile::Html { file_path, .. } => {
         let resource_paths = self.notion_file.upload_files();
-        let md_importer = MDImporter::new(None);
+        let md_importer = MDImporter::new(None)
+          .with_embeds(true)
+          .with_media_embeds(true)
+          .with_file_blocks(true);
         let content = fs::read_to_string(file_path).await?;
-        let document_data = md_importer.import(&self.view_id, content)?;
+        // The html is converted to markdown by the importer, so both exports share the
+        // processing of the links and the resources below.
+        let document_data = if self.notion_file.is_html() {
+          md_importer.import_html(&self.view_id, &content)?
+        } else {
+          md_importer.import(&self.view_id, content)?
+        };
         let mut document = Document::create(&self.view_id, document_data, default_client_id())?;
 
         let url_builder = |view_id, path| async move {
@@ -316,6 +402,9 @@ impl NotionPage {
           .chain(valid_delta_resources.into_iter())
           .collect::<Vec<_>>();
 
+        let source_path = file_path.file_name().unwrap_or_default().to_string_lossy();
+        document.tag_provenance(&BlockProvenance::new("notion", source_path))?;
+
         let files = all_resources
           .iter()
           .filter_map(|p| p.to_str().map(|s| s.to_string()))
@@ -379,22 +468,32 @@ impl NotionPage {
   {
     // Process the current block
     if let Some((block_type, mut block_data)) = document.get_block_data(block_id) {
-      if matches!(block_type, BlockType::Image) {
-        if let Some(image_url) = block_data
+      // The files of the image, video, audio and file blocks are uploaded with the page
+      if matches!(
+        block_type,
+        BlockType::Image | BlockType::Video | BlockType::Audio | BlockType::File
+      ) {
+        if let Some(file_url) = block_data
           .get(URL_FIELD)
           .and_then(|v| v.as_str())
           .and_then(|s| percent_decode_str(s).decode_utf8().ok())
         {
-          let full_image_url = parent_path.join(image_url.to_string());
-          let pos = resources.iter().position(|r| r == &full_image_url);
+          let full_file_path = parent_path.join(file_url.to_string());
+          let pos = resources.iter().position(|r| r == &full_file_path);
           if let Some(pos) = pos {
-            if let Some(url) = file_url_builder(&self.view_id, full_image_url).await {
+            // Keep the size of the file, the link in the markdown doesn't tell it
+            if matches!(block_type, BlockType::File) {
+              if let Ok(metadata) = fs::metadata(&full_file_path).await {
+                block_data.insert(FILE_SIZE_FIELD.to_string(), json!(metadata.len()));
+              }
+            }
+            if let Some(url) = file_url_builder(&self.view_id, full_file_path).await {
               document_resources.insert(resources[pos].clone());
               block_data.insert(URL_FIELD.to_string(), json!(url));
               if let Err(err) = document.update_block(block_id, block_data) {
                 error!(
-                  "Failed to update block when trying to replace image. error:{:?}",
-                  err
+                  "Failed to update block when trying to replace {} file. error:{:?}",
+                  block_type, err
                 );
               }
             }
@@ -561,7 +660,7 @@ impl NotionPage {
                   .unwrap_or("")
                   .to_ascii_lowercase();
 
-                if !ext.is_empty() && ext != "md" && ext != "csv" {
+                if !ext.is_empty() && ext != "md" && ext != "html" && ext != "csv" {
                   let full_path = parent_path.join(decoded);
                   let pos = resources.iter().position(|r| r == &full_path);
                   if let Some(pos) = pos {
@@ -581,12 +680,14 @@ impl NotionPage {
 
             // Replace links in the deltas with the corresponding view IDs
             if let Ok(links) = extract_external_links(&delta_str) {
-              for link in &links {
-                if let Some(view) = external_link_views.get(&link.id) {
-                  is_changed = true;
-                  *delta = mention_block_delta(&view.view_id);
-                  break;
-                }
+              let view_id = links
+                .iter()
+                .find_map(|link| external_link_views.get(&link.id))
+                .map(|view| view.view_id.clone())
+                .or_else(|| self.workspace_view_id(links.last()?));
+              if let Some(view_id) = view_id {
+                is_changed = true;
+                *delta = mention_block_delta(&view_id);
               }
 
               self.update_paragraph_block(
@@ -632,6 +733,17 @@ impl NotionPage {
     result
   }
 
+  /// The view id of the page the link points to, searched in all the pages of the export.
+  fn workspace_view_id(&self, link: &ExternalLink) -> Option<String> {
+    if !matches!(
+      link.link_type,
+      ExternalLinkType::Markdown | ExternalLinkType::Html | ExternalLinkType::CSV
+    ) {
+      return None;
+    }
+    self.workspace_view_ids.as_ref()?.get(&link.id).cloned()
+  }
+
   /// Update the paragraph block if the last link points to an external view
   fn update_paragraph_block(
     &self,
@@ -677,8 +789,10 @@ impl NotionPage {
           .filter(|s| !s.is_empty())
           .collect::<HashSet<_>>();
 
-        let title_idx = parse_csv_from_str(&content)
-          .map(|(headers, rows)| select_title_column_index(&headers, &rows, &row_titles))
+        let parsed_csv = parse_csv_from_str(&content);
+        let title_idx = parsed_csv
+          .as_ref()
+          .map(|(headers, rows)| select_title_column_index(headers, rows, &row_titles))
           .unwrap_or(0);
 
         let csv_resource = CSVResource {
@@ -688,10 +802,26 @@ impl NotionPage {
         };
 
         // create csv template, we need to set the view id as csv template view id
-        let mut csv_template =
-          CSVTemplate::try_from_reader(content.as_bytes(), true, Some(csv_resource))?;
+        let mut csv_template = CSVTemplate::try_from_reader_with_locale(
+          content.as_bytes(),
+          true,
+          Some(csv_resource),
+          &self.locale,
+        )?;
         csv_template.reset_view_id(self.view_id.clone());
+        for date in &csv_template.ambiguous_dates {
+          warn!(
+            "Ambiguous date {:?} in column {} of {}: {:?}",
+            date.cell, date.field_name, self.notion_name, date.ambiguities
+          );
+        }
         reorder_csv_template_primary_column(&mut csv_template, title_idx);
+        let relations = self.relations.as_deref().zip(self.notion_id.as_deref());
+        if let Some(database_id) =
+          relations.and_then(|(relations, notion_id)| relations.database_id(notion_id))
+        {
+          csv_template.database_id = database_id.to_string();
+        }
         let database_id = csv_template.database_id.clone();
 
         let file_url_builder = FileUrlBuilderImpl {
@@ -700,13 +830,70 @@ impl NotionPage {
         };
 
         let files = csv_template.resource.as_ref().unwrap().files.clone();
-        let database_template = csv_template
+        let mut database_template = csv_template
           .try_into_database_template(Some(Box::new(file_url_builder)))
           .await?;
+        if let Some((relations, notion_id)) = relations {
+          relations.assign_row_ids(notion_id, &mut database_template);
+        }
         let service = Arc::new(NoPersistenceDatabaseCollabService::new(default_client_id()));
         let mut database =
           Database::create_with_template(database_template, service.clone(), service).await?;
+        if let (Some((relations, notion_id)), Some((headers, rows))) = (relations, &parsed_csv) {
+          relations
+            .convert_relation_columns(notion_id, &mut database, headers, rows)
+            .await;
+        }
+        if let Some(resolver) = &self.member_resolver {
+          convert_person_columns(&mut database, resolver.as_ref()).await;
+        }
         let mut row_documents = row_documents.clone();
+        if let Some(row_dedupe) = &self.row_dedupe {
+          let fields = database.get_all_fields();
+          if let Some(key_field) = row_dedupe.key_field(&fields) {
+            let duplicates = database
+              .dedupe_rows(&[&key_field.id], row_dedupe.policy, false)
+              .await;
+            if !duplicates.is_empty() {
+              info!(
+                "Deduplicated {} rows of {} by {}",
+                duplicates.len(),
+                self.notion_name,
+                key_field.name
+              );
+              // The documents of the removed rows have the title of the kept row
+              if key_field.is_primary {
+                let mut titles = HashSet::new();
+                row_documents.retain(|document| titles.insert(document.page.notion_name.clone()));
+              }
+            }
+          }
+        }
+
+        // The _all.csv contains every property of the database, the csv of the view only the
+        // visible ones. Hide the fields the view doesn't show.
+        if let Some(view_headers) = read_view_csv_headers(file_path).await {
+          let view_id = database.get_first_database_view_id().unwrap();
+          let hidden_field_ids = database
+            .get_all_fields()
+            .into_iter()
+            .filter(|field| {
+              !field.is_primary
+                && !view_headers.contains(field.name.trim_start_matches('\u{feff}').trim())
+            })
+            .map(|field| field.id)
+            .collect::<Vec<_>>();
+          if !hidden_field_ids.is_empty() {
+            database.update_field_settings(
+              &view_id,
+              Some(hidden_field_ids),
+              FieldSettingsMapBuilder::from([(
+                VISIBILITY.into(),
+                Any::BigInt(i64::from(FieldVisibility::AlwaysHidden)),
+              )]),
+            );
+          }
+        }
 
         if let Some(field) = database.get_primary_field() {
           let view_id = database.get_first_database_view_id().unwrap();
@@ -747,8 +934,17 @@ impl NotionPage {
     }
   }
 
-  #[async_recursion::async_recursion(?Send)]
   pub async fn build_imported_collab(&self) -> Result<Option<ImportedCollabInfo>, ImporterError> {
+    self.build_imported_collab_with_hook(None).await
+  }
+
+  /// Same as [Self::build_imported_collab], and pass the preview of each imported document to
+  /// the hook.
+  #[async_recursion::async_recursion(?Send)]
+  pub(crate) async fn build_imported_collab_with_hook(
+    &self,
+    preview_hook: Option<&Arc<dyn ImportPreviewHook>>,
+  ) -> Result<Option<ImportedCollabInfo>, ImporterError> {
     let name = self.notion_name.clone();
     match &self.notion_file {
       NotionFile::CSV { .. } => {
@@ -785,6 +981,13 @@ impl NotionPage {
         let mut row_document_ids = vec![];
         for row_document in content.row_documents {
           if let Ok((document, resource)) = row_document.page.as_document().await {
+            if let Some(hook) = preview_hook {
+              hook.on_page_imported(ImportedPagePreview::from_document(
+                &row_document.page.view_id,
+                &row_document.page.notion_name,
+                &document,
+              ));
+            }
             if let Ok(encoded_collab) = document.encode_collab() {
               resources.push(resource);
               let imported_collab = ImportedCollab {
@@ -798,7 +1001,7 @@ impl NotionPage {
           }
 
           for child in row_document.page.children {
-            if let Ok(Some(value)) = child.build_imported_collab().await {
+            if let Ok(Some(value)) = child.build_imported_collab_with_hook(preview_hook).await {
               imported_collabs.extend(value.imported_collabs);
               resources.extend(value.resources);
             }
@@ -816,8 +1019,15 @@ impl NotionPage {
           },
         }))
       },
-      NotionFile::Markdown { .. } => {
+      NotionFile::Markdown { .. } | NotionFile::Html { .. } => {
         let (document, collab_resource) = self.as_document().await?;
+        if let Some(hook) = preview_hook {
+          hook.on_page_imported(ImportedPagePreview::from_document(
+            &self.view_id,
+            &self.notion_name,
+            &document,
+          ));
+        }
         let encoded_collab = document.encode_collab()?;
         let imported_collab = ImportedCollab {
           object_id: self.view_id.clone(),
@@ -858,17 +1068,51 @@ impl NotionPage {
 pub async fn build_imported_collab_recursively<'a>(
   notion_page: NotionPage,
 ) -> ImportedCollabInfoStream<'a> {
-  let imported_collab_info = notion_page.build_imported_collab().await;
+  build_imported_collab_recursively_with_hooks(notion_page, CollabBuildHooks::default()).await
+}
+
+/// The hooks of [ImportedInfo::into_collab_stream](crate::notion::ImportedInfo::into_collab_stream).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CollabBuildHooks {
+  pub(crate) preview_hook: Option<Arc<dyn ImportPreviewHook>>,
+  pub(crate) progress: Option<ImportProgressTracker>,
+  pub(crate) cancel_token: Option<CancellationToken>,
+}
+
+impl CollabBuildHooks {
+  fn is_cancelled(&self) -> bool {
+    self
+      .cancel_token
+      .as_ref()
+      .is_some_and(CancellationToken::is_cancelled)
+  }
+}
+
+/// Build the collabs of the page and of its children. When the import is cancelled, the pages
+/// that are not built yet are skipped.
+pub(crate) async fn build_imported_collab_recursively_with_hooks<'a>(
+  notion_page: NotionPage,
+  hooks: CollabBuildHooks,
+) -> ImportedCollabInfoStream<'a> {
+  if hooks.is_cancelled() {
+    return Box::pin(stream::empty());
+  }
+  let imported_collab_info = notion_page
+    .build_imported_collab_with_hook(hooks.preview_hook.as_ref())
+    .await;
+  if let (Some(progress), Ok(Some(_))) = (&hooks.progress, &imported_collab_info) {
+    progress.page_parsed(notion_page.notion_file.size());
+  }
   let initial_stream: ImportedCollabInfoStream = match imported_collab_info {
     Ok(Some(info)) => Box::pin(stream::once(async { info })),
     Ok(None) => Box::pin(stream::empty()),
     Err(_) => Box::pin(stream::empty()),
   };
 
-  let child_streams = notion_page
-    .children
-    .into_iter()
-    .map(|child| async move { build_imported_collab_recursively(child).await });
+  let child_streams = notion_page.children.into_iter().map(move |child| {
+    let hooks = hooks.clone();
+    async move { build_imported_collab_recursively_with_hooks(child, hooks).await }
+  });
 
   let child_stream = stream::iter(child_streams)
     .then(|stream_future| stream_future)
@@ -897,6 +1141,7 @@ pub enum ExternalLinkType {
   Unknown,
   CSV,
   Markdown,
+  Html,
 }
 
 #[derive(Debug, Clone)]
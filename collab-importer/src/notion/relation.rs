@@ -0,0 +1,344 @@
+use crate::notion::file::NotionFile;
+use crate::notion::mapping::ImportMapping;
+use crate::notion::page::{NotionPage, parse_csv_from_str, select_title_column_index};
+use crate::notion::walk_dir::name_and_id_from_path;
+use collab_database::database::{Database, gen_row_id};
+use collab_database::entity::FieldType;
+use collab_database::fields::relation_type_option::RelationTypeOption;
+use collab_database::rows::{Cell, RowId};
+use collab_database::template::entity::DatabaseTemplate;
+use collab_database::template::relation_parse::RelationCellData;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tokio::fs;
+use tracing::warn;
+
+/// The databases of a Notion export, used to turn the relation columns of their csv into
+/// relation fields, see [crate::notion::NotionImporter::with_relations].
+///
+/// Notion exports a relation cell as the comma-separated titles of the related pages, each
+/// followed by the link to the page, e.g. `Interpret findings (Tasks 76aa…/Interpret findings
+/// c418….md)`. The ids of the databases and of their rows are generated up front so that a
+/// database can reference the rows of a database that isn't built yet, or taken from the
+/// [ImportMapping] of a previous import.
+#[derive(Debug, Default)]
+pub struct NotionRelationIndex {
+  /// The databases by the Notion id of their page.
+  databases: HashMap<String, RelationDatabase>,
+  /// Whether the relation columns are converted, the index only gives the ids to the databases
+  /// and to their rows otherwise.
+  resolve_columns: bool,
+}
+
+#[derive(Debug)]
+struct RelationDatabase {
+  database_id: String,
+  /// The ids of the rows, in the order of the csv.
+  row_ids: Vec<RowId>,
+  /// The titles of the rows, in the order of the csv.
+  titles: Vec<String>,
+  /// The id of the first row with the title.
+  row_id_by_title: HashMap<String, RowId>,
+  /// The id of the rows exported with a page, by the Notion id of the page.
+  row_id_by_notion_id: HashMap<String, RowId>,
+}
+
+/// A page referenced by a relation cell.
+#[derive(Debug, PartialEq, Eq)]
+struct PageReference {
+  title: String,
+  /// The Notion id of the database containing the page, None when the cell only has the title.
+  database_notion_id: Option<String>,
+  notion_id: Option<String>,
+}
+
+impl NotionRelationIndex {
+  /// Index the databases of the pages and of their children. The databases and the rows of the
+  /// mapping keep their ids.
+  pub(crate) async fn build(
+    pages: &[NotionPage],
+    mapping: Option<&ImportMapping>,
+    resolve_columns: bool,
+  ) -> Self {
+    let mut csv_pages = vec![];
+    collect_csv_pages(pages, &mut csv_pages);
+
+    let mut databases = HashMap::new();
+    for page in csv_pages {
+      let NotionFile::CSV {
+        file_path,
+        row_documents,
+        ..
+      } = &page.notion_file
+      else {
+        continue;
+      };
+      let Some(notion_id) = &page.notion_id else {
+        continue;
+      };
+      let content = match fs::read_to_string(file_path).await {
+        Ok(content) => content,
+        Err(err) => {
+          warn!("Failed to read csv file {:?}: {}", file_path, err);
+          continue;
+        },
+      };
+      let Some((headers, rows)) = parse_csv_from_str(&content) else {
+        continue;
+      };
+
+      let row_titles = row_documents
+        .iter()
+        .map(|d| d.page.notion_name.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect::<HashSet<_>>();
+      let title_idx = select_title_column_index(&headers, &rows, &row_titles);
+      let titles = rows
+        .iter()
+        .map(|row| row.get(title_idx).map(|s| s.trim()).unwrap_or_default())
+        .collect::<Vec<_>>();
+      let row_ids = match mapping {
+        Some(mapping) => mapping.row_ids(notion_id, &titles),
+        None => rows.iter().map(|_| gen_row_id()).collect::<Vec<_>>(),
+      };
+
+      let mut row_id_by_title = HashMap::new();
+      for (title, row_id) in titles.iter().zip(row_ids.iter()) {
+        if !title.is_empty() {
+          row_id_by_title
+            .entry(title.to_string())
+            .or_insert_with(|| row_id.clone());
+        }
+      }
+
+      // The rows with the same title are matched with their page in order
+      let mut row_id_by_notion_id = HashMap::new();
+      let mut matched_rows = HashSet::new();
+      for row_document in row_documents {
+        let Some(row_notion_id) = &row_document.page.notion_id else {
+          continue;
+        };
+        let name = row_document.page.notion_name.trim();
+        let row_index =
+          (0..titles.len()).find(|index| titles[*index] == name && !matched_rows.contains(index));
+        if let Some(row_index) = row_index {
+          matched_rows.insert(row_index);
+          row_id_by_notion_id.insert(row_notion_id.clone(), row_ids[row_index].clone());
+        }
+      }
+
+      databases.insert(
+        notion_id.clone(),
+        RelationDatabase {
+          database_id: mapping
+            .and_then(|mapping| mapping.database_id(notion_id))
+            .map(|database_id| database_id.to_string())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+          row_ids,
+          titles: titles.iter().map(|title| title.to_string()).collect(),
+          row_id_by_title,
+          row_id_by_notion_id,
+        },
+      );
+    }
+    Self {
+      databases,
+      resolve_columns,
+    }
+  }
+
+  /// Record the ids of the databases and of their rows in the mapping.
+  pub(crate) fn record_ids(&self, mapping: &mut ImportMapping) {
+    for (notion_id, database) in &self.databases {
+      mapping
+        .databases
+        .insert(notion_id.clone(), database.database_id.clone());
+      mapping.insert_rows(notion_id, &database.titles, &database.row_ids);
+    }
+  }
+
+  /// The id the database of the page is created with.
+  pub(crate) fn database_id(&self, notion_id: &str) -> Option<&str> {
+    self
+      .databases
+      .get(notion_id)
+      .map(|database| database.database_id.as_str())
+  }
+
+  /// Give the rows of the template the ids generated for the database of the page. Nothing is
+  /// changed if the template doesn't have the rows of the csv.
+  pub(crate) fn assign_row_ids(&self, notion_id: &str, template: &mut DatabaseTemplate) {
+    let Some(database) = self.databases.get(notion_id) else {
+      return;
+    };
+    if template.rows.len() != database.row_ids.len() {
+      warn!(
+        "The database {} has {} rows instead of {}, its relations are not resolved",
+        notion_id,
+        template.rows.len(),
+        database.row_ids.len()
+      );
+      return;
+    }
+    for (row, row_id) in template.rows.iter_mut().zip(database.row_ids.iter()) {
+      row.row_id = row_id.to_string();
+    }
+  }
+
+  /// Convert the columns of the csv whose cells all reference the pages of one database to
+  /// relation fields. A column with a reference that can't be resolved is kept as is, so that
+  /// no title is lost.
+  pub(crate) async fn convert_relation_columns(
+    &self,
+    notion_id: &str,
+    database: &mut Database,
+    headers: &[String],
+    rows: &[Vec<String>],
+  ) {
+    if !self.resolve_columns {
+      return;
+    }
+    let Some(source) = self.databases.get(notion_id) else {
+      return;
+    };
+    let row_ids = database
+      .collect_all_rows(false)
+      .await
+      .into_iter()
+      .flatten()
+      .map(|row| row.id)
+      .collect::<HashSet<_>>();
+    if source
+      .row_ids
+      .iter()
+      .any(|row_id| !row_ids.contains(row_id))
+    {
+      return;
+    }
+
+    let fields = database.get_all_fields();
+    for (column, header) in headers.iter().enumerate() {
+      let Some(field) = fields
+        .iter()
+        .find(|field| !field.is_primary && field.name == *header)
+      else {
+        continue;
+      };
+      let cells = rows
+        .iter()
+        .map(|row| row.get(column).map(String::as_str).unwrap_or_default())
+        .collect::<Vec<_>>();
+      let Some((target, cells)) = self.resolve_column(&cells) else {
+        continue;
+      };
+
+      let field_type = i64::from(FieldType::Relation);
+      let type_option = RelationTypeOption::new(&target.database_id);
+      database.update_field(&field.id, |update| {
+        update
+          .set_field_type(field_type)
+          .set_type_option(field_type, Some(type_option.into()));
+      });
+      for (row_id, related_row_ids) in source.row_ids.iter().zip(cells) {
+        let cell = Cell::from(RelationCellData {
+          row_ids: related_row_ids,
+        });
+        database
+          .update_row(row_id.clone(), |update| {
+            update.update_cells(|cells_update| {
+              cells_update.insert_cell(&field.id, cell);
+            });
+          })
+          .await;
+      }
+    }
+  }
+
+  /// Return the database referenced by the cells and the ids of the rows of each cell, None if
+  /// the cells are not relation cells.
+  fn resolve_column(&self, cells: &[&str]) -> Option<(&RelationDatabase, Vec<Vec<RowId>>)> {
+    let references = cells
+      .iter()
+      .map(|cell| parse_page_references(cell))
+      .collect::<Vec<_>>();
+
+    // Only the links tell which database the column references
+    let mut database_notion_ids = references
+      .iter()
+      .flatten()
+      .filter_map(|reference| reference.database_notion_id.as_deref());
+    let database_notion_id = database_notion_ids.next()?;
+    if database_notion_ids.any(|id| id != database_notion_id) {
+      return None;
+    }
+    let target = self.databases.get(database_notion_id)?;
+
+    let mut resolved = Vec::with_capacity(references.len());
+    for cell_references in references {
+      let mut row_ids = vec![];
+      for reference in cell_references {
+        let row_id = reference
+          .notion_id
+          .as_ref()
+          .and_then(|id| target.row_id_by_notion_id.get(id))
+          .or_else(|| target.row_id_by_title.get(&reference.title))?;
+        if !row_ids.contains(row_id) {
+          row_ids.push(row_id.clone());
+        }
+      }
+      resolved.push(row_ids);
+    }
+    Some((target, resolved))
+  }
+}
+
+fn collect_csv_pages<'a>(pages: &'a [NotionPage], csv_pages: &mut Vec<&'a NotionPage>) {
+  for page in pages {
+    if page.notion_file.is_csv() {
+      csv_pages.push(page);
+    }
+    collect_csv_pages(&page.children, csv_pages);
+  }
+}
+
+/// Parse the pages of a relation cell, e.g. `Task A (Tasks 76aa…/Task A 86ce….md), Task B`.
+fn parse_page_references(cell: &str) -> Vec<PageReference> {
+  let mut references = vec![];
+  let mut rest = cell;
+  while let Some(end) = rest.find(".md)") {
+    let segment = &rest[..end];
+    rest = &rest[end + ".md)".len()..];
+    let Some(link_start) = segment.rfind(" (") else {
+      continue;
+    };
+    let link = format!("{}.md", &segment[link_start + 2..]);
+    let title = segment[..link_start].trim_start_matches(", ").trim();
+
+    let path = Path::new(&link);
+    let notion_id = name_and_id_from_path(path).ok().and_then(|(_, id)| id);
+    let database_notion_id = path
+      .parent()
+      .and_then(|parent| name_and_id_from_path(parent).ok())
+      .and_then(|(_, id)| id);
+    references.push(PageReference {
+      title: title.to_string(),
+      database_notion_id,
+      notion_id,
+    });
+  }
+  references.extend(title_references(rest));
+  references
+}
+
+fn title_references(text: &str) -> Vec<PageReference> {
+  text
+    .split(", ")
+    .map(|title| title.trim())
+    .filter(|title| !title.is_empty())
+    .map(|title| PageReference {
+      title: title.to_string(),
+      database_notion_id: None,
+      notion_id: None,
+    })
+    .collect()
+}
@@ -6,11 +6,13 @@ use markdown::mdast::Node;
 use markdown::{ParseOptions, to_mdast};
 use percent_encoding::percent_decode_str;
 
+use crate::html_folder::links::rewrite_links;
 use crate::notion::NotionExportContext;
 use crate::notion::file::{NotionFile, Resource, process_row_md_content};
 use crate::notion::page::{ExternalLink, ExternalLinkType, ImportedRowDocument, NotionPage};
 use crate::util::parse_csv;
 
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Component, Path, PathBuf};
 use tracing::{error, warn};
@@ -270,28 +272,25 @@ fn find_matching_directory(parent_path: &Path, name: &str, id: Option<&str>) ->
   None
 }
 
-fn find_matching_markdown_file(
+/// Find the markdown or the html file of the page of a directory, see [FileExtension::is_page].
+fn find_matching_page_file(
   parent_path: &Path,
   name: &str,
   id: Option<&str>,
 ) -> Option<(PathBuf, Option<String>)> {
-  let direct = parent_path.join(format!("{}.md", name));
-  if direct.is_file() {
-    if let Ok((file_name, file_id)) = name_and_id_from_path(&direct) {
-      if is_matching_notion_entry(name, id, &file_name, file_id.as_deref()) {
-        return Some((direct, file_id));
+  for ext in ["md", "html"] {
+    let direct = parent_path.join(format!("{}.{}", name, ext));
+    if direct.is_file() {
+      if let Ok((file_name, file_id)) = name_and_id_from_path(&direct) {
+        if is_matching_notion_entry(name, id, &file_name, file_id.as_deref()) {
+          return Some((direct, file_id));
+        }
       }
     }
   }
 
   for entry in walk_sub_dir(parent_path) {
-    if entry.path().is_file()
-      && entry
-        .path()
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
-    {
+    if entry.path().is_file() && get_file_extension(entry.path(), false).is_page() {
       if let Ok((file_name, file_id)) = name_and_id_from_path(entry.path()) {
         if is_matching_notion_entry(name, id, &file_name, file_id.as_deref()) {
           return Some((entry.path().to_path_buf(), file_id));
@@ -378,40 +377,43 @@ pub(crate) fn process_entry(
   let path = current_entry.path();
   let ext = get_file_extension(path, include_partial_csv);
   if ext.is_file() {
-    // Check if there's a corresponding directory for this .md file and skip it if so
+    // Check if there's a corresponding directory for this .md or .html file and skip it if so
     process_file(host, workspace_id, path, ext, notion_export)
   } else if path.is_dir() {
-    // If the path is a directory, it should contain a file with the same name but with either a .md or .csv extension.
+    // If the path is a directory, it should contain a file with the same name but with either a .md, .html or .csv extension.
     // If no such file is found, the directory will be treated as a space.
     // Proceed to extract the name and ID for the directory.
     let (name, id) = name_and_id_from_path(path).ok()?;
 
-    // Look for the corresponding .md file for this directory in the parent directory
+    // Look for the corresponding .md or .html file for this directory in the parent directory
     let parent_path = path.parent()?;
-    if let Some((md_file_path, md_id)) =
-      find_matching_markdown_file(parent_path, &name, id.as_deref())
+    if let Some((page_file_path, page_id)) =
+      find_matching_page_file(parent_path, &name, id.as_deref())
     {
-      let id = id.or(md_id);
-
-      if let Some(database_page) = try_process_inline_database_wrapper_dir(
-        host,
-        workspace_id,
-        path,
-        name.clone(),
-        id.clone(),
-        &md_file_path,
-        notion_export,
-      ) {
-        return Some(database_page);
+      let id = id.or(page_id);
+
+      // The html exports contain the databases as html tables, not as csv files.
+      if let FileExtension::Markdown = get_file_extension(&page_file_path, false) {
+        if let Some(database_page) = try_process_inline_database_wrapper_dir(
+          host,
+          workspace_id,
+          path,
+          name.clone(),
+          id.clone(),
+          &page_file_path,
+          notion_export,
+        ) {
+          return Some(database_page);
+        }
       }
 
-      process_md_dir(
+      process_page_dir(
         host,
         workspace_id,
         path,
         name,
         id,
-        &md_file_path,
+        &page_file_path,
         include_partial_csv,
         notion_export,
       )
@@ -468,6 +470,11 @@ fn process_space_dir(
     workspace_id: workspace_id.to_string(),
     is_dir: true,
     csv_relation: notion_export.csv_relation.clone(),
+    locale: notion_export.locale.clone(),
+    relations: None,
+    workspace_view_ids: None,
+    row_dedupe: None,
+    member_resolver: None,
   })
 }
 
@@ -505,16 +512,33 @@ fn process_csv_dir(
     };
     let csv_file = parse_csv(csv_source_path);
     let title_col_candidates = title_column_candidates(&csv_file.columns);
+    let mut matched_untitled_rows = HashSet::new();
     for sub_entry in walk_sub_dir(&csv_dir) {
       if let Some(mut page) = process_entry(host, workspace_id, &sub_entry, true, notion_export) {
-        if page.children.iter().any(|c| c.notion_file.is_markdown()) {
+        if page.children.iter().any(|c| c.notion_file.is_document()) {
           warn!("Only CSV file exist in the database row directory");
         }
 
         let normalized_page_name = normalize_database_row_name(&page.notion_name);
-        for row in csv_file.rows.iter() {
-          let mut matched_title: Option<&String> = None;
+        let is_untitled_page = notion_export.locale.is_untitled(&page.notion_name);
+        for (row_index, row) in csv_file.rows.iter().enumerate() {
+          let mut matched_title: Option<String> = None;
+          if is_untitled_page {
+            // Rows without a title are exported as "Untitled" pages in the language of the
+            // exporter. Match them with the first row whose title is empty.
+            let is_empty_title = title_col_candidates
+              .first()
+              .and_then(|&col_index| row.get(col_index))
+              .is_none_or(|cell| cell.trim().is_empty());
+            if is_empty_title && matched_untitled_rows.insert(row_index) {
+              matched_title = Some(page.notion_name.clone());
+            }
+          }
+
           for &col_index in title_col_candidates.iter() {
+            if matched_title.is_some() {
+              break;
+            }
             let Some(cell) = row.get(col_index) else {
               continue;
             };
@@ -526,7 +550,7 @@ fn process_csv_dir(
             if normalized_page_name.starts_with(&normalized_row_title)
               || normalized_row_title.starts_with(&normalized_page_name)
             {
-              matched_title = Some(cell);
+              matched_title = Some(cell.clone());
               break;
             }
           }
@@ -535,7 +559,7 @@ fn process_csv_dir(
             continue;
           };
 
-          page.notion_name = row_title.clone();
+          page.notion_name = row_title;
           if let Some(file_path) = page.notion_file.file_path() {
             if let Ok(md_content) = fs::read_to_string(file_path) {
               if md_content.is_empty() {
@@ -608,6 +632,11 @@ fn process_csv_dir(
     workspace_id: workspace_id.to_string(),
     is_dir: false,
     csv_relation: notion_export.csv_relation.clone(),
+    locale: notion_export.locale.clone(),
+    relations: None,
+    workspace_view_ids: None,
+    row_dedupe: None,
+    member_resolver: None,
   };
 
   notion_export
@@ -627,23 +656,23 @@ pub fn walk_sub_dir(path: &Path) -> Vec<DirEntry> {
 }
 
 #[allow(clippy::too_many_arguments)]
-fn process_md_dir(
+fn process_page_dir(
   host: &str,
   workspace_id: &str,
   dir_path: &Path,
   name: String,
   id: Option<String>,
-  md_file_path: &PathBuf,
+  page_file_path: &PathBuf,
   include_partial_csv: bool,
   notion_export: &NotionExportContext,
 ) -> Option<NotionPage> {
   let mut children = vec![];
-  let external_links = get_md_links(md_file_path).unwrap_or_default();
+  let external_links = get_page_links(page_file_path).unwrap_or_default();
   let mut resources = vec![];
   // Walk through sub-entries of the directory
   for sub_entry in walk_sub_dir(dir_path) {
-    // Skip the directory itself and its corresponding .md file
-    if sub_entry.path() != md_file_path {
+    // Skip the directory itself and its corresponding .md or .html file
+    if sub_entry.path() != page_file_path {
       if let Some(child_view) = process_entry(
         host,
         workspace_id,
@@ -660,11 +689,18 @@ fn process_md_dir(
     }
   }
 
-  let file_size = get_file_size(md_file_path).ok()?;
-  let notion_file = NotionFile::Markdown {
-    file_path: md_file_path.clone(),
-    size: file_size,
-    resources,
+  let file_size = get_file_size(page_file_path).ok()?;
+  let notion_file = match get_file_extension(page_file_path, false) {
+    FileExtension::Html => NotionFile::Html {
+      file_path: page_file_path.clone(),
+      size: file_size,
+      resources,
+    },
+    _ => NotionFile::Markdown {
+      file_path: page_file_path.clone(),
+      size: file_size,
+      resources,
+    },
   };
   Some(NotionPage {
     notion_name: name,
@@ -677,6 +713,11 @@ fn process_md_dir(
     workspace_id: workspace_id.to_string(),
     is_dir: false,
     csv_relation: notion_export.csv_relation.clone(),
+    locale: notion_export.locale.clone(),
+    relations: None,
+    workspace_view_ids: None,
+    row_dedupe: None,
+    member_resolver: None,
   })
 }
 
@@ -689,7 +730,9 @@ fn process_file(
 ) -> Option<NotionPage> {
   match ext {
     FileExtension::Unknown => None,
-    FileExtension::Markdown => process_md_file(host, workspace_id, path, notion_export),
+    FileExtension::Markdown | FileExtension::Html => {
+      process_page_file(host, workspace_id, path, notion_export)
+    },
     FileExtension::Csv {
       include_partial_csv,
     } => process_csv_file(host, workspace_id, path, include_partial_csv, notion_export),
@@ -752,16 +795,28 @@ fn process_csv_file(
     workspace_id: workspace_id.to_string(),
     is_dir: false,
     csv_relation: notion_export.csv_relation.clone(),
+    locale: notion_export.locale.clone(),
+    relations: None,
+    workspace_view_ids: None,
+    row_dedupe: None,
+    member_resolver: None,
   })
 }
 
-fn process_md_file(
+fn process_page_file(
   host: &str,
   workspace_id: &str,
   path: &Path,
   notion_export: &NotionExportContext,
 ) -> Option<NotionPage> {
   let (name, id) = name_and_id_from_path(path).ok()?;
+  // The index of a workspace html export only links to the exported pages.
+  if id.is_none()
+    && name == "index"
+    && matches!(get_file_extension(path, false), FileExtension::Html)
+  {
+    return None;
+  }
   if let Some(parent) = path.parent() {
     if find_matching_directory(parent, &name, id.as_deref()).is_some() {
       return None; // Skip .md or .csv file if there's a corresponding directory
@@ -771,8 +826,8 @@ fn process_md_file(
   // Process the file normally if it doesn't correspond to a directory
   let notion_file = notion_file_from_path(path, notion_export.no_subpages)?;
   let mut external_links = vec![];
-  if notion_file.is_markdown() {
-    external_links = get_md_links(path).unwrap_or_default();
+  if notion_file.is_document() {
+    external_links = get_page_links(path).unwrap_or_default();
   }
 
   // If the file is CSV, then it should be handled later.
@@ -790,9 +845,47 @@ fn process_md_file(
     workspace_id: workspace_id.to_string(),
     is_dir: false,
     csv_relation: notion_export.csv_relation.clone(),
+    locale: notion_export.locale.clone(),
+    relations: None,
+    workspace_view_ids: None,
+    row_dedupe: None,
+    member_resolver: None,
   })
 }
 
+/// Get all links from the markdown or the html file of a page.
+pub(crate) fn get_page_links(
+  page_file_path: &Path,
+) -> Result<Vec<Vec<ExternalLink>>, ImporterError> {
+  match get_file_extension(page_file_path, false) {
+    FileExtension::Html => get_html_links(page_file_path),
+    _ => get_md_links(page_file_path),
+  }
+}
+
+/// Get all links from an html file: the `href` and `src` attributes of its elements.
+pub(crate) fn get_html_links(
+  html_file_path: &Path,
+) -> Result<Vec<Vec<ExternalLink>>, ImporterError> {
+  let content = std::fs::read_to_string(html_file_path)?;
+  let mut links = Vec::new();
+  rewrite_links(&content, |link| {
+    links.push(link.to_string());
+    None
+  });
+  Ok(
+    links
+      .into_iter()
+      .filter(|link| !link.starts_with("http://") && !link.starts_with("https://"))
+      .flat_map(|link| {
+        let links = extract_external_links(&link).ok()?;
+        Some(links)
+      })
+      .filter(|links| !links.is_empty())
+      .collect(),
+  )
+}
+
 // Main function to get all links from a markdown file
 pub(crate) fn get_md_links(md_file_path: &Path) -> Result<Vec<Vec<ExternalLink>>, ImporterError> {
   let content = std::fs::read_to_string(md_file_path)?;
@@ -888,6 +981,7 @@ pub(crate) fn collect_links_from_node(node: &Node, links: &mut Vec<String>) {
 fn link_type_from_extension(extension: Option<&str>) -> ExternalLinkType {
   match extension.map(|s| s.to_ascii_lowercase()).as_deref() {
     Some("md") => ExternalLinkType::Markdown,
+    Some("html") => ExternalLinkType::Html,
     Some("csv") => ExternalLinkType::CSV,
     _ => ExternalLinkType::Unknown,
   }
@@ -937,9 +1031,7 @@ pub(crate) fn extract_external_links(path_str: &str) -> Result<Vec<ExternalLink>
           });
         }
       } else {
-        return Err(ImporterError::Internal(anyhow::anyhow!(
-          "Non-UTF8 path component"
-        )));
+        return Err(ImporterError::InvalidPathFormat);
       }
     }
   }
@@ -989,12 +1081,22 @@ pub fn extract_delta_link(input: &str) -> Option<DeltaLink> {
 enum FileExtension {
   Unknown,
   Markdown,
+  Html,
   Csv { include_partial_csv: bool },
 }
 
 impl FileExtension {
   fn is_file(&self) -> bool {
-    matches!(self, FileExtension::Markdown | FileExtension::Csv { .. })
+    matches!(
+      self,
+      FileExtension::Markdown | FileExtension::Html | FileExtension::Csv { .. }
+    )
+  }
+
+  /// Whether the file holds the content of a page: Notion exports the pages either as markdown
+  /// or as html files.
+  fn is_page(&self) -> bool {
+    matches!(self, FileExtension::Markdown | FileExtension::Html)
   }
 }
 
@@ -1004,13 +1106,16 @@ fn get_file_extension(path: &Path, include_partial_csv: bool) -> FileExtension {
     .and_then(|ext| ext.to_str().map(|s| s.to_ascii_lowercase()))
     .map_or(FileExtension::Unknown, |ext| match ext.as_str() {
       "md" => FileExtension::Markdown,
+      "html" => FileExtension::Html,
       "csv" => FileExtension::Csv {
         include_partial_csv,
       },
       _ => FileExtension::Unknown,
     })
 }
-fn name_and_id_from_path(path: &Path) -> Result<(String, Option<String>), ImporterError> {
+pub(crate) fn name_and_id_from_path(
+  path: &Path,
+) -> Result<(String, Option<String>), ImporterError> {
   let re =
     Regex::new(r"^(.*?)(?:\s+([a-fA-F0-9]{32})(?:_[a-zA-Z0-9]+)?)?(?:\.[a-zA-Z0-9]+)?\s*$").unwrap();
 
@@ -1037,12 +1142,13 @@ fn name_and_id_from_path(path: &Path) -> Result<(String, Option<String>), Import
 /// - If the file is a `.csv` and contains `_all`, it's considered a `CSV`.
 /// - Otherwise, if it's a `.csv`, it's considered a `CSVPart`.
 /// - `.md` files are classified as `Markdown`.
+/// - `.html` files are classified as `Html`.
 fn notion_file_from_path(path: &Path, no_subpages: bool) -> Option<NotionFile> {
   let extension = path.extension()?.to_str()?.to_ascii_lowercase();
   let file_size = get_file_size(&path.to_path_buf()).ok()?;
 
   match extension.as_str() {
-    "md" => {
+    "md" | "html" => {
       let mut resources = vec![];
       if no_subpages {
         if let Some(parent_path) = path.parent() {
@@ -1050,11 +1156,20 @@ fn notion_file_from_path(path: &Path, no_subpages: bool) -> Option<NotionFile> {
         }
       }
 
-      Some(NotionFile::Markdown {
-        file_path: path.to_path_buf(),
-        size: file_size,
-        resources,
-      })
+      let file_path = path.to_path_buf();
+      if extension == "html" {
+        Some(NotionFile::Html {
+          file_path,
+          size: file_size,
+          resources,
+        })
+      } else {
+        Some(NotionFile::Markdown {
+          file_path,
+          size: file_size,
+          resources,
+        })
+      }
     },
     "csv" => {
       let file_name = path.file_name()?.to_str()?;
@@ -1279,6 +1394,10 @@ mod notion_entry_pairing_tests {
     let notion_export = NotionExportContext {
       csv_relation: crate::notion::CSVRelation::default(),
       no_subpages: false,
+      locale: Default::default(),
+      relations: None,
+      workspace_view_ids: None,
+      row_dedupe: None,
     };
 
     let dir_entry = WalkDir::new(root)
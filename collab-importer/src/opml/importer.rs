@@ -0,0 +1,270 @@
+use crate::error::{ImporterError, ImporterResultExt};
+use crate::imported_collab::{ImportType, ImportedCollab, ImportedCollabInfo};
+use crate::opml::parser::{OpmlOutline, parse_opml};
+use collab::core::collab::default_client_id;
+use collab_document::blocks::{Block, BlockType, DocumentDataBuilder, NewBlock};
+use collab_document::document::Document;
+use collab_document::importer::define::*;
+use collab_document::provenance::BlockProvenance;
+use collab_entity::CollabType;
+use collab_folder::ViewLayout;
+use collab_folder::hierarchy_builder::{NestedChildViewBuilder, NestedViews, ParentChildViews};
+use serde_json::{Map, json};
+use std::path::Path;
+
+const UNTITLED_PAGE: &str = "Untitled";
+const COLLAPSED_FIELD: &str = "collapsed";
+
+/// How the outlines of an OPML file are split into documents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OpmlImportMode {
+  /// All the outlines in a single document named after the file.
+  #[default]
+  SingleDocument,
+  /// A document per top-level outline, named after its text, under a page named after the file.
+  DocumentPerOutline,
+}
+
+/// Imports the outlines exported as OPML by Workflowy, Dynalist or OmniOutliner.
+///
+/// The outlines become nested bulleted lists, or toggle lists for the outlines with children
+/// when [OpmlImporter::with_toggle_lists] is set. The checkboxes become todos, the completed
+/// outlines without a checkbox are struck through and the notes are imported as paragraphs under
+/// their outline.
+pub struct OpmlImporter {
+  uid: i64,
+  workspace_id: String,
+  mode: OpmlImportMode,
+  toggle_lists: bool,
+}
+
+impl OpmlImporter {
+  pub fn new<S: ToString>(uid: i64, workspace_id: S) -> Self {
+    Self {
+      uid,
+      workspace_id: workspace_id.to_string(),
+      mode: OpmlImportMode::default(),
+      toggle_lists: false,
+    }
+  }
+
+  /// See [OpmlImportMode].
+  pub fn with_mode(mut self, mode: OpmlImportMode) -> Self {
+    self.mode = mode;
+    self
+  }
+
+  /// Import the outlines with children as collapsed toggle lists instead of bulleted lists.
+  pub fn with_toggle_lists(mut self, enabled: bool) -> Self {
+    self.toggle_lists = enabled;
+    self
+  }
+
+  pub fn import_file<P: AsRef<Path>>(&self, path: P) -> Result<OpmlImportedInfo, ImporterError> {
+    let path = path.as_ref();
+    if !path.exists() {
+      return Err(ImporterError::FileNotFound);
+    }
+    let content = std::fs::read_to_string(path)?;
+    let file_name = path
+      .file_stem()
+      .map(|stem| stem.to_string_lossy().to_string())
+      .unwrap_or_default();
+    self
+      .import_opml(&file_name, &content)
+      .with_context(|| format!("import {}", path.display()))
+  }
+
+  /// Import the content of an OPML file. The page is named after the title of the file, or
+  /// `file_name` when it has none.
+  pub fn import_opml(
+    &self,
+    file_name: &str,
+    content: &str,
+  ) -> Result<OpmlImportedInfo, ImporterError> {
+    let document = parse_opml(content)?;
+    let name = document
+      .title
+      .or_else(|| (!file_name.trim().is_empty()).then(|| file_name.to_string()))
+      .unwrap_or_else(|| UNTITLED_PAGE.to_string());
+    let view_id = uuid::Uuid::new_v4().to_string();
+    let source_path = format!("{}.opml", file_name);
+
+    let (outlines, pages) = match self.mode {
+      OpmlImportMode::SingleDocument => (document.outlines.as_slice(), vec![]),
+      OpmlImportMode::DocumentPerOutline => {
+        let pages = document
+          .outlines
+          .iter()
+          .map(|outline| self.import_outline_page(outline, &source_path))
+          .collect::<Result<Vec<_>, _>>()?;
+        (&[][..], pages)
+      },
+    };
+    let collab_info = self.document_collab_info(&view_id, &name, outlines, None, &source_path)?;
+    Ok(OpmlImportedInfo {
+      uid: self.uid,
+      workspace_id: self.workspace_id.clone(),
+      view_id,
+      name,
+      pages,
+      collab_info,
+    })
+  }
+
+  fn import_outline_page(
+    &self,
+    outline: &OpmlOutline,
+    source_path: &str,
+  ) -> Result<OpmlImportedPage, ImporterError> {
+    let view_id = uuid::Uuid::new_v4().to_string();
+    let title = if outline.text.trim().is_empty() {
+      UNTITLED_PAGE.to_string()
+    } else {
+      outline.text.trim().to_string()
+    };
+    let collab_info = self.document_collab_info(
+      &view_id,
+      &title,
+      &outline.children,
+      outline.note.as_deref(),
+      source_path,
+    )?;
+    Ok(OpmlImportedPage {
+      view_id,
+      title,
+      collab_info,
+    })
+  }
+
+  fn document_collab_info(
+    &self,
+    view_id: &str,
+    name: &str,
+    outlines: &[OpmlOutline],
+    note: Option<&str>,
+    source_path: &str,
+  ) -> Result<ImportedCollabInfo, ImporterError> {
+    let mut blocks = note.map(note_blocks).unwrap_or_default();
+    blocks.extend(outline_blocks(outlines, self.toggle_lists));
+    if blocks.is_empty() {
+      blocks.push(Block::paragraph(""));
+    }
+    let mut builder = DocumentDataBuilder::with_page_id(view_id);
+    for block in blocks {
+      builder.push(block);
+    }
+    let mut document_data = builder.build();
+    document_data.tag_provenance(&BlockProvenance::new("opml", source_path));
+    let document = Document::create(view_id, document_data, default_client_id())?;
+    let encoded_collab = document.encode_collab()?;
+    Ok(ImportedCollabInfo {
+      name: name.to_string(),
+      imported_collabs: vec![ImportedCollab {
+        object_id: view_id.to_string(),
+        collab_type: CollabType::Document,
+        encoded_collab,
+      }],
+      resources: vec![],
+      import_type: ImportType::Document,
+    })
+  }
+}
+
+/// The page imported by [OpmlImporter]. It holds the outlines, or the pages of the top-level
+/// outlines with [OpmlImportMode::DocumentPerOutline].
+#[derive(Debug, Clone)]
+pub struct OpmlImportedInfo {
+  pub uid: i64,
+  pub workspace_id: String,
+  pub view_id: String,
+  pub name: String,
+  pub pages: Vec<OpmlImportedPage>,
+  pub collab_info: ImportedCollabInfo,
+}
+
+impl OpmlImportedInfo {
+  /// The number of pages, the page of the file included.
+  pub fn num_of_pages(&self) -> usize {
+    1 + self.pages.len()
+  }
+
+  /// The view of the file, with the views of the outlines, under the workspace.
+  pub fn build_nested_views(&self) -> NestedViews {
+    let pages = self
+      .pages
+      .iter()
+      .map(|page| {
+        NestedChildViewBuilder::new(self.uid, self.view_id.clone())
+          .with_view_id(&page.view_id)
+          .with_name(&page.title)
+          .with_layout(ViewLayout::Document)
+          .build()
+      })
+      .collect::<Vec<ParentChildViews>>();
+    let view = NestedChildViewBuilder::new(self.uid, self.workspace_id.clone())
+      .with_view_id(&self.view_id)
+      .with_name(&self.name)
+      .with_layout(ViewLayout::Document)
+      .with_children(pages)
+      .build();
+    NestedViews { views: vec![view] }
+  }
+
+  /// The collabs of the file and of the outlines, the file first.
+  pub fn into_collab_infos(self) -> Vec<ImportedCollabInfo> {
+    let mut infos = vec![self.collab_info];
+    infos.extend(self.pages.into_iter().map(|page| page.collab_info));
+    infos
+  }
+}
+
+/// The page of a top-level outline.
+#[derive(Debug, Clone)]
+pub struct OpmlImportedPage {
+  pub view_id: String,
+  pub title: String,
+  pub collab_info: ImportedCollabInfo,
+}
+
+/// The blocks of the outlines, the note and the children of an outline are nested in its block.
+fn outline_blocks(outlines: &[OpmlOutline], toggle_lists: bool) -> Vec<NewBlock> {
+  outlines
+    .iter()
+    .map(|outline| {
+      let mut attributes = Map::new();
+      let block = if outline.checkbox {
+        NewBlock::new(BlockType::TodoList).with_data(CHECKED_FIELD, json!(outline.completed))
+      } else {
+        if outline.completed {
+          attributes.insert(STRIKETHROUGH_ATTR.to_string(), json!(true));
+        }
+        if toggle_lists && !outline.children.is_empty() {
+          NewBlock::new(BlockType::ToggleList).with_data(COLLAPSED_FIELD, json!(true))
+        } else {
+          NewBlock::new(BlockType::BulletedList)
+        }
+      };
+      let delta = if outline.text.is_empty() {
+        json!([])
+      } else if attributes.is_empty() {
+        json!([{ "insert": outline.text }])
+      } else {
+        json!([{ "insert": outline.text, "attributes": attributes }])
+      };
+      block
+        .with_delta(delta)
+        .with_children(outline.note.as_deref().map(note_blocks).unwrap_or_default())
+        .with_children(outline_blocks(&outline.children, toggle_lists))
+    })
+    .collect()
+}
+
+/// A paragraph per line of the note.
+fn note_blocks(note: &str) -> Vec<NewBlock> {
+  note
+    .lines()
+    .filter(|line| !line.trim().is_empty())
+    .map(|line| Block::paragraph(line.trim_end()))
+    .collect()
+}
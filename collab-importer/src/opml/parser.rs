@@ -0,0 +1,135 @@
+use crate::error::ImporterError;
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+/// An OPML document, as exported by Workflowy, Dynalist or OmniOutliner.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OpmlDocument {
+  /// The `<title>` of the `<head>`.
+  pub title: Option<String>,
+  /// The top-level outlines of the `<body>`.
+  pub outlines: Vec<OpmlOutline>,
+}
+
+/// An `<outline>` element with its nested outlines.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OpmlOutline {
+  pub text: String,
+  /// The note attached to the outline, the `_note` attribute.
+  pub note: Option<String>,
+  /// Whether the outline is a checkbox, Dynalist's `checkbox` or OmniOutliner's `_status`.
+  pub checkbox: bool,
+  /// Whether the outline is done, Workflowy's `_complete`, Dynalist's `complete` or
+  /// OmniOutliner's `_status="checked"`.
+  pub completed: bool,
+  pub children: Vec<OpmlOutline>,
+}
+
+/// Parse an .opml file.
+pub fn parse_opml(xml: &str) -> Result<OpmlDocument, ImporterError> {
+  let mut reader = Reader::from_str(xml);
+  let mut document = OpmlDocument::default();
+  // The open outlines, the innermost last.
+  let mut outlines: Vec<OpmlOutline> = vec![];
+  // The names of the open elements, the innermost last.
+  let mut path: Vec<String> = vec![];
+  let mut text = String::new();
+  let mut has_root = false;
+  loop {
+    let event = reader
+      .read_event()
+      .map_err(|err| ImporterError::ParseOpmlError(err.to_string()))?;
+    match event {
+      Event::Start(start) => {
+        let name = String::from_utf8_lossy(start.local_name().as_ref()).to_string();
+        has_root |= name == "opml";
+        if name == "outline" {
+          outlines.push(outline_from_start(&start)?);
+        }
+        path.push(name);
+        text.clear();
+      },
+      Event::Empty(start) => {
+        if start.local_name().as_ref() == b"outline" {
+          let outline = outline_from_start(&start)?;
+          push_outline(&mut document, &mut outlines, outline);
+        }
+      },
+      Event::Text(value) => {
+        let value = value
+          .unescape()
+          .map_err(|err| ImporterError::ParseOpmlError(err.to_string()))?;
+        text.push_str(&value);
+      },
+      Event::CData(data) => text.push_str(&String::from_utf8_lossy(&data.into_inner())),
+      Event::End(_) => {
+        let name = path
+          .pop()
+          .ok_or_else(|| ImporterError::ParseOpmlError("unexpected closing tag".to_string()))?;
+        let parent = path.last().map(String::as_str).unwrap_or_default();
+        match (parent, name.as_str()) {
+          ("head", "title") => {
+            let title = text.trim();
+            document.title = (!title.is_empty()).then(|| title.to_string());
+          },
+          (_, "outline") => {
+            if let Some(outline) = outlines.pop() {
+              push_outline(&mut document, &mut outlines, outline);
+            }
+          },
+          _ => {},
+        }
+        text.clear();
+      },
+      Event::Eof => break,
+      _ => {},
+    }
+  }
+  if !path.is_empty() {
+    return Err(ImporterError::ParseOpmlError(
+      "unclosed xml element".to_string(),
+    ));
+  }
+  if !has_root {
+    return Err(ImporterError::ParseOpmlError(
+      "missing <opml> element".to_string(),
+    ));
+  }
+  Ok(document)
+}
+
+/// Add the closed outline to its parent, or to the body when it's a top-level outline.
+fn push_outline(document: &mut OpmlDocument, open: &mut [OpmlOutline], outline: OpmlOutline) {
+  match open.last_mut() {
+    Some(parent) => parent.children.push(outline),
+    None => document.outlines.push(outline),
+  }
+}
+
+fn outline_from_start(start: &BytesStart) -> Result<OpmlOutline, ImporterError> {
+  let mut outline = OpmlOutline::default();
+  for attr in start.attributes().flatten() {
+    let value = attr
+      .unescape_value()
+      .map_err(|err| ImporterError::ParseOpmlError(err.to_string()))?;
+    match attr.key.local_name().as_ref() {
+      b"text" => outline.text = value.to_string(),
+      b"_note" => {
+        let note = value.trim();
+        outline.note = (!note.is_empty()).then(|| note.to_string());
+      },
+      b"checkbox" => outline.checkbox = is_true(&value),
+      b"_complete" | b"complete" => outline.completed = is_true(&value),
+      b"_status" => {
+        outline.checkbox = true;
+        outline.completed = value.eq_ignore_ascii_case("checked");
+      },
+      _ => {},
+    }
+  }
+  Ok(outline)
+}
+
+fn is_true(value: &str) -> bool {
+  value.eq_ignore_ascii_case("true") || value == "1"
+}
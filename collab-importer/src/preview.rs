@@ -0,0 +1,144 @@
+use collab_document::blocks::{BlockType, DocumentData};
+use collab_document::document::Document;
+use collab_document::importer::define::URL_FIELD;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Mutex;
+
+/// The plain text of a page passed to the hook is truncated to this number of characters.
+pub const MAX_PREVIEW_TEXT_LEN: usize = 2000;
+
+/// What an imported page contains at a glance, passed to [ImportPreviewHook::on_page_imported].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedPagePreview {
+  pub view_id: String,
+  pub name: String,
+  /// The plain text of the page, one line per block, truncated to [MAX_PREVIEW_TEXT_LEN]
+  /// characters.
+  pub text: String,
+  /// The url of the first image of the page, in document order.
+  pub first_image_url: Option<String>,
+}
+
+impl ImportedPagePreview {
+  pub fn from_document(view_id: &str, name: &str, document: &Document) -> Self {
+    let text = document
+      .to_plain_text()
+      .into_iter()
+      .map(|line| line.trim().to_string())
+      .filter(|line| !line.is_empty())
+      .collect::<Vec<_>>()
+      .join("\n");
+    let first_image_url = document
+      .get_document_data()
+      .ok()
+      .and_then(|data| first_image_url(&data));
+    Self {
+      view_id: view_id.to_string(),
+      name: name.to_string(),
+      text: text.chars().take(MAX_PREVIEW_TEXT_LEN).collect(),
+      first_image_url,
+    }
+  }
+}
+
+/// Invoked for each imported page, so hosts can generate preview cards or thumbnails while
+/// importing instead of reading every document again afterwards.
+///
+/// The hook is called from the import tasks, it should return quickly and defer heavy work
+/// like image downloads.
+pub trait ImportPreviewHook: Send + Sync + Debug {
+  fn on_page_imported(&self, preview: ImportedPagePreview);
+}
+
+/// The default [ImportPreviewHook], it keeps a short text snippet of each imported page.
+#[derive(Debug)]
+pub struct TextSnippetPreviewHook {
+  max_chars: usize,
+  snippets: Mutex<HashMap<String, String>>,
+}
+
+impl Default for TextSnippetPreviewHook {
+  fn default() -> Self {
+    Self::new(200)
+  }
+}
+
+impl TextSnippetPreviewHook {
+  pub fn new(max_chars: usize) -> Self {
+    Self {
+      max_chars,
+      snippets: Mutex::new(HashMap::new()),
+    }
+  }
+
+  /// Return the snippet of the page with the given view id.
+  pub fn snippet(&self, view_id: &str) -> Option<String> {
+    self.snippets.lock().ok()?.get(view_id).cloned()
+  }
+
+  /// Return the snippets of all the imported pages, keyed by view id.
+  pub fn snippets(&self) -> HashMap<String, String> {
+    self
+      .snippets
+      .lock()
+      .map(|snippets| snippets.clone())
+      .unwrap_or_default()
+  }
+}
+
+impl ImportPreviewHook for TextSnippetPreviewHook {
+  fn on_page_imported(&self, preview: ImportedPagePreview) {
+    let snippet = text_snippet(&preview.text, self.max_chars);
+    if let Ok(mut snippets) = self.snippets.lock() {
+      snippets.insert(preview.view_id, snippet);
+    }
+  }
+}
+
+/// Collapse the whitespace of the text and cut it at a word boundary, appending an ellipsis if
+/// the text is longer than `max_chars`.
+pub fn text_snippet(text: &str, max_chars: usize) -> String {
+  let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+  if text.chars().count() <= max_chars {
+    return text;
+  }
+  let cut = text
+    .char_indices()
+    .nth(max_chars)
+    .map(|(index, _)| index)
+    .unwrap_or(text.len());
+  let snippet = &text[..cut];
+  // Don't cut a word in half, unless the snippet is a single word.
+  let snippet = match snippet.rfind(' ') {
+    Some(index) if index > 0 => &snippet[..index],
+    _ => snippet,
+  };
+  format!("{}…", snippet.trim_end())
+}
+
+fn first_image_url(data: &DocumentData) -> Option<String> {
+  let page = data.blocks.get(&data.page_id)?;
+  let mut stack = data
+    .meta
+    .children_map
+    .get(&page.children)?
+    .iter()
+    .rev()
+    .collect::<Vec<_>>();
+  while let Some(block_id) = stack.pop() {
+    let Some(block) = data.blocks.get(block_id) else {
+      continue;
+    };
+    if block.ty == BlockType::Image.as_str() {
+      let url = block.data.get(URL_FIELD).and_then(|url| url.as_str());
+      if let Some(url) = url.filter(|url| !url.is_empty()) {
+        return Some(url.to_string());
+      }
+    }
+    if let Some(children) = data.meta.children_map.get(&block.children) {
+      stack.extend(children.iter().rev());
+    }
+  }
+  None
+}
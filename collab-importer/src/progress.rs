@@ -0,0 +1,69 @@
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// How far an import went, see [ImportProgressTracker].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportProgress {
+  /// The files found in the export, the pages and their resources.
+  pub files_discovered: usize,
+  /// The pages converted to collabs.
+  pub pages_parsed: usize,
+  /// The resources reported by [ImportProgressTracker::resource_uploaded].
+  pub resources_uploaded: usize,
+  /// The size of the parsed pages and of the uploaded resources, in bytes.
+  pub bytes_processed: u64,
+}
+
+/// Publishes the [ImportProgress] of an import. The importer updates the files and the pages,
+/// the resources are uploaded by the caller, which reports them with
+/// [ImportProgressTracker::resource_uploaded].
+///
+/// The tracker is cheap to clone, the clones update the same progress. Use
+/// [ImportProgressTracker::subscribe] to be notified of the changes.
+#[derive(Debug, Clone)]
+pub struct ImportProgressTracker {
+  sender: Arc<watch::Sender<ImportProgress>>,
+}
+
+impl Default for ImportProgressTracker {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl ImportProgressTracker {
+  pub fn new() -> Self {
+    Self {
+      sender: Arc::new(watch::Sender::new(ImportProgress::default())),
+    }
+  }
+
+  pub fn subscribe(&self) -> watch::Receiver<ImportProgress> {
+    self.sender.subscribe()
+  }
+
+  /// The current progress.
+  pub fn progress(&self) -> ImportProgress {
+    *self.sender.borrow()
+  }
+
+  pub fn files_discovered(&self, count: usize) {
+    self
+      .sender
+      .send_modify(|progress| progress.files_discovered += count);
+  }
+
+  pub fn page_parsed(&self, bytes: u64) {
+    self.sender.send_modify(|progress| {
+      progress.pages_parsed += 1;
+      progress.bytes_processed += bytes;
+    });
+  }
+
+  pub fn resource_uploaded(&self, bytes: u64) {
+    self.sender.send_modify(|progress| {
+      progress.resources_uploaded += 1;
+      progress.bytes_processed += bytes;
+    });
+  }
+}
@@ -0,0 +1,373 @@
+use crate::error::ImporterError;
+use crate::xhtml::escape_html;
+use collab::preclude::{Any, Attrs};
+use collab_document::block_parser::{
+  DocumentParser, DocumentParserDelegate, OutputFormat, ParseContext,
+};
+use collab_document::blocks::{BlockType, DocumentData};
+use collab_document::document_data::generate_id;
+use collab_document::importer::define::{
+  HREF_ATTR, MENTION_ATTR, MENTION_PAGE_ID_FIELD, MENTION_TYPE_FIELD, MENTION_TYPE_PAGE, URL_FIELD,
+};
+use collab_folder::hierarchy_builder::ParentChildViews;
+use collab_folder::{Folder, View, ViewLayout};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The directory of the site the assets are copied to.
+pub const PUBLISH_ASSET_DIR: &str = "assets";
+
+/// The file of the page of the root view of the site.
+pub const PUBLISH_INDEX_FILE: &str = "index.html";
+
+/// The key of the subpage block data holding the id of the view, it comes from the flutter code.
+const SUBPAGE_VIEW_ID_FIELD: &str = "viewId";
+
+/// Gives the content of the views being published.
+pub trait PublishSource {
+  /// The document of the view, None if the view is not a document or its document is missing.
+  fn document_data(&self, view_id: &str) -> Option<DocumentData>;
+
+  /// The local file an image or a file block refers to, None to keep the url as is, e.g. for
+  /// the files hosted on the web.
+  fn asset_path(&self, url: &str) -> Option<PathBuf>;
+}
+
+/// A page of a [PublishedSite].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublishedPage {
+  pub view_id: String,
+  pub name: String,
+  /// The path of the page, relative to the directory of the site.
+  pub path: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PublishedSite {
+  /// The pages, the root view first and then in the order of the folder hierarchy.
+  pub pages: Vec<PublishedPage>,
+  /// The assets copied into the site, relative to the directory of the site.
+  pub assets: Vec<String>,
+}
+
+/// Read the subtree of the view from the folder, see [publish_static_site]. Return None if the
+/// view doesn't exist.
+pub fn publish_tree_from_folder(
+  folder: &Folder,
+  uid: i64,
+  root_view_id: &str,
+) -> Option<ParentChildViews> {
+  fn read_tree(
+    folder: &Folder,
+    uid: i64,
+    view_id: &str,
+    visited: &mut HashSet<String>,
+  ) -> Option<ParentChildViews> {
+    if !visited.insert(view_id.to_string()) {
+      return None;
+    }
+    let view = folder.get_view(view_id, uid)?;
+    let children = view
+      .children
+      .items
+      .iter()
+      .filter_map(|child| read_tree(folder, uid, &child.id, visited))
+      .collect();
+    Some(ParentChildViews {
+      view: view.as_ref().clone(),
+      children,
+    })
+  }
+  read_tree(folder, uid, root_view_id, &mut HashSet::new())
+}
+
+/// Export the views into a static HTML site written to the directory: one page per view, with
+/// the navigation of the folder hierarchy on each page. The mentions of the published pages and
+/// the subpage blocks become relative links and the local assets are copied into the site.
+///
+/// The root view is published as [PUBLISH_INDEX_FILE], the other views as `{view_id}.html`.
+pub fn publish_static_site(
+  root: &ParentChildViews,
+  source: &dyn PublishSource,
+  out_dir: &Path,
+) -> Result<PublishedSite, ImporterError> {
+  let site = SiteMap::new(root);
+  std::fs::create_dir_all(out_dir)?;
+
+  let mut published = PublishedSite::default();
+  for node in site.pages.iter() {
+    let view = &node.view;
+    let path = site.paths[&view.id].clone();
+    let content = match source.document_data(&view.id) {
+      Some(mut data) if view.layout == ViewLayout::Document => {
+        link_subpages(&mut data, &site);
+        let assets = copy_assets(&mut data, source, out_dir)?;
+        published.assets.extend(assets);
+        render_document(&data, &site)?
+      },
+      _ => String::new(),
+    };
+
+    let html = render_page(&site, node, &content);
+    std::fs::write(out_dir.join(&path), html)?;
+    published.pages.push(PublishedPage {
+      view_id: view.id.clone(),
+      name: view.name.clone(),
+      path,
+    });
+  }
+  Ok(published)
+}
+
+/// The views of the site, with the path and the ancestors of each view.
+struct SiteMap<'a> {
+  root: &'a ParentChildViews,
+  pages: Vec<&'a ParentChildViews>,
+  paths: HashMap<String, String>,
+  names: HashMap<String, String>,
+  parents: HashMap<String, String>,
+}
+
+impl<'a> SiteMap<'a> {
+  fn new(root: &'a ParentChildViews) -> Self {
+    let mut site = Self {
+      root,
+      pages: vec![],
+      paths: HashMap::new(),
+      names: HashMap::new(),
+      parents: HashMap::new(),
+    };
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+      // a view listed twice is published once
+      if site.paths.contains_key(&node.view.id) {
+        continue;
+      }
+      let path = if site.pages.is_empty() {
+        PUBLISH_INDEX_FILE.to_string()
+      } else {
+        format!("{}.html", node.view.id)
+      };
+      site.paths.insert(node.view.id.clone(), path);
+      site
+        .names
+        .insert(node.view.id.clone(), node.view.name.clone());
+      for child in node.children.iter() {
+        site
+          .parents
+          .entry(child.view.id.clone())
+          .or_insert_with(|| node.view.id.clone());
+      }
+      site.pages.push(node);
+      stack.extend(node.children.iter().rev());
+    }
+    site
+  }
+
+  /// The ancestors of the view, from the root.
+  fn ancestors(&self, view_id: &str) -> Vec<&str> {
+    let mut ancestors = vec![];
+    let mut current = view_id;
+    while let Some(parent) = self.parents.get(current) {
+      if ancestors.contains(&parent.as_str()) {
+        break;
+      }
+      ancestors.push(parent.as_str());
+      current = parent;
+    }
+    ancestors.reverse();
+    ancestors
+  }
+
+  fn link(&self, view_id: &str) -> Option<(&str, &str)> {
+    Some((self.names.get(view_id)?, self.paths.get(view_id)?))
+  }
+}
+
+/// Replace the subpage blocks of the published views with a paragraph linking to their page.
+fn link_subpages(data: &mut DocumentData, site: &SiteMap) {
+  let text_map = data.meta.text_map.get_or_insert_with(HashMap::new);
+  for block in data.blocks.values_mut() {
+    if block.ty != BlockType::SubPage.as_str() {
+      continue;
+    }
+    let Some((name, path)) = block
+      .data
+      .get(SUBPAGE_VIEW_ID_FIELD)
+      .and_then(|view_id| view_id.as_str())
+      .and_then(|view_id| site.link(view_id))
+    else {
+      continue;
+    };
+    let text_id = generate_id();
+    let delta = json!([{ "insert": name, "attributes": { HREF_ATTR: path } }]);
+    text_map.insert(text_id.clone(), delta.to_string());
+    block.ty = BlockType::Paragraph.to_string();
+    block.external_id = Some(text_id);
+    block.external_type = Some("text".to_string());
+  }
+}
+
+/// Copy the local files of the image and the file blocks into the [PUBLISH_ASSET_DIR] and point
+/// the blocks to the copies. Return the paths of the copies, relative to the site.
+fn copy_assets(
+  data: &mut DocumentData,
+  source: &dyn PublishSource,
+  out_dir: &Path,
+) -> Result<Vec<String>, ImporterError> {
+  let mut assets = vec![];
+  for block in data.blocks.values_mut() {
+    if block.ty != BlockType::Image.as_str() && block.ty != BlockType::File.as_str() {
+      continue;
+    }
+    let Some(file_path) = block
+      .data
+      .get(URL_FIELD)
+      .and_then(|url| url.as_str())
+      .and_then(|url| source.asset_path(url))
+      .filter(|file_path| file_path.is_file())
+    else {
+      continue;
+    };
+    let file_name = file_path
+      .file_name()
+      .map(|file_name| sanitize_filename::sanitize(file_name.to_string_lossy()))
+      .unwrap_or_default()
+      .replace(' ', "-");
+    let asset = format!("{}/{}-{}", PUBLISH_ASSET_DIR, block.id, file_name);
+    let asset_path = out_dir.join(&asset);
+    if let Some(parent) = asset_path.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    std::fs::copy(&file_path, &asset_path)?;
+    block.data.insert(URL_FIELD.to_string(), json!(asset));
+    assets.push(asset);
+  }
+  Ok(assets)
+}
+
+fn render_document(data: &DocumentData, site: &SiteMap) -> Result<String, ImporterError> {
+  let links = site
+    .paths
+    .keys()
+    .filter_map(|view_id| {
+      let (name, path) = site.link(view_id)?;
+      Some((view_id.clone(), (name.to_string(), path.to_string())))
+    })
+    .collect();
+  let parser =
+    DocumentParser::with_default_parsers().with_delegate(Arc::new(PageMentionLinks { links }));
+  let markdown = parser.parse_document(data, OutputFormat::Markdown)?;
+  markdown::to_html_with_options(&markdown, &markdown::Options::gfm())
+    .map_err(ImporterError::ParseMarkdownError)
+}
+
+/// Turn the mentions of the published pages into markdown links to their page.
+#[derive(Debug)]
+struct PageMentionLinks {
+  /// The name and the path of the published views, by view id.
+  links: HashMap<String, (String, String)>,
+}
+
+impl DocumentParserDelegate for PageMentionLinks {
+  fn handle_text_delta(
+    &self,
+    _text: &str,
+    attributes: Option<&Attrs>,
+    _context: &ParseContext,
+  ) -> Option<String> {
+    let Some(Any::Map(mention)) = attributes?.get(MENTION_ATTR) else {
+      return None;
+    };
+    match mention.get(MENTION_TYPE_FIELD) {
+      Some(Any::String(ty)) if ty.as_ref() == MENTION_TYPE_PAGE => {},
+      _ => return None,
+    }
+    let Some(Any::String(page_id)) = mention.get(MENTION_PAGE_ID_FIELD) else {
+      return None;
+    };
+    let (name, path) = self.links.get(page_id.as_ref())?;
+    let name = name.replace('[', "\\[").replace(']', "\\]");
+    Some(format!("[{}]({})", name, path))
+  }
+}
+
+fn render_page(site: &SiteMap, node: &ParentChildViews, content: &str) -> String {
+  let view = &node.view;
+  let title = escape_html(&page_name(view));
+  let mut html = String::new();
+  html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+  html.push_str(&format!("<title>{}</title>\n</head>\n<body>\n", title));
+
+  html.push_str("<nav class=\"site-nav\">\n<ul>\n");
+  render_nav(site, site.root, &view.id, &mut html);
+  html.push_str("</ul>\n</nav>\n");
+
+  let ancestors = site.ancestors(&view.id);
+  if !ancestors.is_empty() {
+    let crumbs = ancestors
+      .iter()
+      .filter_map(|view_id| site.link(view_id))
+      .map(|(name, path)| format!("<a href=\"{}\">{}</a>", path, escape_html(name)))
+      .collect::<Vec<_>>();
+    html.push_str(&format!(
+      "<nav class=\"breadcrumbs\">{} / {}</nav>\n",
+      crumbs.join(" / "),
+      title
+    ));
+  }
+
+  html.push_str(&format!("<main>\n<h1>{}</h1>\n", title));
+  html.push_str(content);
+  if !node.children.is_empty() {
+    html.push_str("<ul class=\"subpages\">\n");
+    for child in node.children.iter() {
+      if let Some(path) = site.paths.get(&child.view.id) {
+        html.push_str(&format!(
+          "<li><a href=\"{}\">{}</a></li>\n",
+          path,
+          escape_html(&page_name(&child.view))
+        ));
+      }
+    }
+    html.push_str("</ul>\n");
+  }
+  html.push_str("</main>\n</body>\n</html>\n");
+  html
+}
+
+/// The navigation of the site, the link of the current page is marked with `aria-current`.
+fn render_nav(site: &SiteMap, node: &ParentChildViews, current_view_id: &str, html: &mut String) {
+  let Some(path) = site.paths.get(&node.view.id) else {
+    return;
+  };
+  let current = if node.view.id == current_view_id {
+    " aria-current=\"page\""
+  } else {
+    ""
+  };
+  html.push_str(&format!(
+    "<li><a href=\"{}\"{}>{}</a>",
+    path,
+    current,
+    escape_html(&page_name(&node.view))
+  ));
+  if !node.children.is_empty() {
+    html.push_str("\n<ul>\n");
+    for child in node.children.iter() {
+      render_nav(site, child, current_view_id, html);
+    }
+    html.push_str("</ul>\n");
+  }
+  html.push_str("</li>\n");
+}
+
+fn page_name(view: &View) -> String {
+  if view.name.trim().is_empty() {
+    "Untitled".to_string()
+  } else {
+    view.name.clone()
+  }
+}
@@ -0,0 +1,246 @@
+use crate::error::ImporterError;
+
+/// A value of an EDN document, with the subset of the format used by the Roam exports.
+///
+/// The lists, vectors and sets are all read as [Edn::List], and the tagged elements, e.g.
+/// `#datascript/DB {...}`, as the element they tag.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Edn {
+  Nil,
+  Bool(bool),
+  Int(i64),
+  Float(f64),
+  Str(String),
+  /// A keyword without its leading colon, e.g. `block/uid`.
+  Keyword(String),
+  Symbol(String),
+  List(Vec<Edn>),
+  Map(Vec<(Edn, Edn)>),
+}
+
+impl Edn {
+  pub(crate) fn get(&self, keyword: &str) -> Option<&Edn> {
+    match self {
+      Edn::Map(entries) => entries
+        .iter()
+        .find(|(key, _)| matches!(key, Edn::Keyword(k) if k == keyword))
+        .map(|(_, value)| value),
+      _ => None,
+    }
+  }
+
+  pub(crate) fn as_list(&self) -> Option<&[Edn]> {
+    match self {
+      Edn::List(items) => Some(items),
+      _ => None,
+    }
+  }
+
+  pub(crate) fn as_int(&self) -> Option<i64> {
+    match self {
+      Edn::Int(value) => Some(*value),
+      _ => None,
+    }
+  }
+
+  pub(crate) fn as_str(&self) -> Option<&str> {
+    match self {
+      Edn::Str(value) => Some(value),
+      _ => None,
+    }
+  }
+
+  pub(crate) fn as_keyword(&self) -> Option<&str> {
+    match self {
+      Edn::Keyword(value) => Some(value),
+      _ => None,
+    }
+  }
+}
+
+/// Read the first element of the EDN document.
+pub(crate) fn parse_edn(input: &str) -> Result<Edn, ImporterError> {
+  let mut reader = EdnReader {
+    chars: input.chars().collect(),
+    pos: 0,
+  };
+  reader.skip_ignored()?;
+  reader.read()
+}
+
+struct EdnReader {
+  chars: Vec<char>,
+  pos: usize,
+}
+
+impl EdnReader {
+  fn peek(&self) -> Option<char> {
+    self.chars.get(self.pos).copied()
+  }
+
+  fn error(&self, message: &str) -> ImporterError {
+    ImporterError::ParseRoamError(format!("{} at {}", message, self.pos))
+  }
+
+  /// Skip the whitespaces, the commas, the comments and the discarded elements.
+  fn skip_ignored(&mut self) -> Result<(), ImporterError> {
+    while let Some(c) = self.peek() {
+      if c.is_whitespace() || c == ',' {
+        self.pos += 1;
+      } else if c == ';' {
+        while self.peek().is_some_and(|c| c != '\n') {
+          self.pos += 1;
+        }
+      } else if c == '#' && self.chars.get(self.pos + 1) == Some(&'_') {
+        self.pos += 2;
+        self.skip_ignored()?;
+        self.read()?;
+      } else {
+        break;
+      }
+    }
+    Ok(())
+  }
+
+  fn read(&mut self) -> Result<Edn, ImporterError> {
+    let c = self.peek().ok_or_else(|| self.error("unexpected end"))?;
+    match c {
+      '{' => {
+        self.pos += 1;
+        let items = self.read_until('}')?;
+        if items.len() % 2 != 0 {
+          return Err(self.error("odd number of map elements"));
+        }
+        let mut entries = Vec::with_capacity(items.len() / 2);
+        let mut items = items.into_iter();
+        while let (Some(key), Some(value)) = (items.next(), items.next()) {
+          entries.push((key, value));
+        }
+        Ok(Edn::Map(entries))
+      },
+      '[' => {
+        self.pos += 1;
+        Ok(Edn::List(self.read_until(']')?))
+      },
+      '(' => {
+        self.pos += 1;
+        Ok(Edn::List(self.read_until(')')?))
+      },
+      '#' => {
+        self.pos += 1;
+        if self.peek() == Some('{') {
+          self.pos += 1;
+          return Ok(Edn::List(self.read_until('}')?));
+        }
+        // a tagged element, the tag is dropped
+        self.read_token();
+        self.skip_ignored()?;
+        self.read()
+      },
+      '"' => self.read_string(),
+      ':' => {
+        self.pos += 1;
+        Ok(Edn::Keyword(self.read_token()))
+      },
+      '\\' => {
+        self.pos += 1;
+        let token = self.read_token();
+        let value = match token.as_str() {
+          "newline" => "\n".to_string(),
+          "space" => " ".to_string(),
+          "tab" => "\t".to_string(),
+          _ => token,
+        };
+        Ok(Edn::Str(value))
+      },
+      _ => {
+        let token = self.read_token();
+        if token.is_empty() {
+          return Err(self.error(&format!("unexpected character {}", c)));
+        }
+        Ok(token_to_edn(token))
+      },
+    }
+  }
+
+  fn read_until(&mut self, end: char) -> Result<Vec<Edn>, ImporterError> {
+    let mut items = vec![];
+    loop {
+      self.skip_ignored()?;
+      match self.peek() {
+        Some(c) if c == end => {
+          self.pos += 1;
+          return Ok(items);
+        },
+        Some(_) => items.push(self.read()?),
+        None => return Err(self.error(&format!("missing {}", end))),
+      }
+    }
+  }
+
+  fn read_string(&mut self) -> Result<Edn, ImporterError> {
+    self.pos += 1;
+    let mut value = String::new();
+    loop {
+      let c = self
+        .peek()
+        .ok_or_else(|| self.error("unterminated string"))?;
+      self.pos += 1;
+      match c {
+        '"' => return Ok(Edn::Str(value)),
+        '\\' => {
+          let escaped = self
+            .peek()
+            .ok_or_else(|| self.error("unterminated string"))?;
+          self.pos += 1;
+          match escaped {
+            'n' => value.push('\n'),
+            't' => value.push('\t'),
+            'r' => value.push('\r'),
+            'u' => {
+              let code = self.chars[self.pos..(self.pos + 4).min(self.chars.len())]
+                .iter()
+                .collect::<String>();
+              self.pos += code.len();
+              let c = u32::from_str_radix(&code, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or_else(|| self.error("invalid unicode escape"))?;
+              value.push(c);
+            },
+            other => value.push(other),
+          }
+        },
+        _ => value.push(c),
+      }
+    }
+  }
+
+  fn read_token(&mut self) -> String {
+    let start = self.pos;
+    while self.peek().is_some_and(|c| {
+      !c.is_whitespace() && !matches!(c, ',' | '(' | ')' | '[' | ']' | '{' | '}' | '"' | ';')
+    }) {
+      self.pos += 1;
+    }
+    self.chars[start..self.pos].iter().collect()
+  }
+}
+
+fn token_to_edn(token: String) -> Edn {
+  match token.as_str() {
+    "nil" => Edn::Nil,
+    "true" => Edn::Bool(true),
+    "false" => Edn::Bool(false),
+    _ => {
+      let number = token.trim_end_matches(['N', 'M']);
+      if let Ok(value) = number.parse::<i64>() {
+        Edn::Int(value)
+      } else if let Ok(value) = number.parse::<f64>() {
+        Edn::Float(value)
+      } else {
+        Edn::Symbol(token)
+      }
+    },
+  }
+}
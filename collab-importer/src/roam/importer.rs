@@ -0,0 +1,363 @@
+use crate::error::{ImporterError, ImporterResultExt};
+use crate::imported_collab::{ImportType, ImportedCollab, ImportedCollabInfo};
+use crate::roam::markup::{RoamTargets, roam_text_to_delta};
+use crate::roam::parser::{RoamBlock, RoamPage, parse_roam_edn, parse_roam_json};
+use chrono::NaiveDate;
+use collab::core::collab::default_client_id;
+use collab_document::blocks::{Block, BlockType, DiagramData, DocumentDataBuilder, NewBlock};
+use collab_document::document::Document;
+use collab_document::document_data::{default_document_collab_data, generate_id};
+use collab_document::importer::define::*;
+use collab_document::provenance::BlockProvenance;
+use collab_entity::CollabType;
+use collab_folder::hierarchy_builder::{NestedChildViewBuilder, NestedViews, ParentChildViews};
+use collab_folder::{SpaceInfo, ViewLayout};
+use serde_json::json;
+use std::collections::HashSet;
+use std::path::Path;
+
+const JOURNAL_NAME: &str = "Journal";
+const UNTITLED_PAGE: &str = "Untitled";
+/// The markers of the todo blocks, with whether the todo is done.
+const TODO_MARKERS: [(&str, bool); 4] = [
+  ("{{[[TODO]]}}", false),
+  ("{{TODO}}", false),
+  ("{{[[DONE]]}}", true),
+  ("{{DONE}}", true),
+];
+
+/// Imports the graph exports of Roam Research, in JSON or EDN.
+///
+/// Each page becomes a document whose blocks keep the outline of the page as nested bulleted
+/// lists. The headings, the todos and the code blocks get their own block types, and the links
+/// to the pages and the references to the blocks of the graph become mentions. The daily notes
+/// are grouped under a Journal space, the latest first, the other pages are imported under the
+/// workspace.
+pub struct RoamImporter {
+  uid: i64,
+  workspace_id: String,
+}
+
+impl RoamImporter {
+  pub fn new<S: ToString>(uid: i64, workspace_id: S) -> Self {
+    Self {
+      uid,
+      workspace_id: workspace_id.to_string(),
+    }
+  }
+
+  /// Import a `.json` or `.edn` export.
+  pub fn import_file<P: AsRef<Path>>(&self, path: P) -> Result<RoamImportedInfo, ImporterError> {
+    let path = path.as_ref();
+    if !path.exists() {
+      return Err(ImporterError::FileNotFound);
+    }
+    let content = std::fs::read_to_string(path)?;
+    let is_edn = match path.extension().and_then(|ext| ext.to_str()) {
+      Some(ext) => ext.eq_ignore_ascii_case("edn"),
+      None => !content.trim_start().starts_with('['),
+    };
+    let pages = if is_edn {
+      parse_roam_edn(&content)
+    } else {
+      parse_roam_json(&content)
+    }
+    .with_context(|| format!("import {}", path.display()))?;
+    self.import_pages(&pages)
+  }
+
+  pub fn import_pages(&self, pages: &[RoamPage]) -> Result<RoamImportedInfo, ImporterError> {
+    let mut targets = RoamTargets::default();
+    let view_ids = pages
+      .iter()
+      .map(|page| {
+        let view_id = uuid::Uuid::new_v4().to_string();
+        targets
+          .pages
+          .entry(page.title.clone())
+          .or_insert_with(|| view_id.clone());
+        collect_block_ids(&page.children, &view_id, &mut targets);
+        view_id
+      })
+      .collect::<Vec<_>>();
+
+    let mut daily_notes = vec![];
+    let mut imported_pages = vec![];
+    for (page, view_id) in pages.iter().zip(view_ids) {
+      let imported = self.import_page(page, view_id, &targets)?;
+      if imported.date.is_some() {
+        daily_notes.push(imported);
+      } else {
+        imported_pages.push(imported);
+      }
+    }
+
+    let journal = if daily_notes.is_empty() {
+      None
+    } else {
+      daily_notes.sort_by(|a, b| b.date.cmp(&a.date));
+      let view_id = uuid::Uuid::new_v4().to_string();
+      let encoded_collab = default_document_collab_data(&view_id, default_client_id())?;
+      Some(RoamJournal {
+        view_id: view_id.clone(),
+        name: JOURNAL_NAME.to_string(),
+        pages: daily_notes,
+        collab_info: ImportedCollabInfo {
+          name: JOURNAL_NAME.to_string(),
+          imported_collabs: vec![ImportedCollab {
+            object_id: view_id,
+            collab_type: CollabType::Document,
+            encoded_collab,
+          }],
+          resources: vec![],
+          import_type: ImportType::Document,
+        },
+      })
+    };
+
+    Ok(RoamImportedInfo {
+      uid: self.uid,
+      workspace_id: self.workspace_id.clone(),
+      pages: imported_pages,
+      journal,
+    })
+  }
+
+  fn import_page(
+    &self,
+    page: &RoamPage,
+    view_id: String,
+    targets: &RoamTargets,
+  ) -> Result<RoamImportedPage, ImporterError> {
+    let title = if page.title.trim().is_empty() {
+      UNTITLED_PAGE.to_string()
+    } else {
+      page.title.clone()
+    };
+    let mut blocks = RoamBlockConverter::new(targets).convert(&page.children);
+    if blocks.is_empty() {
+      blocks.push(Block::paragraph(""));
+    }
+    let mut builder = DocumentDataBuilder::with_page_id(&view_id);
+    for block in blocks {
+      builder.push(block);
+    }
+    let mut document_data = builder.build();
+    document_data.tag_provenance(&BlockProvenance::new("roam", &page.title));
+    let document = Document::create(&view_id, document_data, default_client_id())?;
+    let encoded_collab = document.encode_collab()?;
+    Ok(RoamImportedPage {
+      view_id: view_id.clone(),
+      title: title.clone(),
+      roam_uid: page.uid.clone(),
+      date: page.daily_note_date(),
+      collab_info: ImportedCollabInfo {
+        name: title,
+        imported_collabs: vec![ImportedCollab {
+          object_id: view_id,
+          collab_type: CollabType::Document,
+          encoded_collab,
+        }],
+        resources: vec![],
+        import_type: ImportType::Document,
+      },
+    })
+  }
+}
+
+/// The pages imported by [RoamImporter].
+#[derive(Debug, Clone)]
+pub struct RoamImportedInfo {
+  pub uid: i64,
+  pub workspace_id: String,
+  /// The pages that are not daily notes, in the order of the export.
+  pub pages: Vec<RoamImportedPage>,
+  /// The space of the daily notes, none when the graph has no daily notes.
+  pub journal: Option<RoamJournal>,
+}
+
+impl RoamImportedInfo {
+  pub fn num_of_pages(&self) -> usize {
+    self.pages.len()
+      + self
+        .journal
+        .as_ref()
+        .map(|journal| journal.pages.len())
+        .unwrap_or_default()
+  }
+
+  /// The view of the journal space, with the views of the daily notes, followed by the views of
+  /// the other pages, under the workspace.
+  pub fn build_nested_views(&self) -> NestedViews {
+    let mut views = vec![];
+    if let Some(journal) = &self.journal {
+      let daily_notes = journal
+        .pages
+        .iter()
+        .map(|page| self.page_view(&journal.view_id, page))
+        .collect::<Vec<_>>();
+      views.push(
+        NestedChildViewBuilder::new(self.uid, self.workspace_id.clone())
+          .with_view_id(&journal.view_id)
+          .with_name(&journal.name)
+          .with_layout(ViewLayout::Document)
+          .with_children(daily_notes)
+          .with_extra(|extra| extra.with_space_info(SpaceInfo::default()).build())
+          .build(),
+      );
+    }
+    views.extend(
+      self
+        .pages
+        .iter()
+        .map(|page| self.page_view(&self.workspace_id, page)),
+    );
+    NestedViews { views }
+  }
+
+  /// The collabs of the journal and of the pages, the journal before the daily notes.
+  pub fn into_collab_infos(self) -> Vec<ImportedCollabInfo> {
+    let mut infos = vec![];
+    if let Some(journal) = self.journal {
+      infos.push(journal.collab_info);
+      infos.extend(journal.pages.into_iter().map(|page| page.collab_info));
+    }
+    infos.extend(self.pages.into_iter().map(|page| page.collab_info));
+    infos
+  }
+
+  fn page_view(&self, parent_view_id: &str, page: &RoamImportedPage) -> ParentChildViews {
+    NestedChildViewBuilder::new(self.uid, parent_view_id.to_string())
+      .with_view_id(&page.view_id)
+      .with_name(&page.title)
+      .with_layout(ViewLayout::Document)
+      .build()
+  }
+}
+
+/// The space of the daily notes.
+#[derive(Debug, Clone)]
+pub struct RoamJournal {
+  pub view_id: String,
+  pub name: String,
+  /// The daily notes, the latest first.
+  pub pages: Vec<RoamImportedPage>,
+  pub collab_info: ImportedCollabInfo,
+}
+
+#[derive(Debug, Clone)]
+pub struct RoamImportedPage {
+  pub view_id: String,
+  pub title: String,
+  pub roam_uid: Option<String>,
+  /// The date of the daily notes.
+  pub date: Option<NaiveDate>,
+  pub collab_info: ImportedCollabInfo,
+}
+
+/// Assign the ids of the blocks with a uid, so the references can be resolved before the pages
+/// are built.
+fn collect_block_ids(blocks: &[RoamBlock], view_id: &str, targets: &mut RoamTargets) {
+  for block in blocks {
+    if let Some(uid) = &block.uid {
+      targets
+        .blocks
+        .entry(uid.clone())
+        .or_insert_with(|| (view_id.to_string(), generate_id()));
+    }
+    collect_block_ids(&block.children, view_id, targets);
+  }
+}
+
+/// Convert the outline of a page to blocks. The blocks with a uid keep the id assigned by
+/// [collect_block_ids], so the block references point to them.
+struct RoamBlockConverter<'a> {
+  targets: &'a RoamTargets,
+  /// The ids of the blocks already in the document, a uid that is used twice gets a new id.
+  used_ids: HashSet<String>,
+}
+
+impl<'a> RoamBlockConverter<'a> {
+  fn new(targets: &'a RoamTargets) -> Self {
+    Self {
+      targets,
+      used_ids: HashSet::new(),
+    }
+  }
+
+  /// The blocks of the outline. The children of the headings and of the code blocks follow
+  /// them at the same level, the other blocks nest their children.
+  fn convert(&mut self, blocks: &[RoamBlock]) -> Vec<NewBlock> {
+    let mut new_blocks = vec![];
+    for block in blocks {
+      let id = self.block_id(block);
+      if let Some((language, code)) = code_block(&block.string) {
+        // the mermaid and plantuml code blocks are diagrams, they keep their source in the data
+        let new_block = match DiagramData::from_code_block(language, code) {
+          Some(diagram) => {
+            let mut new_block = NewBlock::new(BlockType::Diagram).with_delta(json!([]));
+            new_block.data = diagram.into_block_data();
+            new_block
+          },
+          None => NewBlock::new(BlockType::Code)
+            .with_data(LANGUAGE_FIELD, json!(language))
+            .with_delta(json!([{ "insert": code }])),
+        };
+        new_blocks.push(new_block.with_id(&id));
+        new_blocks.extend(self.convert(&block.children));
+      } else if let Some(level) = block.heading.filter(|level| (1..=3).contains(level)) {
+        let new_block = NewBlock::new(BlockType::Heading)
+          .with_id(&id)
+          .with_data(LEVEL_FIELD, json!(level))
+          .with_delta(roam_text_to_delta(&block.string, self.targets));
+        new_blocks.push(new_block);
+        new_blocks.extend(self.convert(&block.children));
+      } else {
+        let (new_block, text) = match todo_marker(&block.string) {
+          Some((checked, text)) => (
+            NewBlock::new(BlockType::TodoList).with_data(CHECKED_FIELD, json!(checked)),
+            text,
+          ),
+          None => (
+            NewBlock::new(BlockType::BulletedList),
+            block.string.as_str(),
+          ),
+        };
+        let new_block = new_block
+          .with_id(&id)
+          .with_delta(roam_text_to_delta(text, self.targets));
+        let children = self.convert(&block.children);
+        new_blocks.push(new_block.with_children(children));
+      }
+    }
+    new_blocks
+  }
+
+  fn block_id(&mut self, block: &RoamBlock) -> String {
+    let id = block
+      .uid
+      .as_ref()
+      .and_then(|uid| self.targets.blocks.get(uid))
+      .map(|(_, id)| id.clone())
+      .filter(|id| !self.used_ids.contains(id))
+      .unwrap_or_else(generate_id);
+    self.used_ids.insert(id.clone());
+    id
+  }
+}
+
+/// The language and the code of a block written as a fenced code block.
+fn code_block(text: &str) -> Option<(&str, &str)> {
+  let rest = text.trim().strip_prefix("```")?.strip_suffix("```")?;
+  let (language, code) = rest.split_once('\n').unwrap_or(("", rest));
+  Some((language.trim(), code.trim_end_matches('\n')))
+}
+
+/// Whether the todo is done and its text, when the block starts with a todo marker.
+fn todo_marker(text: &str) -> Option<(bool, &str)> {
+  TODO_MARKERS.iter().find_map(|(marker, checked)| {
+    text
+      .strip_prefix(marker)
+      .map(|rest| (*checked, rest.trim_start()))
+  })
+}
@@ -0,0 +1,195 @@
+use collab_document::importer::define::*;
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+
+/// The pages and blocks that the links and the block references of the graph point to.
+#[derive(Debug, Default)]
+pub(crate) struct RoamTargets {
+  /// The view id of each page, by title.
+  pub(crate) pages: HashMap<String, String>,
+  /// The view id of the page and the id of the block, by block uid.
+  pub(crate) blocks: HashMap<String, (String, String)>,
+}
+
+/// Convert the text of a block to a text delta.
+///
+/// The `[[Page]]`, `#[[Page]]` and `#Page` links become page mentions and the `((uid))` block
+/// references become mentions of the block, when their target is part of the graph. The
+/// `**bold**`, `__italic__`, `~~strikethrough~~`, `` `code` `` and `[label](url)` markup
+/// become attributes, the `^^highlight^^` markers are dropped.
+pub(crate) fn roam_text_to_delta(text: &str, targets: &RoamTargets) -> Value {
+  let mut converter = InlineConverter {
+    targets,
+    ops: vec![],
+    text: String::new(),
+    attributes: Map::new(),
+  };
+  converter.convert(text);
+  converter.flush();
+  Value::Array(converter.ops)
+}
+
+struct InlineConverter<'a> {
+  targets: &'a RoamTargets,
+  ops: Vec<Value>,
+  /// The text of the current op.
+  text: String,
+  /// The attributes of the current op, toggled by the markup.
+  attributes: Map<String, Value>,
+}
+
+impl InlineConverter<'_> {
+  fn convert(&mut self, text: &str) {
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+      if let Some(inner) = enclosed(rest, "((", "))") {
+        match self.targets.blocks.get(inner) {
+          Some((view_id, block_id)) => self.push_mention(json!({
+            MENTION_TYPE_FIELD: MENTION_TYPE_PAGE,
+            MENTION_PAGE_ID_FIELD: view_id,
+            MENTION_BLOCK_ID_FIELD: block_id,
+          })),
+          None => self.text.push_str(&rest[..inner.len() + 4]),
+        }
+        rest = &rest[inner.len() + 4..];
+      } else if let Some(title) = page_link(rest) {
+        let skip = if rest.starts_with('#') { 5 } else { 4 };
+        self.push_page_link(title);
+        rest = &rest[title.len() + skip..];
+      } else if let Some(name) = starts_word(text, rest).then(|| tag(rest)).flatten() {
+        self.push_page_link(name);
+        rest = &rest[name.len() + 1..];
+      } else if let Some(marker) = ["**", "__", "~~", "^^"]
+        .into_iter()
+        .find(|marker| rest.starts_with(marker))
+      {
+        self.toggle(marker);
+        rest = &rest[2..];
+      } else if let Some(code) = enclosed(rest, "`", "`") {
+        self.flush();
+        self
+          .ops
+          .push(json!({ "insert": code, "attributes": { CODE_ATTR: true } }));
+        rest = &rest[code.len() + 2..];
+      } else if let Some((label, url, len)) = markdown_link(rest) {
+        self.flush();
+        let mut attributes = self.attributes.clone();
+        attributes.insert(HREF_ATTR.to_string(), json!(url));
+        self
+          .ops
+          .push(json!({ "insert": label, "attributes": attributes }));
+        rest = &rest[len..];
+      } else {
+        self.text.push(c);
+        rest = &rest[c.len_utf8()..];
+      }
+    }
+  }
+
+  fn push_page_link(&mut self, title: &str) {
+    match self.targets.pages.get(title) {
+      Some(view_id) => self.push_mention(json!({
+        MENTION_TYPE_FIELD: MENTION_TYPE_PAGE,
+        MENTION_PAGE_ID_FIELD: view_id,
+      })),
+      None => self.text.push_str(title),
+    }
+  }
+
+  fn push_mention(&mut self, mention: Value) {
+    self.flush();
+    let mut attributes = self.attributes.clone();
+    attributes.insert(MENTION_ATTR.to_string(), mention);
+    self
+      .ops
+      .push(json!({ "insert": MENTION_SYMBOL, "attributes": attributes }));
+  }
+
+  fn toggle(&mut self, marker: &str) {
+    let attribute = match marker {
+      "**" => BOLD_ATTR,
+      "__" => ITALIC_ATTR,
+      "~~" => STRIKETHROUGH_ATTR,
+      _ => return,
+    };
+    self.flush();
+    if self.attributes.remove(attribute).is_none() {
+      self.attributes.insert(attribute.to_string(), json!(true));
+    }
+  }
+
+  fn flush(&mut self) {
+    if self.text.is_empty() {
+      return;
+    }
+    let text = std::mem::take(&mut self.text);
+    if self.attributes.is_empty() {
+      self.ops.push(json!({ "insert": text }));
+    } else {
+      self
+        .ops
+        .push(json!({ "insert": text, "attributes": self.attributes.clone() }));
+    }
+  }
+}
+
+/// The text between `open` and the next `close`, when the text starts with `open`.
+fn enclosed<'a>(text: &'a str, open: &str, close: &str) -> Option<&'a str> {
+  let rest = text.strip_prefix(open)?;
+  let end = rest.find(close)?;
+  let inner = &rest[..end];
+  (!inner.is_empty() && !inner.contains('\n')).then_some(inner)
+}
+
+/// The title of a `[[Page]]` or `#[[Page]]` link. The titles can contain links themselves, e.g.
+/// `[[[[Roam]] tips]]`.
+fn page_link(text: &str) -> Option<&str> {
+  let rest = text.strip_prefix('#').unwrap_or(text).strip_prefix("[[")?;
+  let mut depth = 1;
+  let mut index = 0;
+  while index < rest.len() {
+    if rest[index..].starts_with("[[") {
+      depth += 1;
+      index += 2;
+    } else if rest[index..].starts_with("]]") {
+      depth -= 1;
+      if depth == 0 {
+        let title = &rest[..index];
+        return (!title.is_empty()).then_some(title);
+      }
+      index += 2;
+    } else {
+      index += rest[index..].chars().next()?.len_utf8();
+    }
+  }
+  None
+}
+
+/// Whether `rest`, a suffix of `text`, is at the start of a word.
+fn starts_word(text: &str, rest: &str) -> bool {
+  text[..text.len() - rest.len()]
+    .chars()
+    .last()
+    .is_none_or(|c| c.is_whitespace() || c == '(')
+}
+
+/// The name of a `#tag`. Only the tags starting a word are links, see [starts_word], so `C#` is
+/// not a tag.
+fn tag(text: &str) -> Option<&str> {
+  let rest = text.strip_prefix('#')?;
+  let len = rest
+    .char_indices()
+    .find(|(_, c)| !(c.is_alphanumeric() || matches!(c, '-' | '_' | '/' | '.')))
+    .map(|(index, _)| index)
+    .unwrap_or(rest.len());
+  let name = rest[..len].trim_end_matches('.');
+  (!name.is_empty()).then_some(name)
+}
+
+/// The label, the url and the length of a `[label](url)` link.
+fn markdown_link(text: &str) -> Option<(&str, &str, usize)> {
+  let label = enclosed(text, "[", "]")?;
+  let rest = &text[label.len() + 2..];
+  let url = enclosed(rest, "(", ")")?;
+  Some((label, url, label.len() + url.len() + 4))
+}
@@ -0,0 +1,6 @@
+mod edn;
+mod importer;
+mod markup;
+pub mod parser;
+
+pub use importer::*;
@@ -0,0 +1,152 @@
+use crate::error::ImporterError;
+use crate::roam::edn::{Edn, parse_edn};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A page of a Roam Research graph export.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RoamPage {
+  pub title: String,
+  /// The uid of the page. The daily notes use the date, e.g. `10-16-2026`.
+  pub uid: Option<String>,
+  /// The creation time, in milliseconds.
+  #[serde(rename = "create-time")]
+  pub create_time: Option<i64>,
+  /// The last modification time, in milliseconds.
+  #[serde(rename = "edit-time")]
+  pub edit_time: Option<i64>,
+  pub children: Vec<RoamBlock>,
+}
+
+impl RoamPage {
+  /// The date of the page when it's a daily note. The daily notes are recognized by their uid,
+  /// or by their title, e.g. `October 16th, 2026`, for the exports without page uids.
+  pub fn daily_note_date(&self) -> Option<NaiveDate> {
+    if let Some(date) = self
+      .uid
+      .as_deref()
+      .and_then(|uid| NaiveDate::parse_from_str(uid, "%m-%d-%Y").ok())
+    {
+      return Some(date);
+    }
+    let (month_day, year) = self.title.split_once(", ")?;
+    let (month, day) = month_day.split_once(' ')?;
+    let day = day
+      .strip_suffix("st")
+      .or_else(|| day.strip_suffix("nd"))
+      .or_else(|| day.strip_suffix("rd"))
+      .or_else(|| day.strip_suffix("th"))?;
+    NaiveDate::parse_from_str(&format!("{} {} {}", month, day, year), "%B %d %Y").ok()
+  }
+}
+
+/// A block of the outline of a page.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RoamBlock {
+  /// The text of the block, with the Roam markup, e.g. `**bold**`, `[[Page]]` or `((uid))`.
+  pub string: String,
+  pub uid: Option<String>,
+  /// The heading level, from 1 to 3.
+  pub heading: Option<u8>,
+  pub children: Vec<RoamBlock>,
+}
+
+/// Parse the JSON export of a Roam graph.
+pub fn parse_roam_json(json: &str) -> Result<Vec<RoamPage>, ImporterError> {
+  serde_json::from_str(json).map_err(|err| ImporterError::ParseRoamError(err.to_string()))
+}
+
+/// Parse the EDN export of a Roam graph, a dump of its datoms:
+/// `#datascript/DB {:schema {...} :datoms [[entity attribute value tx] ...]}`.
+pub fn parse_roam_edn(edn: &str) -> Result<Vec<RoamPage>, ImporterError> {
+  let db = parse_edn(edn)?;
+  let datoms = db
+    .get("datoms")
+    .and_then(Edn::as_list)
+    .ok_or_else(|| ImporterError::ParseRoamError("missing datoms".to_string()))?;
+
+  let mut entities: HashMap<i64, RoamEntity> = HashMap::new();
+  let mut order = vec![];
+  for datom in datoms {
+    let Some([entity, attribute, value, ..]) = datom.as_list() else {
+      continue;
+    };
+    let (Some(entity), Some(attribute)) = (entity.as_int(), attribute.as_keyword()) else {
+      continue;
+    };
+    let entry = entities.entry(entity).or_insert_with(|| {
+      order.push(entity);
+      RoamEntity::default()
+    });
+    match attribute {
+      "node/title" => entry.title = value.as_str().map(|s| s.to_string()),
+      "block/string" => entry.string = value.as_str().map(|s| s.to_string()),
+      "block/uid" => entry.uid = value.as_str().map(|s| s.to_string()),
+      "block/heading" => entry.heading = value.as_int().map(|level| level as u8),
+      "block/order" => entry.order = value.as_int().unwrap_or_default(),
+      "block/children" => entry.children.extend(value.as_int()),
+      "create/time" => entry.create_time = value.as_int(),
+      "edit/time" => entry.edit_time = value.as_int(),
+      _ => {},
+    }
+  }
+
+  let pages = order
+    .iter()
+    .filter_map(|id| {
+      let entity = &entities[id];
+      let title = entity.title.clone()?;
+      Some(RoamPage {
+        title,
+        uid: entity.uid.clone(),
+        create_time: entity.create_time,
+        edit_time: entity.edit_time,
+        children: edn_children(&entities, entity, 0),
+      })
+    })
+    .collect();
+  Ok(pages)
+}
+
+/// The outlines deeper than this are cut, in case of a reference cycle in a broken export.
+const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Default)]
+struct RoamEntity {
+  title: Option<String>,
+  string: Option<String>,
+  uid: Option<String>,
+  heading: Option<u8>,
+  order: i64,
+  children: Vec<i64>,
+  create_time: Option<i64>,
+  edit_time: Option<i64>,
+}
+
+fn edn_children(
+  entities: &HashMap<i64, RoamEntity>,
+  parent: &RoamEntity,
+  depth: usize,
+) -> Vec<RoamBlock> {
+  if depth >= MAX_DEPTH {
+    return vec![];
+  }
+  let mut children = parent
+    .children
+    .iter()
+    .filter_map(|id| entities.get(id))
+    .collect::<Vec<_>>();
+  children.sort_by_key(|child| child.order);
+  children
+    .into_iter()
+    .map(|child| RoamBlock {
+      string: child.string.clone().unwrap_or_default(),
+      uid: child.uid.clone(),
+      heading: child.heading,
+      children: edn_children(entities, child, depth + 1),
+    })
+    .collect()
+}
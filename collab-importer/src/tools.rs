@@ -0,0 +1,589 @@
+//! Checks run on an imported Notion export, shared by the admin tools, the import services and
+//! the tests.
+//!
+//! [ImportVerifier] returns a typed [VerifyReport], the callers decide how to print it or whether
+//! to commit the import. The `Display` implementations give the one-line descriptions used by
+//! `examples/verify_notion_zip.rs`.
+
+use crate::error::ImporterError;
+use crate::notion::NotionImporter;
+use crate::notion::file::NotionFile;
+use crate::notion::importer::ImportedInfo;
+use crate::notion::page::NotionPage;
+use crate::zip_tool::sync_zip::sync_unzip;
+use crate::zip_tool::util::remove_part_suffix;
+use collab_document::error::ErrorCategory;
+use percent_encoding::percent_decode_str;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// The depth of the view tree returned by [verify_imported] when none is given.
+pub const DEFAULT_TREE_DEPTH: usize = 6;
+
+/// The exit codes of the tools, following the BSD `sysexits.h` conventions.
+pub const EXIT_OK: i32 = 0;
+/// The import succeeded but the checks found issues, see [VerifyReport::has_issues].
+pub const EXIT_ISSUES_FOUND: i32 = 1;
+pub const EXIT_USAGE: i32 = 64;
+pub const EXIT_DATA_ERROR: i32 = 65;
+pub const EXIT_NO_INPUT: i32 = 66;
+pub const EXIT_SOFTWARE: i32 = 70;
+pub const EXIT_IO_ERROR: i32 = 74;
+
+/// The exit code of a tool that failed with the error, derived from the error category.
+pub fn exit_code(err: &ImporterError) -> i32 {
+  match err.category() {
+    ErrorCategory::SourceFormat | ErrorCategory::Structural => EXIT_DATA_ERROR,
+    ErrorCategory::Resource => EXIT_NO_INPUT,
+    ErrorCategory::Storage => EXIT_IO_ERROR,
+    ErrorCategory::Internal => EXIT_SOFTWARE,
+  }
+}
+
+/// The arguments of the verify tool: `<path-to-export-zip> [--depth <n>]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyArgs {
+  pub zip_path: PathBuf,
+  pub max_depth: usize,
+}
+
+impl VerifyArgs {
+  pub const USAGE: &'static str = "usage: verify_notion_zip <path-to-export-zip> [--depth <n>]";
+
+  /// Parse the arguments, without the program name. Return [VerifyArgs::USAGE] with the reason
+  /// when they are invalid, the tool should exit with [EXIT_USAGE].
+  pub fn parse<I, S>(args: I) -> Result<Self, String>
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    let mut zip_path = None;
+    let mut max_depth = DEFAULT_TREE_DEPTH;
+    let mut args = args.into_iter().map(Into::into);
+    while let Some(arg) = args.next() {
+      match arg.as_str() {
+        "--depth" => {
+          max_depth = args
+            .next()
+            .and_then(|depth| depth.parse().ok())
+            .ok_or_else(|| format!("--depth expects a number\n{}", Self::USAGE))?;
+        },
+        _ if arg.starts_with("--") => {
+          return Err(format!("unknown option {}\n{}", arg, Self::USAGE));
+        },
+        _ if zip_path.is_none() => zip_path = Some(PathBuf::from(arg)),
+        _ => return Err(format!("unexpected argument {}\n{}", arg, Self::USAGE)),
+      }
+    }
+    let zip_path = zip_path.ok_or_else(|| Self::USAGE.to_string())?;
+    Ok(Self {
+      zip_path,
+      max_depth,
+    })
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportSummary {
+  pub name: String,
+  pub num_of_top_level_views: usize,
+  pub num_of_markdown: usize,
+  pub num_of_csv: usize,
+}
+
+impl ImportSummary {
+  pub fn new(imported: &ImportedInfo) -> Self {
+    Self {
+      name: imported.name.clone(),
+      num_of_top_level_views: imported.views().len(),
+      num_of_markdown: imported.num_of_markdown(),
+      num_of_csv: imported.num_of_csv(),
+    }
+  }
+}
+
+impl Display for ImportSummary {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    writeln!(f, "Imported workspace name: {}", self.name)?;
+    writeln!(f, "Top-level views: {}", self.num_of_top_level_views)?;
+    writeln!(f, "Markdown count: {}", self.num_of_markdown)?;
+    write!(f, "CSV count: {}", self.num_of_csv)
+  }
+}
+
+/// A page of the view tree, see [view_tree].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViewTreeLine {
+  pub depth: usize,
+  pub name: String,
+  pub is_dir: bool,
+  /// The Notion id of the page, only when it's a 32 hex characters id.
+  pub notion_id: Option<String>,
+}
+
+impl Display for ViewTreeLine {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{}- {} {}",
+      " ".repeat(self.depth * 2),
+      self.name,
+      if self.is_dir { "[dir] " } else { "" }
+    )?;
+    if let Some(id) = &self.notion_id {
+      write!(f, " ({})", id)?;
+    }
+    Ok(())
+  }
+}
+
+/// Flatten the pages into the lines of a tree, each page followed by its children, down to
+/// `max_depth` levels.
+pub fn view_tree(pages: &[NotionPage], max_depth: usize) -> Vec<ViewTreeLine> {
+  let mut lines = vec![];
+  collect_view_tree(pages, 0, max_depth, &mut lines);
+  lines
+}
+
+fn collect_view_tree(
+  pages: &[NotionPage],
+  depth: usize,
+  max_depth: usize,
+  lines: &mut Vec<ViewTreeLine>,
+) {
+  if depth >= max_depth {
+    return;
+  }
+  for page in pages {
+    lines.push(ViewTreeLine {
+      depth,
+      name: page.notion_name.clone(),
+      is_dir: page.is_dir,
+      notion_id: notion_hex_id(page).map(|id| id.to_string()),
+    });
+    collect_view_tree(&page.children, depth + 1, max_depth, lines);
+  }
+}
+
+/// A Notion id shared by several pages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateId {
+  /// The id, in lowercase.
+  pub id: String,
+  /// The paths of the pages with the id, made of the page names joined with `/`.
+  pub paths: Vec<String>,
+}
+
+impl Display for DuplicateId {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "DUP id={}:\n{}", self.id, self.paths.join("\n"))
+  }
+}
+
+/// Return the 32 hex characters Notion ids shared by several pages, ordered by id.
+pub fn find_duplicate_ids(pages: &[NotionPage]) -> Vec<DuplicateId> {
+  let mut paths_by_id: HashMap<String, Vec<String>> = HashMap::new();
+  collect_ids(pages, "", &mut paths_by_id);
+  let mut duplicates = paths_by_id
+    .into_iter()
+    .filter(|(_, paths)| paths.len() > 1)
+    .map(|(id, paths)| DuplicateId { id, paths })
+    .collect::<Vec<_>>();
+  duplicates.sort_by(|a, b| a.id.cmp(&b.id));
+  duplicates
+}
+
+fn collect_ids(pages: &[NotionPage], path: &str, paths_by_id: &mut HashMap<String, Vec<String>>) {
+  for page in pages {
+    let page_path = child_path(path, &page.notion_name);
+    if let Some(id) = notion_hex_id(page) {
+      paths_by_id
+        .entry(id.to_ascii_lowercase())
+        .or_default()
+        .push(page_path.clone());
+    }
+    collect_ids(&page.children, &page_path, paths_by_id);
+  }
+}
+
+/// Sibling pages whose names are the same once trimmed and lowercased.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiblingNameCollision {
+  /// The path of the parent, empty for the top-level pages.
+  pub path: String,
+  /// The normalized name.
+  pub name: String,
+  /// The kind of each colliding page, `dir` or `page`, with its id, e.g. `page:<id>`. Sorted and
+  /// deduplicated.
+  pub variants: Vec<String>,
+}
+
+impl Display for SiblingNameCollision {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "Sibling name collision at '{}': name='{}', variants={}",
+      if self.path.is_empty() {
+        "<root>"
+      } else {
+        &self.path
+      },
+      self.name,
+      self.variants.join(", ")
+    )
+  }
+}
+
+/// Return the sibling name collisions, ordered by parent path and name.
+pub fn find_sibling_name_collisions(pages: &[NotionPage]) -> Vec<SiblingNameCollision> {
+  let mut collisions = vec![];
+  collect_sibling_name_collisions(pages, "", &mut collisions);
+  collisions.sort_by(|a, b| (&a.path, &a.name).cmp(&(&b.path, &b.name)));
+  collisions
+}
+
+fn collect_sibling_name_collisions(
+  pages: &[NotionPage],
+  path: &str,
+  collisions: &mut Vec<SiblingNameCollision>,
+) {
+  let mut pages_by_name: HashMap<String, Vec<&NotionPage>> = HashMap::new();
+  for page in pages {
+    pages_by_name
+      .entry(page.notion_name.trim().to_ascii_lowercase())
+      .or_default()
+      .push(page);
+  }
+  for (name, group) in pages_by_name {
+    if group.len() < 2 {
+      continue;
+    }
+    let mut variants = group
+      .iter()
+      .map(|page| {
+        let kind = if page.is_dir { "dir" } else { "page" };
+        match notion_hex_id(page) {
+          Some(id) => format!("{}:{}", kind, id),
+          None => kind.to_string(),
+        }
+      })
+      .collect::<Vec<_>>();
+    variants.sort();
+    variants.dedup();
+    collisions.push(SiblingNameCollision {
+      path: path.to_string(),
+      name,
+      variants,
+    });
+  }
+  for page in pages {
+    collect_sibling_name_collisions(
+      &page.children,
+      &child_path(path, &page.notion_name),
+      collisions,
+    );
+  }
+}
+
+/// A file of the directory of a page that no page links to. The files of the directory are
+/// uploaded with the page anyway.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnreferencedAsset {
+  /// The path of the page, made of the page names joined with `/`.
+  pub page_path: String,
+  pub file: PathBuf,
+}
+
+impl Display for UnreferencedAsset {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "Unreferenced asset in '{}': {}",
+      self.page_path,
+      self.file.display()
+    )
+  }
+}
+
+/// A relative link of a page to a file missing from the export, e.g. a page that wasn't exported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenLink {
+  pub page_path: String,
+  /// The link, as written in the page.
+  pub link: String,
+}
+
+impl Display for BrokenLink {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "Broken link in '{}': {}", self.page_path, self.link)
+  }
+}
+
+/// A markdown page without content besides its title, nor sub pages or files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmptyPage {
+  pub page_path: String,
+  pub view_id: String,
+}
+
+impl Display for EmptyPage {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "Empty page '{}' ({})", self.page_path, self.view_id)
+  }
+}
+
+/// The result of the checks run on an imported export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyReport {
+  pub summary: ImportSummary,
+  pub tree: Vec<ViewTreeLine>,
+  pub duplicate_ids: Vec<DuplicateId>,
+  pub sibling_name_collisions: Vec<SiblingNameCollision>,
+  pub unreferenced_assets: Vec<UnreferencedAsset>,
+  pub broken_links: Vec<BrokenLink>,
+  pub empty_pages: Vec<EmptyPage>,
+}
+
+impl VerifyReport {
+  pub fn has_issues(&self) -> bool {
+    !self.duplicate_ids.is_empty()
+      || !self.sibling_name_collisions.is_empty()
+      || !self.unreferenced_assets.is_empty()
+      || !self.broken_links.is_empty()
+      || !self.empty_pages.is_empty()
+  }
+
+  /// [EXIT_ISSUES_FOUND] when the checks found issues, [EXIT_OK] otherwise.
+  pub fn exit_code(&self) -> i32 {
+    if self.has_issues() {
+      EXIT_ISSUES_FOUND
+    } else {
+      EXIT_OK
+    }
+  }
+}
+
+/// Run the checks on an imported export, before its collabs are built, e.g. to reject the
+/// exports with issues.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportVerifier {
+  max_depth: usize,
+}
+
+impl Default for ImportVerifier {
+  fn default() -> Self {
+    Self {
+      max_depth: DEFAULT_TREE_DEPTH,
+    }
+  }
+}
+
+impl ImportVerifier {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Set the depth of the view tree of the report.
+  pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+    self.max_depth = max_depth;
+    self
+  }
+
+  pub fn verify(&self, imported: &ImportedInfo) -> VerifyReport {
+    let mut report = VerifyReport {
+      summary: ImportSummary::new(imported),
+      tree: view_tree(imported.views(), self.max_depth),
+      duplicate_ids: find_duplicate_ids(imported.views()),
+      sibling_name_collisions: find_sibling_name_collisions(imported.views()),
+      unreferenced_assets: vec![],
+      broken_links: vec![],
+      empty_pages: vec![],
+    };
+    let mut files = PageFiles::default();
+    check_page_files(imported.views(), "", &mut files, &mut report);
+    let mut seen = HashSet::new();
+    report.unreferenced_assets = files
+      .assets
+      .into_iter()
+      .filter(|asset| seen.insert(asset.file.clone()))
+      .filter(|asset| {
+        asset
+          .file
+          .file_name()
+          .and_then(|name| name.to_str())
+          .is_none_or(|name| !files.linked_file_names.contains(name))
+      })
+      .collect();
+    report
+  }
+}
+
+/// Run the checks on the imported export, see [ImportVerifier].
+pub fn verify_imported(imported: &ImportedInfo, max_depth: usize) -> VerifyReport {
+  ImportVerifier::new()
+    .with_max_depth(max_depth)
+    .verify(imported)
+}
+
+/// The assets of the pages and the names of the files the pages link to. A page may link to the
+/// files of its sub pages, so the assets are checked once all the pages are read.
+#[derive(Default)]
+struct PageFiles {
+  assets: Vec<UnreferencedAsset>,
+  linked_file_names: HashSet<String>,
+}
+
+/// Check the files of the pages and of the row pages of the databases: their links and their
+/// content.
+fn check_page_files(
+  pages: &[NotionPage],
+  path: &str,
+  files: &mut PageFiles,
+  report: &mut VerifyReport,
+) {
+  for page in pages {
+    let page_path = child_path(path, &page.notion_name);
+    check_page_file(page, &page_path, files, report);
+    if let NotionFile::CSV { row_documents, .. } = &page.notion_file {
+      for row_document in row_documents {
+        let row_path = child_path(&page_path, &row_document.page.notion_name);
+        check_page_file(&row_document.page, &row_path, files, report);
+      }
+    }
+    check_page_files(&page.children, &page_path, files, report);
+  }
+}
+
+fn check_page_file(
+  page: &NotionPage,
+  page_path: &str,
+  files: &mut PageFiles,
+  report: &mut VerifyReport,
+) {
+  if !page.notion_file.is_document() {
+    return;
+  }
+  let is_html = page.notion_file.is_html();
+  let Some(file_path) = page.notion_file.file_path() else {
+    return;
+  };
+  let Ok(content) = std::fs::read_to_string(file_path) else {
+    return;
+  };
+  let page_dir = file_path.parent().unwrap_or(Path::new(""));
+
+  for link in relative_links(&content, is_html) {
+    let target = link.split(['#', '?']).next().unwrap_or_default();
+    let target = percent_decode_str(target).decode_utf8_lossy();
+    if target.is_empty() {
+      continue;
+    }
+    let target = page_dir.join(target.as_ref());
+    if let Some(name) = target.file_name().and_then(|name| name.to_str()) {
+      files.linked_file_names.insert(name.to_string());
+    }
+    if !target.exists() {
+      report.broken_links.push(BrokenLink {
+        page_path: page_path.to_string(),
+        link: link.clone(),
+      });
+    }
+  }
+
+  let assets = page.notion_file.upload_files();
+  let is_empty = !is_html
+    && assets.is_empty()
+    && page.children.is_empty()
+    && content
+      .lines()
+      .map(str::trim)
+      .filter(|line| !line.is_empty())
+      .all(|line| line.starts_with("# ") && line[2..].trim() == page.notion_name.trim());
+  if is_empty {
+    report.empty_pages.push(EmptyPage {
+      page_path: page_path.to_string(),
+      view_id: page.view_id.clone(),
+    });
+  }
+  files
+    .assets
+    .extend(assets.into_iter().map(|file| UnreferencedAsset {
+      page_path: page_path.to_string(),
+      file,
+    }));
+}
+
+/// The relative links of a markdown or an html page: the targets of the markdown links and
+/// images, or of the `href` and `src` attributes. The urls and the anchors are skipped.
+fn relative_links(content: &str, is_html: bool) -> Vec<String> {
+  let mut links = vec![];
+  let openings: &[&str] = if is_html {
+    &["href=\"", "src=\""]
+  } else {
+    &["]("]
+  };
+  for opening in openings {
+    let closing = if is_html { '"' } else { ')' };
+    for (index, _) in content.match_indices(opening) {
+      let rest = &content[index + opening.len()..];
+      let Some(end) = rest.find(closing) else {
+        continue;
+      };
+      let link = rest[..end].trim();
+      // Markdown links may have a title, e.g. `[a](b.md "title")`
+      let link = link.split(" \"").next().unwrap_or_default().trim();
+      let is_relative = !link.is_empty()
+        && !link.starts_with('#')
+        && !link.starts_with('/')
+        && !link.starts_with("mailto:")
+        && !link.starts_with("data:")
+        && !link.contains("://");
+      if is_relative {
+        links.push(link.to_string());
+      }
+    }
+  }
+  links
+}
+
+/// Unzip the Notion export into `out_dir`, import it and run the checks on it.
+pub async fn verify_notion_zip(
+  zip_path: &Path,
+  out_dir: &Path,
+  max_depth: usize,
+) -> Result<VerifyReport, ImporterError> {
+  let default_name = zip_path
+    .file_stem()
+    .and_then(|stem| stem.to_str())
+    .map(remove_part_suffix)
+    .unwrap_or_else(|| "notion_export".to_string());
+  let unzip = sync_unzip(
+    zip_path.to_path_buf(),
+    out_dir.to_path_buf(),
+    Some(default_name),
+  )?;
+  let importer = NotionImporter::new(
+    1,
+    &unzip.unzip_dir,
+    uuid::Uuid::new_v4().to_string(),
+    "http://test.appflowy.cloud".to_string(),
+  )?;
+  let imported = importer.import().await?;
+  Ok(verify_imported(&imported, max_depth))
+}
+
+fn child_path(path: &str, name: &str) -> String {
+  if path.is_empty() {
+    name.to_string()
+  } else {
+    format!("{}/{}", path, name)
+  }
+}
+
+fn notion_hex_id(page: &NotionPage) -> Option<&str> {
+  page
+    .notion_id
+    .as_deref()
+    .filter(|id| id.len() == 32 && id.bytes().all(|b| b.is_ascii_hexdigit()))
+}
@@ -0,0 +1,533 @@
+use crate::error::{ImporterError, ImporterResultExt};
+use crate::imported_collab::{ImportType, ImportedCollab, ImportedCollabInfo};
+use crate::trello::parser::{TrelloBoard, TrelloCard, TrelloChecklist, TrelloLabel, parse_trello};
+use collab::core::collab::default_client_id;
+use collab::preclude::Any;
+use collab_database::database::{
+  Database, gen_database_id, gen_database_view_id, gen_field_id, gen_option_id, gen_row_id,
+  get_row_document_id,
+};
+use collab_database::database_trait::{MemberResolver, NoPersistenceDatabaseCollabService};
+use collab_database::entity::FieldType;
+use collab_database::fields::checkbox_type_option::CheckboxTypeOption;
+use collab_database::fields::checklist_type_option::ChecklistTypeOption;
+use collab_database::fields::date_type_option::{DateCellData, DateTypeOption};
+use collab_database::fields::person_type_option::{PersonCellData, PersonTypeOption};
+use collab_database::fields::select_type_option::{
+  SelectOption, SelectOptionColor, SelectTypeOption,
+};
+use collab_database::fields::text_type_option::RichTextTypeOption;
+use collab_database::rows::{Cell, RowId, new_cell_builder};
+use collab_database::template::check_list_parse::ChecklistCellData;
+use collab_database::template::entity::{
+  CELL_DATA, DatabaseTemplate, DatabaseViewTemplate, FieldTemplate, RowTemplate,
+};
+use collab_database::views::{
+  BoardLayoutSetting, DatabaseLayout, Group, GroupSetting, GroupSettingMap, LayoutSettings,
+};
+use collab_document::document::Document;
+use collab_document::importer::md_importer::MDImporter;
+use collab_document::provenance::BlockProvenance;
+use collab_entity::CollabType;
+use collab_folder::ViewLayout;
+use collab_folder::hierarchy_builder::{NestedChildViewBuilder, NestedViews};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+const NAME_FIELD: &str = "Name";
+const LIST_FIELD: &str = "List";
+const LABELS_FIELD: &str = "Labels";
+const DUE_FIELD: &str = "Due";
+const DUE_COMPLETE_FIELD: &str = "Done";
+const CHECKLIST_FIELD: &str = "Checklist";
+const MEMBERS_FIELD: &str = "Members";
+const UNTITLED_BOARD: &str = "Untitled";
+
+/// Imports the JSON exports of Trello boards as Kanban databases.
+///
+/// Each open list of the board becomes an option of the `List` single select field, which groups
+/// the board view, and each open card becomes a row of its list. The labels are imported as a
+/// multi select field, the due date and its completion as a date and a checkbox field, and the
+/// items of the checklists as a checklist field. The description of a card is imported as the
+/// document of its row, with the checklists of the card as todo lists when it has more than one,
+/// so their names are kept. The archived lists and cards are skipped.
+///
+/// With a [MemberResolver], the members of the cards are imported as a person field, the members
+/// of the board being matched to the workspace members by name or username.
+pub struct TrelloImporter {
+  uid: i64,
+  workspace_id: String,
+  member_resolver: Option<Arc<dyn MemberResolver>>,
+}
+
+impl TrelloImporter {
+  pub fn new<S: ToString>(uid: i64, workspace_id: S) -> Self {
+    Self {
+      uid,
+      workspace_id: workspace_id.to_string(),
+      member_resolver: None,
+    }
+  }
+
+  pub fn with_member_resolver(mut self, resolver: Arc<dyn MemberResolver>) -> Self {
+    self.member_resolver = Some(resolver);
+    self
+  }
+
+  pub async fn import_file<P: AsRef<Path>>(
+    &self,
+    path: P,
+  ) -> Result<TrelloImportedBoard, ImporterError> {
+    let path = path.as_ref();
+    if !path.exists() {
+      return Err(ImporterError::FileNotFound);
+    }
+    let json = std::fs::read_to_string(path)?;
+    let board = parse_trello(&json).with_context(|| format!("import {}", path.display()))?;
+    self.import_board(&board).await
+  }
+
+  pub async fn import_board(
+    &self,
+    board: &TrelloBoard,
+  ) -> Result<TrelloImportedBoard, ImporterError> {
+    let name = if board.name.trim().is_empty() {
+      UNTITLED_BOARD.to_string()
+    } else {
+      board.name.clone()
+    };
+    let view_id = gen_database_view_id();
+    let database_id = gen_database_id();
+    let cards = board.open_cards();
+
+    let fields = BoardFields::new(board, self.member_resolver.as_deref());
+    let rows = cards
+      .iter()
+      .map(|card| RowTemplate {
+        row_id: gen_row_id().to_string(),
+        height: 60,
+        visibility: true,
+        cells: fields.card_cells(board, card),
+      })
+      .collect::<Vec<_>>();
+    let row_ids = rows
+      .iter()
+      .map(|row| RowId::from(row.row_id.clone()))
+      .collect::<Vec<_>>();
+
+    let template = DatabaseTemplate {
+      database_id: database_id.clone(),
+      view_id: view_id.clone(),
+      views: vec![fields.board_view(&name)],
+      fields: fields.into_templates(),
+      rows,
+    };
+    let service = Arc::new(NoPersistenceDatabaseCollabService::new(default_client_id()));
+    let mut database = Database::create_with_template(template, service.clone(), service).await?;
+
+    let mut row_documents = vec![];
+    for (card, row_id) in cards.iter().zip(row_ids.iter()) {
+      let markdown = card_markdown(card, &board.card_checklists(&card.id));
+      if markdown.trim().is_empty() {
+        continue;
+      }
+      let document_id = get_row_document_id(row_id)?;
+      let mut data = MDImporter::new(None).import(&document_id, markdown)?;
+      data.tag_provenance(&BlockProvenance::new(
+        "trello",
+        format!("{}/{}", name, card.id),
+      ));
+      let document = Document::create(&document_id, data, default_client_id())?;
+      row_documents.push(ImportedCollab {
+        object_id: document_id,
+        collab_type: CollabType::Document,
+        encoded_collab: document.encode_collab()?,
+      });
+      database
+        .update_row_meta(row_id, |meta| {
+          meta.update_is_document_empty(false);
+        })
+        .await;
+    }
+
+    let row_document_ids = row_documents
+      .iter()
+      .map(|collab| collab.object_id.clone())
+      .collect::<Vec<_>>();
+    let mut imported_collabs = database
+      .encode_database_collabs()
+      .await?
+      .into_collabs()
+      .into_iter()
+      .map(|collab_info| ImportedCollab {
+        object_id: collab_info.object_id.to_string(),
+        collab_type: collab_info.collab_type,
+        encoded_collab: collab_info.encoded_collab,
+      })
+      .collect::<Vec<_>>();
+    imported_collabs.extend(row_documents);
+
+    Ok(TrelloImportedBoard {
+      uid: self.uid,
+      workspace_id: self.workspace_id.clone(),
+      view_id: view_id.clone(),
+      database_id: database_id.clone(),
+      name: name.clone(),
+      num_of_cards: cards.len(),
+      collab_info: ImportedCollabInfo {
+        name,
+        imported_collabs,
+        resources: vec![],
+        import_type: ImportType::Database {
+          database_id,
+          view_ids: vec![view_id],
+          row_document_ids,
+        },
+      },
+    })
+  }
+}
+
+/// A board imported by [TrelloImporter].
+#[derive(Debug, Clone)]
+pub struct TrelloImportedBoard {
+  pub uid: i64,
+  pub workspace_id: String,
+  pub view_id: String,
+  pub database_id: String,
+  pub name: String,
+  pub num_of_cards: usize,
+  pub collab_info: ImportedCollabInfo,
+}
+
+impl TrelloImportedBoard {
+  /// The board view of the database, under the workspace.
+  pub fn build_nested_views(&self) -> NestedViews {
+    let view = NestedChildViewBuilder::new(self.uid, self.workspace_id.clone())
+      .with_view_id(&self.view_id)
+      .with_name(&self.name)
+      .with_layout(ViewLayout::Board)
+      .build();
+    NestedViews { views: vec![view] }
+  }
+}
+
+/// The fields of the database, with the options of the select fields.
+struct BoardFields {
+  name_id: String,
+  list_id: String,
+  labels_id: String,
+  due_id: String,
+  due_complete_id: String,
+  checklist_id: String,
+  /// The person field and the uids of the board members, by Trello id. None without
+  /// [MemberResolver].
+  members: Option<(String, HashMap<String, i64>)>,
+  /// The options of the `List` field, in the order of the board.
+  list_options: Vec<(String, SelectOption)>,
+  label_options: Vec<(String, SelectOption)>,
+}
+
+impl BoardFields {
+  fn new(board: &TrelloBoard, member_resolver: Option<&dyn MemberResolver>) -> Self {
+    let list_options = board
+      .open_lists()
+      .into_iter()
+      .enumerate()
+      .map(|(index, list)| {
+        let option = SelectOption {
+          id: gen_option_id(),
+          name: list.name.clone(),
+          color: SelectOptionColor::from(index % 8),
+        };
+        (list.id.clone(), option)
+      })
+      .collect();
+    let label_options = board
+      .labels
+      .iter()
+      .enumerate()
+      .map(|(index, label)| (label.id.clone(), label_option(label, index)))
+      .collect();
+    let members = member_resolver.map(|resolver| {
+      let uids = board
+        .members
+        .iter()
+        .filter_map(|member| {
+          let workspace_member = resolver
+            .find_member(&member.full_name)
+            .or_else(|| resolver.find_member(&member.username))?;
+          Some((member.id.clone(), workspace_member.uid))
+        })
+        .collect();
+      (gen_field_id(), uids)
+    });
+    Self {
+      name_id: gen_field_id(),
+      list_id: gen_field_id(),
+      labels_id: gen_field_id(),
+      due_id: gen_field_id(),
+      due_complete_id: gen_field_id(),
+      checklist_id: gen_field_id(),
+      members,
+      list_options,
+      label_options,
+    }
+  }
+
+  fn card_cells(&self, board: &TrelloBoard, card: &TrelloCard) -> HashMap<String, Cell> {
+    let mut cells = HashMap::new();
+    cells.insert(
+      self.name_id.clone(),
+      text_cell(FieldType::RichText, &card.name),
+    );
+    if let Some((_, option)) = self
+      .list_options
+      .iter()
+      .find(|(list_id, _)| list_id == &card.id_list)
+    {
+      cells.insert(
+        self.list_id.clone(),
+        text_cell(FieldType::SingleSelect, &option.id),
+      );
+    }
+    let label_ids = card
+      .id_labels
+      .iter()
+      .filter_map(|label_id| {
+        self
+          .label_options
+          .iter()
+          .find(|(id, _)| id == label_id)
+          .map(|(_, option)| option.id.clone())
+      })
+      .collect::<Vec<_>>();
+    if !label_ids.is_empty() {
+      cells.insert(
+        self.labels_id.clone(),
+        text_cell(FieldType::MultiSelect, &label_ids.join(",")),
+      );
+    }
+    if let Some(due) = card.due_timestamp() {
+      cells.insert(
+        self.due_id.clone(),
+        Cell::from(&DateCellData::from_timestamp_include_time(due)),
+      );
+    }
+    cells.insert(
+      self.due_complete_id.clone(),
+      text_cell(
+        FieldType::Checkbox,
+        if card.due_complete { "Yes" } else { "No" },
+      ),
+    );
+
+    if let Some((members_id, uids)) = &self.members {
+      let cell_data = PersonCellData::new(
+        card
+          .id_members
+          .iter()
+          .filter_map(|member_id| uids.get(member_id).copied())
+          .collect(),
+      );
+      if !cell_data.uids.is_empty() {
+        cells.insert(members_id.clone(), Cell::from(&cell_data));
+      }
+    }
+
+    let checklists = board.card_checklists(&card.id);
+    let mut checklist = ChecklistCellData::default();
+    for (index, item) in checklists
+      .iter()
+      .flat_map(|checklist| checklist.items())
+      .enumerate()
+    {
+      let option = SelectOption {
+        id: gen_option_id(),
+        name: item.name.clone(),
+        color: SelectOptionColor::from(index % 8),
+      };
+      if item.is_complete() {
+        checklist.selected_option_ids.push(option.id.clone());
+      }
+      checklist.options.push(option);
+    }
+    if !checklist.options.is_empty() {
+      cells.insert(self.checklist_id.clone(), Cell::from(checklist));
+    }
+    cells
+  }
+
+  fn board_view(&self, name: &str) -> DatabaseViewTemplate {
+    let mut group_setting = GroupSetting::new(
+      self.list_id.clone(),
+      FieldType::SingleSelect as i64,
+      "".to_string(),
+    );
+    // the first group holds the cards without list
+    group_setting.groups = std::iter::once(self.list_id.clone())
+      .chain(
+        self
+          .list_options
+          .iter()
+          .map(|(_, option)| option.id.clone()),
+      )
+      .map(Group::new)
+      .collect();
+    let mut layout_settings = LayoutSettings::new();
+    layout_settings.insert(DatabaseLayout::Board, BoardLayoutSetting::new().into());
+    DatabaseViewTemplate {
+      name: name.to_string(),
+      layout: DatabaseLayout::Board,
+      layout_settings,
+      filters: vec![],
+      group_settings: vec![GroupSettingMap::from(group_setting)],
+      sorts: vec![],
+    }
+  }
+
+  fn into_templates(self) -> Vec<FieldTemplate> {
+    let list_type_option = SelectTypeOption {
+      options: self
+        .list_options
+        .into_iter()
+        .map(|(_, option)| option)
+        .collect(),
+      disable_color: false,
+    };
+    let labels_type_option = SelectTypeOption {
+      options: self
+        .label_options
+        .into_iter()
+        .map(|(_, option)| option)
+        .collect(),
+      disable_color: false,
+    };
+    let mut templates = vec![
+      field_template(
+        self.name_id,
+        NAME_FIELD,
+        FieldType::RichText,
+        true,
+        RichTextTypeOption.into(),
+      ),
+      field_template(
+        self.list_id,
+        LIST_FIELD,
+        FieldType::SingleSelect,
+        false,
+        list_type_option.into(),
+      ),
+      field_template(
+        self.labels_id,
+        LABELS_FIELD,
+        FieldType::MultiSelect,
+        false,
+        labels_type_option.into(),
+      ),
+      field_template(
+        self.due_id,
+        DUE_FIELD,
+        FieldType::DateTime,
+        false,
+        DateTypeOption::new().into(),
+      ),
+      field_template(
+        self.due_complete_id,
+        DUE_COMPLETE_FIELD,
+        FieldType::Checkbox,
+        false,
+        CheckboxTypeOption::new().into(),
+      ),
+      field_template(
+        self.checklist_id,
+        CHECKLIST_FIELD,
+        FieldType::Checklist,
+        false,
+        ChecklistTypeOption.into(),
+      ),
+    ];
+    if let Some((members_id, _)) = self.members {
+      templates.push(field_template(
+        members_id,
+        MEMBERS_FIELD,
+        FieldType::Person,
+        false,
+        PersonTypeOption::default().into(),
+      ));
+    }
+    templates
+  }
+}
+
+fn field_template(
+  field_id: String,
+  name: &str,
+  field_type: FieldType,
+  is_primary: bool,
+  type_option: HashMap<String, Any>,
+) -> FieldTemplate {
+  FieldTemplate {
+    field_id,
+    name: name.to_string(),
+    field_type,
+    is_primary,
+    type_options: HashMap::from([(field_type, type_option)]),
+  }
+}
+
+fn text_cell(field_type: FieldType, data: &str) -> Cell {
+  let mut cell = new_cell_builder(field_type);
+  cell.insert(CELL_DATA.into(), data.into());
+  cell
+}
+
+/// The label is named after its color when it has no name.
+fn label_option(label: &TrelloLabel, index: usize) -> SelectOption {
+  let color = label.color.as_deref().unwrap_or_default();
+  let name = if label.name.trim().is_empty() {
+    color.to_string()
+  } else {
+    label.name.clone()
+  };
+  SelectOption {
+    id: gen_option_id(),
+    name,
+    color: label_color(color).unwrap_or_else(|| SelectOptionColor::from(index % 8)),
+  }
+}
+
+/// Map the Trello colors, including their `_dark` and `_light` variants, to the closest option
+/// color.
+fn label_color(color: &str) -> Option<SelectOptionColor> {
+  let base = color.split('_').next().unwrap_or_default();
+  match base {
+    "purple" => Some(SelectOptionColor::Purple),
+    "red" => Some(SelectOptionColor::Pink),
+    "pink" => Some(SelectOptionColor::LightPink),
+    "orange" => Some(SelectOptionColor::Orange),
+    "yellow" => Some(SelectOptionColor::Yellow),
+    "lime" => Some(SelectOptionColor::Lime),
+    "green" => Some(SelectOptionColor::Green),
+    "sky" => Some(SelectOptionColor::Aqua),
+    "blue" => Some(SelectOptionColor::Blue),
+    _ => None,
+  }
+}
+
+/// The markdown of the row document: the description, followed by the checklists as todo lists
+/// when the card has more than one.
+fn card_markdown(card: &TrelloCard, checklists: &[&TrelloChecklist]) -> String {
+  let mut markdown = card.desc.trim().to_string();
+  if checklists.len() > 1 {
+    for checklist in checklists {
+      markdown.push_str(&format!("\n\n## {}\n", checklist.name));
+      for item in checklist.items() {
+        let mark = if item.is_complete() { "x" } else { " " };
+        markdown.push_str(&format!("\n- [{}] {}", mark, item.name));
+      }
+    }
+  }
+  markdown
+}
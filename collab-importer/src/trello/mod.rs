@@ -0,0 +1,4 @@
+mod importer;
+pub mod parser;
+
+pub use importer::*;
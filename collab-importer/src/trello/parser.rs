@@ -0,0 +1,156 @@
+use crate::error::ImporterError;
+use chrono::DateTime;
+use serde::Deserialize;
+
+/// A board of a Trello JSON export, as downloaded from `Menu > Print, export and share > Export
+/// as JSON`. Only the parts used by the importer are kept.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TrelloBoard {
+  pub name: String,
+  pub desc: String,
+  pub lists: Vec<TrelloList>,
+  pub cards: Vec<TrelloCard>,
+  pub labels: Vec<TrelloLabel>,
+  pub checklists: Vec<TrelloChecklist>,
+  pub members: Vec<TrelloMember>,
+}
+
+impl TrelloBoard {
+  /// The lists that are not archived, in the order of the board.
+  pub fn open_lists(&self) -> Vec<&TrelloList> {
+    let mut lists = self
+      .lists
+      .iter()
+      .filter(|list| !list.closed)
+      .collect::<Vec<_>>();
+    lists.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+    lists
+  }
+
+  /// The cards of the open lists that are not archived, ordered by list and then by position in
+  /// the list.
+  pub fn open_cards(&self) -> Vec<&TrelloCard> {
+    let lists = self.open_lists();
+    let mut cards = self
+      .cards
+      .iter()
+      .filter(|card| !card.closed)
+      .filter_map(|card| {
+        lists
+          .iter()
+          .position(|list| list.id == card.id_list)
+          .map(|index| (index, card))
+      })
+      .collect::<Vec<_>>();
+    cards.sort_by(|(a_index, a), (b_index, b)| a_index.cmp(b_index).then(a.pos.total_cmp(&b.pos)));
+    cards.into_iter().map(|(_, card)| card).collect()
+  }
+
+  /// The checklists of the card, in the order of the card.
+  pub fn card_checklists(&self, card_id: &str) -> Vec<&TrelloChecklist> {
+    let mut checklists = self
+      .checklists
+      .iter()
+      .filter(|checklist| checklist.id_card == card_id)
+      .collect::<Vec<_>>();
+    checklists.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+    checklists
+  }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TrelloList {
+  pub id: String,
+  pub name: String,
+  pub closed: bool,
+  pub pos: f64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TrelloCard {
+  pub id: String,
+  pub name: String,
+  /// The description of the card, in markdown.
+  pub desc: String,
+  pub closed: bool,
+  pub id_list: String,
+  pub id_labels: Vec<String>,
+  /// The members assigned to the card.
+  pub id_members: Vec<String>,
+  /// The due date, in RFC 3339, e.g. `2024-05-01T10:00:00.000Z`.
+  pub due: Option<String>,
+  pub due_complete: bool,
+  pub pos: f64,
+  pub url: String,
+}
+
+impl TrelloCard {
+  /// The due date, in seconds.
+  pub fn due_timestamp(&self) -> Option<i64> {
+    let due = self.due.as_deref()?;
+    DateTime::parse_from_rfc3339(due)
+      .ok()
+      .map(|date| date.timestamp())
+  }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TrelloLabel {
+  pub id: String,
+  pub name: String,
+  /// The color name, e.g. `green`, or none for the labels without color.
+  pub color: Option<String>,
+}
+
+/// A member of the board.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TrelloMember {
+  pub id: String,
+  pub full_name: String,
+  pub username: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TrelloChecklist {
+  pub id: String,
+  pub name: String,
+  pub id_card: String,
+  pub pos: f64,
+  pub check_items: Vec<TrelloCheckItem>,
+}
+
+impl TrelloChecklist {
+  /// The items of the checklist, in the order of the checklist.
+  pub fn items(&self) -> Vec<&TrelloCheckItem> {
+    let mut items = self.check_items.iter().collect::<Vec<_>>();
+    items.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+    items
+  }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TrelloCheckItem {
+  pub id: String,
+  pub name: String,
+  /// `complete` or `incomplete`.
+  pub state: String,
+  pub pos: f64,
+}
+
+impl TrelloCheckItem {
+  pub fn is_complete(&self) -> bool {
+    self.state == "complete"
+  }
+}
+
+/// Parse a Trello JSON export.
+pub fn parse_trello(json: &str) -> Result<TrelloBoard, ImporterError> {
+  serde_json::from_str(json).map_err(|err| ImporterError::ParseTrelloError(err.to_string()))
+}
@@ -0,0 +1,181 @@
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+/// Elements written as `<br/>` instead of an opening and a closing tag.
+const VOID_ELEMENTS: [&str; 4] = ["br", "hr", "img", "input"];
+
+/// A node of an XHTML document of another app, e.g. the ENML of Evernote or the storage format
+/// of Confluence, converted to html before being imported.
+///
+/// Element and attribute names are kept with their namespace prefix, e.g. `ac:image`. The text
+/// and the attribute values are kept escaped, they are written back to html as is, so the html
+/// entities that the xml parser doesn't know, e.g. `&nbsp;`, are decoded by the html importer.
+#[derive(Debug)]
+pub(crate) enum XhtmlNode {
+  Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<XhtmlNode>,
+  },
+  Text(String),
+}
+
+impl XhtmlNode {
+  pub fn name(&self) -> Option<&str> {
+    match self {
+      XhtmlNode::Element { name, .. } => Some(name),
+      XhtmlNode::Text(_) => None,
+    }
+  }
+
+  pub fn attr(&self, key: &str) -> Option<&str> {
+    match self {
+      XhtmlNode::Element { attributes, .. } => attributes
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.as_str()),
+      XhtmlNode::Text(_) => None,
+    }
+  }
+
+  pub fn children(&self) -> &[XhtmlNode] {
+    match self {
+      XhtmlNode::Element { children, .. } => children,
+      XhtmlNode::Text(_) => &[],
+    }
+  }
+
+  pub fn child(&self, name: &str) -> Option<&XhtmlNode> {
+    self
+      .children()
+      .iter()
+      .find(|child| child.name() == Some(name))
+  }
+
+  pub fn is_blank(&self) -> bool {
+    matches!(self, XhtmlNode::Text(text) if text.trim().is_empty())
+  }
+
+  /// The escaped text of the node and of its descendants.
+  pub fn text_content(&self) -> String {
+    match self {
+      XhtmlNode::Text(text) => text.clone(),
+      XhtmlNode::Element { children, .. } => children.iter().map(|c| c.text_content()).collect(),
+    }
+  }
+}
+
+/// Parse the XHTML document. The xml declaration, the doctype and the comments are dropped.
+pub(crate) fn parse_xhtml(xhtml: &str) -> Result<Vec<XhtmlNode>, String> {
+  let mut reader = Reader::from_str(xhtml);
+  let mut stack: Vec<(String, Vec<(String, String)>, Vec<XhtmlNode>)> =
+    vec![(String::new(), vec![], vec![])];
+  loop {
+    let event = reader.read_event().map_err(|err| err.to_string())?;
+    match event {
+      Event::Start(start) => {
+        let (name, attributes) = name_and_attributes(&start);
+        stack.push((name, attributes, vec![]));
+      },
+      Event::Empty(start) => {
+        let (name, attributes) = name_and_attributes(&start);
+        if let Some((_, _, children)) = stack.last_mut() {
+          children.push(XhtmlNode::Element {
+            name,
+            attributes,
+            children: vec![],
+          });
+        }
+      },
+      Event::End(_) => {
+        let (name, attributes, children) = stack
+          .pop()
+          .filter(|_| !stack.is_empty())
+          .ok_or_else(|| "unexpected closing tag".to_string())?;
+        if let Some((_, _, parent_children)) = stack.last_mut() {
+          parent_children.push(XhtmlNode::Element {
+            name,
+            attributes,
+            children,
+          });
+        }
+      },
+      Event::Text(text) => {
+        if let Some((_, _, children)) = stack.last_mut() {
+          children.push(XhtmlNode::Text(String::from_utf8_lossy(&text).to_string()));
+        }
+      },
+      Event::CData(data) => {
+        if let Some((_, _, children)) = stack.last_mut() {
+          let text = String::from_utf8_lossy(&data.into_inner()).to_string();
+          children.push(XhtmlNode::Text(escape_html(&text)));
+        }
+      },
+      Event::Eof => break,
+      _ => {},
+    }
+  }
+  match stack.pop() {
+    Some((_, _, nodes)) if stack.is_empty() => Ok(nodes),
+    _ => Err("unclosed xml element".to_string()),
+  }
+}
+
+/// Write the element as html with its attributes, writing its children with `write_children`.
+pub(crate) fn write_element<F: FnOnce(&mut String)>(
+  node: &XhtmlNode,
+  html: &mut String,
+  write_children: F,
+) {
+  let XhtmlNode::Element {
+    name,
+    attributes,
+    children,
+  } = node
+  else {
+    return;
+  };
+  html.push('<');
+  html.push_str(name);
+  for (key, value) in attributes {
+    html.push_str(&format!(" {}=\"{}\"", key, value));
+  }
+  if children.is_empty() && VOID_ELEMENTS.contains(&name.as_str()) {
+    html.push_str("/>");
+    return;
+  }
+  html.push('>');
+  write_children(html);
+  html.push_str(&format!("</{}>", name));
+}
+
+pub(crate) fn write_checkbox(checked: bool, html: &mut String) {
+  if checked {
+    html.push_str("<input type=\"checkbox\" checked/>");
+  } else {
+    html.push_str("<input type=\"checkbox\"/>");
+  }
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+fn name_and_attributes(start: &BytesStart) -> (String, Vec<(String, String)>) {
+  let name = String::from_utf8_lossy(start.name().as_ref()).to_string();
+  let attributes = start
+    .attributes()
+    .flatten()
+    .map(|attr| {
+      (
+        String::from_utf8_lossy(attr.key.as_ref()).to_string(),
+        String::from_utf8_lossy(&attr.value).to_string(),
+      )
+    })
+    .collect();
+  (name, attributes)
+}
@@ -94,7 +94,7 @@ where
                   entry_reader.entry(),
                   err,
                 );
-                return Err(ImporterError::Internal(anyhow!(
+                return Err(ImporterError::InvalidArchive(anyhow!(
                   "Unexpected EOF while reading: {}",
                   filename
                 )));
@@ -153,7 +153,7 @@ where
 }
 
 #[async_recursion]
-async fn move_all(old_path: &Path, new_path: &Path) -> io::Result<()> {
+pub(crate) async fn move_all(old_path: &Path, new_path: &Path) -> io::Result<()> {
   if !new_path.exists() {
     fs::create_dir_all(new_path).await?;
   }
@@ -205,14 +205,14 @@ pub async fn unzip_single_file(
   let archive = BufReader::new(archive).compat();
   let mut reader = SeekZipFileReader::new(archive)
     .await
-    .map_err(|err| ImporterError::Internal(err.into()))?;
+    .map_err(|err| ImporterError::InvalidArchive(err.into()))?;
 
   for index in 0..reader.file().entries().len() {
     let entry = reader.file().entries().get(index).unwrap();
     let file_name = entry
       .filename()
       .as_str()
-      .map_err(|err| ImporterError::Internal(err.into()))?;
+      .map_err(|err| ImporterError::InvalidArchive(err.into()))?;
     if root_dir.is_none() && file_name.ends_with('/') {
       root_dir = Some(file_name.split('/').next().unwrap_or(file_name).to_string());
     }
@@ -224,11 +224,11 @@ pub async fn unzip_single_file(
     // https://github.com/python/cpython/blob/820ef62833bd2d84a141adedd9a05998595d6b6d/Lib/zipfile.py#L528
     let entry_is_dir = entry
       .dir()
-      .map_err(|err| ImporterError::Internal(err.into()))?;
+      .map_err(|err| ImporterError::InvalidArchive(err.into()))?;
     let mut entry_reader = reader
       .reader_without_entry(index)
       .await
-      .map_err(|err| ImporterError::Internal(err.into()))?;
+      .map_err(|err| ImporterError::InvalidArchive(err.into()))?;
 
     if entry_is_dir {
       if !path.exists() {
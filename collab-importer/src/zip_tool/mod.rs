@@ -1,3 +1,5 @@
 pub mod async_zip;
+pub mod multi_part;
+pub mod safe_unzip;
 pub mod sync_zip;
 pub mod util;
@@ -0,0 +1,149 @@
+use crate::error::ImporterError;
+use crate::zip_tool::async_zip::move_all;
+use crate::zip_tool::safe_unzip::{UnzipLimits, safe_unzip_file};
+use crate::zip_tool::util::remove_part_suffix;
+use fancy_regex::Regex;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// The directory the parts are extracted into before being merged, inside the output directory.
+const PARTS_DIR: &str = ".parts";
+
+/// The content of an export split in several zip files, see [unzip_multi_part].
+#[derive(Debug, Clone)]
+pub struct MultiPartUnzip {
+  /// The name of the export, the name of the shared root folder or of the first part without
+  /// its part suffix.
+  pub dir_name: String,
+  /// The directory holding the merged content of the parts.
+  pub unzip_dir: PathBuf,
+  /// The parts, in the order they were extracted.
+  pub parts: Vec<PathBuf>,
+}
+
+/// The number of the part in the name of the file, e.g. 2 for `Export-Part-2.zip`,
+/// `export.part2.zip`, `export_2.zip` or `export(2).zip`.
+pub fn part_number(file_name: &str) -> Option<u32> {
+  let stem = Path::new(file_name).file_stem()?.to_str()?;
+  let re = Regex::new(r"(?i)(?:-part-|\.part|_|\()(\d+)\)?$").unwrap();
+  let captures = re.captures(stem).ok()??;
+  captures.get(1)?.as_str().parse().ok()
+}
+
+/// The zip files of the directory, ordered by their [part_number].
+pub async fn zip_parts_in_dir(dir: &Path) -> Result<Vec<PathBuf>, ImporterError> {
+  let mut parts = vec![];
+  let mut read_dir = fs::read_dir(dir).await?;
+  while let Some(entry) = read_dir.next_entry().await? {
+    let path = entry.path();
+    let is_zip = path
+      .extension()
+      .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+    if is_zip && path.is_file() {
+      parts.push(path);
+    }
+  }
+  sort_parts(&mut parts);
+  Ok(parts)
+}
+
+/// Extract the parts of an export into `out_dir` and merge their content.
+///
+/// Each path is a zip part or a directory of zip parts. The parts are extracted in the order of
+/// their [part_number]. When every part holds a single root folder with the same name, e.g.
+/// `Export-abc/`, the content of these folders is merged into `out_dir/Export-abc`. Otherwise,
+/// the content of the parts is merged into a folder named after the first part, without its part
+/// suffix. A file found in several parts is kept from the last one.
+///
+/// The limits apply to all the parts together.
+pub async fn unzip_multi_part(
+  paths: &[PathBuf],
+  out_dir: &Path,
+  limits: &UnzipLimits,
+) -> Result<MultiPartUnzip, ImporterError> {
+  let mut parts = vec![];
+  for path in paths {
+    if path.is_dir() {
+      parts.extend(zip_parts_in_dir(path).await?);
+    } else if path.exists() {
+      parts.push(path.clone());
+    } else {
+      return Err(ImporterError::FileNotFound);
+    }
+  }
+  sort_parts(&mut parts);
+  parts.dedup();
+  let first_part_name = parts
+    .first()
+    .and_then(|part| part.file_name())
+    .and_then(|name| name.to_str())
+    .map(remove_part_suffix)
+    .ok_or(ImporterError::FileNotFound)?;
+
+  let parts_dir = out_dir.join(PARTS_DIR);
+  let mut remaining = *limits;
+  let mut part_roots = vec![];
+  for (index, part) in parts.iter().enumerate() {
+    let part_dir = parts_dir.join(index.to_string());
+    let output = safe_unzip_file(part, &part_dir, &remaining).await?;
+    remaining.max_uncompressed_size -= output.uncompressed_size;
+    remaining.max_file_count = remaining.max_file_count.saturating_sub(output.files.len());
+    let root = single_root_dir(&part_dir).await?;
+    part_roots.push((part_dir, root));
+  }
+
+  // The parts of a Notion export share their root folder, which must only be created once.
+  let shared_root = match part_roots.first() {
+    Some((_, Some(first_root))) => {
+      let name = remove_part_suffix(first_root);
+      part_roots
+        .iter()
+        .all(|(_, root)| root.as_deref().map(remove_part_suffix).as_ref() == Some(&name))
+        .then(|| first_root.clone())
+    },
+    _ => None,
+  };
+  let dir_name = shared_root.clone().unwrap_or(first_part_name);
+  let unzip_dir = out_dir.join(&dir_name);
+  for (part_dir, root) in part_roots {
+    let content_dir = match (&shared_root, root) {
+      (Some(_), Some(root)) => part_dir.join(root),
+      _ => part_dir,
+    };
+    move_all(&content_dir, &unzip_dir).await?;
+  }
+  let _ = fs::remove_dir_all(&parts_dir).await;
+
+  Ok(MultiPartUnzip {
+    dir_name,
+    unzip_dir,
+    parts,
+  })
+}
+
+fn sort_parts(parts: &mut [PathBuf]) {
+  parts.sort_by_cached_key(|part| {
+    let file_name = part
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_default();
+    (part_number(&file_name).unwrap_or(0), file_name)
+  });
+}
+
+/// The name of the only entry of the directory when it's a directory, the hidden entries and
+/// the `__MACOSX` folder excluded.
+async fn single_root_dir(dir: &Path) -> Result<Option<String>, ImporterError> {
+  let mut entries = vec![];
+  let mut read_dir = fs::read_dir(dir).await?;
+  while let Some(entry) = read_dir.next_entry().await? {
+    let name = entry.file_name().to_string_lossy().to_string();
+    if !name.starts_with('.') && name != "__MACOSX" {
+      entries.push((name, entry.path()));
+    }
+  }
+  match entries.as_slice() {
+    [(name, path)] if path.is_dir() => Ok(Some(name.clone())),
+    _ => Ok(None),
+  }
+}
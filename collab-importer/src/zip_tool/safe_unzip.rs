@@ -0,0 +1,171 @@
+use crate::error::{ImporterError, UnsafeArchiveError};
+use crate::zip_tool::async_zip::get_filename_from_zip_string;
+use async_zip::base::read::stream::ZipFileReader;
+use futures::AsyncReadExt;
+use futures::io::AsyncBufRead;
+use std::path::{Component, Path, PathBuf};
+use tokio::fs;
+use tokio::io::{AsyncWriteExt, BufReader};
+use tokio_util::compat::TokioAsyncReadCompatExt;
+
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// The limits enforced by [safe_unzip] while extracting an untrusted archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnzipLimits {
+  /// The maximum size of all the extracted files, in bytes.
+  pub max_uncompressed_size: u64,
+  /// The maximum number of entries, the directories included.
+  pub max_file_count: usize,
+  /// The maximum ratio between the extracted and the compressed size of a file. A zip bomb
+  /// compresses gigabytes of zeros into a few kilobytes.
+  pub max_compression_ratio: u64,
+}
+
+impl Default for UnzipLimits {
+  fn default() -> Self {
+    Self {
+      max_uncompressed_size: 10 * 1024 * 1024 * 1024,
+      max_file_count: 100_000,
+      max_compression_ratio: 100,
+    }
+  }
+}
+
+/// The files extracted by [safe_unzip].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SafeUnzipOutput {
+  /// The extracted files, in the order of the archive.
+  pub files: Vec<PathBuf>,
+  /// The size of the extracted files, in bytes.
+  pub uncompressed_size: u64,
+}
+
+/// Extract the archive of the file into `out_dir`, see [safe_unzip].
+pub async fn safe_unzip_file<P: AsRef<Path>>(
+  zip_path: P,
+  out_dir: &Path,
+  limits: &UnzipLimits,
+) -> Result<SafeUnzipOutput, ImporterError> {
+  let zip_path = zip_path.as_ref();
+  if !zip_path.exists() {
+    return Err(ImporterError::FileNotFound);
+  }
+  let file = fs::File::open(zip_path).await?;
+  safe_unzip(BufReader::new(file).compat(), out_dir, limits).await
+}
+
+/// Extract an untrusted archive into `out_dir` while it's read, without buffering its entries in
+/// memory.
+///
+/// The entry paths are sanitized: the `..` and the root components are dropped, so every file
+/// is written inside `out_dir`. The extraction stops with an
+/// [ImporterError::UnsafeArchive] as soon as a [UnzipLimits] is exceeded, the files extracted
+/// until then are left in `out_dir`.
+pub async fn safe_unzip<R>(
+  reader: R,
+  out_dir: &Path,
+  limits: &UnzipLimits,
+) -> Result<SafeUnzipOutput, ImporterError>
+where
+  R: AsyncBufRead + Unpin,
+{
+  fs::create_dir_all(out_dir).await?;
+  let mut output = SafeUnzipOutput::default();
+  let mut num_of_entries = 0;
+  let mut buffer = vec![0; BUFFER_SIZE];
+  let mut zip_reader = ZipFileReader::new(reader);
+  while let Some(mut next_reader) = zip_reader
+    .next_with_entry()
+    .await
+    .map_err(|err| ImporterError::InvalidArchive(err.into()))?
+  {
+    num_of_entries += 1;
+    if num_of_entries > limits.max_file_count {
+      return Err(
+        UnsafeArchiveError::TooManyFiles {
+          limit: limits.max_file_count,
+        }
+        .into(),
+      );
+    }
+
+    let entry_reader = next_reader.reader_mut();
+    let file_name = get_filename_from_zip_string(entry_reader.entry().filename())
+      .map_err(ImporterError::InvalidArchive)?;
+    let is_dir = file_name.ends_with('/') || entry_reader.entry().dir().unwrap_or(false);
+    let compressed_size = entry_reader.entry().compressed_size();
+    match sanitize_entry_path(&file_name) {
+      Some(relative_path) if is_dir => {
+        fs::create_dir_all(out_dir.join(relative_path)).await?;
+      },
+      Some(relative_path) => {
+        let path = out_dir.join(relative_path);
+        if let Some(parent) = path.parent() {
+          fs::create_dir_all(parent).await?;
+        }
+        let mut file = fs::File::create(&path).await?;
+        let mut written = 0u64;
+        loop {
+          let len = entry_reader
+            .read(&mut buffer)
+            .await
+            .map_err(|err| ImporterError::InvalidArchive(err.into()))?;
+          if len == 0 {
+            break;
+          }
+          written += len as u64;
+          output.uncompressed_size += len as u64;
+          if output.uncompressed_size > limits.max_uncompressed_size {
+            return Err(
+              UnsafeArchiveError::TooLarge {
+                limit: limits.max_uncompressed_size,
+              }
+              .into(),
+            );
+          }
+          // The compressed size is unknown when it's written after the data of the entry.
+          if compressed_size > 0
+            && written > compressed_size.saturating_mul(limits.max_compression_ratio)
+          {
+            return Err(
+              UnsafeArchiveError::CompressionRatioTooHigh {
+                file: file_name,
+                limit: limits.max_compression_ratio,
+              }
+              .into(),
+            );
+          }
+          file.write_all(&buffer[..len]).await?;
+        }
+        file.flush().await?;
+        output.files.push(path);
+      },
+      None => {},
+    }
+
+    zip_reader = next_reader
+      .done()
+      .await
+      .map_err(|err| ImporterError::InvalidArchive(err.into()))?;
+  }
+  Ok(output)
+}
+
+/// The path of an entry relative to the output directory. The components that could escape it,
+/// e.g. `..` or `/`, are dropped and the others are sanitized. Return `None` when nothing is
+/// left, e.g. for `../`.
+pub fn sanitize_entry_path(file_name: &str) -> Option<PathBuf> {
+  let file_name = file_name.replace('\\', "/");
+  let path = Path::new(&file_name)
+    .components()
+    .filter_map(|component| match component {
+      Component::Normal(name) => {
+        let name = sanitize_filename::sanitize(name.to_string_lossy());
+        (!name.is_empty()).then_some(name)
+      },
+      Component::Prefix(_) | Component::RootDir | Component::CurDir | Component::ParentDir => None,
+    })
+    .collect::<PathBuf>();
+  path.components().next().is_some().then_some(path)
+}
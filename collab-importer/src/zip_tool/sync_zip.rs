@@ -1,6 +1,5 @@
-use crate::error::ImporterError;
+use crate::error::{ImporterError, ImporterResultExt};
 use crate::zip_tool::util::{is_multi_part_zip_signature, remove_part_suffix, sanitize_file_path};
-use anyhow::{Result, anyhow};
 
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Write};
@@ -20,11 +19,10 @@ pub fn sync_unzip(
   mut out_dir: PathBuf,
   default_file_name: Option<String>,
 ) -> Result<UnzipFile, ImporterError> {
-  let file = File::open(file_path)
-    .map_err(|e| ImporterError::Internal(anyhow!("Failed to open zip file: {:?}", e)))?;
+  let file =
+    File::open(&file_path).with_context(|| format!("Failed to open zip file: {:?}", file_path))?;
 
-  let mut archive = ZipArchive::new(file)
-    .map_err(|e| ImporterError::Internal(anyhow!("Failed to read zip archive: {:?}", e)))?;
+  let mut archive = ZipArchive::new(file).map_err(|e| ImporterError::InvalidArchive(e.into()))?;
 
   let mut root_dir = None;
   let mut parts = vec![];
@@ -41,8 +39,7 @@ pub fn sync_unzip(
     if let Some(default_name) = &default_file_name {
       out_dir = out_dir.join(default_name);
       if !out_dir.exists() {
-        fs::create_dir_all(&out_dir)
-          .map_err(|e| ImporterError::Internal(anyhow!("Failed to create dir: {:?}", e)))?;
+        fs::create_dir_all(&out_dir).context("Failed to create dir")?;
       }
     }
   }
@@ -51,7 +48,7 @@ pub fn sync_unzip(
   for i in 0..archive.len() {
     let mut entry = archive
       .by_index(i)
-      .map_err(|e| ImporterError::Internal(anyhow!("Failed to read entry: {:?}", e)))?;
+      .map_err(|e| ImporterError::InvalidArchive(e.into()))?;
 
     let filename = entry.name().to_string();
 
@@ -66,12 +63,10 @@ pub fn sync_unzip(
 
     let output_path = out_dir.join(sanitize_file_path(&filename));
     if entry.is_dir() {
-      fs::create_dir_all(&output_path)
-        .map_err(|e| ImporterError::Internal(anyhow!("Failed to create dir: {:?}", e)))?;
+      fs::create_dir_all(&output_path).context("Failed to create dir")?;
     } else {
       if let Some(parent) = output_path.parent() {
-        fs::create_dir_all(parent)
-          .map_err(|e| ImporterError::Internal(anyhow!("Failed to create parent dir: {:?}", e)))?;
+        fs::create_dir_all(parent).context("Failed to create parent dir")?;
       }
 
       // Create and write the file
@@ -79,18 +74,13 @@ pub fn sync_unzip(
         .write(true)
         .create_new(true)
         .open(&output_path)
-        .map_err(|e| {
-          ImporterError::Internal(anyhow!(
-            "Failed to create or open file with path: {:?}, error: {:?}",
-            output_path,
-            e
-          ))
-        }) {
+        .with_context(|| format!("Failed to create or open file with path: {:?}", output_path))
+      {
         Ok(mut outfile) => {
           let mut buffer = vec![];
-          entry.read_to_end(&mut buffer).map_err(|e| {
-            ImporterError::Internal(anyhow!("Failed to read entry content: {:?}", e))
-          })?;
+          entry
+            .read_to_end(&mut buffer)
+            .context("Failed to read entry content")?;
 
           // Check if it's a multipart zip file.
           // Only attempt this for entries at the archive root. This avoids
@@ -106,9 +96,7 @@ pub fn sync_unzip(
             }
           }
 
-          outfile
-            .write_all(&buffer)
-            .map_err(|e| ImporterError::Internal(anyhow!("Failed to write file: {:?}", e)))?;
+          outfile.write_all(&buffer).context("Failed to write file")?;
         },
         Err(err) => {
           warn!("{}", err);
@@ -150,14 +138,14 @@ fn unzip_single_file(
   out_dir: &Path,
   mut root_dir: Option<String>,
 ) -> Result<UnzipFile, ImporterError> {
-  let mut archive = ZipArchive::new(archive_file)
-    .map_err(|e| ImporterError::Internal(anyhow!("Failed to read zip archive: {:?}", e)))?;
+  let mut archive =
+    ZipArchive::new(archive_file).map_err(|e| ImporterError::InvalidArchive(e.into()))?;
 
   // Iterate through each file in the archive
   for i in 0..archive.len() {
     let mut entry = archive
       .by_index(i)
-      .map_err(|e| ImporterError::Internal(anyhow!("Failed to read entry: {:?}", e)))?;
+      .map_err(|e| ImporterError::InvalidArchive(e.into()))?;
 
     let entry_name = entry.name();
     if entry_name == ".DS_Store"
@@ -184,16 +172,13 @@ fn unzip_single_file(
     // Create directories if needed
     if entry.is_dir() {
       if !path.exists() {
-        fs::create_dir_all(&path)
-          .map_err(|e| ImporterError::Internal(anyhow!("Failed to create directory: {:?}", e)))?;
+        fs::create_dir_all(&path).context("Failed to create directory")?;
       }
     } else {
       // Ensure parent directories exist
       if let Some(parent) = path.parent() {
         if !parent.exists() {
-          fs::create_dir_all(parent).map_err(|e| {
-            ImporterError::Internal(anyhow!("Failed to create parent directory: {:?}", e))
-          })?;
+          fs::create_dir_all(parent).context("Failed to create parent directory")?;
         }
       }
 
@@ -202,16 +187,9 @@ fn unzip_single_file(
         .write(true)
         .create_new(true)
         .open(&path)
-        .map_err(|e| {
-          ImporterError::Internal(anyhow!(
-            "Failed to create part file: {:?}, path:{:?}",
-            e,
-            path
-          ))
-        })?;
-
-      io::copy(&mut entry, &mut outfile)
-        .map_err(|e| ImporterError::Internal(anyhow!("Failed to write file: {:?}", e)))?;
+        .with_context(|| format!("Failed to create part file: {:?}", path))?;
+
+      io::copy(&mut entry, &mut outfile).context("Failed to write file")?;
     }
   }
 
@@ -0,0 +1,116 @@
+use collab::core::collab::{CollabOptions, default_client_id};
+use collab::core::origin::CollabOrigin;
+use collab::preclude::Collab;
+use collab_entity::reference::ReferenceAuthor;
+use collab_folder::ReferenceStore;
+use collab_importer::bibtex::{BibtexImportSummary, import_bibtex, parse_bibtex};
+use collab_importer::error::ImporterError;
+
+const BIBTEX: &str = r#"
+% exported by Zotero
+@comment{jabref-meta: databaseType:bibtex;}
+
+@article{smith2020,
+  author = {Smith, John and Bob Lee},
+  title = {{Deep} Learning for
+           Everyone},
+  journal = "Nature",
+  year = 2020,
+  volume = {5},
+  number = {2},
+  pages = {10--20},
+  doi = {10.1/abc},
+}
+
+@Book(who2019,
+  author = {{World Health Organization}},
+  title = "Global " # "Report",
+  publisher = {WHO Press},
+  year = {2019}
+)
+"#;
+
+#[test]
+fn parse_bibtex_entries_test() {
+  let references = parse_bibtex(BIBTEX).unwrap();
+  assert_eq!(references.len(), 2);
+
+  let article = &references[0];
+  assert_eq!(article.key, "smith2020");
+  assert_eq!(article.kind, "article");
+  assert_eq!(article.title, "Deep Learning for Everyone");
+  assert_eq!(
+    article.authors,
+    vec![
+      ReferenceAuthor {
+        family: "Smith".to_string(),
+        given: Some("John".to_string()),
+      },
+      ReferenceAuthor {
+        family: "Lee".to_string(),
+        given: Some("Bob".to_string()),
+      },
+    ]
+  );
+  assert_eq!(article.container_title.as_deref(), Some("Nature"));
+  assert_eq!(article.year.as_deref(), Some("2020"));
+  assert_eq!(article.issue.as_deref(), Some("2"));
+  assert_eq!(article.pages.as_deref(), Some("10–20"));
+  assert_eq!(article.doi.as_deref(), Some("10.1/abc"));
+
+  let book = &references[1];
+  assert_eq!(book.key, "who2019");
+  assert_eq!(book.kind, "book");
+  assert_eq!(book.title, "Global Report");
+  assert_eq!(
+    book.authors,
+    vec![ReferenceAuthor {
+      family: "World Health Organization".to_string(),
+      given: None,
+    }]
+  );
+  assert_eq!(book.publisher.as_deref(), Some("WHO Press"));
+}
+
+#[test]
+fn parse_invalid_bibtex_test() {
+  let err = parse_bibtex("@article{smith2020,\n  title = {Unterminated").unwrap_err();
+  assert!(matches!(err, ImporterError::ParseBibtexError(_)));
+
+  let err = parse_bibtex("@article{,\n  title = {No key}}").unwrap_err();
+  assert!(matches!(err, ImporterError::ParseBibtexError(_)));
+}
+
+#[test]
+fn import_bibtex_into_reference_store_test() {
+  let options = CollabOptions::new("references".to_string(), default_client_id());
+  let collab = Collab::new_with_options(CollabOrigin::Empty, options).unwrap();
+  let mut store = ReferenceStore::open(collab);
+
+  let summary = import_bibtex(&mut store, BIBTEX).unwrap();
+  assert_eq!(
+    summary,
+    BibtexImportSummary {
+      added: 2,
+      updated: 0,
+    }
+  );
+
+  let summary = import_bibtex(
+    &mut store,
+    "@misc{who2019, title = {Global Report 2019}, year = 2019}",
+  )
+  .unwrap();
+  assert_eq!(
+    summary,
+    BibtexImportSummary {
+      added: 0,
+      updated: 1,
+    }
+  );
+  assert_eq!(
+    store.get_reference("who2019").unwrap().title,
+    "Global Report 2019"
+  );
+  assert_eq!(store.get_all_references().len(), 2);
+}
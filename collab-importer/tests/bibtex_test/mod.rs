@@ -0,0 +1 @@
+mod bibtex_import_test;
@@ -0,0 +1,202 @@
+use crate::util::document_data;
+use collab_document::blocks::BlockType;
+use collab_importer::confluence::ConfluenceImporter;
+use collab_importer::error::ImporterError;
+use serde_json::json;
+use std::path::Path;
+
+const PNG_BYTES: &[u8] = &[0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 1, 2, 3, 4];
+
+const HOME_BODY: &str = r#"<h1>Welcome</h1>
+<p>Read the <ac:link><ri:page ri:content-title="Guide" /></ac:link>&nbsp;first.</p>
+<ac:structured-macro ac:name="info" ac:schema-version="1"><ac:rich-text-body><p>Be kind</p></ac:rich-text-body></ac:structured-macro>
+<ac:structured-macro ac:name="code"><ac:parameter ac:name="language">Rust</ac:parameter><ac:plain-text-body><![CDATA[fn main() {}]]></ac:plain-text-body></ac:structured-macro>
+<ac:task-list><ac:task><ac:task-id>1</ac:task-id><ac:task-status>complete</ac:task-status><ac:task-body>Ship it</ac:task-body></ac:task><ac:task><ac:task-id>2</ac:task-id><ac:task-status>incomplete</ac:task-status><ac:task-body>Celebrate</ac:task-body></ac:task></ac:task-list>
+<p><ac:image><ri:attachment ri:filename="logo.png" /></ac:image></p>
+<ac:structured-macro ac:name="toc" />"#;
+
+fn entities() -> String {
+  format!(
+    r#"<?xml version="1.0" encoding="UTF-8"?>
+<hibernate-generic datetime="2024-03-10 12:00:00">
+  <object class="Space" package="com.atlassian.confluence.spaces">
+    <id name="id">10</id>
+    <property name="name"><![CDATA[Engineering]]></property>
+    <property name="key"><![CDATA[ENG]]></property>
+  </object>
+  <object class="Page" package="com.atlassian.confluence.pages">
+    <id name="id">100</id>
+    <property name="title"><![CDATA[Home]]></property>
+    <property name="space" class="Space" package="com.atlassian.confluence.spaces"><id name="id">10</id></property>
+    <property name="contentStatus"><![CDATA[current]]></property>
+    <collection name="bodyContents" class="java.util.Collection"><element class="BodyContent" package="com.atlassian.confluence.core"><id name="id">1000</id></element></collection>
+  </object>
+  <object class="Page" package="com.atlassian.confluence.pages">
+    <id name="id">102</id>
+    <property name="title"><![CDATA[Setup]]></property>
+    <property name="space" class="Space"><id name="id">10</id></property>
+    <property name="parent" class="Page"><id name="id">100</id></property>
+    <property name="position">1</property>
+  </object>
+  <object class="Page" package="com.atlassian.confluence.pages">
+    <id name="id">101</id>
+    <property name="title"><![CDATA[Guide]]></property>
+    <property name="space" class="Space"><id name="id">10</id></property>
+    <property name="parent" class="Page"><id name="id">100</id></property>
+    <property name="position">0</property>
+  </object>
+  <object class="Page" package="com.atlassian.confluence.pages">
+    <id name="id">103</id>
+    <property name="title"><![CDATA[Home]]></property>
+    <property name="originalVersion" class="Page"><id name="id">100</id></property>
+  </object>
+  <object class="Page" package="com.atlassian.confluence.pages">
+    <id name="id">104</id>
+    <property name="title"><![CDATA[Old]]></property>
+    <property name="space" class="Space"><id name="id">10</id></property>
+    <property name="contentStatus"><![CDATA[deleted]]></property>
+  </object>
+  <object class="BodyContent" package="com.atlassian.confluence.core">
+    <id name="id">1000</id>
+    <property name="body"><![CDATA[{body}]]></property>
+    <property name="content" class="Page"><id name="id">100</id></property>
+    <property name="bodyType">2</property>
+  </object>
+  <object class="Attachment" package="com.atlassian.confluence.pages">
+    <id name="id">200</id>
+    <property name="title"><![CDATA[logo.png]]></property>
+    <property name="version">2</property>
+    <property name="containerContent" class="Page"><id name="id">100</id></property>
+  </object>
+</hibernate-generic>"#,
+    // A CDATA section can't contain its end marker, the exports split it in two sections.
+    body = HOME_BODY.replace("]]>", "]]]]><![CDATA[>")
+  )
+}
+
+fn write_export(dir: &Path) {
+  std::fs::write(dir.join("entities.xml"), entities()).unwrap();
+  let attachment_dir = dir.join("attachments").join("100").join("200");
+  std::fs::create_dir_all(&attachment_dir).unwrap();
+  std::fs::write(attachment_dir.join("2"), PNG_BYTES).unwrap();
+}
+
+#[test]
+fn import_confluence_space_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let export_dir = dir.path().join("export");
+  std::fs::create_dir_all(&export_dir).unwrap();
+  write_export(&export_dir);
+  let output_dir = dir.path().join("output");
+
+  let importer =
+    ConfluenceImporter::new(1, "workspace_id", "http://test.appflowy.cloud".to_string());
+  let info = importer
+    .import_dir(export_dir.as_path(), output_dir.as_path())
+    .unwrap();
+
+  // the past versions and the deleted pages are skipped
+  assert_eq!(info.spaces.len(), 1);
+  assert_eq!(info.num_of_pages(), 3);
+  let space = &info.spaces[0];
+  assert_eq!(space.name, "Engineering");
+  assert_eq!(space.pages.len(), 1);
+  let home = &space.pages[0];
+  assert_eq!(home.title, "Home");
+  assert_eq!(home.content_id, "100");
+  let children = home
+    .children
+    .iter()
+    .map(|page| page.title.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(children, vec!["Guide", "Setup"]);
+
+  // spaces are folders of pages, the pages keep their hierarchy
+  let views = info.build_nested_views();
+  assert_eq!(views.views.len(), 1);
+  assert_eq!(views.views[0].view.id, space.view_id);
+  assert_eq!(views.views[0].view.parent_view_id, "workspace_id");
+  let home_view = &views.views[0].children[0];
+  assert_eq!(home_view.view.id, home.view_id);
+  assert_eq!(home_view.children.len(), 2);
+  assert_eq!(home_view.children[0].view.parent_view_id, home.view_id);
+
+  // content
+  let data = document_data(&home.collab_info);
+  let page = &data.blocks[&data.page_id];
+  let blocks = data.meta.children_map[&page.children]
+    .iter()
+    .map(|id| &data.blocks[id])
+    .collect::<Vec<_>>();
+  let types = blocks
+    .iter()
+    .map(|block| block.ty.clone())
+    .collect::<Vec<_>>();
+  assert_eq!(
+    types,
+    vec![
+      BlockType::Heading.to_string(),
+      BlockType::Paragraph.to_string(),
+      BlockType::Callout.to_string(),
+      BlockType::Code.to_string(),
+      BlockType::TodoList.to_string(),
+      BlockType::TodoList.to_string(),
+      BlockType::Image.to_string(),
+    ]
+  );
+  let text_map = data.meta.text_map.as_ref().unwrap();
+  let link_text = text_map[blocks[1].external_id.as_ref().unwrap()].clone();
+  assert!(link_text.contains("Read the Guide"));
+  assert_eq!(blocks[2].data["icon"], json!("💡"));
+  assert_eq!(blocks[3].data["language"], json!("rust"));
+  assert_eq!(blocks[4].data["checked"], json!(true));
+  assert_eq!(blocks[5].data["checked"], json!(false));
+
+  // attachments are uploaded and referenced by the blocks
+  let image_url = blocks[6].data["url"].as_str().unwrap();
+  assert!(image_url.starts_with(&format!(
+    "http://test.appflowy.cloud/api/file_storage/workspace_id/v1/blob/{}/",
+    home.view_id
+  )));
+  assert!(image_url.ends_with(".png"));
+  let resource = &home.collab_info.resources[0];
+  assert_eq!(resource.object_id, home.view_id);
+  assert_eq!(resource.files.len(), 1);
+  assert_eq!(std::fs::read(&resource.files[0]).unwrap(), PNG_BYTES);
+
+  // each space and page comes before its children
+  let names = info
+    .into_collab_infos()
+    .into_iter()
+    .map(|info| info.name)
+    .collect::<Vec<_>>();
+  assert_eq!(names, vec!["Engineering", "Home", "Guide", "Setup"]);
+}
+
+#[test]
+fn import_confluence_without_entities_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let importer =
+    ConfluenceImporter::new(1, "workspace_id", "http://test.appflowy.cloud".to_string());
+  let err = importer
+    .import_dir(dir.path(), dir.path().join("output").as_path())
+    .unwrap_err();
+  assert!(matches!(err, ImporterError::FileNotFound));
+}
+
+#[test]
+fn import_invalid_confluence_entities_test() {
+  let dir = tempfile::tempdir().unwrap();
+  std::fs::write(
+    dir.path().join("entities.xml"),
+    "<hibernate-generic><object class=\"Page\"></hibernate-generic>",
+  )
+  .unwrap();
+  let importer =
+    ConfluenceImporter::new(1, "workspace_id", "http://test.appflowy.cloud".to_string());
+  let err = importer
+    .import_dir(dir.path(), dir.path().join("output").as_path())
+    .unwrap_err();
+  assert!(matches!(err.root(), ImporterError::ParseConfluenceError(_)));
+  assert_eq!(err.code(), "importer.parse_confluence_failed");
+}
@@ -0,0 +1 @@
+mod confluence_import_test;
@@ -0,0 +1,236 @@
+use collab_document::blocks::{BlockType, DocumentData, SimpleTableData};
+use collab_importer::docx::DocxImporter;
+use collab_importer::imported_collab::ImportType;
+use serde_json::{Value, json};
+use std::io::Write;
+use std::path::Path;
+use zip::ZipWriter;
+use zip::write::FileOptions;
+
+const W_NS: &str = r#"xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main""#;
+
+const PNG_BYTES: &[u8] = &[0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 1, 2, 3, 4];
+
+fn document_xml() -> String {
+  format!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document {W_NS}>
+  <w:body>
+    <w:p><w:pPr><w:pStyle w:val="Heading1"/></w:pPr><w:r><w:t>Project plan</w:t></w:r></w:p>
+    <w:p>
+      <w:r><w:t xml:space="preserve">Plain </w:t></w:r>
+      <w:r><w:rPr><w:b/></w:rPr><w:t>bold</w:t></w:r>
+      <w:r><w:rPr><w:u w:val="single"/><w:i/></w:rPr><w:t>underlined</w:t></w:r>
+      <w:r><w:rPr><w:b w:val="0"/></w:rPr><w:t xml:space="preserve"> and </w:t></w:r>
+      <w:hyperlink r:id="rId2"><w:r><w:t>a link</w:t></w:r></w:hyperlink>
+    </w:p>
+    <w:p><w:pPr><w:numPr><w:ilvl w:val="0"/><w:numId w:val="1"/></w:numPr></w:pPr><w:r><w:t>First</w:t></w:r></w:p>
+    <w:p><w:pPr><w:numPr><w:ilvl w:val="1"/><w:numId w:val="1"/></w:numPr></w:pPr><w:r><w:t>Nested</w:t></w:r></w:p>
+    <w:p><w:pPr><w:numPr><w:ilvl w:val="0"/><w:numId w:val="2"/></w:numPr></w:pPr><w:r><w:t>Step one</w:t></w:r></w:p>
+    <w:p><w:pPr><w:pStyle w:val="Quote"/></w:pPr><w:r><w:t>Quoted</w:t></w:r></w:p>
+    <w:tbl>
+      <w:tblGrid><w:gridCol w:w="3000"/><w:gridCol w:w="1500"/></w:tblGrid>
+      <w:tr><w:trPr><w:tblHeader/></w:trPr>
+        <w:tc><w:p><w:r><w:t>Name</w:t></w:r></w:p></w:tc>
+        <w:tc><w:p><w:r><w:t>Owner</w:t></w:r></w:p></w:tc>
+      </w:tr>
+      <w:tr>
+        <w:tc><w:p><w:r><w:t>Design</w:t></w:r></w:p></w:tc>
+        <w:tc></w:tc>
+      </w:tr>
+    </w:tbl>
+    <w:p><w:r><w:drawing><a:graphic><a:graphicData><a:blip r:embed="rId3"/></a:graphicData></a:graphic></w:drawing></w:r></w:p>
+  </w:body>
+</w:document>"#
+  )
+}
+
+const RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://appflowy.io" TargetMode="External"/>
+  <Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="media/image1.png"/>
+</Relationships>"#;
+
+fn styles_xml() -> String {
+  format!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:styles {W_NS}>
+  <w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/></w:style>
+  <w:style w:type="paragraph" w:styleId="Quote"><w:name w:val="Quote"/></w:style>
+</w:styles>"#
+  )
+}
+
+fn numbering_xml() -> String {
+  format!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:numbering {W_NS}>
+  <w:abstractNum w:abstractNumId="0">
+    <w:lvl w:ilvl="0"><w:numFmt w:val="bullet"/></w:lvl>
+    <w:lvl w:ilvl="1"><w:numFmt w:val="bullet"/></w:lvl>
+  </w:abstractNum>
+  <w:abstractNum w:abstractNumId="1">
+    <w:lvl w:ilvl="0"><w:numFmt w:val="decimal"/></w:lvl>
+  </w:abstractNum>
+  <w:num w:numId="1"><w:abstractNumId w:val="0"/></w:num>
+  <w:num w:numId="2"><w:abstractNumId w:val="1"/></w:num>
+</w:numbering>"#
+  )
+}
+
+fn write_docx(path: &Path) {
+  let file = std::fs::File::create(path).unwrap();
+  let mut zip = ZipWriter::new(file);
+  let options = FileOptions::default();
+  let parts: Vec<(&str, Vec<u8>)> = vec![
+    ("word/document.xml", document_xml().into_bytes()),
+    ("word/_rels/document.xml.rels", RELS_XML.as_bytes().to_vec()),
+    ("word/styles.xml", styles_xml().into_bytes()),
+    ("word/numbering.xml", numbering_xml().into_bytes()),
+    ("word/media/image1.png", PNG_BYTES.to_vec()),
+  ];
+  for (name, bytes) in parts {
+    zip.start_file(name, options).unwrap();
+    zip.write_all(&bytes).unwrap();
+  }
+  zip.finish().unwrap();
+}
+
+fn children_of<'a>(data: &'a DocumentData, id: &str) -> Vec<&'a collab_document::blocks::Block> {
+  data.meta.children_map[id]
+    .iter()
+    .map(|child_id| &data.blocks[child_id])
+    .collect()
+}
+
+fn delta_of(data: &DocumentData, block_id: &str) -> Value {
+  let text_map = data.meta.text_map.as_ref().unwrap();
+  serde_json::from_str(&text_map[block_id]).unwrap()
+}
+
+#[test]
+fn import_docx_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let docx_path = dir.path().join("Project plan.docx");
+  write_docx(&docx_path);
+  let output_dir = dir.path().join("output");
+
+  let importer = DocxImporter::new("workspace_id", "http://test.appflowy.cloud".to_string());
+  let file = std::fs::File::open(&docx_path).unwrap();
+  let (data, resource) = importer
+    .import_reader("view_id", file, &output_dir)
+    .unwrap();
+
+  let page_children = children_of(&data, &data.page_id);
+  let types = page_children
+    .iter()
+    .map(|block| block.ty.clone())
+    .collect::<Vec<_>>();
+  assert_eq!(
+    types,
+    vec![
+      BlockType::Heading.to_string(),
+      BlockType::Paragraph.to_string(),
+      BlockType::BulletedList.to_string(),
+      BlockType::NumberedList.to_string(),
+      BlockType::Quote.to_string(),
+      BlockType::SimpleTable.to_string(),
+      BlockType::Image.to_string(),
+    ]
+  );
+
+  // heading
+  assert_eq!(page_children[0].data["level"], json!(1));
+  assert_eq!(
+    delta_of(&data, &page_children[0].id),
+    json!([{ "insert": "Project plan" }])
+  );
+
+  // inline formatting and hyperlink
+  assert_eq!(
+    delta_of(&data, &page_children[1].id),
+    json!([
+      { "insert": "Plain " },
+      { "insert": "bold", "attributes": { "bold": true } },
+      { "insert": "underlined", "attributes": { "italic": true, "underline": true } },
+      { "insert": " and " },
+      { "insert": "a link", "attributes": { "href": "https://appflowy.io" } },
+    ])
+  );
+
+  // the second level list item is nested in the first one
+  let nested = children_of(&data, &page_children[2].id);
+  assert_eq!(nested.len(), 1);
+  assert_eq!(nested[0].ty, BlockType::BulletedList.to_string());
+  assert_eq!(
+    delta_of(&data, &nested[0].id),
+    json!([{ "insert": "Nested" }])
+  );
+
+  // table
+  let table = page_children[5];
+  let table_data = SimpleTableData::from_block_data(&table.data);
+  assert!(table_data.enable_header_row);
+  assert_eq!(table_data.column_width(0), 200.0);
+  assert_eq!(table_data.column_width(1), 100.0);
+  let rows = children_of(&data, &table.id);
+  assert_eq!(rows.len(), 2);
+  let cells = children_of(&data, &rows[1].id);
+  assert_eq!(cells.len(), 2);
+  assert_eq!(cells[1].data["rowPosition"], json!(1));
+  assert_eq!(cells[1].data["colPosition"], json!(1));
+  let cell_content = children_of(&data, &cells[0].id);
+  assert_eq!(
+    delta_of(&data, &cell_content[0].id),
+    json!([{ "insert": "Design" }])
+  );
+  // empty cells get an empty paragraph
+  assert_eq!(children_of(&data, &cells[1].id).len(), 1);
+
+  // image is extracted as a resource
+  let image = page_children[6];
+  let url = image.data["url"].as_str().unwrap();
+  assert!(
+    url.starts_with("http://test.appflowy.cloud/api/file_storage/workspace_id/v1/blob/view_id/")
+  );
+  assert!(url.ends_with(".png"));
+  assert_eq!(resource.object_id, "view_id");
+  assert_eq!(resource.files.len(), 1);
+  assert_eq!(std::fs::read(&resource.files[0]).unwrap(), PNG_BYTES);
+}
+
+#[test]
+fn import_docx_file_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let docx_path = dir.path().join("Project plan.docx");
+  write_docx(&docx_path);
+  let output_dir = dir.path().join("output");
+
+  let importer = DocxImporter::new("workspace_id", "http://test.appflowy.cloud".to_string());
+  let info = importer
+    .import_file("view_id", docx_path, output_dir)
+    .unwrap();
+  assert_eq!(info.name, "Project plan");
+  assert!(matches!(info.import_type, ImportType::Document));
+  assert_eq!(info.imported_collabs.len(), 1);
+  assert_eq!(info.imported_collabs[0].object_id, "view_id");
+  assert_eq!(info.resources[0].files.len(), 1);
+}
+
+#[test]
+fn import_non_docx_file_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let path = dir.path().join("empty.docx");
+  let file = std::fs::File::create(&path).unwrap();
+  let mut zip = ZipWriter::new(file);
+  zip
+    .start_file("readme.txt", FileOptions::default())
+    .unwrap();
+  zip.write_all(b"hello").unwrap();
+  zip.finish().unwrap();
+
+  let importer = DocxImporter::new("workspace_id", "http://test.appflowy.cloud".to_string());
+  let file = std::fs::File::open(&path).unwrap();
+  let result = importer.import_reader("view_id", file, dir.path());
+  assert!(result.is_err());
+}
@@ -0,0 +1 @@
+mod docx_import_test;
@@ -0,0 +1,206 @@
+use crate::util::document_data;
+use collab_document::blocks::BlockType;
+use collab_importer::enex::parser::parse_enex;
+use collab_importer::enex::{EnexImporter, TAGS_FIELD};
+use collab_importer::error::ImporterError;
+use serde_json::json;
+
+const PNG_BYTES: &[u8] = &[0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 1, 2, 3, 4];
+const PNG_HASH: &str = "36bbd90a8f515b48cafb94118844ed8f";
+const PDF_HASH: &str = "662d150c1c021efdffc61004e797114b";
+
+fn enex() -> String {
+  format!(
+    r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE en-export SYSTEM "http://xml.evernote.com/pub/evernote-export3.dtd">
+<en-export export-date="20240310T120000Z" application="Evernote" version="10.0">
+  <note>
+    <title>Groceries</title>
+    <content><![CDATA[<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<!DOCTYPE en-note SYSTEM "http://xml.evernote.com/pub/enml2.dtd">
+<en-note>
+<h1>Shopping</h1>
+<div>Buy <b>milk</b> &amp; eggs</div>
+<div><en-todo checked="true"/>Call mom</div>
+<div><en-todo/>Pay bills</div>
+<div><en-media hash="{PNG_HASH}" type="image/png"/></div>
+<div><en-media hash="{PDF_HASH}" type="application/pdf"/></div>
+<en-crypt>c2VjcmV0</en-crypt>
+</en-note>]]></content>
+    <created>20240304T101500Z</created>
+    <updated>20240305T080000Z</updated>
+    <tag>home</tag>
+    <tag>weekly</tag>
+    <note-attributes>
+      <author>Nathan</author>
+      <source-url>https://example.com/list</source-url>
+    </note-attributes>
+    <resource>
+      <data encoding="base64">
+iVBORw0KGgoB
+AgME
+      </data>
+      <mime>image/png</mime>
+      <resource-attributes><file-name>photo.png</file-name></resource-attributes>
+    </resource>
+    <resource>
+      <data encoding="base64">JVBERi0xLjQgdGVzdA==</data>
+      <mime>application/pdf</mime>
+      <resource-attributes><file-name>receipt.pdf</file-name></resource-attributes>
+    </resource>
+  </note>
+  <note>
+    <title></title>
+    <content><![CDATA[<en-note><div>Second</div></en-note>]]></content>
+  </note>
+</en-export>"#
+  )
+}
+
+#[test]
+fn parse_enex_test() {
+  let notes = parse_enex(&enex()).unwrap();
+  assert_eq!(notes.len(), 2);
+
+  let note = &notes[0];
+  assert_eq!(note.title, "Groceries");
+  assert_eq!(note.tags, vec!["home", "weekly"]);
+  assert_eq!(note.created_at, Some(1709547300));
+  assert_eq!(note.updated_at, Some(1709625600));
+  assert_eq!(note.author.as_deref(), Some("Nathan"));
+  assert_eq!(note.source_url.as_deref(), Some("https://example.com/list"));
+  assert!(note.content.contains("<en-note>"));
+
+  assert_eq!(note.resources.len(), 2);
+  assert_eq!(note.resources[0].data, PNG_BYTES);
+  assert_eq!(note.resources[0].hash, PNG_HASH);
+  assert_eq!(note.resources[0].extension(), "png");
+  assert!(note.resources[0].is_image());
+  assert_eq!(note.resources[1].hash, PDF_HASH);
+  assert!(!note.resources[1].is_image());
+
+  assert!(notes[1].title.is_empty());
+  assert!(notes[1].resources.is_empty());
+}
+
+#[test]
+fn import_enex_file_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let enex_path = dir.path().join("Personal.enex");
+  std::fs::write(&enex_path, enex()).unwrap();
+  let output_dir = dir.path().join("output");
+
+  let importer = EnexImporter::new(1, "workspace_id", "http://test.appflowy.cloud".to_string());
+  let info = importer.import_file(&enex_path, &output_dir).unwrap();
+  assert_eq!(info.notebooks.len(), 1);
+  assert_eq!(info.num_of_notes(), 2);
+
+  let notebook = &info.notebooks[0];
+  assert_eq!(notebook.name, "Personal");
+  let note = &notebook.notes[0];
+  assert_eq!(note.title, "Groceries");
+  assert_eq!(notebook.notes[1].title, "Untitled");
+
+  // notebooks are folders of notes
+  let views = info.build_nested_views();
+  assert_eq!(views.views.len(), 1);
+  assert_eq!(views.views[0].view.id, notebook.view_id);
+  assert_eq!(views.views[0].view.parent_view_id, "workspace_id");
+  let note_views = &views.views[0].children;
+  assert_eq!(note_views.len(), 2);
+  assert_eq!(note_views[0].view.id, note.view_id);
+  assert_eq!(note_views[0].view.name, "Groceries");
+
+  // content
+  let data = document_data(&note.collab_info);
+  let page = &data.blocks[&data.page_id];
+  assert_eq!(page.data[TAGS_FIELD], json!(["home", "weekly"]));
+  assert_eq!(page.data["created_at"], json!(1709547300));
+  assert_eq!(page.data["source_url"], json!("https://example.com/list"));
+
+  let children = data.meta.children_map[&page.children]
+    .iter()
+    .map(|id| &data.blocks[id])
+    .collect::<Vec<_>>();
+  let types = children
+    .iter()
+    .map(|block| block.ty.clone())
+    .collect::<Vec<_>>();
+  assert_eq!(
+    types,
+    vec![
+      BlockType::Heading.to_string(),
+      BlockType::Paragraph.to_string(),
+      BlockType::TodoList.to_string(),
+      BlockType::TodoList.to_string(),
+      BlockType::Image.to_string(),
+      BlockType::Paragraph.to_string(),
+    ]
+  );
+  assert_eq!(children[2].data["checked"], json!(true));
+  assert_eq!(children[3].data["checked"], json!(false));
+
+  // resources are uploaded and referenced by the blocks
+  let image_url = children[4].data["url"].as_str().unwrap();
+  let url_prefix = format!(
+    "http://test.appflowy.cloud/api/file_storage/workspace_id/v1/blob/{}/",
+    note.view_id
+  );
+  assert!(image_url.starts_with(&url_prefix));
+  assert!(image_url.ends_with(".png"));
+  let text_map = data.meta.text_map.as_ref().unwrap();
+  let attachment = text_map[children[5].external_id.as_ref().unwrap()].clone();
+  assert!(attachment.contains("receipt.pdf"));
+  assert!(attachment.contains(&url_prefix));
+
+  let resource = &note.collab_info.resources[0];
+  assert_eq!(resource.object_id, note.view_id);
+  assert_eq!(resource.files.len(), 2);
+  assert!(
+    resource
+      .files
+      .iter()
+      .any(|file| std::fs::read(file).unwrap() == PNG_BYTES)
+  );
+
+  // each notebook comes before its notes
+  let collab_infos = info.into_collab_infos();
+  assert_eq!(collab_infos.len(), 3);
+  assert_eq!(collab_infos[0].name, "Personal");
+  assert_eq!(collab_infos[1].name, "Groceries");
+}
+
+#[test]
+fn import_enex_dir_test() {
+  let dir = tempfile::tempdir().unwrap();
+  std::fs::write(dir.path().join("b.enex"), enex()).unwrap();
+  std::fs::write(dir.path().join("a.enex"), enex()).unwrap();
+  std::fs::write(dir.path().join("notes.txt"), "not an export").unwrap();
+  let output_dir = dir.path().join("output");
+
+  let importer = EnexImporter::new(1, "workspace_id", "http://test.appflowy.cloud".to_string());
+  let info = importer
+    .import_dir(dir.path(), output_dir.as_path())
+    .unwrap();
+  let names = info
+    .notebooks
+    .iter()
+    .map(|notebook| notebook.name.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(names, vec!["a", "b"]);
+  assert_eq!(info.num_of_notes(), 4);
+}
+
+#[test]
+fn import_invalid_enex_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let enex_path = dir.path().join("broken.enex");
+  std::fs::write(&enex_path, "<en-export><note><title>Oops</note>").unwrap();
+
+  let importer = EnexImporter::new(1, "workspace_id", "http://test.appflowy.cloud".to_string());
+  let err = importer
+    .import_file(&enex_path, &dir.path().join("output"))
+    .unwrap_err();
+  assert!(matches!(err.root(), ImporterError::ParseEnexError(_)));
+  assert_eq!(err.code(), "importer.parse_enex_failed");
+}
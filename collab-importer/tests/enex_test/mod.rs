@@ -0,0 +1 @@
+mod enex_import_test;
@@ -0,0 +1,60 @@
+use collab_document::error::{DocumentError, ErrorCategory};
+use collab_importer::error::{ImporterError, ImporterResultExt};
+use collab_importer::zip_tool::sync_zip::sync_unzip;
+
+#[test]
+fn invalid_zip_error_code_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let path = dir.path().join("not_a_zip.zip");
+  std::fs::write(&path, b"hello world").unwrap();
+
+  let err = sync_unzip(path, dir.path().join("output"), None)
+    .err()
+    .unwrap();
+  assert_eq!(err.code(), "importer.invalid_archive");
+  assert_eq!(err.category(), ErrorCategory::SourceFormat);
+  assert!(!err.is_retryable());
+}
+
+#[test]
+fn missing_zip_error_code_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let err = sync_unzip(
+    dir.path().join("missing.zip"),
+    dir.path().to_path_buf(),
+    None,
+  )
+  .err()
+  .unwrap();
+  assert_eq!(err.code(), "importer.io");
+  assert_eq!(err.category(), ErrorCategory::Resource);
+  assert!(!err.is_retryable());
+  assert!(err.context_chain()[0].starts_with("Failed to open zip file"));
+}
+
+#[test]
+fn error_context_chain_test() {
+  let err = ImporterError::FileNotFound
+    .context("Failed to import page")
+    .context("Failed to import workspace");
+  assert_eq!(err.code(), "importer.file_not_found");
+  assert_eq!(err.category(), ErrorCategory::Resource);
+  assert!(matches!(err.root(), ImporterError::FileNotFound));
+  assert_eq!(
+    err.context_chain(),
+    vec![
+      "Failed to import workspace",
+      "Failed to import page",
+      "File not found"
+    ]
+  );
+  assert_eq!(
+    err.to_string(),
+    "Failed to import workspace: Failed to import page: File not found"
+  );
+
+  let result: Result<(), DocumentError> = Err(DocumentError::ParseMarkdownError);
+  let err = result.context("Failed to import README.md").unwrap_err();
+  assert_eq!(err.code(), "document.parse_markdown_failed");
+  assert_eq!(err.category(), ErrorCategory::SourceFormat);
+}
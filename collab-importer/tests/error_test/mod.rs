@@ -0,0 +1 @@
+mod error_code_test;
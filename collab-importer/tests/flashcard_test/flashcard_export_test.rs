@@ -0,0 +1,85 @@
+use collab::core::collab::default_client_id;
+use collab_database::database::Database;
+use collab_database::database_trait::NoPersistenceDatabaseCollabService;
+use collab_database::template::csv::CSVTemplate;
+use collab_document::blocks::{Block, BlockType, DocumentDataBuilder, NewBlock};
+use collab_importer::flashcard::{
+  Flashcard, flashcards_from_database, flashcards_from_document, write_anki_csv,
+};
+use std::sync::Arc;
+
+#[test]
+fn flashcards_from_toggle_lists_test() {
+  let data = DocumentDataBuilder::new()
+    .with_block(Block::heading(2, "Rust basics"))
+    .with_block(
+      NewBlock::new(BlockType::ToggleList)
+        .with_text("What is a borrow?")
+        .with_child(Block::paragraph("A reference to a value"))
+        .with_child(
+          NewBlock::new(BlockType::ToggleList)
+            .with_text("Example")
+            .with_child(Block::paragraph("&value")),
+        ),
+    )
+    .with_block(NewBlock::new(BlockType::ToggleList).with_text("Without answer"))
+    .with_block(Block::paragraph("Not a card"))
+    .build();
+
+  let cards = flashcards_from_document(&data).unwrap();
+  assert_eq!(
+    cards,
+    vec![Flashcard {
+      front: "What is a borrow?".to_string(),
+      back: "A reference to a value\nExample\n  &value".to_string(),
+      tags: vec!["Rust_basics".to_string()],
+    }]
+  );
+
+  let csv = write_anki_csv(&cards, Some("Rust")).unwrap();
+  assert_eq!(
+    csv,
+    "#separator:Comma\n#html:true\n#tags column:3\n#deck:Rust\n\
+     What is a borrow?,A reference to a value<br>Example<br>  &amp;value,Rust_basics\n"
+  );
+}
+
+#[tokio::test]
+async fn flashcards_from_database_test() {
+  let csv = "Word,Translation,Notes\nhola,hello,greeting\nadiós,goodbye,\ngracias,,\n";
+  let template = CSVTemplate::try_from_reader(csv.as_bytes(), false, None)
+    .unwrap()
+    .try_into_database_template(None)
+    .await
+    .unwrap();
+  let service = Arc::new(NoPersistenceDatabaseCollabService::new(default_client_id()));
+  let database = Database::create_with_template(template, service.clone(), service)
+    .await
+    .unwrap();
+
+  let cards = flashcards_from_database(&database, None, None)
+    .await
+    .unwrap();
+  let pairs = cards
+    .iter()
+    .map(|card| (card.front.as_str(), card.back.as_str()))
+    .collect::<Vec<_>>();
+  assert_eq!(pairs, vec![("hola", "hello"), ("adiós", "goodbye")]);
+
+  let notes_field = database
+    .get_all_fields()
+    .into_iter()
+    .find(|field| field.name == "Notes")
+    .unwrap();
+  let cards = flashcards_from_database(&database, None, Some(&notes_field.id))
+    .await
+    .unwrap();
+  assert_eq!(cards.len(), 1);
+  assert_eq!(cards[0].back, "greeting");
+
+  let csv = write_anki_csv(&cards, None).unwrap();
+  assert_eq!(
+    csv,
+    "#separator:Comma\n#html:true\n#tags column:3\nhola,greeting,\n"
+  );
+}
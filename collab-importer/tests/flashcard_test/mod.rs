@@ -0,0 +1 @@
+mod flashcard_export_test;
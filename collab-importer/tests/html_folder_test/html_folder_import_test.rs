@@ -0,0 +1,129 @@
+use crate::util::document_data;
+use collab_document::blocks::BlockType;
+use collab_document::provenance::BlockProvenance;
+use collab_importer::error::ImporterError;
+use collab_importer::html_folder::HtmlFolderImporter;
+use collab_importer::util::{FileId, upload_file_url};
+use std::path::Path;
+
+const HOST: &str = "http://test.appflowy.cloud";
+const PNG_BYTES: &[u8] = &[0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 1, 2, 3, 4];
+
+const TRIP_HTML: &str = r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"><title>Trip &amp; Plans &#x2708;</title></head>
+<body>
+<h1>Trip</h1>
+<p>Read the <a href="attachments/plan.pdf">plan</a> and the <a href="https://appflowy.io">site</a>.</p>
+<p><img src="Trip_files/photo%201.png"></p>
+<p><img src="../outside.png"></p>
+</body>
+</html>"#;
+
+fn write_export(dir: &Path) {
+  std::fs::create_dir_all(dir.join("Trip_files")).unwrap();
+  std::fs::create_dir_all(dir.join("attachments")).unwrap();
+  std::fs::create_dir_all(dir.join("Trip")).unwrap();
+  std::fs::create_dir_all(dir.join("Work")).unwrap();
+  std::fs::write(dir.join("Trip.html"), TRIP_HTML).unwrap();
+  std::fs::write(dir.join("Trip_files").join("photo 1.png"), PNG_BYTES).unwrap();
+  std::fs::write(dir.join("attachments").join("plan.pdf"), b"%PDF-1.4").unwrap();
+  std::fs::write(dir.join("Trip").join("Day 1.html"), "<p>Hiking</p>").unwrap();
+  std::fs::write(dir.join("Work").join("Meeting.htm"), "<p>Agenda</p>").unwrap();
+  std::fs::write(dir.join(".DS_Store"), b"").unwrap();
+  std::fs::write(dir.parent().unwrap().join("outside.png"), PNG_BYTES).unwrap();
+}
+
+#[test]
+fn import_html_folder_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let export_dir = dir.path().join("Notes");
+  write_export(&export_dir);
+
+  let importer = HtmlFolderImporter::new(1, "workspace_id", HOST.to_string());
+  let info = importer.import_dir(&export_dir).unwrap();
+  assert_eq!(info.name, "Notes");
+  assert_eq!(info.num_of_pages(), 4);
+
+  // the asset directories are not pages, the directory named after a page holds its children
+  let titles = info
+    .pages
+    .iter()
+    .map(|page| page.title.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(titles, vec!["Trip & Plans ✈", "Work"]);
+  let trip = &info.pages[0];
+  assert_eq!(trip.children.len(), 1);
+  assert_eq!(trip.children[0].title, "Day 1");
+  let work = &info.pages[1];
+  assert!(work.file_path.is_none());
+  assert_eq!(work.children[0].title, "Meeting");
+
+  let views = info.build_nested_views();
+  assert_eq!(views.views.len(), 1);
+  assert_eq!(views.views[0].view.id, info.view_id);
+  assert_eq!(views.views[0].view.parent_view_id, "workspace_id");
+  let trip_view = &views.views[0].children[0];
+  assert_eq!(trip_view.view.id, trip.view_id);
+  assert_eq!(trip_view.children[0].view.parent_view_id, trip.view_id);
+
+  // the assets are uploaded, the links to the web and outside of the export are kept
+  let data = document_data(&trip.collab_info);
+  let page = &data.blocks[&data.page_id];
+  let blocks = data.meta.children_map[&page.children]
+    .iter()
+    .map(|id| &data.blocks[id])
+    .collect::<Vec<_>>();
+  assert_eq!(blocks[0].ty, BlockType::Heading.to_string());
+  let provenance = BlockProvenance::from_block(blocks[0]).unwrap();
+  assert_eq!(provenance.importer, "html_folder");
+  assert_eq!(provenance.source_path, "Trip.html");
+  let image_url = upload_file_url(
+    HOST,
+    "workspace_id",
+    &trip.view_id,
+    &FileId::from_bytes(PNG_BYTES, "png".to_string()),
+  );
+  let images = blocks
+    .iter()
+    .filter(|block| block.ty == BlockType::Image.to_string())
+    .map(|block| block.data["url"].as_str().unwrap())
+    .collect::<Vec<_>>();
+  assert_eq!(images, vec![image_url.as_str(), "../outside.png"]);
+
+  let text_map = data.meta.text_map.as_ref().unwrap();
+  let link_text = &text_map[blocks[1].external_id.as_ref().unwrap()];
+  let pdf_url = upload_file_url(
+    HOST,
+    "workspace_id",
+    &trip.view_id,
+    &FileId::from_bytes(b"%PDF-1.4", "pdf".to_string()),
+  );
+  assert!(link_text.contains(&pdf_url));
+  assert!(link_text.contains("https://appflowy.io"));
+
+  let resource = &trip.collab_info.resources[0];
+  assert_eq!(resource.object_id, trip.view_id);
+  assert_eq!(resource.files.len(), 2);
+  assert!(resource.files[0].ends_with("plan.pdf"));
+  assert_eq!(std::fs::read(&resource.files[1]).unwrap(), PNG_BYTES);
+
+  // the directory and each page come before their children
+  let names = info
+    .into_collab_infos()
+    .into_iter()
+    .map(|info| info.name)
+    .collect::<Vec<_>>();
+  assert_eq!(
+    names,
+    vec!["Notes", "Trip & Plans ✈", "Day 1", "Work", "Meeting"]
+  );
+}
+
+#[test]
+fn import_missing_html_folder_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let importer = HtmlFolderImporter::new(1, "workspace_id", HOST.to_string());
+  let err = importer.import_dir(dir.path().join("missing")).unwrap_err();
+  assert!(matches!(err, ImporterError::FileNotFound));
+}
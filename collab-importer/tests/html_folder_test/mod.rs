@@ -0,0 +1 @@
+mod html_folder_import_test;
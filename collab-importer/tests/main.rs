@@ -1,2 +1,15 @@
+mod bibtex_test;
+mod confluence_test;
+mod docx_test;
+mod enex_test;
+mod error_test;
+mod flashcard_test;
+mod html_folder_test;
+mod name_collision_test;
 mod notion_test;
+mod opml_test;
+mod publish_test;
+mod roam_test;
+mod trello_test;
 mod util;
+mod zip_tool_test;
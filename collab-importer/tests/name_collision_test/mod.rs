@@ -0,0 +1 @@
+mod name_collision_test;
@@ -0,0 +1,169 @@
+use collab::core::collab::default_client_id;
+use collab_document::document::Document;
+use collab_document::importer::md_importer::MDImporter;
+use collab_importer::html_folder::HtmlFolderImporter;
+use collab_importer::name_collision::{
+  ExistingView, NameCollisionAction, NameCollisionPolicy, NameCollisionResolver,
+  merge_imported_document,
+};
+use collab_importer::notion::NotionImporter;
+use futures::StreamExt;
+
+const HOST: &str = "http://test.appflowy.cloud";
+
+#[test]
+fn resolve_name_collisions_test() {
+  let existing = vec![
+    ExistingView::new("v1", "Notes"),
+    ExistingView::new("v2", "Notes (imported)"),
+  ];
+  let mut resolver = NameCollisionResolver::new(NameCollisionPolicy::Rename, &existing);
+  assert_eq!(
+    resolver.resolve("a", " notes ", true),
+    NameCollisionAction::Renamed {
+      name: "notes (imported 2)".to_string()
+    }
+  );
+  assert_eq!(
+    resolver.resolve("b", "Notes", true),
+    NameCollisionAction::Renamed {
+      name: "Notes (imported 3)".to_string()
+    }
+  );
+  assert_eq!(
+    resolver.resolve("c", "Plans", true),
+    NameCollisionAction::Imported
+  );
+  let report = resolver.into_report();
+  assert!(report.has_collisions());
+  assert_eq!(report.outcomes.len(), 3);
+  assert_eq!(report.outcomes[0].view_id, "a");
+
+  let mut resolver = NameCollisionResolver::new(NameCollisionPolicy::Merge, &existing);
+  assert_eq!(
+    resolver.resolve("a", "Notes", true),
+    NameCollisionAction::Merged {
+      existing_view_id: "v1".to_string()
+    }
+  );
+  // A database can't be merged.
+  assert_eq!(
+    resolver.resolve("b", "Notes", false),
+    NameCollisionAction::Renamed {
+      name: "Notes (imported 2)".to_string()
+    }
+  );
+  assert_eq!(resolver.into_report().merged_views(), vec![("a", "v1")]);
+
+  let mut resolver = NameCollisionResolver::new(NameCollisionPolicy::Skip, &existing);
+  assert_eq!(
+    resolver.resolve("a", "NOTES", true),
+    NameCollisionAction::Skipped {
+      existing_view_id: "v1".to_string()
+    }
+  );
+}
+
+#[tokio::test]
+async fn merge_notion_page_into_existing_view_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let export_dir = dir.path().join("Export");
+  let notes_dir = export_dir.join("Notes 0a1b2c3d4e5f60718293a4b5c6d7e8f9");
+  std::fs::create_dir_all(&notes_dir).unwrap();
+  std::fs::write(
+    export_dir.join("Notes 0a1b2c3d4e5f60718293a4b5c6d7e8f9.md"),
+    "# Notes\n\nImported paragraph\n",
+  )
+  .unwrap();
+  std::fs::write(
+    notes_dir.join("Todo 1234567890abcdef1234567890abcdef.md"),
+    "# Todo\n",
+  )
+  .unwrap();
+  std::fs::write(
+    export_dir.join("Plans 2234567890abcdef1234567890abcdef.md"),
+    "# Plans\n",
+  )
+  .unwrap();
+
+  let mut info = NotionImporter::new(1, &export_dir, uuid::Uuid::new_v4(), HOST.to_string())
+    .unwrap()
+    .import()
+    .await
+    .unwrap()
+    .with_parent_view("folder");
+  let existing = vec![ExistingView::new("existing_notes", "Notes")];
+  let report = info.resolve_name_collisions(&existing, NameCollisionPolicy::Merge);
+  let notes_view_id = info
+    .view_id_by_notion_id("0a1b2c3d4e5f60718293a4b5c6d7e8f9")
+    .unwrap();
+  assert_eq!(
+    report.merged_views(),
+    vec![(notes_view_id.as_str(), "existing_notes")]
+  );
+
+  // The sub page of the merged page is imported under the existing view.
+  let nested_views = info.build_nested_views().await;
+  let mut views = nested_views
+    .views
+    .iter()
+    .map(|view| (view.view.name.clone(), view.view.parent_view_id.clone()))
+    .collect::<Vec<_>>();
+  views.sort();
+  assert_eq!(
+    views,
+    vec![
+      ("Plans".to_string(), "folder".to_string()),
+      ("Todo".to_string(), "existing_notes".to_string()),
+    ]
+  );
+
+  // The content of the merged page is appended to the existing page.
+  let collabs = info.into_collab_stream().await.collect::<Vec<_>>().await;
+  let notes_collab = collabs
+    .iter()
+    .flat_map(|info| info.imported_collabs.iter())
+    .find(|collab| collab.object_id == notes_view_id)
+    .unwrap();
+  let data = MDImporter::new(None)
+    .import("existing_notes", "Existing paragraph".to_string())
+    .unwrap();
+  let mut document = Document::create("existing_notes", data, default_client_id()).unwrap();
+  let inserted =
+    merge_imported_document(&mut document, &notes_collab.encoded_collab, &notes_view_id).unwrap();
+  assert!(!inserted.is_empty());
+  let texts = document.to_plain_text();
+  assert_eq!(
+    texts.first().map(String::as_str),
+    Some("Existing paragraph")
+  );
+  assert!(
+    texts.iter().any(|text| text == "Imported paragraph"),
+    "{:?}",
+    texts
+  );
+}
+
+#[test]
+fn skip_and_rename_html_folder_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let export_dir = dir.path().join("Notes");
+  std::fs::create_dir_all(&export_dir).unwrap();
+  std::fs::write(export_dir.join("Trip.html"), "<p>Hiking</p>").unwrap();
+  let existing = vec![ExistingView::new("existing_notes", "Notes")];
+  let importer = HtmlFolderImporter::new(1, uuid::Uuid::new_v4(), HOST.to_string());
+
+  let mut info = importer.import_dir(&export_dir).unwrap();
+  let report = info.resolve_name_collisions(&existing, NameCollisionPolicy::Skip);
+  assert!(report.has_collisions());
+  assert!(info.build_nested_views().views.is_empty());
+  assert!(info.into_collab_infos().is_empty());
+
+  let mut info = importer.import_dir(&export_dir).unwrap();
+  info.resolve_name_collisions(&existing, NameCollisionPolicy::Rename);
+  let views = info.build_nested_views().views;
+  assert_eq!(views.len(), 1);
+  assert_eq!(views[0].view.name, "Notes (imported)");
+  assert_eq!(views[0].children.len(), 1);
+  assert_eq!(info.into_collab_infos()[0].name, "Notes (imported)");
+}
@@ -0,0 +1,348 @@
+use async_trait::async_trait;
+use collab_document::error::ErrorCategory;
+use collab_importer::error::ImporterError;
+use collab_importer::notion::NotionImporter;
+use collab_importer::notion::api::{
+  NotionApiClient, NotionApiMethod, NotionApiRequest, NotionApiResponse, NotionApiTransport,
+};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tempfile::tempdir;
+
+const PROJECT_ID: &str = "1a2b3c4d-5e6f-4071-8293-a4b5c6d7e8f9";
+const TASK_ID: &str = "9f8e7d6c-5b4a-4031-2213-f4e5d6c7b8a9";
+const DATABASE_ID: &str = "2c3d4e5f-6a7b-4c8d-9e0f-a1b2c3d4e5f6";
+const ROW_ID: &str = "3d4e5f6a-7b8c-4d9e-8f0a-b1c2d3e4f5a6";
+const IMAGE_URL: &str = "https://files.notion.test/diagram.png?expires=1";
+const PNG_BYTES: &[u8] = &[0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 1, 2, 3, 4];
+
+/// Serve the canned responses by the url of the request, without its query.
+struct FakeTransport {
+  responses: HashMap<String, Value>,
+}
+
+#[async_trait]
+impl NotionApiTransport for FakeTransport {
+  async fn send(&self, request: NotionApiRequest) -> Result<NotionApiResponse, ImporterError> {
+    if request.url == IMAGE_URL {
+      return Ok(NotionApiResponse {
+        status: 200,
+        headers: vec![],
+        body: PNG_BYTES.to_vec(),
+      });
+    }
+    assert!(
+      request
+        .headers
+        .iter()
+        .any(|(name, value)| name == "Authorization" && value == "Bearer secret")
+    );
+
+    let mut key = request.url.split('?').next().unwrap().to_string();
+    if request.method == NotionApiMethod::Post && key.ends_with("/search") {
+      let object = request.body.as_ref().unwrap()["filter"]["value"]
+        .as_str()
+        .unwrap();
+      key = format!("{}/{}", key, object);
+    }
+    let (status, body) = match self.responses.get(&key) {
+      Some(body) => (200, body.clone()),
+      None => (
+        404,
+        json!({ "object": "error", "code": "object_not_found", "message": key }),
+      ),
+    };
+    Ok(NotionApiResponse {
+      status,
+      headers: vec![],
+      body: serde_json::to_vec(&body).unwrap(),
+    })
+  }
+}
+
+fn list(results: Vec<Value>) -> Value {
+  json!({ "object": "list", "results": results, "has_more": false, "next_cursor": null })
+}
+
+fn text(content: &str) -> Value {
+  json!({
+    "type": "text",
+    "text": { "content": content, "link": null },
+    "annotations": { "bold": false, "italic": false, "strikethrough": false, "code": false },
+    "plain_text": content,
+    "href": null
+  })
+}
+
+fn page(id: &str, title: &str, parent: Value) -> Value {
+  json!({
+    "object": "page",
+    "id": id,
+    "parent": parent,
+    "properties": { "Name": { "type": "title", "title": [text(title)] } }
+  })
+}
+
+fn block(id: &str, ty: &str, content: Value) -> Value {
+  json!({ "object": "block", "id": id, "type": ty, "has_children": false, ty: content })
+}
+
+fn fake_workspace() -> FakeTransport {
+  let base = "https://api.notion.com/v1";
+  let mut row = page(
+    ROW_ID,
+    "Write the plan",
+    json!({ "type": "database_id", "database_id": DATABASE_ID }),
+  );
+  row["properties"]["Status"] = json!({ "type": "select", "select": { "name": "Done" } });
+
+  let mut bold = text("world");
+  bold["annotations"]["bold"] = json!(true);
+  let mention = json!({
+    "type": "mention",
+    "mention": { "type": "page", "page": { "id": PROJECT_ID } },
+    "plain_text": "Project",
+    "href": null
+  });
+
+  let responses = HashMap::from([
+    (
+      format!("{}/search/page", base),
+      list(vec![
+        page(
+          PROJECT_ID,
+          "Project",
+          json!({ "type": "workspace", "workspace": true }),
+        ),
+        page(
+          TASK_ID,
+          "Task",
+          json!({ "type": "page_id", "page_id": PROJECT_ID }),
+        ),
+        row.clone(),
+      ]),
+    ),
+    (
+      format!("{}/search/database", base),
+      list(vec![json!({
+        "object": "database",
+        "id": DATABASE_ID,
+        "title": [text("Tasks")],
+        "parent": { "type": "page_id", "page_id": PROJECT_ID },
+        "properties": {
+          "Name": { "type": "title", "title": {} },
+          "Status": { "type": "select", "select": {} }
+        }
+      })]),
+    ),
+    (
+      format!("{}/blocks/{}/children", base, PROJECT_ID),
+      list(vec![
+        block(
+          "b1",
+          "paragraph",
+          json!({ "rich_text": [text("Hello "), bold] }),
+        ),
+        block("b2", "child_page", json!({ "title": "Task" })),
+        block(
+          "b3",
+          "image",
+          json!({ "type": "file", "file": { "url": IMAGE_URL }, "caption": [] }),
+        ),
+        block(DATABASE_ID, "child_database", json!({ "title": "Tasks" })),
+      ]),
+    ),
+    (
+      format!("{}/blocks/{}/children", base, TASK_ID),
+      list(vec![block(
+        "b4",
+        "paragraph",
+        json!({ "rich_text": [text("Back to "), mention] }),
+      )]),
+    ),
+    (
+      format!("{}/blocks/{}/children", base, ROW_ID),
+      list(vec![block(
+        "b5",
+        "paragraph",
+        json!({ "rich_text": [text("Row content")] }),
+      )]),
+    ),
+    (
+      format!("{}/databases/{}/query", base, DATABASE_ID),
+      list(vec![row.clone()]),
+    ),
+  ]);
+  FakeTransport { responses }
+}
+
+#[tokio::test]
+async fn import_notion_workspace_from_api_test() {
+  let dir = tempdir().unwrap();
+  let client = NotionApiClient::new("secret", fake_workspace());
+  let importer = NotionImporter::from_api(
+    1,
+    client,
+    dir.path(),
+    "My workspace",
+    uuid::Uuid::new_v4(),
+    "http://test.appflowy.cloud".to_string(),
+  )
+  .await
+  .unwrap();
+  let info = importer.import().await.unwrap();
+  assert_eq!(info.views().len(), 1);
+  assert_eq!(info.num_of_csv(), 1);
+
+  let project = info.views()[0].clone();
+  assert_eq!(project.notion_name, "Project");
+  assert_eq!(
+    project.notion_id.as_deref(),
+    Some("1a2b3c4d5e6f40718293a4b5c6d7e8f9")
+  );
+  let task = project
+    .children
+    .iter()
+    .find(|child| child.notion_name == "Task")
+    .unwrap();
+  assert!(task.notion_file.is_markdown());
+  let database = project
+    .children
+    .iter()
+    .find(|child| child.notion_file.is_csv())
+    .unwrap();
+  assert_eq!(database.notion_name, "Tasks");
+
+  // The file hosted by Notion is downloaded next to the page.
+  let (document, resource) = project.as_document().await.unwrap();
+  let image = resource
+    .files
+    .iter()
+    .find(|file| file.ends_with("diagram.png"))
+    .unwrap();
+  assert_eq!(std::fs::read(image).unwrap(), PNG_BYTES);
+
+  // The sub page is imported as a mention of its view.
+  let data = document.get_document_data().unwrap();
+  let texts = serde_json::to_string(&data.meta.text_map).unwrap();
+  assert!(texts.contains(&task.view_id), "{}", texts);
+  assert!(texts.contains("bold"), "{}", texts);
+
+  // The mention of a page is imported as a mention of its view.
+  let (task_document, _) = task.as_document().await.unwrap();
+  let task_texts =
+    serde_json::to_string(&task_document.get_document_data().unwrap().meta.text_map).unwrap();
+  assert!(task_texts.contains(&project.view_id), "{}", task_texts);
+}
+
+#[tokio::test]
+async fn notion_api_error_test() {
+  struct UnauthorizedTransport;
+
+  #[async_trait]
+  impl NotionApiTransport for UnauthorizedTransport {
+    async fn send(&self, _request: NotionApiRequest) -> Result<NotionApiResponse, ImporterError> {
+      Ok(NotionApiResponse {
+        status: 401,
+        headers: vec![],
+        body: br#"{"object":"error","code":"unauthorized","message":"API token is invalid."}"#
+          .to_vec(),
+      })
+    }
+  }
+
+  let client = NotionApiClient::new("secret", UnauthorizedTransport);
+  let err = client.search_pages().await.unwrap_err();
+  assert_eq!(err.code(), "importer.notion_api_failed");
+  assert_eq!(err.category(), ErrorCategory::Resource);
+  assert!(!err.is_retryable());
+  assert_eq!(err.to_string(), "Notion API error: API token is invalid.");
+}
+
+/// Reply with the canned responses in order and record the urls of the requests.
+#[derive(Clone)]
+struct ScriptedTransport {
+  responses: Arc<Mutex<Vec<NotionApiResponse>>>,
+  urls: Arc<Mutex<Vec<String>>>,
+}
+
+impl ScriptedTransport {
+  fn new(responses: Vec<NotionApiResponse>) -> Self {
+    Self {
+      responses: Arc::new(Mutex::new(responses)),
+      urls: Arc::new(Mutex::new(vec![])),
+    }
+  }
+}
+
+#[async_trait]
+impl NotionApiTransport for ScriptedTransport {
+  async fn send(&self, request: NotionApiRequest) -> Result<NotionApiResponse, ImporterError> {
+    self.urls.lock().unwrap().push(request.url);
+    Ok(self.responses.lock().unwrap().remove(0))
+  }
+}
+
+fn json_response(status: u16, headers: Vec<(&str, &str)>, body: Value) -> NotionApiResponse {
+  NotionApiResponse {
+    status,
+    headers: headers
+      .into_iter()
+      .map(|(name, value)| (name.to_string(), value.to_string()))
+      .collect(),
+    body: serde_json::to_vec(&body).unwrap(),
+  }
+}
+
+#[tokio::test]
+async fn notion_api_retry_test() {
+  let rate_limited = json!({ "object": "error", "code": "rate_limited", "message": "slow down" });
+  let transport = ScriptedTransport::new(vec![
+    json_response(429, vec![("retry-after", "0")], rate_limited.clone()),
+    json_response(503, vec![], json!({ "message": "unavailable" })),
+    json_response(200, vec![], list(vec![json!({ "id": PROJECT_ID })])),
+  ]);
+  let client = NotionApiClient::new("secret", transport.clone()).with_retries(3, Duration::ZERO);
+  let pages = client.search_pages().await.unwrap();
+  assert_eq!(pages, vec![json!({ "id": PROJECT_ID })]);
+  assert_eq!(transport.urls.lock().unwrap().len(), 3);
+
+  // The last response is returned once the retries are exhausted.
+  let transport = ScriptedTransport::new(vec![
+    json_response(429, vec![("Retry-After", "0")], rate_limited.clone()),
+    json_response(429, vec![("Retry-After", "0")], rate_limited),
+  ]);
+  let client = NotionApiClient::new("secret", transport.clone()).with_retries(1, Duration::ZERO);
+  let err = client.search_pages().await.unwrap_err();
+  assert!(matches!(
+    err,
+    ImporterError::NotionApiError {
+      status: Some(429),
+      ..
+    }
+  ));
+  assert_eq!(transport.urls.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn notion_api_pagination_cursor_test() {
+  let transport = ScriptedTransport::new(vec![
+    json_response(
+      200,
+      vec![],
+      json!({ "results": [{ "id": "a" }], "has_more": true, "next_cursor": "a+b/c=&d" }),
+    ),
+    json_response(200, vec![], list(vec![json!({ "id": "b" })])),
+  ]);
+  let client = NotionApiClient::new("secret", transport.clone());
+  let children = client.block_children(PROJECT_ID).await.unwrap();
+  assert_eq!(children.len(), 2);
+  let urls = transport.urls.lock().unwrap();
+  assert!(
+    urls[1].ends_with("?page_size=100&start_cursor=a%2Bb%2Fc%3D%26d"),
+    "{}",
+    urls[1]
+  );
+}
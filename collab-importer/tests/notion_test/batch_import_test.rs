@@ -0,0 +1,90 @@
+use collab_folder::hierarchy_builder::ParentChildViews;
+use collab_importer::error::ImporterError;
+use collab_importer::imported_collab::import_notion_zip_files;
+use collab_importer::notion::NotionBatchImporter;
+use futures::stream::StreamExt;
+use std::path::PathBuf;
+
+const HOST: &str = "http://test.appflowy.cloud";
+
+fn asset(name: &str) -> PathBuf {
+  PathBuf::from(format!("./tests/asset/{}.zip", name))
+}
+
+#[tokio::test]
+async fn batch_import_archives_as_spaces_test() {
+  let dir = tempfile::tempdir().unwrap();
+  // the same export, under another name
+  let copy = dir.path().join("blog_post_copy.zip");
+  std::fs::copy(asset("blog_post"), &copy).unwrap();
+
+  let workspace_id = uuid::Uuid::new_v4().to_string();
+  let info = NotionBatchImporter::new(1, &workspace_id, HOST.to_string(), dir.path().join("out"))
+    .with_archive(asset("blog_post"))
+    .with_archive(asset("project&task"))
+    .with_archive(&copy)
+    .import()
+    .await
+    .unwrap();
+
+  let names = info
+    .views()
+    .iter()
+    .map(|view| view.notion_name.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(names, vec!["blog_post", "project&task", "blog_post_copy"]);
+  assert!(info.views().iter().all(|view| view.is_dir));
+  assert_eq!(
+    info.num_of_markdown(),
+    1 + info.views()[1].num_of_markdown()
+  );
+
+  // the pages of the first archive are not imported again
+  let blog_post = &info.views()[0].children[0];
+  assert!(info.views()[2].children.is_empty());
+  assert_eq!(
+    info.view_id_by_notion_id(blog_post.notion_id.as_ref().unwrap()),
+    Some(blog_post.view_id.clone())
+  );
+
+  // each archive is a space of the workspace
+  let views: Vec<ParentChildViews> = info.build_nested_views().await.into_inner();
+  assert_eq!(views.len(), 3);
+  for view in views.iter() {
+    assert_eq!(view.view.parent_view_id, workspace_id);
+    assert!(view.view.space_info().is_some());
+  }
+  assert_eq!(views[0].children[0].view.id, blog_post.view_id);
+
+  let collabs = info.into_collab_stream().await.collect::<Vec<_>>().await;
+  assert_eq!(collabs[0].name, "blog_post");
+  assert_eq!(collabs.last().unwrap().name, "blog_post_copy");
+}
+
+#[tokio::test]
+async fn batch_import_zip_files_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let infos = import_notion_zip_files(
+    1,
+    HOST,
+    &uuid::Uuid::new_v4().to_string(),
+    vec![asset("blog_post"), asset("design")],
+    dir.path().to_path_buf(),
+  )
+  .await
+  .unwrap();
+  assert_eq!(infos.infos[0].name, "blog_post");
+  assert!(infos.iter().any(|info| info.name == "design"));
+
+  let err = import_notion_zip_files(
+    1,
+    HOST,
+    &uuid::Uuid::new_v4().to_string(),
+    vec![asset("blog_post"), asset("missing")],
+    dir.path().join("missing"),
+  )
+  .await
+  .unwrap_err();
+  assert!(matches!(err.root(), ImporterError::FileNotFound));
+  assert!(err.context_chain()[0].ends_with("missing.zip"));
+}
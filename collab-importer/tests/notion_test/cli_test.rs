@@ -0,0 +1,116 @@
+use collab_importer::cli::{
+  CliArgs, DEFAULT_HOST, ImportSource, matches_glob, read_import_archive, run,
+};
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+#[test]
+fn parse_cli_args_test() {
+  let args = CliArgs::parse(["notion", "export.zip", "--out", "workspace.archive"]).unwrap();
+  assert_eq!(
+    args.source,
+    ImportSource::Notion(PathBuf::from("export.zip"))
+  );
+  assert_eq!(args.out, Some(PathBuf::from("workspace.archive")));
+  assert!(!args.dry_run);
+  assert!(args.includes.is_empty());
+  assert_eq!(args.workspace_id, None);
+  assert_eq!(args.host, DEFAULT_HOST);
+
+  let args = CliArgs::parse([
+    "notion",
+    "export.zip",
+    "--dry-run",
+    "--include",
+    "Team Space/**",
+    "--include",
+    "Wiki",
+    "--workspace-id",
+    "w1",
+  ])
+  .unwrap();
+  assert!(args.dry_run);
+  assert_eq!(args.includes, vec!["Team Space/**", "Wiki"]);
+  assert_eq!(args.workspace_id.as_deref(), Some("w1"));
+
+  assert!(CliArgs::parse(Vec::<String>::new()).is_err());
+  assert!(CliArgs::parse(["trello", "export.json", "--dry-run"]).is_err());
+  // the archive is required without --dry-run
+  assert!(CliArgs::parse(["notion", "export.zip"]).is_err());
+  assert!(CliArgs::parse(["notion", "export.zip", "--out"]).is_err());
+  assert!(CliArgs::parse(["notion", "export.zip", "--dry-run", "--verbose"]).is_err());
+}
+
+#[test]
+fn matches_glob_test() {
+  assert!(matches_glob("Team Space/**", "Team Space"));
+  assert!(matches_glob("Team Space/**", "Team Space/Roadmap/Q1"));
+  assert!(!matches_glob("Team Space/**", "Private"));
+  assert!(matches_glob("Team Space/*", "Team Space/Roadmap"));
+  assert!(!matches_glob("Team Space/*", "Team Space/Roadmap/Q1"));
+  assert!(matches_glob("**/Q?", "Team Space/Roadmap/Q1"));
+  assert!(matches_glob("*Space", "Team Space"));
+  assert!(!matches_glob("*Space", "Team Spaces"));
+}
+
+#[tokio::test]
+async fn write_and_read_import_archive_test() {
+  let dir = tempdir().unwrap();
+  let out = dir.path().join("workspace.archive");
+  let args = CliArgs::parse([
+    "notion",
+    "tests/asset/two_spaces.zip",
+    "--out",
+    out.to_str().unwrap(),
+    "--include",
+    "space one/**",
+  ])
+  .unwrap();
+  let manifest = run(&args, &dir.path().join("unzip")).await.unwrap();
+
+  // only the first space is imported
+  let names = manifest
+    .views
+    .iter()
+    .map(|view| view.name.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(names, vec!["space one", "Blog Post"]);
+  assert!(manifest.collabs.iter().all(|collab| {
+    manifest
+      .views
+      .iter()
+      .any(|view| view.id == collab.object_id)
+  }));
+  // the image of the blog post is in the archive
+  assert!(
+    manifest
+      .collabs
+      .iter()
+      .any(|collab| !collab.files.is_empty())
+  );
+
+  let (read_manifest, collabs) = read_import_archive(&out).unwrap();
+  assert_eq!(read_manifest, manifest);
+  assert_eq!(collabs.len(), manifest.collabs.len());
+  for (collab, entry) in collabs.iter().zip(manifest.collabs.iter()) {
+    assert_eq!(collab.object_id, entry.object_id);
+    assert_eq!(
+      collab.encoded_collab.encode_to_bytes().unwrap().len() as u64,
+      entry.size
+    );
+  }
+
+  // a dry run doesn't write the archive
+  let out = dir.path().join("dry_run.archive");
+  let args = CliArgs::parse([
+    "notion",
+    "tests/asset/two_spaces.zip",
+    "--out",
+    out.to_str().unwrap(),
+    "--dry-run",
+  ])
+  .unwrap();
+  let manifest = run(&args, &dir.path().join("unzip_dry_run")).await.unwrap();
+  assert!(!out.exists());
+  assert!(manifest.views.iter().any(|view| view.name == "space two"));
+}
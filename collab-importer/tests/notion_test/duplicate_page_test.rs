@@ -0,0 +1,71 @@
+use crate::util::sync_unzip_asset;
+use collab_importer::duplicate_page::{
+  DEFAULT_SIMILARITY_THRESHOLD, PageFingerprint, find_near_duplicate_pages,
+  fingerprint_imported_collabs,
+};
+use collab_importer::imported_collab::ImportedCollabInfo;
+use collab_importer::notion::NotionImporter;
+use futures::stream::StreamExt;
+
+const MEETING_NOTES: &str = "Weekly sync. We reviewed the roadmap for the next quarter and agreed \
+to ship the importer first. Action items: write the migration guide, fix the flaky tests, and \
+schedule the design review with the mobile team before the end of the month.";
+
+#[test]
+fn find_near_duplicate_pages_test() {
+  let edited_notes = MEETING_NOTES.replace("end of the month", "end of the week");
+  let fingerprints = vec![
+    PageFingerprint::from_text("1", "Meeting notes", MEETING_NOTES),
+    PageFingerprint::from_text("2", "Meeting notes (copy)", &MEETING_NOTES.to_uppercase()),
+    PageFingerprint::from_text("3", "Meeting notes (edited)", &edited_notes),
+    PageFingerprint::from_text("4", "Recipes", "Mix the flour with the eggs and bake it."),
+    PageFingerprint::from_text("5", "Empty", ""),
+    PageFingerprint::from_text("6", "Also empty", " "),
+  ];
+
+  let report = find_near_duplicate_pages(&fingerprints, DEFAULT_SIMILARITY_THRESHOLD);
+  let pairs = report
+    .pairs
+    .iter()
+    .map(|pair| (pair.view_id.as_str(), pair.duplicate_view_id.as_str()))
+    .collect::<Vec<_>>();
+  assert_eq!(pairs, vec![("1", "2"), ("1", "3"), ("2", "3")]);
+  assert_eq!(report.pairs[0].similarity, 1.0);
+  assert!(report.pairs[1].similarity < 1.0);
+  assert_eq!(report.groups(), vec![vec!["1", "2", "3"]]);
+
+  // Only the identical copies are reported with an exact threshold.
+  let report = find_near_duplicate_pages(&fingerprints, 1.0);
+  assert_eq!(report.groups(), vec![vec!["1", "2"]]);
+}
+
+#[tokio::test]
+async fn fingerprint_imported_collabs_test() {
+  let (_cleaner, file_path) = sync_unzip_asset("blog_post").await.unwrap();
+  let info = NotionImporter::new(
+    1,
+    &file_path,
+    uuid::Uuid::new_v4(),
+    "http://test.appflowy.cloud".to_string(),
+  )
+  .unwrap()
+  .import()
+  .await
+  .unwrap();
+  let root_view_id = info.views()[0].view_id.clone();
+  let infos = info
+    .into_collab_stream()
+    .await
+    .collect::<Vec<ImportedCollabInfo>>()
+    .await;
+
+  let fingerprints = fingerprint_imported_collabs(&infos);
+  let root = fingerprints
+    .iter()
+    .find(|fingerprint| fingerprint.view_id == root_view_id)
+    .unwrap();
+  assert!(!root.is_empty());
+  assert!(root.word_count > 0);
+  assert_eq!(root.similarity(root), 1.0);
+  assert!(find_near_duplicate_pages(&fingerprints, DEFAULT_SIMILARITY_THRESHOLD).is_empty());
+}
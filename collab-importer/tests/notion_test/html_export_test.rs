@@ -0,0 +1,88 @@
+use collab_importer::notion::NotionImporter;
+use tempfile::tempdir;
+
+const PROJECT_ID: &str = "1a2b3c4d5e6f40718293a4b5c6d7e8f9";
+const TASK_ID: &str = "9f8e7d6c5b4a40312213f4e5d6c7b8a9";
+const PNG_BYTES: &[u8] = &[0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 1, 2, 3, 4];
+
+fn project_html() -> String {
+  format!(
+    r#"<html><head><title>Project</title></head><body><article>
+<header><h1 class="page-title">Project</h1></header>
+<div class="page-body">
+<p>See <a href="Project%20{project_id}/Task%20{task_id}.html">Task</a></p>
+<div class="column-list">
+<div class="column" style="width:50%"><p>Left</p></div>
+<div class="column" style="width:50%"><p>Right</p></div>
+</div>
+<p><img src="Project%20{project_id}/diagram.png"></p>
+</div>
+</article></body></html>"#,
+    project_id = PROJECT_ID,
+    task_id = TASK_ID
+  )
+}
+
+#[tokio::test]
+async fn import_notion_html_export_test() {
+  let dir = tempdir().unwrap();
+  let root = dir.path();
+  let project_dir = root.join(format!("Project {}", PROJECT_ID));
+  std::fs::create_dir_all(&project_dir).unwrap();
+  std::fs::write(
+    root.join(format!("Project {}.html", PROJECT_ID)),
+    project_html(),
+  )
+  .unwrap();
+  std::fs::write(
+    project_dir.join(format!("Task {}.html", TASK_ID)),
+    "<html><body><h1>Task</h1><p>Write the plan</p></body></html>",
+  )
+  .unwrap();
+  std::fs::write(project_dir.join("diagram.png"), PNG_BYTES).unwrap();
+  // The index of a workspace export is not a page.
+  std::fs::write(root.join("index.html"), "<html><body></body></html>").unwrap();
+
+  let importer = NotionImporter::new(
+    1,
+    root,
+    uuid::Uuid::new_v4(),
+    "http://test.appflowy.cloud".to_string(),
+  )
+  .unwrap();
+  let info = importer.import().await.unwrap();
+  assert_eq!(info.views().len(), 1);
+  assert_eq!(info.num_of_html(), 2);
+  assert_eq!(info.num_of_markdown(), 0);
+
+  let project = info.views()[0].clone();
+  assert_eq!(project.notion_name, "Project");
+  assert_eq!(project.notion_id.as_deref(), Some(PROJECT_ID));
+  assert!(project.notion_file.is_html());
+  assert_eq!(project.children.len(), 1);
+  let task = &project.children[0];
+  assert_eq!(task.notion_name, "Task");
+  assert!(task.notion_file.is_html());
+
+  let (document, resource) = project.as_document().await.unwrap();
+  assert_eq!(resource.files.len(), 1);
+  assert!(resource.files[0].ends_with("diagram.png"));
+
+  // The link to the sub page is imported as a mention of its view.
+  let data = document.get_document_data().unwrap();
+  let texts = serde_json::to_string(&data.meta.text_map).unwrap();
+  assert!(texts.contains(&task.view_id), "{}", texts);
+
+  // The columns of the html export are imported as columns.
+  assert!(
+    data
+      .blocks
+      .values()
+      .any(|block| block.ty == "simple_columns")
+  );
+  assert!(
+    texts.contains("Left") && texts.contains("Right"),
+    "{}",
+    texts
+  );
+}
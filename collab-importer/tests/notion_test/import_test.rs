@@ -1,12 +1,18 @@
 use crate::util::{async_unzip_asset, setup_log, sync_unzip_asset};
-use collab::preclude::Collab;
+use collab::preclude::{Any, Collab};
+use collab::util::AnyMapExt;
 use collab_database::database::Database;
 use collab_database::entity::FieldType;
 use collab_database::entity::FieldType::*;
 use collab_database::error::DatabaseError;
 use collab_database::fields::media_type_option::MediaCellData;
-use collab_database::fields::{Field, TypeOptionCellReader};
-use collab_database::rows::Row;
+use collab_database::fields::person_type_option::{PersonCellData, WorkspaceMember};
+use collab_database::fields::relation_type_option::RelationTypeOption;
+use collab_database::fields::{Field, FieldVisibility, TypeOptionCellReader, VISIBILITY};
+use collab_database::rows::{DuplicateRowPolicy, Row};
+use collab_database::template::entity::CELL_DATA;
+use collab_database::template::relation_parse::RelationCellData;
+use collab_database::views::FieldSettingsMap;
 use collab_document::blocks::{
   BlockType, extract_page_id_from_block_delta, extract_view_id_from_block_data,
   mention_block_content_from_delta,
@@ -19,6 +25,8 @@ use collab_folder::hierarchy_builder::ParentChildViews;
 use collab_folder::{Folder, View, default_folder_data};
 use collab_importer::error::ImporterError;
 use collab_importer::imported_collab::{ImportType, ImportedCollabInfo, import_notion_zip_file};
+use collab_importer::notion::dedupe::RowDedupeOptions;
+use collab_importer::notion::mapping::ImportMapping;
 use collab_importer::notion::page::NotionPage;
 use collab_importer::notion::{CSVContentCache, NotionImporter, is_csv_contained_cached};
 use collab_importer::util::{CSVRow, parse_csv};
@@ -27,6 +35,7 @@ use collab::core::collab::default_client_id;
 use collab_document::document::Document;
 use futures::stream::StreamExt;
 use percent_encoding::percent_decode_str;
+use serde_json::json;
 use std::collections::{HashMap, HashSet};
 use std::env::temp_dir;
 use std::path::PathBuf;
@@ -114,7 +123,7 @@ async fn import_document_rewrites_local_attachment_href() {
   let pdf_path = root.join(pdf_name);
   tokio::fs::write(&pdf_path, b"%PDF-1.4\n%").await.unwrap();
 
-  let markdown = format!("[My PDF]({})\n", pdf_name);
+  let markdown = format!("See [My PDF]({}) for details\n", pdf_name);
   tokio::fs::write(&md_path, markdown).await.unwrap();
 
   let importer = NotionImporter::new(
@@ -152,6 +161,52 @@ async fn import_document_rewrites_local_attachment_href() {
   assert!(href.contains(&view.view_id));
 }
 
+#[tokio::test]
+async fn import_document_local_attachment_as_file_block() {
+  let dir = tempdir().unwrap();
+  let root = dir.path();
+
+  let page_name = "Attachment Page";
+  let page_id = "103d4deadd2c80d39a5bc34d92cc7321";
+  let md_path = root.join(format!("{} {}.md", page_name, page_id));
+
+  let asset_dir = root.join(page_name);
+  tokio::fs::create_dir_all(&asset_dir).await.unwrap();
+  let content = b"%PDF-1.4\n%";
+  tokio::fs::write(asset_dir.join("Quarterly report.pdf"), content)
+    .await
+    .unwrap();
+
+  let markdown = "[Quarterly report.pdf](Attachment%20Page/Quarterly%20report.pdf)\n";
+  tokio::fs::write(&md_path, markdown).await.unwrap();
+
+  let importer = NotionImporter::new(
+    1,
+    root,
+    uuid::Uuid::new_v4(),
+    "http://test.appflowy.cloud".to_string(),
+  )
+  .unwrap();
+  let info = importer.import().await.unwrap();
+  let view = info.views()[0].clone();
+
+  let (document, resource) = view.as_document().await.unwrap();
+  assert_eq!(resource.files.len(), 1);
+  assert!(resource.files[0].ends_with("Quarterly report.pdf"));
+
+  let page_block_id = document.get_page_id().unwrap();
+  let block_ids = document.get_block_children_ids(&page_block_id);
+  assert_eq!(block_ids.len(), 1);
+
+  let (block_type, data) = document.get_block_data(&block_ids[0]).unwrap();
+  assert_eq!(block_type, BlockType::File);
+  assert_eq!(data["name"], json!("Quarterly report.pdf"));
+  assert_eq!(data["size"], json!(content.len()));
+  let url = data["url"].as_str().unwrap();
+  assert!(url.contains("/api/file_storage/"));
+  assert!(url.contains(&view.view_id));
+}
+
 #[tokio::test]
 async fn import_csv_without_subpage_folder_test() {
   let (_cleaner, file_path_1) = async_unzip_asset("project&task_no_subpages").await.unwrap();
@@ -239,6 +294,106 @@ async fn import_two_spaces_test2() {
   assert!(!collabs.is_empty())
 }
 
+#[tokio::test]
+async fn import_link_to_sibling_page_as_mention_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let export_dir = dir.path().join("Export");
+  std::fs::create_dir_all(&export_dir).unwrap();
+  std::fs::write(
+    export_dir.join("Page A 0a1b2c3d4e5f60718293a4b5c6d7e8f9.md"),
+    "# Page A\n\nSee [Page B](Page%20B%201234567890abcdef1234567890abcdef.md)\n",
+  )
+  .unwrap();
+  std::fs::write(
+    export_dir.join("Page B 1234567890abcdef1234567890abcdef.md"),
+    "# Page B\n\nHello\n",
+  )
+  .unwrap();
+
+  let importer = NotionImporter::new(
+    1,
+    &export_dir,
+    uuid::Uuid::new_v4(),
+    "http://test.appflowy.cloud".to_string(),
+  )
+  .unwrap();
+  let info = importer.import().await.unwrap();
+  let page_a = info
+    .views()
+    .iter()
+    .find(|view| view.notion_name == "Page A")
+    .unwrap();
+  let page_b_view_id = info
+    .view_id_by_notion_id("1234567890abcdef1234567890abcdef")
+    .unwrap();
+
+  let (document, _) = page_a.as_document().await.unwrap();
+  let page_block_id = document.get_page_id().unwrap();
+  let mention_page_ids = document
+    .get_block_children_ids(&page_block_id)
+    .iter()
+    .filter_map(|block_id| document.get_block_delta(block_id))
+    .flat_map(|(_, deltas)| deltas)
+    .filter_map(|delta| mention_block_content_from_delta(&delta))
+    .map(|mention| mention.page_id)
+    .collect::<Vec<_>>();
+  assert_eq!(mention_page_ids, vec![page_b_view_id]);
+}
+
+#[tokio::test]
+async fn import_page_into_existing_view_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let export_dir = dir.path().join("Export");
+  let page_dir = export_dir.join("Project 0a1b2c3d4e5f60718293a4b5c6d7e8f9");
+  std::fs::create_dir_all(&page_dir).unwrap();
+  std::fs::write(
+    export_dir.join("Project 0a1b2c3d4e5f60718293a4b5c6d7e8f9.md"),
+    "# Project\n\nSee [Task](Project%200a1b2c3d4e5f60718293a4b5c6d7e8f9/Task%201234567890abcdef1234567890abcdef.md)\n",
+  )
+  .unwrap();
+  std::fs::write(
+    page_dir.join("Task 1234567890abcdef1234567890abcdef.md"),
+    "# Task\n\nHello\n",
+  )
+  .unwrap();
+  std::fs::write(
+    export_dir.join("Other 2234567890abcdef1234567890abcdef.md"),
+    "# Other\n",
+  )
+  .unwrap();
+
+  let importer = NotionImporter::new(
+    1,
+    &export_dir,
+    uuid::Uuid::new_v4(),
+    "http://test.appflowy.cloud".to_string(),
+  )
+  .unwrap();
+  let info = importer
+    .import_page_into("parent_view", "Project 0a1b2c3d4e5f60718293a4b5c6d7e8f9.md")
+    .await
+    .unwrap();
+
+  // Only the page and its sub pages are imported.
+  assert_eq!(info.views().len(), 1);
+  let project = &info.views()[0];
+  assert_eq!(project.notion_name, "Project");
+  assert_eq!(project.children.len(), 1);
+  assert_eq!(project.children[0].notion_name, "Task");
+  assert!(info.view_id_by_notion_id("2234567890abcdef1234567890abcdef").is_none());
+
+  // The page is a child of the existing view, no space is created.
+  let nested_views = info.build_nested_views().await;
+  assert_eq!(nested_views.views.len(), 1);
+  assert_eq!(nested_views.views[0].view.id, project.view_id);
+  assert_eq!(nested_views.views[0].view.parent_view_id, "parent_view");
+  assert_eq!(nested_views.views[0].children.len(), 1);
+
+  let collabs = info.into_collab_stream().await.collect::<Vec<_>>().await;
+  assert_eq!(collabs.len(), 2);
+  assert!(collabs.iter().all(|collab| collab.name != "Export"));
+}
+
 #[tokio::test]
 async fn import_two_spaces_test() {
   let (_cleaner, file_path) = sync_unzip_asset("two_spaces").await.unwrap();
@@ -409,6 +564,266 @@ async fn import_project_and_task_test() {
   assert_project_and_task(root_view, true).await;
 }
 
+#[tokio::test]
+async fn import_project_and_task_row_dedupe_test() {
+  let (_cleaner, file_path) = sync_unzip_asset("project&task").await.unwrap();
+  let import_tasks = |row_dedupe: RowDedupeOptions| {
+    let file_path = file_path.clone();
+    async move {
+      let import = NotionImporter::new(
+        1,
+        &file_path,
+        uuid::Uuid::new_v4(),
+        "http://test.appflowy.cloud".to_string(),
+      )
+      .unwrap()
+      .with_row_dedupe(row_dedupe)
+      .import()
+      .await
+      .unwrap();
+      import.views()[0]
+        .get_linked_views()
+        .into_iter()
+        .find(|v| v.notion_name == "Tasks")
+        .unwrap()
+        .as_database()
+        .await
+        .unwrap()
+    }
+  };
+
+  // The task names are unique
+  let tasks = import_tasks(RowDedupeOptions::new(DuplicateRowPolicy::Merge)).await;
+  assert_eq!(tasks.database.get_all_row_orders().await.len(), 17);
+
+  // One task by status: Not Started, In Progress and Done
+  let tasks =
+    import_tasks(RowDedupeOptions::new(DuplicateRowPolicy::Skip).with_key_column("status")).await;
+  assert_eq!(tasks.database.get_all_row_orders().await.len(), 3);
+}
+
+#[tokio::test]
+async fn reimport_project_and_task_with_mapping_test() {
+  let (_cleaner, file_path) = sync_unzip_asset("project&task").await.unwrap();
+  let dir = tempdir().unwrap();
+  let mapping_path = dir.path().join("mapping.json");
+  let import = |mapping: ImportMapping| {
+    let file_path = file_path.clone();
+    async move {
+      let import = NotionImporter::new(
+        1,
+        &file_path,
+        uuid::Uuid::new_v4(),
+        "http://test.appflowy.cloud".to_string(),
+      )
+      .unwrap()
+      .with_import_mapping(mapping)
+      .import()
+      .await
+      .unwrap();
+      let tasks = import.views()[0]
+        .get_linked_views()
+        .into_iter()
+        .find(|v| v.notion_name == "Tasks")
+        .unwrap();
+      let database = tasks.as_database().await.unwrap().database;
+      let row_ids = database
+        .get_all_row_orders()
+        .await
+        .into_iter()
+        .map(|row_order| row_order.id)
+        .collect::<Vec<_>>();
+      let ids = (
+        import.views()[0].view_id.clone(),
+        tasks.view_id.clone(),
+        database.get_database_id(),
+        row_ids,
+      );
+      (import.import_mapping().unwrap().clone(), ids)
+    }
+  };
+
+  let (mapping, ids) = import(ImportMapping::load(&mapping_path).await.unwrap()).await;
+  let tasks_database_id = mapping.database_id("76aaf8a4637542ed8175259692ca08bb");
+  assert_eq!(tasks_database_id, Some(ids.2.as_str()));
+  mapping.save(&mapping_path).await.unwrap();
+
+  // The pages, the databases and the rows are imported with the same ids
+  let loaded_mapping = ImportMapping::load(&mapping_path).await.unwrap();
+  assert_eq!(loaded_mapping, mapping);
+  let (reimported_mapping, reimported_ids) = import(loaded_mapping).await;
+  assert_eq!(reimported_ids, ids);
+  assert_eq!(reimported_mapping, mapping);
+}
+
+#[tokio::test]
+async fn import_project_and_task_relations_test() {
+  let (_cleaner, file_path) = sync_unzip_asset("project&task").await.unwrap();
+  let importer = NotionImporter::new(
+    1,
+    &file_path,
+    uuid::Uuid::new_v4(),
+    "http://test.appflowy.cloud".to_string(),
+  )
+  .unwrap()
+  .with_relations(true);
+  let import = importer.import().await.unwrap();
+  let linked_views = import.views()[0].get_linked_views();
+  let projects = linked_views
+    .iter()
+    .find(|v| v.notion_name == "Projects")
+    .unwrap()
+    .as_database()
+    .await
+    .unwrap()
+    .database;
+  let tasks = linked_views
+    .iter()
+    .find(|v| v.notion_name == "Tasks")
+    .unwrap()
+    .as_database()
+    .await
+    .unwrap()
+    .database;
+
+  let relation_database_id = |database: &Database, name: &str| {
+    let field = database
+      .get_all_fields()
+      .into_iter()
+      .find(|field| field.name == name)
+      .unwrap();
+    assert_eq!(FieldType::from(field.field_type), Relation);
+    field
+      .get_type_option::<RelationTypeOption>(Relation.type_id())
+      .unwrap()
+      .database_id
+  };
+  assert_eq!(
+    relation_database_id(&projects, "Tasks"),
+    tasks.get_database_id()
+  );
+  assert_eq!(
+    relation_database_id(&projects, "Blocked By"),
+    projects.get_database_id()
+  );
+  assert_eq!(
+    relation_database_id(&tasks, "Project"),
+    projects.get_database_id()
+  );
+
+  // Research study is related to its four tasks
+  let task_name_field_id = tasks.get_primary_field().unwrap().id;
+  let task_names = tasks
+    .collect_all_rows(false)
+    .await
+    .into_iter()
+    .flatten()
+    .map(|row| {
+      let name = row
+        .cells
+        .get(&task_name_field_id)
+        .and_then(|cell| cell.get_as::<String>(CELL_DATA));
+      (row.id, name.unwrap_or_default())
+    })
+    .collect::<HashMap<_, _>>();
+  let tasks_field_id = projects
+    .get_all_fields()
+    .into_iter()
+    .find(|field| field.name == "Tasks")
+    .unwrap()
+    .id;
+  let primary_field_id = projects.get_primary_field().unwrap().id;
+  let research_study = projects
+    .collect_all_rows(false)
+    .await
+    .into_iter()
+    .flatten()
+    .find(|row| {
+      row
+        .cells
+        .get(&primary_field_id)
+        .and_then(|cell| cell.get_as::<String>(CELL_DATA))
+        .as_deref()
+        == Some("Research study")
+    })
+    .unwrap();
+  let related_tasks = RelationCellData::from(research_study.cells.get(&tasks_field_id).unwrap())
+    .row_ids
+    .iter()
+    .map(|row_id| task_names[row_id].clone())
+    .collect::<Vec<_>>();
+  assert_eq!(
+    related_tasks,
+    vec![
+      "Develop survey questions",
+      "Interpret findings",
+      "Write research report",
+      "Conduct interviews"
+    ]
+  );
+}
+
+#[tokio::test]
+async fn import_project_and_task_people_test() {
+  let (_cleaner, file_path) = sync_unzip_asset("project&task").await.unwrap();
+  let members = vec![
+    WorkspaceMember::new(1, "Nate Martins"),
+    WorkspaceMember::new(2, "Sohrab Amin"),
+    WorkspaceMember::new(3, "Ben Lang"),
+  ];
+  let importer = NotionImporter::new(
+    1,
+    &file_path,
+    uuid::Uuid::new_v4(),
+    "http://test.appflowy.cloud".to_string(),
+  )
+  .unwrap()
+  .with_member_resolver(Arc::new(members));
+  let import = importer.import().await.unwrap();
+  let tasks = import.views()[0]
+    .get_linked_views()
+    .into_iter()
+    .find(|v| v.notion_name == "Tasks")
+    .unwrap()
+    .as_database()
+    .await
+    .unwrap()
+    .database;
+
+  let field_type = |name: &str| {
+    let field = tasks
+      .get_all_fields()
+      .into_iter()
+      .find(|field| field.name == name)
+      .unwrap();
+    FieldType::from(field.field_type)
+  };
+  assert_eq!(field_type("Assignee"), Person);
+  // The statuses are not members
+  assert_ne!(field_type("Status"), Person);
+
+  let assignee_field_id = tasks
+    .get_all_fields()
+    .into_iter()
+    .find(|field| field.name == "Assignee")
+    .unwrap()
+    .id;
+  let uids = tasks
+    .collect_all_rows(false)
+    .await
+    .into_iter()
+    .flatten()
+    .flat_map(|row| {
+      row
+        .cells
+        .get(&assignee_field_id)
+        .map(|cell| PersonCellData::from(cell).uids)
+        .unwrap_or_default()
+    })
+    .collect::<HashSet<_>>();
+  assert_eq!(uids, HashSet::from([1, 2, 3]));
+}
+
 #[tokio::test]
 async fn import_project_and_task_collab_test() {
   let workspace_id = uuid::Uuid::new_v4().to_string();
@@ -656,6 +1071,25 @@ async fn check_project_database(linked_view: &NotionPage, include_sub_dir: bool)
   for (index, field) in csv_file.columns.iter().enumerate() {
     assert_eq!(&fields[index].name, field);
   }
+
+  // the properties missing from the csv of the view are hidden
+  let view_id = content.database.get_first_database_view_id().unwrap();
+  let field_settings = content
+    .database
+    .get_field_settings::<FieldSettingsMap>(&view_id, None);
+  let hidden_fields = fields
+    .iter()
+    .filter(|field| {
+      field_settings[&field.id].get(VISIBILITY)
+        == Some(&Any::BigInt(i64::from(FieldVisibility::AlwaysHidden)))
+    })
+    .map(|field| field.name.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(
+    hidden_fields,
+    vec!["Tasks", "Is Blocking", "Summary", "Checkbox", "Files & media"]
+  );
+
   let expected_files = HashMap::from([
     (
       "DO010003572.jpeg",
@@ -1,2 +1,10 @@
+mod api_import_test;
+mod batch_import_test;
+mod cli_test;
 mod customer_import_test;
+mod duplicate_page_test;
+mod html_export_test;
 mod import_test;
+mod preview_hook_test;
+mod progress_test;
+mod tools_test;
@@ -0,0 +1,82 @@
+use crate::util::sync_unzip_asset;
+use collab_importer::imported_collab::ImportedCollabInfo;
+use collab_importer::notion::NotionImporter;
+use collab_importer::preview::{
+  ImportPreviewHook, ImportedPagePreview, TextSnippetPreviewHook, text_snippet,
+};
+use futures::stream::StreamExt;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Default)]
+struct RecordingHook {
+  previews: Mutex<Vec<ImportedPagePreview>>,
+}
+
+impl ImportPreviewHook for RecordingHook {
+  fn on_page_imported(&self, preview: ImportedPagePreview) {
+    self.previews.lock().unwrap().push(preview);
+  }
+}
+
+#[tokio::test]
+async fn preview_hook_is_called_for_each_imported_page_test() {
+  let (_cleaner, file_path) = sync_unzip_asset("blog_post").await.unwrap();
+  let host = "http://test.appflowy.cloud";
+  let hook = Arc::new(RecordingHook::default());
+  let info = NotionImporter::new(1, &file_path, uuid::Uuid::new_v4(), host.to_string())
+    .unwrap()
+    .with_preview_hook(hook.clone())
+    .import()
+    .await
+    .unwrap();
+  let root_view_id = info.views()[0].view_id.clone();
+  let _ = info
+    .into_collab_stream()
+    .await
+    .collect::<Vec<ImportedCollabInfo>>()
+    .await;
+
+  let previews = hook.previews.lock().unwrap();
+  let root_preview = previews
+    .iter()
+    .find(|preview| preview.view_id == root_view_id)
+    .unwrap();
+  assert!(!root_preview.text.is_empty());
+  let first_image_url = root_preview.first_image_url.as_ref().unwrap();
+  assert!(first_image_url.starts_with(host));
+}
+
+#[tokio::test]
+async fn text_snippet_preview_hook_test() {
+  let (_cleaner, file_path) = sync_unzip_asset("blog_post").await.unwrap();
+  let hook = Arc::new(TextSnippetPreviewHook::new(50));
+  let info = NotionImporter::new(
+    1,
+    &file_path,
+    uuid::Uuid::new_v4(),
+    "http://test.appflowy.cloud".to_string(),
+  )
+  .unwrap()
+  .with_preview_hook(hook.clone())
+  .import()
+  .await
+  .unwrap();
+  let root_view_id = info.views()[0].view_id.clone();
+  let _ = info
+    .into_collab_stream()
+    .await
+    .collect::<Vec<ImportedCollabInfo>>()
+    .await;
+
+  let snippet = hook.snippet(&root_view_id).unwrap();
+  assert!(!snippet.is_empty());
+  assert!(snippet.chars().count() <= 51);
+  assert!(!snippet.contains('\n'));
+}
+
+#[test]
+fn text_snippet_test() {
+  assert_eq!(text_snippet("Hello\n  world", 20), "Hello world");
+  assert_eq!(text_snippet("The quick brown fox jumps", 12), "The quick…");
+  assert_eq!(text_snippet("Supercalifragilistic", 5), "Super…");
+}
@@ -0,0 +1,84 @@
+use crate::util::sync_unzip_asset;
+use collab_importer::error::ImporterError;
+use collab_importer::notion::NotionImporter;
+use collab_importer::progress::ImportProgressTracker;
+use futures::stream::StreamExt;
+use tokio_util::sync::CancellationToken;
+
+const HOST: &str = "http://test.appflowy.cloud";
+
+#[tokio::test]
+async fn import_progress_test() {
+  let (_cleaner, file_path) = sync_unzip_asset("project&task").await.unwrap();
+  let progress = ImportProgressTracker::new();
+  let mut receiver = progress.subscribe();
+  let info = NotionImporter::new(1, &file_path, uuid::Uuid::new_v4(), HOST.to_string())
+    .unwrap()
+    .with_progress(progress.clone())
+    .import()
+    .await
+    .unwrap();
+  assert!(receiver.has_changed().unwrap());
+  let discovered = receiver.borrow_and_update().files_discovered;
+  assert!(discovered >= info.num_of_markdown() + info.num_of_csv());
+  assert_eq!(progress.progress().pages_parsed, 0);
+
+  let num_of_markdown = info.num_of_markdown();
+  let collabs = info.into_collab_stream().await.collect::<Vec<_>>().await;
+  let current = progress.progress();
+  assert!(current.pages_parsed >= num_of_markdown);
+  assert!(current.pages_parsed <= collabs.len());
+  assert!(current.bytes_processed > 0);
+  assert_eq!(current.files_discovered, discovered);
+
+  // the resources are uploaded by the caller
+  progress.resource_uploaded(10);
+  assert_eq!(progress.progress().resources_uploaded, 1);
+  assert_eq!(
+    progress.progress().bytes_processed,
+    current.bytes_processed + 10
+  );
+}
+
+#[tokio::test]
+async fn cancel_import_test() {
+  let (_cleaner, file_path) = sync_unzip_asset("project&task").await.unwrap();
+  let cancel_token = CancellationToken::new();
+  cancel_token.cancel();
+  let err = NotionImporter::new(1, &file_path, uuid::Uuid::new_v4(), HOST.to_string())
+    .unwrap()
+    .with_cancel_token(cancel_token)
+    .import()
+    .await
+    .unwrap_err();
+  assert!(matches!(err, ImporterError::Cancelled));
+  assert_eq!(err.code(), "importer.cancelled");
+
+  let num_of_collabs = NotionImporter::new(1, &file_path, uuid::Uuid::new_v4(), HOST.to_string())
+    .unwrap()
+    .import()
+    .await
+    .unwrap()
+    .into_collab_stream()
+    .await
+    .count()
+    .await;
+
+  // the collabs built before the cancellation are the partial result
+  let cancel_token = CancellationToken::new();
+  let info = NotionImporter::new(1, &file_path, uuid::Uuid::new_v4(), HOST.to_string())
+    .unwrap()
+    .with_cancel_token(cancel_token.clone())
+    .import()
+    .await
+    .unwrap();
+  assert!(!info.is_cancelled());
+  let mut stream = info.into_collab_stream().await;
+  let mut collabs = vec![];
+  while let Some(collab) = stream.next().await {
+    collabs.push(collab);
+    cancel_token.cancel();
+  }
+  assert!(!collabs.is_empty());
+  assert!(collabs.len() < num_of_collabs);
+}
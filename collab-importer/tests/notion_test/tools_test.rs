@@ -0,0 +1,117 @@
+use crate::util::sync_unzip_asset;
+use collab_importer::error::ImporterError;
+use collab_importer::notion::NotionImporter;
+use collab_importer::tools::{
+  DEFAULT_TREE_DEPTH, EXIT_DATA_ERROR, EXIT_ISSUES_FOUND, EXIT_NO_INPUT, ImportVerifier,
+  VerifyArgs, exit_code, verify_imported,
+};
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn verify_duplicate_name_export_test() {
+  let (_cleaner, file_path) = sync_unzip_asset("blog_post_duplicate_name").await.unwrap();
+  let importer = NotionImporter::new(
+    1,
+    &file_path,
+    uuid::Uuid::new_v4(),
+    "http://test.appflowy.cloud".to_string(),
+  )
+  .unwrap();
+  let info = importer.import().await.unwrap();
+
+  let report = verify_imported(&info, DEFAULT_TREE_DEPTH);
+  assert_eq!(report.summary.name, "blog_post_duplicate_name");
+  assert_eq!(report.summary.num_of_top_level_views, 2);
+  assert_eq!(report.tree[0].depth, 0);
+  assert_eq!(report.tree[0].name, "Blog Post");
+  assert!(report.tree[0].notion_id.is_some());
+
+  // the two pages have the same name but different ids
+  assert!(report.duplicate_ids.is_empty());
+  assert_eq!(report.sibling_name_collisions.len(), 1);
+  let collision = &report.sibling_name_collisions[0];
+  assert_eq!(collision.path, "");
+  assert_eq!(collision.name, "blog post");
+  assert_eq!(collision.variants.len(), 2);
+  assert!(
+    collision
+      .to_string()
+      .starts_with("Sibling name collision at '<root>': name='blog post'")
+  );
+  assert_eq!(report.exit_code(), EXIT_ISSUES_FOUND);
+
+  // the tree is cut at the given depth
+  let report = verify_imported(&info, 1);
+  assert!(report.tree.iter().all(|line| line.depth == 0));
+}
+
+#[tokio::test]
+async fn verify_page_files_test() {
+  let dir = tempdir().unwrap();
+  let export_dir = dir.path().join("export");
+  let home_dir = export_dir.join("Home 11111111111111111111111111111111");
+  std::fs::create_dir_all(&home_dir).unwrap();
+  std::fs::write(
+    export_dir.join("Home 11111111111111111111111111111111.md"),
+    "# Home\n\n\
+![photo](Home%2011111111111111111111111111111111/photo.png)\n\n\
+[Empty](Home%2011111111111111111111111111111111/Empty%2033333333333333333333333333333333.md)\n\n\
+[Missing](Missing%2022222222222222222222222222222222.md)\n\n\
+[AppFlowy](https://appflowy.io) [Top](#home)\n",
+  )
+  .unwrap();
+  std::fs::write(home_dir.join("photo.png"), [0u8; 8]).unwrap();
+  std::fs::write(home_dir.join("unused.png"), [0u8; 8]).unwrap();
+  std::fs::write(
+    home_dir.join("Empty 33333333333333333333333333333333.md"),
+    "# Empty\n\n",
+  )
+  .unwrap();
+
+  let info = NotionImporter::new(
+    1,
+    &export_dir,
+    uuid::Uuid::new_v4(),
+    "http://test.appflowy.cloud".to_string(),
+  )
+  .unwrap()
+  .import()
+  .await
+  .unwrap();
+  let report = ImportVerifier::new().verify(&info);
+
+  assert_eq!(report.broken_links.len(), 1);
+  assert_eq!(report.broken_links[0].page_path, "Home");
+  assert_eq!(
+    report.broken_links[0].link,
+    "Missing%2022222222222222222222222222222222.md"
+  );
+  assert_eq!(report.unreferenced_assets.len(), 1);
+  assert!(report.unreferenced_assets[0].file.ends_with("unused.png"));
+  assert_eq!(report.empty_pages.len(), 1);
+  assert_eq!(report.empty_pages[0].page_path, "Home/Empty");
+  assert_eq!(report.exit_code(), EXIT_ISSUES_FOUND);
+}
+
+#[test]
+fn parse_verify_args_test() {
+  let args = VerifyArgs::parse(["export.zip"]).unwrap();
+  assert_eq!(args.zip_path, PathBuf::from("export.zip"));
+  assert_eq!(args.max_depth, DEFAULT_TREE_DEPTH);
+
+  let args = VerifyArgs::parse(["--depth", "2", "export.zip"]).unwrap();
+  assert_eq!(args.max_depth, 2);
+
+  assert!(VerifyArgs::parse(Vec::<String>::new()).is_err());
+  assert!(VerifyArgs::parse(["export.zip", "--depth"]).is_err());
+  assert!(VerifyArgs::parse(["export.zip", "--verbose"]).is_err());
+  assert!(VerifyArgs::parse(["a.zip", "b.zip"]).is_err());
+}
+
+#[test]
+fn exit_code_test() {
+  assert_eq!(exit_code(&ImporterError::FileNotFound), EXIT_NO_INPUT);
+  let err = ImporterError::ParseEnexError("oops".to_string()).context("import notes.enex");
+  assert_eq!(exit_code(&err), EXIT_DATA_ERROR);
+}
@@ -0,0 +1 @@
+mod opml_import_test;
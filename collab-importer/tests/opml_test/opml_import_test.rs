@@ -0,0 +1,149 @@
+use crate::util::document_data;
+use collab_document::blocks::{Block, BlockType, DocumentData};
+use collab_document::provenance::BlockProvenance;
+use collab_importer::error::ImporterError;
+use collab_importer::opml::parser::parse_opml;
+use collab_importer::opml::{OpmlImportMode, OpmlImporter};
+use serde_json::{Value, json};
+
+const OUTLINE_OPML: &str = r#"<?xml version="1.0"?>
+<opml version="2.0">
+  <head><title>Plans &amp; Ideas</title></head>
+  <body>
+    <outline text="Trip" _note="Book early&#10;Pack light">
+      <outline text="Flights" _complete="true"/>
+      <outline text="Hotel" checkbox="true" complete="true"/>
+      <outline text="Days">
+        <outline text="Hiking"/>
+      </outline>
+    </outline>
+    <outline text="Work">
+      <outline text="Review" _status="unchecked"/>
+    </outline>
+  </body>
+</opml>"#;
+
+fn children<'a>(data: &'a DocumentData, block_id: &str) -> Vec<&'a Block> {
+  let block = &data.blocks[block_id];
+  data.meta.children_map[&block.children]
+    .iter()
+    .map(|id| &data.blocks[id])
+    .collect()
+}
+
+fn delta(data: &DocumentData, block: &Block) -> Value {
+  let text = &data.meta.text_map.as_ref().unwrap()[block.external_id.as_ref().unwrap()];
+  serde_json::from_str(text).unwrap()
+}
+
+#[test]
+fn parse_opml_test() {
+  let document = parse_opml(OUTLINE_OPML).unwrap();
+  assert_eq!(document.title.as_deref(), Some("Plans & Ideas"));
+  assert_eq!(document.outlines.len(), 2);
+  let trip = &document.outlines[0];
+  assert_eq!(trip.note.as_deref(), Some("Book early\nPack light"));
+  assert!(trip.children[0].completed && !trip.children[0].checkbox);
+  assert!(trip.children[1].completed && trip.children[1].checkbox);
+  assert_eq!(trip.children[2].children[0].text, "Hiking");
+  let review = &document.outlines[1].children[0];
+  assert!(review.checkbox && !review.completed);
+
+  let err = parse_opml("<opml><body><outline text=\"a\"></body>").unwrap_err();
+  assert_eq!(err.code(), "importer.parse_opml_failed");
+  assert!(parse_opml("<html></html>").is_err());
+}
+
+#[test]
+fn import_opml_as_single_document_test() {
+  let info = OpmlImporter::new(1, "workspace_id")
+    .import_opml("plans", OUTLINE_OPML)
+    .unwrap();
+  assert_eq!(info.name, "Plans & Ideas");
+  assert_eq!(info.num_of_pages(), 1);
+
+  let data = document_data(&info.collab_info);
+  let top = children(&data, &data.page_id);
+  assert_eq!(top.len(), 2);
+  assert_eq!(top[0].ty, BlockType::BulletedList.as_str());
+  assert_eq!(delta(&data, top[0]), json!([{ "insert": "Trip" }]));
+
+  // the note comes first, then the nested outlines
+  let trip = children(&data, &top[0].id);
+  assert_eq!(trip[0].ty, BlockType::Paragraph.as_str());
+  assert_eq!(delta(&data, trip[1]), json!([{ "insert": "Pack light" }]));
+  assert_eq!(
+    delta(&data, trip[2]),
+    json!([{ "insert": "Flights", "attributes": { "strikethrough": true } }])
+  );
+  assert_eq!(trip[3].ty, BlockType::TodoList.as_str());
+  assert_eq!(trip[3].data["checked"], json!(true));
+  assert_eq!(trip[4].ty, BlockType::BulletedList.as_str());
+  assert_eq!(
+    children(&data, &trip[4].id)[0].ty,
+    BlockType::BulletedList.as_str()
+  );
+
+  let provenance = BlockProvenance::from_block(top[0]).unwrap();
+  assert_eq!(provenance.importer, "opml");
+  assert_eq!(provenance.source_path, "plans.opml");
+}
+
+#[test]
+fn import_opml_document_per_outline_test() {
+  let info = OpmlImporter::new(1, "workspace_id")
+    .with_mode(OpmlImportMode::DocumentPerOutline)
+    .with_toggle_lists(true)
+    .import_opml("plans", OUTLINE_OPML)
+    .unwrap();
+  assert_eq!(info.num_of_pages(), 3);
+  let titles = info
+    .pages
+    .iter()
+    .map(|page| page.title.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(titles, vec!["Trip", "Work"]);
+
+  // the outline is the title, its note and its children are the content
+  let data = document_data(&info.pages[0].collab_info);
+  let blocks = children(&data, &data.page_id);
+  assert_eq!(blocks.len(), 5);
+  assert_eq!(delta(&data, blocks[0]), json!([{ "insert": "Book early" }]));
+  let days = blocks[4];
+  assert_eq!(days.ty, BlockType::ToggleList.as_str());
+  assert_eq!(days.data["collapsed"], json!(true));
+  assert_eq!(
+    children(&data, &days.id)[0].ty,
+    BlockType::BulletedList.as_str()
+  );
+
+  let views = info.build_nested_views();
+  assert_eq!(views.views.len(), 1);
+  assert_eq!(views.views[0].view.parent_view_id, "workspace_id");
+  assert_eq!(views.views[0].children[1].view.id, info.pages[1].view_id);
+  assert_eq!(views.views[0].children[1].view.parent_view_id, info.view_id);
+
+  let names = info
+    .into_collab_infos()
+    .into_iter()
+    .map(|info| info.name)
+    .collect::<Vec<_>>();
+  assert_eq!(names, vec!["Plans & Ideas", "Trip", "Work"]);
+}
+
+#[test]
+fn import_opml_file_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let path = dir.path().join("outline.opml");
+  std::fs::write(&path, "<opml><body><outline text=\"only\"/></body></opml>").unwrap();
+  let info = OpmlImporter::new(1, "workspace_id")
+    .import_file(&path)
+    .unwrap();
+  // named after the file when it has no title
+  assert_eq!(info.name, "outline");
+
+  let err = OpmlImporter::new(1, "workspace_id")
+    .import_file(dir.path().join("missing.opml"))
+    .unwrap_err();
+  assert!(matches!(err, ImporterError::FileNotFound));
+}
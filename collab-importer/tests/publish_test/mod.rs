@@ -0,0 +1 @@
+mod publish_site_test;
@@ -0,0 +1,112 @@
+use collab_document::blocks::{Block, BlockType, DocumentData, DocumentDataBuilder, NewBlock};
+use collab_folder::hierarchy_builder::ParentChildViews;
+use collab_folder::{View, ViewLayout};
+use collab_importer::publish::{PublishSource, publish_static_site};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+struct TestSource {
+  documents: HashMap<String, DocumentData>,
+  asset_dir: PathBuf,
+}
+
+impl PublishSource for TestSource {
+  fn document_data(&self, view_id: &str) -> Option<DocumentData> {
+    self.documents.get(view_id).cloned()
+  }
+
+  fn asset_path(&self, url: &str) -> Option<PathBuf> {
+    (!url.starts_with("http")).then(|| self.asset_dir.join(url))
+  }
+}
+
+fn node(id: &str, parent_id: &str, name: &str, layout: ViewLayout) -> ParentChildViews {
+  ParentChildViews {
+    view: View::new(
+      id.to_string(),
+      parent_id.to_string(),
+      name.to_string(),
+      layout,
+      None,
+    ),
+    children: vec![],
+  }
+}
+
+#[test]
+fn publish_static_site_test() {
+  let asset_dir = tempfile::tempdir().unwrap();
+  std::fs::write(asset_dir.path().join("photo 1.png"), [1, 2, 3]).unwrap();
+  let out_dir = tempfile::tempdir().unwrap();
+
+  let mut guide = node("guide", "handbook", "Guide", ViewLayout::Document);
+  guide
+    .children
+    .push(node("deep", "guide", "Deep <Dive>", ViewLayout::Document));
+  let mut root = node("handbook", "workspace", "Handbook", ViewLayout::Document);
+  root.children.push(guide);
+  root
+    .children
+    .push(node("tasks", "handbook", "Tasks", ViewLayout::Grid));
+
+  let handbook = DocumentDataBuilder::new()
+    .with_block(NewBlock::new(BlockType::Paragraph).with_delta(json!([
+      { "insert": "Read the " },
+      {
+        "insert": "$",
+        "attributes": { "mention": { "type": "page", "page_id": "guide" } }
+      },
+    ])))
+    .with_block(NewBlock::new(BlockType::SubPage).with_data("viewId", json!("deep")))
+    .with_block(Block::image("photo 1.png"))
+    .with_block(Block::image("https://appflowy.io/logo.png"))
+    .build();
+  let guide = DocumentDataBuilder::new()
+    .with_block(Block::paragraph("How to start"))
+    .build();
+  let source = TestSource {
+    documents: HashMap::from([
+      ("handbook".to_string(), handbook),
+      ("guide".to_string(), guide),
+    ]),
+    asset_dir: asset_dir.path().to_path_buf(),
+  };
+
+  let site = publish_static_site(&root, &source, out_dir.path()).unwrap();
+  let paths = site
+    .pages
+    .iter()
+    .map(|page| page.path.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(
+    paths,
+    vec!["index.html", "guide.html", "deep.html", "tasks.html"]
+  );
+  assert_eq!(site.assets.len(), 1);
+  assert!(site.assets[0].starts_with("assets/"));
+  assert!(site.assets[0].ends_with("-photo-1.png"));
+  assert_eq!(
+    std::fs::read(out_dir.path().join(&site.assets[0])).unwrap(),
+    vec![1, 2, 3]
+  );
+
+  let index = std::fs::read_to_string(out_dir.path().join("index.html")).unwrap();
+  assert!(index.contains("<title>Handbook</title>"));
+  // the mention and the subpage block link to the pages
+  assert!(index.contains("Read the <a href=\"guide.html\">Guide</a>"));
+  assert!(index.contains("<a href=\"deep.html\">Deep &lt;Dive&gt;</a>"));
+  assert!(index.contains(&format!("src=\"{}\"", site.assets[0])));
+  assert!(index.contains("src=\"https://appflowy.io/logo.png\""));
+  assert!(index.contains("<a href=\"index.html\" aria-current=\"page\">Handbook</a>"));
+
+  let deep = std::fs::read_to_string(out_dir.path().join("deep.html")).unwrap();
+  assert!(deep.contains(
+    "<nav class=\"breadcrumbs\"><a href=\"index.html\">Handbook</a> / <a href=\"guide.html\">Guide</a> / Deep &lt;Dive&gt;</nav>"
+  ));
+  assert!(deep.contains("<a href=\"deep.html\" aria-current=\"page\">Deep &lt;Dive&gt;</a>"));
+
+  // the database has a page listing nothing but its title and the navigation
+  let tasks = std::fs::read_to_string(out_dir.path().join("tasks.html")).unwrap();
+  assert!(tasks.contains("<main>\n<h1>Tasks</h1>\n</main>"));
+}
@@ -0,0 +1 @@
+mod roam_import_test;
@@ -0,0 +1,242 @@
+use crate::util::document_data;
+use chrono::NaiveDate;
+use collab_document::blocks::{Block, BlockType, DocumentData};
+use collab_folder::ViewLayout;
+use collab_importer::error::ImporterError;
+use collab_importer::roam::RoamImporter;
+use collab_importer::roam::parser::{parse_roam_edn, parse_roam_json};
+use serde_json::{Value, json};
+
+const GRAPH_JSON: &str = r#"[
+  {
+    "title": "Project",
+    "uid": "projectuid",
+    "create-time": 1700000000000,
+    "children": [
+      {
+        "string": "Goals",
+        "uid": "goals-uid",
+        "heading": 2,
+        "children": [{ "string": "Ship **v1** with __care__", "uid": "ship-uid" }]
+      },
+      {
+        "string": "{{[[DONE]]}} Write the spec",
+        "uid": "spec-uid",
+        "children": [{ "string": "See [docs](https://appflowy.io) #Missing", "uid": "docs-uid" }]
+      },
+      { "string": "```rust\nfn main() {}\n```", "uid": "code-uid" }
+    ]
+  },
+  {
+    "title": "October 15th, 2026",
+    "children": [{ "string": "Started [[Project]]", "uid": "started-uid" }]
+  },
+  {
+    "title": "October 16th, 2026",
+    "uid": "10-16-2026",
+    "children": [{ "string": "Follow ((ship-uid)) and ((unknown))", "uid": "follow-uid" }]
+  }
+]"#;
+
+const GRAPH_EDN: &str = r#"#datascript/DB {:schema {:block/uid {:db/unique :db.unique/identity}},
+ :datoms [[1 :node/title "Inbox" 536870913]
+          [1 :block/uid "inbox-uid" 536870913]
+          [1 :block/children 3 536870913]
+          [1 :block/children 2 536870913]
+          [2 :block/string "first \"quoted\"" 536870913]
+          [2 :block/order 0 536870913]
+          [3 :block/string "second" 536870913]
+          [3 :block/order 1 536870913]
+          [3 :block/heading 1 536870913]
+          [3 :block/children 4 536870913]
+          [4 :block/string "nested" 536870913]
+          [4 :block/order 0 536870913]
+          ; a comment
+          [5 :node/title "10/16/2026" 536870913]
+          [5 :block/uid "10-16-2026" 536870913]]}"#;
+
+fn children<'a>(data: &'a DocumentData, block_id: &str) -> Vec<&'a Block> {
+  let block = &data.blocks[block_id];
+  data.meta.children_map[&block.children]
+    .iter()
+    .map(|id| &data.blocks[id])
+    .collect()
+}
+
+fn delta(data: &DocumentData, block: &Block) -> Value {
+  let text = &data.meta.text_map.as_ref().unwrap()[block.external_id.as_ref().unwrap()];
+  serde_json::from_str(text).unwrap()
+}
+
+#[test]
+fn parse_roam_json_test() {
+  let pages = parse_roam_json(GRAPH_JSON).unwrap();
+  assert_eq!(pages.len(), 3);
+  assert_eq!(pages[0].children[0].heading, Some(2));
+  assert_eq!(pages[0].daily_note_date(), None);
+  // by title, or by uid
+  assert_eq!(
+    pages[1].daily_note_date(),
+    NaiveDate::from_ymd_opt(2026, 10, 15)
+  );
+  assert_eq!(
+    pages[2].daily_note_date(),
+    NaiveDate::from_ymd_opt(2026, 10, 16)
+  );
+
+  let err = parse_roam_json("{").unwrap_err();
+  assert_eq!(err.code(), "importer.parse_roam_failed");
+}
+
+#[test]
+fn parse_roam_edn_test() {
+  let pages = parse_roam_edn(GRAPH_EDN).unwrap();
+  assert_eq!(pages.len(), 2);
+  let inbox = &pages[0];
+  assert_eq!(inbox.title, "Inbox");
+  assert_eq!(inbox.uid.as_deref(), Some("inbox-uid"));
+  // the children are ordered by their order, not by the datoms
+  let strings = inbox
+    .children
+    .iter()
+    .map(|block| block.string.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(strings, vec!["first \"quoted\"", "second"]);
+  assert_eq!(inbox.children[1].heading, Some(1));
+  assert_eq!(inbox.children[1].children[0].string, "nested");
+  assert_eq!(
+    pages[1].daily_note_date(),
+    NaiveDate::from_ymd_opt(2026, 10, 16)
+  );
+
+  let err = parse_roam_edn("{:datoms [[1 :node/title").unwrap_err();
+  assert!(matches!(err, ImporterError::ParseRoamError(_)));
+}
+
+#[test]
+fn import_roam_graph_test() {
+  let pages = parse_roam_json(GRAPH_JSON).unwrap();
+  let info = RoamImporter::new(1, "workspace_id")
+    .import_pages(&pages)
+    .unwrap();
+  assert_eq!(info.num_of_pages(), 3);
+  assert_eq!(info.pages.len(), 1);
+  let project = &info.pages[0];
+  assert_eq!(project.title, "Project");
+
+  // the daily notes are in the journal, the latest first
+  let journal = info.journal.as_ref().unwrap();
+  let titles = journal
+    .pages
+    .iter()
+    .map(|page| page.title.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(titles, vec!["October 16th, 2026", "October 15th, 2026"]);
+  let views = info.build_nested_views();
+  assert_eq!(views.views.len(), 2);
+  assert_eq!(views.views[0].view.id, journal.view_id);
+  assert_eq!(views.views[0].view.layout, ViewLayout::Document);
+  assert!(
+    views.views[0]
+      .view
+      .extra
+      .as_ref()
+      .unwrap()
+      .contains("is_space")
+  );
+  assert_eq!(views.views[0].children.len(), 2);
+  assert_eq!(views.views[1].view.id, project.view_id);
+
+  // the outline
+  let data = document_data(&project.collab_info);
+  let top_level = children(&data, &data.page_id);
+  let types = top_level
+    .iter()
+    .map(|block| block.ty.clone())
+    .collect::<Vec<_>>();
+  // the children of the heading follow it
+  assert_eq!(
+    types,
+    vec![
+      BlockType::Heading.to_string(),
+      BlockType::BulletedList.to_string(),
+      BlockType::TodoList.to_string(),
+      BlockType::Code.to_string(),
+    ]
+  );
+  assert_eq!(top_level[0].data["level"], json!(2));
+  assert_eq!(top_level[2].data["checked"], json!(true));
+  assert_eq!(top_level[3].data["language"], json!("rust"));
+  assert_eq!(
+    delta(&data, top_level[1]),
+    json!([
+      { "insert": "Ship " },
+      { "insert": "v1", "attributes": { "bold": true } },
+      { "insert": " with " },
+      { "insert": "care", "attributes": { "italic": true } }
+    ])
+  );
+  assert_eq!(
+    delta(&data, top_level[2]),
+    json!([{ "insert": "Write the spec" }])
+  );
+  let nested = children(&data, &top_level[2].id);
+  assert_eq!(nested.len(), 1);
+  assert_eq!(
+    delta(&data, nested[0]),
+    json!([
+      { "insert": "See " },
+      { "insert": "docs", "attributes": { "href": "https://appflowy.io" } },
+      { "insert": " Missing" }
+    ])
+  );
+
+  // the links and the block references become mentions
+  let started = document_data(&journal.pages[1].collab_info);
+  let block = children(&started, &started.page_id)[0];
+  assert_eq!(
+    delta(&started, block)[1],
+    json!({ "insert": "$", "attributes": { "mention": { "type": "page", "page_id": project.view_id } } })
+  );
+  let follow = document_data(&journal.pages[0].collab_info);
+  let block = children(&follow, &follow.page_id)[0];
+  let ops = delta(&follow, block);
+  assert_eq!(
+    ops[1]["attributes"]["mention"],
+    json!({ "type": "page", "page_id": project.view_id, "block_id": top_level[1].id })
+  );
+  assert_eq!(ops[2], json!({ "insert": " and ((unknown))" }));
+
+  // the journal comes before the daily notes
+  let names = info
+    .into_collab_infos()
+    .into_iter()
+    .map(|info| info.name)
+    .collect::<Vec<_>>();
+  assert_eq!(
+    names,
+    vec![
+      "Journal",
+      "October 16th, 2026",
+      "October 15th, 2026",
+      "Project"
+    ]
+  );
+}
+
+#[test]
+fn import_roam_file_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let path = dir.path().join("graph.edn");
+  std::fs::write(&path, GRAPH_EDN).unwrap();
+  let info = RoamImporter::new(1, "workspace_id")
+    .import_file(&path)
+    .unwrap();
+  assert_eq!(info.pages.len(), 1);
+  assert_eq!(info.journal.unwrap().pages.len(), 1);
+
+  let err = RoamImporter::new(1, "workspace_id")
+    .import_file(dir.path().join("missing.json"))
+    .unwrap_err();
+  assert!(matches!(err, ImporterError::FileNotFound));
+}
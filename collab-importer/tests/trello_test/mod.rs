@@ -0,0 +1 @@
+mod trello_import_test;
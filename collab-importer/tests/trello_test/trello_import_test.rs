@@ -0,0 +1,196 @@
+use collab::core::collab::{DataSource, default_client_id};
+use collab::core::origin::CollabOrigin;
+use collab_document::blocks::BlockType;
+use collab_document::document::Document;
+use collab_entity::CollabType;
+use collab_folder::ViewLayout;
+use collab_importer::error::ImporterError;
+use collab_importer::imported_collab::ImportType;
+use collab_importer::trello::TrelloImporter;
+use collab_importer::trello::parser::parse_trello;
+
+const BOARD_JSON: &str = r#"{
+  "id": "board",
+  "name": "Roadmap",
+  "desc": "",
+  "members": [
+    { "id": "member_nate", "fullName": "Nate Martins", "username": "nate" },
+    { "id": "member_ben", "fullName": "Ben Lang", "username": "benlang" }
+  ],
+  "labels": [
+    { "id": "label_bug", "name": "Bug", "color": "red" },
+    { "id": "label_green", "name": "", "color": "green_dark" }
+  ],
+  "lists": [
+    { "id": "list_done", "name": "Done", "closed": false, "pos": 2048 },
+    { "id": "list_todo", "name": "To Do", "closed": false, "pos": 1024 },
+    { "id": "list_old", "name": "Old", "closed": true, "pos": 512 }
+  ],
+  "cards": [
+    {
+      "id": "card_release",
+      "name": "Release",
+      "desc": "Ship the **release**",
+      "closed": false,
+      "idList": "list_done",
+      "idLabels": ["label_green"],
+      "idMembers": ["member_nate", "member_ben"],
+      "due": "2024-05-01T10:00:00.000Z",
+      "dueComplete": true,
+      "pos": 1
+    },
+    {
+      "id": "card_fix",
+      "name": "Fix crash",
+      "desc": "",
+      "closed": false,
+      "idList": "list_todo",
+      "idLabels": ["label_bug", "label_green"],
+      "due": null,
+      "pos": 20
+    },
+    {
+      "id": "card_plan",
+      "name": "Plan",
+      "desc": "",
+      "closed": false,
+      "idList": "list_todo",
+      "idLabels": [],
+      "pos": 10
+    },
+    { "id": "card_archived", "name": "Archived", "closed": true, "idList": "list_todo", "pos": 5 },
+    { "id": "card_old", "name": "In old list", "closed": false, "idList": "list_old", "pos": 1 }
+  ],
+  "checklists": [
+    {
+      "id": "checklist_qa",
+      "name": "QA",
+      "idCard": "card_plan",
+      "pos": 2,
+      "checkItems": [
+        { "id": "item_2", "name": "Test", "state": "incomplete", "pos": 2 },
+        { "id": "item_1", "name": "Review", "state": "complete", "pos": 1 }
+      ]
+    },
+    {
+      "id": "checklist_docs",
+      "name": "Docs",
+      "idCard": "card_plan",
+      "pos": 1,
+      "checkItems": [{ "id": "item_3", "name": "Write", "state": "complete", "pos": 1 }]
+    }
+  ]
+}"#;
+
+#[test]
+fn parse_trello_board_test() {
+  let board = parse_trello(BOARD_JSON).unwrap();
+  assert_eq!(board.name, "Roadmap");
+
+  // the archived lists and cards are skipped, the cards are ordered by list and position
+  let lists = board
+    .open_lists()
+    .into_iter()
+    .map(|list| list.name.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(lists, vec!["To Do", "Done"]);
+  let cards = board
+    .open_cards()
+    .into_iter()
+    .map(|card| card.name.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(cards, vec!["Plan", "Fix crash", "Release"]);
+
+  let release = board.open_cards()[2];
+  assert_eq!(release.due_timestamp(), Some(1714557600));
+  assert!(release.due_complete);
+  assert_eq!(release.id_members, vec!["member_nate", "member_ben"]);
+  assert_eq!(board.members[1].full_name, "Ben Lang");
+  let checklists = board.card_checklists("card_plan");
+  assert_eq!(checklists[0].name, "Docs");
+  let items = checklists[1]
+    .items()
+    .into_iter()
+    .map(|item| (item.name.as_str(), item.is_complete()))
+    .collect::<Vec<_>>();
+  assert_eq!(items, vec![("Review", true), ("Test", false)]);
+
+  let err = parse_trello("{ \"name\": ").unwrap_err();
+  assert!(matches!(err, ImporterError::ParseTrelloError(_)));
+  assert_eq!(err.code(), "importer.parse_trello_failed");
+}
+
+#[tokio::test]
+async fn import_trello_board_test() {
+  let board = parse_trello(BOARD_JSON).unwrap();
+  let importer = TrelloImporter::new(1, "workspace_id");
+  let imported = importer.import_board(&board).await.unwrap();
+  assert_eq!(imported.name, "Roadmap");
+  assert_eq!(imported.num_of_cards, 3);
+
+  // the board is a database with a board view
+  let views = imported.build_nested_views();
+  assert_eq!(views.views.len(), 1);
+  assert_eq!(views.views[0].view.id, imported.view_id);
+  assert_eq!(views.views[0].view.parent_view_id, "workspace_id");
+  assert_eq!(views.views[0].view.layout, ViewLayout::Board);
+
+  let info = &imported.collab_info;
+  let ImportType::Database {
+    database_id,
+    view_ids,
+    row_document_ids,
+  } = &info.import_type
+  else {
+    panic!("expected a database import");
+  };
+  assert_eq!(database_id, &imported.database_id);
+  assert_eq!(view_ids, &vec![imported.view_id.clone()]);
+  let num_of_rows = info
+    .imported_collabs
+    .iter()
+    .filter(|collab| collab.collab_type == CollabType::DatabaseRow)
+    .count();
+  assert_eq!(num_of_rows, 3);
+
+  // only the cards with a description or several checklists have a row document
+  assert_eq!(row_document_ids.len(), 2);
+  let documents = info
+    .imported_collabs
+    .iter()
+    .filter(|collab| collab.collab_type == CollabType::Document)
+    .collect::<Vec<_>>();
+  assert_eq!(documents.len(), 2);
+
+  // the checklists of the plan card are kept as named todo lists
+  let todo_counts = documents
+    .iter()
+    .map(|collab| {
+      let document = Document::open_with_options(
+        CollabOrigin::Empty,
+        DataSource::DocStateV1(collab.encoded_collab.doc_state.to_vec()),
+        &collab.object_id,
+        default_client_id(),
+      )
+      .unwrap();
+      let data = document.get_document_data().unwrap();
+      data
+        .blocks
+        .values()
+        .filter(|block| block.ty == BlockType::TodoList.to_string())
+        .count()
+    })
+    .collect::<Vec<_>>();
+  assert!(todo_counts.contains(&3));
+  assert!(todo_counts.contains(&0));
+}
+
+#[tokio::test]
+async fn import_trello_missing_file_test() {
+  let importer = TrelloImporter::new(1, "workspace_id");
+  let err = importer
+    .import_file("not_exist_trello.json")
+    .await
+    .unwrap_err();
+  assert!(matches!(err, ImporterError::FileNotFound));
+}
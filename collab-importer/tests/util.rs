@@ -1,6 +1,11 @@
 use std::env::temp_dir;
 
 use async_zip::base::read::stream::ZipFileReader;
+use collab::core::collab::{DataSource, default_client_id};
+use collab::core::origin::CollabOrigin;
+use collab_document::blocks::DocumentData;
+use collab_document::document::Document;
+use collab_importer::imported_collab::ImportedCollabInfo;
 use collab_importer::zip_tool::async_zip::async_unzip;
 use collab_importer::zip_tool::sync_zip::sync_unzip;
 use std::path::PathBuf;
@@ -99,3 +104,15 @@ pub fn setup_log() {
     subscriber.try_init().unwrap();
   });
 }
+
+pub fn document_data(info: &ImportedCollabInfo) -> DocumentData {
+  let collab = &info.imported_collabs[0];
+  let document = Document::open_with_options(
+    CollabOrigin::Empty,
+    DataSource::DocStateV1(collab.encoded_collab.doc_state.to_vec()),
+    &collab.object_id,
+    default_client_id(),
+  )
+  .unwrap();
+  document.get_document_data().unwrap()
+}
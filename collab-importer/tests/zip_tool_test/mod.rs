@@ -0,0 +1,18 @@
+mod multi_part_test;
+mod safe_unzip_test;
+
+use std::io::{Cursor, Write};
+use std::path::Path;
+use zip::CompressionMethod;
+use zip::write::FileOptions;
+
+pub(crate) fn write_zip(path: &Path, entries: &[(&str, Vec<u8>)]) {
+  let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+  let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+  for (name, content) in entries {
+    writer.start_file(*name, options).unwrap();
+    writer.write_all(content).unwrap();
+  }
+  let bytes = writer.finish().unwrap().into_inner();
+  std::fs::write(path, bytes).unwrap();
+}
@@ -0,0 +1,177 @@
+use crate::util::sync_unzip_asset;
+use crate::zip_tool_test::write_zip;
+use collab_importer::error::{ImporterError, UnsafeArchiveError};
+use collab_importer::notion::NotionImporter;
+use collab_importer::zip_tool::multi_part::{part_number, unzip_multi_part, zip_parts_in_dir};
+use collab_importer::zip_tool::safe_unzip::UnzipLimits;
+use std::io::Read;
+use std::path::Path;
+
+const HOST: &str = "http://test.appflowy.cloud";
+
+#[test]
+fn part_number_test() {
+  assert_eq!(part_number("Export-abc-Part-2.zip"), Some(2));
+  assert_eq!(part_number("export.part10.zip"), Some(10));
+  assert_eq!(part_number("export_3.zip"), Some(3));
+  assert_eq!(part_number("export(4).zip"), Some(4));
+  assert_eq!(part_number("export.zip"), None);
+}
+
+#[tokio::test]
+async fn unzip_parts_with_shared_root_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let parts_dir = dir.path().join("parts");
+  std::fs::create_dir(&parts_dir).unwrap();
+  write_zip(
+    &parts_dir.join("Export-abc-Part-2.zip"),
+    &[
+      ("Export-abc/Page/Sub.md", b"# Sub".to_vec()),
+      ("Export-abc/Page.md", b"# Page v2".to_vec()),
+    ],
+  );
+  write_zip(
+    &parts_dir.join("Export-abc-Part-1.zip"),
+    &[
+      ("Export-abc/Page.md", b"# Page".to_vec()),
+      ("Export-abc/Page/image.png", vec![1, 2, 3]),
+      ("__MACOSX/Export-abc/._Page.md", vec![0]),
+    ],
+  );
+  std::fs::write(parts_dir.join("notes.txt"), "not a part").unwrap();
+
+  let parts = zip_parts_in_dir(&parts_dir).await.unwrap();
+  let names = parts
+    .iter()
+    .map(|part| part.file_name().unwrap().to_str().unwrap())
+    .collect::<Vec<_>>();
+  assert_eq!(
+    names,
+    vec!["Export-abc-Part-1.zip", "Export-abc-Part-2.zip"]
+  );
+
+  let out_dir = dir.path().join("out");
+  let unzip = unzip_multi_part(&[parts_dir], &out_dir, &UnzipLimits::default())
+    .await
+    .unwrap();
+  assert_eq!(unzip.dir_name, "Export-abc");
+  assert_eq!(unzip.unzip_dir, out_dir.join("Export-abc"));
+  assert_eq!(unzip.parts.len(), 2);
+  // the file of the last part wins
+  assert_eq!(read(&unzip.unzip_dir.join("Page.md")), "# Page v2");
+  assert_eq!(read(&unzip.unzip_dir.join("Page/Sub.md")), "# Sub");
+  assert!(unzip.unzip_dir.join("Page/image.png").exists());
+  assert!(!out_dir.join(".parts").exists());
+}
+
+#[tokio::test]
+async fn unzip_parts_without_shared_root_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let part_1 = dir.path().join("export.part1.zip");
+  let part_2 = dir.path().join("export.part2.zip");
+  write_zip(&part_1, &[("A.md", b"# A".to_vec())]);
+  write_zip(&part_2, &[("B/B.md", b"# B".to_vec())]);
+
+  let out_dir = dir.path().join("out");
+  let unzip = unzip_multi_part(&[part_2, part_1], &out_dir, &UnzipLimits::default())
+    .await
+    .unwrap();
+  assert_eq!(unzip.dir_name, "export");
+  assert_eq!(read(&unzip.unzip_dir.join("A.md")), "# A");
+  assert_eq!(read(&unzip.unzip_dir.join("B/B.md")), "# B");
+
+  let err = unzip_multi_part(
+    &[dir.path().join("missing.zip")],
+    &out_dir,
+    &UnzipLimits::default(),
+  )
+  .await
+  .unwrap_err();
+  assert!(matches!(err, ImporterError::FileNotFound));
+}
+
+#[tokio::test]
+async fn unzip_parts_limits_apply_to_all_parts_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let part_1 = dir.path().join("export_1.zip");
+  let part_2 = dir.path().join("export_2.zip");
+  write_zip(&part_1, &[("Export/A.md", vec![b'a'; 60])]);
+  write_zip(&part_2, &[("Export/B.md", vec![b'b'; 60])]);
+
+  let limits = UnzipLimits {
+    max_uncompressed_size: 100,
+    ..Default::default()
+  };
+  let err = unzip_multi_part(&[part_1, part_2], &dir.path().join("out"), &limits)
+    .await
+    .unwrap_err();
+  assert!(matches!(
+    err,
+    ImporterError::UnsafeArchive(UnsafeArchiveError::TooLarge { limit: 100 })
+  ));
+}
+
+#[tokio::test]
+async fn import_notion_zip_parts_test() {
+  // split the export in two parts, the page in the first one and its images in the second one
+  let mut archive =
+    zip::ZipArchive::new(std::fs::File::open("./tests/asset/blog_post.zip").unwrap()).unwrap();
+  let mut part_1 = vec![];
+  let mut part_2 = vec![];
+  for index in 0..archive.len() {
+    let mut entry = archive.by_index(index).unwrap();
+    if entry.is_dir() {
+      continue;
+    }
+    let mut content = vec![];
+    entry.read_to_end(&mut content).unwrap();
+    let name = entry.name().to_string();
+    if name.ends_with(".jpg") {
+      part_2.push((name, content));
+    } else {
+      part_1.push((name, content));
+    }
+  }
+  let dir = tempfile::tempdir().unwrap();
+  let parts = [
+    ("blog_post-Part-1.zip", part_1),
+    ("blog_post-Part-2.zip", part_2),
+  ]
+  .into_iter()
+  .map(|(file_name, entries)| {
+    let entries = entries
+      .iter()
+      .map(|(name, content)| (name.as_str(), content.clone()))
+      .collect::<Vec<_>>();
+    let path = dir.path().join(file_name);
+    write_zip(&path, &entries);
+    path
+  })
+  .collect::<Vec<_>>();
+
+  let info = NotionImporter::from_zip_parts(
+    1,
+    &parts,
+    &dir.path().join("out"),
+    uuid::Uuid::new_v4(),
+    HOST.to_string(),
+  )
+  .await
+  .unwrap()
+  .import()
+  .await
+  .unwrap();
+
+  let (_cleaner, file_path) = sync_unzip_asset("blog_post").await.unwrap();
+  let expected = NotionImporter::new(1, &file_path, uuid::Uuid::new_v4(), HOST.to_string())
+    .unwrap()
+    .import()
+    .await
+    .unwrap();
+  assert_eq!(info.num_of_markdown(), expected.num_of_markdown());
+  assert_eq!(info.views()[0].notion_name, expected.views()[0].notion_name);
+}
+
+fn read(path: &Path) -> String {
+  std::fs::read_to_string(path).unwrap()
+}
@@ -0,0 +1,128 @@
+use crate::zip_tool_test::write_zip;
+use collab_importer::error::{ImporterError, UnsafeArchiveError};
+use collab_importer::zip_tool::safe_unzip::{
+  UnzipLimits, safe_unzip, safe_unzip_file, sanitize_entry_path,
+};
+use std::path::PathBuf;
+
+#[test]
+fn sanitize_entry_path_test() {
+  assert_eq!(
+    sanitize_entry_path("Export/Page.md"),
+    Some(PathBuf::from("Export/Page.md"))
+  );
+  assert_eq!(
+    sanitize_entry_path("../../etc/passwd"),
+    Some(PathBuf::from("etc/passwd"))
+  );
+  assert_eq!(
+    sanitize_entry_path("/abs/./file.txt"),
+    Some(PathBuf::from("abs/file.txt"))
+  );
+  assert_eq!(
+    sanitize_entry_path("dir\\..\\..\\file.txt"),
+    Some(PathBuf::from("dir/file.txt"))
+  );
+  assert_eq!(sanitize_entry_path("../"), None);
+}
+
+#[tokio::test]
+async fn safe_unzip_sanitizes_paths_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let zip_path = dir.path().join("export.zip");
+  write_zip(
+    &zip_path,
+    &[
+      ("Export/Page.md", b"# Page".to_vec()),
+      ("../evil.txt", b"evil".to_vec()),
+      ("/etc/cron.txt", b"evil".to_vec()),
+    ],
+  );
+  let out_dir = dir.path().join("out");
+  let output = safe_unzip_file(&zip_path, &out_dir, &UnzipLimits::default())
+    .await
+    .unwrap();
+
+  assert_eq!(
+    output.files,
+    vec![
+      out_dir.join("Export/Page.md"),
+      out_dir.join("evil.txt"),
+      out_dir.join("etc/cron.txt"),
+    ]
+  );
+  assert!(!dir.path().join("evil.txt").exists());
+  assert_eq!(output.uncompressed_size, 14);
+  assert_eq!(
+    std::fs::read_to_string(out_dir.join("Export/Page.md")).unwrap(),
+    "# Page"
+  );
+
+  // the reader of the archive can be any stream
+  let bytes = std::fs::read(&zip_path).unwrap();
+  let output = safe_unzip(
+    futures::io::Cursor::new(bytes),
+    &dir.path().join("stream"),
+    &UnzipLimits::default(),
+  )
+  .await
+  .unwrap();
+  assert_eq!(output.files.len(), 3);
+}
+
+#[tokio::test]
+async fn safe_unzip_limits_test() {
+  let dir = tempfile::tempdir().unwrap();
+  let bomb_path = dir.path().join("bomb.zip");
+  write_zip(&bomb_path, &[("zeros.bin", vec![0; 1024 * 1024])]);
+  let err = safe_unzip_file(
+    &bomb_path,
+    &dir.path().join("bomb"),
+    &UnzipLimits::default(),
+  )
+  .await
+  .unwrap_err();
+  assert!(matches!(
+    err,
+    ImporterError::UnsafeArchive(UnsafeArchiveError::CompressionRatioTooHigh { ref file, limit: 100 })
+      if file == "zeros.bin"
+  ));
+  assert_eq!(err.code(), "importer.unsafe_archive");
+
+  let zip_path = dir.path().join("files.zip");
+  write_zip(
+    &zip_path,
+    &[
+      ("a.txt", b"first file".to_vec()),
+      ("b.txt", b"second file".to_vec()),
+      ("c.txt", b"third file".to_vec()),
+    ],
+  );
+  let limits = UnzipLimits {
+    max_file_count: 2,
+    ..UnzipLimits::default()
+  };
+  let err = safe_unzip_file(&zip_path, &dir.path().join("count"), &limits)
+    .await
+    .unwrap_err();
+  assert!(matches!(
+    err,
+    ImporterError::UnsafeArchive(UnsafeArchiveError::TooManyFiles { limit: 2 })
+  ));
+
+  let limits = UnzipLimits {
+    max_uncompressed_size: 16,
+    ..UnzipLimits::default()
+  };
+  let err = safe_unzip_file(&zip_path, &dir.path().join("size"), &limits)
+    .await
+    .unwrap_err();
+  assert!(matches!(
+    err,
+    ImporterError::UnsafeArchive(UnsafeArchiveError::TooLarge { limit: 16 })
+  ));
+  assert_eq!(
+    err.to_string(),
+    "Unsafe zip archive: more than 16 bytes once extracted"
+  );
+}
@@ -45,6 +45,17 @@ pub enum PersistenceError {
   #[error("Can't find the latest update key")]
   LatestUpdateKeyNotExist,
 
+  #[error("Checksum mismatch at chunk {chunk}: expected {expected:#010x}, got {actual:#010x}")]
+  ChecksumMismatch {
+    chunk: usize,
+    expected: u32,
+    actual: u32,
+  },
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[error(transparent)]
+  IO(#[from] std::io::Error),
+
   #[error(transparent)]
   Collab(#[from] collab::error::CollabError),
 
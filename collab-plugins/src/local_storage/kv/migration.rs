@@ -0,0 +1,249 @@
+use crate::local_storage::kv::doc::CollabKVAction;
+use crate::local_storage::kv::{KVStore, PersistenceError};
+use collab::core::collab::DATA_SECTION;
+use tracing::{error, info};
+use yrs::types::Attrs;
+use yrs::types::text::YChange;
+use yrs::updates::encoder::Encode;
+use yrs::{
+  Any, Array, ArrayRef, Doc, Map, MapRef, OffsetKind, Options, Out, ReadTxn, StateVector, Text,
+  TextRef, Transact, TransactionMut, WriteTxn,
+};
+
+/// A schema migration that is applied to every collab in a workspace by the [WorkspaceMigrator].
+pub trait CollabMigration: Send + Sync {
+  /// The name of the migration. It's only used for logging and reporting.
+  fn name(&self) -> &str;
+
+  /// Apply the migration to the collab with the given object id. Implementations should be
+  /// idempotent: running a migration on a collab that was already migrated must be a no-op.
+  fn migrate(&self, object_id: &str, txn: &mut TransactionMut) -> Result<(), PersistenceError>;
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationProgress {
+  /// The zero-based index of the object that was just processed.
+  pub index: usize,
+  pub total: usize,
+  pub object_id: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationFailure {
+  pub object_id: String,
+  pub reason: String,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct MigrationReport {
+  /// The number of objects that were decoded, migrated and written back.
+  pub num_of_migrated: usize,
+  /// The number of pending updates that were folded into the doc state while re-encoding.
+  pub num_of_merged_updates: usize,
+  /// The number of text runs whose empty formatting attributes were removed, see
+  /// [normalize_text_deltas].
+  pub num_of_normalized_deltas: usize,
+  pub failures: Vec<MigrationFailure>,
+}
+
+impl MigrationReport {
+  pub fn is_success(&self) -> bool {
+    self.failures.is_empty()
+  }
+}
+
+/// Re-encodes all the collabs of a workspace to the latest format.
+///
+/// For each object it loads the doc state and the pending updates, runs the registered
+/// [CollabMigration]s in order, normalizes the text deltas with [normalize_text_deltas] and writes
+/// the result back as a single doc state. Failing objects are recorded in the [MigrationReport]
+/// and left untouched, so one broken collab doesn't stop the whole workspace from being upgraded.
+#[derive(Default)]
+pub struct WorkspaceMigrator {
+  migrations: Vec<Box<dyn CollabMigration>>,
+  skip_delta_normalization: bool,
+}
+
+impl WorkspaceMigrator {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_migration<M: CollabMigration + 'static>(mut self, migration: M) -> Self {
+    self.migrations.push(Box::new(migration));
+    self
+  }
+
+  pub fn register<M: CollabMigration + 'static>(&mut self, migration: M) {
+    self.migrations.push(Box::new(migration));
+  }
+
+  /// Only run the registered migrations, the text deltas are re-encoded as they are.
+  pub fn without_delta_normalization(mut self) -> Self {
+    self.skip_delta_normalization = true;
+    self
+  }
+
+  pub fn run<'a, S>(
+    &self,
+    store: &S,
+    uid: i64,
+    workspace_id: &str,
+    mut progress: impl FnMut(MigrationProgress),
+  ) -> Result<MigrationReport, PersistenceError>
+  where
+    S: KVStore<'a> + 'a,
+    PersistenceError: From<<S as KVStore<'a>>::Error>,
+  {
+    // Collect the ids first, the store is written while migrating.
+    let object_ids = store
+      .get_all_object_ids(uid, workspace_id)?
+      .collect::<Vec<_>>();
+    let total = object_ids.len();
+    info!(
+      "start migrating {} objects in workspace:{}",
+      total, workspace_id
+    );
+
+    let mut report = MigrationReport::default();
+    for (index, object_id) in object_ids.into_iter().enumerate() {
+      match self.migrate_object(store, uid, workspace_id, &object_id) {
+        Ok((num_of_updates, num_of_normalized_deltas)) => {
+          report.num_of_migrated += 1;
+          report.num_of_merged_updates += num_of_updates as usize;
+          report.num_of_normalized_deltas += num_of_normalized_deltas;
+        },
+        Err(err) => {
+          error!("🔴migrate object:{} failed: {}", object_id, err);
+          report.failures.push(MigrationFailure {
+            object_id: object_id.clone(),
+            reason: err.to_string(),
+          });
+        },
+      }
+      progress(MigrationProgress {
+        index,
+        total,
+        object_id,
+      });
+    }
+
+    info!(
+      "finish migrating workspace:{}, migrated:{}, failed:{}",
+      workspace_id,
+      report.num_of_migrated,
+      report.failures.len()
+    );
+    Ok(report)
+  }
+
+  fn migrate_object<'a, S>(
+    &self,
+    store: &S,
+    uid: i64,
+    workspace_id: &str,
+    object_id: &str,
+  ) -> Result<(u32, usize), PersistenceError>
+  where
+    S: KVStore<'a> + 'a,
+    PersistenceError: From<<S as KVStore<'a>>::Error>,
+  {
+    // The offsets of the text deltas are counted in bytes by the normalization.
+    let doc = Doc::with_options(Options {
+      offset_kind: OffsetKind::Bytes,
+      ..Options::default()
+    });
+    let (num_of_updates, num_of_normalized_deltas, doc_state, state_vector) = {
+      let mut txn = doc.transact_mut();
+      let num_of_updates = store.load_doc_with_txn(uid, workspace_id, object_id, &mut txn)?;
+      for migration in self.migrations.iter() {
+        migration.migrate(object_id, &mut txn).map_err(|err| {
+          PersistenceError::InvalidData(format!("migration {}: {}", migration.name(), err))
+        })?;
+      }
+      let num_of_normalized_deltas = if self.skip_delta_normalization {
+        0
+      } else {
+        normalize_text_deltas(&mut txn)
+      };
+      let doc_state = txn.encode_state_as_update_v1(&StateVector::default());
+      let state_vector = txn.state_vector().encode_v1();
+      (
+        num_of_updates,
+        num_of_normalized_deltas,
+        doc_state,
+        state_vector,
+      )
+    };
+
+    store.flush_doc(uid, workspace_id, object_id, state_vector, doc_state)?;
+    Ok((num_of_updates, num_of_normalized_deltas))
+  }
+}
+
+/// Remove the formatting attributes with an empty value, e.g. `{"href": ""}` left by the older
+/// editors, from the texts stored in the [DATA_SECTION] of the collab. The attributes are removed
+/// with regular format operations, so the result merges with the edits of the other devices.
+/// Return the number of text runs that were normalized.
+///
+/// The doc of the transaction must count the text offsets in [OffsetKind::Bytes].
+pub fn normalize_text_deltas(txn: &mut TransactionMut) -> usize {
+  let data = txn.get_or_insert_map(DATA_SECTION);
+  let mut texts = vec![];
+  collect_texts_in_map(&*txn, &data, &mut texts);
+
+  let mut num_of_normalized = 0;
+  for text in texts {
+    let mut index = 0;
+    for chunk in text.diff(&*txn, YChange::identity) {
+      let len = match &chunk.insert {
+        Out::Any(Any::String(s)) => s.len() as u32,
+        _ => 1,
+      };
+      let empty_attributes = chunk
+        .attributes
+        .iter()
+        .flat_map(|attributes| attributes.iter())
+        .filter(|(_, value)| is_empty_attribute(value))
+        .map(|(key, _)| (key.clone(), Any::Null))
+        .collect::<Attrs>();
+      if !empty_attributes.is_empty() {
+        // Formatting doesn't change the length of the text, so the next indexes stay valid.
+        text.format(txn, index, len, empty_attributes);
+        num_of_normalized += 1;
+      }
+      index += len;
+    }
+  }
+  num_of_normalized
+}
+
+fn is_empty_attribute(value: &Any) -> bool {
+  match value {
+    Any::String(s) => s.is_empty(),
+    Any::Array(array) => array.is_empty(),
+    Any::Map(map) => map.is_empty(),
+    _ => false,
+  }
+}
+
+fn collect_texts_in_map<T: ReadTxn>(txn: &T, map: &MapRef, texts: &mut Vec<TextRef>) {
+  for (_, value) in map.iter(txn) {
+    collect_texts(txn, value, texts);
+  }
+}
+
+fn collect_texts_in_array<T: ReadTxn>(txn: &T, array: &ArrayRef, texts: &mut Vec<TextRef>) {
+  for value in array.iter(txn) {
+    collect_texts(txn, value, texts);
+  }
+}
+
+fn collect_texts<T: ReadTxn>(txn: &T, value: Out, texts: &mut Vec<TextRef>) {
+  match value {
+    Out::YText(text) => texts.push(text),
+    Out::YMap(map) => collect_texts_in_map(txn, &map, texts),
+    Out::YArray(array) => collect_texts_in_array(txn, &array, texts),
+    _ => {},
+  }
+}
@@ -6,6 +6,7 @@ mod db;
 pub mod doc;
 pub mod error;
 pub mod keys;
+pub mod migration;
 pub mod oid;
 mod range;
 pub mod snapshot;
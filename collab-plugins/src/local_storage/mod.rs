@@ -3,6 +3,9 @@ pub mod kv;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod rocksdb;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod snapshot_file;
+
 #[cfg(target_arch = "wasm32")]
 pub mod indexeddb;
 
@@ -0,0 +1,307 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use memmap2::Mmap;
+use tracing::{trace, warn};
+use yrs::TransactionMut;
+use yrs::updates::decoder::Decode;
+use yrs::{ReadTxn, StateVector, Update};
+
+use crate::local_storage::kv::{PersistenceError, TransactionMutExt};
+
+/// Magic bytes at the beginning of every snapshot file.
+const SNAPSHOT_FILE_MAGIC: &[u8; 8] = b"CLBSNAP1";
+const SNAPSHOT_FILE_VERSION: u32 = 1;
+/// magic(8) + version(4) + chunk_size(4) + payload_len(8) + chunk_count(8)
+const HEADER_LEN: usize = 32;
+
+/// The payload is split into chunks of this size. Each chunk has its own checksum so that
+/// a caller that only touches part of the file doesn't need to fault in the whole mapping.
+pub const DEFAULT_SNAPSHOT_CHUNK_SIZE: u32 = 64 * 1024;
+
+/// Files smaller than this are read into memory instead of being mapped. Mapping tiny files
+/// costs more than it saves.
+pub const DEFAULT_MMAP_THRESHOLD: u64 = 1024 * 1024;
+
+/// Write the encoded doc state of a collab into a snapshot file.
+///
+/// The file is written to a temporary path first and then renamed, so a reader never observes
+/// a partially written snapshot.
+pub fn write_snapshot_file<P: AsRef<Path>>(
+  path: P,
+  doc_state: &[u8],
+) -> Result<(), PersistenceError> {
+  write_snapshot_file_with_chunk_size(path, doc_state, DEFAULT_SNAPSHOT_CHUNK_SIZE)
+}
+
+pub fn write_snapshot_file_with_chunk_size<P: AsRef<Path>>(
+  path: P,
+  doc_state: &[u8],
+  chunk_size: u32,
+) -> Result<(), PersistenceError> {
+  if chunk_size == 0 {
+    return Err(PersistenceError::InvalidData(
+      "snapshot chunk size must be greater than 0".to_string(),
+    ));
+  }
+
+  let path = path.as_ref();
+  let tmp_path = tmp_path_for(path);
+  {
+    let file = OpenOptions::new()
+      .create(true)
+      .write(true)
+      .truncate(true)
+      .open(&tmp_path)?;
+    let mut writer = BufWriter::new(file);
+    let checksums = doc_state
+      .chunks(chunk_size as usize)
+      .map(crc32fast::hash)
+      .collect::<Vec<u32>>();
+
+    writer.write_all(SNAPSHOT_FILE_MAGIC)?;
+    writer.write_all(&SNAPSHOT_FILE_VERSION.to_le_bytes())?;
+    writer.write_all(&chunk_size.to_le_bytes())?;
+    writer.write_all(&(doc_state.len() as u64).to_le_bytes())?;
+    writer.write_all(&(checksums.len() as u64).to_le_bytes())?;
+    for checksum in checksums {
+      writer.write_all(&checksum.to_le_bytes())?;
+    }
+    writer.write_all(doc_state)?;
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+  }
+  fs::rename(&tmp_path, path)?;
+  trace!(
+    "write snapshot file:{:?}, payload len:{}",
+    path,
+    doc_state.len()
+  );
+  Ok(())
+}
+
+fn tmp_path_for(path: &Path) -> PathBuf {
+  let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+  file_name.push(".tmp");
+  path.with_file_name(file_name)
+}
+
+enum SnapshotBytes {
+  Mapped(Mmap),
+  Loaded(Vec<u8>),
+}
+
+impl SnapshotBytes {
+  fn as_slice(&self) -> &[u8] {
+    match self {
+      SnapshotBytes::Mapped(mmap) => mmap.as_ref(),
+      SnapshotBytes::Loaded(data) => data.as_slice(),
+    }
+  }
+}
+
+/// A read-only view of a snapshot file written by [write_snapshot_file].
+///
+/// Large files are memory mapped, so pages are only brought in when they are touched. The
+/// checksum of each chunk is validated the first time the chunk is read and the result is
+/// cached, which means that opening the file only validates the header.
+pub struct SnapshotFile {
+  path: PathBuf,
+  bytes: SnapshotBytes,
+  chunk_size: usize,
+  payload_len: usize,
+  checksums_offset: usize,
+  payload_offset: usize,
+  verified: Vec<AtomicU64>,
+}
+
+impl SnapshotFile {
+  /// Open the snapshot file. Files larger than [DEFAULT_MMAP_THRESHOLD] are memory mapped.
+  pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, PersistenceError> {
+    Self::open_with_threshold(path, DEFAULT_MMAP_THRESHOLD)
+  }
+
+  /// Open the snapshot file. Files with a size greater than or equal to `mmap_threshold` are
+  /// memory mapped, smaller files are read into memory.
+  pub fn open_with_threshold<P: AsRef<Path>>(
+    path: P,
+    mmap_threshold: u64,
+  ) -> Result<Self, PersistenceError> {
+    let path = path.as_ref().to_path_buf();
+    let file = File::open(&path)?;
+    let file_len = file.metadata()?.len();
+    let bytes = if file_len >= mmap_threshold {
+      // Safety: snapshot files are only replaced via rename, never modified in place, so the
+      // mapped content stays valid for the lifetime of the mapping.
+      let mmap = unsafe { Mmap::map(&file)? };
+      SnapshotBytes::Mapped(mmap)
+    } else {
+      SnapshotBytes::Loaded(fs::read(&path)?)
+    };
+    Self::from_bytes(path, bytes)
+  }
+
+  fn from_bytes(path: PathBuf, bytes: SnapshotBytes) -> Result<Self, PersistenceError> {
+    let data = bytes.as_slice();
+    if data.len() < HEADER_LEN || &data[0..8] != SNAPSHOT_FILE_MAGIC {
+      return Err(PersistenceError::InvalidData(format!(
+        "{:?} is not a snapshot file",
+        path
+      )));
+    }
+
+    let version = read_u32(data, 8);
+    if version != SNAPSHOT_FILE_VERSION {
+      return Err(PersistenceError::InvalidData(format!(
+        "unsupported snapshot file version: {}",
+        version
+      )));
+    }
+
+    // The header is untrusted, a corrupted file must not overflow the offsets below.
+    let corrupted =
+      || PersistenceError::InvalidData(format!("corrupted snapshot header: {:?}", path));
+    let chunk_size = read_u32(data, 12) as usize;
+    let payload_len = usize::try_from(read_u64(data, 16)).map_err(|_| corrupted())?;
+    let chunk_count = usize::try_from(read_u64(data, 24)).map_err(|_| corrupted())?;
+    if chunk_size == 0 || chunk_count != payload_len.div_ceil(chunk_size) {
+      return Err(corrupted());
+    }
+
+    let checksums_offset = HEADER_LEN;
+    let payload_offset = chunk_count
+      .checked_mul(4)
+      .and_then(|checksums_len| checksums_offset.checked_add(checksums_len))
+      .ok_or_else(corrupted)?;
+    let file_len = payload_offset
+      .checked_add(payload_len)
+      .ok_or_else(corrupted)?;
+    if data.len() != file_len {
+      return Err(PersistenceError::InvalidData(format!(
+        "snapshot file is truncated: {:?}, expected {} bytes, got {}",
+        path,
+        file_len,
+        data.len()
+      )));
+    }
+
+    let verified = (0..chunk_count.div_ceil(64))
+      .map(|_| AtomicU64::new(0))
+      .collect();
+    Ok(Self {
+      path,
+      bytes,
+      chunk_size,
+      payload_len,
+      checksums_offset,
+      payload_offset,
+      verified,
+    })
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  pub fn is_mapped(&self) -> bool {
+    matches!(self.bytes, SnapshotBytes::Mapped(_))
+  }
+
+  pub fn payload_len(&self) -> usize {
+    self.payload_len
+  }
+
+  pub fn chunk_count(&self) -> usize {
+    self.payload_len.div_ceil(self.chunk_size)
+  }
+
+  /// Return the bytes of the given range of the payload. Only the chunks that overlap with the
+  /// range are validated.
+  pub fn read_range(&self, range: Range<usize>) -> Result<&[u8], PersistenceError> {
+    if range.start > range.end || range.end > self.payload_len {
+      return Err(PersistenceError::InvalidData(format!(
+        "range {:?} is out of bounds, payload len: {}",
+        range, self.payload_len
+      )));
+    }
+    if range.is_empty() {
+      return Ok(&[]);
+    }
+
+    let first_chunk = range.start / self.chunk_size;
+    let last_chunk = (range.end - 1) / self.chunk_size;
+    for chunk_index in first_chunk..=last_chunk {
+      self.verify_chunk(chunk_index)?;
+    }
+    let data = self.bytes.as_slice();
+    Ok(&data[self.payload_offset + range.start..self.payload_offset + range.end])
+  }
+
+  /// Validate every chunk and return the whole payload.
+  pub fn payload(&self) -> Result<&[u8], PersistenceError> {
+    self.read_range(0..self.payload_len)
+  }
+
+  /// Validate the checksum of every chunk.
+  pub fn validate(&self) -> Result<(), PersistenceError> {
+    self.payload().map(|_| ())
+  }
+
+  /// Decode the payload as a v1 update and apply it to the given transaction.
+  pub fn apply_to_txn(&self, txn: &mut TransactionMut) -> Result<(), PersistenceError> {
+    let update = Update::decode_v1(self.payload()?)?;
+    txn.try_apply_update(update)?;
+    Ok(())
+  }
+
+  fn verify_chunk(&self, chunk_index: usize) -> Result<(), PersistenceError> {
+    let slot = &self.verified[chunk_index / 64];
+    let mask = 1u64 << (chunk_index % 64);
+    if slot.load(Ordering::Acquire) & mask != 0 {
+      return Ok(());
+    }
+
+    let data = self.bytes.as_slice();
+    let start = self.payload_offset + chunk_index * self.chunk_size;
+    let end = (start + self.chunk_size).min(self.payload_offset + self.payload_len);
+    let expected = read_u32(data, self.checksums_offset + chunk_index * 4);
+    let actual = crc32fast::hash(&data[start..end]);
+    if expected != actual {
+      warn!(
+        "🟡snapshot file:{:?} chunk:{} checksum mismatch",
+        self.path, chunk_index
+      );
+      return Err(PersistenceError::ChecksumMismatch {
+        chunk: chunk_index,
+        expected,
+        actual,
+      });
+    }
+    slot.fetch_or(mask, Ordering::AcqRel);
+    Ok(())
+  }
+}
+
+/// Encode the current state of the transaction and write it to a snapshot file.
+pub fn write_snapshot_file_from_txn<P: AsRef<Path>, T: ReadTxn>(
+  path: P,
+  txn: &T,
+) -> Result<(), PersistenceError> {
+  let doc_state = txn.encode_state_as_update_v1(&StateVector::default());
+  write_snapshot_file(path, &doc_state)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+  let mut buf = [0u8; 4];
+  buf.copy_from_slice(&data[offset..offset + 4]);
+  u32::from_le_bytes(buf)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+  let mut buf = [0u8; 8];
+  buf.copy_from_slice(&data[offset..offset + 8]);
+  u64::from_le_bytes(buf)
+}
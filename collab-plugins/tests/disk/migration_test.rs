@@ -0,0 +1,161 @@
+use crate::disk::util::rocks_db;
+use collab_plugins::local_storage::kv::doc::CollabKVAction;
+use collab_plugins::local_storage::kv::migration::{
+  CollabMigration, MigrationProgress, WorkspaceMigrator,
+};
+use collab_plugins::local_storage::kv::{KVTransactionDB, PersistenceError};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+use yrs::types::Attrs;
+use yrs::types::text::YChange;
+use yrs::{
+  Any, Doc, GetString, Map, MapPrelim, MapRef, Text, TextPrelim, TextRef, Transact, TransactionMut,
+  WriteTxn,
+};
+
+struct AddVersionMigration;
+
+impl CollabMigration for AddVersionMigration {
+  fn name(&self) -> &str {
+    "add_version"
+  }
+
+  fn migrate(&self, _object_id: &str, txn: &mut TransactionMut) -> Result<(), PersistenceError> {
+    let meta = txn.get_or_insert_map("meta");
+    meta.insert(txn, "version", 2);
+    Ok(())
+  }
+}
+
+#[test]
+fn migrate_workspace_test() {
+  let workspace_id = Uuid::new_v4().to_string();
+  let (_path, db) = rocks_db();
+  for i in 0..10 {
+    let oid = Uuid::new_v4().to_string();
+    let doc = Doc::new();
+    {
+      let txn = doc.transact();
+      db.with_write_txn(|w| w.create_new_doc(1, &workspace_id, &oid, &txn))
+        .unwrap();
+    }
+    for j in 0..5 {
+      let text = doc.get_or_insert_text("text");
+      let mut txn = doc.transact_mut();
+      text.insert(&mut txn, 0, &format!("{}-{}", i, j));
+      let update = txn.encode_update_v1();
+      db.with_write_txn(|w| w.push_update(1, &workspace_id, &oid, &update).map(|_| ()))
+        .unwrap();
+    }
+  }
+
+  let mut progress = Vec::<MigrationProgress>::new();
+  let migrator = WorkspaceMigrator::new().with_migration(AddVersionMigration);
+  let report = db
+    .with_write_txn(|w| migrator.run(w, 1, &workspace_id, |p| progress.push(p)))
+    .unwrap();
+  assert!(report.is_success());
+  assert_eq!(report.num_of_migrated, 10);
+  assert_eq!(report.num_of_merged_updates, 50);
+  assert_eq!(progress.len(), 10);
+  assert_eq!(progress.last().unwrap().total, 10);
+
+  let object_ids = db
+    .read_txn()
+    .get_all_object_ids(1, &workspace_id)
+    .unwrap()
+    .collect::<Vec<_>>();
+  for oid in object_ids {
+    // All the updates were folded into the doc state
+    assert_eq!(db.read_txn().number_of_updates(1, &workspace_id, &oid), 0);
+
+    let doc = Doc::new();
+    db.read_txn()
+      .load_doc(1, &workspace_id, &oid, &doc)
+      .unwrap();
+    let text = doc.get_or_insert_text("text");
+    let meta = doc.get_or_insert_map("meta");
+    let txn = doc.transact();
+    assert!(text.get_string(&txn).ends_with("-0"));
+    assert!(meta.get(&txn, "version").is_some());
+  }
+}
+
+#[test]
+fn migrate_workspace_with_broken_object_test() {
+  let workspace_id = Uuid::new_v4().to_string();
+  let (_path, db) = rocks_db();
+  let good_oid = Uuid::new_v4().to_string();
+  let broken_oid = Uuid::new_v4().to_string();
+  db.with_write_txn(|w| {
+    let doc = Doc::new();
+    w.create_new_doc(1, &workspace_id, &good_oid, &doc.transact())?;
+    w.upsert_doc_with_doc_state(1, &workspace_id, &broken_oid, vec![0], vec![1, 2, 3])
+  })
+  .unwrap();
+
+  let migrator = WorkspaceMigrator::new().with_migration(AddVersionMigration);
+  let report = db
+    .with_write_txn(|w| migrator.run(w, 1, &workspace_id, |_| {}))
+    .unwrap();
+  assert!(!report.is_success());
+  assert_eq!(report.num_of_migrated, 1);
+  assert_eq!(report.failures.len(), 1);
+  assert_eq!(report.failures[0].object_id, broken_oid);
+}
+
+#[test]
+fn migrate_workspace_normalize_text_deltas_test() {
+  let workspace_id = Uuid::new_v4().to_string();
+  let (_path, db) = rocks_db();
+  let oid = Uuid::new_v4().to_string();
+  let doc = Doc::new();
+  {
+    let data = doc.get_or_insert_map("data");
+    let mut txn = doc.transact_mut();
+    let blocks = data.insert(&mut txn, "blocks", MapPrelim::default());
+    let text: TextRef = blocks.insert(&mut txn, "text", TextPrelim::new("Hello world"));
+    let mut attributes = Attrs::new();
+    attributes.insert(Arc::from("bold"), Any::Bool(true));
+    attributes.insert(Arc::from("href"), Any::from(""));
+    text.format(&mut txn, 0, 5, attributes);
+    db.with_write_txn(|w| w.create_new_doc(1, &workspace_id, &oid, &txn))
+      .unwrap();
+  }
+
+  let migrator = WorkspaceMigrator::new();
+  let report = db
+    .with_write_txn(|w| migrator.run(w, 1, &workspace_id, |_| {}))
+    .unwrap();
+  assert!(report.is_success());
+  assert_eq!(report.num_of_normalized_deltas, 1);
+
+  let doc = Doc::new();
+  db.read_txn()
+    .load_doc(1, &workspace_id, &oid, &doc)
+    .unwrap();
+  let data = doc.get_or_insert_map("data");
+  let txn = doc.transact();
+  let blocks: MapRef = data.get(&txn, "blocks").unwrap().cast().unwrap();
+  let text: TextRef = blocks.get(&txn, "text").unwrap().cast().unwrap();
+  assert_eq!(text.get_string(&txn), "Hello world");
+  let attributes = text
+    .diff(&txn, YChange::identity)
+    .into_iter()
+    .map(|chunk| chunk.attributes.map(|attributes| *attributes))
+    .collect::<Vec<_>>();
+  assert_eq!(
+    attributes,
+    vec![
+      Some(HashMap::from([(Arc::from("bold"), Any::Bool(true))])),
+      None
+    ]
+  );
+
+  // Running the migration again finds nothing to normalize.
+  let report = db
+    .with_write_txn(|w| migrator.run(w, 1, &workspace_id, |_| {}))
+    .unwrap();
+  assert_eq!(report.num_of_normalized_deltas, 0);
+}
@@ -1,7 +1,9 @@
 mod delete_test;
 mod insert_test;
+mod migration_test;
 mod range_test;
 mod restore_test;
 mod script;
+mod snapshot_file_test;
 mod undo_test;
 mod util;
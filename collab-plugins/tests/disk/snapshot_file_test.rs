@@ -0,0 +1,119 @@
+use collab_plugins::local_storage::kv::PersistenceError;
+use collab_plugins::local_storage::snapshot_file::{
+  SnapshotFile, write_snapshot_file, write_snapshot_file_with_chunk_size,
+};
+use tempfile::TempDir;
+use yrs::{Doc, GetString, ReadTxn, StateVector, Text, Transact};
+
+fn doc_with_text(content: &str) -> Vec<u8> {
+  let doc = Doc::new();
+  let text = doc.get_or_insert_text("text");
+  let mut txn = doc.transact_mut();
+  text.insert(&mut txn, 0, content);
+  txn.encode_state_as_update_v1(&StateVector::default())
+}
+
+#[test]
+fn mmap_snapshot_file_round_trip_test() {
+  let dir = TempDir::new().unwrap();
+  let path = dir.path().join("doc.snapshot");
+  let content = "Hello, world! ".repeat(1000);
+  let doc_state = doc_with_text(&content);
+  write_snapshot_file_with_chunk_size(&path, &doc_state, 128).unwrap();
+
+  // Force the mmap path by using a zero threshold
+  let file = SnapshotFile::open_with_threshold(&path, 0).unwrap();
+  assert!(file.is_mapped());
+  assert_eq!(file.payload_len(), doc_state.len());
+  assert_eq!(file.chunk_count(), doc_state.len().div_ceil(128));
+  assert_eq!(file.payload().unwrap(), doc_state.as_slice());
+
+  let doc = Doc::new();
+  {
+    let mut txn = doc.transact_mut();
+    file.apply_to_txn(&mut txn).unwrap();
+  }
+  let text = doc.get_or_insert_text("text");
+  assert_eq!(text.get_string(&doc.transact()), content);
+}
+
+#[test]
+fn small_snapshot_file_is_loaded_into_memory_test() {
+  let dir = TempDir::new().unwrap();
+  let path = dir.path().join("doc.snapshot");
+  let doc_state = doc_with_text("small");
+  write_snapshot_file(&path, &doc_state).unwrap();
+
+  let file = SnapshotFile::open(&path).unwrap();
+  assert!(!file.is_mapped());
+  assert_eq!(file.payload().unwrap(), doc_state.as_slice());
+}
+
+#[test]
+fn snapshot_file_checksum_mismatch_test() {
+  let dir = TempDir::new().unwrap();
+  let path = dir.path().join("doc.snapshot");
+  let doc_state = doc_with_text(&"abc".repeat(500));
+  write_snapshot_file_with_chunk_size(&path, &doc_state, 64).unwrap();
+
+  // Corrupt the last byte of the payload, which belongs to the last chunk
+  let mut bytes = std::fs::read(&path).unwrap();
+  let last = bytes.len() - 1;
+  bytes[last] ^= 0xff;
+  std::fs::write(&path, bytes).unwrap();
+
+  let file = SnapshotFile::open_with_threshold(&path, 0).unwrap();
+  // The first chunk is untouched, so reading it succeeds without validating the rest
+  assert!(file.read_range(0..32).is_ok());
+  let err = file.validate().unwrap_err();
+  assert!(matches!(
+    err,
+    PersistenceError::ChecksumMismatch { chunk, .. } if chunk == file.chunk_count() - 1
+  ));
+}
+
+#[test]
+fn open_invalid_snapshot_file_test() {
+  let dir = TempDir::new().unwrap();
+  let path = dir.path().join("doc.snapshot");
+  std::fs::write(&path, b"not a snapshot").unwrap();
+  assert!(SnapshotFile::open(&path).is_err());
+
+  let doc_state = doc_with_text("truncated");
+  write_snapshot_file(&path, &doc_state).unwrap();
+  let mut bytes = std::fs::read(&path).unwrap();
+  bytes.truncate(bytes.len() - 2);
+  std::fs::write(&path, bytes).unwrap();
+  assert!(SnapshotFile::open(&path).is_err());
+}
+
+#[test]
+fn open_snapshot_file_with_overflowing_header_test() {
+  let dir = TempDir::new().unwrap();
+  let path = dir.path().join("doc.snapshot");
+  let doc_state = doc_with_text("header");
+  write_snapshot_file_with_chunk_size(&path, &doc_state, 1).unwrap();
+  let bytes = std::fs::read(&path).unwrap();
+
+  // A payload length and a chunk count whose offsets overflow usize.
+  let mut corrupted = bytes.clone();
+  corrupted[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+  corrupted[24..32].copy_from_slice(&u64::MAX.to_le_bytes());
+  std::fs::write(&path, &corrupted).unwrap();
+  assert!(matches!(
+    SnapshotFile::open(&path),
+    Err(PersistenceError::InvalidData(_))
+  ));
+
+  // A chunk count whose checksums don't fit in the file.
+  let mut corrupted = bytes;
+  let payload_len = u64::MAX / 8;
+  corrupted[12..16].copy_from_slice(&1u32.to_le_bytes());
+  corrupted[16..24].copy_from_slice(&payload_len.to_le_bytes());
+  corrupted[24..32].copy_from_slice(&payload_len.to_le_bytes());
+  std::fs::write(&path, &corrupted).unwrap();
+  assert!(matches!(
+    SnapshotFile::open(&path),
+    Err(PersistenceError::InvalidData(_))
+  ));
+}
@@ -1,7 +1,9 @@
+mod reading_position;
 mod reminder;
 mod user_awareness;
 
 pub mod core {
+  pub use crate::reading_position::*;
   pub use crate::reminder::*;
   pub use crate::user_awareness::*;
 }
@@ -0,0 +1,111 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use collab::preclude::encoding::serde::{from_any, to_any};
+use collab::preclude::{Map, MapExt, MapRef, Out, ReadTxn, TransactionMut};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+const READING_POSITIONS: &str = "reading_positions";
+
+/// Where the user stopped reading a document, synced across the devices of the user.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadingPosition {
+  pub document_id: String,
+  /// The id of the last viewed block.
+  pub block_id: String,
+  /// The scroll position within the block, from 0.0 at its top to 1.0 at its bottom, so it
+  /// doesn't depend on the screen size of the device.
+  #[serde(default)]
+  pub scroll_anchor: f64,
+  /// The time the position was recorded, in milliseconds since the unix epoch.
+  pub updated_at: i64,
+}
+
+impl ReadingPosition {
+  pub fn new<T: ToString>(document_id: T, block_id: T, scroll_anchor: f64) -> Self {
+    let updated_at = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|duration| duration.as_millis() as i64)
+      .unwrap_or_default();
+    Self {
+      document_id: document_id.to_string(),
+      block_id: block_id.to_string(),
+      scroll_anchor: scroll_anchor.clamp(0.0, 1.0),
+      updated_at,
+    }
+  }
+}
+
+/// The reading positions of the user, keyed by document id.
+///
+/// The map is created on the first write, so user awareness collabs created before it existed
+/// can still be opened.
+pub struct ReadingPositions {
+  container: MapRef,
+}
+
+impl ReadingPositions {
+  pub fn new(container: MapRef) -> Self {
+    Self { container }
+  }
+
+  fn positions<T: ReadTxn>(&self, txn: &T) -> Option<MapRef> {
+    self.container.get_with_txn(txn, READING_POSITIONS)
+  }
+
+  pub(crate) fn init(&self, txn: &mut TransactionMut) {
+    let _: MapRef = self.container.get_or_init(txn, READING_POSITIONS);
+  }
+
+  pub fn get<T: ReadTxn>(&self, txn: &T, document_id: &str) -> Option<ReadingPosition> {
+    let positions = self.positions(txn)?;
+    match positions.get(txn, document_id)? {
+      Out::Any(any) => from_any(&any).ok(),
+      _ => None,
+    }
+  }
+
+  /// Return all the reading positions, the most recently updated first.
+  pub fn get_all<T: ReadTxn>(&self, txn: &T) -> Vec<ReadingPosition> {
+    let Some(positions) = self.positions(txn) else {
+      return vec![];
+    };
+    let mut positions = positions
+      .iter(txn)
+      .filter_map(|(_, value)| match value {
+        Out::Any(any) => from_any::<ReadingPosition>(&any).ok(),
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+    positions.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    positions
+  }
+
+  /// Save the position, unless a newer position of the same document is already stored, e.g.
+  /// one synced from another device while this device was offline.
+  ///
+  /// Return true if the position was saved.
+  pub fn set(&self, txn: &mut TransactionMut, position: ReadingPosition) -> bool {
+    if let Some(existing) = self.get(txn, &position.document_id) {
+      if existing.updated_at > position.updated_at {
+        return false;
+      }
+    }
+    let any = match to_any(&position) {
+      Ok(any) => any,
+      Err(err) => {
+        error!("Failed to encode reading position: {}", err);
+        return false;
+      },
+    };
+    let positions: MapRef = self.container.get_or_init(txn, READING_POSITIONS);
+    positions.insert(txn, position.document_id.as_str(), any);
+    true
+  }
+
+  pub fn remove(&self, txn: &mut TransactionMut, document_id: &str) {
+    if let Some(positions) = self.positions(txn) {
+      positions.remove(txn, document_id);
+    }
+  }
+}
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 
 use crate::core::ReminderUpdate;
+use crate::reading_position::{ReadingPosition, ReadingPositions};
 use crate::reminder::{Reminders, RemindersChangeSender};
 use anyhow::{Error, Result};
 use collab::core::collab::CollabOptions;
@@ -95,9 +96,11 @@ impl UserAwareness {
   pub fn to_json(&self) -> Result<serde_json::Value> {
     let txn = self.collab.transact();
     let reminders = self.body.reminders.get_all_reminders(&txn);
+    let reading_positions = self.body.reading_positions.get_all(&txn);
     let data = UserAwarenessData {
       appearance_settings: Default::default(),
       reminders,
+      reading_positions,
     };
     let value = serde_json::to_value(data)?;
     Ok(value)
@@ -144,6 +147,33 @@ impl UserAwareness {
       .reminders
       .update_reminder(&mut txn, reminder_id, f);
   }
+
+  /// Returns the position where the user stopped reading the document, on any device.
+  pub fn get_reading_position(&self, document_id: &str) -> Option<ReadingPosition> {
+    let txn = self.collab.transact();
+    self.body.reading_positions.get(&txn, document_id)
+  }
+
+  /// Returns the reading positions of all documents, the most recently read first.
+  pub fn get_all_reading_positions(&self) -> Vec<ReadingPosition> {
+    let txn = self.collab.transact();
+    self.body.reading_positions.get_all(&txn)
+  }
+
+  /// Saves the reading position of a document.
+  ///
+  /// Returns false if a newer position of the document is already stored, e.g. one recorded on
+  /// another device.
+  pub fn set_reading_position(&mut self, position: ReadingPosition) -> bool {
+    let mut txn = self.collab.transact_mut();
+    self.body.reading_positions.set(&mut txn, position)
+  }
+
+  /// Removes the reading position of a document, e.g. when the document is deleted.
+  pub fn remove_reading_position(&mut self, document_id: &str) {
+    let mut txn = self.collab.transact_mut();
+    self.body.reading_positions.remove(&mut txn, document_id);
+  }
 }
 
 pub fn default_user_awareness_data(object_id: &str, client_id: ClientID) -> EncodedCollab {
@@ -188,6 +218,7 @@ pub struct UserAwarenessBody {
   #[allow(dead_code)]
   appearance_settings: MapRef,
   reminders: Reminders,
+  reading_positions: ReadingPositions,
   #[allow(dead_code)]
   notifier: Option<UserAwarenessNotifier>,
 }
@@ -206,10 +237,13 @@ impl UserAwarenessBody {
         .as_ref()
         .map(|notifier| notifier.reminder_change_tx.clone()),
     );
+    let reading_positions = ReadingPositions::new(container.clone());
+    reading_positions.init(&mut txn);
     Self {
       container,
       appearance_settings,
       reminders,
+      reading_positions,
       notifier,
     }
   }
@@ -225,10 +259,13 @@ impl UserAwarenessBody {
         .as_ref()
         .map(|notifier| notifier.reminder_change_tx.clone()),
     );
+    // Older collabs don't have the reading positions, it's created on the first write.
+    let reading_positions = ReadingPositions::new(awareness.clone());
     Some(Self {
       container: awareness,
       appearance_settings,
       reminders,
+      reading_positions,
       notifier,
     })
   }
@@ -243,4 +280,6 @@ pub struct UserAwarenessNotifier {
 pub struct UserAwarenessData {
   pub appearance_settings: HashMap<String, String>,
   pub reminders: Vec<Reminder>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub reading_positions: Vec<ReadingPosition>,
 }
@@ -1,2 +1,3 @@
+mod reading_position_test;
 mod reminder_test;
 mod util;
@@ -0,0 +1,74 @@
+use collab::core::collab::{CollabOptions, default_client_id};
+use collab::core::origin::CollabOrigin;
+use collab::preclude::Collab;
+use collab_user::core::{ReadingPosition, UserAwareness};
+
+use crate::util::UserAwarenessTest;
+
+fn position(document_id: &str, block_id: &str, updated_at: i64) -> ReadingPosition {
+  ReadingPosition {
+    document_id: document_id.to_string(),
+    block_id: block_id.to_string(),
+    scroll_anchor: 0.5,
+    updated_at,
+  }
+}
+
+#[test]
+fn set_and_get_reading_position_test() {
+  let mut test = UserAwarenessTest::new(1);
+  assert!(test.get_reading_position("d1").is_none());
+
+  assert!(test.set_reading_position(position("d1", "b1", 100)));
+  assert!(test.set_reading_position(position("d2", "b1", 300)));
+  assert!(test.set_reading_position(position("d1", "b2", 200)));
+  assert_eq!(
+    test.get_reading_position("d1").unwrap(),
+    position("d1", "b2", 200)
+  );
+
+  // The most recently read document comes first.
+  let document_ids = test
+    .get_all_reading_positions()
+    .into_iter()
+    .map(|position| position.document_id)
+    .collect::<Vec<_>>();
+  assert_eq!(document_ids, vec!["d2", "d1"]);
+
+  test.remove_reading_position("d2");
+  assert!(test.get_reading_position("d2").is_none());
+  assert_eq!(test.get_all_reading_positions().len(), 1);
+}
+
+#[test]
+fn stale_reading_position_is_ignored_test() {
+  let mut test = UserAwarenessTest::new(1);
+  assert!(test.set_reading_position(position("d1", "b2", 200)));
+  assert!(!test.set_reading_position(position("d1", "b1", 100)));
+  assert_eq!(test.get_reading_position("d1").unwrap().block_id, "b2");
+
+  let new_position = ReadingPosition::new("d1", "b3", 2.0);
+  assert_eq!(new_position.scroll_anchor, 1.0);
+  assert!(test.set_reading_position(new_position));
+  assert_eq!(test.get_reading_position("d1").unwrap().block_id, "b3");
+}
+
+#[test]
+fn reading_position_synced_to_other_device_test() {
+  let mut test = UserAwarenessTest::new(1);
+  test.set_reading_position(position("d1", "b1", 100));
+  let encoded = test
+    .encode_collab_v1(|_collab| Ok::<_, anyhow::Error>(()))
+    .unwrap();
+
+  let options = CollabOptions::new(test.object_id().to_string(), default_client_id())
+    .with_data_source(encoded.into());
+  let collab = Collab::new_with_options(CollabOrigin::Empty, options).unwrap();
+  let other_device = UserAwareness::open(collab, None).unwrap();
+  assert_eq!(
+    other_device.get_reading_position("d1").unwrap(),
+    position("d1", "b1", 100)
+  );
+  let json = other_device.to_json().unwrap();
+  assert_eq!(json["reading_positions"][0]["block_id"], "b1");
+}
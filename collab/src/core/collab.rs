@@ -25,6 +25,7 @@ use yrs::{
 use crate::core::awareness::Awareness;
 use crate::core::collab_plugin::{CollabPersistence, CollabPlugin, CollabPluginType, Plugins};
 use crate::core::collab_state::{InitState, SnapshotState, State, SyncState};
+use crate::core::edit_lock::EDIT_LOCKS;
 use crate::core::origin::{CollabClient, CollabOrigin};
 use crate::core::transaction::DocTransactionExtension;
 
@@ -71,6 +72,8 @@ pub struct Collab {
   pub data: MapRef,
   #[allow(dead_code)]
   meta: MapRef,
+  /// The edit locks, see [Collab::acquire_edit_lock].
+  pub(crate) edit_locks: MapRef,
   /// This is an inner collab state that requires mut access in order to modify it.
   pub context: CollabContext,
 }
@@ -298,6 +301,7 @@ impl Collab {
     let doc = make_yrs_doc(&object_id, false, options.client_id);
     let data = doc.get_or_insert_map(DATA_SECTION);
     let meta = doc.get_or_insert_map(META_SECTION);
+    let edit_locks = doc.get_or_insert_map(EDIT_LOCKS);
     let plugins = Plugins::new(vec![]);
     let state = Arc::new(State::new(&object_id));
     let awareness = Awareness::new(doc);
@@ -307,6 +311,7 @@ impl Collab {
       state,
       data,
       meta,
+      edit_locks,
       plugins,
       update_subscription: Default::default(),
       after_txn_subscription: Default::default(),
@@ -362,6 +367,7 @@ impl Collab {
     let object_id = doc.guid().to_string();
     let data = doc.get_or_insert_map(DATA_SECTION);
     let meta = doc.get_or_insert_map(META_SECTION);
+    let edit_locks = doc.get_or_insert_map(EDIT_LOCKS);
     let state = Arc::new(State::new(&object_id));
     let awareness = Awareness::new(doc);
     Self {
@@ -372,6 +378,7 @@ impl Collab {
       state,
       data,
       meta,
+      edit_locks,
       plugins: Plugins::default(),
       update_subscription: Default::default(),
       after_txn_subscription: Default::default(),
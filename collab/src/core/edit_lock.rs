@@ -0,0 +1,229 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use yrs::encoding::serde::{from_any, to_any};
+use yrs::types::EntryChange;
+use yrs::{Any, Map, MapRef, Observable, Out, ReadTxn, Subscription};
+
+use crate::core::collab::Collab;
+use crate::core::origin::CollabOrigin;
+use crate::error::CollabError;
+
+/// The root map of the edit locks. It's outside of the data section, so the locks are synced
+/// with the other peers but are not undone with the edits.
+pub(crate) const EDIT_LOCKS: &str = "edit_locks";
+
+/// The scope of the lock on the whole collab, e.g. only the owner of a meeting edits its agenda.
+pub const COLLAB_EDIT_LOCK: &str = "collab";
+/// The scope of the lock serializing the schema changes of a database, e.g. adding a field.
+pub const SCHEMA_EDIT_LOCK: &str = "schema";
+
+/// An advisory lock on a part of a collab, see [Collab::acquire_edit_lock].
+///
+/// The lock doesn't prevent the edits, the clients check it before editing. It expires unless
+/// its holder renews it, so a lock held by a client that went offline is not held forever.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EditLock {
+  /// What is locked, e.g. [COLLAB_EDIT_LOCK] or [SCHEMA_EDIT_LOCK].
+  pub scope: String,
+  pub holder: CollabOrigin,
+  /// When the lock was acquired, in milliseconds since the epoch.
+  pub acquired_at: i64,
+  /// When the lock expires, in milliseconds since the epoch.
+  pub expires_at: i64,
+  /// Why the lock is held, shown to the other clients.
+  #[serde(default)]
+  pub reason: Option<String>,
+}
+
+impl EditLock {
+  pub fn is_expired(&self) -> bool {
+    self.expires_at <= now_millis()
+  }
+
+  pub fn is_held_by(&self, origin: &CollabOrigin) -> bool {
+    &self.holder == origin
+  }
+}
+
+/// A change of an edit lock, written locally or by a remote peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditLockChange {
+  pub scope: String,
+  /// The lock after the change, None when it was released.
+  pub lock: Option<EditLock>,
+}
+
+impl Collab {
+  /// Acquire the edit lock of the scope for the duration, or renew it if this client already
+  /// holds it.
+  ///
+  /// Return [CollabError::EditLockHeld] if another client holds the lock and it hasn't expired.
+  /// Two clients acquiring the lock offline both get it until they sync, then the lock of one of
+  /// them wins: observe the locks, see [Collab::observe_edit_locks], to find out which one.
+  pub fn acquire_edit_lock(
+    &mut self,
+    scope: &str,
+    duration: Duration,
+    reason: Option<String>,
+  ) -> Result<EditLock, CollabError> {
+    let holder = self.lock_holder()?;
+    let locks = &self.edit_locks;
+    let mut txn = self.context.transact_mut();
+    let now = now_millis();
+    let acquired_at = match get_lock_with_txn(locks, &txn, scope) {
+      Some(lock) if lock.expires_at > now && !lock.is_held_by(&holder) => {
+        return Err(edit_lock_held(lock));
+      },
+      Some(lock) if lock.expires_at > now => lock.acquired_at,
+      _ => now,
+    };
+    let lock = EditLock {
+      scope: scope.to_string(),
+      holder,
+      acquired_at,
+      expires_at: now + duration.as_millis() as i64,
+      reason,
+    };
+    locks.insert(&mut txn, scope, lock_to_any(&lock)?);
+    Ok(lock)
+  }
+
+  /// Extend the edit lock held by this client for the duration, from now.
+  ///
+  /// Return [CollabError::EditLockNotHeld] if the lock expired or is held by another client.
+  pub fn renew_edit_lock(
+    &mut self,
+    scope: &str,
+    duration: Duration,
+  ) -> Result<EditLock, CollabError> {
+    let holder = self.lock_holder()?;
+    let locks = &self.edit_locks;
+    let mut txn = self.context.transact_mut();
+    let mut lock = get_lock_with_txn(locks, &txn, scope)
+      .filter(|lock| !lock.is_expired() && lock.is_held_by(&holder))
+      .ok_or_else(|| CollabError::EditLockNotHeld(scope.to_string()))?;
+    lock.expires_at = now_millis() + duration.as_millis() as i64;
+    locks.insert(&mut txn, scope, lock_to_any(&lock)?);
+    Ok(lock)
+  }
+
+  /// Release the edit lock held by this client. Releasing a lock that expired or that is not
+  /// held does nothing.
+  ///
+  /// Return [CollabError::EditLockHeld] if another client holds the lock.
+  pub fn release_edit_lock(&mut self, scope: &str) -> Result<(), CollabError> {
+    let holder = self.lock_holder()?;
+    let locks = &self.edit_locks;
+    let mut txn = self.context.transact_mut();
+    match get_lock_with_txn(locks, &txn, scope) {
+      Some(lock) if !lock.is_expired() && !lock.is_held_by(&holder) => Err(edit_lock_held(lock)),
+      Some(_) => {
+        locks.remove(&mut txn, scope);
+        Ok(())
+      },
+      None => Ok(()),
+    }
+  }
+
+  /// The edit lock of the scope, None if it's not held or expired.
+  pub fn get_edit_lock(&self, scope: &str) -> Option<EditLock> {
+    let txn = self.context.transact();
+    self.get_edit_lock_with_txn(&txn, scope)
+  }
+
+  /// Same as [Collab::get_edit_lock], read with the transaction of the caller.
+  pub fn get_edit_lock_with_txn<T: ReadTxn>(&self, txn: &T, scope: &str) -> Option<EditLock> {
+    get_lock_with_txn(&self.edit_locks, txn, scope).filter(|lock| !lock.is_expired())
+  }
+
+  /// The edit locks that are held, ordered by scope.
+  pub fn get_edit_locks(&self) -> Vec<EditLock> {
+    let txn = self.context.transact();
+    self.get_edit_locks_with_txn(&txn)
+  }
+
+  /// Same as [Collab::get_edit_locks], read with the transaction of the caller.
+  pub fn get_edit_locks_with_txn<T: ReadTxn>(&self, txn: &T) -> Vec<EditLock> {
+    let mut locks = self
+      .edit_locks
+      .iter(txn)
+      .filter_map(|(_, value)| lock_from_out(value))
+      .filter(|lock| !lock.is_expired())
+      .collect::<Vec<_>>();
+    locks.sort_by(|a, b| a.scope.cmp(&b.scope));
+    locks
+  }
+
+  /// True if the scope is not locked, or locked by this client.
+  pub fn can_edit(&self, scope: &str) -> bool {
+    self
+      .get_edit_lock(scope)
+      .is_none_or(|lock| lock.is_held_by(self.origin()))
+  }
+
+  /// Observe the changes of the edit locks, local or remote. The expiration of a lock is not a
+  /// change, check [EditLock::is_expired].
+  ///
+  /// The locks are observed as long as the returned [Subscription] is kept.
+  pub fn observe_edit_locks<F>(&self, callback: F) -> Subscription
+  where
+    F: Fn(&[EditLockChange]) + Send + Sync + 'static,
+  {
+    self.edit_locks.observe(move |txn, event| {
+      let changes = event
+        .keys(txn)
+        .iter()
+        .map(|(scope, change)| EditLockChange {
+          scope: scope.to_string(),
+          lock: match change {
+            EntryChange::Inserted(value) | EntryChange::Updated(_, value) => {
+              lock_from_out(value.clone())
+            },
+            EntryChange::Removed(_) => None,
+          },
+        })
+        .collect::<Vec<_>>();
+      if !changes.is_empty() {
+        callback(&changes);
+      }
+    })
+  }
+
+  /// The holder of the locks acquired by this client.
+  fn lock_holder(&self) -> Result<CollabOrigin, CollabError> {
+    match self.origin() {
+      CollabOrigin::Empty => Err(CollabError::NoRequiredData(
+        "an edit lock requires a client or server origin".to_string(),
+      )),
+      origin => Ok(origin.clone()),
+    }
+  }
+}
+
+fn get_lock_with_txn<T: ReadTxn>(locks: &MapRef, txn: &T, scope: &str) -> Option<EditLock> {
+  lock_from_out(locks.get(txn, scope)?)
+}
+
+fn lock_to_any(lock: &EditLock) -> Result<Any, CollabError> {
+  to_any(lock).map_err(|err| CollabError::Internal(anyhow::anyhow!("encode edit lock: {}", err)))
+}
+
+fn lock_from_out(value: Out) -> Option<EditLock> {
+  match value {
+    Out::Any(any) => from_any(&any).ok(),
+    _ => None,
+  }
+}
+
+fn edit_lock_held(lock: EditLock) -> CollabError {
+  CollabError::EditLockHeld {
+    scope: lock.scope,
+    holder: lock.holder.to_string(),
+    expires_at: lock.expires_at,
+  }
+}
+
+pub(crate) fn now_millis() -> i64 {
+  chrono::Utc::now().timestamp_millis()
+}
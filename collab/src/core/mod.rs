@@ -3,7 +3,9 @@ pub mod collab;
 pub mod collab_plugin;
 mod collab_search;
 pub mod collab_state;
+pub mod edit_lock;
 pub mod fill;
 pub mod origin;
 pub mod transaction;
+pub mod update_batch;
 pub mod value;
@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::MissedTickBehavior;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio_stream::{Stream, StreamExt};
+use yrs::{Doc, Transact, TransactionMut};
+
+use crate::core::collab::Collab;
+use crate::core::edit_lock::now_millis;
+use crate::core::origin::CollabOrigin;
+
+/// When an [UpdateBatcher] commits its edits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpdateBatchOptions {
+  /// The number of edits committed together.
+  pub max_edits: usize,
+  /// How long the first edit of a batch waits for the others before it's committed. Checked on
+  /// each edit, by [UpdateBatcher::flush_if_due] and on a timer by [UpdateBatcher::edit_stream].
+  pub max_delay: Duration,
+}
+
+impl Default for UpdateBatchOptions {
+  fn default() -> Self {
+    Self {
+      max_edits: 100,
+      max_delay: Duration::from_millis(200),
+    }
+  }
+}
+
+impl UpdateBatchOptions {
+  pub fn with_max_edits(mut self, max_edits: usize) -> Self {
+    self.max_edits = max_edits.max(1);
+    self
+  }
+
+  pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+    self.max_delay = max_delay;
+    self
+  }
+}
+
+/// Group many small programmatic edits, e.g. the chunks of a streamed AI response or the writes
+/// of an import, into a few transactions. Each committed transaction emits a single update to
+/// the plugins and the peers instead of one update per edit.
+///
+/// The edits are committed when the batch reaches [UpdateBatchOptions::max_edits] edits or
+/// [UpdateBatchOptions::max_delay], on [UpdateBatcher::flush] and when the batcher is dropped.
+/// The edits of a batch are not visible to the other transactions of the collab until they are
+/// committed, and no other transaction can be opened while a batch is pending.
+pub struct UpdateBatcher<'a> {
+  doc: &'a Doc,
+  origin: CollabOrigin,
+  options: UpdateBatchOptions,
+  txn: Option<TransactionMut<'a>>,
+  pending_edits: usize,
+  /// When the first pending edit was made, in milliseconds. [std::time::Instant] isn't
+  /// available on wasm32.
+  pending_since: Option<i64>,
+  num_of_flushes: usize,
+}
+
+impl<'a> UpdateBatcher<'a> {
+  pub fn new(collab: &'a Collab, options: UpdateBatchOptions) -> Self {
+    Self {
+      doc: collab.context.doc(),
+      origin: collab.origin().clone(),
+      options,
+      txn: None,
+      pending_edits: 0,
+      pending_since: None,
+      num_of_flushes: 0,
+    }
+  }
+
+  /// Make an edit in the pending transaction, committing the batch if it's full or due.
+  pub fn edit<F, T>(&mut self, f: F) -> T
+  where
+    F: FnOnce(&mut TransactionMut<'a>) -> T,
+  {
+    let doc = self.doc;
+    let origin = &self.origin;
+    let txn = self
+      .txn
+      .get_or_insert_with(|| doc.transact_mut_with(origin.clone()));
+    let result = f(txn);
+    self.pending_edits += 1;
+    self.pending_since.get_or_insert_with(now_millis);
+    if self.pending_edits >= self.options.max_edits {
+      self.flush();
+    } else {
+      self.flush_if_due();
+    }
+    result
+  }
+
+  /// Commit the pending edits if the first of them waited for [UpdateBatchOptions::max_delay].
+  ///
+  /// Return true if the edits were committed.
+  pub fn flush_if_due(&mut self) -> bool {
+    let max_delay = self.options.max_delay.as_millis() as i64;
+    let due = self
+      .pending_since
+      .is_some_and(|since| now_millis() - since >= max_delay);
+    due && self.flush()
+  }
+
+  /// Make the edits received from the stream until it ends, then commit them. A timer ticking
+  /// every [UpdateBatchOptions::max_delay] commits the pending edits when the stream stalls, e.g.
+  /// while a streamed AI response waits for its next chunk. The tokio timer isn't available on
+  /// wasm32.
+  #[cfg(not(target_arch = "wasm32"))]
+  pub async fn edit_stream<S, F>(&mut self, stream: S)
+  where
+    S: Stream<Item = F>,
+    F: FnOnce(&mut TransactionMut<'a>),
+  {
+    // An interval can't tick every 0ms.
+    let period = self.options.max_delay.max(Duration::from_millis(1));
+    let mut interval = tokio::time::interval(period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    tokio::pin!(stream);
+    loop {
+      tokio::select! {
+        edit = stream.next() => match edit {
+          Some(edit) => self.edit(edit),
+          None => break,
+        },
+        _ = interval.tick() => {
+          self.flush_if_due();
+        },
+      }
+    }
+    self.flush();
+  }
+
+  /// Commit the pending edits as one update. Return false if there was nothing to commit.
+  pub fn flush(&mut self) -> bool {
+    self.pending_edits = 0;
+    self.pending_since = None;
+    match self.txn.take() {
+      Some(txn) => {
+        // Dropping the transaction commits it.
+        drop(txn);
+        self.num_of_flushes += 1;
+        true
+      },
+      None => false,
+    }
+  }
+
+  /// The number of edits waiting to be committed.
+  pub fn pending_edits(&self) -> usize {
+    self.pending_edits
+  }
+
+  /// The number of batches committed so far.
+  pub fn num_of_flushes(&self) -> usize {
+    self.num_of_flushes
+  }
+}
+
+impl Drop for UpdateBatcher<'_> {
+  fn drop(&mut self) {
+    self.flush();
+  }
+}
+
+impl Collab {
+  /// Batch the edits made through the returned [UpdateBatcher], see [UpdateBatchOptions].
+  pub fn batch_updates(&self, options: UpdateBatchOptions) -> UpdateBatcher<'_> {
+    UpdateBatcher::new(self, options)
+  }
+}
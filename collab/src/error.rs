@@ -38,6 +38,16 @@ pub enum CollabError {
 
   #[error("Internal failure: {0}")]
   Internal(#[from] anyhow::Error),
+
+  #[error("The {scope} edit lock is held by {holder} until {expires_at}")]
+  EditLockHeld {
+    scope: String,
+    holder: String,
+    expires_at: i64,
+  },
+
+  #[error("The {0} edit lock is not held")]
+  EditLockNotHeld(String),
 }
 
 impl From<TransactionAcqError> for CollabError {
@@ -0,0 +1,154 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use assert_matches2::assert_matches;
+use collab::core::collab::CollabOptions;
+use collab::core::edit_lock::{COLLAB_EDIT_LOCK, SCHEMA_EDIT_LOCK};
+use collab::core::origin::{CollabClient, CollabOrigin};
+use collab::error::CollabError;
+use collab::preclude::Collab;
+use yrs::updates::decoder::Decode;
+use yrs::{ReadTxn, Update};
+
+const LOCK_DURATION: Duration = Duration::from_secs(60);
+
+fn create_collab(uid: i64) -> Collab {
+  let options = CollabOptions::new("agenda".to_string(), uid as u64);
+  let origin = CollabOrigin::Client(CollabClient::new(uid, format!("device-{}", uid)));
+  Collab::new_with_options(origin, options).unwrap()
+}
+
+fn sync(from: &Collab, to: &mut Collab) {
+  let state_vector = to.transact().state_vector();
+  let update = from.transact().encode_state_as_update_v1(&state_vector);
+  to.transact_mut()
+    .apply_update(Update::decode_v1(&update).unwrap())
+    .unwrap();
+}
+
+#[test]
+fn edit_lock_held_by_another_client_test() {
+  let mut owner = create_collab(1);
+  let mut guest = create_collab(2);
+
+  let lock = owner
+    .acquire_edit_lock(COLLAB_EDIT_LOCK, LOCK_DURATION, Some("meeting".to_string()))
+    .unwrap();
+  assert!(owner.can_edit(COLLAB_EDIT_LOCK));
+  sync(&owner, &mut guest);
+
+  assert_eq!(guest.get_edit_lock(COLLAB_EDIT_LOCK), Some(lock.clone()));
+  assert!(!guest.can_edit(COLLAB_EDIT_LOCK));
+  assert!(guest.can_edit(SCHEMA_EDIT_LOCK));
+  assert_matches!(
+    guest.acquire_edit_lock(COLLAB_EDIT_LOCK, LOCK_DURATION, None),
+    Err(CollabError::EditLockHeld { .. })
+  );
+  assert_matches!(
+    guest.release_edit_lock(COLLAB_EDIT_LOCK),
+    Err(CollabError::EditLockHeld { .. })
+  );
+  assert_matches!(
+    guest.renew_edit_lock(COLLAB_EDIT_LOCK, LOCK_DURATION),
+    Err(CollabError::EditLockNotHeld(_))
+  );
+
+  // Acquiring the lock again renews it, it was acquired at the same time.
+  let renewed = owner
+    .acquire_edit_lock(COLLAB_EDIT_LOCK, LOCK_DURATION, None)
+    .unwrap();
+  assert_eq!(renewed.acquired_at, lock.acquired_at);
+  assert!(renewed.expires_at >= lock.expires_at);
+
+  owner.release_edit_lock(COLLAB_EDIT_LOCK).unwrap();
+  sync(&owner, &mut guest);
+  assert!(guest.can_edit(COLLAB_EDIT_LOCK));
+  guest
+    .acquire_edit_lock(COLLAB_EDIT_LOCK, LOCK_DURATION, None)
+    .unwrap();
+}
+
+#[test]
+fn expired_edit_lock_test() {
+  let mut owner = create_collab(1);
+  let mut guest = create_collab(2);
+  owner
+    .acquire_edit_lock(SCHEMA_EDIT_LOCK, Duration::ZERO, None)
+    .unwrap();
+  sync(&owner, &mut guest);
+
+  // A lock that isn't renewed expires.
+  assert!(guest.get_edit_lock(SCHEMA_EDIT_LOCK).is_none());
+  assert!(guest.get_edit_locks().is_empty());
+  assert_matches!(
+    owner.renew_edit_lock(SCHEMA_EDIT_LOCK, LOCK_DURATION),
+    Err(CollabError::EditLockNotHeld(_))
+  );
+  let lock = guest
+    .acquire_edit_lock(SCHEMA_EDIT_LOCK, LOCK_DURATION, None)
+    .unwrap();
+  assert_eq!(guest.get_edit_locks(), vec![lock]);
+}
+
+#[test]
+fn observe_edit_locks_test() {
+  let mut owner = create_collab(1);
+  let mut guest = create_collab(2);
+  let changes = Arc::new(Mutex::new(vec![]));
+  let cloned_changes = changes.clone();
+  let _subscription = guest.observe_edit_locks(move |events| {
+    cloned_changes.lock().unwrap().extend(
+      events
+        .iter()
+        .map(|event| (event.scope.clone(), event.lock.is_some())),
+    );
+  });
+
+  owner
+    .acquire_edit_lock(COLLAB_EDIT_LOCK, LOCK_DURATION, None)
+    .unwrap();
+  sync(&owner, &mut guest);
+  owner.release_edit_lock(COLLAB_EDIT_LOCK).unwrap();
+  sync(&owner, &mut guest);
+
+  assert_eq!(
+    changes.lock().unwrap().as_slice(),
+    &[
+      (COLLAB_EDIT_LOCK.to_string(), true),
+      (COLLAB_EDIT_LOCK.to_string(), false),
+    ]
+  );
+}
+
+#[test]
+fn edit_lock_is_not_undone_test() {
+  let mut owner = create_collab(1);
+  owner.enable_undo_redo();
+  owner.insert("title", "Agenda");
+  owner
+    .acquire_edit_lock(COLLAB_EDIT_LOCK, LOCK_DURATION, None)
+    .unwrap();
+
+  owner.undo().unwrap();
+  assert!(owner.get_edit_lock(COLLAB_EDIT_LOCK).is_some());
+}
+
+#[test]
+fn read_edit_locks_with_txn_test() {
+  let mut owner = create_collab(1);
+  let mut guest = create_collab(2);
+  let lock = owner
+    .acquire_edit_lock(SCHEMA_EDIT_LOCK, LOCK_DURATION, None)
+    .unwrap();
+  sync(&owner, &mut guest);
+
+  // Reading the locks doesn't open a write transaction, so it works while one is read.
+  let txn = guest.transact();
+  assert_eq!(
+    guest.get_edit_lock_with_txn(&txn, SCHEMA_EDIT_LOCK),
+    Some(lock.clone())
+  );
+  assert_eq!(guest.get_edit_lock(SCHEMA_EDIT_LOCK), Some(lock.clone()));
+  assert!(!guest.can_edit(SCHEMA_EDIT_LOCK));
+  assert_eq!(guest.get_edit_locks_with_txn(&txn), vec![lock]);
+}
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use collab::core::collab::{CollabOptions, default_client_id};
+use collab::core::origin::CollabOrigin;
+use collab::core::update_batch::UpdateBatchOptions;
+use collab::preclude::Collab;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use yrs::{GetString, Map, Subscription, Text, TextPrelim, TextRef, TransactionMut};
+
+fn create_collab_with_text() -> (Collab, TextRef, Arc<AtomicUsize>, Subscription) {
+  let options = CollabOptions::new("batch".to_string(), default_client_id());
+  let mut collab = Collab::new_with_options(CollabOrigin::Empty, options).unwrap();
+  let text = {
+    let mut txn = collab.context.transact_mut();
+    collab.data.insert(&mut txn, "text", TextPrelim::new(""))
+  };
+  let num_of_updates = Arc::new(AtomicUsize::new(0));
+  let cloned_num_of_updates = num_of_updates.clone();
+  let subscription = collab
+    .context
+    .doc()
+    .observe_update_v1(move |_, _| {
+      cloned_num_of_updates.fetch_add(1, Ordering::SeqCst);
+    })
+    .unwrap();
+  (collab, text, num_of_updates, subscription)
+}
+
+#[test]
+fn batch_edits_by_count_test() {
+  let (collab, text, num_of_updates, _subscription) = create_collab_with_text();
+  {
+    let mut batcher =
+      collab.batch_updates(UpdateBatchOptions::default().with_max_delay(Duration::from_secs(60)));
+    for i in 0..250 {
+      batcher.edit(|txn| {
+        let len = text.len(txn);
+        text.insert(txn, len, &format!("{} ", i % 10));
+      });
+    }
+    assert_eq!(batcher.num_of_flushes(), 2);
+    assert_eq!(batcher.pending_edits(), 50);
+    assert_eq!(num_of_updates.load(Ordering::SeqCst), 2);
+  }
+
+  // Dropping the batcher commits the remaining edits.
+  assert_eq!(num_of_updates.load(Ordering::SeqCst), 3);
+  let txn = collab.transact();
+  assert_eq!(text.get_string(&txn).len(), 500);
+}
+
+#[test]
+fn batch_edits_by_delay_test() {
+  let (collab, text, num_of_updates, _subscription) = create_collab_with_text();
+  let mut batcher = collab.batch_updates(
+    UpdateBatchOptions::default()
+      .with_max_edits(1000)
+      .with_max_delay(Duration::from_millis(20)),
+  );
+  batcher.edit(|txn| text.insert(txn, 0, "Hello"));
+  assert!(!batcher.flush_if_due());
+  assert_eq!(num_of_updates.load(Ordering::SeqCst), 0);
+
+  std::thread::sleep(Duration::from_millis(30));
+  assert!(batcher.flush_if_due());
+  assert_eq!(num_of_updates.load(Ordering::SeqCst), 1);
+
+  // Nothing left to commit.
+  assert!(!batcher.flush());
+  assert_eq!(batcher.num_of_flushes(), 1);
+}
+
+#[tokio::test]
+async fn batch_edit_stream_flushes_on_stall_test() {
+  let (collab, text, num_of_updates, _subscription) = create_collab_with_text();
+  let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Box<dyn FnOnce(&mut TransactionMut)>>();
+  let mut batcher = collab.batch_updates(
+    UpdateBatchOptions::default()
+      .with_max_edits(1000)
+      .with_max_delay(Duration::from_millis(20)),
+  );
+  let cloned_text = text.clone();
+  let cloned_num_of_updates = num_of_updates.clone();
+  let produce = async move {
+    for chunk in ["Hello", " world"] {
+      let text = cloned_text.clone();
+      tx.send(Box::new(move |txn: &mut TransactionMut| {
+        let len = text.len(txn);
+        text.insert(txn, len, chunk);
+      }))
+      .unwrap();
+    }
+    // The stream stalls, the timer commits the pending edits.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(cloned_num_of_updates.load(Ordering::SeqCst), 1);
+
+    tx.send(Box::new(move |txn: &mut TransactionMut| {
+      let len = cloned_text.len(txn);
+      cloned_text.insert(txn, len, "!");
+    }))
+    .unwrap();
+  };
+  tokio::join!(
+    batcher.edit_stream(UnboundedReceiverStream::new(rx)),
+    produce
+  );
+  drop(batcher);
+
+  assert_eq!(num_of_updates.load(Ordering::SeqCst), 2);
+  let txn = collab.transact();
+  assert_eq!(text.get_string(&txn), "Hello world!");
+}